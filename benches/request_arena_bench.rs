@@ -0,0 +1,89 @@
+//! Бенчмарки построчного разбора модуля с ареной запроса
+//!
+//! Группа `pooled` — арена из пула потока (как в LSP), `fresh` — новая
+//! арена на каждый проход. Сравнение с версией без арены — через базовую
+//! линию criterion: `cargo bench --bench request_arena_bench -- --save-baseline before`
+//! на предыдущей версии, затем `-- --baseline before` на текущей.
+
+use bsl_gradual_types::architecture::domain::{
+    function_returns, reaching_assignments, type_guards_at,
+};
+use bsl_gradual_types::core::request_arena::{arena_stats, set_pooling};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Модуль из `procedures` процедур с ветвлениями, циклами и проверками типов
+fn generate_module(procedures: usize) -> String {
+    let mut text = String::new();
+    for i in 0..procedures {
+        text.push_str(&format!(
+            r#"Функция Обработать{i}(Значение, Флаг) Экспорт
+    Результат = Неопределено; // @type Строка
+    Если ТипЗнч(Значение) = Тип("Строка") Тогда
+        Результат = "строка {i}";
+    ИначеЕсли ТипЗнч(Значение) = Тип("Число") ИЛИ ТипЗнч(Значение) = Тип("Дата") Тогда
+        Результат = Значение + {i};
+    Иначе
+        Массив = Новый Массив();
+        Для Индекс = 1 По 10 Цикл
+            Массив.Добавить(Индекс);
+        КонецЦикла;
+        Для Каждого Элемент Из Массив Цикл
+            Если Флаг И Элемент > {i} Тогда
+                Результат = Элемент;
+            КонецЕсли;
+        КонецЦикла;
+    КонецЕсли;
+    Попытка
+        Данные = ОбщийМодуль.Получить(Результат);
+    Исключение
+        Данные = Неопределено;
+    КонецПопытки;
+    Возврат Результат;
+КонецФункции
+
+"#
+        ));
+    }
+    text
+}
+
+fn bench_local_types(c: &mut Criterion) {
+    let mut group = c.benchmark_group("local_types");
+    for procedures in [50, 500, 2000] {
+        let text = generate_module(procedures);
+        // Позиция внутри вложенного цикла последней процедуры
+        let line = text.lines().count() as u32 - 12;
+        for (mode, pooling) in [("pooled", true), ("fresh", false)] {
+            set_pooling(pooling);
+            group.bench_with_input(BenchmarkId::new(mode, procedures), &text, |b, text| {
+                b.iter(|| {
+                    let assignments = reaching_assignments(black_box(text), line, 16);
+                    let guards = type_guards_at(black_box(text), line, 16);
+                    (assignments, guards)
+                });
+            });
+        }
+    }
+    set_pooling(true);
+    group.finish();
+}
+
+fn bench_function_returns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("function_returns");
+    group.sample_size(20);
+    for procedures in [500, 2000] {
+        let text = generate_module(procedures);
+        for (mode, pooling) in [("pooled", true), ("fresh", false)] {
+            set_pooling(pooling);
+            group.bench_with_input(BenchmarkId::new(mode, procedures), &text, |b, text| {
+                b.iter(|| function_returns(black_box(text)));
+            });
+        }
+    }
+    set_pooling(true);
+    group.finish();
+    println!("Арены: {:?}", arena_stats());
+}
+
+criterion_group!(benches, bench_local_types, bench_function_returns);
+criterion_main!(benches);
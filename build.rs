@@ -2,6 +2,14 @@
 //! Создает заглушку для tree_sitter_bsl пока нет реальной библиотеки
 
 fn main() {
+    // gRPC API: генерируем код из proto/ только при включенной feature "grpc"
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/bsl_types.proto");
+        tonic_build::compile_protos("proto/bsl_types.proto")
+            .expect("Не удалось сгенерировать gRPC код из proto/bsl_types.proto");
+    }
+
     // Проверяем наличие реальной tree-sitter-bsl библиотеки
     let tree_sitter_bsl_path = "../tree-sitter-bsl";
     let parser_c_path = format!("{}/src/parser.c", tree_sitter_bsl_path);
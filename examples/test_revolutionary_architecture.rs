@@ -79,6 +79,8 @@ async fn main() -> anyhow::Result<()> {
         column: 5,
         prefix: "Стр".to_string(),
         trigger_character: None,
+        expected_type: None,
+        document_text: None,
     };
 
     let completion_response = lsp_interface
@@ -165,6 +167,13 @@ async fn main() -> anyhow::Result<()> {
         include_coverage: true,
         include_errors: true,
         verbose: false,
+        subsystems: None,
+        subsystem_filter: None,
+        ownership: None,
+        quality_gates: None,
+        update_dynamic_baseline: false,
+        update_coverage_baseline: false,
+        memory: None,
     };
 
     let analysis_response = cli_interface
@@ -201,6 +210,8 @@ async fn main() -> anyhow::Result<()> {
             column: 1,
             prefix: "Тест".to_string(),
             trigger_character: None,
+            expected_type: None,
+            document_text: None,
         };
 
         let _response = lsp_interface
@@ -1,17 +1,16 @@
 //! Сервис документации типов (плоская архитектура)
 //!
 //! Оборачивает провайдеры документации платформы и конфигурации,
-//! а также движок поиска документации для удобного использования
-//! из Web/CLI/LSP сервисов.
+//! а также движок поиска документации (фича `docs-search`) для удобного
+//! использования из Web/CLI/LSP сервисов.
 
 use anyhow::Result;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::documentation::{
-    AdvancedSearchQuery, ConfigurationDocumentationProvider, DocumentationSearchEngine,
-    PlatformDocumentationProvider, SearchResults,
-};
+#[cfg(feature = "docs-search")]
+use crate::documentation::{AdvancedSearchQuery, DocumentationSearchEngine, SearchResults};
+use crate::documentation::{ConfigurationDocumentationProvider, PlatformDocumentationProvider};
 
 /// Высокоуровневый сервис документации
 pub struct DocumentationService {
@@ -20,6 +19,7 @@ pub struct DocumentationService {
     /// Провайдер документации конфигурационных типов
     configuration: Arc<RwLock<Option<ConfigurationDocumentationProvider>>>,
     /// Поисковый движок по документации
+    #[cfg(feature = "docs-search")]
     search: Arc<RwLock<Option<DocumentationSearchEngine>>>,
 }
 
@@ -35,6 +35,7 @@ impl DocumentationService {
         Self {
             platform: Arc::new(RwLock::new(None)),
             configuration: Arc::new(RwLock::new(None)),
+            #[cfg(feature = "docs-search")]
             search: Arc::new(RwLock::new(None)),
         }
     }
@@ -56,6 +57,7 @@ impl DocumentationService {
     }
 
     /// Инициализировать поисковый движок документации
+    #[cfg(feature = "docs-search")]
     pub async fn init_search(&self) -> Result<()> {
         // Простейшая инициализация; детали наполнения индексами будут добавлены позже
         let engine = DocumentationSearchEngine::new();
@@ -65,6 +67,7 @@ impl DocumentationService {
     }
 
     /// Выполнить расширенный поиск по документации
+    #[cfg(feature = "docs-search")]
     pub async fn search(&self, query: AdvancedSearchQuery) -> Result<SearchResults> {
         let guard = self.search.read().await;
         let engine = guard
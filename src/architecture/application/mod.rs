@@ -6,18 +6,39 @@
 //! - AnalysisTypeService: оптимизирован для анализа проектов
 
 use anyhow::Result;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{info};
+use tracing::{info, info_span, warn, Instrument};
 
 use super::domain::{
-    CompletionItem, CompletionKind, TypeCheckerService, TypeContext, TypeResolutionService,
-    TypeSearchResult,
+    await_operand, call_target, AssignedValue, CompletionItem, CompletionKind, GlobalModuleKind,
+    GlobalScope, QueryParameterIssueKind, SignatureTable, TypeCheckerService, TypeContext,
+    TypeResolutionService, TypeSearchResult,
+};
+use crate::core::annotation_coverage::{self, AnnotationCoverage};
+use crate::core::bounded_memory::{
+    format_megabytes, plan_waves, BoundedAnalysisConfig, MemoryCeiling, SpillFile,
+};
+use crate::core::call_hierarchy::ProjectCallGraph;
+use crate::core::common_module_calls::module_name_from_path;
+use crate::core::compilation_context::{method_context, CompilationContext, GlobalAvailability};
+use crate::core::completion_scope::{
+    scope_symbols, CompletionGroup, CompletionGroupsConfig, ScopeSymbolKind,
+};
+use crate::core::inlay_hints::{call_arguments, variable_sites};
+use crate::core::module_dependencies::ModuleDependencyGraph;
+use crate::core::ownership::{OwnerSummary, OwnershipMap};
+use crate::core::snippets::SnippetProvider;
+use crate::core::quality_gates::GateInput;
+use crate::data::loaders::subsystems::SubsystemIndex;
+use crate::data::WriteGuard;
+use crate::unified::data::{NameCandidates, RawTypeData, RepositoryChange, TypeSource};
+use crate::domain::types::{Certainty, FacetKind, ResolutionResult, TypeResolution};
+use crate::parsing::bsl::language_features::{
+    await_outside_async, unsupported_syntax, LanguageFeatures,
 };
-use crate::unified::data::{RawTypeData, TypeSource};
-use crate::domain::types::{FacetKind, TypeResolution};
 
 // === LSP TYPE SERVICE ===
 
@@ -31,6 +52,15 @@ pub struct LspTypeService {
 
     /// Монитор производительности
     performance_monitor: Arc<RwLock<PerformanceMonitor>>,
+
+    /// Порядок групп автодополнения
+    completion_groups: CompletionGroupsConfig,
+
+    /// Доступность глобальных методов для отбора по директиве компиляции
+    global_availability: Arc<RwLock<GlobalAvailability>>,
+
+    /// Шаблоны конструкций (встроенные и из файла пользователя)
+    snippets: Arc<RwLock<SnippetProvider>>,
 }
 
 /// LSP кеш для быстрых операций
@@ -44,6 +74,45 @@ pub struct LspCache {
 
     /// Кеш разрешений типов в позициях
     position_cache: HashMap<PositionKey, TypeResolution>,
+
+    /// Глобальные автодополнения по префиксу без точки: не зависят от
+    /// позиции и заполняются прогревом
+    prefix_completions: HashMap<String, Vec<LspCompletion>>,
+}
+
+impl LspCache {
+    /// Убрать записи, затронутые изменением репозитория. Автодополнение
+    /// членов (`Объект.`) зависит от типа объекта, которого ключ не хранит,
+    /// поэтому сбрасывается при любом изменении.
+    fn invalidate(&mut self, change: &RepositoryChange) {
+        if change.cleared {
+            *self = Self::default();
+            return;
+        }
+        self.position_cache
+            .retain(|_, resolution| !change.affects(resolution));
+        self.hover_cache.retain(|key, hover| {
+            let expression = key.rsplit(':').next().unwrap_or_default();
+            match &hover.type_name {
+                Some(name) => !change.affects_name(name) && !change.mentions(expression),
+                None => false,
+            }
+        });
+        let fresh = |prefix: &str, completions: &[LspCompletion]| {
+            !prefix.contains('.')
+                && !change.mentions(prefix)
+                && !completions
+                    .iter()
+                    .any(|completion| change.mentions(&completion.label))
+        };
+        self.completion_cache.retain(|key, completions| {
+            // Префикс — последняя часть ключа `файл:строка:колонка:префикс`
+            let prefix = key.rsplit(':').next().unwrap_or_default();
+            fresh(prefix, completions)
+        });
+        self.prefix_completions
+            .retain(|prefix, completions| fresh(prefix, completions));
+    }
 }
 
 /// Ключ для кеша позиций
@@ -59,8 +128,12 @@ pub struct PositionKey {
 pub struct HoverInfo {
     pub content: String,
     pub type_info: String,
+    /// Имя разрешённого типа (для заметок команды)
+    pub type_name: Option<String>,
     pub documentation: Option<String>,
     pub examples: Vec<String>,
+    /// Файл описания объекта метаданных (для конфигурационных типов)
+    pub definition_path: Option<String>,
 }
 
 /// LSP автодополнение (оптимизированное)
@@ -115,9 +188,28 @@ impl LspTypeService {
             resolution_service,
             lsp_cache: Arc::new(RwLock::new(LspCache::default())),
             performance_monitor: Arc::new(RwLock::new(PerformanceMonitor::default())),
+            completion_groups: CompletionGroupsConfig::default(),
+            global_availability: Arc::new(RwLock::new(GlobalAvailability::default())),
+            snippets: Arc::new(RwLock::new(SnippetProvider::builtin())),
         }
     }
 
+    /// Задать порядок групп автодополнения
+    pub fn with_completion_groups(mut self, config: CompletionGroupsConfig) -> Self {
+        self.completion_groups = config;
+        self
+    }
+
+    /// Задать доступность глобальных методов (из синтакс-помощника)
+    pub async fn set_global_availability(&self, availability: GlobalAvailability) {
+        *self.global_availability.write().await = availability;
+    }
+
+    /// Заменить шаблоны конструкций (изменился файл шаблонов пользователя)
+    pub async fn set_snippets(&self, snippets: SnippetProvider) {
+        *self.snippets.write().await = snippets;
+    }
+
     /// Разрешить тип в позиции (основной LSP API)
     pub async fn resolve_at_position(
         &self,
@@ -144,14 +236,7 @@ impl LspTypeService {
         }
 
         // Создаём контекст для разрешения
-        let context = TypeContext {
-            file_path: Some(file_path.to_string()),
-            line: Some(line),
-            column: Some(column),
-            local_variables: HashMap::new(), // TODO: извлечь из файла
-            current_function: None,          // TODO: определить из позиции
-            current_facet: None,
-        };
+        let context = TypeContext::at_position(file_path, line, column);
 
         // Разрешаем через центральный сервис
         let resolution = self
@@ -185,21 +270,19 @@ impl LspTypeService {
         let cache_key = format!("{}:{}:{}:{}", file_path, line, column, prefix);
         {
             let cache = self.lsp_cache.read().await;
-            if let Some(cached_completions) = cache.completion_cache.get(&cache_key) {
+            let cached = cache.completion_cache.get(&cache_key).or_else(|| {
+                (!prefix.contains('.'))
+                    .then(|| cache.prefix_completions.get(prefix))
+                    .flatten()
+            });
+            if let Some(cached_completions) = cached {
                 self.record_cache_hit().await;
                 return cached_completions.clone();
             }
         }
 
         // Создаём контекст
-        let context = TypeContext {
-            file_path: Some(file_path.to_string()),
-            line: Some(line),
-            column: Some(column),
-            local_variables: HashMap::new(),
-            current_function: None,
-            current_facet: None,
-        };
+        let context = TypeContext::at_position(file_path, line, column);
 
         // Получаем автодополнение через центральный сервис
         let completions = self
@@ -225,14 +308,272 @@ impl LspTypeService {
         lsp_completions
     }
 
-    /// Получить hover информацию
+    /// Автодополнение в контексте документа. Символы текущего метода и модуля
+    /// (если передан текст документа) идут перед глобальными в порядке групп
+    /// из настроек; при известном ожидаемом типе значения, совместимые с ним
+    /// по присваиванию, поднимаются в начало списка. Внутри метода с директивой
+    /// компиляции глобальные методы, недоступные в его контексте (серверные на
+    /// клиенте и наоборот), отбрасываются.
+    pub async fn get_completions_in_context(
+        &self,
+        prefix: &str,
+        file_path: &str,
+        line: u32,
+        column: u32,
+        document: Option<&str>,
+        expected_type: Option<&TypeResolution>,
+    ) -> Vec<LspCompletion> {
+        let mut global = self
+            .get_completions_fast(prefix, file_path, line, column)
+            .await;
+        let compilation_context = document
+            .filter(|_| !prefix.contains('.'))
+            .and_then(|text| method_context(text, line));
+        if let Some(compilation_context) = compilation_context {
+            let availability = self.global_availability.read().await;
+            global.retain(|c| availability.allows(&c.label, compilation_context));
+        }
+        let mut completions = document
+            .map(|text| self.scope_completions(text, line, prefix))
+            .unwrap_or_default();
+        completions.extend(
+            self.global_module_completions(prefix, file_path, compilation_context)
+                .await,
+        );
+        // Символ модуля скрывает одноимённый глобальный
+        let mut seen: HashSet<String> =
+            completions.iter().map(|c| c.label.to_lowercase()).collect();
+        completions.extend(
+            global
+                .into_iter()
+                .filter(|c| seen.insert(c.label.to_lowercase())),
+        );
+        completions.extend(self.snippet_completions(prefix).await);
+
+        if let Some(expected) = expected_type {
+            let context = TypeContext::at_position(file_path, line, column);
+            self.rank_by_expected_type(&mut completions, prefix, &context, expected)
+                .await;
+        }
+        completions.sort_by(|a, b| {
+            let a = a.sort_text.as_deref().unwrap_or(&a.label);
+            let b = b.sort_text.as_deref().unwrap_or(&b.label);
+            a.cmp(b)
+        });
+        completions
+    }
+
+    /// Шаблоны конструкций, подходящие под префикс; вставляются как сниппеты
+    /// LSP с позициями табуляции
+    pub async fn snippet_completions(&self, prefix: &str) -> Vec<LspCompletion> {
+        self.snippets
+            .read()
+            .await
+            .matching(prefix)
+            .into_iter()
+            .map(|snippet| LspCompletion {
+                label: snippet.prefix.clone(),
+                kind: LspCompletionKind::Snippet,
+                detail: Some(snippet.description.clone()),
+                documentation: Some(format!("```bsl\n{}\n```", snippet.body)),
+                insert_text: snippet.body.clone(),
+                filter_text: Some(snippet.prefix.clone()),
+                sort_text: Some(
+                    self.completion_groups
+                        .sort_text(CompletionGroup::Global, &snippet.prefix),
+                ),
+            })
+            .collect()
+    }
+
+    /// Локальные символы метода и члены модуля, подходящие под префикс
+    pub fn scope_completions(&self, document: &str, line: u32, prefix: &str) -> Vec<LspCompletion> {
+        // После точки дополняются члены объекта, а не символы модуля
+        if prefix.contains('.') {
+            return Vec::new();
+        }
+        let prefix_lower = prefix.to_lowercase();
+
+        scope_symbols(document, line as usize)
+            .into_iter()
+            .filter(|symbol| symbol.name.to_lowercase().starts_with(&prefix_lower))
+            .map(|symbol| {
+                let (kind, detail, insert_text) = match symbol.kind {
+                    ScopeSymbolKind::Parameter => {
+                        (LspCompletionKind::Variable, "Параметр", symbol.name.clone())
+                    }
+                    ScopeSymbolKind::Variable if symbol.group == CompletionGroup::Local => {
+                        (LspCompletionKind::Variable, "Локальная переменная", symbol.name.clone())
+                    }
+                    ScopeSymbolKind::Variable => {
+                        (LspCompletionKind::Variable, "Переменная модуля", symbol.name.clone())
+                    }
+                    ScopeSymbolKind::Procedure => (
+                        LspCompletionKind::Method,
+                        "Процедура модуля",
+                        format!("{}()", symbol.name),
+                    ),
+                    ScopeSymbolKind::Function => (
+                        LspCompletionKind::Function,
+                        "Функция модуля",
+                        format!("{}()", symbol.name),
+                    ),
+                };
+                LspCompletion {
+                    sort_text: Some(self.completion_groups.sort_text(symbol.group, &symbol.name)),
+                    filter_text: Some(symbol.name.clone()),
+                    label: symbol.name,
+                    kind,
+                    detail: Some(detail.to_string()),
+                    documentation: None,
+                    insert_text,
+                }
+            })
+            .collect()
+    }
+
+    /// Экспортные переменные и методы модулей приложения и сеанса; в методе
+    /// с директивой — только доступные в его контексте. Символы самого
+    /// модуля дополняет [`Self::scope_completions`].
+    pub async fn global_module_completions(
+        &self,
+        prefix: &str,
+        file_path: &str,
+        compilation_context: Option<CompilationContext>,
+    ) -> Vec<LspCompletion> {
+        if prefix.contains('.') {
+            return Vec::new();
+        }
+        let prefix_lower = prefix.to_lowercase();
+        let globals = self.resolution_service.globals().await;
+        let available = |module: GlobalModuleKind, file: &str, name: &str| {
+            file != file_path
+                && name.to_lowercase().starts_with(&prefix_lower)
+                && match compilation_context {
+                    Some(context) => module.available_in(context),
+                    None => true,
+                }
+        };
+
+        let variables = globals
+            .variables()
+            .iter()
+            .filter(|v| available(v.module, &v.file, &v.name))
+            .map(|v| (v.name.clone(), v.module, LspCompletionKind::Variable, v.name.clone()));
+        let methods = globals
+            .methods()
+            .iter()
+            .filter(|m| available(m.module, &m.file, &m.name))
+            .map(|m| {
+                let kind = if m.is_function {
+                    LspCompletionKind::Function
+                } else {
+                    LspCompletionKind::Method
+                };
+                (m.name.clone(), m.module, kind, format!("{}()", m.name))
+            });
+        variables
+            .chain(methods)
+            .map(|(name, module, kind, insert_text)| LspCompletion {
+                sort_text: Some(self.completion_groups.sort_text(CompletionGroup::Global, &name)),
+                filter_text: Some(name.clone()),
+                label: name,
+                kind,
+                detail: Some(module.label().to_string()),
+                documentation: None,
+                insert_text,
+            })
+            .collect()
+    }
+
+    /// Поднять значения, совместимые по присваиванию с ожидаемым типом
+    async fn rank_by_expected_type(
+        &self,
+        completions: &mut [LspCompletion],
+        prefix: &str,
+        context: &TypeContext,
+        expected: &TypeResolution,
+    ) {
+        if !matches!(expected.result, ResolutionResult::Concrete(_)) {
+            return;
+        }
+
+        // Члены типа дополняются после последней точки префикса
+        let base = prefix.rsplit_once('.').map(|(base, _)| base);
+        let checker = TypeCheckerService::new();
+
+        for completion in completions.iter_mut() {
+            let assignable = match completion.kind {
+                // Имена типов, ключевые слова и шаблоны значениями не являются
+                LspCompletionKind::Method
+                | LspCompletionKind::Function
+                | LspCompletionKind::Variable
+                | LspCompletionKind::Property => {
+                    let call = match completion.kind {
+                        LspCompletionKind::Method | LspCompletionKind::Function => "()",
+                        _ => "",
+                    };
+                    let expression = match base {
+                        Some(base) => format!("{}.{}{}", base, completion.label, call),
+                        None => format!("{}{}", completion.label, call),
+                    };
+                    let item_type = self
+                        .resolution_service
+                        .resolve_expression(&expression, context)
+                        .await;
+                    // Неизвестный тип совместим с любым, но поднимать его незачем
+                    matches!(item_type.result, ResolutionResult::Concrete(_))
+                        && checker.is_assignment_compatible(&item_type, expected)
+                }
+                _ => false,
+            };
+            let rank = if assignable { 0 } else { 1 };
+            let sort_text = completion.sort_text.as_deref().unwrap_or(&completion.label);
+            completion.sort_text = Some(format!("{}_{}", rank, sort_text));
+        }
+    }
+
+    /// Разрешить тип в позиции документа с учётом проверок `ТипЗнч`
+    /// (без кеша: результат зависит от текста)
+    pub async fn resolve_in_document(
+        &self,
+        file_path: &str,
+        text: &str,
+        line: u32,
+        column: u32,
+        expression: &str,
+    ) -> TypeResolution {
+        let start_time = std::time::Instant::now();
+        let context = TypeContext::at_position(file_path, line, column);
+        let resolution = self
+            .resolution_service
+            .resolve_in_document(expression, &context, text)
+            .await;
+        self.record_performance(start_time.elapsed()).await;
+        resolution
+    }
+
+    /// Получить hover информацию. Если передан текст документа, тип
+    /// уточняется проверками `ТипЗнч` в позиции.
     pub async fn get_hover_info(
         &self,
         expression: &str,
         file_path: &str,
         line: u32,
         column: u32,
+        document: Option<&str>,
     ) -> Option<HoverInfo> {
+        if let Some(text) = document {
+            let resolution = self
+                .resolve_in_document(file_path, text, line, column, expression)
+                .await;
+            let mut hover_info = self.create_hover_info(&resolution, expression);
+            if let Some(note) = self.async_call_note(expression, file_path).await {
+                hover_info.content.push_str(&note);
+            }
+            return Some(hover_info);
+        }
+
         // Проверяем кеш hover
         let cache_key = format!("hover:{}:{}:{}:{}", file_path, line, column, expression);
         {
@@ -278,6 +619,52 @@ impl LspTypeService {
         checker.is_assignment_compatible(&from_type, &to_type)
     }
 
+    /// Все типы с именем `name` в порядке приоритета источников
+    pub async fn name_candidates(&self, name: &str) -> Result<NameCandidates> {
+        Ok(self.resolution_service.name_candidates(name).await?)
+    }
+
+    /// Прогреть глобальные автодополнения для префиксов без точки;
+    /// возвращает число прогретых префиксов
+    pub async fn warm_completions(&self, prefixes: &[String]) -> usize {
+        let context = TypeContext {
+            file_path: None,
+            line: None,
+            column: None,
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let mut warmed = HashMap::new();
+        for prefix in prefixes.iter().filter(|prefix| !prefix.contains('.')) {
+            let completions = self
+                .resolution_service
+                .get_completions(prefix, &context)
+                .await
+                .into_iter()
+                .map(|comp| self.convert_to_lsp_completion(comp))
+                .collect();
+            warmed.insert(prefix.clone(), completions);
+        }
+        let count = warmed.len();
+        self.lsp_cache
+            .write()
+            .await
+            .prefix_completions
+            .extend(warmed);
+        count
+    }
+
+    /// Сбросить LSP кеши (после перезагрузки типов)
+    pub async fn clear_cache(&self) {
+        *self.lsp_cache.write().await = LspCache::default();
+    }
+
+    /// Сбросить записи LSP кешей, затронутые изменением репозитория
+    pub async fn invalidate_types(&self, change: &RepositoryChange) {
+        self.lsp_cache.write().await.invalidate(change);
+    }
+
     /// Получить метрики производительности
     pub async fn get_performance_metrics(&self) -> PerformanceMonitor {
         (*self.performance_monitor.read().await).clone()
@@ -304,15 +691,45 @@ impl LspTypeService {
 
         LspCompletion {
             label: completion.label.clone(),
-            kind: lsp_kind.clone(),
+            kind: lsp_kind,
             detail: completion.detail,
             documentation: completion.documentation,
             insert_text: completion.insert_text,
             filter_text: Some(completion.label.clone()),
-            sort_text: Some(format!("{:02}_{}", lsp_kind as u8, completion.label)),
+            sort_text: Some(
+                self.completion_groups
+                    .sort_text(CompletionGroup::Global, &completion.label),
+            ),
         }
     }
 
+    /// Пояснение для вызова асинхронной функции проекта: вызов возвращает
+    /// `Обещание`, а значение даёт только `Ждать`
+    async fn async_call_note(&self, expression: &str, file_path: &str) -> Option<String> {
+        let awaited = await_operand(expression);
+        let call = call_target(awaited.unwrap_or(expression).trim());
+        let Some(AssignedValue::Call { module, function }) = call else {
+            return None;
+        };
+        let signature = self
+            .resolution_service
+            .function_signature(module.as_deref(), &function, Some(file_path))
+            .await
+            .filter(|signature| signature.is_async)?;
+        let value = signature
+            .return_type
+            .map(|resolution| format!("{:?}", resolution.result))
+            .unwrap_or_else(|| "не выведен".to_string());
+        Some(if awaited.is_some() {
+            format!("\n\nАсинх Функция {}: значение обещания — {}", signature.name, value)
+        } else {
+            format!(
+                "\n\nАсинх Функция {}: вызов возвращает Обещание, `Ждать` даёт {}",
+                signature.name, value
+            )
+        })
+    }
+
     fn create_hover_info(&self, resolution: &TypeResolution, expression: &str) -> HoverInfo {
         let type_info = format!("{:?}", resolution.result);
         let content = format!(
@@ -323,8 +740,10 @@ impl LspTypeService {
         HoverInfo {
             content,
             type_info,
+            type_name: resolution.get_name(),
             documentation: None,  // TODO: получить из репозитория
             examples: Vec::new(), // TODO: получить примеры использования
+            definition_path: resolution.definition_path().map(str::to_string),
         }
     }
 
@@ -371,6 +790,12 @@ pub struct WebTypeService {
 
     /// Монитор производительности веб-операций
     performance_monitor: Arc<RwLock<PerformanceMonitor>>,
+
+    /// Сколько раз запрашивались подробности каждого типа
+    type_requests: Arc<RwLock<HashMap<String, u64>>>,
+
+    /// Подробности горячих типов, заполняются прогревом
+    details_cache: Arc<RwLock<HashMap<String, WebTypeDetails>>>,
 }
 
 /// Построитель документации для веб-интерфейса
@@ -414,7 +839,7 @@ pub struct WebCategory {
 }
 
 /// Информация о типе для веб-интерфейса
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct WebTypeInfo {
     pub name: String,
     pub description: String,
@@ -425,6 +850,62 @@ pub struct WebTypeInfo {
     pub tags: Vec<String>,
 }
 
+/// Поле сортировки списка типов
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TypeSortField {
+    #[default]
+    Name,
+    MethodsCount,
+    PropertiesCount,
+}
+
+impl std::str::FromStr for TypeSortField {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "name" => Ok(TypeSortField::Name),
+            "methods_count" => Ok(TypeSortField::MethodsCount),
+            "properties_count" => Ok(TypeSortField::PropertiesCount),
+            other => Err(anyhow::anyhow!("Неизвестное поле сортировки: {}", other)),
+        }
+    }
+}
+
+/// Параметры постраничного получения списка типов
+#[derive(Debug, Clone)]
+pub struct TypeListQuery {
+    /// Номер страницы (с 1)
+    pub page: usize,
+    pub per_page: usize,
+    pub sort_by: TypeSortField,
+    pub descending: bool,
+}
+
+impl Default for TypeListQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            per_page: 50,
+            sort_by: TypeSortField::Name,
+            descending: false,
+        }
+    }
+}
+
+/// Страница списка типов
+#[derive(Debug, Clone)]
+pub struct TypeListPage {
+    pub items: Vec<WebTypeInfo>,
+    pub total_count: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub total_pages: usize,
+}
+
+/// Максимальный размер страницы списка типов
+pub const MAX_TYPES_PER_PAGE: usize = 500;
+
 /// UI метаданные для веб
 #[derive(Debug, Clone)]
 pub struct WebUiMetadata {
@@ -450,9 +931,50 @@ impl WebTypeService {
             documentation_builder: Arc::new(DocumentationBuilder::new()),
             search_engine: Arc::new(WebSearchEngine::new()),
             performance_monitor: Arc::new(RwLock::new(PerformanceMonitor::default())),
+            type_requests: Arc::new(RwLock::new(HashMap::new())),
+            details_cache: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Самые запрашиваемые типы, начиная с популярного
+    pub async fn popular_types(&self, limit: usize) -> Vec<String> {
+        let requests = self.type_requests.read().await;
+        let mut popular: Vec<(&String, &u64)> = requests.iter().collect();
+        popular.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        popular
+            .into_iter()
+            .take(limit)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Заранее подготовить подробности типов; возвращает число найденных
+    pub async fn warm_type_details(&self, names: &[String]) -> usize {
+        let mut warmed = HashMap::new();
+        for name in names {
+            if let Ok(details) = self.build_type_details(name).await {
+                warmed.insert(name.clone(), details);
+            }
+        }
+        let count = warmed.len();
+        self.details_cache.write().await.extend(warmed);
+        count
+    }
+
+    /// Сбросить подготовленные подробности типов (после перезагрузки типов);
+    /// статистика запросов сохраняется
+    pub async fn clear_cache(&self) {
+        self.details_cache.write().await.clear();
+    }
+
+    /// Сбросить подробности типов, затронутых изменением репозитория
+    pub async fn invalidate_types(&self, change: &RepositoryChange) {
+        self.details_cache
+            .write()
+            .await
+            .retain(|name, _| !change.affects_name(name));
+    }
+
     /// Получить все типы с документацией для веб-интерфейса
     pub async fn get_all_types_with_documentation(&self) -> Result<Vec<WebTypeInfo>> {
         let start_time = std::time::Instant::now();
@@ -485,6 +1007,12 @@ impl WebTypeService {
         Ok(web_types)
     }
 
+    /// Получить страницу списка типов с сортировкой
+    pub async fn list_types(&self, query: &TypeListQuery) -> Result<TypeListPage> {
+        let all_types = self.get_all_types_with_documentation().await?;
+        Ok(paginate_types(all_types, query))
+    }
+
     /// Построить иерархию типов для веб-интерфейса
     pub async fn build_type_hierarchy(&self) -> Result<WebTypeHierarchy> {
         let start_time = std::time::Instant::now();
@@ -629,8 +1157,22 @@ impl WebTypeService {
         Ok(web_results)
     }
 
-    /// Получить детальную информацию о типе
+    /// Получить детальную информацию о типе; запрос учитывается в
+    /// популярности типов для прогрева
     pub async fn get_type_details(&self, type_name: &str) -> Result<WebTypeDetails> {
+        *self
+            .type_requests
+            .write()
+            .await
+            .entry(type_name.to_string())
+            .or_default() += 1;
+        if let Some(details) = self.details_cache.read().await.get(type_name) {
+            return Ok(details.clone());
+        }
+        self.build_type_details(type_name).await
+    }
+
+    async fn build_type_details(&self, type_name: &str) -> Result<WebTypeDetails> {
         println!("📄 Получение деталей для типа: {}", type_name);
 
         // Ищем тип по точному имени
@@ -760,6 +1302,45 @@ impl WebTypeService {
     }
 }
 
+/// Отсортировать типы и вырезать запрошенную страницу
+pub fn paginate_types(mut types: Vec<WebTypeInfo>, query: &TypeListQuery) -> TypeListPage {
+    match query.sort_by {
+        TypeSortField::Name => types.sort_by(|a, b| a.name.cmp(&b.name)),
+        TypeSortField::MethodsCount => types.sort_by(|a, b| {
+            a.methods_count
+                .cmp(&b.methods_count)
+                .then_with(|| a.name.cmp(&b.name))
+        }),
+        TypeSortField::PropertiesCount => types.sort_by(|a, b| {
+            a.properties_count
+                .cmp(&b.properties_count)
+                .then_with(|| a.name.cmp(&b.name))
+        }),
+    }
+    if query.descending {
+        types.reverse();
+    }
+
+    let page = query.page.max(1);
+    let per_page = query.per_page.clamp(1, MAX_TYPES_PER_PAGE);
+    let total_count = types.len();
+    let total_pages = total_count.div_ceil(per_page);
+
+    let items = types
+        .into_iter()
+        .skip((page - 1) * per_page)
+        .take(per_page)
+        .collect();
+
+    TypeListPage {
+        items,
+        total_count,
+        page,
+        per_page,
+        total_pages,
+    }
+}
+
 /// Фильтры для поиска в веб-интерфейсе
 #[derive(Debug, Clone, Default)]
 pub struct SearchFilters {
@@ -835,6 +1416,13 @@ pub struct AnalysisTypeService {
 
     /// Калькулятор покрытия типизации
     coverage_calculator: Arc<CoverageCalculator>,
+
+    /// Конструкции языка целевой версии платформы
+    language_features: LanguageFeatures,
+
+    /// Зависимости модулей проекта: какие модули анализировать заново
+    /// после правки общего модуля
+    module_graph: Arc<RwLock<ModuleDependencyGraph>>,
 }
 
 /// Анализатор BSL проектов
@@ -844,6 +1432,8 @@ pub struct ProjectAnalyzer {
 
 /// Калькулятор покрытия типизации
 pub struct CoverageCalculator {
+    /// Разрешение типов выражений модуля
+    resolution_service: Arc<TypeResolutionService>,
     coverage_cache: Arc<RwLock<HashMap<String, CoverageReport>>>,
 }
 
@@ -857,29 +1447,252 @@ pub struct ProjectAnalysisResult {
     pub total_variables: usize,
     pub type_errors: Vec<TypeDiagnostic>,
     pub coverage_report: CoverageReport,
+    /// Покрытие явными аннотациями (отдельно от покрытия выводом)
+    pub annotation_coverage: AnnotationCoverage,
+    /// Покрытие аннотациями по файлам
+    pub annotations_by_file: HashMap<String, AnnotationCoverage>,
+    /// Диагностики, сброшенные на диск при анализе в ограниченной памяти
+    /// (в `type_errors` их нет)
+    pub spilled_diagnostics: Option<SpilledDiagnostics>,
     pub analysis_time: std::time::Duration,
 }
 
-/// Отчёт о покрытии типизации
+/// Диагностики на диске и их количество по файлам: сводки и пороги
+/// считаются без чтения самих диагностик
 #[derive(Debug, Clone)]
+pub struct SpilledDiagnostics {
+    file: Arc<SpillFile<TypeDiagnostic>>,
+    /// Файл → (ошибок, предупреждений, прочих)
+    counts: HashMap<String, (usize, usize, usize)>,
+}
+
+impl SpilledDiagnostics {
+    /// Диагностик в файлах, оставшихся в результате
+    pub fn len(&self) -> usize {
+        self.counts.values().map(|(e, w, o)| e + w + o).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn errors(&self) -> usize {
+        self.counts.values().map(|(errors, _, _)| errors).sum()
+    }
+
+    /// Прочитать диагностики файлов, оставшихся в результате
+    pub fn read(&self) -> crate::Result<impl Iterator<Item = crate::Result<TypeDiagnostic>> + '_> {
+        Ok(self.file.read()?.filter(|diagnostic| match diagnostic {
+            Ok(diagnostic) => self.counts.contains_key(&diagnostic.file_path),
+            Err(_) => true,
+        }))
+    }
+}
+
+/// Диагностики анализа: в памяти, пока их объём не превысит бюджет, затем
+/// на диске
+struct DiagnosticSink {
+    retained: Vec<TypeDiagnostic>,
+    retained_bytes: usize,
+    budget: usize,
+    spill_dir: Option<std::path::PathBuf>,
+    write_guard: WriteGuard,
+    spill: Option<SpillFile<TypeDiagnostic>>,
+    counts: HashMap<String, (usize, usize, usize)>,
+}
+
+impl DiagnosticSink {
+    fn new(config: &BoundedAnalysisConfig) -> Self {
+        Self {
+            retained: Vec::new(),
+            retained_bytes: 0,
+            budget: config.wave_bytes(),
+            spill_dir: config.spill_dir.clone(),
+            write_guard: config.write_guard.clone(),
+            spill: None,
+            counts: HashMap::new(),
+        }
+    }
+
+    fn extend(&mut self, diagnostics: Vec<TypeDiagnostic>) {
+        for diagnostic in diagnostics {
+            self.retained_bytes += std::mem::size_of::<TypeDiagnostic>()
+                + diagnostic.file_path.len()
+                + diagnostic.message.len()
+                + diagnostic.suggested_fix.as_ref().map_or(0, String::len);
+            self.retained.push(diagnostic);
+        }
+    }
+
+    /// Сбросить диагностики на диск, если они не умещаются в бюджет
+    fn spill_if_needed(&mut self) -> Result<()> {
+        let Some(dir) = &self.spill_dir else {
+            return Ok(());
+        };
+        if self.retained_bytes <= self.budget {
+            return Ok(());
+        }
+        if self.spill.is_none() {
+            self.spill = SpillFile::create(dir, "bsl-diagnostics", &self.write_guard)?;
+        }
+        let Some(spill) = self.spill.as_mut() else {
+            // Запись запрещена: диагностики остаются в памяти
+            self.spill_dir = None;
+            return Ok(());
+        };
+        for diagnostic in self.retained.drain(..) {
+            let counts = self.counts.entry(diagnostic.file_path.clone()).or_default();
+            match diagnostic.severity {
+                DiagnosticSeverity::Error => counts.0 += 1,
+                DiagnosticSeverity::Warning => counts.1 += 1,
+                _ => counts.2 += 1,
+            }
+            spill.push(&diagnostic)?;
+        }
+        self.retained_bytes = 0;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(Vec<TypeDiagnostic>, Option<SpilledDiagnostics>)> {
+        let spilled = match self.spill {
+            Some(mut file) => {
+                file.finish()?;
+                info!(
+                    "💾 Сброшено на диск {} диагностик: {}",
+                    file.len(),
+                    file.path().display()
+                );
+                Some(SpilledDiagnostics {
+                    file: Arc::new(file),
+                    counts: self.counts,
+                })
+            }
+            None => None,
+        };
+        Ok((self.retained, spilled))
+    }
+}
+
+/// Отчёт о покрытии типизации
+#[derive(Debug, Clone, Default)]
 pub struct CoverageReport {
     pub total_expressions: usize,
+    /// Выражения с известным или выведенным типом
     pub typed_expressions: usize,
+    pub known_expressions: usize,
+    pub inferred_expressions: usize,
     pub coverage_percentage: f32,
     pub by_file: HashMap<String, FileCoverage>,
 }
 
+impl CoverageReport {
+    /// Отчёт по покрытию файлов
+    pub fn from_files(files: impl IntoIterator<Item = FileCoverage>) -> Self {
+        let mut report = Self {
+            by_file: files
+                .into_iter()
+                .map(|file| (file.file_path.clone(), file))
+                .collect(),
+            ..Default::default()
+        };
+        report.recalculate();
+        report
+    }
+
+    /// Пересчитать итоги по файлам отчёта
+    pub fn recalculate(&mut self) {
+        let files = self.by_file.values();
+        self.total_expressions = files.clone().map(|f| f.total_expressions).sum();
+        self.typed_expressions = files.clone().map(|f| f.typed_expressions).sum();
+        self.known_expressions = files.clone().map(|f| f.known_expressions).sum();
+        self.inferred_expressions = files.map(|f| f.inferred_expressions).sum();
+        self.coverage_percentage = percentage(self.typed_expressions, self.total_expressions);
+    }
+
+    /// Файлы с наименьшим покрытием (только с неизвестными типами)
+    pub fn least_covered(&self, limit: usize) -> Vec<&FileCoverage> {
+        let mut files: Vec<&FileCoverage> = self
+            .by_file
+            .values()
+            .filter(|file| file.unknown_expressions > 0)
+            .collect();
+        files.sort_by(|a, b| {
+            a.coverage_percentage
+                .total_cmp(&b.coverage_percentage)
+                .then_with(|| b.unknown_expressions.cmp(&a.unknown_expressions))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+        });
+        files.truncate(limit);
+        files
+    }
+}
+
 /// Покрытие типизации файла
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FileCoverage {
     pub file_path: String,
     pub total_expressions: usize,
+    /// Выражения с известным или выведенным типом
     pub typed_expressions: usize,
+    /// Тип известен точно
+    pub known_expressions: usize,
+    /// Тип выведен с неполной уверенностью
+    pub inferred_expressions: usize,
+    /// Тип не определить (Dynamic)
+    pub unknown_expressions: usize,
     pub coverage_percentage: f32,
 }
 
+impl FileCoverage {
+    pub fn new(file_path: &str) -> Self {
+        Self {
+            file_path: file_path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Учесть выражение с разрешённым типом
+    pub fn record(&mut self, resolution: &TypeResolution) {
+        match ExpressionCoverage::of(resolution) {
+            ExpressionCoverage::Known => self.known_expressions += 1,
+            ExpressionCoverage::Inferred => self.inferred_expressions += 1,
+            ExpressionCoverage::Unknown => self.unknown_expressions += 1,
+        }
+        self.total_expressions += 1;
+        self.typed_expressions = self.known_expressions + self.inferred_expressions;
+        self.coverage_percentage = percentage(self.typed_expressions, self.total_expressions);
+    }
+}
+
+/// Насколько определён тип выражения
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpressionCoverage {
+    Known,
+    Inferred,
+    /// Тип неизвестен или динамический
+    Unknown,
+}
+
+impl ExpressionCoverage {
+    pub fn of(resolution: &TypeResolution) -> Self {
+        match (&resolution.certainty, &resolution.result) {
+            (_, ResolutionResult::Dynamic) | (Certainty::Unknown, _) => Self::Unknown,
+            (Certainty::Known, _) => Self::Known,
+            (Certainty::Inferred(_), _) => Self::Inferred,
+        }
+    }
+}
+
+fn percentage(part: usize, total: usize) -> f32 {
+    if total > 0 {
+        part as f32 / total as f32 * 100.0
+    } else {
+        0.0
+    }
+}
+
 /// Диагностика типов
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TypeDiagnostic {
     pub file_path: String,
     pub line: u32,
@@ -887,10 +1700,12 @@ pub struct TypeDiagnostic {
     pub severity: DiagnosticSeverity,
     pub message: String,
     pub suggested_fix: Option<String>,
+    /// Владельцы модуля по файлу владельцев (пусто, если не назначены)
+    pub owners: Vec<String>,
 }
 
 /// Уровень серьёзности диагностики
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum DiagnosticSeverity {
     Error,
     Warning,
@@ -898,48 +1713,357 @@ pub enum DiagnosticSeverity {
     Hint,
 }
 
+impl DiagnosticSeverity {
+    /// Важность в протоколе LSP: 1=Error, 2=Warning, 3=Information, 4=Hint
+    pub fn lsp_severity(&self) -> u8 {
+        match self {
+            DiagnosticSeverity::Error => 1,
+            DiagnosticSeverity::Warning => 2,
+            DiagnosticSeverity::Info => 3,
+            DiagnosticSeverity::Hint => 4,
+        }
+    }
+}
+
+/// Сводка анализа по одной подсистеме конфигурации
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SubsystemAnalysisSummary {
+    pub subsystem: String,
+    pub files: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub total_expressions: usize,
+    pub typed_expressions: usize,
+    pub coverage_percentage: f32,
+}
+
+impl ProjectAnalysisResult {
+    /// Сгруппировать диагностики и покрытие по основной подсистеме модулей
+    pub fn by_subsystem(&self, index: &SubsystemIndex) -> Vec<SubsystemAnalysisSummary> {
+        let mut groups: HashMap<String, SubsystemAnalysisSummary> = HashMap::new();
+
+        for file in self.coverage_report.by_file.values() {
+            let summary = subsystem_summary(&mut groups, index, &self.project_path, &file.file_path);
+            summary.files += 1;
+            summary.total_expressions += file.total_expressions;
+            summary.typed_expressions += file.typed_expressions;
+        }
+        for diagnostic in &self.type_errors {
+            let summary =
+                subsystem_summary(&mut groups, index, &self.project_path, &diagnostic.file_path);
+            match diagnostic.severity {
+                DiagnosticSeverity::Error => summary.errors += 1,
+                DiagnosticSeverity::Warning => summary.warnings += 1,
+                _ => {}
+            }
+        }
+        for (file_path, (errors, warnings, _)) in self.spilled_counts() {
+            let summary = subsystem_summary(&mut groups, index, &self.project_path, file_path);
+            summary.errors += errors;
+            summary.warnings += warnings;
+        }
+
+        let mut result: Vec<_> = groups.into_values().collect();
+        for summary in &mut result {
+            if summary.total_expressions > 0 {
+                summary.coverage_percentage =
+                    summary.typed_expressions as f32 / summary.total_expressions as f32 * 100.0;
+            }
+        }
+        result.sort_by(|a, b| a.subsystem.cmp(&b.subsystem));
+        result
+    }
+
+    /// Данные для проверки порогов качества: выражения без известного типа
+    /// (Dynamic) считаются по модулям относительно корня проекта
+    pub fn gate_input(&self) -> GateInput {
+        let spilled_errors = self.spilled_diagnostics.as_ref().map_or(0, |s| s.errors());
+        GateInput {
+            errors: self
+                .type_errors
+                .iter()
+                .filter(|d| d.severity == DiagnosticSeverity::Error)
+                .count()
+                + spilled_errors,
+            coverage_percentage: self.coverage_report.coverage_percentage,
+            dynamic_by_module: self
+                .coverage_report
+                .by_file
+                .values()
+                .map(|file| {
+                    (
+                        relative_module_path(&self.project_path, &file.file_path),
+                        file.unknown_expressions,
+                    )
+                })
+                .collect(),
+            coverage_by_module: self
+                .coverage_report
+                .by_file
+                .values()
+                .filter(|file| file.total_expressions > 0)
+                .map(|file| {
+                    (
+                        relative_module_path(&self.project_path, &file.file_path),
+                        file.coverage_percentage,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Количество диагностик на диске по файлам
+    fn spilled_counts(&self) -> impl Iterator<Item = (&String, (usize, usize, usize))> {
+        self.spilled_diagnostics
+            .iter()
+            .flat_map(|spilled| spilled.counts.iter().map(|(file, counts)| (file, *counts)))
+    }
+
+    /// Всего диагностик, включая сброшенные на диск
+    pub fn diagnostics_count(&self) -> usize {
+        self.type_errors.len() + self.spilled_diagnostics.as_ref().map_or(0, |s| s.len())
+    }
+
+    /// Проставить владельцев диагностикам по файлу владельцев (сброшенным
+    /// на диск — при чтении)
+    pub fn assign_owners(&mut self, ownership: &OwnershipMap) {
+        for diagnostic in &mut self.type_errors {
+            let module = relative_module_path(&self.project_path, &diagnostic.file_path);
+            diagnostic.owners = ownership.owners_for_module(&module).to_vec();
+        }
+    }
+
+    /// Сводка по владельцам: модули, ошибки и предупреждения
+    pub fn by_owner(&self, ownership: &OwnershipMap) -> Vec<OwnerSummary> {
+        let files: Vec<String> = self
+            .coverage_report
+            .by_file
+            .values()
+            .map(|f| relative_module_path(&self.project_path, &f.file_path))
+            .collect();
+        let diagnostics: Vec<(String, bool)> = self
+            .type_errors
+            .iter()
+            .filter(|d| {
+                matches!(
+                    d.severity,
+                    DiagnosticSeverity::Error | DiagnosticSeverity::Warning
+                )
+            })
+            .map(|d| {
+                (
+                    relative_module_path(&self.project_path, &d.file_path),
+                    d.severity == DiagnosticSeverity::Error,
+                )
+            })
+            .chain(
+                self.spilled_counts()
+                    .flat_map(|(file_path, (errors, warnings, _))| {
+                        let module = relative_module_path(&self.project_path, file_path);
+                        std::iter::repeat_n((module.clone(), true), errors)
+                            .chain(std::iter::repeat_n((module, false), warnings))
+                    }),
+            )
+            .collect();
+
+        ownership.summarize(
+            files.iter().map(String::as_str),
+            diagnostics.iter().map(|(path, is_error)| (path.as_str(), *is_error)),
+        )
+    }
+
+    /// Оставить в результате только модули указанной подсистемы (включая вложенные)
+    pub fn retain_subsystem(&mut self, index: &SubsystemIndex, subsystem: &str) {
+        let root = self.project_path.clone();
+        let in_subsystem = |file_path: &str| {
+            index.module_in_subsystem(&relative_module_path(&root, file_path), subsystem)
+        };
+
+        self.type_errors.retain(|d| in_subsystem(&d.file_path));
+        if let Some(spilled) = &mut self.spilled_diagnostics {
+            spilled.counts.retain(|path, _| in_subsystem(path));
+        }
+        self.annotations_by_file.retain(|path, _| in_subsystem(path));
+        self.annotation_coverage = AnnotationCoverage::default();
+        for annotations in self.annotations_by_file.values() {
+            self.annotation_coverage.merge(annotations);
+        }
+        self.coverage_report
+            .by_file
+            .retain(|_, file| in_subsystem(&file.file_path));
+        self.coverage_report.recalculate();
+        self.analyzed_files = self.coverage_report.by_file.len();
+    }
+}
+
+/// Путь модуля относительно корня проекта (подсистемы сопоставляются по нему)
+pub(crate) fn relative_module_path(project_path: &str, file_path: &str) -> String {
+    Path::new(file_path)
+        .strip_prefix(project_path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| file_path.to_string())
+}
+
+fn subsystem_summary<'a>(
+    groups: &'a mut HashMap<String, SubsystemAnalysisSummary>,
+    index: &SubsystemIndex,
+    project_path: &str,
+    file_path: &str,
+) -> &'a mut SubsystemAnalysisSummary {
+    let subsystem = index.group_for_module(&relative_module_path(project_path, file_path));
+    groups
+        .entry(subsystem.clone())
+        .or_insert_with(|| SubsystemAnalysisSummary {
+            subsystem,
+            files: 0,
+            errors: 0,
+            warnings: 0,
+            total_expressions: 0,
+            typed_expressions: 0,
+            coverage_percentage: 0.0,
+        })
+}
+
 impl AnalysisTypeService {
     /// Создать новый сервис анализа
     pub fn new(resolution_service: Arc<TypeResolutionService>) -> Self {
+        let coverage_calculator = Arc::new(CoverageCalculator::new(resolution_service.clone()));
         Self {
             resolution_service,
             project_analyzer: Arc::new(ProjectAnalyzer::new()),
-            coverage_calculator: Arc::new(CoverageCalculator::new()),
+            coverage_calculator,
+            language_features: LanguageFeatures::latest(),
+            module_graph: Arc::new(RwLock::new(ModuleDependencyGraph::new())),
         }
     }
 
+    /// Целевая версия платформы: конструкции более новых версий — ошибки
+    pub fn with_language_features(mut self, features: LanguageFeatures) -> Self {
+        self.language_features = features;
+        self
+    }
+
     /// Проанализировать проект BSL
     pub async fn analyze_project(&self, project_path: &Path) -> Result<ProjectAnalysisResult> {
-        println!("🔍 Анализ проекта: {}", project_path.display());
+        self.analyze_project_streaming(project_path, &mut |_| {}).await
+    }
+
+    /// Проанализировать проект, передавая результат каждого файла сразу после
+    /// его анализа (для потокового вывода на больших конфигурациях)
+    pub async fn analyze_project_streaming(
+        &self,
+        project_path: &Path,
+        on_file: &mut (dyn FnMut(&FileAnalysisResult) + Send),
+    ) -> Result<ProjectAnalysisResult> {
+        self.analyze_project_bounded(project_path, &BoundedAnalysisConfig::default(), on_file)
+            .await
+    }
+
+    /// Анализ проекта волнами модулей: в памяти одновременно только тексты
+    /// одной волны, диагностики сверх бюджета сбрасываются в
+    /// `config.spill_dir`, а после каждой волны память сверяется с пределом
+    pub async fn analyze_project_bounded(
+        &self,
+        project_path: &Path,
+        config: &BoundedAnalysisConfig,
+        on_file: &mut (dyn FnMut(&FileAnalysisResult) + Send),
+    ) -> Result<ProjectAnalysisResult> {
+        info!("🔍 Анализ проекта: {}", project_path.display());
         let start_time = std::time::Instant::now();
 
         // Поиск всех BSL файлов
         let bsl_files = self.find_bsl_files(project_path).await?;
-        println!("📁 Найдено {} BSL файлов", bsl_files.len());
+        info!("📁 Найдено {} BSL файлов", bsl_files.len());
+
+        let sizes: Vec<u64> = bsl_files
+            .iter()
+            .map(|path| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+            .collect();
+        let waves = plan_waves(&sizes, config.wave_bytes());
+        if waves.len() > 1 {
+            info!(
+                "🌊 {} волн по {}",
+                waves.len(),
+                format_megabytes(config.wave_bytes())
+            );
+        }
+        let mut ceiling = MemoryCeiling::new(config.memory_limit);
+
+        // Сигнатуры функций всего проекта: вызовы пользовательских функций
+        // разрешаются по выведенным возвращаемым типам. Таблица собирается
+        // по волнам, тексты волны освобождаются до чтения следующей
+        let mut table = SignatureTable::new();
+        let mut globals = GlobalScope::new();
+        let mut modules = ModuleDependencyGraph::new();
+        for (number, wave) in waves.iter().enumerate() {
+            for path in &bsl_files[wave.clone()] {
+                let Ok(text) = crate::core::fs_utils::read_bsl_file(path) else {
+                    continue;
+                };
+                let file = path.to_string_lossy();
+                table.add_module(&file, &text);
+                globals.add_module(&file, &text);
+                modules.add_module(&file, &text);
+            }
+            ceiling.check(&format!("индексации волны {}", number + 1))?;
+        }
+        *self.module_graph.write().await = modules;
+        let signatures = self
+            .resolution_service
+            .install_signatures(table, globals)
+            .instrument(info_span!("signatures", modules = bsl_files.len()))
+            .await;
+        info!("🧮 Выведены возвращаемые типы {} функций", signatures);
+        if config.is_bounded() {
+            // Типы выведены: выражения возвратов до конца анализа не нужны
+            self.resolution_service.release_signature_sources().await;
+        }
 
         let mut total_functions = 0;
         let mut total_variables = 0;
-        let mut type_errors = Vec::new();
-
-        // Анализируем каждый файл
-        for file_path in &bsl_files {
-            match self.analyze_file(file_path).await {
-                Ok(file_analysis) => {
-                    total_functions += file_analysis.functions_count;
-                    total_variables += file_analysis.variables_count;
-                    type_errors.extend(file_analysis.diagnostics);
-                }
-                Err(e) => {
-                    println!("⚠️ Ошибка анализа {}: {}", file_path.display(), e);
+        let mut diagnostics = DiagnosticSink::new(config);
+        let mut annotation_coverage = AnnotationCoverage::default();
+        let mut annotations_by_file = HashMap::new();
+        let mut coverage_by_file = Vec::new();
+
+        // Анализируем файлы волнами
+        for (number, wave) in waves.iter().enumerate() {
+            for file_path in &bsl_files[wave.clone()] {
+                let span = info_span!("analyze_file", file = %file_path.display());
+                match self.analyze_file(file_path).instrument(span).await {
+                    Ok(file_analysis) => {
+                        on_file(&file_analysis);
+                        total_functions += file_analysis.functions_count;
+                        total_variables += file_analysis.variables_count;
+                        annotation_coverage.merge(&file_analysis.annotations);
+                        annotations_by_file.insert(
+                            file_analysis.file_path.to_string_lossy().to_string(),
+                            file_analysis.annotations,
+                        );
+                        coverage_by_file.push(file_analysis.coverage);
+                        diagnostics.extend(file_analysis.diagnostics);
+                    }
+                    Err(e) => {
+                        warn!("⚠️ Ошибка анализа {}: {}", file_path.display(), e);
+                    }
                 }
             }
+            diagnostics.spill_if_needed()?;
+            ceiling.check(&format!("анализа волны {}", number + 1))?;
+        }
+        let (type_errors, spilled_diagnostics) = diagnostics.finish()?;
+        if let Some(peak) = ceiling.peak_bytes() {
+            info!("📈 Пиковая память анализа: {}", format_megabytes(peak));
         }
 
-        // Рассчитываем покрытие типизации
-        let coverage_report = self
-            .coverage_calculator
-            .calculate_coverage(&bsl_files)
-            .await?;
+        // Покрытие типизации собирается по результатам файлов
+        let coverage_report = CoverageReport::from_files(coverage_by_file);
+        info!(
+            "📊 Покрытие типизации: {:.1}% ({} из {} выражений)",
+            coverage_report.coverage_percentage,
+            coverage_report.typed_expressions,
+            coverage_report.total_expressions
+        );
 
         let analysis_time = start_time.elapsed();
 
@@ -951,10 +2075,114 @@ impl AnalysisTypeService {
             total_variables,
             type_errors,
             coverage_report,
+            annotation_coverage,
+            annotations_by_file,
+            spilled_diagnostics,
             analysis_time,
         })
     }
 
+    /// Построить граф вызовов процедур и функций проекта (иерархия вызовов
+    /// в редакторе); методы общих модулей вызываются как `Модуль.Метод`.
+    /// Попутно строится граф зависимостей модулей
+    pub async fn build_call_graph(&self, project_path: &Path) -> Result<ProjectCallGraph> {
+        let mut graph = ProjectCallGraph::new();
+        let mut modules = ModuleDependencyGraph::new();
+        for file_path in self.find_bsl_files(project_path).await? {
+            let Ok(text) = crate::core::fs_utils::read_bsl_file(&file_path) else {
+                continue;
+            };
+            let module = module_name_from_path(&file_path);
+            let file = file_path.to_string_lossy();
+            graph.add_module(&file, module.as_deref(), &text);
+            modules.add_module(&file, &text);
+        }
+        info!("🔗 Граф вызовов: {} методов", graph.len());
+        *self.module_graph.write().await = modules;
+        Ok(graph)
+    }
+
+    /// Построить граф зависимостей модулей проекта
+    pub async fn build_dependency_graph(
+        &self,
+        project_path: &Path,
+    ) -> Result<ModuleDependencyGraph> {
+        let mut modules = ModuleDependencyGraph::new();
+        for file_path in self.find_bsl_files(project_path).await? {
+            let Ok(text) = crate::core::fs_utils::read_bsl_file(&file_path) else {
+                continue;
+            };
+            modules.add_module(&file_path.to_string_lossy(), &text);
+        }
+        info!("🕸️ Граф зависимостей: {} модулей", modules.len());
+        *self.module_graph.write().await = modules.clone();
+        Ok(modules)
+    }
+
+    /// Граф зависимостей модулей, построенный последним анализом проекта
+    /// (или [`Self::build_dependency_graph`])
+    pub async fn dependency_graph(&self) -> ModuleDependencyGraph {
+        self.module_graph.read().await.clone()
+    }
+
+    /// Обновить модуль в графе зависимостей: `text` — новый текст, `None` —
+    /// файл удалён. Возвращает модули, которые нужно проанализировать
+    /// заново, или `None`, если граф ещё не построен и затронутые модули
+    /// неизвестны
+    pub async fn update_module(&self, file: &str, text: Option<&str>) -> Option<BTreeSet<String>> {
+        let mut graph = self.module_graph.write().await;
+        if graph.is_empty() {
+            return None;
+        }
+        // Удалённый модуль затрагивает тех, кто зависел от него до правки,
+        // изменённый — и прежних, и новых зависимых
+        let mut affected = graph.affected_by([file]);
+        match text {
+            Some(text) => graph.add_module(file, text),
+            None => graph.remove_module(file),
+        }
+        affected.extend(graph.affected_by([file]));
+        Some(affected)
+    }
+
+    /// Проанализировать заново изменённые модули и модули, зависящие от
+    /// них; остальные результаты прежнего анализа остаются в силе. Без
+    /// построенного графа анализируются только изменённые модули
+    pub async fn reanalyze_modules(&self, changed: &[PathBuf]) -> Result<Vec<FileAnalysisResult>> {
+        let mut affected: BTreeSet<String> = BTreeSet::new();
+        for path in changed {
+            let text = crate::core::fs_utils::read_bsl_file(path).ok();
+            let file = path.to_string_lossy();
+            self.resolution_service
+                .update_module(&file, text.as_deref())
+                .await;
+            match self.update_module(&file, text.as_deref()).await {
+                Some(modules) => affected.extend(modules),
+                None => {
+                    affected.insert(file.to_string());
+                }
+            }
+        }
+        info!(
+            "🔁 Повторный анализ: изменено модулей {}, затронуто {}",
+            changed.len(),
+            affected.len()
+        );
+
+        let mut results = Vec::new();
+        for file in &affected {
+            let path = Path::new(file);
+            if !path.exists() {
+                continue;
+            }
+            match self.analyze_file(path).await {
+                Ok(file_analysis) => results.push(file_analysis),
+                Err(e) => warn!("⚠️ Ошибка анализа {}: {}", path.display(), e),
+            }
+        }
+        Ok(results)
+    }
+
     /// Вычислить покрытие типизации
     pub async fn calculate_type_coverage(
         &self,
@@ -999,12 +2227,93 @@ impl AnalysisTypeService {
     }
 
     async fn analyze_file(&self, file_path: &Path) -> Result<FileAnalysisResult> {
-        // TODO: Реализовать анализ отдельного файла
+        let text = crate::core::fs_utils::read_bsl_file(file_path)?;
+        let annotations =
+            info_span!("parse").in_scope(|| annotation_coverage::analyze_module(&text));
+
+        let features = &self.language_features;
+        let mut diagnostics: Vec<TypeDiagnostic> = unsupported_syntax(&text, features)
+            .into_iter()
+            .map(|syntax| TypeDiagnostic {
+                file_path: file_path.display().to_string(),
+                line: syntax.line,
+                column: syntax.column,
+                severity: DiagnosticSeverity::Error,
+                message: syntax.message(features),
+                suggested_fix: Some(format!(
+                    "Повысьте platform_version до {} или перепишите код без этой конструкции",
+                    syntax.feature.since()
+                )),
+                owners: Vec::new(),
+            })
+            .collect();
+        diagnostics.extend(
+            await_outside_async(&text, features)
+                .into_iter()
+                .map(|found| TypeDiagnostic {
+                    file_path: file_path.display().to_string(),
+                    line: found.line,
+                    column: found.column,
+                    severity: DiagnosticSeverity::Error,
+                    message: found.message(),
+                    suggested_fix: Some(
+                        "Добавьте модификатор Асинх в заголовок метода".to_string(),
+                    ),
+                    owners: Vec::new(),
+                }),
+        );
+        let file = file_path.display().to_string();
+        let query_issues = self
+            .resolution_service
+            .check_query_parameters(Some(&file), &text)
+            .await;
+        diagnostics.extend(query_issues.into_iter().map(|issue| {
+            // Запрос без значения параметра падает при выполнении, тип
+            // значения выведен эвристически
+            let (severity, suggested_fix) = match &issue.kind {
+                QueryParameterIssueKind::Missing => (
+                    DiagnosticSeverity::Error,
+                    Some(format!(
+                        "Добавьте {}.УстановитьПараметр(\"{}\", ...)",
+                        issue.variable, issue.parameter
+                    )),
+                ),
+                QueryParameterIssueKind::Mismatch { .. } => (DiagnosticSeverity::Warning, None),
+            };
+            TypeDiagnostic {
+                file_path: file.clone(),
+                line: issue.line,
+                column: issue.column,
+                severity,
+                message: issue.message(),
+                suggested_fix,
+                owners: Vec::new(),
+            }
+        }));
+        // Сравнение с несуществующим значением перечисления падает при
+        // выполнении, но строка может не выполняться никогда
+        let enum_issues = self.resolution_service.check_enum_comparisons(&text).await;
+        diagnostics.extend(enum_issues.into_iter().map(|issue| TypeDiagnostic {
+            file_path: file.clone(),
+            line: issue.line,
+            column: issue.column,
+            severity: DiagnosticSeverity::Warning,
+            message: issue.message(),
+            suggested_fix: None,
+            owners: Vec::new(),
+        }));
+        let coverage = self
+            .coverage_calculator
+            .file_coverage(&file, &text)
+            .instrument(info_span!("coverage"))
+            .await;
         Ok(FileAnalysisResult {
             file_path: file_path.to_path_buf(),
-            functions_count: 0,
+            functions_count: annotations.total_methods,
             variables_count: 0,
-            diagnostics: Vec::new(),
+            diagnostics,
+            annotations,
+            coverage,
         })
     }
 }
@@ -1016,6 +2325,10 @@ pub struct FileAnalysisResult {
     pub functions_count: usize,
     pub variables_count: usize,
     pub diagnostics: Vec<TypeDiagnostic>,
+    /// Покрытие аннотациями методов файла
+    pub annotations: AnnotationCoverage,
+    /// Покрытие типизации выражений файла
+    pub coverage: FileCoverage,
 }
 
 impl ProjectAnalyzer {
@@ -1027,21 +2340,57 @@ impl ProjectAnalyzer {
 }
 
 impl CoverageCalculator {
-    pub fn new() -> Self {
+    pub fn new(resolution_service: Arc<TypeResolutionService>) -> Self {
         Self {
+            resolution_service,
             coverage_cache: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    /// Вычислить покрытие типизации для файлов
+    /// Покрытие типизации модуля. Учитываются выражения, тип которых
+    /// редактор показывает подсказкой: объявления переменных (`Перем` и
+    /// первое присваивание в методе) и аргументы вызовов, заданные именем.
+    /// Тип разрешается с учётом текста модуля: переменной — в конце строки
+    /// объявления (после присваивания), аргумента — в его позиции
+    pub async fn file_coverage(&self, file_path: &str, text: &str) -> FileCoverage {
+        let mut coverage = FileCoverage::new(file_path);
+        let line_ends: Vec<u32> = text
+            .lines()
+            .map(|line| line.chars().count() as u32)
+            .collect();
+        let variables = variable_sites(text).into_iter().map(|site| {
+            let column = line_ends
+                .get(site.line as usize)
+                .copied()
+                .unwrap_or(site.column);
+            (site.line, column, site.name)
+        });
+        let arguments = call_arguments(text).into_iter().flat_map(|call| {
+            call.arguments
+                .into_iter()
+                .filter_map(|argument| Some((argument.line, argument.column, argument.name?)))
+        });
+        for (line, column, name) in variables.chain(arguments) {
+            let context = TypeContext::at_position(file_path, line, column);
+            let resolution = self
+                .resolution_service
+                .resolve_in_document(&name, &context, text)
+                .await;
+            coverage.record(&resolution);
+        }
+        coverage
+    }
+
+    /// Вычислить покрытие типизации для файлов; нечитаемые файлы пропускаются
     pub async fn calculate_coverage(&self, files: &[std::path::PathBuf]) -> Result<CoverageReport> {
-        // TODO: Реализовать расчёт покрытия
-        Ok(CoverageReport {
-            total_expressions: files.len() * 10, // Заглушка
-            typed_expressions: files.len() * 7,  // Заглушка
-            coverage_percentage: 70.0,           // Заглушка
-            by_file: HashMap::new(),
-        })
+        let mut by_file = Vec::new();
+        for path in files {
+            match crate::core::fs_utils::read_bsl_file(path) {
+                Ok(text) => by_file.push(self.file_coverage(&path.to_string_lossy(), &text).await),
+                Err(e) => warn!("⚠️ Покрытие не рассчитано для {}: {}", path.display(), e),
+            }
+        }
+        Ok(CoverageReport::from_files(by_file))
     }
 }
 
@@ -1074,6 +2423,107 @@ mod tests {
         println!("✅ LspTypeService работает");
     }
 
+    #[test]
+    fn test_lsp_cache_invalidation() {
+        use crate::domain::types::{ConcreteType, PlatformType};
+        let known = |name: &str| {
+            TypeResolution::known(ConcreteType::Platform(PlatformType {
+                name: name.to_string(),
+                methods: Vec::new(),
+                properties: Vec::new(),
+            }))
+        };
+        let completion = |label: &str| LspCompletion {
+            label: label.to_string(),
+            kind: LspCompletionKind::Class,
+            detail: None,
+            documentation: None,
+            insert_text: label.to_string(),
+            filter_text: None,
+            sort_text: None,
+        };
+        let position = |line| PositionKey {
+            file_path: "Модуль.bsl".to_string(),
+            line,
+            column: 4,
+        };
+        let hover = |type_name: &str| HoverInfo {
+            content: String::new(),
+            type_info: String::new(),
+            type_name: Some(type_name.to_string()),
+            documentation: None,
+            examples: Vec::new(),
+            definition_path: None,
+        };
+
+        let mut cache = LspCache::default();
+        cache
+            .position_cache
+            .insert(position(1), known("Справочники.Валюты"));
+        cache.position_cache.insert(position(2), known("Массив"));
+        cache
+            .position_cache
+            .insert(position(3), TypeResolution::unknown());
+        cache
+            .hover_cache
+            .insert("hover:Модуль.bsl:2:4:Массив".to_string(), hover("Массив"));
+        cache.hover_cache.insert(
+            "hover:Модуль.bsl:1:4:Справочники.Валюты".to_string(),
+            hover("СправочникМенеджер.Валюты"),
+        );
+        for (key, label) in [
+            ("Модуль.bsl:5:1:Спр", "Справочники"),
+            ("Модуль.bsl:6:1:Мас", "Массив"),
+            ("Модуль.bsl:7:1:Массив.", "Добавить"),
+        ] {
+            cache
+                .completion_cache
+                .insert(key.to_string(), vec![completion(label)]);
+        }
+        cache
+            .prefix_completions
+            .insert("Вал".to_string(), Vec::new());
+        cache
+            .prefix_completions
+            .insert("Док".to_string(), vec![completion("Документы")]);
+
+        // Сбрасываются только записи, которые мог изменить новый справочник
+        let change = RepositoryChange {
+            type_names: ["Справочники.Валюты".to_string()].into_iter().collect(),
+            ..Default::default()
+        };
+        cache.invalidate(&change);
+        let lines: Vec<u32> = cache.position_cache.keys().map(|key| key.line).collect();
+        assert_eq!(lines, [2]);
+        assert_eq!(cache.hover_cache.len(), 1);
+        let completions: Vec<&String> = cache.completion_cache.keys().collect();
+        assert_eq!(completions, ["Модуль.bsl:6:1:Мас"]);
+        let prefixes: Vec<&String> = cache.prefix_completions.keys().collect();
+        assert_eq!(prefixes, ["Док"]);
+
+        cache.invalidate(&RepositoryChange {
+            cleared: true,
+            ..Default::default()
+        });
+        assert!(cache.position_cache.is_empty() && cache.prefix_completions.is_empty());
+    }
+
+    #[test]
+    fn test_scope_completions_grouped() {
+        let repo = Arc::new(InMemoryTypeRepository::new());
+        let lsp_service = LspTypeService::new(Arc::new(TypeResolutionService::new(repo)));
+        let document = concat!(
+            "Перем СуммаМодуля;\n\n",
+            "Процедура Расчет(СуммаДокумента)\n    С\nКонецПроцедуры\n"
+        );
+
+        let completions = lsp_service.scope_completions(document, 3, "С");
+        let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, ["СуммаДокумента", "СуммаМодуля"]);
+        assert!(completions[0].sort_text < completions[1].sort_text);
+        assert!(lsp_service.scope_completions(document, 3, "Объект.С").is_empty());
+    }
+
     #[tokio::test]
     async fn test_web_type_service() {
         let repo = Arc::new(InMemoryTypeRepository::new());
@@ -1093,4 +2543,202 @@ mod tests {
 
         println!("✅ WebTypeService работает");
     }
+
+    #[tokio::test]
+    async fn test_analyze_project_bounded() {
+        let project = tempfile::tempdir().unwrap();
+        for name in ["Первый", "Второй", "Третий"] {
+            let dir = project.path().join("CommonModules").join(name).join("Ext");
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("Module.bsl"),
+                "Процедура Загрузить() Экспорт\n    Ждать ЗагрузитьАсинх();\nКонецПроцедуры\n",
+            )
+            .unwrap();
+        }
+        let repo = Arc::new(InMemoryTypeRepository::new());
+        let service = AnalysisTypeService::new(Arc::new(TypeResolutionService::new(repo)));
+
+        // Без каталога сброса диагностики остаются в памяти
+        let in_memory = service.analyze_project(project.path()).await.unwrap();
+        assert_eq!(in_memory.type_errors.len(), 3);
+        assert!(in_memory.spilled_diagnostics.is_none());
+
+        // Волна на модуль, диагностики каждой волны сбрасываются на диск
+        let spill_dir = tempfile::tempdir().unwrap();
+        let config = BoundedAnalysisConfig {
+            wave_bytes: Some(1),
+            spill_dir: Some(spill_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let mut files = 0;
+        let result = service
+            .analyze_project_bounded(project.path(), &config, &mut |_| files += 1)
+            .await
+            .unwrap();
+        assert_eq!(files, 3);
+        assert!(result.type_errors.is_empty());
+        assert_eq!(result.diagnostics_count(), 3);
+        assert_eq!(result.gate_input().errors, 3);
+        let spilled = result.spilled_diagnostics.as_ref().unwrap();
+        let diagnostics: Vec<TypeDiagnostic> =
+            spilled.read().unwrap().map(Result::unwrap).collect();
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics.iter().all(|d| d.message.contains("Асинх")));
+
+        // Сброшенный файл удаляется вместе с результатом
+        drop(result);
+        assert_eq!(std::fs::read_dir(spill_dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reanalyze_dependents() {
+        let project = tempfile::tempdir().unwrap();
+        let write = |relative: &str, text: &str| {
+            let path = project.path().join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, text).unwrap();
+            path
+        };
+        let common = write(
+            "CommonModules/Заказы/Ext/Module.bsl",
+            "Функция Проверить(Заказ) Экспорт\n    Возврат Истина;\nКонецФункции\n",
+        );
+        let document = write(
+            "Documents/Заказ/Ext/ObjectModule.bsl",
+            "Процедура ПередЗаписью(Отказ)\n    Отказ = Не Заказы.Проверить(ЭтотОбъект);\nКонецПроцедуры\n",
+        );
+        write(
+            "Reports/Продажи/Ext/ObjectModule.bsl",
+            "Процедура Сформировать()\nКонецПроцедуры\n",
+        );
+        let repo = Arc::new(InMemoryTypeRepository::new());
+        let service = AnalysisTypeService::new(Arc::new(TypeResolutionService::new(repo)));
+
+        // Без графа известен только изменённый модуль
+        assert!(service
+            .update_module("Модуль.bsl", Some(""))
+            .await
+            .is_none());
+
+        let graph = service
+            .build_dependency_graph(project.path())
+            .await
+            .unwrap();
+        assert_eq!(graph.len(), 3);
+        let common_file = common.to_string_lossy().to_string();
+        let document_file = document.to_string_lossy().to_string();
+        assert_eq!(graph.dependents(&common_file), [document_file.as_str()]);
+
+        // Правка общего модуля — заново анализируются он и документ, но не отчёт
+        std::fs::write(
+            &common,
+            "Асинх Функция Проверить(Заказ) Экспорт\n    Возврат Истина;\nКонецФункции\n",
+        )
+        .unwrap();
+        let results = service.reanalyze_modules(&[common.clone()]).await.unwrap();
+        let files: Vec<_> = results.iter().map(|r| r.file_path.clone()).collect();
+        assert_eq!(files, [common.clone(), document.clone()]);
+
+        // Удалённый модуль затрагивает прежних зависимых
+        std::fs::remove_file(&common).unwrap();
+        let results = service.reanalyze_modules(&[common]).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_path, document);
+        assert_eq!(service.dependency_graph().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_file_coverage() {
+        use crate::domain::types::{ConcreteType, PrimitiveType};
+
+        let repo = Arc::new(InMemoryTypeRepository::new());
+        let calculator = CoverageCalculator::new(Arc::new(TypeResolutionService::new(repo)));
+
+        // Объявления Итог, Имя, Прочее и аргумент Итог; параметры заголовка
+        // (в том числе перенесённые на следующую строку) не учитываются
+        let text = "Процедура Заполнить(Данные,\n    Режим = 0) Экспорт\n    Итог = 0;\n    Имя = \"Заказ\";\n    Прочее = Данные.Значение;\n    Сообщить(Итог);\nКонецПроцедуры\n";
+        let module = calculator.file_coverage("Module.bsl", text).await;
+        assert_eq!(module.total_expressions, 4);
+        assert!(module.known_expressions >= 3);
+        assert_eq!(
+            module.typed_expressions + module.unknown_expressions,
+            module.total_expressions
+        );
+
+        let number = ConcreteType::Primitive(PrimitiveType::Number);
+        let mut file = FileCoverage::new("Второй.bsl");
+        file.record(&TypeResolution::known(number.clone()));
+        file.record(&TypeResolution::inferred(
+            0.7,
+            ResolutionResult::Concrete(number),
+        ));
+        file.record(&TypeResolution::inferred(0.9, ResolutionResult::Dynamic));
+        file.record(&TypeResolution::unknown());
+        assert_eq!(
+            (
+                file.known_expressions,
+                file.inferred_expressions,
+                file.unknown_expressions
+            ),
+            (1, 1, 2)
+        );
+        assert_eq!(file.coverage_percentage, 50.0);
+
+        let report = CoverageReport::from_files([module.clone(), file]);
+        assert_eq!(report.total_expressions, module.total_expressions + 4);
+        assert_eq!(
+            report.typed_expressions,
+            report.known_expressions + report.inferred_expressions
+        );
+        let least = report.least_covered(10);
+        assert_eq!(least[0].file_path, "Второй.bsl");
+        assert_eq!(least.len(), 1 + usize::from(module.unknown_expressions > 0));
+    }
+
+    fn web_type(name: &str, methods_count: usize) -> WebTypeInfo {
+        WebTypeInfo {
+            name: name.to_string(),
+            description: String::new(),
+            methods_count,
+            properties_count: 0,
+            examples: vec![],
+            url: format!("/types/{}", name),
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_paginate_types() {
+        let types = vec![
+            web_type("Структура", 5),
+            web_type("Массив", 12),
+            web_type("Соответствие", 7),
+        ];
+
+        let page = paginate_types(
+            types.clone(),
+            &TypeListQuery {
+                page: 1,
+                per_page: 2,
+                ..Default::default()
+            },
+        );
+        assert_eq!(page.total_count, 3);
+        assert_eq!(page.total_pages, 2);
+        let names: Vec<_> = page.items.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Массив", "Соответствие"]);
+
+        let page = paginate_types(
+            types,
+            &TypeListQuery {
+                page: 1,
+                per_page: 10,
+                sort_by: TypeSortField::MethodsCount,
+                descending: true,
+            },
+        );
+        assert_eq!(page.items[0].name, "Массив");
+        assert_eq!(page.items[2].name, "Структура");
+    }
 }
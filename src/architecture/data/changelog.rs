@@ -0,0 +1,428 @@
+//! Журнал изменений данных репозитория
+//!
+//! При каждой загрузке типов (запуск, смена настроек, перезагрузка) журнал
+//! сравнивает новый набор типов с предыдущим и записывает, какие типы
+//! появились, какие исчезли и у каких изменился состав методов и свойств.
+//! Так команда замечает дрейф модели конфигурации: записи отдаются лентой
+//! JSON и RSS и страницей «Что нового» веб-сервера.
+//!
+//! Для сравнения хранится только «отпечаток» каждого типа — имя, источник и
+//! строки членов (`Метод()`, `Свойство: Тип`). Если задан файл журнала
+//! (`changelog_path`, `BSL_CHANGELOG`), отпечатки и записи сохраняются в нём
+//! и переживают перезапуск; иначе журнал живёт в памяти процесса. Первая
+//! загрузка только запоминает отпечатки: «добавлены все типы» — не новость.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use super::{RawTypeData, TypeSource, WriteGuard};
+
+/// Версия формата файла; файл другой версии начинается заново
+pub const CHANGELOG_FORMAT_VERSION: u32 = 1;
+
+/// Сколько последних записей хранит журнал
+const MAX_ENTRIES: usize = 200;
+
+/// Что журнал помнит о типе между загрузками
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeDigest {
+    pub name: String,
+    /// `platform`, `configuration` или `user`
+    pub source: String,
+    /// `Метод()` и `Свойство: Тип`
+    pub members: BTreeSet<String>,
+}
+
+impl TypeDigest {
+    pub fn from_raw(raw: &RawTypeData) -> Self {
+        let source = match raw.source {
+            TypeSource::Platform { .. } => "platform",
+            TypeSource::Configuration { .. } => "configuration",
+            TypeSource::UserDefined { .. } => "user",
+        };
+        let methods = raw
+            .methods
+            .iter()
+            .map(|method| format!("{}()", method.name));
+        let properties = raw
+            .properties
+            .iter()
+            .map(|property| match property.type_name.as_str() {
+                "" => property.name.clone(),
+                type_name => format!("{}: {}", property.name, type_name),
+            });
+        Self {
+            name: raw.russian_name.clone(),
+            source: source.to_string(),
+            members: methods.chain(properties).collect(),
+        }
+    }
+}
+
+/// Тип, появившийся или исчезнувший в записи журнала
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedTypeRef {
+    pub id: String,
+    pub name: String,
+    pub source: String,
+}
+
+/// Тип, у которого изменился состав членов
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeMemberChange {
+    pub id: String,
+    pub name: String,
+    pub added_members: Vec<String>,
+    pub removed_members: Vec<String>,
+}
+
+/// Одна загрузка типов, изменившая данные репозитория
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeEntry {
+    /// Номер записи, растёт с каждой записью
+    pub sequence: u64,
+    /// Время загрузки, секунды Unix
+    pub timestamp: u64,
+    pub added: Vec<ChangedTypeRef>,
+    pub removed: Vec<ChangedTypeRef>,
+    pub changed: Vec<TypeMemberChange>,
+}
+
+impl ChangeEntry {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Краткая сводка для заголовка: «добавлено типов: 3, удалено: 1»
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.added.is_empty() {
+            parts.push(format!("добавлено типов: {}", self.added.len()));
+        }
+        if !self.removed.is_empty() {
+            parts.push(format!("удалено: {}", self.removed.len()));
+        }
+        if !self.changed.is_empty() {
+            parts.push(format!("изменено: {}", self.changed.len()));
+        }
+        parts.join(", ")
+    }
+
+    /// Время записи в формате RFC 2822 (для RSS)
+    pub fn rfc2822(&self) -> String {
+        chrono::DateTime::from_timestamp(self.timestamp as i64, 0)
+            .unwrap_or_default()
+            .to_rfc2822()
+    }
+}
+
+/// Сравнить отпечатки двух загрузок; `sequence` и `timestamp` записи
+/// заполняет журнал
+pub fn diff_digests(
+    old: &BTreeMap<String, TypeDigest>,
+    new: &BTreeMap<String, TypeDigest>,
+) -> ChangeEntry {
+    let type_ref = |id: &String, digest: &TypeDigest| ChangedTypeRef {
+        id: id.clone(),
+        name: digest.name.clone(),
+        source: digest.source.clone(),
+    };
+    let mut entry = ChangeEntry {
+        sequence: 0,
+        timestamp: 0,
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+    for (id, digest) in new {
+        match old.get(id) {
+            None => entry.added.push(type_ref(id, digest)),
+            Some(previous) if previous.members != digest.members => {
+                entry.changed.push(TypeMemberChange {
+                    id: id.clone(),
+                    name: digest.name.clone(),
+                    added_members: digest
+                        .members
+                        .difference(&previous.members)
+                        .cloned()
+                        .collect(),
+                    removed_members: previous
+                        .members
+                        .difference(&digest.members)
+                        .cloned()
+                        .collect(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    entry.removed = old
+        .iter()
+        .filter(|(id, _)| !new.contains_key(*id))
+        .map(|(id, digest)| type_ref(id, digest))
+        .collect();
+    entry
+}
+
+/// Содержимое файла журнала
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ChangeJournalState {
+    format_version: u32,
+    next_sequence: u64,
+    /// Отпечатки типов последней загрузки по идентификатору
+    baseline: BTreeMap<String, TypeDigest>,
+    /// Записи, от старых к новым
+    entries: Vec<ChangeEntry>,
+}
+
+impl ChangeJournalState {
+    /// Прочитать файл журнала; отсутствующий, повреждённый файл или файл
+    /// другой версии формата — пустой журнал
+    fn load(path: &Path) -> Self {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!("⚠️ Журнал изменений {} не прочитан: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+        match serde_json::from_str::<Self>(&content) {
+            Ok(state) if state.format_version == CHANGELOG_FORMAT_VERSION => state,
+            _ => {
+                warn!(
+                    "Журнал изменений {} не распознан и будет начат заново",
+                    path.display()
+                );
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| Error::io(&tmp_path, e))?;
+        std::fs::rename(&tmp_path, path).map_err(|e| Error::io(path, e))?;
+        Ok(())
+    }
+}
+
+/// Журнал изменений данных репозитория
+#[derive(Debug)]
+pub struct ChangeJournal {
+    path: Option<PathBuf>,
+    write_guard: WriteGuard,
+    state: Mutex<ChangeJournalState>,
+}
+
+impl ChangeJournal {
+    /// Журнал в файле `path` (`None` — только в памяти процесса)
+    pub fn new(path: Option<&str>, write_guard: WriteGuard) -> Self {
+        let path = path.map(PathBuf::from);
+        let state = path
+            .as_deref()
+            .map(ChangeJournalState::load)
+            .unwrap_or_default();
+        Self {
+            path,
+            write_guard,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Сравнить загруженные типы с предыдущей загрузкой и записать отличия.
+    /// Возвращает новую запись; `None` — первая загрузка или ничего не
+    /// изменилось.
+    pub fn record(&self, types: &[RawTypeData]) -> Option<ChangeEntry> {
+        let digests: BTreeMap<String, TypeDigest> = types
+            .iter()
+            .map(|raw| (raw.id.clone(), TypeDigest::from_raw(raw)))
+            .collect();
+        let mut state = self.state.lock().ok()?;
+        let first_load = state.baseline.is_empty();
+        let mut entry = diff_digests(&state.baseline, &digests);
+        if !first_load && entry.is_empty() {
+            return None;
+        }
+        state.baseline = digests;
+        state.format_version = CHANGELOG_FORMAT_VERSION;
+
+        let recorded = (!first_load).then(|| {
+            state.next_sequence += 1;
+            entry.sequence = state.next_sequence;
+            entry.timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+            state.entries.push(entry.clone());
+            let overflow = state.entries.len().saturating_sub(MAX_ENTRIES);
+            state.entries.drain(..overflow);
+            entry
+        });
+
+        if let Some(path) = &self.path {
+            if self.write_guard.allows("журнал изменений", path) {
+                if let Err(e) = state.save(path) {
+                    warn!("⚠️ Журнал изменений не сохранён: {:#}", e);
+                }
+            }
+        }
+        recorded
+    }
+
+    /// Последние записи, от новых к старым
+    pub fn entries(&self, limit: usize) -> Vec<ChangeEntry> {
+        self.state.lock().map_or_else(
+            |_| Vec::new(),
+            |state| state.entries.iter().rev().take(limit).cloned().collect(),
+        )
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Лента RSS 2.0 по записям журнала; `link` — адрес страницы «Что нового»
+pub fn render_rss(entries: &[ChangeEntry], title: &str, link: &str) -> String {
+    let mut rss = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n",
+    );
+    rss.push_str(&format!("<title>{}</title>\n", escape_xml(title)));
+    rss.push_str(&format!("<link>{}</link>\n", escape_xml(link)));
+    rss.push_str("<description>Изменения данных репозитория типов BSL</description>\n");
+    for entry in entries {
+        let mut lines = Vec::new();
+        lines.extend(
+            entry
+                .added
+                .iter()
+                .map(|t| format!("+ {} ({})", t.name, t.source)),
+        );
+        lines.extend(
+            entry
+                .removed
+                .iter()
+                .map(|t| format!("− {} ({})", t.name, t.source)),
+        );
+        for change in &entry.changed {
+            let members = change
+                .added_members
+                .iter()
+                .map(|m| format!("+{}", m))
+                .chain(change.removed_members.iter().map(|m| format!("−{}", m)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(format!("~ {}: {}", change.name, members));
+        }
+        rss.push_str("<item>\n");
+        rss.push_str(&format!(
+            "<title>#{}: {}</title>\n",
+            entry.sequence,
+            escape_xml(&entry.summary())
+        ));
+        rss.push_str(&format!(
+            "<link>{}#change-{}</link>\n",
+            escape_xml(link),
+            entry.sequence
+        ));
+        rss.push_str(&format!(
+            "<guid isPermaLink=\"false\">bsl-change-{}-{}</guid>\n",
+            entry.sequence, entry.timestamp
+        ));
+        rss.push_str(&format!("<pubDate>{}</pubDate>\n", entry.rfc2822()));
+        rss.push_str(&format!(
+            "<description>{}</description>\n",
+            escape_xml(&lines.join("\n"))
+        ));
+        rss.push_str("</item>\n");
+    }
+    rss.push_str("</channel>\n</rss>\n");
+    rss
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::RawMethodData;
+
+    fn raw(id: &str, methods: &[&str]) -> RawTypeData {
+        let configuration = TypeSource::Configuration {
+            config_version: "1.0".to_string(),
+        };
+        RawTypeData {
+            methods: methods
+                .iter()
+                .map(|name| RawMethodData::test_method(name))
+                .collect(),
+            ..RawTypeData::test_type(id, configuration)
+        }
+    }
+
+    #[test]
+    fn test_journal_records_drift() {
+        let journal = ChangeJournal::new(None, WriteGuard::new(false));
+        assert!(journal
+            .record(&[
+                raw("Справочник.Товары", &["Записать"]),
+                raw("Документ.Заказ", &[])
+            ])
+            .is_none());
+        assert!(journal
+            .record(&[
+                raw("Справочник.Товары", &["Записать"]),
+                raw("Документ.Заказ", &[])
+            ])
+            .is_none());
+
+        let entry = journal
+            .record(&[
+                raw("Справочник.Товары", &["Записать", "Заблокировать"]),
+                raw("Документ.Счёт", &[]),
+            ])
+            .unwrap();
+        assert_eq!(entry.sequence, 1);
+        assert_eq!(entry.added[0].id, "Документ.Счёт");
+        assert_eq!(entry.removed[0].id, "Документ.Заказ");
+        assert_eq!(entry.changed[0].added_members, ["Заблокировать()"]);
+        assert!(entry.changed[0].removed_members.is_empty());
+        assert_eq!(journal.entries(10), [entry.clone()]);
+
+        let rss = render_rss(
+            &journal.entries(10),
+            "Изменения <ERP>",
+            "http://localhost/whats-new",
+        );
+        assert!(rss.contains("<title>Изменения &lt;ERP&gt;</title>"));
+        assert!(rss.contains("<title>#1: добавлено типов: 1, удалено: 1, изменено: 1</title>"));
+        assert!(rss.contains("~ Справочник.Товары: +Заблокировать()"));
+    }
+
+    #[test]
+    fn test_journal_persists_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("changes.json");
+        let path = path.to_str().unwrap();
+
+        ChangeJournal::new(Some(path), WriteGuard::new(false))
+            .record(&[raw("Документ.Заказ", &[])]);
+        let journal = ChangeJournal::new(Some(path), WriteGuard::new(false));
+        let entry = journal.record(&[]).unwrap();
+        assert_eq!(entry.removed[0].id, "Документ.Заказ");
+
+        let reopened = ChangeJournal::new(Some(path), WriteGuard::new(false));
+        assert_eq!(reopened.entries(10).len(), 1);
+    }
+}
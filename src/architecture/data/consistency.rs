@@ -0,0 +1,442 @@
+//! Проверка внутренней согласованности данных репозитория
+//!
+//! Ошибки парсеров синтакс-помощника и конфигурации часто проявляются не
+//! сразу, а как странные результаты вывода типов. Проверка находит их на
+//! уровне данных: ссылки на несуществующие типы, фасеты с членами, которых
+//! нет у типа, повторяющиеся идентификаторы и пустую документацию.
+
+use super::RawTypeData;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Код выхода `doctor --strict`, если в данных есть ошибки
+pub const EXIT_DATA_INCONSISTENT: i32 = 6;
+
+/// Встроенные типы, которые не описаны в репозитории отдельными записями
+const BUILTIN_TYPE_NAMES: &[&str] = &[
+    "Строка",
+    "String",
+    "Число",
+    "Number",
+    "Булево",
+    "Boolean",
+    "Дата",
+    "Date",
+    "Неопределено",
+    "Undefined",
+    "Null",
+    "Тип",
+    "Type",
+    "Произвольный",
+    "Arbitrary",
+];
+
+/// Вид проблемы в данных
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataIssueKind {
+    /// Тип возвращаемого значения, свойства или параметра не найден в репозитории
+    DanglingTypeReference,
+    /// Член фасеты отсутствует среди методов/свойств типа
+    FacetMemberMismatch,
+    /// Фасета одного вида указана несколько раз
+    DuplicateFacet,
+    /// Идентификатор типа встречается несколько раз
+    DuplicateId,
+    /// У типа или метода нет документации
+    EmptyDocumentation,
+}
+
+impl DataIssueKind {
+    pub fn severity(&self) -> DataIssueSeverity {
+        match self {
+            DataIssueKind::DanglingTypeReference | DataIssueKind::DuplicateId => {
+                DataIssueSeverity::Error
+            }
+            DataIssueKind::FacetMemberMismatch | DataIssueKind::DuplicateFacet => {
+                DataIssueSeverity::Warning
+            }
+            DataIssueKind::EmptyDocumentation => DataIssueSeverity::Info,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            DataIssueKind::DanglingTypeReference => "Ссылки на несуществующие типы",
+            DataIssueKind::FacetMemberMismatch => "Члены фасет не совпадают с членами типа",
+            DataIssueKind::DuplicateFacet => "Повторяющиеся фасеты",
+            DataIssueKind::DuplicateId => "Повторяющиеся идентификаторы",
+            DataIssueKind::EmptyDocumentation => "Пустая документация",
+        }
+    }
+}
+
+/// Серьёзность проблемы
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataIssueSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// Найденная проблема
+#[derive(Debug, Clone, Serialize)]
+pub struct DataIssue {
+    pub kind: DataIssueKind,
+    /// Идентификатор типа
+    pub type_id: String,
+    /// Метод, свойство или параметр, к которому относится проблема
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    pub message: String,
+}
+
+/// Отчёт о качестве данных репозитория
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DataQualityReport {
+    pub total_types: usize,
+    pub issues: Vec<DataIssue>,
+}
+
+impl DataQualityReport {
+    /// Нет ошибок (предупреждения и замечания допускаются)
+    pub fn is_consistent(&self) -> bool {
+        self.count_by_severity(DataIssueSeverity::Error) == 0
+    }
+
+    pub fn count_by_severity(&self, severity: DataIssueSeverity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.kind.severity() == severity)
+            .count()
+    }
+
+    pub fn count_by_kind(&self) -> BTreeMap<DataIssueKind, usize> {
+        let mut counts = BTreeMap::new();
+        for issue in &self.issues {
+            *counts.entry(issue.kind).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Проблемы заданного вида
+    pub fn issues_of(&self, kind: DataIssueKind) -> impl Iterator<Item = &DataIssue> {
+        self.issues.iter().filter(move |i| i.kind == kind)
+    }
+
+    /// Текстовая сводка; для каждого вида выводится не более `limit` примеров
+    pub fn to_text(&self, limit: usize) -> String {
+        let mut output = format!(
+            "🧪 Качество данных репозитория ({} типов)\n",
+            self.total_types
+        );
+        if self.issues.is_empty() {
+            output.push_str("✅ Проблем не найдено\n");
+            return output;
+        }
+
+        for (kind, count) in self.count_by_kind() {
+            let icon = match kind.severity() {
+                DataIssueSeverity::Error => "❌",
+                DataIssueSeverity::Warning => "⚠️",
+                DataIssueSeverity::Info => "ℹ️",
+            };
+            output.push_str(&format!("{} {}: {}\n", icon, kind.title(), count));
+            for issue in self.issues_of(kind).take(limit) {
+                output.push_str(&format!("   - {}\n", issue.message));
+            }
+            if count > limit {
+                output.push_str(&format!("   … и ещё {}\n", count - limit));
+            }
+        }
+        output
+    }
+}
+
+/// Индекс известных имён типов (без учёта регистра)
+struct KnownTypes {
+    names: HashSet<String>,
+}
+
+impl KnownTypes {
+    fn new(types: &[RawTypeData]) -> Self {
+        let mut names: HashSet<String> = BUILTIN_TYPE_NAMES
+            .iter()
+            .map(|n| n.to_lowercase())
+            .collect();
+        for raw in types {
+            for name in [&raw.russian_name, &raw.english_name, &raw.id] {
+                if !name.is_empty() {
+                    names.insert(name.to_lowercase());
+                }
+            }
+        }
+        Self { names }
+    }
+
+    /// Неизвестные имена в описании типа вида `Строка, Число` или `Строка | Число`.
+    /// Для ссылок вида `СправочникСсылка.Контрагенты` достаточно, чтобы был
+    /// известен объект метаданных.
+    fn unknown_names<'a>(&self, type_name: &'a str) -> Vec<&'a str> {
+        type_name
+            .split([',', '|'])
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .filter(|name| {
+                let lower = name.to_lowercase();
+                let object = lower.rsplit('.').next().unwrap_or(&lower);
+                !self.names.contains(&lower) && !self.names.contains(object)
+            })
+            .collect()
+    }
+}
+
+/// Проверить согласованность данных репозитория
+pub fn check_consistency(types: &[RawTypeData]) -> DataQualityReport {
+    let known = KnownTypes::new(types);
+    let mut issues = Vec::new();
+
+    let mut id_counts: HashMap<&str, usize> = HashMap::new();
+    for raw in types {
+        *id_counts.entry(raw.id.as_str()).or_insert(0) += 1;
+    }
+    let mut duplicate_ids: Vec<_> = id_counts.into_iter().filter(|(_, c)| *c > 1).collect();
+    duplicate_ids.sort();
+    for (id, count) in duplicate_ids {
+        issues.push(DataIssue {
+            kind: DataIssueKind::DuplicateId,
+            type_id: id.to_string(),
+            member: None,
+            message: format!("{}: встречается {} раз", id, count),
+        });
+    }
+
+    for raw in types {
+        check_type(raw, &known, &mut issues);
+    }
+
+    DataQualityReport {
+        total_types: types.len(),
+        issues,
+    }
+}
+
+fn check_type(raw: &RawTypeData, known: &KnownTypes, issues: &mut Vec<DataIssue>) {
+    let mut dangling = |member: String, type_name: &str| {
+        for name in known.unknown_names(type_name) {
+            issues.push(DataIssue {
+                kind: DataIssueKind::DanglingTypeReference,
+                type_id: raw.id.clone(),
+                message: format!("{}.{}: неизвестный тип '{}'", raw.id, member, name),
+                member: Some(member.clone()),
+            });
+        }
+    };
+
+    for method in &raw.methods {
+        let return_types: BTreeSet<&str> = method
+            .return_type
+            .iter()
+            .chain(method.return_type_name.iter())
+            .map(String::as_str)
+            .collect();
+        for return_type in return_types {
+            dangling(method.name.clone(), return_type);
+        }
+        // `params` обычно дублирует `parameters` — каждый параметр проверяется один раз
+        let params: BTreeSet<(&str, &str)> = method
+            .parameters
+            .iter()
+            .chain(method.params.iter())
+            .map(|p| (p.name.as_str(), p.type_name.as_str()))
+            .collect();
+        for (name, type_name) in params {
+            dangling(format!("{}({})", method.name, name), type_name);
+        }
+    }
+    for property in &raw.properties {
+        dangling(property.name.clone(), &property.type_name);
+    }
+
+    let methods: HashSet<String> = raw.methods.iter().map(|m| m.name.to_lowercase()).collect();
+    let properties: HashSet<String> = raw
+        .properties
+        .iter()
+        .map(|p| p.name.to_lowercase())
+        .collect();
+    let mut facet_kinds = HashSet::new();
+    for facet in &raw.available_facets {
+        if !facet_kinds.insert(facet.kind) {
+            issues.push(DataIssue {
+                kind: DataIssueKind::DuplicateFacet,
+                type_id: raw.id.clone(),
+                member: None,
+                message: format!("{}: фасета {:?} указана повторно", raw.id, facet.kind),
+            });
+        }
+
+        let missing_methods = facet
+            .methods
+            .iter()
+            .map(|m| &m.name)
+            .filter(|name| !methods.contains(&name.to_lowercase()));
+        let missing_properties = facet
+            .properties
+            .iter()
+            .map(|p| &p.name)
+            .filter(|name| !properties.contains(&name.to_lowercase()));
+        for name in missing_methods.chain(missing_properties) {
+            issues.push(DataIssue {
+                kind: DataIssueKind::FacetMemberMismatch,
+                type_id: raw.id.clone(),
+                member: Some(name.clone()),
+                message: format!(
+                    "{}: член '{}' фасеты {:?} отсутствует у типа",
+                    raw.id, name, facet.kind
+                ),
+            });
+        }
+    }
+
+    if raw.documentation.trim().is_empty() {
+        issues.push(DataIssue {
+            kind: DataIssueKind::EmptyDocumentation,
+            type_id: raw.id.clone(),
+            member: None,
+            message: format!("{}: нет описания типа", raw.id),
+        });
+    }
+    for method in raw
+        .methods
+        .iter()
+        .filter(|m| m.documentation.trim().is_empty())
+    {
+        issues.push(DataIssue {
+            kind: DataIssueKind::EmptyDocumentation,
+            type_id: raw.id.clone(),
+            member: Some(method.name.clone()),
+            message: format!("{}.{}: нет описания метода", raw.id, method.name),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{
+        InMemoryTypeRepository, RawMethodData, RawPropertyData, TypeRepository, TypeSource,
+    };
+    use crate::domain::types::{Facet, FacetKind, Method};
+
+    fn raw_type(id: &str, name: &str) -> RawTypeData {
+        let platform = TypeSource::Platform {
+            version: "8.3".to_string(),
+        };
+        RawTypeData {
+            russian_name: name.to_string(),
+            documentation: format!("Тип {}", name),
+            ..RawTypeData::test_type(id, platform)
+        }
+    }
+
+    fn method(name: &str, return_type: Option<&str>) -> RawMethodData {
+        RawMethodData {
+            name: name.to_string(),
+            documentation: "Описание".to_string(),
+            parameters: vec![],
+            return_type: return_type.map(str::to_string),
+            return_type_name: None,
+            params: vec![],
+            is_function: return_type.is_some(),
+            examples: vec![],
+        }
+    }
+
+    #[test]
+    fn test_consistent_data() {
+        let mut array = raw_type("platform.Array", "Массив");
+        array.methods = vec![method("Количество", Some("Число"))];
+        let mut catalog = raw_type("Catalog.Контрагенты", "Контрагенты");
+        catalog.properties = vec![RawPropertyData {
+            name: "Договоры".to_string(),
+            type_name: "Массив, СправочникСсылка.Контрагенты".to_string(),
+            is_readonly: false,
+            description: String::new(),
+        }];
+
+        let report = check_consistency(&[array, catalog]);
+        assert!(report.issues.is_empty(), "{}", report.to_text(10));
+        assert!(report.is_consistent());
+    }
+
+    #[test]
+    fn test_detects_issues() {
+        let mut array = raw_type("platform.Array", "Массив");
+        array.methods = vec![
+            method("Получить", Some("НеизвестныйТип")),
+            method("Очистить", None),
+        ];
+        array.methods[1].documentation.clear();
+        array.available_facets = vec![
+            Facet {
+                kind: FacetKind::Collection,
+                methods: vec![Method {
+                    name: "Вставить".to_string(),
+                    parameters: vec![],
+                    return_type: None,
+                    is_function: false,
+                }],
+                properties: vec![],
+            },
+            Facet {
+                kind: FacetKind::Collection,
+                methods: vec![],
+                properties: vec![],
+            },
+        ];
+        let mut duplicate = raw_type("platform.Array", "Массив");
+        duplicate.documentation.clear();
+
+        let report = check_consistency(&[array, duplicate]);
+        let counts = report.count_by_kind();
+        assert_eq!(counts[&DataIssueKind::DuplicateId], 1);
+        assert_eq!(counts[&DataIssueKind::DanglingTypeReference], 1);
+        assert_eq!(counts[&DataIssueKind::FacetMemberMismatch], 1);
+        assert_eq!(counts[&DataIssueKind::DuplicateFacet], 1);
+        assert_eq!(counts[&DataIssueKind::EmptyDocumentation], 2);
+        assert!(!report.is_consistent());
+        assert!(report.to_text(10).contains("НеизвестныйТип"));
+    }
+
+    #[tokio::test]
+    async fn test_repository_data_quality_report() {
+        let repo = InMemoryTypeRepository::new();
+        let mut catalog = raw_type("config.Catalog.Контрагенты", "Контрагенты");
+        catalog.properties = vec![
+            RawPropertyData {
+                name: "Наименование".to_string(),
+                type_name: "Строка".to_string(),
+                is_readonly: false,
+                description: String::new(),
+            },
+            RawPropertyData {
+                name: "Партнёр".to_string(),
+                type_name: "СправочникСсылка.Партнёры".to_string(),
+                is_readonly: false,
+                description: String::new(),
+            },
+        ];
+
+        repo.save_types(vec![catalog]).await.unwrap();
+        let report = check_consistency(&repo.load_all_types().await.unwrap());
+
+        assert_eq!(report.total_types, 1);
+        let dangling: Vec<_> = report
+            .issues_of(DataIssueKind::DanglingTypeReference)
+            .collect();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].member.as_deref(), Some("Партнёр"));
+        assert!(!report.is_consistent());
+    }
+}
@@ -0,0 +1,362 @@
+//! Полнота документации типов
+//!
+//! Подсказки и страницы типов хороши настолько, насколько полна
+//! документация в репозитории. Отчёт оценивает каждый тип по проверкам:
+//! есть описание типа, описания методов и свойств, описания параметров
+//! методов и примеры. Оценка — доля выполненных проверок; типы с худшей
+//! оценкой идут первыми, а сводка по источникам (платформа, конфигурация,
+//! пользовательские типы) показывает, где дописывать документацию выгоднее
+//! всего.
+
+use super::{RawMethodData, RawTypeData, TypeOrigin};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Полнота документации одного типа
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeDocumentation {
+    pub type_id: String,
+    pub name: String,
+    pub origin: TypeOrigin,
+    /// Доля выполненных проверок, 0–100
+    pub score: f64,
+    pub missing_description: bool,
+    /// Методы без описания
+    pub undocumented_methods: Vec<String>,
+    /// Методы, у которых описаны не все параметры
+    pub methods_without_parameter_docs: Vec<String>,
+    /// Свойства без описания
+    pub undocumented_properties: Vec<String>,
+    /// Нет примеров ни у типа, ни у его методов
+    pub missing_examples: bool,
+}
+
+impl TypeDocumentation {
+    /// Всё описано
+    pub fn is_complete(&self) -> bool {
+        self.score >= 100.0
+    }
+}
+
+/// Сводка по источнику типов
+#[derive(Debug, Clone, Serialize)]
+pub struct OriginDocumentation {
+    pub origin: TypeOrigin,
+    pub total_types: usize,
+    pub average_score: f64,
+    pub complete_types: usize,
+    pub missing_descriptions: usize,
+    pub undocumented_methods: usize,
+    pub methods_without_parameter_docs: usize,
+    pub undocumented_properties: usize,
+    pub missing_examples: usize,
+}
+
+/// Отчёт о полноте документации репозитория
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DocumentationReport {
+    pub total_types: usize,
+    pub average_score: f64,
+    pub origins: Vec<OriginDocumentation>,
+    /// Типы от худшей оценки к лучшей
+    pub types: Vec<TypeDocumentation>,
+}
+
+/// Параметры метода без повторов: `params` обычно дублирует `parameters`
+fn parameter_descriptions(method: &RawMethodData) -> BTreeMap<&str, bool> {
+    let mut parameters: BTreeMap<&str, bool> = BTreeMap::new();
+    for parameter in method.parameters.iter().chain(method.params.iter()) {
+        let documented = !parameter.description.trim().is_empty();
+        *parameters.entry(parameter.name.as_str()).or_insert(false) |= documented;
+    }
+    parameters
+}
+
+/// Оценить полноту документации типа
+pub fn type_documentation(raw: &RawTypeData) -> TypeDocumentation {
+    let missing_description = raw.documentation.trim().is_empty();
+    let undocumented_methods: Vec<String> = raw
+        .methods
+        .iter()
+        .filter(|m| m.documentation.trim().is_empty())
+        .map(|m| m.name.clone())
+        .collect();
+    let with_parameters: Vec<&RawMethodData> = raw
+        .methods
+        .iter()
+        .filter(|m| !m.parameters.is_empty() || !m.params.is_empty())
+        .collect();
+    let methods_without_parameter_docs: Vec<String> = with_parameters
+        .iter()
+        .filter(|m| {
+            parameter_descriptions(m)
+                .values()
+                .any(|documented| !documented)
+        })
+        .map(|m| m.name.clone())
+        .collect();
+    let undocumented_properties: Vec<String> = raw
+        .properties
+        .iter()
+        .filter(|p| p.description.trim().is_empty())
+        .map(|p| p.name.clone())
+        .collect();
+    let missing_examples =
+        raw.examples.is_empty() && raw.methods.iter().all(|m| m.examples.is_empty());
+
+    // Описание типа, примеры, каждый метод, каждый метод с параметрами и
+    // каждое свойство — по проверке
+    let checks = 2 + raw.methods.len() + with_parameters.len() + raw.properties.len();
+    let failed = usize::from(missing_description)
+        + usize::from(missing_examples)
+        + undocumented_methods.len()
+        + methods_without_parameter_docs.len()
+        + undocumented_properties.len();
+    let score = (checks - failed) as f64 * 100.0 / checks as f64;
+
+    TypeDocumentation {
+        type_id: raw.id.clone(),
+        name: raw.russian_name.clone(),
+        origin: TypeOrigin::of(&raw.source),
+        score,
+        missing_description,
+        undocumented_methods,
+        methods_without_parameter_docs,
+        undocumented_properties,
+        missing_examples,
+    }
+}
+
+fn average(scores: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = scores.fold((0.0, 0usize), |(sum, count), s| (sum + s, count + 1));
+    if count == 0 {
+        100.0
+    } else {
+        sum / count as f64
+    }
+}
+
+/// Построить отчёт по типам репозитория
+pub fn documentation_report(types: &[RawTypeData]) -> DocumentationReport {
+    let mut documented: Vec<TypeDocumentation> = types.iter().map(type_documentation).collect();
+    documented.sort_by(|a, b| {
+        a.score
+            .total_cmp(&b.score)
+            .then_with(|| a.type_id.cmp(&b.type_id))
+    });
+
+    let origins = [
+        TypeOrigin::Platform,
+        TypeOrigin::Configuration,
+        TypeOrigin::UserDefined,
+    ]
+    .into_iter()
+    .filter_map(|origin| {
+        let of_origin: Vec<&TypeDocumentation> =
+            documented.iter().filter(|t| t.origin == origin).collect();
+        if of_origin.is_empty() {
+            return None;
+        }
+        Some(OriginDocumentation {
+            origin,
+            total_types: of_origin.len(),
+            average_score: average(of_origin.iter().map(|t| t.score)),
+            complete_types: of_origin.iter().filter(|t| t.is_complete()).count(),
+            missing_descriptions: of_origin.iter().filter(|t| t.missing_description).count(),
+            undocumented_methods: of_origin.iter().map(|t| t.undocumented_methods.len()).sum(),
+            methods_without_parameter_docs: of_origin
+                .iter()
+                .map(|t| t.methods_without_parameter_docs.len())
+                .sum(),
+            undocumented_properties: of_origin
+                .iter()
+                .map(|t| t.undocumented_properties.len())
+                .sum(),
+            missing_examples: of_origin.iter().filter(|t| t.missing_examples).count(),
+        })
+    })
+    .collect();
+
+    DocumentationReport {
+        total_types: documented.len(),
+        average_score: average(documented.iter().map(|t| t.score)),
+        origins,
+        types: documented,
+    }
+}
+
+/// Поле CSV: в кавычках, если содержит разделитель, кавычки или перевод строки
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl DocumentationReport {
+    /// Отчёт только по типам одного источника
+    pub fn for_origin(mut self, origin: TypeOrigin) -> Self {
+        self.types.retain(|t| t.origin == origin);
+        self.origins.retain(|o| o.origin == origin);
+        self.total_types = self.types.len();
+        self.average_score = average(self.types.iter().map(|t| t.score));
+        self
+    }
+
+    /// Текстовая сводка; выводится не более `limit` типов с худшей оценкой
+    pub fn to_text(&self, limit: usize) -> String {
+        let mut output = format!(
+            "📚 Полнота документации ({} типов): {:.1}%\n",
+            self.total_types, self.average_score
+        );
+        for origin in &self.origins {
+            output.push_str(&format!(
+                "   {}: {:.1}% ({} из {} полностью), без описания {}, методов без описания {}, \
+                 без описания параметров {}, свойств без описания {}, без примеров {}\n",
+                origin.origin.as_str(),
+                origin.average_score,
+                origin.complete_types,
+                origin.total_types,
+                origin.missing_descriptions,
+                origin.undocumented_methods,
+                origin.methods_without_parameter_docs,
+                origin.undocumented_properties,
+                origin.missing_examples
+            ));
+        }
+        let incomplete: Vec<&TypeDocumentation> =
+            self.types.iter().filter(|t| !t.is_complete()).collect();
+        if incomplete.is_empty() {
+            output.push_str("✅ Документация полная\n");
+            return output;
+        }
+        output.push_str("Начать стоит с:\n");
+        for doc in incomplete.iter().take(limit) {
+            output.push_str(&format!(
+                "   - {} ({}): {:.0}%\n",
+                doc.name,
+                doc.origin.as_str(),
+                doc.score
+            ));
+        }
+        if incomplete.len() > limit {
+            output.push_str(&format!("   … и ещё {}\n", incomplete.len() - limit));
+        }
+        output
+    }
+
+    /// Таблица CSV: строка на тип, числа — количества недостающих описаний
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from(
+            "type_id,name,origin,score,missing_description,undocumented_methods,\
+             methods_without_parameter_docs,undocumented_properties,missing_examples\n",
+        );
+        for doc in &self.types {
+            output.push_str(&format!(
+                "{},{},{},{:.1},{},{},{},{},{}\n",
+                csv_field(&doc.type_id),
+                csv_field(&doc.name),
+                doc.origin.as_str(),
+                doc.score,
+                doc.missing_description,
+                doc.undocumented_methods.len(),
+                doc.methods_without_parameter_docs.len(),
+                doc.undocumented_properties.len(),
+                doc.missing_examples
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{RawParameterData, RawPropertyData, TypeSource};
+
+    fn raw_type(id: &str, source: TypeSource) -> RawTypeData {
+        RawTypeData {
+            documentation: format!("Тип {}", id),
+            examples: vec![format!("{} = Новый {};", id, id)],
+            ..RawTypeData::test_type(id, source)
+        }
+    }
+
+    fn parameter(name: &str, description: &str) -> RawParameterData {
+        RawParameterData {
+            name: name.to_string(),
+            type_name: "Строка".to_string(),
+            description: description.to_string(),
+            is_optional: false,
+            is_by_value: false,
+        }
+    }
+
+    #[test]
+    fn test_documentation_report() {
+        let platform = TypeSource::Platform {
+            version: "8.3".to_string(),
+        };
+        let complete = raw_type("Массив", platform.clone());
+
+        let mut structure = raw_type("Структура", platform);
+        structure.examples.clear();
+        structure.methods = vec![RawMethodData {
+            name: "Вставить".to_string(),
+            documentation: "Добавляет элемент".to_string(),
+            parameters: vec![
+                parameter("Ключ", "Ключ элемента"),
+                parameter("Значение", ""),
+            ],
+            return_type: None,
+            return_type_name: None,
+            params: vec![parameter("Значение", "")],
+            is_function: false,
+            examples: vec![],
+        }];
+
+        let mut catalog = raw_type(
+            "Контрагенты",
+            TypeSource::Configuration {
+                config_version: "8.3".to_string(),
+            },
+        );
+        catalog.documentation.clear();
+        catalog.properties = vec![RawPropertyData {
+            name: "ИНН".to_string(),
+            type_name: "Строка".to_string(),
+            is_readonly: false,
+            description: String::new(),
+        }];
+
+        let report = documentation_report(&[complete, structure, catalog]);
+        assert_eq!(report.total_types, 3);
+        // Худшие — первыми: у справочника не выполнены 2 проверки из 3
+        assert_eq!(report.types[0].name, "Контрагенты");
+        assert!(report.types[0].missing_description);
+        assert_eq!(report.types[0].undocumented_properties, ["ИНН"]);
+        assert_eq!(report.types[1].name, "Структура");
+        assert_eq!(report.types[1].methods_without_parameter_docs, ["Вставить"]);
+        assert!(report.types[1].missing_examples);
+        assert_eq!(report.types[1].score, 50.0);
+        assert!(report.types[2].is_complete());
+
+        let platform = &report.origins[0];
+        assert_eq!(platform.origin, TypeOrigin::Platform);
+        assert_eq!(platform.total_types, 2);
+        assert_eq!(platform.complete_types, 1);
+        assert_eq!(platform.average_score, 75.0);
+
+        let csv = report.to_csv();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.contains("Структура,platform,50.0,false,0,1,0,true"));
+        assert!(report.to_text(1).contains("… и ещё 1"));
+        assert_eq!(csv_field("А, \"Б\""), "\"А, \"\"Б\"\"\"");
+
+        let configuration = report.for_origin(TypeOrigin::Configuration);
+        assert_eq!(configuration.total_types, 1);
+        assert_eq!(configuration.origins.len(), 1);
+        assert!(configuration.average_score < 34.0);
+    }
+}
@@ -0,0 +1,378 @@
+//! Репозиторий типов с хранением на диске и перезагрузкой только
+//! изменившихся источников
+//!
+//! Без него каждый запуск заново разбирает справку платформы и выгрузку
+//! конфигурации (секунды на больших конфигурациях). Здесь разобранные
+//! типы каждого источника (артефакта) сохраняются в отдельный файл каталога
+//! хранилища вместе с отпечатком исходников: путями, размерами и временем
+//! изменения файлов. При запуске артефакт разбирается заново, только если
+//! отпечаток изменился; иначе типы читаются из файла.
+//!
+//! Запросы обслуживает слой в памяти ([`InMemoryTypeRepository`]), поэтому
+//! после загрузки репозиторий работает так же быстро, как обычный. В режиме
+//! только для чтения ([`WriteGuard`]) сохранённые артефакты читаются, но
+//! новые не записываются: разобранные типы остаются только в памяти.
+//!
+//! Артефакты лежат в [`StorageBackend`]: по умолчанию в каталоге, а с адресом
+//! `sqlite://` или `s3://` — в общей базе или бакете команды.
+
+use super::filters::TypeFilter;
+use super::stats::RepositoryStats;
+use super::storage::{FsStorage, StorageBackend, StorageLocation};
+use super::type_repository::{InMemoryTypeRepository, RepositoryChange, TypeDelta, TypeRepository};
+use super::write_guard::WriteGuard;
+use super::RawTypeData;
+use crate::domain::types::TypeResolution;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tokio::sync::broadcast;
+use walkdir::WalkDir;
+
+/// Версия формата файлов хранилища; файлы другой версии разбираются заново
+pub const STORE_FORMAT_VERSION: u32 = 1;
+
+/// Источник типов, хранимый отдельным файлом
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreArtifact {
+    Platform,
+    Configuration,
+}
+
+impl StoreArtifact {
+    fn file_name(&self) -> &'static str {
+        match self {
+            StoreArtifact::Platform => "platform.types",
+            StoreArtifact::Configuration => "configuration.types",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StoreArtifact::Platform => "platform",
+            StoreArtifact::Configuration => "configuration",
+        }
+    }
+}
+
+/// Отпечаток исходников артефакта (SHA-256, hex)
+///
+/// Учитываются относительные пути, размеры и время изменения файлов, а также
+/// дополнительные ключи (версия платформы, версия инструмента): содержимое
+/// не читается, поэтому проверка большой выгрузки занимает миллисекунды.
+pub fn source_fingerprint(source: &Path, keys: &[&str]) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    for key in keys {
+        hasher.update([0]);
+        hasher.update(key.as_bytes());
+    }
+
+    if !source.exists() {
+        hasher.update(b"\0missing");
+        return Ok(format!("{:x}", hasher.finalize()));
+    }
+    for entry in WalkDir::new(source).sort_by_file_name() {
+        let entry = entry.with_context(|| format!("Не удалось обойти {}", source.display()))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let metadata = entry
+            .metadata()
+            .with_context(|| format!("Нет сведений о файле {}", entry.path().display()))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_nanos());
+        let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+        hasher.update([0]);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update(metadata.len().to_le_bytes());
+        hasher.update(modified.to_le_bytes());
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Содержимое файла артефакта
+#[derive(Serialize, Deserialize)]
+struct StoredArtifact {
+    format_version: u32,
+    fingerprint: String,
+    types: Vec<RawTypeData>,
+}
+
+/// Результат загрузки артефакта
+#[derive(Debug)]
+pub struct ArtifactLoad {
+    pub types: Vec<RawTypeData>,
+    /// Типы взяты из хранилища без разбора
+    pub reused: bool,
+}
+
+/// Репозиторий типов с хранилищем разобранных артефактов
+pub struct FileBackedTypeRepository {
+    storage: Arc<dyn StorageBackend>,
+    memory: InMemoryTypeRepository,
+    write_guard: WriteGuard,
+}
+
+impl FileBackedTypeRepository {
+    /// Открыть хранилище в каталоге `dir` (создаётся при необходимости)
+    pub fn open(dir: &Path) -> Result<Self> {
+        Self::open_with_guard(dir, WriteGuard::default())
+    }
+
+    /// Открыть хранилище, записи в которое проверяет `write_guard`
+    /// (в режиме только для чтения каталог не создаётся)
+    pub fn open_with_guard(dir: &Path, write_guard: WriteGuard) -> Result<Self> {
+        if !dir.is_dir() && write_guard.allows("каталог хранилища типов", dir) {
+            std::fs::create_dir_all(dir).with_context(|| {
+                format!("Не удалось создать каталог хранилища {}", dir.display())
+            })?;
+        }
+        Ok(Self::with_storage(
+            Arc::new(FsStorage::new(dir)),
+            write_guard,
+        ))
+    }
+
+    /// Хранилище по адресу из настроек: каталог, `sqlite://` или `s3://`
+    pub fn open_location(location: &str, write_guard: WriteGuard) -> Result<Self> {
+        match StorageLocation::parse(location)? {
+            StorageLocation::Directory(dir) => Self::open_with_guard(&dir, write_guard),
+            other => Ok(Self::with_storage(other.open(&write_guard)?, write_guard)),
+        }
+    }
+
+    /// Артефакты в заданном хранилище
+    pub fn with_storage(storage: Arc<dyn StorageBackend>, write_guard: WriteGuard) -> Self {
+        Self {
+            storage,
+            memory: InMemoryTypeRepository::new(),
+            write_guard,
+        }
+    }
+
+    /// Где лежат артефакты (каталог, база SQLite, `s3://bucket/prefix`)
+    pub fn location(&self) -> String {
+        self.storage.location()
+    }
+
+    /// Сохранённые типы артефакта, если их отпечаток совпадает с `fingerprint`.
+    /// Повреждённый файл или файл другой версии формата считается отсутствующим.
+    pub fn cached(
+        &self,
+        artifact: StoreArtifact,
+        fingerprint: &str,
+    ) -> Result<Option<Vec<RawTypeData>>> {
+        let Some(bytes) = self.storage.read(artifact.file_name())? else {
+            return Ok(None);
+        };
+        let Ok(stored) = bincode::deserialize::<StoredArtifact>(&bytes) else {
+            return Ok(None);
+        };
+        let valid = stored.format_version == STORE_FORMAT_VERSION
+            && stored.fingerprint == fingerprint;
+        Ok(valid.then_some(stored.types))
+    }
+
+    /// Сохранить разобранные типы артефакта с отпечатком исходников
+    /// (в режиме только для чтения ничего не делает)
+    pub fn store(
+        &self,
+        artifact: StoreArtifact,
+        fingerprint: &str,
+        types: &[RawTypeData],
+    ) -> Result<()> {
+        let key = artifact.file_name();
+        let path = self.storage.describe_key(key);
+        if !self.write_guard.allows("хранилище типов", Path::new(&path)) {
+            return Ok(());
+        }
+        #[derive(Serialize)]
+        struct StoredArtifactRef<'a> {
+            format_version: u32,
+            fingerprint: &'a str,
+            types: &'a [RawTypeData],
+        }
+        let bytes = bincode::serialize(&StoredArtifactRef {
+            format_version: STORE_FORMAT_VERSION,
+            fingerprint,
+            types,
+        })?;
+        Ok(self.storage.write(key, &bytes)?)
+    }
+
+    /// Типы артефакта: из хранилища, если исходники не менялись, иначе
+    /// результат `parse`, который сразу сохраняется
+    pub async fn load_artifact<F, Fut>(
+        &self,
+        artifact: StoreArtifact,
+        fingerprint: &str,
+        parse: F,
+    ) -> Result<ArtifactLoad>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<RawTypeData>>>,
+    {
+        if let Some(types) = self.cached(artifact, fingerprint)? {
+            return Ok(ArtifactLoad {
+                types,
+                reused: true,
+            });
+        }
+        let types = parse().await?;
+        self.store(artifact, fingerprint, &types)?;
+        Ok(ArtifactLoad {
+            types,
+            reused: false,
+        })
+    }
+}
+
+#[async_trait]
+impl TypeRepository for FileBackedTypeRepository {
+    fn add_resolution(&self, resolution: TypeResolution) {
+        self.memory.add_resolution(resolution);
+    }
+
+    fn get_stats(&self) -> RepositoryStats {
+        self.memory.get_stats()
+    }
+
+    async fn clear(&self) -> crate::Result<()> {
+        // Файлы артефактов остаются: их актуальность проверяется по отпечатку
+        self.memory.clear().await
+    }
+
+    async fn save_types(&self, types: Vec<RawTypeData>) -> crate::Result<()> {
+        self.memory.save_types(types).await
+    }
+
+    async fn search_types(&self, query: &str) -> crate::Result<Vec<RawTypeData>> {
+        self.memory.search_types(query).await
+    }
+
+    async fn load_all_types(&self) -> crate::Result<Vec<RawTypeData>> {
+        self.memory.load_all_types().await
+    }
+
+    async fn load_types_filtered(&self, filter: &TypeFilter) -> crate::Result<Vec<RawTypeData>> {
+        self.memory.load_types_filtered(filter).await
+    }
+
+    async fn apply_delta(&self, delta: TypeDelta) -> crate::Result<RepositoryChange> {
+        self.memory.apply_delta(delta).await
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<RepositoryChange> {
+        self.memory.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::TypeSource;
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn raw_type(name: &str) -> RawTypeData {
+        RawTypeData::test_type(
+            name,
+            TypeSource::Configuration {
+                config_version: "1.0".to_string(),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_reparse_only_when_sources_change() {
+        let sources = tempfile::tempdir().unwrap();
+        let store_dir = tempfile::tempdir().unwrap();
+        std::fs::write(sources.path().join("Catalog.xml"), "<Catalog/>").unwrap();
+
+        let counter = AtomicUsize::new(0);
+        let parses = &counter;
+        let parse = move || async move {
+            parses.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![raw_type("Товары")])
+        };
+
+        let repository = FileBackedTypeRepository::open(store_dir.path()).unwrap();
+        let fingerprint = source_fingerprint(sources.path(), &["8.3"]).unwrap();
+        let first = repository
+            .load_artifact(StoreArtifact::Configuration, &fingerprint, parse)
+            .await
+            .unwrap();
+        assert!(!first.reused);
+
+        // Новый процесс с тем же каталогом: исходники не менялись
+        let reopened = FileBackedTypeRepository::open(store_dir.path()).unwrap();
+        let second = reopened
+            .load_artifact(StoreArtifact::Configuration, &fingerprint, parse)
+            .await
+            .unwrap();
+        assert!(second.reused);
+        assert_eq!(second.types[0].russian_name, "Товары");
+        assert_eq!(parses.load(Ordering::SeqCst), 1);
+
+        // Изменение исходников или ключа даёт другой отпечаток
+        std::fs::write(sources.path().join("Document.xml"), "<Document/>").unwrap();
+        let changed = source_fingerprint(sources.path(), &["8.3"]).unwrap();
+        assert_ne!(changed, fingerprint);
+        assert_ne!(source_fingerprint(sources.path(), &["8.4"]).unwrap(), changed);
+        let third = reopened
+            .load_artifact(StoreArtifact::Configuration, &changed, parse)
+            .await
+            .unwrap();
+        assert!(!third.reused);
+        assert_eq!(parses.load(Ordering::SeqCst), 2);
+
+        // Другой артефакт хранится отдельно
+        assert!(reopened
+            .cached(StoreArtifact::Platform, &changed)
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_store_is_reparsed() {
+        let store_dir = tempfile::tempdir().unwrap();
+        std::fs::write(store_dir.path().join("platform.types"), b"garbage").unwrap();
+        let repository = FileBackedTypeRepository::open(store_dir.path()).unwrap();
+        assert!(repository
+            .cached(StoreArtifact::Platform, "any")
+            .unwrap()
+            .is_none());
+
+        repository.save_types(vec![raw_type("Товары")]).await.unwrap();
+        assert_eq!(repository.search_types("Товары").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_store_keeps_types_in_memory() {
+        let root = tempfile::tempdir().unwrap();
+        let store_dir = root.path().join("store");
+        let guard = WriteGuard::new(true);
+        let repository = FileBackedTypeRepository::open_with_guard(&store_dir, guard.clone())
+            .unwrap();
+        let load = repository
+            .load_artifact(StoreArtifact::Platform, "fp", || async {
+                Ok(vec![raw_type("Массив")])
+            })
+            .await
+            .unwrap();
+        assert_eq!(load.types.len(), 1);
+        assert!(!store_dir.exists());
+
+        let skipped = guard.skipped_writes();
+        assert_eq!(skipped.len(), 2);
+        assert!(skipped[1].path.ends_with("platform.types"));
+    }
+}
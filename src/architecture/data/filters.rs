@@ -1,7 +1,7 @@
-use super::TypeSource;
+use super::{RawTypeData, TypeSource};
 use crate::domain::types::FacetKind;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct TypeFilter {
     pub source: Option<TypeSource>,
     pub category: Option<String>,
@@ -12,3 +12,53 @@ pub struct TypeFilter {
     /// Требуемая фасета среди доступных у типа
     pub facet: Option<FacetKind>,
 }
+
+impl TypeFilter {
+    /// Подходит ли тип под все заданные условия фильтра
+    pub fn matches(&self, raw: &RawTypeData) -> bool {
+        // Фильтрация по источнику
+        if let Some(src) = &self.source {
+            if std::mem::discriminant(&raw.source) != std::mem::discriminant(src) {
+                return false;
+            }
+        }
+
+        // Фильтр по категории (грубый по вхождению в путь категории)
+        if let Some(cat) = &self.category {
+            if !raw.category_path.iter().any(|c| c.contains(cat)) {
+                return false;
+            }
+        }
+
+        // Фильтр по имени (подстрока, регистр игнорируется)
+        if let Some(name_substr) = &self.name_contains {
+            let nn = name_substr.to_lowercase();
+            if !raw.russian_name.to_lowercase().contains(&nn)
+                && !raw.english_name.to_lowercase().contains(&nn)
+            {
+                return false;
+            }
+        }
+
+        // Фильтр по фасете (должна присутствовать среди доступных)
+        if let Some(facet_kind) = self.facet {
+            if !raw.available_facets.iter().any(|f| f.kind == facet_kind) {
+                return false;
+            }
+        }
+
+        // Фильтр по наличию методов/свойств
+        if let Some(has_methods) = self.has_methods {
+            if has_methods == raw.methods.is_empty() {
+                return false;
+            }
+        }
+        if let Some(has_properties) = self.has_properties {
+            if has_properties == raw.properties.is_empty() {
+                return false;
+            }
+        }
+
+        true
+    }
+}
@@ -1,13 +1,41 @@
+pub mod changelog;
+pub mod consistency;
+pub mod doc_completeness;
+pub mod file_repository;
 pub mod filters;
+pub mod precedence;
 pub mod raw_models;
+pub mod remote_repository;
+pub mod s3_storage;
+pub mod snapshot;
+pub mod snapshot_artifact;
+pub mod snapshot_delta;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_storage;
 pub mod stats;
+pub mod storage;
 pub mod syntax_helper_loader;
 pub mod type_repository;
+pub mod write_guard;
 
+pub use changelog::{ChangeEntry, ChangeJournal, ChangedTypeRef, TypeMemberChange};
+pub use consistency::{
+    check_consistency, DataIssue, DataIssueKind, DataIssueSeverity, DataQualityReport,
+};
+pub use doc_completeness::{documentation_report, DocumentationReport, TypeDocumentation};
+pub use file_repository::{source_fingerprint, FileBackedTypeRepository, StoreArtifact};
 pub use filters::TypeFilter;
+pub use precedence::{NameCandidates, NamePrecedence, PrecedenceTypeRepository, TypeOrigin};
 pub use raw_models::TypeSource;
 pub use raw_models::{
-    ParseMetadata, RawMethodData, RawParameterData, RawPropertyData, RawTypeData,
+    composite_type_names, ParseMetadata, RawMethodData, RawParameterData, RawPropertyData,
+    RawTypeData,
 };
+pub use remote_repository::RemoteTypeRepository;
+pub use snapshot::{encode_snapshot, write_snapshot, SnapshotTypeRepository, TypeSnapshot};
 pub use stats::RepositoryStats;
-pub use type_repository::{InMemoryTypeRepository, TypeRepository};
+pub use storage::{nested_location, open_storage, FsStorage, StorageBackend, StorageLocation};
+pub use type_repository::{
+    InMemoryTypeRepository, RepositoryChange, TypeDelta, TypeRepository, TypeTombstone,
+};
+pub use write_guard::{SkippedWrite, WriteGuard};
@@ -0,0 +1,297 @@
+//! Приоритет источников при совпадении имён типов
+//!
+//! Объект метаданных может называться так же, как тип платформы (общий
+//! модуль или справочник `Файл`, перечисление `Цвет` и т.п.). В коде
+//! конфигурации такое имя ссылается на объект конфигурации, поэтому
+//! кандидаты упорядочиваются по источнику: по умолчанию пользовательские
+//! типы, затем конфигурация, затем платформа. Порядок настраивается
+//! (`name_precedence`), а все кандидаты доступны через [`NameCandidates`],
+//! чтобы неоднозначную ссылку можно было показать пользователю.
+
+use super::filters::TypeFilter;
+use super::stats::RepositoryStats;
+use super::type_repository::{RepositoryChange, TypeDelta};
+use super::{RawTypeData, TypeRepository, TypeSource};
+use crate::domain::types::TypeResolution;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Источник типа без подробностей (версии, пути)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeOrigin {
+    Platform,
+    Configuration,
+    UserDefined,
+}
+
+impl TypeOrigin {
+    pub fn of(source: &TypeSource) -> Self {
+        match source {
+            TypeSource::Platform { .. } => TypeOrigin::Platform,
+            TypeSource::Configuration { .. } => TypeOrigin::Configuration,
+            TypeSource::UserDefined { .. } => TypeOrigin::UserDefined,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TypeOrigin::Platform => "platform",
+            TypeOrigin::Configuration => "configuration",
+            TypeOrigin::UserDefined => "user_defined",
+        }
+    }
+}
+
+impl std::str::FromStr for TypeOrigin {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "platform" => Ok(TypeOrigin::Platform),
+            "configuration" => Ok(TypeOrigin::Configuration),
+            "user_defined" | "user" => Ok(TypeOrigin::UserDefined),
+            other => Err(format!(
+                "неизвестный источник типа '{}' (platform, configuration, user_defined)",
+                other
+            )),
+        }
+    }
+}
+
+/// Порядок источников при совпадении имён: первый в списке побеждает,
+/// не указанные источники идут после указанных
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NamePrecedence {
+    pub order: Vec<TypeOrigin>,
+}
+
+impl Default for NamePrecedence {
+    fn default() -> Self {
+        Self {
+            order: vec![
+                TypeOrigin::UserDefined,
+                TypeOrigin::Configuration,
+                TypeOrigin::Platform,
+            ],
+        }
+    }
+}
+
+impl NamePrecedence {
+    /// Номер источника в порядке приоритета (меньше — важнее)
+    pub fn rank(&self, origin: TypeOrigin) -> usize {
+        self.order
+            .iter()
+            .position(|o| *o == origin)
+            .unwrap_or(self.order.len())
+    }
+
+    /// Упорядочить типы по приоритету источника; порядок внутри одного
+    /// источника сохраняется
+    pub fn sort(&self, types: &mut [RawTypeData]) {
+        types.sort_by_key(|raw| self.rank(TypeOrigin::of(&raw.source)));
+    }
+
+    /// Кандидаты на имя `name` (русское или английское, без учёта регистра)
+    pub fn candidates(&self, name: &str, types: Vec<RawTypeData>) -> NameCandidates {
+        let name_lower = name.to_lowercase();
+        let mut candidates: Vec<RawTypeData> = types
+            .into_iter()
+            .filter(|raw| {
+                raw.russian_name.to_lowercase() == name_lower
+                    || (!raw.english_name.is_empty()
+                        && raw.english_name.to_lowercase() == name_lower)
+            })
+            .collect();
+        self.sort(&mut candidates);
+        NameCandidates {
+            name: name.to_string(),
+            candidates,
+        }
+    }
+}
+
+/// Все типы, на которые может ссылаться имя, в порядке приоритета
+#[derive(Debug, Clone)]
+pub struct NameCandidates {
+    pub name: String,
+    pub candidates: Vec<RawTypeData>,
+}
+
+impl NameCandidates {
+    /// Тип, на который ссылается имя с учётом приоритета
+    pub fn winner(&self) -> Option<&RawTypeData> {
+        self.candidates.first()
+    }
+
+    /// Имя есть в нескольких источниках (одноимённые типы одного источника
+    /// неоднозначностью не считаются)
+    pub fn is_ambiguous(&self) -> bool {
+        let mut origins = self.candidates.iter().map(|raw| TypeOrigin::of(&raw.source));
+        match origins.next() {
+            Some(first) => origins.any(|origin| origin != first),
+            None => false,
+        }
+    }
+
+    /// Источники кандидатов по порядку, без повторов
+    pub fn origins(&self) -> Vec<TypeOrigin> {
+        let mut origins = Vec::new();
+        for raw in &self.candidates {
+            let origin = TypeOrigin::of(&raw.source);
+            if !origins.contains(&origin) {
+                origins.push(origin);
+            }
+        }
+        origins
+    }
+}
+
+/// Репозиторий, отдающий результаты поиска в порядке [`NamePrecedence`]
+///
+/// Резолверы берут первый тип с подходящим именем, поэтому упорядочивания
+/// выдачи достаточно, чтобы приоритет соблюдался во всей цепочке.
+pub struct PrecedenceTypeRepository {
+    inner: Arc<dyn TypeRepository>,
+    precedence: NamePrecedence,
+}
+
+impl PrecedenceTypeRepository {
+    pub fn new(inner: Arc<dyn TypeRepository>, precedence: NamePrecedence) -> Self {
+        Self { inner, precedence }
+    }
+
+    pub fn precedence(&self) -> &NamePrecedence {
+        &self.precedence
+    }
+
+    fn sorted(&self, mut types: Vec<RawTypeData>) -> Vec<RawTypeData> {
+        self.precedence.sort(&mut types);
+        types
+    }
+}
+
+#[async_trait]
+impl TypeRepository for PrecedenceTypeRepository {
+    fn add_resolution(&self, resolution: TypeResolution) {
+        self.inner.add_resolution(resolution);
+    }
+
+    fn get_stats(&self) -> RepositoryStats {
+        self.inner.get_stats()
+    }
+
+    async fn clear(&self) -> crate::Result<()> {
+        self.inner.clear().await
+    }
+
+    async fn save_types(&self, types: Vec<RawTypeData>) -> crate::Result<()> {
+        self.inner.save_types(types).await
+    }
+
+    async fn search_types(&self, query: &str) -> crate::Result<Vec<RawTypeData>> {
+        Ok(self.sorted(self.inner.search_types(query).await?))
+    }
+
+    async fn load_all_types(&self) -> crate::Result<Vec<RawTypeData>> {
+        Ok(self.sorted(self.inner.load_all_types().await?))
+    }
+
+    async fn load_types_filtered(&self, filter: &TypeFilter) -> crate::Result<Vec<RawTypeData>> {
+        Ok(self.sorted(self.inner.load_types_filtered(filter).await?))
+    }
+
+    async fn apply_delta(&self, delta: TypeDelta) -> crate::Result<RepositoryChange> {
+        self.inner.apply_delta(delta).await
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<RepositoryChange> {
+        self.inner.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::InMemoryTypeRepository;
+    use super::*;
+
+    fn platform(name: &str) -> RawTypeData {
+        RawTypeData::test_type(
+            name,
+            TypeSource::Platform {
+                version: "8.3".to_string(),
+            },
+        )
+    }
+
+    fn configuration(name: &str) -> RawTypeData {
+        RawTypeData::test_type(
+            name,
+            TypeSource::Configuration {
+                config_version: "1.0".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_configuration_shadows_platform_by_default() {
+        let types = vec![platform("Файл"), configuration("Файл"), platform("ФайлыВыбора")];
+        let candidates = NamePrecedence::default().candidates("файл", types);
+        assert_eq!(candidates.candidates.len(), 2);
+        assert_eq!(
+            TypeOrigin::of(&candidates.winner().unwrap().source),
+            TypeOrigin::Configuration
+        );
+        assert!(candidates.is_ambiguous());
+        assert_eq!(
+            candidates.origins(),
+            [TypeOrigin::Configuration, TypeOrigin::Platform]
+        );
+
+        let precedence = NamePrecedence {
+            order: vec![TypeOrigin::Platform],
+        };
+        let types = vec![configuration("Файл"), platform("Файл")];
+        let candidates = precedence.candidates("Файл", types);
+        assert_eq!(
+            TypeOrigin::of(&candidates.winner().unwrap().source),
+            TypeOrigin::Platform
+        );
+    }
+
+    #[test]
+    fn test_single_origin_is_not_ambiguous() {
+        let candidates = NamePrecedence::default().candidates("Массив", vec![platform("Массив")]);
+        assert!(!candidates.is_ambiguous());
+        assert!(NamePrecedence::default()
+            .candidates("Нет", vec![platform("Массив")])
+            .winner()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_repository_keeps_colliding_types() {
+        let inner = Arc::new(InMemoryTypeRepository::new());
+        inner
+            .save_types(vec![platform("Цвет"), configuration("Цвет")])
+            .await
+            .unwrap();
+        let repository = PrecedenceTypeRepository::new(inner, NamePrecedence::default());
+
+        let found = repository.search_types("Цвет").await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(TypeOrigin::of(&found[0].source), TypeOrigin::Configuration);
+        assert_eq!(repository.get_stats().configuration_types, 1);
+    }
+
+    #[test]
+    fn test_origin_from_str() {
+        assert_eq!("Configuration".parse::<TypeOrigin>(), Ok(TypeOrigin::Configuration));
+        assert_eq!("user".parse::<TypeOrigin>(), Ok(TypeOrigin::UserDefined));
+        assert!("extension".parse::<TypeOrigin>().is_err());
+    }
+}
@@ -15,6 +15,45 @@ pub struct RawPropertyData {
     pub description: String,
 }
 
+impl RawPropertyData {
+    /// Имена типов значения; у реквизита составного типа их несколько
+    pub fn type_names(&self) -> Vec<&str> {
+        composite_type_names(&self.type_name)
+    }
+
+    pub fn is_composite(&self) -> bool {
+        self.type_names().len() > 1
+    }
+}
+
+/// Имена типов составного типа: `СправочникСсылка.Контрагенты,ДокументСсылка.Заказ`
+/// или `Составной(СправочникСсылка.А, СправочникСсылка.Б)`. Запятые внутри
+/// квалификаторов (`Число(15, 2)`) типы не разделяют.
+pub fn composite_type_names(type_name: &str) -> Vec<&str> {
+    let type_name = type_name.trim();
+    let type_name = type_name
+        .strip_prefix("Составной(")
+        .and_then(|inner| inner.strip_suffix(')'))
+        .unwrap_or(type_name);
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (idx, ch) in type_name.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(type_name[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(type_name[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RawParameterData {
     pub name: String,
@@ -57,3 +96,74 @@ pub struct ParseMetadata {
     pub line: u32,
     pub column: u32,
 }
+
+#[cfg(test)]
+impl RawTypeData {
+    /// Тестовый тип: идентификатор и русское имя равны `name`, остальные поля пусты
+    pub(crate) fn test_type(name: &str, source: TypeSource) -> Self {
+        Self {
+            id: name.to_string(),
+            russian_name: name.to_string(),
+            english_name: String::new(),
+            source,
+            category_path: vec![],
+            methods: vec![],
+            properties: vec![],
+            documentation: String::new(),
+            examples: vec![],
+            available_facets: vec![],
+            parse_metadata: ParseMetadata {
+                file_path: String::new(),
+                line: 0,
+                column: 0,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+impl RawMethodData {
+    /// Тестовая процедура без параметров и документации
+    pub(crate) fn test_method(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            documentation: String::new(),
+            parameters: vec![],
+            return_type: None,
+            return_type_name: None,
+            params: vec![],
+            is_function: false,
+            examples: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composite_type_names() {
+        assert_eq!(
+            composite_type_names("СправочникСсылка.Контрагенты,ДокументСсылка.Заказ"),
+            ["СправочникСсылка.Контрагенты", "ДокументСсылка.Заказ"]
+        );
+        assert_eq!(
+            composite_type_names("Составной(СправочникСсылка.А, СправочникСсылка.Б)"),
+            ["СправочникСсылка.А", "СправочникСсылка.Б"]
+        );
+        assert_eq!(
+            composite_type_names("Число(15, 2),Строка"),
+            ["Число(15, 2)", "Строка"]
+        );
+        assert!(composite_type_names("").is_empty());
+
+        let property = RawPropertyData {
+            name: "Контрагент".to_string(),
+            type_name: "Число(15, 2)".to_string(),
+            is_readonly: false,
+            description: String::new(),
+        };
+        assert!(!property.is_composite());
+    }
+}
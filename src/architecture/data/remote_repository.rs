@@ -0,0 +1,518 @@
+//! Удалённый репозиторий типов (режим общего сервера для команды)
+//!
+//! Тяжёлые индексы платформы и конфигурации парсятся один раз на центральном
+//! сервере (`bsl-web-server`), а лёгкие клиенты (LSP) обращаются к нему по HTTP
+//! за запросами к репозиторию и поиском. Вывод типов внутри файла остаётся
+//! локальным: результаты `add_resolution`/`save_types` складываются в локальный
+//! слой и на сервер не отправляются.
+//!
+//! Серверная сторона — маршруты `/api/repository/*` веб-сервера.
+
+use super::filters::TypeFilter;
+use super::stats::RepositoryStats;
+use super::type_repository::{InMemoryTypeRepository, RepositoryChange, TypeDelta, TypeRepository};
+use super::RawTypeData;
+use crate::domain::types::TypeResolution;
+use crate::error::ResultExt;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Префикс маршрутов репозитория на сервере
+pub const REPOSITORY_API_PREFIX: &str = "api/repository";
+
+/// Репозиторий, проксирующий запросы на центральный сервер анализа
+pub struct RemoteTypeRepository {
+    base_url: String,
+    client: reqwest::Client,
+    /// Локальный слой: результаты вывода типов текущего клиента
+    local: InMemoryTypeRepository,
+    /// Последняя полученная с сервера статистика (get_stats синхронный)
+    remote_stats: RwLock<RepositoryStats>,
+    /// Полный список типов сервера; перед выдачей сверяется с сервером по
+    /// ETag (`If-None-Match`), после перезагрузки данных сервера скачивается
+    /// заново
+    all_types_cache: tokio::sync::Mutex<Option<CachedTypes>>,
+}
+
+/// Полученный с сервера список типов и его ETag
+struct CachedTypes {
+    etag: Option<String>,
+    types: Vec<RawTypeData>,
+}
+
+impl RemoteTypeRepository {
+    /// Создать клиент для сервера `base_url` (например, "http://analysis:8080")
+    pub fn new(base_url: &str, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("Не удалось создать HTTP клиент удалённого репозитория")?;
+
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+            local: InMemoryTypeRepository::new(),
+            remote_stats: RwLock::new(RepositoryStats::default()),
+            all_types_cache: tokio::sync::Mutex::new(None),
+        })
+    }
+
+    /// Адрес сервера
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Проверить доступность сервера и обновить статистику
+    pub async fn connect(&self) -> Result<RepositoryStats> {
+        let stats: RepositoryStats = self
+            .get_json("stats")
+            .await
+            .with_context(|| format!("Сервер репозитория {} недоступен", self.base_url))?;
+        if let Ok(mut cached) = self.remote_stats.write() {
+            *cached = stats.clone();
+        }
+        Ok(stats)
+    }
+
+    fn url(&self, endpoint: &str) -> String {
+        format!("{}/{}/{}", self.base_url, REPOSITORY_API_PREFIX, endpoint)
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(self.url(endpoint))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Полный список типов сервера; `None`, если копия с ETag `etag` актуальна
+    async fn fetch_types(&self, etag: Option<&str>) -> Result<Option<CachedTypes>> {
+        let mut request = self.client.get(self.url("types"));
+        if let Some(etag) = etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?;
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        let response = response.error_for_status()?;
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        Ok(Some(CachedTypes {
+            etag,
+            types: response.json().await?,
+        }))
+    }
+
+    async fn post_json<B: serde::Serialize + ?Sized, T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<T> {
+        let response = self
+            .client
+            .post(self.url(endpoint))
+            .json(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl TypeRepository for RemoteTypeRepository {
+    fn add_resolution(&self, resolution: TypeResolution) {
+        self.local.add_resolution(resolution);
+    }
+
+    fn get_stats(&self) -> RepositoryStats {
+        let remote = self
+            .remote_stats
+            .read()
+            .map(|s| s.clone())
+            .unwrap_or_default();
+        let local = self.local.get_stats();
+
+        RepositoryStats {
+            total_types: remote.total_types + local.total_types,
+            platform_types: remote.platform_types + local.platform_types,
+            configuration_types: remote.configuration_types + local.configuration_types,
+            user_defined_types: remote.user_defined_types + local.user_defined_types,
+            types_count: remote.types_count + local.types_count,
+        }
+    }
+
+    async fn clear(&self) -> crate::Result<()> {
+        // Серверные данные общие для команды — очищаем только локальное состояние
+        self.local.clear().await?;
+        *self.all_types_cache.lock().await = None;
+        Ok(())
+    }
+
+    async fn save_types(&self, types: Vec<RawTypeData>) -> crate::Result<()> {
+        self.local.save_types(types).await
+    }
+
+    async fn search_types(&self, query: &str) -> crate::Result<Vec<RawTypeData>> {
+        let mut result: Vec<RawTypeData> = self
+            .get_json(&format!("search?q={}", urlencoding::encode(query)))
+            .await
+            .data_source_err(format!("Поиск на сервере {} не выполнен", self.base_url))?;
+        result.extend(self.local.search_types(query).await?);
+        Ok(result)
+    }
+
+    async fn load_all_types(&self) -> crate::Result<Vec<RawTypeData>> {
+        // Блокировка держится на время запроса: параллельные загрузки не
+        // скачивают список повторно, а clear не теряет ответ 304
+        let mut cache = self.all_types_cache.lock().await;
+        let etag = cache.as_ref().and_then(|cached| cached.etag.clone());
+        let fetched = self
+            .fetch_types(etag.as_deref())
+            .await
+            .data_source_err(format!(
+                "Не удалось загрузить типы с сервера {}",
+                self.base_url
+            ))?;
+        if let Some(fetched) = fetched {
+            *cache = Some(fetched);
+        }
+        let mut all = cache
+            .as_ref()
+            .map(|cached| cached.types.clone())
+            .unwrap_or_default();
+        drop(cache);
+
+        all.extend(self.local.load_all_types().await?);
+        Ok(all)
+    }
+
+    async fn load_types_filtered(&self, filter: &TypeFilter) -> crate::Result<Vec<RawTypeData>> {
+        let mut result: Vec<RawTypeData> = self
+            .post_json("filter", filter)
+            .await
+            .data_source_err(format!("Фильтрация на сервере {} не выполнена", self.base_url))?;
+        result.extend(self.local.load_types_filtered(filter).await?);
+        Ok(result)
+    }
+
+    async fn apply_delta(&self, delta: TypeDelta) -> crate::Result<RepositoryChange> {
+        // Типы сервера только читаются: надгробия удаляют лишь локальные типы
+        self.local.apply_delta(delta).await
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<RepositoryChange> {
+        self.local.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::architecture::data::TypeSource;
+    use crate::Error;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Запрос, полученный mock-сервером
+    #[derive(Debug, Clone)]
+    struct RecordedRequest {
+        method: String,
+        path: String,
+        body: String,
+        if_none_match: Option<String>,
+    }
+
+    type Route = Arc<dyn Fn(&str, &str) -> (u16, String) + Send + Sync>;
+
+    /// Минимальный HTTP/1.1 сервер на 127.0.0.1: отвечает по маршруту и
+    /// запоминает все запросы. Ответ 200 несёт ETag тела, совпавший
+    /// If-None-Match даёт 304
+    async fn mock_server(
+        route: impl Fn(&str, &str) -> (u16, String) + Send + Sync + 'static,
+    ) -> (String, Arc<Mutex<Vec<RecordedRequest>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let route: Route = Arc::new(route);
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let recorded = recorded.clone();
+                let route = route.clone();
+                tokio::spawn(async move {
+                    let Some(request) = read_request(&mut stream).await else {
+                        return;
+                    };
+                    let (mut status, mut body) = route(&request.method, &request.path);
+                    let etag = body_etag(&body);
+                    if status == 200 && request.if_none_match.as_deref() == Some(etag.as_str()) {
+                        status = 304;
+                        body.clear();
+                    }
+                    recorded.lock().unwrap().push(request);
+                    let response = format!(
+                        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nETag: {}\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        etag,
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    fn body_etag(body: &str) -> String {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        body.hash(&mut hasher);
+        format!("\"{:x}\"", hasher.finish())
+    }
+
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<RecordedRequest> {
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let header_end = loop {
+            let read = stream.read(&mut chunk).await.ok()?;
+            if read == 0 {
+                return None;
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+            if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+
+        let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+        let mut request_line = head.lines().next()?.split_whitespace();
+        let method = request_line.next()?.to_string();
+        let path = request_line.next()?.to_string();
+        let header = |wanted: &str| {
+            head.lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+                .map(|(_, value)| value.trim().to_string())
+        };
+        let content_length = header("content-length")
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(0);
+        let if_none_match = header("if-none-match");
+
+        while buffer.len() < header_end + content_length {
+            let read = stream.read(&mut chunk).await.ok()?;
+            if read == 0 {
+                break;
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+        }
+        let body = String::from_utf8_lossy(&buffer[header_end..]).to_string();
+
+        Some(RecordedRequest {
+            method,
+            path,
+            body,
+            if_none_match,
+        })
+    }
+
+    fn platform_types(names: &[&str]) -> String {
+        let types: Vec<RawTypeData> = names
+            .iter()
+            .map(|name| {
+                RawTypeData::test_type(
+                    name,
+                    TypeSource::Platform {
+                        version: "8.3.25".to_string(),
+                    },
+                )
+            })
+            .collect();
+        serde_json::to_string(&types).unwrap()
+    }
+
+    fn repository(base_url: &str) -> RemoteTypeRepository {
+        RemoteTypeRepository::new(base_url, Duration::from_secs(5)).unwrap()
+    }
+
+    fn paths(requests: &Arc<Mutex<Vec<RecordedRequest>>>) -> Vec<String> {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.path.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_connect_fetches_stats() {
+        let (url, requests) = mock_server(|_, _| {
+            let stats = RepositoryStats {
+                total_types: 3,
+                platform_types: 2,
+                configuration_types: 1,
+                user_defined_types: 0,
+                types_count: 3,
+            };
+            (200, serde_json::to_string(&stats).unwrap())
+        })
+        .await;
+
+        let repo = repository(&url);
+        let stats = repo.connect().await.unwrap();
+
+        assert_eq!(stats.total_types, 3);
+        assert_eq!(repo.get_stats().platform_types, 2);
+        assert_eq!(repo.get_stats().configuration_types, 1);
+        assert_eq!(paths(&requests), vec!["/api/repository/stats"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_types_encodes_query() {
+        let (url, requests) = mock_server(|_, _| (200, platform_types(&["Массив"]))).await;
+
+        let repo = repository(&url);
+        let found = repo.search_types("Массив значений").await.unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].russian_name, "Массив");
+        assert_eq!(
+            paths(&requests),
+            vec![format!(
+                "/api/repository/search?q={}",
+                urlencoding::encode("Массив значений")
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_all_types_cached_and_merged_with_local() {
+        let (url, requests) =
+            mock_server(|_, _| (200, platform_types(&["Массив", "Структура"]))).await;
+
+        let repo = repository(&url);
+        repo.save_types(vec![RawTypeData::test_type(
+            "ЛокальныйТип",
+            TypeSource::UserDefined {
+                file_path: "module.bsl".to_string(),
+            },
+        )])
+        .await
+        .unwrap();
+
+        let first = repo.load_all_types().await.unwrap();
+        let second = repo.load_all_types().await.unwrap();
+
+        assert_eq!(first.len(), 3);
+        assert_eq!(second.len(), 3);
+        assert!(first.iter().any(|t| t.russian_name == "Структура"));
+        // Повторная загрузка лишь сверяет ETag: сервер отвечает 304 без тела
+        assert_eq!(paths(&requests), vec!["/api/repository/types"; 2]);
+        let recorded = requests.lock().unwrap().clone();
+        assert_eq!(recorded[0].if_none_match, None);
+        assert!(recorded[1].if_none_match.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_load_all_types_refreshed_after_server_reload() {
+        let reloaded = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = reloaded.clone();
+        let (url, _) = mock_server(move |_, _| {
+            if flag.load(std::sync::atomic::Ordering::SeqCst) {
+                (200, platform_types(&["Массив", "Структура"]))
+            } else {
+                (200, platform_types(&["Массив"]))
+            }
+        })
+        .await;
+
+        let repo = repository(&url);
+        assert_eq!(repo.load_all_types().await.unwrap().len(), 1);
+
+        // Сервер перезагрузил индексы — ETag сменился, кэш клиента обновляется
+        reloaded.store(true, std::sync::atomic::Ordering::SeqCst);
+        let types = repo.load_all_types().await.unwrap();
+        assert_eq!(types.len(), 2);
+        assert!(types.iter().any(|t| t.russian_name == "Структура"));
+    }
+
+    #[tokio::test]
+    async fn test_load_types_filtered_posts_filter() {
+        let (url, requests) = mock_server(|method, _| match method {
+            "POST" => (200, platform_types(&["Структура"])),
+            _ => (405, String::new()),
+        })
+        .await;
+
+        let repo = repository(&url);
+        let filter = TypeFilter {
+            name_contains: Some("Структ".to_string()),
+            has_methods: Some(true),
+            ..Default::default()
+        };
+        let found = repo.load_types_filtered(&filter).await.unwrap();
+
+        assert_eq!(found.len(), 1);
+        let recorded = requests.lock().unwrap().clone();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].method, "POST");
+        assert_eq!(recorded[0].path, "/api/repository/filter");
+        let sent: TypeFilter = serde_json::from_str(&recorded[0].body).unwrap();
+        assert_eq!(sent.name_contains.as_deref(), Some("Структ"));
+        assert_eq!(sent.has_methods, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_server_error_translated_to_data_source() {
+        let (url, _) = mock_server(|_, _| (500, "{}".to_string())).await;
+        let repo = repository(&url);
+
+        let error = repo.search_types("Массив").await.unwrap_err();
+        assert!(matches!(error, Error::DataSource { .. }));
+        assert!(error.to_string().contains("Поиск на сервере"));
+
+        let error = repo
+            .load_types_filtered(&TypeFilter::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::DataSource { .. }));
+        assert!(error.to_string().contains("Фильтрация на сервере"));
+
+        // Неудачная загрузка не кэшируется
+        assert!(repo.load_all_types().await.is_err());
+        assert!(repo.all_types_cache.lock().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server() {
+        // Занимаем свободный порт и сразу освобождаем его
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let repo = repository(&url);
+        let error = repo.connect().await.unwrap_err();
+        assert!(error.to_string().contains("недоступен"));
+
+        let error = repo.load_all_types().await.unwrap_err();
+        assert!(matches!(error, Error::DataSource { .. }));
+        assert!(error.to_string().contains("Не удалось загрузить типы"));
+    }
+}
@@ -0,0 +1,534 @@
+//! Снимок репозитория типов, отображаемый в память только для чтения
+//!
+//! LSP, веб-сервер и CLI на одной машине без снимка держат каждый свою копию
+//! разобранных типов платформы и конфигурации (сотни мегабайт). Снимок —
+//! один файл, который процессы отображают в память через `mmap`: страницы
+//! общие в кеше ОС, а запись типа десериализуется только при обращении к ней.
+//!
+//! Формат (все числа little-endian):
+//!
+//! | Раздел | Содержимое |
+//! |--------|------------|
+//! | Заголовок | `BSLTYPES`, версия формата, число типов и ключей, счётчики по источникам |
+//! | Таблица записей | для каждого типа: смещение `u64` и длина `u32` записи |
+//! | Таблица ключей | смещение `u64` и длина `u32` ключа, номер записи `u32`; отсортирована по ключу |
+//! | Данные | ключи (имена в нижнем регистре, UTF-8) и записи `RawTypeData` в bincode |
+//!
+//! Поиск по имени — двоичный поиск по таблице ключей без десериализации.
+//! Записи хранятся в bincode, а не rkyv/flatbuffers: `RawTypeData` и типы
+//! домена уже сериализуются через serde, и отдельная схема не понадобилась.
+//! Поэтому чтение не zero-copy: общими между процессами остаются байты
+//! файла, а каждая прочитанная запись декодируется в собственный
+//! `RawTypeData` процесса — отсюда имена методов `decode_*`.
+//!
+//! Снимок перезаписывается только атомарной заменой файла, поэтому уже
+//! открытые отображения продолжают видеть прежнюю версию.
+
+use super::filters::TypeFilter;
+use super::stats::RepositoryStats;
+use super::type_repository::{InMemoryTypeRepository, RepositoryChange, TypeDelta, TypeRepository};
+use super::{RawTypeData, TypeSource};
+use crate::domain::types::TypeResolution;
+use crate::error::{Error, Result, ResultExt};
+use async_trait::async_trait;
+use memmap2::Mmap;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tokio::sync::broadcast;
+
+/// Сигнатура файла снимка
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"BSLTYPES";
+
+/// Версия формата; снимок другой версии не открывается
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+const HEADER_LEN: usize = 32;
+const RECORD_ENTRY_LEN: usize = 12;
+const KEY_ENTRY_LEN: usize = 16;
+
+/// Ключи поиска типа: русское и английское имя в нижнем регистре
+fn type_keys(raw: &RawTypeData) -> Vec<String> {
+    let mut keys = vec![raw.russian_name.to_lowercase()];
+    let english = raw.english_name.to_lowercase();
+    if !english.is_empty() && english != keys[0] {
+        keys.push(english);
+    }
+    keys
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// Записать снимок типов в файл `path` (через временный файл и переименование)
+pub fn write_snapshot(path: &Path, types: &[RawTypeData]) -> Result<()> {
+    write_snapshot_bytes(path, &encode_snapshot(types)?)
+}
+
+/// Записать уже закодированный снимок (через временный файл и переименование)
+pub fn write_snapshot_bytes(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, bytes).map_err(|e| Error::io(&tmp_path, e))?;
+    std::fs::rename(&tmp_path, path).map_err(|e| Error::io(path, e))?;
+    Ok(())
+}
+
+/// Закодировать типы в формат снимка
+pub fn encode_snapshot(types: &[RawTypeData]) -> Result<Vec<u8>> {
+    let records = types
+        .iter()
+        .map(bincode::serialize)
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .parse_err("Не удалось сериализовать типы снимка")?;
+    let mut keys: Vec<(String, u32)> = types
+        .iter()
+        .enumerate()
+        .flat_map(|(index, raw)| {
+            type_keys(raw)
+                .into_iter()
+                .map(move |key| (key, index as u32))
+        })
+        .collect();
+    keys.sort();
+
+    let (mut platform, mut configuration, mut user_defined) = (0u32, 0u32, 0u32);
+    for raw in types {
+        match raw.source {
+            TypeSource::Platform { .. } => platform += 1,
+            TypeSource::Configuration { .. } => configuration += 1,
+            TypeSource::UserDefined { .. } => user_defined += 1,
+        }
+    }
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(SNAPSHOT_MAGIC);
+    for value in [
+        SNAPSHOT_FORMAT_VERSION,
+        records.len() as u32,
+        keys.len() as u32,
+        platform,
+        configuration,
+        user_defined,
+    ] {
+        header.extend_from_slice(&value.to_le_bytes());
+    }
+
+    // Данные начинаются сразу после таблиц
+    let mut offset =
+        (HEADER_LEN + records.len() * RECORD_ENTRY_LEN + keys.len() * KEY_ENTRY_LEN) as u64;
+    let mut record_table = Vec::with_capacity(records.len() * RECORD_ENTRY_LEN);
+    for record in &records {
+        record_table.extend_from_slice(&offset.to_le_bytes());
+        record_table.extend_from_slice(&(record.len() as u32).to_le_bytes());
+        offset += record.len() as u64;
+    }
+    let mut key_table = Vec::with_capacity(keys.len() * KEY_ENTRY_LEN);
+    for (key, record) in &keys {
+        key_table.extend_from_slice(&offset.to_le_bytes());
+        key_table.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        key_table.extend_from_slice(&record.to_le_bytes());
+        offset += key.len() as u64;
+    }
+
+    let mut bytes = Vec::with_capacity(offset as usize);
+    bytes.extend_from_slice(&header);
+    bytes.extend_from_slice(&record_table);
+    bytes.extend_from_slice(&key_table);
+    for record in &records {
+        bytes.extend_from_slice(record);
+    }
+    for (key, _) in &keys {
+        bytes.extend_from_slice(key.as_bytes());
+    }
+    Ok(bytes)
+}
+
+/// Содержимое снимка: отображённый файл или буфер (при сравнении артефактов)
+enum SnapshotBytes {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl std::ops::Deref for SnapshotBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            SnapshotBytes::Mapped(mmap) => mmap,
+            SnapshotBytes::Owned(bytes) => bytes,
+        }
+    }
+}
+
+/// Снимок типов, отображённый в память; записи декодируются при каждом чтении
+pub struct TypeSnapshot {
+    path: PathBuf,
+    data: SnapshotBytes,
+    type_count: usize,
+    key_count: usize,
+    stats: RepositoryStats,
+}
+
+impl TypeSnapshot {
+    /// Открыть снимок и проверить заголовок и границы таблиц
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path).map_err(|e| Error::io(path, e))?;
+        // SAFETY: файл снимка не меняется на месте — новая версия записывается
+        // рядом и атомарно заменяет старую (см. `write_snapshot`)
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|e| Error::io(path, e))?;
+        Self::parse(path, SnapshotBytes::Mapped(mmap))
+    }
+
+    /// Разобрать снимок из буфера; `origin` используется в сообщениях об ошибках
+    pub fn from_bytes(bytes: Vec<u8>, origin: &Path) -> Result<Self> {
+        Self::parse(origin, SnapshotBytes::Owned(bytes))
+    }
+
+    fn parse(path: &Path, data: SnapshotBytes) -> Result<Self> {
+        if data.len() < HEADER_LEN || &data[..8] != SNAPSHOT_MAGIC {
+            return Err(Error::parse(format!(
+                "{} не является снимком типов",
+                path.display()
+            )));
+        }
+        let version = read_u32(&data, 8);
+        if version != SNAPSHOT_FORMAT_VERSION {
+            return Err(Error::parse(format!(
+                "Снимок {} имеет формат версии {}, поддерживается {}",
+                path.display(),
+                version,
+                SNAPSHOT_FORMAT_VERSION
+            )));
+        }
+        let type_count = read_u32(&data, 12) as usize;
+        let key_count = read_u32(&data, 16) as usize;
+        let (platform, configuration, user_defined) = (
+            read_u32(&data, 20) as usize,
+            read_u32(&data, 24) as usize,
+            read_u32(&data, 28) as usize,
+        );
+
+        let snapshot = Self {
+            path: path.to_path_buf(),
+            data,
+            type_count,
+            key_count,
+            stats: RepositoryStats {
+                total_types: type_count,
+                platform_types: platform,
+                configuration_types: configuration,
+                user_defined_types: user_defined,
+                types_count: type_count,
+            },
+        };
+        snapshot.validate()?;
+        Ok(snapshot)
+    }
+
+    /// Проверить, что все таблицы и данные лежат в пределах файла
+    fn validate(&self) -> Result<()> {
+        let tables_end =
+            HEADER_LEN + self.type_count * RECORD_ENTRY_LEN + self.key_count * KEY_ENTRY_LEN;
+        if tables_end > self.data.len() {
+            return Err(Error::parse(format!(
+                "Снимок {} повреждён: таблицы обрезаны",
+                self.path.display()
+            )));
+        }
+        let spans = (0..self.type_count)
+            .map(|i| self.record_span(i))
+            .chain((0..self.key_count).map(|i| self.key_span(i)));
+        for (start, end) in spans {
+            if start < tables_end || start > end || end > self.data.len() {
+                return Err(Error::parse(format!(
+                    "Снимок {} повреждён: данные вне файла",
+                    self.path.display()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Путь к файлу снимка
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Число типов
+    pub fn len(&self) -> usize {
+        self.type_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.type_count == 0
+    }
+
+    /// Счётчики по источникам (из заголовка, без чтения записей)
+    pub fn stats(&self) -> RepositoryStats {
+        self.stats.clone()
+    }
+
+    fn record_span(&self, index: usize) -> (usize, usize) {
+        let entry = HEADER_LEN + index * RECORD_ENTRY_LEN;
+        let start = read_u64(&self.data, entry) as usize;
+        (
+            start,
+            start.saturating_add(read_u32(&self.data, entry + 8) as usize),
+        )
+    }
+
+    fn key_span(&self, index: usize) -> (usize, usize) {
+        let entry = HEADER_LEN + self.type_count * RECORD_ENTRY_LEN + index * KEY_ENTRY_LEN;
+        let start = read_u64(&self.data, entry) as usize;
+        (
+            start,
+            start.saturating_add(read_u32(&self.data, entry + 8) as usize),
+        )
+    }
+
+    /// Ключ с номером `index` и номер записи, на которую он указывает
+    fn key(&self, index: usize) -> (&[u8], usize) {
+        let (start, end) = self.key_span(index);
+        let entry = HEADER_LEN + self.type_count * RECORD_ENTRY_LEN + index * KEY_ENTRY_LEN;
+        (
+            &self.data[start..end],
+            read_u32(&self.data, entry + 12) as usize,
+        )
+    }
+
+    /// Тип с номером `index` (в порядке записи снимка)
+    pub fn decode_at(&self, index: usize) -> Result<RawTypeData> {
+        let (start, end) = self.record_span(index);
+        bincode::deserialize(&self.data[start..end]).parse_err(format!(
+            "Снимок {} повреждён: запись {}",
+            self.path.display(),
+            index
+        ))
+    }
+
+    /// Найти тип по русскому или английскому имени (регистр не важен)
+    pub fn decode_by_name(&self, name: &str) -> Result<Option<RawTypeData>> {
+        let key = name.to_lowercase();
+        let (mut low, mut high) = (0, self.key_count);
+        while low < high {
+            let mid = (low + high) / 2;
+            let (candidate, record) = self.key(mid);
+            match candidate.cmp(key.as_bytes()) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return self.decode_at(record).map(Some),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Типы, у которых имя содержит подстроку (десериализуются только найденные)
+    pub fn decode_matching(&self, query: &str) -> Result<Vec<RawTypeData>> {
+        let query = query.to_lowercase();
+        let records: BTreeSet<usize> = (0..self.key_count)
+            .map(|i| self.key(i))
+            .filter(|(key, _)| std::str::from_utf8(key).is_ok_and(|key| key.contains(&query)))
+            .map(|(_, record)| record)
+            .collect();
+        records.into_iter().map(|i| self.decode_at(i)).collect()
+    }
+
+    /// Все типы снимка
+    pub fn decode_all(&self) -> Result<Vec<RawTypeData>> {
+        (0..self.type_count).map(|i| self.decode_at(i)).collect()
+    }
+}
+
+/// Репозиторий поверх снимка: типы платформы и конфигурации читаются из
+/// общего отображения, результаты вывода типов текущего процесса
+/// складываются в локальный слой (как у [`super::RemoteTypeRepository`])
+pub struct SnapshotTypeRepository {
+    snapshot: TypeSnapshot,
+    local: InMemoryTypeRepository,
+}
+
+impl SnapshotTypeRepository {
+    /// Открыть репозиторий по файлу снимка
+    pub fn open(path: &Path) -> Result<Self> {
+        Ok(Self {
+            snapshot: TypeSnapshot::open(path)?,
+            local: InMemoryTypeRepository::new(),
+        })
+    }
+
+    /// Отображённый снимок
+    pub fn snapshot(&self) -> &TypeSnapshot {
+        &self.snapshot
+    }
+
+    fn read_err(&self) -> String {
+        format!(
+            "Не удалось прочитать снимок {}",
+            self.snapshot.path().display()
+        )
+    }
+}
+
+#[async_trait]
+impl TypeRepository for SnapshotTypeRepository {
+    fn add_resolution(&self, resolution: TypeResolution) {
+        self.local.add_resolution(resolution);
+    }
+
+    fn get_stats(&self) -> RepositoryStats {
+        let base = self.snapshot.stats();
+        let local = self.local.get_stats();
+        RepositoryStats {
+            total_types: base.total_types + local.total_types,
+            platform_types: base.platform_types + local.platform_types,
+            configuration_types: base.configuration_types + local.configuration_types,
+            user_defined_types: base.user_defined_types + local.user_defined_types,
+            types_count: base.types_count + local.types_count,
+        }
+    }
+
+    async fn clear(&self) -> crate::Result<()> {
+        // Снимок только для чтения — очищаем локальный слой
+        self.local.clear().await
+    }
+
+    async fn save_types(&self, types: Vec<RawTypeData>) -> crate::Result<()> {
+        self.local.save_types(types).await
+    }
+
+    async fn search_types(&self, query: &str) -> crate::Result<Vec<RawTypeData>> {
+        let mut result = self
+            .snapshot
+            .decode_matching(query)
+            .data_source_err(self.read_err())?;
+        result.extend(self.local.search_types(query).await?);
+        Ok(result)
+    }
+
+    async fn load_all_types(&self) -> crate::Result<Vec<RawTypeData>> {
+        let mut all = self
+            .snapshot
+            .decode_all()
+            .data_source_err(self.read_err())?;
+        all.extend(self.local.load_all_types().await?);
+        Ok(all)
+    }
+
+    async fn load_types_filtered(&self, filter: &TypeFilter) -> crate::Result<Vec<RawTypeData>> {
+        let mut result = self
+            .snapshot
+            .decode_all()
+            .data_source_err(self.read_err())?;
+        result.retain(|raw| filter.matches(raw));
+        result.extend(self.local.load_types_filtered(filter).await?);
+        Ok(result)
+    }
+
+    async fn apply_delta(&self, delta: TypeDelta) -> crate::Result<RepositoryChange> {
+        // Типы снимка только читаются: надгробия удаляют лишь локальные типы
+        self.local.apply_delta(delta).await
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<RepositoryChange> {
+        self.local.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_type(russian: &str, english: &str, source: TypeSource) -> RawTypeData {
+        RawTypeData {
+            english_name: english.to_string(),
+            documentation: format!("Тип {}", russian),
+            ..RawTypeData::test_type(russian, source)
+        }
+    }
+
+    fn sample_types() -> Vec<RawTypeData> {
+        let platform = || TypeSource::Platform {
+            version: "8.3".to_string(),
+        };
+        vec![
+            raw_type("ТаблицаЗначений", "ValueTable", platform()),
+            raw_type("Массив", "Array", platform()),
+            raw_type(
+                "СправочникСсылка.Контрагенты",
+                "",
+                TypeSource::Configuration {
+                    config_version: "1.0".to_string(),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("types.snapshot");
+        write_snapshot(&path, &sample_types())?;
+
+        let snapshot = TypeSnapshot::open(&path)?;
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot.stats().platform_types, 2);
+        assert_eq!(snapshot.stats().configuration_types, 1);
+
+        let array = snapshot.decode_by_name("array")?.unwrap();
+        assert_eq!(array.russian_name, "Массив");
+        assert_eq!(
+            snapshot.decode_by_name("МАССИВ")?.unwrap().english_name,
+            "Array"
+        );
+        assert!(snapshot.decode_by_name("Структура")?.is_none());
+
+        let found = snapshot.decode_matching("значен")?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].documentation, "Тип ТаблицаЗначений");
+
+        let names: Vec<String> = snapshot.decode_all()?.into_iter().map(|t| t.id).collect();
+        assert_eq!(
+            names,
+            ["ТаблицаЗначений", "Массив", "СправочникСсылка.Контрагенты"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_foreign_and_truncated_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("types.snapshot");
+        std::fs::write(&path, b"not a snapshot at all, definitely not")?;
+        assert!(TypeSnapshot::open(&path).is_err());
+
+        write_snapshot(&path, &sample_types())?;
+        let bytes = std::fs::read(&path)?;
+        std::fs::write(&path, &bytes[..bytes.len() - 10])?;
+        assert!(TypeSnapshot::open(&path).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_repository_with_local_layer() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("types.snapshot");
+        write_snapshot(&path, &sample_types())?;
+
+        let repository = SnapshotTypeRepository::open(&path)?;
+        let filter = TypeFilter {
+            source: Some(TypeSource::Platform {
+                version: String::new(),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(repository.load_types_filtered(&filter).await?.len(), 2);
+
+        repository.clear().await?;
+        assert_eq!(repository.get_stats().total_types, 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+//! Распространение снимка платформенных типов одним артефактом
+//!
+//! Разбор справки платформы — самая долгая часть первого запуска. Команда
+//! может один раз собрать снимок платформенных типов, опубликовать его во
+//! внутреннем хранилище, а новые машины — импортировать готовый снимок и
+//! указать его в `BSL_SNAPSHOT`, не разбирая справку вовсе.
+//!
+//! Артефакт — zip-архив из двух файлов: `manifest.json` (версия платформы,
+//! версия формата, число типов и SHA-256 снимка) и `types.snapshot`.
+//! При импорте хеш и формат проверяются до того, как снимок заменит
+//! существующий файл.
+
+use super::snapshot::{encode_snapshot, TypeSnapshot, SNAPSHOT_FORMAT_VERSION};
+use super::{RawTypeData, TypeSource};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Имя манифеста внутри артефакта
+pub const ARTIFACT_MANIFEST: &str = "manifest.json";
+
+/// Имя снимка внутри артефакта
+pub const ARTIFACT_SNAPSHOT: &str = "types.snapshot";
+
+/// Манифест артефакта снимка
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Версия формата снимка (`SNAPSHOT_FORMAT_VERSION`)
+    pub format_version: u32,
+    /// Версия платформы, из справки которой получены типы
+    pub platform_version: String,
+    /// Версия bsl-gradual-types, собравшей снимок
+    pub tool_version: String,
+    pub type_count: usize,
+    /// SHA-256 файла снимка (hex)
+    pub sha256: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Файл манифеста рядом с импортированным снимком (`types.snapshot` →
+/// `types.manifest.json`)
+pub fn manifest_path(snapshot_path: &Path) -> PathBuf {
+    snapshot_path.with_extension("manifest.json")
+}
+
+/// Прочитать манифест, сохранённый рядом со снимком при импорте
+pub fn read_manifest(snapshot_path: &Path) -> Result<SnapshotManifest> {
+    let path = manifest_path(snapshot_path);
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Не удалось прочитать манифест {}", path.display()))?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+/// Собрать артефакт из платформенных типов `types` (остальные источники
+/// отбрасываются: типы конфигурации у каждой команды свои)
+pub fn export_platform_artifact(
+    types: &[RawTypeData],
+    platform_version: &str,
+    artifact: &Path,
+) -> Result<SnapshotManifest> {
+    let mut platform: Vec<RawTypeData> = types
+        .iter()
+        .filter(|raw| matches!(raw.source, TypeSource::Platform { .. }))
+        .cloned()
+        .collect();
+    if platform.is_empty() {
+        bail!("Нет платформенных типов для экспорта");
+    }
+    // Канонический порядок: одинаковые типы дают одинаковый снимок и хеш,
+    // что нужно для проверки дельт между версиями
+    platform.sort_by(|a, b| a.id.cmp(&b.id));
+    let bytes = encode_snapshot(&platform)?;
+    let manifest = SnapshotManifest {
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        platform_version: platform_version.to_string(),
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        type_count: platform.len(),
+        sha256: snapshot_sha256(&bytes),
+        created_at: chrono::Utc::now(),
+    };
+
+    let file = std::fs::File::create(artifact)
+        .with_context(|| format!("Не удалось создать артефакт {}", artifact.display()))?;
+    let mut zip = zip::ZipWriter::new(std::io::BufWriter::new(file));
+    let options = zip::write::FileOptions::default().large_file(bytes.len() > u32::MAX as usize);
+    zip.start_file(ARTIFACT_MANIFEST, zip::write::FileOptions::default())?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.start_file(ARTIFACT_SNAPSHOT, options)?;
+    zip.write_all(&bytes)?;
+    zip.finish()?.flush()?;
+    Ok(manifest)
+}
+
+/// Хеш содержимого снимка в формате манифеста
+pub fn snapshot_sha256(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Открыть артефакт и прочитать его манифест, проверив версию формата
+fn open_artifact(
+    artifact: &Path,
+) -> Result<(
+    zip::ZipArchive<std::io::BufReader<std::fs::File>>,
+    SnapshotManifest,
+)> {
+    let file = std::fs::File::open(artifact)
+        .with_context(|| format!("Не удалось открыть артефакт {}", artifact.display()))?;
+    let mut zip = zip::ZipArchive::new(std::io::BufReader::new(file))
+        .with_context(|| format!("{} не является артефактом снимка", artifact.display()))?;
+
+    let manifest: SnapshotManifest = {
+        let mut text = String::new();
+        zip.by_name(ARTIFACT_MANIFEST)
+            .with_context(|| format!("В артефакте нет {}", ARTIFACT_MANIFEST))?
+            .read_to_string(&mut text)?;
+        serde_json::from_str(&text).context("Некорректный манифест артефакта")?
+    };
+    if manifest.format_version != SNAPSHOT_FORMAT_VERSION {
+        bail!(
+            "Артефакт собран в формате снимка версии {}, поддерживается {}",
+            manifest.format_version,
+            SNAPSHOT_FORMAT_VERSION
+        );
+    }
+    Ok((zip, manifest))
+}
+
+/// Прочитать артефакт целиком в память с проверкой хеша снимка
+pub fn read_artifact(artifact: &Path) -> Result<(SnapshotManifest, Vec<u8>)> {
+    let (mut zip, manifest) = open_artifact(artifact)?;
+    let mut bytes = Vec::new();
+    zip.by_name(ARTIFACT_SNAPSHOT)
+        .with_context(|| format!("В артефакте нет {}", ARTIFACT_SNAPSHOT))?
+        .read_to_end(&mut bytes)?;
+    let sha256 = snapshot_sha256(&bytes);
+    if sha256 != manifest.sha256 {
+        bail!(
+            "Хеш снимка {} не совпадает с манифестом {}",
+            sha256,
+            manifest.sha256
+        );
+    }
+    Ok((manifest, bytes))
+}
+
+/// Проверить артефакт и распаковать снимок в `output`.
+///
+/// Если задана `expected_version`, артефакт другой версии платформы
+/// отклоняется. Рядом со снимком сохраняется его манифест.
+pub fn import_artifact(
+    artifact: &Path,
+    output: &Path,
+    expected_version: Option<&str>,
+) -> Result<SnapshotManifest> {
+    let (mut zip, manifest) = open_artifact(artifact)?;
+    if let Some(expected) = expected_version {
+        if manifest.platform_version != expected {
+            bail!(
+                "Артефакт содержит типы платформы {}, ожидалась {}",
+                manifest.platform_version,
+                expected
+            );
+        }
+    }
+
+    // Распаковываем во временный файл, считая хеш по ходу копирования
+    let tmp_path = output.with_extension("tmp");
+    let sha256 = {
+        let mut entry = zip
+            .by_name(ARTIFACT_SNAPSHOT)
+            .with_context(|| format!("В артефакте нет {}", ARTIFACT_SNAPSHOT))?;
+        let mut writer = std::io::BufWriter::new(
+            std::fs::File::create(&tmp_path)
+                .with_context(|| format!("Не удалось создать {}", tmp_path.display()))?,
+        );
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = entry.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            writer.write_all(&buffer[..read])?;
+        }
+        writer.flush()?;
+        format!("{:x}", hasher.finalize())
+    };
+
+    let verified = if sha256 != manifest.sha256 {
+        Err(anyhow::anyhow!(
+            "Хеш снимка {} не совпадает с манифестом {}",
+            sha256,
+            manifest.sha256
+        ))
+    } else {
+        TypeSnapshot::open(&tmp_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|snapshot| {
+                if snapshot.len() != manifest.type_count {
+                    bail!(
+                        "Снимок содержит {} типов, в манифесте {}",
+                        snapshot.len(),
+                        manifest.type_count
+                    );
+                }
+                Ok(())
+            })
+    };
+    if let Err(e) = verified {
+        std::fs::remove_file(&tmp_path).ok();
+        return Err(e);
+    }
+
+    std::fs::rename(&tmp_path, output)
+        .with_context(|| format!("Не удалось записать снимок {}", output.display()))?;
+    std::fs::write(
+        manifest_path(output),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types() -> Vec<RawTypeData> {
+        let platform = || TypeSource::Platform {
+            version: "8.3.25".to_string(),
+        };
+        vec![
+            RawTypeData::test_type("Массив", platform()),
+            RawTypeData::test_type("Структура", platform()),
+            RawTypeData::test_type(
+                "СправочникСсылка.Номенклатура",
+                TypeSource::Configuration {
+                    config_version: "1.0".to_string(),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let artifact = dir.path().join("platform-8.3.25.zip");
+        let exported = export_platform_artifact(&types(), "8.3.25", &artifact)?;
+        assert_eq!(exported.type_count, 2);
+
+        let output = dir.path().join("types.snapshot");
+        let imported = import_artifact(&artifact, &output, Some("8.3.25"))?;
+        assert_eq!(imported, exported);
+        assert_eq!(read_manifest(&output)?, exported);
+
+        let snapshot = TypeSnapshot::open(&output)?;
+        assert!(snapshot.decode_by_name("Структура")?.is_some());
+        assert!(snapshot
+            .decode_by_name("СправочникСсылка.Номенклатура")?
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_rejects_wrong_version_and_hash() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let artifact = dir.path().join("platform.zip");
+        let mut manifest = export_platform_artifact(&types(), "8.3.25", &artifact)?;
+        let output = dir.path().join("types.snapshot");
+
+        let err = import_artifact(&artifact, &output, Some("8.3.24")).unwrap_err();
+        assert!(err.to_string().contains("8.3.24"));
+
+        // Подменяем хеш в манифесте — снимок не должен появиться
+        manifest.sha256 = "0".repeat(64);
+        let snapshot = encode_snapshot(&types()[..2])?;
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&artifact)?);
+        zip.start_file(ARTIFACT_MANIFEST, zip::write::FileOptions::default())?;
+        zip.write_all(serde_json::to_string(&manifest)?.as_bytes())?;
+        zip.start_file(ARTIFACT_SNAPSHOT, zip::write::FileOptions::default())?;
+        zip.write_all(&snapshot)?;
+        zip.finish()?;
+
+        assert!(import_artifact(&artifact, &output, None).is_err());
+        assert!(!output.exists());
+        assert!(!output.with_extension("tmp").exists());
+        Ok(())
+    }
+}
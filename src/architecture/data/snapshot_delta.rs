@@ -0,0 +1,246 @@
+//! Дельты снимков платформенных типов между версиями платформы
+//!
+//! Между соседними версиями платформы (8.3.23 → 8.3.24) меняется малая часть
+//! типов, поэтому вместо нового артефакта целиком достаточно передать дельту:
+//! идентификаторы удалённых типов и записи добавленных или изменённых.
+//!
+//! Дельта — zip-архив из `delta.json` (хеш базового снимка, манифест
+//! целевого и список удалённых типов) и `upserts.bin` (записи в bincode).
+//! Применение возможно только к снимку с хешем базы, а результат проверяется
+//! по хешу целевого манифеста: снимки артефактов строятся в каноническом
+//! порядке (по идентификатору типа), поэтому совпадение хеша означает
+//! побайтно тот же снимок, что дал бы полный артефакт.
+
+use super::snapshot::{
+    encode_snapshot, write_snapshot_bytes, TypeSnapshot, SNAPSHOT_FORMAT_VERSION,
+};
+use super::snapshot_artifact::{manifest_path, read_artifact, snapshot_sha256, SnapshotManifest};
+use super::RawTypeData;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Имя манифеста внутри дельты
+pub const DELTA_MANIFEST: &str = "delta.json";
+
+/// Имя файла с добавленными и изменёнными типами внутри дельты
+pub const DELTA_UPSERTS: &str = "upserts.bin";
+
+/// Манифест дельты
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeltaManifest {
+    /// Версия формата снимка, к которому применяется дельта
+    pub format_version: u32,
+    pub base_platform_version: String,
+    /// SHA-256 базового снимка
+    pub base_sha256: String,
+    /// Манифест снимка, получаемого после применения
+    pub target: SnapshotManifest,
+    /// Идентификаторы удалённых типов
+    pub removed: Vec<String>,
+    /// Число добавленных и изменённых типов
+    pub upserted: usize,
+}
+
+/// Рассчитать дельту между артефактами `base` и `target` и записать её в `output`
+pub fn create_delta(base: &Path, target: &Path, output: &Path) -> Result<DeltaManifest> {
+    let (base_manifest, base_bytes) = read_artifact(base)?;
+    let (target_manifest, target_bytes) = read_artifact(target)?;
+    let base_types = TypeSnapshot::from_bytes(base_bytes, base)?.decode_all()?;
+    let target_types = TypeSnapshot::from_bytes(target_bytes, target)?.decode_all()?;
+
+    // Записи сравниваются в том же виде, в каком хранятся в снимке
+    let base_records = base_types
+        .iter()
+        .map(|raw| Ok((raw.id.as_str(), bincode::serialize(raw)?)))
+        .collect::<Result<HashMap<_, _>>>()?;
+    let mut upserts = Vec::new();
+    for raw in &target_types {
+        if base_records.get(raw.id.as_str()) != Some(&bincode::serialize(raw)?) {
+            upserts.push(raw);
+        }
+    }
+    let target_ids: HashSet<&str> = target_types.iter().map(|raw| raw.id.as_str()).collect();
+    let mut removed: Vec<String> = base_records
+        .keys()
+        .filter(|id| !target_ids.contains(*id))
+        .map(|id| id.to_string())
+        .collect();
+    removed.sort();
+
+    let manifest = DeltaManifest {
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        base_platform_version: base_manifest.platform_version,
+        base_sha256: base_manifest.sha256,
+        target: target_manifest,
+        removed,
+        upserted: upserts.len(),
+    };
+
+    let file = std::fs::File::create(output)
+        .with_context(|| format!("Не удалось создать дельту {}", output.display()))?;
+    let mut zip = zip::ZipWriter::new(std::io::BufWriter::new(file));
+    zip.start_file(DELTA_MANIFEST, zip::write::FileOptions::default())?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.start_file(DELTA_UPSERTS, zip::write::FileOptions::default())?;
+    zip.write_all(&bincode::serialize(&upserts)?)?;
+    zip.finish()?.flush()?;
+    Ok(manifest)
+}
+
+/// Применить дельту к снимку `base` и записать результат в `output`
+/// (может совпадать с `base`). Рядом с результатом сохраняется манифест
+/// целевой версии.
+pub fn apply_delta(delta: &Path, base: &Path, output: &Path) -> Result<SnapshotManifest> {
+    let file = std::fs::File::open(delta)
+        .with_context(|| format!("Не удалось открыть дельту {}", delta.display()))?;
+    let mut zip = zip::ZipArchive::new(std::io::BufReader::new(file))
+        .with_context(|| format!("{} не является дельтой снимка", delta.display()))?;
+    let manifest: DeltaManifest = {
+        let mut text = String::new();
+        zip.by_name(DELTA_MANIFEST)
+            .with_context(|| format!("В дельте нет {}", DELTA_MANIFEST))?
+            .read_to_string(&mut text)?;
+        serde_json::from_str(&text).context("Некорректный манифест дельты")?
+    };
+    if manifest.format_version != SNAPSHOT_FORMAT_VERSION {
+        bail!(
+            "Дельта рассчитана для формата снимка версии {}, поддерживается {}",
+            manifest.format_version,
+            SNAPSHOT_FORMAT_VERSION
+        );
+    }
+
+    let base_bytes = std::fs::read(base)
+        .with_context(|| format!("Не удалось прочитать снимок {}", base.display()))?;
+    if snapshot_sha256(&base_bytes) != manifest.base_sha256 {
+        bail!(
+            "Снимок {} не является базой дельты (ожидается снимок платформы {})",
+            base.display(),
+            manifest.base_platform_version
+        );
+    }
+    let upserts: Vec<RawTypeData> = {
+        let mut bytes = Vec::new();
+        zip.by_name(DELTA_UPSERTS)
+            .with_context(|| format!("В дельте нет {}", DELTA_UPSERTS))?
+            .read_to_end(&mut bytes)?;
+        bincode::deserialize(&bytes).context("Некорректные записи дельты")?
+    };
+
+    // BTreeMap сразу даёт канонический порядок по идентификатору
+    let mut types: BTreeMap<String, RawTypeData> = TypeSnapshot::from_bytes(base_bytes, base)?
+        .decode_all()?
+        .into_iter()
+        .map(|raw| (raw.id.clone(), raw))
+        .collect();
+    for id in &manifest.removed {
+        types.remove(id);
+    }
+    for raw in upserts {
+        types.insert(raw.id.clone(), raw);
+    }
+
+    let types: Vec<RawTypeData> = types.into_values().collect();
+    let bytes = encode_snapshot(&types)?;
+    let sha256 = snapshot_sha256(&bytes);
+    if sha256 != manifest.target.sha256 {
+        bail!(
+            "После применения дельты хеш снимка {} не совпал с ожидаемым {}; \
+             загрузите полный артефакт платформы {}",
+            sha256,
+            manifest.target.sha256,
+            manifest.target.platform_version
+        );
+    }
+
+    write_snapshot_bytes(output, &bytes)?;
+    std::fs::write(
+        manifest_path(output),
+        serde_json::to_string_pretty(&manifest.target)?,
+    )?;
+    Ok(manifest.target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::snapshot_artifact::{export_platform_artifact, import_artifact};
+    use super::super::TypeSource;
+    use super::*;
+
+    fn raw_type(name: &str, documentation: &str) -> RawTypeData {
+        let platform = TypeSource::Platform {
+            version: "8.3".to_string(),
+        };
+        RawTypeData {
+            documentation: documentation.to_string(),
+            ..RawTypeData::test_type(name, platform)
+        }
+    }
+
+    #[test]
+    fn test_delta_roundtrip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let old = dir.path().join("8.3.23.zip");
+        let new = dir.path().join("8.3.24.zip");
+        export_platform_artifact(
+            &[
+                raw_type("Массив", "Коллекция"),
+                raw_type("Структура", "Ключ и значение"),
+                raw_type("УстаревшийТип", ""),
+            ],
+            "8.3.23",
+            &old,
+        )?;
+        let target = export_platform_artifact(
+            &[
+                raw_type("Структура", "Ключ и значение"),
+                raw_type("Массив", "Упорядоченная коллекция"),
+                raw_type("НовыйТип", ""),
+            ],
+            "8.3.24",
+            &new,
+        )?;
+
+        let delta_path = dir.path().join("8.3.23-8.3.24.delta.zip");
+        let delta = create_delta(&old, &new, &delta_path)?;
+        assert_eq!(delta.removed, ["УстаревшийТип"]);
+        assert_eq!(delta.upserted, 2);
+        assert_eq!(delta.base_platform_version, "8.3.23");
+
+        // Применяем к импортированному снимку старой версии на его же месте
+        let snapshot = dir.path().join("platform.snapshot");
+        import_artifact(&old, &snapshot, None)?;
+        let applied = apply_delta(&delta_path, &snapshot, &snapshot)?;
+        assert_eq!(applied, target);
+
+        let snapshot = TypeSnapshot::open(&snapshot)?;
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(
+            snapshot.decode_by_name("Массив")?.unwrap().documentation,
+            "Упорядоченная коллекция"
+        );
+        assert!(snapshot.decode_by_name("УстаревшийТип")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_delta_requires_matching_base() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let old = dir.path().join("old.zip");
+        let new = dir.path().join("new.zip");
+        export_platform_artifact(&[raw_type("Массив", "")], "8.3.23", &old)?;
+        export_platform_artifact(&[raw_type("Массив", "Новое")], "8.3.24", &new)?;
+        let delta_path = dir.path().join("delta.zip");
+        create_delta(&old, &new, &delta_path)?;
+
+        // Снимок новой версии не является базой дельты
+        let snapshot = dir.path().join("platform.snapshot");
+        import_artifact(&new, &snapshot, None)?;
+        let err = apply_delta(&delta_path, &snapshot, &snapshot).unwrap_err();
+        assert!(err.to_string().contains("8.3.23"));
+        Ok(())
+    }
+}
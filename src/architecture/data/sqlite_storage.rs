@@ -0,0 +1,157 @@
+//! Хранилище артефактов в базе SQLite (feature `sqlite`)
+//!
+//! Все записи лежат в одной таблице одного файла: его проще положить на
+//! общий том, скопировать или забэкапить, чем каталог из тысяч файлов кеша.
+
+use super::storage::{check_key, StorageBackend};
+use crate::error::{Error, Result, ResultExt};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub struct SqliteStorage {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    /// Открыть базу `path`; без `read_only` база и таблица создаются при
+    /// необходимости
+    pub fn open(path: &Path, read_only: bool) -> Result<Self> {
+        let connection = if read_only {
+            Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        } else {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+            }
+            Connection::open(path)
+        }
+        .data_source_err(format!("Не удалось открыть базу {}", path.display()))?;
+        if !read_only {
+            connection
+                .execute_batch(
+                    "CREATE TABLE IF NOT EXISTS artifacts (
+                         key TEXT PRIMARY KEY,
+                         value BLOB NOT NULL,
+                         updated_at INTEGER NOT NULL
+                     );",
+                )
+                .data_source_err(format!("Не удалось подготовить базу {}", path.display()))?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.connection.lock().map_err(|_| {
+            Error::data_source(format!("Соединение с {} повреждено", self.path.display()))
+        })
+    }
+}
+
+impl StorageBackend for SqliteStorage {
+    fn kind(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn location(&self) -> String {
+        format!("sqlite://{}", self.path.display())
+    }
+
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        check_key(key)?;
+        let value = self
+            .connection()?
+            .query_row(
+                "SELECT value FROM artifacts WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .data_source_err(format!("Не удалось прочитать {}", self.describe_key(key)))?;
+        Ok(value)
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        check_key(key)?;
+        let updated_at = chrono::Utc::now().timestamp();
+        self.connection()?
+            .execute(
+                "INSERT INTO artifacts (key, value, updated_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(key) DO UPDATE SET
+                     value = excluded.value,
+                     updated_at = excluded.updated_at",
+                params![key, bytes, updated_at],
+            )
+            .data_source_err(format!("Не удалось записать {}", self.describe_key(key)))?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        check_key(key)?;
+        self.connection()?
+            .execute("DELETE FROM artifacts WHERE key = ?1", params![key])
+            .data_source_err(format!("Не удалось удалить {}", self.describe_key(key)))?;
+        Ok(())
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let connection = self.connection()?;
+        let keys = connection
+            .prepare("SELECT key FROM artifacts WHERE substr(key, 1, length(?1)) = ?1 ORDER BY key")
+            .and_then(|mut statement| {
+                statement
+                    .query_map(params![prefix], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<String>>>()
+            })
+            .data_source_err(format!("Не удалось получить список {}", self.location()))?;
+        Ok(keys)
+    }
+
+    fn size(&self, key: &str) -> Result<Option<u64>> {
+        check_key(key)?;
+        let size: Option<i64> = self
+            .connection()?
+            .query_row(
+                "SELECT length(value) FROM artifacts WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .data_source_err(format!("Не удалось прочитать {}", self.describe_key(key)))?;
+        Ok(size.map(|size| size as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bsl/store.db");
+        let storage = SqliteStorage::open(&path, false).unwrap();
+        storage.write("platform.types", b"types").unwrap();
+        storage.write("platform.types", b"types v2").unwrap();
+        storage.write("cache/a.cache", b"cache").unwrap();
+
+        assert_eq!(
+            storage.read("platform.types").unwrap().unwrap(),
+            b"types v2"
+        );
+        assert_eq!(storage.size("cache/a.cache").unwrap(), Some(5));
+        assert_eq!(storage.list("cache/").unwrap(), ["cache/a.cache"]);
+
+        storage.remove("cache/a.cache").unwrap();
+        assert!(storage.read("cache/a.cache").unwrap().is_none());
+
+        drop(storage);
+        let reader = SqliteStorage::open(&path, true).unwrap();
+        assert_eq!(reader.list("").unwrap(), ["platform.types"]);
+        assert!(reader.write("other.types", b"x").is_err());
+        assert!(SqliteStorage::open(&dir.path().join("missing.db"), true).is_err());
+    }
+}
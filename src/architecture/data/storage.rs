@@ -0,0 +1,338 @@
+//! Сменные хранилища артефактов
+//!
+//! Разобранные типы (`BSL_TYPE_STORE`) и кеш анализа по умолчанию лежат
+//! файлами в локальном каталоге. В корпоративной установке их удобнее
+//! держать централизованно: в одном файле SQLite на общем томе или в
+//! S3-совместимом хранилище (MinIO, Ceph, AWS S3), чтобы сборочные агенты
+//! и серверы команды не разбирали одно и то же заново.
+//!
+//! [`StorageBackend`] — плоское хранилище записей «ключ → байты». Ключи —
+//! относительные пути через `/` (`platform.types`, `0a1b…_0_4_2.cache`).
+//! Реализация выбирается при запуске по адресу хранилища
+//! ([`StorageLocation`]): каталог, `sqlite://` или `s3://`. Режим только для
+//! чтения ([`super::WriteGuard`]) проверяют вызывающие, поэтому он одинаково
+//! работает с любым хранилищем.
+
+use super::s3_storage::S3Storage;
+use super::snapshot::write_snapshot_bytes;
+use super::write_guard::WriteGuard;
+use crate::error::{Error, Result, ResultExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use walkdir::WalkDir;
+
+/// Хранилище записей «ключ → байты»
+pub trait StorageBackend: Send + Sync {
+    /// Вид хранилища: `fs`, `sqlite`, `s3`
+    fn kind(&self) -> &'static str;
+
+    /// Где лежат данные (каталог, файл базы, `s3://bucket/prefix`)
+    fn location(&self) -> String;
+
+    /// Полное имя записи для журнала и списка пропущенных записей
+    fn describe_key(&self, key: &str) -> String {
+        format!("{}/{}", self.location().trim_end_matches('/'), key)
+    }
+
+    /// Содержимое записи; `None`, если её нет
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Записать или заменить запись целиком
+    fn write(&self, key: &str, bytes: &[u8]) -> Result<()>;
+
+    /// Удалить запись; отсутствующая запись — не ошибка
+    fn remove(&self, key: &str) -> Result<()>;
+
+    /// Ключи с префиксом `prefix` в порядке возрастания
+    fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Размер записи в байтах
+    fn size(&self, key: &str) -> Result<Option<u64>> {
+        Ok(self.read(key)?.map(|bytes| bytes.len() as u64))
+    }
+}
+
+/// Ключ записи: непустой относительный путь без `..`
+pub fn check_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && !key.starts_with('/')
+        && !key.contains('\\')
+        && key.split('/').all(|part| !part.is_empty() && part != "..");
+    if !valid {
+        return Err(Error::config(format!(
+            "Недопустимый ключ хранилища '{}'",
+            key
+        )));
+    }
+    Ok(())
+}
+
+/// Адрес хранилища из настроек
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageLocation {
+    /// Локальный каталог (`/var/cache/bsl/store` или `file:///...`)
+    Directory(PathBuf),
+    /// Файл базы SQLite: `sqlite:///var/cache/bsl/store.db`
+    Sqlite(PathBuf),
+    /// S3-совместимое хранилище:
+    /// `s3://bucket/prefix?endpoint=http://minio:9000&region=us-east-1`
+    S3(String),
+}
+
+impl StorageLocation {
+    pub fn parse(location: &str) -> Result<Self> {
+        let location = location.trim();
+        if location.is_empty() {
+            return Err(Error::config("Не задан адрес хранилища"));
+        }
+        if let Some(path) = location.strip_prefix("sqlite://") {
+            if path.is_empty() {
+                return Err(Error::config(format!(
+                    "Не задан файл базы SQLite: {}",
+                    location
+                )));
+            }
+            return Ok(StorageLocation::Sqlite(PathBuf::from(path)));
+        }
+        if location.starts_with("s3://") {
+            return Ok(StorageLocation::S3(location.to_string()));
+        }
+        let path = location.strip_prefix("file://").unwrap_or(location);
+        Ok(StorageLocation::Directory(PathBuf::from(path)))
+    }
+
+    /// Открыть хранилище; в режиме только для чтения база SQLite не
+    /// создаётся, а открывается только на чтение
+    pub fn open(&self, write_guard: &WriteGuard) -> Result<Arc<dyn StorageBackend>> {
+        Ok(match self {
+            StorageLocation::Directory(dir) => Arc::new(FsStorage::new(dir)),
+            StorageLocation::Sqlite(path) => open_sqlite(path, write_guard.is_read_only())?,
+            StorageLocation::S3(url) => Arc::new(S3Storage::from_url(url)?),
+        })
+    }
+}
+
+/// Адрес вложенного хранилища (`projects/<имя>` для проекта сервера):
+/// подкаталог каталога, префикс внутри бакета S3 или отдельная база SQLite
+/// в подкаталоге рядом с основной
+pub fn nested_location(location: &str, segments: &[&str]) -> String {
+    match StorageLocation::parse(location) {
+        Ok(StorageLocation::Sqlite(path)) => {
+            let parent = path.parent().unwrap_or(Path::new(""));
+            let file_name = path.file_name().unwrap_or_default();
+            let nested = segments
+                .iter()
+                .fold(parent.to_path_buf(), |dir, segment| dir.join(segment));
+            format!("sqlite://{}", nested.join(file_name).display())
+        }
+        Ok(StorageLocation::S3(url)) => {
+            let (base, query) = match url.split_once('?') {
+                Some((base, query)) => (base, format!("?{}", query)),
+                None => (url.as_str(), String::new()),
+            };
+            format!(
+                "{}/{}{}",
+                base.trim_end_matches('/'),
+                segments.join("/"),
+                query
+            )
+        }
+        _ => segments
+            .iter()
+            .fold(Path::new(location).to_path_buf(), |dir, segment| {
+                dir.join(segment)
+            })
+            .to_string_lossy()
+            .to_string(),
+    }
+}
+
+/// Открыть хранилище по адресу из настроек
+pub fn open_storage(location: &str, write_guard: &WriteGuard) -> Result<Arc<dyn StorageBackend>> {
+    StorageLocation::parse(location)?.open(write_guard)
+}
+
+#[cfg(feature = "sqlite")]
+fn open_sqlite(path: &Path, read_only: bool) -> Result<Arc<dyn StorageBackend>> {
+    Ok(Arc::new(super::sqlite_storage::SqliteStorage::open(
+        path, read_only,
+    )?))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn open_sqlite(path: &Path, _read_only: bool) -> Result<Arc<dyn StorageBackend>> {
+    Err(Error::config(format!(
+        "Хранилище SQLite {} недоступно: сборка без feature \"sqlite\"",
+        path.display()
+    )))
+}
+
+/// Хранилище в локальном каталоге: запись — файл по пути ключа
+pub struct FsStorage {
+    root: PathBuf,
+}
+
+impl FsStorage {
+    /// Каталог создаётся при первой записи
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        check_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+impl StorageBackend for FsStorage {
+    fn kind(&self) -> &'static str {
+        "fs"
+    }
+
+    fn location(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn describe_key(&self, key: &str) -> String {
+        self.root.join(key).display().to_string()
+    }
+
+    fn read(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(key)?;
+        match std::fs::read(&path) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::io(path, e)),
+        }
+    }
+
+    fn write(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.path(key)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        }
+        // Через временный файл: читатель не увидит наполовину записанную запись
+        write_snapshot_bytes(&path, bytes)
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        let path = self.path(key)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(Error::io(path, e)),
+        }
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        if !self.root.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut keys = Vec::new();
+        for entry in WalkDir::new(&self.root).sort_by_file_name() {
+            let entry =
+                entry.data_source_err(format!("Не удалось обойти {}", self.root.display()))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry
+                .path()
+                .strip_prefix(&self.root)
+                .unwrap_or(entry.path());
+            let key = relative
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            // Временные файлы незавершённой записи
+            if key.starts_with(prefix) && !key.ends_with(".tmp") {
+                keys.push(key);
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn size(&self, key: &str) -> Result<Option<u64>> {
+        let path = self.path(key)?;
+        match std::fs::metadata(&path) {
+            Ok(metadata) => Ok(Some(metadata.len())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::io(path, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_location() {
+        assert_eq!(
+            StorageLocation::parse("/var/cache/bsl/store").unwrap(),
+            StorageLocation::Directory(PathBuf::from("/var/cache/bsl/store"))
+        );
+        assert_eq!(
+            StorageLocation::parse("file:///srv/store").unwrap(),
+            StorageLocation::Directory(PathBuf::from("/srv/store"))
+        );
+        assert_eq!(
+            StorageLocation::parse("sqlite:///srv/bsl/store.db").unwrap(),
+            StorageLocation::Sqlite(PathBuf::from("/srv/bsl/store.db"))
+        );
+        assert!(matches!(
+            StorageLocation::parse("s3://bsl-artifacts/store").unwrap(),
+            StorageLocation::S3(_)
+        ));
+        assert!(StorageLocation::parse("sqlite://").is_err());
+        assert!(StorageLocation::parse(" ").is_err());
+
+        assert_eq!(
+            nested_location("sqlite:///srv/bsl/store.db", &["projects", "erp"]),
+            "sqlite:///srv/bsl/projects/erp/store.db"
+        );
+        assert_eq!(
+            nested_location("s3://bsl/store?region=eu-west-1", &["projects", "erp"]),
+            "s3://bsl/store/projects/erp?region=eu-west-1"
+        );
+        assert_eq!(
+            nested_location("/srv/store", &["projects", "erp"]),
+            "/srv/store/projects/erp"
+        );
+
+        assert!(check_key("types/platform.types").is_ok());
+        assert!(check_key("../etc/passwd").is_err());
+        assert!(check_key("/abs").is_err());
+        assert!(check_key("a//b").is_err());
+    }
+
+    #[test]
+    fn test_fs_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FsStorage::new(&dir.path().join("store"));
+        assert!(storage.list("").unwrap().is_empty());
+        assert!(storage.read("platform.types").unwrap().is_none());
+
+        storage.write("platform.types", b"types").unwrap();
+        storage.write("cache/a.cache", b"cache").unwrap();
+        assert_eq!(storage.read("platform.types").unwrap().unwrap(), b"types");
+        assert_eq!(storage.size("cache/a.cache").unwrap(), Some(5));
+        assert_eq!(
+            storage.list("").unwrap(),
+            ["cache/a.cache", "platform.types"]
+        );
+        assert_eq!(storage.list("cache/").unwrap(), ["cache/a.cache"]);
+
+        storage.remove("cache/a.cache").unwrap();
+        storage.remove("cache/a.cache").unwrap();
+        assert_eq!(storage.list("").unwrap(), ["platform.types"]);
+        assert!(storage.read("../outside").is_err());
+    }
+}
@@ -1,10 +1,117 @@
+//! Хранилище типов и его пошаговое обновление
+//!
+//! Кроме полной записи (`save_types`) и очистки репозиторий принимает пакет
+//! изменений [`TypeDelta`]: новые и изменённые типы заменяют прежние с тем
+//! же источником и именем, а «надгробия» ([`TypeTombstone`]) удаляют
+//! отдельные типы или все типы файла описания. Каждое изменение рассылается
+//! подписчикам ([`TypeRepository::subscribe`]) как [`RepositoryChange`]:
+//! кеши прикладного слоя сбрасывают только записи затронутых типов, а не
+//! всё целиком.
+
+use super::precedence::TypeOrigin;
 use super::stats::RepositoryStats;
 use super::RawTypeData;
-use crate::domain::types::TypeResolution;
-use anyhow::Result;
+use crate::domain::types::{Certainty, ConcreteType, ResolutionResult, TypeResolution};
+use crate::error::Result;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Сколько изменений ждут медленного подписчика; отставший подписчик
+/// получает `Lagged` и сбрасывает свои кеши целиком
+const CHANGE_CHANNEL_CAPACITY: usize = 256;
+
+/// Удаляемые типы
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeTombstone {
+    /// Все типы, описанные в файле (удалённый объект метаданных)
+    SourceFile(String),
+    /// Один тип
+    Type { origin: TypeOrigin, name: String },
+}
+
+/// Пакет изменений репозитория: сначала применяются надгробия, затем
+/// добавляются и заменяются типы
+#[derive(Debug, Clone, Default)]
+pub struct TypeDelta {
+    pub upserts: Vec<RawTypeData>,
+    pub tombstones: Vec<TypeTombstone>,
+}
+
+impl TypeDelta {
+    pub fn is_empty(&self) -> bool {
+        self.upserts.is_empty() && self.tombstones.is_empty()
+    }
+}
+
+/// Что изменилось в репозитории (рассылается подписчикам)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepositoryChange {
+    /// Файлы описаний добавленных, изменённых и удалённых типов
+    pub source_files: BTreeSet<String>,
+    /// Имена добавленных, изменённых и удалённых типов
+    pub type_names: BTreeSet<String>,
+    /// Репозиторий очищен: затронуто всё
+    pub cleared: bool,
+}
+
+impl RepositoryChange {
+    pub fn is_empty(&self) -> bool {
+        !self.cleared && self.type_names.is_empty() && self.source_files.is_empty()
+    }
+
+    /// Устарело ли закешированное разрешение. Неизвестный или выведенный
+    /// тип мог зависеть от добавленного типа, поэтому тоже устаревает.
+    pub fn affects(&self, resolution: &TypeResolution) -> bool {
+        if self.cleared || resolution.certainty != Certainty::Known {
+            return true;
+        }
+        match resolution.get_name() {
+            Some(name) => self.affects_name(&name),
+            None => true,
+        }
+    }
+
+    /// Затронут ли тип с именем `name` (без учёта регистра)
+    pub fn affects_name(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.cleared
+            || self
+                .type_names
+                .iter()
+                .any(|changed| changed.to_lowercase() == name)
+    }
+
+    /// Упоминает ли текст (префикс автодополнения, подпись) затронутый тип:
+    /// содержит часть его имени (`Справочники`, `Валюты`) или заканчивается
+    /// началом такой части (по префиксу `Вал` может появиться новый тип
+    /// `Валюты`)
+    pub fn mentions(&self, text: &str) -> bool {
+        if self.cleared {
+            return true;
+        }
+        let text = text.to_lowercase();
+        let last_word = text
+            .rsplit(|ch: char| !ch.is_alphanumeric() && ch != '_')
+            .next()
+            .unwrap_or_default();
+        self.type_names.iter().any(|name| {
+            name.to_lowercase().split('.').any(|part| {
+                !part.is_empty()
+                    && (text.contains(part)
+                        || (!last_word.is_empty() && part.starts_with(last_word)))
+            })
+        })
+    }
+
+    /// Добавить изменения другого пакета
+    pub fn merge(&mut self, other: RepositoryChange) {
+        self.source_files.extend(other.source_files);
+        self.type_names.extend(other.type_names);
+        self.cleared |= other.cleared;
+    }
+}
 
 #[async_trait]
 pub trait TypeRepository: Send + Sync {
@@ -18,26 +125,84 @@ pub trait TypeRepository: Send + Sync {
         &self,
         filter: &super::filters::TypeFilter,
     ) -> Result<Vec<RawTypeData>>;
+
+    /// Применить пакет изменений и разослать его подписчикам
+    async fn apply_delta(&self, delta: TypeDelta) -> Result<RepositoryChange>;
+
+    /// Подписаться на изменения репозитория
+    fn subscribe(&self) -> broadcast::Receiver<RepositoryChange>;
+
+    /// Добавить типы или заменить одноимённые типы того же источника
+    async fn upsert_types(&self, types: Vec<RawTypeData>) -> Result<RepositoryChange> {
+        self.apply_delta(TypeDelta {
+            upserts: types,
+            tombstones: Vec::new(),
+        })
+        .await
+    }
+
+    /// Удалить все типы, описанные в файле `file`
+    async fn remove_types_by_source_file(&self, file: &str) -> Result<RepositoryChange> {
+        self.apply_delta(TypeDelta {
+            upserts: Vec::new(),
+            tombstones: vec![TypeTombstone::SourceFile(file.to_string())],
+        })
+        .await
+    }
 }
 
+/// Типы хранятся по паре (источник, имя): одноимённые типы платформы и
+/// конфигурации не вытесняют друг друга
 pub struct InMemoryTypeRepository {
-    resolutions_by_name: Mutex<HashMap<String, TypeResolution>>,
+    resolutions_by_name: Mutex<HashMap<(TypeOrigin, String), TypeResolution>>,
+    /// Файл описания каждого типа (для надгробий по файлу)
+    source_files: Mutex<HashMap<(TypeOrigin, String), String>>,
+    changes: broadcast::Sender<RepositoryChange>,
 }
 
 impl InMemoryTypeRepository {
     pub fn new() -> Self {
         Self {
             resolutions_by_name: Mutex::new(HashMap::new()),
+            source_files: Mutex::new(HashMap::new()),
+            changes: broadcast::channel(CHANGE_CHANNEL_CAPACITY).0,
         }
     }
+
+    /// Разослать изменение; без подписчиков оно просто отбрасывается
+    fn notify(&self, change: &RepositoryChange) {
+        if !change.is_empty() {
+            let _ = self.changes.send(change.clone());
+        }
+    }
+}
+
+/// Ключ хранения разрешения; источник определяется так же, как в
+/// `TypeResolution::to_raw_data`
+fn resolution_key(resolution: &TypeResolution) -> Option<(TypeOrigin, String)> {
+    let origin = match &resolution.result {
+        ResolutionResult::Concrete(ConcreteType::Configuration(_)) => TypeOrigin::Configuration,
+        _ => TypeOrigin::Platform,
+    };
+    resolution.get_name().map(|name| (origin, name))
+}
+
+/// Ключ хранения типа из надгробия: пользовательские типы хранятся как
+/// типы платформы (см. [`resolution_key`])
+fn tombstone_key(origin: TypeOrigin, name: &str) -> (TypeOrigin, String) {
+    let origin = match origin {
+        TypeOrigin::Configuration => TypeOrigin::Configuration,
+        _ => TypeOrigin::Platform,
+    };
+    (origin, name.to_string())
 }
 
 #[async_trait]
 impl TypeRepository for InMemoryTypeRepository {
     fn add_resolution(&self, resolution: TypeResolution) {
-        if let Some(name) = resolution.get_name() {
+        if let Some(key) = resolution_key(&resolution) {
             if let Ok(mut map) = self.resolutions_by_name.lock() {
-                map.insert(name, resolution);
+                map.insert(key, resolution);
             }
         }
     }
@@ -80,19 +245,18 @@ impl TypeRepository for InMemoryTypeRepository {
         if let Ok(mut map) = self.resolutions_by_name.lock() {
             map.clear();
         }
+        if let Ok(mut files) = self.source_files.lock() {
+            files.clear();
+        }
+        self.notify(&RepositoryChange {
+            cleared: true,
+            ..Default::default()
+        });
         Ok(())
     }
 
     async fn save_types(&self, types: Vec<RawTypeData>) -> Result<()> {
-        if let Ok(mut map) = self.resolutions_by_name.lock() {
-            for raw_type in types {
-                // Конвертируем RawTypeData в TypeResolution
-                let resolution = TypeResolution::from_raw_data(&raw_type);
-                if let Some(name) = resolution.get_name() {
-                    map.insert(name, resolution);
-                }
-            }
-        }
+        self.upsert_types(types).await?;
         Ok(())
     }
 
@@ -126,82 +290,151 @@ impl TypeRepository for InMemoryTypeRepository {
 
     async fn load_types_filtered(
         &self,
-        _filter: &super::filters::TypeFilter,
+        filter: &super::filters::TypeFilter,
     ) -> Result<Vec<RawTypeData>> {
-        let filter = _filter;
         if let Ok(map) = self.resolutions_by_name.lock() {
-            let mut out = Vec::new();
-            for res in map.values() {
-                let raw = res.to_raw_data();
-                // Фильтрация по источнику
-                if let Some(src) = &filter.source {
-                    let matches_source = match (&raw.source, src) {
-                        (
-                            super::TypeSource::Platform { .. },
-                            super::TypeSource::Platform { .. },
-                        ) => true,
-                        (
-                            super::TypeSource::Configuration { .. },
-                            super::TypeSource::Configuration { .. },
-                        ) => true,
-                        (
-                            super::TypeSource::UserDefined { .. },
-                            super::TypeSource::UserDefined { .. },
-                        ) => true,
-                        _ => false,
-                    };
-                    if !matches_source {
-                        continue;
-                    }
-                }
-
-                // Фильтр по категории (грубый по вхождению в путь категории)
-                if let Some(cat) = &filter.category {
-                    if !raw.category_path.iter().any(|c| c.contains(cat)) {
-                        continue;
-                    }
-                }
+            Ok(map
+                .values()
+                .map(|res| res.to_raw_data())
+                .filter(|raw| filter.matches(raw))
+                .collect())
+        } else {
+            Ok(Vec::new())
+        }
+    }
 
-                // Фильтр по имени (подстрока, регистр игнорируется)
-                if let Some(name_substr) = &filter.name_contains {
-                    let nn = name_substr.to_lowercase();
-                    if !raw.russian_name.to_lowercase().contains(&nn)
-                        && !raw.english_name.to_lowercase().contains(&nn)
-                    {
-                        continue;
+    async fn apply_delta(&self, delta: TypeDelta) -> Result<RepositoryChange> {
+        let mut change = RepositoryChange::default();
+        {
+            let (Ok(mut map), Ok(mut files)) =
+                (self.resolutions_by_name.lock(), self.source_files.lock())
+            else {
+                return Ok(change);
+            };
+            for tombstone in &delta.tombstones {
+                let keys: Vec<(TypeOrigin, String)> = match tombstone {
+                    TypeTombstone::SourceFile(file) => files
+                        .iter()
+                        .filter(|(_, source)| *source == file)
+                        .map(|(key, _)| key.clone())
+                        .collect(),
+                    TypeTombstone::Type { origin, name } => vec![tombstone_key(*origin, name)],
+                };
+                for key in keys {
+                    if let Some(file) = files.remove(&key) {
+                        change.source_files.insert(file);
                     }
-                }
-
-                // Фильтр по фасете (должна присутствовать среди доступных)
-                if let Some(facet_kind) = filter.facet {
-                    if !raw.available_facets.iter().any(|f| f.kind == facet_kind) {
-                        continue;
+                    if map.remove(&key).is_some() {
+                        change.type_names.insert(key.1);
                     }
                 }
-
-                // Фильтр по наличию методов/свойств
-                if let Some(has_methods) = filter.has_methods {
-                    if has_methods && raw.methods.is_empty() {
-                        continue;
-                    }
-                    if !has_methods && !raw.methods.is_empty() {
-                        continue;
-                    }
+            }
+            for raw_type in delta.upserts {
+                // Конвертируем RawTypeData в TypeResolution
+                let resolution = TypeResolution::from_raw_data(&raw_type);
+                let Some(key) = resolution_key(&resolution) else {
+                    continue;
+                };
+                // Тип мог переехать в другой файл: затронуты оба
+                if let Some(previous) = files.remove(&key) {
+                    change.source_files.insert(previous);
                 }
-                if let Some(has_properties) = filter.has_properties {
-                    if has_properties && raw.properties.is_empty() {
-                        continue;
-                    }
-                    if !has_properties && !raw.properties.is_empty() {
-                        continue;
-                    }
+                let file = raw_type.parse_metadata.file_path;
+                if !file.is_empty() {
+                    change.source_files.insert(file.clone());
+                    files.insert(key.clone(), file);
                 }
-
-                out.push(raw);
+                change.type_names.insert(key.1.clone());
+                map.insert(key, resolution);
             }
-            Ok(out)
-        } else {
-            Ok(Vec::new())
         }
+        self.notify(&change);
+        Ok(change)
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<RepositoryChange> {
+        self.changes.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::TypeSource;
+    use super::*;
+
+    fn configuration_type(name: &str, file: &str) -> RawTypeData {
+        let configuration = TypeSource::Configuration {
+            config_version: "1.0".to_string(),
+        };
+        let mut raw = RawTypeData::test_type(name, configuration);
+        raw.parse_metadata.file_path = file.to_string();
+        raw
+    }
+
+    #[tokio::test]
+    async fn test_apply_delta_with_tombstones() {
+        let repository = InMemoryTypeRepository::new();
+        let mut changes = repository.subscribe();
+        repository
+            .save_types(vec![
+                configuration_type("Справочники.Валюты", "Catalogs/Валюты.xml"),
+                configuration_type("СправочникСсылка.Валюты", "Catalogs/Валюты.xml"),
+                configuration_type("Справочники.Банки", "Catalogs/Банки.xml"),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(changes.recv().await.unwrap().type_names.len(), 3);
+
+        // Удалённый объект уносит все типы своего файла
+        let change = repository
+            .remove_types_by_source_file("Catalogs/Валюты.xml")
+            .await
+            .unwrap();
+        assert_eq!(
+            change.type_names,
+            BTreeSet::from([
+                "Справочники.Валюты".to_string(),
+                "СправочникСсылка.Валюты".to_string()
+            ])
+        );
+        assert_eq!(changes.recv().await.unwrap(), change);
+        assert_eq!(repository.load_all_types().await.unwrap().len(), 1);
+
+        // Надгробие и замена одним пакетом; несуществующий тип не упоминается
+        let change = repository
+            .apply_delta(TypeDelta {
+                upserts: vec![configuration_type(
+                    "Справочники.Банки",
+                    "Catalogs/БанкиРФ.xml",
+                )],
+                tombstones: vec![TypeTombstone::Type {
+                    origin: TypeOrigin::Configuration,
+                    name: "Справочники.Кассы".to_string(),
+                }],
+            })
+            .await
+            .unwrap();
+        assert_eq!(change.type_names.len(), 1);
+        assert_eq!(
+            change.source_files,
+            BTreeSet::from([
+                "Catalogs/Банки.xml".to_string(),
+                "Catalogs/БанкиРФ.xml".to_string()
+            ])
+        );
+        assert!(change.affects_name("справочники.банки"));
+        assert!(change.mentions("Справочники."));
+        assert!(change.mentions("Бан"));
+        assert!(!change.mentions("Документы.Зак"));
+        changes.recv().await.unwrap();
+
+        // Пустой пакет подписчикам не рассылается
+        assert!(repository
+            .apply_delta(TypeDelta::default())
+            .await
+            .unwrap()
+            .is_empty());
+        repository.clear().await.unwrap();
+        assert!(changes.recv().await.unwrap().cleared);
     }
 }
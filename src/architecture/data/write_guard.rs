@@ -0,0 +1,92 @@
+//! Режим только для чтения: гарантия, что инструмент не пишет на диск
+//!
+//! На закрытых сборочных агентах рабочий каталог и кеши часто смонтированы
+//! только для чтения, а любая запись считается нарушением политики. В этом
+//! режиме каждая операция, которая сохранила бы данные на диск (хранилище
+//! типов, кеш анализа, baseline Dynamic, отчёты), спрашивает [`WriteGuard`]
+//! и вместо записи оставляет результат только в памяти. Пропущенные записи
+//! запоминаются, чтобы `doctor` показал, что было бы записано.
+
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Запись на диск, пропущенная в режиме только для чтения
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedWrite {
+    /// Что записывалось (`хранилище типов`, `кеш анализа`, ...)
+    pub operation: String,
+    pub path: String,
+}
+
+/// Разрешение на запись на диск; клоны разделяют список пропущенных записей
+#[derive(Debug, Clone, Default)]
+pub struct WriteGuard {
+    read_only: bool,
+    skipped: Arc<Mutex<Vec<SkippedWrite>>>,
+}
+
+impl WriteGuard {
+    pub fn new(read_only: bool) -> Self {
+        Self {
+            read_only,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Можно ли записать `path`; в режиме только для чтения запись
+    /// запоминается как пропущенная (повторы одной записи не дублируются)
+    pub fn allows(&self, operation: &str, path: &Path) -> bool {
+        if !self.read_only {
+            return true;
+        }
+        let skipped = SkippedWrite {
+            operation: operation.to_string(),
+            path: path.display().to_string(),
+        };
+        if let Ok(mut list) = self.skipped.lock() {
+            if !list.contains(&skipped) {
+                info!(
+                    "Режим только для чтения: {} не записан ({})",
+                    skipped.path,
+                    skipped.operation
+                );
+                list.push(skipped);
+            }
+        }
+        false
+    }
+
+    /// Пропущенные записи в порядке первого обращения
+    pub fn skipped_writes(&self) -> Vec<SkippedWrite> {
+        self.skipped.lock().map(|list| list.clone()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_records_skipped_writes() {
+        let writable = WriteGuard::default();
+        assert!(writable.allows("кеш анализа", Path::new("/tmp/cache")));
+        assert!(writable.skipped_writes().is_empty());
+
+        let guard = WriteGuard::new(true);
+        let shared = guard.clone();
+        assert!(!shared.allows("хранилище типов", Path::new("/store/platform.types")));
+        assert!(!guard.allows("хранилище типов", Path::new("/store/platform.types")));
+        assert!(!guard.allows("кеш анализа", Path::new(".bsl_cache")));
+
+        let skipped = guard.skipped_writes();
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0].operation, "хранилище типов");
+        assert_eq!(skipped[1].path, ".bsl_cache");
+    }
+}
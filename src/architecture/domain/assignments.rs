@@ -0,0 +1,896 @@
+//! Значения локальных переменных по присваиваниям в тексте метода
+//!
+//! Переменная, которой в ветках `Если` присвоены значения разных типов,
+//! после `КонецЕсли` может иметь любой из них:
+//!
+//! ```bsl
+//! Если Флаг Тогда
+//!     Значение = "строка";
+//! Иначе
+//!     Значение = 0;
+//! КонецЕсли;
+//! // Значение: Строка|Число
+//! ```
+//!
+//! Здесь по тексту метода собираются присваивания, достигающие заданной
+//! позиции: последнее присваивание в каждой ветке, а для `Если` без
+//! `Иначе` и для тела цикла — ещё и значение до блока. Тип определяется
+//! для литералов, конструкторов `Новый` и копирования другой переменной;
+//! вызов функции запоминается как [`AssignedValue::Call`] (с `Ждать` —
+//! [`AssignedValue::Awaited`]) и разрешается по таблице сигнатур проекта,
+//! значение константы или параметра сеанса — по их объявленному типу
+//! ([`AssignedValue::MetadataValue`]), остальные выражения дают
+//! [`AssignedValue::Unknown`]. Наборы и менеджеры записей регистров
+//! (`РегистрыСведений.Имя.СоздатьНаборЗаписей()`) получают типы записей
+//! регистра; запись набора — `Набор.Добавить()`, `Набор[Индекс]` и
+//! переменная цикла `Для Каждого Запись Из Набор`; `Документы.Имя.СоздатьДокумент()`
+//! — объект документа.
+//!
+//! Запрос с текстом из литерала (`Запрос.Текст = "ВЫБРАТЬ ..."`) запоминается
+//! вместе с текстом ([`AssignedValue::Query`]), `Запрос.Выполнить()` и
+//! `.Выбрать()` переводят его в результат и выборку, колонки которой
+//! выводятся из текста запроса.
+//!
+//! Аннотация `// @type Тип` в конце строки задаёт тип присваиваний этой
+//! строки явно, вместо выведенного по выражению.
+//!
+//! Тот же разбор собирает значения `Возврат` каждой функции модуля
+//! ([`function_returns`]) для вывода возвращаемых типов.
+
+use bumpalo::collections::Vec as ArenaVec;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::documents::created_document_object;
+use super::predefined::PredefinedRef;
+use super::queries::QueryStage;
+use super::registers::{created_record_type, record_set_element};
+use crate::core::example_mining::strip_comment;
+use crate::core::request_arena::{with_request_arena, RequestArena};
+use crate::core::type_quick_fixes::type_annotation;
+use crate::data::loaders::metadata_values::{referenced_value, MetadataValueRef};
+use crate::parsing::query::embedded::{embedded_queries, EmbeddedQuery};
+
+/// Значение, присвоенное переменной
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssignedValue {
+    /// Тип известен по имени (`Строка`, `Неопределено`, `Массив`, ...)
+    Type(String),
+    /// Результат вызова функции (`Функция()` или `ОбщийМодуль.Функция()`)
+    Call {
+        module: Option<String>,
+        function: String,
+    },
+    /// Результат `Ждать Функция()`: значение, которым завершится обещание
+    /// асинхронной функции
+    Awaited {
+        module: Option<String>,
+        function: String,
+    },
+    /// Значение константы (`Константы.Имя.Получить()`) или параметра сеанса
+    /// (`ПараметрыСеанса.Имя`)
+    MetadataValue(MetadataValueRef),
+    /// Запрос с известным текстом, его результат или выборка
+    Query { text: String, stage: QueryStage },
+    /// Выражение, тип которого по тексту не определить
+    Unknown,
+}
+
+/// Возможные значения переменной в позиции
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableAssignments {
+    pub variable: String,
+    /// Значения без повторов в порядке появления в тексте
+    pub values: Vec<AssignedValue>,
+}
+
+impl VariableAssignments {
+    /// Имена типов, если все значения известны
+    pub fn type_names(&self) -> Option<Vec<&str>> {
+        self.values
+            .iter()
+            .map(|value| match value {
+                AssignedValue::Type(name) => Some(name.as_str()),
+                AssignedValue::Call { .. }
+                | AssignedValue::Awaited { .. }
+                | AssignedValue::MetadataValue(_)
+                | AssignedValue::Query { .. }
+                | AssignedValue::Unknown => None,
+            })
+            .collect()
+    }
+}
+
+/// Значения `Возврат` функции модуля
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionReturns {
+    pub name: String,
+    /// Строка заголовка функции (с 0)
+    pub line: u32,
+    /// Функция объявлена с `Асинх`
+    pub is_async: bool,
+    /// Возвращаемые значения без повторов; пусто, если функция не
+    /// возвращает значение явно
+    pub values: Vec<AssignedValue>,
+}
+
+fn assignment_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^([\p{L}_][\p{L}\p{N}_]*)\s*=(.*)$").unwrap())
+}
+
+fn constructor_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^(?:новый|new)\s+([\p{L}_][\p{L}\p{N}_]*)\s*(?:\(.*\))?$").unwrap()
+    })
+}
+
+fn number_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[+-]?\d+(?:\.\d+)?$").unwrap())
+}
+
+fn identifier_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[\p{L}_][\p{L}\p{N}_]*$").unwrap())
+}
+
+fn record_set_item_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^([\p{L}_][\p{L}\p{N}_]*)\s*(?:\.\s*(?:добавить|add)\s*\(\s*\)|\[.+\])$")
+            .unwrap()
+    })
+}
+
+fn query_text_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^([\p{L}_][\p{L}\p{N}_]*)\s*\.\s*(?:текст|text)\s*=").unwrap()
+    })
+}
+
+fn method_chain_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^([\p{L}_][\p{L}\p{N}_]*)((?:\s*\.\s*[\p{L}_][\p{L}\p{N}_]*\s*\(\s*\))+)$")
+            .unwrap()
+    })
+}
+
+/// Вызываемая функция, если всё выражение — один вызов:
+/// `Имя(...)` или `Модуль.Имя(...)`
+pub fn call_target(expression: &str) -> Option<AssignedValue> {
+    let open = expression.find('(')?;
+    let callee = expression[..open].trim_end();
+    let (module, function) = match callee.split_once('.') {
+        Some((module, function)) => (Some(module), function),
+        None => (None, callee),
+    };
+    let valid = |name: &str| identifier_regex().is_match(name);
+    if !valid(function) || !module.into_iter().all(valid) {
+        return None;
+    }
+
+    // Скобка после имени должна закрываться в конце выражения
+    let mut depth = 0usize;
+    let mut in_string = false;
+    for (idx, ch) in expression[open..].char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return (open + idx + 1 == expression.len()).then(|| AssignedValue::Call {
+                        module: module.map(str::to_string),
+                        function: function.to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Операнд `Ждать`, если выражение начинается с этого оператора
+pub fn await_operand(expression: &str) -> Option<&str> {
+    let expression = expression.trim_start();
+    let (word, rest) = first_word(expression);
+    let is_await = matches!(word.to_lowercase().as_str(), "ждать" | "await");
+    // `Ждать = ...` или `Ждать(...)` — имя, а не оператор
+    (is_await && rest.starts_with(char::is_whitespace))
+        .then(|| rest.trim())
+        .filter(|operand| !operand.is_empty() && !operand.starts_with('='))
+}
+
+/// Записи наборов записей регистров, если все значения — наборы
+fn record_set_elements(values: &[AssignedValue]) -> Option<Vec<AssignedValue>> {
+    values
+        .iter()
+        .map(|value| match value {
+            AssignedValue::Type(name) => record_set_element(name).map(AssignedValue::Type),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .filter(|records| !records.is_empty())
+}
+
+/// Значения переменных: ключ — имя в нижнем регистре
+type State = HashMap<String, VariableAssignments>;
+
+/// Открытый блок, значения после которого собираются из веток
+struct Frame {
+    /// Значения до входа в блок
+    before: State,
+    /// Значения в конце уже завершённых веток
+    branches: Vec<State>,
+    /// Одна из веток выполняется обязательно (`Иначе`, `Исключение`)
+    exhaustive: bool,
+}
+
+/// Ветки сливаются: переменная получает значения из всех путей, а путь,
+/// на котором её не присваивали, даёт неизвестное значение
+fn merge(branches: Vec<State>) -> State {
+    let count = branches.len();
+    let mut merged = State::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for branch in branches {
+        for (key, assignments) in branch {
+            *seen.entry(key.clone()).or_default() += 1;
+            let entry = merged.entry(key).or_insert_with(|| VariableAssignments {
+                variable: assignments.variable.clone(),
+                values: Vec::new(),
+            });
+            for value in assignments.values {
+                if !entry.values.contains(&value) {
+                    entry.values.push(value);
+                }
+            }
+        }
+    }
+    for (key, entry) in merged.iter_mut() {
+        if seen.get(key).copied().unwrap_or(0) < count
+            && !entry.values.contains(&AssignedValue::Unknown)
+        {
+            entry.values.push(AssignedValue::Unknown);
+        }
+    }
+    merged
+}
+
+/// Разбить строку на инструкции по `;` вне строковых литералов
+fn statements<'a>(arena: &'a RequestArena, code: &'a str) -> ArenaVec<'a, &'a str> {
+    let mut out = arena.vec();
+    let mut in_string = false;
+    let mut from = 0;
+    for (idx, ch) in code.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            ';' if !in_string => {
+                out.push(&code[from..idx]);
+                from = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    out.push(&code[from..]);
+    out
+}
+
+/// Первое слово инструкции и остаток после него
+fn first_word(statement: &str) -> (&str, &str) {
+    let end = statement
+        .char_indices()
+        .find(|(_, ch)| !(ch.is_alphanumeric() || *ch == '_'))
+        .map_or(statement.len(), |(idx, _)| idx);
+    (&statement[..end], &statement[end..])
+}
+
+/// Остаток после слова `keywords` вне строк (например, после `Тогда`)
+fn after_keyword<'a>(arena: &RequestArena, text: &'a str, keywords: &[&str]) -> Option<&'a str> {
+    let mut in_string = false;
+    let mut start: Option<usize> = None;
+    for (idx, ch) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        let is_word_char = !in_string && (ch.is_alphanumeric() || ch == '_');
+        match (start, is_word_char) {
+            (None, true) => start = Some(idx),
+            (Some(s), false) => {
+                if keywords.contains(&arena.lowercase(&text[s..idx])) {
+                    return Some(&text[idx..]);
+                }
+                start = None;
+            }
+            _ => {}
+        }
+        if ch == '"' {
+            in_string = !in_string;
+        }
+    }
+    None
+}
+
+const THEN: &[&str] = &["тогда", "then"];
+const DO: &[&str] = &["цикл", "do"];
+
+/// Разбор инструкций метода с отслеживанием веток
+#[derive(Default)]
+struct Walker {
+    state: State,
+    stack: Vec<Frame>,
+    /// Условие `Если`/`ИначеЕсли` продолжается до `Тогда`
+    awaiting: Option<&'static [&'static str]>,
+    /// Текущая строка текста (с 0)
+    line: u32,
+    /// Разбираемая функция и уже завершённые функции модуля
+    function: Option<FunctionReturns>,
+    functions: Vec<FunctionReturns>,
+    /// Тип из аннотации `// @type` текущей строки
+    annotation: Option<String>,
+    /// Тексты запросов, присвоенные в тексте
+    queries: Vec<EmbeddedQuery>,
+}
+
+impl Walker {
+    fn open(&mut self, exhaustive: bool) {
+        self.stack.push(Frame {
+            before: self.state.clone(),
+            branches: Vec::new(),
+            exhaustive,
+        });
+    }
+
+    /// Начать следующую ветку открытого блока
+    fn next_branch(&mut self, exhaustive: bool) {
+        if let Some(frame) = self.stack.last_mut() {
+            let finished = std::mem::replace(&mut self.state, frame.before.clone());
+            frame.branches.push(finished);
+            frame.exhaustive |= exhaustive;
+        }
+    }
+
+    fn close(&mut self) {
+        let Some(mut frame) = self.stack.pop() else {
+            return;
+        };
+        frame.branches.push(std::mem::take(&mut self.state));
+        if !frame.exhaustive {
+            frame.branches.insert(0, frame.before);
+        }
+        self.state = merge(frame.branches);
+    }
+
+    fn assign(&mut self, variable: &str, values: Vec<AssignedValue>) {
+        self.state.insert(
+            variable.to_lowercase(),
+            VariableAssignments {
+                variable: variable.to_string(),
+                values,
+            },
+        );
+    }
+
+    /// Текст запроса, присвоенный переменной в текущей строке
+    fn embedded_query(&self, variable: &str) -> Option<&EmbeddedQuery> {
+        self.queries.iter().find(|query| {
+            query.line == self.line && query.variable.to_lowercase() == variable.to_lowercase()
+        })
+    }
+
+    /// Результат `Запрос.Выполнить()`, выборка `Результат.Выбрать()` или
+    /// `Запрос.Выполнить().Выбрать()`, если переменная — запрос с текстом
+    fn query_call(&self, expression: &str) -> Option<Vec<AssignedValue>> {
+        let caps = method_chain_regex().captures(expression)?;
+        let values = &self.state.get(&caps[1].to_lowercase())?.values;
+        let methods: Vec<&str> = caps[2]
+            .split(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+            .filter(|name| !name.is_empty())
+            .collect();
+        values
+            .iter()
+            .map(|value| match value {
+                AssignedValue::Query { text, stage } => {
+                    let stage = methods
+                        .iter()
+                        .try_fold(*stage, |stage, method| stage.after_call(method))?;
+                    Some(AssignedValue::Query {
+                        text: text.clone(),
+                        stage,
+                    })
+                }
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|queries| !queries.is_empty())
+    }
+
+    /// Тип выражения справа от `=`
+    fn value_of(&self, expression: &str) -> Vec<AssignedValue> {
+        let expression = expression.trim();
+        let named = |name: &str| vec![AssignedValue::Type(name.to_string())];
+        if expression.starts_with('"') {
+            return named("Строка");
+        }
+        if expression.starts_with('\'') {
+            return named("Дата");
+        }
+        if number_regex().is_match(expression) {
+            return named("Число");
+        }
+        match expression.to_lowercase().as_str() {
+            "истина" | "ложь" | "true" | "false" => return named("Булево"),
+            "неопределено" | "undefined" => return named("Неопределено"),
+            "null" => return named("Null"),
+            _ => {}
+        }
+        if let Some(caps) = constructor_regex().captures(expression) {
+            return named(&caps[1]);
+        }
+        if identifier_regex().is_match(expression) {
+            if let Some(copied) = self.state.get(&expression.to_lowercase()) {
+                return copied.values.clone();
+            }
+        }
+        if let Some(reference) = referenced_value(expression) {
+            return vec![AssignedValue::MetadataValue(reference)];
+        }
+        if let Some(record) = created_record_type(expression) {
+            return named(&record.type_name());
+        }
+        if let Some(document) = created_document_object(expression) {
+            return named(&document.type_name());
+        }
+        let predefined =
+            PredefinedRef::from_value_call(expression).or_else(|| PredefinedRef::parse(expression));
+        if let Some(reference) = predefined {
+            return named(&reference.reference_type());
+        }
+        if let Some(caps) = record_set_item_regex().captures(expression) {
+            if let Some(state) = self.state.get(&caps[1].to_lowercase()) {
+                if let Some(records) = record_set_elements(&state.values) {
+                    return records;
+                }
+            }
+        }
+        if let Some(queries) = self.query_call(expression) {
+            return queries;
+        }
+        if let Some(call) = call_target(expression) {
+            return vec![call];
+        }
+        if let Some(AssignedValue::Call { module, function }) =
+            await_operand(expression).and_then(call_target)
+        {
+            return vec![AssignedValue::Awaited { module, function }];
+        }
+        vec![AssignedValue::Unknown]
+    }
+
+    /// Заголовок или конец метода: локальные переменные начинаются заново
+    fn begin_method(&mut self, arena: &RequestArena, keyword: &str, rest: &str) {
+        let mut functions = std::mem::take(&mut self.functions);
+        functions.extend(self.function.take());
+        *self = Walker {
+            line: self.line,
+            annotation: self.annotation.take(),
+            functions,
+            queries: std::mem::take(&mut self.queries),
+            ..Walker::default()
+        };
+        match keyword {
+            "асинх" | "async" => {
+                self.statement(arena, rest);
+                // Заголовок функции только что открыл новую функцию
+                if let Some(function) = &mut self.function {
+                    function.is_async = true;
+                }
+            }
+            "функция" | "function" => {
+                let (name, _) = first_word(rest.trim_start());
+                if !name.is_empty() {
+                    self.function = Some(FunctionReturns {
+                        name: name.to_string(),
+                        line: self.line,
+                        is_async: false,
+                        values: Vec::new(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn returns(&mut self, expression: &str) {
+        if expression.trim().is_empty() {
+            return;
+        }
+        let values = self.value_of(expression);
+        if let Some(function) = &mut self.function {
+            for value in values {
+                if !function.values.contains(&value) {
+                    function.values.push(value);
+                }
+            }
+        }
+    }
+
+    fn loop_header(&mut self, arena: &RequestArena, counter: bool, rest: &str) {
+        let header = rest.trim_start();
+        let (word, after) = first_word(header);
+        if matches!(arena.lowercase(word), "каждого" | "each") {
+            // Элемент коллекции: известен только для наборов записей
+            let (variable, source) = first_word(after.trim_start());
+            let (_, source) = first_word(source.trim_start());
+            let collection = match after_keyword(arena, source, DO) {
+                Some(tail) => source[..source.len() - tail.len()]
+                    .trim_end_matches(|ch: char| ch.is_alphanumeric() || ch == '_'),
+                None => source,
+            };
+            let element = record_set_elements(&self.value_of(collection))
+                .unwrap_or_else(|| vec![AssignedValue::Unknown]);
+            if !variable.is_empty() {
+                self.assign(variable, element);
+            }
+        } else if let Some(caps) = assignment_regex().captures(header).filter(|_| counter) {
+            self.assign(&caps[1], vec![AssignedValue::Type("Число".to_string())]);
+        }
+        self.open(false);
+        self.continue_after(arena, rest, DO);
+    }
+
+    /// Продолжить разбор после `Тогда`/`Цикл` либо ждать их на следующих строках
+    fn continue_after(
+        &mut self,
+        arena: &RequestArena,
+        rest: &str,
+        keywords: &'static [&'static str],
+    ) {
+        match after_keyword(arena, rest, keywords) {
+            Some(tail) => self.statement(arena, tail),
+            None => self.awaiting = Some(keywords),
+        }
+    }
+
+    fn statement(&mut self, arena: &RequestArena, statement: &str) {
+        let statement = statement.trim();
+        if statement.is_empty() {
+            return;
+        }
+        if let Some(keywords) = self.awaiting {
+            if let Some(tail) = after_keyword(arena, statement, keywords) {
+                self.awaiting = None;
+                self.statement(arena, tail);
+            }
+            return;
+        }
+        // Инструкции препроцессора и директивы компиляции не ветвят код
+        if statement.starts_with('#') || statement.starts_with('&') {
+            return;
+        }
+
+        let (word, rest) = first_word(statement);
+        match arena.lowercase(word) {
+            "если" | "if" => {
+                self.open(false);
+                self.continue_after(arena, rest, THEN);
+            }
+            "иначеесли" | "elsif" => {
+                self.next_branch(false);
+                self.continue_after(arena, rest, THEN);
+            }
+            "иначе" | "else" => {
+                self.next_branch(true);
+                self.statement(arena, rest);
+            }
+            "конецесли" | "endif" | "конеццикла" | "enddo" | "конецпопытки" | "endtry" => {
+                self.close();
+                self.statement(arena, rest);
+            }
+            "для" | "for" => self.loop_header(arena, true, rest),
+            "пока" | "while" => self.loop_header(arena, false, rest),
+            "попытка" | "try" => {
+                self.open(true);
+                self.statement(arena, rest);
+            }
+            "исключение" | "except" => {
+                self.next_branch(true);
+                self.statement(arena, rest);
+            }
+            // Новый метод — новые локальные переменные
+            keyword @ ("процедура" | "функция" | "procedure" | "function" | "асинх"
+            | "async" | "конецпроцедуры" | "конецфункции" | "endprocedure"
+            | "endfunction") => self.begin_method(arena, keyword, rest),
+            "возврат" | "return" => self.returns(rest),
+            _ => {
+                // `Запрос.Текст = "..."` и `Запрос = Новый Запрос("...")`
+                let query_variable = query_text_regex()
+                    .captures(statement)
+                    .or_else(|| assignment_regex().captures(statement))
+                    .map(|caps| caps[1].to_string());
+                if let Some(query) = query_variable.and_then(|v| self.embedded_query(&v)) {
+                    if self.annotation.is_none() {
+                        let value = AssignedValue::Query {
+                            text: query.text.clone(),
+                            stage: QueryStage::Query,
+                        };
+                        let variable = query.variable.clone();
+                        self.assign(&variable, vec![value]);
+                        return;
+                    }
+                }
+                if let Some(caps) = assignment_regex().captures(statement) {
+                    let values = match &self.annotation {
+                        Some(name) => vec![AssignedValue::Type(name.clone())],
+                        None => self.value_of(&caps[2]),
+                    };
+                    self.assign(&caps[1], values);
+                }
+            }
+        }
+    }
+}
+
+/// Разобрать текст до позиции `(line, column)` или целиком; инструкции и
+/// слова строк живут в арене прохода
+fn walk(text: &str, until: Option<(u32, u32)>) -> Walker {
+    with_request_arena(|arena| walk_in(arena, text, until))
+}
+
+fn walk_in(arena: &RequestArena, text: &str, until: Option<(u32, u32)>) -> Walker {
+    let mut walker = Walker {
+        queries: embedded_queries(text),
+        ..Walker::default()
+    };
+    for (idx, source) in text.lines().enumerate() {
+        let idx = idx as u32;
+        let code = strip_comment(source);
+        let code = match until {
+            Some((line, _)) if idx > line => break,
+            Some((line, column)) if idx == line => {
+                let limit = code
+                    .char_indices()
+                    .nth(column as usize)
+                    .map_or(code.len(), |(byte, _)| byte);
+                &code[..limit]
+            }
+            _ => code,
+        };
+        walker.line = idx;
+        walker.annotation = type_annotation(source).map(str::to_string);
+        for statement in statements(arena, code) {
+            walker.statement(arena, statement);
+        }
+    }
+    walker
+}
+
+/// Присваивания локальных переменных метода, достигающие позиции
+/// (`line` и `column` с 0, колонка в символах), по имени переменной
+pub fn reaching_assignments(text: &str, line: u32, column: u32) -> Vec<VariableAssignments> {
+    let walker = walk(text, Some((line, column)));
+
+    // Блоки, открытые в позиции, ещё не завершены: действуют значения
+    // текущей ветки
+    let mut out: Vec<VariableAssignments> = walker.state.into_values().collect();
+    out.sort_by(|a, b| a.variable.cmp(&b.variable));
+    out
+}
+
+/// Значения `Возврат` всех функций модуля в порядке объявления.
+/// Переменные в `Возврат Результат` раскрываются по присваиваниям,
+/// достигающим инструкции.
+pub fn function_returns(text: &str) -> Vec<FunctionReturns> {
+    let mut walker = walk(text, None);
+    walker.functions.extend(walker.function.take());
+    walker.functions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types_of(text: &str, line: u32, variable: &str) -> Option<Vec<String>> {
+        reaching_assignments(text, line, 0)
+            .into_iter()
+            .find(|a| a.variable == variable)
+            .and_then(|a| a.type_names().map(|n| n.into_iter().map(String::from).collect()))
+    }
+
+    #[test]
+    fn test_if_else_branches_merge() {
+        let text = r#"Процедура Тест(Флаг)
+    Если Флаг Тогда
+        Значение = "строка";
+    ИначеЕсли НЕ Флаг
+        И Истина Тогда
+        Значение = Новый Массив();
+    Иначе
+        Значение = 0;
+    КонецЕсли;
+    Копия = Значение;
+КонецПроцедуры"#;
+        assert_eq!(types_of(text, 3, "Значение"), Some(vec!["Строка".to_string()]));
+        assert_eq!(types_of(text, 7, "Значение"), None);
+        assert_eq!(
+            types_of(text, 10, "Копия"),
+            Some(vec!["Строка".into(), "Массив".into(), "Число".into()])
+        );
+    }
+
+    #[test]
+    fn test_missing_branch_and_loops_are_unknown() {
+        let text = r#"А = Истина; Б = Неопределено;
+Если Условие Тогда А = 1; КонецЕсли;
+Для Каждого Элемент Из Коллекция Цикл
+    Б = "текст";
+КонецЦикла;
+Для Индекс = 1 По 10 Цикл КонецЦикла;
+"#;
+        assert_eq!(
+            types_of(text, 6, "А"),
+            Some(vec!["Булево".into(), "Число".into()])
+        );
+        assert_eq!(
+            types_of(text, 6, "Б"),
+            Some(vec!["Неопределено".into(), "Строка".into()])
+        );
+        assert_eq!(types_of(text, 6, "Индекс"), Some(vec!["Число".into()]));
+        assert_eq!(types_of(text, 6, "Элемент"), None);
+
+        // Переменная, не присвоенная до Если без Иначе, может быть любой
+        let text = "Если Условие Тогда\n    В = 1;\nКонецЕсли;\n";
+        assert_eq!(types_of(text, 3, "В"), None);
+    }
+
+    #[test]
+    fn test_calls_and_function_returns() {
+        let text = r#"Функция Получить(Ключ) Экспорт
+    Если Ключ = "" Тогда Возврат Неопределено; КонецЕсли;
+    Результат = Общий.Прочитать(Ключ, "(");
+    Возврат Результат;
+КонецФункции
+
+Процедура Выполнить()
+    Сумма = Получить(1) + Получить(2);
+    Данные = Получить("а");
+    Возврат;
+КонецПроцедуры"#;
+        let call = |module: Option<&str>, function: &str| AssignedValue::Call {
+            module: module.map(String::from),
+            function: function.to_string(),
+        };
+        let values_of = |variable: &str| {
+            reaching_assignments(text, 10, 0)
+                .into_iter()
+                .find(|a| a.variable == variable)
+                .map(|a| a.values)
+        };
+        assert_eq!(values_of("Данные"), Some(vec![call(None, "Получить")]));
+        assert_eq!(values_of("Сумма"), Some(vec![AssignedValue::Unknown]));
+
+        assert_eq!(
+            function_returns(text),
+            vec![FunctionReturns {
+                name: "Получить".to_string(),
+                line: 0,
+                is_async: false,
+                values: vec![
+                    AssignedValue::Type("Неопределено".to_string()),
+                    call(Some("Общий"), "Прочитать"),
+                ],
+            }]
+        );
+    }
+    #[test]
+    fn test_await_in_async_function() {
+        let text = r#"Асинх Функция Загрузить()
+    Ответ = Ждать Сеть.Запрос("http://");
+    Ждать = Истина;
+    Возврат Ответ;
+КонецФункции"#;
+        let returns = function_returns(text);
+        assert_eq!(returns.len(), 1);
+        assert!(returns[0].is_async);
+        assert_eq!(
+            returns[0].values,
+            vec![AssignedValue::Awaited {
+                module: Some("Сеть".to_string()),
+                function: "Запрос".to_string(),
+            }]
+        );
+        assert_eq!(types_of(text, 4, "Ждать"), Some(vec!["Булево".to_string()]));
+        assert_eq!(await_operand("Ждать(1)"), None);
+        assert_eq!(await_operand("Ждать = 1"), None);
+        assert_eq!(await_operand("ждать  Обещание"), Some("Обещание"));
+    }
+
+    #[test]
+    fn test_predefined_values() {
+        let text = r#"Валюта = Справочники.Валюты.Рубль;
+Счет = ПредопределенноеЗначение("ПланСчетов.Хозрасчетный.Касса");
+Статус = ПредопределенноеЗначение("Перечисление.СтатусыЗаказов.Оплачен");
+"#;
+        assert_eq!(
+            types_of(text, 3, "Валюта"),
+            Some(vec!["СправочникСсылка.Валюты".into()])
+        );
+        assert_eq!(
+            types_of(text, 3, "Счет"),
+            Some(vec!["ПланСчетовСсылка.Хозрасчетный".into()])
+        );
+        assert_eq!(
+            types_of(text, 3, "Статус"),
+            Some(vec!["ПеречислениеСсылка.СтатусыЗаказов".into()])
+        );
+    }
+
+    #[test]
+    fn test_register_record_set_elements() {
+        let text = r#"Набор = РегистрыСведений.КурсыВалют.СоздатьНаборЗаписей();
+Новая = Набор.Добавить();
+Первая = Набор[0];
+Для Каждого Запись Из Набор Цикл
+КонецЦикла;
+"#;
+        let record = Some(vec!["РегистрСведенийЗапись.КурсыВалют".to_string()]);
+        assert_eq!(
+            types_of(text, 1, "Набор"),
+            Some(vec!["РегистрСведенийНаборЗаписей.КурсыВалют".into()])
+        );
+        assert_eq!(types_of(text, 3, "Новая"), record);
+        assert_eq!(types_of(text, 3, "Первая"), record);
+        assert_eq!(types_of(text, 5, "Запись"), record);
+    }
+
+    #[test]
+    fn test_query_result_and_selection() {
+        let text = r#"Запрос = Новый Запрос;
+Запрос.Текст =
+    "ВЫБРАТЬ
+    |    Товары.Ссылка
+    |ИЗ
+    |    Справочник.Номенклатура КАК Товары";
+Результат = Запрос.Выполнить();
+Выборка = Результат.Выбрать();
+Второй = Новый Запрос("ВЫБРАТЬ 1 КАК Один ИЗ Документ.Заказ");
+Сразу = Второй.Выполнить().Выбрать();
+Повтор = Результат.Выполнить();
+"#;
+        let values_of = |variable: &str| {
+            reaching_assignments(text, 11, 0)
+                .into_iter()
+                .find(|a| a.variable == variable)
+                .map(|a| a.values)
+        };
+        let query = |text: &str, stage: QueryStage| {
+            Some(vec![AssignedValue::Query {
+                text: text.to_string(),
+                stage,
+            }])
+        };
+        let products = "ВЫБРАТЬ\n    Товары.Ссылка\nИЗ\n    Справочник.Номенклатура КАК Товары";
+        assert_eq!(values_of("Запрос"), query(products, QueryStage::Query));
+        assert_eq!(values_of("Результат"), query(products, QueryStage::Result));
+        assert_eq!(values_of("Выборка"), query(products, QueryStage::Selection));
+        assert_eq!(
+            values_of("Сразу"),
+            query("ВЫБРАТЬ 1 КАК Один ИЗ Документ.Заказ", QueryStage::Selection)
+        );
+        assert_eq!(
+            values_of("Повтор"),
+            Some(vec![AssignedValue::Call {
+                module: Some("Результат".to_string()),
+                function: "Выполнить".to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_type_annotation_overrides_inferred_value() {
+        let text = "Данные = Прочитать(); // @type Массив\nКопия = Данные;\n";
+        assert_eq!(types_of(text, 2, "Копия"), Some(vec!["Массив".to_string()]));
+    }
+}
@@ -0,0 +1,263 @@
+//! Объект документа и его движения
+//!
+//! Проведение — самая нагруженная ошибками часть конфигураций, поэтому
+//! члены объекта документа описываются точно: `Провести()`, `Записать()`,
+//! `ОбменДанными` (`ПараметрыОбменаДанными`), `Проведен` и коллекция
+//! `Движения`, в которой каждый регистр из состава движений документа —
+//! набор записей этого регистра (`Движения.ТоварыНаСкладах` —
+//! `РегистрНакопленияНаборЗаписей.ТоварыНаСкладах`).
+//!
+//! Объект получают `Документы.Имя.СоздатьДокумент()`, а в модуле объекта
+//! документа — `ЭтотОбъект` и неявные обращения `Движения`, `ОбменДанными`.
+//! Состав движений читается из описания документа (`RegisterRecords`) и
+//! хранится в репозитории отдельным типом `КоллекцияДвижений.Имя`.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use super::registers::{RecordObject, RecordType, RegisterKind};
+use crate::domain::types::{ConcreteType, Method, PlatformType, Property, TypeResolution};
+use crate::unified::data::{ParseMetadata, RawMethodData, RawPropertyData, RawTypeData, TypeSource};
+
+const OBJECT_PREFIX: &str = "ДокументОбъект";
+const MOVEMENTS_PREFIX: &str = "КоллекцияДвижений";
+
+/// Тип, связанный с документом конфигурации
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentType {
+    /// `ДокументОбъект.Имя`
+    Object(String),
+    /// `КоллекцияДвижений.Имя`
+    Movements(String),
+}
+
+impl DocumentType {
+    pub fn type_name(&self) -> String {
+        match self {
+            DocumentType::Object(document) => format!("{}.{}", OBJECT_PREFIX, document),
+            DocumentType::Movements(document) => format!("{}.{}", MOVEMENTS_PREFIX, document),
+        }
+    }
+
+    /// Разобрать имя типа объекта документа или коллекции движений
+    pub fn parse(type_name: &str) -> Option<Self> {
+        let (prefix, document) = type_name.trim().split_once('.')?;
+        if document.is_empty() || document.contains('.') {
+            return None;
+        }
+        match prefix {
+            OBJECT_PREFIX => Some(DocumentType::Object(document.to_string())),
+            MOVEMENTS_PREFIX => Some(DocumentType::Movements(document.to_string())),
+            _ => None,
+        }
+    }
+}
+
+fn creation_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)^(?:Документы|Documents)\.([\p{L}_][\p{L}\p{N}_]*)\.",
+            r"(?:СоздатьДокумент|CreateDocument)\(\)$",
+        ))
+        .unwrap()
+    })
+}
+
+fn object_module_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?:^|[/\\])Documents[/\\]([^/\\]+)[/\\](?:Ext[/\\])?ObjectModule\.bsl$")
+            .unwrap()
+    })
+}
+
+/// Объект документа, создаваемый `Документы.Имя.СоздатьДокумент()`
+pub fn created_document_object(expression: &str) -> Option<DocumentType> {
+    let expression: String = expression.split_whitespace().collect();
+    let caps = creation_regex().captures(&expression)?;
+    Some(DocumentType::Object(caps[1].to_string()))
+}
+
+/// Документ, модулем объекта которого является файл
+/// (`Documents/Имя/Ext/ObjectModule.bsl` или `src/Documents/Имя/ObjectModule.bsl`)
+pub fn object_module_document(file_path: &str) -> Option<String> {
+    object_module_regex()
+        .captures(file_path)
+        .map(|caps| caps[1].to_string())
+}
+
+/// Набор записей регистра из состава движений
+/// (`AccumulationRegister.ТоварыНаСкладах`); регистры расчёта не
+/// поддерживаются
+pub fn register_record_set(qualified_name: &str) -> Option<RecordType> {
+    let (kind, register) = qualified_name.trim().split_once('.')?;
+    let kind = RegisterKind::from_metadata_tag(kind)?;
+    Some(RecordType {
+        kind,
+        object: RecordObject::RecordSet,
+        register: register.to_string(),
+    })
+}
+
+fn method(name: &str, return_type: Option<&str>) -> Method {
+    Method {
+        name: name.to_string(),
+        parameters: Vec::new(),
+        return_type: return_type.map(str::to_string),
+        is_function: return_type.is_some(),
+    }
+}
+
+fn property(name: &str, type_name: &str, readonly: bool) -> Property {
+    Property {
+        name: name.to_string(),
+        type_: type_name.to_string(),
+        readonly,
+    }
+}
+
+/// Объект документа `document` с реквизитами `attributes` из описания
+/// конфигурации, стандартными реквизитами и членами проведения
+pub fn document_object_resolution(
+    document: &str,
+    attributes: &[RawPropertyData],
+) -> TypeResolution {
+    let object = DocumentType::Object(document.to_string()).type_name();
+    let mut properties: Vec<Property> = attributes
+        .iter()
+        .map(|a| property(&a.name, &a.type_name, a.is_readonly))
+        .collect();
+    let reference = format!("ДокументСсылка.{}", document);
+    let movements = DocumentType::Movements(document.to_string()).type_name();
+    for (name, type_name, readonly) in [
+        ("Ссылка", reference.as_str(), true),
+        ("Дата", "Дата", false),
+        ("Номер", "Строка", false),
+        ("Проведен", "Булево", true),
+        ("ПометкаУдаления", "Булево", true),
+        ("Движения", movements.as_str(), true),
+        ("ОбменДанными", "ПараметрыОбменаДанными", true),
+        ("ДополнительныеСвойства", "Структура", true),
+    ] {
+        if !properties.iter().any(|p| p.name == name) {
+            properties.push(property(name, type_name, readonly));
+        }
+    }
+    let methods = vec![
+        method("Провести", None),
+        method("Записать", None),
+        method("Удалить", None),
+        method("Прочитать", None),
+        method("Заблокировать", None),
+        method("Разблокировать", None),
+        method("Заполнить", None),
+        method("УстановитьНовыйНомер", None),
+        method("УстановитьВремя", None),
+        method("ПроверитьЗаполнение", Some("Булево")),
+        method("Модифицированность", Some("Булево")),
+        method("ЭтоНовый", Some("Булево")),
+        method("Заблокирован", Some("Булево")),
+        method("Скопировать", Some(&object)),
+    ];
+    TypeResolution::known(ConcreteType::Platform(PlatformType {
+        name: object,
+        methods,
+        properties,
+    }))
+}
+
+/// Тип коллекции движений документа для репозитория: свойство на каждый
+/// регистр из состава движений (`register_records` — имена из описания
+/// документа)
+pub fn movements_raw_type(
+    document: &str,
+    register_records: &[String],
+    file_path: &str,
+) -> RawTypeData {
+    let name = DocumentType::Movements(document.to_string()).type_name();
+    let method = |name: &str, return_type: Option<&str>| RawMethodData {
+        name: name.to_string(),
+        documentation: String::new(),
+        parameters: Vec::new(),
+        return_type: return_type.map(str::to_string),
+        return_type_name: return_type.map(str::to_string),
+        params: Vec::new(),
+        is_function: return_type.is_some(),
+        examples: Vec::new(),
+    };
+    RawTypeData {
+        id: name.clone(),
+        russian_name: name.clone(),
+        english_name: name.clone(),
+        source: TypeSource::Platform {
+            version: "8.3".to_string(),
+        },
+        category_path: vec![MOVEMENTS_PREFIX.to_string()],
+        methods: vec![method("Записать", None), method("Количество", Some("Число"))],
+        properties: register_records
+            .iter()
+            .filter_map(|qualified| register_record_set(qualified))
+            .map(|set| RawPropertyData {
+                name: set.register.clone(),
+                type_name: set.type_name(),
+                is_readonly: true,
+                description: set.description(),
+            })
+            .collect(),
+        documentation: format!("Движения документа {}", document),
+        examples: vec!["Движения.Записать();".to_string()],
+        available_facets: Vec::new(),
+        parse_metadata: ParseMetadata {
+            file_path: file_path.to_string(),
+            line: 0,
+            column: 0,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_object_and_movements() {
+        let object = created_document_object("Документы.ПриходТовара.СоздатьДокумент()").unwrap();
+        assert_eq!(object.type_name(), "ДокументОбъект.ПриходТовара");
+        assert_eq!(DocumentType::parse(&object.type_name()), Some(object));
+        assert_eq!(
+            object_module_document("Documents/ПриходТовара/Ext/ObjectModule.bsl").as_deref(),
+            Some("ПриходТовара")
+        );
+        assert!(object_module_document("Documents/ПриходТовара/Ext/ManagerModule.bsl").is_none());
+
+        let raw = document_object_resolution("ПриходТовара", &[]).to_raw_data();
+        let exchange = raw.properties.iter().find(|p| p.name == "ОбменДанными").unwrap();
+        assert_eq!(exchange.type_name, "ПараметрыОбменаДанными");
+        assert!(raw.methods.iter().any(|m| m.name == "Провести"));
+
+        let records = vec![
+            "AccumulationRegister.ТоварыНаСкладах".to_string(),
+            "AccountingRegister.Хозрасчетный".to_string(),
+        ];
+        let movements = movements_raw_type("ПриходТовара", &records, "Documents/ПриходТовара.xml");
+        assert_eq!(movements.russian_name, "КоллекцияДвижений.ПриходТовара");
+        assert_eq!(movements.properties.len(), 2);
+        assert_eq!(
+            movements.properties[0].type_name,
+            "РегистрНакопленияНаборЗаписей.ТоварыНаСкладах"
+        );
+        assert_eq!(
+            movements.properties[0].description,
+            "Набор записей регистра ТоварыНаСкладах"
+        );
+        assert_eq!(
+            movements.properties[1].type_name,
+            "РегистрБухгалтерииНаборЗаписей.Хозрасчетный"
+        );
+        // Регистры расчёта не поддерживаются
+        let records = vec!["CalculationRegister.Начисления".to_string()];
+        let movements = movements_raw_type("Начисление", &records, "Documents/Начисление.xml");
+        assert!(movements.properties.is_empty());
+    }
+}
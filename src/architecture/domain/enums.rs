@@ -0,0 +1,265 @@
+//! Значения перечислений конфигурации
+//!
+//! `Перечисления.СтатусыЗаказов.Оплачен` — значение типа
+//! `ПеречислениеСсылка.СтатусыЗаказов`. Значения перечисления хранятся среди
+//! свойств его типа конфигурации с этим типом (из `<EnumValue>` описания
+//! объекта), по нему их отличают от прочих свойств. Сравнение со значением,
+//! которого в перечислении нет, при выполнении падает — такие сравнения
+//! находит `enum_comparisons`.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use super::managers::{ManagerKind, MANAGER_KINDS};
+use crate::core::example_mining::strip_comment;
+use crate::unified::data::{RawPropertyData, RawTypeData};
+
+const ENUM_CATEGORY: &str = "Enum";
+const REFERENCE_PREFIX: &str = "ПеречислениеСсылка";
+
+/// Описание пояснения значения в свойствах типа и автодополнении
+pub const ENUM_VALUE_DETAIL: &str = "Значение перечисления";
+
+/// Вид менеджера перечислений из `MANAGER_KINDS`
+pub fn enum_kind() -> &'static ManagerKind {
+    MANAGER_KINDS
+        .iter()
+        .find(|kind| kind.category == ENUM_CATEGORY)
+        .expect("перечисления в MANAGER_KINDS")
+}
+
+/// Ссылка на значение: `Перечисления.СтатусыЗаказов.Оплачен`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumValueRef {
+    pub enumeration: String,
+    pub value: String,
+}
+
+impl EnumValueRef {
+    /// Разобрать выражение; методы менеджера (`ПустаяСсылка()`) и более
+    /// длинные цепочки — не значения
+    pub fn parse(expression: &str) -> Option<Self> {
+        let mut parts = expression.trim().split('.').map(str::trim);
+        let (Some(collection), Some(enumeration), Some(value), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        let kind = enum_kind();
+        let collection = collection.to_lowercase();
+        if collection != kind.collection.to_lowercase() && collection != kind.english.to_lowercase()
+        {
+            return None;
+        }
+        if !is_identifier(enumeration) || !is_identifier(value) || is_manager_method(value) {
+            return None;
+        }
+        Some(Self {
+            enumeration: enumeration.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    pub fn type_name(&self) -> String {
+        enum_reference_type(&self.enumeration)
+    }
+}
+
+pub(super) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|ch| ch.is_alphanumeric() || ch == '_')
+}
+
+fn is_manager_method(name: &str) -> bool {
+    let name = name.to_lowercase();
+    enum_kind()
+        .methods
+        .iter()
+        .any(|method| method.name.to_lowercase() == name)
+}
+
+/// Тип значения перечисления: `ПеречислениеСсылка.СтатусыЗаказов`
+pub fn enum_reference_type(enumeration: &str) -> String {
+    format!("{}.{}", REFERENCE_PREFIX, enumeration)
+}
+
+/// Свойства типа перечисления для его значений
+pub fn enum_value_properties(enumeration: &str, values: &[String]) -> Vec<RawPropertyData> {
+    let type_name = enum_reference_type(enumeration);
+    values
+        .iter()
+        .map(|value| RawPropertyData {
+            name: value.clone(),
+            type_name: type_name.clone(),
+            is_readonly: true,
+            description: ENUM_VALUE_DETAIL.to_string(),
+        })
+        .collect()
+}
+
+/// Свойство типа перечисления — его значение
+pub fn is_enum_value(raw: &RawTypeData, property: &RawPropertyData) -> bool {
+    property.type_name == enum_reference_type(&raw.russian_name)
+}
+
+/// Значения перечисления из описания его типа в порядке объявления
+pub fn enum_values(raw: &RawTypeData) -> Vec<&str> {
+    raw.properties
+        .iter()
+        .filter(|property| is_enum_value(raw, property))
+        .map(|property| property.name.as_str())
+        .collect()
+}
+
+/// Сравнение со значением, которого нет в перечислении
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumValueIssue {
+    pub enumeration: String,
+    pub value: String,
+    /// Строка и столбец ссылки на значение (с 0)
+    pub line: u32,
+    pub column: u32,
+    /// Значения перечисления из описания конфигурации
+    pub known: Vec<String>,
+}
+
+impl EnumValueIssue {
+    pub fn message(&self) -> String {
+        format!(
+            "Перечисление '{}' не содержит значения '{}' (значения: {})",
+            self.enumeration,
+            self.value,
+            self.known.join(", ")
+        )
+    }
+}
+
+/// Сравнение со значением перечисления в тексте модуля
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumComparison {
+    pub reference: EnumValueRef,
+    pub line: u32,
+    pub column: u32,
+}
+
+fn enum_value_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)(?:Перечисления|Enums)\s*\.\s*[\p{L}_][\p{L}\p{N}_]*\s*\.\s*[\p{L}_][\p{L}\p{N}_]*",
+        )
+        .unwrap()
+    })
+}
+
+fn lvalue_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[\p{L}_][\p{L}\p{N}_.]*$").unwrap())
+}
+
+/// Строковые литералы заменены пробелами, длина в символах сохраняется
+fn mask_strings(line: &str) -> String {
+    let mut in_string = false;
+    line.chars()
+        .map(|ch| {
+            if ch == '"' {
+                in_string = !in_string;
+                ch
+            } else if in_string {
+                ' '
+            } else {
+                ch
+            }
+        })
+        .collect()
+}
+
+/// Сравнения `=`/`<>` со значениями перечислений (`Статус =
+/// Перечисления.СтатусыЗаказов.Оплачен`). Присваивание значения
+/// (`Статус = Перечисления...;` в начале оператора) сравнением не считается;
+/// комментарии, строки и тексты запросов пропускаются.
+pub fn enum_comparisons(text: &str) -> Vec<EnumComparison> {
+    let mut out = Vec::new();
+    for (idx, source) in text.lines().enumerate() {
+        if source.trim_start().starts_with('|') {
+            continue;
+        }
+        let code = mask_strings(strip_comment(source));
+        for found in enum_value_regex().find_iter(&code) {
+            let prefix = &code[..found.start()];
+            let before = prefix.trim_end();
+            let after = code[found.end()..].trim_start();
+            // Часть другого имени (`МоиПеречисления.А.Б`, `Объект.Перечисления`)
+            let inside_name = prefix
+                .chars()
+                .last()
+                .is_some_and(|ch| ch.is_alphanumeric() || ch == '_' || ch == '.');
+            if inside_name || after.starts_with('(') || after.starts_with('.') {
+                continue;
+            }
+            let compared_after = after.starts_with('=') || after.starts_with("<>");
+            let compared_before = before.ends_with("<>")
+                || before.strip_suffix('=').is_some_and(|left| {
+                    let left = left.trim();
+                    !lvalue_regex().is_match(left)
+                });
+            if !compared_after && !compared_before {
+                continue;
+            }
+            let Some(reference) = EnumValueRef::parse(found.as_str()) else {
+                continue;
+            };
+            out.push(EnumComparison {
+                reference,
+                line: idx as u32,
+                column: prefix.chars().count() as u32,
+            });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enum_value_ref() {
+        let reference = EnumValueRef::parse("Перечисления.СтатусыЗаказов.Оплачен").unwrap();
+        assert_eq!(reference.enumeration, "СтатусыЗаказов");
+        assert_eq!(reference.value, "Оплачен");
+        assert_eq!(reference.type_name(), "ПеречислениеСсылка.СтатусыЗаказов");
+        assert!(EnumValueRef::parse("Enums.OrderStatuses.Paid").is_some());
+        assert!(EnumValueRef::parse("Перечисления.СтатусыЗаказов.ПустаяСсылка()").is_none());
+        assert!(EnumValueRef::parse("Перечисления.СтатусыЗаказов.ПустаяСсылка").is_none());
+        assert!(EnumValueRef::parse("Перечисления.СтатусыЗаказов").is_none());
+        assert!(EnumValueRef::parse("Справочники.Товары.Услуга").is_none());
+
+        let properties = enum_value_properties("СтатусыЗаказов", &["Новый".to_string()]);
+        assert_eq!(properties[0].type_name, "ПеречислениеСсылка.СтатусыЗаказов");
+        assert!(properties[0].is_readonly);
+    }
+
+    #[test]
+    fn test_enum_comparisons() {
+        let text = r#"Статус = Перечисления.СтатусыЗаказов.Новый;
+Если Заказ.Статус = Перечисления.СтатусыЗаказов.Оплачен Тогда
+    // Статус = Перечисления.СтатусыЗаказов.Закомментирован
+    Текст = "Статус <> Перечисления.СтатусыЗаказов.ВСтроке";
+ИначеЕсли Перечисления.СтатусыЗаказов.Отменен <> Статус Тогда
+    Пусто = Статус = Перечисления.СтатусыЗаказов.ПустаяСсылка();
+КонецЕсли;
+Готов = Статус = Enums.СтатусыЗаказов.Отгружен;
+"#;
+        let comparisons = enum_comparisons(text);
+        let found: Vec<(u32, &str)> = comparisons
+            .iter()
+            .map(|c| (c.line, c.reference.value.as_str()))
+            .collect();
+        assert_eq!(found, [(1, "Оплачен"), (4, "Отменен"), (7, "Отгружен")]);
+        assert_eq!(comparisons[0].column, 20);
+    }
+}
@@ -0,0 +1,326 @@
+//! Внешние источники данных
+//!
+//! `ВнешниеИсточникиДанных.Склад.Таблицы.Остатки` — менеджер таблицы
+//! внешнего источника: `Выбрать()`, `СоздатьНаборЗаписей()` или, для
+//! объектных таблиц, `ПолучитьСсылку()`/`СоздатьОбъект()`; выборки, записи,
+//! объекты и ссылки несут поля таблицы из описания источника. Функции
+//! источника — методы `ВнешниеИсточникиДанных.Склад.Функции`.
+//!
+//! Каждое звено цепочки хранится в репозитории отдельным типом
+//! (`ВнешнийИсточникДанныхТаблицаМенеджер.Склад.Остатки`), поэтому разрешение
+//! выражений и автодополнение идут обычной навигацией по свойствам и
+//! результатам методов. Члены этих типов задаёт платформа, поэтому, как и
+//! коллекции движений, они хранятся платформенными типами.
+
+use crate::data::loaders::external_data_sources::{ExternalDataSource, ExternalTable};
+use crate::unified::data::{
+    ParseMetadata, RawMethodData, RawPropertyData, RawTypeData, TypeSource,
+};
+
+/// Тип глобального свойства `ВнешниеИсточникиДанных`
+pub const COLLECTION_TYPE: &str = "ВнешниеИсточникиДанныхМенеджер";
+const SOURCE_MANAGER: &str = "ВнешнийИсточникДанныхМенеджер";
+const TABLES_MANAGER: &str = "ВнешнийИсточникДанныхТаблицыМенеджер";
+const FUNCTIONS_MANAGER: &str = "ВнешнийИсточникДанныхФункцииМенеджер";
+const CATEGORY: &str = "ExternalDataSource";
+
+/// Тип, связанный с таблицей внешнего источника
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableType {
+    Manager,
+    Reference,
+    Object,
+    Selection,
+    RecordSet,
+    RecordManager,
+    Record,
+}
+
+impl TableType {
+    fn suffix(self) -> &'static str {
+        match self {
+            TableType::Manager => "Менеджер",
+            TableType::Reference => "Ссылка",
+            TableType::Object => "Объект",
+            TableType::Selection => "Выборка",
+            TableType::RecordSet => "НаборЗаписей",
+            TableType::RecordManager => "МенеджерЗаписи",
+            TableType::Record => "Запись",
+        }
+    }
+
+    /// `ВнешнийИсточникДанныхТаблицаСсылка.Склад.Остатки`
+    pub fn type_name(self, source: &str, table: &str) -> String {
+        format!(
+            "ВнешнийИсточникДанныхТаблица{}.{}.{}",
+            self.suffix(),
+            source,
+            table
+        )
+    }
+}
+
+/// Тип глобального свойства по имени (`ВнешниеИсточникиДанных`,
+/// `ExternalDataSources`, без учёта регистра)
+pub fn collection_type(name: &str) -> Option<&'static str> {
+    let name = name.trim().to_lowercase();
+    (name == "внешниеисточникиданных" || name == "externaldatasources").then_some(COLLECTION_TYPE)
+}
+
+fn method(name: &str, return_type: Option<String>) -> RawMethodData {
+    RawMethodData {
+        name: name.to_string(),
+        documentation: String::new(),
+        parameters: Vec::new(),
+        is_function: return_type.is_some(),
+        return_type_name: return_type.clone(),
+        return_type,
+        params: Vec::new(),
+        examples: Vec::new(),
+    }
+}
+
+fn property(name: &str, type_name: String, readonly: bool) -> RawPropertyData {
+    RawPropertyData {
+        name: name.to_string(),
+        type_name,
+        is_readonly: readonly,
+        description: String::new(),
+    }
+}
+
+fn raw_type(
+    name: String,
+    methods: Vec<RawMethodData>,
+    properties: Vec<RawPropertyData>,
+    documentation: String,
+    file_path: &str,
+) -> RawTypeData {
+    RawTypeData {
+        id: name.clone(),
+        russian_name: name.clone(),
+        english_name: name,
+        source: TypeSource::Platform {
+            version: "8.3".to_string(),
+        },
+        category_path: vec![CATEGORY.to_string()],
+        methods,
+        properties,
+        documentation,
+        examples: Vec::new(),
+        available_facets: Vec::new(),
+        parse_metadata: ParseMetadata {
+            file_path: file_path.to_string(),
+            line: 0,
+            column: 0,
+        },
+    }
+}
+
+/// Поля таблицы как свойства; составной тип — имена через запятую
+fn field_properties(table: &ExternalTable, readonly: bool) -> Vec<RawPropertyData> {
+    table
+        .fields
+        .iter()
+        .map(|field| property(&field.name, field.types.join(","), readonly))
+        .collect()
+}
+
+/// Менеджер таблицы и типы её значений
+fn table_types(source: &str, table: &ExternalTable, file_path: &str) -> Vec<RawTypeData> {
+    let name = |kind: TableType| kind.type_name(source, &table.name);
+    let returns = |kind: TableType| Some(name(kind));
+    let full_name = format!("{}.{}", source, table.name);
+    let mut types = Vec::new();
+
+    let mut manager_methods = vec![method("Выбрать", returns(TableType::Selection))];
+    let mut selection_methods = vec![method("Следующий", Some("Булево".to_string()))];
+    let mut selection_properties = field_properties(table, true);
+    if table.object {
+        manager_methods.extend([
+            method("ПолучитьСсылку", returns(TableType::Reference)),
+            method("НайтиПоПолю", returns(TableType::Reference)),
+            method("СоздатьОбъект", returns(TableType::Object)),
+        ]);
+        selection_methods.push(method("ПолучитьОбъект", returns(TableType::Object)));
+        selection_properties.push(property("Ссылка", name(TableType::Reference), true));
+
+        let mut object_properties = field_properties(table, false);
+        object_properties.push(property("Ссылка", name(TableType::Reference), true));
+        types.push(raw_type(
+            name(TableType::Object),
+            vec![
+                method("Записать", None),
+                method("Удалить", None),
+                method("Прочитать", None),
+            ],
+            object_properties,
+            format!("Объект таблицы внешнего источника {}", full_name),
+            file_path,
+        ));
+        types.push(raw_type(
+            name(TableType::Reference),
+            vec![method("ПолучитьОбъект", returns(TableType::Object))],
+            field_properties(table, true),
+            format!("Ссылка на таблицу внешнего источника {}", full_name),
+            file_path,
+        ));
+    } else {
+        manager_methods.extend([
+            method("СоздатьНаборЗаписей", returns(TableType::RecordSet)),
+            method("СоздатьМенеджерЗаписи", returns(TableType::RecordManager)),
+        ]);
+        types.push(raw_type(
+            name(TableType::RecordSet),
+            vec![
+                method("Записать", None),
+                method("Прочитать", None),
+                method("Добавить", returns(TableType::Record)),
+                method("Количество", Some("Число".to_string())),
+            ],
+            Vec::new(),
+            format!("Набор записей таблицы внешнего источника {}", full_name),
+            file_path,
+        ));
+        types.push(raw_type(
+            name(TableType::RecordManager),
+            vec![
+                method("Записать", None),
+                method("Прочитать", None),
+                method("Удалить", None),
+            ],
+            field_properties(table, false),
+            format!("Менеджер записи таблицы внешнего источника {}", full_name),
+            file_path,
+        ));
+        types.push(raw_type(
+            name(TableType::Record),
+            Vec::new(),
+            field_properties(table, false),
+            format!("Запись таблицы внешнего источника {}", full_name),
+            file_path,
+        ));
+    }
+    manager_methods.push(method(
+        "ПолучитьФорму",
+        Some("ФормаКлиентскогоПриложения".to_string()),
+    ));
+
+    types.push(raw_type(
+        name(TableType::Manager),
+        manager_methods,
+        Vec::new(),
+        format!("Менеджер таблицы внешнего источника {}", full_name),
+        file_path,
+    ));
+    types.push(raw_type(
+        name(TableType::Selection),
+        selection_methods,
+        selection_properties,
+        format!("Выборка из таблицы внешнего источника {}", full_name),
+        file_path,
+    ));
+    types
+}
+
+/// Типы одного источника: менеджер источника, коллекции таблиц и функций,
+/// менеджеры таблиц и типы их значений
+fn source_types(source: &ExternalDataSource) -> Vec<RawTypeData> {
+    let file_path = source.file_path.to_string_lossy();
+    let name = &source.name;
+    let tables_type = format!("{}.{}", TABLES_MANAGER, name);
+    let functions_type = format!("{}.{}", FUNCTIONS_MANAGER, name);
+    let parameters = Some("ПараметрыСоединенияВнешнегоИсточникаДанных".to_string());
+
+    let mut types = vec![
+        raw_type(
+            format!("{}.{}", SOURCE_MANAGER, name),
+            vec![
+                method("УстановитьОбщиеПараметрыСоединения", None),
+                method("ПолучитьОбщиеПараметрыСоединения", parameters.clone()),
+                method("УстановитьПараметрыСоединенияПользователя", None),
+                method(
+                    "ПолучитьПараметрыСоединенияПользователя",
+                    parameters.clone(),
+                ),
+                method("УстановитьПараметрыСоединенияСеанса", None),
+                method("ПолучитьПараметрыСоединенияСеанса", parameters),
+                method("УстановитьСоединение", None),
+                method("РазорватьСоединение", None),
+                method(
+                    "ПолучитьСостояние",
+                    Some("СостояниеВнешнегоИсточникаДанных".to_string()),
+                ),
+            ],
+            vec![
+                property("Таблицы", tables_type.clone(), true),
+                property("Функции", functions_type.clone(), true),
+            ],
+            format!("Внешний источник данных {}", name),
+            &file_path,
+        ),
+        raw_type(
+            tables_type,
+            Vec::new(),
+            source
+                .tables
+                .iter()
+                .map(|table| {
+                    property(
+                        &table.name,
+                        TableType::Manager.type_name(name, &table.name),
+                        true,
+                    )
+                })
+                .collect(),
+            format!("Таблицы внешнего источника {}", name),
+            &file_path,
+        ),
+        raw_type(
+            functions_type,
+            source
+                .functions
+                .iter()
+                .map(|function| {
+                    let return_type = (!function.return_types.is_empty())
+                        .then(|| function.return_types.join(","));
+                    method(&function.name, return_type)
+                })
+                .collect(),
+            Vec::new(),
+            format!("Функции внешнего источника {}", name),
+            &file_path,
+        ),
+    ];
+    for table in &source.tables {
+        types.extend(table_types(name, table, &file_path));
+    }
+    types
+}
+
+/// Типы для репозитория: `ВнешниеИсточникиДанныхМенеджер` со свойством на
+/// каждый источник и типы всех источников; пусто, если источников нет
+pub fn external_sources_raw_types(sources: &[ExternalDataSource]) -> Vec<RawTypeData> {
+    if sources.is_empty() {
+        return Vec::new();
+    }
+    let collection = raw_type(
+        COLLECTION_TYPE.to_string(),
+        Vec::new(),
+        sources
+            .iter()
+            .map(|source| {
+                property(
+                    &source.name,
+                    format!("{}.{}", SOURCE_MANAGER, source.name),
+                    true,
+                )
+            })
+            .collect(),
+        "Внешние источники данных конфигурации".to_string(),
+        "ExternalDataSources",
+    );
+    std::iter::once(collection)
+        .chain(sources.iter().flat_map(source_types))
+        .collect()
+}
@@ -0,0 +1,195 @@
+//! Контекст модуля управляемой формы
+//!
+//! В модуле формы без квалификатора доступны сама форма (`ЭтаФорма`,
+//! `ЭтотОбъект` — `ФормаКлиентскогоПриложения`), коллекция `Элементы` и
+//! реквизиты формы (`Объект`, `Список`). Их состав и типы берутся из
+//! описания формы рядом с модулем (`Form.xml` конфигуратора или
+//! `Form.form` EDT): элемент `Контрагент` виден как свойство
+//! `Элементы.Контрагент` типа `ПолеФормы`, основной реквизит
+//! `cfg:DocumentObject.Заказ` — как `ДокументОбъект.Заказ`.
+
+use std::path::Path;
+use tracing::warn;
+
+use super::{ExpressionResolver, TypeContext};
+use crate::data::loaders::forms::FormDescription;
+use crate::domain::types::{ConcreteType, Method, PlatformType, Property, TypeResolution};
+use crate::unified::data::TypeRepository;
+
+/// Тип формы в модуле формы
+pub const FORM_TYPE: &str = "ФормаКлиентскогоПриложения";
+/// Тип коллекции `Элементы`
+pub const FORM_ITEMS_TYPE: &str = "ВсеЭлементыФормы";
+
+fn method(name: &str, return_type: Option<&str>) -> Method {
+    Method {
+        name: name.to_string(),
+        parameters: Vec::new(),
+        return_type: return_type.map(str::to_string),
+        is_function: return_type.is_some(),
+    }
+}
+
+fn property(name: &str, type_name: &str, readonly: bool) -> Property {
+    Property {
+        name: name.to_string(),
+        type_: type_name.to_string(),
+        readonly,
+    }
+}
+
+/// Переменные модуля формы из её описания
+#[derive(Debug, Clone)]
+pub struct FormContextProvider {
+    form: FormDescription,
+}
+
+impl FormContextProvider {
+    pub fn new(form: FormDescription) -> Self {
+        Self { form }
+    }
+
+    /// Провайдер для модуля формы `file_path`; `None` — файл не модуль
+    /// формы или описание формы не читается
+    pub fn for_module(file_path: &str) -> Option<Self> {
+        match FormDescription::load_for_module(Path::new(file_path)) {
+            Ok(form) => form.map(Self::new),
+            Err(e) => {
+                warn!(
+                    "⚠️ Описание формы модуля {} не прочитано: {:#}",
+                    file_path, e
+                );
+                None
+            }
+        }
+    }
+
+    pub fn form(&self) -> &FormDescription {
+        &self.form
+    }
+
+    /// Коллекция `Элементы`: свойство на каждый элемент формы
+    pub fn items_resolution(&self) -> TypeResolution {
+        let properties = self
+            .form
+            .elements
+            .iter()
+            .map(|element| property(&element.name, &element.type_name, true))
+            .collect();
+        TypeResolution::known(ConcreteType::Platform(PlatformType {
+            name: FORM_ITEMS_TYPE.to_string(),
+            methods: vec![
+                method("Найти", Some("ЭлементФормы")),
+                method("Добавить", Some("ЭлементФормы")),
+                method("Удалить", None),
+                method("Переместить", None),
+            ],
+            properties,
+        }))
+    }
+
+    /// Сама форма (`ЭтаФорма`): реквизиты формы, `Элементы` и стандартные
+    /// члены формы
+    pub fn form_resolution(&self) -> TypeResolution {
+        let mut properties: Vec<Property> = self
+            .form
+            .attributes
+            .iter()
+            .map(|attribute| property(&attribute.name, &attribute.types.join(","), false))
+            .collect();
+        for (name, type_name, readonly) in [
+            ("Элементы", FORM_ITEMS_TYPE, true),
+            ("Заголовок", "Строка", false),
+            ("ТолькоПросмотр", "Булево", false),
+            ("Модифицированность", "Булево", false),
+            ("КлючУникальности", "Произвольный", true),
+            ("ВладелецФормы", "Произвольный", false),
+            ("ТекущийЭлемент", "ЭлементФормы", false),
+        ] {
+            if !properties.iter().any(|p| p.name == name) {
+                properties.push(property(name, type_name, readonly));
+            }
+        }
+        TypeResolution::known(ConcreteType::Platform(PlatformType {
+            name: FORM_TYPE.to_string(),
+            methods: vec![
+                method("Открыть", None),
+                method("Закрыть", None),
+                method("Открыта", Some("Булево")),
+                method("Активизировать", None),
+                method("ОбновитьОтображениеДанных", None),
+                method("ПроверитьЗаполнение", Some("Булево")),
+            ],
+            properties,
+        }))
+    }
+
+    /// Добавить в контекст `ЭтаФорма`, `ЭтотОбъект`, `Элементы` и
+    /// реквизиты формы; переменные, уже известные контексту, не
+    /// перекрываются. Реквизит, тип которого не разрешается, пропускается.
+    pub(super) async fn populate(
+        &self,
+        context: &mut TypeContext,
+        resolver: &ExpressionResolver,
+        repository: &dyn TypeRepository,
+    ) {
+        let form = self.form_resolution();
+        let mut variables = vec![
+            ("ЭтаФорма".to_string(), form.clone()),
+            ("ЭтотОбъект".to_string(), form),
+            ("Элементы".to_string(), self.items_resolution()),
+        ];
+        for attribute in &self.form.attributes {
+            if let Some(resolution) = resolver
+                .resolve_declared_types(&attribute.types, repository)
+                .await
+            {
+                variables.push((attribute.name.clone(), resolution));
+            }
+        }
+        for (name, resolution) in variables {
+            if context.local_variable(&name).is_none() {
+                context.local_variables.insert(name, resolution);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::loaders::forms::{FormAttribute, FormElement};
+
+    #[test]
+    fn test_form_and_items_resolution() {
+        let provider = FormContextProvider::new(FormDescription {
+            elements: vec![FormElement {
+                name: "Контрагент".to_string(),
+                type_name: "ПолеФормы".to_string(),
+            }],
+            attributes: vec![FormAttribute {
+                name: "Объект".to_string(),
+                types: vec!["ДокументОбъект.Заказ".to_string()],
+                main: true,
+            }],
+            ..FormDescription::default()
+        });
+
+        let items = provider.items_resolution().to_raw_data();
+        assert_eq!(items.russian_name, FORM_ITEMS_TYPE);
+        assert_eq!(items.properties[0].name, "Контрагент");
+        assert_eq!(items.properties[0].type_name, "ПолеФормы");
+
+        let form = provider.form_resolution().to_raw_data();
+        assert_eq!(form.russian_name, FORM_TYPE);
+        let object = form.properties.iter().find(|p| p.name == "Объект").unwrap();
+        assert_eq!(object.type_name, "ДокументОбъект.Заказ");
+        let items = form
+            .properties
+            .iter()
+            .find(|p| p.name == "Элементы")
+            .unwrap();
+        assert_eq!(items.type_name, FORM_ITEMS_TYPE);
+        assert!(form.methods.iter().any(|m| m.name == "Закрыть"));
+    }
+}
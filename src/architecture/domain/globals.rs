@@ -0,0 +1,320 @@
+//! Глобальная область модулей приложения и сеанса
+//!
+//! Экспортные переменные (`Перем Имя Экспорт;`) и экспортные методы модуля
+//! управляемого или обычного приложения, модуля сеанса и модуля внешнего
+//! соединения доступны во всех модулях своего контекста без квалификатора.
+//! Модули распознаются по пути в выгрузке конфигуратора
+//! (`Ext/ManagedApplicationModule.bsl`) и EDT
+//! (`Configuration/ManagedApplicationModule.bsl`). Тип глобальной переменной
+//! выводится по присваиваниям в теле модуля и в конце его методов
+//! (`ПриНачалеРаботыСистемы`): значения всех мест объединяются.
+
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use super::assignments::{reaching_assignments, AssignedValue};
+use crate::core::compilation_context::CompilationContext;
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::{is_method_end, method_header};
+
+/// Модуль, экспортные символы которого глобальны
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GlobalModuleKind {
+    ManagedApplication,
+    OrdinaryApplication,
+    Session,
+    ExternalConnection,
+}
+
+impl GlobalModuleKind {
+    pub const ALL: [GlobalModuleKind; 4] = [
+        GlobalModuleKind::ManagedApplication,
+        GlobalModuleKind::OrdinaryApplication,
+        GlobalModuleKind::Session,
+        GlobalModuleKind::ExternalConnection,
+    ];
+
+    /// Имя файла модуля в выгрузке
+    pub fn file_name(self) -> &'static str {
+        match self {
+            GlobalModuleKind::ManagedApplication => "ManagedApplicationModule.bsl",
+            GlobalModuleKind::OrdinaryApplication => "OrdinaryApplicationModule.bsl",
+            GlobalModuleKind::Session => "SessionModule.bsl",
+            GlobalModuleKind::ExternalConnection => "ExternalConnectionModule.bsl",
+        }
+    }
+
+    /// Название модуля для подсказок
+    pub fn label(self) -> &'static str {
+        match self {
+            GlobalModuleKind::ManagedApplication => "Модуль управляемого приложения",
+            GlobalModuleKind::OrdinaryApplication => "Модуль обычного приложения",
+            GlobalModuleKind::Session => "Модуль сеанса",
+            GlobalModuleKind::ExternalConnection => "Модуль внешнего соединения",
+        }
+    }
+
+    /// Вид модуля по пути: файл лежит в `Ext` корня выгрузки или в
+    /// `Configuration` проекта EDT (у объектов метаданных модули с такими
+    /// именами не бывает)
+    pub fn from_path(path: &str) -> Option<Self> {
+        let path = Path::new(path);
+        let file_name = path.file_name()?.to_str()?;
+        let parent = path.parent()?.file_name()?.to_str()?;
+        if parent != "Ext" && parent != "Configuration" {
+            return None;
+        }
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.file_name() == file_name)
+    }
+
+    /// Контекст, в котором доступны экспортные символы модуля
+    pub fn context(self) -> CompilationContext {
+        match self {
+            GlobalModuleKind::ManagedApplication | GlobalModuleKind::OrdinaryApplication => {
+                CompilationContext::Client
+            }
+            GlobalModuleKind::Session | GlobalModuleKind::ExternalConnection => {
+                CompilationContext::Server
+            }
+        }
+    }
+
+    /// Доступны ли символы модуля в методе с контекстом `context`
+    pub fn available_in(self, context: CompilationContext) -> bool {
+        self.context() == context
+    }
+}
+
+/// Экспортная переменная глобального модуля
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalVariable {
+    pub name: String,
+    pub module: GlobalModuleKind,
+    pub file: String,
+    /// Строка объявления (с 0)
+    pub line: u32,
+    /// Присвоенные значения без повторов; тип по ним выводит резолвер
+    pub values: Vec<AssignedValue>,
+}
+
+/// Экспортный метод глобального модуля (сигнатура — в таблице сигнатур)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalMethod {
+    pub name: String,
+    pub module: GlobalModuleKind,
+    pub file: String,
+    pub line: u32,
+    pub is_function: bool,
+}
+
+/// Глобальные символы модулей приложения и сеанса проекта
+#[derive(Debug, Clone, Default)]
+pub struct GlobalScope {
+    variables: Vec<GlobalVariable>,
+    methods: Vec<GlobalMethod>,
+}
+
+fn variable_declaration_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^\s*(?:Перем|Var)\s+([^;]+);?").unwrap())
+}
+
+impl GlobalScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Построить по модулям проекта (путь, текст); модули, не являющиеся
+    /// глобальными, пропускаются
+    pub fn build(modules: &[(String, String)]) -> Self {
+        let mut scope = Self::new();
+        for (file, text) in modules {
+            scope.add_module(file, text);
+        }
+        scope
+    }
+
+    /// Добавить экспортные символы модуля; `false` — модуль не глобальный
+    pub fn add_module(&mut self, file: &str, text: &str) -> bool {
+        let Some(module) = GlobalModuleKind::from_path(file) else {
+            return false;
+        };
+        self.add_module_of_kind(module, file, text);
+        true
+    }
+
+    /// Убрать символы модуля `file` (изменён или удалён)
+    pub fn remove_module(&mut self, file: &str) {
+        self.variables.retain(|variable| variable.file != file);
+        self.methods.retain(|method| method.file != file);
+    }
+
+    /// Добавить экспортные символы модуля известного вида
+    pub fn add_module_of_kind(&mut self, module: GlobalModuleKind, file: &str, text: &str) {
+        let mut variables = Vec::new();
+        // Присваивания смотрятся в конце каждого метода и в конце модуля
+        let mut ends = Vec::new();
+        for (idx, source) in text.lines().enumerate() {
+            let code = strip_comment(source);
+            if is_method_end(code) {
+                ends.push(idx as u32);
+                continue;
+            }
+            if let Some(header) = method_header(code).filter(|header| header.export) {
+                self.methods.push(GlobalMethod {
+                    name: header.name.to_string(),
+                    module,
+                    file: file.to_string(),
+                    line: idx as u32,
+                    is_function: header.is_function,
+                });
+                continue;
+            }
+            let Some(caps) = variable_declaration_regex().captures(code) else {
+                continue;
+            };
+            for declaration in caps[1].split(',') {
+                let mut words = declaration.split_whitespace();
+                let (Some(name), Some(export)) = (words.next(), words.next()) else {
+                    continue;
+                };
+                if matches!(export.to_lowercase().as_str(), "экспорт" | "export") {
+                    variables.push(GlobalVariable {
+                        name: name.to_string(),
+                        module,
+                        file: file.to_string(),
+                        line: idx as u32,
+                        values: Vec::new(),
+                    });
+                }
+            }
+        }
+        if variables.is_empty() {
+            return;
+        }
+        ends.push(text.lines().count() as u32);
+        for line in ends {
+            for assignments in reaching_assignments(text, line, 0) {
+                let Some(variable) = variables
+                    .iter_mut()
+                    .find(|v| v.name.to_lowercase() == assignments.variable.to_lowercase())
+                else {
+                    continue;
+                };
+                for value in assignments.values {
+                    if !variable.values.contains(&value) {
+                        variable.values.push(value);
+                    }
+                }
+            }
+        }
+        self.variables.extend(variables);
+    }
+
+    pub fn variables(&self) -> &[GlobalVariable] {
+        &self.variables
+    }
+
+    pub fn methods(&self) -> &[GlobalMethod] {
+        &self.methods
+    }
+
+    /// Глобальная переменная по имени (без учёта регистра)
+    pub fn variable(&self, name: &str) -> Option<&GlobalVariable> {
+        let name = name.to_lowercase();
+        self.variables
+            .iter()
+            .find(|v| v.name.to_lowercase() == name)
+    }
+
+    /// Глобальный метод по имени (без учёта регистра)
+    pub fn method(&self, name: &str) -> Option<&GlobalMethod> {
+        let name = name.to_lowercase();
+        self.methods.iter().find(|m| m.name.to_lowercase() == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.variables.len() + self.methods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty() && self.methods.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APPLICATION: &str = r#"Перем ТекущийПользователь Экспорт;
+Перем Кеш Экспорт, Счётчик;
+
+Процедура ПриНачалеРаботыСистемы()
+    Кеш = Новый Соответствие;
+КонецПроцедуры
+
+Функция ВерсияКлиента() Экспорт
+    Возврат "1.0";
+КонецФункции
+
+Процедура Служебная()
+КонецПроцедуры
+
+ТекущийПользователь = "";
+"#;
+
+    #[test]
+    fn test_module_kind_from_path() {
+        assert_eq!(
+            GlobalModuleKind::from_path("src/cf/Ext/ManagedApplicationModule.bsl"),
+            Some(GlobalModuleKind::ManagedApplication)
+        );
+        assert_eq!(
+            GlobalModuleKind::from_path("project/src/Configuration/SessionModule.bsl"),
+            Some(GlobalModuleKind::Session)
+        );
+        assert_eq!(
+            GlobalModuleKind::from_path("src/cf/Catalogs/Товары/Ext/ObjectModule.bsl"),
+            None
+        );
+        assert_eq!(
+            GlobalModuleKind::from_path("ManagedApplicationModule.bsl"),
+            None
+        );
+        assert!(GlobalModuleKind::ManagedApplication.available_in(CompilationContext::Client));
+        assert!(!GlobalModuleKind::Session.available_in(CompilationContext::Client));
+    }
+
+    #[test]
+    fn test_exported_symbols() {
+        let scope = GlobalScope::build(&[
+            (
+                "cf/Ext/ManagedApplicationModule.bsl".to_string(),
+                APPLICATION.to_string(),
+            ),
+            (
+                "cf/CommonModules/Общий/Ext/Module.bsl".to_string(),
+                "Перем Скрытая Экспорт;".to_string(),
+            ),
+        ]);
+        let names: Vec<&str> = scope.variables().iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["ТекущийПользователь", "Кеш"]);
+
+        let cache = scope.variable("кеш").unwrap();
+        assert_eq!(
+            cache.values,
+            [AssignedValue::Type("Соответствие".to_string())]
+        );
+        let user = scope.variable("ТекущийПользователь").unwrap();
+        assert_eq!(user.values, [AssignedValue::Type("Строка".to_string())]);
+
+        assert_eq!(scope.methods().len(), 1);
+        let method = scope.method("ВерсияКлиента").unwrap();
+        assert!(method.is_function);
+        assert_eq!(method.module, GlobalModuleKind::ManagedApplication);
+        assert!(scope.method("Служебная").is_none());
+    }
+}
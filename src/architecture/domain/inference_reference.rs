@@ -0,0 +1,547 @@
+//! Справка «Как работает вывод типов»
+//!
+//! Описание вывода типов, написанное вручную, быстро расходится с кодом:
+//! резолверы добавляются, меняют порядок и отключаются настройками. Справка
+//! строится из работающего сервиса разрешения: цепочка резолверов в порядке
+//! опроса (класс стоимости, шаблоны выражений) и политика остановки;
+//! аксиомы — базовые выражения, разрешённые той же цепочкой; матрица
+//! присваиваний между представительными типами по правилам
+//! [`TypeCheckerService`] с функциями явного преобразования. Справка
+//! выводится в Markdown и HTML (`bsl-analyzer inference-reference`, страница
+//! `/help/inference` веб-сервера).
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::{
+    ResolverCost, ShortCircuitPolicy, TypeCheckerService, TypeContext, TypeResolutionService,
+};
+use crate::core::standard_types::{platform_type, primitive_type, special_type};
+use crate::core::type_quick_fixes::CONVERSIONS;
+use crate::domain::types::{
+    Certainty, ConcreteType, ConfigurationType, MetadataKind, PrimitiveType, ResolutionResult,
+    SpecialType, TypeResolution,
+};
+
+/// Аксиомы: выражение и правило вывода, которое оно показывает
+const AXIOMS: &[(&str, &str)] = &[
+    ("\"Текст\"", "строковый литерал"),
+    ("42", "числовой литерал"),
+    ("Истина", "логический литерал"),
+    ("'20240101'", "литерал даты"),
+    ("Неопределено", "пустое значение"),
+    ("Null", "значение NULL"),
+    ("Новый Массив", "конструктор типа платформы"),
+    ("Новый Структура", "конструктор коллекции"),
+    ("Строка(42)", "функция преобразования"),
+    ("ТекущаяДата()", "функция глобального контекста"),
+];
+
+/// Название неизвестного типа в матрице присваиваний
+const UNKNOWN_TYPE: &str = "Неизвестный";
+
+/// Резолвер цепочки
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolverEntry {
+    pub name: String,
+    pub cost: ResolverCost,
+    /// Шаблоны выражений; пустой список — резолвер решает сам
+    pub patterns: Vec<String>,
+}
+
+/// Базовое выражение и тип, который выводит для него цепочка
+#[derive(Debug, Clone, Serialize)]
+pub struct Axiom {
+    pub expression: String,
+    /// Правило вывода, которое показывает выражение
+    pub rule: String,
+    /// Выведенный тип; `None` — тип неизвестен
+    pub type_name: Option<String>,
+    pub certainty: Certainty,
+    /// Резолвер, давший результат
+    pub resolver: Option<String>,
+}
+
+/// Можно ли присвоить значение одного типа переменной другого
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "function", rename_all = "snake_case")]
+pub enum Assignability {
+    Compatible,
+    /// Несовместимо, но есть функция явного преобразования
+    Conversion(String),
+    Incompatible,
+}
+
+/// Матрица присваиваний между представительными типами
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversionMatrix {
+    pub types: Vec<String>,
+    /// `cells[значение][переменная]`
+    pub cells: Vec<Vec<Assignability>>,
+}
+
+/// Справка по выводу типов
+#[derive(Debug, Clone, Serialize)]
+pub struct InferenceReference {
+    /// Резолверы в порядке опроса
+    pub resolvers: Vec<ResolverEntry>,
+    /// Отключённые настройками резолверы
+    pub disabled: Vec<String>,
+    pub policy: ShortCircuitPolicy,
+    pub axioms: Vec<Axiom>,
+    pub matrix: ConversionMatrix,
+}
+
+/// Собрать справку по цепочке резолверов сервиса
+pub(super) async fn build(service: &TypeResolutionService) -> InferenceReference {
+    let resolvers = service
+        .resolvers
+        .iter()
+        .map(|entry| ResolverEntry {
+            name: entry.name().to_string(),
+            cost: entry.capabilities().cost,
+            patterns: entry
+                .capabilities()
+                .patterns
+                .iter()
+                .map(|pattern| pattern.as_str().to_string())
+                .collect(),
+        })
+        .collect();
+
+    let context = TypeContext {
+        file_path: None,
+        line: None,
+        column: None,
+        local_variables: HashMap::new(),
+        current_function: None,
+        current_facet: None,
+    };
+    let mut axioms = Vec::new();
+    for (expression, rule) in AXIOMS {
+        let resolution = service.resolve_expression(expression, &context).await;
+        axioms.push(Axiom {
+            expression: expression.to_string(),
+            rule: rule.to_string(),
+            type_name: (resolution.certainty != Certainty::Unknown)
+                .then(|| display_type(&resolution)),
+            certainty: resolution.certainty,
+            resolver: resolution.metadata.resolver,
+        });
+    }
+
+    InferenceReference {
+        resolvers,
+        disabled: service.resolvers.config().disabled.clone(),
+        policy: service.chain_policy,
+        axioms,
+        matrix: conversion_matrix(),
+    }
+}
+
+/// Матрица присваиваний по правилам [`TypeCheckerService`]
+pub fn conversion_matrix() -> ConversionMatrix {
+    let samples = sample_types();
+    let checker = TypeCheckerService::new();
+    let cells = samples
+        .iter()
+        .map(|(_, from)| {
+            samples
+                .iter()
+                .map(|(to_name, to)| {
+                    if checker.is_assignment_compatible(from, to) {
+                        Assignability::Compatible
+                    } else if CONVERSIONS.contains(to_name) {
+                        Assignability::Conversion(format!("{}(…)", to_name))
+                    } else {
+                        Assignability::Incompatible
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    ConversionMatrix {
+        types: samples.iter().map(|(name, _)| name.to_string()).collect(),
+        cells,
+    }
+}
+
+/// Представительные типы: примитивы, специальные значения, тип платформы,
+/// тип конфигурации и неизвестный тип
+fn sample_types() -> Vec<(&'static str, TypeResolution)> {
+    let catalog = ConfigurationType {
+        kind: MetadataKind::Catalog,
+        name: "СправочникСсылка.Валюты".to_string(),
+        attributes: Vec::new(),
+        tabular_sections: Vec::new(),
+    };
+    vec![
+        ("Строка", primitive_type(PrimitiveType::String)),
+        ("Число", primitive_type(PrimitiveType::Number)),
+        ("Булево", primitive_type(PrimitiveType::Boolean)),
+        ("Дата", primitive_type(PrimitiveType::Date)),
+        ("Неопределено", special_type(SpecialType::Undefined)),
+        ("Null", special_type(SpecialType::Null)),
+        ("Массив", platform_type("Массив")),
+        (
+            "СправочникСсылка.Валюты",
+            TypeResolution::known(ConcreteType::Configuration(catalog)),
+        ),
+        (UNKNOWN_TYPE, TypeResolution::unknown()),
+    ]
+}
+
+/// Имя выведенного типа; варианты объединения — через `|`
+fn display_type(resolution: &TypeResolution) -> String {
+    match &resolution.result {
+        ResolutionResult::Concrete(concrete) => concrete_name(concrete),
+        ResolutionResult::Union(types) => types
+            .iter()
+            .map(|weighted| concrete_name(&weighted.type_))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        ResolutionResult::Conditional(_) | ResolutionResult::Contextual(_) => {
+            "зависит от контекста".to_string()
+        }
+        ResolutionResult::Dynamic => "динамический".to_string(),
+    }
+}
+
+fn concrete_name(concrete: &ConcreteType) -> String {
+    match concrete {
+        ConcreteType::Platform(platform) => platform.name.clone(),
+        ConcreteType::Configuration(configuration) => configuration.name.clone(),
+        ConcreteType::Primitive(primitive) => primitive.to_string(),
+        ConcreteType::GlobalFunction(function) => function.name.clone(),
+        ConcreteType::Special(SpecialType::Undefined) => "Неопределено".to_string(),
+        ConcreteType::Special(SpecialType::Null) => "Null".to_string(),
+        ConcreteType::Special(SpecialType::Type) => "Тип".to_string(),
+    }
+}
+
+fn certainty_label(certainty: Certainty) -> String {
+    match certainty {
+        Certainty::Known => "известен".to_string(),
+        Certainty::Inferred(confidence) => format!("выведен ({:.0}%)", confidence * 100.0),
+        Certainty::Unknown => "неизвестен".to_string(),
+    }
+}
+
+fn cost_label(cost: ResolverCost) -> &'static str {
+    match cost {
+        ResolverCost::Cheap => "дешёвый",
+        ResolverCost::Moderate => "средний",
+        ResolverCost::Expensive => "дорогой",
+    }
+}
+
+/// Название политики в настройках и что она делает
+fn policy_label(policy: ShortCircuitPolicy) -> (&'static str, &'static str) {
+    match policy {
+        ShortCircuitPolicy::FirstKnown => (
+            "first_known",
+            "опрос прекращается на первом точно известном типе",
+        ),
+        ShortCircuitPolicy::FirstResolved => (
+            "first_resolved",
+            "опрос прекращается на первом результате, отличном от неизвестного",
+        ),
+        ShortCircuitPolicy::BestConfidence => (
+            "best_confidence",
+            "опрашиваются все резолверы, выбирается результат с наибольшей уверенностью",
+        ),
+    }
+}
+
+fn assignability_label(cell: &Assignability) -> String {
+    match cell {
+        Assignability::Compatible => "✓".to_string(),
+        Assignability::Conversion(function) => function.clone(),
+        Assignability::Incompatible => "✗".to_string(),
+    }
+}
+
+const INTRODUCTION: &str = "Тип выражения определяет цепочка резолверов: каждый резолвер, \
+     шаблоны которого подходят к выражению, предлагает тип с уверенностью — тип известен, \
+     выведен с вероятностью или неизвестен. Неизвестный тип совместим с любым: постепенная \
+     типизация не сообщает об ошибке там, где тип нельзя установить статически.";
+
+const MATRIX_LEGEND: &str = "Строка — тип присваиваемого значения, столбец — тип переменной. \
+     ✓ — присваивание допустимо, `Строка(…)` — нужно явное преобразование, ✗ — несовместимо.";
+
+impl InferenceReference {
+    /// Справка в Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# Как работает вывод типов\n\n{}\n\n", INTRODUCTION);
+
+        let (policy, policy_description) = policy_label(self.policy);
+        output.push_str("## Цепочка резолверов\n\n");
+        output.push_str(&format!(
+            "Политика остановки: `{}` — {}.\n\n",
+            policy, policy_description
+        ));
+        output.push_str("| № | Резолвер | Стоимость | Шаблоны выражений |\n|---|---|---|---|\n");
+        for (index, resolver) in self.resolvers.iter().enumerate() {
+            let patterns = if resolver.patterns.is_empty() {
+                "любые".to_string()
+            } else {
+                resolver
+                    .patterns
+                    .iter()
+                    .map(|pattern| format!("`{}`", markdown_cell(pattern)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            output.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                index + 1,
+                resolver.name,
+                cost_label(resolver.cost),
+                patterns
+            ));
+        }
+        if !self.disabled.is_empty() {
+            output.push_str(&format!(
+                "\nОтключены настройками: {}.\n",
+                self.disabled.join(", ")
+            ));
+        }
+
+        output.push_str("\n## Аксиомы\n\n");
+        output.push_str("| Выражение | Правило | Тип | Уверенность | Резолвер |\n");
+        output.push_str("|---|---|---|---|---|\n");
+        for axiom in &self.axioms {
+            output.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} |\n",
+                markdown_cell(&axiom.expression),
+                axiom.rule,
+                markdown_cell(axiom.type_name.as_deref().unwrap_or("—")),
+                certainty_label(axiom.certainty),
+                axiom.resolver.as_deref().unwrap_or("—")
+            ));
+        }
+
+        output.push_str(&format!(
+            "\n## Матрица присваиваний\n\n{}\n\n",
+            MATRIX_LEGEND
+        ));
+        output.push_str(&format!(
+            "| Значение \\ Переменная | {} |\n|---|{}\n",
+            self.matrix.types.join(" | "),
+            "---|".repeat(self.matrix.types.len())
+        ));
+        for (from, row) in self.matrix.types.iter().zip(&self.matrix.cells) {
+            let cells: Vec<String> = row.iter().map(assignability_label).collect();
+            output.push_str(&format!("| **{}** | {} |\n", from, cells.join(" | ")));
+        }
+        output
+    }
+
+    /// Справка отдельной страницей HTML
+    pub fn to_html(&self) -> String {
+        let (policy, policy_description) = policy_label(self.policy);
+        let resolvers: String = self
+            .resolvers
+            .iter()
+            .enumerate()
+            .map(|(index, resolver)| {
+                let patterns = if resolver.patterns.is_empty() {
+                    "любые".to_string()
+                } else {
+                    resolver
+                        .patterns
+                        .iter()
+                        .map(|pattern| format!("<code>{}</code>", escape_html(pattern)))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    index + 1,
+                    escape_html(&resolver.name),
+                    cost_label(resolver.cost),
+                    patterns
+                )
+            })
+            .collect();
+        let disabled = if self.disabled.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "<p>Отключены настройками: {}.</p>\n",
+                escape_html(&self.disabled.join(", "))
+            )
+        };
+        let axioms: String = self
+            .axioms
+            .iter()
+            .map(|axiom| {
+                format!(
+                    "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&axiom.expression),
+                    escape_html(&axiom.rule),
+                    escape_html(axiom.type_name.as_deref().unwrap_or("—")),
+                    certainty_label(axiom.certainty),
+                    escape_html(axiom.resolver.as_deref().unwrap_or("—"))
+                )
+            })
+            .collect();
+        let header: String = self
+            .matrix
+            .types
+            .iter()
+            .map(|name| format!("<th>{}</th>", escape_html(name)))
+            .collect();
+        let rows: String = self
+            .matrix
+            .types
+            .iter()
+            .zip(&self.matrix.cells)
+            .map(|(from, row)| {
+                let cells: String = row
+                    .iter()
+                    .map(|cell| {
+                        let class = match cell {
+                            Assignability::Compatible => "compatible",
+                            Assignability::Conversion(_) => "conversion",
+                            Assignability::Incompatible => "incompatible",
+                        };
+                        format!(
+                            "<td class=\"{}\">{}</td>",
+                            class,
+                            escape_html(&assignability_label(cell))
+                        )
+                    })
+                    .collect();
+                format!("<tr><th>{}</th>{}</tr>\n", escape_html(from), cells)
+            })
+            .collect();
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="ru">
+<head>
+    <meta charset="UTF-8">
+    <title>BSL Type Browser — как работает вывод типов</title>
+    <style>
+        body {{ font-family: 'Segoe UI', Tahoma, sans-serif; background: #1e1e1e; color: #d4d4d4; }}
+        .container {{ max-width: 1100px; margin: 0 auto; padding: 20px; }}
+        h1, h2 {{ color: #569cd6; }}
+        table {{ border-collapse: collapse; margin: 10px 0; }}
+        th, td {{ border: 1px solid #3c3c3c; padding: 4px 8px; text-align: left; }}
+        th {{ color: #9cdcfe; }}
+        code {{ color: #ce9178; }}
+        .compatible {{ color: #4ec9b0; }}
+        .conversion {{ color: #dcdcaa; }}
+        .incompatible {{ color: #f48771; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <h1>📖 Как работает вывод типов</h1>
+        <p>{}</p>
+        <h2>Цепочка резолверов</h2>
+        <p>Политика остановки: <code>{}</code> — {}.</p>
+        <table>
+<tr><th>№</th><th>Резолвер</th><th>Стоимость</th><th>Шаблоны выражений</th></tr>
+{}</table>
+        {}
+        <h2>Аксиомы</h2>
+        <table>
+<tr><th>Выражение</th><th>Правило</th><th>Тип</th><th>Уверенность</th><th>Резолвер</th></tr>
+{}</table>
+        <h2>Матрица присваиваний</h2>
+        <p>{}</p>
+        <table>
+<tr><th>Значение \ Переменная</th>{}</tr>
+{}</table>
+    </div>
+</body>
+</html>
+"#,
+            INTRODUCTION,
+            policy,
+            policy_description,
+            resolvers,
+            disabled,
+            axioms,
+            escape_html(MATRIX_LEGEND).replace('`', ""),
+            header,
+            rows
+        )
+    }
+}
+
+/// Ячейка таблицы Markdown: `|` внутри ячейки экранируется
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inference_reference() {
+        let matrix = conversion_matrix();
+        let index = |name: &str| matrix.types.iter().position(|t| t == name).unwrap();
+        let (array, string, catalog) = (
+            index("Массив"),
+            index("Строка"),
+            index("СправочникСсылка.Валюты"),
+        );
+        assert_eq!(matrix.cells.len(), matrix.types.len());
+        assert_eq!(matrix.cells[array][array], Assignability::Compatible);
+        assert_eq!(
+            matrix.cells[array][string],
+            Assignability::Conversion("Строка(…)".to_string())
+        );
+        assert_eq!(matrix.cells[array][catalog], Assignability::Incompatible);
+        // Неизвестный тип совместим с любым в обе стороны
+        let unknown = index(UNKNOWN_TYPE);
+        assert!(matrix.cells[unknown]
+            .iter()
+            .all(|cell| *cell == Assignability::Compatible));
+        assert!(matrix
+            .cells
+            .iter()
+            .all(|row| row[unknown] == Assignability::Compatible));
+
+        let reference = InferenceReference {
+            resolvers: vec![ResolverEntry {
+                name: "QueryResolver".to_string(),
+                cost: ResolverCost::Expensive,
+                patterns: vec!["(?i)^Запрос|Query".to_string()],
+            }],
+            disabled: vec!["FormResolver".to_string()],
+            policy: ShortCircuitPolicy::BestConfidence,
+            axioms: vec![Axiom {
+                expression: "Новый Массив".to_string(),
+                rule: "конструктор типа платформы".to_string(),
+                type_name: Some("Массив".to_string()),
+                certainty: Certainty::Known,
+                resolver: Some("BuiltinTypeResolver".to_string()),
+            }],
+            matrix,
+        };
+        let markdown = reference.to_markdown();
+        assert!(markdown.contains("`best_confidence`"));
+        assert!(markdown.contains("| 1 | QueryResolver | дорогой | `(?i)^Запрос\\|Query` |"));
+        assert!(markdown.contains("Отключены настройками: FormResolver."));
+        assert!(markdown.contains(
+            "| `Новый Массив` | конструктор типа платформы | Массив | известен | BuiltinTypeResolver |"
+        ));
+        assert!(markdown.contains("| **Массив** | "));
+
+        let html = reference.to_html();
+        assert!(html.contains("<code>(?i)^Запрос|Query</code>"));
+        assert!(html.contains("<td class=\"conversion\">Строка(…)</td>"));
+    }
+}
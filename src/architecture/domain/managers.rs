@@ -0,0 +1,359 @@
+//! Менеджеры объектов конфигурации
+//!
+//! `Справочники.Номенклатура` — менеджер справочника: методы поиска и
+//! создания (`НайтиПоКоду`, `СоздатьЭлемент`, `Выбрать`), общие для всех
+//! справочников, плюс состав самого объекта из описания конфигурации —
+//! реквизиты и табличные части. Автодополнение после
+//! `Справочники.Номенклатура.` показывает и то и другое; без описания
+//! объекта остаются только методы менеджера.
+//!
+//! Табличные части хранятся среди свойств типа конфигурации с типом вида
+//! `СправочникТабличнаяЧасть.Номенклатура.Штрихкоды`, по которому их
+//! отличают от реквизитов; значения перечисления — с типом
+//! `ПеречислениеСсылка.Имя`.
+
+use super::{enums, predefined, CompletionItem, CompletionKind};
+use crate::unified::data::{RawTypeData, TypeSource};
+
+/// Метод менеджера; `*` в типе результата — имя объекта
+#[derive(Debug)]
+pub struct ManagerMethod {
+    pub name: &'static str,
+    pub return_type: Option<&'static str>,
+}
+
+const fn method(name: &'static str, return_type: Option<&'static str>) -> ManagerMethod {
+    ManagerMethod { name, return_type }
+}
+
+/// Коллекция менеджеров (`Справочники`) и вид её объектов
+#[derive(Debug)]
+pub struct ManagerKind {
+    pub collection: &'static str,
+    pub english: &'static str,
+    /// Категория типа конфигурации (`Catalog`)
+    pub category: &'static str,
+    /// Префикс имён типов объекта (`Справочник` в `СправочникСсылка`)
+    pub type_prefix: &'static str,
+    pub methods: &'static [ManagerMethod],
+}
+
+const GET_FORM: ManagerMethod = method("ПолучитьФорму", Some("ФормаКлиентскогоПриложения"));
+const GET_TEMPLATE: ManagerMethod = method("ПолучитьМакет", None);
+
+const CATALOG_METHODS: &[ManagerMethod] = &[
+    method("НайтиПоКоду", Some("СправочникСсылка.*")),
+    method("НайтиПоНаименованию", Some("СправочникСсылка.*")),
+    method("НайтиПоРеквизиту", Some("СправочникСсылка.*")),
+    method("СоздатьЭлемент", Some("СправочникОбъект.*")),
+    method("СоздатьГруппу", Some("СправочникОбъект.*")),
+    method("Выбрать", Some("СправочникВыборка.*")),
+    method("ВыбратьИерархически", Some("СправочникВыборка.*")),
+    method("ПустаяСсылка", Some("СправочникСсылка.*")),
+    method("ПолучитьСсылку", Some("СправочникСсылка.*")),
+    method("ПолучитьИменаПредопределенных", Some("Массив")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const DOCUMENT_METHODS: &[ManagerMethod] = &[
+    method("НайтиПоНомеру", Some("ДокументСсылка.*")),
+    method("НайтиПоРеквизиту", Some("ДокументСсылка.*")),
+    method("СоздатьДокумент", Some("ДокументОбъект.*")),
+    method("Выбрать", Some("ДокументВыборка.*")),
+    method("ПустаяСсылка", Some("ДокументСсылка.*")),
+    method("ПолучитьСсылку", Some("ДокументСсылка.*")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const ENUM_METHODS: &[ManagerMethod] = &[
+    method("ПустаяСсылка", Some("ПеречислениеСсылка.*")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const DATA_PROCESSOR_METHODS: &[ManagerMethod] = &[
+    method("Создать", Some("ОбработкаОбъект.*")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const REPORT_METHODS: &[ManagerMethod] = &[
+    method("Создать", Some("ОтчетОбъект.*")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const CHART_OF_ACCOUNTS_METHODS: &[ManagerMethod] = &[
+    method("НайтиПоКоду", Some("ПланСчетовСсылка.*")),
+    method("НайтиПоНаименованию", Some("ПланСчетовСсылка.*")),
+    method("НайтиПоРеквизиту", Some("ПланСчетовСсылка.*")),
+    method("СоздатьСчет", Some("ПланСчетовОбъект.*")),
+    method("Выбрать", Some("ПланСчетовВыборка.*")),
+    method("ВыбратьИерархически", Some("ПланСчетовВыборка.*")),
+    method("ПустаяСсылка", Some("ПланСчетовСсылка.*")),
+    method("ПолучитьСсылку", Some("ПланСчетовСсылка.*")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const CHARACTERISTIC_TYPES_METHODS: &[ManagerMethod] = &[
+    method("НайтиПоКоду", Some("ПланВидовХарактеристикСсылка.*")),
+    method("НайтиПоНаименованию", Some("ПланВидовХарактеристикСсылка.*")),
+    method("НайтиПоРеквизиту", Some("ПланВидовХарактеристикСсылка.*")),
+    method("СоздатьЭлемент", Some("ПланВидовХарактеристикОбъект.*")),
+    method("СоздатьГруппу", Some("ПланВидовХарактеристикОбъект.*")),
+    method("Выбрать", Some("ПланВидовХарактеристикВыборка.*")),
+    method("ВыбратьИерархически", Some("ПланВидовХарактеристикВыборка.*")),
+    method("ПустаяСсылка", Some("ПланВидовХарактеристикСсылка.*")),
+    method("ПолучитьСсылку", Some("ПланВидовХарактеристикСсылка.*")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const INFORMATION_REGISTER_METHODS: &[ManagerMethod] = &[
+    method("СоздатьМенеджерЗаписи", Some("РегистрСведенийМенеджерЗаписи.*")),
+    method("СоздатьНаборЗаписей", Some("РегистрСведенийНаборЗаписей.*")),
+    method("Выбрать", Some("РегистрСведенийВыборка.*")),
+    method("Получить", Some("Структура")),
+    method("ПолучитьПервое", Some("Структура")),
+    method("ПолучитьПоследнее", Some("Структура")),
+    method("СрезПервых", Some("ТаблицаЗначений")),
+    method("СрезПоследних", Some("ТаблицаЗначений")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const ACCUMULATION_REGISTER_METHODS: &[ManagerMethod] = &[
+    method("СоздатьНаборЗаписей", Some("РегистрНакопленияНаборЗаписей.*")),
+    method("Выбрать", Some("РегистрНакопленияВыборка.*")),
+    method("ВыбратьПоРегистратору", Some("РегистрНакопленияВыборка.*")),
+    method("Остатки", Some("ТаблицаЗначений")),
+    method("Обороты", Some("ТаблицаЗначений")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const ACCOUNTING_REGISTER_METHODS: &[ManagerMethod] = &[
+    method("СоздатьНаборЗаписей", Some("РегистрБухгалтерииНаборЗаписей.*")),
+    method("Выбрать", Some("РегистрБухгалтерииВыборка.*")),
+    method("ВыбратьПоРегистратору", Some("РегистрБухгалтерииВыборка.*")),
+    method("Остатки", Some("ТаблицаЗначений")),
+    method("Обороты", Some("ТаблицаЗначений")),
+    method("ОстаткиИОбороты", Some("ТаблицаЗначений")),
+    method("ДвиженияССубконто", Some("ТаблицаЗначений")),
+    GET_FORM,
+    GET_TEMPLATE,
+];
+
+const fn manager(
+    collection: &'static str,
+    english: &'static str,
+    category: &'static str,
+    type_prefix: &'static str,
+    methods: &'static [ManagerMethod],
+) -> ManagerKind {
+    ManagerKind {
+        collection,
+        english,
+        category,
+        type_prefix,
+        methods,
+    }
+}
+
+/// Коллекции менеджеров с описанными в конфигурации объектами
+pub const MANAGER_KINDS: &[ManagerKind] = &[
+    manager("Справочники", "Catalogs", "Catalog", "Справочник", CATALOG_METHODS),
+    manager("Документы", "Documents", "Document", "Документ", DOCUMENT_METHODS),
+    manager("Перечисления", "Enums", "Enum", "Перечисление", ENUM_METHODS),
+    manager("Обработки", "DataProcessors", "DataProcessor", "Обработка", DATA_PROCESSOR_METHODS),
+    manager("Отчеты", "Reports", "Report", "Отчет", REPORT_METHODS),
+    manager(
+        "ПланыСчетов",
+        "ChartsOfAccounts",
+        "ChartOfAccounts",
+        "ПланСчетов",
+        CHART_OF_ACCOUNTS_METHODS,
+    ),
+    manager(
+        "ПланыВидовХарактеристик",
+        "ChartsOfCharacteristicTypes",
+        "ChartOfCharacteristicTypes",
+        "ПланВидовХарактеристик",
+        CHARACTERISTIC_TYPES_METHODS,
+    ),
+    manager(
+        "РегистрыСведений",
+        "InformationRegisters",
+        "Register",
+        "РегистрСведений",
+        INFORMATION_REGISTER_METHODS,
+    ),
+    manager(
+        "РегистрыНакопления",
+        "AccumulationRegisters",
+        "Register",
+        "РегистрНакопления",
+        ACCUMULATION_REGISTER_METHODS,
+    ),
+    manager(
+        "РегистрыБухгалтерии",
+        "AccountingRegisters",
+        "Register",
+        "РегистрБухгалтерии",
+        ACCOUNTING_REGISTER_METHODS,
+    ),
+];
+
+const TABULAR_SECTION: &str = "ТабличнаяЧасть";
+
+impl ManagerKind {
+    /// Описание объекта этого вида в конфигурации; объект называется
+    /// русским или английским именем
+    pub fn describes(&self, raw: &RawTypeData, object: &str) -> bool {
+        let object = object.to_lowercase();
+        (raw.russian_name.to_lowercase() == object
+            || (!raw.english_name.is_empty() && raw.english_name.to_lowercase() == object))
+            && matches!(raw.source, TypeSource::Configuration { .. })
+            && raw.category_path.iter().any(|category| category == self.category)
+    }
+}
+
+/// Менеджер объекта: `Справочники.Номенклатура` → (вид, `Номенклатура`)
+pub fn manager_reference(expression: &str) -> Option<(&'static ManagerKind, String)> {
+    let (collection, object) = expression.trim().split_once('.')?;
+    let object = object.trim();
+    if object.is_empty() || object.contains('.') || object.contains('(') {
+        return None;
+    }
+    let collection = collection.trim().to_lowercase();
+    let kind = MANAGER_KINDS.iter().find(|kind| {
+        kind.collection.to_lowercase() == collection || kind.english.to_lowercase() == collection
+    })?;
+    Some((kind, object.to_string()))
+}
+
+/// Имя типа табличной части объекта конфигурации категории `category`:
+/// `СправочникТабличнаяЧасть.Номенклатура.Штрихкоды`
+pub fn tabular_section_type(category: &str, object: &str, section: &str) -> String {
+    let prefix = MANAGER_KINDS
+        .iter()
+        .find(|kind| kind.category == category)
+        .map_or("", |kind| kind.type_prefix);
+    format!("{}{}.{}.{}", prefix, TABULAR_SECTION, object, section)
+}
+
+/// Тип свойства — табличная часть
+pub fn is_tabular_section(type_name: &str) -> bool {
+    type_name
+        .split('.')
+        .next()
+        .is_some_and(|prefix| prefix.ends_with(TABULAR_SECTION))
+}
+
+/// Автодополнение после `Справочники.Номенклатура.`: методы менеджера,
+/// предопределённые элементы (`predefined` — их список), затем реквизиты и
+/// табличные части из описания объекта; `prefix` — начало имени без учёта
+/// регистра
+pub fn manager_completions(
+    kind: &ManagerKind,
+    object: &str,
+    configuration: Option<&RawTypeData>,
+    predefined: Option<&RawTypeData>,
+    prefix: &str,
+) -> Vec<CompletionItem> {
+    let prefix = prefix.trim().to_lowercase();
+    let matches = |name: &str| name.to_lowercase().starts_with(&prefix);
+
+    let mut out: Vec<CompletionItem> = kind
+        .methods
+        .iter()
+        .filter(|m| matches(m.name))
+        .map(|m| CompletionItem {
+            label: m.name.to_string(),
+            detail: Some(match m.return_type {
+                Some(return_type) => {
+                    format!("Метод менеджера → {}", return_type.replace('*', object))
+                }
+                None => "Метод менеджера".to_string(),
+            }),
+            documentation: None,
+            kind: CompletionKind::Method,
+            insert_text: format!("{}()", m.name),
+        })
+        .collect();
+
+    let items = predefined
+        .map(|raw| raw.properties.as_slice())
+        .unwrap_or_default();
+    for item in items.iter().filter(|p| matches(&p.name)) {
+        out.push(CompletionItem {
+            label: item.name.clone(),
+            detail: Some(format!(
+                "{}: {}",
+                predefined::PREDEFINED_DETAIL,
+                item.type_name
+            )),
+            documentation: (!item.description.is_empty()).then(|| item.description.clone()),
+            kind: CompletionKind::Enum,
+            insert_text: item.name.clone(),
+        });
+    }
+
+    let properties = configuration.map(|raw| raw.properties.as_slice()).unwrap_or_default();
+    for property in properties.iter().filter(|p| matches(&p.name)) {
+        if configuration.is_some_and(|raw| enums::is_enum_value(raw, property)) {
+            out.push(CompletionItem {
+                label: property.name.clone(),
+                detail: Some(format!(
+                    "{}: {}",
+                    enums::ENUM_VALUE_DETAIL,
+                    property.type_name
+                )),
+                documentation: None,
+                kind: CompletionKind::Enum,
+                insert_text: property.name.clone(),
+            });
+            continue;
+        }
+        let detail = if is_tabular_section(&property.type_name) {
+            "Табличная часть".to_string()
+        } else if property.type_name.is_empty() {
+            "Реквизит".to_string()
+        } else {
+            format!("Реквизит: {}", property.type_name)
+        };
+        out.push(CompletionItem {
+            label: property.name.clone(),
+            detail: Some(detail),
+            documentation: (!property.description.is_empty())
+                .then(|| property.description.clone()),
+            kind: CompletionKind::Property,
+            insert_text: property.name.clone(),
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manager_reference() {
+        let (kind, object) = manager_reference("Справочники.Номенклатура").unwrap();
+        assert_eq!((kind.category, object.as_str()), ("Catalog", "Номенклатура"));
+        let (kind, _) = manager_reference("InformationRegisters.Курсы").unwrap();
+        assert_eq!(kind.type_prefix, "РегистрСведений");
+        assert!(manager_reference("Справочники").is_none());
+        assert!(manager_reference("Справочники.Номенклатура.НайтиПоКоду()").is_none());
+        assert!(manager_reference("Массив.Количество").is_none());
+
+        let section = tabular_section_type("Document", "Заказ", "Товары");
+        assert_eq!(section, "ДокументТабличнаяЧасть.Заказ.Товары");
+        assert!(is_tabular_section(&section));
+        assert!(!is_tabular_section("СправочникСсылка.Номенклатура"));
+    }
+}
@@ -3,18 +3,68 @@
 //! Центральная бизнес-логика для разрешения типов BSL
 //! Принципы: Single Responsibility, правильные абстракции, честная неопределённость
 
-use crate::domain::types::PrimitiveType;
+pub mod assignments;
+pub mod documents;
+pub mod enums;
+pub mod external_sources;
+pub mod forms;
+pub mod globals;
+pub mod inference_reference;
+pub mod managers;
+pub mod narrowing;
+pub mod objects;
+pub mod predefined;
+pub mod queries;
+pub mod registers;
+pub mod registry;
+pub mod signatures;
+
+pub use assignments::{
+    await_operand, call_target, function_returns, reaching_assignments, AssignedValue,
+    FunctionReturns, VariableAssignments,
+};
+pub use documents::{created_document_object, object_module_document, DocumentType};
+pub use enums::{enum_comparisons, EnumValueIssue, EnumValueRef};
+pub use forms::FormContextProvider;
+pub use globals::{GlobalModuleKind, GlobalScope, GlobalVariable};
+pub use inference_reference::{
+    Assignability, Axiom, ConversionMatrix, InferenceReference, ResolverEntry,
+};
+pub use managers::{manager_completions, manager_reference};
+pub use narrowing::{type_guards_at, TypeGuard};
+pub use objects::{object_module, ObjectType};
+pub use predefined::PredefinedRef;
+pub use queries::{
+    query_columns, query_parameters, ColumnOrigin, QueryColumn, QueryParameter,
+    QueryParameterIssue, QueryParameterIssueKind, QueryStage,
+};
+pub use registers::{created_record_type, record_set_element, RecordType};
+pub use registry::{
+    RegisteredResolver, ResolverCapabilities, ResolverCost, ResolverPlugins, ResolverRegistry,
+};
+pub use signatures::{FunctionSignature, ParameterSignature, SignatureTable};
+
+use crate::domain::types::{PrimitiveType, SpecialType};
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
+use crate::core::compilation_context::method_context;
 use crate::data::loaders::config_parser_guided_discovery::ConfigurationGuidedParser;
+use crate::data::loaders::metadata_values::{referenced_value, MetadataValueIndex};
 use crate::unified::data::stats::RepositoryStats;
-use crate::unified::data::{RawTypeData, TypeRepository, TypeSource};
-use crate::domain::types::{Certainty, ConcreteType, FacetKind, ResolutionResult, TypeResolution};
+use crate::unified::data::{
+    composite_type_names, NameCandidates, NamePrecedence, PrecedenceTypeRepository, RawTypeData,
+    RepositoryChange, TypeRepository, TypeSource,
+};
+use crate::domain::types::{
+    Certainty, ConcreteType, FacetKind, PlatformType, ResolutionResult, TypeResolution,
+};
 use crate::parsing::bsl::tree_sitter_adapter::TreeSitterAdapter;
 
 /// Центральный сервис разрешения типов
@@ -25,14 +75,103 @@ pub struct TypeResolutionService {
     repository: Arc<dyn TypeRepository>,
 
     /// Резолверы для разных типов выражений
-    // Храним резолверы как TypeResolverAny для возможности downcast в тестах
-    resolvers: Vec<Box<dyn TypeResolverAny>>,
+    resolvers: ResolverRegistry,
 
     /// Кеш разрешений для производительности
     cache: Arc<RwLock<HashMap<String, CachedTypeResolution>>>,
 
     /// Статистика работы сервиса
     metrics: Arc<RwLock<ResolutionMetrics>>,
+
+    /// Когда цепочка резолверов прекращает опрос
+    chain_policy: ShortCircuitPolicy,
+
+    /// Приоритет источников при совпадении имён типов
+    name_precedence: NamePrecedence,
+
+    /// Сигнатуры и возвращаемые типы функций проекта
+    signatures: Arc<RwLock<SignatureTable>>,
+
+    /// Константы и параметры сеанса конфигурации
+    metadata_values: Arc<RwLock<MetadataValueIndex>>,
+
+    /// Экспортные переменные и методы модулей приложения и сеанса
+    globals: Arc<RwLock<GlobalScope>>,
+}
+
+/// Политика остановки цепочки резолверов
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortCircuitPolicy {
+    /// Остановиться на первом точно известном типе
+    #[default]
+    FirstKnown,
+    /// Остановиться на первом результате, отличном от неизвестного
+    FirstResolved,
+    /// Опросить все резолверы и выбрать результат с наибольшей уверенностью
+    BestConfidence,
+}
+
+impl ShortCircuitPolicy {
+    fn should_stop(&self, resolution: &TypeResolution) -> bool {
+        match self {
+            ShortCircuitPolicy::FirstKnown | ShortCircuitPolicy::BestConfidence => {
+                resolution.certainty == Certainty::Known
+            }
+            ShortCircuitPolicy::FirstResolved => resolution.certainty != Certainty::Unknown,
+        }
+    }
+}
+
+impl std::str::FromStr for ShortCircuitPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "first_known" => Ok(ShortCircuitPolicy::FirstKnown),
+            "first_resolved" => Ok(ShortCircuitPolicy::FirstResolved),
+            "best_confidence" => Ok(ShortCircuitPolicy::BestConfidence),
+            other => Err(format!(
+                "неизвестная политика '{}' (first_known, first_resolved, best_confidence)",
+                other
+            )),
+        }
+    }
+}
+
+/// Настройка цепочки резолверов
+///
+/// Резолверы называются по имени типа (`BuiltinTypeResolver`,
+/// `ExpressionResolver`, ...), регистр не важен. Резолверы из `order`
+/// опрашиваются первыми в указанном порядке, остальные — после них в
+/// исходном порядке.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResolverChainConfig {
+    /// Порядок опроса резолверов
+    pub order: Vec<String>,
+    /// Отключённые резолверы
+    pub disabled: Vec<String>,
+    /// Политика остановки
+    pub policy: ShortCircuitPolicy,
+}
+
+impl ResolverChainConfig {
+    fn is_disabled(&self, name: &str) -> bool {
+        self.disabled.iter().any(|d| same_resolver_name(d, name))
+    }
+
+    fn priority(&self, name: &str) -> usize {
+        self.order
+            .iter()
+            .position(|o| same_resolver_name(o, name))
+            .unwrap_or(self.order.len())
+    }
+}
+
+/// Имена резолверов совпадают без учёта регистра (в том числе кириллицы)
+fn same_resolver_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
 }
 
 /// Кешированное разрешение типа
@@ -53,6 +192,8 @@ pub struct ResolutionMetrics {
     pub average_resolution_time_ms: f64,
     pub successful_resolutions: u64,
     pub failed_resolutions: u64,
+    /// Разрешения, прерванные по таймауту резолвера
+    pub timed_out_resolutions: u64,
 }
 
 /// Контекст для разрешения типов
@@ -66,9 +207,50 @@ pub struct TypeContext {
     pub current_facet: Option<FacetKind>,
 }
 
+impl TypeContext {
+    /// Контекст позиции в файле; фасет — по виду модуля: в модуле объекта
+    /// `Object`, в модуле менеджера `Manager`
+    pub fn at_position(file_path: &str, line: u32, column: u32) -> Self {
+        let current_facet = if object_module(file_path).is_some()
+            || object_module_document(file_path).is_some()
+        {
+            Some(FacetKind::Object)
+        } else if file_path.ends_with("ManagerModule.bsl") {
+            Some(FacetKind::Manager)
+        } else {
+            None
+        };
+        Self {
+            file_path: Some(file_path.to_string()),
+            line: Some(line),
+            column: Some(column),
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet,
+        }
+    }
+
+    /// Тип локальной переменной (имя без учёта регистра)
+    pub fn local_variable(&self, name: &str) -> Option<&TypeResolution> {
+        let name = name.trim().to_lowercase();
+        self.local_variables
+            .iter()
+            .find(|(local, _)| local.to_lowercase() == name)
+            .map(|(_, resolution)| resolution)
+    }
+}
+
 /// Абстракция резолвера типов
 #[async_trait]
 pub trait TypeResolver: Send + Sync {
+    /// Имя резолвера для настройки цепочки и отладки (по умолчанию — имя
+    /// типа без пути и параметров: `DeadlineResolver<R>` → `DeadlineResolver`)
+    fn name(&self) -> &str {
+        let full = std::any::type_name::<Self>();
+        let path = full.split('<').next().unwrap_or(full);
+        path.rsplit("::").next().unwrap_or(path)
+    }
+
     /// Может ли резолвер обработать данное выражение
     fn can_resolve(&self, expression: &str) -> bool;
 
@@ -78,7 +260,7 @@ pub trait TypeResolver: Send + Sync {
         _expression: &str,
         _context: &TypeContext,
         _repository: &dyn TypeRepository,
-    ) -> Result<TypeResolution> {
+    ) -> crate::Result<TypeResolution> {
         // TODO: Реализовать разрешение конфигурационных типов
         Ok(TypeResolution {
            certainty: Certainty::Inferred(0.8), // Не 100% уверены без полной конфигурации
@@ -95,7 +277,7 @@ pub trait TypeResolver: Send + Sync {
         _prefix: &str,
         _context: &TypeContext,
         _repository: &dyn TypeRepository,
-    ) -> Result<Vec<CompletionItem>> {
+    ) -> crate::Result<Vec<CompletionItem>> {
         // TODO: Реализовать автодополнение конфигурационных типов
         Ok(Vec::new())
     }
@@ -129,6 +311,72 @@ impl BslCodeResolver {
             }
         }
     }
+
+    /// Типы локальных переменных в позиции по присваиваниям в тексте
+    /// метода. Значения из разных веток `Если` объединяются
+    /// ([`TypeResolution::join_all`]), результат вызова функции проекта
+    /// берётся из таблицы сигнатур, значение константы или параметра
+    /// сеанса — из их объявления; переменные, у которых хоть одно значение
+    /// не определить, пропускаются.
+    pub async fn local_types(
+        text: &str,
+        line: u32,
+        column: u32,
+        file: Option<&str>,
+        signatures: &SignatureTable,
+        metadata_values: &MetadataValueIndex,
+        repository: &dyn TypeRepository,
+    ) -> HashMap<String, TypeResolution> {
+        let mut locals = HashMap::new();
+        for assignments in reaching_assignments(text, line, column) {
+            let resolution = Self::assigned_type(
+                &assignments.values,
+                file,
+                signatures,
+                metadata_values,
+                repository,
+            )
+            .await;
+            if let Some(resolution) = resolution {
+                locals.insert(assignments.variable, resolution);
+            }
+        }
+        locals
+    }
+
+    /// Объединённый тип присвоенных значений; `None`, если хоть одно
+    /// значение не определить
+    pub async fn assigned_type(
+        values: &[AssignedValue],
+        file: Option<&str>,
+        signatures: &SignatureTable,
+        metadata_values: &MetadataValueIndex,
+        repository: &dyn TypeRepository,
+    ) -> Option<TypeResolution> {
+        let resolver = ExpressionResolver::new();
+        let mut branches = Vec::new();
+        for value in values {
+            let resolution = match value {
+                AssignedValue::Type(name) => resolver.resolve_type_by_name(name, repository).await,
+                AssignedValue::Call { module, function } => {
+                    signatures.call_type(module.as_deref(), function, file)
+                }
+                AssignedValue::Awaited { module, function } => {
+                    signatures.awaited_type(module.as_deref(), function, file)
+                }
+                AssignedValue::MetadataValue(reference) => match metadata_values.find(reference) {
+                    Some(value) => resolver.resolve_declared_types(&value.types, repository).await,
+                    None => None,
+                },
+                AssignedValue::Query { text, stage } => {
+                    Some(resolver.resolve_query_value(text, *stage, repository).await)
+                }
+                AssignedValue::Unknown => None,
+            };
+            branches.push(resolution?);
+        }
+        (!branches.is_empty()).then(|| TypeResolution::join_all(branches))
+    }
 }
 
 #[async_trait]
@@ -145,7 +393,7 @@ impl TypeResolver for BslCodeResolver {
         _expression: &str,
         _context: &TypeContext,
         _repository: &dyn TypeRepository,
-    ) -> Result<TypeResolution> {
+    ) -> crate::Result<TypeResolution> {
         let parser_opt = self.parser.read().await;
 
         if let Some(_parser) = parser_opt.as_ref() {
@@ -169,7 +417,7 @@ impl TypeResolver for BslCodeResolver {
         _prefix: &str,
         _context: &TypeContext,
         _repository: &dyn TypeRepository,
-    ) -> Result<Vec<CompletionItem>> {
+    ) -> crate::Result<Vec<CompletionItem>> {
         // TODO: Реализовать автодополнение на основе BSL парсинга
         Ok(Vec::new())
     }
@@ -205,7 +453,7 @@ impl TypeResolver for BuiltinTypeResolver {
         expression: &str,
         _context: &TypeContext,
         _repository: &dyn TypeRepository,
-    ) -> Result<TypeResolution> {
+    ) -> crate::Result<TypeResolution> {
         let primitive_type = if expression.contains("Строка") || expression.contains("String")
         {
             crate::domain::types::PrimitiveType::String
@@ -234,7 +482,7 @@ impl TypeResolver for BuiltinTypeResolver {
         prefix: &str,
         _context: &TypeContext,
         _repository: &dyn TypeRepository,
-    ) -> Result<Vec<CompletionItem>> {
+    ) -> crate::Result<Vec<CompletionItem>> {
         let builtins = [
             ("Строка", "String"),
             ("Число", "Number"),
@@ -283,7 +531,11 @@ impl ExpressionResolver {
     }
 
     fn primitive_from_name(name: &str) -> Option<ConcreteType> {
-        let n = name.trim();
+        // Квалификаторы из описания метаданных: `Строка(25)`, `Число(15, 2)`
+        let n = name.split('(').next().unwrap_or_default().trim();
+        if n.eq_ignore_ascii_case("ДатаВремя") {
+            return Some(ConcreteType::Primitive(PrimitiveType::Date));
+        }
         if n.eq_ignore_ascii_case("Строка") || n.eq_ignore_ascii_case("String") {
             return Some(ConcreteType::Primitive(PrimitiveType::String));
         }
@@ -296,6 +548,12 @@ impl ExpressionResolver {
         if n.eq_ignore_ascii_case("Дата") || n.eq_ignore_ascii_case("Date") {
             return Some(ConcreteType::Primitive(PrimitiveType::Date));
         }
+        if n.to_lowercase() == "неопределено" || n.eq_ignore_ascii_case("Undefined") {
+            return Some(ConcreteType::Special(SpecialType::Undefined));
+        }
+        if n.eq_ignore_ascii_case("Null") {
+            return Some(ConcreteType::Special(SpecialType::Null));
+        }
         None
     }
 
@@ -303,6 +561,23 @@ impl ExpressionResolver {
         &self,
         name: &str,
         repository: &dyn TypeRepository,
+    ) -> Option<TypeResolution> {
+        // Составной тип реквизита: `СправочникСсылка.Валюты,Строка(10)`
+        let parts = composite_type_names(name);
+        if parts.len() > 1 {
+            let mut resolutions = Vec::new();
+            for part in parts {
+                resolutions.push(self.resolve_single_type(part, repository).await?);
+            }
+            return Some(TypeResolution::join_all(resolutions));
+        }
+        self.resolve_single_type(name, repository).await
+    }
+
+    async fn resolve_single_type(
+        &self,
+        name: &str,
+        repository: &dyn TypeRepository,
     ) -> Option<TypeResolution> {
         if let Some(ct) = Self::primitive_from_name(name) {
             return Some(TypeResolution::known(ct));
@@ -310,6 +585,15 @@ impl ExpressionResolver {
         if name.is_empty() {
             return None;
         }
+        if let Some(record) = RecordType::parse(name) {
+            return Some(self.resolve_record_type(&record, repository).await);
+        }
+        if let Some(document) = DocumentType::parse(name) {
+            return Some(self.resolve_document_type(&document, repository).await);
+        }
+        if let Some(object) = ObjectType::parse(name) {
+            return Some(self.resolve_object_type(&object, repository).await);
+        }
         let candidates = repository.search_types(name).await.ok()?;
         for raw in candidates {
             if raw.russian_name == name || raw.english_name == name {
@@ -321,6 +605,293 @@ impl ExpressionResolver {
         }
         None
     }
+
+    /// Менеджер записи, набор записей или запись регистра с полями из
+    /// описания регистра в конфигурации (без описания — только члены
+    /// платформы)
+    async fn resolve_record_type(
+        &self,
+        record: &RecordType,
+        repository: &dyn TypeRepository,
+    ) -> TypeResolution {
+        let candidates = repository
+            .search_types(&record.register)
+            .await
+            .unwrap_or_default();
+        let found: Vec<&RawTypeData> = candidates
+            .iter()
+            .filter(|raw| {
+                raw.russian_name == record.register
+                    && matches!(raw.source, TypeSource::Configuration { .. })
+                    && raw.category_path.iter().any(|category| category == "Register")
+            })
+            .collect();
+        // Одноимённые регистры разных видов различаются тегом в категориях
+        let tag = record.kind.metadata_tag();
+        let register = found
+            .iter()
+            .find(|raw| raw.category_path.iter().any(|category| category == tag))
+            .or(found.first())
+            .copied();
+        let fields = register.map(|raw| raw.properties.as_slice()).unwrap_or_default();
+        let mut resolution = registers::record_type_resolution(record, fields);
+        if register.is_none() {
+            resolution.certainty = Certainty::Inferred(0.6);
+        }
+        resolution
+    }
+
+    /// Объект документа с реквизитами из описания документа или коллекция
+    /// его движений из репозитория (без описания — только члены платформы)
+    async fn resolve_document_type(
+        &self,
+        document: &DocumentType,
+        repository: &dyn TypeRepository,
+    ) -> TypeResolution {
+        // Имя коллекции движений с префиксом однозначно; категорию
+        // платформенного типа репозиторий не сохраняет, поэтому по ней
+        // отличаем только документ от одноимённых объектов конфигурации
+        let (name, category) = match document {
+            DocumentType::Object(name) => (name.clone(), Some("Document")),
+            DocumentType::Movements(_) => (document.type_name(), None),
+        };
+        let candidates = repository.search_types(&name).await.unwrap_or_default();
+        let found = candidates.iter().find(|raw| {
+            raw.russian_name == name
+                && category.is_none_or(|category| raw.category_path.iter().any(|c| c == category))
+        });
+        let mut resolution = match (document, found) {
+            (DocumentType::Object(name), found) => documents::document_object_resolution(
+                name,
+                found.map(|raw| raw.properties.as_slice()).unwrap_or_default(),
+            ),
+            (DocumentType::Movements(_), Some(raw)) => TypeResolution::from_raw_data(raw),
+            (DocumentType::Movements(_), None) => {
+                TypeResolution::known(ConcreteType::Platform(PlatformType {
+                    name: name.clone(),
+                    methods: Vec::new(),
+                    properties: Vec::new(),
+                }))
+            }
+        };
+        if found.is_none() {
+            resolution.certainty = Certainty::Inferred(0.6);
+        }
+        resolution
+    }
+
+    /// Объект справочника, плана, обработки или отчёта с реквизитами и
+    /// табличными частями из описания (без описания — только члены
+    /// платформы)
+    async fn resolve_object_type(
+        &self,
+        object: &ObjectType,
+        repository: &dyn TypeRepository,
+    ) -> TypeResolution {
+        let candidates = repository
+            .search_types(&object.object)
+            .await
+            .unwrap_or_default();
+        let found = candidates
+            .iter()
+            .find(|raw| object.kind().describes(raw, &object.object));
+        let attributes = found.map(|raw| raw.properties.as_slice()).unwrap_or_default();
+        let mut resolution = objects::object_resolution(object, attributes);
+        if found.is_none() {
+            resolution.certainty = Certainty::Inferred(0.6);
+        }
+        resolution
+    }
+
+    /// Значение перечисления — ссылка на перечисление из описания
+    /// конфигурации; значения, которого в описании нет, не существует. Без
+    /// описания — тип по имени с выведенной уверенностью.
+    async fn resolve_enum_value(
+        &self,
+        reference: &EnumValueRef,
+        repository: &dyn TypeRepository,
+    ) -> TypeResolution {
+        let candidates = repository
+            .search_types(&reference.enumeration)
+            .await
+            .unwrap_or_default();
+        let found = candidates
+            .iter()
+            .find(|raw| enums::enum_kind().describes(raw, &reference.enumeration));
+        let Some(raw) = found else {
+            return TypeResolution {
+                certainty: Certainty::Inferred(0.6),
+                ..TypeResolution::known(ConcreteType::Platform(PlatformType {
+                    name: reference.type_name(),
+                    methods: Vec::new(),
+                    properties: Vec::new(),
+                }))
+            };
+        };
+        let values = enums::enum_values(raw);
+        let value = reference.value.to_lowercase();
+        if !values.is_empty() && !values.iter().any(|v| v.to_lowercase() == value) {
+            return TypeResolution::unknown();
+        }
+        let mut resolution = TypeResolution::from_raw_data(raw);
+        resolution.certainty = Certainty::Known;
+        resolution.active_facet = Some(FacetKind::Reference);
+        resolution
+    }
+
+    /// Предопределённый элемент — ссылка на объект; элемента, которого нет
+    /// в списке предопределённых, не существует. Без списка элементов тип
+    /// выводится по имени с пониженной уверенностью (кроме `ПустаяСсылка`).
+    async fn resolve_predefined_value(
+        &self,
+        reference: &PredefinedRef,
+        repository: &dyn TypeRepository,
+    ) -> TypeResolution {
+        let list_name = predefined::predefined_type_name(reference.kind, &reference.object);
+        let candidates = repository
+            .search_types(&list_name)
+            .await
+            .unwrap_or_default();
+        let list = candidates.iter().find(|raw| raw.russian_name == list_name);
+        if list.is_some_and(|raw| !predefined::has_item(raw, &reference.item))
+            && !reference.is_empty_reference()
+        {
+            return TypeResolution::unknown();
+        }
+        let type_name = reference.reference_type();
+        let certainty = if list.is_none() && !reference.is_empty_reference() {
+            Certainty::Inferred(0.6)
+        } else {
+            Certainty::Known
+        };
+        match self
+            .resolve_declared_types(std::slice::from_ref(&type_name), repository)
+            .await
+        {
+            Some(resolution) => TypeResolution {
+                certainty,
+                ..resolution
+            },
+            None => TypeResolution {
+                certainty: Certainty::Inferred(0.6),
+                ..TypeResolution::known(ConcreteType::Platform(PlatformType {
+                    name: type_name,
+                    methods: Vec::new(),
+                    properties: Vec::new(),
+                }))
+            },
+        }
+    }
+
+    /// Запрос или его результат — тип платформы; выборка — с колонками,
+    /// выведенными из текста запроса по описаниям объектов конфигурации
+    async fn resolve_query_value(
+        &self,
+        text: &str,
+        stage: QueryStage,
+        repository: &dyn TypeRepository,
+    ) -> TypeResolution {
+        if stage != QueryStage::Selection {
+            return match self.resolve_type_by_name(stage.type_name(), repository).await {
+                Some(resolution) => resolution,
+                None => TypeResolution::known(ConcreteType::Platform(PlatformType {
+                    name: stage.type_name().to_string(),
+                    methods: Vec::new(),
+                    properties: Vec::new(),
+                })),
+            };
+        }
+        let mut columns = Vec::new();
+        for column in query_columns(text).unwrap_or_default() {
+            let type_name = match column.origin {
+                ColumnOrigin::Field { table, path } => self
+                    .query_field_type(&table, &path, repository)
+                    .await
+                    .unwrap_or_default(),
+                ColumnOrigin::Type(name) => name,
+                ColumnOrigin::Unknown => String::new(),
+            };
+            columns.push((column.name, type_name));
+        }
+        TypeResolution {
+            // Колонки выведены из текста запроса, а не объявлены
+            certainty: Certainty::Inferred(0.8),
+            ..queries::selection_resolution(&columns)
+        }
+    }
+
+    /// Тип поля таблицы запроса; звенья пути после первого — поля объекта,
+    /// на который ссылается предыдущее (`Номенклатура.Родитель.Наименование`)
+    async fn query_field_type(
+        &self,
+        table: &queries::QueryTable,
+        path: &[String],
+        repository: &dyn TypeRepository,
+    ) -> Option<String> {
+        let mut table = table.clone();
+        let mut type_name: Option<String> = None;
+        for field in path {
+            if let Some(reference) = &type_name {
+                table = queries::reference_table(reference)?;
+            }
+            let candidates = repository.search_types(&table.object).await.unwrap_or_default();
+            let properties = candidates
+                .iter()
+                .find(|raw| table.describes(raw))
+                .map(|raw| raw.properties.as_slice())
+                .unwrap_or_default();
+            type_name = Some(table.field_type(properties, field)?);
+        }
+        type_name
+    }
+
+    /// Тип значения по объявленным в метаданных именам типов; ссылка
+    /// (`СправочникСсылка.Валюты`) — объект конфигурации с фасетом ссылки,
+    /// несколько имён (или составной тип реквизита) — объединение. `None`,
+    /// если ни одно имя не найдено.
+    async fn resolve_declared_types(
+        &self,
+        type_names: &[String],
+        repository: &dyn TypeRepository,
+    ) -> Option<TypeResolution> {
+        let mut resolutions = Vec::new();
+        let names = type_names
+            .iter()
+            .flat_map(|name| composite_type_names(name));
+        for name in names {
+            if let Some(resolution) = self.resolve_type_by_name(name, repository).await {
+                resolutions.push(resolution);
+                continue;
+            }
+            let Some((kind, object)) = name.split_once('.') else {
+                continue;
+            };
+            if !kind.ends_with("Ссылка") {
+                continue;
+            }
+            let candidates = repository.search_types(object).await.unwrap_or_default();
+            let configuration = candidates.iter().find(|raw| {
+                raw.russian_name == object && matches!(raw.source, TypeSource::Configuration { .. })
+            });
+            if let Some(raw) = configuration {
+                let mut resolution = TypeResolution::from_raw_data(raw);
+                resolution.active_facet = Some(FacetKind::Reference);
+                resolutions.push(resolution);
+            }
+        }
+        // Единственный тип сохраняет фасет и файл описания, которые
+        // объединение отбрасывает
+        let resolution = match resolutions.len() {
+            0 => return None,
+            1 => resolutions.remove(0),
+            _ => TypeResolution::join_all(resolutions),
+        };
+        // Тип объявлен в конфигурации, а не выведен
+        Some(TypeResolution {
+            certainty: Certainty::Known,
+            ..resolution
+        })
+    }
 }
 
 #[async_trait]
@@ -333,9 +904,26 @@ impl TypeResolver for ExpressionResolver {
     async fn resolve(
         &self,
         expression: &str,
-        _context: &TypeContext,
+        context: &TypeContext,
         repository: &dyn TypeRepository,
-    ) -> Result<TypeResolution> {
+    ) -> crate::Result<TypeResolution> {
+        if let Some(reference) = PredefinedRef::from_value_call(expression) {
+            if reference.is_enum_value() {
+                let value = EnumValueRef {
+                    enumeration: reference.object,
+                    value: reference.item,
+                };
+                return Ok(self.resolve_enum_value(&value, repository).await);
+            }
+            return Ok(self.resolve_predefined_value(&reference, repository).await);
+        }
+        if let Some(reference) = EnumValueRef::parse(expression) {
+            return Ok(self.resolve_enum_value(&reference, repository).await);
+        }
+        if let Some(reference) = PredefinedRef::parse(expression) {
+            return Ok(self.resolve_predefined_value(&reference, repository).await);
+        }
+
         // Базовый разбор точечных выражений: Base.Segment1.Segment2...
         let mut parts = expression
             .split('.')
@@ -346,10 +934,16 @@ impl TypeResolver for ExpressionResolver {
             None => return Ok(TypeResolution::unknown()),
         };
 
-        // Разрешаем базовый тип
-        let mut current = match self.resolve_type_by_name(base, repository).await {
-            Some(res) => res,
-            None => return Ok(TypeResolution::unknown()),
+        // Разрешаем базовый тип: сначала локальная переменная (в том числе
+        // уточнённая проверкой ТипЗнч), затем тип по имени; коллекция
+        // `ВнешниеИсточникиДанных` — тип её менеджера
+        let base_type = external_sources::collection_type(base).unwrap_or(base);
+        let mut current = match context.local_variable(base) {
+            Some(local) => local.clone(),
+            None => match self.resolve_type_by_name(base_type, repository).await {
+                Some(res) => res,
+                None => return Ok(TypeResolution::unknown()),
+            },
         };
 
         // Навигация по свойствам/методам
@@ -385,7 +979,10 @@ impl TypeResolver for ExpressionResolver {
                 .iter()
                 .find(|p| p.name.to_lowercase() == name.to_lowercase());
             if let Some(p) = prop_opt {
-                if let Some(next) = self.resolve_type_by_name(&p.type_name, repository).await {
+                // Ссылочный тип свойства (`СправочникСсылка.Валюты`) — объект
+                // конфигурации, составной — объединение
+                let declared = std::slice::from_ref(&p.type_name);
+                if let Some(next) = self.resolve_declared_types(declared, repository).await {
                     current = TypeResolution {
                         certainty: Certainty::Inferred(0.9),
                         ..next
@@ -418,7 +1015,7 @@ impl TypeResolver for ExpressionResolver {
         prefix: &str,
         _context: &TypeContext,
         repository: &dyn TypeRepository,
-    ) -> Result<Vec<CompletionItem>> {
+    ) -> crate::Result<Vec<CompletionItem>> {
         let mut out = Vec::new();
         // Если нет точки — предлагаем базовые типы по первому сегменту
         if !prefix.contains('.') {
@@ -453,6 +1050,23 @@ impl TypeResolver for ExpressionResolver {
         let last = segs.pop().unwrap_or("").trim();
         let base_expr = segs.join(".");
 
+        // Менеджер объекта конфигурации: методы менеджера, реквизиты и
+        // табличные части из описания объекта
+        if let Some((kind, object)) = manager_reference(&base_expr) {
+            let candidates = repository.search_types(&object).await.unwrap_or_default();
+            let configuration = candidates.iter().find(|raw| kind.describes(raw, &object));
+            let list_name = predefined::predefined_type_name(kind, &object);
+            let list = repository
+                .search_types(&list_name)
+                .await
+                .unwrap_or_default();
+            let predefined = list.iter().find(|raw| raw.russian_name == list_name);
+            out = manager_completions(kind, &object, configuration, predefined, last);
+            out.sort_by(|a, b| a.label.cmp(&b.label));
+            out.dedup_by(|a, b| a.label == b.label);
+            return Ok(out);
+        }
+
         // Разрешаем тип до последней точки
         let resolved = self
             .resolve(&base_expr, _context, repository)
@@ -498,132 +1112,1966 @@ impl TypeResolver for ExpressionResolver {
 
 // === TYPE CHECKER SERVICE (минимальный) ===
 
-/// Минимальный сервис проверки совместимости типов (Domain)
-pub struct TypeCheckerService;
+impl TypeResolutionService {
+    /// Настроить порядок, состав и политику остановки цепочки резолверов
+    pub fn with_chain_config(mut self, config: &ResolverChainConfig) -> Self {
+        self.resolvers.configure(config);
+        self.chain_policy = config.policy;
+        self
+    }
 
-impl TypeCheckerService {
-    pub fn new() -> Self {
-        Self
+    /// Задать приоритет источников при совпадении имён: резолверы получают
+    /// репозиторий, выдающий типы победившего источника первыми
+    pub fn with_name_precedence(mut self, precedence: NamePrecedence) -> Self {
+        self.repository = Arc::new(PrecedenceTypeRepository::new(
+            self.repository.clone(),
+            precedence.clone(),
+        ));
+        self.name_precedence = precedence;
+        self
     }
 
-    /// Базовая проверка присваивания: конкретные типы должны совпадать по дисриминатору
-    pub fn is_assignment_compatible(&self, from: &TypeResolution, to: &TypeResolution) -> bool {
-        match (&from.result, &to.result) {
-            (ResolutionResult::Concrete(cf), ResolutionResult::Concrete(ct)) => {
-                std::mem::discriminant(cf) == std::mem::discriminant(ct)
-            }
-            // Разрешаем неизвестные/динамические на данном этапе
-            _ => true,
-        }
+    /// Все типы с именем `name` в порядке приоритета источников
+    pub async fn name_candidates(&self, name: &str) -> crate::Result<NameCandidates> {
+        let types = self.repository.search_types(name).await?;
+        Ok(self.name_precedence.candidates(name, types))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::unified::data::{InMemoryTypeRepository, ParseMetadata, TypeSource};
+    /// Подключить сторонний резолвер. Он встаёт в цепочку согласно классу
+    /// стоимости и настройкам цепочки и получает те же обёртки (таймаут);
+    /// экземпляр доступен через [`resolver`](Self::resolver).
+    pub fn with_resolver<R: TypeResolver + 'static>(
+        mut self,
+        resolver: R,
+        capabilities: ResolverCapabilities,
+    ) -> Self {
+        self.resolvers.register_with(resolver, capabilities);
+        self
+    }
 
-    #[tokio::test]
-    async fn test_type_resolution_service() {
-        // Создаём репозиторий с тестовыми данными
-        let repo = Arc::new(InMemoryTypeRepository::new());
+    /// Подключить сторонние резолверы из настроек системы
+    pub fn with_resolver_plugins(mut self, plugins: &ResolverPlugins) -> Self {
+        plugins.install(&mut self.resolvers);
+        self
+    }
 
-        let test_types = vec![RawTypeData {
-            id: "array".to_string(),
-            russian_name: "Массив".to_string(),
-            english_name: "Array".to_string(),
-            source: TypeSource::Platform {
-                version: "8.3".to_string(),
-            },
-            category_path: vec!["Коллекции".to_string()],
-            methods: vec![],
-            properties: vec![],
-            documentation: "Коллекция упорядоченных значений".to_string(),
-            examples: vec![],
-            available_facets: vec![crate::domain::types::Facet {
-                kind: FacetKind::Object,
-                methods: vec![],
-                properties: vec![],
-            }],
-            parse_metadata: ParseMetadata {
-                file_path: "test.xml".to_string(),
-                line: 0,
-                column: 0,
-            },
-        }];
+    /// Резолвер заданного типа
+    pub fn resolver<R: TypeResolver + 'static>(&self) -> Option<Arc<R>> {
+        self.resolvers.get::<R>()
+    }
 
-        repo.save_types(test_types).await.unwrap();
+    /// Имена резолверов в порядке опроса
+    pub fn resolver_names(&self) -> Vec<String> {
+        self.resolvers.names()
+    }
 
-        // Создаём сервис
-        let service = TypeResolutionService::new(repo);
+    /// Справка «Как работает вывод типов» по действующей цепочке резолверов
+    pub async fn inference_reference(&self) -> InferenceReference {
+        inference_reference::build(self).await
+    }
 
-        // Инициализируем резолверы
-        if let Some(platform_resolver) = service
-            .resolvers
-            .iter()
-            .find_map(|r| r.as_any().downcast_ref::<PlatformTypeResolver>())
-        {
-            platform_resolver
-                .initialize_cache(service.repository.as_ref())
-                .await
-                .unwrap();
+    /// Разрешить выражение: из кеша или цепочкой резолверов с настроенной
+    /// политикой остановки. Этим путём идут LSP, веб-интерфейс и анализ
+    /// проектов; контекст с локальными переменными не кешируется.
+    pub async fn resolve_expression(
+        &self,
+        expression: &str,
+        context: &TypeContext,
+    ) -> TypeResolution {
+        let start = std::time::Instant::now();
+        let key = context.local_variables.is_empty().then(|| {
+            format!(
+                "{}|{}|{:?}",
+                expression,
+                context.file_path.as_deref().unwrap_or_default(),
+                context.current_facet
+            )
+        });
+        if let Some(key) = &key {
+            if let Some(cached) = self.cache.write().await.get_mut(key) {
+                cached.access_count += 1;
+                cached.last_accessed = start;
+                let mut metrics = self.metrics.write().await;
+                metrics.total_resolutions += 1;
+                metrics.cache_hits += 1;
+                return cached.resolution.clone();
+            }
         }
 
-        // Тестируем разрешение типов
-        let context = TypeContext {
-            file_path: None,
-            line: None,
-            column: None,
-            local_variables: HashMap::new(),
-            current_function: None,
-            current_facet: None,
-        };
+        let resolution = self.resolve_with_chain(expression, context).await;
 
-        let resolution = service.resolve_expression("Массив", &context).await;
-        assert_eq!(resolution.certainty, Certainty::Known);
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.total_resolutions += 1;
+            metrics.cache_misses += 1;
+            if matches!(resolution.certainty, Certainty::Unknown) {
+                metrics.failed_resolutions += 1;
+            } else {
+                metrics.successful_resolutions += 1;
+            }
+            let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+            let misses = metrics.cache_misses as f64;
+            metrics.average_resolution_time_ms +=
+                (elapsed - metrics.average_resolution_time_ms) / misses;
+        }
+        if let Some(key) = key {
+            self.cache.write().await.insert(
+                key,
+                CachedTypeResolution {
+                    resolution: resolution.clone(),
+                    created_at: start,
+                    access_count: 1,
+                    last_accessed: start,
+                },
+            );
+        }
+        resolution
+    }
 
-        // Тестируем автодополнение
-        let completions = service.get_completions("Масс", &context).await;
-        assert!(!completions.is_empty());
+    /// Разрешить выражение цепочкой резолверов. Имя резолвера, давшего
+    /// результат, сохраняется в `metadata.resolver`.
+    pub async fn resolve_with_chain(
+        &self,
+        expression: &str,
+        context: &TypeContext,
+    ) -> TypeResolution {
+        let mut best: Option<TypeResolution> = None;
+
+        for entry in self.resolvers.candidates(expression) {
+            let resolver = entry.resolver();
+            let mut resolution = match resolver
+                .resolve(expression, context, self.repository.as_ref())
+                .await
+            {
+                Ok(resolution) => resolution,
+                Err(e) => {
+                    warn!("⚠️ Резолвер {} не разрешил '{}': {}", resolver.name(), expression, e);
+                    continue;
+                }
+            };
+            resolution.metadata.resolver = Some(resolver.name().to_string());
 
-        // Тестируем поиск
-        let search_results = service.search_types("массив").await.unwrap();
-        assert_eq!(search_results.len(), 1);
+            if self.chain_policy.should_stop(&resolution) {
+                return resolution;
+            }
+            let is_better = best
+                .as_ref()
+                .is_none_or(|b| confidence(&resolution) > confidence(b));
+            if is_better {
+                best = Some(resolution);
+            }
+        }
+
+        best.unwrap_or_else(TypeResolution::unknown)
     }
-}
 
-// Хак для тестирования downcast
-trait TypeResolverAny: TypeResolver {
-    fn as_any(&self) -> &dyn std::any::Any;
+    /// Ограничить время работы каждого резолвера
+    /// (`PerformanceSettings::resolver_timeout_ms`)
+    pub fn with_resolver_timeout(mut self, timeout: Duration) -> Self {
+        let metrics = self.metrics.clone();
+        self.resolvers.add_layer(move |inner| {
+            Arc::new(DeadlineResolver::new(inner, timeout, metrics.clone()))
+        });
+        self
+    }
+
+    /// Количество разрешений, прерванных по таймауту
+    pub async fn timed_out_resolutions(&self) -> u64 {
+        self.metrics.read().await.timed_out_resolutions
+    }
+
+    /// Сбросить кеш разрешений (после перезагрузки типов)
+    pub async fn clear_cache(&self) {
+        self.cache.write().await.clear();
+    }
+
+    /// Сбросить разрешения, затронутые изменением репозитория; сигнатуры
+    /// функций, возвращающих затронутые типы, выводятся заново
+    pub async fn invalidate_types(&self, change: &RepositoryChange) {
+        self.cache
+            .write()
+            .await
+            .retain(|_, cached| !change.affects(&cached.resolution));
+        let mut table = self.signatures.write().await;
+        if table.returns_type(|name| change.affects_name(name)) {
+            table.infer(self.repository.as_ref()).await;
+        }
+    }
+
+    /// Разрешить выражение с учётом текста документа: проверки `ТипЗнч`
+    /// уточняют тип в своей ветке (внутри `Если ТипЗнч(Х) = Тип("Строка")
+    /// Тогда` переменная `Х` — строка), а присваивания в разных ветках
+    /// дают объединение типов. Позиция берётся из `context.line` и
+    /// `context.column`.
+    pub async fn resolve_in_document(
+        &self,
+        expression: &str,
+        context: &TypeContext,
+        text: &str,
+    ) -> TypeResolution {
+        let narrowed = self.narrowed_context(context, text).await;
+        if let Some(local) = narrowed.local_variable(expression) {
+            return local.clone();
+        }
+        // `Ждать Функция()` — значение обещания, `Функция()` — результат вызова
+        let awaited = await_operand(expression);
+        let call = call_target(awaited.unwrap_or(expression).trim());
+        if let Some(AssignedValue::Call { module, function }) = call {
+            let signatures = self.signatures.read().await;
+            let file = context.file_path.as_deref();
+            let resolution = match awaited {
+                Some(_) => signatures.awaited_type(module.as_deref(), &function, file),
+                None => signatures.call_type(module.as_deref(), &function, file),
+            };
+            if let Some(resolution) = resolution {
+                return resolution;
+            }
+        }
+        if let Some(resolution) = self.metadata_value_type(expression).await {
+            return resolution;
+        }
+        if let Some(record) = created_record_type(expression) {
+            return ExpressionResolver::new()
+                .resolve_record_type(&record, self.repository.as_ref())
+                .await;
+        }
+        if let Some(document) = created_document_object(expression) {
+            return ExpressionResolver::new()
+                .resolve_document_type(&document, self.repository.as_ref())
+                .await;
+        }
+        self.resolve_expression(expression, &narrowed).await
+    }
+
+    /// Установки параметров запросов модуля: параметры текста без
+    /// `УстановитьПараметр` и значения, тип которых не подходит полю, с
+    /// которым параметр сравнивается в запросе. Тип значения берётся из
+    /// литерала, локальной переменной или константы; выражения другого вида
+    /// не проверяются.
+    pub async fn check_query_parameters(
+        &self,
+        file_path: Option<&str>,
+        text: &str,
+    ) -> Vec<QueryParameterIssue> {
+        let resolver = ExpressionResolver::new();
+        let repository = self.repository.as_ref();
+        let mut issues = Vec::new();
+        for usage in queries::query_parameter_usages(text) {
+            let variable = &usage.query.variable;
+            for parameter in queries::missing_parameters(&usage) {
+                issues.push(QueryParameterIssue {
+                    variable: variable.clone(),
+                    parameter: parameter.name,
+                    line: usage.query.line,
+                    column: 0,
+                    kind: QueryParameterIssueKind::Missing,
+                });
+            }
+            let parameters = query_parameters(&usage.query.text);
+            for call in &usage.calls {
+                let Some(name) = call.name.as_deref() else {
+                    continue;
+                };
+                let Some(parameter) = parameters
+                    .iter()
+                    .find(|p| p.name.to_lowercase() == name.to_lowercase())
+                else {
+                    continue;
+                };
+                let expected = match &parameter.expected {
+                    ColumnOrigin::Field { table, path } => {
+                        resolver.query_field_type(table, path, repository).await
+                    }
+                    ColumnOrigin::Type(name) => Some(name.clone()),
+                    ColumnOrigin::Unknown => None,
+                };
+                let Some(expected) = expected else {
+                    continue;
+                };
+                let value = match queries::literal_type(&call.value) {
+                    Some(literal) => resolver.resolve_type_by_name(literal, repository).await,
+                    None => {
+                        let context = TypeContext {
+                            file_path: file_path.map(str::to_string),
+                            line: Some(call.line),
+                            column: Some(call.column),
+                            local_variables: HashMap::new(),
+                            current_function: None,
+                            current_facet: None,
+                        };
+                        let narrowed = self.narrowed_context(&context, text).await;
+                        match narrowed.local_variable(&call.value) {
+                            Some(local) => Some(local.clone()),
+                            None => self.metadata_value_type(&call.value).await,
+                        }
+                    }
+                };
+                let Some(value) = value else {
+                    continue;
+                };
+                if !queries::parameter_accepts(&expected, parameter.list, &value) {
+                    issues.push(QueryParameterIssue {
+                        variable: variable.clone(),
+                        parameter: parameter.name.clone(),
+                        line: call.line,
+                        column: call.column,
+                        kind: QueryParameterIssueKind::Mismatch {
+                            expected,
+                            actual: value.get_name().unwrap_or_default(),
+                        },
+                    });
+                }
+            }
+        }
+        issues
+    }
+
+    /// Сравнения со значениями, которых нет в перечислении; перечисления без
+    /// описания или без значений в описании не проверяются
+    pub async fn check_enum_comparisons(&self, text: &str) -> Vec<EnumValueIssue> {
+        let mut known: HashMap<String, Vec<String>> = HashMap::new();
+        let mut issues = Vec::new();
+        for comparison in enum_comparisons(text) {
+            let reference = comparison.reference;
+            let key = reference.enumeration.to_lowercase();
+            if !known.contains_key(&key) {
+                let candidates = self
+                    .repository
+                    .search_types(&reference.enumeration)
+                    .await
+                    .unwrap_or_default();
+                let values = candidates
+                    .iter()
+                    .find(|raw| enums::enum_kind().describes(raw, &reference.enumeration))
+                    .map(|raw| {
+                        enums::enum_values(raw)
+                            .into_iter()
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                known.insert(key.clone(), values);
+            }
+            let values = &known[&key];
+            let value = reference.value.to_lowercase();
+            if values.is_empty() || values.iter().any(|v| v.to_lowercase() == value) {
+                continue;
+            }
+            issues.push(EnumValueIssue {
+                enumeration: reference.enumeration,
+                value: reference.value,
+                line: comparison.line,
+                column: comparison.column,
+                known: values.clone(),
+            });
+        }
+        issues
+    }
+
+    /// Заменить индекс констант и параметров сеанса конфигурации
+    pub async fn set_metadata_values(&self, values: MetadataValueIndex) {
+        *self.metadata_values.write().await = values;
+        self.cache.write().await.clear();
+    }
+
+    /// Объявленный тип значения `Константы.Имя.Получить()` или
+    /// `ПараметрыСеанса.Имя`
+    pub async fn metadata_value_type(&self, expression: &str) -> Option<TypeResolution> {
+        let reference = referenced_value(expression)?;
+        let types = self.metadata_values.read().await.find(&reference)?.types.clone();
+        ExpressionResolver::new()
+            .resolve_declared_types(&types, self.repository.as_ref())
+            .await
+    }
+
+    /// Освободить исходные выражения таблицы сигнатур (анализ в ограниченной
+    /// памяти, см. [`SignatureTable::release_returns`])
+    pub async fn release_signature_sources(&self) {
+        self.signatures.write().await.release_returns();
+    }
+
+    /// Заменить таблицу сигнатур функций проекта
+    pub async fn set_signatures(&self, signatures: SignatureTable) {
+        *self.signatures.write().await = signatures;
+        self.cache.write().await.clear();
+    }
+
+    /// Построить таблицу сигнатур по модулям проекта (путь, текст) и
+    /// использовать её при разрешении вызовов; заодно собираются глобальные
+    /// символы модулей приложения и сеанса. Возвращает число функций.
+    pub async fn index_signatures(&self, modules: &[(String, String)]) -> usize {
+        let table = SignatureTable::build(modules, self.repository.as_ref()).await;
+        let count = table.len();
+        self.set_globals(GlobalScope::build(modules)).await;
+        self.set_signatures(table).await;
+        count
+    }
+
+    /// То же для таблицы и глобальных символов, собранных по модулям
+    /// постепенно (`add_module`), без текстов всего проекта в памяти:
+    /// выводит возвращаемые типы и возвращает число функций
+    pub async fn install_signatures(
+        &self,
+        mut table: SignatureTable,
+        globals: GlobalScope,
+    ) -> usize {
+        table.infer(self.repository.as_ref()).await;
+        let count = table.len();
+        self.set_globals(globals).await;
+        self.set_signatures(table).await;
+        count
+    }
+
+    /// Обновить сигнатуры и глобальные символы одного модуля: `text` —
+    /// новый текст, `None` — модуль удалён. Возвращаемые типы пересчитываются
+    /// для всей таблицы: от изменённой функции зависят вызывающие её
+    pub async fn update_module(&self, file: &str, text: Option<&str>) {
+        {
+            let mut table = self.signatures.write().await;
+            table.remove_module(file);
+            if let Some(text) = text {
+                table.add_module(file, text);
+            }
+            table.infer(self.repository.as_ref()).await;
+        }
+        {
+            let mut globals = self.globals.write().await;
+            globals.remove_module(file);
+            if let Some(text) = text {
+                globals.add_module(file, text);
+            }
+        }
+        self.cache.write().await.clear();
+    }
+
+    /// Заменить глобальные символы модулей приложения и сеанса
+    pub async fn set_globals(&self, globals: GlobalScope) {
+        *self.globals.write().await = globals;
+        self.cache.write().await.clear();
+    }
+
+    /// Глобальные символы модулей приложения и сеанса
+    pub async fn globals(&self) -> GlobalScope {
+        self.globals.read().await.clone()
+    }
+
+    /// Сигнатура функции проекта (см. [`SignatureTable::find`])
+    pub async fn function_signature(
+        &self,
+        module: Option<&str>,
+        name: &str,
+        file: Option<&str>,
+    ) -> Option<FunctionSignature> {
+        self.signatures.read().await.find(module, name, file).cloned()
+    }
+
+    /// Контекст, дополненный типами локальных переменных из присваиваний
+    /// и уточнённый проверками типа, действующими в позиции контекста
+    pub async fn narrowed_context(&self, context: &TypeContext, text: &str) -> TypeContext {
+        let mut narrowed = context.clone();
+        let Some(line) = context.line else {
+            return narrowed;
+        };
+        let column = context.column.unwrap_or(0);
+        // Переменные, переданные вызывающим, не перекрываются
+        let assigned = {
+            let signatures = self.signatures.read().await;
+            let metadata_values = self.metadata_values.read().await;
+            BslCodeResolver::local_types(
+                text,
+                line,
+                column,
+                context.file_path.as_deref(),
+                &signatures,
+                &metadata_values,
+                self.repository.as_ref(),
+            )
+            .await
+        };
+        for (name, resolution) in assigned {
+            if narrowed.local_variable(&name).is_none() {
+                narrowed.local_variables.insert(name, resolution);
+            }
+        }
+
+        // Экспортные переменные модулей приложения и сеанса видны во всех
+        // модулях своего контекста; тип — по присваиваниям в самом модуле
+        {
+            let method_context = method_context(text, line);
+            let globals = self.globals.read().await;
+            let signatures = self.signatures.read().await;
+            let metadata_values = self.metadata_values.read().await;
+            for variable in globals.variables() {
+                let available = match method_context {
+                    Some(context) => variable.module.available_in(context),
+                    None => true,
+                };
+                if !available || narrowed.local_variable(&variable.name).is_some() {
+                    continue;
+                }
+                let resolution = BslCodeResolver::assigned_type(
+                    &variable.values,
+                    Some(&variable.file),
+                    &signatures,
+                    &metadata_values,
+                    self.repository.as_ref(),
+                )
+                .await
+                .unwrap_or_else(TypeResolution::unknown);
+                narrowed
+                    .local_variables
+                    .insert(variable.name.clone(), resolution);
+            }
+        }
+
+        let resolver = ExpressionResolver::new();
+        // В модуле объекта документа `ЭтотОбъект`, `Движения` и
+        // `ОбменДанными` доступны без квалификатора
+        let document = context.file_path.as_deref().and_then(object_module_document);
+        if let Some(document) = document {
+            let object = resolver
+                .resolve_document_type(&DocumentType::Object(document), self.repository.as_ref())
+                .await;
+            let raw = object.to_raw_data();
+            for member in ["Движения", "ОбменДанными"] {
+                let Some(property) = raw.properties.iter().find(|p| p.name == member) else {
+                    continue;
+                };
+                if narrowed.local_variable(member).is_some() {
+                    continue;
+                }
+                if let Some(resolution) = resolver
+                    .resolve_type_by_name(&property.type_name, self.repository.as_ref())
+                    .await
+                {
+                    narrowed.local_variables.insert(member.to_string(), resolution);
+                }
+            }
+            if narrowed.local_variable("ЭтотОбъект").is_none() {
+                narrowed.local_variables.insert("ЭтотОбъект".to_string(), object);
+            }
+        }
+        // В модуле объекта справочника, плана, обработки или отчёта
+        // `ЭтотОбъект`, `ОбменДанными`, реквизиты и табличные части доступны
+        // без квалификатора
+        let object = context.file_path.as_deref().and_then(object_module);
+        if let Some(object) = object {
+            let resolution = resolver
+                .resolve_object_type(&object, self.repository.as_ref())
+                .await;
+            let raw = resolution.to_raw_data();
+            let standard = objects::object_resolution(&object, &[]).to_raw_data();
+            let members = raw.properties.iter().filter(|p| {
+                p.name == "ОбменДанными" || !standard.properties.iter().any(|s| s.name == p.name)
+            });
+            for property in members {
+                if narrowed.local_variable(&property.name).is_some() {
+                    continue;
+                }
+                let declared = std::slice::from_ref(&property.type_name);
+                if let Some(resolution) = resolver
+                    .resolve_declared_types(declared, self.repository.as_ref())
+                    .await
+                {
+                    narrowed
+                        .local_variables
+                        .insert(property.name.clone(), resolution);
+                }
+            }
+            if narrowed.local_variable("ЭтотОбъект").is_none() {
+                narrowed
+                    .local_variables
+                    .insert("ЭтотОбъект".to_string(), resolution);
+            }
+        }
+        // В модуле формы доступны `ЭтаФорма`, `Элементы` и реквизиты формы
+        let form = context
+            .file_path
+            .as_deref()
+            .and_then(FormContextProvider::for_module);
+        if let Some(form) = form {
+            form.populate(&mut narrowed, &resolver, self.repository.as_ref())
+                .await;
+        }
+        for guard in type_guards_at(text, line, column) {
+            // Внутренняя проверка заменяет внешнюю
+            let variable = guard.variable.to_lowercase();
+            narrowed
+                .local_variables
+                .retain(|name, _| name.to_lowercase() != variable);
+
+            let mut resolutions = Vec::new();
+            for name in &guard.type_names {
+                if let Some(resolution) = resolver
+                    .resolve_type_by_name(name, self.repository.as_ref())
+                    .await
+                {
+                    resolutions.push(resolution);
+                }
+            }
+            if resolutions.is_empty() {
+                continue;
+            }
+            // Проверка гарантирует тип, поэтому уверенность полная
+            let resolution = TypeResolution {
+                certainty: Certainty::Known,
+                ..TypeResolution::join_all(resolutions)
+            };
+            narrowed.local_variables.insert(guard.variable, resolution);
+        }
+        narrowed
+    }
 }
 
-impl TypeResolverAny for PlatformTypeResolver {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+/// Резолвер с ограничением времени работы
+///
+/// Если вложенный резолвер не уложился в срок, возвращается
+/// `TypeResolution::unknown()` с пометкой в метаданных, а событие
+/// учитывается в метриках. Прерывание возможно только в точках `await`,
+/// поэтому долгие синхронные вычисления резолверы должны делить на части.
+struct DeadlineResolver {
+    inner: Arc<dyn TypeResolver>,
+    timeout: Duration,
+    metrics: Arc<RwLock<ResolutionMetrics>>,
+}
+
+/// Уверенность результата для сравнения в цепочке
+fn confidence(resolution: &TypeResolution) -> f32 {
+    match resolution.certainty {
+        Certainty::Known => 1.0,
+        Certainty::Inferred(value) => value,
+        Certainty::Unknown => 0.0,
     }
 }
 
-impl TypeResolverAny for ConfigurationTypeResolver {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+impl DeadlineResolver {
+    fn new(
+        inner: Arc<dyn TypeResolver>,
+        timeout: Duration,
+        metrics: Arc<RwLock<ResolutionMetrics>>,
+    ) -> Self {
+        Self {
+            inner,
+            timeout,
+            metrics,
+        }
+    }
+
+    async fn record_timeout(&self, expression: &str) {
+        warn!(
+            "⏱️ Резолвер не уложился в {} мс: {}",
+            self.timeout.as_millis(),
+            expression
+        );
+        self.metrics.write().await.timed_out_resolutions += 1;
     }
 }
 
-impl TypeResolverAny for BslCodeResolver {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+#[async_trait]
+impl TypeResolver for DeadlineResolver {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn can_resolve(&self, expression: &str) -> bool {
+        self.inner.can_resolve(expression)
+    }
+
+    async fn resolve(
+        &self,
+        expression: &str,
+        context: &TypeContext,
+        repository: &dyn TypeRepository,
+    ) -> crate::Result<TypeResolution> {
+        let resolve = self.inner.resolve(expression, context, repository);
+        match tokio::time::timeout(self.timeout, resolve).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.record_timeout(expression).await;
+                let mut resolution = TypeResolution::unknown();
+                resolution.metadata.notes.push(format!(
+                    "Разрешение прервано: превышено время {} мс",
+                    self.timeout.as_millis()
+                ));
+                Ok(resolution)
+            }
+        }
+    }
+
+    async fn get_completions(
+        &self,
+        prefix: &str,
+        context: &TypeContext,
+        repository: &dyn TypeRepository,
+    ) -> crate::Result<Vec<CompletionItem>> {
+        let completions = self.inner.get_completions(prefix, context, repository);
+        match tokio::time::timeout(self.timeout, completions).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.record_timeout(prefix).await;
+                Ok(Vec::new())
+            }
+        }
     }
 }
 
-impl TypeResolverAny for BuiltinTypeResolver {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+/// Минимальный сервис проверки совместимости типов (Domain)
+pub struct TypeCheckerService;
+
+impl TypeCheckerService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Базовая проверка присваивания: конкретные типы должны совпадать по дисриминатору
+    pub fn is_assignment_compatible(&self, from: &TypeResolution, to: &TypeResolution) -> bool {
+        match (&from.result, &to.result) {
+            (ResolutionResult::Concrete(cf), ResolutionResult::Concrete(ct)) => {
+                std::mem::discriminant(cf) == std::mem::discriminant(ct)
+            }
+            // Разрешаем неизвестные/динамические на данном этапе
+            _ => true,
+        }
     }
 }
 
-impl TypeResolverAny for ExpressionResolver {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unified::data::{InMemoryTypeRepository, ParseMetadata, RawPropertyData, TypeSource};
+
+    #[tokio::test]
+    async fn test_type_resolution_service() {
+        // Создаём репозиторий с тестовыми данными
+        let repo = Arc::new(InMemoryTypeRepository::new());
+
+        let test_types = vec![RawTypeData {
+            id: "array".to_string(),
+            russian_name: "Массив".to_string(),
+            english_name: "Array".to_string(),
+            source: TypeSource::Platform {
+                version: "8.3".to_string(),
+            },
+            category_path: vec!["Коллекции".to_string()],
+            methods: vec![],
+            properties: vec![],
+            documentation: "Коллекция упорядоченных значений".to_string(),
+            examples: vec![],
+            available_facets: vec![crate::domain::types::Facet {
+                kind: FacetKind::Object,
+                methods: vec![],
+                properties: vec![],
+            }],
+            parse_metadata: ParseMetadata {
+                file_path: "test.xml".to_string(),
+                line: 0,
+                column: 0,
+            },
+        }];
+
+        repo.save_types(test_types).await.unwrap();
+
+        // Создаём сервис
+        let service = TypeResolutionService::new(repo);
+
+        // Инициализируем резолверы
+        if let Some(platform_resolver) = service.resolver::<PlatformTypeResolver>() {
+            platform_resolver
+                .initialize_cache(service.repository.as_ref())
+                .await
+                .unwrap();
+        }
+
+        // Тестируем разрешение типов
+        let context = TypeContext {
+            file_path: None,
+            line: None,
+            column: None,
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+
+        let resolution = service.resolve_expression("Массив", &context).await;
+        assert_eq!(resolution.certainty, Certainty::Known);
+
+        // Тестируем автодополнение
+        let completions = service.get_completions("Масс", &context).await;
+        assert!(!completions.is_empty());
+
+        // Тестируем поиск
+        let search_results = service.search_types("массив").await.unwrap();
+        assert_eq!(search_results.len(), 1);
+    }
+
+    /// Резолвер, который никогда не успевает
+    struct SlowResolver;
+
+    #[async_trait]
+    impl TypeResolver for SlowResolver {
+        fn can_resolve(&self, _expression: &str) -> bool {
+            true
+        }
+
+        async fn resolve(
+            &self,
+            _expression: &str,
+            _context: &TypeContext,
+            _repository: &dyn TypeRepository,
+        ) -> crate::Result<TypeResolution> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(TypeResolution::known(ConcreteType::Primitive(PrimitiveType::String)))
+        }
+
+        async fn get_completions(
+            &self,
+            _prefix: &str,
+            _context: &TypeContext,
+            _repository: &dyn TypeRepository,
+        ) -> crate::Result<Vec<CompletionItem>> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deadline_resolver_falls_back_to_unknown() {
+        let repo = InMemoryTypeRepository::new();
+        let metrics = Arc::new(RwLock::new(ResolutionMetrics::default()));
+        let resolver = DeadlineResolver::new(
+            Arc::new(SlowResolver),
+            Duration::from_millis(10),
+            metrics.clone(),
+        );
+        let context = TypeContext {
+            file_path: None,
+            line: None,
+            column: None,
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+
+        let resolution = resolver.resolve("А.Б.В", &context, &repo).await.unwrap();
+        assert_eq!(resolution.certainty, Certainty::Unknown);
+        assert!(resolution.metadata.notes[0].contains("превышено время"));
+
+        let completions = resolver.get_completions("А.", &context, &repo).await.unwrap();
+        assert!(completions.is_empty());
+        assert_eq!(metrics.read().await.timed_out_resolutions, 2);
+        assert_eq!(resolver.name(), "SlowResolver");
+    }
+
+    #[derive(Clone)]
+    struct FixedResolver {
+        name: &'static str,
+        certainty: Certainty,
+    }
+
+    #[async_trait]
+    impl TypeResolver for FixedResolver {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn can_resolve(&self, _expression: &str) -> bool {
+            true
+        }
+
+        async fn resolve(
+            &self,
+            _expression: &str,
+            _context: &TypeContext,
+            _repository: &dyn TypeRepository,
+        ) -> crate::Result<TypeResolution> {
+            let mut resolution =
+                TypeResolution::known(ConcreteType::Primitive(PrimitiveType::String));
+            resolution.certainty = self.certainty;
+            Ok(resolution)
+        }
+    }
+
+    fn chain_service() -> TypeResolutionService {
+        let mut resolvers = ResolverRegistry::new();
+        for (name, certainty) in [
+            ("Первый", Certainty::Inferred(0.4)),
+            ("Второй", Certainty::Inferred(0.7)),
+            ("Третий", Certainty::Known),
+        ] {
+            resolvers.register(FixedResolver { name, certainty });
+        }
+        TypeResolutionService {
+            repository: Arc::new(InMemoryTypeRepository::new()),
+            resolvers,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            metrics: Arc::new(RwLock::new(ResolutionMetrics::default())),
+            chain_policy: ShortCircuitPolicy::default(),
+            name_precedence: NamePrecedence::default(),
+            signatures: Arc::new(RwLock::new(SignatureTable::new())),
+            metadata_values: Arc::new(RwLock::new(MetadataValueIndex::default())),
+            globals: Arc::new(RwLock::new(GlobalScope::new())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolver_chain_config() {
+        let context = TypeContext {
+            file_path: None,
+            line: None,
+            column: None,
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+
+        let service = chain_service();
+        let resolution = service.resolve_with_chain("А", &context).await;
+        assert_eq!(resolution.metadata.resolver.as_deref(), Some("Третий"));
+
+        let service = chain_service().with_chain_config(&ResolverChainConfig {
+            order: vec!["второй".to_string()],
+            disabled: vec!["Третий".to_string()],
+            policy: ShortCircuitPolicy::FirstResolved,
+        });
+        assert_eq!(service.resolver_names(), ["Второй", "Первый"]);
+        let resolution = service.resolve_with_chain("А", &context).await;
+        assert_eq!(resolution.metadata.resolver.as_deref(), Some("Второй"));
+
+        let service = chain_service().with_chain_config(&ResolverChainConfig {
+            disabled: vec!["Третий".to_string()],
+            policy: ShortCircuitPolicy::BestConfidence,
+            ..Default::default()
+        });
+        let resolution = service.resolve_with_chain("А", &context).await;
+        assert_eq!(resolution.certainty, Certainty::Inferred(0.7));
+        assert_eq!(resolution.metadata.resolver.as_deref(), Some("Второй"));
+
+        // Разрешение выражений (LSP, веб, анализ) идёт той же цепочкой;
+        // повторное — из кеша
+        let first = service.resolve_expression("А", &context).await;
+        assert_eq!(first.metadata.resolver.as_deref(), Some("Второй"));
+        let second = service.resolve_expression("А", &context).await;
+        assert_eq!(second.certainty, first.certainty);
+        assert_eq!(service.metrics.read().await.cache_hits, 1);
+
+        // Сторонний резолвер с шаблоном опрашивается только для своих выражений
+        let capabilities = ResolverCapabilities::new(ResolverCost::Cheap)
+            .with_pattern(r"^@dsl\s")
+            .unwrap();
+        let dsl = FixedResolver {
+            name: "DSL",
+            certainty: Certainty::Known,
+        };
+        let plugins = ResolverPlugins::new().with(dsl, capabilities);
+        let service = chain_service().with_resolver_plugins(&plugins);
+        assert_eq!(service.resolver_names()[0], "DSL");
+        let resolution = service.resolve_with_chain("@dsl Запрос", &context).await;
+        assert_eq!(resolution.metadata.resolver.as_deref(), Some("DSL"));
+        let resolution = service.resolve_with_chain("А", &context).await;
+        assert_eq!(resolution.metadata.resolver.as_deref(), Some("Третий"));
+
+        // Повторная регистрация под тем же именем заменяет резолвер в цепочке
+        let weaker = FixedResolver {
+            name: "Третий",
+            certainty: Certainty::Inferred(0.1),
+        };
+        let service = chain_service()
+            .with_resolver(weaker, ResolverCapabilities::default())
+            .with_chain_config(&ResolverChainConfig {
+                policy: ShortCircuitPolicy::BestConfidence,
+                ..Default::default()
+            });
+        assert_eq!(service.resolver_names().len(), 3);
+        let resolution = service.resolve_with_chain("А", &context).await;
+        assert_eq!(resolution.metadata.resolver.as_deref(), Some("Второй"));
+
+        assert_eq!(
+            "best_confidence".parse::<ShortCircuitPolicy>(),
+            Ok(ShortCircuitPolicy::BestConfidence)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_type_of_narrowing_in_branches() {
+        let text = r#"Если ТипЗнч(Х) = Тип("Строка") Тогда
+    А = Х;
+ИначеЕсли ТипЗнч(Х) = Тип("Число") ИЛИ ТипЗнч(Х) = Тип("Дата") Тогда
+    А = Х;
+КонецЕсли;
+А = Х;"#;
+        let service = chain_service();
+        let context_at = |line| TypeContext {
+            file_path: None,
+            line: Some(line),
+            column: Some(8),
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+
+        let resolution = service.resolve_in_document("х", &context_at(1), text).await;
+        assert_eq!(resolution.certainty, Certainty::Known);
+        assert_eq!(
+            resolution.result,
+            ResolutionResult::Concrete(ConcreteType::Primitive(PrimitiveType::String))
+        );
+
+        let narrowed = service.narrowed_context(&context_at(3), text).await;
+        match &narrowed.local_variable("Х").unwrap().result {
+            ResolutionResult::Union(types) => assert_eq!(types.len(), 2),
+            other => panic!("ожидалось объединение, получено {:?}", other),
+        }
+
+        let narrowed = service.narrowed_context(&context_at(5), text).await;
+        assert!(narrowed.local_variable("Х").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_branch_assignments_resolve_to_union() {
+        let text = r#"Процедура Тест(Флаг)
+    Если Флаг Тогда
+        Значение = "строка";
+    Иначе
+        Значение = 42;
+    КонецЕсли;
+    Сообщить(Значение);
+    Если ТипЗнч(Значение) = Тип("Число") Тогда
+        Сообщить(Значение);
+    КонецЕсли;
+КонецПроцедуры"#;
+        let service = chain_service();
+        let context_at = |line| TypeContext {
+            file_path: None,
+            line: Some(line),
+            column: Some(8),
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+
+        let resolution = service
+            .resolve_in_document("Значение", &context_at(6), text)
+            .await;
+        assert_eq!(resolution.certainty, Certainty::Known);
+        match &resolution.result {
+            ResolutionResult::Union(types) => {
+                assert_eq!(types.len(), 2);
+                assert!(types.iter().all(|t| (t.weight - 0.5).abs() < 1e-6));
+            }
+            other => panic!("ожидалось объединение, получено {:?}", other),
+        }
+
+        // Проверка ТипЗнч уточняет объединение до одного типа
+        let resolution = service
+            .resolve_in_document("Значение", &context_at(8), text)
+            .await;
+        assert_eq!(
+            resolution.result,
+            ResolutionResult::Concrete(ConcreteType::Primitive(PrimitiveType::Number))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_call_resolves_to_inferred_return_type() {
+        let text = r#"Функция Имя(Код)
+    Возврат "имя";
+КонецФункции
+
+Процедура Показать()
+    Текст = Имя(1);
+    Сообщить(Текст);
+КонецПроцедуры"#;
+        let service = chain_service();
+        let modules = vec![("Module.bsl".to_string(), text.to_string())];
+        assert_eq!(service.index_signatures(&modules).await, 1);
+
+        let context = TypeContext {
+            file_path: Some("Module.bsl".to_string()),
+            line: Some(6),
+            column: Some(13),
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let string = ResolutionResult::Concrete(ConcreteType::Primitive(PrimitiveType::String));
+        for expression in ["Текст", "Имя(2)"] {
+            let resolution = service.resolve_in_document(expression, &context, text).await;
+            assert_eq!(resolution.result, string, "{expression}");
+        }
+        let signature = service
+            .function_signature(None, "имя", Some("Module.bsl"))
+            .await
+            .unwrap();
+        assert_eq!(signature.params[0].name, "Код");
+    }
+
+    #[tokio::test]
+    async fn test_configuration_shadows_platform_name() {
+        let raw = |source| RawTypeData::test_type("Цвет", source);
+        let service = chain_service().with_name_precedence(NamePrecedence::default());
+        service
+            .repository
+            .save_types(vec![
+                raw(TypeSource::Platform {
+                    version: "8.3".to_string(),
+                }),
+                raw(TypeSource::Configuration {
+                    config_version: "1.0".to_string(),
+                }),
+            ])
+            .await
+            .unwrap();
+
+        let candidates = service.name_candidates("Цвет").await.unwrap();
+        assert!(candidates.is_ambiguous());
+        assert_eq!(candidates.candidates.len(), 2);
+
+        let resolution = ExpressionResolver::new()
+            .resolve_type_by_name("Цвет", service.repository.as_ref())
+            .await
+            .unwrap();
+        assert!(matches!(
+            resolution.result,
+            ResolutionResult::Concrete(ConcreteType::Configuration(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_constant_and_session_parameter_types() {
+        use crate::data::loaders::metadata_values::DeclaredValue;
+
+        let declared = |name: &str, types: &[&str]| DeclaredValue {
+            name: name.to_string(),
+            types: types.iter().map(|t| t.to_string()).collect(),
+        };
+        let service = chain_service();
+        service
+            .repository
+            .save_types(vec![RawTypeData {
+                russian_name: "Валюты".to_string(),
+                english_name: "Валюты".to_string(),
+                category_path: vec!["Catalog".to_string()],
+                parse_metadata: ParseMetadata {
+                    file_path: "Catalogs/Валюты.xml".to_string(),
+                    line: 0,
+                    column: 0,
+                },
+                ..RawTypeData::test_type(
+                    "Catalog.Валюты",
+                    TypeSource::Configuration {
+                        config_version: "8.3".to_string(),
+                    },
+                )
+            }])
+            .await
+            .unwrap();
+        service
+            .set_metadata_values(MetadataValueIndex::new(
+                vec![declared("ОсновнаяВалюта", &["СправочникСсылка.Валюты"])],
+                vec![declared("Ставка", &["Число"])],
+            ))
+            .await;
+
+        let text = "Процедура Пересчитать()
+    Ставка = ПараметрыСеанса.Ставка;
+    Сообщить(Ставка);
+КонецПроцедуры";
+        let context = TypeContext {
+            file_path: Some("Module.bsl".to_string()),
+            line: Some(2),
+            column: Some(13),
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let currency = service
+            .resolve_in_document("Константы.ОсновнаяВалюта.Получить()", &context, text)
+            .await;
+        assert_eq!(currency.certainty, Certainty::Known);
+        assert_eq!(currency.active_facet, Some(FacetKind::Reference));
+        assert!(matches!(
+            currency.result,
+            ResolutionResult::Concrete(ConcreteType::Configuration(_))
+        ));
+
+        let number = ResolutionResult::Concrete(ConcreteType::Primitive(PrimitiveType::Number));
+        for expression in ["ПараметрыСеанса.Ставка", "Ставка"] {
+            let resolution = service.resolve_in_document(expression, &context, text).await;
+            assert_eq!(resolution.result, number, "{expression}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manager_member_completions() {
+        let property = |name: &str, type_name: &str| RawPropertyData {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            is_readonly: false,
+            description: String::new(),
+        };
+        let repo = InMemoryTypeRepository::new();
+        repo.save_types(vec![RawTypeData {
+            russian_name: "Номенклатура".to_string(),
+            english_name: "Номенклатура".to_string(),
+            category_path: vec!["Catalog".to_string()],
+            properties: vec![
+                property("Артикул", "Строка(25)"),
+                property("Штрихкоды", "СправочникТабличнаяЧасть.Номенклатура.Штрихкоды"),
+            ],
+            parse_metadata: ParseMetadata {
+                file_path: "Catalogs/Номенклатура.xml".to_string(),
+                line: 0,
+                column: 0,
+            },
+            ..RawTypeData::test_type(
+                "Catalog.Номенклатура",
+                TypeSource::Configuration {
+                    config_version: "8.3".to_string(),
+                },
+            )
+        }])
+        .await
+        .unwrap();
+        let context = TypeContext {
+            file_path: None,
+            line: None,
+            column: None,
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let resolver = ExpressionResolver::new();
+
+        let completions = resolver
+            .get_completions("Справочники.Номенклатура.", &context, &repo)
+            .await
+            .unwrap();
+        let find = |label: &str| completions.iter().find(|c| c.label == label);
+        assert_eq!(find("Артикул").unwrap().detail.as_deref(), Some("Реквизит: Строка(25)"));
+        assert_eq!(find("Штрихкоды").unwrap().detail.as_deref(), Some("Табличная часть"));
+        let by_code = find("НайтиПоКоду").unwrap();
+        assert_eq!(by_code.kind, CompletionKind::Method);
+        assert_eq!(
+            by_code.detail.as_deref(),
+            Some("Метод менеджера → СправочникСсылка.Номенклатура")
+        );
+
+        let filtered = resolver
+            .get_completions("Справочники.Номенклатура.Ар", &context, &repo)
+            .await
+            .unwrap();
+        let labels: Vec<&str> = filtered.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, ["Артикул"]);
+    }
+
+    #[tokio::test]
+    async fn test_composite_attribute_type() {
+        let property = |name: &str, type_name: &str| RawPropertyData {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            is_readonly: false,
+            description: String::new(),
+        };
+        let raw = |category: &str, name: &str, properties| RawTypeData {
+            russian_name: name.to_string(),
+            english_name: name.to_string(),
+            category_path: vec![category.to_string()],
+            properties,
+            parse_metadata: ParseMetadata {
+                file_path: format!("{}/{}.xml", category, name),
+                line: 0,
+                column: 0,
+            },
+            ..RawTypeData::test_type(
+                &format!("{}.{}", category, name),
+                TypeSource::Configuration {
+                    config_version: "8.3".to_string(),
+                },
+            )
+        };
+        let repo = InMemoryTypeRepository::new();
+        repo.save_types(vec![
+            raw(
+                "Catalog",
+                "Контрагенты",
+                vec![
+                    property("Наименование", "Строка(100)"),
+                    property("Код", "Строка(9)"),
+                    property("ИНН", "Строка(12)"),
+                ],
+            ),
+            raw(
+                "Catalog",
+                "Организации",
+                vec![
+                    property("Наименование", "Строка(100)"),
+                    property("Код", "Число(5)"),
+                    property("КПП", "Строка(9)"),
+                ],
+            ),
+            raw(
+                "Document",
+                "Заказ",
+                vec![property(
+                    "Контрагент",
+                    "СправочникСсылка.Контрагенты,СправочникСсылка.Организации",
+                )],
+            ),
+        ])
+        .await
+        .unwrap();
+        let context = TypeContext {
+            file_path: None,
+            line: None,
+            column: None,
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let resolver = ExpressionResolver::new();
+
+        let counterparty = resolver
+            .resolve("Заказ.Контрагент", &context, &repo)
+            .await
+            .unwrap();
+        let ResolutionResult::Union(members) = &counterparty.result else {
+            panic!("ожидалось объединение: {:?}", counterparty.result);
+        };
+        assert_eq!(members.len(), 2);
+
+        // Общий реквизит с разными типами — объединение их типов
+        let code = resolver
+            .resolve("Заказ.Контрагент.Код", &context, &repo)
+            .await
+            .unwrap();
+        assert!(matches!(code.result, ResolutionResult::Union(ref types) if types.len() == 2));
+
+        let completions = resolver
+            .get_completions("Заказ.Контрагент.", &context, &repo)
+            .await
+            .unwrap();
+        let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, ["Код", "Наименование"]);
+    }
+
+    #[tokio::test]
+    async fn test_external_data_source_tables() {
+        use crate::data::loaders::external_data_sources::{
+            ExternalDataSource, ExternalField, ExternalTable,
+        };
+
+        let source = ExternalDataSource {
+            name: "Склад".to_string(),
+            tables: vec![ExternalTable {
+                name: "Остатки".to_string(),
+                object: false,
+                fields: vec![ExternalField {
+                    name: "Количество".to_string(),
+                    types: vec!["Число".to_string()],
+                }],
+            }],
+            functions: vec![],
+            file_path: "ExternalDataSources/Склад.xml".into(),
+        };
+        let repo = InMemoryTypeRepository::new();
+        repo.save_types(external_sources::external_sources_raw_types(&[source]))
+            .await
+            .unwrap();
+        let context = TypeContext {
+            file_path: None,
+            line: None,
+            column: None,
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let resolver = ExpressionResolver::new();
+
+        let selection = resolver
+            .resolve(
+                "ВнешниеИсточникиДанных.Склад.Таблицы.Остатки.Выбрать()",
+                &context,
+                &repo,
+            )
+            .await
+            .unwrap();
+        let raw = selection.to_raw_data();
+        assert_eq!(raw.russian_name, "ВнешнийИсточникДанныхТаблицаВыборка.Склад.Остатки");
+        assert_eq!(raw.properties[0].name, "Количество");
+
+        let completions = resolver
+            .get_completions("ExternalDataSources.Склад.Таблицы.Остатки.Созд", &context, &repo)
+            .await
+            .unwrap();
+        let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, ["СоздатьМенеджерЗаписи", "СоздатьНаборЗаписей"]);
+    }
+
+    #[tokio::test]
+    async fn test_register_record_set_iteration() {
+        let service = chain_service();
+        service
+            .repository
+            .save_types(vec![RawTypeData {
+                russian_name: "КурсыВалют".to_string(),
+                english_name: "КурсыВалют".to_string(),
+                category_path: vec!["Register".to_string()],
+                properties: vec![RawPropertyData {
+                    name: "Курс".to_string(),
+                    type_name: "Число".to_string(),
+                    is_readonly: false,
+                    description: String::new(),
+                }],
+                parse_metadata: ParseMetadata {
+                    file_path: "InformationRegisters/КурсыВалют.xml".to_string(),
+                    line: 0,
+                    column: 0,
+                },
+                ..RawTypeData::test_type(
+                    "InformationRegister.КурсыВалют",
+                    TypeSource::Configuration {
+                        config_version: "8.3".to_string(),
+                    },
+                )
+            }])
+            .await
+            .unwrap();
+
+        let text = "Процедура Загрузить()
+    Набор = РегистрыСведений.КурсыВалют.СоздатьНаборЗаписей();
+    Для Каждого Запись Из Набор Цикл
+        Сообщить(Запись);
+    КонецЦикла;
+КонецПроцедуры";
+        let context = TypeContext {
+            file_path: Some("Module.bsl".to_string()),
+            line: Some(3),
+            column: Some(17),
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let record = service.resolve_in_document("Запись", &context, text).await;
+        assert_eq!(record.certainty, Certainty::Known);
+        let raw = record.to_raw_data();
+        assert_eq!(raw.russian_name, "РегистрСведенийЗапись.КурсыВалют");
+        assert!(raw.properties.iter().any(|p| p.name == "Курс" && p.type_name == "Число"));
+
+        let set = service.resolve_in_document("Набор", &context, text).await;
+        let raw = set.to_raw_data();
+        let add = raw.methods.iter().find(|m| m.name == "Добавить").unwrap();
+        assert_eq!(add.return_type.as_deref(), Some("РегистрСведенийЗапись.КурсыВалют"));
+    }
+
+    #[tokio::test]
+    async fn test_query_selection_columns() {
+        let service = chain_service();
+        service
+            .repository
+            .save_types(vec![RawTypeData {
+                russian_name: "Номенклатура".to_string(),
+                english_name: "Номенклатура".to_string(),
+                category_path: vec!["Catalog".to_string()],
+                properties: vec![RawPropertyData {
+                    name: "Артикул".to_string(),
+                    type_name: "Строка(25)".to_string(),
+                    is_readonly: false,
+                    description: String::new(),
+                }],
+                parse_metadata: ParseMetadata {
+                    file_path: "Catalogs/Номенклатура.xml".to_string(),
+                    line: 0,
+                    column: 0,
+                },
+                ..RawTypeData::test_type(
+                    "Catalog.Номенклатура",
+                    TypeSource::Configuration {
+                        config_version: "8.3".to_string(),
+                    },
+                )
+            }])
+            .await
+            .unwrap();
+
+        let text = r#"Процедура Вывести()
+    Запрос = Новый Запрос;
+    Запрос.Текст =
+        "ВЫБРАТЬ
+        |    Товары.Ссылка КАК Товар,
+        |    Товары.Артикул,
+        |    Товары.Родитель.Наименование КАК Группа,
+        |    КОЛИЧЕСТВО(Товары.Код) КАК Всего
+        |ИЗ
+        |    Справочник.Номенклатура КАК Товары";
+    Выборка = Запрос.Выполнить().Выбрать();
+    Пока Выборка.Следующий() Цикл
+    КонецЦикла;
+КонецПроцедуры"#;
+        let context = TypeContext {
+            file_path: Some("Module.bsl".to_string()),
+            line: Some(12),
+            column: Some(4),
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let context = service.narrowed_context(&context, text).await;
+        let selection = context.local_variable("Выборка").unwrap().to_raw_data();
+        assert_eq!(selection.russian_name, "ВыборкаИзРезультатаЗапроса");
+        let column = |name: &str| {
+            let property = selection.properties.iter().find(|p| p.name == name).unwrap();
+            property.type_name.clone()
+        };
+        assert_eq!(column("Товар"), "СправочникСсылка.Номенклатура");
+        assert_eq!(column("Артикул"), "Строка(25)");
+        assert_eq!(column("Группа"), "Строка");
+        assert_eq!(column("Всего"), "Число");
+
+        let resolver = ExpressionResolver::new();
+        let repository = service.repository.as_ref();
+        let article = resolver.resolve("Выборка.Артикул", &context, repository).await.unwrap();
+        assert_eq!(
+            article.result,
+            ResolutionResult::Concrete(ConcreteType::Primitive(PrimitiveType::String))
+        );
+        let product = resolver.resolve("Выборка.Товар", &context, repository).await.unwrap();
+        assert_eq!(product.active_facet, Some(FacetKind::Reference));
+        assert_eq!(product.to_raw_data().russian_name, "Номенклатура");
+    }
+
+    #[tokio::test]
+    async fn test_check_query_parameters() {
+        let service = chain_service();
+        service
+            .repository
+            .save_types(vec![RawTypeData {
+                russian_name: "Номенклатура".to_string(),
+                english_name: "Номенклатура".to_string(),
+                category_path: vec!["Catalog".to_string()],
+                properties: vec![RawPropertyData {
+                    name: "Артикул".to_string(),
+                    type_name: "Строка(25)".to_string(),
+                    is_readonly: false,
+                    description: String::new(),
+                }],
+                parse_metadata: ParseMetadata {
+                    file_path: "Catalogs/Номенклатура.xml".to_string(),
+                    line: 0,
+                    column: 0,
+                },
+                ..RawTypeData::test_type(
+                    "Catalog.Номенклатура",
+                    TypeSource::Configuration {
+                        config_version: "8.3".to_string(),
+                    },
+                )
+            }])
+            .await
+            .unwrap();
+
+        let text = r#"Процедура Найти()
+    Запрос = Новый Запрос;
+    Запрос.Текст =
+        "ВЫБРАТЬ Товары.Ссылка
+        |ИЗ Справочник.Номенклатура КАК Товары
+        |ГДЕ Товары.Артикул = &Артикул
+        |    И Товары.Родитель = &Группа
+        |    И Товары.Наименование = &Наименование
+        |    И Товары.Код = &Код";
+    Наименование = "Гвозди";
+    Запрос.УстановитьПараметр("Артикул", 15);
+    Запрос.УстановитьПараметр("Группа", "Основная");
+    Запрос.УстановитьПараметр("Наименование", Наименование);
+    Выборка = Запрос.Выполнить().Выбрать();
+КонецПроцедуры"#;
+        let issues = service.check_query_parameters(Some("Module.bsl"), text).await;
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[0].parameter, "Код");
+        assert_eq!(issues[0].kind, QueryParameterIssueKind::Missing);
+        assert_eq!(issues[0].line, 2);
+        assert_eq!(issues[1].parameter, "Артикул");
+        assert_eq!((issues[1].line, issues[1].column), (10, 41));
+        assert_eq!(
+            issues[1].kind,
+            QueryParameterIssueKind::Mismatch {
+                expected: "Строка(25)".to_string(),
+                actual: "Число".to_string(),
+            }
+        );
+        assert_eq!(issues[2].parameter, "Группа");
+        assert_eq!(
+            issues[2].kind,
+            QueryParameterIssueKind::Mismatch {
+                expected: "СправочникСсылка.Номенклатура".to_string(),
+                actual: "Строка".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_document_posting_members() {
+        let service = chain_service();
+        let records = vec!["AccumulationRegister.ТоварыНаСкладах".to_string()];
+        service
+            .repository
+            .save_types(vec![documents::movements_raw_type(
+                "ПриходТовара",
+                &records,
+                "Documents/ПриходТовара.xml",
+            )])
+            .await
+            .unwrap();
+
+        // В модуле объекта `Движения` доступны без `ЭтотОбъект`
+        let text = "Процедура ОбработкаПроведения(Отказ, РежимПроведения)
+    Движения.ТоварыНаСкладах.Записывать = Истина;
+КонецПроцедуры";
+        let context = TypeContext {
+            file_path: Some("Documents/ПриходТовара/Ext/ObjectModule.bsl".to_string()),
+            line: Some(1),
+            column: Some(4),
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let movements = service.resolve_in_document("Движения", &context, text).await;
+        assert_eq!(movements.certainty, Certainty::Known);
+        let raw = movements.to_raw_data();
+        assert_eq!(raw.properties[0].name, "ТоварыНаСкладах");
+        assert_eq!(
+            raw.properties[0].type_name,
+            "РегистрНакопленияНаборЗаписей.ТоварыНаСкладах"
+        );
+
+        // Запись набора из движений — с полями регистра накопления, а не
+        // одноимённого регистра сведений
+        let register = |tag: &str, field: &str| RawTypeData {
+            russian_name: "ТоварыНаСкладах".to_string(),
+            english_name: "TovaryNaSkladakh".to_string(),
+            category_path: vec!["Register".to_string(), tag.to_string()],
+            properties: vec![RawPropertyData {
+                name: field.to_string(),
+                type_name: "Число".to_string(),
+                is_readonly: false,
+                description: String::new(),
+            }],
+            parse_metadata: ParseMetadata {
+                file_path: format!("{}s/ТоварыНаСкладах.xml", tag),
+                line: 0,
+                column: 0,
+            },
+            ..RawTypeData::test_type(
+                &format!("{}.ТоварыНаСкладах", tag),
+                TypeSource::Configuration {
+                    config_version: "8.3".to_string(),
+                },
+            )
+        };
+        service
+            .repository
+            .save_types(vec![
+                register("InformationRegister", "Цена"),
+                register("AccumulationRegister", "Количество"),
+            ])
+            .await
+            .unwrap();
+        // Точечные выражения разрешает ExpressionResolver по неявным
+        // переменным модуля объекта
+        let narrowed = service.narrowed_context(&context, text).await;
+        let record = ExpressionResolver::new()
+            .resolve(
+                "Движения.ТоварыНаСкладах.Добавить()",
+                &narrowed,
+                service.repository.as_ref(),
+            )
+            .await
+            .unwrap();
+        let raw = record.to_raw_data();
+        assert_eq!(raw.russian_name, "РегистрНакопленияЗапись.ТоварыНаСкладах");
+        assert!(raw.properties.iter().any(|p| p.name == "Количество"));
+        assert!(raw.properties.iter().any(|p| p.name == "ВидДвижения"));
+        assert!(!raw.properties.iter().any(|p| p.name == "Цена"));
+
+        let text = "Документ = Документы.ПриходТовара.СоздатьДокумент();\nДокумент.Провести();";
+        let context = TypeContext {
+            file_path: Some("CommonModules/Склад/Ext/Module.bsl".to_string()),
+            line: Some(1),
+            column: Some(0),
+            ..context
+        };
+        let document = service.resolve_in_document("Документ", &context, text).await;
+        let raw = document.to_raw_data();
+        assert_eq!(raw.russian_name, "ДокументОбъект.ПриходТовара");
+        assert!(raw.methods.iter().any(|m| m.name == "Провести"));
+        let exchange = raw.properties.iter().find(|p| p.name == "ОбменДанными").unwrap();
+        assert_eq!(exchange.type_name, "ПараметрыОбменаДанными");
+    }
+
+    #[tokio::test]
+    async fn test_application_module_globals() {
+        let service = chain_service();
+        let modules = vec![(
+            "cf/Ext/ManagedApplicationModule.bsl".to_string(),
+            "Перем ИмяКлиента Экспорт;\n\nИмяКлиента = \"\";".to_string(),
+        )];
+        service.index_signatures(&modules).await;
+        assert_eq!(service.globals().await.variables().len(), 1);
+
+        let file = "cf/CommonForms/Настройки/Ext/Form/Module.bsl";
+        let text = "&НаКлиенте\nПроцедура Показать()\n    А = 1;\nКонецПроцедуры\n\n&НаСервере\nПроцедура Загрузить()\n    А = 1;\nКонецПроцедуры";
+        let client = service
+            .narrowed_context(&TypeContext::at_position(file, 2, 4), text)
+            .await;
+        let name = client.local_variable("ИмяКлиента").unwrap();
+        assert!(matches!(
+            name.result,
+            ResolutionResult::Concrete(ConcreteType::Primitive(PrimitiveType::String))
+        ));
+
+        // На сервере глобальные переменные клиентского модуля недоступны
+        let server = service
+            .narrowed_context(&TypeContext::at_position(file, 7, 4), text)
+            .await;
+        assert!(server.local_variable("ИмяКлиента").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_object_module_context() {
+        let service = chain_service();
+        let section = "СправочникТабличнаяЧасть.Номенклатура.Штрихкоды";
+        let property = |name: &str, type_name: &str| RawPropertyData {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            is_readonly: false,
+            description: String::new(),
+        };
+        let mut types = objects::tabular_section_raw_types(
+            section,
+            &[property("Штрихкод", "Строка(20)")],
+            "Catalogs/Номенклатура.xml",
+        );
+        types.push(RawTypeData {
+            russian_name: "Номенклатура".to_string(),
+            english_name: "Номенклатура".to_string(),
+            category_path: vec!["Catalog".to_string()],
+            properties: vec![
+                property("Артикул", "Строка(25)"),
+                property("Штрихкоды", section),
+            ],
+            parse_metadata: ParseMetadata {
+                file_path: "Catalogs/Номенклатура.xml".to_string(),
+                line: 0,
+                column: 0,
+            },
+            ..RawTypeData::test_type(
+                "Catalog.Номенклатура",
+                TypeSource::Configuration {
+                    config_version: "8.3".to_string(),
+                },
+            )
+        });
+        service.repository.save_types(types).await.unwrap();
+
+        let text = "Процедура ПередЗаписью(Отказ)
+    Штрихкоды.Очистить();
+КонецПроцедуры";
+        let context = TypeContext::at_position("Catalogs/Номенклатура/Ext/ObjectModule.bsl", 1, 4);
+        assert_eq!(context.current_facet, Some(FacetKind::Object));
+
+        let object = service.resolve_in_document("ЭтотОбъект", &context, text).await;
+        assert_eq!(object.certainty, Certainty::Known);
+        let raw = object.to_raw_data();
+        assert_eq!(raw.russian_name, "СправочникОбъект.Номенклатура");
+        assert!(raw.properties.iter().any(|p| p.name == "Артикул"));
+        assert!(raw.methods.iter().any(|m| m.name == "УстановитьНовыйКод"));
+
+        // Табличная часть доступна без `ЭтотОбъект`
+        let barcodes = service.resolve_in_document("Штрихкоды", &context, text).await;
+        let raw = barcodes.to_raw_data();
+        assert_eq!(raw.russian_name, section);
+        let add = raw.methods.iter().find(|m| m.name == "Добавить").unwrap();
+        assert_eq!(
+            add.return_type.as_deref(),
+            Some("СправочникТабличнаяЧастьСтрока.Номенклатура.Штрихкоды")
+        );
+
+        let manager = TypeContext::at_position("Catalogs/Номенклатура/Ext/ManagerModule.bsl", 1, 4);
+        assert_eq!(manager.current_facet, Some(FacetKind::Manager));
+    }
+
+    #[tokio::test]
+    async fn test_enum_values() {
+        let service = chain_service();
+        let values = ["Новый".to_string(), "Оплачен".to_string()];
+        service
+            .repository
+            .save_types(vec![RawTypeData {
+                russian_name: "СтатусыЗаказов".to_string(),
+                english_name: "СтатусыЗаказов".to_string(),
+                category_path: vec!["Enum".to_string()],
+                properties: enums::enum_value_properties("СтатусыЗаказов", &values),
+                parse_metadata: ParseMetadata {
+                    file_path: "Enums/СтатусыЗаказов.xml".to_string(),
+                    line: 0,
+                    column: 0,
+                },
+                ..RawTypeData::test_type(
+                    "Enum.СтатусыЗаказов",
+                    TypeSource::Configuration {
+                        config_version: "8.3".to_string(),
+                    },
+                )
+            }])
+            .await
+            .unwrap();
+
+        let resolver = ExpressionResolver::new();
+        let repository = service.repository.as_ref();
+        let context = TypeContext::at_position("Module.bsl", 0, 0);
+        let paid = resolver
+            .resolve("Перечисления.СтатусыЗаказов.Оплачен", &context, repository)
+            .await
+            .unwrap();
+        assert_eq!(paid.certainty, Certainty::Known);
+        assert_eq!(paid.active_facet, Some(FacetKind::Reference));
+        let missing = resolver
+            .resolve("Перечисления.СтатусыЗаказов.Отгружен", &context, repository)
+            .await
+            .unwrap();
+        assert_eq!(missing.certainty, Certainty::Unknown);
+
+        let completions = resolver
+            .get_completions("Перечисления.СтатусыЗаказов.", &context, repository)
+            .await
+            .unwrap();
+        let new = completions.iter().find(|c| c.label == "Новый").unwrap();
+        assert_eq!(new.kind, CompletionKind::Enum);
+        assert!(completions.iter().any(|c| c.label == "ПустаяСсылка"));
+
+        let text = "Если Статус = Перечисления.СтатусыЗаказов.Оплачен Тогда
+ИначеЕсли Статус <> Перечисления.СтатусыЗаказов.Отгружен Тогда
+КонецЕсли;";
+        let issues = service.check_enum_comparisons(text).await;
+        assert_eq!(issues.len(), 1);
+        assert_eq!((issues[0].line, issues[0].value.as_str()), (1, "Отгружен"));
+        assert_eq!(issues[0].known, values);
+    }
+
+    #[tokio::test]
+    async fn test_predefined_values() {
+        let service = chain_service();
+        let items = vec![crate::data::loaders::predefined::PredefinedItem {
+            name: "Рубль".to_string(),
+            code: Some("643".to_string()),
+            description: Some("руб.".to_string()),
+            is_folder: false,
+        }];
+        let catalog = RawTypeData {
+            russian_name: "Валюты".to_string(),
+            english_name: "Валюты".to_string(),
+            category_path: vec!["Catalog".to_string()],
+            parse_metadata: ParseMetadata {
+                file_path: "Catalogs/Валюты.xml".to_string(),
+                line: 0,
+                column: 0,
+            },
+            ..RawTypeData::test_type(
+                "Catalog.Валюты",
+                TypeSource::Configuration {
+                    config_version: "8.3".to_string(),
+                },
+            )
+        };
+        let list =
+            predefined::predefined_raw_type("Catalog", "Валюты", &items, "Catalogs/Валюты.xml")
+                .unwrap();
+        service
+            .repository
+            .save_types(vec![catalog, list])
+            .await
+            .unwrap();
+
+        let resolver = ExpressionResolver::new();
+        let repository = service.repository.as_ref();
+        let context = TypeContext::at_position("Module.bsl", 0, 0);
+        for expression in [
+            "Справочники.Валюты.Рубль",
+            r#"ПредопределенноеЗначение("Справочник.Валюты.Рубль")"#,
+            r#"ПредопределенноеЗначение("Справочник.Валюты.ПустаяСсылка")"#,
+        ] {
+            let resolution = resolver
+                .resolve(expression, &context, repository)
+                .await
+                .unwrap();
+            assert_eq!(resolution.certainty, Certainty::Known, "{}", expression);
+            assert_eq!(resolution.active_facet, Some(FacetKind::Reference));
+        }
+        let missing = resolver
+            .resolve("Справочники.Валюты.Евро", &context, repository)
+            .await
+            .unwrap();
+        assert_eq!(missing.certainty, Certainty::Unknown);
+
+        let completions = resolver
+            .get_completions("Справочники.Валюты.", &context, repository)
+            .await
+            .unwrap();
+        let ruble = completions.iter().find(|c| c.label == "Рубль").unwrap();
+        assert_eq!(ruble.kind, CompletionKind::Enum);
+        assert!(completions.iter().any(|c| c.label == "НайтиПоКоду"));
+    }
+
+    #[tokio::test]
+    async fn test_form_module_context() {
+        let service = chain_service();
+        let dir = tempfile::tempdir().unwrap();
+        let form_dir = dir.path().join("Documents/Заказ/Forms/ФормаДокумента/Ext");
+        std::fs::create_dir_all(form_dir.join("Form")).unwrap();
+        std::fs::write(
+            form_dir.join("Form.xml"),
+            r#"<Form xmlns="http://v8.1c.ru/8.3/xcf/logform">
+  <ChildItems>
+    <InputField name="Контрагент" id="1"></InputField>
+  </ChildItems>
+  <Attributes>
+    <Attribute name="Объект" id="2">
+      <Type><v8:Type>cfg:DocumentObject.Заказ</v8:Type></Type>
+      <MainAttribute>true</MainAttribute>
+    </Attribute>
+  </Attributes>
+</Form>"#,
+        )
+        .unwrap();
+        let module = form_dir.join("Form/Module.bsl");
+        let text = "&НаКлиенте\nПроцедура Заполнить(Команда)\n    Элементы.Контрагент.Видимость = Ложь;\nКонецПроцедуры";
+        std::fs::write(&module, text).unwrap();
+
+        let context = TypeContext {
+            file_path: Some(module.to_string_lossy().to_string()),
+            line: Some(2),
+            column: Some(4),
+            local_variables: HashMap::new(),
+            current_function: None,
+            current_facet: None,
+        };
+        let items = service.resolve_in_document("Элементы", &context, text).await;
+        let raw = items.to_raw_data();
+        assert_eq!(raw.russian_name, forms::FORM_ITEMS_TYPE);
+        assert_eq!(raw.properties[0].name, "Контрагент");
+        assert_eq!(raw.properties[0].type_name, "ПолеФормы");
+
+        let form = service.resolve_in_document("ЭтаФорма", &context, text).await;
+        assert_eq!(form.to_raw_data().russian_name, forms::FORM_TYPE);
+        let object = service.resolve_in_document("Объект", &context, text).await;
+        assert_eq!(object.to_raw_data().russian_name, "ДокументОбъект.Заказ");
+
+        // Вне модуля формы переменных формы нет
+        let context = TypeContext {
+            file_path: Some("CommonModules/Склад/Ext/Module.bsl".to_string()),
+            ..context
+        };
+        let narrowed = service.narrowed_context(&context, text).await;
+        assert!(narrowed.local_variable("Элементы").is_none());
     }
 }
 
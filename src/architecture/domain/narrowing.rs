@@ -0,0 +1,392 @@
+//! Уточнение типов в ветках `Если` по проверкам `ТипЗнч`
+//!
+//! Внутри `Если ТипЗнч(Х) = Тип("Строка") Тогда` переменная `Х` — строка,
+//! а после `КонецЕсли` снова имеет исходный тип. Здесь по тексту модуля
+//! определяется, какие проверки действуют в заданной позиции:
+//!
+//! - `ТипЗнч(Х) = Тип("Т")` уточняет `Х` до `Т` в своей ветке (условия,
+//!   соединённые `И`, уточняют каждую переменную);
+//! - проверки одной переменной через `ИЛИ` дают объединение типов;
+//! - `ТипЗнч(Х) <> Тип("Т")` уточняет `Х` до `Т` в последующих ветках
+//!   `ИначеЕсли` и `Иначе`;
+//! - присваивание переменной внутри ветки отменяет уточнение.
+
+use bumpalo::collections::Vec as ArenaVec;
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::core::example_mining::strip_comment;
+use crate::core::request_arena::{with_request_arena, RequestArena};
+
+/// Уточнение переменной проверкой типа
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeGuard {
+    pub variable: String,
+    /// Имена допустимых типов (несколько — объединение)
+    pub type_names: Vec<String>,
+}
+
+fn check_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r#"(?i)(?:ТипЗнч|TypeOf)\s*\(\s*([\p{L}_][\p{L}\p{N}_]*)\s*\)\s*(=|<>)\s*(?:Тип|Type)\s*\(\s*"([^"]+)"\s*\)"#,
+        )
+        .unwrap()
+    })
+}
+
+fn assignment_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*([\p{L}_][\p{L}\p{N}_]*)\s*=[^=]").unwrap())
+}
+
+/// Проверка `ТипЗнч(переменная) =/<> Тип("имя")`
+struct TypeCheck {
+    variable: String,
+    equals: bool,
+    type_name: String,
+}
+
+fn parse_check(text: &str) -> Option<TypeCheck> {
+    let caps = check_regex().captures(text)?;
+    Some(TypeCheck {
+        variable: caps[1].to_string(),
+        equals: &caps[2] == "=",
+        type_name: caps[3].trim().to_string(),
+    })
+}
+
+/// Снять одну пару скобок, если она охватывает всё условие:
+/// `(ТипЗнч(Х) = Тип("Число"))` → `ТипЗнч(Х) = Тип("Число")`
+fn strip_outer_parens(text: &str) -> &str {
+    let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) else {
+        return text;
+    };
+    let mut depth = 0usize;
+    let mut in_string = false;
+    for ch in inner.chars() {
+        match ch {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                // Первая скобка закрылась раньше конца: `(А) = (Б)`
+                None => return text,
+            },
+            _ => {}
+        }
+    }
+    if depth == 0 {
+        inner
+    } else {
+        text
+    }
+}
+
+/// Условие целиком является одной проверкой (с точностью до скобок)
+fn single_check(text: &str) -> Option<TypeCheck> {
+    let trimmed = strip_outer_parens(text.trim()).trim();
+    let check = parse_check(trimmed)?;
+    let whole = check_regex().find(trimmed)?;
+    (whole.start() == 0 && whole.end() == trimmed.len()).then_some(check)
+}
+
+/// Слова условия вне строковых литералов (в нижнем регистре)
+fn condition_words<'a>(arena: &'a RequestArena, text: &'a str) -> ArenaVec<'a, &'a str> {
+    let mut out = arena.vec();
+    out.extend(
+        words(arena, text)
+            .into_iter()
+            .map(|(_, word)| arena.lowercase(word)),
+    );
+    out
+}
+
+/// Уточнения, действующие в ветке условия и после его ложности
+struct ConditionGuards {
+    when_true: Vec<TypeGuard>,
+    when_false: Vec<TypeGuard>,
+}
+
+fn condition_guards(arena: &RequestArena, condition: &str) -> ConditionGuards {
+    let mut guards = ConditionGuards {
+        when_true: Vec::new(),
+        when_false: Vec::new(),
+    };
+    let words = condition_words(arena, condition);
+    // Отрицание меняет смысл проверок — не уточняем
+    if words.iter().any(|w| *w == "не" || *w == "not") {
+        return guards;
+    }
+
+    if words.iter().any(|w| *w == "или" || *w == "or") {
+        // Проверки одной переменной через ИЛИ: объединение типов
+        let mut union: Option<TypeGuard> = None;
+        for part in split_words(arena, condition, &["или", "or"]) {
+            let Some(check) = single_check(part).filter(|c| c.equals) else {
+                return guards;
+            };
+            match union.as_mut() {
+                None => {
+                    union = Some(TypeGuard {
+                        variable: check.variable,
+                        type_names: vec![check.type_name],
+                    })
+                }
+                Some(guard) if guard.variable.to_lowercase() == check.variable.to_lowercase() => {
+                    guard.type_names.push(check.type_name)
+                }
+                Some(_) => return guards,
+            }
+        }
+        guards.when_true.extend(union);
+        return guards;
+    }
+
+    if let Some(check) = single_check(condition).filter(|c| !c.equals) {
+        guards.when_false.push(TypeGuard {
+            variable: check.variable,
+            type_names: vec![check.type_name],
+        });
+        return guards;
+    }
+    // Проверки через И действуют одновременно
+    for part in split_words(arena, condition, &["и", "and"]) {
+        if let Some(check) = parse_check(part).filter(|c| c.equals) {
+            guards.when_true.push(TypeGuard {
+                variable: check.variable,
+                type_names: vec![check.type_name],
+            });
+        }
+    }
+    guards
+}
+
+/// Слова вне строковых литералов с байтовыми позициями начала
+fn words<'a>(arena: &'a RequestArena, code: &'a str) -> ArenaVec<'a, (usize, &'a str)> {
+    let mut out = arena.vec();
+    let mut in_string = false;
+    let mut start: Option<usize> = None;
+    for (idx, ch) in code.char_indices() {
+        let is_word_char = !in_string && (ch.is_alphanumeric() || ch == '_');
+        match (start, is_word_char) {
+            (None, true) => start = Some(idx),
+            (Some(s), false) => {
+                out.push((s, &code[s..idx]));
+                start = None;
+            }
+            _ => {}
+        }
+        if ch == '"' {
+            in_string = !in_string;
+        }
+    }
+    if let Some(s) = start {
+        out.push((s, &code[s..]));
+    }
+    out
+}
+
+/// Разбить текст по словам-разделителям (вне строк, регистр не важен)
+fn split_words<'a>(
+    arena: &'a RequestArena,
+    text: &'a str,
+    separators: &[&str],
+) -> ArenaVec<'a, &'a str> {
+    let mut parts = arena.vec();
+    let mut from = 0;
+    for (idx, word) in words(arena, text) {
+        if separators.contains(&arena.lowercase(word)) {
+            parts.push(&text[from..idx]);
+            from = idx + word.len();
+        }
+    }
+    parts.push(&text[from..]);
+    parts
+}
+
+/// Открытый блок `Если`
+#[derive(Default)]
+struct Frame {
+    /// Уточнения текущей ветки
+    branch: Vec<TypeGuard>,
+    /// Уточнения из ложности предыдущих условий
+    otherwise: Vec<TypeGuard>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ConditionKind {
+    If,
+    ElseIf,
+}
+
+/// Уточнения типов, действующие в позиции (`line` и `column` с 0, колонка
+/// в символах). Уточнения внешних блоков идут раньше внутренних.
+pub fn type_guards_at(text: &str, line: u32, column: u32) -> Vec<TypeGuard> {
+    with_request_arena(|arena| type_guards_in(arena, text, line, column))
+}
+
+fn type_guards_in(arena: &RequestArena, text: &str, line: u32, column: u32) -> Vec<TypeGuard> {
+    let mut stack: Vec<Frame> = Vec::new();
+    // Начатое, но ещё не закрытое `Тогда` условие
+    let mut condition: Option<(ConditionKind, String)> = None;
+
+    for (idx, source) in text.lines().enumerate() {
+        if idx as u32 > line {
+            break;
+        }
+        let code = strip_comment(source);
+        // Байтовая граница позиции в строке запроса
+        let limit = if idx as u32 == line {
+            code.char_indices()
+                .nth(column as usize)
+                .map_or(code.len(), |(byte, _)| byte)
+        } else {
+            code.len()
+        };
+
+        if condition.is_none() {
+            if let Some(caps) = assignment_regex().captures(&code[..limit]) {
+                let variable = caps[1].to_lowercase();
+                for frame in stack.iter_mut() {
+                    frame
+                        .branch
+                        .retain(|g| g.variable.to_lowercase() != variable);
+                }
+            }
+        }
+
+        let mut condition_from = 0;
+        for (pos, word) in words(arena, code) {
+            if pos >= limit {
+                break;
+            }
+            // Инструкции препроцессора (`#Если Сервер Тогда`) не ветвят код
+            if code[..pos].trim_end().ends_with('#') {
+                continue;
+            }
+            match arena.lowercase(word) {
+                "если" | "if" => {
+                    condition = Some((ConditionKind::If, String::new()));
+                    condition_from = pos + word.len();
+                }
+                "иначеесли" | "elsif" => {
+                    condition = Some((ConditionKind::ElseIf, String::new()));
+                    condition_from = pos + word.len();
+                }
+                "тогда" | "then" => {
+                    let Some((kind, mut text)) = condition.take() else {
+                        continue;
+                    };
+                    text.push_str(&code[condition_from..pos]);
+                    let guards = condition_guards(arena, &text);
+                    if kind == ConditionKind::If {
+                        stack.push(Frame::default());
+                    }
+                    if let Some(frame) = stack.last_mut() {
+                        frame.branch = frame.otherwise.clone();
+                        frame.branch.extend(guards.when_true);
+                        frame.otherwise.extend(guards.when_false);
+                    }
+                }
+                "иначе" | "else" => {
+                    if let Some(frame) = stack.last_mut() {
+                        frame.branch = frame.otherwise.clone();
+                    }
+                }
+                "конецесли" | "endif" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+        // Условие продолжается на следующей строке
+        if let Some((_, text)) = condition.as_mut() {
+            text.push_str(&code[condition_from.min(code.len())..]);
+            text.push(' ');
+        }
+    }
+
+    stack.into_iter().flat_map(|frame| frame.branch).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = r#"Процедура Обработать(Значение)
+    Если ТипЗнч(Значение) = Тип("Строка") Тогда
+        Сообщить(Значение);
+    ИначеЕсли ТипЗнч(Значение) = Тип("Число") ИЛИ ТипЗнч(Значение) = Тип("Дата") Тогда
+        Сообщить(Значение);
+    ИначеЕсли ТипЗнч(Значение) <> Тип("Массив") Тогда
+        Возврат;
+    Иначе
+        Сообщить(Значение.Количество());
+    КонецЕсли;
+    Сообщить(Значение);
+КонецПроцедуры"#;
+
+    fn types_at(line: u32) -> Vec<Vec<String>> {
+        type_guards_at(MODULE, line, 8)
+            .into_iter()
+            .map(|g| g.type_names)
+            .collect()
+    }
+
+    #[test]
+    fn test_if_elsif_chain() {
+        assert_eq!(types_at(2), [["Строка"]]);
+        assert_eq!(types_at(4), [["Число", "Дата"]]);
+        assert!(types_at(6).is_empty());
+        // Иначе после `<> Тип("Массив")` — значит, массив
+        assert_eq!(types_at(8), [["Массив"]]);
+        // После КонецЕсли уточнение снимается
+        assert!(types_at(10).is_empty());
+        // В самом условии переменная ещё не уточнена
+        assert!(type_guards_at(MODULE, 1, 20).is_empty());
+    }
+
+    #[test]
+    fn test_outer_parens() {
+        assert_eq!(
+            strip_outer_parens(r#"(ТипЗнч(Х) = Тип("Число"))"#),
+            r#"ТипЗнч(Х) = Тип("Число")"#
+        );
+        // Скобки не охватывают условие целиком
+        let text = r#"ТипЗнч(Х) = Тип("Число")"#;
+        assert_eq!(strip_outer_parens(text), text);
+        assert_eq!(strip_outer_parens("(А) = (Б)"), "(А) = (Б)");
+        assert_eq!(strip_outer_parens(r#"(Х = ")")"#), r#"Х = ")""#);
+    }
+
+    #[test]
+    fn test_nested_and_reassigned() {
+        let text = r#"Если ТипЗнч(А) = Тип("Строка") И ТипЗнч(Б) = Тип("Число") Тогда
+    Если ТипЗнч(А) = Тип("Число") Тогда
+        Б = 1;
+        Х = А;
+    КонецЕсли;
+КонецЕсли;"#;
+        let guards = type_guards_at(text, 1, 0);
+        let variables: Vec<&str> = guards.iter().map(|g| g.variable.as_str()).collect();
+        assert_eq!(variables, ["А", "Б"]);
+
+        // Присваивание снимает уточнение Б, внутреннее уточнение А последнее
+        let guards = type_guards_at(text, 3, 4);
+        assert_eq!(guards.len(), 2);
+        assert_eq!(guards[1].type_names, ["Число"]);
+        assert!(guards.iter().all(|g| g.variable == "А"));
+    }
+
+    #[test]
+    fn test_negation_and_preprocessor_ignored() {
+        let text = r#"#Если Сервер Тогда
+Если НЕ ТипЗнч(А) = Тип("Строка") Тогда
+    А = А;
+КонецЕсли;
+#КонецЕсли"#;
+        assert!(type_guards_at(text, 2, 4).is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+//! Объекты справочников, планов, обработок и отчётов
+//!
+//! В модуле объекта (`Catalogs/Номенклатура/Ext/ObjectModule.bsl`)
+//! `ЭтотОбъект` — `СправочникОбъект.Номенклатура`: реквизиты и табличные
+//! части из описания конфигурации плюс стандартные члены вида объекта
+//! (`Код`, `Наименование`, `Записать()`). Реквизиты и табличные части
+//! доступны в модуле и без квалификатора.
+//!
+//! Табличная часть (`СправочникТабличнаяЧасть.Номенклатура.Штрихкоды`) и её
+//! строка (`СправочникТабличнаяЧастьСтрока.Номенклатура.Штрихкоды`) хранятся
+//! в репозитории отдельными типами с колонками из описания; документы
+//! используют их так же. Объекты документов описаны в `documents`.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use super::managers::{ManagerKind, MANAGER_KINDS};
+use crate::domain::types::{ConcreteType, Method, PlatformType, Property, TypeResolution};
+use crate::unified::data::{
+    ParseMetadata, RawMethodData, RawPropertyData, RawTypeData, TypeSource,
+};
+
+const OBJECT_SUFFIX: &str = "Объект";
+const TABULAR_SECTION: &str = "ТабличнаяЧасть";
+const TABULAR_SECTION_ROW: &str = "ТабличнаяЧастьСтрока";
+
+/// Категории объектов с модулем объекта, кроме документов
+const OBJECT_CATEGORIES: &[&str] = &[
+    "Catalog",
+    "ChartOfCharacteristicTypes",
+    "ChartOfAccounts",
+    "DataProcessor",
+    "Report",
+];
+
+/// Объект конфигурации (`СправочникОбъект.Номенклатура`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectType {
+    /// Категория типа конфигурации (`Catalog`)
+    pub category: &'static str,
+    pub object: String,
+}
+
+impl ObjectType {
+    pub fn kind(&self) -> &'static ManagerKind {
+        MANAGER_KINDS
+            .iter()
+            .find(|kind| kind.category == self.category)
+            .expect("категория объекта из MANAGER_KINDS")
+    }
+
+    pub fn type_name(&self) -> String {
+        format!(
+            "{}{}.{}",
+            self.kind().type_prefix,
+            OBJECT_SUFFIX,
+            self.object
+        )
+    }
+
+    /// Разобрать имя типа объекта; `ДокументОбъект` разбирает `DocumentType`
+    pub fn parse(type_name: &str) -> Option<Self> {
+        let (prefix, object) = type_name.trim().split_once('.')?;
+        if object.is_empty() || object.contains('.') {
+            return None;
+        }
+        let kind = object_kinds().find(|kind| {
+            prefix
+                .strip_suffix(OBJECT_SUFFIX)
+                .is_some_and(|type_prefix| type_prefix == kind.type_prefix)
+        })?;
+        Some(Self {
+            category: kind.category,
+            object: object.to_string(),
+        })
+    }
+}
+
+fn object_kinds() -> impl Iterator<Item = &'static ManagerKind> {
+    MANAGER_KINDS
+        .iter()
+        .filter(|kind| OBJECT_CATEGORIES.contains(&kind.category))
+}
+
+fn object_module_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?:^|[/\\])(Catalogs|ChartsOfCharacteristicTypes|ChartsOfAccounts|DataProcessors|Reports)",
+            r"[/\\]([^/\\]+)[/\\](?:Ext[/\\])?ObjectModule\.bsl$",
+        ))
+        .unwrap()
+    })
+}
+
+/// Объект, модулем которого является файл
+/// (`Catalogs/Имя/Ext/ObjectModule.bsl` или `src/Catalogs/Имя/ObjectModule.bsl`)
+pub fn object_module(file_path: &str) -> Option<ObjectType> {
+    let caps = object_module_regex().captures(file_path)?;
+    let kind = object_kinds().find(|kind| kind.english == &caps[1])?;
+    Some(ObjectType {
+        category: kind.category,
+        object: caps[2].to_string(),
+    })
+}
+
+/// Строка табличной части:
+/// `СправочникТабличнаяЧасть.Номенклатура.Штрихкоды` →
+/// `СправочникТабличнаяЧастьСтрока.Номенклатура.Штрихкоды`
+pub fn tabular_section_row_type(section_type: &str) -> Option<String> {
+    let (prefix, rest) = section_type.split_once('.')?;
+    let kind = prefix.strip_suffix(TABULAR_SECTION)?;
+    Some(format!("{}{}.{}", kind, TABULAR_SECTION_ROW, rest))
+}
+
+fn method(name: &str, return_type: Option<&str>) -> Method {
+    Method {
+        name: name.to_string(),
+        parameters: Vec::new(),
+        return_type: return_type.map(str::to_string),
+        is_function: return_type.is_some(),
+    }
+}
+
+fn property(name: &str, type_name: &str, readonly: bool) -> Property {
+    Property {
+        name: name.to_string(),
+        type_: type_name.to_string(),
+        readonly,
+    }
+}
+
+/// Стандартные реквизиты и методы объекта со ссылкой (справочник, план)
+fn reference_object_members(
+    object: &ObjectType,
+    properties: &mut Vec<(&'static str, String, bool)>,
+    methods: &mut Vec<Method>,
+) {
+    let prefix = object.kind().type_prefix;
+    let reference = format!("{}Ссылка.{}", prefix, object.object);
+    properties.extend([
+        ("Ссылка", reference.clone(), true),
+        ("Код", "Строка".to_string(), false),
+        ("Наименование", "Строка".to_string(), false),
+        ("ПометкаУдаления", "Булево".to_string(), true),
+        ("Предопределенный", "Булево".to_string(), true),
+        ("ИмяПредопределенныхДанных", "Строка".to_string(), false),
+        ("Родитель", reference.clone(), false),
+        ("ОбменДанными", "ПараметрыОбменаДанными".to_string(), true),
+        ("ДополнительныеСвойства", "Структура".to_string(), true),
+    ]);
+    match object.category {
+        "ChartOfAccounts" => properties.extend([
+            ("Порядок", "Строка".to_string(), false),
+            ("Вид", "ВидСчета".to_string(), false),
+            ("Забалансовый", "Булево".to_string(), false),
+        ]),
+        "ChartOfCharacteristicTypes" => {
+            properties.push(("ТипЗначения", "ОписаниеТипов".to_string(), false));
+            properties.push(("ЭтоГруппа", "Булево".to_string(), true));
+        }
+        _ => properties.push(("ЭтоГруппа", "Булево".to_string(), true)),
+    }
+    let object_type = object.type_name();
+    methods.extend([
+        method("Записать", None),
+        method("Удалить", None),
+        method("Прочитать", None),
+        method("Заблокировать", None),
+        method("Разблокировать", None),
+        method("Заполнить", None),
+        method("УстановитьНовыйКод", None),
+        method("УстановитьПометкуУдаления", None),
+        method("УстановитьСсылкуНового", None),
+        method("ПолучитьСсылкуНового", Some(&reference)),
+        method("ПроверитьЗаполнение", Some("Булево")),
+        method("Модифицированность", Some("Булево")),
+        method("ЭтоНовый", Some("Булево")),
+        method("Заблокирован", Some("Булево")),
+        method("Скопировать", Some(&object_type)),
+    ]);
+}
+
+/// Объект `object` с реквизитами и табличными частями `attributes` из
+/// описания конфигурации и стандартными членами своего вида
+pub fn object_resolution(object: &ObjectType, attributes: &[RawPropertyData]) -> TypeResolution {
+    let mut properties: Vec<Property> = attributes
+        .iter()
+        .map(|a| property(&a.name, &a.type_name, a.is_readonly))
+        .collect();
+    let mut standard = Vec::new();
+    let mut methods = Vec::new();
+    match object.category {
+        "DataProcessor" | "Report" => {
+            if object.category == "Report" {
+                standard.push((
+                    "КомпоновщикНастроек",
+                    "КомпоновщикНастроекКомпоновкиДанных".to_string(),
+                    true,
+                ));
+                methods.push(method("СкомпоноватьРезультат", None));
+                methods.push(method(
+                    "ПолучитьНастройкиПоУмолчанию",
+                    Some("НастройкиКомпоновкиДанных"),
+                ));
+            }
+            methods.push(method("ПолучитьФорму", Some("ФормаКлиентскогоПриложения")));
+            methods.push(method("ПолучитьМакет", None));
+            methods.push(method("ПроверитьЗаполнение", Some("Булево")));
+        }
+        _ => reference_object_members(object, &mut standard, &mut methods),
+    }
+    for (name, type_name, readonly) in standard {
+        if !properties.iter().any(|p| p.name == name) {
+            properties.push(property(name, &type_name, readonly));
+        }
+    }
+    TypeResolution::known(ConcreteType::Platform(PlatformType {
+        name: object.type_name(),
+        methods,
+        properties,
+    }))
+}
+
+/// Типы табличной части `section_type` и её строки для репозитория:
+/// методы коллекции строк и колонки `columns` из описания табличной части
+pub fn tabular_section_raw_types(
+    section_type: &str,
+    columns: &[RawPropertyData],
+    file_path: &str,
+) -> Vec<RawTypeData> {
+    let Some(row_type) = tabular_section_row_type(section_type) else {
+        return Vec::new();
+    };
+    let method = |name: &str, return_type: Option<&str>| RawMethodData {
+        name: name.to_string(),
+        documentation: String::new(),
+        parameters: Vec::new(),
+        return_type: return_type.map(str::to_string),
+        return_type_name: return_type.map(str::to_string),
+        params: Vec::new(),
+        is_function: return_type.is_some(),
+        examples: Vec::new(),
+    };
+    let raw_type = |name: &str, category: &str, methods, properties, documentation| RawTypeData {
+        id: name.to_string(),
+        russian_name: name.to_string(),
+        english_name: name.to_string(),
+        source: TypeSource::Platform {
+            version: "8.3".to_string(),
+        },
+        category_path: vec![category.to_string()],
+        methods,
+        properties,
+        documentation,
+        examples: Vec::new(),
+        available_facets: Vec::new(),
+        parse_metadata: ParseMetadata {
+            file_path: file_path.to_string(),
+            line: 0,
+            column: 0,
+        },
+    };
+    let row = Some(row_type.as_str());
+    let section = raw_type(
+        section_type,
+        TABULAR_SECTION,
+        vec![
+            method("Добавить", row),
+            method("Вставить", row),
+            method("Получить", row),
+            method("Найти", row),
+            method("НайтиСтроки", Some("Массив")),
+            method("Количество", Some("Число")),
+            method("Индекс", Some("Число")),
+            method("Итог", Some("Число")),
+            method("Выгрузить", Some("ТаблицаЗначений")),
+            method("ВыгрузитьКолонку", Some("Массив")),
+            method("Загрузить", None),
+            method("ЗагрузитьКолонку", None),
+            method("Очистить", None),
+            method("Удалить", None),
+            method("Сдвинуть", None),
+            method("Сортировать", None),
+        ],
+        Vec::new(),
+        format!("Табличная часть {}", section_type),
+    );
+    let mut properties = columns.to_vec();
+    if !properties.iter().any(|p| p.name == "НомерСтроки") {
+        properties.push(RawPropertyData {
+            name: "НомерСтроки".to_string(),
+            type_name: "Число".to_string(),
+            is_readonly: true,
+            description: String::new(),
+        });
+    }
+    let row = raw_type(
+        &row_type,
+        TABULAR_SECTION_ROW,
+        Vec::new(),
+        properties,
+        format!("Строка табличной части {}", section_type),
+    );
+    vec![section, row]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_module_and_type() {
+        let object = object_module("Catalogs/Номенклатура/Ext/ObjectModule.bsl").unwrap();
+        assert_eq!(object.type_name(), "СправочникОбъект.Номенклатура");
+        assert_eq!(ObjectType::parse(&object.type_name()), Some(object));
+        let object = object_module("src/DataProcessors/Загрузка/ObjectModule.bsl").unwrap();
+        assert_eq!(object.type_name(), "ОбработкаОбъект.Загрузка");
+        assert!(object_module("Catalogs/Номенклатура/Ext/ManagerModule.bsl").is_none());
+        assert!(object_module("Documents/Заказ/Ext/ObjectModule.bsl").is_none());
+        assert!(ObjectType::parse("ДокументОбъект.Заказ").is_none());
+        assert!(ObjectType::parse("СправочникСсылка.Номенклатура").is_none());
+    }
+
+    #[test]
+    fn test_object_resolution_members() {
+        let object = ObjectType::parse("СправочникОбъект.Номенклатура").unwrap();
+        let attributes = vec![RawPropertyData {
+            name: "Штрихкоды".to_string(),
+            type_name: "СправочникТабличнаяЧасть.Номенклатура.Штрихкоды".to_string(),
+            is_readonly: true,
+            description: String::new(),
+        }];
+        let raw = object_resolution(&object, &attributes).to_raw_data();
+        assert_eq!(raw.russian_name, "СправочникОбъект.Номенклатура");
+        let parent = raw
+            .properties
+            .iter()
+            .find(|p| p.name == "Родитель")
+            .unwrap();
+        assert_eq!(parent.type_name, "СправочникСсылка.Номенклатура");
+        assert!(raw.properties.iter().any(|p| p.name == "Штрихкоды"));
+        assert!(raw.methods.iter().any(|m| m.name == "УстановитьНовыйКод"));
+
+        let report = ObjectType::parse("ОтчетОбъект.Продажи").unwrap();
+        let raw = object_resolution(&report, &[]).to_raw_data();
+        assert!(raw.properties.iter().all(|p| p.name != "Ссылка"));
+        assert!(raw
+            .methods
+            .iter()
+            .any(|m| m.name == "СкомпоноватьРезультат"));
+    }
+
+    #[test]
+    fn test_tabular_section_raw_types() {
+        let columns = vec![RawPropertyData {
+            name: "Штрихкод".to_string(),
+            type_name: "Строка(20)".to_string(),
+            is_readonly: false,
+            description: String::new(),
+        }];
+        let section_type = "СправочникТабличнаяЧасть.Номенклатура.Штрихкоды";
+        let types = tabular_section_raw_types(section_type, &columns, "Catalogs/Номенклатура.xml");
+        assert_eq!(types.len(), 2);
+        let add = types[0]
+            .methods
+            .iter()
+            .find(|m| m.name == "Добавить")
+            .unwrap();
+        assert_eq!(
+            add.return_type.as_deref(),
+            Some("СправочникТабличнаяЧастьСтрока.Номенклатура.Штрихкоды")
+        );
+        assert_eq!(types[1].russian_name, add.return_type.clone().unwrap());
+        let names: Vec<_> = types[1]
+            .properties
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, ["Штрихкод", "НомерСтроки"]);
+        assert!(tabular_section_row_type("СправочникСсылка.Номенклатура").is_none());
+    }
+}
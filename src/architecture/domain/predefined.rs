@@ -0,0 +1,239 @@
+//! Предопределённые элементы справочников и планов
+//!
+//! `Справочники.Валюты.Рубль` и `ПредопределенноеЗначение("Справочник.Валюты.Рубль")`
+//! — ссылка `СправочникСсылка.Валюты`. Элементы хранятся в репозитории
+//! отдельным типом `СправочникПредопределенные.Валюты`, а не среди свойств
+//! типа объекта: там они смешались бы с реквизитами объекта и ссылки.
+//! Элемента, которого в описании объекта нет, не существует — такое
+//! обращение при выполнении падает.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use super::enums::is_identifier;
+use super::managers::{ManagerKind, MANAGER_KINDS};
+use crate::data::loaders::predefined::PredefinedItem;
+use crate::unified::data::{ParseMetadata, RawPropertyData, RawTypeData, TypeSource};
+
+const PREDEFINED_SUFFIX: &str = "Предопределенные";
+const EMPTY_REFERENCES: &[&str] = &["ПустаяСсылка", "EmptyRef"];
+
+/// Описание предопределённого элемента в свойствах типа и автодополнении
+pub const PREDEFINED_DETAIL: &str = "Предопределенный элемент";
+
+/// Категории объектов с предопределёнными элементами
+const PREDEFINED_CATEGORIES: &[&str] =
+    &["Catalog", "ChartOfAccounts", "ChartOfCharacteristicTypes"];
+
+/// Значения перечислений тоже получают через `ПредопределенноеЗначение`
+const ENUM_CATEGORY: &str = "Enum";
+
+fn predefined_value_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"(?i)^(?:ПредопределенноеЗначение|PredefinedValue)\s*\(\s*"([^"]*)"\s*\)$"#)
+            .unwrap()
+    })
+}
+
+fn predefined_kinds() -> impl Iterator<Item = &'static ManagerKind> {
+    MANAGER_KINDS
+        .iter()
+        .filter(|kind| PREDEFINED_CATEGORIES.contains(&kind.category))
+}
+
+/// Обращение к предопределённому элементу
+#[derive(Debug, Clone)]
+pub struct PredefinedRef {
+    pub kind: &'static ManagerKind,
+    pub object: String,
+    pub item: String,
+}
+
+impl PredefinedRef {
+    /// Разобрать `Справочники.Валюты.Рубль`; методы менеджера
+    /// (`ПустаяСсылка()`) и более длинные цепочки — не элементы
+    pub fn parse(expression: &str) -> Option<Self> {
+        let mut parts = expression.trim().split('.').map(str::trim);
+        let (Some(collection), Some(object), Some(item), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        let collection = collection.to_lowercase();
+        let kind = predefined_kinds().find(|kind| {
+            kind.collection.to_lowercase() == collection
+                || kind.english.to_lowercase() == collection
+        })?;
+        let is_method = kind
+            .methods
+            .iter()
+            .any(|method| method.name.to_lowercase() == item.to_lowercase());
+        if !is_identifier(object) || !is_identifier(item) || is_method {
+            return None;
+        }
+        Some(Self {
+            kind,
+            object: object.to_string(),
+            item: item.to_string(),
+        })
+    }
+
+    /// Разобрать `ПредопределенноеЗначение("Справочник.Валюты.Рубль")`;
+    /// вид объекта — русский (`ПланСчетов`) или английский (`ChartOfAccounts`),
+    /// перечисление (`Перечисление.СтатусыЗаказов.Оплачен`) — тоже
+    pub fn from_value_call(expression: &str) -> Option<Self> {
+        let caps = predefined_value_regex().captures(expression.trim())?;
+        let mut parts = caps[1].split('.').map(str::trim);
+        let (Some(kind_name), Some(object), Some(item), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        let kind_name = kind_name.to_lowercase();
+        let kind = MANAGER_KINDS.iter().find(|kind| {
+            (PREDEFINED_CATEGORIES.contains(&kind.category) || kind.category == ENUM_CATEGORY)
+                && (kind.type_prefix.to_lowercase() == kind_name
+                    || kind.category.to_lowercase() == kind_name)
+        })?;
+        if !is_identifier(object) || !is_identifier(item) {
+            return None;
+        }
+        Some(Self {
+            kind,
+            object: object.to_string(),
+            item: item.to_string(),
+        })
+    }
+
+    /// Тип значения: `СправочникСсылка.Валюты`
+    pub fn reference_type(&self) -> String {
+        format!("{}Ссылка.{}", self.kind.type_prefix, self.object)
+    }
+
+    /// `ПредопределенноеЗначение("Справочник.Валюты.ПустаяСсылка")`
+    pub fn is_empty_reference(&self) -> bool {
+        let item = self.item.to_lowercase();
+        EMPTY_REFERENCES
+            .iter()
+            .any(|empty| empty.to_lowercase() == item)
+    }
+
+    /// Значение перечисления, полученное через `ПредопределенноеЗначение`
+    pub fn is_enum_value(&self) -> bool {
+        self.kind.category == ENUM_CATEGORY
+    }
+}
+
+/// Имя типа со списком элементов: `СправочникПредопределенные.Валюты`
+pub fn predefined_type_name(kind: &ManagerKind, object: &str) -> String {
+    format!("{}{}.{}", kind.type_prefix, PREDEFINED_SUFFIX, object)
+}
+
+/// Тип со списком предопределённых элементов объекта категории `category`;
+/// `None` — у объектов категории их не бывает
+pub fn predefined_raw_type(
+    category: &str,
+    object: &str,
+    items: &[PredefinedItem],
+    file_path: &str,
+) -> Option<RawTypeData> {
+    let kind = predefined_kinds().find(|kind| kind.category == category)?;
+    let name = predefined_type_name(kind, object);
+    let reference = format!("{}Ссылка.{}", kind.type_prefix, object);
+    let properties = items
+        .iter()
+        .map(|item| {
+            let mut parts = vec![if item.is_folder {
+                format!("{} (группа)", PREDEFINED_DETAIL)
+            } else {
+                PREDEFINED_DETAIL.to_string()
+            }];
+            parts.extend(item.code.iter().map(|code| format!("Код {}", code)));
+            parts.extend(item.description.iter().cloned());
+            RawPropertyData {
+                name: item.name.clone(),
+                type_name: reference.clone(),
+                is_readonly: true,
+                description: parts.join(". "),
+            }
+        })
+        .collect();
+    Some(RawTypeData {
+        id: name.clone(),
+        russian_name: name.clone(),
+        english_name: name,
+        source: TypeSource::Platform {
+            version: "8.3".to_string(),
+        },
+        category_path: vec![PREDEFINED_SUFFIX.to_string()],
+        methods: Vec::new(),
+        properties,
+        documentation: format!("Предопределенные элементы {}", object),
+        examples: vec![format!("{}.{}.", kind.collection, object)],
+        available_facets: Vec::new(),
+        parse_metadata: ParseMetadata {
+            file_path: file_path.to_string(),
+            line: 0,
+            column: 0,
+        },
+    })
+}
+
+/// Есть ли элемент `item` в списке (имя без учёта регистра)
+pub fn has_item(raw: &RawTypeData, item: &str) -> bool {
+    let item = item.to_lowercase();
+    raw.properties
+        .iter()
+        .any(|property| property.name.to_lowercase() == item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predefined_references() {
+        let reference = PredefinedRef::parse("Справочники.Валюты.Рубль").unwrap();
+        assert_eq!(reference.reference_type(), "СправочникСсылка.Валюты");
+        assert_eq!(reference.item, "Рубль");
+        let reference = PredefinedRef::parse("ChartsOfAccounts.Хозрасчетный.Касса").unwrap();
+        assert_eq!(reference.reference_type(), "ПланСчетовСсылка.Хозрасчетный");
+        assert!(PredefinedRef::parse("Справочники.Валюты.ПустаяСсылка").is_none());
+        assert!(PredefinedRef::parse("Справочники.Валюты.НайтиПоКоду(\"643\")").is_none());
+        assert!(PredefinedRef::parse("Документы.Заказ.Первый").is_none());
+
+        let call = r#"ПредопределенноеЗначение("Справочник.Валюты.Рубль")"#;
+        let reference = PredefinedRef::from_value_call(call).unwrap();
+        assert_eq!(reference.reference_type(), "СправочникСсылка.Валюты");
+        let call = r#"PredefinedValue("Catalog.Валюты.EmptyRef")"#;
+        assert!(PredefinedRef::from_value_call(call)
+            .unwrap()
+            .is_empty_reference());
+        let call = r#"ПредопределенноеЗначение("Справочник.Валюты.ПустаяСсылка")"#;
+        assert!(PredefinedRef::from_value_call(call)
+            .unwrap()
+            .is_empty_reference());
+        let call = r#"ПредопределенноеЗначение("Перечисление.СтатусыЗаказов.Оплачен")"#;
+        assert!(PredefinedRef::from_value_call(call)
+            .unwrap()
+            .is_enum_value());
+        assert!(PredefinedRef::from_value_call(r#"ПредопределенноеЗначение(Имя)"#).is_none());
+
+        let items = vec![PredefinedItem {
+            name: "Рубль".to_string(),
+            code: Some("643".to_string()),
+            description: None,
+            is_folder: false,
+        }];
+        let raw = predefined_raw_type("Catalog", "Валюты", &items, "Catalogs/Валюты.xml").unwrap();
+        assert_eq!(raw.russian_name, "СправочникПредопределенные.Валюты");
+        assert_eq!(raw.properties[0].type_name, "СправочникСсылка.Валюты");
+        assert_eq!(
+            raw.properties[0].description,
+            "Предопределенный элемент. Код 643"
+        );
+        assert!(has_item(&raw, "рубль"));
+        assert!(predefined_raw_type("Document", "Заказ", &items, "").is_none());
+    }
+}
@@ -0,0 +1,1053 @@
+//! Колонки результата запроса
+//!
+//! Текст запроса из кода (`Запрос.Текст = "ВЫБРАТЬ ... ИЗ Справочник.Номенклатура"`)
+//! разбирается парсером языка запросов, таблицы-источники сопоставляются с
+//! объектами конфигурации, и каждая колонка получает тип: поле таблицы — тип
+//! реквизита из описания объекта или стандартного поля (`Ссылка`, `Код`,
+//! `Дата`), в том числе через ссылку (`Номенклатура.Родитель.Наименование`);
+//! `КОЛИЧЕСТВО` и `СУММА` — `Число`, `ВЫРАЗИТЬ` — указанный тип, литерал —
+//! свой. Выборка `Запрос.Выполнить().Выбрать()` — платформенный тип
+//! `ВыборкаИзРезультатаЗапроса` с колонками-свойствами, поэтому
+//! `Выборка.Поле` разрешается так же, как реквизит объекта.
+//!
+//! В пакетном запросе результат даёт последний запрос; колонки временной
+//! таблицы (`ПОМЕСТИТЬ ВТ`) переходят в запросы, которые из неё выбирают.
+//!
+//! Параметры запроса (`&Товар`) получают ожидаемый тип от того, с чем их
+//! сравнивают (`Товары.Ссылка = &Товар`, `Дата МЕЖДУ &Начало И &Конец`,
+//! `Товары.Ссылка В (&Список)`), и сверяются с вызовами
+//! `Запрос.УстановитьПараметр` того же метода: параметр без значения и
+//! значение несовместимого типа — диагностики.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::managers::{ManagerKind, MANAGER_KINDS};
+use super::ExpressionResolver;
+use crate::core::module_structure::method_header_regex;
+use crate::domain::types::{
+    ConcreteType, Method, PlatformType, Property, ResolutionResult, SpecialType, TypeResolution,
+};
+use crate::parsing::query::ast::{
+    BinaryOperator, DataType, Expression, Literal, Query, TableReference, UnaryOperator,
+};
+use crate::parsing::query::embedded::{
+    embedded_queries, parameter_calls, EmbeddedQuery, ParameterCall,
+};
+use crate::parsing::query::parser::parse_1c_queries;
+use crate::unified::data::{composite_type_names, RawPropertyData, RawTypeData};
+
+pub const QUERY_TYPE: &str = "Запрос";
+pub const RESULT_TYPE: &str = "РезультатЗапроса";
+pub const SELECTION_TYPE: &str = "ВыборкаИзРезультатаЗапроса";
+
+/// Поля виртуальных таблиц регистров, образованные от имени ресурса
+/// (`КоличествоОстаток`)
+const RESOURCE_SUFFIXES: &[&str] = &[
+    "НачальныйОстаток",
+    "КонечныйОстаток",
+    "Остаток",
+    "Оборот",
+    "Приход",
+    "Расход",
+];
+
+/// Что получено из запроса к моменту присваивания
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryStage {
+    /// `Новый Запрос` с текстом
+    Query,
+    /// `Запрос.Выполнить()`
+    Result,
+    /// `Результат.Выбрать()`
+    Selection,
+}
+
+impl QueryStage {
+    pub fn type_name(self) -> &'static str {
+        match self {
+            QueryStage::Query => QUERY_TYPE,
+            QueryStage::Result => RESULT_TYPE,
+            QueryStage::Selection => SELECTION_TYPE,
+        }
+    }
+
+    /// Стадия после вызова метода: `Выполнить()` у запроса, `Выбрать()` у
+    /// результата или выборки
+    pub fn after_call(self, method: &str) -> Option<QueryStage> {
+        match (self, method.to_lowercase().as_str()) {
+            (QueryStage::Query, "выполнить" | "execute") => Some(QueryStage::Result),
+            (QueryStage::Result | QueryStage::Selection, "выбрать" | "select") => {
+                Some(QueryStage::Selection)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Таблица объекта конфигурации в запросе
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryTable {
+    /// Префикс вида объекта (`Справочник`, `РегистрНакопления`)
+    pub prefix: &'static str,
+    pub object: String,
+    /// Виртуальная таблица регистра (`Остатки`)
+    pub virtual_table: Option<String>,
+}
+
+impl QueryTable {
+    fn new(prefix: &str, object: &str, virtual_table: Option<&str>) -> Option<Self> {
+        let kind = MANAGER_KINDS
+            .iter()
+            .find(|kind| kind.type_prefix.to_lowercase() == prefix.trim().to_lowercase())?;
+        Some(Self {
+            prefix: kind.type_prefix,
+            object: object.trim().to_string(),
+            virtual_table: virtual_table.map(str::to_string),
+        })
+    }
+
+    fn kind(&self) -> &'static ManagerKind {
+        MANAGER_KINDS
+            .iter()
+            .find(|kind| kind.type_prefix == self.prefix)
+            .expect("префикс взят из MANAGER_KINDS")
+    }
+
+    /// Описание объекта таблицы в конфигурации
+    pub fn describes(&self, raw: &RawTypeData) -> bool {
+        self.kind().describes(raw, &self.object)
+    }
+
+    /// Тип поля: реквизит из описания объекта (`properties`), стандартное
+    /// поле или поле ресурса виртуальной таблицы; `None` — поле не известно
+    pub fn field_type(&self, properties: &[RawPropertyData], field: &str) -> Option<String> {
+        let lower = field.to_lowercase();
+        if let Some(property) = properties.iter().find(|p| p.name.to_lowercase() == lower) {
+            return Some(property.type_name.clone());
+        }
+        if let Some(standard) = self.standard_field_type(&lower) {
+            return Some(standard);
+        }
+        let resource_field = self.virtual_table.is_some()
+            && RESOURCE_SUFFIXES
+                .iter()
+                .any(|suffix| field.len() > suffix.len() && field.ends_with(suffix));
+        resource_field.then(|| "Число".to_string())
+    }
+
+    fn standard_field_type(&self, field: &str) -> Option<String> {
+        let reference = || format!("{}Ссылка.{}", self.prefix, self.object);
+        let referenced = matches!(
+            self.prefix,
+            "Справочник" | "Документ" | "Перечисление" | "ПланСчетов" | "ПланВидовХарактеристик"
+        );
+        let hierarchical = matches!(
+            self.prefix,
+            "Справочник" | "ПланСчетов" | "ПланВидовХарактеристик"
+        );
+        let register = self.prefix.starts_with("Регистр");
+        let type_name = match field {
+            "ссылка" if referenced => return Some(reference()),
+            "родитель" if hierarchical => return Some(reference()),
+            "представление" => "Строка",
+            "пометкаудаления" | "предопределенный" if referenced => {
+                "Булево"
+            }
+            "код" | "наименование" if hierarchical => "Строка",
+            "этогруппа" if hierarchical => "Булево",
+            "номер" if self.prefix == "Документ" => "Строка",
+            "дата" if self.prefix == "Документ" => "Дата",
+            "проведен" if self.prefix == "Документ" => "Булево",
+            "период" if register => "Дата",
+            "номерстроки" if register => "Число",
+            "активность" if register => "Булево",
+            "виддвижения" if self.prefix == "РегистрНакопления" => {
+                "ВидДвиженияНакопления"
+            }
+            _ => return None,
+        };
+        Some(type_name.to_string())
+    }
+}
+
+/// Таблица объекта по типу ссылки: `СправочникСсылка.Валюты`
+pub fn reference_table(type_name: &str) -> Option<QueryTable> {
+    let (kind, object) = type_name.split_once('.')?;
+    QueryTable::new(kind.strip_suffix("Ссылка")?, object, None)
+}
+
+/// Откуда берётся значение колонки
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnOrigin {
+    /// Поле таблицы; путь длиннее одного звена идёт через ссылки
+    Field {
+        table: QueryTable,
+        path: Vec<String>,
+    },
+    /// Тип известен по выражению
+    Type(String),
+    Unknown,
+}
+
+/// Колонка результата запроса
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryColumn {
+    pub name: String,
+    pub origin: ColumnOrigin,
+}
+
+/// Источник в `ИЗ`: таблица конфигурации или временная таблица
+enum Source {
+    Table(QueryTable),
+    Temporary(Vec<QueryColumn>),
+    Unknown,
+}
+
+fn type_origin(name: &str) -> ColumnOrigin {
+    ColumnOrigin::Type(name.to_string())
+}
+
+/// Колонки результата запроса; `None` — текст не разобран или последний
+/// запрос пакета помещает результат во временную таблицу
+pub fn query_columns(text: &str) -> Option<Vec<QueryColumn>> {
+    let queries = parse_1c_queries(text).ok()?;
+    let mut temporary: HashMap<String, Vec<QueryColumn>> = HashMap::new();
+    let mut last = None;
+    for query in &queries {
+        let columns = select_columns(query, &temporary);
+        match &query.select_clause.into_temp_table {
+            Some(table) => {
+                temporary.insert(table.to_lowercase(), columns);
+                last = None;
+            }
+            None => last = Some(columns),
+        }
+    }
+    last
+}
+
+/// Источник таблицы из `ИЗ` и псевдоним по умолчанию
+fn table_source(
+    table: &TableReference,
+    temporary: &HashMap<String, Vec<QueryColumn>>,
+) -> (Source, String) {
+    match table {
+        TableReference::Catalog(kind, name)
+        | TableReference::Document(kind, name)
+        | TableReference::Register(kind, name) => (
+            QueryTable::new(kind, name, None).map_or(Source::Unknown, Source::Table),
+            name.clone(),
+        ),
+        TableReference::VirtualTable(base, virtual_table, _) => {
+            let table = base
+                .split_once('.')
+                .and_then(|(kind, name)| QueryTable::new(kind, name, Some(virtual_table.as_str())));
+            (
+                table.map_or(Source::Unknown, Source::Table),
+                virtual_table.clone(),
+            )
+        }
+        TableReference::Table(name) => {
+            let source = temporary
+                .get(&name.to_lowercase())
+                .map_or(Source::Unknown, |columns| {
+                    Source::Temporary(columns.clone())
+                });
+            (source, name.clone())
+        }
+        TableReference::Subquery(subquery) => (
+            Source::Temporary(select_columns(subquery, temporary)),
+            String::new(),
+        ),
+    }
+}
+
+/// Источники запроса: псевдоним в нижнем регистре -> источник
+fn query_sources(
+    query: &Query,
+    temporary: &HashMap<String, Vec<QueryColumn>>,
+) -> Vec<(String, Source)> {
+    let mut sources = Vec::new();
+    let mut add = |table: &TableReference, alias: &Option<String>| {
+        let (source, default_alias) = table_source(table, temporary);
+        let alias = alias.clone().unwrap_or(default_alias);
+        sources.push((alias.to_lowercase(), source));
+    };
+    for source in &query.from_clause.sources {
+        add(&source.table, &source.alias);
+        for join in &source.joins {
+            add(&join.table.table, &join.table.alias);
+        }
+    }
+    sources
+}
+
+fn select_columns(
+    query: &Query,
+    temporary: &HashMap<String, Vec<QueryColumn>>,
+) -> Vec<QueryColumn> {
+    let sources = query_sources(query, temporary);
+
+    // Выражения без псевдонима платформа называет `Поле1`, `Поле2`, ...
+    let mut unnamed = 0;
+    query
+        .select_clause
+        .fields
+        .iter()
+        .map(|field| {
+            let name = field
+                .alias
+                .clone()
+                .unwrap_or_else(|| match &field.expression {
+                    Expression::Field(name) => name.clone(),
+                    Expression::QualifiedField(_, path) => {
+                        path.rsplit('.').next().unwrap_or(path).to_string()
+                    }
+                    _ => {
+                        unnamed += 1;
+                        format!("Поле{}", unnamed)
+                    }
+                });
+            QueryColumn {
+                name,
+                origin: origin_of(&field.expression, &sources),
+            }
+        })
+        .collect()
+}
+
+/// Значение поля источника по пути
+fn source_field(source: &Source, path: Vec<String>) -> ColumnOrigin {
+    match source {
+        Source::Table(table) => ColumnOrigin::Field {
+            table: table.clone(),
+            path,
+        },
+        Source::Temporary(columns) => {
+            let Some(column) = columns
+                .iter()
+                .find(|column| column.name.to_lowercase() == path[0].to_lowercase())
+            else {
+                return ColumnOrigin::Unknown;
+            };
+            match &column.origin {
+                _ if path.len() == 1 => column.origin.clone(),
+                ColumnOrigin::Field { table, path: base } => ColumnOrigin::Field {
+                    table: table.clone(),
+                    path: base.iter().chain(&path[1..]).cloned().collect(),
+                },
+                _ => ColumnOrigin::Unknown,
+            }
+        }
+        Source::Unknown => ColumnOrigin::Unknown,
+    }
+}
+
+fn origin_of(expression: &Expression, sources: &[(String, Source)]) -> ColumnOrigin {
+    let single = || match sources {
+        [(_, source)] => Some(source),
+        _ => None,
+    };
+    match expression {
+        Expression::Field(name) => match single() {
+            Some(source) => source_field(source, vec![name.clone()]),
+            None => ColumnOrigin::Unknown,
+        },
+        Expression::QualifiedField(qualifier, path) => {
+            let path: Vec<String> = path.split('.').map(str::to_string).collect();
+            let qualifier_lower = qualifier.to_lowercase();
+            if let Some((_, source)) = sources.iter().find(|(alias, _)| *alias == qualifier_lower) {
+                return source_field(source, path);
+            }
+            // Без псевдонима первое звено — поле единственной таблицы
+            match single() {
+                Some(source) => source_field(
+                    source,
+                    std::iter::once(qualifier.clone()).chain(path).collect(),
+                ),
+                None => ColumnOrigin::Unknown,
+            }
+        }
+        Expression::Literal(literal) => match literal {
+            Literal::Number(_) => type_origin("Число"),
+            Literal::String(_) => type_origin("Строка"),
+            Literal::Boolean(_) => type_origin("Булево"),
+            Literal::Date(_) => type_origin("Дата"),
+            Literal::Null => type_origin("Null"),
+            Literal::Undefined => type_origin("Неопределено"),
+            Literal::EmptyReference => ColumnOrigin::Unknown,
+        },
+        Expression::Function(call) => match call.name.to_uppercase().as_str() {
+            "КОЛИЧЕСТВО"
+            | "COUNT"
+            | "СУММА"
+            | "SUM"
+            | "СРЕДНЕЕ"
+            | "AVG"
+            | "ГОД"
+            | "YEAR"
+            | "КВАРТАЛ"
+            | "QUARTER"
+            | "МЕСЯЦ"
+            | "MONTH"
+            | "ДЕНЬ"
+            | "DAY"
+            | "ЧАС"
+            | "HOUR"
+            | "РАЗНОСТЬДАТ"
+            | "DATEDIFF" => type_origin("Число"),
+            "ПРЕДСТАВЛЕНИЕ" | "PRESENTATION" | "ПОДСТРОКА" | "SUBSTRING" => {
+                type_origin("Строка")
+            }
+            "НАЧАЛОПЕРИОДА"
+            | "BEGINOFPERIOD"
+            | "КОНЕЦПЕРИОДА"
+            | "ENDOFPERIOD"
+            | "ДОБАВИТЬКДАТЕ"
+            | "DATEADD" => type_origin("Дата"),
+            "МАКСИМУМ" | "MAX" | "МИНИМУМ" | "MIN" | "ЕСТЬNULL" | "ISNULL" => {
+                call.args
+                    .first()
+                    .map_or(ColumnOrigin::Unknown, |arg| origin_of(arg, sources))
+            }
+            _ => ColumnOrigin::Unknown,
+        },
+        Expression::Cast(_, data_type) => match data_type {
+            DataType::Number(..) => type_origin("Число"),
+            DataType::String(_) => type_origin("Строка"),
+            DataType::Date => type_origin("Дата"),
+            DataType::Boolean => type_origin("Булево"),
+            // `Справочник.Валюты` в запросе — `СправочникСсылка.Валюты` в коде
+            DataType::Reference(name) => match name.split_once('.') {
+                Some((kind, object)) => ColumnOrigin::Type(format!("{}Ссылка.{}", kind, object)),
+                None => ColumnOrigin::Unknown,
+            },
+        },
+        Expression::BinaryOp(left, operator, _) => match operator {
+            BinaryOperator::Add => match origin_of(left, sources) {
+                ColumnOrigin::Type(name) if name == "Строка" => type_origin("Строка"),
+                _ => type_origin("Число"),
+            },
+            BinaryOperator::Subtract | BinaryOperator::Multiply | BinaryOperator::Divide => {
+                type_origin("Число")
+            }
+            _ => type_origin("Булево"),
+        },
+        Expression::UnaryOp(UnaryOperator::Minus, _) => type_origin("Число"),
+        Expression::UnaryOp(..) | Expression::Between(..) | Expression::In(..) => {
+            type_origin("Булево")
+        }
+        Expression::Case(case) => case
+            .when_clauses
+            .first()
+            .map_or(ColumnOrigin::Unknown, |when| {
+                origin_of(&when.result, sources)
+            }),
+        Expression::Parameter(_) | Expression::Subquery(_) => ColumnOrigin::Unknown,
+    }
+}
+
+/// Параметр в тексте запроса
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryParameter {
+    pub name: String,
+    /// С чем сравнивается параметр; `Unknown` — тип по тексту не выводится
+    pub expected: ColumnOrigin,
+    /// Параметр — список значений (`В (&Список)`)
+    pub list: bool,
+}
+
+fn parameter_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"&([\p{L}_][\p{L}\p{N}_]*)").unwrap())
+}
+
+/// Параметры запроса в порядке первого упоминания. Имена собираются по
+/// тексту (вне строковых литералов), поэтому находятся и в запросе, который
+/// парсер не разобрал; ожидаемый тип — только по разобранным запросам.
+pub fn query_parameters(text: &str) -> Vec<QueryParameter> {
+    let mut parameters: Vec<QueryParameter> = Vec::new();
+    for (idx, part) in text.split('"').enumerate() {
+        if idx % 2 == 1 {
+            continue;
+        }
+        for caps in parameter_regex().captures_iter(part) {
+            let name = &caps[1];
+            if !parameters
+                .iter()
+                .any(|p| p.name.to_lowercase() == name.to_lowercase())
+            {
+                parameters.push(QueryParameter {
+                    name: name.to_string(),
+                    expected: ColumnOrigin::Unknown,
+                    list: false,
+                });
+            }
+        }
+    }
+    let Ok(queries) = parse_1c_queries(text) else {
+        return parameters;
+    };
+    let mut found = Vec::new();
+    let mut temporary: HashMap<String, Vec<QueryColumn>> = HashMap::new();
+    for query in &queries {
+        query_parameter_types(query, &temporary, &mut found);
+        if let Some(table) = &query.select_clause.into_temp_table {
+            temporary.insert(table.to_lowercase(), select_columns(query, &temporary));
+        }
+    }
+    // Первое сравнение с известным типом задаёт ожидаемый тип
+    for parameter in &mut parameters {
+        let lower = parameter.name.to_lowercase();
+        if let Some(typed) = found
+            .iter()
+            .find(|p| p.name.to_lowercase() == lower && p.expected != ColumnOrigin::Unknown)
+        {
+            parameter.expected = typed.expected.clone();
+            parameter.list = typed.list;
+        }
+    }
+    parameters
+}
+
+/// Ожидаемые типы параметров запроса и его подзапросов
+fn query_parameter_types(
+    query: &Query,
+    temporary: &HashMap<String, Vec<QueryColumn>>,
+    out: &mut Vec<QueryParameter>,
+) {
+    let sources = query_sources(query, temporary);
+    let mut expressions: Vec<&Expression> = query
+        .select_clause
+        .fields
+        .iter()
+        .map(|field| &field.expression)
+        .collect();
+    expressions.extend(query.where_clause.iter().map(|clause| &clause.condition));
+    expressions.extend(query.having_clause.iter().map(|clause| &clause.condition));
+    for source in &query.from_clause.sources {
+        let tables =
+            std::iter::once(&source.table).chain(source.joins.iter().map(|j| &j.table.table));
+        for table in tables {
+            match table {
+                TableReference::VirtualTable(_, virtual_table, params) => {
+                    for (position, param) in params.iter().enumerate() {
+                        let expected = period_parameter(virtual_table, &param.name, position)
+                            .map_or(ColumnOrigin::Unknown, type_origin);
+                        expression_parameters(&param.value, expected, &sources, temporary, out);
+                    }
+                }
+                TableReference::Subquery(subquery) => {
+                    query_parameter_types(subquery, temporary, out)
+                }
+                _ => {}
+            }
+        }
+        for join in &source.joins {
+            if let Some(condition) = &join.condition {
+                expressions.push(condition);
+            }
+        }
+    }
+    for expression in expressions {
+        expression_parameters(expression, ColumnOrigin::Unknown, &sources, temporary, out);
+    }
+    for union in query.union_clause.iter().flatten() {
+        query_parameter_types(union, temporary, out);
+    }
+}
+
+/// Тип параметра виртуальной таблицы, задающего период: по имени
+/// (`Период = &Дата`) или по позиции (`Остатки(&Дата)`)
+fn period_parameter(virtual_table: &str, name: &str, position: usize) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    if matches!(name.as_str(), "период" | "началопериода" | "конецпериода")
+    {
+        return Some("Дата");
+    }
+    if !name.is_empty() {
+        return None;
+    }
+    let periods = match virtual_table.to_lowercase().as_str() {
+        "остатки" | "срезпоследних" | "срезпервых" => 1,
+        "обороты" | "остаткииобороты" => 2,
+        _ => 0,
+    };
+    (position < periods).then_some("Дата")
+}
+
+/// Параметры выражения; `expected` — тип, ожидаемый на месте самого выражения
+fn expression_parameters(
+    expression: &Expression,
+    expected: ColumnOrigin,
+    sources: &[(String, Source)],
+    temporary: &HashMap<String, Vec<QueryColumn>>,
+    out: &mut Vec<QueryParameter>,
+) {
+    let mut visit = |expression: &Expression, expected: ColumnOrigin| {
+        expression_parameters(expression, expected, sources, temporary, out)
+    };
+    match expression {
+        Expression::Parameter(name) => out.push(QueryParameter {
+            name: name.clone(),
+            expected,
+            list: false,
+        }),
+        Expression::BinaryOp(left, operator, right) => {
+            let comparison = matches!(
+                operator,
+                BinaryOperator::Equal
+                    | BinaryOperator::NotEqual
+                    | BinaryOperator::Less
+                    | BinaryOperator::LessOrEqual
+                    | BinaryOperator::Greater
+                    | BinaryOperator::GreaterOrEqual
+            );
+            let (left_expected, right_expected) = if comparison {
+                (origin_of(right, sources), origin_of(left, sources))
+            } else {
+                (ColumnOrigin::Unknown, ColumnOrigin::Unknown)
+            };
+            visit(left, left_expected);
+            visit(right, right_expected);
+        }
+        Expression::Between(value, low, high) => {
+            visit(value, ColumnOrigin::Unknown);
+            visit(low, origin_of(value, sources));
+            visit(high, origin_of(value, sources));
+        }
+        Expression::In(value, items) => {
+            visit(value, ColumnOrigin::Unknown);
+            let expected = origin_of(value, sources);
+            match items.as_slice() {
+                [Expression::Parameter(name)] => out.push(QueryParameter {
+                    name: name.clone(),
+                    expected,
+                    list: true,
+                }),
+                _ => {
+                    for item in items {
+                        visit(item, expected.clone());
+                    }
+                }
+            }
+        }
+        Expression::Function(call) => {
+            for arg in &call.args {
+                visit(arg, ColumnOrigin::Unknown);
+            }
+        }
+        Expression::UnaryOp(_, operand) | Expression::Cast(operand, _) => {
+            visit(operand, ColumnOrigin::Unknown)
+        }
+        Expression::Case(case) => {
+            for when in &case.when_clauses {
+                visit(&when.condition, ColumnOrigin::Unknown);
+                visit(&when.result, ColumnOrigin::Unknown);
+            }
+            if let Some(otherwise) = &case.else_clause {
+                visit(otherwise, ColumnOrigin::Unknown);
+            }
+        }
+        Expression::Subquery(subquery) => query_parameter_types(subquery, temporary, out),
+        Expression::Field(_) | Expression::QualifiedField(..) | Expression::Literal(_) => {}
+    }
+}
+
+/// Текст запроса и установки его параметров
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryParameterUsage {
+    pub query: EmbeddedQuery,
+    pub calls: Vec<ParameterCall>,
+}
+
+/// Номер метода, к которому относится каждая строка кода (0 — тело модуля)
+fn line_methods(code: &str) -> Vec<usize> {
+    let mut method = 0;
+    code.lines()
+        .map(|line| {
+            if method_header_regex().is_match(line) {
+                method += 1;
+            }
+            method
+        })
+        .collect()
+}
+
+/// Запросы модуля с установками их параметров. Установка относится к
+/// последнему присваиванию текста той же переменной в том же методе, а
+/// сделанная до первого присваивания — к первому.
+pub fn query_parameter_usages(code: &str) -> Vec<QueryParameterUsage> {
+    let methods = line_methods(code);
+    let method_of = |line: u32| methods.get(line as usize).copied().unwrap_or_default();
+    let mut usages: Vec<QueryParameterUsage> = embedded_queries(code)
+        .into_iter()
+        .map(|query| QueryParameterUsage {
+            query,
+            calls: Vec::new(),
+        })
+        .collect();
+    for call in parameter_calls(code) {
+        let variable = call.variable.to_lowercase();
+        let same_scope: Vec<usize> = usages
+            .iter()
+            .enumerate()
+            .filter(|(_, usage)| {
+                usage.query.variable.to_lowercase() == variable
+                    && method_of(usage.query.line) == method_of(call.line)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        let target = same_scope
+            .iter()
+            .rev()
+            .find(|&&idx| usages[idx].query.line <= call.line)
+            .or_else(|| same_scope.first());
+        if let Some(&idx) = target {
+            usages[idx].calls.push(call);
+        }
+    }
+    usages
+}
+
+/// Параметры запроса, которым не установлено значение; если имя хотя бы
+/// одного параметра задано не литералом, проверить это нельзя
+pub fn missing_parameters(usage: &QueryParameterUsage) -> Vec<QueryParameter> {
+    if usage.calls.iter().any(|call| call.name.is_none()) {
+        return Vec::new();
+    }
+    query_parameters(&usage.query.text)
+        .into_iter()
+        .filter(|parameter| {
+            let lower = parameter.name.to_lowercase();
+            !usage
+                .calls
+                .iter()
+                .filter_map(|call| call.name.as_deref())
+                .any(|name| name.to_lowercase() == lower)
+        })
+        .collect()
+}
+
+/// Тип литерала значения параметра (`"А-1"`, `'20240101'`, `10`, `Истина`)
+pub fn literal_type(value: &str) -> Option<&'static str> {
+    static NUMBER: OnceLock<Regex> = OnceLock::new();
+    let number = NUMBER.get_or_init(|| Regex::new(r"^-?\d+(?:\.\d+)?$").unwrap());
+    let value = value.trim();
+    if value.starts_with('"') {
+        return Some("Строка");
+    }
+    if value.starts_with('\'') {
+        return Some("Дата");
+    }
+    if number.is_match(value) {
+        return Some("Число");
+    }
+    match value.to_lowercase().as_str() {
+        "истина" | "ложь" | "true" | "false" => Some("Булево"),
+        "неопределено" | "undefined" => Some("Неопределено"),
+        "null" => Some("Null"),
+        _ => None,
+    }
+}
+
+/// Что не так с параметром запроса
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryParameterIssueKind {
+    /// Параметр есть в тексте, но значение ему не установлено
+    Missing,
+    /// Значение не подходит полю, с которым параметр сравнивается
+    Mismatch { expected: String, actual: String },
+}
+
+/// Проблема установки параметра запроса
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryParameterIssue {
+    /// Переменная запроса
+    pub variable: String,
+    pub parameter: String,
+    /// Строка присваивания текста (нет значения) или значения в вызове
+    /// (несовместимый тип), с 0
+    pub line: u32,
+    pub column: u32,
+    pub kind: QueryParameterIssueKind,
+}
+
+impl QueryParameterIssue {
+    pub fn message(&self) -> String {
+        match &self.kind {
+            QueryParameterIssueKind::Missing => format!(
+                "Не установлено значение параметра '{}' запроса {}",
+                self.parameter, self.variable
+            ),
+            QueryParameterIssueKind::Mismatch { expected, actual } => format!(
+                "Параметр '{}' запроса {} сравнивается со значением типа '{}', а устанавливается значение типа '{}'",
+                self.parameter, self.variable, expected, actual
+            ),
+        }
+    }
+}
+
+/// Часть объявленного типа: примитив, ссылка на объект или другое
+enum DeclaredPart {
+    Primitive(ConcreteType),
+    Reference(String),
+    Other,
+}
+
+fn declared_part(name: &str) -> DeclaredPart {
+    if let Some(primitive) = ExpressionResolver::primitive_from_name(name) {
+        return DeclaredPart::Primitive(primitive);
+    }
+    match name.split_once('.') {
+        Some((kind, object)) if kind.ends_with("Ссылка") => {
+            DeclaredPart::Reference(object.trim().to_string())
+        }
+        _ => DeclaredPart::Other,
+    }
+}
+
+/// Можно ли передать значение `value` в параметр, ожидающий тип `expected`
+/// (имя типа из метаданных, возможно составное). Несовместимость
+/// признаётся только для конкретного типа значения и полностью известного
+/// ожидаемого типа: `Неопределено` и `NULL` допустимы всегда, массив и
+/// список значений — для параметра-списка.
+pub fn parameter_accepts(expected: &str, list: bool, value: &TypeResolution) -> bool {
+    let ResolutionResult::Concrete(concrete) = &value.result else {
+        return true;
+    };
+    let parts: Vec<DeclaredPart> = composite_type_names(expected)
+        .into_iter()
+        .map(declared_part)
+        .collect();
+    if parts.is_empty() || parts.iter().any(|part| matches!(part, DeclaredPart::Other)) {
+        return true;
+    }
+    match concrete {
+        ConcreteType::Special(SpecialType::Undefined | SpecialType::Null) => true,
+        ConcreteType::Primitive(_) => parts
+            .iter()
+            .any(|part| matches!(part, DeclaredPart::Primitive(p) if p == concrete)),
+        ConcreteType::Configuration(configuration) => parts.iter().any(
+            |part| matches!(part, DeclaredPart::Reference(object) if *object == configuration.name),
+        ),
+        ConcreteType::Platform(platform) => {
+            list && matches!(
+                platform.name.as_str(),
+                "Массив" | "ФиксированныйМассив" | "СписокЗначений"
+            )
+        }
+        ConcreteType::Special(SpecialType::Type) | ConcreteType::GlobalFunction(_) => true,
+    }
+}
+
+fn method(name: &str, return_type: Option<&str>) -> Method {
+    Method {
+        name: name.to_string(),
+        parameters: Vec::new(),
+        return_type: return_type.map(str::to_string),
+        is_function: return_type.is_some(),
+    }
+}
+
+/// Выборка из результата запроса с колонками (имя, тип); колонка с
+/// неизвестным типом — пустое имя типа
+pub fn selection_resolution(columns: &[(String, String)]) -> TypeResolution {
+    TypeResolution::known(ConcreteType::Platform(PlatformType {
+        name: SELECTION_TYPE.to_string(),
+        methods: vec![
+            method("Следующий", Some("Булево")),
+            method("СледующийПоЗначениюПоля", Some("Булево")),
+            method("НайтиСледующий", Some("Булево")),
+            method("Количество", Some("Число")),
+            method("Выбрать", Some(SELECTION_TYPE)),
+            method("Владелец", Some(RESULT_TYPE)),
+            method("Сбросить", None),
+        ],
+        properties: columns
+            .iter()
+            .map(|(name, type_name)| Property {
+                name: name.clone(),
+                type_: type_name.clone(),
+                readonly: true,
+            })
+            .collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(prefix: &'static str, object: &str, path: &[&str]) -> ColumnOrigin {
+        ColumnOrigin::Field {
+            table: QueryTable {
+                prefix,
+                object: object.to_string(),
+                virtual_table: None,
+            },
+            path: path.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_query_columns() {
+        let text = "ВЫБРАТЬ
+            Товары.Ссылка КАК Товар,
+            Товары.Родитель.Наименование КАК Группа,
+            КОЛИЧЕСТВО(Товары.Код) КАК Всего,
+            ВЫРАЗИТЬ(Товары.Комментарий КАК СТРОКА(100)) КАК Комментарий
+        ПОМЕСТИТЬ ВТ
+        ИЗ Справочник.Номенклатура КАК Товары;
+        ВЫБРАТЬ ВТ.Товар, ВТ.Группа, ВТ.Всего, Цены.Цена, ЕСТЬNULL(Цены.Валюта, 0)
+        ИЗ ВТ КАК ВТ
+            ЛЕВОЕ СОЕДИНЕНИЕ РегистрСведений.Цены КАК Цены
+            ПО ВТ.Товар = Цены.Номенклатура";
+        let columns = query_columns(text).unwrap();
+        let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Товар", "Группа", "Всего", "Цена", "Поле1"]);
+        assert_eq!(
+            columns[0].origin,
+            field("Справочник", "Номенклатура", &["Ссылка"])
+        );
+        assert_eq!(
+            columns[1].origin,
+            field("Справочник", "Номенклатура", &["Родитель", "Наименование"])
+        );
+        assert_eq!(columns[2].origin, type_origin("Число"));
+        assert_eq!(
+            columns[3].origin,
+            field("РегистрСведений", "Цены", &["Цена"])
+        );
+        assert_eq!(
+            columns[4].origin,
+            field("РегистрСведений", "Цены", &["Валюта"])
+        );
+
+        let table = reference_table("СправочникСсылка.Номенклатура").unwrap();
+        let properties = vec![RawPropertyData {
+            name: "Артикул".to_string(),
+            type_name: "Строка(25)".to_string(),
+            is_readonly: false,
+            description: String::new(),
+        }];
+        assert_eq!(
+            table.field_type(&properties, "артикул").as_deref(),
+            Some("Строка(25)")
+        );
+        assert_eq!(
+            table.field_type(&[], "Родитель").as_deref(),
+            Some("СправочникСсылка.Номенклатура")
+        );
+        assert_eq!(table.field_type(&[], "Проведен"), None);
+
+        let remains = query_columns(
+            "ВЫБРАТЬ Остатки.КоличествоОстаток \
+             ИЗ РегистрНакопления.Товары.Остатки(&Дата, ) КАК Остатки",
+        )
+        .unwrap();
+        let ColumnOrigin::Field { table, path } = &remains[0].origin else {
+            panic!("поле виртуальной таблицы");
+        };
+        assert_eq!(table.field_type(&[], &path[0]).as_deref(), Some("Число"));
+    }
+
+    #[test]
+    fn test_query_parameters() {
+        let text = "ВЫБРАТЬ Товары.Ссылка
+        ИЗ Справочник.Номенклатура КАК Товары
+        ГДЕ Товары.Родитель = &Группа
+            И Товары.Ссылка В (&Список)
+            И Товары.Наименование ПОДОБНО &Шаблон
+            И Товары.Код <> \"&НеПараметр\"";
+        let parameters = query_parameters(text);
+        let names: Vec<&str> = parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Группа", "Список", "Шаблон"]);
+        assert_eq!(
+            parameters[0].expected,
+            field("Справочник", "Номенклатура", &["Родитель"])
+        );
+        assert!(!parameters[0].list);
+        assert_eq!(
+            parameters[1].expected,
+            field("Справочник", "Номенклатура", &["Ссылка"])
+        );
+        assert!(parameters[1].list);
+        assert_eq!(parameters[2].expected, ColumnOrigin::Unknown);
+
+        let remains = query_parameters(
+            "ВЫБРАТЬ Остатки.КоличествоОстаток \
+             ИЗ РегистрНакопления.Товары.Остатки(&Дата, Склад = &Склад) КАК Остатки",
+        );
+        assert_eq!(remains[0].expected, type_origin("Дата"));
+        assert_eq!(remains[1].expected, ColumnOrigin::Unknown);
+    }
+
+    #[test]
+    fn test_query_parameter_usages() {
+        let code = r#"Процедура Первая()
+    Запрос = Новый Запрос;
+    Запрос.УстановитьПараметр("Дата", ТекущаяДата());
+    Запрос.Текст = "ВЫБРАТЬ Заказы.Ссылка ИЗ Документ.Заказ КАК Заказы
+    |ГДЕ Заказы.Дата > &Дата И Заказы.Клиент = &Клиент";
+КонецПроцедуры
+
+Процедура Вторая()
+    Запрос = Новый Запрос("ВЫБРАТЬ 1 КАК Один ИЗ Документ.Заказ ГДЕ Номер = &Номер");
+    Запрос.УстановитьПараметр(ИмяПараметра, Значение);
+КонецПроцедуры"#;
+        let usages = query_parameter_usages(code);
+        assert_eq!(usages.len(), 2);
+        assert_eq!(usages[0].calls.len(), 1);
+        assert_eq!(usages[0].calls[0].name.as_deref(), Some("Дата"));
+        let missing: Vec<String> = missing_parameters(&usages[0])
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(missing, ["Клиент"]);
+        // Имя задано переменной: какие параметры установлены, неизвестно
+        assert_eq!(usages[1].calls.len(), 1);
+        assert!(missing_parameters(&usages[1]).is_empty());
+    }
+
+    #[test]
+    fn test_parameter_accepts() {
+        use crate::domain::types::PrimitiveType;
+        let primitive = |primitive| TypeResolution::known(ConcreteType::Primitive(primitive));
+        let reference = "СправочникСсылка.Номенклатура";
+        assert!(parameter_accepts(
+            "Дата",
+            false,
+            &primitive(PrimitiveType::Date)
+        ));
+        assert!(!parameter_accepts(
+            reference,
+            false,
+            &primitive(PrimitiveType::String)
+        ));
+        assert!(parameter_accepts(
+            &format!("{},Строка(10)", reference),
+            false,
+            &primitive(PrimitiveType::String)
+        ));
+        assert!(parameter_accepts(
+            reference,
+            false,
+            &TypeResolution::known(ConcreteType::Special(SpecialType::Undefined))
+        ));
+        let array = TypeResolution::known(ConcreteType::Platform(PlatformType {
+            name: "Массив".to_string(),
+            methods: Vec::new(),
+            properties: Vec::new(),
+        }));
+        assert!(parameter_accepts(reference, true, &array));
+        assert!(!parameter_accepts(reference, false, &array));
+        // Тип не из метаданных — проверить нельзя
+        assert!(parameter_accepts("ВидДвиженияНакопления", false, &array));
+        assert_eq!(literal_type("\"А-1\""), Some("Строка"));
+        assert_eq!(literal_type("-1.5"), Some("Число"));
+        assert_eq!(literal_type("Товар"), None);
+    }
+}
@@ -0,0 +1,396 @@
+//! Записи регистров: менеджер записи, набор записей и запись набора
+//!
+//! `РегистрыСведений.Курсы.СоздатьМенеджерЗаписи()` и
+//! `СоздатьНаборЗаписей()` создают объекты с полями регистра — измерениями,
+//! ресурсами и реквизитами. Их типы получают имена вида
+//! `РегистрСведенийМенеджерЗаписи.Курсы`, `РегистрСведенийНаборЗаписей.Курсы`
+//! и `РегистрСведенийЗапись.Курсы` (для регистров накопления и бухгалтерии —
+//! `РегистрНакопления...` и `РегистрБухгалтерии...`) и строятся по описанию
+//! регистра из конфигурации: поля становятся свойствами, а `Добавить()` и
+//! обход набора в `Для Каждого` дают запись того же регистра. Загрузчик
+//! конфигурации кладёт эти типы и в репозиторий (`register_raw_types`),
+//! чтобы они были видны в поиске и документации.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::domain::types::{ConcreteType, Method, PlatformType, Property, TypeResolution};
+use crate::unified::data::{RawPropertyData, RawTypeData};
+
+/// Категория типов записей регистров в репозитории
+pub const RECORDS_CATEGORY: &str = "ЗаписиРегистра";
+
+/// Вид регистра, записи которого типизируются
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegisterKind {
+    Information,
+    Accumulation,
+    Accounting,
+}
+
+impl RegisterKind {
+    pub const ALL: [RegisterKind; 3] = [
+        RegisterKind::Information,
+        RegisterKind::Accumulation,
+        RegisterKind::Accounting,
+    ];
+
+    /// Префикс имён типов записей (`РегистрСведений`)
+    fn type_prefix(self) -> &'static str {
+        match self {
+            RegisterKind::Information => "РегистрСведений",
+            RegisterKind::Accumulation => "РегистрНакопления",
+            RegisterKind::Accounting => "РегистрБухгалтерии",
+        }
+    }
+
+    /// Тег вида регистра в описании конфигурации (`InformationRegister`)
+    pub fn metadata_tag(self) -> &'static str {
+        match self {
+            RegisterKind::Information => "InformationRegister",
+            RegisterKind::Accumulation => "AccumulationRegister",
+            RegisterKind::Accounting => "AccountingRegister",
+        }
+    }
+
+    /// Вид по тегу описания или русскому имени вида (`РегистрНакопления`)
+    pub fn from_metadata_tag(tag: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.metadata_tag() == tag || kind.type_prefix() == tag)
+    }
+
+    fn from_manager(manager: &str) -> Option<Self> {
+        match manager.to_lowercase().as_str() {
+            "регистрысведений" | "informationregisters" => Some(RegisterKind::Information),
+            "регистрынакопления" | "accumulationregisters" => Some(RegisterKind::Accumulation),
+            "регистрыбухгалтерии" | "accountingregisters" => Some(RegisterKind::Accounting),
+            _ => None,
+        }
+    }
+
+    /// Менеджер записи есть только у регистров сведений
+    pub fn has_record_manager(self) -> bool {
+        self == RegisterKind::Information
+    }
+
+    /// Стандартные поля записи, которых может не быть в описании регистра
+    fn record_fields(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            RegisterKind::Information => &[],
+            RegisterKind::Accumulation => &[
+                ("ВидДвижения", "ВидДвиженияНакопления"),
+                ("НомерСтроки", "Число"),
+            ],
+            RegisterKind::Accounting => &[("НомерСтроки", "Число")],
+        }
+    }
+}
+
+/// Объект записей регистра
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordObject {
+    /// Менеджер записи (только регистры сведений)
+    RecordManager,
+    RecordSet,
+    /// Запись набора
+    Record,
+}
+
+impl RecordObject {
+    fn label(self) -> &'static str {
+        match self {
+            RecordObject::RecordManager => "Менеджер записи",
+            RecordObject::RecordSet => "Набор записей",
+            RecordObject::Record => "Запись",
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            RecordObject::RecordManager => "МенеджерЗаписи",
+            RecordObject::RecordSet => "НаборЗаписей",
+            RecordObject::Record => "Запись",
+        }
+    }
+}
+
+/// Тип записей регистра, разобранный из имени типа
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordType {
+    pub kind: RegisterKind,
+    pub object: RecordObject,
+    /// Имя регистра в конфигурации
+    pub register: String,
+}
+
+impl RecordType {
+    /// Имя типа: `РегистрСведенийНаборЗаписей.Курсы`
+    pub fn type_name(&self) -> String {
+        format!("{}{}.{}", self.kind.type_prefix(), self.object.suffix(), self.register)
+    }
+
+    /// Описание для подсказок: `Набор записей регистра ТоварыНаСкладах`
+    pub fn description(&self) -> String {
+        format!("{} регистра {}", self.object.label(), self.register)
+    }
+
+    /// Разобрать имя типа записей регистра
+    pub fn parse(type_name: &str) -> Option<Self> {
+        let (prefix, register) = type_name.trim().split_once('.')?;
+        if register.is_empty() || register.contains('.') {
+            return None;
+        }
+        for kind in RegisterKind::ALL {
+            let Some(suffix) = prefix.strip_prefix(kind.type_prefix()) else {
+                continue;
+            };
+            let object = [
+                RecordObject::RecordManager,
+                RecordObject::RecordSet,
+                RecordObject::Record,
+            ]
+            .into_iter()
+            .find(|object| object.suffix() == suffix)?;
+            if !kind.has_record_manager() && object == RecordObject::RecordManager {
+                return None;
+            }
+            return Some(Self {
+                kind,
+                object,
+                register: register.to_string(),
+            });
+        }
+        None
+    }
+}
+
+fn creation_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)^([\p{L}_]+)\.([\p{L}_][\p{L}\p{N}_]*)\.",
+            r"(СоздатьМенеджерЗаписи|CreateRecordManager|СоздатьНаборЗаписей|CreateRecordSet)\(\)$",
+        ))
+        .unwrap()
+    })
+}
+
+/// Тип объекта, создаваемого выражением
+/// `РегистрыСведений.Имя.СоздатьМенеджерЗаписи()` или `...СоздатьНаборЗаписей()`
+pub fn created_record_type(expression: &str) -> Option<RecordType> {
+    let expression: String = expression.split_whitespace().collect();
+    let caps = creation_regex().captures(&expression)?;
+    let kind = RegisterKind::from_manager(&caps[1])?;
+    let object = match caps[3].to_lowercase().as_str() {
+        "создатьменеджерзаписи" | "createrecordmanager" => RecordObject::RecordManager,
+        _ => RecordObject::RecordSet,
+    };
+    if !kind.has_record_manager() && object == RecordObject::RecordManager {
+        return None;
+    }
+    Some(RecordType {
+        kind,
+        object,
+        register: caps[2].to_string(),
+    })
+}
+
+/// Элемент набора записей (`Добавить()`, обход в `Для Каждого`):
+/// имя типа записи по имени типа набора
+pub fn record_set_element(type_name: &str) -> Option<String> {
+    let set = RecordType::parse(type_name).filter(|t| t.object == RecordObject::RecordSet)?;
+    Some(
+        RecordType {
+            object: RecordObject::Record,
+            ..set
+        }
+        .type_name(),
+    )
+}
+
+fn method(name: &str, return_type: Option<&str>) -> Method {
+    Method {
+        name: name.to_string(),
+        parameters: Vec::new(),
+        return_type: return_type.map(str::to_string),
+        is_function: return_type.is_some(),
+    }
+}
+
+fn property(name: &str, type_name: &str) -> Property {
+    Property {
+        name: name.to_string(),
+        type_: type_name.to_string(),
+        readonly: false,
+    }
+}
+
+/// Тип объекта записей с полями регистра `fields` (измерения, ресурсы,
+/// реквизиты и стандартные поля из описания конфигурации)
+pub fn record_type_resolution(record: &RecordType, fields: &[RawPropertyData]) -> TypeResolution {
+    let mut properties: Vec<Property> = Vec::new();
+    let mut methods: Vec<Method> = Vec::new();
+    match record.object {
+        RecordObject::RecordManager | RecordObject::Record => {
+            properties.extend(fields.iter().map(|f| property(&f.name, &f.type_name)));
+            for (name, type_name) in record.kind.record_fields() {
+                if !properties.iter().any(|p| p.name == *name) {
+                    properties.push(property(name, type_name));
+                }
+            }
+            if record.object == RecordObject::RecordManager {
+                methods.extend([
+                    method("Записать", None),
+                    method("Прочитать", None),
+                    method("Удалить", None),
+                    method("Выбран", Some("Булево")),
+                ]);
+            }
+        }
+        RecordObject::RecordSet => {
+            let element = RecordType {
+                object: RecordObject::Record,
+                ..record.clone()
+            }
+            .type_name();
+            properties.extend([
+                property("Отбор", "Отбор"),
+                property("ДополнительныеСвойства", "Структура"),
+            ]);
+            methods.extend([
+                method("Добавить", Some(&element)),
+                method("Вставить", Some(&element)),
+                method("Получить", Some(&element)),
+                method("Количество", Some("Число")),
+                method("Выгрузить", Some("ТаблицаЗначений")),
+                method("Модифицированность", Some("Булево")),
+                method("Загрузить", None),
+                method("Записать", None),
+                method("Прочитать", None),
+                method("Очистить", None),
+                method("Удалить", None),
+            ]);
+        }
+    }
+    TypeResolution::known(ConcreteType::Platform(PlatformType {
+        name: record.type_name(),
+        methods,
+        properties,
+    }))
+}
+
+/// Типы записей регистра для репозитория: менеджер записи (у регистров
+/// сведений), набор записей и запись с полями `fields` из описания регистра
+pub fn register_raw_types(
+    kind: RegisterKind,
+    register: &str,
+    fields: &[RawPropertyData],
+    file_path: &str,
+) -> Vec<RawTypeData> {
+    [
+        RecordObject::RecordManager,
+        RecordObject::RecordSet,
+        RecordObject::Record,
+    ]
+    .into_iter()
+    .filter(|object| kind.has_record_manager() || *object != RecordObject::RecordManager)
+    .map(|object| {
+        let record = RecordType {
+            kind,
+            object,
+            register: register.to_string(),
+        };
+        let mut raw = record_type_resolution(&record, fields).to_raw_data();
+        // Синоним и вид поля теряются в `Property`, описания берутся из
+        // описания регистра
+        for property in &mut raw.properties {
+            if let Some(field) = fields.iter().find(|f| f.name == property.name) {
+                property.description = field.description.clone();
+                property.is_readonly = field.is_readonly;
+            }
+        }
+        raw.category_path = vec![RECORDS_CATEGORY.to_string()];
+        raw.documentation = record.description();
+        raw.parse_metadata.file_path = file_path.to_string();
+        raw
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_set_and_record_types() {
+        let set = created_record_type("РегистрыСведений.КурсыВалют.СоздатьНаборЗаписей()").unwrap();
+        assert_eq!(set.type_name(), "РегистрСведенийНаборЗаписей.КурсыВалют");
+        assert_eq!(RecordType::parse(&set.type_name()), Some(set.clone()));
+        assert_eq!(
+            record_set_element(&set.type_name()).as_deref(),
+            Some("РегистрСведенийЗапись.КурсыВалют")
+        );
+        // У регистров накопления нет менеджера записи
+        let manager = created_record_type("РегистрыНакопления.Остатки.СоздатьМенеджерЗаписи()");
+        assert!(manager.is_none());
+        assert!(record_set_element("РегистрСведенийЗапись.КурсыВалют").is_none());
+
+        let fields = vec![RawPropertyData {
+            name: "Курс".to_string(),
+            type_name: "Число".to_string(),
+            is_readonly: false,
+            description: String::new(),
+        }];
+        let manager = RecordType {
+            object: RecordObject::RecordManager,
+            ..set
+        };
+        let raw = record_type_resolution(&manager, &fields).to_raw_data();
+        assert_eq!(raw.russian_name, "РегистрСведенийМенеджерЗаписи.КурсыВалют");
+        assert_eq!(raw.properties[0].name, "Курс");
+        assert!(raw.methods.iter().any(|m| m.name == "Записать"));
+    }
+
+    #[test]
+    fn test_register_raw_types() {
+        let fields = vec![RawPropertyData {
+            name: "Сумма".to_string(),
+            type_name: "Число(15, 2)".to_string(),
+            is_readonly: false,
+            description: "Ресурс".to_string(),
+        }];
+        let names = |kind| -> Vec<String> {
+            register_raw_types(kind, "Хозрасчетный", &fields, "Хозрасчетный.xml")
+                .into_iter()
+                .map(|raw| raw.russian_name)
+                .collect()
+        };
+        assert_eq!(
+            names(RegisterKind::Accounting),
+            [
+                "РегистрБухгалтерииНаборЗаписей.Хозрасчетный",
+                "РегистрБухгалтерииЗапись.Хозрасчетный"
+            ]
+        );
+        assert_eq!(names(RegisterKind::Information).len(), 3);
+
+        let types = register_raw_types(RegisterKind::Accumulation, "Остатки", &fields, "");
+        let record = &types[1];
+        assert_eq!(record.category_path, [RECORDS_CATEGORY]);
+        let amount = record
+            .properties
+            .iter()
+            .find(|p| p.name == "Сумма")
+            .unwrap();
+        assert_eq!(amount.description, "Ресурс");
+        assert!(record.properties.iter().any(|p| p.name == "ВидДвижения"));
+
+        assert_eq!(
+            RegisterKind::from_metadata_tag("AccountingRegister"),
+            Some(RegisterKind::Accounting)
+        );
+        let set = created_record_type("РегистрыБухгалтерии.Хозрасчетный.СоздатьНаборЗаписей()");
+        assert_eq!(set.unwrap().kind, RegisterKind::Accounting);
+    }
+}
@@ -0,0 +1,325 @@
+//! Реестр резолверов типов
+//!
+//! Резолверы регистрируются вместе с заявленными возможностями: шаблонами
+//! выражений, которые они обрабатывают, и классом стоимости. Это позволяет
+//! подключать сторонние резолверы (например, для DSL во встроенных
+//! комментариях) без изменения крейта, а доступ к конкретному резолверу
+//! выполняется по его типу через [`ResolverRegistry::get`].
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use super::{ResolverChainConfig, TypeResolver};
+use crate::error::ResultExt;
+
+/// Класс стоимости резолвера: дешёвые опрашиваются раньше дорогих
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolverCost {
+    /// Поиск по таблицам в памяти
+    Cheap,
+    /// Обращение к репозиторию типов
+    #[default]
+    Moderate,
+    /// Разбор кода, внешние процессы и т.п.
+    Expensive,
+}
+
+/// Заявленные возможности резолвера
+#[derive(Debug, Clone, Default)]
+pub struct ResolverCapabilities {
+    /// Шаблоны выражений; пустой список — резолвер решает сам через `can_resolve`
+    pub patterns: Vec<Regex>,
+    /// Класс стоимости
+    pub cost: ResolverCost,
+}
+
+impl ResolverCapabilities {
+    pub fn new(cost: ResolverCost) -> Self {
+        Self {
+            patterns: Vec::new(),
+            cost,
+        }
+    }
+
+    /// Добавить шаблон выражений (регулярное выражение)
+    pub fn with_pattern(mut self, pattern: &str) -> crate::Result<Self> {
+        let regex = Regex::new(pattern)
+            .config_err(format!("некорректный шаблон резолвера '{}'", pattern))?;
+        self.patterns.push(regex);
+        Ok(self)
+    }
+
+    /// Подходит ли выражение под заявленные шаблоны
+    pub fn matches(&self, expression: &str) -> bool {
+        self.patterns.is_empty() || self.patterns.iter().any(|p| p.is_match(expression))
+    }
+}
+
+/// Обёртка, применяемая к каждому резолверу цепочки (например, ограничение времени)
+type ResolverLayer = Arc<dyn Fn(Arc<dyn TypeResolver>) -> Arc<dyn TypeResolver> + Send + Sync>;
+
+/// Зарегистрированный резолвер
+#[derive(Clone)]
+pub struct RegisteredResolver {
+    resolver: Arc<dyn TypeResolver>,
+    capabilities: ResolverCapabilities,
+    type_id: TypeId,
+}
+
+impl RegisteredResolver {
+    pub fn name(&self) -> &str {
+        self.resolver.name()
+    }
+
+    pub fn capabilities(&self) -> &ResolverCapabilities {
+        &self.capabilities
+    }
+
+    /// Готов ли резолвер обработать выражение (шаблоны и `can_resolve`)
+    pub fn accepts(&self, expression: &str) -> bool {
+        self.capabilities.matches(expression) && self.resolver.can_resolve(expression)
+    }
+
+    pub fn resolver(&self) -> &Arc<dyn TypeResolver> {
+        &self.resolver
+    }
+}
+
+/// Установщик стороннего резолвера в реестр
+type ResolverInstaller = Arc<dyn Fn(&mut ResolverRegistry) + Send + Sync>;
+
+/// Сторонние резолверы, передаваемые через настройки системы
+/// (`CentralSystemConfig::resolver_plugins`). Каждый сервис разрешения,
+/// созданный по этим настройкам, получает собственную копию резолверов.
+#[derive(Clone, Default)]
+pub struct ResolverPlugins {
+    installers: Vec<ResolverInstaller>,
+}
+
+impl ResolverPlugins {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавить резолвер с заявленными возможностями
+    pub fn with<R: TypeResolver + Clone + 'static>(
+        mut self,
+        resolver: R,
+        capabilities: ResolverCapabilities,
+    ) -> Self {
+        self.installers.push(Arc::new(move |registry| {
+            registry.register_with(resolver.clone(), capabilities.clone());
+        }));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.installers.is_empty()
+    }
+
+    /// Зарегистрировать все резолверы в реестре
+    pub fn install(&self, registry: &mut ResolverRegistry) {
+        for installer in &self.installers {
+            installer(registry);
+        }
+    }
+}
+
+impl fmt::Debug for ResolverPlugins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolverPlugins")
+            .field("count", &self.installers.len())
+            .finish()
+    }
+}
+
+/// Упорядоченная цепочка резолверов с доступом по типу
+#[derive(Default)]
+pub struct ResolverRegistry {
+    chain: Vec<RegisteredResolver>,
+    /// Исходные экземпляры по типу (без обёрток) — для настройки после регистрации
+    typed: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    config: ResolverChainConfig,
+    layers: Vec<ResolverLayer>,
+}
+
+impl ResolverRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Зарегистрировать резолвер с возможностями по умолчанию
+    pub fn register<R: TypeResolver + 'static>(&mut self, resolver: R) -> Arc<R> {
+        self.register_with(resolver, ResolverCapabilities::default())
+    }
+
+    /// Зарегистрировать резолвер с заявленными возможностями.
+    ///
+    /// Возвращает типизированный экземпляр. Повторная регистрация резолвера
+    /// того же типа и с тем же именем заменяет прежний экземпляр и в
+    /// [`get`](Self::get), и в цепочке; экземпляры одного типа с разными
+    /// именами опрашиваются как разные резолверы.
+    pub fn register_with<R: TypeResolver + 'static>(
+        &mut self,
+        resolver: R,
+        capabilities: ResolverCapabilities,
+    ) -> Arc<R> {
+        let resolver = Arc::new(resolver);
+        let type_id = TypeId::of::<R>();
+        self.typed.insert(type_id, resolver.clone());
+
+        let wrapped = self
+            .layers
+            .iter()
+            .fold(resolver.clone() as Arc<dyn TypeResolver>, |inner, layer| {
+                layer(inner)
+            });
+        let entry = RegisteredResolver {
+            resolver: wrapped,
+            capabilities,
+            type_id,
+        };
+        let existing = self.chain.iter().position(|registered| {
+            registered.type_id == type_id && registered.name() == entry.name()
+        });
+        match existing {
+            Some(index) => self.chain[index] = entry,
+            None => self.chain.push(entry),
+        }
+        self.sort();
+        resolver
+    }
+
+    /// Резолвер заданного типа
+    pub fn get<R: TypeResolver + 'static>(&self) -> Option<Arc<R>> {
+        let resolver = self.typed.get(&TypeId::of::<R>())?.clone();
+        resolver.downcast::<R>().ok()
+    }
+
+    /// Применить порядок и список отключённых резолверов (в том числе к
+    /// зарегистрированным позже). Отключённые резолверы остаются в реестре и
+    /// возвращаются в цепочку, если следующая настройка их не отключает.
+    pub fn configure(&mut self, config: &ResolverChainConfig) {
+        self.config = config.clone();
+        self.sort();
+    }
+
+    /// Обернуть все резолверы цепочки, включая зарегистрированные позже
+    pub(super) fn add_layer(
+        &mut self,
+        layer: impl Fn(Arc<dyn TypeResolver>) -> Arc<dyn TypeResolver> + Send + Sync + 'static,
+    ) {
+        let layer: ResolverLayer = Arc::new(layer);
+        for entry in &mut self.chain {
+            entry.resolver = layer(entry.resolver.clone());
+        }
+        self.layers.push(layer);
+    }
+
+    /// Резолверы, готовые обработать выражение, в порядке опроса
+    pub fn candidates<'a>(
+        &'a self,
+        expression: &'a str,
+    ) -> impl Iterator<Item = &'a RegisteredResolver> + 'a {
+        self.iter().filter(move |entry| entry.accepts(expression))
+    }
+
+    /// Действующая настройка цепочки
+    pub fn config(&self) -> &ResolverChainConfig {
+        &self.config
+    }
+
+    /// Включённые резолверы в порядке опроса
+    pub fn iter(&self) -> impl Iterator<Item = &RegisteredResolver> {
+        self.chain
+            .iter()
+            .filter(|entry| !self.config.is_disabled(entry.name()))
+    }
+
+    /// Имена резолверов в порядке опроса
+    pub fn names(&self) -> Vec<String> {
+        self.iter().map(|entry| entry.name().to_string()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Явный порядок из настроек, затем класс стоимости; сортировка
+    /// устойчивая, поэтому при равенстве сохраняется порядок регистрации
+    fn sort(&mut self) {
+        let config = &self.config;
+        self.chain
+            .sort_by_key(|entry| (config.priority(entry.name()), entry.capabilities.cost));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::architecture::domain::{BuiltinTypeResolver, ExpressionResolver};
+
+    #[test]
+    fn test_registry_order_and_typed_access() {
+        let mut registry = ResolverRegistry::new();
+        let cheap = ResolverCapabilities::new(ResolverCost::Cheap);
+        let expensive = ResolverCapabilities::new(ResolverCost::Expensive);
+        registry.register_with(ExpressionResolver, expensive);
+        registry.register_with(BuiltinTypeResolver::new(), cheap);
+        assert_eq!(
+            registry.names(),
+            ["BuiltinTypeResolver", "ExpressionResolver"]
+        );
+        assert!(registry.get::<ExpressionResolver>().is_some());
+
+        registry.configure(&ResolverChainConfig {
+            order: vec!["ExpressionResolver".to_string()],
+            disabled: vec!["BuiltinTypeResolver".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(registry.names(), ["ExpressionResolver"]);
+        // Отключение убирает резолвер из цепочки, но не из доступа по типу
+        assert!(registry.get::<BuiltinTypeResolver>().is_some());
+        assert_eq!(registry.candidates("Массив.Добавить").count(), 1);
+        // ExpressionResolver принимает только точечные выражения
+        assert_eq!(registry.candidates("Массив").count(), 0);
+
+        // Следующая настройка возвращает отключённый ранее резолвер
+        registry.configure(&ResolverChainConfig::default());
+        assert_eq!(
+            registry.names(),
+            ["BuiltinTypeResolver", "ExpressionResolver"]
+        );
+
+        // Повторная регистрация заменяет резолвер, а не дублирует его
+        let moderate = ResolverCapabilities::new(ResolverCost::Moderate);
+        registry.register_with(BuiltinTypeResolver::new(), moderate);
+        assert_eq!(
+            registry.names(),
+            ["BuiltinTypeResolver", "ExpressionResolver"]
+        );
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_capability_patterns() {
+        let capabilities = ResolverCapabilities::new(ResolverCost::Cheap)
+            .with_pattern(r"^//\s*@dsl")
+            .unwrap();
+        assert!(capabilities.matches("// @dsl Запрос"));
+        assert!(!capabilities.matches("Массив.Добавить"));
+        assert!(ResolverCapabilities::default().matches("Массив.Добавить"));
+        assert!(ResolverCapabilities::default().with_pattern("(").is_err());
+    }
+}
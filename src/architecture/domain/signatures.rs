@@ -0,0 +1,523 @@
+//! Сигнатуры пользовательских функций проекта и вывод их возвращаемых типов
+//!
+//! Таблица строится проходом по всем модулям проекта: для каждой функции
+//! запоминаются параметры и значения `Возврат` ([`function_returns`]).
+//! Возвращаемый тип — объединение типов этих значений, а значение-вызов
+//! другой функции берёт её текущий возвращаемый тип. Взаимно рекурсивные
+//! функции зависят друг от друга, поэтому типы уточняются итерациями до
+//! неподвижной точки: ещё не выведенный тип вызываемой функции не вносит
+//! вклада, а набор типов каждой функции от итерации к итерации только растёт.
+//!
+//! Вызов асинхронной функции (`Асинх Функция`) возвращает `Обещание`, а
+//! выведенный тип значений `Возврат` получает только `Ждать Функция()`.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use super::assignments::{function_returns, AssignedValue};
+use super::globals::GlobalModuleKind;
+use super::ExpressionResolver;
+use crate::core::common_module_calls::module_name_from_path;
+use crate::core::module_structure::method_header_regex;
+use crate::domain::types::{
+    ConcreteType, PlatformType, ResolutionResult, SpecialType, TypeResolution,
+};
+use crate::unified::data::TypeRepository;
+
+/// Предел итераций: при росте наборов типов неподвижная точка достигается
+/// раньше, предел лишь страхует от зацикливания
+const MAX_ITERATIONS: usize = 16;
+
+/// Параметр функции
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterSignature {
+    pub name: String,
+    /// Передаётся по значению (`Знач`)
+    pub by_value: bool,
+    /// Значение по умолчанию как в тексте (`0`, `Неопределено`)
+    pub default: Option<String>,
+}
+
+/// Сигнатура функции проекта
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionSignature {
+    pub name: String,
+    /// Общий модуль, если функция объявлена в нём
+    pub module: Option<String>,
+    pub file: String,
+    /// Строка заголовка (с 0)
+    pub line: u32,
+    pub params: Vec<ParameterSignature>,
+    pub exported: bool,
+    /// Асинхронная функция (`Асинх`): вызов возвращает `Обещание`
+    pub is_async: bool,
+    /// Выведенный тип значений `Возврат` (для асинхронной функции — тип, с
+    /// которым завершается обещание); `None`, пока ни одно значение
+    /// `Возврат` не удалось вывести
+    pub return_type: Option<TypeResolution>,
+}
+
+impl FunctionSignature {
+    /// Тип результата вызова: `Обещание` для асинхронной функции
+    pub fn call_type(&self) -> Option<TypeResolution> {
+        if self.is_async {
+            Some(promise_type())
+        } else {
+            self.return_type.clone()
+        }
+    }
+}
+
+/// Платформенный тип `Обещание` — результат вызова асинхронного метода
+pub fn promise_type() -> TypeResolution {
+    TypeResolution::known(ConcreteType::Platform(PlatformType {
+        name: "Обещание".to_string(),
+        methods: Vec::new(),
+        properties: Vec::new(),
+    }))
+}
+
+/// Функция вместе с исходными значениями `Возврат`
+#[derive(Debug, Clone)]
+struct FunctionEntry {
+    signature: FunctionSignature,
+    returns: Vec<AssignedValue>,
+}
+
+/// Таблица сигнатур функций проекта
+#[derive(Debug, Clone, Default)]
+pub struct SignatureTable {
+    functions: Vec<FunctionEntry>,
+    /// Имя функции в нижнем регистре → индексы в `functions`
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+fn export_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)\)\s*(?:экспорт|export)\b").unwrap())
+}
+
+/// Параметры из заголовка метода
+fn parse_params(params: &str) -> Vec<ParameterSignature> {
+    params
+        .split(',')
+        .filter_map(|param| {
+            let (name, default) = match param.split_once('=') {
+                Some((name, default)) => (name, Some(default.trim().to_string())),
+                None => (param, None),
+            };
+            let name = name.trim();
+            let (name, by_value) = match name
+                .strip_prefix("Знач ")
+                .or_else(|| name.strip_prefix("Val "))
+            {
+                Some(rest) => (rest.trim(), true),
+                None => (name, false),
+            };
+            (!name.is_empty()).then(|| ParameterSignature {
+                name: name.to_string(),
+                by_value,
+                default,
+            })
+        })
+        .collect()
+}
+
+/// Одинаковые наборы типов и уверенность (веса объединения при итерациях
+/// уточняются бесконечно, поэтому не сравниваются)
+fn same_types(a: &TypeResolution, b: &TypeResolution) -> bool {
+    let dynamic = |r: &TypeResolution| matches!(r.result, ResolutionResult::Dynamic);
+    dynamic(a) == dynamic(b)
+        && a.certainty == b.certainty
+        && a.is_subtype_of(b)
+        && b.is_subtype_of(a)
+}
+
+impl SignatureTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Освободить выражения возвращаемых значений: выведенные типы
+    /// остаются, но пересчитать их после правки модуля уже нельзя
+    pub fn release_returns(&mut self) {
+        for entry in &mut self.functions {
+            entry.returns = Vec::new();
+        }
+        self.functions.shrink_to_fit();
+        self.by_name.shrink_to_fit();
+    }
+
+    /// Добавить функции модуля (возвращаемые типы выводит [`Self::infer`])
+    pub fn add_module(&mut self, file: &str, text: &str) {
+        let module = module_name_from_path(Path::new(file));
+        let lines: Vec<&str> = text.lines().collect();
+        for function in function_returns(text) {
+            let header = lines.get(function.line as usize).copied().unwrap_or_default();
+            let params = method_header_regex()
+                .captures(header)
+                .map(|caps| parse_params(&caps[3]))
+                .unwrap_or_default();
+            let index = self.functions.len();
+            self.by_name
+                .entry(function.name.to_lowercase())
+                .or_default()
+                .push(index);
+            self.functions.push(FunctionEntry {
+                signature: FunctionSignature {
+                    name: function.name,
+                    module: module.clone(),
+                    file: file.to_string(),
+                    line: function.line,
+                    params,
+                    exported: export_regex().is_match(header),
+                    is_async: function.is_async,
+                    return_type: None,
+                },
+                returns: function.values,
+            });
+        }
+    }
+
+    /// Убрать функции модуля `file` (изменён или удалён); возвращаемые
+    /// типы остальных функций пересчитывает [`Self::infer`]
+    pub fn remove_module(&mut self, file: &str) {
+        self.functions.retain(|entry| entry.signature.file != file);
+        self.by_name.clear();
+        for (index, entry) in self.functions.iter().enumerate() {
+            self.by_name
+                .entry(entry.signature.name.to_lowercase())
+                .or_default()
+                .push(index);
+        }
+    }
+
+    /// Возвращает ли какая-нибудь функция значение типа, для имени которого
+    /// `affected` истинно (`Возврат Новый Массив`)
+    pub fn returns_type(&self, affected: impl Fn(&str) -> bool) -> bool {
+        self.functions.iter().any(|entry| {
+            entry.returns.iter().any(|value| match value {
+                AssignedValue::Type(name) => affected(name.as_str()),
+                _ => false,
+            })
+        })
+    }
+
+    /// Построить таблицу по модулям проекта (путь, текст) и вывести
+    /// возвращаемые типы
+    pub async fn build(modules: &[(String, String)], repository: &dyn TypeRepository) -> Self {
+        let mut table = Self::new();
+        for (file, text) in modules {
+            table.add_module(file, text);
+        }
+        table.infer(repository).await;
+        table
+    }
+
+    /// Вывести возвращаемые типы итерациями до неподвижной точки
+    pub async fn infer(&mut self, repository: &dyn TypeRepository) {
+        // Типы по имени не зависят от итерации — разрешаются один раз
+        let resolver = ExpressionResolver::new();
+        let mut named: HashMap<String, Option<TypeResolution>> = HashMap::new();
+        for entry in &self.functions {
+            for value in &entry.returns {
+                if let AssignedValue::Type(name) = value {
+                    if !named.contains_key(name) {
+                        let resolution = resolver.resolve_type_by_name(name, repository).await;
+                        named.insert(name.clone(), resolution);
+                    }
+                }
+            }
+        }
+
+        for _ in 0..MAX_ITERATIONS {
+            let mut changed = false;
+            for index in 0..self.functions.len() {
+                let inferred = self.infer_function(index, &named);
+                let current = &mut self.functions[index].signature.return_type;
+                let same = match (&*current, &inferred) {
+                    (Some(old), Some(new)) => same_types(old, new),
+                    (None, None) => true,
+                    _ => false,
+                };
+                if !same {
+                    *current = inferred;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Возвращаемый тип функции по текущим типам вызываемых функций
+    fn infer_function(
+        &self,
+        index: usize,
+        named: &HashMap<String, Option<TypeResolution>>,
+    ) -> Option<TypeResolution> {
+        let entry = &self.functions[index];
+        if entry.returns.is_empty() {
+            // Функция без `Возврат значение` возвращает Неопределено
+            return Some(TypeResolution::known(ConcreteType::Special(
+                SpecialType::Undefined,
+            )));
+        }
+        let mut branches = Vec::new();
+        for value in &entry.returns {
+            match value {
+                AssignedValue::Type(name) => branches.push(
+                    named
+                        .get(name)
+                        .cloned()
+                        .flatten()
+                        .unwrap_or_else(TypeResolution::unknown),
+                ),
+                AssignedValue::Call { module, function } => {
+                    match self.find(module.as_deref(), function, Some(&entry.signature.file)) {
+                        // Ещё не выведенный тип не вносит вклада
+                        Some(callee) => branches.extend(callee.call_type()),
+                        None => branches.push(TypeResolution::unknown()),
+                    }
+                }
+                AssignedValue::Awaited { module, function } => {
+                    match self.find(module.as_deref(), function, Some(&entry.signature.file)) {
+                        Some(callee) => branches.extend(callee.return_type.clone()),
+                        None => branches.push(TypeResolution::unknown()),
+                    }
+                }
+                // Объявленные типы метаданных и колонки запросов (их выводит
+                // репозиторий типов) таблице сигнатур не известны
+                AssignedValue::MetadataValue(_)
+                | AssignedValue::Query { .. }
+                | AssignedValue::Unknown => branches.push(TypeResolution::unknown()),
+            }
+        }
+        (!branches.is_empty()).then(|| TypeResolution::join_all(branches))
+    }
+
+    /// Найти вызываемую функцию: `Модуль.Функция` — экспортная функция
+    /// общего модуля, просто `Функция` — функция того же файла, а если её
+    /// нет — экспортная функция модуля приложения или сеанса (без файла —
+    /// единственная функция с таким именем)
+    pub fn find(
+        &self,
+        module: Option<&str>,
+        name: &str,
+        file: Option<&str>,
+    ) -> Option<&FunctionSignature> {
+        let mut candidates = self
+            .by_name
+            .get(&name.to_lowercase())?
+            .iter()
+            .map(|&index| &self.functions[index].signature);
+        match (module, file) {
+            (Some(module), _) => candidates.find(|signature| {
+                signature.exported
+                    && signature
+                        .module
+                        .as_deref()
+                        .is_some_and(|m| m.to_lowercase() == module.to_lowercase())
+            }),
+            (None, Some(file)) => {
+                let candidates: Vec<_> = candidates.collect();
+                candidates
+                    .iter()
+                    .find(|signature| signature.file == file)
+                    .or_else(|| {
+                        candidates.iter().find(|signature| {
+                            signature.exported
+                                && GlobalModuleKind::from_path(&signature.file).is_some()
+                        })
+                    })
+                    .copied()
+            }
+            (None, None) => {
+                let first = candidates.next()?;
+                candidates.next().is_none().then_some(first)
+            }
+        }
+    }
+
+    /// Тип результата вызова, если он выведен (неизвестный тип не
+    /// возвращается: он ничего не добавляет к выводу)
+    pub fn call_type(
+        &self,
+        module: Option<&str>,
+        name: &str,
+        file: Option<&str>,
+    ) -> Option<TypeResolution> {
+        self.find(module, name, file)?
+            .call_type()
+            .filter(|resolution| !matches!(resolution.result, ResolutionResult::Dynamic))
+    }
+
+    /// Тип `Ждать Функция()`: значение, которым завершается обещание
+    /// асинхронной функции. `Ждать` с обычной функцией возвращает её
+    /// результат без изменений.
+    pub fn awaited_type(
+        &self,
+        module: Option<&str>,
+        name: &str,
+        file: Option<&str>,
+    ) -> Option<TypeResolution> {
+        self.find(module, name, file)?
+            .return_type
+            .clone()
+            .filter(|resolution| !matches!(resolution.result, ResolutionResult::Dynamic))
+    }
+
+    /// Все функции таблицы
+    pub fn functions(&self) -> impl Iterator<Item = &FunctionSignature> {
+        self.functions.iter().map(|entry| &entry.signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::types::{PrimitiveType, WeightedType};
+    use crate::unified::data::InMemoryTypeRepository;
+
+    fn type_set(resolution: &TypeResolution) -> Vec<ConcreteType> {
+        match &resolution.result {
+            ResolutionResult::Concrete(type_) => vec![type_.clone()],
+            ResolutionResult::Union(members) => {
+                members.iter().map(|m: &WeightedType| m.type_.clone()).collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mutual_recursion_reaches_fixed_point() {
+        let module = r#"Функция Чёт(Н)
+    Если Н = 0 Тогда Возврат Истина; КонецЕсли;
+    Возврат Нечёт(Н - 1);
+КонецФункции
+
+Функция Нечёт(Знач Н, Режим = 0)
+    Если Н = 0 Тогда Возврат "нет"; КонецЕсли;
+    Результат = Чёт(Н - 1);
+    Возврат Результат;
+КонецФункции
+
+Функция Факториал(Н) Экспорт
+    Если Н <= 1 Тогда Возврат 1; КонецЕсли;
+    Возврат Факториал(Н - 1);
+КонецФункции"#;
+        let repository = InMemoryTypeRepository::new();
+        let modules = vec![(
+            "CommonModules/Математика/Ext/Module.bsl".to_string(),
+            module.to_string(),
+        )];
+        let table = SignatureTable::build(&modules, &repository).await;
+        assert_eq!(table.len(), 3);
+
+        let boolean = ConcreteType::Primitive(PrimitiveType::Boolean);
+        let string = ConcreteType::Primitive(PrimitiveType::String);
+        for name in ["Чёт", "Нечёт"] {
+            let types = type_set(&table.call_type(None, name, None).unwrap());
+            assert_eq!(types.len(), 2, "{name}");
+            assert!(types.contains(&boolean) && types.contains(&string));
+        }
+
+        let odd = table.find(None, "Нечёт", None).unwrap();
+        assert_eq!(odd.params.len(), 2);
+        assert!(odd.params[0].by_value);
+        assert_eq!(odd.params[1].default.as_deref(), Some("0"));
+        assert!(!odd.exported);
+
+        // Экспортная функция общего модуля по квалифицированному имени
+        let factorial = table
+            .call_type(Some("математика"), "Факториал", Some("Other.bsl"))
+            .unwrap();
+        assert_eq!(
+            type_set(&factorial),
+            [ConcreteType::Primitive(PrimitiveType::Number)]
+        );
+        assert!(table.find(Some("Математика"), "Чёт", None).is_none());
+        assert!(table.call_type(None, "Факториал", Some("Other.bsl")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_async_function_returns_promise() {
+        let module = r#"Асинх Функция Загрузить(Адрес) Экспорт
+    Возврат "данные";
+КонецФункции
+
+Асинх Функция Обработать()
+    Данные = Ждать Загрузить("http://");
+    Возврат Данные;
+КонецФункции
+
+Функция Запустить()
+    Возврат Загрузить("http://");
+КонецФункции"#;
+        let repository = InMemoryTypeRepository::new();
+        let modules = vec![(
+            "CommonModules/Сеть/Ext/Module.bsl".to_string(),
+            module.to_string(),
+        )];
+        let table = SignatureTable::build(&modules, &repository).await;
+        let string = ConcreteType::Primitive(PrimitiveType::String);
+        let promise = type_set(&promise_type());
+
+        let load = table.find(Some("Сеть"), "Загрузить", None).unwrap();
+        assert!(load.is_async && load.exported);
+        assert_eq!(type_set(&table.call_type(Some("Сеть"), "Загрузить", None).unwrap()), promise);
+        assert_eq!(
+            type_set(&table.awaited_type(Some("Сеть"), "Загрузить", None).unwrap()),
+            [string.clone()]
+        );
+
+        // `Ждать` внутри другой асинхронной функции раскрывает обещание
+        assert_eq!(type_set(&table.awaited_type(None, "Обработать", None).unwrap()), [string]);
+        assert_eq!(type_set(&table.call_type(None, "Запустить", None).unwrap()), promise);
+        assert!(!table.find(None, "Запустить", None).unwrap().is_async);
+    }
+
+    #[tokio::test]
+    async fn test_application_module_functions_are_global() {
+        let application = r#"Функция ВерсияКлиента() Экспорт
+    Возврат "1.0";
+КонецФункции
+
+Функция Служебная()
+    Возврат 0;
+КонецФункции"#;
+        let repository = InMemoryTypeRepository::new();
+        let modules = vec![
+            (
+                "cf/Ext/ManagedApplicationModule.bsl".to_string(),
+                application.to_string(),
+            ),
+            (
+                "cf/Documents/Заказ/Forms/Форма/Ext/Form/Module.bsl".to_string(),
+                "Функция Заголовок()\n    Возврат ВерсияКлиента();\nКонецФункции".to_string(),
+            ),
+        ];
+        let table = SignatureTable::build(&modules, &repository).await;
+        let form = modules[1].0.as_str();
+
+        let version = table.find(None, "ВерсияКлиента", Some(form)).unwrap();
+        assert_eq!(version.file, modules[0].0);
+        assert_eq!(
+            type_set(&table.call_type(None, "Заголовок", Some(form)).unwrap()),
+            [ConcreteType::Primitive(PrimitiveType::String)]
+        );
+        // Неэкспортная функция модуля приложения не глобальна
+        assert!(table.find(None, "Служебная", Some(form)).is_none());
+    }
+}
@@ -0,0 +1,569 @@
+//! Форматирование модулей BSL (`textDocument/formatting`,
+//! `rangeFormatting`, `onTypeFormatting`)
+//!
+//! Форматирование построчное и не меняет число строк: ключевые слова
+//! приводятся к единому регистру (`конецесли` → `КонецЕсли`), отступ
+//! строки вычисляется по вложенности блоков (`Если`/`Цикл`/`Попытка`,
+//! тела методов) и незакрытым скобкам, вокруг операторов и после запятых
+//! ставится по одному пробелу. Строки продолжения многострочных литералов
+//! (`|ВЫБРАТЬ ...`), инструкции препроцессора и директивы компиляции
+//! не переформатируются, только последние прижимаются к началу строки.
+//! Профиль стиля задаётся в настройках (`FormattingConfig`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tower_lsp::lsp_types::{FormattingOptions, Position, Range, TextEdit};
+
+/// Регистр ключевых слов
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordCase {
+    /// Как в синтакс-помощнике: `КонецЕсли`, `EndIf`
+    #[default]
+    Canonical,
+    Lower,
+    Upper,
+    /// Не менять
+    Preserve,
+}
+
+impl std::str::FromStr for KeywordCase {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "canonical" => Ok(KeywordCase::Canonical),
+            "lower" => Ok(KeywordCase::Lower),
+            "upper" => Ok(KeywordCase::Upper),
+            "preserve" => Ok(KeywordCase::Preserve),
+            other => Err(format!("неизвестный регистр ключевых слов '{}'", other)),
+        }
+    }
+}
+
+/// Единица отступа
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndentStyle {
+    /// Из параметров запроса редактора (`tabSize`, `insertSpaces`)
+    #[default]
+    Editor,
+    Tabs,
+    /// `indent_size` пробелов
+    Spaces,
+}
+
+impl std::str::FromStr for IndentStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "editor" => Ok(IndentStyle::Editor),
+            "tabs" => Ok(IndentStyle::Tabs),
+            "spaces" => Ok(IndentStyle::Spaces),
+            other => Err(format!("неизвестный стиль отступа '{}'", other)),
+        }
+    }
+}
+
+/// Профиль стиля форматирования
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormattingConfig {
+    pub keyword_case: KeywordCase,
+    pub indent: IndentStyle,
+    /// Ширина отступа для `IndentStyle::Spaces`
+    pub indent_size: u32,
+    /// Пробелы вокруг операторов и после запятых
+    pub operator_spacing: bool,
+}
+
+impl Default for FormattingConfig {
+    fn default() -> Self {
+        Self {
+            keyword_case: KeywordCase::default(),
+            indent: IndentStyle::default(),
+            indent_size: 4,
+            operator_spacing: true,
+        }
+    }
+}
+
+impl FormattingConfig {
+    fn indent_unit(&self, options: &FormattingOptions) -> String {
+        match self.indent {
+            IndentStyle::Editor if options.insert_spaces => " ".repeat(options.tab_size as usize),
+            IndentStyle::Editor | IndentStyle::Tabs => "\t".to_string(),
+            IndentStyle::Spaces => " ".repeat(self.indent_size as usize),
+        }
+    }
+}
+
+/// Ключевые слова в каноническом написании (русское и английское)
+const KEYWORDS: &[(&str, &str)] = &[
+    ("Если", "If"),
+    ("Тогда", "Then"),
+    ("ИначеЕсли", "ElsIf"),
+    ("Иначе", "Else"),
+    ("КонецЕсли", "EndIf"),
+    ("Для", "For"),
+    ("Каждого", "Each"),
+    ("Из", "In"),
+    ("По", "To"),
+    ("Цикл", "Do"),
+    ("КонецЦикла", "EndDo"),
+    ("Пока", "While"),
+    ("Процедура", "Procedure"),
+    ("КонецПроцедуры", "EndProcedure"),
+    ("Функция", "Function"),
+    ("КонецФункции", "EndFunction"),
+    ("Перем", "Var"),
+    ("Возврат", "Return"),
+    ("Продолжить", "Continue"),
+    ("Прервать", "Break"),
+    ("И", "And"),
+    ("Или", "Or"),
+    ("Не", "Not"),
+    ("Истина", "True"),
+    ("Ложь", "False"),
+    ("Неопределено", "Undefined"),
+    ("Null", "Null"),
+    ("Попытка", "Try"),
+    ("Исключение", "Except"),
+    ("КонецПопытки", "EndTry"),
+    ("ВызватьИсключение", "Raise"),
+    ("Новый", "New"),
+    ("Выполнить", "Execute"),
+    ("Экспорт", "Export"),
+    ("Знач", "Val"),
+    ("Перейти", "Goto"),
+    ("Асинх", "Async"),
+    ("Ждать", "Await"),
+    ("ДобавитьОбработчик", "AddHandler"),
+    ("УдалитьОбработчик", "RemoveHandler"),
+];
+
+/// Открывают блок: отступ следующих строк увеличивается
+const OPENERS: &[&str] = &[
+    "если",
+    "цикл",
+    "процедура",
+    "функция",
+    "попытка",
+    "if",
+    "do",
+    "procedure",
+    "function",
+    "try",
+];
+
+/// Закрывают блок
+const CLOSERS: &[&str] = &[
+    "конецесли",
+    "конеццикла",
+    "конецпроцедуры",
+    "конецфункции",
+    "конецпопытки",
+    "endif",
+    "enddo",
+    "endprocedure",
+    "endfunction",
+    "endtry",
+];
+
+/// Продолжают блок на уровне его начала
+const BRANCHES: &[&str] = &[
+    "иначеесли",
+    "иначе",
+    "исключение",
+    "elsif",
+    "else",
+    "except",
+];
+
+/// Значения, после которых `-` и `+` — бинарные операторы
+const VALUES: &[&str] = &[
+    "истина",
+    "ложь",
+    "неопределено",
+    "null",
+    "true",
+    "false",
+    "undefined",
+];
+
+fn keyword(word: &str) -> Option<&'static str> {
+    static MAP: OnceLock<HashMap<String, &'static str>> = OnceLock::new();
+    MAP.get_or_init(|| {
+        KEYWORDS
+            .iter()
+            .flat_map(|(ru, en)| [*ru, *en])
+            .map(|k| (k.to_lowercase(), k))
+            .collect()
+    })
+    .get(&word.to_lowercase())
+    .copied()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Word,
+    Number,
+    /// Строка или её часть до конца строки модуля
+    Str,
+    Date,
+    Operator,
+    Punct,
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: Kind,
+    text: &'a str,
+    /// Пробелы перед лексемой в исходной строке
+    gap: &'a str,
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Лексемы строки модуля; `in_string` — строка начинается внутри
+/// многострочного литерала. Второе значение — литерал не закрыт в конце
+/// строки.
+fn tokenize(line: &str, mut in_string: bool) -> (Vec<Token<'_>>, bool) {
+    let mut tokens = Vec::new();
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let byte = |idx: usize| chars.get(idx).map_or(line.len(), |(b, _)| *b);
+    let mut idx = 0;
+    let mut gap_start = 0;
+    while idx < chars.len() {
+        let start = idx;
+        let ch = chars[idx].1;
+        let kind = if in_string || ch == '"' {
+            // Литерал до закрывающей кавычки (`""` внутри — экранированная)
+            if !in_string {
+                idx += 1;
+            }
+            in_string = true;
+            while idx < chars.len() {
+                if chars[idx].1 == '"' {
+                    if chars.get(idx + 1).is_some_and(|(_, c)| *c == '"') {
+                        idx += 2;
+                        continue;
+                    }
+                    in_string = false;
+                    idx += 1;
+                    break;
+                }
+                idx += 1;
+            }
+            Kind::Str
+        } else if ch.is_whitespace() {
+            idx += 1;
+            continue;
+        } else if ch == '/' && chars.get(idx + 1).is_some_and(|(_, c)| *c == '/') {
+            idx = chars.len();
+            Kind::Comment
+        } else if ch == '\'' {
+            idx += 1;
+            while idx < chars.len() && chars[idx].1 != '\'' {
+                idx += 1;
+            }
+            idx = (idx + 1).min(chars.len());
+            Kind::Date
+        } else if ch.is_ascii_digit() {
+            while idx < chars.len() && (chars[idx].1.is_ascii_digit() || chars[idx].1 == '.') {
+                idx += 1;
+            }
+            Kind::Number
+        } else if is_word_char(ch) {
+            while idx < chars.len() && is_word_char(chars[idx].1) {
+                idx += 1;
+            }
+            Kind::Word
+        } else if "=<>+-*/%".contains(ch) {
+            idx += 1;
+            let next = chars.get(idx).map(|(_, c)| *c);
+            if ch == '<' && matches!(next, Some('>' | '=')) || ch == '>' && next == Some('=') {
+                idx += 1;
+            }
+            Kind::Operator
+        } else {
+            idx += 1;
+            Kind::Punct
+        };
+        tokens.push(Token {
+            kind,
+            text: &line[byte(start)..byte(idx)],
+            gap: &line[gap_start..byte(start)],
+        });
+        gap_start = byte(idx);
+    }
+    (tokens, in_string)
+}
+
+fn is_keyword_in(token: &Token, list: &[&str]) -> bool {
+    token.kind == Kind::Word && list.contains(&token.text.to_lowercase().as_str())
+}
+
+/// `+`/`-` в начале выражения: после оператора, скобки, запятой или
+/// ключевого слова (`Возврат -1`)
+fn is_unary(previous: Option<&Token>) -> bool {
+    match previous {
+        None => true,
+        Some(token) => match token.kind {
+            Kind::Operator => true,
+            Kind::Punct => matches!(token.text, "(" | "[" | "," | ";" | "?"),
+            Kind::Word => keyword(token.text).is_some() && !is_keyword_in(token, VALUES),
+            _ => false,
+        },
+    }
+}
+
+fn cased(token: &Token, previous: Option<&Token>, case: KeywordCase) -> String {
+    // Имя после точки — член объекта, а не ключевое слово
+    let member = previous.is_some_and(|p| p.text == ".");
+    match keyword(token.text).filter(|_| token.kind == Kind::Word && !member) {
+        Some(canonical) => match case {
+            KeywordCase::Canonical => canonical.to_string(),
+            KeywordCase::Lower => canonical.to_lowercase(),
+            KeywordCase::Upper => canonical.to_uppercase(),
+            KeywordCase::Preserve => token.text.to_string(),
+        },
+        None => token.text.to_string(),
+    }
+}
+
+/// Код строки без отступа, собранный из лексем
+fn render(tokens: &[Token], config: &FormattingConfig) -> String {
+    let mut out = String::new();
+    let mut unary = false;
+    for (idx, token) in tokens.iter().enumerate() {
+        let previous = idx.checked_sub(1).map(|p| &tokens[p]);
+        let binary = token.kind == Kind::Operator
+            && !(matches!(token.text, "+" | "-") && is_unary(previous));
+        if let Some(previous) = previous {
+            let separator = if !config.operator_spacing {
+                token.gap
+            } else if token.kind == Kind::Comment {
+                if token.gap.is_empty() {
+                    " "
+                } else {
+                    token.gap
+                }
+            } else if unary {
+                ""
+            } else if binary || previous.kind == Kind::Operator || previous.text == "," {
+                " "
+            } else if matches!(token.text, "," | ";") || token.gap.is_empty() {
+                ""
+            } else {
+                " "
+            };
+            out.push_str(separator);
+        }
+        unary = token.kind == Kind::Operator && !binary;
+        out.push_str(&cased(token, previous, config.keyword_case));
+    }
+    out
+}
+
+/// Отформатированные строки модуля: строка `i` результата соответствует
+/// строке `i` исходного текста. Пустая строка `cursor_line` получает отступ
+/// своего уровня (курсор после перевода строки), остальные пустые строки
+/// очищаются.
+fn format_lines(
+    text: &str,
+    config: &FormattingConfig,
+    unit: &str,
+    cursor_line: Option<usize>,
+) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut depth: usize = 0;
+    let mut brackets: usize = 0;
+    let mut in_string = false;
+    for (idx, line) in text.lines().enumerate() {
+        let continuation = in_string;
+        let (tokens, open) = tokenize(line, in_string);
+        in_string = open;
+        let trimmed = line.trim();
+        if continuation {
+            out.push(line.to_string());
+        } else if trimmed.starts_with('#') || trimmed.starts_with('&') {
+            out.push(trimmed.to_string());
+            continue;
+        } else if trimmed.is_empty() {
+            let level = depth + (brackets > 0) as usize;
+            let indent = if cursor_line == Some(idx) {
+                unit.repeat(level)
+            } else {
+                String::new()
+            };
+            out.push(indent);
+        } else {
+            let dedent = tokens
+                .first()
+                .is_some_and(|t| is_keyword_in(t, CLOSERS) || is_keyword_in(t, BRANCHES));
+            let level = depth.saturating_sub(dedent as usize) + (brackets > 0) as usize;
+            out.push(format!("{}{}", unit.repeat(level), render(&tokens, config)));
+        }
+        for token in &tokens {
+            if is_keyword_in(token, OPENERS) {
+                depth += 1;
+            } else if is_keyword_in(token, CLOSERS) {
+                depth = depth.saturating_sub(1);
+            } else if matches!(token.text, "(" | "[") && token.kind == Kind::Punct {
+                brackets += 1;
+            } else if matches!(token.text, ")" | "]") && token.kind == Kind::Punct {
+                brackets = brackets.saturating_sub(1);
+            }
+        }
+    }
+    out
+}
+
+/// Правки строк `first..=last`, текст которых изменился
+fn line_edits(text: &str, formatted: &[String], first: u32, last: u32) -> Vec<TextEdit> {
+    text.lines()
+        .zip(formatted)
+        .enumerate()
+        .skip(first as usize)
+        .take_while(|(idx, _)| *idx as u32 <= last)
+        .filter(|(_, (original, formatted))| original != formatted)
+        .map(|(idx, (original, formatted))| {
+            let end = original.encode_utf16().count() as u32;
+            TextEdit::new(
+                Range::new(Position::new(idx as u32, 0), Position::new(idx as u32, end)),
+                formatted.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Правки, форматирующие весь модуль
+pub fn format_document(
+    text: &str,
+    config: &FormattingConfig,
+    options: &FormattingOptions,
+) -> Vec<TextEdit> {
+    let formatted = format_lines(text, config, &config.indent_unit(options), None);
+    line_edits(text, &formatted, 0, u32::MAX)
+}
+
+/// Правки строк диапазона; отступы вычисляются по всему модулю
+pub fn format_range(
+    text: &str,
+    range: Range,
+    config: &FormattingConfig,
+    options: &FormattingOptions,
+) -> Vec<TextEdit> {
+    let formatted = format_lines(text, config, &config.indent_unit(options), None);
+    // Диапазон, заканчивающийся в начале строки, эту строку не включает
+    let last = match range.end.character {
+        0 if range.end.line > range.start.line => range.end.line - 1,
+        _ => range.end.line,
+    };
+    line_edits(text, &formatted, range.start.line, last)
+}
+
+/// Форматирование при вводе: `;` — текущая строка, перевод строки —
+/// законченная строка и отступ новой (курсор на строке `position.line`)
+pub fn format_on_type(
+    text: &str,
+    position: Position,
+    ch: &str,
+    config: &FormattingConfig,
+    options: &FormattingOptions,
+) -> Vec<TextEdit> {
+    let cursor_line = (ch == "\n").then_some(position.line as usize);
+    let formatted = format_lines(text, config, &config.indent_unit(options), cursor_line);
+    match ch {
+        "\n" => line_edits(
+            text,
+            &formatted,
+            position.line.saturating_sub(1),
+            position.line,
+        ),
+        _ => line_edits(text, &formatted, position.line, position.line),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> FormattingOptions {
+        FormattingOptions {
+            tab_size: 4,
+            insert_spaces: true,
+            ..Default::default()
+        }
+    }
+
+    fn apply(text: &str, edits: &[TextEdit]) -> String {
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        for edit in edits {
+            lines[edit.range.start.line as usize] = edit.new_text.clone();
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_format_document() {
+        let text = "&наклиенте
+процедура Пересчитать(Ставка,Знач Округлять=Ложь) экспорт
+если Ставка>0 тогда
+Сумма=Сумма*Ставка+-1; // итог
+иначе
+Запрос = Новый Запрос(\"ВЫБРАТЬ
+|  Товары.Цена КАК Цена  \");
+Вызов(Ставка ,
+1);
+конецесли;
+  конецпроцедуры";
+        let config = FormattingConfig::default();
+        let formatted = apply(text, &format_document(text, &config, &options()));
+        assert_eq!(
+            formatted,
+            "&наклиенте
+Процедура Пересчитать(Ставка, Знач Округлять = Ложь) Экспорт
+    Если Ставка > 0 Тогда
+        Сумма = Сумма * Ставка + -1; // итог
+    Иначе
+        Запрос = Новый Запрос(\"ВЫБРАТЬ
+|  Товары.Цена КАК Цена  \");
+        Вызов(Ставка,
+            1);
+    КонецЕсли;
+КонецПроцедуры"
+        );
+
+        // Правки только в диапазоне, отступ — по всему модулю
+        let range = Range::new(Position::new(3, 0), Position::new(4, 0));
+        let edits = format_range(text, range, &config, &options());
+        assert_eq!(edits.len(), 1);
+        assert_eq!(
+            edits[0].new_text,
+            "        Сумма = Сумма * Ставка + -1; // итог"
+        );
+
+        let lower = FormattingConfig {
+            keyword_case: KeywordCase::Lower,
+            indent: IndentStyle::Tabs,
+            ..FormattingConfig::default()
+        };
+        let edits = format_on_type(text, Position::new(9, 10), ";", &lower, &options());
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].new_text, "\tконецесли;");
+
+        // Перевод строки после `Тогда`: отступ новой строки — уровень блока
+        let text = "Если Истина Тогда\n  \n";
+        let edits = format_on_type(text, Position::new(1, 2), "\n", &config, &options());
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.start.line, 1);
+        assert_eq!(edits[0].new_text, "    ");
+    }
+}
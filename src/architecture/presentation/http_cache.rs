@@ -0,0 +1,249 @@
+//! HTTP кеширование и сжатие ответов веб API
+//!
+//! Полная иерархия и результаты поиска по платформе занимают мегабайты JSON и
+//! часто запрашиваются повторно без изменений. Модуль не зависит от веб-фреймворка:
+//! вычисляет ETag по телу ответа, проверяет `If-None-Match` и сжимает тело
+//! согласно `Accept-Encoding` (br / gzip).
+//!
+//! ETag слабый (`W/"..."`): он считается по JSON до сжатия, а байты ответа в
+//! br, gzip и без сжатия различаются. Кешам нужен и `Vary: Accept-Encoding`.
+
+use anyhow::Result;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+
+/// Ответы меньше этого размера не сжимаются — накладные расходы больше выигрыша
+pub const MIN_COMPRESS_SIZE: usize = 1024;
+
+/// Кодировка тела ответа
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Brotli,
+    Gzip,
+    Identity,
+}
+
+impl ContentEncoding {
+    /// Значение заголовка `Content-Encoding` (None для identity)
+    pub fn header_value(&self) -> Option<&'static str> {
+        match self {
+            ContentEncoding::Brotli => Some("br"),
+            ContentEncoding::Gzip => Some("gzip"),
+            ContentEncoding::Identity => None,
+        }
+    }
+
+    /// Выбрать кодировку по заголовку `Accept-Encoding`: побеждает больший
+    /// вес `q`, при равном весе br предпочтительнее gzip
+    pub fn negotiate(accept_encoding: Option<&str>) -> Self {
+        let Some(header) = accept_encoding else {
+            return ContentEncoding::Identity;
+        };
+
+        // Вес кодировки: явная запись (`br;q=0`) важнее `*`
+        let quality = |name: &str| {
+            let mut wildcard = None;
+            for part in header.split(',') {
+                let mut pieces = part.trim().split(';');
+                let coding = pieces.next().unwrap_or("").trim();
+                let Some(q) = pieces
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                else {
+                    continue;
+                };
+                if coding.eq_ignore_ascii_case(name) {
+                    return q;
+                }
+                if coding == "*" {
+                    wildcard = Some(q);
+                }
+            }
+            wildcard.unwrap_or(0.0)
+        };
+
+        let (brotli, gzip) = (quality("br"), quality("gzip"));
+        if brotli > 0.0 && brotli >= gzip {
+            ContentEncoding::Brotli
+        } else if gzip > 0.0 {
+            ContentEncoding::Gzip
+        } else {
+            ContentEncoding::Identity
+        }
+    }
+
+    /// Сжать тело выбранной кодировкой
+    pub fn encode(&self, body: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            ContentEncoding::Brotli => {
+                let mut out = Vec::with_capacity(body.len() / 4);
+                {
+                    // quality 5 — компромисс между степенью сжатия и задержкой ответа
+                    let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+                    writer.write_all(body)?;
+                }
+                Ok(out)
+            }
+            ContentEncoding::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+                encoder.write_all(body)?;
+                Ok(encoder.finish()?)
+            }
+            ContentEncoding::Identity => Ok(body.to_vec()),
+        }
+    }
+}
+
+/// Подготовленный к отдаче JSON ответ
+#[derive(Debug, Clone)]
+pub struct CachedBody {
+    /// Слабый ETag (`W/"..."`, готов для заголовка)
+    pub etag: String,
+    /// Тело ответа (возможно, сжатое)
+    pub body: Vec<u8>,
+    /// Применённая кодировка
+    pub encoding: ContentEncoding,
+}
+
+/// Результат обработки условного запроса
+#[derive(Debug, Clone)]
+pub enum CachedResponse {
+    /// Клиентская копия актуальна — ответить 304 с этим ETag
+    NotModified { etag: String },
+    /// Отдать тело
+    Body(CachedBody),
+}
+
+/// Вычислить слабый ETag для несжатого тела ответа: один на все кодировки
+pub fn compute_etag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("W/\"{}\"", hex)
+}
+
+/// Совпадает ли ETag с заголовком `If-None-Match` (поддерживаются списки и
+/// `*`; сравнение слабое — префикс `W/` не учитывается)
+pub fn etag_matches(if_none_match: Option<&str>, etag: &str) -> bool {
+    let Some(header) = if_none_match else {
+        return false;
+    };
+    let opaque = |tag: &str| tag.strip_prefix("W/").unwrap_or(tag).to_string();
+    let etag = opaque(etag);
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || opaque(candidate) == etag)
+}
+
+/// Сериализовать значение в JSON и подготовить ответ с учётом заголовков запроса
+pub fn prepare_json<T: Serialize>(
+    value: &T,
+    if_none_match: Option<&str>,
+    accept_encoding: Option<&str>,
+) -> Result<CachedResponse> {
+    let json = serde_json::to_vec(value)?;
+    let etag = compute_etag(&json);
+
+    if etag_matches(if_none_match, &etag) {
+        return Ok(CachedResponse::NotModified { etag });
+    }
+
+    let encoding = if json.len() < MIN_COMPRESS_SIZE {
+        ContentEncoding::Identity
+    } else {
+        ContentEncoding::negotiate(accept_encoding)
+    };
+    let body = encoding.encode(&json)?;
+
+    Ok(CachedResponse::Body(CachedBody {
+        etag,
+        body,
+        encoding,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_negotiate_encoding() {
+        assert_eq!(
+            ContentEncoding::negotiate(Some("gzip, deflate, br")),
+            ContentEncoding::Brotli
+        );
+        assert_eq!(
+            ContentEncoding::negotiate(Some("gzip;q=1.0, br;q=0")),
+            ContentEncoding::Gzip
+        );
+        assert_eq!(
+            ContentEncoding::negotiate(Some("identity")),
+            ContentEncoding::Identity
+        );
+        assert_eq!(ContentEncoding::negotiate(None), ContentEncoding::Identity);
+
+        // Явный запрет важнее `*`, порядок записей не важен
+        assert_eq!(
+            ContentEncoding::negotiate(Some("*, br;q=0")),
+            ContentEncoding::Gzip
+        );
+        assert_eq!(
+            ContentEncoding::negotiate(Some("br;q=0, gzip;q=0, *")),
+            ContentEncoding::Identity
+        );
+        assert_eq!(
+            ContentEncoding::negotiate(Some("*;q=0, gzip")),
+            ContentEncoding::Gzip
+        );
+        assert_eq!(
+            ContentEncoding::negotiate(Some("*")),
+            ContentEncoding::Brotli
+        );
+        assert_eq!(
+            ContentEncoding::negotiate(Some("br;q=0.5, gzip;q=0.8")),
+            ContentEncoding::Gzip
+        );
+    }
+
+    #[test]
+    fn test_etag_not_modified() {
+        let value = serde_json::json!({ "types": ["Массив", "Структура"] });
+        let etag = match prepare_json(&value, None, None).unwrap() {
+            CachedResponse::Body(body) => body.etag,
+            CachedResponse::NotModified { .. } => panic!("ожидалось тело ответа"),
+        };
+        assert!(etag.starts_with("W/\""));
+
+        assert!(matches!(
+            prepare_json(&value, Some(&etag), Some("gzip")).unwrap(),
+            CachedResponse::NotModified { .. }
+        ));
+        assert!(matches!(
+            prepare_json(&value, Some(etag.trim_start_matches("W/")), None).unwrap(),
+            CachedResponse::NotModified { .. }
+        ));
+        assert!(matches!(
+            prepare_json(&value, Some("\"other\""), None).unwrap(),
+            CachedResponse::Body(_)
+        ));
+    }
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        let value: Vec<String> = (0..500).map(|i| format!("Тип{}", i)).collect();
+        let body = match prepare_json(&value, None, Some("gzip")).unwrap() {
+            CachedResponse::Body(body) => body,
+            CachedResponse::NotModified { .. } => panic!("ожидалось тело ответа"),
+        };
+        assert_eq!(body.encoding, ContentEncoding::Gzip);
+
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(body.body.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, serde_json::to_string(&value).unwrap());
+    }
+}
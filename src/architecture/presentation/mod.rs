@@ -3,15 +3,74 @@
 //! Слой представления обеспечивает адаптацию между специализированными сервисами
 //! и конкретными потребителями (LSP протокол, HTTP API, CLI вывод)
 
+pub mod formatting;
+pub mod http_cache;
+
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::info;
+use tower_lsp::lsp_types::{InlayHint, SignatureHelp, TextDocumentContentChangeEvent, Url};
+use tracing::{info, warn};
 
 use super::domain::TypeCheckerService;
 
-use super::application::SearchFilters;
-use super::application::{AnalysisTypeService, LspTypeService, WebTypeService};
+use super::application::{SearchFilters, TypeListQuery, TypeSortField};
+use super::application::{AnalysisTypeService, LspCompletionKind, LspTypeService, WebTypeService};
+use super::application::{
+    relative_module_path, DiagnosticSeverity, FileAnalysisResult, ProjectAnalysisResult,
+    SubsystemAnalysisSummary, TypeDiagnostic,
+};
+use crate::core::access_rights::{check_access_references, AccessReferenceKind};
+use crate::core::annotation_coverage::AnnotationCoverage;
+use crate::core::bounded_memory::BoundedAnalysisConfig;
+use crate::core::call_hierarchy::{CallableMethod, MethodCalls, ProjectCallGraph};
+use crate::core::command_handlers::{check_command_module, find_command_handler};
+use crate::core::common_module_calls::{module_name_from_path, CommonModuleIndex};
+use crate::core::example_mining::strip_comment;
+use crate::core::form_handlers::{check_form_handlers, FormHandlerIssueKind};
+use crate::core::incremental_diagnostics::{IncrementalDiagnostics, LineAnchored};
+use crate::core::inlay_hints::{
+    call_arguments, documented_parameters, method_parameters, parameter_hints, variable_sites,
+    variable_type_hint, InlayHintsConfig,
+};
+use crate::core::module_structure::method_header_regex;
+use crate::core::semantic_tokens::{self, classify_resolution, SemanticToken};
+use crate::core::signature_help::{call_site, export_signature_help, method_signature_help};
+use crate::core::type_hints::{TypeHintsProvider, TypeHintsSettings};
+use crate::core::type_quick_fixes::{assignment_mismatch_message, type_annotation};
+use crate::core::type_references::type_name_references;
+#[cfg(feature = "docs-search")]
+use crate::core::workspace_symbols::{
+    documentation_symbol, repository_documentation, REPOSITORY_PROVIDER_ID,
+};
+use crate::core::workspace_symbols::{
+    module_procedures, rank_symbols, symbol_score, ProjectSymbolIndex, WorkspaceSymbol,
+    DEFAULT_WORKSPACE_SYMBOL_LIMIT,
+};
+use crate::core::ownership::{OwnerSummary, OwnershipMap};
+use crate::core::quality_gates::{
+    self, CoverageBaseline, DynamicBaseline, GateInput, GateReport, QualityGatesConfig,
+};
+use crate::core::rename::{self, RenameTarget};
+use crate::data::WriteGuard;
+use crate::data::loaders::commands::CommandIndex;
+use crate::data::loaders::forms::FormDescription;
+use crate::data::loaders::roles::RoleIndex;
+use crate::data::loaders::subsystems::SubsystemIndex;
+#[cfg(feature = "docs-search")]
+use crate::documentation::core::providers::DocumentationProvider;
+use crate::documentation::overlay::TeamNotesOverlay;
+#[cfg(feature = "docs-search")]
+use crate::documentation::search::DocumentationSearchEngine;
+use crate::documentation::search::IndexUpdateSummary;
+use crate::documentation::PlatformDocumentationProvider;
+use crate::domain::types::{ConcreteType, ResolutionResult, TypeResolution};
+use crate::parsing::bsl::language_features::{
+    await_outside_async, unsupported_syntax, LanguageFeatures,
+};
+use crate::parsing::bsl::tree_sitter_adapter::TreeSitterAdapter;
+use crate::unified::data::RawTypeData;
 
 // === LSP INTERFACE ===
 
@@ -20,6 +79,30 @@ use super::application::{AnalysisTypeService, LspTypeService, WebTypeService};
 /// Адаптирует LspTypeService к LSP протоколу
 pub struct LspInterface {
     lsp_service: Arc<LspTypeService>,
+    /// Роли конфигурации для проверки `РольДоступна("Имя")`
+    roles: tokio::sync::RwLock<Arc<RoleIndex>>,
+    /// Команды конфигурации: переход от `Метаданные.ОбщиеКоманды.Имя` к
+    /// обработчику
+    commands: tokio::sync::RwLock<Arc<CommandIndex>>,
+    /// Экспортные методы общих модулей проекта (переход к определению)
+    common_modules: tokio::sync::RwLock<Arc<CommonModuleIndex>>,
+    /// Конструкции языка целевой версии платформы (`platform_version`)
+    language_features: tokio::sync::RwLock<LanguageFeatures>,
+    /// Открытые документы и диагностики их методов (повторный анализ только
+    /// изменённых методов)
+    document_diagnostics: tokio::sync::Mutex<IncrementalDiagnostics<LspDiagnostic>>,
+    /// Справка синтакс-помощника: параметры методов для подсказки сигнатур
+    platform_documentation: tokio::sync::RwLock<Option<Arc<PlatformDocumentationProvider>>>,
+    /// Поиск по именам объектов конфигурации и типов платформы (символы
+    /// рабочей области)
+    #[cfg(feature = "docs-search")]
+    documentation_search: Arc<DocumentationSearchEngine>,
+    /// Процедуры и функции модулей проекта (символы рабочей области)
+    project_symbols: tokio::sync::RwLock<Arc<ProjectSymbolIndex>>,
+    /// Граф вызовов процедур и функций проекта (иерархия вызовов)
+    call_graph: tokio::sync::RwLock<Arc<ProjectCallGraph>>,
+    /// Заметки команды к типам: добавляются к подсказке при наведении
+    team_notes: tokio::sync::RwLock<Arc<TeamNotesOverlay>>,
 }
 
 /// LSP запрос автодополнения
@@ -30,6 +113,12 @@ pub struct LspCompletionRequest {
     pub column: u32,
     pub prefix: String,
     pub trigger_character: Option<String>,
+    /// Тип, ожидаемый в позиции (правая часть присваивания, аргумент вызова)
+    #[serde(default)]
+    pub expected_type: Option<TypeResolution>,
+    /// Текст документа — для локальных символов и членов модуля
+    #[serde(default)]
+    pub document_text: Option<String>,
 }
 
 /// LSP ответ автодополнения
@@ -39,6 +128,9 @@ pub struct LspCompletionResponse {
     pub is_incomplete: bool,
 }
 
+/// LSP InsertTextFormat.Snippet
+pub const SNIPPET_INSERT_TEXT_FORMAT: u8 = 2;
+
 /// Элемент автодополнения для LSP
 #[derive(Debug, Clone, Serialize)]
 pub struct LspCompletionItem {
@@ -52,6 +144,9 @@ pub struct LspCompletionItem {
     pub filter_text: Option<String>,
     #[serde(rename = "sortText")]
     pub sort_text: Option<String>,
+    /// `2` — `insert_text` является сниппетом LSP с позициями табуляции
+    #[serde(rename = "insertTextFormat", skip_serializing_if = "Option::is_none")]
+    pub insert_text_format: Option<u8>,
 }
 
 /// LSP запрос hover
@@ -61,6 +156,9 @@ pub struct LspHoverRequest {
     pub line: u32,
     pub column: u32,
     pub expression: String,
+    /// Текст документа: типы уточняются проверками `ТипЗнч` в позиции
+    #[serde(default)]
+    pub document: Option<String>,
 }
 
 /// LSP ответ hover
@@ -68,17 +166,125 @@ pub struct LspHoverRequest {
 pub struct LspHoverResponse {
     pub contents: Vec<String>,
     pub range: Option<LspRange>,
+    /// Файл описания объекта метаданных (XML/MDO) для перехода из hover
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definition_path: Option<String>,
 }
 
-/// LSP диапазон в файле
+/// LSP запрос перехода к определению
+#[derive(Debug, Clone, Deserialize)]
+pub struct LspDefinitionRequest {
+    pub file_path: String,
+    pub line: u32,
+    pub column: u32,
+    /// Выражение под курсором (`Справочники.Номенклатура`, `Модуль.Метод`)
+    pub expression: String,
+    /// Текст документа: в нём ищутся объявления локальных методов
+    #[serde(default)]
+    pub document: Option<String>,
+}
+
+/// Место определения: файл и диапазон имени
+#[derive(Debug, Clone, Serialize)]
+pub struct LspLocation {
+    pub file_path: String,
+    pub range: LspRange,
+}
+
+/// LSP запрос переименования символа под курсором
+#[derive(Debug, Clone, Deserialize)]
+pub struct LspRenameRequest {
+    pub file_path: String,
+    /// Текст документа
+    pub document: String,
+    pub line: u32,
+    pub column: u32,
+    pub new_name: String,
+    /// Корень проекта: в его модулях ищутся вызовы `Модуль.Метод`
+    /// переименовываемого экспортного метода
+    #[serde(default)]
+    pub workspace_root: Option<std::path::PathBuf>,
+    /// Открытые документы (путь, текст): их текст новее файлов на диске
+    #[serde(default)]
+    pub open_documents: Vec<(String, String)>,
+}
+
+/// Замена текста в файле
+#[derive(Debug, Clone, Serialize)]
+pub struct LspTextEdit {
+    pub file_path: String,
+    pub range: LspRange,
+    pub new_text: String,
+}
+
+/// LSP запрос семантических токенов документа или диапазона
+#[derive(Debug, Clone, Deserialize)]
+pub struct LspSemanticTokensRequest {
+    pub file_path: String,
+    pub text: String,
+    /// Диапазон строк; `None` — весь документ
+    #[serde(default)]
+    pub range: Option<LspRange>,
+}
+
+/// Семантические токены в формате LSP (по пять чисел на токен)
 #[derive(Debug, Clone, Serialize)]
+pub struct LspSemanticTokensResponse {
+    pub data: Vec<u32>,
+}
+
+/// LSP запрос подсказки сигнатуры вызова под курсором
+#[derive(Debug, Clone, Deserialize)]
+pub struct LspSignatureHelpRequest {
+    pub file_path: String,
+    /// Текст документа: в нём ищется незакрытый вызов до курсора
+    pub document: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// LSP запрос inlay hints для строк документа
+#[derive(Debug, Clone, Deserialize)]
+pub struct LspInlayHintsRequest {
+    pub file_path: String,
+    pub document: String,
+    /// Строки видимой области (включительно)
+    pub start_line: u32,
+    pub end_line: u32,
+    #[serde(default)]
+    pub config: InlayHintsConfig,
+}
+
+/// LSP запрос символов рабочей области (`workspace/symbol`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct LspWorkspaceSymbolRequest {
+    pub query: String,
+    /// Открытые документы (путь, текст): их текст новее файлов на диске
+    #[serde(default)]
+    pub open_documents: Vec<(String, String)>,
+}
+
+/// LSP запрос иерархии вызовов: метод в позиции (имя в заголовке или
+/// вызов), его вызывающие или вызываемые методы
+#[derive(Debug, Clone, Deserialize)]
+pub struct LspCallHierarchyRequest {
+    pub file_path: String,
+    pub line: u32,
+    pub column: u32,
+    /// Открытые документы (путь, текст): их текст новее файлов на диске
+    #[serde(default)]
+    pub open_documents: Vec<(String, String)>,
+}
+
+/// LSP диапазон в файле
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LspRange {
     pub start: LspPosition,
     pub end: LspPosition,
 }
 
 /// LSP позиция в файле
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LspPosition {
     pub line: u32,
     pub character: u32,
@@ -93,10 +299,210 @@ pub struct LspDiagnostic {
     pub message: String,
 }
 
+impl LineAnchored for LspDiagnostic {
+    fn shift_lines(&mut self, delta: i64) {
+        let shift = |line: u32| (line as i64 + delta).max(0) as u32;
+        self.range.start.line = shift(self.range.start.line);
+        self.range.end.line = shift(self.range.end.line);
+    }
+}
+
 impl LspInterface {
     /// Создать новый LSP интерфейс
     pub fn new(lsp_service: Arc<LspTypeService>) -> Self {
-        Self { lsp_service }
+        Self {
+            lsp_service,
+            roles: tokio::sync::RwLock::new(Arc::new(RoleIndex::default())),
+            commands: tokio::sync::RwLock::new(Arc::new(CommandIndex::default())),
+            common_modules: tokio::sync::RwLock::new(Arc::new(CommonModuleIndex::default())),
+            language_features: tokio::sync::RwLock::new(LanguageFeatures::latest()),
+            document_diagnostics: tokio::sync::Mutex::new(IncrementalDiagnostics::new()),
+            platform_documentation: tokio::sync::RwLock::new(None),
+            #[cfg(feature = "docs-search")]
+            documentation_search: Arc::new(DocumentationSearchEngine::new()),
+            project_symbols: tokio::sync::RwLock::new(Arc::new(ProjectSymbolIndex::new())),
+            call_graph: tokio::sync::RwLock::new(Arc::new(ProjectCallGraph::new())),
+            team_notes: tokio::sync::RwLock::new(Arc::new(TeamNotesOverlay::default())),
+        }
+    }
+
+    /// Целевая версия платформы: конструкции новых версий помечаются ошибкой
+    pub fn with_language_features(mut self, features: LanguageFeatures) -> Self {
+        self.language_features = tokio::sync::RwLock::new(features);
+        self
+    }
+
+    /// Заменить целевую версию платформы (изменились настройки)
+    pub async fn set_language_features(&self, features: LanguageFeatures) {
+        *self.language_features.write().await = features;
+    }
+
+    /// Заменить индекс ролей конфигурации
+    pub async fn set_role_index(&self, roles: RoleIndex) {
+        *self.roles.write().await = Arc::new(roles);
+    }
+
+    /// Заменить индекс команд конфигурации
+    pub async fn set_command_index(&self, commands: CommandIndex) {
+        *self.commands.write().await = Arc::new(commands);
+    }
+
+    /// Заменить заметки команды к типам
+    pub async fn set_team_notes(&self, notes: TeamNotesOverlay) {
+        *self.team_notes.write().await = Arc::new(notes);
+    }
+
+    /// Заменить индекс общих модулей проекта
+    pub async fn set_common_module_index(&self, index: CommonModuleIndex) {
+        *self.common_modules.write().await = Arc::new(index);
+    }
+
+    /// Подключить справку синтакс-помощника (загружается в фоне); её типы
+    /// индексируются для поиска символов рабочей области
+    pub async fn set_platform_documentation(&self, provider: Arc<PlatformDocumentationProvider>) {
+        #[cfg(feature = "docs-search")]
+        match provider.get_all_types().await {
+            Ok(types) => {
+                self.documentation_search
+                    .update_provider_indexes(provider.provider_id(), &types)
+                    .await;
+            }
+            Err(e) => warn!("Типы справки не проиндексированы: {}", e),
+        }
+        self.lsp_service
+            .set_global_availability(provider.global_availability().await)
+            .await;
+        *self.platform_documentation.write().await = Some(provider);
+    }
+
+    /// Проиндексировать объекты конфигурации единого репозитория для поиска
+    /// символов рабочей области (без фичи `docs-search` индекса нет)
+    #[cfg(feature = "docs-search")]
+    pub async fn index_repository_types(&self, types: &[RawTypeData]) -> IndexUpdateSummary {
+        let documents: Vec<_> = types.iter().filter_map(repository_documentation).collect();
+        self.documentation_search
+            .update_provider_indexes(REPOSITORY_PROVIDER_ID, &documents)
+            .await
+    }
+
+    #[cfg(not(feature = "docs-search"))]
+    pub async fn index_repository_types(&self, _types: &[RawTypeData]) -> IndexUpdateSummary {
+        IndexUpdateSummary::default()
+    }
+
+    /// Заменить индекс процедур и функций проекта
+    pub async fn set_project_symbol_index(&self, index: ProjectSymbolIndex) {
+        *self.project_symbols.write().await = Arc::new(index);
+    }
+
+    /// Заменить граф вызовов проекта
+    pub async fn set_call_graph(&self, graph: ProjectCallGraph) {
+        *self.call_graph.write().await = Arc::new(graph);
+    }
+
+    /// Обновить модуль проекта в символах рабочей области и графе вызовов:
+    /// `text` — новый текст, `None` — файл удалён
+    pub async fn update_project_module(&self, file_path: &str, text: Option<&str>) {
+        let mut symbols = self.project_symbols.write().await;
+        let mut graph = self.call_graph.write().await;
+        match text {
+            Some(text) => {
+                Arc::make_mut(&mut symbols).update_file(file_path, text);
+                let module = module_name_from_path(std::path::Path::new(file_path));
+                Arc::make_mut(&mut graph).add_module(file_path, module.as_deref(), text);
+            }
+            None => {
+                Arc::make_mut(&mut symbols).remove_file(file_path);
+                Arc::make_mut(&mut graph).remove_module(file_path);
+            }
+        }
+    }
+
+    /// Граф вызовов с модулями открытых документов, перестроенными по их
+    /// текущему тексту
+    async fn current_call_graph(
+        &self,
+        open_documents: &[(String, String)],
+    ) -> Arc<ProjectCallGraph> {
+        let mut graph = self.call_graph.write().await;
+        for (path, text) in open_documents {
+            let module = module_name_from_path(std::path::Path::new(path));
+            Arc::make_mut(&mut graph).add_module(path, module.as_deref(), text);
+        }
+        graph.clone()
+    }
+
+    /// Обработать запрос подготовки иерархии вызовов: метод, объявленный или
+    /// вызванный в позиции
+    pub async fn handle_prepare_call_hierarchy(
+        &self,
+        request: LspCallHierarchyRequest,
+    ) -> Result<Option<CallableMethod>> {
+        let graph = self.current_call_graph(&request.open_documents).await;
+        Ok(graph.method_at(&request.file_path, request.line, request.column).cloned())
+    }
+
+    /// Обработать запрос входящих вызовов: методы, вызывающие метод в позиции
+    pub async fn handle_incoming_calls(
+        &self,
+        request: LspCallHierarchyRequest,
+    ) -> Result<Vec<MethodCalls>> {
+        let graph = self.current_call_graph(&request.open_documents).await;
+        Ok(graph
+            .method_at(&request.file_path, request.line, request.column)
+            .map(|method| graph.incoming_calls(method))
+            .unwrap_or_default())
+    }
+
+    /// Обработать запрос исходящих вызовов: методы, которые вызывает метод в
+    /// позиции
+    pub async fn handle_outgoing_calls(
+        &self,
+        request: LspCallHierarchyRequest,
+    ) -> Result<Vec<MethodCalls>> {
+        let graph = self.current_call_graph(&request.open_documents).await;
+        Ok(graph
+            .method_at(&request.file_path, request.line, request.column)
+            .map(|method| graph.outgoing_calls(method))
+            .unwrap_or_default())
+    }
+
+    /// Обработать запрос символов рабочей области: процедуры и функции
+    /// проекта, объекты конфигурации и типы платформы, лучшие первыми
+    pub async fn handle_workspace_symbol_request(
+        &self,
+        request: LspWorkspaceSymbolRequest,
+    ) -> Result<Vec<WorkspaceSymbol>> {
+        let query = request.query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let limit = DEFAULT_WORKSPACE_SYMBOL_LIMIT;
+        let index = self.project_symbols.read().await.clone();
+        let open_files: Vec<&str> = request
+            .open_documents
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .collect();
+
+        let mut scored: Vec<(u32, WorkspaceSymbol)> = index
+            .symbols(&open_files)
+            .filter_map(|symbol| Some((symbol_score(query, symbol)?, symbol.clone())))
+            .collect();
+        for (path, text) in &request.open_documents {
+            let container = index.container(path);
+            scored.extend(
+                module_procedures(path, Some(&container), text)
+                    .into_iter()
+                    .filter_map(|symbol| Some((symbol_score(query, &symbol)?, symbol))),
+            );
+        }
+        #[cfg(feature = "docs-search")]
+        {
+            let found = self.documentation_search.search_by_name(query, limit).await;
+            scored.extend(found.iter().filter_map(documentation_symbol));
+        }
+        Ok(rank_symbols(scored, limit))
     }
 
     /// Обработать запрос автодополнения
@@ -112,11 +518,13 @@ impl LspInterface {
         // Получаем автодополнение от LSP сервиса
         let lsp_completions = self
             .lsp_service
-            .get_completions_fast(
+            .get_completions_in_context(
                 &request.prefix,
                 &request.file_path,
                 request.line,
                 request.column,
+                request.document_text.as_deref(),
+                request.expected_type.as_ref(),
             )
             .await;
 
@@ -124,6 +532,8 @@ impl LspInterface {
         let lsp_items = lsp_completions
             .into_iter()
             .map(|comp| LspCompletionItem {
+                insert_text_format: (comp.kind == LspCompletionKind::Snippet)
+                    .then_some(SNIPPET_INSERT_TEXT_FORMAT),
                 label: comp.label.clone(),
                 kind: comp.kind as u8,
                 detail: comp.detail,
@@ -153,11 +563,16 @@ impl LspInterface {
                 &request.file_path,
                 request.line,
                 request.column,
+                request.document.as_deref(),
             )
             .await
         {
+            let mut contents = vec![hover_info.content, hover_info.type_info];
+            if let Some(type_name) = &hover_info.type_name {
+                contents.extend(self.team_notes.read().await.hover_markdown(type_name));
+            }
             Ok(Some(LspHoverResponse {
-                contents: vec![hover_info.content, hover_info.type_info],
+                contents,
                 range: Some(LspRange {
                     start: LspPosition {
                         line: request.line,
@@ -168,12 +583,413 @@ impl LspInterface {
                         character: request.column + request.expression.len() as u32,
                     },
                 }),
+                definition_path: hover_info.definition_path,
             }))
         } else {
             Ok(None)
         }
     }
 
+    /// Обработать запрос подсказки сигнатуры: `Модуль.Метод(` — экспортный
+    /// метод общего модуля, `Получатель.Метод(` — метод платформенного типа
+    /// получателя из справки синтакс-помощника
+    pub async fn handle_signature_help_request(
+        &self,
+        request: LspSignatureHelpRequest,
+    ) -> Result<Option<SignatureHelp>> {
+        let Some(site) = call_site(&request.document, request.line, request.column) else {
+            return Ok(None);
+        };
+        let Some(receiver) = site.receiver.as_deref() else {
+            return Ok(None);
+        };
+
+        let index = self.common_modules.read().await.clone();
+        if let Some(export) = index.find_export(receiver, &site.method) {
+            return Ok(Some(export_signature_help(export, site.argument_index)));
+        }
+
+        let Some(provider) = self.platform_documentation.read().await.clone() else {
+            return Ok(None);
+        };
+        let resolution = self
+            .lsp_service
+            .resolve_in_document(
+                &request.file_path,
+                &request.document,
+                request.line,
+                request.column,
+                receiver,
+            )
+            .await;
+        let Some(type_name) = resolution.get_name() else {
+            return Ok(None);
+        };
+        Ok(provider
+            .find_method(&type_name, &site.method)
+            .await
+            .map(|method| method_signature_help(&type_name, &method, site.argument_index)))
+    }
+
+    /// Обработать запрос inlay hints: выведенный тип после объявления
+    /// переменной и имена параметров перед аргументами вызовов методов
+    /// документа, общих модулей и типов платформы
+    pub async fn handle_inlay_hints_request(
+        &self,
+        request: LspInlayHintsRequest,
+    ) -> Result<Vec<InlayHint>> {
+        let visible = request.start_line..=request.end_line;
+        let mut hints = Vec::new();
+
+        if request.config.variable_types {
+            let formatter = TypeHintsProvider::new(TypeHintsSettings {
+                min_certainty: request.config.min_certainty,
+                ..Default::default()
+            });
+            for site in variable_sites(&request.document) {
+                if !visible.contains(&site.line) {
+                    continue;
+                }
+                let resolution = self
+                    .lsp_service
+                    .resolve_in_document(
+                        &request.file_path,
+                        &request.document,
+                        site.line,
+                        site.column,
+                        &site.name,
+                    )
+                    .await;
+                hints.extend(variable_type_hint(&site, &resolution, &formatter));
+            }
+        }
+
+        if request.config.parameter_names {
+            let local = method_parameters(&request.document);
+            let index = self.common_modules.read().await.clone();
+            let provider = self.platform_documentation.read().await.clone();
+            for call in call_arguments(&request.document) {
+                if !call.arguments.iter().any(|argument| visible.contains(&argument.line)) {
+                    continue;
+                }
+                let parameters = match call.receiver.as_deref() {
+                    None => local
+                        .get(&call.method.to_lowercase())
+                        .cloned()
+                        .unwrap_or_default(),
+                    Some(receiver) => match index.find_export(receiver, &call.method) {
+                        Some(export) => export.params.clone(),
+                        None => {
+                            let Some(provider) = &provider else {
+                                continue;
+                            };
+                            let first = &call.arguments[0];
+                            let resolution = self
+                                .lsp_service
+                                .resolve_in_document(
+                                    &request.file_path,
+                                    &request.document,
+                                    first.line,
+                                    first.column,
+                                    receiver,
+                                )
+                                .await;
+                            let Some(type_name) = resolution.get_name() else {
+                                continue;
+                            };
+                            match provider.find_method(&type_name, &call.method).await {
+                                Some(method) => {
+                                    documented_parameters(&method, call.arguments.len())
+                                }
+                                None => continue,
+                            }
+                        }
+                    },
+                };
+                hints.extend(
+                    parameter_hints(&call, &parameters)
+                        .into_iter()
+                        .filter(|hint| visible.contains(&hint.position.line)),
+                );
+            }
+        }
+
+        hints.sort_by_key(|hint| (hint.position.line, hint.position.character));
+        Ok(hints)
+    }
+
+    /// Обработать запрос перехода к определению: `Модуль.Метод` — экспортный
+    /// метод общего модуля, `Метод` — объявление в текущем документе,
+    /// команда (`Метаданные.ОбщиеКоманды.Печать`) — её обработчик, объект
+    /// конфигурации (`Справочники.Номенклатура`) — его файл описания
+    pub async fn handle_definition_request(
+        &self,
+        request: LspDefinitionRequest,
+    ) -> Result<Option<LspLocation>> {
+        let expression = request.expression.trim().trim_end_matches('(');
+        if expression.is_empty() {
+            return Ok(None);
+        }
+
+        let commands = self.commands.read().await.clone();
+        if let Some(module) = commands
+            .find_reference(expression)
+            .and_then(|command| command.module_path.as_ref())
+        {
+            let range = std::fs::read_to_string(module)
+                .ok()
+                .and_then(|text| find_command_handler(&text))
+                .map_or_else(
+                    || name_range(0, 0, ""),
+                    |handler| name_range(handler.line, handler.column, &handler.name),
+                );
+            return Ok(Some(LspLocation {
+                file_path: module.to_string_lossy().to_string(),
+                range,
+            }));
+        }
+
+        match expression.rsplit_once('.') {
+            Some((module, method)) => {
+                let index = self.common_modules.read().await.clone();
+                let location = index.find_export(module, method).and_then(|export| {
+                    let path = index.module(module)?.path.as_ref()?;
+                    Some(LspLocation {
+                        file_path: path.to_string_lossy().to_string(),
+                        range: name_range(export.line, export.column, &export.name),
+                    })
+                });
+                if location.is_some() {
+                    return Ok(location);
+                }
+            }
+            None => {
+                let declaration = request
+                    .document
+                    .as_deref()
+                    .and_then(|text| method_declaration(text, expression));
+                if let Some(range) = declaration {
+                    return Ok(Some(LspLocation {
+                        file_path: request.file_path,
+                        range,
+                    }));
+                }
+            }
+        }
+
+        let resolution = match request.document.as_deref() {
+            Some(text) => {
+                self.lsp_service
+                    .resolve_in_document(
+                        &request.file_path,
+                        text,
+                        request.line,
+                        request.column,
+                        expression,
+                    )
+                    .await
+            }
+            None => {
+                self.lsp_service
+                    .resolve_at_position(
+                        &request.file_path,
+                        request.line,
+                        request.column,
+                        expression,
+                    )
+                    .await
+            }
+        };
+        Ok(resolution.definition_path().map(|path| LspLocation {
+            file_path: path.to_string(),
+            range: name_range(0, 0, ""),
+        }))
+    }
+
+    /// Обработать запрос семантических токенов: роли идентификаторов берутся
+    /// из дерева tree-sitter, категория остальных имён (глобальная функция,
+    /// тип платформы, объект конфигурации, переменная) — из разрешения типа
+    pub async fn handle_semantic_tokens_request(
+        &self,
+        request: LspSemanticTokensRequest,
+    ) -> Result<LspSemanticTokensResponse> {
+        let tree = TreeSitterAdapter::new()?.parse_tree(&request.text)?;
+        let module = semantic_tokens::module_tokens(&tree, &request.text);
+        let in_range = |line: u32, start: u32| {
+            request.range.iter().all(|range| {
+                (line, start) >= (range.start.line, range.start.character)
+                    && (line, start) < (range.end.line, range.end.character)
+            })
+        };
+
+        let mut tokens: Vec<SemanticToken> = module
+            .tokens
+            .into_iter()
+            .filter(|token| in_range(token.line, token.start))
+            .collect();
+        let mut classified = HashMap::new();
+        for name in module.unresolved {
+            if !in_range(name.line, name.start) {
+                continue;
+            }
+            let key = (name.name.to_lowercase(), name.call);
+            if !classified.contains_key(&key) {
+                let resolution = self
+                    .lsp_service
+                    .resolve_at_position(&request.file_path, name.line, name.start, &name.name)
+                    .await;
+                classified.insert(key.clone(), classify_resolution(&resolution, name.call));
+            }
+            if let Some(Some((token_type, modifiers))) = classified.get(&key) {
+                tokens.push(SemanticToken {
+                    line: name.line,
+                    start: name.start,
+                    length: name.length,
+                    token_type: *token_type,
+                    modifiers: *modifiers,
+                });
+            }
+        }
+        tokens.sort_by_key(|token| (token.line, token.start));
+
+        Ok(LspSemanticTokensResponse {
+            data: semantic_tokens::encode(&tokens),
+        })
+    }
+
+    /// Обработать запрос переименования. Переименовываются локальные символы
+    /// метода, переменные и методы модуля; экспортный метод общего модуля —
+    /// и в вызовах `Модуль.Метод` во всех модулях проекта. Недопустимое имя,
+    /// совпадение с глобальным именем платформы или с уже объявленным
+    /// символом — ошибка с описанием причины.
+    pub async fn handle_rename_request(
+        &self,
+        request: LspRenameRequest,
+    ) -> Result<Vec<LspTextEdit>> {
+        let Some(target) = rename::rename_target(&request.document, request.line, request.column)
+        else {
+            anyhow::bail!("Под курсором нет переименуемого символа");
+        };
+        let new_name = request.new_name.trim();
+        rename::validate_identifier(new_name).map_err(anyhow::Error::msg)?;
+
+        // Глобальная функция или свойство глобального контекста из репозитория
+        let resolution = self
+            .lsp_service
+            .resolve_at_position(&request.file_path, request.line, request.column, new_name)
+            .await;
+        let global = matches!(
+            resolution.result,
+            ResolutionResult::Concrete(ConcreteType::GlobalFunction(_) | ConcreteType::Platform(_))
+        );
+        if global {
+            anyhow::bail!("«{}» совпадает с глобальным именем платформы", new_name);
+        }
+
+        // Экспортный метод общего модуля: (модуль, метод)
+        let index = self.common_modules.read().await.clone();
+        let shared = match &target {
+            RenameTarget::Method {
+                name,
+                exported: true,
+            } => module_name_from_path(std::path::Path::new(&request.file_path))
+                .map(|module| (module, name.clone())),
+            RenameTarget::ModuleMethod { module, name } => {
+                if index.find_export(module, name).is_none() {
+                    anyhow::bail!("Экспортный метод {}.{} не найден в общих модулях", module, name);
+                }
+                Some((module.clone(), name.clone()))
+            }
+            _ => None,
+        };
+        if rename::conflicts(&request.document, &target, new_name) {
+            anyhow::bail!("Имя «{}» уже используется", new_name);
+        }
+        if let Some((module, name)) = &shared {
+            let taken = index
+                .find_export(module, new_name)
+                .is_some_and(|export| export.name.to_lowercase() != name.to_lowercase());
+            if taken {
+                anyhow::bail!("В модуле {} уже есть метод {}", module, new_name);
+            }
+        }
+
+        let old_name = target.name().to_string();
+        let mut edits = Vec::new();
+        let mut push = |file_path: &str, text: &str, target: &RenameTarget| {
+            for occurrence in rename::occurrences(text, target) {
+                edits.push(LspTextEdit {
+                    file_path: file_path.to_string(),
+                    range: name_range(occurrence.line, occurrence.column, &old_name),
+                    new_text: new_name.to_string(),
+                });
+            }
+        };
+        let Some((module, name)) = shared else {
+            push(&request.file_path, &request.document, &target);
+            return Ok(edits);
+        };
+
+        let qualified = RenameTarget::ModuleMethod {
+            module: module.clone(),
+            name: name.clone(),
+        };
+        let declaration = RenameTarget::Method {
+            name,
+            exported: true,
+        };
+        let canonical = |path: &std::path::Path| {
+            std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+        };
+        let current = canonical(std::path::Path::new(&request.file_path));
+        let open: HashMap<std::path::PathBuf, &str> = request
+            .open_documents
+            .iter()
+            .map(|(path, text)| (canonical(std::path::Path::new(path)), text.as_str()))
+            .collect();
+
+        // Модули проекта и файл объявления (он может лежать вне корня)
+        let mut files = match &request.workspace_root {
+            Some(root) => crate::core::parallel_analysis::ParallelAnalyzer::find_bsl_files(root)?,
+            None => Vec::new(),
+        };
+        files.extend(index.module(&module).and_then(|info| info.path.clone()));
+        let mut seen = std::collections::HashSet::new();
+        for file in files {
+            let path = canonical(&file);
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            let (file_path, text) = if path == current {
+                (request.file_path.clone(), request.document.clone())
+            } else if let Some(text) = open.get(&path) {
+                (file.to_string_lossy().to_string(), text.to_string())
+            } else {
+                match crate::core::fs_utils::read_bsl_file(&file) {
+                    Ok(text) => (file.to_string_lossy().to_string(), text),
+                    Err(e) => {
+                        warn!("Модуль {} не прочитан при переименовании: {}", file.display(), e);
+                        continue;
+                    }
+                }
+            };
+            let declaring = module_name_from_path(&file)
+                .is_some_and(|name| name.to_lowercase() == module.to_lowercase());
+            if declaring {
+                push(&file_path, &text, &declaration);
+            }
+            push(&file_path, &text, &qualified);
+        }
+        // Текущий документ вне корня проекта
+        if !seen.contains(&current) {
+            if matches!(target, RenameTarget::Method { .. }) {
+                push(&request.file_path, &request.document, &declaration);
+            }
+            push(&request.file_path, &request.document, &qualified);
+        }
+        Ok(edits)
+    }
+
     /// Получить метрики производительности LSP
     pub async fn get_performance_metrics(&self) -> Result<LspPerformanceMetrics> {
         let metrics = self.lsp_service.get_performance_metrics().await;
@@ -193,14 +1009,98 @@ impl LspInterface {
         file_path: &str,
         text: &str,
     ) -> Result<Vec<LspDiagnostic>> {
+        Ok(self.analyze_lines(file_path, text, 0).await)
+    }
+
+    /// Открыть документ: его диагностики будут пересчитываться по методам
+    pub async fn open_document(&self, file_path: &str, text: &str) {
+        self.document_diagnostics
+            .lock()
+            .await
+            .open(file_path, text.to_string());
+    }
+
+    /// Применить правки `didChange` к открытому документу (текст и дерево
+    /// tree-sitter обновляются инкрементально); `false` — документ не открыт
+    pub async fn change_document(
+        &self,
+        file_path: &str,
+        changes: &[TextDocumentContentChangeEvent],
+    ) -> bool {
+        self.document_diagnostics
+            .lock()
+            .await
+            .change(file_path, changes)
+    }
+
+    /// Закрыть документ
+    pub async fn close_document(&self, file_path: &str) {
+        self.document_diagnostics.lock().await.close(file_path);
+    }
+
+    /// Забыть диагностики открытых документов (изменились настройки или типы)
+    pub async fn invalidate_document_diagnostics(&self) {
+        self.document_diagnostics.lock().await.invalidate_all();
+    }
+
+    /// Забыть диагностики открытых документов модулей `files` (пути файлов)
+    pub async fn invalidate_module_diagnostics(&self, files: &std::collections::BTreeSet<String>) {
+        let mut documents = self.document_diagnostics.lock().await;
+        for uri in documents.uris() {
+            // Документ открыт по URI `file://`, модули известны по путям
+            let path = Url::parse(&uri)
+                .ok()
+                .and_then(|url| url.to_file_path().ok())
+                .map(|path| path.to_string_lossy().to_string());
+            if files.contains(path.as_deref().unwrap_or(&uri)) {
+                documents.invalidate(&uri);
+            }
+        }
+    }
+
+    /// Диагностики открытого документа: заново анализируются только методы,
+    /// изменённые с прошлого вызова, остальные берутся из кеша
+    pub async fn document_diagnostics(&self, file_path: &str) -> Vec<LspDiagnostic> {
+        let pending = self.document_diagnostics.lock().await.pending(file_path);
+        for work in &pending {
+            let diagnostics = self.analyze_lines(file_path, &work.text, work.start_line).await;
+            self.document_diagnostics
+                .lock()
+                .await
+                .store(file_path, work, diagnostics);
+        }
+        let (mut out, text) = {
+            let documents = self.document_diagnostics.lock().await;
+            let text = documents.text(file_path).map(str::to_string);
+            (documents.diagnostics(file_path), text)
+        };
+        if let Some(text) = text {
+            out.extend(form_handler_diagnostics(file_path, &text));
+            out.extend(command_module_diagnostics(file_path, &text));
+            let index = self.common_modules.read().await.clone();
+            out.extend(common_module_call_diagnostics(&index, file_path, &text));
+        }
+        out
+    }
+
+    /// Диагностики фрагмента модуля, первая строка которого — `first_line`
+    /// строка документа
+    async fn analyze_lines(
+        &self,
+        file_path: &str,
+        text: &str,
+        first_line: u32,
+    ) -> Vec<LspDiagnostic> {
         let mut out = Vec::new();
         let checker = TypeCheckerService::new();
         for (idx, line) in text.lines().enumerate() {
+            let idx = first_line as usize + idx;
             let trimmed = line.trim();
             if trimmed.is_empty() {
                 continue;
             }
-            if trimmed.starts_with("//") {
+            // Строка с аннотацией `// @type` объявляет тип переменной явно
+            if trimmed.starts_with("//") || type_annotation(trimmed).is_some() {
                 continue;
             }
             if let Some(eq_pos) = trimmed.find('=') {
@@ -237,16 +1137,256 @@ impl LspInterface {
                                 character: len_chars,
                             },
                         },
-                        severity: 1,
-                        message: format!("Несовместимые типы в присваивании: rhs -> lhs"),
+                        severity: DiagnosticSeverity::Error.lsp_severity(),
+                        message: assignment_mismatch_message(
+                            lhs.trim(),
+                            &type_name(&rhs_type),
+                            &type_name(&lhs_type),
+                        ),
                     });
                 }
             }
         }
-        Ok(out)
+        // Поиск ссылок нумерует строки фрагмента с нуля
+        let mut references = self.ambiguous_name_diagnostics(text).await;
+        references.extend(self.access_diagnostics(text).await);
+        references.extend(self.language_version_diagnostics(text).await);
+        for mut diagnostic in references {
+            diagnostic.shift_lines(first_line as i64);
+            out.push(diagnostic);
+        }
+        out
+    }
+
+    /// Конструкции, которых нет в целевой версии платформы (`Асинх`/`Ждать`
+    /// до 8.3.18), и `Ждать` вне асинхронного метода: такой модуль не
+    /// скомпилируется
+    async fn language_version_diagnostics(&self, text: &str) -> Vec<LspDiagnostic> {
+        let features = *self.language_features.read().await;
+        let error = |keyword: &str, line: u32, column: u32, message: String| LspDiagnostic {
+            range: LspRange {
+                start: LspPosition {
+                    line,
+                    character: column,
+                },
+                end: LspPosition {
+                    line,
+                    character: column + keyword.chars().count() as u32,
+                },
+            },
+            severity: DiagnosticSeverity::Error.lsp_severity(),
+            message,
+        };
+        let mut out: Vec<LspDiagnostic> = unsupported_syntax(text, &features)
+            .into_iter()
+            .map(|syntax| {
+                let message = syntax.message(&features);
+                error(&syntax.keyword, syntax.line, syntax.column, message)
+            })
+            .collect();
+        out.extend(
+            await_outside_async(text, &features)
+                .into_iter()
+                .map(|found| error(&found.keyword, found.line, found.column, found.message())),
+        );
+        out
+    }
+
+    /// Ссылки на несуществующие права и роли: неизвестная роль — ошибка
+    /// при выполнении, неизвестное право — предупреждение (список прав
+    /// платформы пополняется от версии к версии)
+    async fn access_diagnostics(&self, text: &str) -> Vec<LspDiagnostic> {
+        let roles = self.roles.read().await.clone();
+        check_access_references(text, &roles)
+            .into_iter()
+            .map(|issue| {
+                let reference = &issue.reference;
+                let len_chars = reference.name.chars().count() as u32;
+                LspDiagnostic {
+                    range: LspRange {
+                        start: LspPosition {
+                            line: reference.line,
+                            character: reference.column,
+                        },
+                        end: LspPosition {
+                            line: reference.line,
+                            character: reference.column + len_chars,
+                        },
+                    },
+                    severity: match reference.kind {
+                        AccessReferenceKind::Role => DiagnosticSeverity::Error,
+                        AccessReferenceKind::Right => DiagnosticSeverity::Warning,
+                    }
+                    .lsp_severity(),
+                    message: issue.message(),
+                }
+            })
+            .collect()
+    }
+
+    /// Предупреждения о ссылках на имя, которое есть у типов разных
+    /// источников (например, объект конфигурации с именем типа платформы)
+    async fn ambiguous_name_diagnostics(&self, text: &str) -> Vec<LspDiagnostic> {
+        let mut checked: HashMap<String, Option<String>> = HashMap::new();
+        let mut out = Vec::new();
+        for reference in type_name_references(text) {
+            let key = reference.name.to_lowercase();
+            if !checked.contains_key(&key) {
+                let message = match self.lsp_service.name_candidates(&reference.name).await {
+                    Ok(candidates) if candidates.is_ambiguous() => {
+                        let origins: Vec<&str> =
+                            candidates.origins().iter().map(|o| o.as_str()).collect();
+                        Some(format!(
+                            "Имя '{}' неоднозначно ({}): используется тип из источника {}",
+                            reference.name,
+                            origins.join(", "),
+                            origins[0]
+                        ))
+                    }
+                    Ok(_) => None,
+                    Err(e) => {
+                        warn!("⚠️ Кандидаты для '{}' не получены: {}", reference.name, e);
+                        None
+                    }
+                };
+                checked.insert(key.clone(), message);
+            }
+            if let Some(Some(message)) = checked.get(&key) {
+                let len_chars = reference.name.chars().count() as u32;
+                out.push(LspDiagnostic {
+                    range: LspRange {
+                        start: LspPosition {
+                            line: reference.line,
+                            character: reference.column,
+                        },
+                        end: LspPosition {
+                            line: reference.line,
+                            character: reference.column + len_chars,
+                        },
+                    },
+                    severity: DiagnosticSeverity::Warning.lsp_severity(),
+                    message: message.clone(),
+                });
+            }
+        }
+        out
+    }
+}
+
+/// Имя типа для сообщений диагностик (`Строка`, `Массив`)
+fn type_name(resolution: &TypeResolution) -> String {
+    resolution
+        .get_name()
+        .unwrap_or_else(|| format!("{:?}", resolution.result))
+}
+
+/// Диапазон имени в строке
+fn name_range(line: u32, column: u32, name: &str) -> LspRange {
+    LspRange {
+        start: LspPosition {
+            line,
+            character: column,
+        },
+        end: LspPosition {
+            line,
+            character: column + name.chars().count() as u32,
+        },
     }
 }
 
+/// Диапазон имени в заголовке процедуры или функции `name` (без учёта регистра)
+fn method_declaration(text: &str, name: &str) -> Option<LspRange> {
+    let name = name.to_lowercase();
+    text.lines().enumerate().find_map(|(idx, line)| {
+        let line = strip_comment(line);
+        let caps = method_header_regex().captures(line)?;
+        let found = caps.get(2)?;
+        (found.as_str().to_lowercase() == name).then(|| {
+            let column = line[..found.start()].chars().count() as u32;
+            name_range(idx as u32, column, found.as_str())
+        })
+    })
+}
+
+/// Путь к файлу документа (`file_path` — URI или путь)
+fn document_path(file_path: &str) -> std::path::PathBuf {
+    Url::parse(file_path)
+        .ok()
+        .and_then(|uri| uri.to_file_path().ok())
+        .unwrap_or_else(|| std::path::PathBuf::from(file_path))
+}
+
+/// Обработчики событий формы, модулем которой является документ: описание
+/// формы читается рядом с модулем
+fn form_handler_diagnostics(file_path: &str, text: &str) -> Vec<LspDiagnostic> {
+    let path = document_path(file_path);
+    let form = match FormDescription::load_for_module(&path) {
+        Ok(Some(form)) => form,
+        Ok(None) => return Vec::new(),
+        Err(e) => {
+            warn!("⚠️ Описание формы для {} не прочитано: {:#}", file_path, e);
+            return Vec::new();
+        }
+    };
+    check_form_handlers(&form, text)
+        .into_iter()
+        .map(|issue| {
+            let name = match issue.kind {
+                FormHandlerIssueKind::Missing => "",
+                _ => issue.handler.procedure.as_str(),
+            };
+            let severity = if issue.is_error() {
+                DiagnosticSeverity::Error
+            } else {
+                DiagnosticSeverity::Warning
+            };
+            LspDiagnostic {
+                range: name_range(issue.line, issue.column, name),
+                severity: severity.lsp_severity(),
+                message: issue.message(),
+            }
+        })
+        .collect()
+}
+
+/// Обработчик команды, если документ — модуль команды (`CommandModule.bsl`)
+fn command_module_diagnostics(file_path: &str, text: &str) -> Vec<LspDiagnostic> {
+    if document_path(file_path).file_name() != Some(std::ffi::OsStr::new("CommandModule.bsl")) {
+        return Vec::new();
+    }
+    let handler = find_command_handler(text).map(|handler| handler.name);
+    check_command_module(text)
+        .into_iter()
+        .map(|issue| {
+            let name = handler.as_deref().unwrap_or_default();
+            LspDiagnostic {
+                range: name_range(issue.line, issue.column, name),
+                severity: DiagnosticSeverity::Error.lsp_severity(),
+                message: issue.message(),
+            }
+        })
+        .collect()
+}
+
+/// Вызовы `Модуль.Метод()` общих модулей: неэкспортный метод и модуль,
+/// недоступный в контексте вызывающего метода
+fn common_module_call_diagnostics(
+    index: &CommonModuleIndex,
+    file_path: &str,
+    text: &str,
+) -> Vec<LspDiagnostic> {
+    let current_module = module_name_from_path(&document_path(file_path));
+    index
+        .check_calls(text, current_module.as_deref())
+        .into_iter()
+        .map(|issue| LspDiagnostic {
+            range: name_range(issue.line, issue.column, &issue.method),
+            severity: DiagnosticSeverity::Error.lsp_severity(),
+            message: issue.message(),
+        })
+        .collect()
+}
+
 /// Метрики производительности для LSP
 #[derive(Debug, Clone, Serialize)]
 pub struct LspPerformanceMetrics {
@@ -304,6 +1444,50 @@ pub struct WebSearchResultItem {
     pub tags: Vec<String>,
 }
 
+/// HTTP запрос списка типов: `?page=&per_page=&sort=&order=&fields=`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebTypeListRequest {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    /// "name" | "methods_count" | "properties_count"
+    pub sort: Option<String>,
+    /// "asc" | "desc"
+    pub order: Option<String>,
+    /// Список полей через запятую (например, "name,url"); по умолчанию все поля
+    pub fields: Option<String>,
+}
+
+impl WebTypeListRequest {
+    /// Разобрать параметры сортировки и пагинации (ошибка — некорректный запрос клиента)
+    pub fn to_query(&self) -> Result<TypeListQuery> {
+        let defaults = TypeListQuery::default();
+        Ok(TypeListQuery {
+            page: self.page.unwrap_or(defaults.page),
+            per_page: self.per_page.unwrap_or(defaults.per_page),
+            sort_by: match self.sort.as_deref() {
+                Some(sort) => sort.parse()?,
+                None => TypeSortField::default(),
+            },
+            descending: match self.order.as_deref() {
+                None | Some("asc") => false,
+                Some("desc") => true,
+                Some(other) => anyhow::bail!("Неизвестный порядок сортировки: {}", other),
+            },
+        })
+    }
+}
+
+/// HTTP ответ списка типов
+#[derive(Debug, Clone, Serialize)]
+pub struct WebTypeListResponse {
+    /// Элементы с учётом проекции `fields`
+    pub items: Vec<serde_json::Value>,
+    pub total_count: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub total_pages: usize,
+}
+
 /// HTTP ответ иерархии
 #[derive(Debug, Clone, Serialize)]
 pub struct WebHierarchyResponse {
@@ -422,6 +1606,43 @@ impl WebInterface {
         })
     }
 
+    /// Обработать запрос постраничного списка типов; `query` уже проверен
+    /// ([`WebTypeListRequest::to_query`]), `fields` — проекция полей через запятую
+    pub async fn handle_type_list_request(
+        &self,
+        query: &TypeListQuery,
+        fields: Option<&str>,
+    ) -> Result<WebTypeListResponse> {
+        let fields: Option<Vec<&str>> = fields.map(|f| {
+            f.split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .collect()
+        });
+
+        let page = self.web_service.list_types(query).await?;
+
+        let items = page
+            .items
+            .iter()
+            .map(|item| {
+                let value = serde_json::to_value(item)?;
+                Ok(match &fields {
+                    Some(fields) => project_fields(value, fields),
+                    None => value,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(WebTypeListResponse {
+            items,
+            total_count: page.total_count,
+            page: page.page,
+            per_page: page.per_page,
+            total_pages: page.total_pages,
+        })
+    }
+
     /// Обработать запрос деталей типа
     pub async fn handle_type_details_request(
         &self,
@@ -494,6 +1715,18 @@ impl WebInterface {
     }
 }
 
+/// Оставить в JSON объекте только перечисленные поля (неизвестные поля игнорируются)
+pub fn project_fields(value: serde_json::Value, fields: &[&str]) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .filter(|(key, _)| fields.contains(&key.as_str()))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
 /// Ответ деталей типа для веб
 #[derive(Debug, Clone, Serialize)]
 pub struct WebTypeDetailsResponse {
@@ -539,6 +1772,8 @@ pub struct WebPropertyResponse {
 /// Адаптирует AnalysisTypeService к CLI выводу
 pub struct CliInterface {
     analysis_service: Arc<AnalysisTypeService>,
+    /// Разрешение на запись baseline и отчётов (режим только для чтения)
+    write_guard: WriteGuard,
 }
 
 /// CLI запрос анализа проекта
@@ -549,6 +1784,21 @@ pub struct CliAnalysisRequest {
     pub include_coverage: bool,
     pub include_errors: bool,
     pub verbose: bool,
+    /// Подсистемы конфигурации для группировки результатов
+    pub subsystems: Option<Arc<SubsystemIndex>>,
+    /// Ограничить результаты одной подсистемой (требует `subsystems`)
+    pub subsystem_filter: Option<String>,
+    /// Файл владельцев для назначения ответственных за диагностики
+    pub ownership: Option<Arc<OwnershipMap>>,
+    /// Пороги качества, проверяемые после анализа
+    pub quality_gates: Option<QualityGatesConfig>,
+    /// Перезаписать baseline Dynamic текущими результатами вместо проверки
+    pub update_dynamic_baseline: bool,
+    /// Перезаписать baseline покрытия текущими результатами вместо проверки
+    pub update_coverage_baseline: bool,
+    /// Анализ в ограниченной памяти: волны модулей, сброс диагностик на
+    /// диск и предел памяти
+    pub memory: Option<BoundedAnalysisConfig>,
 }
 
 /// Форматы вывода CLI
@@ -556,6 +1806,8 @@ pub struct CliAnalysisRequest {
 pub enum CliOutputFormat {
     Text,
     Json,
+    /// JSON Lines: одна диагностика на строку и итоговая сводка последней строкой
+    Jsonl,
     Csv,
     Html,
 }
@@ -566,9 +1818,28 @@ pub struct CliAnalysisResponse {
     pub summary: CliAnalysisSummary,
     pub coverage: Option<CliCoverageReport>,
     pub errors: Vec<CliTypeError>,
+    /// Сводка по подсистемам (пусто, если подсистемы не заданы)
+    pub by_subsystem: Vec<SubsystemAnalysisSummary>,
+    /// Сводка по владельцам (пусто, если файл владельцев не задан)
+    pub by_owner: Vec<OwnerSummary>,
+    /// Результат проверки порогов качества (None, если пороги не заданы)
+    pub gates: Option<GateReport>,
     pub formatted_output: String,
 }
 
+/// Итог потокового анализа
+#[derive(Debug, Clone)]
+pub struct CliStreamResult {
+    pub summary: CliAnalysisSummary,
+    pub gates: Option<GateReport>,
+}
+
+/// Сколько ошибок типов показывает текстовый вывод CLI
+const TEXT_ERRORS_SHOWN: usize = 5;
+
+/// Сколько файлов с наименьшим покрытием попадает в отчёт
+const TOP_UNCOVERED_FILES: usize = 10;
+
 /// Сводка анализа для CLI
 #[derive(Debug, Clone, Serialize)]
 pub struct CliAnalysisSummary {
@@ -581,17 +1852,51 @@ pub struct CliAnalysisSummary {
     pub analysis_time_seconds: f64,
 }
 
+impl CliAnalysisSummary {
+    fn from_result(result: &ProjectAnalysisResult) -> Self {
+        Self {
+            project_path: result.project_path.clone(),
+            total_files: result.total_files,
+            analyzed_files: result.analyzed_files,
+            total_functions: result.total_functions,
+            total_variables: result.total_variables,
+            error_count: result.diagnostics_count(),
+            analysis_time_seconds: result.analysis_time.as_secs_f64(),
+        }
+    }
+}
+
+/// JSON отчёт CLI: сводка, покрытие, группировки и пороги качества
+#[derive(Serialize)]
+struct CliJsonReport<'a> {
+    #[serde(flatten)]
+    summary: &'a CliAnalysisSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coverage: Option<&'a CliCoverageReport>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    by_subsystem: &'a [SubsystemAnalysisSummary],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    by_owner: &'a [OwnerSummary],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gates: Option<&'a GateReport>,
+}
+
 /// Отчёт покрытия для CLI
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CliCoverageReport {
     pub total_expressions: usize,
     pub typed_expressions: usize,
+    /// Из типизированных: тип известен точно и выведен
+    pub known_expressions: usize,
+    pub inferred_expressions: usize,
     pub coverage_percentage: f32,
+    /// Покрытие явными аннотациями методов (в отличие от покрытия выводом выше)
+    pub annotations: AnnotationCoverage,
     pub top_uncovered_files: Vec<String>,
 }
 
 /// Ошибка типа для CLI
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CliTypeError {
     pub file_path: String,
     pub line: u32,
@@ -599,12 +1904,53 @@ pub struct CliTypeError {
     pub severity: String,
     pub message: String,
     pub suggested_fix: Option<String>,
+    pub owners: Vec<String>,
+}
+
+impl From<TypeDiagnostic> for CliTypeError {
+    fn from(err: TypeDiagnostic) -> Self {
+        Self {
+            file_path: err.file_path,
+            line: err.line,
+            column: err.column,
+            severity: format!("{:?}", err.severity),
+            message: err.message,
+            suggested_fix: err.suggested_fix,
+            owners: err.owners,
+        }
+    }
+}
+
+/// Запись потока JSON Lines
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum CliJsonlRecord<'a> {
+    Diagnostic(&'a CliTypeError),
+    Gates(&'a GateReport),
+    Summary(&'a CliAnalysisSummary),
+}
+
+impl CliJsonlRecord<'_> {
+    fn write_line(&self, out: &mut dyn std::io::Write) -> Result<()> {
+        serde_json::to_writer(&mut *out, self)?;
+        out.write_all(b"\n")?;
+        Ok(())
+    }
 }
 
 impl CliInterface {
     /// Создать новый CLI интерфейс
     pub fn new(analysis_service: Arc<AnalysisTypeService>) -> Self {
-        Self { analysis_service }
+        Self {
+            analysis_service,
+            write_guard: WriteGuard::default(),
+        }
+    }
+
+    /// Проверять записи на диск через `write_guard`
+    pub fn with_write_guard(mut self, write_guard: WriteGuard) -> Self {
+        self.write_guard = write_guard;
+        self
     }
 
     /// Обработать запрос анализа проекта
@@ -612,67 +1958,301 @@ impl CliInterface {
         &self,
         request: CliAnalysisRequest,
     ) -> Result<CliAnalysisResponse> {
-        println!("🔍 CLI анализ проекта: {}", request.project_path.display());
+        info!("🔍 CLI анализ проекта: {}", request.project_path.display());
 
         // Выполняем анализ проекта
-        let analysis_result = self
+        let memory = self.bounded_config(&request);
+        let mut analysis_result = self
             .analysis_service
-            .analyze_project(&request.project_path)
+            .analyze_project_bounded(&request.project_path, &memory, &mut |_| {})
             .await?;
 
-        // Создаём сводку
-        let summary = CliAnalysisSummary {
-            project_path: analysis_result.project_path.clone(),
-            total_files: analysis_result.total_files,
-            analyzed_files: analysis_result.analyzed_files,
-            total_functions: analysis_result.total_functions,
-            total_variables: analysis_result.total_variables,
-            error_count: analysis_result.type_errors.len(),
-            analysis_time_seconds: analysis_result.analysis_time.as_secs_f64(),
+        if let (Some(index), Some(subsystem)) = (&request.subsystems, &request.subsystem_filter) {
+            analysis_result.retain_subsystem(index, subsystem);
+        }
+        let by_subsystem = request
+            .subsystems
+            .as_ref()
+            .map(|index| analysis_result.by_subsystem(index))
+            .unwrap_or_default();
+        let by_owner = match &request.ownership {
+            Some(ownership) => {
+                analysis_result.assign_owners(ownership);
+                analysis_result.by_owner(ownership)
+            }
+            None => Vec::new(),
         };
 
+        // Создаём сводку и проверяем пороги качества
+        let summary = CliAnalysisSummary::from_result(&analysis_result);
+        let gates = self.check_gates(&request, &analysis_result)?;
+
         // Конвертируем покрытие если запрошено
         let coverage = if request.include_coverage {
+            let report = &analysis_result.coverage_report;
             Some(CliCoverageReport {
-                total_expressions: analysis_result.coverage_report.total_expressions,
-                typed_expressions: analysis_result.coverage_report.typed_expressions,
-                coverage_percentage: analysis_result.coverage_report.coverage_percentage,
-                top_uncovered_files: Vec::new(), // TODO: найти файлы с низким покрытием
+                total_expressions: report.total_expressions,
+                typed_expressions: report.typed_expressions,
+                known_expressions: report.known_expressions,
+                inferred_expressions: report.inferred_expressions,
+                coverage_percentage: report.coverage_percentage,
+                annotations: analysis_result.annotation_coverage.clone(),
+                top_uncovered_files: report
+                    .least_covered(TOP_UNCOVERED_FILES)
+                    .into_iter()
+                    .map(|file| {
+                        format!(
+                            "{} ({:.1}%)",
+                            relative_module_path(&analysis_result.project_path, &file.file_path),
+                            file.coverage_percentage
+                        )
+                    })
+                    .collect(),
             })
         } else {
             None
         };
 
-        // Конвертируем ошибки если запрошены
+        // Конвертируем ошибки если запрошены; сброшенные на диск читаются
+        // обратно (для текста — только показываемые)
         let errors = if request.include_errors {
-            analysis_result
+            let mut errors: Vec<CliTypeError> = analysis_result
                 .type_errors
                 .into_iter()
-                .map(|err| CliTypeError {
-                    file_path: err.file_path,
-                    line: err.line,
-                    column: err.column,
-                    severity: format!("{:?}", err.severity),
-                    message: err.message,
-                    suggested_fix: err.suggested_fix,
-                })
-                .collect()
+                .map(CliTypeError::from)
+                .collect();
+            if let Some(spilled) = &analysis_result.spilled_diagnostics {
+                let limit = match request.output_format {
+                    CliOutputFormat::Text => TEXT_ERRORS_SHOWN.saturating_sub(errors.len()),
+                    _ => usize::MAX,
+                };
+                for diagnostic in spilled.read()?.take(limit) {
+                    let mut error = CliTypeError::from(diagnostic?);
+                    if let Some(ownership) = &request.ownership {
+                        let module = relative_module_path(&summary.project_path, &error.file_path);
+                        error.owners = ownership.owners_for_module(&module).to_vec();
+                    }
+                    errors.push(error);
+                }
+            }
+            errors
         } else {
             Vec::new()
         };
 
         // Форматируем вывод
-        let formatted_output =
+        let mut formatted_output =
             self.format_analysis_output(&summary, &coverage, &errors, &request.output_format);
+        match request.output_format {
+            CliOutputFormat::Text => {
+                if !by_subsystem.is_empty() {
+                    formatted_output.push_str(&Self::format_subsystem_output(&by_subsystem));
+                }
+                if !by_owner.is_empty() {
+                    formatted_output.push_str(&Self::format_owner_output(&by_owner));
+                }
+                if let Some(gates) = &gates {
+                    formatted_output.push('\n');
+                    formatted_output.push_str(&gates.to_text());
+                }
+            }
+            CliOutputFormat::Json => {
+                let report = CliJsonReport {
+                    summary: &summary,
+                    coverage: coverage.as_ref(),
+                    by_subsystem: &by_subsystem,
+                    by_owner: &by_owner,
+                    gates: gates.as_ref(),
+                };
+                formatted_output =
+                    serde_json::to_string_pretty(&report).unwrap_or_else(|_| "{}".to_string());
+            }
+            _ => {}
+        }
 
         Ok(CliAnalysisResponse {
             summary,
             coverage,
             errors,
+            by_subsystem,
+            by_owner,
+            gates,
             formatted_output,
         })
     }
 
+    /// Анализ с потоковым выводом JSON Lines: каждая диагностика пишется сразу
+    /// после анализа её файла, итоговая сводка — последней строкой
+    pub async fn stream_analysis_jsonl(
+        &self,
+        request: CliAnalysisRequest,
+        out: &mut (dyn std::io::Write + Send),
+    ) -> Result<CliStreamResult> {
+        let project_root = request.project_path.to_string_lossy().to_string();
+        let mut write_error: Option<anyhow::Error> = None;
+
+        let mut on_file = |file: &FileAnalysisResult| {
+            if write_error.is_some() {
+                return;
+            }
+            let module = relative_module_path(&project_root, &file.file_path.to_string_lossy());
+            if let (Some(index), Some(subsystem)) = (&request.subsystems, &request.subsystem_filter)
+            {
+                if !index.module_in_subsystem(&module, subsystem) {
+                    return;
+                }
+            }
+
+            for diagnostic in &file.diagnostics {
+                let mut error = CliTypeError::from(diagnostic.clone());
+                if let Some(ownership) = &request.ownership {
+                    error.owners = ownership.owners_for_module(&module).to_vec();
+                }
+                if let Err(e) = CliJsonlRecord::Diagnostic(&error).write_line(&mut *out) {
+                    write_error = Some(e);
+                    return;
+                }
+            }
+            // Обёртки читают поток построчно — не копим вывод в буфере
+            if let Err(e) = out.flush() {
+                write_error = Some(e.into());
+            }
+        };
+
+        let memory = self.bounded_config(&request);
+        let mut analysis_result = self
+            .analysis_service
+            .analyze_project_bounded(&request.project_path, &memory, &mut on_file)
+            .await?;
+        if let Some(e) = write_error {
+            return Err(e);
+        }
+
+        if let (Some(index), Some(subsystem)) = (&request.subsystems, &request.subsystem_filter) {
+            analysis_result.retain_subsystem(index, subsystem);
+        }
+        let summary = CliAnalysisSummary::from_result(&analysis_result);
+        let gates = self.check_gates(&request, &analysis_result)?;
+        if let Some(gates) = &gates {
+            CliJsonlRecord::Gates(gates).write_line(&mut *out)?;
+        }
+        CliJsonlRecord::Summary(&summary).write_line(&mut *out)?;
+        out.flush()?;
+        Ok(CliStreamResult { summary, gates })
+    }
+
+    /// Настройки анализа в ограниченной памяти; в режиме только для чтения
+    /// диагностики остаются в памяти
+    fn bounded_config(&self, request: &CliAnalysisRequest) -> BoundedAnalysisConfig {
+        let Some(memory) = &request.memory else {
+            return BoundedAnalysisConfig::default();
+        };
+        BoundedAnalysisConfig {
+            write_guard: self.write_guard.clone(),
+            ..memory.clone()
+        }
+    }
+
+    /// Проверить пороги качества (или обновить baseline, если запрошено)
+    fn check_gates(
+        &self,
+        request: &CliAnalysisRequest,
+        result: &ProjectAnalysisResult,
+    ) -> Result<Option<GateReport>> {
+        let Some(config) = request.quality_gates.as_ref().filter(|c| c.is_enabled()) else {
+            return Ok(None);
+        };
+        let input = result.gate_input();
+        let baseline = self.dynamic_baseline(request, config, &input)?;
+        let coverage_baseline = self.coverage_baseline(request, config, &input)?;
+        Ok(Some(quality_gates::evaluate(
+            config,
+            &input,
+            baseline.as_ref(),
+            coverage_baseline.as_ref(),
+        )?))
+    }
+
+    /// Baseline Dynamic: из файла или снятый заново
+    fn dynamic_baseline(
+        &self,
+        request: &CliAnalysisRequest,
+        config: &QualityGatesConfig,
+        input: &GateInput,
+    ) -> Result<Option<DynamicBaseline>> {
+        let baseline_path = config.dynamic_baseline.as_deref().map(std::path::Path::new);
+
+        // Обновлённый baseline применяется из памяти: в режиме только для
+        // чтения он не сохраняется на диск
+        let mut updated = None;
+        if request.update_dynamic_baseline {
+            let path = baseline_path
+                .ok_or_else(|| anyhow::anyhow!("Не задан путь baseline Dynamic"))?;
+            let baseline = DynamicBaseline::capture(config, input)?;
+            if self.write_guard.allows("baseline Dynamic", path) {
+                baseline.save(path)?;
+            }
+            info!(
+                "📝 Baseline Dynamic обновлён: {} ({} модулей)",
+                path.display(),
+                baseline.modules.len()
+            );
+            updated = Some(baseline);
+        }
+
+        let baseline = match baseline_path {
+            _ if updated.is_some() => updated,
+            Some(path) if path.exists() => Some(DynamicBaseline::load(path)?),
+            Some(path) => {
+                warn!(
+                    "Baseline Dynamic {} не найден: новыми считаются все Dynamic",
+                    path.display()
+                );
+                None
+            }
+            None => None,
+        };
+        Ok(baseline)
+    }
+
+    /// Baseline покрытия: из файла или снятый заново; без файла снижение
+    /// покрытия не проверяется
+    fn coverage_baseline(
+        &self,
+        request: &CliAnalysisRequest,
+        config: &QualityGatesConfig,
+        input: &GateInput,
+    ) -> Result<Option<CoverageBaseline>> {
+        let Some(path) = config.coverage_baseline.as_deref() else {
+            if request.update_coverage_baseline {
+                anyhow::bail!("Не задан путь baseline покрытия");
+            }
+            return Ok(None);
+        };
+        let path = std::path::Path::new(path);
+        if request.update_coverage_baseline {
+            let baseline = CoverageBaseline::capture(input);
+            if self.write_guard.allows("baseline покрытия", path) {
+                baseline.save(path)?;
+            }
+            info!(
+                "📝 Baseline покрытия обновлён: {} ({:.1}%, {} модулей)",
+                path.display(),
+                baseline.total,
+                baseline.modules.len()
+            );
+            return Ok(Some(baseline));
+        }
+        if !path.exists() {
+            warn!(
+                "Baseline покрытия {} не найден: снижение покрытия не проверяется",
+                path.display()
+            );
+            return Ok(None);
+        }
+        CoverageBaseline::load(path).map(Some)
+    }
+
     /// Экспортировать отчёты в файлы
     pub async fn export_reports(
         &self,
@@ -684,16 +2264,20 @@ impl CliInterface {
         // Экспорт JSON отчёта
         let json_path = output_dir.join("analysis_report.json");
         let json_content = serde_json::to_string_pretty(&analysis.summary)?;
-        std::fs::write(&json_path, json_content)?;
-        exported_files.push(json_path.to_string_lossy().to_string());
+        if self.write_guard.allows("отчёт анализа", &json_path) {
+            std::fs::write(&json_path, json_content)?;
+            exported_files.push(json_path.to_string_lossy().to_string());
+        }
 
         // Экспорт HTML отчёта (если есть покрытие)
         if let Some(coverage) = &analysis.coverage {
             let html_path = output_dir.join("coverage_report.html");
             let html_content =
                 self.generate_html_report(&analysis.summary, coverage, &analysis.errors);
-            std::fs::write(&html_path, html_content)?;
-            exported_files.push(html_path.to_string_lossy().to_string());
+            if self.write_guard.allows("отчёт покрытия", &html_path) {
+                std::fs::write(&html_path, html_content)?;
+                exported_files.push(html_path.to_string_lossy().to_string());
+            }
         }
 
         println!("✅ Отчёты экспортированы: {} файлов", exported_files.len());
@@ -714,6 +2298,7 @@ impl CliInterface {
             CliOutputFormat::Json => {
                 serde_json::to_string_pretty(summary).unwrap_or_else(|_| "{}".to_string())
             }
+            CliOutputFormat::Jsonl => Self::format_jsonl_output(summary, errors),
             CliOutputFormat::Csv => self.format_csv_output(summary, errors),
             CliOutputFormat::Html => self.format_html_output(summary, coverage, errors),
         }
@@ -745,14 +2330,33 @@ impl CliInterface {
         if let Some(cov) = coverage {
             output.push_str("📈 Покрытие типизации:\n");
             output.push_str(&format!(
-                "   Выражений: {} / {} ({:.1}%)\n\n",
-                cov.typed_expressions, cov.total_expressions, cov.coverage_percentage
+                "   Выражений (вывод): {} / {} ({:.1}%), известно точно: {}, выведено: {}\n",
+                cov.typed_expressions,
+                cov.total_expressions,
+                cov.coverage_percentage,
+                cov.known_expressions,
+                cov.inferred_expressions
             ));
+            output.push_str(&format!(
+                "   Методов с аннотациями: {} / {} ({:.1}%), частично: {}, только вывод: {}\n",
+                cov.annotations.fully_annotated,
+                cov.annotations.total_methods,
+                cov.annotations.annotation_percentage,
+                cov.annotations.partially_annotated,
+                cov.annotations.inferred_only()
+            ));
+            if !cov.top_uncovered_files.is_empty() {
+                output.push_str("   Наименьшее покрытие:\n");
+                for file in &cov.top_uncovered_files {
+                    output.push_str(&format!("      - {}\n", file));
+                }
+            }
+            output.push('\n');
         }
 
         if !errors.is_empty() {
             output.push_str("🚨 Ошибки типов:\n");
-            for (i, error) in errors.iter().take(5).enumerate() {
+            for (i, error) in errors.iter().take(TEXT_ERRORS_SHOWN).enumerate() {
                 output.push_str(&format!(
                     "   {}. {}:{}:{} [{}] {}\n",
                     i + 1,
@@ -763,14 +2367,53 @@ impl CliInterface {
                     error.message
                 ));
             }
-            if errors.len() > 5 {
-                output.push_str(&format!("   ... и ещё {} ошибок\n", errors.len() - 5));
+            let shown = errors.len().min(TEXT_ERRORS_SHOWN);
+            if summary.error_count > shown {
+                output.push_str(&format!(
+                    "   ... и ещё {} ошибок\n",
+                    summary.error_count - shown
+                ));
             }
         }
 
         output
     }
 
+    fn format_jsonl_output(summary: &CliAnalysisSummary, errors: &[CliTypeError]) -> String {
+        let mut buffer = Vec::new();
+        for error in errors {
+            let _ = CliJsonlRecord::Diagnostic(error).write_line(&mut buffer);
+        }
+        let _ = CliJsonlRecord::Summary(summary).write_line(&mut buffer);
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+
+    fn format_subsystem_output(by_subsystem: &[SubsystemAnalysisSummary]) -> String {
+        let mut output = String::from("\n🗂️ По подсистемам:\n");
+        for group in by_subsystem {
+            output.push_str(&format!(
+                "   {}: файлов {}, ошибок {}, предупреждений {}, покрытие {:.1}%\n",
+                group.subsystem,
+                group.files,
+                group.errors,
+                group.warnings,
+                group.coverage_percentage
+            ));
+        }
+        output
+    }
+
+    fn format_owner_output(by_owner: &[OwnerSummary]) -> String {
+        let mut output = String::from("\n👥 По владельцам:\n");
+        for group in by_owner {
+            output.push_str(&format!(
+                "   {}: файлов {}, ошибок {}, предупреждений {}\n",
+                group.owner, group.files, group.errors, group.warnings
+            ));
+        }
+        output
+    }
+
     fn format_csv_output(&self, _summary: &CliAnalysisSummary, _errors: &[CliTypeError]) -> String {
         // TODO: Реализовать CSV формат
         "file,line,column,severity,message\n".to_string()
@@ -818,6 +2461,8 @@ mod tests {
             column: 5,
             prefix: "Стр".to_string(),
             trigger_character: None,
+            expected_type: None,
+            document_text: None,
         };
 
         let response = lsp_interface
@@ -829,6 +2474,114 @@ mod tests {
         println!("✅ LspInterface работает");
     }
 
+    #[tokio::test]
+    async fn test_definition_of_local_and_exported_methods() {
+        let repo = Arc::new(InMemoryTypeRepository::new());
+        let resolution_service = Arc::new(TypeResolutionService::new(repo));
+        let lsp_interface = LspInterface::new(Arc::new(LspTypeService::new(resolution_service)));
+
+        let project = tempfile::tempdir().unwrap();
+        let module_dir = project.path().join("CommonModules/Продажи/Ext");
+        std::fs::create_dir_all(&module_dir).unwrap();
+        std::fs::write(
+            module_dir.join("Module.bsl"),
+            "// Продажи\nФункция СуммаДокумента(Документ) Экспорт\nКонецФункции\n",
+        )
+        .unwrap();
+        let index = CommonModuleIndex::scan_directory(project.path()).unwrap();
+        lsp_interface.set_common_module_index(index).await;
+
+        let document = "Процедура Заполнить()\n    Пересчитать();\n    \
+            Сумма = Продажи.СуммаДокумента(Ссылка);\nКонецПроцедуры\n\n\
+            &НаСервере\nПроцедура Пересчитать()\nКонецПроцедуры\n";
+        let request = |expression: &str| LspDefinitionRequest {
+            file_path: "Documents/Заказ/Ext/ObjectModule.bsl".to_string(),
+            line: 1,
+            column: 6,
+            expression: expression.to_string(),
+            document: Some(document.to_string()),
+        };
+
+        let local = lsp_interface
+            .handle_definition_request(request("пересчитать"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(local.file_path, "Documents/Заказ/Ext/ObjectModule.bsl");
+        assert_eq!((local.range.start.line, local.range.start.character), (6, 10));
+        assert_eq!(local.range.end.character, 21);
+
+        let exported = lsp_interface
+            .handle_definition_request(request("Продажи.СуммаДокумента("))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(exported.file_path.ends_with("Module.bsl"));
+        assert_eq!((exported.range.start.line, exported.range.start.character), (1, 8));
+
+        assert!(lsp_interface
+            .handle_definition_request(request("НетТакого"))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rename_exported_method_across_modules() {
+        let repo = Arc::new(InMemoryTypeRepository::new());
+        let resolution_service = Arc::new(TypeResolutionService::new(repo));
+        let lsp_interface = LspInterface::new(Arc::new(LspTypeService::new(resolution_service)));
+
+        let project = tempfile::tempdir().unwrap();
+        let module_dir = project.path().join("CommonModules/Продажи/Ext");
+        std::fs::create_dir_all(&module_dir).unwrap();
+        std::fs::write(
+            module_dir.join("Module.bsl"),
+            "// Продажи\nФункция СуммаДокумента(Документ) Экспорт\n    \
+             Возврат Продажи.СуммаДокумента(Документ.Основание);\nКонецФункции\n",
+        )
+        .unwrap();
+        let document_dir = project.path().join("Documents/Заказ/Ext");
+        std::fs::create_dir_all(&document_dir).unwrap();
+        let document_path = document_dir.join("ObjectModule.bsl");
+        let document =
+            "Процедура Заполнить()\n    Сумма = Продажи.СуммаДокумента(Ссылка);\nКонецПроцедуры\n";
+        std::fs::write(&document_path, document).unwrap();
+        let index = CommonModuleIndex::scan_directory(project.path()).unwrap();
+        lsp_interface.set_common_module_index(index).await;
+
+        let request = |new_name: &str| LspRenameRequest {
+            file_path: document_path.to_string_lossy().to_string(),
+            document: document.to_string(),
+            line: 1,
+            column: 22,
+            new_name: new_name.to_string(),
+            workspace_root: Some(project.path().to_path_buf()),
+            open_documents: Vec::new(),
+        };
+
+        let edits = lsp_interface
+            .handle_rename_request(request("ИтогДокумента"))
+            .await
+            .unwrap();
+        let positions = |suffix: &str| {
+            let mut found: Vec<(u32, u32)> = edits
+                .iter()
+                .filter(|edit| edit.file_path.ends_with(suffix))
+                .map(|edit| (edit.range.start.line, edit.range.start.character))
+                .collect();
+            found.sort();
+            found
+        };
+        assert_eq!(positions("/Module.bsl"), [(1, 8), (2, 20)]);
+        assert_eq!(positions("ObjectModule.bsl"), [(1, 20)]);
+        assert!(edits.iter().all(|edit| edit.new_text == "ИтогДокумента"));
+        assert_eq!(edits[0].range.end.character - edits[0].range.start.character, 14);
+
+        assert!(lsp_interface.handle_rename_request(request("Если")).await.is_err());
+        assert!(lsp_interface.handle_rename_request(request("1Сумма")).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_web_interface() {
         let repo = Arc::new(InMemoryTypeRepository::new());
@@ -871,6 +2624,13 @@ mod tests {
             include_coverage: true,
             include_errors: true,
             verbose: false,
+            subsystems: None,
+            subsystem_filter: None,
+            ownership: None,
+            quality_gates: None,
+            update_dynamic_baseline: false,
+            update_coverage_baseline: false,
+            memory: None,
         };
 
         let response = cli_interface
@@ -881,4 +2641,154 @@ mod tests {
 
         println!("✅ CliInterface работает");
     }
+
+    #[test]
+    fn test_project_fields() {
+        let value = serde_json::json!({
+            "name": "Массив",
+            "description": "Коллекция значений",
+            "methods_count": 12,
+        });
+
+        let projected = project_fields(value, &["name", "methods_count", "unknown"]);
+        assert_eq!(
+            projected,
+            serde_json::json!({ "name": "Массив", "methods_count": 12 })
+        );
+    }
+
+    #[test]
+    fn test_jsonl_output() {
+        let summary = CliAnalysisSummary {
+            project_path: "src".to_string(),
+            total_files: 1,
+            analyzed_files: 1,
+            total_functions: 0,
+            total_variables: 0,
+            error_count: 1,
+            analysis_time_seconds: 0.0,
+        };
+        let error = CliTypeError {
+            file_path: "CommonModules/Общий/Ext/Module.bsl".to_string(),
+            line: 3,
+            column: 5,
+            severity: "Error".to_string(),
+            message: "Несовместимые типы".to_string(),
+            suggested_fix: None,
+            owners: vec!["@core".to_string()],
+        };
+
+        let output = CliInterface::format_jsonl_output(&summary, &[error]);
+        let records: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["kind"], "diagnostic");
+        assert_eq!(records[0]["line"], 3);
+        assert_eq!(records[1]["kind"], "summary");
+        assert_eq!(records[1]["error_count"], 1);
+    }
+
+    /// Вывод, запоминающий число записанных строк при каждом сбросе
+    #[derive(Default)]
+    struct FlushRecorder {
+        buffer: Vec<u8>,
+        lines_at_flush: Vec<usize>,
+    }
+
+    impl std::io::Write for FlushRecorder {
+        fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+            self.buffer.extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            let lines = self.buffer.iter().filter(|&&byte| byte == b'\n').count();
+            self.lines_at_flush.push(lines);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_analysis_jsonl() {
+        let project = tempfile::tempdir().unwrap();
+        for name in ["Первый", "Второй"] {
+            let dir = project.path().join("CommonModules").join(name).join("Ext");
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("Module.bsl"),
+                "Процедура Загрузить() Экспорт\n    Ждать ЗагрузитьАсинх();\nКонецПроцедуры\n",
+            )
+            .unwrap();
+        }
+        let repo = Arc::new(InMemoryTypeRepository::new());
+        let resolution_service = Arc::new(TypeResolutionService::new(repo));
+        let cli_interface =
+            CliInterface::new(Arc::new(AnalysisTypeService::new(resolution_service)));
+        let request = CliAnalysisRequest {
+            project_path: project.path().to_path_buf(),
+            output_format: CliOutputFormat::Jsonl,
+            include_coverage: false,
+            include_errors: true,
+            verbose: false,
+            subsystems: None,
+            subsystem_filter: None,
+            ownership: None,
+            quality_gates: None,
+            update_dynamic_baseline: false,
+            update_coverage_baseline: false,
+            memory: None,
+        };
+
+        let mut out = FlushRecorder::default();
+        let result = cli_interface
+            .stream_analysis_jsonl(request, &mut out)
+            .await
+            .unwrap();
+        assert_eq!(result.summary.analyzed_files, 2);
+
+        // Диагностики каждого файла сбрасываются сразу после его анализа,
+        // сводка — последним сбросом
+        assert_eq!(out.lines_at_flush, [1, 2, 3]);
+
+        let records: Vec<serde_json::Value> = String::from_utf8(out.buffer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 3);
+        assert!(records[..2]
+            .iter()
+            .all(|record| record["kind"] == "diagnostic"));
+        assert_ne!(records[0]["file_path"], records[1]["file_path"]);
+        assert_eq!(records[2]["kind"], "summary");
+        assert_eq!(records[2]["error_count"], 2);
+    }
+
+    #[test]
+    fn test_type_list_request_to_query() {
+        let request = WebTypeListRequest {
+            page: Some(2),
+            sort: Some("methods_count".to_string()),
+            order: Some("desc".to_string()),
+            ..Default::default()
+        };
+        let query = request.to_query().unwrap();
+        assert_eq!(query.page, 2);
+        assert_eq!(query.per_page, TypeListQuery::default().per_page);
+        assert!(query.descending);
+
+        let bad_order = WebTypeListRequest {
+            order: Some("up".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_order.to_query().is_err());
+
+        let bad_sort = WebTypeListRequest {
+            sort: Some("size".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_sort.to_query().is_err());
+    }
 }
@@ -0,0 +1,309 @@
+//! Команды сервера для палитры команд
+//!
+//! Веб-интерфейс и скрипты выполняют обслуживающие действия одинаково:
+//! список команд со схемами аргументов отдаёт `GET /api/commands`, команда
+//! выполняется `POST /api/commands/{id}` с аргументами в JSON. Аргументы
+//! проверяются по схеме до выполнения, поэтому опечатка в имени или типе
+//! аргумента не запускает долгую операцию.
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Провайдеры данных, которые перезагружает `reload_provider`
+pub const RELOADABLE_PROVIDERS: &[&str] = &[
+    "roles",
+    "commands",
+    "metadata_values",
+    "snippets",
+    "team_notes",
+    "all",
+];
+
+/// Тип значения аргумента
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgumentType {
+    String,
+    Integer,
+    Boolean,
+}
+
+impl ArgumentType {
+    pub fn name(self) -> &'static str {
+        match self {
+            ArgumentType::String => "string",
+            ArgumentType::Integer => "integer",
+            ArgumentType::Boolean => "boolean",
+        }
+    }
+
+    fn accepts(self, value: &Value) -> bool {
+        match self {
+            ArgumentType::String => value.is_string(),
+            ArgumentType::Integer => value.is_u64() || value.is_i64(),
+            ArgumentType::Boolean => value.is_boolean(),
+        }
+    }
+}
+
+/// Схема аргумента команды
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandArgument {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub kind: ArgumentType,
+    pub required: bool,
+    pub description: &'static str,
+    /// Допустимые значения строкового аргумента
+    #[serde(rename = "enum", skip_serializing_if = "no_values")]
+    pub values: &'static [&'static str],
+}
+
+fn no_values(values: &&[&str]) -> bool {
+    values.is_empty()
+}
+
+const fn argument(
+    name: &'static str,
+    kind: ArgumentType,
+    required: bool,
+    description: &'static str,
+) -> CommandArgument {
+    CommandArgument {
+        name,
+        kind,
+        required,
+        description,
+        values: &[],
+    }
+}
+
+/// Команда сервера
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerCommand {
+    /// Перечитать провайдер данных конфигурации или проекта
+    ReloadProvider,
+    /// Перестроить индексы типов
+    RebuildIndex,
+    /// Выгрузить типы ветки иерархии категорий
+    ExportSubtree,
+    /// Проанализировать проект на сервере
+    RunAnalysis,
+}
+
+/// Описание команды для палитры
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandDescriptor {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub arguments: Vec<CommandArgument>,
+}
+
+impl ServerCommand {
+    pub const ALL: [ServerCommand; 4] = [
+        ServerCommand::ReloadProvider,
+        ServerCommand::RebuildIndex,
+        ServerCommand::ExportSubtree,
+        ServerCommand::RunAnalysis,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            ServerCommand::ReloadProvider => "reload_provider",
+            ServerCommand::RebuildIndex => "rebuild_index",
+            ServerCommand::ExportSubtree => "export_subtree",
+            ServerCommand::RunAnalysis => "run_analysis",
+        }
+    }
+
+    /// Название в палитре
+    pub fn title(self) -> &'static str {
+        match self {
+            ServerCommand::ReloadProvider => "Перезагрузить провайдер данных",
+            ServerCommand::RebuildIndex => "Перестроить индексы типов",
+            ServerCommand::ExportSubtree => "Выгрузить ветку иерархии",
+            ServerCommand::RunAnalysis => "Проанализировать проект",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ServerCommand::ReloadProvider => {
+                "Перечитывает роли, команды, константы, шаблоны или заметки команды"
+            }
+            ServerCommand::RebuildIndex => {
+                "Перечитывает справку и конфигурацию, как плановая задача reindex"
+            }
+            ServerCommand::ExportSubtree => {
+                "Возвращает типы категории и её подкатегорий (путь через `/`)"
+            }
+            ServerCommand::RunAnalysis => {
+                "Анализирует модули проекта и возвращает сводку ошибок и покрытия"
+            }
+        }
+    }
+
+    pub fn arguments(self) -> Vec<CommandArgument> {
+        match self {
+            ServerCommand::ReloadProvider => vec![CommandArgument {
+                values: RELOADABLE_PROVIDERS,
+                ..argument(
+                    "provider",
+                    ArgumentType::String,
+                    true,
+                    "Провайдер (all — все сразу)",
+                )
+            }],
+            ServerCommand::RebuildIndex => Vec::new(),
+            ServerCommand::ExportSubtree => vec![
+                argument(
+                    "category",
+                    ArgumentType::String,
+                    true,
+                    "Путь категории: `Универсальные коллекции/Массив`",
+                ),
+                argument(
+                    "limit",
+                    ArgumentType::Integer,
+                    false,
+                    "Не больше стольких типов",
+                ),
+            ],
+            ServerCommand::RunAnalysis => vec![argument(
+                "project",
+                ArgumentType::String,
+                true,
+                "Путь к исходникам проекта на сервере",
+            )],
+        }
+    }
+
+    pub fn descriptor(self) -> CommandDescriptor {
+        CommandDescriptor {
+            id: self.id(),
+            title: self.title(),
+            description: self.description(),
+            arguments: self.arguments(),
+        }
+    }
+
+    /// Проверить аргументы по схеме; `null` — без аргументов
+    pub fn validate(self, arguments: &Value) -> Result<Map<String, Value>> {
+        let arguments = match arguments {
+            Value::Null => Map::new(),
+            Value::Object(arguments) => arguments.clone(),
+            _ => anyhow::bail!("Аргументы команды {} — JSON-объект", self.id()),
+        };
+        let schema = self.arguments();
+        if let Some(unknown) = arguments
+            .keys()
+            .find(|name| !schema.iter().any(|a| a.name == name.as_str()))
+        {
+            anyhow::bail!("Команда {} не принимает аргумент '{}'", self.id(), unknown);
+        }
+        for spec in &schema {
+            let Some(value) = arguments.get(spec.name).filter(|v| !v.is_null()) else {
+                anyhow::ensure!(
+                    !spec.required,
+                    "Команде {} нужен аргумент '{}'",
+                    self.id(),
+                    spec.name
+                );
+                continue;
+            };
+            anyhow::ensure!(
+                spec.kind.accepts(value),
+                "Аргумент '{}' команды {}: ожидается {}",
+                spec.name,
+                self.id(),
+                spec.kind.name()
+            );
+            if let Some(text) = value.as_str().filter(|_| !spec.values.is_empty()) {
+                anyhow::ensure!(
+                    spec.values.contains(&text),
+                    "Аргумент '{}' команды {}: '{}' не из {}",
+                    spec.name,
+                    self.id(),
+                    text,
+                    spec.values.join(", ")
+                );
+            }
+        }
+        Ok(arguments)
+    }
+}
+
+impl std::str::FromStr for ServerCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        ServerCommand::ALL
+            .into_iter()
+            .find(|command| command.id() == s.trim().to_lowercase())
+            .ok_or_else(|| format!("неизвестная команда сервера '{}'", s))
+    }
+}
+
+/// Описания всех команд сервера
+pub fn command_descriptors() -> Vec<CommandDescriptor> {
+    ServerCommand::ALL
+        .into_iter()
+        .map(ServerCommand::descriptor)
+        .collect()
+}
+
+/// Строковый аргумент из проверенных аргументов
+pub fn string_argument<'a>(arguments: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    arguments.get(name).and_then(Value::as_str)
+}
+
+/// Результат выполнения команды
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandOutcome {
+    pub command: ServerCommand,
+    pub started_at: DateTime<Local>,
+    pub duration_ms: u64,
+    /// Что сделано (`выгружено 12 типов категории ...`)
+    pub summary: String,
+    /// Данные команды (выгруженные типы, сводка анализа)
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub result: Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_arguments() {
+        let command: ServerCommand = "reload_provider".parse().unwrap();
+        assert_eq!(command, ServerCommand::ReloadProvider);
+        assert!("drop_database".parse::<ServerCommand>().is_err());
+
+        let arguments = command.validate(&json!({ "provider": "roles" })).unwrap();
+        assert_eq!(string_argument(&arguments, "provider"), Some("roles"));
+        assert!(command.validate(&json!({ "provider": "cache" })).is_err());
+        assert!(command.validate(&Value::Null).is_err());
+        assert!(command.validate(&json!({ "provider": 1 })).is_err());
+
+        let export = ServerCommand::ExportSubtree;
+        assert!(export.validate(&json!({ "category": "Коллекции" })).is_ok());
+        let wrong_type = json!({ "category": "Коллекции", "limit": "10" });
+        assert!(export.validate(&wrong_type).is_err());
+        let unknown = json!({ "category": "Коллекции", "depth": 1 });
+        assert!(export.validate(&unknown).is_err());
+        assert!(ServerCommand::RebuildIndex.validate(&Value::Null).is_ok());
+        assert!(ServerCommand::RebuildIndex.validate(&json!([])).is_err());
+
+        let descriptors = serde_json::to_value(command_descriptors()).unwrap();
+        assert_eq!(descriptors[0]["id"], "reload_provider");
+        assert_eq!(descriptors[0]["arguments"][0]["type"], "string");
+        assert_eq!(descriptors[0]["arguments"][0]["enum"][0], "roles");
+        assert!(descriptors[1]["arguments"].as_array().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+//! gRPC API центральной системы типов (feature `grpc`)
+//!
+//! Повторяет REST интерфейс веб-сервера (поиск, разрешение, анализ, статистика)
+//! поверх tonic. Описание сервиса — `proto/bsl_types.proto`.
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use tonic::{Request, Response, Status};
+
+use super::CentralTypeSystem;
+use crate::presentation::{LspHoverRequest, WebSearchFilters, WebSearchRequest};
+
+/// Сгенерированный из proto код
+pub mod proto {
+    tonic::include_proto!("bsl.types.v1");
+}
+
+pub use proto::bsl_types_server::BslTypesServer;
+
+/// Реализация gRPC сервиса поверх `CentralTypeSystem`
+///
+/// Система хранится в `ArcSwap`, чтобы перезагрузка конфигурации веб-сервера
+/// подхватывалась и gRPC клиентами.
+pub struct GrpcTypeService {
+    central: Arc<ArcSwap<CentralTypeSystem>>,
+}
+
+impl GrpcTypeService {
+    pub fn new(central: Arc<ArcSwap<CentralTypeSystem>>) -> Self {
+        Self { central }
+    }
+
+    /// Обернуть в tonic сервер
+    pub fn into_server(self) -> BslTypesServer<Self> {
+        BslTypesServer::new(self)
+    }
+
+    fn central(&self) -> Arc<CentralTypeSystem> {
+        self.central.load_full()
+    }
+
+    async fn search_internal(
+        &self,
+        request: proto::SearchRequest,
+    ) -> Result<proto::SearchResponse, Status> {
+        let filters = request.filters.map(|f| WebSearchFilters {
+            source: f.source,
+            category: f.category,
+            has_methods: f.has_methods,
+            has_properties: f.has_properties,
+        });
+        let web_request = WebSearchRequest {
+            query: request.query,
+            page: request.page.map(|p| p.max(1) as usize),
+            per_page: request.per_page.map(|p| p as usize),
+            filters,
+        };
+
+        let response = self
+            .central()
+            .web_interface()
+            .handle_search_request(web_request)
+            .await
+            .map_err(internal)?;
+
+        Ok(proto::SearchResponse {
+            results: response
+                .results
+                .into_iter()
+                .map(|item| proto::SearchResultItem {
+                    name: item.name,
+                    category: item.category,
+                    description: item.description,
+                    relevance_score: item.relevance_score,
+                    url: item.url,
+                    tags: item.tags,
+                })
+                .collect(),
+            total_count: response.total_count as u64,
+            page: response.page as u32,
+            per_page: response.per_page as u32,
+            total_pages: response.total_pages as u32,
+        })
+    }
+}
+
+/// Размер страницы при потоковом поиске (не больше лимита веб-интерфейса)
+const STREAM_PAGE_SIZE: u32 = 100;
+
+fn internal(e: anyhow::Error) -> Status {
+    Status::internal(e.to_string())
+}
+
+type SearchStream = std::pin::Pin<
+    Box<dyn tokio_stream::Stream<Item = Result<proto::SearchResultItem, Status>> + Send>,
+>;
+
+#[tonic::async_trait]
+impl proto::bsl_types_server::BslTypes for GrpcTypeService {
+    async fn search(
+        &self,
+        request: Request<proto::SearchRequest>,
+    ) -> Result<Response<proto::SearchResponse>, Status> {
+        let response = self.search_internal(request.into_inner()).await?;
+        Ok(Response::new(response))
+    }
+
+    type SearchStreamStream = SearchStream;
+
+    async fn search_stream(
+        &self,
+        request: Request<proto::SearchRequest>,
+    ) -> Result<Response<Self::SearchStreamStream>, Status> {
+        let request = request.into_inner();
+        let service = GrpcTypeService::new(self.central.clone());
+        let (tx, rx) = tokio::sync::mpsc::channel(STREAM_PAGE_SIZE as usize);
+
+        // Постранично обходим результаты поиска и отдаём их в поток,
+        // пока клиент не отключится или страницы не закончатся
+        tokio::spawn(async move {
+            let mut page = 1;
+            loop {
+                let page_request = proto::SearchRequest {
+                    page: Some(page),
+                    per_page: Some(STREAM_PAGE_SIZE),
+                    ..request.clone()
+                };
+                let response = match service.search_internal(page_request).await {
+                    Ok(response) => response,
+                    Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        return;
+                    }
+                };
+                for item in response.results {
+                    if tx.send(Ok(item)).await.is_err() {
+                        return;
+                    }
+                }
+                if page >= response.total_pages {
+                    return;
+                }
+                page += 1;
+            }
+        });
+
+        Ok(Response::new(Box::pin(
+            tokio_stream::wrappers::ReceiverStream::new(rx),
+        )))
+    }
+
+    async fn resolve(
+        &self,
+        request: Request<proto::ResolveRequest>,
+    ) -> Result<Response<proto::ResolveResponse>, Status> {
+        let request = request.into_inner();
+        let hover = self
+            .central()
+            .lsp_interface()
+            .handle_hover_request(LspHoverRequest {
+                file_path: request.file_path,
+                line: request.line,
+                column: request.column,
+                expression: request.expression,
+                document: None,
+            })
+            .await
+            .map_err(internal)?;
+
+        let response = match hover {
+            Some(hover) => proto::ResolveResponse {
+                resolved: true,
+                contents: hover.contents,
+            },
+            None => proto::ResolveResponse {
+                resolved: false,
+                contents: Vec::new(),
+            },
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn analyze(
+        &self,
+        request: Request<proto::AnalyzeRequest>,
+    ) -> Result<Response<proto::AnalyzeResponse>, Status> {
+        let request = request.into_inner();
+        let diagnostics = self
+            .central()
+            .lsp_interface()
+            .analyze_text_for_diagnostics(&request.file_path, &request.text)
+            .await
+            .map_err(internal)?;
+
+        Ok(Response::new(proto::AnalyzeResponse {
+            diagnostics: diagnostics
+                .into_iter()
+                .map(|d| proto::Diagnostic {
+                    start: Some(proto::Position {
+                        line: d.range.start.line,
+                        character: d.range.start.character,
+                    }),
+                    end: Some(proto::Position {
+                        line: d.range.end.line,
+                        character: d.range.end.character,
+                    }),
+                    severity: d.severity as u32,
+                    message: d.message,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn stats(
+        &self,
+        _request: Request<proto::StatsRequest>,
+    ) -> Result<Response<proto::StatsResponse>, Status> {
+        let central = self.central();
+        let metrics = central.get_system_metrics().await;
+        let health = central.health_check().await;
+
+        Ok(Response::new(proto::StatsResponse {
+            total_types: metrics.total_types as u64,
+            platform_types: metrics.platform_types as u64,
+            configuration_types: metrics.configuration_types as u64,
+            user_defined_types: metrics.user_defined_types as u64,
+            total_requests: metrics.total_requests,
+            average_lsp_response_ms: metrics.average_lsp_response_ms,
+            average_web_response_ms: metrics.average_web_response_ms,
+            cache_hit_rate: metrics.cache_hit_rate,
+            health_status: health.status,
+        }))
+    }
+}
@@ -0,0 +1,285 @@
+//! Плановые работы общего сервера
+//!
+//! Центральная система типов может по расписанию перестраивать индексы
+//! (перечитывать справку и конфигурацию; хранилище типов разбирает заново
+//! только изменившиеся источники), прогревать горячие кеши (подробности
+//! самых запрашиваемых типов и глобальные автодополнения по их первым
+//! буквам) и обновлять провайдеры данных (роли, команды, константы,
+//! заметки команды, шаблоны). Каждая задача запускается через свой
+//! интервал; если задано окно нерабочих часов, задачи выполняются только в
+//! нём, а задача без интервала — один раз за окно.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+/// Как часто планировщик проверяет, не пора ли выполнить задачи
+pub const MAINTENANCE_TICK: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Типы, прогреваемые до того, как накопилась статистика запросов
+pub const DEFAULT_HOT_TYPES: &[&str] = &[
+    "Массив",
+    "Структура",
+    "Соответствие",
+    "ТаблицаЗначений",
+    "СписокЗначений",
+    "Запрос",
+    "РезультатЗапроса",
+    "ВыборкаИзРезультатаЗапроса",
+    "Строка",
+    "Число",
+    "Дата",
+    "ДеревоЗначений",
+];
+
+/// Плановая задача
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceTask {
+    /// Перестроить индексы типов
+    Reindex,
+    /// Прогреть горячие кеши
+    Warmup,
+    /// Обновить провайдеры данных конфигурации и проекта
+    Refresh,
+}
+
+impl MaintenanceTask {
+    pub const ALL: [MaintenanceTask; 3] = [
+        MaintenanceTask::Reindex,
+        MaintenanceTask::Warmup,
+        MaintenanceTask::Refresh,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MaintenanceTask::Reindex => "reindex",
+            MaintenanceTask::Warmup => "warmup",
+            MaintenanceTask::Refresh => "refresh",
+        }
+    }
+}
+
+impl std::str::FromStr for MaintenanceTask {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        MaintenanceTask::ALL
+            .into_iter()
+            .find(|task| task.name() == s.trim().to_lowercase())
+            .ok_or_else(|| format!("неизвестная плановая задача '{}'", s))
+    }
+}
+
+/// Окно нерабочих часов по местному времени; может переходить через
+/// полночь (`22:00-06:00`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl OffHours {
+    /// Разобрать окно вида `02:00-05:00`
+    pub fn parse(value: &str) -> Result<Self> {
+        let (start, end) = value
+            .split_once('-')
+            .with_context(|| format!("окно '{}': ожидается ЧЧ:ММ-ЧЧ:ММ", value))?;
+        let time = |part: &str| {
+            NaiveTime::parse_from_str(part.trim(), "%H:%M")
+                .with_context(|| format!("окно '{}': некорректное время '{}'", value, part))
+        };
+        let window = Self {
+            start: time(start)?,
+            end: time(end)?,
+        };
+        anyhow::ensure!(window.start != window.end, "окно '{}' пустое", value);
+        Ok(window)
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// Длительность окна
+    pub fn length(&self) -> Duration {
+        let length = self.end - self.start;
+        if length < Duration::zero() {
+            length + Duration::days(1)
+        } else {
+            length
+        }
+    }
+}
+
+/// Настройки плановых работ (по умолчанию выключены)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaintenanceSettings {
+    /// Интервал перестройки индексов, секунды
+    pub reindex_interval_secs: Option<u64>,
+    /// Интервал прогрева кешей, секунды
+    pub warmup_interval_secs: Option<u64>,
+    /// Интервал обновления провайдеров, секунды
+    pub refresh_interval_secs: Option<u64>,
+    /// Окно нерабочих часов (`02:00-05:00`): задачи выполняются только в
+    /// нём, задачи без интервала — раз за окно
+    pub off_hours: Option<String>,
+    /// Сколько самых запрашиваемых типов прогревать
+    pub warmup_top_types: usize,
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            reindex_interval_secs: None,
+            warmup_interval_secs: None,
+            refresh_interval_secs: None,
+            off_hours: None,
+            warmup_top_types: 100,
+        }
+    }
+}
+
+impl MaintenanceSettings {
+    /// Интервал задачи
+    pub fn interval(&self, task: MaintenanceTask) -> Option<Duration> {
+        let secs = match task {
+            MaintenanceTask::Reindex => self.reindex_interval_secs,
+            MaintenanceTask::Warmup => self.warmup_interval_secs,
+            MaintenanceTask::Refresh => self.refresh_interval_secs,
+        }?;
+        (secs > 0).then(|| Duration::seconds(secs as i64))
+    }
+
+    /// Окно нерабочих часов; некорректное окно не даёт выполнять задачи
+    pub fn off_hours(&self) -> Option<Result<OffHours>> {
+        self.off_hours
+            .as_deref()
+            .filter(|value| !value.trim().is_empty())
+            .map(OffHours::parse)
+    }
+
+    /// Запланирована ли хотя бы одна задача
+    pub fn is_enabled(&self) -> bool {
+        self.off_hours().is_some()
+            || MaintenanceTask::ALL
+                .into_iter()
+                .any(|task| self.interval(task).is_some())
+    }
+
+    /// Пора ли выполнить задачу, последний раз выполненную в `last_run`
+    /// (до первого запуска — время старта системы)
+    pub fn is_due(
+        &self,
+        task: MaintenanceTask,
+        last_run: DateTime<Local>,
+        now: DateTime<Local>,
+    ) -> bool {
+        let elapsed = now - last_run;
+        let interval = self.interval(task);
+        match self.off_hours() {
+            None => interval.is_some_and(|interval| elapsed >= interval),
+            Some(Err(_)) => false,
+            Some(Ok(window)) => {
+                window.contains(now.time())
+                    && elapsed >= interval.unwrap_or_else(|| window.length())
+            }
+        }
+    }
+}
+
+/// Итог выполнения плановой задачи
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceRun {
+    pub task: MaintenanceTask,
+    pub started_at: DateTime<Local>,
+    pub duration_ms: u64,
+    /// Что сделано (`прогрето 100 типов, 12 префиксов`)
+    pub summary: String,
+    pub error: Option<String>,
+}
+
+/// Расписание и последние запуски плановых задач
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceStatus {
+    pub settings: MaintenanceSettings,
+    pub runs: Vec<MaintenanceRun>,
+}
+
+/// Префиксы прогреваемых автодополнений: пустой и первые буквы горячих
+/// типов
+pub fn warmup_prefixes(hot_types: &[String]) -> Vec<String> {
+    let mut prefixes = vec![String::new()];
+    for name in hot_types {
+        let Some(first) = name.chars().next() else {
+            continue;
+        };
+        let prefix = first.to_string();
+        if !prefixes.contains(&prefix) {
+            prefixes.push(prefix);
+        }
+    }
+    prefixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 3, 10, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_off_hours_window() {
+        let night = OffHours::parse("22:00-06:00").unwrap();
+        assert!(night.contains(NaiveTime::from_hms_opt(23, 30, 0).unwrap()));
+        assert!(night.contains(NaiveTime::from_hms_opt(5, 59, 0).unwrap()));
+        assert!(!night.contains(NaiveTime::from_hms_opt(12, 0, 0).unwrap()));
+        assert_eq!(night.length(), Duration::hours(8));
+        assert!(OffHours::parse("02:00").is_err());
+        assert!(OffHours::parse("02:00-02:00").is_err());
+    }
+
+    #[test]
+    fn test_due_tasks() {
+        let settings = MaintenanceSettings {
+            warmup_interval_secs: Some(600),
+            ..MaintenanceSettings::default()
+        };
+        assert!(settings.is_enabled());
+        assert!(settings.is_due(MaintenanceTask::Warmup, at(10, 0), at(10, 10)));
+        assert!(!settings.is_due(MaintenanceTask::Warmup, at(10, 0), at(10, 5)));
+        assert!(!settings.is_due(MaintenanceTask::Reindex, at(10, 0), at(23, 0)));
+        assert!(!MaintenanceSettings::default().is_enabled());
+
+        // В окне задача без интервала выполняется один раз
+        let settings = MaintenanceSettings {
+            off_hours: Some("02:00-05:00".to_string()),
+            ..settings
+        };
+        assert!(!settings.is_due(MaintenanceTask::Reindex, at(1, 0), at(1, 59)));
+        assert!(settings.is_due(MaintenanceTask::Reindex, at(1, 0), at(4, 30)));
+        assert!(!settings.is_due(MaintenanceTask::Reindex, at(2, 0), at(4, 30)));
+        assert!(settings.is_due(MaintenanceTask::Warmup, at(2, 0), at(2, 10)));
+        assert!(!settings.is_due(MaintenanceTask::Warmup, at(2, 0), at(12, 0)));
+    }
+
+    #[test]
+    fn test_warmup_prefixes() {
+        let hot = ["Массив", "Структура", "Соответствие"].map(str::to_string);
+        assert_eq!(warmup_prefixes(&hot), ["", "М", "С"]);
+        assert_eq!(
+            "warmup".parse::<MaintenanceTask>(),
+            Ok(MaintenanceTask::Warmup)
+        );
+    }
+}
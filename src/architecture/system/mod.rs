@@ -3,17 +3,60 @@
 //! CentralTypeSystem объединяет все слои архитектуры и предоставляет
 //! единую точку инициализации и управления системой типов BSL
 
-use anyhow::Result;
-use serde::Serialize;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod commands;
+pub mod maintenance;
+pub mod watch;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
 use std::sync::Arc;
-use tracing::{info, warn};
+use tracing::{info, info_span, warn, Instrument};
 
 use super::application::{AnalysisTypeService, LspTypeService, WebTypeService};
-use super::data::{InMemoryTypeRepository, ParseMetadata, RawTypeData, TypeRepository, TypeSource};
-use super::domain::{TypeContext, TypeResolutionService};
+use super::data::{
+    check_consistency, documentation_report, nested_location, source_fingerprint, ChangeJournal,
+    DataQualityReport, DocumentationReport, FileBackedTypeRepository, InMemoryTypeRepository,
+    NamePrecedence, ParseMetadata, RawTypeData, RemoteTypeRepository, RepositoryChange,
+    SnapshotTypeRepository, StoreArtifact, TypeDelta, TypeOrigin, TypeRepository, TypeSource,
+    TypeTombstone, WriteGuard,
+};
+use super::domain::documents::movements_raw_type;
+use super::domain::enums::enum_value_properties;
+use super::domain::external_sources::external_sources_raw_types;
+use super::domain::managers::tabular_section_type;
+use super::domain::objects::tabular_section_raw_types;
+use super::domain::predefined::predefined_raw_type;
+use super::domain::registers::{register_raw_types, RegisterKind};
+use super::domain::{
+    InferenceReference, ResolverChainConfig, ResolverPlugins, TypeContext, TypeResolutionService,
+};
+use super::presentation::formatting::FormattingConfig;
 use super::presentation::{CliInterface, LspInterface, WebInterface};
+use crate::core::completion_scope::{CompletionGroup, CompletionGroupsConfig};
+use crate::core::inlay_hints::InlayHintsConfig;
+use crate::core::quality_gates::QualityGatesConfig;
+use crate::core::snippets::SnippetProvider;
+use crate::core::telemetry::Telemetry;
+use crate::data::loaders::commands::CommandIndex;
+use crate::data::loaders::common_attributes;
 use crate::data::loaders::config_parser_guided_discovery::ConfigurationGuidedParser;
+use crate::data::loaders::english_names::english_name;
+use crate::data::loaders::external_data_sources;
+use crate::data::loaders::metadata_values::MetadataValueIndex;
+use crate::data::loaders::roles::RoleIndex;
+use crate::documentation::overlay::TeamNotesOverlay;
 use crate::domain::types::TypeResolution;
+use crate::parsing::bsl::language_features::LanguageFeatures;
+use commands::{string_argument, CommandOutcome, ServerCommand};
+use maintenance::{
+    warmup_prefixes, MaintenanceRun, MaintenanceSettings, MaintenanceStatus, MaintenanceTask,
+    DEFAULT_HOT_TYPES,
+};
+use watch::{SourceChanges, SourceUpdate, SourceWatcher};
 
 /// Центральная система типов BSL
 ///
@@ -24,6 +67,24 @@ pub struct CentralTypeSystem {
     /// Репозиторий всех типов (единый источник истины)
     repository: Arc<dyn TypeRepository>,
 
+    /// Клиент центрального сервера (если система работает в удалённом режиме)
+    remote_repository: Option<Arc<RemoteTypeRepository>>,
+
+    /// Общий снимок типов, отображённый в память (если задан `snapshot_path`)
+    snapshot_repository: Option<Arc<SnapshotTypeRepository>>,
+
+    /// Хранилище разобранных типов на диске (если задан `type_store_path`)
+    file_repository: Option<Arc<FileBackedTypeRepository>>,
+
+    /// Разрешение на запись на диск (режим только для чтения)
+    write_guard: WriteGuard,
+
+    /// Локальная телеметрия использования (выключена без `telemetry_path`)
+    telemetry: Telemetry,
+
+    /// Журнал изменений данных репозитория между загрузками типов
+    changelog: Arc<ChangeJournal>,
+
     // === DOMAIN LAYER ===
     /// Центральный сервис разрешения типов
     resolution_service: Arc<TypeResolutionService>,
@@ -49,18 +110,29 @@ pub struct CentralTypeSystem {
     cli_interface: CliInterface,
 
     // === INFRASTRUCTURE ===
-    /// Конфигурация системы
-    config: CentralSystemConfig,
+    /// Конфигурация системы (часть настроек меняется на лету, см. `update_config`)
+    config: tokio::sync::RwLock<CentralSystemConfig>,
 
     /// Метрики всей системы
     system_metrics: Arc<tokio::sync::RwLock<SystemMetrics>>,
 
     /// Состояние инициализации
     initialization_state: Arc<tokio::sync::RwLock<InitializationState>>,
+
+    /// Время создания системы: отсчёт интервалов плановых задач
+    started_at: chrono::DateTime<chrono::Local>,
+
+    /// Последний запуск каждой плановой задачи
+    maintenance_runs:
+        tokio::sync::RwLock<std::collections::HashMap<MaintenanceTask, MaintenanceRun>>,
 }
 
 /// Конфигурация центральной системы типов
-#[derive(Debug, Clone)]
+///
+/// Может быть загружена из JSON-файла (`CentralSystemConfig::from_file`);
+/// отсутствующие поля заполняются значениями по умолчанию.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CentralSystemConfig {
     /// Путь к HTML справке платформы
     pub html_path: String,
@@ -68,33 +140,274 @@ pub struct CentralSystemConfig {
     /// Путь к XML конфигурации (опционально)
     pub configuration_path: Option<String>,
 
+    /// Список объектов стандартного интерфейса OData (полные имена по одному
+    /// в строке). Состав OData хранится в информационной базе, а не в
+    /// выгрузке, поэтому для аудита интеграций его передают отдельно.
+    pub odata_composition_path: Option<String>,
+
     /// Включить детальное логирование
     pub verbose_logging: bool,
 
+    /// Headless-режим (контейнеры, CI): без интерактивного вывода и прогресс-баров,
+    /// логи в машиночитаемом формате
+    pub headless: bool,
+
     /// Настройки кеширования
     pub cache_settings: CacheSettings,
 
     /// Настройки производительности
     pub performance_settings: PerformanceSettings,
+
+    /// Настройки HTTP сервера веб-интерфейса
+    pub web_server: WebServerSettings,
+
+    /// Адрес центрального сервера анализа (например, "http://bsl-types:8080").
+    /// Если задан, репозиторий и поиск обслуживаются сервером, а локально
+    /// выполняется только вывод типов внутри файлов.
+    pub remote_repository_url: Option<String>,
+
+    /// Файл снимка репозитория типов (см. `build-index --output`). Если задан,
+    /// типы не разбираются при запуске, а читаются из снимка, общего для всех
+    /// процессов на машине.
+    pub snapshot_path: Option<String>,
+
+    /// Каталог хранилища разобранных типов. Если задан, типы платформы и
+    /// конфигурации сохраняются на диск и при следующем запуске разбираются
+    /// заново, только если изменились их исходные файлы.
+    pub type_store_path: Option<String>,
+
+    /// Режим только для чтения (закрытые сборочные агенты): ничего не
+    /// записывается на диск, хранилище типов, кеши и baseline остаются в
+    /// памяти, а пропущенные записи показывает `doctor`
+    pub read_only: bool,
+
+    /// Файл локальной телеметрии использования (по согласию). Если задан,
+    /// счётчики команд, возможностей и длительности операций добавляются в
+    /// этот JSON-файл; никуда не отправляются
+    pub telemetry_path: Option<String>,
+
+    /// Файл журнала изменений данных репозитория (какие типы и члены
+    /// появились или исчезли при перезагрузке). Без него журнал хранится
+    /// только в памяти процесса
+    pub changelog_path: Option<String>,
+
+    /// Каталог заметок команды к типам (YAML-файлы в репозитории проекта);
+    /// заметки добавляются к документации и подсказке при наведении
+    pub team_notes_path: Option<String>,
+
+    /// JSON-файл пользовательских шаблонов автодополнения (формат сниппетов
+    /// VS Code); дополняет и заменяет встроенные шаблоны
+    pub snippets_path: Option<String>,
+
+    /// Отслеживать изменения модулей и описаний метаданных в каталоге
+    /// конфигурации и применять их без полной перезагрузки (LSP сервер)
+    pub watch_sources: bool,
+
+    /// Пороги качества, проверяемые в конце CLI анализа
+    pub quality_gates: QualityGatesConfig,
+
+    /// Порядок, состав и политика остановки цепочки резолверов
+    pub resolver_chain: ResolverChainConfig,
+
+    /// Сторонние резолверы, подключаемые к цепочке при создании системы
+    /// (задаются только из кода, в файле настроек не хранятся)
+    #[serde(skip)]
+    pub resolver_plugins: ResolverPlugins,
+
+    /// Порядок групп автодополнения (локальные, модуль, глобальные)
+    pub completion_groups: CompletionGroupsConfig,
+
+    /// Какой источник побеждает, если тип конфигурации называется так же,
+    /// как тип платформы
+    pub name_precedence: NamePrecedence,
+
+    /// Версия платформы, к которой относятся платформенные типы; она же
+    /// определяет доступные конструкции языка (`8.3.17` — без `Асинх`/`Ждать`)
+    pub platform_version: String,
+
+    /// Профиль строгости диагностик в редакторе
+    pub strictness: StrictnessProfile,
+
+    /// Inlay hints редактора: типы переменных и имена параметров
+    pub inlay_hints: InlayHintsConfig,
+
+    /// Профиль стиля форматирования модулей в редакторе
+    pub formatting: FormattingConfig,
+
+    /// Плановые работы общего сервера: перестройка индексов, прогрев
+    /// кешей, обновление провайдеров
+    pub maintenance: MaintenanceSettings,
+}
+
+/// Профиль строгости диагностик
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrictnessProfile {
+    /// Только ошибки
+    Relaxed,
+    /// Ошибки и предупреждения
+    #[default]
+    Standard,
+    /// Все диагностики, включая информационные и подсказки
+    Strict,
+}
+
+impl StrictnessProfile {
+    /// Показывать ли диагностику с LSP-важностью `severity` (1=Error … 4=Hint)
+    pub fn reports(self, severity: u8) -> bool {
+        match self {
+            StrictnessProfile::Relaxed => severity <= 1,
+            StrictnessProfile::Standard => severity <= 2,
+            StrictnessProfile::Strict => true,
+        }
+    }
+}
+
+impl std::str::FromStr for StrictnessProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "relaxed" => Ok(StrictnessProfile::Relaxed),
+            "standard" => Ok(StrictnessProfile::Standard),
+            "strict" => Ok(StrictnessProfile::Strict),
+            other => Err(format!("неизвестный профиль строгости '{}'", other)),
+        }
+    }
+}
+
+/// Результат применения новых настроек без перезапуска
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigUpdate {
+    /// Перезагружены платформенные типы (справка, версия платформы)
+    pub platform_reloaded: bool,
+    /// Перезагружены типы конфигурации
+    pub configuration_reloaded: bool,
+    /// Диагностики открытых документов нужно пересчитать
+    pub diagnostics_changed: bool,
+    /// Inlay hints открытых документов нужно запросить заново
+    pub inlay_hints_changed: bool,
+    /// Изменённые настройки, которые вступят в силу только после перезапуска
+    pub restart_required: Vec<&'static str>,
 }
 
 /// Настройки кеширования
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CacheSettings {
     pub enable_repository_cache: bool,
     pub enable_resolution_cache: bool,
     pub enable_lsp_cache: bool,
     pub cache_ttl_seconds: u64,
     pub max_cache_size: usize,
+    /// Хранилище кеша анализа проекта: каталог, `sqlite://` или `s3://`
+    /// (по умолчанию `.bsl_cache` в рабочем каталоге)
+    pub analysis_cache_path: Option<String>,
 }
 
 /// Настройки производительности
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PerformanceSettings {
     pub enable_parallel_parsing: bool,
     pub max_parser_threads: usize,
     pub lsp_response_timeout_ms: u64,
     pub web_request_timeout_ms: u64,
+    /// Количество рабочих потоков tokio для веб-сервера
+    pub web_worker_threads: usize,
+    /// Предельное время работы одного резолвера; при превышении тип
+    /// выражения считается неизвестным. Срок проверяется только в точках
+    /// `await`: синхронный резолвер, занятый вычислениями, прерван не будет
+    /// и вернёт результат позже срока
+    pub resolver_timeout_ms: u64,
+}
+
+/// Настройки HTTP сервера веб-интерфейса
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebServerSettings {
+    /// Адрес для прослушивания (например, "127.0.0.1" или "0.0.0.0")
+    pub bind_address: String,
+    pub port: u16,
+    /// Путь к PEM сертификату (TLS включается, если заданы сертификат и ключ)
+    pub tls_cert_path: Option<String>,
+    /// Путь к PEM приватному ключу
+    pub tls_key_path: Option<String>,
+    /// Сколько ждать завершения активных запросов при остановке
+    pub shutdown_grace_period_secs: u64,
+    /// Порт gRPC API (feature `grpc`); None — gRPC не запускается
+    pub grpc_port: Option<u16>,
+    /// Дополнительные конфигурации, обслуживаемые под `/projects/{name}/`
+    /// (у каждой свой репозиторий типов)
+    pub projects: Vec<WebProjectSettings>,
+}
+
+/// Конфигурация, обслуживаемая веб-сервером под отдельным префиксом URL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebProjectSettings {
+    /// Имя в URL: латиница, цифры, `-` и `_`
+    pub name: String,
+    /// Путь к выгрузке конфигурации
+    pub configuration_path: String,
+    /// Состав стандартного интерфейса OData (см. `odata_composition_path`)
+    #[serde(default)]
+    pub odata_composition_path: Option<String>,
+}
+
+impl WebProjectSettings {
+    /// Разобрать список `имя=путь,имя=путь`
+    pub fn parse_list(value: &str) -> Result<Vec<Self>> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                let (name, path) = item
+                    .split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("Ожидается имя=путь: {}", item))?;
+                Ok(Self {
+                    name: name.trim().to_string(),
+                    configuration_path: path.trim().to_string(),
+                    odata_composition_path: None,
+                })
+            })
+            .collect()
+    }
+}
+
+impl WebServerSettings {
+    /// Проверить имена проектов: допустимы в URL без кодирования и не повторяются
+    pub fn validate_projects(&self) -> Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        for project in &self.projects {
+            let name = &project.name;
+            let valid = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+            if !valid {
+                anyhow::bail!("Недопустимое имя проекта '{}': только латиница, цифры, - и _", name);
+            }
+            if !seen.insert(name.to_lowercase()) {
+                anyhow::bail!("Проект '{}' указан несколько раз", name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Включён ли TLS
+    pub fn tls_enabled(&self) -> bool {
+        self.tls_cert_path.is_some() && self.tls_key_path.is_some()
+    }
+
+    /// Адрес сокета для прослушивания
+    pub fn socket_addr(&self) -> Result<std::net::SocketAddr> {
+        let ip: std::net::IpAddr = self
+            .bind_address
+            .parse()
+            .with_context(|| format!("Некорректный адрес: {}", self.bind_address))?;
+        Ok(std::net::SocketAddr::new(ip, self.port))
+    }
 }
 
 /// Метрики всей системы
@@ -115,6 +428,9 @@ pub struct SystemMetrics {
     pub cache_hit_rate: f64,
     pub cache_memory_mb: f64,
 
+    /// Разрешения типов, прерванные по таймауту резолвера
+    pub resolver_timeouts: u64,
+
     /// Время работы системы
     pub uptime_seconds: u64,
     pub last_updated: Option<std::time::SystemTime>,
@@ -161,24 +477,97 @@ pub struct ComponentHealth {
 impl CentralTypeSystem {
     /// Создать новую центральную систему типов
     pub fn new(config: CentralSystemConfig) -> Self {
-        // Создаём репозиторий
-        let repository: Arc<dyn TypeRepository> = Arc::new(InMemoryTypeRepository::new());
+        let write_guard = WriteGuard::new(config.read_only);
+        let telemetry = Telemetry::new(config.telemetry_path.as_deref(), write_guard.clone());
+        let changelog = Arc::new(ChangeJournal::new(
+            config.changelog_path.as_deref(),
+            write_guard.clone(),
+        ));
+
+        // Создаём репозиторий: удалённый (общий для команды) или локальный
+        let remote_repository = config.remote_repository_url.as_deref().and_then(|url| {
+            let timeout =
+                std::time::Duration::from_millis(config.performance_settings.web_request_timeout_ms);
+            match RemoteTypeRepository::new(url, timeout) {
+                Ok(remote) => Some(Arc::new(remote)),
+                Err(e) => {
+                    warn!("⚠️ Удалённый репозиторий {} недоступен: {}, используется локальный", url, e);
+                    None
+                }
+            }
+        });
+        let snapshot_repository = match (&remote_repository, config.snapshot_path.as_deref()) {
+            (None, Some(path)) => match SnapshotTypeRepository::open(Path::new(path)) {
+                Ok(snapshot) => Some(Arc::new(snapshot)),
+                Err(e) => {
+                    warn!(
+                        "⚠️ Снимок типов {} не открыт: {:#}, типы будут разобраны заново",
+                        path, e
+                    );
+                    None
+                }
+            },
+            _ => None,
+        };
+        let file_repository = match (&remote_repository, &snapshot_repository) {
+            (None, None) => config.type_store_path.as_deref().and_then(|path| {
+                let guard = write_guard.clone();
+                match FileBackedTypeRepository::open_location(path, guard) {
+                    Ok(store) => Some(Arc::new(store)),
+                    Err(e) => {
+                        warn!("⚠️ Хранилище типов {} недоступно: {:#}", path, e);
+                        None
+                    }
+                }
+            }),
+            _ => None,
+        };
+        let repository: Arc<dyn TypeRepository> =
+            match (&remote_repository, &snapshot_repository, &file_repository) {
+                (Some(remote), _, _) => remote.clone(),
+                (None, Some(snapshot), _) => snapshot.clone(),
+                (None, None, Some(store)) => store.clone(),
+                (None, None, None) => Arc::new(InMemoryTypeRepository::new()),
+            };
 
         // Создаём Domain Layer
-        let resolution_service = Arc::new(TypeResolutionService::new(repository.clone()));
+        let resolver_timeout =
+            std::time::Duration::from_millis(config.performance_settings.resolver_timeout_ms);
+        let resolution_service = Arc::new(
+            TypeResolutionService::new(repository.clone())
+                .with_chain_config(&config.resolver_chain)
+                .with_resolver_plugins(&config.resolver_plugins)
+                .with_name_precedence(config.name_precedence.clone())
+                .with_resolver_timeout(resolver_timeout),
+        );
 
         // Создаём Application Layer
-        let lsp_service = Arc::new(LspTypeService::new(resolution_service.clone()));
+        let language_features = LanguageFeatures::for_version(&config.platform_version);
+        let lsp_service = Arc::new(
+            LspTypeService::new(resolution_service.clone())
+                .with_completion_groups(config.completion_groups.clone()),
+        );
         let web_service = Arc::new(WebTypeService::new(resolution_service.clone()));
-        let analysis_service = Arc::new(AnalysisTypeService::new(resolution_service.clone()));
+        let analysis_service = Arc::new(
+            AnalysisTypeService::new(resolution_service.clone())
+                .with_language_features(language_features),
+        );
 
         // Создаём Presentation Layer
-        let lsp_interface = LspInterface::new(lsp_service.clone());
+        let lsp_interface =
+            LspInterface::new(lsp_service.clone()).with_language_features(language_features);
         let web_interface = WebInterface::new(web_service.clone());
-        let cli_interface = CliInterface::new(analysis_service.clone());
+        let cli_interface =
+            CliInterface::new(analysis_service.clone()).with_write_guard(write_guard.clone());
 
         Self {
             repository,
+            remote_repository,
+            snapshot_repository,
+            file_repository,
+            write_guard,
+            telemetry,
+            changelog,
             resolution_service,
             lsp_service,
             web_service,
@@ -186,11 +575,13 @@ impl CentralTypeSystem {
             lsp_interface,
             web_interface,
             cli_interface,
-            config,
+            config: tokio::sync::RwLock::new(config),
             system_metrics: Arc::new(tokio::sync::RwLock::new(SystemMetrics::default())),
             initialization_state: Arc::new(
                 tokio::sync::RwLock::new(InitializationState::default()),
             ),
+            started_at: chrono::Local::now(),
+            maintenance_runs: tokio::sync::RwLock::new(std::collections::HashMap::new()),
         }
     }
 
@@ -258,6 +649,7 @@ impl CentralTypeSystem {
 
         // === ЗАВЕРШЕНИЕ ===
         let total_time = start_time.elapsed();
+        self.telemetry.record_timing("initialize", total_time);
         self.update_progress(100, "Инициализация завершена").await;
 
         {
@@ -278,6 +670,21 @@ impl CentralTypeSystem {
         Ok(())
     }
 
+    /// Получить репозиторий типов (для отдачи клиентам в режиме общего сервера)
+    pub fn repository(&self) -> Arc<dyn TypeRepository> {
+        self.repository.clone()
+    }
+
+    /// Работает ли система как клиент центрального сервера
+    pub fn is_remote(&self) -> bool {
+        self.remote_repository.is_some()
+    }
+
+    /// Получить сервис анализа проектов (граф вызовов для LSP)
+    pub fn analysis_service(&self) -> Arc<AnalysisTypeService> {
+        self.analysis_service.clone()
+    }
+
     /// Получить LSP интерфейс
     pub fn lsp_interface(&self) -> &LspInterface {
         &self.lsp_interface
@@ -295,7 +702,44 @@ impl CentralTypeSystem {
 
     /// Получить метрики системы
     pub async fn get_system_metrics(&self) -> SystemMetrics {
-        self.system_metrics.read().await.clone()
+        let mut metrics = self.system_metrics.read().await.clone();
+        metrics.resolver_timeouts = self.resolution_service.timed_out_resolutions().await;
+        metrics
+    }
+
+    /// Разрешение на запись на диск; в режиме только для чтения хранит
+    /// пропущенные записи
+    pub fn write_guard(&self) -> &WriteGuard {
+        &self.write_guard
+    }
+
+    /// Локальная телеметрия использования; бинарники учитывают в ней команды
+    /// и запросы и сохраняют её при завершении
+    pub fn telemetry(&self) -> &Telemetry {
+        &self.telemetry
+    }
+
+    /// Журнал изменений данных репозитория (лента «Что нового»)
+    pub fn changelog(&self) -> Arc<ChangeJournal> {
+        self.changelog.clone()
+    }
+
+    /// Проверить согласованность загруженных данных репозитория
+    pub async fn data_quality_report(&self) -> Result<DataQualityReport> {
+        let types = self.repository.load_all_types().await?;
+        Ok(check_consistency(&types))
+    }
+
+    /// Оценить полноту документации загруженных типов
+    pub async fn documentation_report(&self) -> Result<DocumentationReport> {
+        let types = self.repository.load_all_types().await?;
+        Ok(documentation_report(&types))
+    }
+
+    /// Справка «Как работает вывод типов»: резолверы, аксиомы и матрица
+    /// присваиваний по действующей цепочке
+    pub async fn inference_reference(&self) -> InferenceReference {
+        self.resolution_service.inference_reference().await
     }
 
     /// Проверить здоровье системы
@@ -361,25 +805,579 @@ impl CentralTypeSystem {
         Ok(())
     }
 
+    /// Текущая конфигурация системы
+    pub async fn config(&self) -> CentralSystemConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Применить новые настройки без перезапуска.
+    ///
+    /// Перезагружаются только затронутые источники типов: смена справки или
+    /// версии платформы перечитывает платформенные типы, смена пути к
+    /// конфигурации — типы конфигурации. Настройки, зафиксированные при
+    /// создании сервисов, перечисляются в `restart_required`.
+    pub async fn update_config(&self, new_config: CentralSystemConfig) -> Result<ConfigUpdate> {
+        let old_config = self.config().await;
+        let mut update = ConfigUpdate {
+            diagnostics_changed: old_config.strictness != new_config.strictness,
+            inlay_hints_changed: old_config.inlay_hints != new_config.inlay_hints,
+            ..Default::default()
+        };
+        if old_config.resolver_chain != new_config.resolver_chain {
+            update.restart_required.push("resolver_chain");
+        }
+        if old_config.completion_groups != new_config.completion_groups {
+            update.restart_required.push("completion_groups");
+        }
+        if old_config.name_precedence != new_config.name_precedence {
+            update.restart_required.push("name_precedence");
+        }
+        if old_config.remote_repository_url != new_config.remote_repository_url {
+            update.restart_required.push("remote_repository_url");
+        }
+        if old_config.snapshot_path != new_config.snapshot_path {
+            update.restart_required.push("snapshot_path");
+        }
+        if old_config.type_store_path != new_config.type_store_path {
+            update.restart_required.push("type_store_path");
+        }
+        if old_config.read_only != new_config.read_only {
+            update.restart_required.push("read_only");
+        }
+        if old_config.telemetry_path != new_config.telemetry_path {
+            update.restart_required.push("telemetry_path");
+        }
+        if old_config.changelog_path != new_config.changelog_path {
+            update.restart_required.push("changelog_path");
+        }
+        if old_config.odata_composition_path != new_config.odata_composition_path {
+            update.restart_required.push("odata_composition_path");
+        }
+        if old_config.performance_settings.resolver_timeout_ms
+            != new_config.performance_settings.resolver_timeout_ms
+        {
+            update.restart_required.push("performance_settings.resolver_timeout_ms");
+        }
+
+        let platform_changed = old_config.html_path != new_config.html_path
+            || old_config.platform_version != new_config.platform_version;
+        let configuration_changed = old_config.configuration_path != new_config.configuration_path;
+        if old_config.platform_version != new_config.platform_version {
+            let features = LanguageFeatures::for_version(&new_config.platform_version);
+            self.lsp_interface.set_language_features(features).await;
+            update.diagnostics_changed = true;
+        }
+        let team_notes_changed = old_config.team_notes_path != new_config.team_notes_path;
+        let snippets_changed = old_config.snippets_path != new_config.snippets_path;
+        *self.config.write().await = new_config;
+        if team_notes_changed {
+            self.load_team_notes().await;
+        }
+        if snippets_changed {
+            self.load_snippets().await;
+        }
+        if configuration_changed {
+            self.load_role_index().await;
+            self.load_command_index().await;
+            self.load_metadata_values().await;
+        }
+
+        // В удалённом режиме типы загружает сервер, снимок только для чтения
+        let types_fixed = self.remote_repository.is_some() || self.snapshot_repository.is_some();
+        if !types_fixed && (platform_changed || configuration_changed) {
+            self.reload_types(platform_changed, configuration_changed)
+                .await?;
+            update.platform_reloaded = platform_changed;
+            update.configuration_reloaded = configuration_changed;
+            update.diagnostics_changed = true;
+        }
+        Ok(update)
+    }
+
+    /// Перечитать выбранные источники типов, сохранив остальные из репозитория
+    async fn reload_types(&self, platform: bool, configuration: bool) -> Result<()> {
+        info!(
+            "🔄 Перезагрузка типов: платформа={}, конфигурация={}",
+            platform, configuration
+        );
+        let existing = self.repository.load_all_types().await?;
+        let previous: Vec<(TypeOrigin, String)> = existing
+            .iter()
+            .map(|raw| (TypeOrigin::of(&raw.source), raw.russian_name.clone()))
+            .collect();
+        let (configuration_types, platform_types): (Vec<_>, Vec<_>) = existing
+            .into_iter()
+            .partition(|raw| matches!(raw.source, TypeSource::Configuration { .. }));
+
+        let mut all_types = if platform {
+            let html_path = self.config.read().await.html_path.clone();
+            self.load_artifact_types(StoreArtifact::Platform, &html_path)
+                .await?
+        } else {
+            platform_types
+        };
+        if configuration {
+            let configuration_path = self.config.read().await.configuration_path.clone();
+            if let Some(config_path) = &configuration_path {
+                all_types.extend(
+                    self.load_artifact_types(StoreArtifact::Configuration, config_path)
+                        .await?,
+                );
+            }
+        } else {
+            all_types.extend(configuration_types);
+        }
+
+        self.changelog.record(&all_types);
+        self.replace_types(previous, all_types).await
+    }
+
+    /// Заменить типы репозитория одним пакетом изменений (читатели не видят
+    /// пустого репозитория между очисткой и загрузкой) и сбросить зависящие
+    /// от них кеши; `previous` — типы до замены
+    async fn replace_types(
+        &self,
+        previous: Vec<(TypeOrigin, String)>,
+        types: Vec<RawTypeData>,
+    ) -> Result<()> {
+        let current: HashSet<(TypeOrigin, &str)> = types
+            .iter()
+            .map(|raw| (TypeOrigin::of(&raw.source), raw.russian_name.as_str()))
+            .collect();
+        let tombstones = previous
+            .into_iter()
+            .filter(|(origin, name)| !current.contains(&(*origin, name.as_str())))
+            .map(|(origin, name)| TypeTombstone::Type { origin, name })
+            .collect();
+        self.repository
+            .apply_delta(TypeDelta {
+                upserts: types,
+                tombstones,
+            })
+            .await?;
+        self.resolution_service.clear_cache().await;
+        self.lsp_service.clear_cache().await;
+        self.web_service.clear_cache().await;
+        self.update_system_metrics().await?;
+        Ok(())
+    }
+
+    // === ОТСЛЕЖИВАНИЕ ИСХОДНИКОВ ===
+
+    /// Следить за каталогом конфигурации и применять изменения исходников
+    /// (см. [`watch`]); отслеживание идёт, пока жив возвращённый наблюдатель
+    pub async fn watch_sources(self: &Arc<Self>) -> Result<SourceWatcher> {
+        let configuration_path = self.config.read().await.configuration_path.clone();
+        let root = configuration_path
+            .ok_or_else(|| anyhow::anyhow!("Не задан путь к конфигурации: отслеживать нечего"))?;
+        SourceWatcher::start(self.clone(), Path::new(&root))
+    }
+
+    /// Применить изменённые исходники: модули обновляются по одному, а при
+    /// изменении описаний метаданных заново разбираются только типы
+    /// конфигурации
+    pub async fn apply_source_changes(&self, changes: &SourceChanges) -> Result<SourceUpdate> {
+        let mut update = SourceUpdate::default();
+        // Модули, диагностики которых устарели; `None` — граф зависимостей
+        // ещё не построен, устарели все
+        let mut affected = Some(BTreeSet::new());
+        for path in &changes.modules {
+            // Удалённый модуль читается как отсутствующий
+            let text = crate::core::fs_utils::read_bsl_file(path).ok();
+            let file = path.to_string_lossy();
+            self.resolution_service
+                .update_module(&file, text.as_deref())
+                .await;
+            self.lsp_interface
+                .update_project_module(&file, text.as_deref())
+                .await;
+            let modules = self
+                .analysis_service
+                .update_module(&file, text.as_deref())
+                .await;
+            affected = affected.zip(modules).map(|(mut affected, modules)| {
+                affected.extend(modules);
+                affected
+            });
+            update.modules += 1;
+        }
+
+        // В удалённом режиме типы загружает сервер, снимок только для чтения
+        let types_fixed = self.remote_repository.is_some() || self.snapshot_repository.is_some();
+        let configuration_path = self.config.read().await.configuration_path.clone();
+        if !changes.metadata.is_empty() && !types_fixed {
+            if let Some(config_path) = &configuration_path {
+                update.changed_types = self.reload_configuration_types(config_path).await?;
+                update.configuration_reloaded = true;
+                self.load_role_index().await;
+                self.load_command_index().await;
+                self.load_metadata_values().await;
+            }
+        }
+
+        // Кеши типов сбрасывает уведомление репозитория, а ответы LSP
+        // зависят и от сигнатур функций изменённых модулей
+        if update.modules > 0 {
+            self.lsp_service.clear_cache().await;
+        }
+        // Диагностики модулей, не зависящих от изменённых, остаются в силе
+        match affected {
+            _ if update.is_empty() => {}
+            Some(modules) if !update.configuration_reloaded => {
+                update.affected_modules = modules.len();
+                self.lsp_interface
+                    .invalidate_module_diagnostics(&modules)
+                    .await;
+            }
+            _ => self.lsp_interface.invalidate_document_diagnostics().await,
+        }
+        Ok(update)
+    }
+
+    /// Разобрать заново типы конфигурации; в репозиторий записываются только
+    /// добавленные и изменённые типы, а исчезнувшие удаляются надгробиями.
+    /// Возвращает число изменённых типов
+    async fn reload_configuration_types(&self, config_path: &str) -> Result<usize> {
+        let mut all_types: Vec<_> = self
+            .repository
+            .load_all_types()
+            .await?
+            .into_iter()
+            .filter(|raw| !matches!(raw.source, TypeSource::Configuration { .. }))
+            .collect();
+        all_types.extend(
+            self.load_artifact_types(StoreArtifact::Configuration, config_path)
+                .await?,
+        );
+
+        // Правка формы или макета не меняет типов
+        let Some(entry) = self.changelog.record(&all_types) else {
+            return Ok(0);
+        };
+        info!("🔄 Типы конфигурации изменились: {}", entry.summary());
+        self.lsp_interface.index_repository_types(&all_types).await;
+        let changed: HashSet<&str> = entry
+            .added
+            .iter()
+            .map(|added| added.id.as_str())
+            .chain(entry.changed.iter().map(|changed| changed.id.as_str()))
+            .collect();
+        let tombstones = entry
+            .removed
+            .iter()
+            .map(|removed| TypeTombstone::Type {
+                origin: removed.source.parse().unwrap_or(TypeOrigin::Configuration),
+                name: removed.name.clone(),
+            })
+            .collect();
+        let upserts = all_types
+            .into_iter()
+            .filter(|raw| changed.contains(raw.id.as_str()))
+            .collect();
+        // Кеши сервисов сбрасываются по уведомлению репозитория
+        self.repository
+            .apply_delta(TypeDelta {
+                upserts,
+                tombstones,
+            })
+            .await?;
+        self.update_system_metrics().await?;
+        Ok(entry.added.len() + entry.removed.len() + entry.changed.len())
+    }
+
+    // === ПЛАНОВЫЕ РАБОТЫ ===
+
+    /// Выполнить плановые задачи, срок которых подошёл по расписанию
+    /// `maintenance`; вызывается планировщиком сервера раз в
+    /// `MAINTENANCE_TICK`
+    pub async fn run_due_maintenance(&self) -> Vec<MaintenanceRun> {
+        let settings = self.config.read().await.maintenance.clone();
+        if !settings.is_enabled() {
+            return Vec::new();
+        }
+        let now = chrono::Local::now();
+        let mut runs = Vec::new();
+        for task in MaintenanceTask::ALL {
+            let last_run = self
+                .maintenance_runs
+                .read()
+                .await
+                .get(&task)
+                .map_or(self.started_at, |run| run.started_at);
+            if settings.is_due(task, last_run, now) {
+                runs.push(self.run_maintenance(task).await);
+            }
+        }
+        runs
+    }
+
+    /// Выполнить плановую задачу сейчас
+    pub async fn run_maintenance(&self, task: MaintenanceTask) -> MaintenanceRun {
+        let started_at = chrono::Local::now();
+        let start = std::time::Instant::now();
+        let result = match task {
+            MaintenanceTask::Reindex => self.reindex().await,
+            MaintenanceTask::Warmup => Ok(self.warm_up().await),
+            MaintenanceTask::Refresh => Ok(self.refresh_providers().await),
+        };
+        let duration = start.elapsed();
+        self.telemetry
+            .record_timing(&format!("maintenance.{}", task.name()), duration);
+        let run = match result {
+            Ok(summary) => {
+                info!("🗓️ Плановая задача {}: {}", task.name(), summary);
+                MaintenanceRun {
+                    task,
+                    started_at,
+                    duration_ms: duration.as_millis() as u64,
+                    summary,
+                    error: None,
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "⚠️ Плановая задача {} завершилась ошибкой: {:#}",
+                    task.name(),
+                    e
+                );
+                MaintenanceRun {
+                    task,
+                    started_at,
+                    duration_ms: duration.as_millis() as u64,
+                    summary: String::new(),
+                    error: Some(format!("{:#}", e)),
+                }
+            }
+        };
+        self.maintenance_runs
+            .write()
+            .await
+            .insert(task, run.clone());
+        run
+    }
+
+    /// Расписание и последние запуски плановых задач
+    pub async fn maintenance_status(&self) -> MaintenanceStatus {
+        let runs = self.maintenance_runs.read().await;
+        MaintenanceStatus {
+            settings: self.config.read().await.maintenance.clone(),
+            runs: MaintenanceTask::ALL
+                .iter()
+                .filter_map(|task| runs.get(task).cloned())
+                .collect(),
+        }
+    }
+
+    // === КОМАНДЫ СЕРВЕРА ===
+
+    /// Выполнить команду палитры; аргументы уже проверены
+    /// `ServerCommand::validate`
+    pub async fn execute_command(
+        &self,
+        command: ServerCommand,
+        arguments: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<CommandOutcome> {
+        let started_at = chrono::Local::now();
+        let start = std::time::Instant::now();
+        let (summary, result) = match command {
+            ServerCommand::ReloadProvider => {
+                let provider = string_argument(arguments, "provider").unwrap_or("all");
+                match provider {
+                    "roles" => self.load_role_index().await,
+                    "commands" => self.load_command_index().await,
+                    "metadata_values" => self.load_metadata_values().await,
+                    "snippets" => self.load_snippets().await,
+                    "team_notes" => self.load_team_notes().await,
+                    _ => {
+                        self.refresh_providers().await;
+                    }
+                }
+                (
+                    format!("провайдер {} перезагружен", provider),
+                    serde_json::Value::Null,
+                )
+            }
+            ServerCommand::RebuildIndex => {
+                let run = self.run_maintenance(MaintenanceTask::Reindex).await;
+                if let Some(error) = run.error {
+                    anyhow::bail!(error);
+                }
+                (run.summary, serde_json::Value::Null)
+            }
+            ServerCommand::ExportSubtree => {
+                let category = string_argument(arguments, "category").unwrap_or_default();
+                let path: Vec<&str> = category
+                    .split('/')
+                    .map(str::trim)
+                    .filter(|segment| !segment.is_empty())
+                    .collect();
+                anyhow::ensure!(!path.is_empty(), "Не задана категория");
+                let limit = arguments
+                    .get("limit")
+                    .and_then(serde_json::Value::as_u64)
+                    .map_or(usize::MAX, |limit| limit as usize);
+                let types: Vec<RawTypeData> = self
+                    .repository
+                    .load_all_types()
+                    .await?
+                    .into_iter()
+                    .filter(|raw| {
+                        raw.category_path.len() >= path.len()
+                            && raw.category_path.iter().zip(&path).all(|(a, b)| a == b)
+                    })
+                    .take(limit)
+                    .collect();
+                anyhow::ensure!(!types.is_empty(), "В категории '{}' нет типов", category);
+                (
+                    format!("выгружено {} типов категории {}", types.len(), category),
+                    serde_json::json!({ "category": path, "types": types }),
+                )
+            }
+            ServerCommand::RunAnalysis => {
+                let project = string_argument(arguments, "project").unwrap_or_default();
+                let analysis = self
+                    .analysis_service
+                    .analyze_project(Path::new(project))
+                    .await?;
+                (
+                    format!(
+                        "проанализировано {} файлов, ошибок типов: {}",
+                        analysis.analyzed_files,
+                        analysis.type_errors.len()
+                    ),
+                    serde_json::json!({
+                        "project": analysis.project_path,
+                        "total_files": analysis.total_files,
+                        "analyzed_files": analysis.analyzed_files,
+                        "type_errors": analysis.type_errors.len(),
+                        "coverage_percentage": analysis.coverage_report.coverage_percentage,
+                        "analysis_time_ms": analysis.analysis_time.as_millis() as u64,
+                    }),
+                )
+            }
+        };
+        let duration = start.elapsed();
+        self.telemetry
+            .record_timing(&format!("command.{}", command.id()), duration);
+        info!("🧭 Команда {}: {}", command.id(), summary);
+        Ok(CommandOutcome {
+            command,
+            started_at,
+            duration_ms: duration.as_millis() as u64,
+            summary,
+            result,
+        })
+    }
+
+    /// Перечитать справку и конфигурацию; в удалённом режиме и со снимком
+    /// индексы строит их владелец
+    async fn reindex(&self) -> Result<String> {
+        if self.remote_repository.is_some() || self.snapshot_repository.is_some() {
+            return Ok("индексы строит центральный сервер или сборка снимка".to_string());
+        }
+        self.reload_types(true, true).await?;
+        let total = self.repository.load_all_types().await?.len();
+        Ok(format!("индексы перестроены, {} типов", total))
+    }
+
+    /// Прогреть подробности самых запрашиваемых типов и глобальные
+    /// автодополнения по их первым буквам
+    async fn warm_up(&self) -> String {
+        let limit = self.config.read().await.maintenance.warmup_top_types;
+        let mut hot_types = self.web_service.popular_types(limit).await;
+        if hot_types.is_empty() {
+            hot_types = DEFAULT_HOT_TYPES
+                .iter()
+                .take(limit)
+                .map(|name| name.to_string())
+                .collect();
+        }
+        let types = self.web_service.warm_type_details(&hot_types).await;
+        let prefixes = self
+            .lsp_service
+            .warm_completions(&warmup_prefixes(&hot_types))
+            .await;
+        format!(
+            "прогрето {} типов, {} префиксов автодополнения",
+            types, prefixes
+        )
+    }
+
+    /// Перечитать роли, команды, константы, заметки команды и шаблоны
+    async fn refresh_providers(&self) -> String {
+        self.load_role_index().await;
+        self.load_command_index().await;
+        self.load_metadata_values().await;
+        self.load_team_notes().await;
+        self.load_snippets().await;
+        "провайдеры обновлены".to_string()
+    }
+
     // === ПРИВАТНЫЕ МЕТОДЫ ИНИЦИАЛИЗАЦИИ ===
 
     async fn initialize_data_layer(&self) -> Result<()> {
         info!("🔧 Инициализация Data Layer...");
 
+        // В удалённом режиме индексы уже построены на сервере
+        if let Some(remote) = &self.remote_repository {
+            let stats = remote.connect().await?;
+            info!(
+                "🌐 Подключен удалённый репозиторий {} ({} типов)",
+                remote.base_url(),
+                stats.total_types
+            );
+            return Ok(());
+        }
+
+        // Снимок уже содержит разобранные типы платформы (и обычно конфигурации)
+        if let Some(snapshot) = &self.snapshot_repository {
+            let snapshot = snapshot.snapshot();
+            info!(
+                "🗺️ Типы загружены из снимка {} ({} типов)",
+                snapshot.path().display(),
+                snapshot.len()
+            );
+            // Снимок только с платформой (импортированный артефакт) —
+            // конфигурацию разбираем в локальный слой
+            let configuration_path = self.config.read().await.configuration_path.clone();
+            if let (0, Some(config_path)) =
+                (snapshot.stats().configuration_types, &configuration_path)
+            {
+                let config_types = self.load_configuration_types(config_path).await?;
+                info!("✅ Загружено {} конфигурационных типов", config_types.len());
+                self.repository.save_types(config_types).await?;
+            }
+            self.changelog.record(&self.repository.load_all_types().await?);
+            return Ok(());
+        }
+
         // Загружаем платформенные типы из HTML
-        let platform_types = self.load_platform_types().await?;
+        let html_path = self.config.read().await.html_path.clone();
+        let platform_types = self
+            .load_artifact_types(StoreArtifact::Platform, &html_path)
+            .instrument(info_span!("parse", source = "platform"))
+            .await?;
         info!("✅ Загружено {} платформенных типов", platform_types.len());
 
         // Загружаем конфигурационные типы если указан путь
         let mut all_types = platform_types;
-        if let Some(config_path) = &self.config.configuration_path {
-            let config_types = self.load_configuration_types(config_path).await?;
+        let configuration_path = self.config.read().await.configuration_path.clone();
+        if let Some(config_path) = &configuration_path {
+            let config_types = self
+                .load_artifact_types(StoreArtifact::Configuration, config_path)
+                .instrument(info_span!("parse", source = "configuration"))
+                .await?;
             info!("✅ Загружено {} конфигурационных типов", config_types.len());
             all_types.extend(config_types);
         }
 
-        // Сохраняем в репозиторий
-        self.repository.save_types(all_types).await?;
+        // Сравниваем с прошлой загрузкой и сохраняем в репозиторий
+        self.changelog.record(&all_types);
+        let count = all_types.len();
+        self.repository
+            .save_types(all_types)
+            .instrument(info_span!("index", types = count))
+            .await?;
 
         info!("✅ Data Layer инициализирован");
         Ok(())
@@ -389,7 +1387,8 @@ impl CentralTypeSystem {
         info!("🔧 Инициализация Domain Layer...");
 
         // Инициализируем резолверы в TypeResolutionService (кеши, tree-sitter)
-        if let Err(e) = self.resolution_service.initialize().await {
+        let resolvers = self.resolution_service.initialize();
+        if let Err(e) = resolvers.instrument(info_span!("resolve.initialize")).await {
             warn!("⚠️ Инициализация резолверов завершилась с ошибкой: {}", e);
         }
 
@@ -403,15 +1402,54 @@ impl CentralTypeSystem {
         // LSP Service готов (использует Domain Layer)
         // Web Service готов (использует Domain Layer)
         // Analysis Service готов (использует Domain Layer)
+        self.spawn_cache_invalidation();
 
         info!("✅ Application Layer инициализирован");
         Ok(())
     }
 
+    /// Сбрасывать кеши сервисов по изменениям репозитория (см.
+    /// [`TypeRepository::subscribe`]): изменённый объект метаданных сбрасывает
+    /// только записи своих типов. Задача завершается вместе с системой.
+    fn spawn_cache_invalidation(&self) {
+        let mut changes = self.repository.subscribe();
+        let resolution_service = Arc::downgrade(&self.resolution_service);
+        let lsp_service = Arc::downgrade(&self.lsp_service);
+        let web_service = Arc::downgrade(&self.web_service);
+        tokio::spawn(async move {
+            loop {
+                let change = match changes.recv().await {
+                    Ok(change) => change,
+                    // Пропущенные изменения неизвестны: сбрасывается всё
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => RepositoryChange {
+                        cleared: true,
+                        ..Default::default()
+                    },
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                let (Some(resolution_service), Some(lsp_service), Some(web_service)) = (
+                    resolution_service.upgrade(),
+                    lsp_service.upgrade(),
+                    web_service.upgrade(),
+                ) else {
+                    break;
+                };
+                resolution_service.invalidate_types(&change).await;
+                lsp_service.invalidate_types(&change).await;
+                web_service.invalidate_types(&change).await;
+            }
+        });
+    }
+
     async fn initialize_presentation_layer(&self) -> Result<()> {
         info!("🔧 Инициализация Presentation Layer...");
 
         // Интерфейсы готовы (используют Application Layer)
+        self.load_role_index().await;
+        self.load_command_index().await;
+        self.load_metadata_values().await;
+        self.load_team_notes().await;
+        self.load_snippets().await;
 
         info!("✅ Presentation Layer инициализирован");
         Ok(())
@@ -419,17 +1457,156 @@ impl CentralTypeSystem {
 
     // === ЗАГРУЗКА ДАННЫХ ===
 
+    /// Загрузить роли конфигурации для проверки ссылок на роли в коде.
+    /// Роли читаются локально и в удалённом режиме: диагностики строятся
+    /// по открытому документу.
+    async fn load_role_index(&self) {
+        let configuration_path = self.config.read().await.configuration_path.clone();
+        let roles = match &configuration_path {
+            Some(path) => match RoleIndex::load(Path::new(path)) {
+                Ok(roles) => {
+                    info!("🔐 Загружено {} ролей конфигурации", roles.len());
+                    roles
+                }
+                Err(e) => {
+                    warn!("⚠️ Роли конфигурации {} не загружены: {:#}", path, e);
+                    RoleIndex::default()
+                }
+            },
+            None => RoleIndex::default(),
+        };
+        self.lsp_interface.set_role_index(roles).await;
+    }
+
+    /// Загрузить команды конфигурации: модули команд для перехода к
+    /// обработчику из `Метаданные.ОбщиеКоманды.Имя`
+    async fn load_command_index(&self) {
+        let configuration_path = self.config.read().await.configuration_path.clone();
+        let commands = match &configuration_path {
+            Some(path) => match CommandIndex::load(Path::new(path)) {
+                Ok(commands) => {
+                    info!("🔘 Загружено {} команд конфигурации", commands.len());
+                    commands
+                }
+                Err(e) => {
+                    warn!("⚠️ Команды конфигурации {} не загружены: {:#}", path, e);
+                    CommandIndex::default()
+                }
+            },
+            None => CommandIndex::default(),
+        };
+        self.lsp_interface.set_command_index(commands).await;
+    }
+
+    /// Загрузить константы и параметры сеанса: их обращения разрешаются в
+    /// объявленные в конфигурации типы значений
+    async fn load_metadata_values(&self) {
+        let configuration_path = self.config.read().await.configuration_path.clone();
+        let values = match &configuration_path {
+            Some(path) => match MetadataValueIndex::load(Path::new(path)) {
+                Ok(values) => {
+                    info!("📌 Загружено {} констант и параметров сеанса", values.len());
+                    values
+                }
+                Err(e) => {
+                    warn!("⚠️ Константы и параметры сеанса {} не загружены: {:#}", path, e);
+                    MetadataValueIndex::default()
+                }
+            },
+            None => MetadataValueIndex::default(),
+        };
+        self.resolution_service.set_metadata_values(values).await;
+    }
+
+    /// Загрузить шаблоны автодополнения: встроенные и из файла пользователя
+    async fn load_snippets(&self) {
+        let snippets_path = self.config.read().await.snippets_path.clone();
+        let snippets = match &snippets_path {
+            Some(path) => match SnippetProvider::with_user_file(Path::new(path)) {
+                Ok(snippets) => {
+                    info!("🧩 Загружено {} шаблонов автодополнения", snippets.len());
+                    snippets
+                }
+                Err(e) => {
+                    warn!("⚠️ Шаблоны {} не загружены: {:#}", path, e);
+                    SnippetProvider::builtin()
+                }
+            },
+            None => SnippetProvider::builtin(),
+        };
+        self.lsp_service.set_snippets(snippets).await;
+    }
+
+    /// Загрузить заметки команды к типам для подсказки при наведении
+    async fn load_team_notes(&self) {
+        let notes_path = self.config.read().await.team_notes_path.clone();
+        let Some(path) = notes_path else {
+            self.lsp_interface
+                .set_team_notes(TeamNotesOverlay::default())
+                .await;
+            return;
+        };
+        let notes = match TeamNotesOverlay::load(Path::new(&path), self.write_guard.clone()) {
+            Ok(notes) => {
+                info!("📝 Загружены заметки команды к {} типам", notes.len());
+                notes
+            }
+            Err(e) => {
+                warn!("⚠️ Заметки команды {} не загружены: {:#}", path, e);
+                TeamNotesOverlay::default()
+            }
+        };
+        self.lsp_interface.set_team_notes(notes).await;
+    }
+
+    /// Типы источника: через хранилище на диске, если оно задано (источник
+    /// разбирается заново только при изменении его файлов), иначе разбором
+    async fn load_artifact_types(
+        &self,
+        artifact: StoreArtifact,
+        source: &str,
+    ) -> Result<Vec<RawTypeData>> {
+        let parse = move || async move {
+            match artifact {
+                StoreArtifact::Platform => self.load_platform_types().await,
+                StoreArtifact::Configuration => self.load_configuration_types(source).await,
+            }
+        };
+        let Some(store) = &self.file_repository else {
+            return parse().await;
+        };
+
+        let platform_version = self.config.read().await.platform_version.clone();
+        let fingerprint = source_fingerprint(Path::new(source), &[&platform_version])?;
+        let loaded = store.load_artifact(artifact, &fingerprint, parse).await?;
+        if loaded.reused {
+            info!(
+                "💾 Типы источника {} взяты из хранилища {} без разбора",
+                artifact.as_str(),
+                store.location()
+            );
+        } else {
+            info!(
+                "💾 Источник {} изменился: типы разобраны заново и сохранены",
+                artifact.as_str()
+            );
+        }
+        Ok(loaded.types)
+    }
+
     async fn load_platform_types(&self) -> Result<Vec<RawTypeData>> {
         info!("📄 Загрузка платформенных типов из HTML...");
 
         // Используем существующий PlatformTypeResolver для загрузки данных
         let platform_resolver = crate::core::platform_resolver::PlatformTypeResolver::new();
         let platform_globals = platform_resolver.get_platform_globals();
+        let platform_version = self.config.read().await.platform_version.clone();
 
         // Конвертируем TypeResolution в RawTypeData
         let mut raw_types = Vec::new();
         for (name, resolution) in platform_globals {
-            let raw_type = self.convert_resolution_to_raw_data(name, resolution)?;
+            let raw_type =
+                self.convert_resolution_to_raw_data(name, resolution, &platform_version)?;
             raw_types.push(raw_type);
         }
 
@@ -449,14 +1626,40 @@ impl CentralTypeSystem {
                 crate::core::types::ConcreteType::Configuration(config),
             ) = &resolution.result
             {
+                // Синонимы, комментарии и флаги реквизитов — из описания
+                // объекта; одноимённые регистры разных видов различаются файлом
+                let definition = resolution.definition_path();
+                let discovered = guided_parser
+                    .get_all_discovered()
+                    .values()
+                    .find(|metadata| {
+                        metadata.kind == config.kind
+                            && metadata.name == config.name
+                            && (definition.is_none() || definition == metadata.file_path.to_str())
+                    });
+                // Тег вида различает регистры сведений, накопления и бухгалтерии
+                let mut category_path = vec![format!("{:?}", config.kind)];
+                if let Some(metadata) =
+                    discovered.filter(|metadata| !category_path.contains(&metadata.metadata_tag))
+                {
+                    category_path.push(metadata.metadata_tag.clone());
+                }
+                let attribute = |name: &str| {
+                    discovered.and_then(|metadata| {
+                        metadata.attributes.iter().find(|attr| attr.name == name)
+                    })
+                };
                 let raw_type = RawTypeData {
                     id: format!("{:?}.{}", config.kind, config.name),
                     russian_name: config.name.clone(),
-                    english_name: config.name.clone(), // TODO: получить английское имя
+                    english_name: english_name(
+                        &config.name,
+                        discovered.and_then(|metadata| metadata.english_synonym.as_deref()),
+                    ),
                     source: TypeSource::Configuration {
                         config_version: "8.3".to_string(),
                     },
-                    category_path: vec![format!("{:?}", config.kind)],
+                    category_path,
                     methods: Vec::new(), // TODO: конвертировать методы
                     properties: config
                         .attributes
@@ -464,9 +1667,24 @@ impl CentralTypeSystem {
                         .map(|attr| super::data::RawPropertyData {
                             name: attr.name.clone(),
                             type_name: attr.type_.clone(),
-                            is_readonly: false,          // TODO: определить из XML
-                            description: "".to_string(), // TODO: получить описание
+                            is_readonly: attribute(&attr.name).is_some_and(|info| info.read_only),
+                            description: attribute(&attr.name)
+                                .map(|info| info.description())
+                                .unwrap_or_default(),
                         })
+                        // Табличные части — свойства с типом табличной части
+                        .chain(config.tabular_sections.iter().map(|section| {
+                            super::data::RawPropertyData {
+                                name: section.name.clone(),
+                                type_name: tabular_section_type(
+                                    &format!("{:?}", config.kind),
+                                    &config.name,
+                                    &section.name,
+                                ),
+                                is_readonly: true,
+                                description: section.synonym.clone().unwrap_or_default(),
+                            }
+                        }))
                         .collect(),
                     documentation: format!("Конфигурационный объект: {}", config.name),
                     examples: vec![format!("объект = {}.СоздатьЭлемент();", config.name)],
@@ -480,7 +1698,10 @@ impl CentralTypeSystem {
                         })
                         .collect(),
                     parse_metadata: ParseMetadata {
-                        file_path: format!("{}.xml", config.name),
+                        file_path: resolution
+                            .definition_path()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| format!("{}.xml", config.name)),
                         line: 0,
                         column: 0,
                     },
@@ -489,6 +1710,132 @@ impl CentralTypeSystem {
             }
         }
 
+        // Общие реквизиты — реквизиты каждого объекта своего состава (до
+        // записей регистров: у тех поля из описания регистра)
+        match common_attributes::load(Path::new(config_path)) {
+            Ok(attributes) => {
+                for metadata in guided_parser.get_all_discovered().values() {
+                    let category = format!("{:?}", metadata.kind);
+                    let applied: Vec<_> = attributes
+                        .iter()
+                        .filter(|attr| attr.applies_to(&metadata.metadata_tag, &metadata.name))
+                        .map(|attr| super::data::RawPropertyData {
+                            name: attr.name.clone(),
+                            type_name: attr.types.join(","),
+                            is_readonly: false,
+                            description: attr.description(),
+                        })
+                        .collect();
+                    if applied.is_empty() {
+                        continue;
+                    }
+                    for raw in raw_types.iter_mut().filter(|raw| {
+                        raw.russian_name == metadata.name
+                            && raw.category_path.contains(&metadata.metadata_tag)
+                            && raw.category_path.contains(&category)
+                    }) {
+                        raw.properties.extend(applied.iter().cloned());
+                    }
+                }
+            }
+            Err(e) => warn!("⚠️ Общие реквизиты не загружены: {:#}", e),
+        }
+
+        // Коллекции движений документов: наборы записей регистров из RegisterRecords
+        for metadata in guided_parser.get_all_discovered().values() {
+            if metadata.register_records.is_empty() {
+                continue;
+            }
+            raw_types.push(movements_raw_type(
+                &metadata.name,
+                &metadata.register_records,
+                &metadata.file_path.to_string_lossy(),
+            ));
+        }
+
+        // Менеджеры записи, наборы записей и записи регистров с полями из
+        // описания регистра
+        let mut registers = HashSet::new();
+        let mut records = Vec::new();
+        for raw in &raw_types {
+            let Some(kind) = raw
+                .category_path
+                .iter()
+                .find_map(|category| RegisterKind::from_metadata_tag(category))
+            else {
+                continue;
+            };
+            // У каждого фасета свой тип с теми же полями
+            if !registers.insert((kind, raw.russian_name.clone())) {
+                continue;
+            }
+            records.extend(register_raw_types(
+                kind,
+                &raw.russian_name,
+                &raw.properties,
+                &raw.parse_metadata.file_path,
+            ));
+        }
+        raw_types.extend(records);
+
+        // Значения перечислений — свойства типа перечисления
+        for metadata in guided_parser.get_all_discovered().values() {
+            if metadata.enum_values.is_empty() {
+                continue;
+            }
+            let category = format!("{:?}", metadata.kind);
+            if let Some(raw) = raw_types.iter_mut().find(|raw| {
+                raw.russian_name == metadata.name && raw.category_path.contains(&category)
+            }) {
+                raw.properties
+                    .extend(enum_value_properties(&metadata.name, &metadata.enum_values));
+            }
+        }
+
+        // Предопределённые элементы — отдельный тип со списком элементов
+        raw_types.extend(
+            guided_parser
+                .get_all_discovered()
+                .values()
+                .filter(|metadata| !metadata.predefined.is_empty())
+                .filter_map(|metadata| {
+                    predefined_raw_type(
+                        &format!("{:?}", metadata.kind),
+                        &metadata.name,
+                        &metadata.predefined,
+                        &metadata.file_path.to_string_lossy(),
+                    )
+                }),
+        );
+
+        // Табличные части и их строки с колонками из описания объекта
+        for metadata in guided_parser.get_all_discovered().values() {
+            let category = format!("{:?}", metadata.kind);
+            for section in &metadata.tabular_sections {
+                let columns: Vec<_> = section
+                    .attributes
+                    .iter()
+                    .map(|column| super::data::RawPropertyData {
+                        name: column.name.clone(),
+                        type_name: column.type_definition.clone(),
+                        is_readonly: column.read_only,
+                        description: column.description(),
+                    })
+                    .collect();
+                raw_types.extend(tabular_section_raw_types(
+                    &tabular_section_type(&category, &metadata.name, &section.name),
+                    &columns,
+                    &metadata.file_path.to_string_lossy(),
+                ));
+            }
+        }
+
+        // Внешние источники данных: менеджеры таблиц с полями и функции
+        match external_data_sources::load(Path::new(config_path)) {
+            Ok(sources) => raw_types.extend(external_sources_raw_types(&sources)),
+            Err(e) => warn!("⚠️ Внешние источники данных не загружены: {:#}", e),
+        }
+
         Ok(raw_types)
     }
 
@@ -496,12 +1843,13 @@ impl CentralTypeSystem {
         &self,
         name: &str,
         resolution: &TypeResolution,
+        platform_version: &str,
     ) -> Result<RawTypeData> {
         let source = match &resolution.result {
             crate::core::types::ResolutionResult::Concrete(
                 crate::core::types::ConcreteType::Platform(_),
             ) => TypeSource::Platform {
-                version: "8.3".to_string(),
+                version: platform_version.to_string(),
             },
             crate::core::types::ResolutionResult::Concrete(
                 crate::core::types::ConcreteType::Configuration(_),
@@ -509,7 +1857,7 @@ impl CentralTypeSystem {
                 config_version: "8.3".to_string(),
             },
             _ => TypeSource::Platform {
-                version: "8.3".to_string(),
+                version: platform_version.to_string(),
             },
         };
 
@@ -595,7 +1943,7 @@ impl CentralTypeSystem {
         state.progress_percent = percent;
         state.current_operation = operation.to_string();
 
-        if self.config.verbose_logging {
+        if self.config.read().await.verbose_logging {
             info!("📊 [{:3}%] {}", percent, operation);
         }
     }
@@ -718,25 +2066,457 @@ impl CentralTypeSystem {
     }
 }
 
+/// Префикс переменных окружения конфигурации
+pub const ENV_PREFIX: &str = "BSL_";
+
+/// Секция настроек расширения редактора
+pub const EDITOR_SETTINGS_SECTION: &str = "bslAnalyzer";
+
+/// Настройки редактора → переменные окружения (без префикса `BSL_`)
+const EDITOR_SETTINGS: &[(&str, &str)] = &[
+    ("configurationPath", "CONFIGURATION_PATH"),
+    ("platformVersion", "PLATFORM_VERSION"),
+    ("platformDocsArchive", "HTML_PATH"),
+    ("strictness", "STRICTNESS"),
+    ("inlayVariableTypes", "INLAY_VARIABLE_TYPES"),
+    ("inlayParameterNames", "INLAY_PARAMETER_NAMES"),
+    ("inlayMinCertainty", "INLAY_MIN_CERTAINTY"),
+    ("formatKeywordCase", "FORMAT_KEYWORD_CASE"),
+    ("formatIndent", "FORMAT_INDENT"),
+    ("formatIndentSize", "FORMAT_INDENT_SIZE"),
+    ("formatOperatorSpacing", "FORMAT_OPERATOR_SPACING"),
+    ("snippetsFile", "SNIPPETS"),
+];
+
+impl CentralSystemConfig {
+    /// Собрать конфигурацию из окружения.
+    ///
+    /// Если задана `BSL_SETTINGS_FILE`, сначала читается JSON-файл, затем
+    /// поверх применяются отдельные переменные `BSL_*` (см. `apply_env_overrides`).
+    pub fn from_env() -> Result<Self> {
+        let mut config = match std::env::var(format!("{}SETTINGS_FILE", ENV_PREFIX)) {
+            Ok(path) if !path.is_empty() => Self::from_file(Path::new(&path))?,
+            _ => Self::default(),
+        };
+        config.apply_env_overrides()?;
+        Ok(config)
+    }
+
+    /// Применить переопределения из переменных окружения
+    ///
+    /// | Переменная | Поле |
+    /// |---|---|
+    /// | `BSL_HTML_PATH` | `html_path` |
+    /// | `BSL_CONFIGURATION_PATH` | `configuration_path` |
+    /// | `BSL_ODATA_COMPOSITION` | `odata_composition_path` |
+    /// | `BSL_VERBOSE` | `verbose_logging` |
+    /// | `BSL_HEADLESS` | `headless` |
+    /// | `BSL_CACHE_TTL_SECONDS` | `cache_settings.cache_ttl_seconds` |
+    /// | `BSL_MAX_CACHE_SIZE` | `cache_settings.max_cache_size` |
+    /// | `BSL_ANALYSIS_CACHE` | `cache_settings.analysis_cache_path` |
+    /// | `BSL_PARSER_THREADS` | `performance_settings.max_parser_threads` |
+    /// | `BSL_LSP_TIMEOUT_MS` | `performance_settings.lsp_response_timeout_ms` |
+    /// | `BSL_WEB_TIMEOUT_MS` | `performance_settings.web_request_timeout_ms` |
+    /// | `BSL_RESOLVER_TIMEOUT_MS` | `performance_settings.resolver_timeout_ms` |
+    /// | `BSL_WEB_WORKERS` | `performance_settings.web_worker_threads` |
+    /// | `BSL_WEB_BIND` | `web_server.bind_address` |
+    /// | `BSL_WEB_PORT` | `web_server.port` |
+    /// | `BSL_TLS_CERT` / `BSL_TLS_KEY` | `web_server.tls_cert_path` / `tls_key_path` |
+    /// | `BSL_SHUTDOWN_GRACE_SECS` | `web_server.shutdown_grace_period_secs` |
+    /// | `BSL_GRPC_PORT` | `web_server.grpc_port` |
+    /// | `BSL_WEB_PROJECTS` | `web_server.projects` (`имя=путь` через запятую) |
+    /// | `BSL_REMOTE_REPOSITORY` | `remote_repository_url` |
+    /// | `BSL_SNAPSHOT` | `snapshot_path` |
+    /// | `BSL_TYPE_STORE` | `type_store_path` |
+    /// | `BSL_READ_ONLY` | `read_only` |
+    /// | `BSL_TELEMETRY` | `telemetry_path` |
+    /// | `BSL_CHANGELOG` | `changelog_path` |
+    /// | `BSL_TEAM_NOTES` | `team_notes_path` |
+    /// | `BSL_SNIPPETS` | `snippets_path` |
+    /// | `BSL_RESOLVER_ORDER` | `resolver_chain.order` (через запятую) |
+    /// | `BSL_DISABLED_RESOLVERS` | `resolver_chain.disabled` (через запятую) |
+    /// | `BSL_RESOLVER_POLICY` | `resolver_chain.policy` |
+    /// | `BSL_COMPLETION_GROUPS` | `completion_groups.order` (через запятую) |
+    /// | `BSL_NAME_PRECEDENCE` | `name_precedence.order` (через запятую) |
+    /// | `BSL_PLATFORM_VERSION` | `platform_version` |
+    /// | `BSL_STRICTNESS` | `strictness` |
+    /// | `BSL_INLAY_VARIABLE_TYPES` | `inlay_hints.variable_types` |
+    /// | `BSL_INLAY_PARAMETER_NAMES` | `inlay_hints.parameter_names` |
+    /// | `BSL_INLAY_MIN_CERTAINTY` | `inlay_hints.min_certainty` |
+    /// | `BSL_FORMAT_KEYWORD_CASE` | `formatting.keyword_case` |
+    /// | `BSL_FORMAT_INDENT` | `formatting.indent` |
+    /// | `BSL_FORMAT_INDENT_SIZE` | `formatting.indent_size` |
+    /// | `BSL_FORMAT_OPERATOR_SPACING` | `formatting.operator_spacing` |
+    /// | `BSL_REINDEX_INTERVAL_SECS` | `maintenance.reindex_interval_secs` |
+    /// | `BSL_WARMUP_INTERVAL_SECS` | `maintenance.warmup_interval_secs` |
+    /// | `BSL_REFRESH_INTERVAL_SECS` | `maintenance.refresh_interval_secs` |
+    /// | `BSL_MAINTENANCE_WINDOW` | `maintenance.off_hours` (`02:00-05:00`) |
+    /// | `BSL_WARMUP_TOP_TYPES` | `maintenance.warmup_top_types` |
+    pub fn apply_env_overrides(&mut self) -> Result<()> {
+        self.apply_overrides(|name| std::env::var(format!("{}{}", ENV_PREFIX, name)).ok())
+    }
+
+    /// Применить переопределения из произвольного источника ключ → значение
+    /// (ключи без префикса `BSL_`)
+    fn apply_overrides(&mut self, get: impl Fn(&str) -> Option<String>) -> Result<()> {
+        fn parse<T: std::str::FromStr>(name: &str, value: String) -> Result<T>
+        where
+            T::Err: std::fmt::Display,
+        {
+            value
+                .trim()
+                .parse::<T>()
+                .map_err(|e| anyhow::anyhow!("{}{}={}: {}", ENV_PREFIX, name, value, e))
+        }
+        fn parse_bool(name: &str, value: String) -> Result<bool> {
+            match value.trim().to_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Ok(true),
+                "0" | "false" | "no" | "off" | "" => Ok(false),
+                _ => Err(anyhow::anyhow!(
+                    "{}{}={}: ожидается true/false",
+                    ENV_PREFIX,
+                    name,
+                    value
+                )),
+            }
+        }
+        fn comma_list(value: &str) -> Vec<String> {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(str::to_string)
+                .collect()
+        }
+        fn non_empty(value: String) -> Option<String> {
+            if value.trim().is_empty() {
+                None
+            } else {
+                Some(value)
+            }
+        }
+
+        if let Some(v) = get("HTML_PATH") {
+            self.html_path = v;
+        }
+        if let Some(v) = get("CONFIGURATION_PATH") {
+            self.configuration_path = non_empty(v);
+        }
+        if let Some(v) = get("ODATA_COMPOSITION") {
+            self.odata_composition_path = non_empty(v);
+        }
+        if let Some(v) = get("VERBOSE") {
+            self.verbose_logging = parse_bool("VERBOSE", v)?;
+        }
+        if let Some(v) = get("HEADLESS") {
+            self.headless = parse_bool("HEADLESS", v)?;
+        }
+        if let Some(v) = get("CACHE_TTL_SECONDS") {
+            self.cache_settings.cache_ttl_seconds = parse("CACHE_TTL_SECONDS", v)?;
+        }
+        if let Some(v) = get("MAX_CACHE_SIZE") {
+            self.cache_settings.max_cache_size = parse("MAX_CACHE_SIZE", v)?;
+        }
+        if let Some(v) = get("ANALYSIS_CACHE") {
+            self.cache_settings.analysis_cache_path = non_empty(v);
+        }
+        if let Some(v) = get("PARSER_THREADS") {
+            self.performance_settings.max_parser_threads = parse("PARSER_THREADS", v)?;
+        }
+        if let Some(v) = get("LSP_TIMEOUT_MS") {
+            self.performance_settings.lsp_response_timeout_ms = parse("LSP_TIMEOUT_MS", v)?;
+        }
+        if let Some(v) = get("WEB_TIMEOUT_MS") {
+            self.performance_settings.web_request_timeout_ms = parse("WEB_TIMEOUT_MS", v)?;
+        }
+        if let Some(v) = get("RESOLVER_TIMEOUT_MS") {
+            self.performance_settings.resolver_timeout_ms = parse("RESOLVER_TIMEOUT_MS", v)?;
+        }
+        if let Some(v) = get("WEB_WORKERS") {
+            self.performance_settings.web_worker_threads = parse("WEB_WORKERS", v)?;
+        }
+        if let Some(v) = get("WEB_BIND") {
+            self.web_server.bind_address = v;
+        }
+        if let Some(v) = get("WEB_PORT") {
+            self.web_server.port = parse("WEB_PORT", v)?;
+        }
+        if let Some(v) = get("TLS_CERT") {
+            self.web_server.tls_cert_path = non_empty(v);
+        }
+        if let Some(v) = get("TLS_KEY") {
+            self.web_server.tls_key_path = non_empty(v);
+        }
+        if let Some(v) = get("SHUTDOWN_GRACE_SECS") {
+            self.web_server.shutdown_grace_period_secs = parse("SHUTDOWN_GRACE_SECS", v)?;
+        }
+        if let Some(v) = get("GRPC_PORT") {
+            self.web_server.grpc_port = if v.trim().is_empty() {
+                None
+            } else {
+                Some(parse("GRPC_PORT", v)?)
+            };
+        }
+        if let Some(v) = get("WEB_PROJECTS") {
+            self.web_server.projects = WebProjectSettings::parse_list(&v)
+                .map_err(|e| anyhow::anyhow!("{}WEB_PROJECTS={}: {}", ENV_PREFIX, v, e))?;
+        }
+        if let Some(v) = get("REMOTE_REPOSITORY") {
+            self.remote_repository_url = non_empty(v);
+        }
+        if let Some(v) = get("SNAPSHOT") {
+            self.snapshot_path = non_empty(v);
+        }
+        if let Some(v) = get("TYPE_STORE") {
+            self.type_store_path = non_empty(v);
+        }
+        if let Some(v) = get("READ_ONLY") {
+            self.read_only = parse_bool("READ_ONLY", v)?;
+        }
+        if let Some(v) = get("TELEMETRY") {
+            self.telemetry_path = non_empty(v);
+        }
+        if let Some(v) = get("CHANGELOG") {
+            self.changelog_path = non_empty(v);
+        }
+        if let Some(v) = get("TEAM_NOTES") {
+            self.team_notes_path = non_empty(v);
+        }
+        if let Some(v) = get("SNIPPETS") {
+            self.snippets_path = non_empty(v);
+        }
+        if let Some(v) = get("WATCH") {
+            self.watch_sources = parse_bool("WATCH", v)?;
+        }
+        if let Some(v) = get("MAX_ERRORS") {
+            self.quality_gates.max_errors = non_empty(v)
+                .map(|v| parse("MAX_ERRORS", v))
+                .transpose()?;
+        }
+        if let Some(v) = get("MIN_COVERAGE") {
+            self.quality_gates.min_coverage = non_empty(v)
+                .map(|v| parse("MIN_COVERAGE", v))
+                .transpose()?;
+        }
+        if let Some(v) = get("STRICT_MODULES") {
+            self.quality_gates.strict_modules = comma_list(&v);
+        }
+        if let Some(v) = get("DYNAMIC_BASELINE") {
+            self.quality_gates.dynamic_baseline = non_empty(v);
+        }
+        if let Some(v) = get("COVERAGE_BASELINE") {
+            self.quality_gates.coverage_baseline = non_empty(v);
+        }
+        if let Some(v) = get("RESOLVER_ORDER") {
+            self.resolver_chain.order = comma_list(&v);
+        }
+        if let Some(v) = get("DISABLED_RESOLVERS") {
+            self.resolver_chain.disabled = comma_list(&v);
+        }
+        if let Some(v) = get("RESOLVER_POLICY") {
+            self.resolver_chain.policy = parse("RESOLVER_POLICY", v)?;
+        }
+        if let Some(v) = get("COMPLETION_GROUPS") {
+            self.completion_groups.order = comma_list(&v)
+                .into_iter()
+                .map(|group| parse::<CompletionGroup>("COMPLETION_GROUPS", group))
+                .collect::<Result<_>>()?;
+        }
+        if let Some(v) = get("NAME_PRECEDENCE") {
+            self.name_precedence.order = comma_list(&v)
+                .into_iter()
+                .map(|origin| parse::<TypeOrigin>("NAME_PRECEDENCE", origin))
+                .collect::<Result<_>>()?;
+        }
+        if let Some(v) = get("PLATFORM_VERSION") {
+            self.platform_version = v.trim().to_string();
+        }
+        if let Some(v) = get("STRICTNESS") {
+            self.strictness = parse("STRICTNESS", v)?;
+        }
+        if let Some(v) = get("INLAY_VARIABLE_TYPES") {
+            self.inlay_hints.variable_types = parse_bool("INLAY_VARIABLE_TYPES", v)?;
+        }
+        if let Some(v) = get("INLAY_PARAMETER_NAMES") {
+            self.inlay_hints.parameter_names = parse_bool("INLAY_PARAMETER_NAMES", v)?;
+        }
+        if let Some(v) = get("INLAY_MIN_CERTAINTY") {
+            self.inlay_hints.min_certainty = parse("INLAY_MIN_CERTAINTY", v)?;
+        }
+        if let Some(v) = get("FORMAT_KEYWORD_CASE") {
+            self.formatting.keyword_case = parse("FORMAT_KEYWORD_CASE", v)?;
+        }
+        if let Some(v) = get("FORMAT_INDENT") {
+            self.formatting.indent = parse("FORMAT_INDENT", v)?;
+        }
+        if let Some(v) = get("FORMAT_INDENT_SIZE") {
+            self.formatting.indent_size = parse("FORMAT_INDENT_SIZE", v)?;
+        }
+        if let Some(v) = get("FORMAT_OPERATOR_SPACING") {
+            self.formatting.operator_spacing = parse_bool("FORMAT_OPERATOR_SPACING", v)?;
+        }
+        for (name, interval) in [
+            (
+                "REINDEX_INTERVAL_SECS",
+                &mut self.maintenance.reindex_interval_secs,
+            ),
+            (
+                "WARMUP_INTERVAL_SECS",
+                &mut self.maintenance.warmup_interval_secs,
+            ),
+            (
+                "REFRESH_INTERVAL_SECS",
+                &mut self.maintenance.refresh_interval_secs,
+            ),
+        ] {
+            if let Some(v) = get(name) {
+                *interval = non_empty(v).map(|v| parse(name, v)).transpose()?;
+            }
+        }
+        if let Some(v) = get("MAINTENANCE_WINDOW") {
+            if let Some(window) = non_empty(v) {
+                maintenance::OffHours::parse(&window)
+                    .map_err(|e| anyhow::anyhow!("{}MAINTENANCE_WINDOW: {:#}", ENV_PREFIX, e))?;
+                self.maintenance.off_hours = Some(window);
+            } else {
+                self.maintenance.off_hours = None;
+            }
+        }
+        if let Some(v) = get("WARMUP_TOP_TYPES") {
+            self.maintenance.warmup_top_types = parse("WARMUP_TOP_TYPES", v)?;
+        }
+        Ok(())
+    }
+
+    /// Применить настройки редактора из `workspace/didChangeConfiguration`.
+    ///
+    /// Принимается как секция `bslAnalyzer`, так и объект, содержащий её.
+    /// Пустые строки сбрасывают путь к конфигурации, для остальных настроек
+    /// означают «оставить как есть».
+    pub fn apply_editor_settings(&mut self, settings: &serde_json::Value) -> Result<()> {
+        let section = settings.get(EDITOR_SETTINGS_SECTION).unwrap_or(settings);
+        self.apply_overrides(|name| {
+            let (key, _) = EDITOR_SETTINGS.iter().find(|(_, env)| *env == name)?;
+            match section.get(key)? {
+                serde_json::Value::Null => None,
+                serde_json::Value::String(v) if v.trim().is_empty() => {
+                    (name == "CONFIGURATION_PATH").then(String::new)
+                }
+                serde_json::Value::String(v) => Some(v.clone()),
+                other => Some(other.to_string()),
+            }
+        })
+    }
+
+    /// Конфигурация системы для проекта веб-сервера: своя выгрузка и свой
+    /// каталог хранилища типов; снимок и удалённый репозиторий относятся к
+    /// основной конфигурации и не используются
+    pub fn for_project(&self, project: &WebProjectSettings) -> Self {
+        Self {
+            configuration_path: Some(project.configuration_path.clone()),
+            odata_composition_path: project.odata_composition_path.clone(),
+            remote_repository_url: None,
+            snapshot_path: None,
+            type_store_path: self
+                .type_store_path
+                .as_ref()
+                .map(|store| nested_location(store, &["projects", &project.name])),
+            changelog_path: self.changelog_path.as_ref().map(|changelog| {
+                let changelog = Path::new(changelog);
+                let stem = changelog.file_stem().unwrap_or_default().to_string_lossy();
+                changelog
+                    .with_file_name(format!("{}.{}.json", stem, project.name))
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            web_server: WebServerSettings {
+                projects: Vec::new(),
+                ..self.web_server.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Загрузить конфигурацию из JSON-файла
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать конфигурацию {}", path.display()))?;
+        let config: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Некорректная конфигурация {}", path.display()))?;
+        Ok(config)
+    }
+}
+
 impl Default for CentralSystemConfig {
     fn default() -> Self {
         Self {
             html_path: "examples/syntax_helper/rebuilt.shcntx_ru".to_string(),
             configuration_path: None,
+            odata_composition_path: None,
             verbose_logging: false,
-            cache_settings: CacheSettings {
-                enable_repository_cache: true,
-                enable_resolution_cache: true,
-                enable_lsp_cache: true,
-                cache_ttl_seconds: 3600, // 1 час
-                max_cache_size: 10000,
-            },
-            performance_settings: PerformanceSettings {
-                enable_parallel_parsing: true,
-                max_parser_threads: num_cpus::get(),
-                lsp_response_timeout_ms: 100,
-                web_request_timeout_ms: 5000,
-            },
+            headless: false,
+            cache_settings: CacheSettings::default(),
+            performance_settings: PerformanceSettings::default(),
+            web_server: WebServerSettings::default(),
+            remote_repository_url: None,
+            snapshot_path: None,
+            type_store_path: None,
+            read_only: false,
+            telemetry_path: None,
+            changelog_path: None,
+            team_notes_path: None,
+            snippets_path: None,
+            watch_sources: false,
+            quality_gates: QualityGatesConfig::default(),
+            resolver_chain: ResolverChainConfig::default(),
+            resolver_plugins: ResolverPlugins::default(),
+            completion_groups: CompletionGroupsConfig::default(),
+            name_precedence: NamePrecedence::default(),
+            platform_version: "8.3".to_string(),
+            strictness: StrictnessProfile::default(),
+            inlay_hints: InlayHintsConfig::default(),
+            formatting: FormattingConfig::default(),
+            maintenance: MaintenanceSettings::default(),
+        }
+    }
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            enable_repository_cache: true,
+            enable_resolution_cache: true,
+            enable_lsp_cache: true,
+            cache_ttl_seconds: 3600, // 1 час
+            max_cache_size: 10000,
+            analysis_cache_path: None,
+        }
+    }
+}
+
+impl Default for PerformanceSettings {
+    fn default() -> Self {
+        Self {
+            enable_parallel_parsing: true,
+            max_parser_threads: num_cpus::get(),
+            lsp_response_timeout_ms: 100,
+            web_request_timeout_ms: 5000,
+            web_worker_threads: num_cpus::get(),
+            resolver_timeout_ms: 500,
+        }
+    }
+}
+
+impl Default for WebServerSettings {
+    fn default() -> Self {
+        Self {
+            bind_address: "127.0.0.1".to_string(),
+            port: 8080,
+            tls_cert_path: None,
+            tls_key_path: None,
+            shutdown_grace_period_secs: 30,
+            grpc_port: None,
+            projects: Vec::new(),
         }
     }
 }
@@ -744,6 +2524,8 @@ impl Default for CentralSystemConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::architecture::domain::ShortCircuitPolicy;
+    use crate::architecture::presentation::formatting::KeywordCase;
 
     #[tokio::test]
     async fn test_central_type_system_creation() {
@@ -751,7 +2533,7 @@ mod tests {
         let system = CentralTypeSystem::new(config);
 
         // Тестируем создание
-        assert!(!system.config.html_path.is_empty());
+        assert!(!system.config.read().await.html_path.is_empty());
 
         // Тестируем получение интерфейсов
         let _lsp_interface = system.lsp_interface();
@@ -761,6 +2543,214 @@ mod tests {
         println!("✅ CentralTypeSystem создана");
     }
 
+    #[tokio::test]
+    async fn test_apply_source_changes() {
+        let system = CentralTypeSystem::new(CentralSystemConfig::default());
+        let project = tempfile::tempdir().unwrap();
+        let path = project.path().join("CommonModules/Общий/Ext/Module.bsl");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let module =
+            |name: &str| format!("Функция {}() Экспорт\n    Возврат 1;\nКонецФункции\n", name);
+        std::fs::write(&path, module("Курс")).unwrap();
+
+        let mut changes = SourceChanges::default();
+        changes.add(&path);
+        let update = system.apply_source_changes(&changes).await.unwrap();
+        assert_eq!(update.modules, 1);
+        assert!(!update.configuration_reloaded);
+        let signature = system
+            .resolution_service
+            .function_signature(Some("Общий"), "Курс", None)
+            .await
+            .unwrap();
+        assert!(signature.exported);
+
+        // Переименованная функция заменяет прежнюю, удалённый модуль убирает её
+        std::fs::write(&path, module("КурсВалюты")).unwrap();
+        system.apply_source_changes(&changes).await.unwrap();
+        let service = &system.resolution_service;
+        assert!(service
+            .function_signature(Some("Общий"), "Курс", None)
+            .await
+            .is_none());
+        assert!(service
+            .function_signature(Some("Общий"), "КурсВалюты", None)
+            .await
+            .is_some());
+        std::fs::remove_file(&path).unwrap();
+        system.apply_source_changes(&changes).await.unwrap();
+        assert!(service
+            .function_signature(Some("Общий"), "КурсВалюты", None)
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn test_config_partial_json_uses_defaults() {
+        let json = r#"{ "web_server": { "port": 9090 }, "performance_settings": { "web_worker_threads": 2 } }"#;
+        let config: CentralSystemConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.web_server.port, 9090);
+        assert_eq!(config.web_server.bind_address, "127.0.0.1");
+        assert!(!config.web_server.tls_enabled());
+        assert_eq!(config.performance_settings.web_worker_threads, 2);
+        assert_eq!(config.performance_settings.lsp_response_timeout_ms, 100);
+    }
+
+    #[test]
+    fn test_config_overrides() {
+        let vars: std::collections::HashMap<&str, &str> = [
+            ("WEB_PORT", "9000"),
+            ("WEB_BIND", "0.0.0.0"),
+            ("HEADLESS", "1"),
+            ("CONFIGURATION_PATH", "/data/cf"),
+            ("RESOLVER_ORDER", "ExpressionResolver, BuiltinTypeResolver"),
+            ("RESOLVER_POLICY", "best_confidence"),
+            ("COMPLETION_GROUPS", "module, local"),
+            ("SNAPSHOT", "/var/cache/bsl/types.snapshot"),
+            ("NAME_PRECEDENCE", "platform, configuration"),
+            ("ODATA_COMPOSITION", "/data/odata.txt"),
+            ("TYPE_STORE", "/var/cache/bsl/store"),
+            ("WEB_PROJECTS", "trade=/srv/trade, hr=/srv/hr"),
+            ("READ_ONLY", "true"),
+            ("ANALYSIS_CACHE", "sqlite:///var/cache/bsl/analysis.db"),
+            ("TELEMETRY", "/var/log/bsl/telemetry.json"),
+            ("TEAM_NOTES", "docs/team-notes"),
+            ("CHANGELOG", "/var/log/bsl/changes.json"),
+            ("WATCH", "1"),
+            ("WARMUP_INTERVAL_SECS", "900"),
+            ("MAINTENANCE_WINDOW", "02:00-05:00"),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut config = CentralSystemConfig::default();
+        config
+            .apply_overrides(|name| vars.get(name).map(|v| v.to_string()))
+            .unwrap();
+
+        assert_eq!(config.web_server.port, 9000);
+        assert_eq!(config.web_server.bind_address, "0.0.0.0");
+        assert!(config.headless);
+        assert_eq!(config.configuration_path.as_deref(), Some("/data/cf"));
+        assert_eq!(config.odata_composition_path.as_deref(), Some("/data/odata.txt"));
+        assert_eq!(config.type_store_path.as_deref(), Some("/var/cache/bsl/store"));
+        assert!(config.read_only);
+        assert_eq!(
+            config.cache_settings.analysis_cache_path.as_deref(),
+            Some("sqlite:///var/cache/bsl/analysis.db")
+        );
+        assert_eq!(config.telemetry_path.as_deref(), Some("/var/log/bsl/telemetry.json"));
+        assert_eq!(config.team_notes_path.as_deref(), Some("docs/team-notes"));
+        assert_eq!(config.changelog_path.as_deref(), Some("/var/log/bsl/changes.json"));
+        assert!(config.watch_sources);
+        assert_eq!(config.maintenance.warmup_interval_secs, Some(900));
+        assert_eq!(config.maintenance.off_hours.as_deref(), Some("02:00-05:00"));
+        assert_eq!(config.maintenance.reindex_interval_secs, None);
+        assert_eq!(config.web_server.projects.len(), 2);
+        assert_eq!(config.web_server.projects[1].name, "hr");
+        assert_eq!(config.web_server.projects[1].configuration_path, "/srv/hr");
+        assert_eq!(
+            config.resolver_chain.order,
+            ["ExpressionResolver", "BuiltinTypeResolver"]
+        );
+        assert_eq!(config.resolver_chain.policy, ShortCircuitPolicy::BestConfidence);
+        assert_eq!(
+            config.completion_groups.order,
+            [CompletionGroup::Module, CompletionGroup::Local]
+        );
+        assert_eq!(
+            config.snapshot_path.as_deref(),
+            Some("/var/cache/bsl/types.snapshot")
+        );
+        assert_eq!(
+            config.name_precedence.order,
+            [TypeOrigin::Platform, TypeOrigin::Configuration]
+        );
+
+        let err = config.apply_overrides(|name| (name == "WEB_PORT").then(|| "abc".to_string()));
+        assert!(err.is_err());
+        let err = config
+            .apply_overrides(|name| (name == "MAINTENANCE_WINDOW").then(|| "ночью".to_string()));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_web_projects() {
+        let mut config = CentralSystemConfig {
+            configuration_path: Some("/data/main".to_string()),
+            snapshot_path: Some("/var/cache/bsl/types.snapshot".to_string()),
+            type_store_path: Some("/var/cache/bsl/store".to_string()),
+            changelog_path: Some("/var/log/bsl/changes.json".to_string()),
+            ..Default::default()
+        };
+        config.web_server.projects = WebProjectSettings::parse_list("trade=/srv/trade").unwrap();
+        config.web_server.validate_projects().unwrap();
+
+        let project = config.for_project(&config.web_server.projects[0]);
+        assert_eq!(project.configuration_path.as_deref(), Some("/srv/trade"));
+        assert!(project.snapshot_path.is_none());
+        assert!(project.web_server.projects.is_empty());
+        assert!(project
+            .type_store_path
+            .unwrap()
+            .ends_with(&format!("projects{}trade", std::path::MAIN_SEPARATOR)));
+        assert!(project.changelog_path.unwrap().ends_with("changes.trade.json"));
+
+        assert!(WebProjectSettings::parse_list("trade").is_err());
+        config.web_server.projects.push(WebProjectSettings {
+            name: "Trade".to_string(),
+            configuration_path: "/srv/other".to_string(),
+            odata_composition_path: None,
+        });
+        assert!(config.web_server.validate_projects().is_err());
+        config.web_server.projects[1].name = "торговля".to_string();
+        assert!(config.web_server.validate_projects().is_err());
+    }
+
+    #[test]
+    fn test_editor_settings() {
+        let mut config = CentralSystemConfig {
+            configuration_path: Some("/old/cf".to_string()),
+            ..Default::default()
+        };
+        let html_path = config.html_path.clone();
+        let settings = serde_json::json!({
+            "bslAnalyzer": {
+                "platformVersion": "8.3.25",
+                "platformDocsArchive": "",
+                "strictness": "strict",
+                "inlayParameterNames": false,
+                "inlayMinCertainty": 0.5,
+                "formatKeywordCase": "lower",
+                "snippetsFile": ".vscode/bsl.code-snippets",
+                "maxFileSize": 1024
+            }
+        });
+        config.apply_editor_settings(&settings).unwrap();
+
+        assert_eq!(config.platform_version, "8.3.25");
+        assert_eq!(config.strictness, StrictnessProfile::Strict);
+        assert!(config.inlay_hints.variable_types);
+        assert!(!config.inlay_hints.parameter_names);
+        assert_eq!(config.inlay_hints.min_certainty, 0.5);
+        assert_eq!(config.formatting.keyword_case, KeywordCase::Lower);
+        assert_eq!(config.snippets_path.as_deref(), Some(".vscode/bsl.code-snippets"));
+        // Пустой путь к справке не затирает значение по умолчанию
+        assert_eq!(config.html_path, html_path);
+        assert_eq!(config.configuration_path.as_deref(), Some("/old/cf"));
+
+        // Пустой путь к конфигурации сбрасывает её
+        config
+            .apply_editor_settings(&serde_json::json!({ "configurationPath": "" }))
+            .unwrap();
+        assert!(config.configuration_path.is_none());
+
+        assert!(!StrictnessProfile::Relaxed.reports(2));
+        assert!(StrictnessProfile::Standard.reports(2));
+        assert!(!StrictnessProfile::Standard.reports(4));
+    }
+
     #[tokio::test]
     async fn test_system_initialization() {
         let config = CentralSystemConfig {
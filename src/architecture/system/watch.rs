@@ -0,0 +1,174 @@
+//! Отслеживание исходников конфигурации
+//!
+//! Без отслеживания изменённые модули и описания метаданных подхватывались
+//! только полной перезагрузкой (`reload_data`): заново разбиралась и справка
+//! платформы, и вся конфигурация. [`SourceWatcher`] следит за каталогом
+//! конфигурации (crate `notify`) и после паузы применяет накопленные
+//! изменения пакетом ([`CentralTypeSystem::apply_source_changes`]):
+//!
+//! - изменённый модуль `.bsl` обновляет только свои сигнатуры функций,
+//!   глобальные символы, символы рабочей области и граф вызовов;
+//! - изменённое описание `.xml`/`.mdo` заново разбирает типы конфигурации
+//!   (типы платформы остаются в репозитории), а в репозиторий записываются
+//!   только добавленные, изменённые и удалённые типы: правка формы или
+//!   макета его не трогает.
+//!
+//! После применения сбрасываются диагностики открытых документов изменённых
+//! модулей и модулей, которые от них зависят (по графу зависимостей модулей;
+//! пока граф не построен и после перезагрузки типов — всех документов), а
+//! кеши разрешения — только для затронутых типов (по уведомлению
+//! репозитория).
+
+use anyhow::{Context, Result};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use super::CentralTypeSystem;
+
+/// Пауза после последнего изменения: серия сохранений (переключение ветки,
+/// выгрузка конфигурации) применяется одним пакетом
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Изменённые исходники, накопленные за паузу
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceChanges {
+    /// Модули `.bsl` (в том числе удалённые)
+    pub modules: BTreeSet<PathBuf>,
+    /// Описания метаданных `.xml` и `.mdo`
+    pub metadata: BTreeSet<PathBuf>,
+}
+
+impl SourceChanges {
+    /// Учесть изменённый файл; `false` — файл не исходник конфигурации
+    pub fn add(&mut self, path: &Path) -> bool {
+        if crate::core::fs_utils::is_bsl_file(path) {
+            self.modules.insert(path.to_path_buf());
+            return true;
+        }
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        if matches!(extension.as_deref(), Some("xml" | "mdo")) {
+            self.metadata.insert(path.to_path_buf());
+            return true;
+        }
+        false
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty() && self.metadata.is_empty()
+    }
+}
+
+/// Что изменилось после применения пакета
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SourceUpdate {
+    /// Обновлено модулей
+    pub modules: usize,
+    /// Типы конфигурации разобраны заново
+    pub configuration_reloaded: bool,
+    /// Добавлено, удалено и изменено типов
+    pub changed_types: usize,
+    /// Модулей, диагностики которых устарели: изменённые и зависящие от них
+    pub affected_modules: usize,
+}
+
+impl SourceUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.modules == 0 && !self.configuration_reloaded
+    }
+}
+
+/// Наблюдатель за каталогом конфигурации; отслеживание прекращается, когда
+/// значение удаляется
+pub struct SourceWatcher {
+    _watcher: notify::RecommendedWatcher,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl SourceWatcher {
+    /// Следить за каталогом `root` и применять изменения к `system`;
+    /// вызывается внутри среды выполнения tokio
+    pub fn start(system: Arc<CentralTypeSystem>, root: &Path) -> Result<Self> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                // Чтение файла не меняет исходники
+                Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+                Ok(event) => {
+                    for path in event.paths {
+                        let _ = sender.send(path);
+                    }
+                }
+                Err(e) => warn!("⚠️ Ошибка отслеживания файлов: {}", e),
+            })
+            .context("Не удалось создать наблюдатель файлов")?;
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .with_context(|| format!("Не удалось отслеживать {}", root.display()))?;
+        info!("👀 Отслеживаются изменения исходников: {}", root.display());
+
+        let task = tokio::spawn(run_watch_loop(system, receiver));
+        Ok(Self {
+            _watcher: watcher,
+            task,
+        })
+    }
+}
+
+impl Drop for SourceWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Собирать изменения до паузы и применять их пакетом
+async fn run_watch_loop(
+    system: Arc<CentralTypeSystem>,
+    mut receiver: mpsc::UnboundedReceiver<PathBuf>,
+) {
+    while let Some(path) = receiver.recv().await {
+        let mut changes = SourceChanges::default();
+        changes.add(&path);
+        while let Ok(Some(path)) = tokio::time::timeout(WATCH_DEBOUNCE, receiver.recv()).await {
+            changes.add(&path);
+        }
+        if changes.is_empty() {
+            continue;
+        }
+        match system.apply_source_changes(&changes).await {
+            Ok(update) if update.is_empty() => {}
+            Ok(update) => info!(
+                "🔁 Применены изменения исходников: модулей {} (затронуто {}), типов {}",
+                update.modules, update.affected_modules, update.changed_types
+            ),
+            Err(e) => warn!("⚠️ Изменения исходников не применены: {:#}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_changes() {
+        let mut changes = SourceChanges::default();
+        assert!(changes.is_empty());
+        assert!(changes.add(Path::new("CommonModules/Общий/Ext/Module.bsl")));
+        assert!(changes.add(Path::new("CommonModules/Общий/Ext/Module.bsl")));
+        assert!(changes.add(Path::new("Catalogs/Валюты.xml")));
+        assert!(changes.add(Path::new("src/Catalogs/Валюты/Валюты.MDO")));
+        assert!(!changes.add(Path::new("Catalogs/Валюты/Ext/Help/ru.html")));
+        assert!(!changes.add(Path::new(".git/index")));
+        assert_eq!(changes.modules.len(), 1);
+        assert_eq!(changes.metadata.len(), 2);
+    }
+}
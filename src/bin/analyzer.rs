@@ -1,9 +1,31 @@
 //! BSL Type Analyzer CLI (target-only)
 
 use anyhow::Result;
+use bsl_gradual_types::core::bounded_memory::{parse_memory_size, BoundedAnalysisConfig};
+use bsl_gradual_types::core::determinism_audit::{
+    run_audit, AuditRun, DeterminismAuditConfig, FileOrder, EXIT_NONDETERMINISTIC,
+};
+use bsl_gradual_types::core::golden_corpus::{
+    bundled_fixtures, load_fixtures, run_corpus, EXIT_SELFTEST_FAILED,
+};
+use bsl_gradual_types::core::ownership::{OwnershipMap, OWNERS_FILE_CANDIDATES};
+use bsl_gradual_types::core::parallel_analysis::ParallelAnalyzer;
+use bsl_gradual_types::core::profiling::chrome_trace_layer;
+use bsl_gradual_types::core::quality_gates::{QualityGatesConfig, EXIT_RUNTIME_ERROR};
+use bsl_gradual_types::core::usage_report::{UsageCollector, DEFAULT_TOP_N};
+use bsl_gradual_types::data::consistency::EXIT_DATA_INCONSISTENT;
+use bsl_gradual_types::data::snapshot_artifact::{export_platform_artifact, import_artifact};
+use bsl_gradual_types::data::snapshot_delta::{apply_delta, create_delta};
+use bsl_gradual_types::data::loaders::subsystems::SubsystemIndex;
+use bsl_gradual_types::presentation::{CliAnalysisRequest, CliOutputFormat};
 use bsl_gradual_types::system::{CentralSystemConfig, CentralTypeSystem};
-use clap::Parser;
-use tracing::info;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
 #[derive(Parser)]
 #[command(name = "bsl-analyzer")]
@@ -11,50 +33,917 @@ use tracing::info;
 struct Args {
     /// Path to BSL file to analyze
     #[arg(short, long)]
-    file: String,
+    file: Option<String>,
 
     /// Configuration path
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     config: Option<String>,
 
     /// Enable verbose output
-    #[arg(short = 'V', long)]
+    #[arg(short = 'V', long, global = true)]
     verbose: bool,
+
+    /// Write a Chrome Tracing profile of parse/resolve/index phases to this file
+    /// (opens in chrome://tracing, Perfetto UI or speedscope)
+    #[arg(long, global = true, value_name = "FILE")]
+    profile: Option<PathBuf>,
+
+    /// Never write to disk: type store, caches and baselines stay in memory,
+    /// skipped writes are listed by `doctor` (same as BSL_READ_ONLY=1)
+    #[arg(long, global = true)]
+    read_only: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Report which platform/configuration types and methods the project uses
+    UsageReport {
+        /// Path to the project sources (configuration dump)
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Number of entries in "most used" sections
+        #[arg(long, default_value_t = DEFAULT_TOP_N)]
+        top: usize,
+
+        /// Output JSON instead of text
+        #[arg(long)]
+        json: bool,
+
+        /// Write report to file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        grouping: GroupingArgs,
+    },
+
+    /// Analyze project types and report diagnostics and coverage
+    Analyze {
+        /// Path to the project sources (configuration dump)
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Report format
+        #[arg(long = "output", value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        #[command(flatten)]
+        grouping: GroupingArgs,
+
+        #[command(flatten)]
+        gates: GateArgs,
+
+        #[command(flatten)]
+        memory: MemoryArgs,
+    },
+
+    /// Run type inference several times with different thread counts and file
+    /// orderings and report expressions whose results differ
+    VerifyDeterminism {
+        /// Path to the project sources (configuration dump)
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Thread counts for the runs (comma separated), e.g. "1,8"
+        #[arg(long, value_delimiter = ',')]
+        threads: Vec<usize>,
+
+        /// Output JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check system health and consistency of the loaded type data
+    Doctor {
+        /// Max examples printed per issue kind
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+
+        /// Output JSON instead of text
+        #[arg(long)]
+        json: bool,
+
+        /// Fail with exit code 6 if the data has errors
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Score documentation completeness per type (descriptions, parameter
+    /// docs, examples) to prioritize documentation work
+    DocsReport {
+        /// Max worst-documented types printed in text output
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Output JSON instead of text
+        #[arg(long, conflicts_with = "csv")]
+        json: bool,
+
+        /// Output CSV (one row per type) instead of text
+        #[arg(long)]
+        csv: bool,
+
+        /// Write report to file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Print the module dependency graph (which modules call which common
+    /// modules) in DOT format
+    DependencyGraph {
+        /// Path to the project sources (configuration dump)
+        #[arg(short, long)]
+        project: PathBuf,
+
+        /// Instead of the graph, list modules to re-analyze after these
+        /// modules change (the modules themselves and their dependents)
+        #[arg(long)]
+        changed: Vec<PathBuf>,
+
+        /// Write graph to file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Render the "how inference works" reference (resolver chain, axioms,
+    /// assignment matrix) from the running type system
+    InferenceReference {
+        /// Output HTML instead of Markdown
+        #[arg(long)]
+        html: bool,
+
+        /// Write reference to file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Run the golden corpus (bundled BSL modules with expected types and
+    /// diagnostics) against the loaded types
+    Selftest {
+        /// Directory with own fixtures (<name>.bsl + <name>.expected.json)
+        /// instead of the bundled corpus
+        #[arg(long)]
+        corpus: Option<PathBuf>,
+
+        /// Output JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Export or import the prebuilt platform-types snapshot
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+}
+
+/// Действия подкоманды `snapshot`
+#[derive(Subcommand)]
+enum SnapshotAction {
+    /// Pack parsed platform types into one artifact with a version + hash manifest
+    Export {
+        /// Artifact file to write (zip)
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+
+    /// Verify an artifact and unpack its snapshot (then point BSL_SNAPSHOT to it)
+    Import {
+        /// Artifact produced by `snapshot export`
+        artifact: PathBuf,
+
+        /// Snapshot file to write
+        #[arg(short, long)]
+        output: PathBuf,
+
+        /// Reject artifacts built for another platform version
+        #[arg(long)]
+        expect_version: Option<String>,
+    },
+
+    /// Compute a delta between two artifacts (e.g. 8.3.23 -> 8.3.24)
+    Diff {
+        /// Artifact of the old platform version
+        #[arg(long)]
+        base: PathBuf,
+
+        /// Artifact of the new platform version
+        #[arg(long)]
+        target: PathBuf,
+
+        /// Delta file to write (zip)
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+
+    /// Apply a delta to an imported snapshot of the base version
+    ApplyDelta {
+        /// Delta produced by `snapshot diff`
+        delta: PathBuf,
+
+        /// Snapshot of the base version
+        #[arg(long)]
+        base: PathBuf,
+
+        /// Snapshot file to write (defaults to updating the base in place)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Пороги качества (переопределяют `quality_gates` из настроек и `BSL_*`)
+#[derive(clap::Args)]
+struct GateArgs {
+    /// Fail with exit code 2 if there are more type errors than this
+    #[arg(long)]
+    max_errors: Option<usize>,
+
+    /// Fail with exit code 3 if type coverage (%) is below this
+    #[arg(long)]
+    min_coverage: Option<f32>,
+
+    /// Strict module globs (comma separated); new Dynamic there fails with exit code 4
+    #[arg(long, value_delimiter = ',')]
+    strict_modules: Vec<String>,
+
+    /// JSON file with allowed Dynamic counts per strict module
+    #[arg(long)]
+    dynamic_baseline: Option<String>,
+
+    /// JSON file with project and per-module coverage; a drop fails with exit code 8
+    #[arg(long)]
+    coverage_baseline: Option<String>,
+
+    /// Rewrite the Dynamic and coverage baselines from the current analysis
+    #[arg(long)]
+    update_baseline: bool,
+}
+
+impl GateArgs {
+    fn apply(self, mut config: QualityGatesConfig) -> QualityGatesConfig {
+        if self.max_errors.is_some() {
+            config.max_errors = self.max_errors;
+        }
+        if self.min_coverage.is_some() {
+            config.min_coverage = self.min_coverage;
+        }
+        if !self.strict_modules.is_empty() {
+            config.strict_modules = self.strict_modules;
+        }
+        if self.dynamic_baseline.is_some() {
+            config.dynamic_baseline = self.dynamic_baseline;
+        }
+        if self.coverage_baseline.is_some() {
+            config.coverage_baseline = self.coverage_baseline;
+        }
+        config
+    }
+}
+
+/// Анализ в ограниченной памяти
+#[derive(clap::Args)]
+struct MemoryArgs {
+    /// Hard memory ceiling, e.g. "6G": analysis stops with an error instead
+    /// of being killed; also sizes module waves
+    #[arg(long, value_name = "SIZE", value_parser = parse_memory_size)]
+    max_memory: Option<usize>,
+
+    /// Source bytes of modules processed per wave, e.g. "64M"
+    #[arg(long, value_name = "SIZE", value_parser = parse_memory_size)]
+    wave_size: Option<usize>,
+
+    /// Directory for diagnostics spilled to disk (default: system temp dir)
+    #[arg(long, value_name = "DIR")]
+    spill_dir: Option<PathBuf>,
+}
+
+impl MemoryArgs {
+    /// Режим включается любым из флагов
+    fn config(self) -> Option<BoundedAnalysisConfig> {
+        if self.max_memory.is_none() && self.wave_size.is_none() && self.spill_dir.is_none() {
+            return None;
+        }
+        Some(BoundedAnalysisConfig {
+            memory_limit: self.max_memory,
+            wave_bytes: self.wave_size,
+            spill_dir: Some(self.spill_dir.unwrap_or_else(std::env::temp_dir)),
+            ..Default::default()
+        })
+    }
+}
+
+/// Формат отчёта подкоманды `analyze`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    /// One JSON diagnostic per line, written as analysis progresses
+    Jsonl,
+}
+
+/// Группировка результатов по подсистемам и владельцам
+#[derive(clap::Args)]
+struct GroupingArgs {
+    /// Group modules by configuration subsystems (requires --config)
+    #[arg(long)]
+    by_subsystem: bool,
+
+    /// Only count modules of this subsystem, e.g. "Продажи" (requires --config)
+    #[arg(long)]
+    subsystem: Option<String>,
+
+    /// Group modules by owners from the ownership file (BSLOWNERS/CODEOWNERS in the project root)
+    #[arg(long)]
+    by_owner: bool,
+
+    /// Ownership file (CODEOWNERS format); implies --by-owner
+    #[arg(long)]
+    owners: Option<PathBuf>,
+}
+
+/// Загруженные подсистемы и владельцы
+struct Grouping {
+    subsystems: Option<Arc<SubsystemIndex>>,
+    subsystem: Option<String>,
+    ownership: Option<Arc<OwnershipMap>>,
+}
+
+impl GroupingArgs {
+    fn load(self, config: Option<&str>, project: &Path) -> Result<Grouping> {
+        let subsystems = if self.by_subsystem || self.subsystem.is_some() {
+            let Some(config) = config else {
+                anyhow::bail!("--by-subsystem/--subsystem require --config");
+            };
+            Some(Arc::new(SubsystemIndex::load(Path::new(config))?))
+        } else {
+            None
+        };
+
+        let owners_file = match self.owners {
+            Some(path) => Some(path),
+            None if self.by_owner => Some(OwnershipMap::discover(project).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Ownership file not found in {} (expected one of: {})",
+                    project.display(),
+                    OWNERS_FILE_CANDIDATES.join(", ")
+                )
+            })?),
+            None => None,
+        };
+        let ownership = match owners_file {
+            Some(path) => Some(Arc::new(OwnershipMap::load(&path)?)),
+            None => None,
+        };
+
+        Ok(Grouping {
+            subsystems,
+            subsystem: self.subsystem,
+            ownership,
+        })
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    // Initialize tracing
-    if args.verbose {
-        tracing_subscriber::fmt().with_env_filter("debug").init();
-    } else {
-        tracing_subscriber::fmt().with_env_filter("info").init();
-    }
+    // Initialize tracing (логи в stderr: stdout занят отчётами, в т.ч. потоком JSON Lines).
+    // Фильтр уровня логов не влияет на профиль: спаны фаз записываются всегда
+    let filter = if args.verbose { "debug" } else { "info" };
+    let profile = args.profile.clone();
+    let (profile_layer, recorder) = profile.as_ref().map(|_| chrome_trace_layer()).unzip();
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_filter(EnvFilter::new(filter)),
+        )
+        .with(profile_layer)
+        .init();
     info!("BSL Gradual Type Analyzer v{}", env!("CARGO_PKG_VERSION"));
 
     // Target-only: инициализируем центральную систему и выводим статус
-    let mut cfg = CentralSystemConfig::default();
+    let mut cfg = CentralSystemConfig::from_env()?;
     if let Some(ref path) = args.config {
         cfg.configuration_path = Some(path.clone());
     }
+    cfg.read_only |= args.read_only;
+    let gates_config = cfg.quality_gates.clone();
     let central = CentralTypeSystem::new(cfg);
+    // Импорт снимка и дельты нужны как раз для того, чтобы не разбирать справку
+    let needs_types = !matches!(
+        args.command,
+        Some(Command::Snapshot {
+            action: SnapshotAction::Import { .. }
+                | SnapshotAction::Diff { .. }
+                | SnapshotAction::ApplyDelta { .. }
+        }) | Some(Command::DependencyGraph { .. })
+    );
+    // Телеметрия (если включена) хранит только имя команды, без аргументов
+    let command_name = match &args.command {
+        Some(Command::UsageReport { .. }) => "usage-report",
+        Some(Command::Analyze { .. }) => "analyze",
+        Some(Command::VerifyDeterminism { .. }) => "verify-determinism",
+        Some(Command::Doctor { .. }) => "doctor",
+        Some(Command::DocsReport { .. }) => "docs-report",
+        Some(Command::DependencyGraph { .. }) => "dependency-graph",
+        Some(Command::InferenceReference { .. }) => "inference-reference",
+        Some(Command::Selftest { .. }) => "selftest",
+        Some(Command::Snapshot { .. }) => "snapshot",
+        None => "status",
+    };
+    central.telemetry().record_command(command_name);
+    let command_start = std::time::Instant::now();
     let rt = tokio::runtime::Runtime::new().expect("tokio runtime");
-    rt.block_on(async {
-        if let Err(e) = central.initialize().await {
-            eprintln!("Initialization error: {}", e);
-        }
-        let health = central.health_check().await;
-        println!(
-            "Health: {} (score {:.2})",
-            health.status, health.overall_score
-        );
-        let metrics = central.get_system_metrics().await;
-        println!(
-            "Types loaded: {} (platform: {}, config: {})",
-            metrics.total_types, metrics.platform_types, metrics.configuration_types
-        );
+    let exit_code = rt.block_on(async {
+        if needs_types {
+            // Без загруженных типов анализ выдал бы ложные диагностики
+            if let Err(e) = central.initialize().await {
+                eprintln!("Initialization error: {:#}", e);
+                return Ok(EXIT_RUNTIME_ERROR);
+            }
+        }
+
+        match args.command {
+            Some(Command::UsageReport {
+                project,
+                top,
+                json,
+                output,
+                grouping,
+            }) => {
+                let grouping = grouping.load(args.config.as_deref(), &project)?;
+                let options = UsageReportOptions {
+                    top,
+                    json,
+                    output,
+                    grouping,
+                };
+                usage_report(&central, &project, options).await?;
+                Ok(0)
+            }
+            Some(Command::Analyze {
+                project,
+                format,
+                grouping,
+                gates,
+                memory,
+            }) => {
+                let grouping = grouping.load(args.config.as_deref(), &project)?;
+                let update_baseline = gates.update_baseline;
+                let gates = gates.apply(gates_config);
+                if update_baseline
+                    && gates.dynamic_baseline.is_none()
+                    && gates.coverage_baseline.is_none()
+                {
+                    anyhow::bail!(
+                        "--update-baseline requires --dynamic-baseline or --coverage-baseline"
+                    );
+                }
+                let options = AnalyzeOptions {
+                    format,
+                    grouping,
+                    update_dynamic_baseline: update_baseline && gates.dynamic_baseline.is_some(),
+                    update_coverage_baseline: update_baseline && gates.coverage_baseline.is_some(),
+                    quality_gates: gates,
+                    memory: memory.config(),
+                };
+                analyze(&central, project, options).await
+            }
+            Some(Command::VerifyDeterminism {
+                project,
+                threads,
+                json,
+            }) => verify_determinism(&project, threads, json),
+            Some(Command::Doctor {
+                limit,
+                json,
+                strict,
+            }) => doctor(&central, limit, json, strict).await,
+            Some(Command::DocsReport {
+                limit,
+                json,
+                csv,
+                output,
+            }) => docs_report(&central, limit, json, csv, output).await,
+            Some(Command::DependencyGraph {
+                project,
+                changed,
+                output,
+            }) => dependency_graph(&central, &project, &changed, output).await,
+            Some(Command::InferenceReference { html, output }) => {
+                inference_reference(&central, html, output).await
+            }
+            Some(Command::Selftest { corpus, json }) => selftest(&central, corpus, json).await,
+            Some(Command::Snapshot { action }) => snapshot(&central, action).await,
+            None => {
+                let health = central.health_check().await;
+                println!(
+                    "Health: {} (score {:.2})",
+                    health.status, health.overall_score
+                );
+                let metrics = central.get_system_metrics().await;
+                println!(
+                    "Types loaded: {} (platform: {}, config: {})",
+                    metrics.total_types, metrics.platform_types, metrics.configuration_types
+                );
+                Ok(0)
+            }
+        }
     });
 
+    central
+        .telemetry()
+        .record_timing(&format!("command.{}", command_name), command_start.elapsed());
+    if let Err(e) = central.telemetry().flush() {
+        warn!("Телеметрия не сохранена: {:#}", e);
+    }
+
+    // Профиль пишем и при ошибке анализа: медленный неудачный запуск тоже стоит разобрать
+    if let (Some(path), Some(recorder)) = (&profile, &recorder) {
+        if central.write_guard().allows("профиль", path) {
+            recorder.write_to(path)?;
+            info!("Profile written to {}", path.display());
+        }
+    }
+    let exit_code = exit_code?;
+
+    // Ненулевой код — нарушен порог качества (см. QualityGate::exit_code)
+    // или не удалось загрузить типы (EXIT_RUNTIME_ERROR)
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
     Ok(())
 }
+
+/// Параметры подкоманды `usage-report`
+struct UsageReportOptions {
+    top: usize,
+    json: bool,
+    output: Option<PathBuf>,
+    grouping: Grouping,
+}
+
+/// Подкоманда `usage-report`
+async fn usage_report(
+    central: &CentralTypeSystem,
+    project: &Path,
+    options: UsageReportOptions,
+) -> Result<()> {
+    let catalog = central.repository().load_all_types().await?;
+    let mut collector = UsageCollector::new(&catalog);
+    let grouping = &options.grouping;
+    if let Some(index) = &grouping.subsystems {
+        info!("Subsystems loaded: {}", index.subsystems().len());
+        let resolver = index.clone();
+        collector = collector
+            .with_group_resolver(Box::new(move |path| resolver.group_for_module(path)));
+        if let Some(name) = grouping.subsystem.clone() {
+            let filter = index.clone();
+            collector = collector
+                .with_module_filter(Box::new(move |path| filter.module_in_subsystem(path, &name)));
+        }
+    }
+    // Группировка по владельцам важнее подсистем: отчёт нужен для маршрутизации исправлений
+    if let Some(ownership) = &grouping.ownership {
+        let resolver = ownership.clone();
+        collector =
+            collector.with_group_resolver(Box::new(move |path| resolver.group_for_module(path)));
+    }
+    collector.add_directory(project)?;
+    let report = collector.finish(options.top);
+    let output = options.output;
+
+    let rendered = if options.json {
+        serde_json::to_string_pretty(&report)?
+    } else {
+        report.to_text()
+    };
+
+    // В режиме только для чтения отчёт выводится в stdout вместо файла
+    match output.filter(|path| central.write_guard().allows("отчёт об использовании", path)) {
+        Some(path) => {
+            std::fs::write(&path, rendered)?;
+            info!("Usage report written to {}", path.display());
+        }
+        None => println!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Параметры подкоманды `analyze`
+struct AnalyzeOptions {
+    format: OutputFormat,
+    grouping: Grouping,
+    quality_gates: QualityGatesConfig,
+    update_dynamic_baseline: bool,
+    update_coverage_baseline: bool,
+    memory: Option<BoundedAnalysisConfig>,
+}
+
+/// Подкоманда `analyze`; возвращает код выхода по порогам качества
+async fn analyze(
+    central: &CentralTypeSystem,
+    project: PathBuf,
+    options: AnalyzeOptions,
+) -> Result<i32> {
+    let AnalyzeOptions {
+        format,
+        grouping,
+        quality_gates,
+        update_dynamic_baseline,
+        update_coverage_baseline,
+        memory,
+    } = options;
+    let request = CliAnalysisRequest {
+        project_path: project,
+        output_format: match format {
+            OutputFormat::Text => CliOutputFormat::Text,
+            OutputFormat::Json => CliOutputFormat::Json,
+            OutputFormat::Jsonl => CliOutputFormat::Jsonl,
+        },
+        include_coverage: true,
+        include_errors: true,
+        verbose: false,
+        subsystems: grouping.subsystems,
+        subsystem_filter: grouping.subsystem,
+        ownership: grouping.ownership,
+        quality_gates: Some(quality_gates),
+        update_dynamic_baseline,
+        update_coverage_baseline,
+        memory,
+    };
+
+    let gates = if format == OutputFormat::Jsonl {
+        let mut stdout = std::io::stdout();
+        let result = central
+            .cli_interface()
+            .stream_analysis_jsonl(request, &mut stdout)
+            .await?;
+        result.gates
+    } else {
+        let response = central.cli_interface().handle_analysis_request(request).await?;
+        println!("{}", response.formatted_output);
+        response.gates
+    };
+
+    Ok(gates.map(|report| report.exit_code()).unwrap_or(0))
+}
+
+/// Подкоманда `verify-determinism`; возвращает код выхода
+fn verify_determinism(project: &Path, threads: Vec<usize>, json: bool) -> Result<i32> {
+    let mut config = DeterminismAuditConfig::default();
+    if !threads.is_empty() {
+        // Порядок файлов чередуется, чтобы прогоны отличались и при одинаковом числе потоков
+        let orders = [FileOrder::Sorted, FileOrder::Reversed, FileOrder::Interleaved];
+        config.runs = threads
+            .into_iter()
+            .enumerate()
+            .map(|(i, threads)| AuditRun {
+                threads: threads.max(1),
+                order: orders[i % orders.len()],
+            })
+            .collect();
+    }
+    if config.runs.len() < 2 {
+        anyhow::bail!("At least two runs are required (e.g. --threads 1,8)");
+    }
+
+    let files = ParallelAnalyzer::find_bsl_files(project)?;
+    info!("Determinism audit: {} files, {} runs", files.len(), config.runs.len());
+    let report = run_audit(&files, &config)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", report.to_text());
+    }
+
+    Ok(if report.is_deterministic() {
+        0
+    } else {
+        EXIT_NONDETERMINISTIC
+    })
+}
+
+/// Подкоманда `doctor`; возвращает код выхода
+async fn doctor(
+    central: &CentralTypeSystem,
+    limit: usize,
+    json: bool,
+    strict: bool,
+) -> Result<i32> {
+    let health = central.health_check().await;
+    let report = central.data_quality_report().await?;
+    let write_guard = central.write_guard();
+    let skipped_writes = write_guard.skipped_writes();
+
+    if json {
+        let value = serde_json::json!({
+            "health": health,
+            "data_quality": report,
+            "read_only": {
+                "enabled": write_guard.is_read_only(),
+                "skipped_writes": skipped_writes,
+            },
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        println!("Health: {} (score {:.2})", health.status, health.overall_score);
+        for component in &health.components {
+            println!("   {}: {}", component.name, component.status);
+        }
+        if write_guard.is_read_only() {
+            println!(
+                "Read-only mode: {} write(s) kept in memory",
+                skipped_writes.len()
+            );
+            for write in skipped_writes.iter().take(limit) {
+                println!("   {}: {}", write.operation, write.path);
+            }
+        }
+        println!();
+        print!("{}", report.to_text(limit));
+    }
+
+    Ok(if strict && !report.is_consistent() {
+        EXIT_DATA_INCONSISTENT
+    } else {
+        0
+    })
+}
+
+/// Подкоманда `docs-report`
+async fn docs_report(
+    central: &CentralTypeSystem,
+    limit: usize,
+    json: bool,
+    csv: bool,
+    output: Option<PathBuf>,
+) -> Result<i32> {
+    let report = central.documentation_report().await?;
+    let rendered = if json {
+        serde_json::to_string_pretty(&report)?
+    } else if csv {
+        report.to_csv()
+    } else {
+        report.to_text(limit)
+    };
+
+    // В режиме только для чтения отчёт выводится в stdout вместо файла
+    match output.filter(|path| central.write_guard().allows("отчёт о документации", path)) {
+        Some(path) => {
+            std::fs::write(&path, rendered)?;
+            info!("Documentation report written to {}", path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(0)
+}
+
+/// Подкоманда `dependency-graph`
+async fn dependency_graph(
+    central: &CentralTypeSystem,
+    project: &Path,
+    changed: &[PathBuf],
+    output: Option<PathBuf>,
+) -> Result<i32> {
+    let graph = central
+        .analysis_service()
+        .build_dependency_graph(project)
+        .await?;
+    let rendered = if changed.is_empty() {
+        graph.to_dot()
+    } else {
+        // Пути сопоставляются с путями, найденными обходом каталога проекта
+        let changed: Vec<String> = changed
+            .iter()
+            .map(|path| match path.strip_prefix(project) {
+                Ok(_) => path.to_string_lossy().to_string(),
+                Err(_) => project.join(path).to_string_lossy().to_string(),
+            })
+            .collect();
+        let affected = graph.affected_by(changed.iter().map(String::as_str));
+        affected.into_iter().map(|file| file + "\n").collect()
+    };
+
+    let output = output.filter(|path| central.write_guard().allows("граф зависимостей", path));
+    match output {
+        Some(path) => {
+            std::fs::write(&path, rendered)?;
+            info!("Dependency graph written to {}", path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(0)
+}
+
+/// Подкоманда `inference-reference`
+async fn inference_reference(
+    central: &CentralTypeSystem,
+    html: bool,
+    output: Option<PathBuf>,
+) -> Result<i32> {
+    let reference = central.inference_reference().await;
+    let rendered = if html {
+        reference.to_html()
+    } else {
+        reference.to_markdown()
+    };
+
+    match output.filter(|path| {
+        central
+            .write_guard()
+            .allows("справка по выводу типов", path)
+    }) {
+        Some(path) => {
+            std::fs::write(&path, rendered)?;
+            info!("Inference reference written to {}", path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(0)
+}
+
+/// Подкоманда `selftest`; возвращает код выхода
+async fn selftest(central: &CentralTypeSystem, corpus: Option<PathBuf>, json: bool) -> Result<i32> {
+    let fixtures = match corpus {
+        Some(dir) => load_fixtures(&dir)?,
+        None => bundled_fixtures()?,
+    };
+    info!("Golden corpus: {} fixtures", fixtures.len());
+    let report = run_corpus(&fixtures, central.repository()).await;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.to_text());
+    }
+
+    Ok(if report.passed() { 0 } else { EXIT_SELFTEST_FAILED })
+}
+
+/// Подкоманда `snapshot`
+async fn snapshot(central: &CentralTypeSystem, action: SnapshotAction) -> Result<i32> {
+    // Единственный результат этих команд — файлы, держать их в памяти бессмысленно
+    if central.write_guard().is_read_only() {
+        anyhow::bail!("Snapshot commands write files and are unavailable in read-only mode");
+    }
+    let manifest = match action {
+        SnapshotAction::Export { output } => {
+            let types = central.repository().load_all_types().await?;
+            let platform_version = central.config().await.platform_version;
+            let manifest = export_platform_artifact(&types, &platform_version, &output)?;
+            info!("Snapshot artifact written to {}", output.display());
+            manifest
+        }
+        SnapshotAction::Import {
+            artifact,
+            output,
+            expect_version,
+        } => {
+            let manifest = import_artifact(&artifact, &output, expect_version.as_deref())?;
+            info!(
+                "Snapshot imported to {} (set BSL_SNAPSHOT to use it)",
+                output.display()
+            );
+            manifest
+        }
+        SnapshotAction::Diff {
+            base,
+            target,
+            output,
+        } => {
+            let delta = create_delta(&base, &target, &output)?;
+            info!(
+                "Delta {} -> {} written to {} ({} changed, {} removed)",
+                delta.base_platform_version,
+                delta.target.platform_version,
+                output.display(),
+                delta.upserted,
+                delta.removed.len()
+            );
+            println!("{}", serde_json::to_string_pretty(&delta)?);
+            return Ok(0);
+        }
+        SnapshotAction::ApplyDelta {
+            delta,
+            base,
+            output,
+        } => {
+            let output = output.unwrap_or_else(|| base.clone());
+            let manifest = apply_delta(&delta, &base, &output)?;
+            info!(
+                "Snapshot {} updated to platform {}",
+                output.display(),
+                manifest.platform_version
+            );
+            manifest
+        }
+    };
+    println!("{}", serde_json::to_string_pretty(&manifest)?);
+    Ok(0)
+}
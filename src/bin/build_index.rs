@@ -2,6 +2,7 @@
 
 use anyhow::Result;
 use clap::Parser;
+use std::path::Path;
 use tracing::info;
 
 #[derive(Parser)]
@@ -16,12 +17,13 @@ struct Args {
     #[arg(short = 'v', long, default_value = "8.3.25")]
     platform_version: String,
 
-    /// Output path
+    /// Output path of the memory-mapped repository snapshot (see BSL_SNAPSHOT)
     #[arg(short, long)]
     output: Option<String>,
 }
 
-use bsl_gradual_types::architecture::data::TypeRepository;
+use bsl_gradual_types::architecture::data::write_snapshot;
+use bsl_gradual_types::system::{CentralSystemConfig, CentralTypeSystem};
 
 fn main() -> Result<()> {
     let args = Args::parse();
@@ -33,7 +35,27 @@ fn main() -> Result<()> {
     info!("Building type index from: {}", args.config);
     info!("Platform version: {}", args.platform_version);
 
-    // TODO: Implement index building
+    // Индекс строится разбором исходников, а не из существующего снимка
+    let mut cfg = CentralSystemConfig::from_env()?;
+    cfg.configuration_path = Some(args.config.clone());
+    cfg.platform_version = args.platform_version.clone();
+    cfg.snapshot_path = None;
+    cfg.remote_repository_url = None;
+    let central = CentralTypeSystem::new(cfg);
+
+    let rt = tokio::runtime::Runtime::new()?;
+    let types = rt.block_on(async {
+        central.initialize().await?;
+        Ok::<_, anyhow::Error>(central.repository().load_all_types().await?)
+    })?;
+
+    match &args.output {
+        Some(output) => {
+            write_snapshot(Path::new(output), &types)?;
+            info!("Snapshot with {} types written to {}", types.len(), output);
+        }
+        None => info!("Indexed {} types (use --output to write a snapshot)", types.len()),
+    }
 
     info!("Index building complete");
 
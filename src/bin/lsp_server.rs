@@ -3,16 +3,43 @@
 use anyhow::Result;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tower_lsp::jsonrpc::Result as JsonRpcResult;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use clap::Parser;
 
 // Target architecture
-use bsl_gradual_types::system::{CentralSystemConfig, CentralTypeSystem};
+use bsl_gradual_types::architecture::presentation::formatting::{
+    format_document, format_on_type, format_range,
+};
+use bsl_gradual_types::core::call_hierarchy::{CallRange, CallableMethod};
+use bsl_gradual_types::core::common_module_calls::{module_name_from_path, CommonModuleIndex};
+use bsl_gradual_types::core::document_symbols::document_symbols;
+use bsl_gradual_types::core::example_mining::{
+    ExampleCorpus, ReceiverTypes, DEFAULT_EXAMPLES_PER_MEMBER,
+};
+use bsl_gradual_types::core::expected_type::expected_type_at;
+use bsl_gradual_types::core::extract_procedure::extract_procedure_action;
+use bsl_gradual_types::core::folding_ranges::folding_ranges;
+use bsl_gradual_types::core::incremental_diagnostics::apply_content_changes;
+use bsl_gradual_types::core::module_variables::organize_variables_action;
+use bsl_gradual_types::core::semantic_tokens::{TOKEN_MODIFIERS, TOKEN_TYPES};
+use bsl_gradual_types::core::type_checker::TypeChecker;
+use bsl_gradual_types::core::type_quick_fixes::type_quick_fixes;
+use bsl_gradual_types::core::workspace_symbols::{
+    ProjectSymbolIndex, WorkspaceSymbol, WorkspaceSymbolKind,
+};
+use bsl_gradual_types::documentation::core::providers::{DocumentationProvider, ProviderConfig};
+use bsl_gradual_types::documentation::PlatformDocumentationProvider;
+use bsl_gradual_types::parsing::bsl::common::ParserFactory;
+use bsl_gradual_types::parsing::bsl::tree_sitter_adapter::TreeSitterAdapter;
+use bsl_gradual_types::system::{CentralSystemConfig, CentralTypeSystem, StrictnessProfile};
+
+/// Команда открытия файла описания объекта метаданных (ссылка из hover)
+const OPEN_METADATA_COMMAND: &str = "bsl.openMetadataDefinition";
 
 #[derive(Parser, Debug)]
 #[command(name = "lsp-server")]
@@ -24,14 +51,378 @@ struct BslLanguageServer {
     client: Client,
     documents: Arc<RwLock<HashMap<Url, String>>>,
     central: Arc<CentralTypeSystem>,
+    /// Примеры использования из проекта (собираются в фоне после initialize)
+    examples: Arc<RwLock<Option<ExampleCorpus>>>,
+    /// Экспортные методы общих модулей проекта (собираются в фоне после initialize)
+    common_modules: Arc<RwLock<Option<CommonModuleIndex>>>,
+    /// Корень рабочей области (поиск вызовов при переименовании)
+    workspace_root: Arc<RwLock<Option<std::path::PathBuf>>>,
+    /// Очередь фонового пересчёта диагностик
+    diagnostics_queue: mpsc::UnboundedSender<DiagnosticsRequest>,
+}
+
+/// Документ и версия, для которой публикуются диагностики
+type DiagnosticsRequest = (Url, Option<i32>);
+
+/// Пауза перед анализом: серия быстрых правок анализируется один раз
+const DIAGNOSTICS_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// Фоновый пересчёт диагностик. Запросы, пришедшие за время паузы,
+/// объединяются по документу; заново анализируются только изменённые методы
+async fn run_diagnostics_worker(
+    client: Client,
+    central: Arc<CentralTypeSystem>,
+    mut queue: mpsc::UnboundedReceiver<DiagnosticsRequest>,
+) {
+    while let Some((uri, version)) = queue.recv().await {
+        let mut latest = HashMap::from([(uri, version)]);
+        tokio::time::sleep(DIAGNOSTICS_DEBOUNCE).await;
+        while let Ok((uri, version)) = queue.try_recv() {
+            latest.insert(uri, version);
+        }
+        let strictness = central.config().await.strictness;
+        for (uri, version) in latest {
+            let diagnostics = central
+                .lsp_interface()
+                .document_diagnostics(uri.as_str())
+                .await;
+            let diagnostics = to_lsp_diagnostics(diagnostics, strictness);
+            client.publish_diagnostics(uri, diagnostics, version).await;
+        }
+    }
+}
+
+/// Диагностики target-движка в формате LSP с учётом профиля строгости
+fn to_lsp_diagnostics(
+    diagnostics: Vec<bsl_gradual_types::unified::presentation::LspDiagnostic>,
+    strictness: StrictnessProfile,
+) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .filter(|d| strictness.reports(d.severity))
+        .map(|d| Diagnostic {
+            range: Range {
+                start: Position {
+                    line: d.range.start.line,
+                    character: d.range.start.character,
+                },
+                end: Position {
+                    line: d.range.end.line,
+                    character: d.range.end.character,
+                },
+            },
+            severity: Some(match d.severity {
+                1 => DiagnosticSeverity::ERROR,
+                2 => DiagnosticSeverity::WARNING,
+                3 => DiagnosticSeverity::INFORMATION,
+                4 => DiagnosticSeverity::HINT,
+                _ => DiagnosticSeverity::INFORMATION,
+            }),
+            source: Some("bsl-target".to_string()),
+            message: d.message,
+            ..Default::default()
+        })
+        .collect()
 }
 
 impl BslLanguageServer {
     fn new(client: Client, central: Arc<CentralTypeSystem>) -> Self {
+        let (diagnostics_queue, queue) = mpsc::unbounded_channel();
+        tokio::spawn(run_diagnostics_worker(client.clone(), central.clone(), queue));
         Self {
             client,
             documents: Arc::new(RwLock::new(HashMap::new())),
             central,
+            examples: Arc::new(RwLock::new(None)),
+            common_modules: Arc::new(RwLock::new(None)),
+            workspace_root: Arc::new(RwLock::new(None)),
+            diagnostics_queue,
+        }
+    }
+
+    /// Запустить фоновую индексацию общих модулей рабочей области
+    fn spawn_common_module_indexing(&self, root: std::path::PathBuf) {
+        let common_modules = self.common_modules.clone();
+        let central = self.central.clone();
+        tokio::spawn(async move {
+            let scanned =
+                tokio::task::spawn_blocking(move || CommonModuleIndex::scan_directory(&root))
+                    .await;
+            match scanned {
+                Ok(Ok(index)) => {
+                    info!("Indexed {} common modules", index.len());
+                    central
+                        .lsp_interface()
+                        .set_common_module_index(index.clone())
+                        .await;
+                    *common_modules.write().await = Some(index);
+                }
+                Ok(Err(e)) => error!("common module indexing failed: {}", e),
+                Err(e) => error!("common module indexing task failed: {}", e),
+            }
+        });
+    }
+
+    /// Запустить фоновую загрузку справки синтакс-помощника (`BSL_HTML_PATH`)
+    /// для подсказки сигнатур
+    fn spawn_platform_documentation_loading(&self) {
+        let central = self.central.clone();
+        tokio::spawn(async move {
+            let mut config = ProviderConfig {
+                data_source: central.config().await.html_path,
+                ..Default::default()
+            };
+            config.performance_settings.show_progress = false;
+            let provider = Arc::new(PlatformDocumentationProvider::new());
+            match provider.initialize(&config).await {
+                Ok(()) => {
+                    info!(
+                        "Loaded syntax helper: {} platform types",
+                        provider.get_loaded_types_count().await
+                    );
+                    central
+                        .lsp_interface()
+                        .set_platform_documentation(provider)
+                        .await;
+                }
+                Err(e) => error!("syntax helper loading failed: {}", e),
+            }
+        });
+    }
+
+    /// Запустить фоновую индексацию символов рабочей области: объекты
+    /// конфигурации из единого репозитория и процедуры модулей проекта
+    fn spawn_workspace_symbol_indexing(&self, root: Option<std::path::PathBuf>) {
+        let central = self.central.clone();
+        tokio::spawn(async move {
+            match central.repository().load_all_types().await {
+                Ok(types) => {
+                    let summary = central.lsp_interface().index_repository_types(&types).await;
+                    info!("Indexed {} configuration objects for symbols", summary.added);
+                }
+                Err(e) => error!("repository symbol indexing failed: {}", e),
+            }
+            let Some(root) = root else {
+                return;
+            };
+            let scanned =
+                tokio::task::spawn_blocking(move || ProjectSymbolIndex::scan_directory(&root))
+                    .await;
+            match scanned {
+                Ok(Ok(index)) => {
+                    info!("Indexed {} project procedures and functions", index.len());
+                    central.lsp_interface().set_project_symbol_index(index).await;
+                }
+                Ok(Err(e)) => error!("project symbol indexing failed: {}", e),
+                Err(e) => error!("project symbol indexing task failed: {}", e),
+            }
+        });
+    }
+
+    /// Запустить фоновое построение графа вызовов проекта
+    fn spawn_call_graph_building(&self, root: std::path::PathBuf) {
+        let central = self.central.clone();
+        tokio::spawn(async move {
+            match central.analysis_service().build_call_graph(&root).await {
+                Ok(graph) => {
+                    info!("Built call graph: {} methods", graph.len());
+                    central.lsp_interface().set_call_graph(graph).await;
+                }
+                Err(e) => error!("call graph building failed: {}", e),
+            }
+        });
+    }
+
+    /// Запрос иерархии вызовов для позиции с текстом открытых документов
+    async fn call_hierarchy_request(
+        &self,
+        uri: &Url,
+        position: Position,
+    ) -> bsl_gradual_types::unified::presentation::LspCallHierarchyRequest {
+        // Пути файловой системы: по ним граф сопоставляет модули проекта
+        let fs_path = |uri: &Url| {
+            uri.to_file_path()
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_else(|_| uri.to_string())
+        };
+        let open_documents = self
+            .documents
+            .read()
+            .await
+            .iter()
+            .map(|(uri, text)| (fs_path(uri), text.clone()))
+            .collect();
+        bsl_gradual_types::unified::presentation::LspCallHierarchyRequest {
+            file_path: fs_path(uri),
+            line: position.line,
+            column: position.character,
+            open_documents,
+        }
+    }
+
+    /// Метод графа вызовов в формате LSP; `None`, если файл не найден
+    fn to_call_hierarchy_item(method: CallableMethod) -> Option<CallHierarchyItem> {
+        let uri = Self::definition_uri(&method.file)?;
+        let name_end = method.column + method.name.encode_utf16().count() as u32;
+        Some(CallHierarchyItem {
+            kind: if method.is_function {
+                SymbolKind::FUNCTION
+            } else {
+                SymbolKind::METHOD
+            },
+            tags: None,
+            detail: method.module.as_ref().map(|_| method.qualified_name()),
+            uri,
+            range: Range::new(
+                Position::new(method.line, 0),
+                Position::new(method.end_line + 1, 0),
+            ),
+            selection_range: Range::new(
+                Position::new(method.line, method.column),
+                Position::new(method.line, name_end),
+            ),
+            data: None,
+            name: method.name,
+        })
+    }
+
+    fn call_ranges(ranges: Vec<CallRange>) -> Vec<Range> {
+        ranges
+            .into_iter()
+            .map(|range| {
+                Range::new(
+                    Position::new(range.line, range.column),
+                    Position::new(range.line, range.column + range.length),
+                )
+            })
+            .collect()
+    }
+
+    /// Символ рабочей области в формате LSP; `None`, если файл не найден
+    fn to_symbol_information(symbol: WorkspaceSymbol) -> Option<SymbolInformation> {
+        let uri = Self::definition_uri(&symbol.file)?;
+        let kind = match symbol.kind {
+            WorkspaceSymbolKind::Procedure => SymbolKind::METHOD,
+            WorkspaceSymbolKind::Function => SymbolKind::FUNCTION,
+            WorkspaceSymbolKind::ConfigurationObject => SymbolKind::STRUCT,
+            WorkspaceSymbolKind::PlatformType => SymbolKind::CLASS,
+        };
+        let start = Position::new(symbol.line, symbol.column);
+        let end = Position::new(
+            symbol.line,
+            symbol.column + symbol.name.encode_utf16().count() as u32,
+        );
+        #[allow(deprecated)]
+        let information = SymbolInformation {
+            name: symbol.name,
+            kind,
+            tags: None,
+            deprecated: None,
+            location: Location::new(uri, Range::new(start, end)),
+            container_name: symbol.container,
+        };
+        Some(information)
+    }
+
+    /// Запустить фоновый сбор примеров из корня рабочей области
+    fn spawn_example_mining(&self, root: std::path::PathBuf) {
+        let examples = self.examples.clone();
+        let central = self.central.clone();
+        tokio::spawn(async move {
+            // Каталог нужен, чтобы отбирать только вызовы платформенного API
+            let catalog = match central.repository().load_all_types().await {
+                Ok(catalog) => catalog,
+                Err(e) => {
+                    error!("example mining skipped, repository unavailable: {}", e);
+                    return;
+                }
+            };
+            let mined = tokio::task::spawn_blocking(move || {
+                ExampleCorpus::mine_directory(&root, &catalog, DEFAULT_EXAMPLES_PER_MEMBER)
+            })
+            .await;
+            match mined {
+                Ok(Ok(corpus)) => {
+                    info!(
+                        "Mined project examples: {} members from {} modules",
+                        corpus.members_count(),
+                        corpus.files_scanned()
+                    );
+                    *examples.write().await = Some(corpus);
+                }
+                Ok(Err(e)) => error!("example mining failed: {}", e),
+                Err(e) => error!("example mining task failed: {}", e),
+            }
+        });
+    }
+
+    /// Markdown с примерами из проекта для выражения под курсором; тип
+    /// получателя метода берётся из присваиваний `Новый` выше по документу
+    async fn project_examples_markdown(
+        &self,
+        expression: &str,
+        text: &str,
+        line: u32,
+    ) -> Option<String> {
+        let member = expression.rsplit('.').next()?.trim();
+        if member.is_empty() {
+            return None;
+        }
+        let examples = self.examples.read().await;
+        let corpus = examples.as_ref()?;
+        let found = match expression.rsplit_once('.') {
+            Some((receiver, _)) => {
+                let receiver = receiver.rsplit('.').next().unwrap_or_default().trim();
+                let receivers = ReceiverTypes::scan(text.lines().take(line as usize + 1));
+                corpus.examples_for_call(receivers.type_of(receiver), member)
+            }
+            None => corpus.examples_for_type(member),
+        };
+        ExampleCorpus::render_markdown(found)
+    }
+
+    /// Markdown со ссылками на файл описания объекта метаданных: обычная
+    /// ссылка на файл и command-link, открывающий его через `window/showDocument`
+    fn metadata_definition_markdown(path: &str) -> Option<String> {
+        let path = std::fs::canonicalize(path).ok()?;
+        let uri = Url::from_file_path(&path).ok()?;
+        let file_name = path.file_name()?.to_string_lossy();
+        let arguments = serde_json::json!([uri.to_string()]).to_string();
+        Some(format!(
+            "Метаданные: [{}]({}) · [Открыть определение](command:{}?{})",
+            file_name,
+            uri,
+            OPEN_METADATA_COMMAND,
+            urlencoding::encode(&arguments)
+        ))
+    }
+
+    /// URI файла определения: путь файловой системы или URI открытого документа
+    fn definition_uri(file_path: &str) -> Option<Url> {
+        if let Ok(uri) = Url::parse(file_path) {
+            if uri.scheme() == "file" {
+                return Some(uri);
+            }
+        }
+        let path = std::fs::canonicalize(file_path).ok()?;
+        Url::from_file_path(path).ok()
+    }
+
+    /// Поставить документ в очередь фонового пересчёта диагностик
+    fn schedule_diagnostics(&self, uri: Url, version: Option<i32>) {
+        if self.diagnostics_queue.send((uri, version)).is_err() {
+            error!("diagnostics worker stopped");
+        }
+    }
+
+    /// Пересчитать диагностики всех открытых документов
+    async fn refresh_open_documents(&self) {
+        self.central
+            .lsp_interface()
+            .invalidate_document_diagnostics()
+            .await;
+        for uri in self.documents.read().await.keys() {
+            self.schedule_diagnostics(uri.clone(), None);
         }
     }
 
@@ -75,12 +466,83 @@ impl BslLanguageServer {
 
         String::new()
     }
+
+    /// Семантические токены документа (или диапазона) от target-движка
+    async fn semantic_tokens(&self, uri: &Url, range: Option<Range>) -> Option<SemanticTokens> {
+        let text = self.documents.read().await.get(uri)?.clone();
+        let position = |p: Position| bsl_gradual_types::unified::presentation::LspPosition {
+            line: p.line,
+            character: p.character,
+        };
+        let req = bsl_gradual_types::unified::presentation::LspSemanticTokensRequest {
+            file_path: uri.to_string(),
+            text,
+            range: range.map(|r| bsl_gradual_types::unified::presentation::LspRange {
+                start: position(r.start),
+                end: position(r.end),
+            }),
+        };
+        match self
+            .central
+            .lsp_interface()
+            .handle_semantic_tokens_request(req)
+            .await
+        {
+            Ok(response) => Some(SemanticTokens {
+                result_id: None,
+                data: response
+                    .data
+                    .chunks_exact(5)
+                    .map(|token| SemanticToken {
+                        delta_line: token[0],
+                        delta_start: token[1],
+                        length: token[2],
+                        token_type: token[3],
+                        token_modifiers_bitset: token[4],
+                    })
+                    .collect(),
+            }),
+            Err(e) => {
+                error!("target semantic tokens failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Выражение под курсором для перехода к определению: префикс до курсора
+    /// и остаток идентификатора после него
+    fn get_definition_expression(&self, text: &str, position: Position) -> String {
+        let mut expression = self.get_completion_prefix(text, position);
+        let Some(line) = text.lines().nth(position.line as usize) else {
+            return expression;
+        };
+        let mut char_count = 0u32;
+        let rest = line
+            .chars()
+            .skip_while(|ch| {
+                let before = char_count < position.character;
+                char_count += ch.len_utf16() as u32;
+                before
+            })
+            .take_while(|ch| ch.is_alphanumeric() || *ch == '_');
+        expression.extend(rest);
+        expression
+    }
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for BslLanguageServer {
     async fn initialize(&self, params: InitializeParams) -> JsonRpcResult<InitializeResult> {
         info!("Initializing BSL Language Server");
+        self.spawn_platform_documentation_loading();
+        let root = params.root_uri.as_ref().and_then(|uri| uri.to_file_path().ok());
+        self.spawn_workspace_symbol_indexing(root.clone());
+        if let Some(root) = root {
+            *self.workspace_root.write().await = Some(root.clone());
+            self.spawn_call_graph_building(root.clone());
+            self.spawn_example_mining(root.clone());
+            self.spawn_common_module_indexing(root);
+        }
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
@@ -95,10 +557,54 @@ impl LanguageServer for BslLanguageServer {
                     },
                 )),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                document_formatting_provider: Some(OneOf::Left(true)),
+                document_range_formatting_provider: Some(OneOf::Left(true)),
+                document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+                    first_trigger_character: ";".to_string(),
+                    more_trigger_character: Some(vec!["\n".to_string()]),
+                }),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+                call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+                inlay_hint_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Left(true)),
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            legend: SemanticTokensLegend {
+                                token_types: TOKEN_TYPES
+                                    .iter()
+                                    .copied()
+                                    .map(SemanticTokenType::new)
+                                    .collect(),
+                                token_modifiers: TOKEN_MODIFIERS
+                                    .iter()
+                                    .copied()
+                                    .map(SemanticTokenModifier::new)
+                                    .collect(),
+                            },
+                            range: Some(true),
+                            full: Some(SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: vec![OPEN_METADATA_COMMAND.to_string()],
+                    ..Default::default()
+                }),
                 completion_provider: Some(CompletionOptions {
                     trigger_characters: Some(vec![".".to_string(), " ".to_string()]),
                     ..Default::default()
                 }),
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                    retrigger_characters: None,
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
                 diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
                     DiagnosticOptions {
                         identifier: Some("bsl-gradual-types".to_string()),
@@ -121,6 +627,9 @@ impl LanguageServer for BslLanguageServer {
 
     async fn shutdown(&self) -> JsonRpcResult<()> {
         info!("Shutting down BSL Language Server");
+        if let Err(e) = self.central.telemetry().flush() {
+            warn!("Телеметрия не сохранена: {:#}", e);
+        }
         Ok(())
     }
 
@@ -135,64 +644,12 @@ impl LanguageServer for BslLanguageServer {
             .await
             .insert(uri.clone(), text.clone());
 
-        // Диагностики через CentralTypeSystem (target)
-        let base_diagnostics: Result<Vec<Diagnostic>, anyhow::Error> = Ok(Vec::new());
-
-        // Если target-движок, добавим базовые диагностики совместимости типов через CentralTypeSystem
-        let mut all_diagnostics: Vec<Diagnostic> = match base_diagnostics {
-            Ok(d) => d,
-            Err(e) => {
-                error!("Failed to analyze document {}: {}", uri, e);
-                Vec::new()
-            }
-        };
-        // Берём актуальный текст документа
-        let documents = self.documents.read().await;
-        if let Some(text) = documents.get(&uri) {
-            match self
-                .central
-                .lsp_interface()
-                .analyze_text_for_diagnostics(&uri.to_string(), text)
-                .await
-            {
-                Ok(diags) => {
-                    for d in diags {
-                        all_diagnostics.push(Diagnostic {
-                            range: Range {
-                                start: Position {
-                                    line: d.range.start.line,
-                                    character: d.range.start.character,
-                                },
-                                end: Position {
-                                    line: d.range.end.line,
-                                    character: d.range.end.character,
-                                },
-                            },
-                            severity: Some(match d.severity {
-                                1 => DiagnosticSeverity::ERROR,
-                                2 => DiagnosticSeverity::WARNING,
-                                3 => DiagnosticSeverity::INFORMATION,
-                                4 => DiagnosticSeverity::HINT,
-                                _ => DiagnosticSeverity::INFORMATION,
-                            }),
-                            code: None,
-                            code_description: None,
-                            source: Some("bsl-target".to_string()),
-                            message: d.message,
-                            related_information: None,
-                            tags: None,
-                            data: None,
-                        });
-                    }
-                }
-                Err(e) => error!("target diagnostics failed: {}", e),
-            }
-        }
-
-        // Отправляем объединённые диагностики
-        self.client
-            .publish_diagnostics(uri.clone(), all_diagnostics, Some(version))
+        // Диагностики через CentralTypeSystem (target) — в фоне
+        self.central
+            .lsp_interface()
+            .open_document(uri.as_str(), &text)
             .await;
+        self.schedule_diagnostics(uri.clone(), Some(version));
 
         self.client
             .log_message(
@@ -207,95 +664,65 @@ impl LanguageServer for BslLanguageServer {
         let version = params.text_document.version;
         let changes = params.content_changes;
 
-        // Применяем изменения к тексту
-        let updated_text = if let Some(full_change) = changes.iter().find(|c| c.range.is_none()) {
-            // Полное обновление содержимого
-            full_change.text.clone()
-        } else {
-            // Инкрементальные изменения - применяем к существующему тексту
-            let existing_text = self
-                .documents
-                .read()
-                .await
-                .get(&uri)
-                .cloned()
-                .unwrap_or_default();
-
-            // TODO: Более сложная логика применения инкрементальных изменений
-            // Пока используем последнее изменение как полное
-            changes
-                .last()
-                .map(|c| c.text.clone())
-                .unwrap_or(existing_text)
-        };
-
-        // Кешируем текст
-        self.documents
-            .write()
-            .await
-            .insert(uri.clone(), updated_text.clone());
-        let _ = (version, changes); // не используем, пока нет инкрементального анализатора в target
+        // Применяем изменения к тексту (полная замена или диапазоны)
+        let mut documents = self.documents.write().await;
+        let existing_text = documents.get(&uri).map_or("", String::as_str);
+        let (updated_text, _) = apply_content_changes(existing_text, &changes);
+        documents.insert(uri.clone(), updated_text.clone());
+        drop(documents);
 
-        // Базовые диагностики (пусто)
-        let base_diagnostics: Result<Vec<Diagnostic>, anyhow::Error> = Ok(Vec::new());
+        // Дерево tree-sitter обновляется инкрементально, а фоновый пересчёт
+        // анализирует только изменённые методы
+        let lsp_interface = self.central.lsp_interface();
+        if !lsp_interface.change_document(uri.as_str(), &changes).await {
+            lsp_interface.open_document(uri.as_str(), &updated_text).await;
+        }
+        self.schedule_diagnostics(uri, Some(version));
+    }
 
-        let mut all_diagnostics: Vec<Diagnostic> = base_diagnostics.unwrap_or_else(|e| {
-            error!("Failed to incrementally analyze document {}: {}", uri, e);
-            Vec::new()
-        });
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        // Настройки редактора накладываются на текущую конфигурацию системы
+        let mut config = self.central.config().await;
+        if let Err(e) = config.apply_editor_settings(&params.settings) {
+            self.client
+                .show_message(MessageType::WARNING, format!("Некорректные настройки BSL: {}", e))
+                .await;
+            return;
+        }
 
-        // Берём актуальный текст документа
-        let documents = self.documents.read().await;
-        if let Some(text) = documents.get(&uri) {
-            match self
-                .central
-                .lsp_interface()
-                .analyze_text_for_diagnostics(&uri.to_string(), text)
-                .await
-            {
-                Ok(diags) => {
-                    for d in diags {
-                        all_diagnostics.push(Diagnostic {
-                            range: Range {
-                                start: Position {
-                                    line: d.range.start.line,
-                                    character: d.range.start.character,
-                                },
-                                end: Position {
-                                    line: d.range.end.line,
-                                    character: d.range.end.character,
-                                },
-                            },
-                            severity: Some(match d.severity {
-                                1 => DiagnosticSeverity::ERROR,
-                                2 => DiagnosticSeverity::WARNING,
-                                3 => DiagnosticSeverity::INFORMATION,
-                                4 => DiagnosticSeverity::HINT,
-                                _ => DiagnosticSeverity::INFORMATION,
-                            }),
-                            code: None,
-                            code_description: None,
-                            source: Some("bsl-target".to_string()),
-                            message: d.message,
-                            related_information: None,
-                            tags: None,
-                            data: None,
-                        });
+        match self.central.update_config(config).await {
+            Ok(update) => {
+                if !update.restart_required.is_empty() {
+                    self.client
+                        .log_message(
+                            MessageType::WARNING,
+                            format!(
+                                "Настройки вступят в силу после перезапуска сервера: {}",
+                                update.restart_required.join(", ")
+                            ),
+                        )
+                        .await;
+                }
+                if update.diagnostics_changed {
+                    self.refresh_open_documents().await;
+                }
+                if update.inlay_hints_changed {
+                    if let Err(e) = self.client.inlay_hint_refresh().await {
+                        warn!("inlay hint refresh failed: {}", e);
                     }
                 }
-                Err(e) => error!("target diagnostics failed: {}", e),
             }
+            Err(e) => error!("failed to apply configuration: {}", e),
         }
-
-        // Отправляем обновленные диагностики
-        self.client
-            .publish_diagnostics(uri.clone(), all_diagnostics, Some(version))
-            .await;
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
         let uri = params.text_document.uri;
         self.documents.write().await.remove(&uri);
+        self.central
+            .lsp_interface()
+            .close_document(uri.as_str())
+            .await;
 
         self.client
             .log_message(MessageType::INFO, format!("Closed document: {}", uri))
@@ -308,6 +735,9 @@ impl LanguageServer for BslLanguageServer {
     ) -> JsonRpcResult<Option<CompletionResponse>> {
         let uri = params.text_document_position.text_document.uri;
         let position = params.text_document_position.position;
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.completion");
+        let started = std::time::Instant::now();
         // Target-only path
         // Берём текущий текст документа, чтобы вычислить префикс
         let documents = self.documents.read().await;
@@ -316,36 +746,62 @@ impl LanguageServer for BslLanguageServer {
             None => return Ok(None),
         };
         let prefix = self.get_completion_prefix(text, position);
+        // Экспортные методы других общих модулей вставляются с именем модуля
+        let current_module = uri.to_file_path().ok().and_then(|p| module_name_from_path(&p));
+        let common_items = self
+            .common_modules
+            .read()
+            .await
+            .as_ref()
+            .map(|index| index.completion_items(&prefix, current_module.as_deref()))
+            .unwrap_or_default();
+        // Ожидаемый тип (присваивание, аргумент метода модуля) влияет на порядок
+        let expected_type = ParserFactory::create().parse(text).ok().and_then(|program| {
+            let file_name = uri.path_segments().and_then(|mut s| s.next_back()).unwrap_or("module.bsl");
+            let (type_context, _) = TypeChecker::new(file_name.to_string()).check(&program);
+            expected_type_at(text, position.line, position.character, &type_context)
+        });
         let req = bsl_gradual_types::unified::presentation::LspCompletionRequest {
             file_path: uri.to_string(),
             line: position.line,
             column: position.character,
             prefix,
             trigger_character: None,
+            expected_type,
+            document_text: Some(text.clone()),
         };
-        match self
+        let response = self
             .central
             .lsp_interface()
             .handle_completion_request(req)
-            .await
-        {
+            .await;
+        telemetry.record_timing("lsp.completion", started.elapsed());
+        match response {
             Ok(resp) => {
-                let items: Vec<CompletionItem> = resp
+                let mut items: Vec<CompletionItem> = resp
                     .items
                     .into_iter()
-                    .map(|it| CompletionItem {
-                        label: it.label,
-                        kind: None,
-                        detail: it.detail,
-                        documentation: it.documentation.map(|doc| {
-                            Documentation::MarkupContent(MarkupContent {
-                                kind: MarkupKind::Markdown,
-                                value: doc,
-                            })
-                        }),
-                        ..Default::default()
+                    .map(|it| {
+                        // Шаблоны вставляются сниппетом с позициями табуляции
+                        let snippet = it.insert_text_format.is_some();
+                        CompletionItem {
+                            label: it.label,
+                            kind: snippet.then_some(CompletionItemKind::SNIPPET),
+                            detail: it.detail,
+                            sort_text: it.sort_text,
+                            documentation: it.documentation.map(|doc| {
+                                Documentation::MarkupContent(MarkupContent {
+                                    kind: MarkupKind::Markdown,
+                                    value: doc,
+                                })
+                            }),
+                            insert_text: snippet.then_some(it.insert_text),
+                            insert_text_format: snippet.then_some(InsertTextFormat::SNIPPET),
+                            ..Default::default()
+                        }
                     })
                     .collect();
+                items.extend(common_items);
                 Ok(Some(CompletionResponse::Array(items)))
             }
             Err(e) => {
@@ -355,6 +811,468 @@ impl LanguageServer for BslLanguageServer {
         }
     }
 
+    async fn signature_help(
+        &self,
+        params: SignatureHelpParams,
+    ) -> JsonRpcResult<Option<SignatureHelp>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.signature_help");
+        let started = std::time::Instant::now();
+        let document = match self.documents.read().await.get(&uri) {
+            Some(text) => text.clone(),
+            None => return Ok(None),
+        };
+        let req = bsl_gradual_types::unified::presentation::LspSignatureHelpRequest {
+            file_path: uri.to_string(),
+            document,
+            line: position.line,
+            column: position.character,
+        };
+        let response = self
+            .central
+            .lsp_interface()
+            .handle_signature_help_request(req)
+            .await;
+        telemetry.record_timing("lsp.signature_help", started.elapsed());
+        match response {
+            Ok(help) => Ok(help),
+            Err(e) => {
+                error!("target signature help failed: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> JsonRpcResult<Option<Vec<InlayHint>>> {
+        let uri = params.text_document.uri;
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.inlay_hint");
+        let started = std::time::Instant::now();
+        let document = match self.documents.read().await.get(&uri) {
+            Some(text) => text.clone(),
+            None => return Ok(None),
+        };
+        let req = bsl_gradual_types::unified::presentation::LspInlayHintsRequest {
+            file_path: uri.to_string(),
+            document,
+            start_line: params.range.start.line,
+            end_line: params.range.end.line,
+            config: self.central.config().await.inlay_hints,
+        };
+        let response = self
+            .central
+            .lsp_interface()
+            .handle_inlay_hints_request(req)
+            .await;
+        telemetry.record_timing("lsp.inlay_hint", started.elapsed());
+        match response {
+            Ok(hints) => Ok(Some(hints)),
+            Err(e) => {
+                error!("target inlay hints failed: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> JsonRpcResult<Option<DocumentSymbolResponse>> {
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.document_symbol");
+        let started = std::time::Instant::now();
+        let symbols = match self.documents.read().await.get(&params.text_document.uri) {
+            Some(text) => document_symbols(text),
+            None => return Ok(None),
+        };
+        telemetry.record_timing("lsp.document_symbol", started.elapsed());
+        Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+    }
+
+    async fn folding_range(
+        &self,
+        params: FoldingRangeParams,
+    ) -> JsonRpcResult<Option<Vec<FoldingRange>>> {
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.folding_range");
+        let started = std::time::Instant::now();
+        let Some(text) = self.documents.read().await.get(&params.text_document.uri).cloned() else {
+            return Ok(None);
+        };
+        let tree = match TreeSitterAdapter::new().and_then(|mut parser| parser.parse_tree(&text)) {
+            Ok(tree) => tree,
+            Err(e) => {
+                error!("folding ranges failed: {}", e);
+                return Ok(None);
+            }
+        };
+        let ranges = folding_ranges(&tree, &text);
+        telemetry.record_timing("lsp.folding_range", started.elapsed());
+        Ok(Some(ranges))
+    }
+
+    async fn formatting(
+        &self,
+        params: DocumentFormattingParams,
+    ) -> JsonRpcResult<Option<Vec<TextEdit>>> {
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.formatting");
+        let started = std::time::Instant::now();
+        let Some(text) = self.documents.read().await.get(&params.text_document.uri).cloned() else {
+            return Ok(None);
+        };
+        let config = self.central.config().await.formatting;
+        let edits = format_document(&text, &config, &params.options);
+        telemetry.record_timing("lsp.formatting", started.elapsed());
+        Ok(Some(edits))
+    }
+
+    async fn range_formatting(
+        &self,
+        params: DocumentRangeFormattingParams,
+    ) -> JsonRpcResult<Option<Vec<TextEdit>>> {
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.range_formatting");
+        let started = std::time::Instant::now();
+        let Some(text) = self.documents.read().await.get(&params.text_document.uri).cloned() else {
+            return Ok(None);
+        };
+        let config = self.central.config().await.formatting;
+        let edits = format_range(&text, params.range, &config, &params.options);
+        telemetry.record_timing("lsp.range_formatting", started.elapsed());
+        Ok(Some(edits))
+    }
+
+    async fn on_type_formatting(
+        &self,
+        params: DocumentOnTypeFormattingParams,
+    ) -> JsonRpcResult<Option<Vec<TextEdit>>> {
+        let position = params.text_document_position;
+        let Some(text) = self.documents.read().await.get(&position.text_document.uri).cloned()
+        else {
+            return Ok(None);
+        };
+        let config = self.central.config().await.formatting;
+        let edits = format_on_type(
+            &text,
+            position.position,
+            &params.ch,
+            &config,
+            &params.options,
+        );
+        Ok(Some(edits))
+    }
+
+    async fn code_action(
+        &self,
+        params: CodeActionParams,
+    ) -> JsonRpcResult<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        self.central.telemetry().record_feature("lsp.code_action");
+        let documents = self.documents.read().await;
+        let Some(text) = documents.get(&uri) else {
+            return Ok(None);
+        };
+        let mut actions = type_quick_fixes(&uri, text, &params.context.diagnostics);
+        let common_modules = self.common_modules.read().await;
+        actions.extend(
+            common_modules
+                .as_ref()
+                .map(|index| index.qualify_call_actions(&uri, text, params.range.start))
+                .unwrap_or_default(),
+        );
+
+        let program = ParserFactory::create().parse(text).ok();
+
+        // Извлечение процедуры: типы параметров берутся из вывода типов документа
+        if params.range.start != params.range.end {
+            let type_context = program.as_ref().map(|program| {
+                let file_name = uri.path_segments().and_then(|mut s| s.next_back()).unwrap_or("module.bsl");
+                TypeChecker::new(file_name.to_string()).check(program).0
+            });
+            actions.extend(extract_procedure_action(
+                &uri,
+                text,
+                params.range,
+                type_context.as_ref(),
+            ));
+        }
+
+        actions.extend(organize_variables_action(&uri, text, program.as_ref()));
+        Ok((!actions.is_empty()).then_some(actions))
+    }
+
+    async fn execute_command(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> JsonRpcResult<Option<serde_json::Value>> {
+        if params.command != OPEN_METADATA_COMMAND {
+            return Ok(None);
+        }
+        let Some(uri) = params
+            .arguments
+            .first()
+            .and_then(|arg| arg.as_str())
+            .and_then(|arg| Url::parse(arg).ok())
+        else {
+            return Err(tower_lsp::jsonrpc::Error::invalid_params(
+                "ожидается URI файла описания метаданных",
+            ));
+        };
+        let shown = self
+            .client
+            .show_document(ShowDocumentParams {
+                uri,
+                external: Some(false),
+                take_focus: Some(true),
+                selection: None,
+            })
+            .await?;
+        Ok(Some(serde_json::Value::Bool(shown)))
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> JsonRpcResult<Option<GotoDefinitionResponse>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+        self.central.telemetry().record_feature("lsp.definition");
+        let documents = self.documents.read().await;
+        let Some(text) = documents.get(&uri) else {
+            return Ok(None);
+        };
+        let req = bsl_gradual_types::unified::presentation::LspDefinitionRequest {
+            file_path: uri.to_string(),
+            line: position.line,
+            column: position.character,
+            expression: self.get_definition_expression(text, position),
+            document: Some(text.clone()),
+        };
+        match self.central.lsp_interface().handle_definition_request(req).await {
+            Ok(Some(location)) => {
+                let Some(target) = Self::definition_uri(&location.file_path) else {
+                    return Ok(None);
+                };
+                let range = Range {
+                    start: Position::new(location.range.start.line, location.range.start.character),
+                    end: Position::new(location.range.end.line, location.range.end.character),
+                };
+                Ok(Some(GotoDefinitionResponse::Scalar(Location::new(target, range))))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => {
+                error!("target definition failed: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> JsonRpcResult<Option<Vec<CallHierarchyItem>>> {
+        self.central.telemetry().record_feature("lsp.call_hierarchy");
+        let position = params.text_document_position_params;
+        let req = self
+            .call_hierarchy_request(&position.text_document.uri, position.position)
+            .await;
+        match self.central.lsp_interface().handle_prepare_call_hierarchy(req).await {
+            Ok(method) => Ok(method
+                .and_then(Self::to_call_hierarchy_item)
+                .map(|item| vec![item])),
+            Err(e) => {
+                error!("target call hierarchy failed: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> JsonRpcResult<Option<Vec<CallHierarchyIncomingCall>>> {
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.incoming_calls");
+        let started = std::time::Instant::now();
+        let item = params.item;
+        let req = self
+            .call_hierarchy_request(&item.uri, item.selection_range.start)
+            .await;
+        let response = self.central.lsp_interface().handle_incoming_calls(req).await;
+        telemetry.record_timing("lsp.incoming_calls", started.elapsed());
+        match response {
+            Ok(calls) => Ok(Some(
+                calls
+                    .into_iter()
+                    .filter_map(|calls| {
+                        Some(CallHierarchyIncomingCall {
+                            from: Self::to_call_hierarchy_item(calls.method)?,
+                            from_ranges: Self::call_ranges(calls.ranges),
+                        })
+                    })
+                    .collect(),
+            )),
+            Err(e) => {
+                error!("target incoming calls failed: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> JsonRpcResult<Option<Vec<CallHierarchyOutgoingCall>>> {
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.outgoing_calls");
+        let started = std::time::Instant::now();
+        let item = params.item;
+        let req = self
+            .call_hierarchy_request(&item.uri, item.selection_range.start)
+            .await;
+        let response = self.central.lsp_interface().handle_outgoing_calls(req).await;
+        telemetry.record_timing("lsp.outgoing_calls", started.elapsed());
+        match response {
+            Ok(calls) => Ok(Some(
+                calls
+                    .into_iter()
+                    .filter_map(|calls| {
+                        Some(CallHierarchyOutgoingCall {
+                            to: Self::to_call_hierarchy_item(calls.method)?,
+                            from_ranges: Self::call_ranges(calls.ranges),
+                        })
+                    })
+                    .collect(),
+            )),
+            Err(e) => {
+                error!("target outgoing calls failed: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> JsonRpcResult<Option<Vec<SymbolInformation>>> {
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.workspace_symbol");
+        let started = std::time::Instant::now();
+        let open_documents = self
+            .documents
+            .read()
+            .await
+            .iter()
+            .filter_map(|(uri, text)| {
+                let path = uri.to_file_path().ok()?;
+                Some((path.to_string_lossy().to_string(), text.clone()))
+            })
+            .collect();
+        let req = bsl_gradual_types::unified::presentation::LspWorkspaceSymbolRequest {
+            query: params.query,
+            open_documents,
+        };
+        let response = self
+            .central
+            .lsp_interface()
+            .handle_workspace_symbol_request(req)
+            .await;
+        telemetry.record_timing("lsp.workspace_symbol", started.elapsed());
+        match response {
+            Ok(symbols) => Ok(Some(
+                symbols
+                    .into_iter()
+                    .filter_map(Self::to_symbol_information)
+                    .collect(),
+            )),
+            Err(e) => {
+                error!("target workspace symbol failed: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn rename(&self, params: RenameParams) -> JsonRpcResult<Option<WorkspaceEdit>> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+        self.central.telemetry().record_feature("lsp.rename");
+        // Пути файловой системы: по ним сопоставляются модули рабочей области
+        let fs_path = |uri: &Url| {
+            uri.to_file_path()
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_else(|_| uri.to_string())
+        };
+        let documents = self.documents.read().await;
+        let Some(text) = documents.get(&uri) else {
+            return Ok(None);
+        };
+        let file_path = fs_path(&uri);
+        let req = bsl_gradual_types::unified::presentation::LspRenameRequest {
+            file_path: file_path.clone(),
+            document: text.clone(),
+            line: position.line,
+            column: position.character,
+            new_name: params.new_name,
+            workspace_root: self.workspace_root.read().await.clone(),
+            open_documents: documents
+                .iter()
+                .map(|(uri, text)| (fs_path(uri), text.clone()))
+                .collect(),
+        };
+        drop(documents);
+
+        let edits = match self.central.lsp_interface().handle_rename_request(req).await {
+            Ok(edits) => edits,
+            // Причина отказа (коллизия, недопустимое имя) показывается пользователю
+            Err(e) => return Err(tower_lsp::jsonrpc::Error::invalid_params(e.to_string())),
+        };
+        let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+        for edit in edits {
+            let target = if edit.file_path == file_path {
+                Some(uri.clone())
+            } else {
+                Self::definition_uri(&edit.file_path)
+            };
+            let Some(target) = target else {
+                continue;
+            };
+            let range = Range {
+                start: Position::new(edit.range.start.line, edit.range.start.character),
+                end: Position::new(edit.range.end.line, edit.range.end.character),
+            };
+            changes
+                .entry(target)
+                .or_default()
+                .push(TextEdit::new(range, edit.new_text));
+        }
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
+
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> JsonRpcResult<Option<SemanticTokensResult>> {
+        self.central.telemetry().record_feature("lsp.semantic_tokens");
+        Ok(self
+            .semantic_tokens(&params.text_document.uri, None)
+            .await
+            .map(SemanticTokensResult::Tokens))
+    }
+
+    async fn semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> JsonRpcResult<Option<SemanticTokensRangeResult>> {
+        self.central.telemetry().record_feature("lsp.semantic_tokens");
+        Ok(self
+            .semantic_tokens(&params.text_document.uri, Some(params.range))
+            .await
+            .map(SemanticTokensRangeResult::Tokens))
+    }
+
     async fn hover(&self, params: HoverParams) -> JsonRpcResult<Option<Hover>> {
         let uri = params.text_document_position_params.text_document.uri;
         let position = params.text_document_position_params.position;
@@ -363,6 +1281,9 @@ impl LanguageServer for BslLanguageServer {
             "Hover requested at {}:{}",
             position.line, position.character
         );
+        let telemetry = self.central.telemetry();
+        telemetry.record_feature("lsp.hover");
+        let started = std::time::Instant::now();
         // Target-only path
         let documents = self.documents.read().await;
         let text = match documents.get(&uri) {
@@ -374,11 +1295,29 @@ impl LanguageServer for BslLanguageServer {
             file_path: uri.to_string(),
             line: position.line,
             column: position.character,
-            expression: expr,
+            expression: expr.clone(),
+            document: Some(text.clone()),
         };
-        match self.central.lsp_interface().handle_hover_request(req).await {
+        let response = self.central.lsp_interface().handle_hover_request(req).await;
+        telemetry.record_timing("lsp.hover", started.elapsed());
+        match response {
             Ok(Some(hr)) => {
-                let value = hr.contents.join("\n\n");
+                let mut value = hr.contents.join("\n\n");
+                if let Some(link) = hr
+                    .definition_path
+                    .as_deref()
+                    .and_then(Self::metadata_definition_markdown)
+                {
+                    value.push_str("\n\n");
+                    value.push_str(&link);
+                }
+                if let Some(examples) = self
+                    .project_examples_markdown(&expr, text, position.line)
+                    .await
+                {
+                    value.push_str("\n\n---\n\n");
+                    value.push_str(&examples);
+                }
                 Ok(Some(Hover {
                     contents: HoverContents::Markup(MarkupContent {
                         kind: MarkupKind::Markdown,
@@ -412,8 +1351,21 @@ async fn main() -> Result<()> {
     // Параметры запуска (без движка)
     let _args = Args::parse();
     // Инициализируем центральную систему (target-only)
-    let cs =
-        Arc::new(CentralTypeSystem::initialize_with_config(CentralSystemConfig::default()).await?);
+    // Конфигурация берётся из переменных окружения BSL_* (см. CentralSystemConfig::from_env)
+    let cs = Arc::new(CentralTypeSystem::initialize_with_config(CentralSystemConfig::from_env()?).await?);
+
+    // Изменения модулей и метаданных подхватываются без перезапуска (BSL_WATCH=1)
+    let _watcher = if cs.config().await.watch_sources {
+        match cs.watch_sources().await {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("source watching disabled: {:#}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
 
     // Создаём stdin/stdout для коммуникации с клиентом
     let stdin = tokio::io::stdin();
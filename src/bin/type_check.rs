@@ -44,6 +44,8 @@ fn main() {
                 column: 0,
                 prefix: args.expression.clone(),
                 trigger_character: None,
+                expected_type: None,
+                document_text: None,
             };
             match central.lsp_interface().handle_completion_request(req).await {
                 Ok(resp) => {
@@ -64,6 +66,7 @@ fn main() {
                 line: 0,
                 column: 0,
                 expression: args.expression.clone(),
+                document: None,
             };
             match central.lsp_interface().handle_hover_request(req).await {
                 Ok(Some(h)) => {
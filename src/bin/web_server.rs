@@ -3,34 +3,88 @@
 //! HTTP сервер для браузерного интерфейса просмотра типов BSL
 
 use anyhow::Result;
+use arc_swap::ArcSwap;
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tracing::{error, info, warn};
 
+use bsl_gradual_types::core::example_mining::{
+    ExampleCorpus, ProjectExample, DEFAULT_EXAMPLES_PER_MEMBER,
+};
+use bsl_gradual_types::core::quick_open::{
+    project_symbols, QuickOpenIndex, QuickOpenItem, QuickOpenMatch, DEFAULT_QUICK_OPEN_LIMIT,
+};
 use bsl_gradual_types::core::type_checker::{TypeChecker, TypeContext};
+use bsl_gradual_types::core::usage_report::{UsageCollector, UsageReport, DEFAULT_TOP_N};
 use bsl_gradual_types::domain::types::{ConcreteType, ResolutionResult, TypeResolution};
 use bsl_gradual_types::documentation::core::providers::DocumentationProvider;
 use bsl_gradual_types::documentation::core::ProviderConfig;
 use bsl_gradual_types::documentation::{
     AdvancedSearchQuery, ConfigurationDocumentationProvider, DocumentationSearchEngine,
-    PlatformDocumentationProvider,
+    Approval, PlatformDocumentationProvider, TeamNote, TeamNotesOverlay,
 };
 use bsl_gradual_types::parsing::bsl::common::ParserFactory;
 // Переход на плоскую архитектуру
-use bsl_gradual_types::presentation::{WebSearchFilters, WebSearchRequest};
-use bsl_gradual_types::system::{CentralSystemConfig, CentralTypeSystem};
-use bsl_gradual_types::application::documentation_service::DocumentationService;
+use bsl_gradual_types::presentation::http_cache::{self, CachedResponse};
+use bsl_gradual_types::presentation::{WebSearchRequest, WebTypeListRequest};
+use bsl_gradual_types::core::command_handlers::{command_summaries, CommandSummary};
+use bsl_gradual_types::data::loaders::commands::CommandIndex;
+use bsl_gradual_types::data::loaders::help_bookmarks::{import_bookmarks, HelpAliasTable};
+use bsl_gradual_types::data::loaders::metadata_flags::{
+    MetadataFlagsFilter, MetadataFlagsIndex, ObjectFlags,
+};
+use bsl_gradual_types::data::loaders::subsystems::SubsystemIndex;
+use bsl_gradual_types::data::changelog::render_rss;
+use bsl_gradual_types::data::{
+    ChangeEntry, ChangedTypeRef, DocumentationReport, TypeFilter, TypeOrigin, WriteGuard,
+};
+use bsl_gradual_types::system::commands::{command_descriptors, ServerCommand};
+use bsl_gradual_types::system::maintenance::MAINTENANCE_TICK;
+use bsl_gradual_types::system::{
+    CentralSystemConfig, CentralTypeSystem, WebProjectSettings, WebServerSettings,
+};
 
 #[derive(Parser)]
 #[command(name = "bsl-web-server")]
 #[command(about = "Web-based type browser для BSL Gradual Type System")]
 struct Cli {
-    /// Порт для HTTP сервера
-    #[arg(short, long, default_value = "8080")]
-    port: u16,
+    /// Порт для HTTP сервера (перекрывает значение из файла настроек)
+    #[arg(short, long)]
+    port: Option<u16>,
+
+    /// Адрес для прослушивания (перекрывает значение из файла настроек)
+    #[arg(long)]
+    bind: Option<String>,
+
+    /// JSON-файл настроек CentralSystemConfig (перечитывается по SIGHUP).
+    /// Поверх файла применяются переменные окружения BSL_*, поверх них — флаги CLI
+    #[arg(long, env = "BSL_SETTINGS_FILE")]
+    settings: Option<PathBuf>,
+
+    /// Путь к PEM сертификату для HTTPS
+    #[arg(long, requires = "tls_key")]
+    tls_cert: Option<String>,
+
+    /// Путь к PEM приватному ключу для HTTPS
+    #[arg(long, requires = "tls_cert")]
+    tls_key: Option<String>,
+
+    /// Порт gRPC API (требует сборки с feature "grpc")
+    #[arg(long)]
+    grpc_port: Option<u16>,
+
+    /// Количество рабочих потоков (по умолчанию из PerformanceSettings)
+    #[arg(long)]
+    workers: Option<usize>,
+
+    /// Headless-режим для контейнеров: JSON-логи, без прогресс-баров
+    /// (также включается переменной окружения BSL_HEADLESS=1)
+    #[arg(long)]
+    headless: bool,
 
     /// Путь к проекту 1С для анализа
     #[arg(short = 'j', long)]
@@ -64,8 +118,74 @@ struct AppState {
     search_engine: Arc<DocumentationSearchEngine>,
     /// Платформенный провайдер документации
     platform_provider: Arc<PlatformDocumentationProvider>,
-    /// Центральная система типов (target-only)
-    central: Arc<CentralTypeSystem>,
+    /// Центральная система типов (target-only), атомарно заменяется при перезагрузке конфигурации
+    central: Arc<ArcSwap<CentralTypeSystem>>,
+    /// Примеры использования из анализируемого проекта (--project)
+    examples: Arc<RwLock<Option<ExampleCorpus>>>,
+    /// Отчёт об использовании типов в анализируемом проекте (--project)
+    usage_report: Arc<RwLock<Option<UsageReport>>>,
+    /// Процедуры и функции анализируемого проекта (--project)
+    project_symbols: Arc<RwLock<Vec<QuickOpenItem>>>,
+    /// Индекс палитры /api/quick-open
+    quick_open: Arc<RwLock<Option<QuickOpenCache>>>,
+    /// Флаги объектов метаданных (OData, полнотекстовый поиск) из выгрузки конфигурации
+    metadata_flags: Arc<RwLock<Option<MetadataFlagsIndex>>>,
+    /// Команды конфигурации и проверка их обработчиков
+    commands: Arc<RwLock<Option<Vec<CommandSummary>>>>,
+    /// Заметки команды к типам (`team_notes_path`), общие для всех проектов
+    team_notes: Arc<RwLock<Option<TeamNotesOverlay>>>,
+}
+
+/// Индекс палитры и система типов, по которой он построен
+struct QuickOpenCache {
+    central: std::sync::Weak<CentralTypeSystem>,
+    index: Arc<QuickOpenIndex>,
+}
+
+impl AppState {
+    /// Состояние для системы типов; поисковая система и провайдер
+    /// документации платформы общие для всех конфигураций сервера
+    fn new(
+        central: Arc<ArcSwap<CentralTypeSystem>>,
+        search_engine: Arc<DocumentationSearchEngine>,
+        platform_provider: Arc<PlatformDocumentationProvider>,
+    ) -> Self {
+        Self {
+            type_context: Arc::new(RwLock::new(None)),
+            search_cache: Arc::new(RwLock::new(HashMap::new())),
+            loading_status: Arc::new(RwLock::new(LoadingStatus {
+                is_loading: false,
+                progress: 100,
+                processed_files: 0,
+                total_files: 0,
+                current_operation: "Поисковая система готова".to_string(),
+                errors: 0,
+            })),
+            search_engine,
+            platform_provider,
+            central,
+            examples: Arc::new(RwLock::new(None)),
+            usage_report: Arc::new(RwLock::new(None)),
+            project_symbols: Arc::new(RwLock::new(Vec::new())),
+            quick_open: Arc::new(RwLock::new(None)),
+            metadata_flags: Arc::new(RwLock::new(None)),
+            commands: Arc::new(RwLock::new(None)),
+            team_notes: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Текущий экземпляр центральной системы
+    fn central(&self) -> Arc<CentralTypeSystem> {
+        self.central.load_full()
+    }
+}
+
+/// Конфигурация, обслуживаемая под `/projects/{name}/`
+#[derive(Clone)]
+struct WebProject {
+    name: String,
+    configuration_path: String,
+    state: AppState,
 }
 
 // Движок legacy удалён, сервер работает только в target-режиме
@@ -123,6 +243,14 @@ struct TypeDetails {
     properties: Vec<PropertyInfo>,
     related_types: Vec<String>,
     usage_examples: Vec<String>,
+    /// Примеры создания объекта в анализируемом проекте
+    project_examples: Vec<ProjectExample>,
+    /// Флаги объекта метаданных (для типов конфигурации)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata_flags: Option<ObjectFlags>,
+    /// Заметки, примеры и предупреждения команды
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    team_notes: Vec<TeamNote>,
 }
 
 #[derive(Serialize)]
@@ -131,6 +259,8 @@ struct MethodInfo {
     parameters: Vec<String>,
     return_type: Option<String>,
     description: Option<String>,
+    /// «Примеры из вашего проекта»
+    project_examples: Vec<ProjectExample>,
 }
 
 #[derive(Serialize)]
@@ -141,30 +271,81 @@ struct PropertyInfo {
     description: Option<String>,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Настраиваем логирование
-    tracing_subscriber::fmt()
-        .with_env_filter("bsl_web_server=info,warp=info")
-        .init();
-
+fn main() -> Result<()> {
     let cli = Cli::parse();
+    let config = build_config(&cli)?;
+
+    // Настраиваем логирование: в headless-режиме — JSON, по строке на событие
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| "bsl_web_server=info,warp=info".into());
+    if config.headless {
+        tracing_subscriber::fmt()
+            .json()
+            .with_env_filter(filter)
+            .init();
+    } else {
+        tracing_subscriber::fmt().with_env_filter(filter).init();
+    }
 
-    println!(
-        "🌐 Starting BSL Type Browser Web Server on port {}",
-        cli.port
+    let workers = cli
+        .workers
+        .unwrap_or(config.performance_settings.web_worker_threads)
+        .max(1);
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(workers)
+        .enable_all()
+        .build()?;
+
+    info!(
+        "🌐 Starting BSL Type Browser Web Server on {}:{} ({} workers)",
+        config.web_server.bind_address, config.web_server.port, workers
     );
 
+    runtime.block_on(run(cli, config))
+}
+
+/// Собрать конфигурацию: файл настроек → переменные окружения `BSL_*` → флаги CLI
+fn build_config(cli: &Cli) -> Result<CentralSystemConfig> {
+    let mut cfg = match &cli.settings {
+        Some(path) => CentralSystemConfig::from_file(path)?,
+        None => CentralSystemConfig::default(),
+    };
+    cfg.apply_env_overrides()?;
+    if cli.headless {
+        cfg.headless = true;
+    }
+    if let Some(path) = &cli.config {
+        cfg.configuration_path = Some(path.clone());
+    }
+    if let Some(port) = cli.port {
+        cfg.web_server.port = port;
+    }
+    if let Some(bind) = &cli.bind {
+        cfg.web_server.bind_address = bind.clone();
+    }
+    if cli.grpc_port.is_some() {
+        cfg.web_server.grpc_port = cli.grpc_port;
+    }
+    if cli.tls_cert.is_some() {
+        cfg.web_server.tls_cert_path = cli.tls_cert.clone();
+        cfg.web_server.tls_key_path = cli.tls_key.clone();
+    }
+    cfg.web_server.validate_projects()?;
+    Ok(cfg)
+}
+
+async fn run(cli: Cli, config: CentralSystemConfig) -> Result<()> {
     // Инициализируем поисковую систему и провайдеры
-    println!("🔧 Инициализация поисковой системы...");
+    info!("🔧 Инициализация поисковой системы...");
     let search_engine = Arc::new(DocumentationSearchEngine::new());
     let platform_provider = Arc::new(PlatformDocumentationProvider::new());
 
     // Инициализируем платформенный провайдер
-    let config = ProviderConfig::default();
-    if let Err(e) = platform_provider.initialize(&config).await {
-        println!("⚠️ Предупреждение при инициализации провайдера: {}", e);
-        println!("   Система будет работать без справки синтакс-помощника");
+    let mut provider_config = ProviderConfig::default();
+    provider_config.performance_settings.show_progress = !config.headless;
+    if let Err(e) = platform_provider.initialize(&provider_config).await {
+        warn!("⚠️ Предупреждение при инициализации провайдера: {}", e);
+        warn!("   Система будет работать без справки синтакс-помощника");
     }
 
     // Строим индексы для поиска
@@ -173,64 +354,378 @@ async fn main() -> Result<()> {
         .build_indexes(&*platform_provider, &config_provider)
         .await
     {
-        println!("⚠️ Предупреждение при построении индексов: {}", e);
+        warn!("⚠️ Предупреждение при построении индексов: {}", e);
     } else {
-        println!("✅ Индексы поиска построены");
+        info!("✅ Индексы поиска построены");
     }
 
     // Инициализируем центральную систему (target-only)
-    println!("🚀 Инициализация CentralTypeSystem (target engine)");
-    let mut cfg = CentralSystemConfig::default();
-    if let Some(path) = &cli.config {
-        cfg.configuration_path = Some(path.clone());
-    }
-    let central = Arc::new(
-        CentralTypeSystem::initialize_with_config(cfg)
+    info!("🚀 Инициализация CentralTypeSystem (target engine)");
+    let central = Arc::new(ArcSwap::from_pointee(
+        CentralTypeSystem::initialize_with_config(config.clone())
             .await
             .unwrap_or_else(|e| {
-                println!("⚠️ Ошибка инициализации CentralTypeSystem: {}", e);
-                CentralTypeSystem::new(CentralSystemConfig::default())
+                warn!("⚠️ Ошибка инициализации CentralTypeSystem: {}", e);
+                CentralTypeSystem::new(config.clone())
             }),
+    ));
+
+    let app_state = AppState::new(
+        central.clone(),
+        search_engine.clone(),
+        platform_provider.clone(),
     );
 
-    let app_state = AppState {
-        type_context: Arc::new(RwLock::new(None)),
-        search_cache: Arc::new(RwLock::new(HashMap::new())),
-        loading_status: Arc::new(RwLock::new(LoadingStatus {
-            is_loading: false,
-            progress: 100,
-            processed_files: 0,
-            total_files: 0,
-            current_operation: "Поисковая система готова".to_string(),
-            errors: 0,
-        })),
-        search_engine,
-        platform_provider,
-        central: central.clone(),
-    };
+    // Флаги объектов метаданных для аудита интеграций
+    if let Some(config_path) = config.configuration_path.clone() {
+        let composition = config.odata_composition_path.clone();
+        load_state_metadata_flags(&app_state, config_path.clone(), composition).await;
+        load_state_commands(&app_state, config_path).await;
+    }
+
+    // Заметки команды к типам
+    if let Some(notes_path) = config.team_notes_path.clone() {
+        let write_guard = central.load().write_guard().clone();
+        load_state_team_notes(&app_state, notes_path, write_guard).await;
+    }
+
+    // Дополнительные конфигурации под /projects/{name}/
+    let projects = load_projects(&config, &app_state).await;
 
     // Если указан проект, анализируем его
-    if let Some(project_path) = cli.project {
-        println!("📁 Analyzing project: {}", project_path.display());
-        let context = analyze_project(&project_path).await?;
+    if let Some(project_path) = &cli.project {
+        info!("📁 Analyzing project: {}", project_path.display());
+        let write_guard = central.load().write_guard().clone();
+        let cache = config.cache_settings.analysis_cache_path.clone();
+        let context = analyze_project(project_path, cache, write_guard).await?;
         *app_state.type_context.write().await = Some(context);
-        println!("✅ Project analysis completed");
+
+        match mine_project_examples(&app_state, project_path).await {
+            Ok(corpus) => {
+                info!(
+                    "📚 Собраны примеры из проекта: {} методов/типов",
+                    corpus.members_count()
+                );
+                *app_state.examples.write().await = Some(corpus);
+            }
+            Err(e) => warn!("⚠️ Не удалось собрать примеры из проекта: {}", e),
+        }
+
+        let configuration_path = config.configuration_path.as_deref();
+        match build_usage_report(&app_state, project_path, configuration_path).await {
+            Ok(report) => {
+                info!(
+                    "📈 Отчёт об использовании: {} модулей, {} вызовов устаревшего API",
+                    report.files_scanned,
+                    report.deprecated_usages.len()
+                );
+                *app_state.usage_report.write().await = Some(report);
+            }
+            Err(e) => warn!("⚠️ Не удалось построить отчёт об использовании: {}", e),
+        }
+
+        let root = project_path.clone();
+        match tokio::task::spawn_blocking(move || project_symbols(&root)).await {
+            Ok(symbols) => *app_state.project_symbols.write().await = symbols,
+            Err(e) => warn!("⚠️ Сбор символов проекта прерван: {}", e),
+        }
+        info!("✅ Project analysis completed");
+    }
+
+    // Строим индекс палитры заранее, чтобы первый запрос был быстрым
+    let quick_open = quick_open_index(&app_state).await;
+    info!("🔎 Индекс палитры: {} элементов", quick_open.len());
+
+    // gRPC API на отдельном порту (если задан)
+    if let Some(port) = config.web_server.grpc_port {
+        spawn_grpc_server(&config.web_server, port, central.clone())?;
     }
 
+    // Плановые работы (перестройка индексов, прогрев кешей) по расписанию
+    let systems = std::iter::once(central.clone())
+        .chain(projects.iter().map(|project| project.state.central.clone()))
+        .collect();
+    spawn_maintenance_scheduler(systems);
+
+    // Перезагрузка конфигурации по SIGHUP
+    let static_dir = cli.static_dir.clone();
+    spawn_reload_listener(
+        cli,
+        central,
+        projects.clone(),
+        search_engine,
+        platform_provider,
+    );
+
     // Запускаем web сервер
-    start_web_server(cli.port, app_state, cli.static_dir).await?;
+    start_web_server(&config.web_server, app_state, projects, static_dir).await?;
+
+    Ok(())
+}
+
+/// Прочитать флаги объектов метаданных выгрузки в состояние сервера
+async fn load_state_metadata_flags(
+    state: &AppState,
+    config_path: String,
+    composition: Option<String>,
+) {
+    match tokio::task::spawn_blocking(move || {
+        load_metadata_flags(&config_path, composition.as_deref())
+    })
+    .await
+    {
+        Ok(Ok(index)) => {
+            info!("🏷️ Флаги метаданных: {} объектов", index.objects().len());
+            *state.metadata_flags.write().await = Some(index);
+        }
+        Ok(Err(e)) => warn!("⚠️ Не удалось прочитать флаги метаданных: {}", e),
+        Err(e) => warn!("⚠️ Чтение флагов метаданных прервано: {}", e),
+    }
+}
+
+/// Прочитать команды конфигурации и проверить модули их обработчиков
+async fn load_state_commands(state: &AppState, config_path: String) {
+    match tokio::task::spawn_blocking(move || {
+        CommandIndex::load(std::path::Path::new(&config_path))
+            .map(|index| command_summaries(&index))
+    })
+    .await
+    {
+        Ok(Ok(commands)) => {
+            info!("🔘 Команды конфигурации: {}", commands.len());
+            *state.commands.write().await = Some(commands);
+        }
+        Ok(Err(e)) => warn!("⚠️ Не удалось прочитать команды конфигурации: {}", e),
+        Err(e) => warn!("⚠️ Чтение команд конфигурации прервано: {}", e),
+    }
+}
+
+/// Прочитать заметки команды к типам в состояние сервера
+async fn load_state_team_notes(state: &AppState, notes_path: String, write_guard: WriteGuard) {
+    match tokio::task::spawn_blocking(move || {
+        TeamNotesOverlay::load(std::path::Path::new(&notes_path), write_guard)
+    })
+    .await
+    {
+        Ok(Ok(notes)) => {
+            info!("📝 Заметки команды: {} типов", notes.len());
+            *state.team_notes.write().await = Some(notes);
+        }
+        Ok(Err(e)) => warn!("⚠️ Не удалось прочитать заметки команды: {}", e),
+        Err(e) => warn!("⚠️ Чтение заметок команды прервано: {}", e),
+    }
+}
+
+/// Инициализировать системы типов проектов из `web_server.projects`.
+/// Проект, конфигурацию которого не удалось загрузить, пропускается.
+async fn load_projects(config: &CentralSystemConfig, main: &AppState) -> Vec<WebProject> {
+    let mut projects = Vec::new();
+    for project in &config.web_server.projects {
+        info!("📦 Проект {}: {}", project.name, project.configuration_path);
+        let system = match CentralTypeSystem::initialize_with_config(config.for_project(project))
+            .await
+        {
+            Ok(system) => system,
+            Err(e) => {
+                warn!("⚠️ Проект {} не загружен: {}", project.name, e);
+                continue;
+            }
+        };
+        let mut state = AppState::new(
+            Arc::new(ArcSwap::from_pointee(system)),
+            main.search_engine.clone(),
+            main.platform_provider.clone(),
+        );
+        state.team_notes = main.team_notes.clone();
+        load_state_metadata_flags(
+            &state,
+            project.configuration_path.clone(),
+            project.odata_composition_path.clone(),
+        )
+        .await;
+        load_state_commands(&state, project.configuration_path.clone()).await;
+        projects.push(WebProject {
+            name: project.name.clone(),
+            configuration_path: project.configuration_path.clone(),
+            state,
+        });
+    }
+    projects
+}
+
+/// Запустить gRPC сервер (proto/bsl_types.proto) рядом с HTTP сервером
+#[cfg(feature = "grpc")]
+fn spawn_grpc_server(
+    settings: &WebServerSettings,
+    port: u16,
+    central: Arc<ArcSwap<CentralTypeSystem>>,
+) -> Result<()> {
+    use bsl_gradual_types::system::grpc::GrpcTypeService;
+
+    let mut addr = settings.socket_addr()?;
+    addr.set_port(port);
+    let service = GrpcTypeService::new(central).into_server();
+
+    tokio::spawn(async move {
+        info!("🚀 gRPC API running on {}", addr);
+        if let Err(e) = tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_shutdown(addr, shutdown_signal())
+            .await
+        {
+            error!("❌ Ошибка gRPC сервера: {}", e);
+        }
+    });
+    Ok(())
+}
 
+#[cfg(not(feature = "grpc"))]
+fn spawn_grpc_server(
+    _settings: &WebServerSettings,
+    port: u16,
+    _central: Arc<ArcSwap<CentralTypeSystem>>,
+) -> Result<()> {
+    warn!(
+        "⚠️ Задан gRPC порт {}, но сервер собран без feature \"grpc\" — gRPC API отключён",
+        port
+    );
     Ok(())
 }
 
+/// Запустить планировщик плановых работ основной системы и проектов. Система
+/// берётся заново на каждом шаге, поэтому после SIGHUP расписание действует
+/// на новый экземпляр; настройки `maintenance` применяются без перезапуска.
+fn spawn_maintenance_scheduler(systems: Vec<Arc<ArcSwap<CentralTypeSystem>>>) {
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(MAINTENANCE_TICK);
+        loop {
+            ticks.tick().await;
+            for central in &systems {
+                central.load_full().run_due_maintenance().await;
+            }
+        }
+    });
+}
+
+/// Запустить обработчик SIGHUP: перечитывает настройки и атомарно заменяет CentralTypeSystem
+/// основной конфигурации и проектов. Запросы, уже выполняющиеся на старом экземпляре,
+/// завершаются на нём. Состав проектов меняется только при перезапуске.
+#[cfg(unix)]
+fn spawn_reload_listener(
+    cli: Cli,
+    central: Arc<ArcSwap<CentralTypeSystem>>,
+    projects: Vec<WebProject>,
+    search_engine: Arc<DocumentationSearchEngine>,
+    platform_provider: Arc<PlatformDocumentationProvider>,
+) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("⚠️ Не удалось подписаться на SIGHUP: {}", e);
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            info!("🔄 SIGHUP: перезагрузка конфигурации...");
+            let config = match build_config(&cli) {
+                Ok(cfg) => cfg,
+                Err(e) => {
+                    error!("❌ Конфигурация не перезагружена: {}", e);
+                    continue;
+                }
+            };
+            for project in &projects {
+                let Some(settings) = find_project(&config.web_server.projects, &project.name)
+                else {
+                    warn!("⚠️ Проект {} удалён из настроек, остаётся до перезапуска", project.name);
+                    continue;
+                };
+                match CentralTypeSystem::initialize_with_config(config.for_project(settings)).await
+                {
+                    Ok(system) => project.state.central.store(Arc::new(system)),
+                    Err(e) => error!("❌ Проект {} не перезагружен: {}", project.name, e),
+                }
+            }
+            match CentralTypeSystem::initialize_with_config(config).await {
+                Ok(system) => {
+                    central.store(Arc::new(system));
+                    info!("✅ Конфигурация перезагружена");
+                }
+                Err(e) => error!("❌ Ошибка инициализации, остаёмся на старой конфигурации: {}", e),
+            }
+            // Индексы документации обновляются на месте: поиск не прерывается
+            match search_engine.refresh_provider(&*platform_provider).await {
+                Ok(summary) => info!(
+                    "📚 Индексы документации обновлены: +{} ~{} -{}",
+                    summary.added, summary.updated, summary.removed
+                ),
+                Err(e) => warn!("⚠️ Индексы документации не обновлены: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_reload_listener(
+    _cli: Cli,
+    _central: Arc<ArcSwap<CentralTypeSystem>>,
+    _projects: Vec<WebProject>,
+    _search_engine: Arc<DocumentationSearchEngine>,
+    _platform_provider: Arc<PlatformDocumentationProvider>,
+) {
+}
+
+/// Настройки проекта по имени
+fn find_project<'a>(
+    projects: &'a [WebProjectSettings],
+    name: &str,
+) -> Option<&'a WebProjectSettings> {
+    projects.iter().find(|project| project.name == name)
+}
+
+/// Сигнал остановки: Ctrl+C или SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut s) => {
+                s.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("🛑 Получен сигнал остановки, ожидаем завершения активных запросов...");
+}
+
 /// Анализ проекта для получения типов
-async fn analyze_project(project_path: &PathBuf) -> Result<TypeContext> {
+async fn analyze_project(
+    project_path: &std::path::Path,
+    cache_location: Option<String>,
+    write_guard: WriteGuard,
+) -> Result<TypeContext> {
     use bsl_gradual_types::core::parallel_analysis::{ParallelAnalysisConfig, ParallelAnalyzer};
 
+    let defaults = ParallelAnalysisConfig::default();
     let config = ParallelAnalysisConfig {
         show_progress: false, // Отключаем для web сервера
         use_cache: true,
-        ..Default::default()
+        cache_location: cache_location.or(defaults.cache_location.clone()),
+        write_guard,
+        ..defaults
     };
 
     let analyzer = ParallelAnalyzer::new(config)?;
@@ -242,6 +737,7 @@ async fn analyze_project(project_path: &PathBuf) -> Result<TypeContext> {
         functions: HashMap::new(),
         current_scope: bsl_gradual_types::core::dependency_graph::Scope::Global,
         scope_stack: vec![],
+        active_contexts: bsl_gradual_types::parsing::bsl::ContextSet::ALL,
     };
 
     for file_result in results.file_results {
@@ -262,15 +758,91 @@ async fn analyze_project(project_path: &PathBuf) -> Result<TypeContext> {
 }
 
 /// Запуск web сервера
-async fn start_web_server(port: u16, app_state: AppState, static_dir: PathBuf) -> Result<()> {
+async fn start_web_server(
+    settings: &WebServerSettings,
+    app_state: AppState,
+    projects: Vec<WebProject>,
+    static_dir: PathBuf,
+) -> Result<()> {
     use warp::Filter;
 
     // CORS для разработки
     let cors = warp::cors()
         .allow_any_origin()
-        .allow_headers(vec!["content-type"])
+        .allow_headers(vec!["content-type", "if-none-match"])
+        .expose_headers(vec!["etag"])
         .allow_methods(vec!["GET", "POST", "OPTIONS"]);
 
+    let whats_new = whats_new_page(app_state.clone());
+    let documentation = documentation_page(app_state.clone());
+    let inference_help = inference_help_page(app_state.clone());
+    let api = api_routes(app_state).with(cors.clone());
+    let projects_api = project_routes(&projects).with(cors);
+
+    // Статические файлы
+    let static_files = warp::fs::dir(static_dir);
+
+    // Главная страница
+    let index = warp::path::end().and(warp::get()).and_then(handle_index);
+
+    let routes = api
+        .or(projects_api)
+        .or(whats_new)
+        .or(documentation)
+        .or(inference_help)
+        .or(static_files)
+        .or(index);
+
+    let addr = settings.socket_addr()?;
+    let grace = std::time::Duration::from_secs(settings.shutdown_grace_period_secs);
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+    let server_signal = async {
+        let _ = rx.await;
+    };
+
+    let server: std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> =
+        if settings.tls_enabled() {
+            let (bound, fut) = warp::serve(routes)
+                .tls()
+                .cert_path(settings.tls_cert_path.as_deref().unwrap_or_default())
+                .key_path(settings.tls_key_path.as_deref().unwrap_or_default())
+                .try_bind_with_graceful_shutdown(addr, server_signal)?;
+            info!("🚀 Web server running on https://{}", bound);
+            Box::pin(fut)
+        } else {
+            let (bound, fut) =
+                warp::serve(routes).try_bind_with_graceful_shutdown(addr, server_signal)?;
+            info!("🚀 Web server running on http://{}", bound);
+            info!("📖 Open http://{} to browse BSL types", bound);
+            Box::pin(fut)
+        };
+
+    let mut server = tokio::spawn(server);
+    tokio::select! {
+        _ = &mut server => return Ok(()),
+        _ = shutdown_signal() => {
+            let _ = tx.send(());
+        }
+    }
+
+    // Даём активным запросам завершиться, но не дольше grace period
+    if tokio::time::timeout(grace, server).await.is_err() {
+        warn!(
+            "⚠️ Активные запросы не завершились за {}с, принудительная остановка",
+            grace.as_secs()
+        );
+    } else {
+        info!("✅ Сервер остановлен");
+    }
+
+    Ok(())
+}
+
+/// Маршруты API (/api/*) одной системы типов: основной конфигурации или
+/// проекта под `/projects/{name}/`
+fn api_routes(
+    app_state: AppState,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     // API routes
     let api_base = warp::path("api");
     let api = api_base
@@ -279,6 +851,7 @@ async fn start_web_server(port: u16, app_state: AppState, static_dir: PathBuf) -
             warp::path("types")
                 .and(warp::get())
                 .and(warp::query::<SearchQuery>())
+                .and(cache_headers())
                 .and(with_state(app_state.clone()))
                 .and_then(handle_search_types)
                 .or(
@@ -317,9 +890,116 @@ async fn start_web_server(port: u16, app_state: AppState, static_dir: PathBuf) -
                         .and(warp::path("search"))
                         .and(warp::post())
                         .and(warp::body::json())
+                        .and(cache_headers())
                         .and(with_state(app_state.clone()))
                         .and_then(handle_advanced_search),
                 )
+                .or(
+                    // GET /api/v1/types?page=&per_page=&sort=&order=&fields= - список типов
+                    warp::path("v1")
+                        .and(warp::path("types"))
+                        .and(warp::path::end())
+                        .and(warp::get())
+                        .and(warp::query::<WebTypeListRequest>())
+                        .and(cache_headers())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_list_types),
+                )
+                .or(
+                    // GET /api/v1/usage-report - отчёт об использовании типов в проекте
+                    warp::path("v1")
+                        .and(warp::path("usage-report"))
+                        .and(warp::get())
+                        .and(cache_headers())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_usage_report),
+                )
+                .or(
+                    // GET /api/v1/metadata-flags?kind=&q=&odata=&full_text_search=
+                    //     &data_history=&standard_commands= - аудит OData и полнотекстового поиска
+                    warp::path("v1")
+                        .and(warp::path("metadata-flags"))
+                        .and(warp::get())
+                        .and(warp::query::<MetadataFlagsFilter>())
+                        .and(cache_headers())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_metadata_flags),
+                )
+                .or(
+                    // GET /api/v1/commands - команды конфигурации, их обработчики и нарушения
+                    warp::path("v1")
+                        .and(warp::path("commands"))
+                        .and(warp::get())
+                        .and(cache_headers())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_commands),
+                )
+                .or(
+                    // GET /api/v1/changes?limit= - журнал изменений данных репозитория
+                    warp::path("v1")
+                        .and(warp::path("changes"))
+                        .and(warp::path::end())
+                        .and(warp::get())
+                        .and(warp::query::<ChangesQuery>())
+                        .and(cache_headers())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_changes),
+                )
+                .or(
+                    // GET /api/v1/changes.rss?limit= - тот же журнал лентой RSS
+                    warp::path("v1")
+                        .and(warp::path("changes.rss"))
+                        .and(warp::path::end())
+                        .and(warp::get())
+                        .and(warp::query::<ChangesQuery>())
+                        .and(warp::path::full())
+                        .and(warp::header::optional::<String>("host"))
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_changes_rss),
+                )
+                .or(
+                    // GET /api/v1/documentation-report?origin=&format=csv - полнота
+                    //     документации типов (JSON или выгрузка CSV)
+                    warp::path("v1")
+                        .and(warp::path("documentation-report"))
+                        .and(warp::path::end())
+                        .and(warp::get())
+                        .and(warp::query::<DocumentationQuery>())
+                        .and(cache_headers())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_documentation_report),
+                )
+                .or(
+                    // GET /api/v1/maintenance - расписание и последние плановые работы
+                    warp::path("v1")
+                        .and(warp::path("maintenance"))
+                        .and(warp::path::end())
+                        .and(warp::get())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_maintenance_status),
+                )
+                .or(commands_routes(app_state.clone()))
+                .or(notes_routes(app_state.clone()))
+                .or(
+                    // POST /api/v1/bookmarks/import - закладки синтакс-помощника 1С
+                    warp::path("v1")
+                        .and(warp::path("bookmarks"))
+                        .and(warp::path("import"))
+                        .and(warp::path::end())
+                        .and(warp::post())
+                        .and(warp::body::json())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_import_bookmarks),
+                )
+                .or(
+                    // GET /api/v1/hierarchy - полная иерархия типов
+                    warp::path("v1")
+                        .and(warp::path("hierarchy"))
+                        .and(warp::get())
+                        .and(cache_headers())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_get_hierarchy),
+                )
                 .or(
                     // GET /api/v1/suggestions?q=query - автодополнение
                     warp::path("v1")
@@ -329,6 +1009,15 @@ async fn start_web_server(port: u16, app_state: AppState, static_dir: PathBuf) -
                         .and(with_state(app_state.clone()))
                         .and_then(handle_get_suggestions),
                 )
+                .or(
+                    // GET /api/quick-open?q=query&limit= - палитра «перейти к чему угодно»
+                    warp::path("quick-open")
+                        .and(warp::path::end())
+                        .and(warp::get())
+                        .and(warp::query::<QuickOpenQuery>())
+                        .and(with_state(app_state.clone()))
+                        .and_then(handle_quick_open),
+                )
                 .or(
                     // GET /api/v1/search-stats - статистика поиска
                     warp::path("v1")
@@ -342,32 +1031,99 @@ async fn start_web_server(port: u16, app_state: AppState, static_dir: PathBuf) -
                     warp::path("v1")
                         .and(warp::path("categories"))
                         .and(warp::get())
+                        .and(cache_headers())
                         .and(with_state(app_state.clone()))
                         .and_then(handle_get_categories),
-                ),
-        )
-        .with(cors);
+                )
+                .or(repository_routes(app_state.clone())),
+        );
 
     // Доп. health endpoint: /api/health (в target режиме отдаёт состояние CentralTypeSystem)
     let health = warp::path!("api" / "health")
         .and(warp::get())
-        .and(with_state(app_state.clone()))
+        .and(with_state(app_state))
         .and_then(handle_health);
 
-    // Статические файлы
-    let static_files = warp::fs::dir(static_dir);
+    api.or(health)
 
-    // Главная страница
-    let index = warp::path::end().and(warp::get()).and_then(handle_index);
+}
+
+/// Маршруты проектов: `/projects` и `/api/projects` (список), страница
+/// `/projects/{name}/` и API проекта `/projects/{name}/api/*`
+fn project_routes(projects: &[WebProject]) -> BoxedFilter<(warp::reply::Response,)> {
+    let listing = Arc::new(projects.to_vec());
+    let index = warp::path("projects")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_projects(listing.clone()))
+        .and_then(handle_projects_page);
+    let list = warp::path!("api" / "projects")
+        .and(warp::get())
+        .and(with_projects(listing))
+        .and_then(handle_list_projects);
+
+    let mut routes = index
+        .map(Reply::into_response)
+        .or(list.map(Reply::into_response))
+        .unify()
+        .boxed();
+    for project in projects {
+        let base = format!("/projects/{}", project.name);
+        let page = warp::path::end()
+            .and(warp::get())
+            .map(move || warp::reply::html(generate_index_html(&base)));
+        let project_routes = warp::path("projects")
+            .and(warp::path(project.name.clone()))
+            .and(
+                api_routes(project.state.clone())
+                    .or(whats_new_page(project.state.clone()))
+                    .or(documentation_page(project.state.clone()))
+                    .or(inference_help_page(project.state.clone()))
+                    .or(page),
+            )
+            .map(Reply::into_response);
+        routes = routes.or(project_routes).unify().boxed();
+    }
+    routes
+}
 
-    let routes = api.or(health).or(static_files).or(index);
+/// Страница «Что нового» (`/whats-new`) по журналу изменений системы типов
+fn whats_new_page(
+    app_state: AppState,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("whats-new")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_state(app_state))
+        .and_then(handle_whats_new_page)
+}
 
-    println!("🚀 Web server running on http://localhost:{}", port);
-    println!("📖 Open http://localhost:{} to browse BSL types", port);
+/// Страница полноты документации (`/documentation?origin=`)
+fn documentation_page(
+    app_state: AppState,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("documentation")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<DocumentationQuery>())
+        .and(with_state(app_state))
+        .and_then(handle_documentation_page)
+}
 
-    warp::serve(routes).run(([127, 0, 0, 1], port)).await;
+/// Справка «Как работает вывод типов» (`/help/inference`)
+fn inference_help_page(
+    app_state: AppState,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("help" / "inference")
+        .and(warp::get())
+        .and(with_state(app_state))
+        .and_then(handle_inference_help_page)
+}
 
-    Ok(())
+fn with_projects(
+    projects: Arc<Vec<WebProject>>,
+) -> impl Filter<Extract = (Arc<Vec<WebProject>>,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || projects.clone())
 }
 
 /// Helper для передачи состояния в handlers
@@ -377,6 +1133,72 @@ fn with_state(
     warp::any().map(move || state.clone())
 }
 
+/// Заголовки условного запроса и согласования сжатия
+#[derive(Debug, Clone, Default)]
+struct CacheHeaders {
+    if_none_match: Option<String>,
+    accept_encoding: Option<String>,
+}
+
+/// Извлечь If-None-Match и Accept-Encoding из запроса
+fn cache_headers() -> impl Filter<Extract = (CacheHeaders,), Error = std::convert::Infallible> + Clone
+{
+    warp::header::optional::<String>("if-none-match")
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .map(|if_none_match, accept_encoding| CacheHeaders {
+            if_none_match,
+            accept_encoding,
+        })
+        .or(warp::any().map(CacheHeaders::default))
+        .unify()
+}
+
+/// JSON ответ с ETag (304 при совпадении If-None-Match) и сжатием br/gzip
+fn cached_json<T: Serialize>(value: &T, headers: &CacheHeaders) -> warp::reply::Response {
+    use warp::http::{header, Response, StatusCode};
+
+    let prepared = http_cache::prepare_json(
+        value,
+        headers.if_none_match.as_deref(),
+        headers.accept_encoding.as_deref(),
+    );
+
+    let result = match prepared {
+        Ok(CachedResponse::NotModified { etag }) => Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, etag)
+            .header(header::VARY, "Accept-Encoding")
+            .body(warp::hyper::Body::empty()),
+        Ok(CachedResponse::Body(cached)) => {
+            let mut builder = Response::builder()
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::ETAG, cached.etag)
+                .header(header::CACHE_CONTROL, "no-cache")
+                .header(header::VARY, "Accept-Encoding");
+            if let Some(encoding) = cached.encoding.header_value() {
+                builder = builder.header(header::CONTENT_ENCODING, encoding);
+            }
+            builder.body(warp::hyper::Body::from(cached.body))
+        }
+        Err(e) => {
+            error!("❌ Ошибка подготовки ответа: {}", e);
+            return warp::reply::with_status(
+                warp::reply::json(&ApiError {
+                    error: e.to_string(),
+                    code: 500,
+                }),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+            .into_response();
+        }
+    };
+
+    result.unwrap_or_else(|e| {
+        error!("❌ Ошибка формирования ответа: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    })
+}
+
 /// Query параметры для поиска
 #[derive(Deserialize)]
 struct SearchQuery {
@@ -385,15 +1207,82 @@ struct SearchQuery {
     per_page: Option<usize>,
 }
 
-/// Query параметры для автодополнения
-#[derive(Deserialize)]
-struct SuggestionsQuery {
-    q: String,
-    limit: Option<usize>,
-}
+/// Маршруты общего репозитория типов (/api/repository/*) для удалённых клиентов
+fn repository_routes(
+    app_state: AppState,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let repository = warp::path("repository");
 
-/// Ответ API с ошибкой
-#[derive(Serialize)]
+    // GET /api/repository/stats
+    let stats = repository
+        .and(warp::path("stats"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_state(app_state.clone()))
+        .and_then(handle_repository_stats);
+
+    // GET /api/repository/types
+    let types = repository
+        .and(warp::path("types"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(cache_headers())
+        .and(with_state(app_state.clone()))
+        .and_then(handle_repository_types);
+
+    // GET /api/repository/search?q=
+    let search = repository
+        .and(warp::path("search"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::query::<RepositorySearchQuery>())
+        .and(with_state(app_state.clone()))
+        .and_then(handle_repository_search);
+
+    // POST /api/repository/filter
+    let filter = repository
+        .and(warp::path("filter"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(app_state))
+        .and_then(handle_repository_filter);
+
+    stats.or(types).or(search).or(filter)
+}
+
+/// Query параметры поиска в репозитории
+#[derive(Deserialize)]
+struct RepositorySearchQuery {
+    q: String,
+}
+
+/// Query параметры для автодополнения
+#[derive(Deserialize)]
+struct SuggestionsQuery {
+    q: String,
+    limit: Option<usize>,
+}
+
+/// Query параметры палитры
+#[derive(Deserialize)]
+struct QuickOpenQuery {
+    q: String,
+    limit: Option<usize>,
+}
+
+/// Ответ палитры
+#[derive(Serialize)]
+struct QuickOpenResponse {
+    query: String,
+    items: Vec<QuickOpenMatch>,
+    /// Размер индекса
+    indexed: usize,
+    took_ms: f64,
+}
+
+/// Ответ API с ошибкой
+#[derive(Serialize)]
 struct ApiError {
     error: String,
     code: u16,
@@ -448,6 +1337,7 @@ struct CategoryInfo {
 /// Обработчик поиска типов
 async fn handle_search_types(
     query: SearchQuery,
+    headers: CacheHeaders,
     state: AppState,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let search_term = query.search.unwrap_or_default();
@@ -461,7 +1351,7 @@ async fn handle_search_types(
         per_page: Some(per_page),
         filters: None,
     };
-    match state.central.web_interface().handle_search_request(req).await {
+    match state.central().web_interface().handle_search_request(req).await {
         Ok(web_resp) => {
             let types: Vec<SearchResult> = web_resp
                 .results
@@ -481,12 +1371,12 @@ async fn handle_search_types(
                 page: web_resp.page,
                 per_page: web_resp.per_page,
             };
-            Ok(warp::reply::json(&response))
+            Ok(cached_json(&response, &headers))
         }
         Err(e) => {
-            eprintln!("WebInterface search error: {}", e);
+            error!("WebInterface search error: {}", e);
             let response = TypesResponse { types: vec![], total: 0, page, per_page };
-            Ok(warp::reply::json(&response))
+            Ok(warp::reply::json(&response).into_response())
         }
     }
 }
@@ -522,9 +1412,34 @@ async fn handle_get_type_details(
 
 /// Получение деталей типа
 async fn get_type_details(state: &AppState, type_name: &str) -> TypeDetails {
+    let metadata_flags = state
+        .metadata_flags
+        .read()
+        .await
+        .as_ref()
+        .and_then(|index| index.find(type_name).cloned());
+    let examples = state.examples.read().await;
+    let method_examples = |name: &str| -> Vec<ProjectExample> {
+        examples
+            .as_ref()
+            .map(|c| c.examples_for_method(type_name, name).to_vec())
+            .unwrap_or_default()
+    };
+    let type_examples = examples
+        .as_ref()
+        .map(|c| c.examples_for_type(type_name).to_vec())
+        .unwrap_or_default();
+    let team_notes = state
+        .team_notes
+        .read()
+        .await
+        .as_ref()
+        .map(|overlay| overlay.notes_for(type_name).to_vec())
+        .unwrap_or_default();
+
     // Target-only: CentralTypeSystem
     match state
-        .central
+        .central()
         .web_interface()
         .handle_type_details_request(type_name)
         .await
@@ -538,6 +1453,7 @@ async fn get_type_details(state: &AppState, type_name: &str) -> TypeDetails {
                         .methods
                         .into_iter()
                         .map(|m| MethodInfo {
+                            project_examples: method_examples(&m.name),
                             name: m.name,
                             parameters: m
                                 .parameters
@@ -566,7 +1482,10 @@ async fn get_type_details(state: &AppState, type_name: &str) -> TypeDetails {
                         })
                         .collect(),
                     related_types: resp.related_types,
-                    usage_examples: Vec::new(),
+                    usage_examples: type_examples.iter().map(|e| e.snippet.clone()).collect(),
+                    project_examples: type_examples,
+                    metadata_flags,
+                    team_notes,
                 };
         }
         Err(_e) => {
@@ -577,7 +1496,10 @@ async fn get_type_details(state: &AppState, type_name: &str) -> TypeDetails {
                 methods: vec![],
                 properties: vec![],
                 related_types: vec![],
-                usage_examples: vec![],
+                usage_examples: type_examples.iter().map(|e| e.snippet.clone()).collect(),
+                project_examples: type_examples,
+                metadata_flags,
+                team_notes,
             }
         }
     }
@@ -598,7 +1520,7 @@ async fn handle_get_loading_status(state: AppState) -> Result<impl warp::Reply,
 /// Получение статистики системы
 async fn get_system_stats(state: &AppState) -> SystemStats {
     let context = state.type_context.read().await;
-    let metrics = state.central.get_system_metrics().await;
+    let metrics = state.central().get_system_metrics().await;
 
     if let Some(ctx) = context.as_ref() {
         SystemStats {
@@ -727,22 +1649,23 @@ async fn analyze_code_snippet(code: &str, filename: &Option<String>) -> AnalyzeR
 /// Обработчик расширенного поиска
 async fn handle_advanced_search(
     query: AdvancedSearchQuery,
+    headers: CacheHeaders,
     state: AppState,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    println!("🔍 API поиск: '{}'", query.query);
+    info!("🔍 API поиск: '{}'", query.query);
 
     match state.search_engine.search(query).await {
         Ok(results) => {
-            println!("✅ Найдено {} результатов", results.total_count);
-            Ok(warp::reply::json(&results))
+            info!("✅ Найдено {} результатов", results.total_count);
+            Ok(cached_json(&results, &headers))
         }
         Err(e) => {
-            println!("❌ Ошибка поиска: {}", e);
+            error!("❌ Ошибка поиска: {}", e);
             let error = ApiError {
                 error: e.to_string(),
                 code: 500,
             };
-            Ok(warp::reply::json(&error))
+            Ok(warp::reply::json(&error).into_response())
         }
     }
 }
@@ -774,6 +1697,49 @@ async fn handle_get_suggestions(
     }
 }
 
+/// Индекс палитры для текущей системы типов; перестраивается после
+/// перезагрузки конфигурации (SIGHUP)
+async fn quick_open_index(state: &AppState) -> Arc<QuickOpenIndex> {
+    let central = state.central();
+    if let Some(cache) = state.quick_open.read().await.as_ref() {
+        if std::sync::Weak::ptr_eq(&cache.central, &Arc::downgrade(&central)) {
+            return cache.index.clone();
+        }
+    }
+
+    let mut index = QuickOpenIndex::new();
+    match central.repository().load_all_types().await {
+        Ok(types) => index.add_types(&types),
+        Err(e) => warn!("⚠️ Типы для палитры не загружены: {}", e),
+    }
+    for symbol in state.project_symbols.read().await.iter() {
+        index.push(symbol.clone(), &[]);
+    }
+    let index = Arc::new(index);
+    *state.quick_open.write().await = Some(QuickOpenCache {
+        central: Arc::downgrade(&central),
+        index: index.clone(),
+    });
+    index
+}
+
+/// Обработчик палитры: типы, методы, категории и символы проекта
+async fn handle_quick_open(
+    query: QuickOpenQuery,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let start = std::time::Instant::now();
+    let index = quick_open_index(&state).await;
+    let limit = query.limit.unwrap_or(DEFAULT_QUICK_OPEN_LIMIT).min(200);
+    let items = index.search(&query.q, limit);
+    Ok(warp::reply::json(&QuickOpenResponse {
+        query: query.q,
+        items,
+        indexed: index.len(),
+        took_ms: start.elapsed().as_secs_f64() * 1000.0,
+    }))
+}
+
 /// Обработчик статистики поиска
 async fn handle_get_search_stats(state: AppState) -> Result<impl warp::Reply, warp::Rejection> {
     match state.search_engine.get_statistics().await {
@@ -788,10 +1754,63 @@ async fn handle_get_search_stats(state: AppState) -> Result<impl warp::Reply, wa
     }
 }
 
+/// Ответ репозитория: данные либо ошибка с HTTP 500 (клиенту нужен статус)
+fn repository_reply<T: Serialize>(
+    result: bsl_gradual_types::Result<T>,
+) -> warp::reply::WithStatus<warp::reply::Json> {
+    match result {
+        Ok(data) => warp::reply::with_status(warp::reply::json(&data), warp::http::StatusCode::OK),
+        Err(e) => {
+            // Недоступность вышестоящего сервера репозитория — не ошибка этого сервера
+            let status = match e {
+                bsl_gradual_types::Error::DataSource { .. } => warp::http::StatusCode::BAD_GATEWAY,
+                _ => warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            let error = ApiError {
+                error: e.to_string(),
+                code: status.as_u16(),
+            };
+            warp::reply::with_status(warp::reply::json(&error), status)
+        }
+    }
+}
+
+async fn handle_repository_stats(state: AppState) -> Result<impl warp::Reply, warp::Rejection> {
+    let stats = state.central().repository().get_stats();
+    Ok(repository_reply(Ok(stats)))
+}
+
+/// Полный список типов с ETag: удалённые клиенты сверяют по нему свой кэш
+async fn handle_repository_types(
+    headers: CacheHeaders,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match state.central().repository().load_all_types().await {
+        Ok(types) => Ok(cached_json(&types, &headers)),
+        Err(e) => Ok(repository_reply::<()>(Err(e)).into_response()),
+    }
+}
+
+async fn handle_repository_search(
+    query: RepositorySearchQuery,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let types = state.central().repository().search_types(&query.q).await;
+    Ok(repository_reply(types))
+}
+
+async fn handle_repository_filter(
+    filter: TypeFilter,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let types = state.central().repository().load_types_filtered(&filter).await;
+    Ok(repository_reply(types))
+}
+
 /// Обработчик /api/health
 async fn handle_health(state: AppState) -> Result<impl warp::Reply, warp::Rejection> {
-    let health = state.central.health_check().await;
-    let sm = state.central.get_system_metrics().await;
+    let health = state.central().health_check().await;
+    let sm = state.central().get_system_metrics().await;
     let response = HealthResponse {
         status: health.status,
         overall_score: health.overall_score,
@@ -810,7 +1829,10 @@ async fn handle_health(state: AppState) -> Result<impl warp::Reply, warp::Reject
 }
 
 /// Обработчик списка категорий
-async fn handle_get_categories(state: AppState) -> Result<impl warp::Reply, warp::Rejection> {
+async fn handle_get_categories(
+    headers: CacheHeaders,
+    _state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
     // Пока простая реализация - возвращаем фиксированный список
     let categories = vec![
         CategoryInfo {
@@ -844,18 +1866,924 @@ async fn handle_get_categories(state: AppState) -> Result<impl warp::Reply, warp
         categories,
     };
 
-    Ok(warp::reply::json(&response))
+    Ok(cached_json(&response, &headers))
+}
+
+/// Обработчик постраничного списка типов с сортировкой и проекцией полей
+async fn handle_list_types(
+    request: WebTypeListRequest,
+    headers: CacheHeaders,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    // Некорректные параметры запроса — 400, сбой построения списка — 500
+    let (e, status) = match request.to_query() {
+        Err(e) => (e, warp::http::StatusCode::BAD_REQUEST),
+        Ok(query) => match state
+            .central()
+            .web_interface()
+            .handle_type_list_request(&query, request.fields.as_deref())
+            .await
+        {
+            Ok(page) => return Ok(cached_json(&page, &headers)),
+            Err(e) => (e, warp::http::StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    };
+    let error = ApiError {
+        error: e.to_string(),
+        code: status.as_u16(),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&error), status).into_response())
+}
+
+/// Собрать примеры вызовов платформенного API из исходников проекта
+async fn mine_project_examples(
+    state: &AppState,
+    project_path: &std::path::Path,
+) -> Result<ExampleCorpus> {
+    let catalog = state.central().repository().load_all_types().await?;
+    let root = project_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        ExampleCorpus::mine_directory(&root, &catalog, DEFAULT_EXAMPLES_PER_MEMBER)
+    })
+    .await?
+}
+
+/// Построить отчёт об использовании типов по исходникам проекта
+async fn build_usage_report(
+    state: &AppState,
+    project_path: &std::path::Path,
+    configuration_path: Option<&str>,
+) -> Result<UsageReport> {
+    let catalog = state.central().repository().load_all_types().await?;
+    let root = project_path.to_path_buf();
+    let configuration_path = configuration_path.map(std::path::PathBuf::from);
+    tokio::task::spawn_blocking(move || {
+        let mut collector = UsageCollector::new(&catalog);
+        // При наличии выгрузки конфигурации группируем модули по подсистемам
+        if let Some(config) = configuration_path {
+            let index = SubsystemIndex::load(&config)?;
+            if !index.is_empty() {
+                collector = collector
+                    .with_group_resolver(Box::new(move |path| index.group_for_module(path)));
+            }
+        }
+        collector.add_directory(&root)?;
+        Ok(collector.finish(DEFAULT_TOP_N))
+    })
+    .await?
+}
+
+/// Обработчик отчёта об использовании типов (404, если проект не анализировался)
+async fn handle_usage_report(
+    headers: CacheHeaders,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match state.usage_report.read().await.as_ref() {
+        Some(report) => Ok(cached_json(report, &headers)),
+        None => {
+            let error = ApiError {
+                error: "Отчёт недоступен: сервер запущен без --project".to_string(),
+                code: 404,
+            };
+            Ok(warp::reply::with_status(
+                warp::reply::json(&error),
+                warp::http::StatusCode::NOT_FOUND,
+            )
+            .into_response())
+        }
+    }
+}
+
+/// Прочитать флаги объектов выгрузки и, если задан, состав стандартного интерфейса OData
+fn load_metadata_flags(
+    config_path: &str,
+    odata_composition: Option<&str>,
+) -> Result<MetadataFlagsIndex> {
+    let mut index = MetadataFlagsIndex::load(std::path::Path::new(config_path))?;
+    if let Some(path) = odata_composition {
+        index.load_odata_composition(std::path::Path::new(path))?;
+    }
+    Ok(index)
+}
+
+/// Обработчик отчёта по флагам метаданных (404, если конфигурация не задана)
+async fn handle_metadata_flags(
+    filter: MetadataFlagsFilter,
+    headers: CacheHeaders,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match state.metadata_flags.read().await.as_ref() {
+        Some(index) => Ok(cached_json(&index.report(&filter), &headers)),
+        None => {
+            let error = ApiError {
+                error: "Отчёт недоступен: не задан путь к выгрузке конфигурации".to_string(),
+                code: 404,
+            };
+            Ok(warp::reply::with_status(
+                warp::reply::json(&error),
+                warp::http::StatusCode::NOT_FOUND,
+            )
+            .into_response())
+        }
+    }
+}
+
+/// Обработчик списка команд конфигурации (404, если конфигурация не задана)
+async fn handle_commands(
+    headers: CacheHeaders,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match state.commands.read().await.as_ref() {
+        Some(commands) => Ok(cached_json(commands, &headers)),
+        None => {
+            let error = ApiError {
+                error: "Команды недоступны: не задан путь к выгрузке конфигурации".to_string(),
+                code: 404,
+            };
+            Ok(warp::reply::with_status(
+                warp::reply::json(&error),
+                warp::http::StatusCode::NOT_FOUND,
+            )
+            .into_response())
+        }
+    }
+}
+
+/// Сколько записей журнала изменений отдаётся по умолчанию
+const DEFAULT_CHANGES_LIMIT: usize = 50;
+
+/// Query параметры журнала изменений
+#[derive(Deserialize)]
+struct ChangesQuery {
+    limit: Option<usize>,
+}
+
+/// Обработчик /api/v1/changes: записи журнала изменений, от новых к старым
+async fn handle_changes(
+    query: ChangesQuery,
+    headers: CacheHeaders,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let limit = query.limit.unwrap_or(DEFAULT_CHANGES_LIMIT);
+    let entries = state.central.load().changelog().entries(limit);
+    Ok(cached_json(&entries, &headers))
+}
+
+/// Обработчик /api/v1/maintenance
+async fn handle_maintenance_status(state: AppState) -> Result<impl warp::Reply, warp::Rejection> {
+    let status = state.central().maintenance_status().await;
+    Ok(warp::reply::json(&status))
+}
+
+/// Обработчик /api/v1/changes.rss; ссылки ведут на страницу «Что нового»
+/// той же системы типов (основной или проекта)
+async fn handle_changes_rss(
+    query: ChangesQuery,
+    full_path: warp::path::FullPath,
+    host: Option<String>,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let limit = query.limit.unwrap_or(DEFAULT_CHANGES_LIMIT);
+    let entries = state.central.load().changelog().entries(limit);
+    let base = full_path
+        .as_str()
+        .strip_suffix("api/v1/changes.rss")
+        .unwrap_or("/");
+    let page = format!("{}whats-new", base);
+    let link = match host {
+        Some(host) => format!("http://{}{}", host, page),
+        None => page,
+    };
+    let rss = render_rss(&entries, "BSL: изменения репозитория типов", &link);
+    Ok(warp::reply::with_header(
+        rss,
+        "content-type",
+        "application/rss+xml; charset=utf-8",
+    ))
+}
+
+/// Обработчик страницы /whats-new
+async fn handle_whats_new_page(state: AppState) -> Result<impl warp::Reply, warp::Rejection> {
+    let entries = state
+        .central
+        .load()
+        .changelog()
+        .entries(DEFAULT_CHANGES_LIMIT);
+    Ok(warp::reply::html(generate_whats_new_html(&entries)))
+}
+
+/// Обработчик страницы /help/inference: справка строится по действующей
+/// цепочке резолверов
+async fn handle_inference_help_page(state: AppState) -> Result<impl warp::Reply, warp::Rejection> {
+    let reference = state.central().inference_reference().await;
+    Ok(warp::reply::html(reference.to_html()))
+}
+
+/// Сколько типов с худшей оценкой показывает страница документации
+const DEFAULT_DOCUMENTATION_LIMIT: usize = 100;
+
+/// Query параметры отчёта о полноте документации
+#[derive(Deserialize)]
+struct DocumentationQuery {
+    /// platform, configuration или user_defined
+    origin: Option<String>,
+    /// `csv` — выгрузка таблицей вместо JSON
+    format: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Отчёт о документации, при заданном `origin` — только по этому источнику
+async fn documentation_report_for(
+    query: &DocumentationQuery,
+    state: &AppState,
+) -> Result<DocumentationReport, (String, warp::http::StatusCode)> {
+    let origin = match query.origin.as_deref().filter(|o| !o.is_empty()) {
+        Some(origin) => Some(
+            origin
+                .parse::<TypeOrigin>()
+                .map_err(|e| (e, warp::http::StatusCode::BAD_REQUEST))?,
+        ),
+        None => None,
+    };
+    let report = state
+        .central()
+        .documentation_report()
+        .await
+        .map_err(|e| (e.to_string(), warp::http::StatusCode::INTERNAL_SERVER_ERROR))?;
+    Ok(match origin {
+        Some(origin) => report.for_origin(origin),
+        None => report,
+    })
+}
+
+/// Обработчик /api/v1/documentation-report
+async fn handle_documentation_report(
+    query: DocumentationQuery,
+    headers: CacheHeaders,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let report = match documentation_report_for(&query, &state).await {
+        Ok(report) => report,
+        Err((error, status)) => {
+            let error = ApiError {
+                error,
+                code: status.as_u16(),
+            };
+            return Ok(warp::reply::with_status(warp::reply::json(&error), status).into_response());
+        }
+    };
+    if query.format.as_deref() == Some("csv") {
+        let response =
+            warp::reply::with_header(report.to_csv(), "content-type", "text/csv; charset=utf-8");
+        let response = warp::reply::with_header(
+            response,
+            "content-disposition",
+            "attachment; filename=\"documentation-report.csv\"",
+        );
+        return Ok(response.into_response());
+    }
+    Ok(cached_json(&report, &headers))
+}
+
+/// Обработчик страницы /documentation
+async fn handle_documentation_page(
+    query: DocumentationQuery,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let limit = query.limit.unwrap_or(DEFAULT_DOCUMENTATION_LIMIT);
+    let html = match documentation_report_for(&query, &state).await {
+        Ok(report) => generate_documentation_html(&report, query.origin.as_deref(), limit),
+        Err((error, _)) => format!("<p>{}</p>", escape_html(&error)),
+    };
+    Ok(warp::reply::html(html))
+}
+
+/// Генерация HTML страницы полноты документации: сводка по источникам и
+/// типы с худшей оценкой
+fn generate_documentation_html(
+    report: &DocumentationReport,
+    origin: Option<&str>,
+    limit: usize,
+) -> String {
+    let origins: String = report
+        .origins
+        .iter()
+        .map(|o| {
+            format!(
+                "<tr><td><a href=\"?origin={0}\">{0}</a></td><td>{1:.1}%</td><td>{2} / {3}</td>\
+                 <td>{4}</td><td>{5}</td><td>{6}</td><td>{7}</td><td>{8}</td></tr>\n",
+                o.origin.as_str(),
+                o.average_score,
+                o.complete_types,
+                o.total_types,
+                o.missing_descriptions,
+                o.undocumented_methods,
+                o.methods_without_parameter_docs,
+                o.undocumented_properties,
+                o.missing_examples
+            )
+        })
+        .collect();
+    let types: String = report
+        .types
+        .iter()
+        .filter(|t| !t.is_complete())
+        .take(limit)
+        .map(|t| {
+            let mut gaps = Vec::new();
+            if t.missing_description {
+                gaps.push("нет описания типа".to_string());
+            }
+            if !t.undocumented_methods.is_empty() {
+                gaps.push(format!("методы: {}", t.undocumented_methods.join(", ")));
+            }
+            if !t.methods_without_parameter_docs.is_empty() {
+                gaps.push(format!(
+                    "параметры: {}",
+                    t.methods_without_parameter_docs.join(", ")
+                ));
+            }
+            if !t.undocumented_properties.is_empty() {
+                gaps.push(format!(
+                    "свойства: {}",
+                    t.undocumented_properties.join(", ")
+                ));
+            }
+            if t.missing_examples {
+                gaps.push("нет примеров".to_string());
+            }
+            format!(
+                "<tr><td>{}</td><td>{}</td><td>{:.0}%</td><td>{}</td></tr>\n",
+                escape_html(&t.name),
+                t.origin.as_str(),
+                t.score,
+                escape_html(&gaps.join("; "))
+            )
+        })
+        .collect();
+    let body = if types.is_empty() {
+        "<p>✅ Документация полная</p>".to_string()
+    } else {
+        format!(
+            "<table>\n<tr><th>Тип</th><th>Источник</th><th>Оценка</th><th>Не описано</th></tr>\n{}</table>",
+            types
+        )
+    };
+    let query = origin
+        .filter(|o| !o.is_empty())
+        .map(|o| format!("&origin={}", escape_html(o)))
+        .unwrap_or_default();
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ru">
+<head>
+    <meta charset="UTF-8">
+    <title>BSL Type Browser — полнота документации</title>
+    <style>
+        body {{ font-family: 'Segoe UI', Tahoma, sans-serif; background: #1e1e1e; color: #d4d4d4; }}
+        .container {{ max-width: 1100px; margin: 0 auto; padding: 20px; }}
+        h1, h2 {{ color: #569cd6; }}
+        table {{ border-collapse: collapse; width: 100%; }}
+        th, td {{ border-bottom: 1px solid #333; padding: 4px 8px; text-align: left; }}
+        th {{ color: #9cdcfe; }}
+        a {{ color: #4ec9b0; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <h1>📚 Полнота документации: {:.1}% ({} типов)</h1>
+        <p><a href="?">Все источники</a> · <a href="api/v1/documentation-report?format=csv{}">CSV</a> · <a href="api/v1/documentation-report?{}">JSON</a></p>
+        <table>
+            <tr><th>Источник</th><th>Оценка</th><th>Полностью</th><th>Без описания</th><th>Методы без описания</th><th>Без описания параметров</th><th>Свойства без описания</th><th>Без примеров</th></tr>
+            {}
+        </table>
+        <h2>Начать стоит с</h2>
+        {}
+    </div>
+</body>
+</html>
+"#,
+        report.average_score,
+        report.total_types,
+        query,
+        query.trim_start_matches('&'),
+        origins,
+        body
+    )
+}
+
+/// Генерация HTML страницы «Что нового»
+fn generate_whats_new_html(entries: &[ChangeEntry]) -> String {
+    let type_items = |types: &[ChangedTypeRef], sign: &str| -> String {
+        types
+            .iter()
+            .map(|t| {
+                format!(
+                    "<li class=\"{}\">{} {} <span>{}</span></li>\n",
+                    if sign == "+" { "added" } else { "removed" },
+                    sign,
+                    escape_html(&t.name),
+                    escape_html(&t.source)
+                )
+            })
+            .collect()
+    };
+    let sections: String = entries
+        .iter()
+        .map(|entry| {
+            let changed: String = entry
+                .changed
+                .iter()
+                .map(|change| {
+                    let members = change
+                        .added_members
+                        .iter()
+                        .map(|m| format!("+{}", m))
+                        .chain(change.removed_members.iter().map(|m| format!("−{}", m)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!(
+                        "<li class=\"changed\">~ {} <span>{}</span></li>\n",
+                        escape_html(&change.name),
+                        escape_html(&members)
+                    )
+                })
+                .collect();
+            format!(
+                "<section id=\"change-{}\">\n<h2>#{} · {}</h2>\n<p>{}</p>\n<ul>\n{}{}{}</ul>\n</section>\n",
+                entry.sequence,
+                entry.sequence,
+                escape_html(&entry.rfc2822()),
+                escape_html(&entry.summary()),
+                type_items(&entry.added, "+"),
+                type_items(&entry.removed, "−"),
+                changed
+            )
+        })
+        .collect();
+    let body = if sections.is_empty() {
+        "<p>Изменений пока нет: журнал пополняется, когда перезагрузка типов находит отличия</p>"
+            .to_string()
+    } else {
+        sections
+    };
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ru">
+<head>
+    <meta charset="UTF-8">
+    <title>BSL Type Browser — что нового</title>
+    <link rel="alternate" type="application/rss+xml" href="api/v1/changes.rss">
+    <style>
+        body {{ font-family: 'Segoe UI', Tahoma, sans-serif; background: #1e1e1e; color: #d4d4d4; }}
+        .container {{ max-width: 900px; margin: 0 auto; padding: 20px; }}
+        h1, h2 {{ color: #569cd6; }}
+        li {{ margin: 4px 0; }}
+        .added {{ color: #4ec9b0; }}
+        .removed {{ color: #f48771; }}
+        .changed {{ color: #dcdcaa; }}
+        span {{ color: #9cdcfe; margin-left: 10px; }}
+        a {{ color: #4ec9b0; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <h1>🆕 Что нового</h1>
+        <p><a href="api/v1/changes.rss">RSS</a> · <a href="api/v1/changes">JSON</a></p>
+        {}
+    </div>
+</body>
+</html>
+"#,
+        body
+    )
+}
+
+/// Черновик заметки в очереди на одобрение
+#[derive(Serialize)]
+struct DraftNote {
+    type_name: String,
+    index: usize,
+    note: TeamNote,
+}
+
+/// Запрос на одобрение черновика
+#[derive(Deserialize)]
+struct ApproveNoteRequest {
+    reviewer: String,
+}
+
+/// Маршруты заметок команды: `GET`/`POST /api/v1/notes/{type}`,
+/// `DELETE /api/v1/notes/{type}/{index}`, очередь черновиков
+/// `GET /api/v1/notes/drafts` и одобрение
+/// `POST /api/v1/notes/{type}/{index}/approve`
+fn notes_routes(
+    app_state: AppState,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let notes = warp::path("v1").and(warp::path("notes"));
+
+    let drafts = notes
+        .and(warp::path("drafts"))
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_state(app_state.clone()))
+        .and_then(handle_get_note_drafts);
+
+    let list = notes
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_state(app_state.clone()))
+        .and_then(handle_get_notes);
+
+    let add = notes
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(app_state.clone()))
+        .and_then(handle_add_note);
+
+    let remove = notes
+        .and(warp::path::param::<String>())
+        .and(warp::path::param::<usize>())
+        .and(warp::path::end())
+        .and(warp::delete())
+        .and(with_state(app_state.clone()))
+        .and_then(handle_remove_note);
+
+    let approve = notes
+        .and(warp::path::param::<String>())
+        .and(warp::path::param::<usize>())
+        .and(warp::path("approve"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state(app_state))
+        .and_then(handle_approve_note);
+
+    drafts.or(list).or(add).or(remove).or(approve)
+}
+
+/// Маршруты палитры команд: `GET /api/commands` — команды со схемами
+/// аргументов, `POST /api/commands/{id}` — выполнение с аргументами в JSON
+fn commands_routes(
+    app_state: AppState,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let list = warp::path("commands")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and_then(handle_list_commands);
+
+    let execute = warp::path("commands")
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .and(with_state(app_state))
+        .and_then(handle_execute_command);
+
+    list.or(execute)
+}
+
+/// Обработчик GET /api/commands
+async fn handle_list_commands() -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&command_descriptors()))
+}
+
+/// Обработчик POST /api/commands/{id}; пустое тело — команда без аргументов
+async fn handle_execute_command(
+    id: String,
+    body: warp::hyper::body::Bytes,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    use warp::http::StatusCode;
+
+    let command: ServerCommand = match id.parse() {
+        Ok(command) => command,
+        Err(error) => return Ok(command_error(error, StatusCode::NOT_FOUND)),
+    };
+    let arguments = if body.iter().all(u8::is_ascii_whitespace) {
+        Ok(serde_json::Value::Null)
+    } else {
+        serde_json::from_slice(&body).map_err(|e| format!("Аргументы команды не JSON: {}", e))
+    };
+    let arguments =
+        match arguments.and_then(|value| command.validate(&value).map_err(|e| e.to_string())) {
+            Ok(arguments) => arguments,
+            Err(error) => return Ok(command_error(error, StatusCode::BAD_REQUEST)),
+        };
+    match state.central().execute_command(command, &arguments).await {
+        Ok(outcome) => Ok(warp::reply::json(&outcome).into_response()),
+        Err(e) => {
+            error!("❌ Команда {} не выполнена: {:#}", command.id(), e);
+            Ok(command_error(
+                format!("{:#}", e),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
+
+/// Ответ об ошибке команды сервера
+fn command_error(error: String, status: warp::http::StatusCode) -> warp::reply::Response {
+    let error = ApiError {
+        error,
+        code: status.as_u16(),
+    };
+    warp::reply::with_status(warp::reply::json(&error), status).into_response()
+}
+
+/// Ответ об ошибке API заметок
+fn notes_error(error: String, status: warp::http::StatusCode) -> warp::reply::Response {
+    let error = ApiError {
+        error,
+        code: status.as_u16(),
+    };
+    warp::reply::with_status(warp::reply::json(&error), status).into_response()
+}
+
+/// Имя типа из сегмента пути (кириллица приходит в percent-encoding)
+fn decode_type_name(type_name: &str) -> String {
+    urlencoding::decode(type_name)
+        .map(|name| name.into_owned())
+        .unwrap_or_else(|_| type_name.to_string())
+}
+
+const NOTES_DISABLED: &str = "Заметки команды недоступны: не задан team_notes_path";
+
+/// Заметки команды к типу
+async fn handle_get_notes(
+    type_name: String,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let type_name = decode_type_name(&type_name);
+    match state.team_notes.read().await.as_ref() {
+        Some(overlay) => Ok(warp::reply::json(&overlay.notes_for(&type_name)).into_response()),
+        None => Ok(notes_error(NOTES_DISABLED.to_string(), warp::http::StatusCode::NOT_FOUND)),
+    }
+}
+
+/// Черновики заметок всех типов, ожидающие одобрения
+async fn handle_get_note_drafts(state: AppState) -> Result<impl warp::Reply, warp::Rejection> {
+    let guard = state.team_notes.read().await;
+    let Some(overlay) = guard.as_ref() else {
+        return Ok(notes_error(NOTES_DISABLED.to_string(), warp::http::StatusCode::NOT_FOUND));
+    };
+    let drafts: Vec<DraftNote> = overlay
+        .drafts()
+        .into_iter()
+        .map(|(type_name, index, note)| DraftNote {
+            type_name: type_name.to_string(),
+            index,
+            note: note.clone(),
+        })
+        .collect();
+    Ok(warp::reply::json(&drafts).into_response())
+}
+
+/// Предложить заметку к типу: сохраняется черновиком в YAML-файл типа и
+/// попадает в подсказки после одобрения
+async fn handle_add_note(
+    type_name: String,
+    note: TeamNote,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let type_name = decode_type_name(&type_name);
+    if note.text.trim().is_empty() {
+        return Ok(notes_error(
+            "Пустой текст заметки".to_string(),
+            warp::http::StatusCode::BAD_REQUEST,
+        ));
+    }
+    if note.author.as_deref().unwrap_or_default().trim().is_empty() {
+        return Ok(notes_error(
+            "Не указан автор заметки".to_string(),
+            warp::http::StatusCode::BAD_REQUEST,
+        ));
+    }
+    let mut guard = state.team_notes.write().await;
+    let Some(overlay) = guard.as_mut() else {
+        return Ok(notes_error(NOTES_DISABLED.to_string(), warp::http::StatusCode::NOT_FOUND));
+    };
+    match overlay.submit(&type_name, note) {
+        Ok(()) => Ok(warp::reply::with_status(
+            warp::reply::json(&overlay.notes_for(&type_name)),
+            warp::http::StatusCode::CREATED,
+        )
+        .into_response()),
+        Err(e) => Ok(notes_error(
+            format!("{:#}", e),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Удалить заметку типа по номеру
+async fn handle_remove_note(
+    type_name: String,
+    index: usize,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let type_name = decode_type_name(&type_name);
+    let mut guard = state.team_notes.write().await;
+    let Some(overlay) = guard.as_mut() else {
+        return Ok(notes_error(NOTES_DISABLED.to_string(), warp::http::StatusCode::NOT_FOUND));
+    };
+    match overlay.remove(&type_name, index) {
+        Ok(true) => Ok(warp::reply::json(&overlay.notes_for(&type_name)).into_response()),
+        Ok(false) => Ok(notes_error(
+            format!("У типа {} нет заметки {}", type_name, index),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Err(e) => Ok(notes_error(
+            format!("{:#}", e),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Одобрить черновик заметки: заметка публикуется с именем рецензента
+async fn handle_approve_note(
+    type_name: String,
+    index: usize,
+    request: ApproveNoteRequest,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let type_name = decode_type_name(&type_name);
+    if request.reviewer.trim().is_empty() {
+        return Ok(notes_error(
+            "Не указан рецензент".to_string(),
+            warp::http::StatusCode::BAD_REQUEST,
+        ));
+    }
+    let mut guard = state.team_notes.write().await;
+    let Some(overlay) = guard.as_mut() else {
+        return Ok(notes_error(NOTES_DISABLED.to_string(), warp::http::StatusCode::NOT_FOUND));
+    };
+    match overlay.approve(&type_name, index, &request.reviewer) {
+        Ok(Approval::Approved) => {
+            Ok(warp::reply::json(&overlay.notes_for(&type_name)).into_response())
+        }
+        Ok(Approval::NotFound) => Ok(notes_error(
+            format!("У типа {} нет заметки {}", type_name, index),
+            warp::http::StatusCode::NOT_FOUND,
+        )),
+        Ok(Approval::AlreadyPublished) => Ok(notes_error(
+            format!("Заметка {} типа {} уже опубликована", index, type_name),
+            warp::http::StatusCode::CONFLICT,
+        )),
+        Ok(Approval::OwnNote) => Ok(notes_error(
+            "Автор не может одобрить свою заметку".to_string(),
+            warp::http::StatusCode::FORBIDDEN,
+        )),
+        Err(e) => Ok(notes_error(
+            format!("{:#}", e),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Выгрузка закладок синтакс-помощника для импорта
+#[derive(Deserialize)]
+struct BookmarkImportRequest {
+    content: String,
+}
+
+/// Сопоставить закладки синтакс-помощника с типами репозитория; избранное
+/// хранится в браузере, сервер только переводит разделы справки в типы
+async fn handle_import_bookmarks(
+    request: BookmarkImportRequest,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let types = state.central().repository().load_all_types().await;
+    Ok(repository_reply(types.map(|types| {
+        import_bookmarks(&request.content, &HelpAliasTable::from_types(&types))
+    })))
+}
+
+/// Обработчик полной иерархии типов (может занимать мегабайты — отдаётся с ETag и сжатием)
+async fn handle_get_hierarchy(
+    headers: CacheHeaders,
+    state: AppState,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match state.central().web_interface().handle_hierarchy_request().await {
+        Ok(hierarchy) => Ok(cached_json(&hierarchy, &headers)),
+        Err(e) => {
+            error!("❌ Ошибка построения иерархии: {}", e);
+            let error = ApiError {
+                error: e.to_string(),
+                code: 500,
+            };
+            Ok(warp::reply::json(&error).into_response())
+        }
+    }
+}
+
+/// Проект в списке /api/projects
+#[derive(Serialize)]
+struct ProjectInfo {
+    name: String,
+    configuration_path: String,
+    /// Страница проекта
+    url: String,
+    total_types: usize,
+}
+
+async fn project_infos(projects: &[WebProject]) -> Vec<ProjectInfo> {
+    let mut infos = Vec::with_capacity(projects.len());
+    for project in projects {
+        let metrics = project.state.central().get_system_metrics().await;
+        infos.push(ProjectInfo {
+            name: project.name.clone(),
+            configuration_path: project.configuration_path.clone(),
+            url: format!("/projects/{}/", project.name),
+            total_types: metrics.total_types,
+        });
+    }
+    infos
+}
+
+/// Обработчик /api/projects
+async fn handle_list_projects(
+    projects: Arc<Vec<WebProject>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&project_infos(&projects).await))
+}
+
+/// Обработчик страницы /projects со списком конфигураций
+async fn handle_projects_page(
+    projects: Arc<Vec<WebProject>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let infos = project_infos(&projects).await;
+    Ok(warp::reply::html(generate_projects_html(&infos)))
+}
+
+/// Экранирование текста для вставки в HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Генерация HTML страницы со списком проектов
+fn generate_projects_html(projects: &[ProjectInfo]) -> String {
+    let rows: String = projects
+        .iter()
+        .map(|project| {
+            format!(
+                "<li><a href=\"{}\">{}</a> <span>{} · типов: {}</span></li>\n",
+                escape_html(&project.url),
+                escape_html(&project.name),
+                escape_html(&project.configuration_path),
+                project.total_types
+            )
+        })
+        .collect();
+    let body = if rows.is_empty() {
+        "<p>Проекты не настроены (web_server.projects, BSL_WEB_PROJECTS)</p>".to_string()
+    } else {
+        format!("<ul>\n{}</ul>", rows)
+    };
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="ru">
+<head>
+    <meta charset="UTF-8">
+    <title>BSL Type Browser — проекты</title>
+    <style>
+        body {{ font-family: 'Segoe UI', Tahoma, sans-serif; background: #1e1e1e; color: #d4d4d4; }}
+        .container {{ max-width: 900px; margin: 0 auto; padding: 20px; }}
+        h1 {{ color: #569cd6; }}
+        li {{ margin: 10px 0; }}
+        a {{ color: #4ec9b0; font-size: 1.2em; }}
+        span {{ color: #9cdcfe; margin-left: 10px; }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <h1>📦 Конфигурации</h1>
+        <p><a href="/">Основная конфигурация</a></p>
+        {}
+    </div>
+</body>
+</html>
+"#,
+        body
+    )
 }
 
 /// Обработчик главной страницы
 async fn handle_index() -> Result<impl warp::Reply, warp::Rejection> {
-    let html = generate_index_html();
+    let html = generate_index_html("");
     Ok(warp::reply::html(html))
 }
 
-/// Генерация HTML главной страницы
-fn generate_index_html() -> String {
-    r#"
+/// Генерация HTML главной страницы; `api_base` — префикс запросов к API
+/// (пустой для основной конфигурации, `/projects/{name}` для проекта)
+fn generate_index_html(api_base: &str) -> String {
+    r##"
 <!DOCTYPE html>
 <html lang="ru">
 <head>
@@ -874,6 +2802,7 @@ fn generate_index_html() -> String {
         .header { text-align: center; margin-bottom: 40px; }
         .header h1 { color: #569cd6; font-size: 2.5em; margin-bottom: 10px; }
         .header p { color: #9cdcfe; font-size: 1.2em; }
+        .help-link { color: #4ec9b0; font-size: 0.8em; }
         
         .search-section { margin-bottom: 40px; }
         .search-box { 
@@ -900,6 +2829,14 @@ fn generate_index_html() -> String {
         .type-name { color: #4ec9b0; font-size: 1.3em; font-weight: bold; }
         .type-category { color: #9cdcfe; font-size: 0.9em; }
         .type-description { color: #d4d4d4; margin-top: 10px; }
+        .favorite { color: #dcdcaa; margin-right: 10px; text-decoration: none; }
+        .notes-btn { margin-top: 10px; background: none; border: 1px solid #569cd6; color: #569cd6; border-radius: 3px; cursor: pointer; }
+        .team-note { border-left: 3px solid #c586c0; padding: 5px 10px; margin-top: 10px; white-space: pre-wrap; }
+        .team-note.warning { border-left-color: #ffcc00; }
+        .team-badge { background: #c586c0; color: #1e1e1e; border-radius: 3px; padding: 1px 6px; font-size: 0.8em; font-weight: bold; }
+        .team-note.warning .team-badge { background: #ffcc00; }
+        .team-note.draft { opacity: 0.7; border-left-style: dashed; }
+        .draft-badge { border: 1px solid #808080; color: #808080; border-radius: 3px; padding: 1px 6px; font-size: 0.8em; }
         
         .stats-grid { 
             display: grid; 
@@ -979,6 +2916,7 @@ fn generate_index_html() -> String {
         <div class="header">
             <h1>🚀 BSL Type Browser</h1>
             <p>Production-ready система типов для 1С:Предприятие</p>
+            <p><a href="{{API_BASE}}/help/inference" class="help-link">📖 Как работает вывод типов</a></p>
         </div>
         
         <div class="progress-section" id="progress-section" style="display: none;">
@@ -1008,10 +2946,36 @@ fn generate_index_html() -> String {
             </div>
         </div>
         
+        <div class="usage-report" id="usage-report" style="display: none;">
+            <h2>📈 Использование типов в проекте</h2>
+            <div class="stats-grid">
+                <div class="stat-card">
+                    <div class="stat-value" id="usage-unused-types">-</div>
+                    <div class="stat-label">Неиспользуемых типов</div>
+                </div>
+                <div class="stat-card">
+                    <div class="stat-value" id="usage-unused-methods">-</div>
+                    <div class="stat-label">Неиспользуемых методов</div>
+                </div>
+                <div class="stat-card">
+                    <div class="stat-value" id="usage-deprecated">-</div>
+                    <div class="stat-label">Вызовов устаревшего API</div>
+                </div>
+            </div>
+            <div id="usage-top"></div>
+        </div>
+
         <div class="search-section">
             <input type="text" class="search-box" id="search-input" 
                    placeholder="Поиск типов BSL... (например: Массив, Структура, ТаблицаЗначений)">
         </div>
+
+        <div class="favorites" id="favorites">
+            <label class="notes-btn">⭐ Импорт закладок синтакс-помощника
+                <input type="file" accept=".txt" style="display: none;" onchange="importBookmarks(this)">
+            </label>
+            <div id="favorites-list"></div>
+        </div>
         
         <div class="results" id="results">
             <p style="text-align: center; color: #9cdcfe;">
@@ -1029,8 +2993,12 @@ fn generate_index_html() -> String {
     </div>
     
     <script>
+        const API_BASE = '{{API_BASE}}';
+
         // Загрузка статистики при старте
         loadStats();
+        renderFavorites();
+        loadUsageReport();
         checkLoadingStatus();
         
         // Поиск типов
@@ -1044,7 +3012,7 @@ fn generate_index_html() -> String {
         
         async function loadStats() {
             try {
-                const response = await fetch('/api/stats');
+                const response = await fetch(API_BASE + '/api/stats');
                 const stats = await response.json();
                 
                 document.getElementById('functions-count').textContent = stats.total_functions || 0;
@@ -1056,6 +3024,29 @@ fn generate_index_html() -> String {
             }
         }
         
+        async function loadUsageReport() {
+            try {
+                const response = await fetch(API_BASE + '/api/v1/usage-report');
+                if (!response.ok) return;
+                const report = await response.json();
+
+                document.getElementById('usage-unused-types').textContent = report.unused_types.length;
+                document.getElementById('usage-unused-methods').textContent = report.unused_methods.length;
+                document.getElementById('usage-deprecated').textContent = report.deprecated_usages.length;
+                document.getElementById('usage-top').innerHTML =
+                    '<h3>Самые используемые методы</h3><ul>' +
+                    report.most_used_methods.slice(0, 10)
+                        .map(m => `<li>${m.name} — ${m.count} (модулей: ${m.files})</li>`).join('') +
+                    '</ul><h3>По группам</h3><ul>' +
+                    report.groups
+                        .map(g => `<li>${g.group}: ${g.total_usages} использований, устаревших: ${g.deprecated_usages}</li>`).join('') +
+                    '</ul>';
+                document.getElementById('usage-report').style.display = 'block';
+            } catch (error) {
+                console.error('Error loading usage report:', error);
+            }
+        }
+
         async function searchTypes(query) {
             if (!query.trim()) {
                 document.getElementById('results').innerHTML = 
@@ -1066,7 +3057,7 @@ fn generate_index_html() -> String {
             document.getElementById('results').innerHTML = '<p class="loading">🔍 Поиск...</p>';
             
             try {
-                const response = await fetch(`/api/types?search=${encodeURIComponent(query)}&per_page=10`);
+                const response = await fetch(`${API_BASE}/api/types?search=${encodeURIComponent(query)}&per_page=10`);
                 const data = await response.json();
                 
                 if (data.types.length === 0) {
@@ -1076,10 +3067,12 @@ fn generate_index_html() -> String {
                 }
                 
                 const html = data.types.map(type => `
-                    <div class="type-card">
+                    <div class="type-card" data-type="${type.name}">
                         <div class="type-name">${type.name}</div>
                         <div class="type-category">${type.category} • ${type.result_type}</div>
                         ${type.description ? `<div class="type-description">${type.description}</div>` : ''}
+                        <button class="notes-btn" onclick="toggleNotes(this)">📝 Заметки команды</button>
+                        <div class="team-notes"></div>
                     </div>
                 `).join('');
                 
@@ -1091,11 +3084,142 @@ fn generate_index_html() -> String {
             }
         }
         
+        function loadFavorites() {
+            return JSON.parse(localStorage.getItem('bsl-web-favorites') || '[]');
+        }
+
+        function renderFavorites(message) {
+            const links = loadFavorites().map(fav => `
+                <a href="#" class="favorite" data-type="${escapeHtml(fav.type_name)}" onclick="openFavorite(event, this.dataset.type)">
+                    ⭐ ${escapeHtml(fav.title)}</a>
+            `).join(' ');
+            document.getElementById('favorites-list').innerHTML =
+                (message ? `<p>${message}</p>` : '') + links;
+        }
+
+        function openFavorite(event, typeName) {
+            event.preventDefault();
+            document.getElementById('search-input').value = typeName;
+            searchTypes(typeName);
+        }
+
+        async function importBookmarks(input) {
+            const file = input.files[0];
+            if (!file) return;
+            const response = await fetch(API_BASE + '/api/v1/bookmarks/import', {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify({ content: await file.text() })
+            });
+            const data = await response.json();
+            if (!response.ok) {
+                renderFavorites(`❌ ${escapeHtml(data.error)}`);
+                return;
+            }
+            const favorites = loadFavorites();
+            const key = (fav) => `${fav.type_id}#${fav.member || ''}`;
+            const known = new Set(favorites.map(key));
+            data.bookmarks.filter(fav => !known.has(key(fav))).forEach(fav => favorites.push(fav));
+            localStorage.setItem('bsl-web-favorites', JSON.stringify(favorites));
+            const skipped = data.unmatched.length
+                ? `, не найдены типы: ${data.unmatched.map(escapeHtml).join('; ')}` : '';
+            renderFavorites(`Импортировано закладок: ${data.bookmarks.length}${skipped}`);
+            input.value = '';
+        }
+
+        const NOTE_BADGES = { note: 'Заметка команды', example: 'Пример команды', warning: 'Предупреждение команды' };
+
+        function escapeHtml(text) {
+            const div = document.createElement('div');
+            div.textContent = text;
+            return div.innerHTML;
+        }
+
+        function notesUrl(typeName) {
+            return `${API_BASE}/api/v1/notes/${encodeURIComponent(typeName)}`;
+        }
+
+        function renderNotes(card, notes) {
+            const list = notes.map((note, index) => `
+                <div class="team-note ${note.kind} ${note.status}">
+                    <span class="team-badge">${NOTE_BADGES[note.kind] || NOTE_BADGES.note}</span>
+                    ${note.status === 'draft' ? '<span class="draft-badge">Черновик</span>' : ''}
+                    ${note.member ? `<code>${escapeHtml(note.member)}</code>` : ''}
+                    ${note.author ? `— ${escapeHtml(note.author)}` : ''}
+                    ${note.reviewer ? `(одобрил ${escapeHtml(note.reviewer)})` : ''}
+                    ${note.status === 'draft' ? `<button class="notes-btn" onclick="approveNote(this, ${index})">Одобрить</button>` : ''}
+                    <button class="notes-btn" onclick="removeNote(this, ${index})">✖</button>
+                    <div>${escapeHtml(note.text)}</div>
+                </div>
+            `).join('');
+            card.querySelector('.team-notes').innerHTML = list + `
+                <div class="team-note">
+                    <select class="note-kind">
+                        <option value="note">Заметка</option>
+                        <option value="example">Пример</option>
+                        <option value="warning">Предупреждение</option>
+                    </select>
+                    <input class="note-member" placeholder="Метод или свойство (необязательно)">
+                    <input class="note-author" placeholder="Автор" required>
+                    <textarea class="note-text" placeholder="Текст заметки"></textarea>
+                    <button class="notes-btn" onclick="addNote(this)">Предложить</button>
+                </div>
+            `;
+        }
+
+        async function notesRequest(card, url, options) {
+            const response = await fetch(url, options);
+            const data = await response.json();
+            if (!response.ok) {
+                card.querySelector('.team-notes').innerHTML = `<p class="error">❌ ${escapeHtml(data.error)}</p>`;
+                return;
+            }
+            renderNotes(card, data);
+        }
+
+        async function toggleNotes(button) {
+            const card = button.closest('.type-card');
+            const container = card.querySelector('.team-notes');
+            if (container.innerHTML.trim()) {
+                container.innerHTML = '';
+                return;
+            }
+            await notesRequest(card, notesUrl(card.dataset.type));
+        }
+
+        async function addNote(button) {
+            const card = button.closest('.type-card');
+            const value = (selector) => card.querySelector(selector).value.trim();
+            const note = { kind: value('.note-kind'), text: value('.note-text'), author: value('.note-author') };
+            if (value('.note-member')) note.member = value('.note-member');
+            await notesRequest(card, notesUrl(card.dataset.type), {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify(note)
+            });
+        }
+
+        async function approveNote(button, index) {
+            const card = button.closest('.type-card');
+            const reviewer = prompt('Рецензент');
+            if (!reviewer || !reviewer.trim()) return;
+            await notesRequest(card, `${notesUrl(card.dataset.type)}/${index}/approve`, {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify({ reviewer: reviewer.trim() })
+            });
+        }
+
+        async function removeNote(button, index) {
+            const card = button.closest('.type-card');
+            await notesRequest(card, `${notesUrl(card.dataset.type)}/${index}`, { method: 'DELETE' });
+        }
+
         let progressInterval;
         
         async function checkLoadingStatus() {
             try {
-                const response = await fetch('/api/loading-status');
+                const response = await fetch(API_BASE + '/api/loading-status');
                 const status = await response.json();
                 
                 const progressSection = document.getElementById('progress-section');
@@ -1158,7 +3282,7 @@ fn generate_index_html() -> String {
             resultsDiv.innerHTML = '<p class="loading">🔍 Анализ...</p>';
             
             try {
-                const response = await fetch('/api/analyze', {
+                const response = await fetch(API_BASE + '/api/analyze', {
                     method: 'POST',
                     headers: { 'Content-Type': 'application/json' },
                     body: JSON.stringify({ code, filename: 'snippet.bsl' })
@@ -1193,8 +3317,10 @@ fn generate_index_html() -> String {
     </script>
 </body>
 </html>
-    "#.to_string()
+    "##
+    .replace("{{API_BASE}}", api_base)
 }
 
 // Добавляем warp dependency
-use warp::Filter;
+use warp::filters::BoxedFilter;
+use warp::{Filter, Reply};
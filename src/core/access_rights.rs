@@ -0,0 +1,297 @@
+//! Проверка строковых ссылок на права и роли
+//!
+//! `ПравоДоступа("Чтение", ...)` и `РольДоступна("Менеджер")` принимают имя
+//! строкой, поэтому опечатка в нём проявляется только ошибкой при
+//! выполнении. Имена прав сверяются со стандартными правами платформы и
+//! правами, встречающимися в ролях конфигурации; имена ролей (в том числе
+//! `Метаданные.Роли.Имя`) — с ролями из выгрузки.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::core::example_mining::strip_comment;
+use crate::data::loaders::roles::RoleIndex;
+
+/// Стандартные права доступа платформы (русское и английское имя)
+pub const STANDARD_RIGHTS: &[(&str, &str)] = &[
+    ("Администрирование", "Administration"),
+    ("АдминистрированиеДанных", "DataAdministration"),
+    ("ОбновлениеКонфигурацииБазыДанных", "UpdateDataBaseConfiguration"),
+    ("МонопольныйРежим", "ExclusiveMode"),
+    ("АктивныеПользователи", "ActiveUsers"),
+    ("ЖурналРегистрации", "EventLog"),
+    ("ТонкийКлиент", "ThinClient"),
+    ("ВебКлиент", "WebClient"),
+    ("МобильныйКлиент", "MobileClient"),
+    ("ТолстыйКлиент", "ThickClient"),
+    ("ВнешнееСоединение", "ExternalConnection"),
+    ("Automation", "Automation"),
+    ("РежимВсеФункции", "AllFunctionsMode"),
+    ("СохранениеДанныхПользователя", "SaveUserData"),
+    ("ИнтерактивноеОткрытиеВнешнихОбработок", "InteractiveOpenExtDataProcessors"),
+    ("ИнтерактивноеОткрытиеВнешнихОтчетов", "InteractiveOpenExtReports"),
+    ("Вывод", "Output"),
+    ("Чтение", "Read"),
+    ("Добавление", "Insert"),
+    ("Изменение", "Update"),
+    ("Удаление", "Delete"),
+    ("Просмотр", "View"),
+    ("ИнтерактивноеДобавление", "InteractiveInsert"),
+    ("Редактирование", "Edit"),
+    ("ИнтерактивноеУдаление", "InteractiveDelete"),
+    ("ИнтерактивнаяПометкаУдаления", "InteractiveSetDeletionMark"),
+    ("ИнтерактивноеСнятиеПометкиУдаления", "InteractiveClearDeletionMark"),
+    ("ИнтерактивноеУдалениеПомеченных", "InteractiveDeleteMarked"),
+    ("ИнтерактивноеУдалениеПредопределенныхДанных", "InteractiveDeletePredefinedData"),
+    (
+        "ИнтерактивнаяПометкаУдаленияПредопределенныхДанных",
+        "InteractiveSetDeletionMarkPredefinedData",
+    ),
+    (
+        "ИнтерактивноеСнятиеПометкиУдаленияПредопределенныхДанных",
+        "InteractiveClearDeletionMarkPredefinedData",
+    ),
+    (
+        "ИнтерактивноеУдалениеПомеченныхПредопределенныхДанных",
+        "InteractiveDeleteMarkedPredefinedData",
+    ),
+    ("ВводПоСтроке", "InputByString"),
+    ("Проведение", "Posting"),
+    ("ОтменаПроведения", "UndoPosting"),
+    ("ИнтерактивноеПроведение", "InteractivePosting"),
+    ("ИнтерактивноеПроведениеНеОперативное", "InteractivePostingRegular"),
+    ("ИнтерактивнаяОтменаПроведения", "InteractiveUndoPosting"),
+    ("ИнтерактивноеИзменениеПроведенных", "InteractiveChangeOfPosted"),
+    ("УправлениеИтогами", "TotalsControl"),
+    ("Использование", "Use"),
+    ("Получение", "Get"),
+    ("Установка", "Set"),
+    ("Старт", "Start"),
+    ("ИнтерактивныйСтарт", "InteractiveStart"),
+    ("ИнтерактивнаяАктивация", "InteractiveActivate"),
+    ("Выполнение", "Execute"),
+    ("ИнтерактивноеВыполнение", "InteractiveExecute"),
+    ("ЧтениеИсторииДанных", "ReadDataHistory"),
+    ("ИзменениеИсторииДанных", "UpdateDataHistory"),
+    ("ПросмотрИсторииДанных", "ViewDataHistory"),
+];
+
+/// Вид ссылки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessReferenceKind {
+    /// Имя права (`ПравоДоступа("Чтение", ...)`)
+    Right,
+    /// Имя роли (`РольДоступна("Менеджер")`, `Метаданные.Роли.Менеджер`)
+    Role,
+}
+
+/// Ссылка на право или роль в тексте модуля
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessReference {
+    pub kind: AccessReferenceKind,
+    /// Строка (с 0)
+    pub line: u32,
+    /// Позиция начала имени в символах
+    pub column: u32,
+    pub name: String,
+}
+
+/// Ссылка на несуществующее право или роль
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessIssue {
+    pub reference: AccessReference,
+    /// Похожее существующее имя
+    pub suggestion: Option<String>,
+}
+
+impl AccessIssue {
+    pub fn message(&self) -> String {
+        let what = match self.reference.kind {
+            AccessReferenceKind::Right => "Неизвестное право доступа",
+            AccessReferenceKind::Role => "Роль не найдена в конфигурации",
+        };
+        match &self.suggestion {
+            Some(suggestion) => format!(
+                "{} '{}': возможно, имелось в виду '{}'",
+                what, self.reference.name, suggestion
+            ),
+            None => format!("{} '{}'", what, self.reference.name),
+        }
+    }
+}
+
+fn reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r#"(?i)(?:^|[^\p{L}\p{N}_.])(?:(праводоступа|accessright)\s*\(\s*"([^"]*)"|(рольдоступна|isinrole)\s*\(\s*"([^"]*)"|(?:метаданные|metadata)\s*\.\s*(?:роли|roles)\s*\.\s*(?:(?:найти|find)\s*\(\s*"([^"]*)"|([\p{L}_][\p{L}\p{N}_]*)))"#,
+        )
+        .unwrap()
+    })
+}
+
+/// Ссылки на права и роли в тексте модуля (комментарии пропускаются)
+pub fn access_references(text: &str) -> Vec<AccessReference> {
+    let mut out = Vec::new();
+    for (idx, source) in text.lines().enumerate() {
+        let code = strip_comment(source);
+        for caps in reference_regex().captures_iter(code) {
+            let (kind, name) = match (caps.get(2), caps.get(4), caps.get(5), caps.get(6)) {
+                (Some(name), ..) => (AccessReferenceKind::Right, name),
+                (_, Some(name), ..) => (AccessReferenceKind::Role, name),
+                (_, _, Some(name), _) | (.., Some(name)) => (AccessReferenceKind::Role, name),
+                _ => continue,
+            };
+            // Вызов внутри строкового литерала — не код. Совпадение может
+            // начинаться с символа перед именем функции
+            let start = caps.get(0).map_or(0, |m| {
+                let before = m.as_str().chars().next().filter(|ch| !ch.is_alphabetic());
+                m.start() + before.map_or(0, char::len_utf8)
+            });
+            if name.as_str().trim().is_empty() || code[..start].matches('"').count() % 2 == 1 {
+                continue;
+            }
+            out.push(AccessReference {
+                kind,
+                line: idx as u32,
+                column: code[..name.start()].chars().count() as u32,
+                name: name.as_str().trim().to_string(),
+            });
+        }
+    }
+    out
+}
+
+/// Расстояние Левенштейна без учёта регистра
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Ближайшее имя, отличающееся не более чем на треть длины
+fn closest<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let limit = (name.chars().count() / 3).max(1);
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Ссылки на неизвестные права и роли. Роли проверяются, только если
+/// индекс ролей не пуст (без выгрузки их не с чем сравнить).
+pub fn check_access_references(text: &str, roles: &RoleIndex) -> Vec<AccessIssue> {
+    let granted = roles.right_names();
+    let mut out = Vec::new();
+    for reference in access_references(text) {
+        let name = reference.name.to_lowercase();
+        let suggestion = match reference.kind {
+            AccessReferenceKind::Right => {
+                let standard = STANDARD_RIGHTS
+                    .iter()
+                    .any(|(ru, en)| ru.to_lowercase() == name || en.to_lowercase() == name);
+                if standard || granted.iter().any(|right| right.to_lowercase() == name) {
+                    continue;
+                }
+                let candidates = STANDARD_RIGHTS.iter().flat_map(|(ru, en)| [*ru, *en]);
+                closest(&reference.name, candidates.chain(granted.iter().copied()))
+            }
+            AccessReferenceKind::Role => {
+                if roles.is_empty() || roles.find(&reference.name).is_some() {
+                    continue;
+                }
+                closest(
+                    &reference.name,
+                    roles.roles().iter().map(|role| role.name.as_str()),
+                )
+            }
+        };
+        out.push(AccessIssue {
+            reference,
+            suggestion,
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::loaders::roles::RoleInfo;
+
+    fn roles() -> RoleIndex {
+        RoleIndex::from_roles(
+            ["Менеджер", "ПолныеПрава"]
+                .into_iter()
+                .map(|name| RoleInfo {
+                    name: name.to_string(),
+                    objects: vec![],
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_references() {
+        let text = r#"Если ПравоДоступа("Чтение", Метаданные.Справочники.Товары) Тогда
+    Разрешено = РольДоступна("Менеджер") ИЛИ IsInRole(Metadata.Roles.ПолныеПрава);
+    Роль = Метаданные.Роли.Найти("Менеджер");
+    Текст = "РольДоступна(""Внутри"")"; // РольДоступна("Комментарий")
+КонецЕсли;"#;
+        let references = access_references(text);
+        let names: Vec<(AccessReferenceKind, &str)> = references
+            .iter()
+            .map(|r| (r.kind, r.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (AccessReferenceKind::Right, "Чтение"),
+                (AccessReferenceKind::Role, "Менеджер"),
+                (AccessReferenceKind::Role, "ПолныеПрава"),
+                (AccessReferenceKind::Role, "Менеджер"),
+            ]
+        );
+        assert_eq!((references[1].line, references[1].column), (1, 30));
+        assert!(check_access_references(text, &roles()).is_empty());
+    }
+
+    #[test]
+    fn test_typos_with_suggestions() {
+        let text = r#"А = ПравоДоступа("Чтенье", Метаданные.Документы.Заказ);
+Б = ПравоДоступа("Reed", Метаданные.Документы.Заказ);
+В = РольДоступна("Менеджр");
+Г = Метаданные.Роли.Бухгалтер;"#;
+        let issues = check_access_references(text, &roles());
+        let found: Vec<(&str, Option<&str>)> = issues
+            .iter()
+            .map(|i| (i.reference.name.as_str(), i.suggestion.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("Чтенье", Some("Чтение")),
+                ("Reed", Some("Read")),
+                ("Менеджр", Some("Менеджер")),
+                ("Бухгалтер", None),
+            ]
+        );
+        assert_eq!(
+            issues[2].message(),
+            "Роль не найдена в конфигурации 'Менеджр': возможно, имелось в виду 'Менеджер'"
+        );
+
+        // Без выгрузки роли не проверяются
+        assert_eq!(check_access_references(text, &RoleIndex::default()).len(), 2);
+    }
+}
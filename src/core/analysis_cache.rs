@@ -1,17 +1,21 @@
 //! Система кеширования результатов анализа типов
 //!
 //! Этот модуль предоставляет эффективное кеширование результатов
-//! межпроцедурного анализа и других дорогих операций.
+//! межпроцедурного анализа и других дорогих операций. Постоянный уровень
+//! кеша лежит в [`StorageBackend`]: в каталоге или, если кеш общий для
+//! сборочных агентов, в базе SQLite или бакете S3.
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 use crate::core::interprocedural::CallGraph;
 use crate::core::type_checker::{FunctionSignature, TypeContext};
+use crate::data::{FsStorage, StorageBackend, StorageLocation, WriteGuard};
 use crate::domain::types::TypeResolution;
 
 /// Ключ кеша на основе хеша содержимого файла
@@ -126,10 +130,13 @@ impl CallGraphSummary {
     }
 }
 
+/// Расширение записей кеша анализа
+const CACHE_EXTENSION: &str = ".cache";
+
 /// Менеджер кеширования анализа
 pub struct AnalysisCacheManager {
-    /// Путь к директории кеша
-    cache_dir: PathBuf,
+    /// Постоянное хранилище кеша
+    storage: Arc<dyn StorageBackend>,
     /// In-memory кеш для быстрого доступа
     memory_cache: HashMap<CacheKey, CachedInterproceduralResults>,
     /// Максимальный размер memory кеша
@@ -139,6 +146,8 @@ pub struct AnalysisCacheManager {
     analyzer_version: String,
     /// Статистика использования кеша
     stats: CacheStats,
+    /// Разрешение на запись (в режиме только для чтения кеш только в памяти)
+    write_guard: WriteGuard,
 }
 
 /// Статистика кеширования
@@ -165,20 +174,56 @@ impl CacheStats {
 impl AnalysisCacheManager {
     /// Создать новый менеджер кеширования
     pub fn new<P: AsRef<Path>>(cache_dir: P, analyzer_version: &str) -> Result<Self> {
-        let cache_dir = cache_dir.as_ref().to_path_buf();
+        Self::with_write_guard(cache_dir, analyzer_version, WriteGuard::default())
+    }
+
+    /// Создать менеджер, записи которого на диск проверяет `write_guard`
+    pub fn with_write_guard<P: AsRef<Path>>(
+        cache_dir: P,
+        analyzer_version: &str,
+        write_guard: WriteGuard,
+    ) -> Result<Self> {
+        let cache_dir = cache_dir.as_ref();
 
         // Создаем директорию кеша если не существует
-        if !cache_dir.exists() {
-            std::fs::create_dir_all(&cache_dir)?;
+        if !cache_dir.exists() && write_guard.allows("каталог кеша анализа", cache_dir) {
+            std::fs::create_dir_all(cache_dir)?;
         }
 
-        Ok(Self {
-            cache_dir,
+        Ok(Self::with_storage(
+            Arc::new(FsStorage::new(cache_dir)),
+            analyzer_version,
+            write_guard,
+        ))
+    }
+
+    /// Кеш по адресу из настроек: каталог, `sqlite://` или `s3://`
+    pub fn open(location: &str, analyzer_version: &str, write_guard: WriteGuard) -> Result<Self> {
+        match StorageLocation::parse(location)? {
+            StorageLocation::Directory(dir) => {
+                Self::with_write_guard(dir, analyzer_version, write_guard)
+            }
+            other => {
+                let storage = other.open(&write_guard)?;
+                Ok(Self::with_storage(storage, analyzer_version, write_guard))
+            }
+        }
+    }
+
+    /// Кеш в заданном хранилище
+    pub fn with_storage(
+        storage: Arc<dyn StorageBackend>,
+        analyzer_version: &str,
+        write_guard: WriteGuard,
+    ) -> Self {
+        Self {
+            storage,
             memory_cache: HashMap::new(),
             max_memory_entries: 100, // Ограничение memory кеша
             analyzer_version: analyzer_version.to_string(),
             stats: CacheStats::default(),
-        })
+            write_guard,
+        }
     }
 
     /// Получить результаты из кеша
@@ -223,8 +268,9 @@ impl AnalysisCacheManager {
         self.memory_cache.insert(key.clone(), results.clone());
 
         // Сохраняем на диск асинхронно
-        self.save_to_disk(&key, &results)?;
-        self.stats.disk_writes += 1;
+        if self.save_to_disk(&key, &results)? {
+            self.stats.disk_writes += 1;
+        }
 
         Ok(())
     }
@@ -240,10 +286,14 @@ impl AnalysisCacheManager {
     pub fn clear(&mut self) -> Result<()> {
         self.memory_cache.clear();
 
-        // Удаляем все файлы кеша
-        if self.cache_dir.exists() {
-            std::fs::remove_dir_all(&self.cache_dir)?;
-            std::fs::create_dir_all(&self.cache_dir)?;
+        // Удаляем все записи кеша
+        let mut keys = self.storage.list("")?;
+        keys.retain(|key| key.ends_with(CACHE_EXTENSION));
+        let (operation, location) = ("очистка кеша анализа", self.storage.location());
+        if !keys.is_empty() && self.write_guard.allows(operation, Path::new(&location)) {
+            for key in &keys {
+                self.storage.remove(key)?;
+            }
         }
 
         self.stats = CacheStats::default();
@@ -267,37 +317,49 @@ impl AnalysisCacheManager {
 
     /// Загрузить из диска
     fn load_from_disk(&self, key: &CacheKey) -> Result<CachedInterproceduralResults> {
-        let file_path = self.get_cache_file_path(key);
-        let data = std::fs::read(&file_path)?;
+        let cache_key = Self::cache_file_name(key);
+        let data = self
+            .storage
+            .read(&cache_key)?
+            .ok_or_else(|| anyhow::anyhow!("Нет записи кеша {}", cache_key))?;
         let cached: CachedInterproceduralResults = bincode::deserialize(&data)?;
         Ok(cached)
     }
 
-    /// Сохранить на диск
-    fn save_to_disk(&self, key: &CacheKey, results: &CachedInterproceduralResults) -> Result<()> {
-        let file_path = self.get_cache_file_path(key);
+    /// Сохранить на диск; `false`, если запись запрещена режимом только для чтения
+    fn save_to_disk(&self, key: &CacheKey, results: &CachedInterproceduralResults) -> Result<bool> {
+        let cache_key = Self::cache_file_name(key);
+        let path = self.storage.describe_key(&cache_key);
+        if !self.write_guard.allows("кеш анализа", Path::new(&path)) {
+            return Ok(false);
+        }
         let data = bincode::serialize(results)?;
-        std::fs::write(&file_path, data)?;
-        Ok(())
+        self.storage.write(&cache_key, &data)?;
+        Ok(true)
     }
 
     /// Удалить с диска
     fn remove_from_disk(&self, key: &CacheKey) -> Result<()> {
-        let file_path = self.get_cache_file_path(key);
-        if file_path.exists() {
-            std::fs::remove_file(&file_path)?;
+        let cache_key = Self::cache_file_name(key);
+        if self.storage.size(&cache_key)?.is_some()
+            && self.write_guard.allows(
+                "кеш анализа",
+                Path::new(&self.storage.describe_key(&cache_key)),
+            )
+        {
+            self.storage.remove(&cache_key)?;
         }
         Ok(())
     }
 
-    /// Получить путь к файлу кеша
-    fn get_cache_file_path(&self, key: &CacheKey) -> PathBuf {
-        let filename = format!(
-            "{}_{}.cache",
+    /// Имя записи кеша в хранилище
+    fn cache_file_name(key: &CacheKey) -> String {
+        format!(
+            "{}_{}{}",
             &key.content_hash[..16], // Первые 16 символов хеша
-            key.analyzer_version.replace('.', "_")
-        );
-        self.cache_dir.join(filename)
+            key.analyzer_version.replace('.', "_"),
+            CACHE_EXTENSION
+        )
     }
 
     /// Очистить устаревшие записи кеша
@@ -318,15 +380,15 @@ impl AnalysisCacheManager {
         }
 
         // Очищаем disk кеш
-        if let Ok(entries) = std::fs::read_dir(&self.cache_dir) {
-            for entry in entries.flatten() {
-                if let Ok(data) = std::fs::read(entry.path()) {
-                    if let Ok(cached) = bincode::deserialize::<CachedInterproceduralResults>(&data)
+        for key in self.storage.list("").unwrap_or_default() {
+            if let Ok(Some(data)) = self.storage.read(&key) {
+                if let Ok(cached) = bincode::deserialize::<CachedInterproceduralResults>(&data) {
+                    let path = self.storage.describe_key(&key);
+                    if !cached.is_valid()
+                        && self.write_guard.allows("кеш анализа", Path::new(&path))
+                        && self.storage.remove(&key).is_ok()
                     {
-                        if !cached.is_valid() {
-                            let _ = std::fs::remove_file(entry.path());
-                            removed_count += 1;
-                        }
+                        removed_count += 1;
                     }
                 }
             }
@@ -340,11 +402,9 @@ impl AnalysisCacheManager {
     pub fn get_disk_cache_size(&self) -> Result<u64> {
         let mut total_size = 0;
 
-        if let Ok(entries) = std::fs::read_dir(&self.cache_dir) {
-            for entry in entries.flatten() {
-                if let Ok(metadata) = entry.metadata() {
-                    total_size += metadata.len();
-                }
+        for key in self.storage.list("").unwrap_or_default() {
+            if let Ok(Some(size)) = self.storage.size(&key) {
+                total_size += size;
             }
         }
 
@@ -394,6 +454,7 @@ impl CachedInterproceduralAnalyzer {
                 functions: cached.function_signatures,
                 current_scope: crate::core::dependency_graph::Scope::Global,
                 scope_stack: vec![],
+                active_contexts: crate::parsing::bsl::ContextSet::ALL,
             };
 
             return Ok(context);
@@ -411,6 +472,7 @@ impl CachedInterproceduralAnalyzer {
             functions: HashMap::new(),
             current_scope: crate::core::dependency_graph::Scope::Global,
             scope_stack: vec![],
+            active_contexts: crate::parsing::bsl::ContextSet::ALL,
         };
 
         // Обновляем контекст
@@ -628,6 +690,74 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_read_only_cache_stays_in_memory() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cache_dir = temp_dir.path().join("cache");
+        let guard = WriteGuard::new(true);
+        let mut manager =
+            AnalysisCacheManager::with_write_guard(&cache_dir, "test-1.0.0", guard.clone())?;
+
+        let key = CacheKey::from_content("test content", "test-1.0.0");
+        let results = CachedInterproceduralResults {
+            function_results: HashMap::new(),
+            function_signatures: HashMap::new(),
+            call_graph_summary: CallGraphSummary {
+                function_call_counts: HashMap::new(),
+                topological_order: vec![],
+                recursive_functions: vec![],
+            },
+            created_at: SystemTime::now(),
+            ttl: Duration::from_secs(3600),
+        };
+        manager.put(key.clone(), results)?;
+
+        assert!(manager.get(&key).is_some());
+        assert_eq!(manager.get_stats().disk_writes, 0);
+        assert!(!cache_dir.exists());
+        assert_eq!(guard.skipped_writes().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_in_shared_storage() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let location = temp_dir.path().join("shared");
+        let storage: Arc<dyn StorageBackend> = Arc::new(FsStorage::new(&location));
+        let mut writer = AnalysisCacheManager::with_storage(
+            storage.clone(),
+            "test-1.0.0",
+            WriteGuard::default(),
+        );
+
+        let key = CacheKey::from_content("test content", "test-1.0.0");
+        let results = CachedInterproceduralResults {
+            function_results: HashMap::new(),
+            function_signatures: HashMap::new(),
+            call_graph_summary: CallGraphSummary {
+                function_call_counts: HashMap::new(),
+                topological_order: vec!["TestFunc".to_string()],
+                recursive_functions: vec![],
+            },
+            created_at: SystemTime::now(),
+            ttl: Duration::from_secs(3600),
+        };
+        writer.put(key.clone(), results)?;
+        assert!(writer.get_disk_cache_size()? > 0);
+
+        // Второй агент видит запись первого через общее хранилище
+        let location = location.to_string_lossy().to_string();
+        let mut reader =
+            AnalysisCacheManager::open(&location, "test-1.0.0", WriteGuard::default())?;
+        assert!(reader.get(&key).is_some());
+        assert_eq!(reader.get_stats().disk_reads, 1);
+
+        reader.clear()?;
+        assert_eq!(writer.get_disk_cache_size()?, 0);
+        assert!(storage.list("")?.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn test_type_lru_cache() {
         let mut cache = TypeLRUCache::new(2);
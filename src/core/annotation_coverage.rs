@@ -0,0 +1,234 @@
+//! Покрытие явными аннотациями типов
+//!
+//! Покрытие выводом показывает, сколько выражений удалось типизировать
+//! автоматически; командам, внедряющим постепенную типизацию, важнее знать,
+//! сколько процедур уже описано явно. Аннотацией считается стандартный
+//! комментарий перед методом: секции `Параметры:` (`Имя - Тип - описание`)
+//! и `Возвращаемое значение:` для функций.
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use crate::core::module_structure::method_header;
+
+/// Степень аннотированности метода
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationLevel {
+    /// Типы всех параметров и возвращаемого значения описаны
+    Full,
+    /// Описана только часть типов
+    Partial,
+    /// Типы только выводятся
+    None,
+}
+
+/// Метрика покрытия аннотациями
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AnnotationCoverage {
+    /// Всего процедур и функций
+    pub total_methods: usize,
+    /// Полностью аннотированные
+    pub fully_annotated: usize,
+    /// Частично аннотированные
+    pub partially_annotated: usize,
+    /// Процент полностью аннотированных методов
+    pub annotation_percentage: f32,
+}
+
+impl AnnotationCoverage {
+    /// Методы без аннотаций (типы только выводятся)
+    pub fn inferred_only(&self) -> usize {
+        self.total_methods - self.fully_annotated - self.partially_annotated
+    }
+
+    /// Добавить результат одного метода
+    pub fn record(&mut self, level: AnnotationLevel) {
+        self.total_methods += 1;
+        match level {
+            AnnotationLevel::Full => self.fully_annotated += 1,
+            AnnotationLevel::Partial => self.partially_annotated += 1,
+            AnnotationLevel::None => {}
+        }
+        self.update_percentage();
+    }
+
+    /// Объединить с метрикой другого модуля
+    pub fn merge(&mut self, other: &AnnotationCoverage) {
+        self.total_methods += other.total_methods;
+        self.fully_annotated += other.fully_annotated;
+        self.partially_annotated += other.partially_annotated;
+        self.update_percentage();
+    }
+
+    fn update_percentage(&mut self) {
+        self.annotation_percentage = if self.total_methods > 0 {
+            self.fully_annotated as f32 / self.total_methods as f32 * 100.0
+        } else {
+            0.0
+        };
+    }
+}
+
+fn parameter_line_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // `Имя - Тип[, Тип] - описание` (допускаются длинное и короткое тире)
+    RE.get_or_init(|| {
+        Regex::new(r"^\s*([\p{L}_][\p{L}\p{N}_]*)\s+[-–—]\s+([^-–—]*\S)").unwrap()
+    })
+}
+
+/// Имена параметров из заголовка метода (без `Знач` и значений по умолчанию)
+fn parameter_names(params: &str) -> Vec<String> {
+    params
+        .split(',')
+        .filter_map(|param| {
+            let name = param.split('=').next()?.trim();
+            let name = name
+                .strip_prefix("Знач ")
+                .or_else(|| name.strip_prefix("Val "))
+                .unwrap_or(name)
+                .trim();
+            (!name.is_empty()).then(|| name.to_lowercase())
+        })
+        .collect()
+}
+
+/// Разобрать комментарий перед методом: типизированные параметры и наличие
+/// типа возвращаемого значения
+fn parse_doc_comment(lines: &[&str]) -> (HashSet<String>, bool) {
+    #[derive(PartialEq)]
+    enum Section {
+        Description,
+        Parameters,
+        Returns,
+    }
+
+    let mut typed_params = HashSet::new();
+    let mut has_return_type = false;
+    let mut section = Section::Description;
+
+    for line in lines {
+        let text = line.trim_start().trim_start_matches('/').trim();
+        let lower = text.to_lowercase();
+        if lower.starts_with("параметры:") || lower.starts_with("parameters:") {
+            section = Section::Parameters;
+            continue;
+        }
+        if lower.starts_with("возвращаемое значение:") || lower.starts_with("returns:") {
+            section = Section::Returns;
+            // Тип может быть указан в той же строке
+            let rest = text.split_once(':').map(|(_, r)| r.trim()).unwrap_or("");
+            if !rest.is_empty() {
+                has_return_type = true;
+            }
+            continue;
+        }
+        if lower.starts_with("пример:") || lower.starts_with("example:") {
+            section = Section::Description;
+            continue;
+        }
+
+        match section {
+            Section::Parameters => {
+                if let Some(caps) = parameter_line_regex().captures(text) {
+                    typed_params.insert(caps[1].to_lowercase());
+                }
+            }
+            Section::Returns if !text.is_empty() => has_return_type = true,
+            _ => {}
+        }
+    }
+
+    (typed_params, has_return_type)
+}
+
+/// Подсчитать покрытие аннотациями для текста модуля
+pub fn analyze_module(text: &str) -> AnnotationCoverage {
+    let mut coverage = AnnotationCoverage::default();
+    let lines: Vec<&str> = text.lines().collect();
+
+    for (idx, line) in lines.iter().enumerate() {
+        let Some(header) = method_header(line) else {
+            continue;
+        };
+        let is_function = header.is_function;
+        let params = parameter_names(header.parameters);
+
+        // Комментарий — непрерывный блок `//` над заголовком (директивы `&НаСервере` пропускаются)
+        let mut start = idx;
+        while start > 0 && lines[start - 1].trim_start().starts_with('&') {
+            start -= 1;
+        }
+        let mut comment_start = start;
+        while comment_start > 0 && lines[comment_start - 1].trim_start().starts_with("//") {
+            comment_start -= 1;
+        }
+        let (typed_params, has_return_type) = parse_doc_comment(&lines[comment_start..start]);
+
+        let typed = params.iter().filter(|p| typed_params.contains(*p)).count()
+            + usize::from(is_function && has_return_type);
+        let required = params.len() + usize::from(is_function);
+
+        let level = if typed == required && (required > 0 || comment_start < start) {
+            AnnotationLevel::Full
+        } else if typed > 0 {
+            AnnotationLevel::Partial
+        } else {
+            AnnotationLevel::None
+        };
+        coverage.record(level);
+    }
+
+    coverage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = r#"
+// Рассчитывает сумму документа.
+//
+// Параметры:
+//   Документ - ДокументСсылка.Заказ - документ для расчёта
+//   СНДС     - Булево - учитывать НДС
+//
+// Возвращаемое значение:
+//   Число - сумма документа
+//
+Функция СуммаДокумента(Документ, СНДС = Истина) Экспорт
+КонецФункции
+
+// Параметры:
+//   Список - Массив - элементы
+&НаСервере
+Процедура Заполнить(Список, Знач Режим)
+КонецПроцедуры
+
+Функция БезОписания(Параметр)
+КонецФункции
+"#;
+
+    #[test]
+    fn test_annotation_levels() {
+        let coverage = analyze_module(MODULE);
+        assert_eq!(coverage.total_methods, 3);
+        assert_eq!(coverage.fully_annotated, 1);
+        assert_eq!(coverage.partially_annotated, 1);
+        assert_eq!(coverage.inferred_only(), 1);
+        assert!((coverage.annotation_percentage - 33.33).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_merge_coverage() {
+        let mut total = AnnotationCoverage::default();
+        total.merge(&analyze_module(MODULE));
+        total.merge(&analyze_module("Процедура Пусто()\nКонецПроцедуры\n"));
+        assert_eq!(total.total_methods, 4);
+        assert_eq!(total.fully_annotated, 1);
+        assert_eq!(total.annotation_percentage, 25.0);
+    }
+}
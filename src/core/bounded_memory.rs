@@ -0,0 +1,330 @@
+//! Анализ больших конфигураций в ограниченной памяти
+//!
+//! Анализ проекта читал тексты всех модулей разом (для таблицы сигнатур) и
+//! копил диагностики до конца прохода: на конфигурациях класса ERP (миллионы
+//! строк) это гигабайты. Теперь модули обрабатываются волнами
+//! ([`plan_waves`]): тексты волны читаются, индексируются или анализируются
+//! и освобождаются до чтения следующей. Диагностики, не уместившиеся в
+//! бюджет, сбрасываются на диск в файл JSON Lines ([`SpillFile`]) и читаются
+//! обратно потоком. Таблица сигнатур после вывода возвращаемых типов
+//! освобождает исходные выражения возвратов.
+//!
+//! С пределом памяти (`--max-memory 6G`) размер волны выводится из предела,
+//! а после каждой волны [`MemoryCeiling`] сверяет с ним резидентную память
+//! процесса: при превышении анализ останавливается с понятной ошибкой, а не
+//! завершается системой из-за нехватки памяти.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::data::WriteGuard;
+use crate::error::{Error, Result, ResultExt};
+
+/// Объём текстов волны без предела памяти
+pub const DEFAULT_WAVE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Какая часть предела памяти отводится текстам одной волны: разобранные
+/// структуры модуля в несколько раз больше его текста
+const WAVE_SHARE: usize = 16;
+
+static SPILL_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Настройки анализа в ограниченной памяти
+#[derive(Debug, Clone, Default)]
+pub struct BoundedAnalysisConfig {
+    /// Предел резидентной памяти процесса в байтах
+    pub memory_limit: Option<usize>,
+    /// Объём текстов модулей в одной волне; по умолчанию — из предела памяти
+    pub wave_bytes: Option<usize>,
+    /// Каталог для сброса диагностик на диск; `None` — диагностики остаются
+    /// в памяти
+    pub spill_dir: Option<PathBuf>,
+    /// Разрешение на запись файлов сброса (`--read-only` оставляет
+    /// диагностики в памяти)
+    pub write_guard: WriteGuard,
+}
+
+impl BoundedAnalysisConfig {
+    /// Задан ли хоть один предел: без них анализ идёт как обычно, а
+    /// промежуточные индексы остаются для последующих правок
+    pub fn is_bounded(&self) -> bool {
+        self.memory_limit.is_some() || self.wave_bytes.is_some() || self.spill_dir.is_some()
+    }
+
+    /// Объём текстов модулей в одной волне; столько же диагностик держится
+    /// в памяти до сброса на диск
+    pub fn wave_bytes(&self) -> usize {
+        self.wave_bytes
+            .or(self.memory_limit.map(|limit| limit / WAVE_SHARE))
+            .unwrap_or(DEFAULT_WAVE_BYTES)
+            .max(1)
+    }
+}
+
+/// Разобрать размер: `6G`, `512M`, `64K`, `1024` (байты); суффиксы
+/// `GB`/`MB`/`KB` и `Г`/`М`/`К` тоже понимаются
+pub fn parse_memory_size(text: &str) -> Result<usize> {
+    let text = text.trim();
+    let digits = text
+        .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(digits);
+    let number: f64 = number
+        .parse()
+        .config_err(format!("Некорректный размер: {:?}", text))?;
+    let unit = unit.trim().to_uppercase();
+    let multiplier: u64 = match unit.trim_end_matches(['B', 'Б']) {
+        "" => 1,
+        "K" | "К" => 1 << 10,
+        "M" | "М" => 1 << 20,
+        "G" | "Г" => 1 << 30,
+        "T" | "Т" => 1 << 40,
+        _ => {
+            return Err(Error::config(format!(
+                "Неизвестная единица размера {:?}: ожидается K, M или G",
+                unit
+            )))
+        }
+    };
+    let bytes = number * multiplier as f64;
+    if bytes < 1.0 {
+        return Err(Error::config(format!(
+            "Размер должен быть положительным: {:?}",
+            text
+        )));
+    }
+    Ok(bytes as usize)
+}
+
+/// Размер в мегабайтах для сообщений
+pub fn format_megabytes(bytes: usize) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// Разбить файлы размеров `sizes` на волны подряд идущих файлов объёмом не
+/// больше `wave_bytes`; файл больше волны обрабатывается отдельной волной
+pub fn plan_waves(sizes: &[u64], wave_bytes: usize) -> Vec<Range<usize>> {
+    let mut waves = Vec::new();
+    let mut start = 0;
+    let mut bytes = 0u64;
+    for (index, &size) in sizes.iter().enumerate() {
+        if index > start && bytes + size > wave_bytes as u64 {
+            waves.push(start..index);
+            start = index;
+            bytes = 0;
+        }
+        bytes += size;
+    }
+    if start < sizes.len() {
+        waves.push(start..sizes.len());
+    }
+    waves
+}
+
+/// Резидентная память процесса (`VmRSS`); `None`, где её не узнать
+pub fn resident_memory_bytes() -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: usize = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Жёсткий предел памяти анализа
+#[derive(Debug, Clone)]
+pub struct MemoryCeiling {
+    limit: Option<usize>,
+    peak: Option<usize>,
+}
+
+impl MemoryCeiling {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self { limit, peak: None }
+    }
+
+    /// Сверить резидентную память с пределом после этапа `stage`
+    pub fn check(&mut self, stage: &str) -> Result<()> {
+        let Some(resident) = resident_memory_bytes() else {
+            return Ok(());
+        };
+        self.peak = Some(self.peak.unwrap_or(0).max(resident));
+        match self.limit {
+            Some(limit) if resident > limit => Err(Error::config(format!(
+                "Превышен предел памяти {} после {}: занято {}; уменьшите --wave-size или увеличьте --max-memory",
+                format_megabytes(limit),
+                stage,
+                format_megabytes(resident)
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Наибольшая замеченная резидентная память
+    pub fn peak_bytes(&self) -> Option<usize> {
+        self.peak
+    }
+}
+
+/// Записи, сброшенные на диск: файл JSON Lines, удаляется вместе со значением
+#[derive(Debug)]
+pub struct SpillFile<T> {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    len: usize,
+    _records: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize + DeserializeOwned> SpillFile<T> {
+    /// Создать файл `<prefix>-<pid>-<n>.jsonl` в каталоге `dir`; `None` —
+    /// запись запрещена режимом только для чтения
+    pub fn create(dir: &Path, prefix: &str, write_guard: &WriteGuard) -> Result<Option<Self>> {
+        if !write_guard.allows("сброс на диск", dir) {
+            return Ok(None);
+        }
+        std::fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        let path = dir.join(format!(
+            "{}-{}-{}.jsonl",
+            prefix,
+            std::process::id(),
+            SPILL_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = File::create(&path).map_err(|e| Error::io(&path, e))?;
+        Ok(Some(Self {
+            path,
+            writer: Some(BufWriter::new(file)),
+            len: 0,
+            _records: PhantomData,
+        }))
+    }
+
+    /// Дописать запись
+    pub fn push(&mut self, record: &T) -> Result<()> {
+        let writer = self.writer.as_mut().ok_or_else(|| {
+            Error::data_source(format!("Файл сброса {} уже закрыт", self.path.display()))
+        })?;
+        serde_json::to_writer(&mut *writer, record).map_err(|e| Error::io(&self.path, e.into()))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|e| Error::io(&self.path, e))?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Закончить запись: после этого файл можно читать
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush().map_err(|e| Error::io(&self.path, e))?;
+        }
+        Ok(())
+    }
+
+    /// Прочитать записи потоком (после [`Self::finish`])
+    pub fn read(&self) -> Result<impl Iterator<Item = Result<T>>> {
+        if self.writer.is_some() {
+            return Err(Error::data_source(format!(
+                "Файл сброса {} ещё записывается",
+                self.path.display()
+            )));
+        }
+        let file = File::open(&self.path).map_err(|e| Error::io(&self.path, e))?;
+        let path = self.path.clone();
+        Ok(BufReader::new(file).lines().map(move |line| {
+            let line = line.map_err(|e| Error::io(&path, e))?;
+            Ok(serde_json::from_str(&line)?)
+        }))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl<T> Drop for SpillFile<T> {
+    fn drop(&mut self) {
+        self.writer.take();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_memory() {
+        assert_eq!(parse_memory_size("6G").unwrap(), 6 << 30);
+        assert_eq!(parse_memory_size("512mb").unwrap(), 512 << 20);
+        assert_eq!(parse_memory_size("1.5 ГБ").unwrap(), 3 << 29);
+        assert_eq!(parse_memory_size("1024").unwrap(), 1024);
+        assert!(parse_memory_size("много").is_err());
+        assert!(parse_memory_size("8X").is_err());
+        assert!(parse_memory_size("0").is_err());
+
+        let config = BoundedAnalysisConfig {
+            memory_limit: Some(1 << 30),
+            ..Default::default()
+        };
+        assert_eq!(config.wave_bytes(), 64 << 20);
+        assert!(config.is_bounded());
+        assert!(!BoundedAnalysisConfig::default().is_bounded());
+        assert_eq!(
+            BoundedAnalysisConfig::default().wave_bytes(),
+            DEFAULT_WAVE_BYTES
+        );
+
+        assert_eq!(
+            plan_waves(&[40, 30, 50, 200, 10], 100),
+            [0..2, 2..3, 3..4, 4..5]
+        );
+        assert_eq!(plan_waves(&[1, 2, 3], usize::MAX), [0..3]);
+        assert!(plan_waves(&[], 100).is_empty());
+
+        if resident_memory_bytes().is_some() {
+            let mut ceiling = MemoryCeiling::new(Some(1024));
+            let error = ceiling.check("теста").unwrap_err();
+            assert!(error.to_string().contains("Превышен предел памяти"));
+            assert!(ceiling.peak_bytes().unwrap() > 1024);
+            assert!(MemoryCeiling::new(None).check("теста").is_ok());
+        }
+    }
+
+    #[test]
+    fn test_spill_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let read_only = WriteGuard::new(true);
+        let spill = SpillFile::<u32>::create(dir.path(), "test", &read_only).unwrap();
+        assert!(spill.is_none());
+        assert_eq!(read_only.skipped_writes().len(), 1);
+
+        let mut spill: SpillFile<(String, u32)> =
+            SpillFile::create(dir.path(), "test", &WriteGuard::default())
+                .unwrap()
+                .unwrap();
+        spill.push(&("Модуль.bsl".to_string(), 1)).unwrap();
+        spill.push(&("Модуль.bsl".to_string(), 2)).unwrap();
+        assert!(spill.read().is_err());
+        spill.finish().unwrap();
+
+        let records: Vec<(String, u32)> = spill.read().unwrap().map(Result::unwrap).collect();
+        assert_eq!(records.len(), spill.len());
+        assert_eq!(records[1], ("Модуль.bsl".to_string(), 2));
+
+        let path = spill.path().to_path_buf();
+        assert!(path.exists());
+        drop(spill);
+        assert!(!path.exists());
+    }
+}
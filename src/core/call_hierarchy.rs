@@ -0,0 +1,331 @@
+//! Иерархия вызовов процедур и функций проекта (`callHierarchy/*`)
+//!
+//! Граф строится по тексту модулей: методы — по заголовкам и
+//! `КонецПроцедуры`/`КонецФункции`, вызовы — по идентификаторам перед `(`
+//! внутри методов (комментарии и строковые литералы пропускаются, как при
+//! переименовании). Вызов без квалификатора ведёт к методу того же модуля,
+//! `Модуль.Метод(` — к экспортному методу общего модуля. Конструкторы
+//! (`Новый Массив(`), вызовы методов объектов и функций платформы в граф
+//! не попадают: для них нет объявления в проекте.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::core::example_mining::strip_comment;
+use crate::core::extract_procedure::KEYWORDS;
+use crate::core::module_structure::method_header_regex;
+use crate::core::rename::{code_chars, declared_method, method_ranges, same_name, sites};
+
+/// Процедура или функция в графе вызовов
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CallableMethod {
+    pub name: String,
+    pub is_function: bool,
+    pub exported: bool,
+    /// Общий модуль, в котором объявлен метод
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    pub file: String,
+    /// Строка заголовка и столбец имени в нём (с 0, в символах)
+    pub line: u32,
+    pub column: u32,
+    /// Последняя строка метода
+    pub end_line: u32,
+}
+
+impl CallableMethod {
+    /// Имя для вызова из других модулей: `Модуль.Метод` или `Метод`
+    pub fn qualified_name(&self) -> String {
+        match &self.module {
+            Some(module) => format!("{}.{}", module, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Тот же метод: файл и строка заголовка
+    fn is(&self, other: &CallableMethod) -> bool {
+        self.file == other.file && self.line == other.line
+    }
+
+    fn name_contains(&self, line: u32, column: u32) -> bool {
+        let end = self.column + self.name.chars().count() as u32;
+        self.line == line && (self.column..=end).contains(&column)
+    }
+}
+
+/// Имя вызываемого метода в месте вызова (с 0, в символах)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CallRange {
+    pub line: u32,
+    pub column: u32,
+    pub length: u32,
+}
+
+/// Метод и места вызовов, связывающих его с запрошенным методом
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MethodCalls {
+    pub method: CallableMethod,
+    pub ranges: Vec<CallRange>,
+}
+
+#[derive(Debug, Clone)]
+struct CallSite {
+    /// Общий модуль в `Модуль.Метод(`
+    qualifier: Option<String>,
+    name: String,
+    range: CallRange,
+}
+
+/// Граф вызовов процедур и функций проекта
+#[derive(Debug, Clone, Default)]
+pub struct ProjectCallGraph {
+    /// Методы модуля и вызовы в их телах по пути файла
+    modules: HashMap<String, Vec<(CallableMethod, Vec<CallSite>)>>,
+}
+
+/// Идентификатор стоит после `Новый`/`New` (вызов конструктора)
+pub(crate) fn after_new(chars: &[char], start: usize) -> bool {
+    let before: String = chars[..start].iter().collect();
+    let word = before
+        .trim_end()
+        .rsplit(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    word == "новый" || word == "new"
+}
+
+impl ProjectCallGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Число методов в графе
+    pub fn len(&self) -> usize {
+        self.modules.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.values().all(Vec::is_empty)
+    }
+
+    /// Добавить (или заменить) модуль; `module` — имя общего модуля
+    pub fn add_module(&mut self, file_path: &str, module: Option<&str>, text: &str) {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut methods = Vec::new();
+        for range in method_ranges(text) {
+            let header = lines[*range.start() as usize];
+            let Some(caps) = method_header_regex().captures(strip_comment(header)) else {
+                continue;
+            };
+            let (Some(name), Some((_, exported))) = (caps.get(2), declared_method(header)) else {
+                continue;
+            };
+            let keyword = caps[1].to_lowercase();
+            let method = CallableMethod {
+                name: name.as_str().to_string(),
+                is_function: keyword == "функция" || keyword == "function",
+                exported,
+                module: module.map(str::to_string),
+                file: file_path.to_string(),
+                line: *range.start(),
+                column: header[..name.start()].chars().count() as u32,
+                end_line: *range.end(),
+            };
+
+            let mut calls = Vec::new();
+            for idx in range {
+                let Some(line) = lines.get(idx as usize) else {
+                    break;
+                };
+                let chars = code_chars(line);
+                for site in sites(&chars) {
+                    let column = site.start as u32;
+                    let skip = !site.call
+                        || KEYWORDS.contains(&site.name.to_lowercase().as_str())
+                        || (idx == method.line && column == method.column)
+                        || after_new(&chars, site.start);
+                    let qualifier = match site.qualifier {
+                        // Метод результата выражения: `Получить().Метод(`
+                        Some(qualifier) if qualifier.is_empty() => continue,
+                        qualifier => qualifier,
+                    };
+                    if skip {
+                        continue;
+                    }
+                    calls.push(CallSite {
+                        qualifier,
+                        range: CallRange {
+                            line: idx,
+                            column,
+                            length: site.name.chars().count() as u32,
+                        },
+                        name: site.name,
+                    });
+                }
+            }
+            methods.push((method, calls));
+        }
+        self.modules.insert(file_path.to_string(), methods);
+    }
+
+    /// Убрать модуль (файл удалён)
+    pub fn remove_module(&mut self, file_path: &str) {
+        self.modules.remove(file_path);
+    }
+
+    /// Метод, объявленный в позиции (имя в заголовке) или вызванный в ней
+    pub fn method_at(&self, file_path: &str, line: u32, column: u32) -> Option<&CallableMethod> {
+        let methods = self.modules.get(file_path)?;
+        if let Some((method, _)) = methods
+            .iter()
+            .find(|(method, _)| method.name_contains(line, column))
+        {
+            return Some(method);
+        }
+        let call = methods.iter().flat_map(|(_, calls)| calls).find(|call| {
+            call.range.line == line
+                && (call.range.column..=call.range.column + call.range.length).contains(&column)
+        })?;
+        self.resolve(file_path, call)
+    }
+
+    /// Объявление вызываемого метода
+    fn resolve(&self, file_path: &str, call: &CallSite) -> Option<&CallableMethod> {
+        match &call.qualifier {
+            None => self
+                .modules
+                .get(file_path)?
+                .iter()
+                .map(|(method, _)| method)
+                .find(|method| same_name(&method.name, &call.name)),
+            Some(qualifier) => self
+                .modules
+                .values()
+                .flatten()
+                .map(|(method, _)| method)
+                .find(|method| {
+                    method.exported
+                        && method
+                            .module
+                            .as_deref()
+                            .is_some_and(|module| same_name(module, qualifier))
+                        && same_name(&method.name, &call.name)
+                }),
+        }
+    }
+
+    /// Методы, вызывающие `target`, с местами вызовов
+    pub fn incoming_calls(&self, target: &CallableMethod) -> Vec<MethodCalls> {
+        let mut found = Vec::new();
+        for (file_path, methods) in &self.modules {
+            for (caller, calls) in methods {
+                let ranges: Vec<CallRange> = calls
+                    .iter()
+                    .filter(|call| same_name(&call.name, &target.name))
+                    .filter(|call| {
+                        self.resolve(file_path, call)
+                            .is_some_and(|callee| callee.is(target))
+                    })
+                    .map(|call| call.range)
+                    .collect();
+                if !ranges.is_empty() {
+                    found.push(MethodCalls {
+                        method: caller.clone(),
+                        ranges,
+                    });
+                }
+            }
+        }
+        found.sort_by(|a, b| (&a.method.file, a.method.line).cmp(&(&b.method.file, b.method.line)));
+        found
+    }
+
+    /// Методы, которые вызывает `source`, в порядке первого вызова
+    pub fn outgoing_calls(&self, source: &CallableMethod) -> Vec<MethodCalls> {
+        let calls = self
+            .modules
+            .get(&source.file)
+            .and_then(|methods| methods.iter().find(|(method, _)| method.is(source)))
+            .map(|(_, calls)| calls.as_slice())
+            .unwrap_or_default();
+        let mut found: Vec<MethodCalls> = Vec::new();
+        for call in calls {
+            let Some(callee) = self.resolve(&source.file, call) else {
+                continue;
+            };
+            match found.iter_mut().find(|entry| entry.method.is(callee)) {
+                Some(entry) => entry.ranges.push(call.range),
+                None => found.push(MethodCalls {
+                    method: callee.clone(),
+                    ranges: vec![call.range],
+                }),
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDERS: &str = "Функция ПроверитьЗаказ(Заказ) Экспорт
+    Возврат Проверить(Заказ) И Проверить(Заказ.Клиент);
+КонецФункции
+
+Функция Проверить(Значение)
+    Список = Новый Массив();
+    Возврат ЗначениеЗаполнено(Значение);
+КонецФункции
+";
+
+    const DOCUMENT: &str = "Процедура ПередЗаписью(Отказ)
+    // Заказы.ПроверитьЗаказ(ЭтотОбъект);
+    Отказ = Не Заказы.ПроверитьЗаказ(ЭтотОбъект);
+    Сообщить(\"Заказы.ПроверитьЗаказ()\");
+КонецПроцедуры
+";
+
+    fn graph() -> ProjectCallGraph {
+        let mut graph = ProjectCallGraph::new();
+        graph.add_module("CommonModules/Заказы/Ext/Module.bsl", Some("Заказы"), ORDERS);
+        graph.add_module("Documents/Заказ/Ext/ObjectModule.bsl", None, DOCUMENT);
+        graph
+    }
+
+    #[test]
+    fn test_incoming_and_outgoing_calls() {
+        let graph = graph();
+        assert_eq!(graph.len(), 3);
+
+        // Курсор на вызове `Заказы.ПроверитьЗаказ` в модуле документа
+        let target = graph
+            .method_at("Documents/Заказ/Ext/ObjectModule.bsl", 2, 24)
+            .unwrap()
+            .clone();
+        assert_eq!(target.qualified_name(), "Заказы.ПроверитьЗаказ");
+        assert_eq!((target.line, target.column), (0, 8));
+
+        let incoming = graph.incoming_calls(&target);
+        assert_eq!(incoming.len(), 1);
+        assert_eq!(incoming[0].method.name, "ПередЗаписью");
+        assert_eq!(
+            incoming[0].ranges,
+            [CallRange {
+                line: 2,
+                column: 22,
+                length: 14
+            }]
+        );
+
+        // Два вызова локальной функции; конструктор и функции платформы пропущены
+        let outgoing = graph.outgoing_calls(&target);
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing[0].method.name, "Проверить");
+        assert_eq!(outgoing[0].ranges.len(), 2);
+        let checker = &outgoing[0].method;
+        assert!(graph.outgoing_calls(checker).is_empty());
+        assert_eq!(graph.incoming_calls(checker)[0].method.name, "ПроверитьЗаказ");
+    }
+}
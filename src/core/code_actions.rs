@@ -497,13 +497,9 @@ impl RefactoringProvider {
         type_context: &TypeContext,
         _program: &Program,
     ) -> Vec<CodeActionOrCommand> {
+        // Извлечение процедуры требует текста документа: см. `Self::extract_procedure`
         let mut actions = Vec::new();
 
-        // Предложение извлечения функции
-        if let Some(action) = Self::create_extract_function_action(uri, range) {
-            actions.push(action);
-        }
-
         // Предложение inline переменной
         if let Some(action) = Self::create_inline_variable_action(uri, range, type_context) {
             actions.push(action);
@@ -517,47 +513,15 @@ impl RefactoringProvider {
         actions
     }
 
-    /// Создать действие извлечения функции
-    fn create_extract_function_action(uri: &str, range: Range) -> Option<CodeActionOrCommand> {
-        let edit = WorkspaceEdit {
-            changes: Some({
-                let mut changes = HashMap::new();
-                if let Ok(parsed_uri) = Url::parse(uri) {
-                    changes.insert(
-                        parsed_uri,
-                        vec![
-                            // Заменяем выделенный код на вызов функции
-                            TextEdit {
-                                range,
-                                new_text: "НоваяФункция();".to_string(),
-                            },
-                            // Добавляем объявление функции в конец файла
-                            TextEdit {
-                                range: Range {
-                                    start: Position { line: u32::MAX, character: 0 },
-                                    end: Position { line: u32::MAX, character: 0 },
-                                },
-                                new_text: "\n\nФункция НоваяФункция()\n    // TODO: Реализовать извлеченную логику\nКонецФункции".to_string(),
-                            },
-                        ]
-                    );
-                }
-                changes
-            }),
-            document_changes: None,
-            change_annotations: None,
-        };
-
-        Some(CodeActionOrCommand::CodeAction(CodeAction {
-            title: "Извлечь в функцию".to_string(),
-            kind: Some(CodeActionKind::REFACTOR_EXTRACT),
-            diagnostics: None,
-            edit: Some(edit),
-            command: None,
-            is_preferred: Some(false),
-            disabled: None,
-            data: None,
-        }))
+    /// Извлечь выделенные строки в процедуру с типизированными параметрами
+    pub fn extract_procedure(
+        uri: &str,
+        text: &str,
+        range: Range,
+        type_context: Option<&TypeContext>,
+    ) -> Option<CodeActionOrCommand> {
+        let uri = Url::parse(uri).ok()?;
+        crate::core::extract_procedure::extract_procedure_action(&uri, text, range, type_context)
     }
 
     /// Создать действие inline переменной
@@ -703,6 +667,7 @@ mod tests {
             functions: HashMap::new(),
             current_scope: crate::core::dependency_graph::Scope::Global,
             scope_stack: vec![],
+            active_contexts: crate::parsing::bsl::ContextSet::ALL,
         }
     }
 
@@ -0,0 +1,244 @@
+//! Проверка обработчика команды в модуле команды
+//!
+//! Платформа вызывает в модуле команды (`CommandModule.bsl`) клиентскую
+//! процедуру `ОбработкаКоманды(ПараметрКоманды, ПараметрыВыполненияКоманды)`.
+//! Ошибка в имени, директиве компиляции или числе параметров обнаруживается
+//! только при нажатии на команду, поэтому модуль проверяется при открытии:
+//! обработчик есть, это процедура с двумя параметрами и директивой
+//! `&НаКлиенте`.
+
+use crate::core::common_module_calls::parameter_names;
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::method_header_regex;
+use crate::data::loaders::commands::{CommandIndex, CommandInfo};
+use serde::Serialize;
+
+/// Имена обработчика команды
+pub const HANDLER_NAMES: &[&str] = &["ОбработкаКоманды", "CommandProcessing"];
+
+/// Параметры, которые передаёт платформа
+pub const HANDLER_PARAMETERS: &[&str] = &["ПараметрКоманды", "ПараметрыВыполненияКоманды"];
+
+const CLIENT_DIRECTIVES: &[&str] = &["наклиенте", "atclient"];
+
+/// Обработчик команды в модуле
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandHandler {
+    pub name: String,
+    pub is_function: bool,
+    pub parameters: usize,
+    /// Директива компиляции перед заголовком (`&НаКлиенте`)
+    pub directive: Option<String>,
+    /// Позиция имени в заголовке
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Директива компиляции в строках, непосредственно предшествующих заголовку
+fn directive_before(lines: &[&str], header: usize) -> Option<String> {
+    lines[..header]
+        .iter()
+        .rev()
+        .map(|line| strip_comment(line).trim())
+        .find(|code| !code.is_empty())
+        .filter(|code| code.starts_with('&'))
+        .map(str::to_string)
+}
+
+/// Найти обработчик `ОбработкаКоманды` в тексте модуля
+pub fn find_command_handler(text: &str) -> Option<CommandHandler> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines.iter().enumerate().find_map(|(idx, line)| {
+        let code = strip_comment(line);
+        let caps = method_header_regex().captures(code)?;
+        let name = caps.get(2)?;
+        let lower = name.as_str().to_lowercase();
+        if !HANDLER_NAMES.iter().any(|h| h.to_lowercase() == lower) {
+            return None;
+        }
+        let keyword = caps[1].to_lowercase();
+        Some(CommandHandler {
+            name: name.as_str().to_string(),
+            is_function: keyword == "функция" || keyword == "function",
+            parameters: parameter_names(&caps[3]).len(),
+            directive: directive_before(&lines, idx),
+            line: idx as u32,
+            column: code[..name.start()].chars().count() as u32,
+        })
+    })
+}
+
+/// Вид нарушения
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum CommandIssueKind {
+    /// Обработчика нет в модуле
+    Missing,
+    /// Обработчик объявлен функцией
+    Function,
+    /// Число параметров не совпадает с передаваемыми платформой
+    ParameterCount { found: usize },
+    /// Нет директивы `&НаКлиенте` (указана другая или никакой)
+    Directive { found: Option<String> },
+}
+
+/// Нарушение: позиция — имя обработчика (для `Missing` — начало модуля)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandIssue {
+    pub kind: CommandIssueKind,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl CommandIssue {
+    pub fn message(&self) -> String {
+        match &self.kind {
+            CommandIssueKind::Missing => format!(
+                "В модуле команды нет обработчика '{}({})'",
+                HANDLER_NAMES[0],
+                HANDLER_PARAMETERS.join(", ")
+            ),
+            CommandIssueKind::Function => {
+                format!("Обработчик '{}' должен быть процедурой", HANDLER_NAMES[0])
+            }
+            CommandIssueKind::ParameterCount { found } => format!(
+                "Обработчик '{}' принимает {} параметров, ожидается {} ({})",
+                HANDLER_NAMES[0],
+                found,
+                HANDLER_PARAMETERS.len(),
+                HANDLER_PARAMETERS.join(", ")
+            ),
+            CommandIssueKind::Directive { found: Some(found) } => format!(
+                "Обработчик '{}' выполняется на клиенте: директива '{}' вместо '&НаКлиенте'",
+                HANDLER_NAMES[0], found
+            ),
+            CommandIssueKind::Directive { found: None } => format!(
+                "Перед обработчиком '{}' нет директивы '&НаКлиенте'",
+                HANDLER_NAMES[0]
+            ),
+        }
+    }
+}
+
+/// Проверить модуль команды `text`
+pub fn check_command_module(text: &str) -> Vec<CommandIssue> {
+    let Some(handler) = find_command_handler(text) else {
+        return vec![CommandIssue {
+            kind: CommandIssueKind::Missing,
+            line: 0,
+            column: 0,
+        }];
+    };
+    let mut kinds = Vec::new();
+    if handler.is_function {
+        kinds.push(CommandIssueKind::Function);
+    }
+    if handler.parameters != HANDLER_PARAMETERS.len() {
+        kinds.push(CommandIssueKind::ParameterCount {
+            found: handler.parameters,
+        });
+    }
+    let is_client = handler.directive.as_deref().is_some_and(|directive| {
+        let directive = directive.trim_start_matches('&').trim().to_lowercase();
+        CLIENT_DIRECTIVES.contains(&directive.as_str())
+    });
+    if !is_client {
+        kinds.push(CommandIssueKind::Directive {
+            found: handler.directive.clone(),
+        });
+    }
+    kinds
+        .into_iter()
+        .map(|kind| CommandIssue {
+            kind,
+            line: handler.line,
+            column: handler.column,
+        })
+        .collect()
+}
+
+/// Команда в отчёте: где лежит обработчик и что с ним не так
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandSummary {
+    pub full_name: String,
+    pub owner: Option<String>,
+    pub module_path: Option<String>,
+    /// Строка заголовка `ОбработкаКоманды` (с нуля)
+    pub handler_line: Option<u32>,
+    pub issues: Vec<String>,
+}
+
+impl CommandSummary {
+    /// Сводка по команде; модуль читается с диска
+    pub fn for_command(command: &CommandInfo) -> Self {
+        let mut summary = Self {
+            full_name: command.full_name(),
+            owner: command.owner.clone(),
+            module_path: None,
+            handler_line: None,
+            issues: Vec::new(),
+        };
+        let Some(path) = &command.module_path else {
+            summary.issues.push("Модуль команды не найден".to_string());
+            return summary;
+        };
+        summary.module_path = Some(path.to_string_lossy().to_string());
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                summary.handler_line = find_command_handler(&text).map(|handler| handler.line);
+                summary.issues = check_command_module(&text)
+                    .iter()
+                    .map(CommandIssue::message)
+                    .collect();
+            }
+            Err(e) => summary.issues.push(format!("Модуль не прочитан: {}", e)),
+        }
+        summary
+    }
+}
+
+/// Сводки по всем командам индекса
+pub fn command_summaries(index: &CommandIndex) -> Vec<CommandSummary> {
+    index.commands().iter().map(CommandSummary::for_command).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_command_module() {
+        let valid = "&НаКлиенте
+Процедура ОбработкаКоманды(ПараметрКоманды, ПараметрыВыполненияКоманды)
+КонецПроцедуры
+";
+        assert!(check_command_module(valid).is_empty());
+        assert_eq!(find_command_handler(valid).unwrap().column, 10);
+
+        let server = "&НаСервере // так нельзя
+Функция ОбработкаКоманды(ПараметрКоманды)
+КонецФункции
+";
+        let kinds: Vec<CommandIssueKind> = check_command_module(server)
+            .into_iter()
+            .map(|issue| issue.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                CommandIssueKind::Function,
+                CommandIssueKind::ParameterCount { found: 1 },
+                CommandIssueKind::Directive {
+                    found: Some("&НаСервере".to_string())
+                },
+            ]
+        );
+
+        let missing = check_command_module("Процедура Другая()\nКонецПроцедуры\n");
+        assert_eq!(missing[0].kind, CommandIssueKind::Missing);
+        assert_eq!(
+            missing[0].message(),
+            "В модуле команды нет обработчика \
+             'ОбработкаКоманды(ПараметрКоманды, ПараметрыВыполненияКоманды)'"
+        );
+    }
+}
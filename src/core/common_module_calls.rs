@@ -0,0 +1,814 @@
+//! Квалифицированные вызовы экспортных методов общих модулей
+//!
+//! В BSL нет импортов: экспортный метод общего модуля вызывается как
+//! `ИмяМодуля.Метод()`. Индекс собирает экспортные процедуры и функции всех
+//! общих модулей проекта (выгрузка конфигуратора `CommonModules/<Имя>/Ext/Module.bsl`
+//! и проекты EDT `src/CommonModules/<Имя>/Module.bsl`) и строит по ним
+//! элементы автодополнения и code action, дописывающий имя модуля к вызову.
+//!
+//! В проектах EDT code action дополнительно правит свойства модуля в `.mdo`:
+//! если вызов находится в клиентском методе (`&НаКлиенте`), а модуль доступен
+//! только на сервере, включается флаг «Вызов сервера» (`serverCall`).
+//!
+//! Флаги модуля (`Клиент`, `Сервер`, `ВызовСервера`, `Глобальный`) читаются
+//! из описания метаданных (`CommonModules/<Имя>.xml` конфигуратора или
+//! `<Имя>.mdo` EDT). По ним вызов `Модуль.Метод()` проверяется против
+//! контекста вызывающего метода: серверный модуль без вызова сервера нельзя
+//! вызвать из `&НаКлиенте`, клиентский — из `&НаСервере`.
+
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tower_lsp::lsp_types::*;
+use walkdir::WalkDir;
+
+use crate::core::compilation_context::{method_context, CompilationContext};
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::{is_method_header, method_header};
+
+/// Экспортный метод общего модуля
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommonModuleExport {
+    /// Имя общего модуля
+    pub module: String,
+    /// Имя метода
+    pub name: String,
+    pub is_function: bool,
+    /// Асинхронный метод (`Асинх`): вызов возвращает `Обещание`
+    pub is_async: bool,
+    /// Имена параметров (без `Знач` и значений по умолчанию)
+    pub params: Vec<String>,
+    /// Строка заголовка метода в модуле (с 0)
+    pub line: u32,
+    /// Позиция имени метода в строке заголовка (в символах)
+    pub column: u32,
+}
+
+impl CommonModuleExport {
+    /// Квалифицированное имя `Модуль.Метод`
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.module, self.name)
+    }
+}
+
+/// Свойства общего модуля из `.mdo` проекта EDT
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EdtModuleProperties {
+    pub mdo_path: PathBuf,
+    pub server: bool,
+    pub server_call: bool,
+    pub client_managed_application: bool,
+    /// Строка (с 0), после которой вставляется `<serverCall>`; порядок
+    /// элементов в `.mdo` фиксирован схемой: server, externalConnection,
+    /// clientOrdinaryApplication, serverCall
+    server_call_insert_line: u32,
+}
+
+impl EdtModuleProperties {
+    /// Разобрать текст `.mdo`
+    pub fn parse(mdo_path: PathBuf, text: &str) -> Self {
+        let flag = |tag: &str| text.contains(&format!("<{}>true</{}>", tag, tag));
+        let anchor = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim_start();
+                ["<server>", "<externalConnection>", "<clientOrdinaryApplication>"]
+                    .iter()
+                    .any(|tag| line.starts_with(tag))
+            })
+            .map(|(idx, _)| idx as u32)
+            .last()
+            .unwrap_or(0);
+
+        Self {
+            mdo_path,
+            server: flag("server"),
+            server_call: flag("serverCall"),
+            client_managed_application: flag("clientManagedApplication"),
+            server_call_insert_line: anchor + 1,
+        }
+    }
+
+    /// Модуль недоступен клиенту без флага «Вызов сервера»
+    pub fn needs_server_call(&self) -> bool {
+        self.server && !self.server_call && !self.client_managed_application
+    }
+}
+
+/// Флаги общего модуля из описания метаданных
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModuleFlags {
+    pub global: bool,
+    /// Клиент (управляемое приложение)
+    pub client: bool,
+    pub server: bool,
+    pub server_call: bool,
+    pub external_connection: bool,
+    pub privileged: bool,
+}
+
+impl ModuleFlags {
+    /// Разобрать описание модуля: `CommonModules/<Имя>.xml` конфигуратора
+    /// (`<Server>true</Server>`) или `.mdo` EDT (`<server>true</server>`)
+    pub fn parse(text: &str) -> Self {
+        let flag = |configurator: &str, edt: &str| {
+            [configurator, edt]
+                .iter()
+                .any(|tag| text.contains(&format!("<{}>true</{}>", tag, tag)))
+        };
+        Self {
+            global: flag("Global", "global"),
+            client: flag("ClientManagedApplication", "clientManagedApplication"),
+            server: flag("Server", "server"),
+            server_call: flag("ServerCall", "serverCall"),
+            external_connection: flag("ExternalConnection", "externalConnection"),
+            privileged: flag("Privileged", "privileged"),
+        }
+    }
+
+    /// Можно ли вызвать методы модуля из метода с контекстом `context`
+    pub fn available_in(&self, context: CompilationContext) -> bool {
+        let client = self.client || self.server_call;
+        match context {
+            CompilationContext::Client => client,
+            CompilationContext::Server => self.server,
+            CompilationContext::ClientAndServer => client && self.server,
+        }
+    }
+
+    /// Контекст, в котором компилируются методы самого модуля без директив
+    pub fn context(&self) -> Option<CompilationContext> {
+        match (self.client, self.server) {
+            (true, true) => Some(CompilationContext::ClientAndServer),
+            (true, false) => Some(CompilationContext::Client),
+            (false, true) => Some(CompilationContext::Server),
+            (false, false) => None,
+        }
+    }
+
+    /// Флаги для подсказок: `Сервер, ВызовСервера`
+    pub fn label(&self) -> String {
+        [
+            (self.global, "Глобальный"),
+            (self.client, "Клиент"),
+            (self.server, "Сервер"),
+            (self.server_call, "ВызовСервера"),
+            (self.external_connection, "ВнешнееСоединение"),
+            (self.privileged, "Привилегированный"),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// Нарушение при вызове `Модуль.Метод()` общего модуля
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleCallIssue {
+    pub module: String,
+    pub method: String,
+    /// Позиция имени метода (строка с 0, столбец в символах)
+    pub line: u32,
+    pub column: u32,
+    pub kind: ModuleCallIssueKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModuleCallIssueKind {
+    /// У модуля нет экспортного метода с таким именем
+    NotExported,
+    /// Модуль недоступен в контексте вызывающего метода
+    Unavailable {
+        context: CompilationContext,
+        flags: ModuleFlags,
+    },
+}
+
+impl ModuleCallIssue {
+    pub fn message(&self) -> String {
+        match &self.kind {
+            ModuleCallIssueKind::NotExported => format!(
+                "Метод {} не найден среди экспортных методов общего модуля {}",
+                self.method, self.module
+            ),
+            ModuleCallIssueKind::Unavailable { context, flags } => {
+                let context = match context {
+                    CompilationContext::Client => "на клиенте",
+                    CompilationContext::Server => "на сервере",
+                    CompilationContext::ClientAndServer => "на клиенте и на сервере",
+                };
+                let flags = flags.label();
+                format!(
+                    "Общий модуль {} ({}) недоступен {}: {}.{}() не будет вызван",
+                    self.module,
+                    if flags.is_empty() { "без флагов" } else { &flags },
+                    context,
+                    self.module,
+                    self.method
+                )
+            }
+        }
+    }
+}
+
+/// Общий модуль проекта
+#[derive(Debug, Clone, Default)]
+pub struct CommonModuleInfo {
+    pub name: String,
+    pub exports: Vec<CommonModuleExport>,
+    /// Флаги из описания метаданных; `None` — описание не найдено
+    pub flags: Option<ModuleFlags>,
+    /// Свойства из `.mdo` (только для проектов EDT)
+    pub edt: Option<EdtModuleProperties>,
+    /// Файл `Module.bsl` (для модулей, найденных в каталоге проекта)
+    pub path: Option<PathBuf>,
+}
+
+/// Индекс экспортных методов общих модулей
+#[derive(Debug, Clone, Default)]
+pub struct CommonModuleIndex {
+    /// Имя модуля (в нижнем регистре) → модуль
+    modules: BTreeMap<String, CommonModuleInfo>,
+    /// Имя метода (в нижнем регистре) → модули, экспортирующие метод
+    by_method: HashMap<String, Vec<String>>,
+}
+
+fn module_call_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"([\p{L}_][\p{L}\p{N}_]*)\s*\.\s*([\p{L}_][\p{L}\p{N}_]*)\s*\(").unwrap()
+    })
+}
+
+/// Имя общего модуля по пути к `Module.bsl` (конфигуратор или EDT)
+pub fn module_name_from_path(path: &Path) -> Option<String> {
+    let components: Vec<String> = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let idx = components.iter().rposition(|c| c == "CommonModules")?;
+    let name = components.get(idx + 1)?;
+    let file = components.last()?;
+    file.eq_ignore_ascii_case("Module.bsl").then(|| name.clone())
+}
+
+impl CommonModuleIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Собрать индекс из каталога проекта
+    pub fn scan_directory(root: &Path) -> Result<Self> {
+        let mut index = Self::new();
+
+        for entry in WalkDir::new(root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let path = entry.path();
+            let Some(name) = module_name_from_path(path) else {
+                continue;
+            };
+            let Ok(text) = crate::core::fs_utils::read_bsl_file(path) else {
+                continue;
+            };
+
+            // В EDT рядом с модулем лежит `<Имя>.mdo`, в выгрузке
+            // конфигуратора описание — `CommonModules/<Имя>.xml`
+            let mdo_path = path.with_file_name(format!("{}.mdo", name));
+            let mdo = std::fs::read_to_string(&mdo_path).ok();
+            let flags = match &mdo {
+                Some(mdo) => Some(ModuleFlags::parse(mdo)),
+                None => path
+                    .ancestors()
+                    .nth(3)
+                    .map(|modules| modules.join(format!("{}.xml", name)))
+                    .and_then(|xml| std::fs::read_to_string(xml).ok())
+                    .map(|xml| ModuleFlags::parse(&xml)),
+            };
+            let edt = mdo.map(|mdo| EdtModuleProperties::parse(mdo_path, &mdo));
+
+            index.add_module(&name, &text, edt);
+            if let Some(module) = index.modules.get_mut(&name.to_lowercase()) {
+                module.path = Some(path.to_path_buf());
+                module.flags = flags;
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Добавить общий модуль по тексту
+    pub fn add_module(&mut self, name: &str, text: &str, edt: Option<EdtModuleProperties>) {
+        let exports: Vec<CommonModuleExport> = text
+            .lines()
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                let line = strip_comment(line);
+                let header = method_header(line).filter(|header| header.export)?;
+                Some(CommonModuleExport {
+                    module: name.to_string(),
+                    name: header.name.to_string(),
+                    is_function: header.is_function,
+                    is_async: header.is_async,
+                    params: parameter_names(header.parameters),
+                    line: idx as u32,
+                    column: line[..header.name_start].chars().count() as u32,
+                })
+            })
+            .collect();
+
+        let key = name.to_lowercase();
+        for export in &exports {
+            let modules = self.by_method.entry(export.name.to_lowercase()).or_default();
+            if !modules.contains(&key) {
+                modules.push(key.clone());
+            }
+        }
+        self.modules.insert(
+            key,
+            CommonModuleInfo {
+                name: name.to_string(),
+                exports,
+                flags: None,
+                edt,
+                path: None,
+            },
+        );
+    }
+
+    /// Задать флаги модуля из описания метаданных
+    pub fn set_module_flags(&mut self, name: &str, flags: ModuleFlags) {
+        if let Some(module) = self.modules.get_mut(&name.to_lowercase()) {
+            module.flags = Some(flags);
+        }
+    }
+
+    pub fn module(&self, name: &str) -> Option<&CommonModuleInfo> {
+        self.modules.get(&name.to_lowercase())
+    }
+
+    /// Количество общих модулей
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// Экспортные методы с заданным именем (без учёта регистра)
+    pub fn find_exports(&self, method: &str) -> Vec<&CommonModuleExport> {
+        let key = method.to_lowercase();
+        self.by_method
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .filter_map(|module| self.modules.get(module))
+            .flat_map(|module| module.exports.iter())
+            .filter(|export| export.name.to_lowercase() == key)
+            .collect()
+    }
+
+    /// Экспортный метод `Модуль.Метод` (без учёта регистра)
+    pub fn find_export(&self, module: &str, method: &str) -> Option<&CommonModuleExport> {
+        let method = method.to_lowercase();
+        self.module(module)?
+            .exports
+            .iter()
+            .find(|export| export.name.to_lowercase() == method)
+    }
+
+    /// Экспортные методы, имя которых начинается с префикса
+    pub fn exports_matching(&self, prefix: &str) -> Vec<&CommonModuleExport> {
+        let prefix = prefix.to_lowercase();
+        self.modules
+            .values()
+            .flat_map(|module| module.exports.iter())
+            .filter(|export| export.name.to_lowercase().starts_with(&prefix))
+            .collect()
+    }
+
+    /// Элементы автодополнения для неквалифицированного префикса: вставляется
+    /// вызов `Модуль.Метод(...)` с параметрами-заполнителями; после
+    /// `Модуль.` — экспортные методы этого модуля
+    pub fn completion_items(
+        &self,
+        prefix: &str,
+        current_module: Option<&str>,
+    ) -> Vec<CompletionItem> {
+        if let Some((module, method)) = prefix.rsplit_once('.') {
+            return self.module_completion_items(module, method);
+        }
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+        self.exports_matching(prefix)
+            .into_iter()
+            .filter(|export| !is_same_module(export, current_module))
+            .map(|export| {
+                let placeholders: Vec<String> = export
+                    .params
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, param)| format!("${{{}:{}}}", idx + 1, param))
+                    .collect();
+                CompletionItem {
+                    label: export.name.clone(),
+                    label_details: Some(CompletionItemLabelDetails {
+                        detail: None,
+                        description: Some(export.module.clone()),
+                    }),
+                    kind: Some(CompletionItemKind::FUNCTION),
+                    detail: Some(format!(
+                        "{}{}({})",
+                        if export.is_async { "Асинх " } else { "" },
+                        export.qualified_name(),
+                        export.params.join(", ")
+                    )),
+                    filter_text: Some(export.name.clone()),
+                    insert_text: Some(format!(
+                        "{}({})",
+                        export.qualified_name(),
+                        placeholders.join(", ")
+                    )),
+                    insert_text_format: Some(InsertTextFormat::SNIPPET),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Экспортные методы модуля `module`, имя которых начинается с `prefix`
+    fn module_completion_items(&self, module: &str, prefix: &str) -> Vec<CompletionItem> {
+        let Some(info) = self.module(module.trim()) else {
+            return Vec::new();
+        };
+        let prefix = prefix.to_lowercase();
+        let flags = info.flags.map(|flags| flags.label()).unwrap_or_default();
+        info.exports
+            .iter()
+            .filter(|export| export.name.to_lowercase().starts_with(&prefix))
+            .map(|export| {
+                let placeholders: Vec<String> = export
+                    .params
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, param)| format!("${{{}:{}}}", idx + 1, param))
+                    .collect();
+                CompletionItem {
+                    label: export.name.clone(),
+                    label_details: (!flags.is_empty()).then(|| CompletionItemLabelDetails {
+                        detail: None,
+                        description: Some(flags.clone()),
+                    }),
+                    kind: Some(if export.is_function {
+                        CompletionItemKind::FUNCTION
+                    } else {
+                        CompletionItemKind::METHOD
+                    }),
+                    detail: Some(format!(
+                        "{}{}({})",
+                        if export.is_async { "Асинх " } else { "" },
+                        export.qualified_name(),
+                        export.params.join(", ")
+                    )),
+                    insert_text: Some(format!("{}({})", export.name, placeholders.join(", "))),
+                    insert_text_format: Some(InsertTextFormat::SNIPPET),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Проверить вызовы `Модуль.Метод()` общих модулей в тексте модуля:
+    /// метод должен быть экспортным, а модуль — доступным в контексте
+    /// вызывающего метода. Метод без директивы в общем модуле
+    /// `current_module` компилируется в контексте самого модуля.
+    pub fn check_calls(&self, text: &str, current_module: Option<&str>) -> Vec<ModuleCallIssue> {
+        let module_context = current_module
+            .and_then(|name| self.module(name))
+            .and_then(|module| module.flags)
+            .and_then(|flags| flags.context());
+        let mut issues = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            let code = strip_comment(line);
+            for caps in module_call_regex().captures_iter(code) {
+                let whole = caps.get(0).unwrap();
+                if code[..whole.start()].trim_end().ends_with('.') {
+                    continue;
+                }
+                let Some(module) = self.module(&caps[1]) else {
+                    continue;
+                };
+                let method = caps.get(2).unwrap();
+                let column = code[..method.start()].chars().count() as u32;
+                let issue = |kind| ModuleCallIssue {
+                    module: module.name.clone(),
+                    method: method.as_str().to_string(),
+                    line: idx as u32,
+                    column,
+                    kind,
+                };
+                if self.find_export(&module.name, method.as_str()).is_none() {
+                    issues.push(issue(ModuleCallIssueKind::NotExported));
+                    continue;
+                }
+                let Some(flags) = module.flags else {
+                    continue;
+                };
+                let context = method_context(text, idx as u32).or(module_context);
+                if let Some(context) = context.filter(|context| !flags.available_in(*context)) {
+                    issues.push(issue(ModuleCallIssueKind::Unavailable { context, flags }));
+                }
+            }
+        }
+        issues
+    }
+
+    /// Code actions для неквалифицированного вызова экспортного метода под курсором
+    pub fn qualify_call_actions(
+        &self,
+        uri: &Url,
+        text: &str,
+        position: Position,
+    ) -> Vec<CodeActionOrCommand> {
+        let Some(line) = text.lines().nth(position.line as usize) else {
+            return Vec::new();
+        };
+        let Some((start, end)) = identifier_at(line, position.character) else {
+            return Vec::new();
+        };
+        let before = &line[..start];
+        if before.trim_end().ends_with('.') {
+            return Vec::new();
+        }
+        let identifier = &line[start..end];
+        let has_call_parens = line[end..].trim_start().starts_with('(');
+
+        let current_module = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| module_name_from_path(&path));
+        let client_context = is_client_context(text, position.line as usize);
+
+        let range = Range {
+            start: Position::new(position.line, utf16_len(before)),
+            end: Position::new(position.line, utf16_len(&line[..end])),
+        };
+
+        self.find_exports(identifier)
+            .into_iter()
+            .filter(|export| !is_same_module(export, current_module.as_deref()))
+            .map(|export| {
+                let new_text = if has_call_parens {
+                    export.qualified_name()
+                } else {
+                    format!("{}()", export.qualified_name())
+                };
+                let mut changes = HashMap::new();
+                changes.insert(uri.clone(), vec![TextEdit { range, new_text }]);
+
+                let mut title = format!("Вызвать как {}()", export.qualified_name());
+                let edt = self.module(&export.module).and_then(|m| m.edt.as_ref());
+                if let Some(edt) = edt.filter(|edt| client_context && edt.needs_server_call()) {
+                    if let Ok(mdo_uri) = Url::from_file_path(&edt.mdo_path) {
+                        let at = Position::new(edt.server_call_insert_line, 0);
+                        changes.insert(
+                            mdo_uri,
+                            vec![TextEdit {
+                                range: Range::new(at, at),
+                                new_text: "  <serverCall>true</serverCall>\n".to_string(),
+                            }],
+                        );
+                        title.push_str(" и разрешить вызов сервера");
+                    }
+                }
+
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title,
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(changes),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
+
+fn is_same_module(export: &CommonModuleExport, current_module: Option<&str>) -> bool {
+    current_module.is_some_and(|current| current.to_lowercase() == export.module.to_lowercase())
+}
+
+/// Имена параметров из заголовка метода
+pub(crate) fn parameter_names(params: &str) -> Vec<String> {
+    params
+        .split(',')
+        .filter_map(|param| {
+            let name = param.split('=').next()?.trim();
+            let name = name
+                .strip_prefix("Знач ")
+                .or_else(|| name.strip_prefix("Val "))
+                .unwrap_or(name)
+                .trim();
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect()
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Границы идентификатора (в байтах) под позицией курсора (UTF-16)
+fn identifier_at(line: &str, character: u32) -> Option<(usize, usize)> {
+    let mut offset = line.len();
+    let mut units = 0u32;
+    for (idx, ch) in line.char_indices() {
+        if units >= character {
+            offset = idx;
+            break;
+        }
+        units += ch.len_utf16() as u32;
+    }
+
+    let start = line[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, ch)| is_identifier_char(*ch))
+        .last()
+        .map(|(idx, _)| idx)
+        .unwrap_or(offset);
+    let end = line[offset..]
+        .char_indices()
+        .find(|(_, ch)| !is_identifier_char(*ch))
+        .map(|(idx, _)| offset + idx)
+        .unwrap_or(line.len());
+
+    (start < end).then_some((start, end))
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.chars().map(|ch| ch.len_utf16() as u32).sum()
+}
+
+/// Находится ли строка внутри метода с директивой `&НаКлиенте`
+fn is_client_context(text: &str, line: usize) -> bool {
+    let lines: Vec<&str> = text.lines().collect();
+    let Some(header) = (0..=line.min(lines.len().saturating_sub(1)))
+        .rev()
+        .find(|&idx| is_method_header(lines[idx]))
+    else {
+        return false;
+    };
+
+    lines[..header]
+        .iter()
+        .rev()
+        .take_while(|l| l.trim_start().starts_with('&'))
+        .any(|l| {
+            let directive = l.trim().to_lowercase();
+            directive.starts_with("&наклиенте") || directive.starts_with("&atclient")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMON_MODULE: &str = r#"
+Функция СуммаДокумента(Документ, Знач СНДС = Истина) Экспорт
+КонецФункции
+
+Процедура Служебная()
+КонецПроцедуры
+"#;
+
+    const MDO: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<mdclass:CommonModule xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass">
+  <name>Продажи</name>
+  <server>true</server>
+  <externalConnection>true</externalConnection>
+</mdclass:CommonModule>
+"#;
+
+    #[test]
+    fn test_index_exports() {
+        let mut index = CommonModuleIndex::new();
+        index.add_module("Продажи", COMMON_MODULE, None);
+
+        let found = index.find_exports("суммадокумента");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].qualified_name(), "Продажи.СуммаДокумента");
+        assert_eq!(found[0].params, ["Документ", "СНДС"]);
+        assert!(index.find_exports("Служебная").is_empty());
+        let export = index.find_export("продажи", "СуммаДокумента").unwrap();
+        assert_eq!((export.line, export.column), (1, 8));
+        assert!(export.is_function && !export.is_async);
+        assert!(index.find_export("Продажи", "Служебная").is_none());
+
+        let items = index.completion_items("Сумма", None);
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0].insert_text.as_deref(),
+            Some("Продажи.СуммаДокумента(${1:Документ}, ${2:СНДС})")
+        );
+        assert!(index.completion_items("Сумма", Some("Продажи")).is_empty());
+        assert_eq!(
+            module_name_from_path(Path::new("src/CommonModules/Продажи/Module.bsl")).as_deref(),
+            Some("Продажи")
+        );
+    }
+
+    #[test]
+    fn test_module_flags_and_context_checks() {
+        let project = tempfile::tempdir().unwrap();
+        let modules = project.path().join("CommonModules");
+        std::fs::create_dir_all(modules.join("Продажи/Ext")).unwrap();
+        std::fs::write(modules.join("Продажи/Ext/Module.bsl"), COMMON_MODULE).unwrap();
+        std::fs::write(
+            modules.join("Продажи.xml"),
+            "<CommonModule><Properties><Name>Продажи</Name>\
+             <Server>true</Server><ServerCall>false</ServerCall></Properties></CommonModule>",
+        )
+        .unwrap();
+        let index = CommonModuleIndex::scan_directory(project.path()).unwrap();
+        let flags = index.module("Продажи").unwrap().flags.unwrap();
+        assert!(flags.server && !flags.server_call && !flags.client);
+        assert_eq!(flags.label(), "Сервер");
+        assert!(!flags.available_in(CompilationContext::Client));
+        assert!(flags.available_in(CompilationContext::Server));
+
+        let text = "&НаКлиенте
+Процедура Команда()
+    Сумма = Продажи.СуммаДокумента(Ссылка);
+    Продажи.Служебная();
+КонецПроцедуры
+
+&НаСервере
+Процедура НаСервере()
+    Сумма = Продажи.СуммаДокумента(Ссылка);
+    Сумма = Объект.Продажи.СуммаДокумента(Ссылка);
+КонецПроцедуры
+";
+        let issues = index.check_calls(text, None);
+        assert_eq!(issues.len(), 2);
+        assert_eq!((issues[0].line, issues[0].column), (2, 20));
+        assert!(matches!(
+            issues[0].kind,
+            ModuleCallIssueKind::Unavailable {
+                context: CompilationContext::Client,
+                ..
+            }
+        ));
+        assert!(issues[0].message().contains("недоступен на клиенте"));
+        assert_eq!(issues[1].method, "Служебная");
+        assert_eq!(issues[1].kind, ModuleCallIssueKind::NotExported);
+
+        let items = index.completion_items("Продажи.Сум", None);
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0].insert_text.as_deref(),
+            Some("СуммаДокумента(${1:Документ}, ${2:СНДС})")
+        );
+        assert_eq!(
+            items[0].label_details.as_ref().unwrap().description.as_deref(),
+            Some("Сервер")
+        );
+        assert!(index.completion_items("Неизвестный.Сум", None).is_empty());
+    }
+
+    #[test]
+    fn test_qualify_call_action_updates_edt_module() {
+        let mdo_path = std::env::temp_dir().join("Продажи.mdo");
+        let mut index = CommonModuleIndex::new();
+        let edt = EdtModuleProperties::parse(mdo_path, MDO);
+        assert!(edt.needs_server_call());
+        index.add_module("Продажи", COMMON_MODULE, Some(edt));
+
+        let text = "&НаКлиенте\nПроцедура Команда()\n    Сумма = СуммаДокумента(Ссылка);\n";
+        let uri = Url::parse("file:///tmp/DataProcessors/Форма/Module.bsl").unwrap();
+        let actions = index.qualify_call_actions(&uri, text, Position::new(2, 14));
+        assert_eq!(actions.len(), 1);
+
+        let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            panic!("ожидался code action");
+        };
+        assert!(action.title.contains("разрешить вызов сервера"));
+        let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+        assert_eq!(changes.len(), 2);
+        let edit = &changes[&uri][0];
+        assert_eq!(edit.new_text, "Продажи.СуммаДокумента");
+        assert_eq!(edit.range.start, Position::new(2, 12));
+        assert_eq!(edit.range.end, Position::new(2, 26));
+    }
+}
@@ -0,0 +1,198 @@
+//! Контекст компиляции метода по директиве
+//!
+//! В модулях форм и команд метод компилируется там, где указывает директива
+//! (`&НаКлиенте`, `&НаСервере`, ...). Глобальные методы платформы доступны не
+//! везде: `ПолучитьФорму` нельзя вызвать на сервере, `ЗначениеВСтрокуВнутр` —
+//! на тонком клиенте. По директиве и доступности из синтакс-помощника
+//! автодополнение отбрасывает недоступные в текущем методе глобальные члены.
+
+use std::collections::HashMap;
+
+use crate::core::example_mining::strip_comment;
+use crate::core::rename::method_ranges;
+use crate::documentation::core::hierarchy::AvailabilityContext;
+use crate::parsing::bsl::preprocessor::ContextSet;
+
+/// Где исполняется метод
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompilationContext {
+    /// `&НаКлиенте`
+    Client,
+    /// `&НаСервере`, `&НаСервереБезКонтекста`
+    Server,
+    /// `&НаКлиентеНаСервереБезКонтекста`, `&НаКлиентеНаСервере`: код
+    /// компилируется и на клиенте, и на сервере
+    ClientAndServer,
+}
+
+impl CompilationContext {
+    /// Контекст по директиве (`&НаКлиенте`, `AtServer`, без учёта регистра)
+    pub fn from_directive(directive: &str) -> Option<Self> {
+        let name = directive
+            .trim()
+            .trim_start_matches('&')
+            .trim()
+            .to_lowercase();
+        match name.as_str() {
+            "наклиенте" | "atclient" => Some(Self::Client),
+            "насервере" | "atserver" | "насерверебезконтекста" | "atservernocontext" => {
+                Some(Self::Server)
+            }
+            "наклиентенасерверебезконтекста"
+            | "atclientatservernocontext"
+            | "наклиентенасервере"
+            | "atclientatserver" => Some(Self::ClientAndServer),
+            _ => None,
+        }
+    }
+
+    /// Можно ли вызвать член с доступностью `availability` в этом контексте.
+    /// Пустой список — доступность неизвестна, член не отбрасывается.
+    pub fn allows(self, availability: &[AvailabilityContext]) -> bool {
+        if availability.is_empty() {
+            return true;
+        }
+        let client = availability.iter().any(|context| {
+            matches!(
+                context,
+                AvailabilityContext::Client
+                    | AvailabilityContext::WebClient
+                    | AvailabilityContext::MobileApp
+            )
+        });
+        let server = availability.iter().any(|context| {
+            matches!(
+                context,
+                AvailabilityContext::Server | AvailabilityContext::MobileServer
+            )
+        });
+        match self {
+            Self::Client => client,
+            Self::Server => server,
+            Self::ClientAndServer => client && server,
+        }
+    }
+}
+
+impl From<CompilationContext> for ContextSet {
+    /// Контексты препроцессора, в которых компилируется метод с директивой:
+    /// ветки `#Если` внутри метода проверяются против них
+    fn from(context: CompilationContext) -> Self {
+        match context {
+            CompilationContext::Client => ContextSet::CLIENT,
+            CompilationContext::Server => ContextSet::SERVER,
+            CompilationContext::ClientAndServer => ContextSet::CLIENT.union(ContextSet::SERVER),
+        }
+    }
+}
+
+/// Контекст метода, в который попадает строка `line` (с нуля); `None` — вне
+/// метода или без директивы
+pub fn method_context(text: &str, line: u32) -> Option<CompilationContext> {
+    let range = method_ranges(text)
+        .into_iter()
+        .find(|range| range.contains(&line))?;
+    let lines: Vec<&str> = text.lines().collect();
+    // Директива и аннотации (`&Вместо("...")`) идут подряд над заголовком
+    lines[..*range.start() as usize]
+        .iter()
+        .rev()
+        .map(|line| strip_comment(line).trim())
+        .take_while(|code| code.starts_with('&'))
+        .find_map(CompilationContext::from_directive)
+}
+
+/// Доступность глобальных методов платформы по имени
+#[derive(Debug, Clone, Default)]
+pub struct GlobalAvailability {
+    by_name: HashMap<String, Vec<AvailabilityContext>>,
+}
+
+impl GlobalAvailability {
+    /// Добавить метод (русское или английское имя)
+    pub fn insert(&mut self, name: &str, availability: Vec<AvailabilityContext>) {
+        if !availability.is_empty() {
+            self.by_name.insert(name.to_lowercase(), availability);
+        }
+    }
+
+    /// Доступность метода; пустой срез — неизвестна
+    pub fn get(&self, name: &str) -> &[AvailabilityContext] {
+        self.by_name
+            .get(&name.to_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Доступен ли метод в контексте
+    pub fn allows(&self, name: &str, context: CompilationContext) -> bool {
+        context.allows(self.get(name))
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_context_filters_globals() {
+        let text = "&НаКлиенте
+Процедура Команда(Команда)
+    Сообщить(1);
+КонецПроцедуры
+
+&НаСервере // комментарий
+&Вместо(\"Проверка\")
+Процедура Проверка()
+КонецПроцедуры
+
+Процедура БезДирективы()
+КонецПроцедуры
+
+&НаКлиентеНаСервереБезКонтекста
+Функция Общая()
+КонецФункции
+";
+        assert_eq!(method_context(text, 2), Some(CompilationContext::Client));
+        assert_eq!(method_context(text, 7), Some(CompilationContext::Server));
+        assert_eq!(method_context(text, 10), None);
+        assert_eq!(
+            ContextSet::from(method_context(text, 2).unwrap()),
+            ContextSet::CLIENT
+        );
+        assert_eq!(method_context(text, 4), None);
+        assert_eq!(
+            method_context(text, 14),
+            Some(CompilationContext::ClientAndServer)
+        );
+
+        let mut availability = GlobalAvailability::default();
+        availability.insert("ПолучитьФорму", vec![AvailabilityContext::Client]);
+        availability.insert(
+            "ЗначениеВСтрокуВнутр",
+            vec![
+                AvailabilityContext::Server,
+                AvailabilityContext::ExternalConnection,
+            ],
+        );
+        availability.insert(
+            "Сообщить",
+            vec![AvailabilityContext::Client, AvailabilityContext::Server],
+        );
+
+        assert!(availability.allows("получитьформу", CompilationContext::Client));
+        assert!(!availability.allows("ПолучитьФорму", CompilationContext::Server));
+        assert!(!availability.allows("ЗначениеВСтрокуВнутр", CompilationContext::Client));
+        assert!(!availability.allows("ПолучитьФорму", CompilationContext::ClientAndServer));
+        assert!(availability.allows("Сообщить", CompilationContext::ClientAndServer));
+        assert!(availability.allows("Неизвестный", CompilationContext::Server));
+    }
+}
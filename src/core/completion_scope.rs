@@ -0,0 +1,282 @@
+//! Символы области видимости для автодополнения
+//!
+//! Автодополнение группируется по близости к курсору: сначала локальные
+//! переменные и параметры текущего метода, затем переменные и методы модуля,
+//! затем глобальный контекст платформы и конфигурации. Здесь по тексту
+//! модуля собираются символы первых двух групп.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::{method_end_regex, method_header_regex};
+use crate::core::module_variables::module_declarations;
+
+/// Группа автодополнения
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionGroup {
+    /// Параметры и локальные переменные текущего метода
+    Local,
+    /// Переменные и методы модуля
+    Module,
+    /// Глобальный контекст, типы платформы и конфигурации
+    Global,
+}
+
+impl std::str::FromStr for CompletionGroup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "local" => Ok(CompletionGroup::Local),
+            "module" => Ok(CompletionGroup::Module),
+            "global" => Ok(CompletionGroup::Global),
+            other => Err(format!("неизвестная группа автодополнения '{}'", other)),
+        }
+    }
+}
+
+/// Порядок групп автодополнения, передаваемый клиенту через `sortText`.
+///
+/// Номер группы в списке становится префиксом `sortText`, поэтому границы
+/// групп сохраняются при любой сортировке на стороне редактора; группы,
+/// не указанные в списке, идут после указанных.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompletionGroupsConfig {
+    pub order: Vec<CompletionGroup>,
+}
+
+impl Default for CompletionGroupsConfig {
+    fn default() -> Self {
+        Self {
+            order: vec![
+                CompletionGroup::Local,
+                CompletionGroup::Module,
+                CompletionGroup::Global,
+            ],
+        }
+    }
+}
+
+impl CompletionGroupsConfig {
+    /// Номер группы в порядке сортировки
+    pub fn rank(&self, group: CompletionGroup) -> usize {
+        self.order
+            .iter()
+            .position(|g| *g == group)
+            .unwrap_or(self.order.len())
+    }
+
+    /// Значение `sortText` для элемента группы
+    pub fn sort_text(&self, group: CompletionGroup, label: &str) -> String {
+        format!("{}_{}", self.rank(group), label)
+    }
+}
+
+/// Вид символа области видимости
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeSymbolKind {
+    Parameter,
+    Variable,
+    Procedure,
+    Function,
+}
+
+/// Символ, доступный в позиции курсора
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeSymbol {
+    pub name: String,
+    pub kind: ScopeSymbolKind,
+    pub group: CompletionGroup,
+}
+
+fn local_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // `Перем А, Б;`, `Имя = ...` и переменные циклов `Для [Каждого] Имя`
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)(?:^|;)\s*(?:(?:перем|var)\s+([^;]+)|(?:(?:для|for)\s+(?:(?:каждого|each)\s+)?)?([\p{L}_][\p{L}\p{N}_]*)\s*(?:=|\s(?:из|in)\s))",
+        )
+        .unwrap()
+    })
+}
+
+/// Имена параметров из заголовка метода
+fn parameter_names(params: &str) -> Vec<String> {
+    params
+        .split(',')
+        .filter_map(|param| {
+            let name = param.split('=').next()?.trim();
+            let mut words = name.split_whitespace();
+            let first = words.next()?;
+            let name = if matches!(first.to_lowercase().as_str(), "знач" | "val") {
+                words.next()?
+            } else {
+                first
+            };
+            Some(name.to_string())
+        })
+        .collect()
+}
+
+/// Символы модуля, доступные в строке `line` (с 0): локальные символы метода,
+/// в котором стоит курсор, затем переменные и методы модуля. Имена не
+/// повторяются; локальный символ скрывает одноимённый символ модуля.
+pub fn scope_symbols(text: &str, line: usize) -> Vec<ScopeSymbol> {
+    let mut locals = Vec::new();
+    let mut members = Vec::new();
+    // Строка заголовка и символы метода, внутри которого идёт обход
+    let mut current: Option<(usize, Vec<ScopeSymbol>)> = None;
+
+    for (idx, source) in text.lines().enumerate() {
+        let code = strip_comment(source);
+        if let Some(caps) = method_header_regex().captures(code) {
+            let kind = match caps[1].to_lowercase().as_str() {
+                "функция" | "function" => ScopeSymbolKind::Function,
+                _ => ScopeSymbolKind::Procedure,
+            };
+            members.push(ScopeSymbol {
+                name: caps[2].to_string(),
+                kind,
+                group: CompletionGroup::Module,
+            });
+            let parameters = parameter_names(&caps[3])
+                .into_iter()
+                .map(|name| ScopeSymbol {
+                    name,
+                    kind: ScopeSymbolKind::Parameter,
+                    group: CompletionGroup::Local,
+                })
+                .collect();
+            current = Some((idx, parameters));
+            continue;
+        }
+        if method_end_regex().is_match(code) {
+            if let Some((start, symbols)) = current.take() {
+                if (start..=idx).contains(&line) {
+                    locals = symbols;
+                }
+            }
+            continue;
+        }
+        let Some((_, symbols)) = current.as_mut() else {
+            continue;
+        };
+        for caps in local_regex().captures_iter(code) {
+            let names: Vec<&str> = match (caps.get(1), caps.get(2)) {
+                (Some(declared), _) => declared
+                    .as_str()
+                    .split(',')
+                    .filter_map(|item| item.split_whitespace().next())
+                    .collect(),
+                (None, Some(assigned)) => vec![assigned.as_str()],
+                _ => continue,
+            };
+            for name in names {
+                symbols.push(ScopeSymbol {
+                    name: name.to_string(),
+                    kind: ScopeSymbolKind::Variable,
+                    group: CompletionGroup::Local,
+                });
+            }
+        }
+    }
+    // Метод без `КонецПроцедуры` (набирается прямо сейчас)
+    if let Some((start, symbols)) = current {
+        if start <= line {
+            locals = symbols;
+        }
+    }
+
+    for variable in module_declarations(text) {
+        members.push(ScopeSymbol {
+            name: variable.name,
+            kind: ScopeSymbolKind::Variable,
+            group: CompletionGroup::Module,
+        });
+    }
+
+    let mut seen = HashSet::new();
+    locals
+        .into_iter()
+        .chain(members)
+        .filter(|symbol| seen.insert(symbol.name.to_lowercase()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = r#"Перем Кеш Экспорт;
+Перем Счетчик;
+
+Процедура Заполнить(Знач Документ, Режим = 0)
+    Перем Итог;
+    Сумма = 0;
+    Для Каждого Строка Из Документ.Товары Цикл
+        Сумма = Сумма + Строка.Сумма;
+    КонецЦикла;
+КонецПроцедуры
+
+Функция Счетчик()
+    Возврат 1;
+КонецФункции
+"#;
+
+    fn names(symbols: &[ScopeSymbol], group: CompletionGroup) -> Vec<&str> {
+        symbols
+            .iter()
+            .filter(|s| s.group == group)
+            .map(|s| s.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_locals_of_current_method_first() {
+        let symbols = scope_symbols(MODULE, 7);
+        assert_eq!(
+            names(&symbols, CompletionGroup::Local),
+            ["Документ", "Режим", "Итог", "Сумма", "Строка"]
+        );
+        // Функция и переменная `Счетчик` совпадают по имени — остаётся первая
+        assert_eq!(
+            names(&symbols, CompletionGroup::Module),
+            ["Заполнить", "Счетчик", "Кеш"]
+        );
+        assert_eq!(symbols[5].kind, ScopeSymbolKind::Procedure);
+    }
+
+    #[test]
+    fn test_no_locals_outside_methods() {
+        let symbols = scope_symbols(MODULE, 1);
+        assert!(names(&symbols, CompletionGroup::Local).is_empty());
+        let symbols = scope_symbols(MODULE, 12);
+        assert!(names(&symbols, CompletionGroup::Local).is_empty());
+    }
+
+    #[test]
+    fn test_group_sort_text() {
+        let config = CompletionGroupsConfig::default();
+        assert!(
+            config.sort_text(CompletionGroup::Local, "Яблоко")
+                < config.sort_text(CompletionGroup::Global, "Абрикос")
+        );
+
+        let config = CompletionGroupsConfig {
+            order: vec![CompletionGroup::Module],
+        };
+        assert_eq!(config.sort_text(CompletionGroup::Module, "Кеш"), "0_Кеш");
+        assert_eq!(config.sort_text(CompletionGroup::Local, "Итог"), "1_Итог");
+    }
+
+    #[test]
+    fn test_group_from_str() {
+        assert_eq!("Module".parse::<CompletionGroup>(), Ok(CompletionGroup::Module));
+        assert!("recent".parse::<CompletionGroup>().is_err());
+    }
+}
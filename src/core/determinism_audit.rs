@@ -0,0 +1,302 @@
+//! Аудит детерминированности вывода типов
+//!
+//! Проект анализируется несколько раз с разным количеством потоков и порядком
+//! файлов, после чего результаты сравниваются по каждой переменной, функции и
+//! набору диагностик. Расхождения указывают на ошибки недетерминированности:
+//! зависимость от порядка обхода `HashMap`, гонки между потоками и т.п.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use crate::core::parallel_analysis::{FileAnalysisResult, ParallelAnalysisConfig, ParallelAnalyzer};
+
+/// Код выхода CLI, если найдены расхождения
+pub const EXIT_NONDETERMINISTIC: i32 = 5;
+
+/// Значение для выражения, которого нет в одном из прогонов
+const MISSING: &str = "<отсутствует>";
+
+/// Порядок подачи файлов в анализатор
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileOrder {
+    Sorted,
+    Reversed,
+    /// Сначала чётные, затем нечётные позиции
+    Interleaved,
+}
+
+impl FileOrder {
+    fn apply(&self, files: &[PathBuf]) -> Vec<PathBuf> {
+        let mut sorted = files.to_vec();
+        sorted.sort();
+        match self {
+            FileOrder::Sorted => sorted,
+            FileOrder::Reversed => {
+                sorted.reverse();
+                sorted
+            }
+            FileOrder::Interleaved => {
+                let (even, odd): (Vec<_>, Vec<_>) =
+                    sorted.into_iter().enumerate().partition(|(i, _)| i % 2 == 0);
+                even.into_iter().chain(odd).map(|(_, path)| path).collect()
+            }
+        }
+    }
+}
+
+/// Параметры одного прогона
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AuditRun {
+    pub threads: usize,
+    pub order: FileOrder,
+}
+
+impl AuditRun {
+    pub fn label(&self) -> String {
+        format!("{} потоков, {:?}", self.threads, self.order)
+    }
+}
+
+/// Настройки аудита
+#[derive(Debug, Clone)]
+pub struct DeterminismAuditConfig {
+    pub runs: Vec<AuditRun>,
+}
+
+impl Default for DeterminismAuditConfig {
+    fn default() -> Self {
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+            .max(2);
+        Self {
+            runs: vec![
+                AuditRun {
+                    threads: 1,
+                    order: FileOrder::Sorted,
+                },
+                AuditRun {
+                    threads,
+                    order: FileOrder::Reversed,
+                },
+            ],
+        }
+    }
+}
+
+/// Результаты одного прогона в каноническом виде: (файл, выражение) → результат
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResolutionSnapshot {
+    entries: BTreeMap<(String, String), String>,
+}
+
+impl ResolutionSnapshot {
+    /// Снять снимок результатов анализа
+    pub fn from_results(results: &[FileAnalysisResult]) -> Self {
+        let mut snapshot = Self::default();
+
+        for result in results {
+            let file = result.file_path.to_string_lossy().replace('\\', "/");
+            if !result.success {
+                snapshot.insert(
+                    &file,
+                    "(анализ)".to_string(),
+                    format!("ошибка: {}", result.error_message.as_deref().unwrap_or("")),
+                );
+                continue;
+            }
+
+            for (name, resolution) in &result.type_context.variables {
+                snapshot.insert(&file, format!("переменная {}", name), canonical(resolution));
+            }
+            for (name, signature) in &result.type_context.functions {
+                snapshot.insert(&file, format!("функция {}", name), canonical(signature));
+            }
+
+            // Порядок диагностик не важен — сравниваем как множество
+            let diagnostics: BTreeSet<String> = result
+                .diagnostics
+                .iter()
+                .map(|d| format!("{}:{} {:?} {}", d.line, d.column, d.severity, d.message))
+                .collect();
+            if !diagnostics.is_empty() {
+                let joined: Vec<_> = diagnostics.into_iter().collect();
+                snapshot.insert(&file, "(диагностики)".to_string(), joined.join("; "));
+            }
+        }
+
+        snapshot
+    }
+
+    /// Добавить результат для выражения
+    pub fn insert(&mut self, file: &str, expression: String, value: String) {
+        self.entries.insert((file.to_string(), expression), value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Каноническое представление: JSON с упорядоченными ключами
+fn canonical<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .map(|v| v.to_string())
+        .unwrap_or_else(|e| format!("<не сериализуется: {}>", e))
+}
+
+/// Выражение, результат которого отличается между прогонами
+#[derive(Debug, Clone, Serialize)]
+pub struct Discrepancy {
+    pub file: String,
+    pub expression: String,
+    pub baseline_run: String,
+    pub baseline_value: String,
+    pub other_run: String,
+    pub other_value: String,
+}
+
+/// Итог аудита
+#[derive(Debug, Clone, Serialize)]
+pub struct DeterminismReport {
+    pub runs: Vec<AuditRun>,
+    pub files: usize,
+    pub expressions_compared: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl DeterminismReport {
+    pub fn is_deterministic(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut output = String::from("🔁 Аудит детерминированности вывода типов\n");
+        for run in &self.runs {
+            output.push_str(&format!("   прогон: {}\n", run.label()));
+        }
+        output.push_str(&format!(
+            "   файлов: {}, выражений сравнено: {}\n",
+            self.files, self.expressions_compared
+        ));
+
+        if self.is_deterministic() {
+            output.push_str("✅ Результаты всех прогонов совпадают\n");
+            return output;
+        }
+
+        output.push_str(&format!("❌ Расхождений: {}\n", self.discrepancies.len()));
+        for d in &self.discrepancies {
+            output.push_str(&format!(
+                "   {} — {}\n      [{}] {}\n      [{}] {}\n",
+                d.file, d.expression, d.baseline_run, d.baseline_value, d.other_run, d.other_value
+            ));
+        }
+        output
+    }
+}
+
+/// Сравнить снимки прогонов с первым (эталонным)
+pub fn compare(snapshots: &[(AuditRun, ResolutionSnapshot)]) -> Vec<Discrepancy> {
+    let Some(((baseline_run, baseline), others)) = snapshots.split_first() else {
+        return Vec::new();
+    };
+
+    let mut discrepancies = Vec::new();
+    for (run, snapshot) in others {
+        let keys: BTreeSet<_> = baseline.entries.keys().chain(snapshot.entries.keys()).collect();
+        for key in keys {
+            let expected = baseline.entries.get(key).map(String::as_str);
+            let actual = snapshot.entries.get(key).map(String::as_str);
+            if expected != actual {
+                discrepancies.push(Discrepancy {
+                    file: key.0.clone(),
+                    expression: key.1.clone(),
+                    baseline_run: baseline_run.label(),
+                    baseline_value: expected.unwrap_or(MISSING).to_string(),
+                    other_run: run.label(),
+                    other_value: actual.unwrap_or(MISSING).to_string(),
+                });
+            }
+        }
+    }
+    discrepancies
+}
+
+/// Выполнить аудит: проанализировать файлы во всех прогонах и сравнить результаты
+pub fn run_audit(files: &[PathBuf], config: &DeterminismAuditConfig) -> Result<DeterminismReport> {
+    // Кеш отключён: иначе второй прогон вернёт результаты первого
+    let analyzer = ParallelAnalyzer::new(ParallelAnalysisConfig {
+        num_threads: None,
+        show_progress: false,
+        use_cache: false,
+        cache_location: None,
+        ..Default::default()
+    })?;
+
+    let mut snapshots = Vec::with_capacity(config.runs.len());
+    for run in &config.runs {
+        // Локальный пул: глобальный пул rayon можно настроить только один раз
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(run.threads)
+            .build()?;
+        let ordered = run.order.apply(files);
+        let batch = pool.install(|| analyzer.analyze_files(ordered))?;
+        snapshots.push((*run, ResolutionSnapshot::from_results(&batch.file_results)));
+    }
+
+    let expressions_compared = snapshots.first().map(|(_, s)| s.len()).unwrap_or(0);
+    Ok(DeterminismReport {
+        runs: config.runs.clone(),
+        files: files.len(),
+        expressions_compared,
+        discrepancies: compare(&snapshots),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_orders() {
+        let files: Vec<PathBuf> = ["a.bsl", "b.bsl", "c.bsl", "d.bsl"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        assert_eq!(FileOrder::Reversed.apply(&files)[0], PathBuf::from("d.bsl"));
+        assert_eq!(
+            FileOrder::Interleaved.apply(&files),
+            ["a.bsl", "c.bsl", "b.bsl", "d.bsl"]
+                .iter()
+                .map(PathBuf::from)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_compare_snapshots() {
+        let runs = DeterminismAuditConfig::default().runs;
+
+        let mut first = ResolutionSnapshot::default();
+        first.insert("m.bsl", "переменная А".to_string(), "Строка".to_string());
+        first.insert("m.bsl", "переменная Б".to_string(), "Число".to_string());
+
+        let mut second = first.clone();
+        assert!(compare(&[(runs[0], first.clone()), (runs[1], second.clone())]).is_empty());
+
+        second.insert("m.bsl", "переменная Б".to_string(), "Строка | Число".to_string());
+        second.insert("m.bsl", "переменная В".to_string(), "Булево".to_string());
+        let discrepancies = compare(&[(runs[0], first), (runs[1], second)]);
+        assert_eq!(discrepancies.len(), 2);
+        assert_eq!(discrepancies[0].expression, "переменная Б");
+        assert_eq!(discrepancies[1].baseline_value, MISSING);
+    }
+}
@@ -0,0 +1,267 @@
+//! Структура модуля (`textDocument/documentSymbol`) для панели структуры
+//! и «хлебных крошек» редактора
+//!
+//! Дерево строится по тексту модуля: области `#Область`/`#КонецОбласти`
+//! (вложенные области — дочерние узлы), процедуры и функции с директивами
+//! компиляции (`&НаСервере`), `Асинх` и `Экспорт`, переменные модуля.
+//! AST парсера BSL не хранит позиций и пропускает инструкции
+//! препроцессора, поэтому методы находятся так же, как в иерархии вызовов
+//! и переименовании — по заголовкам и `КонецПроцедуры`/`КонецФункции`.
+//! Незакрытая область заканчивается в конце модуля.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tower_lsp::lsp_types::*;
+
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::method_header_regex;
+use crate::core::module_variables::module_declarations;
+use crate::core::rename::{declared_method, method_ranges};
+
+fn region_start_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^\s*#\s*(?:область|region)\s+([\p{L}_][\p{L}\p{N}_]*)").unwrap()
+    })
+}
+
+fn region_end_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^\s*#\s*(?:конецобласти|endregion)\b").unwrap())
+}
+
+/// Открытая область: символ без конца диапазона и дочерние узлы
+struct OpenRegion {
+    symbol: DocumentSymbol,
+    children: Vec<DocumentSymbol>,
+}
+
+fn line_end(lines: &[&str], line: usize) -> Position {
+    let length = lines.get(line).map_or(0, |text| text.chars().count());
+    Position::new(line as u32, length as u32)
+}
+
+/// Диапазон имени `name` в строке (с 0, в символах)
+fn name_range(lines: &[&str], line: usize, name: &str) -> Range {
+    let text = lines.get(line).copied().unwrap_or_default();
+    let column = text.find(name).map_or(0, |byte| text[..byte].chars().count()) as u32;
+    let end = column + name.chars().count() as u32;
+    Range::new(
+        Position::new(line as u32, column),
+        Position::new(line as u32, end),
+    )
+}
+
+fn symbol(
+    name: String,
+    detail: Vec<String>,
+    kind: SymbolKind,
+    range: Range,
+    selection_range: Range,
+) -> DocumentSymbol {
+    #[allow(deprecated)]
+    let symbol = DocumentSymbol {
+        name,
+        detail: (!detail.is_empty()).then(|| detail.join(" ")),
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children: None,
+    };
+    symbol
+}
+
+/// Процедура или функция с заголовком в строке `header`; диапазон
+/// начинается с директив компиляции над заголовком
+fn method_symbol(lines: &[&str], header: usize, end: usize) -> Option<DocumentSymbol> {
+    let code = strip_comment(lines[header]);
+    let caps = method_header_regex().captures(code)?;
+    let name = caps.get(2)?;
+    let (_, exported) = declared_method(lines[header])?;
+    let directives: Vec<usize> = (0..header)
+        .rev()
+        .take_while(|&idx| lines[idx].trim_start().starts_with('&'))
+        .collect();
+    let start = directives.last().copied().unwrap_or(header);
+
+    let mut detail: Vec<String> = directives
+        .iter()
+        .rev()
+        .map(|&idx| strip_comment(lines[idx]).trim().to_string())
+        .collect();
+    let keyword = code.trim_start().to_lowercase();
+    if keyword.starts_with("асинх") || keyword.starts_with("async") {
+        detail.push("Асинх".to_string());
+    }
+    if exported {
+        detail.push("Экспорт".to_string());
+    }
+    let keyword = caps[1].to_lowercase();
+    let kind = if keyword == "функция" || keyword == "function" {
+        SymbolKind::FUNCTION
+    } else {
+        SymbolKind::METHOD
+    };
+    let column = code[..name.start()].chars().count() as u32;
+    let selection_range = Range::new(
+        Position::new(header as u32, column),
+        Position::new(header as u32, column + name.as_str().chars().count() as u32),
+    );
+    Some(symbol(
+        name.as_str().to_string(),
+        detail,
+        kind,
+        Range::new(Position::new(start as u32, 0), line_end(lines, end)),
+        selection_range,
+    ))
+}
+
+/// Иерархическая структура модуля: области с вложенными областями,
+/// методами и переменными модуля в порядке текста
+pub fn document_symbols(text: &str) -> Vec<DocumentSymbol> {
+    let lines: Vec<&str> = text.lines().collect();
+    let methods: HashMap<usize, usize> = method_ranges(text)
+        .into_iter()
+        .map(|range| (*range.start() as usize, *range.end() as usize))
+        .collect();
+    let mut variables: HashMap<usize, Vec<_>> = HashMap::new();
+    for variable in module_declarations(text) {
+        variables.entry(variable.line).or_default().push(variable);
+    }
+
+    let mut top: Vec<DocumentSymbol> = Vec::new();
+    let mut open: Vec<OpenRegion> = Vec::new();
+    let close = |mut region: OpenRegion, end: Position| {
+        region.symbol.range.end = end;
+        region.symbol.children = Some(region.children);
+        region.symbol
+    };
+
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = lines[idx];
+        let mut found = Vec::new();
+        if let Some(caps) = region_start_regex().captures(line) {
+            let name = caps[1].to_string();
+            let selection_range = name_range(&lines, idx, &name);
+            open.push(OpenRegion {
+                symbol: symbol(
+                    name,
+                    Vec::new(),
+                    SymbolKind::NAMESPACE,
+                    Range::new(Position::new(idx as u32, 0), line_end(&lines, idx)),
+                    selection_range,
+                ),
+                children: Vec::new(),
+            });
+        } else if region_end_regex().is_match(line) {
+            if let Some(region) = open.pop() {
+                found.push(close(region, line_end(&lines, idx)));
+            }
+        } else if let Some(&end) = methods.get(&idx) {
+            found.extend(method_symbol(&lines, idx, end));
+            idx = end;
+        } else if let Some(declared) = variables.remove(&idx) {
+            for variable in declared {
+                let mut detail: Vec<String> = variable.directive.into_iter().collect();
+                if variable.export {
+                    detail.push("Экспорт".to_string());
+                }
+                let selection_range = name_range(&lines, idx, &variable.name);
+                found.push(symbol(
+                    variable.name,
+                    detail,
+                    SymbolKind::VARIABLE,
+                    Range::new(Position::new(idx as u32, 0), line_end(&lines, idx)),
+                    selection_range,
+                ));
+            }
+        }
+        match open.last_mut() {
+            Some(region) => region.children.extend(found),
+            None => top.extend(found),
+        }
+        idx += 1;
+    }
+
+    // Незакрытые области заканчиваются в конце модуля
+    let end = line_end(&lines, lines.len().saturating_sub(1));
+    while let Some(region) = open.pop() {
+        let closed = close(region, end);
+        match open.last_mut() {
+            Some(parent) => parent.children.push(closed),
+            None => top.push(closed),
+        }
+    }
+    top
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = "Перем мКэш Экспорт;
+
+#Область ПрограммныйИнтерфейс
+
+&НаСервере
+Функция Остаток(Товар) Экспорт
+    Возврат 0;
+КонецФункции
+
+#Область Служебные
+Асинх Процедура Обновить()
+КонецПроцедуры
+#КонецОбласти
+
+#КонецОбласти
+
+Процедура Заполнить()
+КонецПроцедуры
+";
+
+    fn outline(symbols: &[DocumentSymbol]) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|symbol| {
+                let children = symbol.children.as_deref().unwrap_or_default();
+                let nested = outline(children);
+                let detail = symbol.detail.as_deref().unwrap_or_default();
+                if nested.is_empty() {
+                    format!("{} [{}]", symbol.name, detail)
+                } else {
+                    format!("{} {{{}}}", symbol.name, nested.join(", "))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_document_symbols_outline() {
+        let symbols = document_symbols(MODULE);
+        assert_eq!(
+            outline(&symbols),
+            [
+                "мКэш [Экспорт]",
+                "ПрограммныйИнтерфейс {Остаток [&НаСервере Экспорт], \
+                 Служебные {Обновить [Асинх]}}",
+                "Заполнить []",
+            ]
+        );
+
+        let region = &symbols[1];
+        assert_eq!(region.kind, SymbolKind::NAMESPACE);
+        assert_eq!((region.range.start.line, region.range.end.line), (2, 14));
+        let function = &region.children.as_ref().unwrap()[0];
+        assert_eq!(function.kind, SymbolKind::FUNCTION);
+        // Диапазон метода начинается с директивы, выделение — имя
+        assert_eq!((function.range.start.line, function.range.end.line), (4, 7));
+        assert_eq!(
+            function.selection_range,
+            Range::new(Position::new(5, 8), Position::new(5, 15))
+        );
+    }
+}
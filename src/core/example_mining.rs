@@ -0,0 +1,534 @@
+//! Сбор примеров использования платформенных методов из анализируемого проекта
+//!
+//! Модули проекта просматриваются построчно: для каждого вызова метода
+//! (`Объект.Метод(`) и конструктора (`Новый Тип(`) сохраняется короткий
+//! обезличенный фрагмент строки с позицией. Учитываются только методы и
+//! типы платформы: вызовы общих модулей и объектов конфигурации пропускаются.
+//! Вызов относится к типу получателя (`Список = Новый Массив`), а без него —
+//! к единственному типу с таким методом, чтобы примеры одноимённых методов
+//! разных типов не смешивались.
+//! Найденные примеры показываются в документации и hover как «Примеры из
+//! вашего проекта».
+
+use crate::architecture::data::{RawTypeData, TypeSource};
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+use walkdir::WalkDir;
+
+use crate::core::module_structure::is_method_header;
+
+/// Заголовок блока примеров в документации и hover
+pub const PROJECT_EXAMPLES_TITLE: &str = "Примеры из вашего проекта";
+
+/// Сколько примеров хранить на один метод/тип по умолчанию
+pub const DEFAULT_EXAMPLES_PER_MEMBER: usize = 5;
+
+/// Максимальная длина фрагмента (в символах)
+const MAX_SNIPPET_CHARS: usize = 120;
+
+/// Пример использования из проекта
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ProjectExample {
+    /// Путь к модулю относительно корня проекта
+    pub file_path: String,
+    /// Строка (с 1)
+    pub line: usize,
+    /// Колонка начала вызова (с 1, в символах)
+    pub column: usize,
+    /// Обезличенный фрагмент кода
+    pub snippet: String,
+}
+
+/// Корпус примеров, собранных из проекта
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExampleCorpus {
+    /// `тип.метод` (русское имя типа-владельца, в нижнем регистре) → примеры
+    /// вызовов: одноимённые методы разных типов не смешиваются
+    methods: HashMap<String, Vec<ProjectExample>>,
+    /// Русское имя типа (в нижнем регистре) → примеры конструирования
+    constructors: HashMap<String, Vec<ProjectExample>>,
+    /// Уже учтённые фрагменты (чтобы не дублировать одинаковые строки)
+    #[serde(skip)]
+    seen: HashSet<(String, String)>,
+    /// Метод платформы → русские имена типов, которым он принадлежит (всё в
+    /// нижнем регистре)
+    #[serde(skip)]
+    platform_methods: HashMap<String, Vec<String>>,
+    /// Русское или английское имя платформенного типа → русское (в нижнем
+    /// регистре)
+    #[serde(skip)]
+    platform_types: HashMap<String, String>,
+    max_per_member: usize,
+    files_scanned: usize,
+}
+
+/// Вызов `[Получатель].Метод(`: группа 1 — получатель (если это имя), 2 — метод
+pub(crate) fn method_call_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?:([\p{L}_][\p{L}\p{N}_]*)\s*)?\.\s*([\p{L}_][\p{L}\p{N}_]*)\s*\(").unwrap()
+    })
+}
+
+pub(crate) fn constructor_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)(?:^|[^\p{L}\p{N}_])(?:Новый|New)\s+([\p{L}_][\p{L}\p{N}_]*)").unwrap()
+    })
+}
+
+fn assignment_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // `Имя = Новый Тип` (группа 2) или любое другое присваивание
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)^\s*([\p{L}_][\p{L}\p{N}_]*)\s*=\s*(?:(?:Новый|New)\s+([\p{L}_][\p{L}\p{N}_]*))?",
+        )
+        .unwrap()
+    })
+}
+
+/// Типы переменных по присваиваниям `Имя = Новый Тип` (имена в нижнем
+/// регистре): по ним получатель вызова сопоставляется с типом-владельцем
+/// метода. Сбрасываются в начале каждого метода
+#[derive(Debug, Clone, Default)]
+pub struct ReceiverTypes {
+    types: HashMap<String, String>,
+}
+
+impl ReceiverTypes {
+    /// Типы переменных после строк `lines`
+    pub fn scan<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut receivers = Self::default();
+        for line in lines {
+            receivers.observe(strip_comment(line));
+        }
+        receivers
+    }
+
+    /// Учесть строку кода (без комментария)
+    pub fn observe(&mut self, code: &str) {
+        if is_method_header(code) {
+            self.types.clear();
+            return;
+        }
+        let Some(caps) = assignment_regex().captures(code) else {
+            return;
+        };
+        let variable = caps[1].to_lowercase();
+        match caps.get(2) {
+            Some(type_name) => {
+                self.types
+                    .insert(variable, type_name.as_str().to_lowercase());
+            }
+            None => {
+                self.types.remove(&variable);
+            }
+        }
+    }
+
+    /// Тип переменной `receiver` (в нижнем регистре, как записан после `Новый`)
+    pub fn type_of(&self, receiver: &str) -> Option<&str> {
+        self.types.get(&receiver.to_lowercase()).map(String::as_str)
+    }
+}
+
+impl ExampleCorpus {
+    /// Создать корпус; примеры собираются только для платформенных типов каталога
+    pub fn new(catalog: &[RawTypeData], max_per_member: usize) -> Self {
+        let mut platform_methods: HashMap<String, Vec<String>> = HashMap::new();
+        let mut platform_types = HashMap::new();
+        for raw in catalog
+            .iter()
+            .filter(|raw| matches!(raw.source, TypeSource::Platform { .. }))
+        {
+            let owner = raw.russian_name.to_lowercase();
+            for name in [&raw.russian_name, &raw.english_name] {
+                if !name.is_empty() {
+                    platform_types.insert(name.to_lowercase(), owner.clone());
+                }
+            }
+            for method in &raw.methods {
+                let owners = platform_methods
+                    .entry(method.name.to_lowercase())
+                    .or_default();
+                if !owners.contains(&owner) {
+                    owners.push(owner.clone());
+                }
+            }
+        }
+
+        Self {
+            max_per_member,
+            platform_methods,
+            platform_types,
+            ..Default::default()
+        }
+    }
+
+    /// Собрать примеры из всех `.bsl` модулей каталога проекта
+    pub fn mine_directory(
+        root: &Path,
+        catalog: &[RawTypeData],
+        max_per_member: usize,
+    ) -> Result<Self> {
+        let mut corpus = Self::new(catalog, max_per_member);
+
+        for entry in WalkDir::new(root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let path = entry.path();
+            if !crate::core::fs_utils::is_bsl_file(path) {
+                continue;
+            }
+
+            let Ok(text) = crate::core::fs_utils::read_bsl_file(path) else {
+                continue;
+            };
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            corpus.add_source(&relative.to_string_lossy(), &text);
+        }
+
+        Ok(corpus)
+    }
+
+    /// Добавить примеры из текста одного модуля
+    pub fn add_source(&mut self, file_path: &str, text: &str) {
+        self.files_scanned += 1;
+
+        let mut receivers = ReceiverTypes::default();
+        for (idx, raw_line) in text.lines().enumerate() {
+            let code = strip_comment(raw_line);
+            if code.trim().is_empty() {
+                continue;
+            }
+            receivers.observe(code);
+            let snippet = anonymize(code);
+
+            for caps in method_call_regex().captures_iter(code) {
+                let name = caps.get(2).unwrap();
+                let receiver_type = caps
+                    .get(1)
+                    .and_then(|receiver| receivers.type_of(receiver.as_str()));
+                let Some(owner) = self.method_owner(receiver_type, name.as_str()) else {
+                    continue;
+                };
+                let key = format!("{}.{}", owner, name.as_str().to_lowercase());
+                let column = code[..name.start()].chars().count() + 1;
+                self.push(true, key, file_path, idx + 1, column, &snippet);
+            }
+            for caps in constructor_regex().captures_iter(code) {
+                let name = caps.get(1).unwrap();
+                let Some(key) = self.platform_types.get(&name.as_str().to_lowercase()) else {
+                    continue;
+                };
+                let key = key.clone();
+                let column = code[..name.start()].chars().count() + 1;
+                self.push(false, key, file_path, idx + 1, column, &snippet);
+            }
+        }
+    }
+
+    /// Платформенный тип-владелец вызванного метода (русское имя в нижнем
+    /// регистре): тип получателя, если он известен, иначе единственный тип
+    /// каталога с таким методом
+    fn method_owner(&self, receiver_type: Option<&str>, method: &str) -> Option<String> {
+        let owners = self.platform_methods.get(&method.to_lowercase())?;
+        match receiver_type {
+            Some(receiver_type) => {
+                let owner = self.platform_types.get(&receiver_type.to_lowercase())?;
+                owners.contains(owner).then(|| owner.clone())
+            }
+            None => match owners.as_slice() {
+                [owner] => Some(owner.clone()),
+                _ => None,
+            },
+        }
+    }
+
+    fn push(
+        &mut self,
+        is_method: bool,
+        key: String,
+        file_path: &str,
+        line: usize,
+        column: usize,
+        snippet: &str,
+    ) {
+        let seen_key = (
+            format!("{}:{}", if is_method { "m" } else { "c" }, key),
+            snippet.to_string(),
+        );
+        if self.seen.contains(&seen_key) {
+            return;
+        }
+
+        let map = if is_method {
+            &mut self.methods
+        } else {
+            &mut self.constructors
+        };
+        let examples = map.entry(key).or_default();
+        if examples.len() >= self.max_per_member {
+            return;
+        }
+
+        examples.push(ProjectExample {
+            file_path: file_path.replace('\\', "/"),
+            line,
+            column,
+            snippet: snippet.to_string(),
+        });
+        self.seen.insert(seen_key);
+    }
+
+    /// Примеры вызова метода типа `owner_type` (имена без учёта регистра,
+    /// тип — русским или английским именем)
+    pub fn examples_for_method(&self, owner_type: &str, method_name: &str) -> &[ProjectExample] {
+        self.platform_types
+            .get(&owner_type.to_lowercase())
+            .and_then(|owner| {
+                self.methods
+                    .get(&format!("{}.{}", owner, method_name.to_lowercase()))
+            })
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Примеры вызова метода у получателя типа `receiver_type` (если тип
+    /// неизвестен — у единственного типа с таким методом)
+    pub fn examples_for_call(
+        &self,
+        receiver_type: Option<&str>,
+        method_name: &str,
+    ) -> &[ProjectExample] {
+        match self.method_owner(receiver_type, method_name) {
+            Some(owner) => self.examples_for_method(&owner, method_name),
+            None => &[],
+        }
+    }
+
+    /// Примеры создания объекта типа через `Новый`
+    pub fn examples_for_type(&self, type_name: &str) -> &[ProjectExample] {
+        self.platform_types
+            .get(&type_name.to_lowercase())
+            .and_then(|owner| self.constructors.get(owner))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Количество просмотренных модулей
+    pub fn files_scanned(&self) -> usize {
+        self.files_scanned
+    }
+
+    /// Количество методов и типов, для которых найдены примеры
+    pub fn members_count(&self) -> usize {
+        self.methods.len() + self.constructors.len()
+    }
+
+    /// Markdown блок для hover/документации (None, если примеров нет)
+    pub fn render_markdown(examples: &[ProjectExample]) -> Option<String> {
+        if examples.is_empty() {
+            return None;
+        }
+
+        let mut out = format!("**{}:**\n", PROJECT_EXAMPLES_TITLE);
+        for example in examples {
+            out.push_str(&format!(
+                "\n`{}:{}:{}`\n```bsl\n{}\n```\n",
+                example.file_path, example.line, example.column, example.snippet
+            ));
+        }
+        Some(out)
+    }
+}
+
+/// Отрезать однострочный комментарий (с учётом строковых литералов)
+pub(crate) fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut prev_slash = false;
+    for (idx, ch) in line.char_indices() {
+        match ch {
+            '"' => {
+                in_string = !in_string;
+                prev_slash = false;
+            }
+            '/' if !in_string => {
+                if prev_slash {
+                    return &line[..idx - 1];
+                }
+                prev_slash = true;
+            }
+            _ => prev_slash = false,
+        }
+    }
+    line
+}
+
+/// Обезличить фрагмент: содержимое строковых литералов заменяется на "...",
+/// лишние пробелы схлопываются, длина ограничивается
+fn anonymize(code: &str) -> String {
+    let mut out = String::with_capacity(code.len());
+    let mut in_string = false;
+    for ch in code.trim().chars() {
+        if ch == '"' {
+            out.push_str(if in_string { "\"" } else { "\"..." });
+            in_string = !in_string;
+            continue;
+        }
+        if in_string {
+            continue;
+        }
+        if ch.is_whitespace() {
+            if !out.ends_with(' ') {
+                out.push(' ');
+            }
+        } else {
+            out.push(ch);
+        }
+    }
+
+    if out.chars().count() > MAX_SNIPPET_CHARS {
+        let truncated: String = out.chars().take(MAX_SNIPPET_CHARS - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::architecture::data::RawMethodData;
+
+    const MODULE: &str = r#"
+Процедура Тест()
+    Список = Новый Массив;
+    Список.Добавить("секретный пароль"); // комментарий
+    Запрос = Новый Запрос("ВЫБРАТЬ 1");
+    Список.Добавить(1);
+    Товары = Справочники.Товары.Выбрать();
+    Запись = Новый СправочникСсылка.Товары;
+КонецПроцедуры
+"#;
+
+    fn catalog() -> Vec<RawTypeData> {
+        let platform = TypeSource::Platform {
+            version: "8.3.25".to_string(),
+        };
+        vec![
+            RawTypeData {
+                methods: vec![RawMethodData::test_method("Добавить")],
+                english_name: "Array".to_string(),
+                ..RawTypeData::test_type("Массив", platform.clone())
+            },
+            RawTypeData::test_type("Запрос", platform),
+            RawTypeData {
+                methods: vec![RawMethodData::test_method("Выбрать")],
+                ..RawTypeData::test_type(
+                    "СправочникСсылка",
+                    TypeSource::Configuration {
+                        config_version: "1.0".to_string(),
+                    },
+                )
+            },
+        ]
+    }
+
+    #[test]
+    fn test_mine_method_calls_and_constructors() {
+        let mut corpus = ExampleCorpus::new(&catalog(), DEFAULT_EXAMPLES_PER_MEMBER);
+        corpus.add_source("CommonModules/Тест/Ext/Module.bsl", MODULE);
+
+        let examples = corpus.examples_for_method("Массив", "добавить");
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].line, 4);
+        assert_eq!(examples[0].column, 12);
+        assert_eq!(examples[0].snippet, "Список.Добавить(\"...\");");
+        assert!(!examples[0].snippet.contains("пароль"));
+
+        assert_eq!(corpus.examples_for_type("Массив").len(), 1);
+        assert_eq!(
+            corpus.examples_for_type("запрос")[0].snippet,
+            "Запрос = Новый Запрос(\"...\");"
+        );
+    }
+
+    #[test]
+    fn test_examples_limit_and_markdown() {
+        let mut corpus = ExampleCorpus::new(&catalog(), 1);
+        corpus.add_source("Module.bsl", MODULE);
+        assert_eq!(corpus.examples_for_method("Array", "Добавить").len(), 1);
+
+        let markdown =
+            ExampleCorpus::render_markdown(corpus.examples_for_method("Array", "Добавить"))
+                .unwrap();
+        assert!(markdown.contains(PROJECT_EXAMPLES_TITLE));
+        assert!(markdown.contains("Module.bsl:4:12"));
+        assert!(ExampleCorpus::render_markdown(&[]).is_none());
+    }
+
+    #[test]
+    fn test_only_platform_members_mined() {
+        let mut corpus = ExampleCorpus::new(&catalog(), DEFAULT_EXAMPLES_PER_MEMBER);
+        corpus.add_source("Module.bsl", MODULE);
+
+        // Метод и тип конфигурации не попадают в корпус
+        assert!(corpus
+            .examples_for_method("СправочникСсылка", "Выбрать")
+            .is_empty());
+        assert!(corpus.examples_for_type("СправочникСсылка").is_empty());
+        // Тип без методов в каталоге всё равно учитывается для конструкторов
+        assert_eq!(corpus.examples_for_type("Запрос").len(), 1);
+        assert_eq!(corpus.members_count(), 3);
+
+        // Пустой каталог — примеров нет
+        let mut empty = ExampleCorpus::new(&[], DEFAULT_EXAMPLES_PER_MEMBER);
+        empty.add_source("Module.bsl", MODULE);
+        assert_eq!(empty.members_count(), 0);
+        assert_eq!(empty.files_scanned(), 1);
+    }
+
+    #[test]
+    fn test_examples_keyed_by_owner_type() {
+        let platform = TypeSource::Platform {
+            version: "8.3.25".to_string(),
+        };
+        let catalog = vec![
+            RawTypeData {
+                methods: vec![RawMethodData::test_method("Добавить")],
+                ..RawTypeData::test_type("Массив", platform.clone())
+            },
+            RawTypeData {
+                methods: vec![RawMethodData::test_method("Добавить")],
+                ..RawTypeData::test_type("СписокЗначений", platform)
+            },
+        ];
+        let mut corpus = ExampleCorpus::new(&catalog, DEFAULT_EXAMPLES_PER_MEMBER);
+        corpus.add_source(
+            "Module.bsl",
+            "Список = Новый СписокЗначений;\nСписок.Добавить(1);\nНеизвестный.Добавить(2);\n",
+        );
+
+        // Вызов относится к типу получателя; получатель неизвестного типа
+        // при одноимённых методах не учитывается
+        let examples = corpus.examples_for_method("СписокЗначений", "Добавить");
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].line, 2);
+        assert!(corpus.examples_for_method("Массив", "Добавить").is_empty());
+        assert_eq!(
+            corpus
+                .examples_for_call(Some("СписокЗначений"), "Добавить")
+                .len(),
+            1
+        );
+        assert!(corpus.examples_for_call(None, "Добавить").is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+//! Ожидаемый тип в позиции автодополнения
+//!
+//! Если курсор стоит в правой части присваивания или в аргументе вызова
+//! метода модуля, тип, который ожидается в этом месте, известен из контекста
+//! вывода типов: это тип переменной слева от `=` или тип соответствующего
+//! параметра. По нему автодополнение поднимает выше подходящие варианты.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::core::type_checker::TypeContext;
+use crate::domain::types::{Certainty, TypeResolution};
+
+fn assignment_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*([\p{L}_][\p{L}\p{N}_]*)\s*=\s*$").unwrap())
+}
+
+fn callee_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"([\p{L}_][\p{L}\p{N}_]*)\s*$").unwrap())
+}
+
+/// Ожидаемый тип в позиции (строка и символ с 0).
+///
+/// Возвращает `None`, если позиция не в правой части присваивания и не в
+/// аргументе известного метода либо если ожидаемый тип не выведен.
+pub fn expected_type_at(
+    text: &str,
+    line: u32,
+    character: u32,
+    context: &TypeContext,
+) -> Option<TypeResolution> {
+    let line_text = text.lines().nth(line as usize)?;
+    let before: String = line_text.chars().take(character as usize).collect();
+    // Отбрасываем уже набранный префикс дополнения
+    let before = before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_' || c == '.');
+
+    let expected = match open_call(before) {
+        Some((callee, argument)) => {
+            let (_, signature) = context
+                .functions
+                .iter()
+                .find(|(name, _)| name.to_lowercase() == callee.to_lowercase())?;
+            signature.params.get(argument)?.1.clone()
+        }
+        None => {
+            let target = assignment_regex().captures(before)?.get(1)?.as_str().to_lowercase();
+            context
+                .variables
+                .iter()
+                .find(|(name, _)| name.to_lowercase() == target)?
+                .1
+                .clone()
+        }
+    };
+
+    (!matches!(expected.certainty, Certainty::Unknown)).then_some(expected)
+}
+
+/// Незакрытый вызов перед курсором: имя метода и номер аргумента
+fn open_call(before: &str) -> Option<(String, usize)> {
+    // (позиция открывающей скобки, число запятых на её уровне)
+    let mut calls: Vec<(usize, usize)> = Vec::new();
+    let mut in_string = false;
+    for (index, c) in before.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '/' if before[index..].starts_with("//") => break,
+            '(' => calls.push((index, 0)),
+            ')' => {
+                calls.pop();
+            }
+            ',' => {
+                if let Some(call) = calls.last_mut() {
+                    call.1 += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let (paren, argument) = *calls.last()?;
+    let callee = callee_regex().captures(&before[..paren])?.get(1)?.as_str();
+    Some((callee.to_string(), argument))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::dependency_graph::Scope;
+    use crate::core::standard_types::primitive_type;
+    use crate::core::type_checker::FunctionSignature;
+    use crate::domain::types::PrimitiveType;
+    use std::collections::HashMap;
+
+    fn context() -> TypeContext {
+        let mut variables = HashMap::new();
+        variables.insert("Итог".to_string(), primitive_type(PrimitiveType::Number));
+        variables.insert("Данные".to_string(), TypeResolution::unknown());
+        let mut functions = HashMap::new();
+        functions.insert(
+            "Записать".to_string(),
+            FunctionSignature {
+                params: vec![
+                    ("Путь".to_string(), primitive_type(PrimitiveType::String)),
+                    ("Перезаписать".to_string(), primitive_type(PrimitiveType::Boolean)),
+                ],
+                return_type: TypeResolution::unknown(),
+                exported: false,
+            },
+        );
+        TypeContext {
+            variables,
+            functions,
+            current_scope: Scope::Global,
+            scope_stack: vec![],
+            active_contexts: crate::parsing::bsl::ContextSet::ALL,
+        }
+    }
+
+    #[test]
+    fn test_expected_type_of_assignment() {
+        let ctx = context();
+        let text = "    итог = Сум";
+        let expected = expected_type_at(text, 0, 14, &ctx).unwrap();
+        assert_eq!(expected, primitive_type(PrimitiveType::Number));
+
+        // Тип переменной не выведен
+        assert!(expected_type_at("Данные = ", 0, 9, &ctx).is_none());
+        // Сравнение в условии — не присваивание
+        assert!(expected_type_at("Если Итог = ", 0, 12, &ctx).is_none());
+    }
+
+    #[test]
+    fn test_expected_type_of_argument() {
+        let ctx = context();
+        let text = "Записать(\"a,b\", Ист";
+        let expected = expected_type_at(text, 0, 19, &ctx).unwrap();
+        assert_eq!(expected, primitive_type(PrimitiveType::Boolean));
+
+        let text = "Записать(Стр(1, 2), ";
+        let expected = expected_type_at(text, 0, 20, &ctx).unwrap();
+        assert_eq!(expected, primitive_type(PrimitiveType::Boolean));
+
+        // Вложенный вызов неизвестного метода
+        assert!(expected_type_at("Записать(Формат(", 0, 16, &ctx).is_none());
+    }
+}
@@ -0,0 +1,497 @@
+//! Рефакторинг «Извлечь процедуру»
+//!
+//! Выделенные строки метода переносятся в новую процедуру или функцию,
+//! которая вставляется после исходного метода. Параметрами становятся
+//! переменные, определённые до выделения и читаемые в нём; если ровно одна
+//! переменная, присвоенная в выделении, используется после него, она
+//! возвращается из функции. Несколько таких переменных передаются
+//! параметрами — в BSL параметры по умолчанию передаются по ссылке.
+//!
+//! Типы параметров и возвращаемого значения берутся из контекста вывода
+//! типов и записываются в стандартный комментарий (`Параметры:`,
+//! `Возвращаемое значение:`).
+
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use tower_lsp::lsp_types::*;
+
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::{is_method_end, is_method_header, method_header};
+use crate::core::type_checker::TypeContext;
+use crate::domain::types::PrimitiveType;
+use crate::domain::types::{ConcreteType, ResolutionResult, TypeResolution};
+
+/// Тип параметра, если вывести его не удалось
+const UNKNOWN_TYPE: &str = "Произвольный";
+
+/// Ключевые слова BSL в нижнем регистре
+pub(crate) const KEYWORDS: &[&str] = &[
+    "если", "тогда", "иначеесли", "иначе", "конецесли", "для", "каждого", "из", "по", "цикл",
+    "конеццикла", "пока", "возврат", "продолжить", "прервать", "попытка", "исключение",
+    "конецпопытки", "вызватьисключение", "новый", "и", "или", "не", "истина", "ложь",
+    "неопределено", "null", "перем", "знач", "экспорт", "процедура", "функция",
+    "конецпроцедуры", "конецфункции", "асинх", "ждать", "перейти", "if", "then", "elsif",
+    "else", "endif", "for", "each", "in", "to", "do", "enddo", "while", "return", "continue",
+    "break", "try", "except", "endtry", "raise", "new", "and", "or", "not", "true", "false",
+    "undefined", "var", "val", "export", "procedure", "function", "endprocedure",
+    "endfunction", "async", "await", "goto",
+];
+
+/// Параметр или возвращаемое значение извлечённой процедуры
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedValue {
+    pub name: String,
+    /// Тип в нотации комментариев 1С (`Строка`, `Массив`, `Строка, Число`)
+    pub type_name: String,
+}
+
+/// План извлечения: что вставить вместо выделения и после метода
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedProcedure {
+    pub name: String,
+    pub parameters: Vec<ExtractedValue>,
+    pub returns: Option<ExtractedValue>,
+    /// Строка вызова (с отступом) вместо выделенных строк
+    pub call_text: String,
+    /// Текст нового метода, включая комментарий
+    pub definition_text: String,
+    /// Строка (с 0), после которой вставляется новый метод
+    pub insert_after_line: usize,
+}
+
+fn identifier_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[\p{L}_][\p{L}\p{N}_]*").unwrap())
+}
+
+fn assignment_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // `Имя = ...` в начале оператора и переменные циклов `Для [Каждого] Имя`
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)(?:^|;)\s*(?:(?:для|for)\s+(?:(?:каждого|each)\s+)?)?([\p{L}_][\p{L}\p{N}_]*)\s*(?:=|\s(?:из|in)\s)",
+        )
+        .unwrap()
+    })
+}
+
+/// Код строки без комментария и содержимого строковых литералов
+fn code_of(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut in_string = false;
+    for ch in strip_comment(line).chars() {
+        if ch == '"' {
+            in_string = !in_string;
+            out.push(ch);
+        } else if !in_string {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Переменные, читаемые в коде: идентификаторы без ключевых слов, вызовов и
+/// обращений к членам (`Объект.Член`)
+fn variables_in(code: &str) -> Vec<String> {
+    identifier_regex()
+        .find_iter(code)
+        .filter(|m| {
+            let before = code[..m.start()].trim_end();
+            let after = code[m.end()..].trim_start();
+            !before.ends_with('.')
+                && !after.starts_with('(')
+                && !KEYWORDS.contains(&m.as_str().to_lowercase().as_str())
+        })
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+fn assigned_in(code: &str) -> Vec<String> {
+    assignment_regex()
+        .captures_iter(code)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| !KEYWORDS.contains(&name.to_lowercase().as_str()))
+        .collect()
+}
+
+/// Баланс блочных конструкций: выделение не должно разрывать `Если`, циклы и `Попытка`
+fn block_balance(code: &str) -> i32 {
+    let mut balance = 0;
+    for word in identifier_regex().find_iter(code) {
+        match word.as_str().to_lowercase().as_str() {
+            "если" | "if" | "цикл" | "do" | "попытка" | "try" => balance += 1,
+            "конецесли" | "endif" | "конеццикла" | "enddo" | "конецпопытки" | "endtry" => {
+                balance -= 1
+            }
+            _ => {}
+        }
+    }
+    balance
+}
+
+/// Тип в нотации комментариев 1С
+fn type_name_of(resolution: &TypeResolution) -> String {
+    fn concrete_name(concrete: &ConcreteType) -> Option<String> {
+        match concrete {
+            ConcreteType::Primitive(primitive) => Some(
+                match primitive {
+                    PrimitiveType::String => "Строка",
+                    PrimitiveType::Number => "Число",
+                    PrimitiveType::Boolean => "Булево",
+                    PrimitiveType::Date => "Дата",
+                }
+                .to_string(),
+            ),
+            ConcreteType::Platform(platform) => Some(platform.name.clone()),
+            _ => None,
+        }
+    }
+
+    let names: Option<Vec<String>> = match &resolution.result {
+        ResolutionResult::Concrete(concrete) => concrete_name(concrete).map(|name| vec![name]),
+        ResolutionResult::Union(types) => types.iter().map(|wt| concrete_name(&wt.type_)).collect(),
+        _ => None,
+    };
+    names
+        .filter(|names| !names.is_empty())
+        .map(|names| names.join(", "))
+        .unwrap_or_else(|| UNKNOWN_TYPE.to_string())
+}
+
+fn lookup_type(context: Option<&TypeContext>, name: &str) -> String {
+    let lower = name.to_lowercase();
+    context
+        .and_then(|ctx| {
+            ctx.variables
+                .iter()
+                .find(|(var, _)| var.to_lowercase() == lower)
+                .map(|(_, resolution)| type_name_of(resolution))
+        })
+        .unwrap_or_else(|| UNKNOWN_TYPE.to_string())
+}
+
+/// Добавить имя, если его ещё нет (без учёта регистра), сохраняя порядок
+fn push_unique(names: &mut Vec<String>, seen: &mut HashSet<String>, name: String) {
+    if seen.insert(name.to_lowercase()) {
+        names.push(name);
+    }
+}
+
+/// Свободное имя вида `НоваяПроцедура`, `НоваяПроцедура1`, ...
+fn unique_method_name(text: &str, base: &str) -> String {
+    let existing: HashSet<String> = text
+        .lines()
+        .filter_map(|line| method_header(line))
+        .map(|header| header.name.to_lowercase())
+        .collect();
+    (0..)
+        .map(|n| if n == 0 { base.to_string() } else { format!("{}{}", base, n) })
+        .find(|name| !existing.contains(&name.to_lowercase()))
+        .unwrap_or_else(|| base.to_string())
+}
+
+/// Спланировать извлечение строк `start_line..=end_line` (с 0)
+pub fn plan_extraction(
+    text: &str,
+    start_line: usize,
+    end_line: usize,
+    type_context: Option<&TypeContext>,
+) -> Option<ExtractedProcedure> {
+    let lines: Vec<&str> = text.lines().collect();
+    if start_line > end_line || end_line >= lines.len() {
+        return None;
+    }
+
+    // Границы метода, внутри которого сделано выделение
+    let header = (0..=start_line)
+        .rev()
+        .find(|&idx| is_method_header(lines[idx]))?;
+    let method_end = (end_line..lines.len()).find(|&idx| is_method_end(lines[idx]))?;
+    if header >= start_line || (header + 1..start_line).any(|idx| is_method_end(lines[idx])) {
+        return None;
+    }
+
+    let selection: Vec<String> = lines[start_line..=end_line].iter().map(|l| code_of(l)).collect();
+    if selection.iter().all(|code| code.trim().is_empty()) {
+        return None;
+    }
+    let selection_code = selection.join("\n");
+    if block_balance(&selection_code) != 0 {
+        return None;
+    }
+    // Возврат внутри выделения изменил бы поток управления исходного метода
+    if identifier_regex()
+        .find_iter(&selection_code)
+        .any(|w| matches!(w.as_str().to_lowercase().as_str(), "возврат" | "return"))
+    {
+        return None;
+    }
+
+    // Переменные, известные до выделения: параметры метода и присвоенные выше
+    let mut defined_before: HashMap<String, String> = HashMap::new();
+    let header_code = code_of(lines[header]);
+    if let Some(header) = method_header(&header_code) {
+        for param in header.parameters.split(',') {
+            let name = param.split('=').next().unwrap_or("").trim();
+            let name = name
+                .strip_prefix("Знач ")
+                .or_else(|| name.strip_prefix("Val "))
+                .unwrap_or(name)
+                .trim();
+            if !name.is_empty() {
+                defined_before.insert(name.to_lowercase(), name.to_string());
+            }
+        }
+    }
+    for line in &lines[header + 1..start_line] {
+        for name in assigned_in(&code_of(line)) {
+            defined_before.insert(name.to_lowercase(), name);
+        }
+    }
+
+    let used_after: HashSet<String> = lines[end_line + 1..method_end]
+        .iter()
+        .flat_map(|line| variables_in(&code_of(line)))
+        .map(|name| name.to_lowercase())
+        .collect();
+
+    let mut parameters = Vec::new();
+    let mut seen_params = HashSet::new();
+    let mut assigned = Vec::new();
+    let mut seen_assigned = HashSet::new();
+    for code in &selection {
+        for name in variables_in(code) {
+            if let Some(original) = defined_before.get(&name.to_lowercase()) {
+                push_unique(&mut parameters, &mut seen_params, original.clone());
+            }
+        }
+        for name in assigned_in(code) {
+            push_unique(&mut assigned, &mut seen_assigned, name);
+        }
+    }
+
+    let outputs: Vec<String> = assigned
+        .into_iter()
+        .filter(|name| used_after.contains(&name.to_lowercase()))
+        .collect();
+    let returns = match outputs.as_slice() {
+        [single] if !seen_params.contains(&single.to_lowercase()) => Some(single.clone()),
+        _ => {
+            // Несколько результатов возвращаются через параметры-ссылки
+            for name in outputs {
+                push_unique(&mut parameters, &mut seen_params, name);
+            }
+            None
+        }
+    };
+
+    let is_function = returns.is_some();
+    let name = unique_method_name(
+        text,
+        if is_function { "НоваяФункция" } else { "НоваяПроцедура" },
+    );
+    let parameters: Vec<ExtractedValue> = parameters
+        .into_iter()
+        .map(|name| ExtractedValue {
+            type_name: lookup_type(type_context, &name),
+            name,
+        })
+        .collect();
+    let returns = returns.map(|name| ExtractedValue {
+        type_name: lookup_type(type_context, &name),
+        name,
+    });
+
+    let indent: String = lines[start_line]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+    let args = parameters
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let call_text = match &returns {
+        Some(value) => format!("{}{} = {}({});", indent, value.name, name, args),
+        None => format!("{}{}({});", indent, name, args),
+    };
+
+    // Директивы компиляции исходного метода (`&НаСервере` и т.п.)
+    let directives: Vec<&str> = lines[..header]
+        .iter()
+        .rev()
+        .take_while(|l| l.trim_start().starts_with('&'))
+        .map(|l| l.trim())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+
+    let mut definition = String::from("\n// Извлечено из метода.\n");
+    if !parameters.is_empty() {
+        definition.push_str("//\n// Параметры:\n");
+        for param in &parameters {
+            definition.push_str(&format!("//   {} - {} -\n", param.name, param.type_name));
+        }
+    }
+    if let Some(value) = &returns {
+        definition.push_str("//\n// Возвращаемое значение:\n");
+        definition.push_str(&format!("//   {} -\n", value.type_name));
+    }
+    definition.push_str("//\n");
+    for directive in directives {
+        definition.push_str(directive);
+        definition.push('\n');
+    }
+    let (keyword, end_keyword) = if is_function {
+        ("Функция", "КонецФункции")
+    } else {
+        ("Процедура", "КонецПроцедуры")
+    };
+    definition.push_str(&format!("{} {}({})\n", keyword, name, args));
+
+    let body_indent = indent.len().min(
+        lines[start_line..=end_line]
+            .iter()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.len() - l.trim_start().len())
+            .min()
+            .unwrap_or(0),
+    );
+    for line in &lines[start_line..=end_line] {
+        let trimmed = line.get(body_indent..).unwrap_or_else(|| line.trim_start());
+        if trimmed.trim().is_empty() {
+            definition.push('\n');
+        } else {
+            definition.push_str(&format!("    {}\n", trimmed.trim_end()));
+        }
+    }
+    if let Some(value) = &returns {
+        definition.push_str(&format!("\n    Возврат {};\n", value.name));
+    }
+    definition.push_str(end_keyword);
+    definition.push('\n');
+
+    Some(ExtractedProcedure {
+        name,
+        parameters,
+        returns,
+        call_text,
+        definition_text: definition,
+        insert_after_line: method_end,
+    })
+}
+
+/// Code action «Извлечь процедуру» для выделенного диапазона строк
+pub fn extract_procedure_action(
+    uri: &Url,
+    text: &str,
+    range: Range,
+    type_context: Option<&TypeContext>,
+) -> Option<CodeActionOrCommand> {
+    // Выделение до начала строки не включает эту строку
+    let end_line = if range.end.character == 0 && range.end.line > range.start.line {
+        range.end.line - 1
+    } else {
+        range.end.line
+    };
+    let plan = plan_extraction(
+        text,
+        range.start.line as usize,
+        end_line as usize,
+        type_context,
+    )?;
+
+    let line_end = |line: u32| Position::new(line, u32::MAX);
+    let insert_at = Position::new(plan.insert_after_line as u32 + 1, 0);
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![
+            TextEdit {
+                range: Range::new(Position::new(range.start.line, 0), line_end(end_line)),
+                new_text: plan.call_text.clone(),
+            },
+            TextEdit {
+                range: Range::new(insert_at, insert_at),
+                new_text: plan.definition_text.clone(),
+            },
+        ],
+    );
+
+    let kind = if plan.returns.is_some() { "функцию" } else { "процедуру" };
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: format!("Извлечь в {} {}", kind, plan.name),
+        kind: Some(CodeActionKind::REFACTOR_EXTRACT),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::dependency_graph::Scope;
+    use crate::core::standard_types::primitive_type;
+
+    const MODULE: &str = r#"&НаСервере
+Процедура Заполнить(Документ)
+    Сумма = 0;
+    Ставка = 20;
+    Для Каждого Строка Из Документ.Товары Цикл
+        Сумма = Сумма + Строка.Сумма * Ставка / 100;
+    КонецЦикла;
+    Сообщить(Сумма);
+КонецПроцедуры
+"#;
+
+    fn context() -> TypeContext {
+        let mut variables = HashMap::new();
+        variables.insert("Сумма".to_string(), primitive_type(PrimitiveType::Number));
+        variables.insert("Ставка".to_string(), primitive_type(PrimitiveType::Number));
+        TypeContext {
+            variables,
+            functions: HashMap::new(),
+            current_scope: Scope::Global,
+            scope_stack: vec![],
+            active_contexts: crate::parsing::bsl::ContextSet::ALL,
+        }
+    }
+
+    #[test]
+    fn test_extract_loop_into_procedure_with_typed_parameters() {
+        let plan = plan_extraction(MODULE, 4, 6, Some(&context())).unwrap();
+
+        let names: Vec<_> = plan.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Документ", "Сумма", "Ставка"]);
+        assert_eq!(plan.parameters[1].type_name, "Число");
+        assert_eq!(plan.parameters[0].type_name, UNKNOWN_TYPE);
+        assert!(plan.returns.is_none());
+        assert_eq!(plan.call_text, "    НоваяПроцедура(Документ, Сумма, Ставка);");
+        assert!(plan.definition_text.contains("//   Ставка - Число -\n"));
+        assert!(plan.definition_text.contains("&НаСервере\nПроцедура НоваяПроцедура("));
+        assert!(plan.definition_text.contains("    Для Каждого Строка Из Документ.Товары Цикл\n"));
+        assert_eq!(plan.insert_after_line, 8);
+    }
+
+    #[test]
+    fn test_extract_single_result_into_function() {
+        let plan = plan_extraction(MODULE, 2, 2, Some(&context())).unwrap();
+        assert_eq!(plan.returns.as_ref().unwrap().name, "Сумма");
+        assert_eq!(plan.call_text, "    Сумма = НоваяФункция();");
+        assert!(plan.definition_text.contains("// Возвращаемое значение:\n//   Число -\n"));
+        assert!(plan.definition_text.contains("Возврат Сумма;"));
+    }
+
+    #[test]
+    fn test_reject_unbalanced_selection() {
+        // Начало цикла без КонецЦикла
+        assert!(plan_extraction(MODULE, 4, 5, None).is_none());
+        // Заголовок метода
+        assert!(plan_extraction(MODULE, 1, 2, None).is_none());
+    }
+}
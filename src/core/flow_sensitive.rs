@@ -373,6 +373,7 @@ impl FlowSensitiveAnalyzer {
             functions: self.base_context.functions.clone(),
             current_scope: self.base_context.current_scope.clone(),
             scope_stack: self.base_context.scope_stack.clone(),
+            active_contexts: self.base_context.active_contexts,
         }
     }
 
@@ -389,6 +390,7 @@ impl FlowSensitiveAnalyzer {
                 line: None,
                 column: None,
                 notes: vec![],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -407,6 +409,7 @@ impl FlowSensitiveAnalyzer {
                 line: None,
                 column: None,
                 notes: vec![],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -425,6 +428,7 @@ impl FlowSensitiveAnalyzer {
                 line: None,
                 column: None,
                 notes: vec![],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -441,6 +445,7 @@ impl FlowSensitiveAnalyzer {
                 line: None,
                 column: None,
                 notes: vec!["Unknown type in flow analysis".to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -0,0 +1,195 @@
+//! Сворачиваемые области модуля (`textDocument/foldingRange`)
+//!
+//! Области берутся из дерева tree-sitter: тела процедур и функций — по
+//! узлам определений, блоки препроцессора — по парным инструкциям
+//! `#Область`/`#КонецОбласти` и `#Если`/`#КонецЕсли` (ветка `#Иначе`
+//! сворачивается отдельно), комментарии — по подряд идущим строкам, целиком
+//! занятым `//`. Строка, закрывающая блок (`КонецПроцедуры`,
+//! `#КонецОбласти`), остаётся видимой.
+
+use tower_lsp::lsp_types::{FoldingRange, FoldingRangeKind};
+use tree_sitter::{Node, Tree};
+
+/// Открытый блок препроцессора
+struct OpenBlock {
+    line: u32,
+    /// Текст инструкции (`#Область Имя`)
+    title: String,
+}
+
+#[derive(Default)]
+struct Collector<'a> {
+    source: &'a str,
+    ranges: Vec<FoldingRange>,
+    regions: Vec<OpenBlock>,
+    conditions: Vec<OpenBlock>,
+    /// Строки, целиком занятые комментарием
+    comment_lines: Vec<u32>,
+}
+
+fn folding_range(
+    start: u32,
+    end: u32,
+    kind: Option<FoldingRangeKind>,
+    collapsed_text: Option<String>,
+) -> Option<FoldingRange> {
+    (end > start).then_some(FoldingRange {
+        start_line: start,
+        start_character: None,
+        end_line: end,
+        end_character: None,
+        kind,
+        collapsed_text,
+    })
+}
+
+/// Ключевое слово инструкции препроцессора в нижнем регистре (`область`)
+fn directive(text: &str) -> String {
+    text.trim_start_matches('#')
+        .trim_start()
+        .chars()
+        .take_while(|ch| ch.is_alphanumeric() || *ch == '_')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+impl<'a> Collector<'a> {
+    fn text(&self, node: Node) -> &'a str {
+        &self.source[node.byte_range()]
+    }
+
+    /// Строка, на которой узел стоит первым (перед ним только пробелы)
+    fn starts_line(&self, node: Node) -> bool {
+        let line_start = self.source[..node.start_byte()]
+            .rfind('\n')
+            .map_or(0, |idx| idx + 1);
+        self.source[line_start..node.start_byte()].trim().is_empty()
+    }
+
+    fn visit(&mut self, node: Node) {
+        let text = self.text(node);
+        // Инструкция препроцессора занимает одну строку; многострочный узел
+        // с `#` в начале — блок, инструкции которого среди дочерних узлов
+        if text.starts_with('#') && !text.trim_end().contains('\n') {
+            self.preprocessor(node, text);
+            return;
+        }
+        match node.kind() {
+            "procedure_definition" | "function_definition" => {
+                let start = node.start_position().row as u32;
+                let end = node.end_position().row as u32;
+                self.ranges
+                    .extend(folding_range(start, end.saturating_sub(1), None, None));
+            }
+            "line_comment" if self.starts_line(node) => {
+                self.comment_lines.push(node.start_position().row as u32);
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.visit(child);
+        }
+    }
+
+    fn preprocessor(&mut self, node: Node, text: &str) {
+        let line = node.start_position().row as u32;
+        let title = text.trim().to_string();
+        match directive(text).as_str() {
+            "область" | "region" => self.regions.push(OpenBlock { line, title }),
+            "конецобласти" | "endregion" => {
+                if let Some(open) = self.regions.pop() {
+                    let kind = Some(FoldingRangeKind::Region);
+                    self.ranges
+                        .extend(folding_range(open.line, line - 1, kind, Some(open.title)));
+                }
+            }
+            "если" | "if" => self.conditions.push(OpenBlock { line, title }),
+            "иначеесли" | "elsif" | "иначе" | "else" => {
+                if let Some(open) = self.conditions.pop() {
+                    self.ranges.extend(folding_range(open.line, line - 1, None, None));
+                    self.conditions.push(OpenBlock { line, title });
+                }
+            }
+            "конецесли" | "endif" => {
+                if let Some(open) = self.conditions.pop() {
+                    self.ranges.extend(folding_range(open.line, line - 1, None, None));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Подряд идущие строки комментариев (не меньше двух) — одна область
+    fn comment_ranges(&mut self) {
+        let mut lines = std::mem::take(&mut self.comment_lines);
+        lines.sort_unstable();
+        lines.dedup();
+        let mut start = 0;
+        for idx in 1..=lines.len() {
+            if idx == lines.len() || lines[idx] != lines[idx - 1] + 1 {
+                let kind = Some(FoldingRangeKind::Comment);
+                self.ranges
+                    .extend(folding_range(lines[start], lines[idx - 1], kind, None));
+                start = idx;
+            }
+        }
+    }
+}
+
+/// Сворачиваемые области модуля по его дереву; незакрытые блоки
+/// препроцессора не сворачиваются
+pub fn folding_ranges(tree: &Tree, source: &str) -> Vec<FoldingRange> {
+    let mut collector = Collector {
+        source,
+        ..Collector::default()
+    };
+    collector.visit(tree.root_node());
+    collector.comment_ranges();
+    let mut ranges = collector.ranges;
+    ranges.sort_by_key(|range| (range.start_line, std::cmp::Reverse(range.end_line)));
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::bsl::tree_sitter_adapter::TreeSitterAdapter;
+
+    #[test]
+    fn test_folding_ranges() {
+        let source = "#Область ПрограммныйИнтерфейс
+
+// Пересчитывает сумму документа.
+// Параметры:
+//  Ставка - Число
+Процедура Пересчитать(Ставка) Экспорт
+    Сумма = 0; // однострочный
+    Сумма = Сумма * Ставка;
+КонецПроцедуры
+
+#КонецОбласти
+
+#Если Сервер Тогда
+Перем мКэш;
+#Иначе
+Перем мКлиент;
+#КонецЕсли
+";
+        let tree = TreeSitterAdapter::new().unwrap().parse_tree(source).unwrap();
+        let ranges: Vec<(u32, u32, Option<FoldingRangeKind>)> = folding_ranges(&tree, source)
+            .into_iter()
+            .map(|range| (range.start_line, range.end_line, range.kind))
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                (0, 9, Some(FoldingRangeKind::Region)),
+                (2, 4, Some(FoldingRangeKind::Comment)),
+                (5, 7, None),
+                (11, 12, None),
+                (13, 14, None),
+            ]
+        );
+    }
+}
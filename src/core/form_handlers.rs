@@ -0,0 +1,374 @@
+//! Проверка обработчиков событий формы
+//!
+//! Обработчики назначаются в описании формы (`Form.xml`, `Form.form`), а
+//! пишутся в модуле формы, поэтому переименование процедуры или события
+//! незаметно рвёт связь: платформа сообщает о ней только при открытии
+//! формы. Проверяется, что каждая назначенная процедура есть в модуле и
+//! принимает параметры, которые передаёт платформа, и что процедуры с
+//! именами обработчиков (`ПриОткрытии`, `КонтрагентПриИзменении`) не
+//! остались без назначения после правки формы.
+
+use crate::core::common_module_calls::parameter_names;
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::method_header_regex;
+use crate::data::loaders::forms::{FormDescription, FormHandler, HandlerOwner};
+
+/// Событие: имя в описании формы, русское имя и параметры обработчика
+pub struct EventSignature {
+    pub event: &'static str,
+    pub russian: &'static str,
+    pub parameters: &'static [&'static str],
+}
+
+const fn event(
+    event: &'static str,
+    russian: &'static str,
+    parameters: &'static [&'static str],
+) -> EventSignature {
+    EventSignature {
+        event,
+        russian,
+        parameters,
+    }
+}
+
+/// События формы
+pub const FORM_EVENTS: &[EventSignature] = &[
+    event("OnCreateAtServer", "ПриСозданииНаСервере", &["Отказ", "СтандартнаяОбработка"]),
+    event("OnOpen", "ПриОткрытии", &["Отказ"]),
+    event(
+        "BeforeClose",
+        "ПередЗакрытием",
+        &["Отказ", "ЗавершениеРаботы", "ТекстПредупреждения", "СтандартнаяОбработка"],
+    ),
+    event("OnClose", "ПриЗакрытии", &["ЗавершениеРаботы"]),
+    event("NotificationProcessing", "ОбработкаОповещения", &["ИмяСобытия", "Параметр", "Источник"]),
+    event("ChoiceProcessing", "ОбработкаВыбора", &["ВыбранноеЗначение", "ИсточникВыбора"]),
+    event("OnReadAtServer", "ПриЧтенииНаСервере", &["ТекущийОбъект"]),
+    event("BeforeWrite", "ПередЗаписью", &["Отказ", "ПараметрыЗаписи"]),
+    event(
+        "BeforeWriteAtServer",
+        "ПередЗаписьюНаСервере",
+        &["Отказ", "ТекущийОбъект", "ПараметрыЗаписи"],
+    ),
+    event(
+        "OnWriteAtServer",
+        "ПриЗаписиНаСервере",
+        &["Отказ", "ТекущийОбъект", "ПараметрыЗаписи"],
+    ),
+    event("AfterWriteAtServer", "ПослеЗаписиНаСервере", &["ТекущийОбъект", "ПараметрыЗаписи"]),
+    event("AfterWrite", "ПослеЗаписи", &["ПараметрыЗаписи"]),
+    event(
+        "FillCheckProcessingAtServer",
+        "ОбработкаПроверкиЗаполненияНаСервере",
+        &["Отказ", "ПроверяемыеРеквизиты"],
+    ),
+    event("OnLoadDataFromSettingsAtServer", "ПриЗагрузкеДанныхИзНастроекНаСервере", &["Настройки"]),
+];
+
+/// События элементов формы
+pub const ITEM_EVENTS: &[EventSignature] = &[
+    event("OnChange", "ПриИзменении", &["Элемент"]),
+    event("StartChoice", "НачалоВыбора", &["Элемент", "ДанныеВыбора", "СтандартнаяОбработка"]),
+    event("Clearing", "Очистка", &["Элемент", "СтандартнаяОбработка"]),
+    event("Opening", "Открытие", &["Элемент", "СтандартнаяОбработка"]),
+    event(
+        "ChoiceProcessing",
+        "ОбработкаВыбора",
+        &["Элемент", "ВыбранноеЗначение", "СтандартнаяОбработка"],
+    ),
+    event(
+        "AutoComplete",
+        "АвтоПодбор",
+        &[
+            "Элемент",
+            "Текст",
+            "ДанныеВыбора",
+            "ПараметрыПолученияДанных",
+            "Ожидание",
+            "СтандартнаяОбработка",
+        ],
+    ),
+    event(
+        "Selection",
+        "Выбор",
+        &["Элемент", "ВыбраннаяСтрока", "Поле", "СтандартнаяОбработка"],
+    ),
+    event("OnActivateRow", "ПриАктивизацииСтроки", &["Элемент"]),
+    event(
+        "BeforeAddRow",
+        "ПередНачаломДобавления",
+        &["Элемент", "Отказ", "Копирование", "Родитель", "Группа", "Параметр"],
+    ),
+    event("BeforeDeleteRow", "ПередУдалением", &["Элемент", "Отказ"]),
+    event("OnStartEdit", "ПриНачалеРедактирования", &["Элемент", "НоваяСтрока", "Копирование"]),
+    event(
+        "OnEditEnd",
+        "ПриОкончанииРедактирования",
+        &["Элемент", "НоваяСтрока", "ОтменаРедактирования"],
+    ),
+    event("Click", "Нажатие", &["Элемент"]),
+];
+
+/// Действие команды формы
+pub static COMMAND_ACTION: EventSignature = event("Action", "Действие", &["Команда"]);
+
+/// Описание события владельца; `None` — событие не описано и не проверяется
+pub fn event_signature(owner: &HandlerOwner, event: &str) -> Option<&'static EventSignature> {
+    match owner {
+        HandlerOwner::Form => FORM_EVENTS.iter().find(|e| e.event == event),
+        HandlerOwner::Item(_) => ITEM_EVENTS.iter().find(|e| e.event == event),
+        HandlerOwner::Command(_) => (event == COMMAND_ACTION.event).then_some(&COMMAND_ACTION),
+    }
+}
+
+/// Вид нарушения
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormHandlerIssueKind {
+    /// Назначенной в форме процедуры нет в модуле
+    Missing,
+    /// Обработчик объявлен функцией
+    Function,
+    /// Число параметров не совпадает с передаваемыми платформой
+    ParameterCount {
+        expected: &'static [&'static str],
+        found: usize,
+    },
+    /// Процедура с именем обработчика не назначена в форме
+    Unassigned,
+}
+
+/// Нарушение: позиция — имя процедуры в заголовке (для `Missing` — начало
+/// модуля)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormHandlerIssue {
+    pub kind: FormHandlerIssueKind,
+    pub handler: FormHandler,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl FormHandlerIssue {
+    /// Ошибка (форма не откроется или обработчик не вызовется) или
+    /// предупреждение
+    pub fn is_error(&self) -> bool {
+        self.kind != FormHandlerIssueKind::Unassigned
+    }
+
+    pub fn message(&self) -> String {
+        let handler = &self.handler;
+        let event = event_signature(&handler.owner, &handler.event)
+            .map_or(handler.event.as_str(), |signature| signature.russian);
+        let owner = match &handler.owner {
+            HandlerOwner::Form => "формы".to_string(),
+            HandlerOwner::Item(item) => format!("элемента '{}'", item),
+            HandlerOwner::Command(command) => format!("команды '{}'", command),
+        };
+        match &self.kind {
+            FormHandlerIssueKind::Missing => format!(
+                "Обработчик '{}' события '{}' {} назначен в форме, но не найден в модуле",
+                handler.procedure, event, owner
+            ),
+            FormHandlerIssueKind::Function => format!(
+                "Обработчик '{}' события '{}' {} должен быть процедурой",
+                handler.procedure, event, owner
+            ),
+            FormHandlerIssueKind::ParameterCount { expected, found } => format!(
+                "Обработчик '{}' события '{}' {} принимает {} параметров, ожидается {} ({})",
+                handler.procedure,
+                event,
+                owner,
+                found,
+                expected.len(),
+                expected.join(", ")
+            ),
+            FormHandlerIssueKind::Unassigned => format!(
+                "Процедура '{}' похожа на обработчик события '{}' {}, но в форме не назначена",
+                handler.procedure, event, owner
+            ),
+        }
+    }
+}
+
+/// Метод модуля формы
+struct ModuleMethod {
+    name: String,
+    is_function: bool,
+    parameters: usize,
+    line: u32,
+    column: u32,
+}
+
+fn module_methods(text: &str) -> Vec<ModuleMethod> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let code = strip_comment(line);
+            let caps = method_header_regex().captures(code)?;
+            let name = caps.get(2)?;
+            let keyword = caps[1].to_lowercase();
+            Some(ModuleMethod {
+                name: name.as_str().to_string(),
+                is_function: keyword == "функция" || keyword == "function",
+                parameters: parameter_names(&caps[3]).len(),
+                line: idx as u32,
+                column: code[..name.start()].chars().count() as u32,
+            })
+        })
+        .collect()
+}
+
+/// Обработчик, которому соответствует имя процедуры по соглашению
+/// конфигуратора: `ПриОткрытии` у формы, `<Элемент><Событие>` у элемента
+fn conventional_handler(form: &FormDescription, procedure: &str) -> Option<FormHandler> {
+    let lower = procedure.to_lowercase();
+    let handler = |owner: HandlerOwner, event: &EventSignature| FormHandler {
+        owner,
+        event: event.event.to_string(),
+        procedure: procedure.to_string(),
+    };
+    if let Some(event) = FORM_EVENTS.iter().find(|e| e.russian.to_lowercase() == lower) {
+        return Some(handler(HandlerOwner::Form, event));
+    }
+    form.items.iter().find_map(|item| {
+        let rest = lower.strip_prefix(&item.to_lowercase())?;
+        let event = ITEM_EVENTS.iter().find(|e| e.russian.to_lowercase() == rest)?;
+        Some(handler(HandlerOwner::Item(item.clone()), event))
+    })
+}
+
+/// Сверить назначения формы с модулем формы `text`
+pub fn check_form_handlers(form: &FormDescription, text: &str) -> Vec<FormHandlerIssue> {
+    let methods = module_methods(text);
+    let find = |name: &str| {
+        let name = name.to_lowercase();
+        methods.iter().find(|m| m.name.to_lowercase() == name)
+    };
+    let mut issues = Vec::new();
+    for handler in &form.handlers {
+        let Some(method) = find(&handler.procedure) else {
+            issues.push(FormHandlerIssue {
+                kind: FormHandlerIssueKind::Missing,
+                handler: handler.clone(),
+                line: 0,
+                column: 0,
+            });
+            continue;
+        };
+        let expected = event_signature(&handler.owner, &handler.event).map(|e| e.parameters);
+        let kind = if method.is_function {
+            FormHandlerIssueKind::Function
+        } else {
+            match expected {
+                Some(expected) if expected.len() != method.parameters => {
+                    FormHandlerIssueKind::ParameterCount {
+                        expected,
+                        found: method.parameters,
+                    }
+                }
+                _ => continue,
+            }
+        };
+        issues.push(FormHandlerIssue {
+            kind,
+            handler: handler.clone(),
+            line: method.line,
+            column: method.column,
+        });
+    }
+
+    for method in methods.iter().filter(|m| !m.is_function) {
+        let lower = method.name.to_lowercase();
+        if form
+            .handlers
+            .iter()
+            .any(|handler| handler.procedure.to_lowercase() == lower)
+        {
+            continue;
+        }
+        if let Some(handler) = conventional_handler(form, &method.name) {
+            issues.push(FormHandlerIssue {
+                kind: FormHandlerIssueKind::Unassigned,
+                handler,
+                line: method.line,
+                column: method.column,
+            });
+        }
+    }
+    issues.sort_by_key(|issue| (issue.line, issue.column));
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler(owner: HandlerOwner, event: &str, procedure: &str) -> FormHandler {
+        FormHandler {
+            owner,
+            event: event.to_string(),
+            procedure: procedure.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_check_form_handlers() {
+        let form = FormDescription {
+            handlers: vec![
+                handler(HandlerOwner::Form, "OnCreateAtServer", "ПриСозданииНаСервере"),
+                handler(HandlerOwner::Form, "OnOpen", "ПриОткрытии"),
+                handler(
+                    HandlerOwner::Item("Контрагент".to_string()),
+                    "OnChange",
+                    "КонтрагентПриИзменении",
+                ),
+                handler(HandlerOwner::Command("Заполнить".to_string()), "Action", "Заполнить"),
+            ],
+            items: vec!["Контрагент".to_string(), "Склад".to_string()],
+            ..FormDescription::default()
+        };
+        let module = "&НаСервере
+Процедура ПриСозданииНаСервере(Отказ, СтандартнаяОбработка)
+КонецПроцедуры
+
+&НаКлиенте
+Процедура КонтрагентПриИзменении()
+КонецПроцедуры
+
+&НаКлиенте
+Процедура СкладПриИзменении(Элемент)
+КонецПроцедуры
+
+&НаКлиенте
+Процедура Заполнить(Команда)
+КонецПроцедуры
+";
+        let issues = check_form_handlers(&form, module);
+        let found: Vec<(FormHandlerIssueKind, &str, u32)> = issues
+            .iter()
+            .map(|i| (i.kind.clone(), i.handler.procedure.as_str(), i.line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (FormHandlerIssueKind::Missing, "ПриОткрытии", 0),
+                (
+                    FormHandlerIssueKind::ParameterCount {
+                        expected: &["Элемент"],
+                        found: 0
+                    },
+                    "КонтрагентПриИзменении",
+                    5
+                ),
+                (FormHandlerIssueKind::Unassigned, "СкладПриИзменении", 9),
+            ]
+        );
+        assert_eq!(issues[1].column, 10);
+        assert!(!issues[2].is_error());
+        assert_eq!(
+            issues[2].message(),
+            "Процедура 'СкладПриИзменении' похожа на обработчик события 'ПриИзменении' \
+             элемента 'Склад', но в форме не назначена"
+        );
+    }
+}
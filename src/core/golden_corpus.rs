@@ -0,0 +1,405 @@
+//! Золотой корпус: модули BSL с ожидаемыми результатами анализа
+//!
+//! Каждый образец — модуль (`<имя>.bsl`) и ожидания к нему
+//! (`<имя>.expected.json`): типы выражений в заданных позициях и
+//! диагностики проверки типов. Корпус из `tests/fixtures/golden` встроен в
+//! библиотеку и служит приёмочным тестом изменений резолверов; его же (или
+//! свой каталог образцов) запускает `bsl-analyzer selftest`, чтобы
+//! проверить сборку на своей машине и со своими типами платформы.
+//!
+//! ```json
+//! {
+//!   "description": "Присваивания в ветках Если дают объединение типов",
+//!   "file_path": "CommonModules/ЗолотойКорпус/Ext/Module.bsl",
+//!   "resolutions": [
+//!     { "line": 6, "column": 4, "expression": "Значение", "type": "Строка|Число" }
+//!   ],
+//!   "diagnostics": [{ "severity": "error", "contains": "ожидает 2 аргументов" }],
+//!   "no_errors": true
+//! }
+//! ```
+//!
+//! Тип объединения записывается именами членов по алфавиту через `|`,
+//! произвольный — `Произвольный`, неизвестный — `null`.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::architecture::domain::{TypeContext, TypeResolutionService};
+use crate::core::type_checker::{DiagnosticSeverity, TypeChecker};
+use crate::domain::types::{
+    Certainty, ConcreteType, ResolutionResult, SpecialType, TypeResolution,
+};
+use crate::parsing::bsl::LanguageFeatures;
+use crate::pure::parse_module_str;
+use crate::unified::data::TypeRepository;
+
+/// Код выхода CLI, если образец корпуса не прошёл
+pub const EXIT_SELFTEST_FAILED: i32 = 7;
+
+/// Встроенный корпус: имя, текст модуля, ожидания
+const BUNDLED: &[(&str, &str, &str)] = &[
+    (
+        "branches",
+        include_str!("../../tests/fixtures/golden/branches.bsl"),
+        include_str!("../../tests/fixtures/golden/branches.expected.json"),
+    ),
+    (
+        "narrowing",
+        include_str!("../../tests/fixtures/golden/narrowing.bsl"),
+        include_str!("../../tests/fixtures/golden/narrowing.expected.json"),
+    ),
+    (
+        "signatures",
+        include_str!("../../tests/fixtures/golden/signatures.bsl"),
+        include_str!("../../tests/fixtures/golden/signatures.expected.json"),
+    ),
+    (
+        "diagnostics",
+        include_str!("../../tests/fixtures/golden/diagnostics.bsl"),
+        include_str!("../../tests/fixtures/golden/diagnostics.expected.json"),
+    ),
+];
+
+/// Ожидаемый тип выражения
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedResolution {
+    /// Позиция с нуля
+    pub line: u32,
+    pub column: u32,
+    pub expression: String,
+    /// `None` — тип не должен определяться
+    #[serde(rename = "type")]
+    pub type_name: Option<String>,
+    /// `known`, `inferred` или `unknown`; без значения не проверяется
+    #[serde(default)]
+    pub certainty: Option<String>,
+}
+
+/// Ожидаемая диагностика: уровень и фрагмент сообщения
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedDiagnostic {
+    pub severity: String,
+    pub contains: String,
+    /// Строка диагностики; без значения не проверяется
+    #[serde(default)]
+    pub line: Option<usize>,
+}
+
+/// Ожидания к образцу
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GoldenExpectations {
+    pub description: String,
+    /// Путь модуля в выгрузке: от него зависит контекст (модуль объекта,
+    /// формы); по умолчанию — имя образца
+    pub file_path: Option<String>,
+    pub resolutions: Vec<ExpectedResolution>,
+    pub diagnostics: Vec<ExpectedDiagnostic>,
+    /// Ошибок проверки типов быть не должно
+    pub no_errors: bool,
+}
+
+/// Образец корпуса
+#[derive(Debug, Clone)]
+pub struct GoldenFixture {
+    pub name: String,
+    pub source: String,
+    pub expected: GoldenExpectations,
+}
+
+impl GoldenFixture {
+    pub fn parse(name: &str, source: &str, expected: &str) -> Result<Self> {
+        let expected = serde_json::from_str(expected)
+            .with_context(|| format!("ожидания образца '{}' не разобраны", name))?;
+        Ok(Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            expected,
+        })
+    }
+
+    /// Путь модуля, в контексте которого разрешаются выражения
+    pub fn file_path(&self) -> String {
+        self.expected
+            .file_path
+            .clone()
+            .unwrap_or_else(|| format!("{}.bsl", self.name))
+    }
+}
+
+/// Встроенный корпус
+pub fn bundled_fixtures() -> Result<Vec<GoldenFixture>> {
+    BUNDLED
+        .iter()
+        .map(|(name, source, expected)| GoldenFixture::parse(name, source, expected))
+        .collect()
+}
+
+/// Загрузить образцы из каталога: каждому `<имя>.bsl` нужен
+/// `<имя>.expected.json` рядом; модули без ожиданий пропускаются
+pub fn load_fixtures(dir: &Path) -> Result<Vec<GoldenFixture>> {
+    let mut fixtures = Vec::new();
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("каталог корпуса {} не читается", dir.display()))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bsl"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let expected_path = path.with_file_name(format!("{}.expected.json", name));
+        if !expected_path.exists() {
+            continue;
+        }
+        let source = crate::core::fs_utils::read_bsl_file(&path)?;
+        let expected = std::fs::read_to_string(&expected_path)
+            .with_context(|| format!("{} не читается", expected_path.display()))?;
+        fixtures.push(GoldenFixture::parse(name, &source, &expected)?);
+    }
+    anyhow::ensure!(
+        !fixtures.is_empty(),
+        "в {} нет образцов (<имя>.bsl и <имя>.expected.json)",
+        dir.display()
+    );
+    Ok(fixtures)
+}
+
+/// Итог проверки образца
+#[derive(Debug, Clone, Serialize)]
+pub struct FixtureOutcome {
+    pub name: String,
+    pub description: String,
+    /// Число проверенных ожиданий
+    pub checks: usize,
+    /// Несовпадения с ожиданиями
+    pub failures: Vec<String>,
+}
+
+impl FixtureOutcome {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Итог прогона корпуса
+#[derive(Debug, Clone, Serialize)]
+pub struct CorpusReport {
+    pub fixtures: Vec<FixtureOutcome>,
+}
+
+impl CorpusReport {
+    pub fn passed(&self) -> bool {
+        self.fixtures.iter().all(FixtureOutcome::passed)
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.fixtures.iter().filter(|f| !f.passed()).count()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for fixture in &self.fixtures {
+            let mark = if fixture.passed() { "✅" } else { "❌" };
+            out.push_str(&format!(
+                "{} {} ({} проверок) — {}\n",
+                mark, fixture.name, fixture.checks, fixture.description
+            ));
+            for failure in &fixture.failures {
+                out.push_str(&format!("   {}\n", failure));
+            }
+        }
+        out.push_str(&format!(
+            "Образцов: {}, не прошли: {}\n",
+            self.fixtures.len(),
+            self.failed_count()
+        ));
+        out
+    }
+}
+
+/// Имя типа для сравнения с ожиданием; `None` — тип неизвестен
+pub fn type_label(resolution: &TypeResolution) -> Option<String> {
+    fn concrete(type_: &ConcreteType) -> Option<String> {
+        match type_ {
+            ConcreteType::Special(SpecialType::Undefined) => Some("Неопределено".to_string()),
+            ConcreteType::Special(SpecialType::Null) => Some("Null".to_string()),
+            other => TypeResolution::known(other.clone()).get_name(),
+        }
+    }
+    match &resolution.result {
+        ResolutionResult::Concrete(type_) => concrete(type_),
+        ResolutionResult::Union(members) => {
+            let mut names: Vec<String> = members
+                .iter()
+                .map(|member| concrete(&member.type_))
+                .collect::<Option<_>>()?;
+            names.sort();
+            names.dedup();
+            Some(names.join("|"))
+        }
+        ResolutionResult::Dynamic if resolution.certainty != Certainty::Unknown => {
+            Some("Произвольный".to_string())
+        }
+        _ => None,
+    }
+}
+
+fn certainty_label(certainty: &Certainty) -> &'static str {
+    match certainty {
+        Certainty::Known => "known",
+        Certainty::Inferred(_) => "inferred",
+        Certainty::Unknown => "unknown",
+    }
+}
+
+fn severity_label(severity: &DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Info => "info",
+        DiagnosticSeverity::Hint => "hint",
+    }
+}
+
+/// Проверить образец: выражения разрешаются сервисом с типами
+/// `repository` (таблица сигнатур строится по самому образцу),
+/// диагностики даёт проверка типов модуля
+pub async fn run_fixture(
+    fixture: &GoldenFixture,
+    repository: Arc<dyn TypeRepository>,
+) -> FixtureOutcome {
+    let expected = &fixture.expected;
+    let file_path = fixture.file_path();
+    let mut failures = Vec::new();
+
+    let service = TypeResolutionService::new(repository);
+    service
+        .index_signatures(&[(file_path.clone(), fixture.source.clone())])
+        .await;
+    for resolution in &expected.resolutions {
+        let context = TypeContext::at_position(&file_path, resolution.line, resolution.column);
+        let actual = service
+            .resolve_in_document(&resolution.expression, &context, &fixture.source)
+            .await;
+        let position = format!(
+            "{}:{} '{}'",
+            resolution.line + 1,
+            resolution.column + 1,
+            resolution.expression
+        );
+        let label = type_label(&actual);
+        if label != resolution.type_name {
+            failures.push(format!(
+                "{}: ожидался тип {}, получен {}",
+                position,
+                resolution.type_name.as_deref().unwrap_or("null"),
+                label.as_deref().unwrap_or("null")
+            ));
+        }
+        if let Some(certainty) = &resolution.certainty {
+            let actual = certainty_label(&actual.certainty);
+            if !certainty.eq_ignore_ascii_case(actual) {
+                failures.push(format!(
+                    "{}: ожидалась уверенность {}, получена {}",
+                    position, certainty, actual
+                ));
+            }
+        }
+    }
+
+    let needs_check = !expected.diagnostics.is_empty() || expected.no_errors;
+    if needs_check {
+        match parse_module_str(&fixture.source, LanguageFeatures::latest()) {
+            Ok(program) => {
+                let (_, diagnostics) = TypeChecker::new(file_path.clone()).check(&program);
+                for wanted in &expected.diagnostics {
+                    let found = diagnostics.iter().any(|d| {
+                        severity_label(&d.severity).eq_ignore_ascii_case(&wanted.severity)
+                            && d.message.contains(&wanted.contains)
+                            && (wanted.line.is_none() || wanted.line == Some(d.line))
+                    });
+                    if !found {
+                        failures.push(format!(
+                            "нет диагностики {} «{}»",
+                            wanted.severity, wanted.contains
+                        ));
+                    }
+                }
+                if expected.no_errors {
+                    for error in diagnostics
+                        .iter()
+                        .filter(|d| d.severity == DiagnosticSeverity::Error)
+                    {
+                        failures.push(format!("{}: лишняя ошибка «{}»", error.line, error.message));
+                    }
+                }
+            }
+            Err(e) => failures.push(format!("модуль не разобран: {}", e)),
+        }
+    }
+
+    FixtureOutcome {
+        name: fixture.name.clone(),
+        description: expected.description.clone(),
+        checks: expected.resolutions.len()
+            + expected.diagnostics.len()
+            + usize::from(expected.no_errors),
+        failures,
+    }
+}
+
+/// Проверить все образцы
+pub async fn run_corpus(
+    fixtures: &[GoldenFixture],
+    repository: Arc<dyn TypeRepository>,
+) -> CorpusReport {
+    let mut outcomes = Vec::new();
+    for fixture in fixtures {
+        outcomes.push(run_fixture(fixture, repository.clone()).await);
+    }
+    CorpusReport { fixtures: outcomes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unified::data::InMemoryTypeRepository;
+
+    #[tokio::test]
+    async fn test_bundled_corpus_passes() {
+        let fixtures = bundled_fixtures().unwrap();
+        assert_eq!(fixtures.len(), BUNDLED.len());
+        let report = run_corpus(&fixtures, Arc::new(InMemoryTypeRepository::new())).await;
+        assert!(report.passed(), "{}", report.to_text());
+    }
+
+    #[tokio::test]
+    async fn test_load_fixtures_and_report_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("сумма.bsl"), "Сумма = 1;\nИтог = Сумма;").unwrap();
+        std::fs::write(
+            dir.path().join("сумма.expected.json"),
+            r#"{"resolutions": [{"line": 1, "column": 0, "expression": "Сумма", "type": "Строка"}]}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("без_ожиданий.bsl"), "А = 1;").unwrap();
+
+        let fixtures = load_fixtures(dir.path()).unwrap();
+        assert_eq!(fixtures.len(), 1);
+        assert_eq!(fixtures[0].file_path(), "сумма.bsl");
+
+        let report = run_corpus(&fixtures, Arc::new(InMemoryTypeRepository::new())).await;
+        assert!(!report.passed());
+        assert_eq!(
+            report.fixtures[0].failures,
+            ["2:1 'Сумма': ожидался тип Строка, получен Число"]
+        );
+        assert!(load_fixtures(&dir.path().join("нет")).is_err());
+    }
+}
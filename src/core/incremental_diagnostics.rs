@@ -0,0 +1,440 @@
+//! Инкрементальные диагностики: при правке анализируется только изменённый метод
+//!
+//! При `textDocument/didChange` текст документа обновляется по диапазонам
+//! изменений, а дерево tree-sitter перестраивается инкрементально: к старому
+//! дереву применяются правки, и парсер переиспользует неизменённые поддеревья.
+//! По дереву модуль делится на области — методы и участки кода модуля между
+//! ними. Диагностики хранятся по отпечатку текста области со строками
+//! относительно её начала, поэтому область, текст которой не изменился (даже
+//! если она сдвинулась вниз или вверх), берёт диагностики из кеша, и заново
+//! анализируется только отредактированный метод.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use tower_lsp::lsp_types::{Position, TextDocumentContentChangeEvent};
+use tree_sitter::{InputEdit, Point, Tree};
+
+use crate::parsing::bsl::tree_sitter_adapter::TreeSitterAdapter;
+
+/// Диагностика, привязанная к строке документа
+pub trait LineAnchored: Clone {
+    /// Сдвинуть на `delta` строк (отрицательный сдвиг — вверх)
+    fn shift_lines(&mut self, delta: i64);
+}
+
+/// Область модуля: метод или код модуля между методами
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleRegion {
+    /// Имя метода (`None` — код модуля вне методов)
+    pub method: Option<String>,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// Отпечаток имени и текста области
+    pub fingerprint: u64,
+}
+
+/// Область без диагностик в кеше: её нужно проанализировать
+#[derive(Debug, Clone)]
+pub struct RegionWork {
+    pub fingerprint: u64,
+    pub method: Option<String>,
+    pub start_line: u32,
+    /// Текст области; строки анализатор нумерует от `start_line`
+    pub text: String,
+}
+
+/// Байтовое смещение позиции LSP (строка и столбец в UTF-16)
+fn byte_offset(text: &str, position: Position) -> usize {
+    let mut line = 0;
+    let mut line_start = 0;
+    if position.line > 0 {
+        for (idx, ch) in text.char_indices() {
+            if ch == '\n' {
+                line += 1;
+                if line == position.line {
+                    line_start = idx + 1;
+                    break;
+                }
+            }
+        }
+        if line < position.line {
+            return text.len();
+        }
+    }
+    let mut units = 0;
+    for (idx, ch) in text[line_start..].char_indices() {
+        if units >= position.character || ch == '\n' {
+            return line_start + idx;
+        }
+        units += ch.len_utf16() as u32;
+    }
+    text.len()
+}
+
+/// Точка tree-sitter (строка и столбец в байтах) по байтовому смещению
+fn point_at(text: &str, offset: usize) -> Point {
+    let before = &text[..offset];
+    let row = before.matches('\n').count();
+    let column = offset - before.rfind('\n').map_or(0, |idx| idx + 1);
+    Point { row, column }
+}
+
+/// Применить изменения `didChange` к тексту. Возвращает новый текст и правки
+/// для дерева tree-sitter; `None` вместо правок — текст заменён целиком
+pub fn apply_content_changes(
+    text: &str,
+    changes: &[TextDocumentContentChangeEvent],
+) -> (String, Option<Vec<InputEdit>>) {
+    let mut text = text.to_string();
+    let mut edits = Some(Vec::new());
+    for change in changes {
+        let Some(range) = change.range else {
+            text = change.text.clone();
+            edits = None;
+            continue;
+        };
+        let start_byte = byte_offset(&text, range.start);
+        let old_end_byte = byte_offset(&text, range.end).max(start_byte);
+        let start_position = point_at(&text, start_byte);
+        let old_end_position = point_at(&text, old_end_byte);
+        text.replace_range(start_byte..old_end_byte, &change.text);
+        let new_end_byte = start_byte + change.text.len();
+        if let Some(edits) = &mut edits {
+            edits.push(InputEdit {
+                start_byte,
+                old_end_byte,
+                new_end_byte,
+                start_position,
+                old_end_position,
+                new_end_position: point_at(&text, new_end_byte),
+            });
+        }
+    }
+    (text, edits)
+}
+
+fn fingerprint(method: Option<&str>, text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    method.hash(&mut hasher);
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Разбить модуль на области по дереву tree-sitter (без дерева — одна область)
+pub fn module_regions(text: &str, tree: Option<&Tree>) -> Vec<ModuleRegion> {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let last_line = lines.len() as u32 - 1;
+    let mut methods: Vec<(String, u32, u32)> = Vec::new();
+    if let Some(tree) = tree {
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for node in root.children(&mut cursor) {
+            if !matches!(node.kind(), "procedure_definition" | "function_definition") {
+                continue;
+            }
+            let mut name_cursor = node.walk();
+            let name = node
+                .children(&mut name_cursor)
+                .find(|child| child.kind() == "identifier")
+                .map(|child| text[child.byte_range()].to_string())
+                .unwrap_or_default();
+            let start = node.start_position().row as u32;
+            let end = (node.end_position().row as u32).min(last_line);
+            methods.push((name, start, end));
+        }
+    }
+
+    let mut bounds: Vec<(Option<String>, u32, u32)> = Vec::new();
+    let mut next_line = 0;
+    for (name, start, end) in methods {
+        if start < next_line {
+            continue;
+        }
+        if start > next_line {
+            bounds.push((None, next_line, start - 1));
+        }
+        bounds.push((Some(name), start, end));
+        next_line = end + 1;
+    }
+    if next_line <= last_line {
+        bounds.push((None, next_line, last_line));
+    }
+
+    bounds
+        .into_iter()
+        .map(|(method, start_line, end_line)| {
+            let region_text = lines[start_line as usize..=end_line as usize].join("\n");
+            ModuleRegion {
+                fingerprint: fingerprint(method.as_deref(), &region_text),
+                method,
+                start_line,
+                end_line,
+            }
+        })
+        .collect()
+}
+
+/// Состояние открытого документа
+struct DocumentState<D> {
+    text: String,
+    tree: Option<Tree>,
+    regions: Vec<ModuleRegion>,
+    /// Диагностики по отпечатку области, строки относительно начала области
+    cache: HashMap<u64, Vec<D>>,
+}
+
+/// Диагностики открытых документов с повторным анализом изменённых областей
+pub struct IncrementalDiagnostics<D> {
+    /// Парсер tree-sitter; без него документ — одна область
+    parser: Option<TreeSitterAdapter>,
+    documents: HashMap<String, DocumentState<D>>,
+}
+
+impl<D: LineAnchored> Default for IncrementalDiagnostics<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: LineAnchored> IncrementalDiagnostics<D> {
+    pub fn new() -> Self {
+        Self {
+            parser: TreeSitterAdapter::new().ok(),
+            documents: HashMap::new(),
+        }
+    }
+
+    fn parse(&mut self, text: &str, old_tree: Option<&Tree>) -> Option<Tree> {
+        let parser = self.parser.as_mut()?;
+        let parsed = match old_tree {
+            Some(old_tree) => parser.reparse_tree(text, old_tree),
+            None => parser.parse_tree(text),
+        };
+        parsed.ok()
+    }
+
+    /// Открыть документ (или заменить его текст целиком)
+    pub fn open(&mut self, uri: &str, text: String) {
+        let tree = self.parse(&text, None);
+        let regions = module_regions(&text, tree.as_ref());
+        self.documents.insert(
+            uri.to_string(),
+            DocumentState {
+                text,
+                tree,
+                regions,
+                cache: HashMap::new(),
+            },
+        );
+    }
+
+    /// Применить изменения `didChange`; `false` — документ не открыт
+    pub fn change(&mut self, uri: &str, changes: &[TextDocumentContentChangeEvent]) -> bool {
+        let Some(mut document) = self.documents.remove(uri) else {
+            return false;
+        };
+        let (text, edits) = apply_content_changes(&document.text, changes);
+        let old_tree = match (document.tree.take(), edits) {
+            (Some(mut tree), Some(edits)) => {
+                for edit in &edits {
+                    tree.edit(edit);
+                }
+                Some(tree)
+            }
+            _ => None,
+        };
+        document.tree = self.parse(&text, old_tree.as_ref());
+        document.regions = module_regions(&text, document.tree.as_ref());
+        document.text = text;
+        // Диагностики исчезнувших областей больше не понадобятся
+        let alive: Vec<u64> = document.regions.iter().map(|r| r.fingerprint).collect();
+        document.cache.retain(|fingerprint, _| alive.contains(fingerprint));
+        self.documents.insert(uri.to_string(), document);
+        true
+    }
+
+    pub fn close(&mut self, uri: &str) {
+        self.documents.remove(uri);
+    }
+
+    /// Текущий текст документа
+    pub fn text(&self, uri: &str) -> Option<&str> {
+        self.documents.get(uri).map(|document| document.text.as_str())
+    }
+
+    /// Открытые документы
+    pub fn uris(&self) -> Vec<String> {
+        self.documents.keys().cloned().collect()
+    }
+
+    /// Забыть все диагностики (изменились настройки или типы)
+    pub fn invalidate_all(&mut self) {
+        for document in self.documents.values_mut() {
+            document.cache.clear();
+        }
+    }
+
+    /// Забыть диагностики одного документа (изменился модуль, от которого
+    /// он зависит)
+    pub fn invalidate(&mut self, uri: &str) {
+        if let Some(document) = self.documents.get_mut(uri) {
+            document.cache.clear();
+        }
+    }
+
+    /// Области документа без диагностик в кеше
+    pub fn pending(&self, uri: &str) -> Vec<RegionWork> {
+        let Some(document) = self.documents.get(uri) else {
+            return Vec::new();
+        };
+        let lines: Vec<&str> = document.text.split('\n').collect();
+        let mut seen = Vec::new();
+        document
+            .regions
+            .iter()
+            .filter(|region| !document.cache.contains_key(&region.fingerprint))
+            .filter(|region| {
+                let first = !seen.contains(&region.fingerprint);
+                seen.push(region.fingerprint);
+                first
+            })
+            .map(|region| RegionWork {
+                fingerprint: region.fingerprint,
+                method: region.method.clone(),
+                start_line: region.start_line,
+                text: lines[region.start_line as usize..=region.end_line as usize].join("\n"),
+            })
+            .collect()
+    }
+
+    /// Сохранить диагностики проанализированной области (строки документа).
+    /// Если документ успел измениться и области больше нет, результат
+    /// отбрасывается.
+    pub fn store(&mut self, uri: &str, work: &RegionWork, diagnostics: Vec<D>) {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return;
+        };
+        if !document.regions.iter().any(|r| r.fingerprint == work.fingerprint) {
+            return;
+        }
+        let relative = diagnostics
+            .into_iter()
+            .map(|mut diagnostic| {
+                diagnostic.shift_lines(-(work.start_line as i64));
+                diagnostic
+            })
+            .collect();
+        document.cache.insert(work.fingerprint, relative);
+    }
+
+    /// Диагностики документа по строкам текущего текста (области, ещё не
+    /// проанализированные заново, пропускаются)
+    pub fn diagnostics(&self, uri: &str) -> Vec<D> {
+        let Some(document) = self.documents.get(uri) else {
+            return Vec::new();
+        };
+        let mut out = Vec::new();
+        for region in &document.regions {
+            if let Some(cached) = document.cache.get(&region.fingerprint) {
+                out.extend(cached.iter().cloned().map(|mut diagnostic| {
+                    diagnostic.shift_lines(region.start_line as i64);
+                    diagnostic
+                }));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::Range;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct TestDiagnostic {
+        line: u32,
+        message: String,
+    }
+
+    impl LineAnchored for TestDiagnostic {
+        fn shift_lines(&mut self, delta: i64) {
+            self.line = (self.line as i64 + delta) as u32;
+        }
+    }
+
+    fn change(line: u32, character: u32, text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(Range::new(
+                Position::new(line, character),
+                Position::new(line, character),
+            )),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_content_changes() {
+        let text = "А = 1;\nБ = \"Строка\";\n";
+        let replace = TextDocumentContentChangeEvent {
+            range: Some(Range::new(Position::new(1, 4), Position::new(1, 12))),
+            range_length: None,
+            text: "2".to_string(),
+        };
+        let (updated, edits) =
+            apply_content_changes(text, &[replace, change(0, 0, "// Начало\n")]);
+        assert_eq!(updated, "// Начало\nА = 1;\nБ = 2;\n");
+        let edits = edits.unwrap();
+        // Столбец tree-sitter — в байтах: «Б» занимает два
+        assert_eq!(edits[0].start_position, Point { row: 1, column: 5 });
+        assert_eq!(edits[1].new_end_position, Point { row: 1, column: 0 });
+
+        let full = TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "В = 3;".to_string(),
+        };
+        assert_eq!(apply_content_changes(text, &[full]), ("В = 3;".to_string(), None));
+    }
+
+    #[test]
+    fn test_only_edited_method_is_reanalyzed() {
+        let text = "Процедура А()\n    Х = 1;\nКонецПроцедуры\n\n\
+                    Процедура Б()\n    У = 2;\nКонецПроцедуры\n";
+        let mut engine = IncrementalDiagnostics::<TestDiagnostic>::new();
+        engine.open("file:///Module.bsl", text.to_string());
+
+        let pending = engine.pending("file:///Module.bsl");
+        let methods: Vec<_> = pending.iter().filter_map(|w| w.method.clone()).collect();
+        assert_eq!(methods, ["А", "Б"]);
+        for work in &pending {
+            let diagnostics = work
+                .method
+                .iter()
+                .map(|name| TestDiagnostic {
+                    line: work.start_line + 1,
+                    message: name.clone(),
+                })
+                .collect();
+            engine.store("file:///Module.bsl", work, diagnostics);
+        }
+        assert!(engine.pending("file:///Module.bsl").is_empty());
+
+        // Строка в начале метода А: заново анализируется только он, а
+        // диагностика метода Б сдвигается вместе с ним
+        assert!(engine.change("file:///Module.bsl", &[change(1, 0, "    З = 0;\n")]));
+        let pending = engine.pending("file:///Module.bsl");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].method.as_deref(), Some("А"));
+        assert_eq!(
+            engine.diagnostics("file:///Module.bsl"),
+            [TestDiagnostic {
+                line: 6,
+                message: "Б".to_string()
+            }]
+        );
+        assert!(!engine.change("file:///Other.bsl", &[]));
+    }
+}
@@ -0,0 +1,358 @@
+//! Inlay hints (`textDocument/inlayHint`): выведенные типы переменных и
+//! имена параметров в местах вызовов
+//!
+//! Места подсказок находятся по тексту модуля: первое присваивание
+//! переменной в методе (или в теле модуля) и `Перем`, начала аргументов в
+//! вызовах `Метод(`, `Модуль.Метод(`, `Объект.Метод(`. Тип переменной и
+//! имена параметров подставляет вызывающий: тип — разрешением в позиции,
+//! параметры — по заголовку метода документа, экспорту общего модуля или
+//! справке платформы. Конструкторы (`Новый Структура(`) пропускаются.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use tower_lsp::lsp_types::*;
+
+use crate::core::call_hierarchy::after_new;
+use crate::core::common_module_calls::parameter_names;
+use crate::core::example_mining::strip_comment;
+use crate::core::extract_procedure::KEYWORDS;
+use crate::core::module_structure::{method_header, method_header_regex};
+use crate::core::rename::{code_chars, local_declaration_regex, method_ranges, same_name};
+use crate::core::signature_help::select_overload;
+use crate::core::type_hints::TypeHintsProvider;
+use crate::documentation::core::hierarchy::MethodDocumentation;
+use crate::domain::types::{ResolutionResult, TypeResolution};
+
+/// Настройки inlay hints (секция `bslAnalyzer` редактора)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InlayHintsConfig {
+    /// Выведенный тип после имени переменной в объявлении
+    pub variable_types: bool,
+    /// Имя параметра перед аргументом вызова
+    pub parameter_names: bool,
+    /// Минимальная уверенность выведенного типа (0.0 - 1.0)
+    pub min_certainty: f32,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        Self {
+            variable_types: true,
+            parameter_names: true,
+            min_certainty: 0.0,
+        }
+    }
+}
+
+/// Объявление переменной: `Перем` или первое присваивание в области
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableSite {
+    pub name: String,
+    pub line: u32,
+    /// Позиция сразу после имени (в символах)
+    pub column: u32,
+}
+
+/// Начало аргумента вызова
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgumentSite {
+    pub line: u32,
+    pub column: u32,
+    /// Аргумент — просто имя (`Отказ` в `Проверить(Отказ)`)
+    pub name: Option<String>,
+}
+
+/// Вызов метода с аргументами
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallArguments {
+    /// Выражение перед точкой (`Таблица` в `Таблица.Добавить(`)
+    pub receiver: Option<String>,
+    pub method: String,
+    pub arguments: Vec<ArgumentSite>,
+}
+
+fn assignment_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*([\p{L}_][\p{L}\p{N}_]*)\s*=").unwrap())
+}
+
+fn is_keyword(name: &str) -> bool {
+    KEYWORDS.contains(&name.to_lowercase().as_str())
+}
+
+/// Объявления переменных модуля: `Перем` и первое присваивание каждого
+/// имени в методе или в теле модуля (параметры метода не объявляются)
+pub fn variable_sites(text: &str) -> Vec<VariableSite> {
+    let methods = method_ranges(text);
+    let mut seen: HashSet<(Option<usize>, String)> = HashSet::new();
+    let mut sites = Vec::new();
+    // Список параметров заголовка продолжается на следующих строках
+    let mut in_header = false;
+    for (idx, line) in text.lines().enumerate() {
+        let line_no = idx as u32;
+        let scope = methods.iter().position(|range| range.contains(&line_no));
+        let code: String = code_chars(line).into_iter().collect();
+        if in_header {
+            in_header = !code.contains(')');
+            continue;
+        }
+        if let Some(header) = method_header(&code) {
+            in_header = !header.complete;
+            continue;
+        }
+
+        let mut names: Vec<(String, usize)> = Vec::new();
+        if let Some(caps) = local_declaration_regex().captures(&code) {
+            let list = caps.get(1).map_or(0, |m| m.start());
+            for part in caps[1].split(',') {
+                let name = part.split_whitespace().next().unwrap_or_default();
+                let offset = list + caps[1].find(part).unwrap_or(0) + part.find(name).unwrap_or(0);
+                names.push((name.to_string(), offset + name.len()));
+            }
+        } else if let Some(name) = assignment_regex().captures(&code).and_then(|caps| caps.get(1)) {
+            names.push((name.as_str().to_string(), name.end()));
+        }
+
+        for (name, end) in names {
+            if name.is_empty() || is_keyword(&name) {
+                continue;
+            }
+            if seen.insert((scope, name.to_lowercase())) {
+                sites.push(VariableSite {
+                    column: code[..end].chars().count() as u32,
+                    name,
+                    line: line_no,
+                });
+            }
+        }
+    }
+    sites
+}
+
+/// Вызовы с аргументами (многострочные вызовы поддерживаются; комментарии
+/// и содержимое строковых литералов пропускаются)
+pub fn call_arguments(text: &str) -> Vec<CallArguments> {
+    let is_ident = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let mut calls: Vec<CallArguments> = Vec::new();
+    // Открытые скобки: (номер вызова, ожидается начало аргумента)
+    let mut open: Vec<(Option<usize>, bool)> = Vec::new();
+
+    for (idx, line) in text.lines().enumerate() {
+        let chars = code_chars(line);
+        // Скобка списка параметров в объявлении метода — не вызов
+        let code: String = chars.iter().collect();
+        let declaration = method_header(&code).map(|header| code[..header.paren].chars().count());
+        for (pos, &ch) in chars.iter().enumerate() {
+            if ch.is_whitespace() {
+                continue;
+            }
+            if let Some((Some(call), pending)) = open.last_mut() {
+                if *pending && ch != ',' && ch != ')' {
+                    *pending = false;
+                    let end = chars[pos..]
+                        .iter()
+                        .position(|&c| !is_ident(c))
+                        .map_or(chars.len(), |p| pos + p);
+                    let next = chars[end..].iter().find(|c| !c.is_whitespace());
+                    let plain_name =
+                        end > pos && !ch.is_ascii_digit() && matches!(next, Some(',' | ')'));
+                    let name = plain_name.then(|| chars[pos..end].iter().collect());
+                    calls[*call].arguments.push(ArgumentSite {
+                        line: idx as u32,
+                        column: pos as u32,
+                        name,
+                    });
+                }
+            }
+            match ch {
+                '(' => {
+                    let start = chars[..pos]
+                        .iter()
+                        .rposition(|&c| !(is_ident(c) || c == '.'))
+                        .map_or(0, |p| p + 1);
+                    let callee: String = chars[start..pos].iter().collect();
+                    let (receiver, method) = match callee.rsplit_once('.') {
+                        Some((receiver, method)) => {
+                            (Some(receiver.to_string()), method.to_string())
+                        }
+                        None => (None, callee),
+                    };
+                    let skip = declaration == Some(pos)
+                        || method.is_empty()
+                        || receiver.as_deref() == Some("")
+                        || (receiver.is_none() && is_keyword(&method))
+                        || after_new(&chars, start);
+                    if skip {
+                        open.push((None, false));
+                    } else {
+                        calls.push(CallArguments {
+                            receiver,
+                            method,
+                            arguments: Vec::new(),
+                        });
+                        open.push((Some(calls.len() - 1), true));
+                    }
+                }
+                ')' => {
+                    open.pop();
+                }
+                ',' => {
+                    if let Some((Some(_), pending)) = open.last_mut() {
+                        *pending = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    calls.retain(|call| !call.arguments.is_empty());
+    calls
+}
+
+/// Параметры методов документа по имени метода в нижнем регистре
+pub fn method_parameters(text: &str) -> HashMap<String, Vec<String>> {
+    let mut methods = HashMap::new();
+    for line in text.lines() {
+        let code = strip_comment(line);
+        if let Some(caps) = method_header_regex().captures(code) {
+            methods.insert(caps[2].to_lowercase(), parameter_names(&caps[3]));
+        }
+    }
+    methods
+}
+
+/// Имена параметров метода платформы для вызова с `argument_count`
+/// аргументами (вариант синтаксиса выбирается как в подсказке сигнатур)
+pub fn documented_parameters(method: &MethodDocumentation, argument_count: usize) -> Vec<String> {
+    let parameters = if method.overloads.is_empty() {
+        &method.parameters
+    } else {
+        let counts: Vec<usize> = method
+            .overloads
+            .iter()
+            .map(|overload| overload.parameters.len())
+            .collect();
+        &method.overloads[select_overload(&counts, argument_count)].parameters
+    };
+    parameters.iter().map(|p| p.name.clone()).collect()
+}
+
+/// Подсказки имён параметров перед аргументами вызова; аргумент с тем же
+/// именем, что и параметр, подсказки не получает
+pub fn parameter_hints(call: &CallArguments, parameters: &[String]) -> Vec<InlayHint> {
+    call.arguments
+        .iter()
+        .zip(parameters)
+        .filter(|(argument, parameter)| {
+            !argument
+                .name
+                .as_deref()
+                .is_some_and(|name| same_name(name, parameter))
+        })
+        .map(|(argument, parameter)| InlayHint {
+            position: Position::new(argument.line, argument.column),
+            label: InlayHintLabel::String(format!("{}:", parameter)),
+            kind: Some(InlayHintKind::PARAMETER),
+            text_edits: None,
+            tooltip: None,
+            padding_left: Some(false),
+            padding_right: Some(true),
+            data: None,
+        })
+        .collect()
+}
+
+/// Подсказка выведенного типа после имени переменной (`: Строка?` для
+/// выведенного с неполной уверенностью); `None` — тип неизвестен или
+/// динамический
+pub fn variable_type_hint(
+    site: &VariableSite,
+    resolution: &TypeResolution,
+    formatter: &TypeHintsProvider,
+) -> Option<InlayHint> {
+    if matches!(resolution.result, ResolutionResult::Dynamic)
+        || !formatter.should_show_hint(resolution)
+    {
+        return None;
+    }
+    Some(InlayHint {
+        position: Position::new(site.line, site.column),
+        label: InlayHintLabel::String(format!(": {}", formatter.format_type_hint(resolution))),
+        kind: Some(InlayHintKind::TYPE),
+        text_edits: None,
+        tooltip: Some(InlayHintTooltip::String(format!(
+            "Тип переменной '{}' выведен как: {}",
+            site.name,
+            formatter.format_detailed_type(resolution)
+        ))),
+        padding_left: Some(false),
+        padding_right: Some(true),
+        data: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = "Перем мКэш Экспорт;
+
+Процедура Заполнить(Заказ, Отказ)
+    Итог = 0; // Итог = \"в комментарии\"
+    Итог = Итог + 1;
+    Проверить(Отказ, Заказ.Сумма,
+        \"Проверка(, )\");
+    Запрос = Новый Запрос(\"ВЫБРАТЬ 1\");
+    Таблица.Добавить(Итог);
+КонецПроцедуры
+";
+
+    #[test]
+    fn test_variable_and_argument_sites() {
+        let variables = variable_sites(MODULE);
+        let names: Vec<(&str, u32, u32)> = variables
+            .iter()
+            .map(|site| (site.name.as_str(), site.line, site.column))
+            .collect();
+        assert_eq!(names, [("мКэш", 0, 10), ("Итог", 3, 8), ("Запрос", 7, 10)]);
+
+        let calls = call_arguments(MODULE);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].method, "Проверить");
+        assert_eq!(
+            calls[0].arguments,
+            [
+                ArgumentSite {
+                    line: 5,
+                    column: 14,
+                    name: Some("Отказ".to_string())
+                },
+                ArgumentSite {
+                    line: 5,
+                    column: 21,
+                    name: None
+                },
+                ArgumentSite {
+                    line: 6,
+                    column: 8,
+                    name: None
+                },
+            ]
+        );
+        assert_eq!(calls[1].receiver.as_deref(), Some("Таблица"));
+
+        let parameters = method_parameters("Процедура Проверить(Знач Флаг, Сумма, Текст = \"\")");
+        let hints = parameter_hints(&calls[0], &parameters["проверить"]);
+        let labels: Vec<String> = hints
+            .iter()
+            .map(|hint| match &hint.label {
+                InlayHintLabel::String(label) => label.clone(),
+                InlayHintLabel::LabelParts(_) => String::new(),
+            })
+            .collect();
+        assert_eq!(labels, ["Флаг:", "Сумма:", "Текст:"]);
+    }
+}
@@ -188,6 +188,7 @@ impl CallGraph {
                 line: None,
                 column: None,
                 notes: vec!["Parameter type to be inferred".to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -666,6 +667,7 @@ impl InterproceduralAnalyzer {
                 line: None,
                 column: None,
                 notes: vec!["Void type (procedure)".to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -682,6 +684,7 @@ impl InterproceduralAnalyzer {
                 line: None,
                 column: None,
                 notes: vec![reason.to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -700,6 +703,7 @@ mod tests {
             functions: HashMap::new(),
             current_scope: Scope::Global,
             scope_stack: vec![],
+            active_contexts: crate::parsing::bsl::ContextSet::ALL,
         }
     }
 
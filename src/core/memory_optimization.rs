@@ -364,6 +364,7 @@ mod tests {
             functions: HashMap::new(),
             current_scope: crate::core::dependency_graph::Scope::Global,
             scope_stack: vec![],
+            active_contexts: crate::parsing::bsl::ContextSet::ALL,
         }
     }
 
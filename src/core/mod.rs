@@ -1,25 +1,60 @@
 //! Core type system components
 
+pub mod access_rights;
 pub mod analysis_cache;
+pub mod annotation_coverage;
+pub mod bounded_memory;
+pub mod call_hierarchy;
 pub mod code_actions;
+pub mod command_handlers;
+pub mod common_module_calls;
+pub mod compilation_context;
+pub mod completion_scope;
 pub mod context;
 pub mod contracts;
 pub mod dependency_graph;
+pub mod determinism_audit;
+pub mod document_symbols;
+pub mod example_mining;
+pub mod expected_type;
+pub mod extract_procedure;
 pub mod facets;
 pub mod flow_sensitive;
+pub mod folding_ranges;
+pub mod form_handlers;
 pub mod fs_utils;
+pub mod golden_corpus;
+pub mod incremental_diagnostics;
+pub mod inlay_hints;
 pub mod interprocedural;
 pub mod lsp_enhanced;
 pub mod memory_optimization;
+pub mod module_dependencies;
+pub mod module_structure;
+pub mod module_variables;
+pub mod ownership;
 pub mod parallel_analysis;
 pub mod performance;
 pub mod platform_resolver;
+pub mod profiling;
+pub mod quality_gates;
+pub mod quick_open;
 pub mod position;
+pub mod rename;
+pub mod request_arena;
 pub mod resolution;
+pub mod semantic_tokens;
+pub mod signature_help;
+pub mod snippets;
 pub mod standard_types;
+pub mod telemetry;
 pub mod type_checker;
 pub mod type_hints;
 pub mod type_narrowing;
+pub mod type_quick_fixes;
+pub mod type_references;
 pub mod type_system_service;
 pub mod unified_type_system;
 pub mod union_types;
+pub mod usage_report;
+pub mod workspace_symbols;
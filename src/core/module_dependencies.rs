@@ -0,0 +1,279 @@
+//! Граф зависимостей модулей проекта
+//!
+//! Правка общего модуля меняет набор его экспортных методов и возвращаемые
+//! типы функций, а значит — диагностики модулей, которые к нему обращаются.
+//! Граф хранит для каждого модуля имена, к которым модуль обращается через
+//! точку (`ОбщегоНазначения.Метод(`, `РаботаСФайлами.Настройки`):
+//! зависимость возникает, если так называется общий модуль проекта, даже
+//! когда вызываемого метода в нём (ещё) нет. Комментарии и строковые
+//! литералы пропускаются.
+//!
+//! [`ModuleDependencyGraph::affected_by`] находит модули, которые нужно
+//! проанализировать заново после правки: изменённые и все зависящие от них
+//! транзитивно (функция одного общего модуля может возвращать результат
+//! функции другого). Экспортные символы модулей приложения и сеанса
+//! доступны без квалификатора, поэтому от них зависят все модули. Граф
+//! выводится в формате DOT (`bsl-analyzer dependency-graph`).
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::Path;
+
+use crate::architecture::domain::GlobalModuleKind;
+use crate::core::common_module_calls::module_name_from_path;
+use crate::core::rename::{code_chars, sites};
+
+/// Модуль в графе
+#[derive(Debug, Clone, Default)]
+struct ModuleNode {
+    /// Имя общего модуля
+    common_module: Option<String>,
+    /// Модуль приложения, сеанса или внешнего соединения
+    global: bool,
+    /// Имена перед точкой, в нижнем регистре
+    references: BTreeSet<String>,
+}
+
+/// Граф зависимостей модулей по пути файла
+#[derive(Debug, Clone, Default)]
+pub struct ModuleDependencyGraph {
+    modules: BTreeMap<String, ModuleNode>,
+    /// Файлы общих модулей по имени в нижнем регистре
+    common_modules: BTreeMap<String, String>,
+}
+
+/// Имена, после которых в тексте модуля идёт точка
+fn referenced_names(text: &str) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    for line in text.lines() {
+        let chars = code_chars(line);
+        for site in sites(&chars) {
+            if site.qualifier.is_some() {
+                continue;
+            }
+            let end = site.start + site.name.chars().count();
+            if chars[end..].iter().find(|ch| !ch.is_whitespace()) == Some(&'.') {
+                names.insert(site.name.to_lowercase());
+            }
+        }
+    }
+    names
+}
+
+impl ModuleDependencyGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Число модулей
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// Добавить (или заменить) модуль; имя общего модуля и глобальность
+    /// определяются по пути
+    pub fn add_module(&mut self, file_path: &str, text: &str) {
+        self.remove_module(file_path);
+        let common_module = module_name_from_path(Path::new(file_path));
+        if let Some(name) = &common_module {
+            self.common_modules
+                .insert(name.to_lowercase(), file_path.to_string());
+        }
+        self.modules.insert(
+            file_path.to_string(),
+            ModuleNode {
+                common_module,
+                global: GlobalModuleKind::from_path(file_path).is_some(),
+                references: referenced_names(text),
+            },
+        );
+    }
+
+    /// Убрать модуль (файл удалён)
+    pub fn remove_module(&mut self, file_path: &str) {
+        let Some(node) = self.modules.remove(file_path) else {
+            return;
+        };
+        if let Some(name) = node.common_module {
+            let name = name.to_lowercase();
+            if self.common_modules.get(&name).map(String::as_str) == Some(file_path) {
+                self.common_modules.remove(&name);
+            }
+        }
+    }
+
+    /// Файлы общих модулей, к которым обращается модуль
+    pub fn dependencies(&self, file_path: &str) -> Vec<&str> {
+        let Some(node) = self.modules.get(file_path) else {
+            return Vec::new();
+        };
+        let mut found: Vec<&str> = node
+            .references
+            .iter()
+            .filter_map(|name| self.common_modules.get(name))
+            .map(String::as_str)
+            .filter(|dependency| *dependency != file_path)
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// Модули, которые обращаются к модулю напрямую; от глобального модуля
+    /// зависят все
+    pub fn dependents(&self, file_path: &str) -> Vec<&str> {
+        let Some(node) = self.modules.get(file_path) else {
+            return Vec::new();
+        };
+        let name = node.common_module.as_deref().map(str::to_lowercase);
+        self.modules
+            .iter()
+            .filter(|(other, _)| other.as_str() != file_path)
+            .filter(|(_, other)| {
+                node.global
+                    || name
+                        .as_ref()
+                        .is_some_and(|name| other.references.contains(name))
+            })
+            .map(|(other, _)| other.as_str())
+            .collect()
+    }
+
+    /// Модули, которые нужно проанализировать заново после изменения
+    /// `changed`: сами изменённые и все зависящие от них транзитивно
+    pub fn affected_by<'a>(&self, changed: impl IntoIterator<Item = &'a str>) -> BTreeSet<String> {
+        let mut affected: BTreeSet<String> = BTreeSet::new();
+        let mut queue: VecDeque<String> = changed.into_iter().map(str::to_string).collect();
+        while let Some(file_path) = queue.pop_front() {
+            if !affected.insert(file_path.clone()) {
+                continue;
+            }
+            queue.extend(
+                self.dependents(&file_path)
+                    .into_iter()
+                    .filter(|dependent| !affected.contains(*dependent))
+                    .map(str::to_string),
+            );
+        }
+        affected
+    }
+
+    /// Рёбра «модуль → общий модуль, к которому он обращается»; зависимости
+    /// от глобальных модулей не перечисляются
+    pub fn edges(&self) -> Vec<(&str, &str)> {
+        self.modules
+            .keys()
+            .flat_map(|file_path| {
+                self.dependencies(file_path)
+                    .into_iter()
+                    .map(move |dependency| (file_path.as_str(), dependency))
+            })
+            .collect()
+    }
+
+    /// Граф в формате DOT (Graphviz); общие модули подписаны именем,
+    /// глобальные модули выделены
+    pub fn to_dot(&self) -> String {
+        let mut output =
+            String::from("digraph modules {\n    rankdir=LR;\n    node [shape=box];\n");
+        for (file_path, node) in &self.modules {
+            let label = node.common_module.as_deref().unwrap_or(file_path);
+            let style = if node.global {
+                ", style=bold, color=red"
+            } else if node.common_module.is_some() {
+                ", style=rounded"
+            } else {
+                ""
+            };
+            output.push_str(&format!(
+                "    \"{}\" [label=\"{}\"{}];\n",
+                dot_escape(file_path),
+                dot_escape(label),
+                style
+            ));
+        }
+        for (from, to) in self.edges() {
+            output.push_str(&format!(
+                "    \"{}\" -> \"{}\";\n",
+                dot_escape(from),
+                dot_escape(to)
+            ));
+        }
+        output.push_str("}\n");
+        output
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMON: &str = "CommonModules/ОбщегоНазначения/Ext/Module.bsl";
+    const ORDERS: &str = "CommonModules/Заказы/Ext/Module.bsl";
+    const DOCUMENT: &str = "Documents/Заказ/Ext/ObjectModule.bsl";
+    const REPORT: &str = "Reports/Продажи/Ext/ObjectModule.bsl";
+    const SESSION: &str = "Ext/SessionModule.bsl";
+
+    fn graph() -> ModuleDependencyGraph {
+        let mut graph = ModuleDependencyGraph::new();
+        graph.add_module(COMMON, "Функция Пустая(Значение) Экспорт\nКонецФункции\n");
+        graph.add_module(
+            ORDERS,
+            "Функция Проверить(Заказ) Экспорт\n    Возврат Не ОбщегоНазначения.Пустая(Заказ);\nКонецФункции\n",
+        );
+        graph.add_module(
+            DOCUMENT,
+            "Процедура ПередЗаписью(Отказ)\n    // ОбщегоНазначения.Пустая(1)\n    Отказ = Не заказы . Проверить(ЭтотОбъект);\nКонецПроцедуры\n",
+        );
+        graph.add_module(
+            REPORT,
+            "Сообщить(\"Заказы.Проверить\");\nСтрока = Стр.Длина;\n",
+        );
+        graph.add_module(SESSION, "Перем Пользователь Экспорт;\n");
+        graph
+    }
+
+    #[test]
+    fn test_module_dependencies() {
+        let mut graph = graph();
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.dependencies(DOCUMENT), [ORDERS]);
+        assert_eq!(graph.dependencies(ORDERS), [COMMON]);
+        assert!(graph.dependencies(REPORT).is_empty());
+        assert_eq!(graph.dependents(COMMON), [ORDERS]);
+        assert_eq!(graph.dependents(SESSION).len(), 4);
+
+        // Правка общего модуля затрагивает зависящие от него модули
+        // транзитивно, но не отчёт
+        let affected = graph.affected_by([COMMON]);
+        assert_eq!(
+            affected.iter().map(String::as_str).collect::<Vec<_>>(),
+            [ORDERS, COMMON, DOCUMENT]
+        );
+        assert_eq!(graph.affected_by([REPORT]).len(), 1);
+        assert_eq!(graph.affected_by([SESSION]).len(), 5);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph modules {"));
+        assert!(dot.contains(&format!(
+            "\"{}\" [label=\"ОбщегоНазначения\", style=rounded];",
+            COMMON
+        )));
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", DOCUMENT, ORDERS)));
+        assert_eq!(graph.edges().len(), 2);
+
+        // Документ перестал обращаться к модулю, модуль удалён
+        graph.add_module(DOCUMENT, "Процедура ПередЗаписью(Отказ)\nКонецПроцедуры\n");
+        assert!(graph.dependents(ORDERS).is_empty());
+        graph.remove_module(COMMON);
+        assert!(graph.dependencies(ORDERS).is_empty());
+        assert_eq!(graph.len(), 4);
+    }
+}
@@ -0,0 +1,111 @@
+//! Структура модуля BSL по тексту: заголовки и окончания методов
+//!
+//! Построчный разбор `[Асинх] Процедура|Функция Имя(Параметры) [Экспорт]` и
+//! `КонецПроцедуры`/`КонецФункции`, общий для текстовых анализов модуля:
+//! символов документа, областей видимости, переименования, извлечения
+//! процедуры, экспортов общих и глобальных модулей. Комментарий из строки
+//! убирает вызывающая сторона.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Заголовок метода: группа 1 — ключевое слово, 2 — имя, 3 — параметры до `)`
+/// (или до конца строки, если список продолжается на следующих строках)
+pub(crate) fn method_header_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)^\s*(?:асинх\s+|async\s+)?(процедура|функция|procedure|function)\s+([\p{L}_][\p{L}\p{N}_]*)\s*\(([^)]*)",
+        )
+        .unwrap()
+    })
+}
+
+/// Окончание метода: `КонецПроцедуры`, `КонецФункции`
+pub(crate) fn method_end_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^\s*(?:конецпроцедуры|конецфункции|endprocedure|endfunction)\b").unwrap()
+    })
+}
+
+fn export_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^\)\s*(?:экспорт|export)\b").unwrap())
+}
+
+/// Разобранный заголовок метода
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MethodHeader<'a> {
+    pub name: &'a str,
+    /// Байтовое смещение имени в строке
+    pub name_start: usize,
+    /// Текст списка параметров (на строке заголовка)
+    pub parameters: &'a str,
+    /// Байтовое смещение открывающей скобки списка параметров
+    pub paren: usize,
+    pub is_function: bool,
+    pub is_async: bool,
+    /// Список параметров закрыт на строке заголовка
+    pub complete: bool,
+    /// После списка параметров стоит `Экспорт`
+    pub export: bool,
+}
+
+/// Разобрать заголовок метода в строке кода (без комментария)
+pub(crate) fn method_header(code: &str) -> Option<MethodHeader<'_>> {
+    let caps = method_header_regex().captures(code)?;
+    let keyword = caps.get(1)?;
+    let name = caps.get(2)?;
+    let parameters = caps.get(3)?;
+    let rest = &code[parameters.end()..];
+    Some(MethodHeader {
+        name: name.as_str(),
+        name_start: name.start(),
+        parameters: parameters.as_str(),
+        paren: parameters.start() - 1,
+        is_function: matches!(keyword.as_str().to_lowercase().as_str(), "функция" | "function"),
+        is_async: !code[..keyword.start()].trim().is_empty(),
+        complete: rest.starts_with(')'),
+        export: export_regex().is_match(rest),
+    })
+}
+
+/// Является ли строка кода заголовком метода
+pub(crate) fn is_method_header(code: &str) -> bool {
+    method_header_regex().is_match(code)
+}
+
+/// Является ли строка кода окончанием метода
+pub(crate) fn is_method_end(code: &str) -> bool {
+    method_end_regex().is_match(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_header() {
+        let header = method_header("Асинх Функция Получить(Знач А, Б = 1) Экспорт").unwrap();
+        assert_eq!(header.name, "Получить");
+        assert_eq!(header.parameters, "Знач А, Б = 1");
+        assert!(header.is_function && header.is_async && header.complete && header.export);
+
+        let header = method_header("Процедура Заполнить(Заказ,").unwrap();
+        assert_eq!(header.name, "Заполнить");
+        assert!(!header.is_function && !header.is_async && !header.complete && !header.export);
+        assert_eq!(&"Процедура Заполнить(Заказ,"[header.paren..], "(Заказ,");
+
+        assert!(method_header("Заполнить(Заказ)").is_none());
+        assert!(method_header("Процедура Экспорт()").is_some_and(|h| !h.export));
+    }
+
+    #[test]
+    fn test_method_end() {
+        assert!(is_method_end("КонецПроцедуры"));
+        assert!(is_method_end("  EndFunction // конец"));
+        assert!(!is_method_end("КонецЕсли;"));
+        assert!(is_method_header("  function Get()"));
+    }
+}
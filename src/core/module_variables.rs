@@ -0,0 +1,478 @@
+//! Упорядочивание объявлений переменных модуля
+//!
+//! Code action собирает объявления `Перем` уровня модуля, объединяет
+//! повторные объявления, удаляет неиспользуемые и выстраивает остальные по
+//! соглашению: группы по директиве компиляции, внутри группы сначала
+//! экспортные, затем по алфавиту, по одному объявлению на строку.
+//!
+//! Использование определяется обходом AST с учётом областей видимости:
+//! параметры и локальные `Перем` метода скрывают одноимённую переменную
+//! модуля. Экспортные переменные не удаляются никогда — они могут
+//! использоваться извне модуля. Дополнительно переменная удаляется, только
+//! если её имя не встречается в тексте вне объявлений: неполный разбор не
+//! должен приводить к потере используемых объявлений.
+
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
+use tower_lsp::lsp_types::*;
+
+use crate::core::dependency_graph::Scope;
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::{is_method_end, is_method_header};
+use crate::parsing::bsl::ast::{Expression, Parameter, Program, Statement};
+use crate::parsing::bsl::AstVisitor;
+
+/// Объявление переменной модуля
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleVariable {
+    pub name: String,
+    pub export: bool,
+    /// Директива компиляции (`&НаКлиенте`), под которой объявлена переменная
+    pub directive: Option<String>,
+    /// Комментарий в конце строки объявления
+    pub comment: Option<String>,
+    /// Строка объявления (с 0)
+    pub line: usize,
+}
+
+/// Результат упорядочивания
+#[derive(Debug, Clone, Default)]
+pub struct OrganizedVariables {
+    /// Оставшиеся объявления в новом порядке
+    pub kept: Vec<ModuleVariable>,
+    /// Удалённые неиспользуемые переменные
+    pub removed_unused: Vec<String>,
+    /// Удалённые повторные объявления
+    pub removed_duplicates: Vec<String>,
+    pub edits: Vec<TextEdit>,
+}
+
+fn declaration_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^\s*(?:перем|var)\s+([^;]+);?\s*$").unwrap())
+}
+
+fn identifier_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[\p{L}_][\p{L}\p{N}_]*").unwrap())
+}
+
+/// Сбор обращений к переменным модуля с учётом областей видимости
+struct ModuleUsageCollector {
+    scope: Scope,
+    /// Параметры и локальные переменные текущего метода (в нижнем регистре)
+    locals: HashSet<String>,
+    used: HashSet<String>,
+}
+
+impl ModuleUsageCollector {
+    fn new() -> Self {
+        Self {
+            scope: Scope::Global,
+            locals: HashSet::new(),
+            used: HashSet::new(),
+        }
+    }
+
+    fn mark_used(&mut self, name: &str) {
+        let name = name.to_lowercase();
+        // Вне методов (раздел основной программы) локальных имён нет
+        if matches!(self.scope, Scope::Global) || !self.locals.contains(&name) {
+            self.used.insert(name);
+        }
+    }
+
+    fn visit_method(&mut self, name: &str, params: &[Parameter], body: &[Statement]) {
+        let prev_scope = std::mem::replace(&mut self.scope, Scope::Function(name.to_string()));
+        self.locals = params.iter().map(|p| p.name.to_lowercase()).collect();
+        // Локальные `Перем` объявляются в начале метода и скрывают переменные модуля
+        for stmt in body {
+            if let Statement::VarDeclaration { name, .. } = stmt {
+                self.locals.insert(name.to_lowercase());
+            }
+        }
+        for param in params {
+            if let Some(default) = &param.default_value {
+                self.visit_expression(default);
+            }
+        }
+        for stmt in body {
+            self.visit_statement(stmt);
+        }
+        self.locals.clear();
+        self.scope = prev_scope;
+    }
+}
+
+impl AstVisitor for ModuleUsageCollector {
+    fn visit_var_declaration(&mut self, _name: &str, value: Option<&Expression>, _export: bool) {
+        if let Some(value) = value {
+            self.visit_expression(value);
+        }
+    }
+
+    fn visit_procedure_decl(
+        &mut self,
+        name: &str,
+        params: &[Parameter],
+        body: &[Statement],
+        _export: bool,
+    ) {
+        self.visit_method(name, params, body);
+    }
+
+    fn visit_function_decl(
+        &mut self,
+        name: &str,
+        params: &[Parameter],
+        body: &[Statement],
+        return_value: Option<&Expression>,
+        _export: bool,
+    ) {
+        self.visit_method(name, params, body);
+        if let Some(value) = return_value {
+            self.visit_expression(value);
+        }
+    }
+
+    fn visit_procedure_call(&mut self, name: &str, args: &[Expression]) {
+        // `Переменная.Метод(...)` — обращение к переменной
+        if let Some((object, _)) = name.split_once('.') {
+            self.mark_used(object);
+        }
+        for arg in args {
+            self.visit_expression(arg);
+        }
+    }
+
+    fn visit_for(
+        &mut self,
+        variable: &str,
+        from: &Expression,
+        to: &Expression,
+        step: &Option<Expression>,
+        body: &[Statement],
+    ) {
+        self.mark_used(variable);
+        self.visit_expression(from);
+        self.visit_expression(to);
+        if let Some(step) = step {
+            self.visit_expression(step);
+        }
+        for stmt in body {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_for_each(&mut self, variable: &str, collection: &Expression, body: &[Statement]) {
+        self.mark_used(variable);
+        self.visit_expression(collection);
+        for stmt in body {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_identifier(&mut self, name: &str) {
+        self.mark_used(name);
+    }
+}
+
+/// Переменные модуля (в нижнем регистре), к которым есть обращения
+pub fn used_module_variables(program: &Program) -> HashSet<String> {
+    let mut collector = ModuleUsageCollector::new();
+    collector.visit_program(program);
+    collector.used
+}
+
+/// Объявления `Перем` уровня модуля (вне процедур и функций)
+pub fn module_declarations(text: &str) -> Vec<ModuleVariable> {
+    let mut declarations = Vec::new();
+    let mut in_method = false;
+    let lines: Vec<&str> = text.lines().collect();
+
+    for (idx, line) in lines.iter().enumerate() {
+        if is_method_header(line) {
+            in_method = true;
+            continue;
+        }
+        if is_method_end(line) {
+            in_method = false;
+            continue;
+        }
+        if in_method {
+            continue;
+        }
+
+        let code = strip_comment(line);
+        let Some(caps) = declaration_regex().captures(code) else {
+            continue;
+        };
+        let comment = line[code.len()..]
+            .trim_start_matches('/')
+            .trim()
+            .to_string();
+        let directive = idx
+            .checked_sub(1)
+            .map(|prev| lines[prev].trim())
+            .filter(|prev| prev.starts_with('&'))
+            .map(str::to_string);
+
+        for item in caps[1].split(',') {
+            let mut words = item.split_whitespace();
+            let Some(name) = words.next() else {
+                continue;
+            };
+            let export = words.any(|w| matches!(w.to_lowercase().as_str(), "экспорт" | "export"));
+            declarations.push(ModuleVariable {
+                name: name.to_string(),
+                export,
+                directive: directive.clone(),
+                comment: (!comment.is_empty()).then(|| comment.clone()),
+                line: idx,
+            });
+        }
+    }
+
+    declarations
+}
+
+/// Имена (в нижнем регистре), встречающиеся в тексте вне строк объявлений
+fn names_in_code(text: &str, declaration_lines: &HashSet<usize>) -> HashSet<String> {
+    text.lines()
+        .enumerate()
+        .filter(|(idx, _)| !declaration_lines.contains(idx))
+        .flat_map(|(_, line)| {
+            identifier_regex()
+                .find_iter(strip_comment(line))
+                .map(|m| m.as_str().to_lowercase())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Упорядочить объявления; `None`, если менять нечего. Без AST неиспользуемые
+/// переменные не удаляются.
+pub fn organize_module_variables(
+    text: &str,
+    program: Option<&Program>,
+) -> Option<OrganizedVariables> {
+    let declarations = module_declarations(text);
+    let first = declarations.first()?;
+    let lines: Vec<&str> = text.lines().collect();
+
+    let declaration_lines: HashSet<usize> = declarations.iter().map(|d| d.line).collect();
+    let used = program.map(|program| {
+        let mut used = used_module_variables(program);
+        used.extend(names_in_code(text, &declaration_lines));
+        used
+    });
+
+    let mut result = OrganizedVariables::default();
+    let mut by_name: HashMap<String, usize> = HashMap::new();
+    for declaration in &declarations {
+        let key = declaration.name.to_lowercase();
+        if let Some(&existing) = by_name.get(&key) {
+            let kept: &mut ModuleVariable = &mut result.kept[existing];
+            kept.export |= declaration.export;
+            result.removed_duplicates.push(declaration.name.clone());
+            continue;
+        }
+        by_name.insert(key, result.kept.len());
+        result.kept.push(declaration.clone());
+    }
+
+    if let Some(used) = &used {
+        result.kept.retain(|variable| {
+            let keep = variable.export || used.contains(&variable.name.to_lowercase());
+            if !keep {
+                result.removed_unused.push(variable.name.clone());
+            }
+            keep
+        });
+    }
+
+    // Группы по директиве в порядке появления, внутри — экспортные, затем по алфавиту
+    let mut group_order: Vec<Option<String>> = Vec::new();
+    let mut groups: BTreeMap<usize, Vec<ModuleVariable>> = BTreeMap::new();
+    for variable in result.kept.drain(..) {
+        let group = match group_order.iter().position(|g| *g == variable.directive) {
+            Some(group) => group,
+            None => {
+                group_order.push(variable.directive.clone());
+                group_order.len() - 1
+            }
+        };
+        groups.entry(group).or_default().push(variable);
+    }
+    let mut block = String::new();
+    for (group, mut variables) in groups {
+        variables.sort_by_key(|v| (!v.export, v.name.to_lowercase()));
+        if !block.is_empty() {
+            block.push('\n');
+        }
+        if let Some(directive) = &group_order[group] {
+            block.push_str(directive);
+            block.push('\n');
+        }
+        for variable in &variables {
+            block.push_str(&format!(
+                "Перем {}{};",
+                variable.name,
+                if variable.export { " Экспорт" } else { "" }
+            ));
+            if let Some(comment) = &variable.comment {
+                block.push_str(&format!(" // {}", comment));
+            }
+            block.push('\n');
+        }
+        result.kept.extend(variables);
+    }
+
+    // Исходные строки объявлений (с директивами) удаляются, блок встаёт на место первой
+    let mut removed_lines: Vec<usize> = declaration_lines.iter().copied().collect();
+    for declaration in &declarations {
+        if declaration.directive.is_some() {
+            removed_lines.push(declaration.line - 1);
+        }
+    }
+    removed_lines.sort_unstable();
+    removed_lines.dedup();
+
+    let original: String = removed_lines
+        .iter()
+        .map(|&idx| format!("{}\n", lines[idx].trim_end()))
+        .collect();
+    if original == block {
+        return None;
+    }
+
+    let anchor = if first.directive.is_some() {
+        first.line - 1
+    } else {
+        first.line
+    };
+    for idx in removed_lines {
+        let range = Range::new(Position::new(idx as u32, 0), Position::new(idx as u32 + 1, 0));
+        let new_text = if idx == anchor { block.clone() } else { String::new() };
+        result.edits.push(TextEdit { range, new_text });
+    }
+
+    Some(result)
+}
+
+/// Code action «Упорядочить объявления переменных модуля»
+pub fn organize_variables_action(
+    uri: &Url,
+    text: &str,
+    program: Option<&Program>,
+) -> Option<CodeActionOrCommand> {
+    let organized = organize_module_variables(text, program)?;
+
+    let mut title = "Упорядочить объявления переменных модуля".to_string();
+    let removed = organized.removed_unused.len() + organized.removed_duplicates.len();
+    if removed > 0 {
+        title.push_str(&format!(" (удалить: {})", removed));
+    }
+
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), organized.edits);
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::SOURCE),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = "Перем Счетчик;
+Перем Кеш Экспорт; // общий кеш
+Перем Буфер, Счетчик;
+&НаКлиенте
+Перем ФормаОткрыта;
+
+Процедура Увеличить(Буфер)
+    Перем ФормаОткрыта;
+    Счетчик = Счетчик + 1;
+    ФормаОткрыта = Буфер;
+КонецПроцедуры
+";
+
+    fn program() -> Program {
+        Program {
+            statements: vec![Statement::ProcedureDecl {
+                name: "Увеличить".to_string(),
+                params: vec![Parameter {
+                    name: "Буфер".to_string(),
+                    by_value: false,
+                    default_value: None,
+                }],
+                body: vec![
+                    Statement::VarDeclaration {
+                        name: "ФормаОткрыта".to_string(),
+                        export: false,
+                        value: None,
+                    },
+                    Statement::Assignment {
+                        target: Expression::Identifier("Счетчик".to_string()),
+                        value: Expression::Identifier("Счетчик".to_string()),
+                    },
+                    Statement::Assignment {
+                        target: Expression::Identifier("ФормаОткрыта".to_string()),
+                        value: Expression::Identifier("Буфер".to_string()),
+                    },
+                ],
+                export: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_scope_aware_usage() {
+        let used = used_module_variables(&program());
+        assert!(used.contains("счетчик"));
+        // Параметр и локальная переменная скрывают переменные модуля
+        assert!(!used.contains("буфер"));
+        assert!(!used.contains("формаоткрыта"));
+    }
+
+    #[test]
+    fn test_organize_declarations() {
+        let declarations = module_declarations(MODULE);
+        assert_eq!(declarations.len(), 5);
+        assert_eq!(declarations[4].directive.as_deref(), Some("&НаКлиенте"));
+
+        let organized = organize_module_variables(MODULE, Some(&program())).unwrap();
+        assert_eq!(organized.removed_duplicates, ["Счетчик"]);
+        // Имена параметра и локальной переменной встречаются в тексте — страховка оставляет их
+        assert!(organized.removed_unused.is_empty());
+        assert_eq!(
+            organized.edits[0].new_text,
+            concat!(
+                "Перем Кеш Экспорт; // общий кеш\nПерем Буфер;\nПерем Счетчик;\n",
+                "\n&НаКлиенте\nПерем ФормаОткрыта;\n"
+            )
+        );
+        assert_eq!(organized.edits.len(), 5);
+        assert!(organized.edits[1..].iter().all(|e| e.new_text.is_empty()));
+    }
+
+    #[test]
+    fn test_remove_unused_and_keep_exported() {
+        let text = "Перем Лишняя;\nПерем Внешняя Экспорт;\n\nПроцедура П()\nКонецПроцедуры\n";
+        let program = Program { statements: vec![] };
+        let organized = organize_module_variables(text, Some(&program)).unwrap();
+        assert_eq!(organized.removed_unused, ["Лишняя"]);
+        assert_eq!(organized.edits[0].new_text, "Перем Внешняя Экспорт;\n");
+
+        // Уже упорядоченный блок не порождает действия
+        assert!(organize_module_variables("Перем А;\nПерем Б;\n", None).is_none());
+    }
+}
@@ -0,0 +1,268 @@
+//! Владельцы кода: сопоставление модулей и объектов метаданных командам
+//!
+//! Формат файла совпадает с CODEOWNERS: `шаблон владелец1 владелец2`,
+//! комментарии начинаются с `#`, при нескольких совпадениях побеждает
+//! последнее правило. Кроме путей поддерживаются ссылки на объекты
+//! метаданных (`Catalog.Номенклатура`, `CommonModule.Продажи*`), которые
+//! сопоставляются с модулями выгрузки.
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::data::loaders::subsystems::object_for_module;
+
+/// Группа для модулей без владельца
+pub const UNOWNED: &str = "(без владельца)";
+
+/// Файлы владельцев, которые ищутся в корне проекта (в порядке приоритета)
+pub const OWNERS_FILE_CANDIDATES: &[&str] =
+    &["BSLOWNERS", ".bsl-owners", "CODEOWNERS", ".github/CODEOWNERS"];
+
+/// Что сопоставляет правило
+#[derive(Debug, Clone)]
+enum RuleTarget {
+    /// Путь модуля относительно корня проекта
+    Path(Vec<GlobMatcher>),
+    /// Объект метаданных вида `Catalog.Номенклатура`
+    Object(GlobMatcher),
+}
+
+/// Правило файла владельцев
+#[derive(Debug, Clone)]
+pub struct OwnershipRule {
+    /// Шаблон в исходном виде
+    pub pattern: String,
+    /// Владельцы (команды или пользователи)
+    pub owners: Vec<String>,
+    /// Номер строки в файле (с 1)
+    pub line: usize,
+    target: RuleTarget,
+}
+
+/// Карта владельцев проекта
+#[derive(Debug, Clone, Default)]
+pub struct OwnershipMap {
+    rules: Vec<OwnershipRule>,
+}
+
+/// Сводка по владельцу для отчётов
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OwnerSummary {
+    pub owner: String,
+    pub files: usize,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+impl OwnershipRule {
+    fn matches(&self, module_path: &str, object: Option<&str>) -> bool {
+        match &self.target {
+            RuleTarget::Path(matchers) => matchers.iter().any(|m| m.is_match(module_path)),
+            RuleTarget::Object(matcher) => object.is_some_and(|o| matcher.is_match(o)),
+        }
+    }
+}
+
+impl OwnershipMap {
+    /// Разобрать текст файла владельцев
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+
+        for (idx, raw_line) in text.lines().enumerate() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let pattern = parts.next().unwrap_or_default().to_string();
+            let owners: Vec<String> = parts.map(str::to_string).collect();
+            let target = compile_target(&pattern)
+                .with_context(|| format!("Строка {}: неверный шаблон '{}'", idx + 1, pattern))?;
+
+            rules.push(OwnershipRule {
+                pattern,
+                owners,
+                line: idx + 1,
+                target,
+            });
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Загрузить файл владельцев
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Ошибка в файле владельцев {}", path.display()))
+    }
+
+    /// Найти файл владельцев в корне проекта
+    pub fn discover(project_root: &Path) -> Option<PathBuf> {
+        OWNERS_FILE_CANDIDATES
+            .iter()
+            .map(|name| project_root.join(name))
+            .find(|path| path.is_file())
+    }
+
+    pub fn rules(&self) -> &[OwnershipRule] {
+        &self.rules
+    }
+
+    /// Владельцы модуля (путь относительно корня проекта); пусто, если не назначены.
+    /// Правило без владельцев снимает владельца, как в CODEOWNERS.
+    pub fn owners_for_module(&self, module_path: &str) -> &[String] {
+        let normalized = module_path.replace('\\', "/");
+        let normalized = normalized.trim_start_matches("./");
+        let object = object_for_module(normalized);
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(normalized, object.as_deref()))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Основной владелец модуля для группировки
+    pub fn group_for_module(&self, module_path: &str) -> String {
+        self.owners_for_module(module_path)
+            .first()
+            .cloned()
+            .unwrap_or_else(|| UNOWNED.to_string())
+    }
+
+    /// Подсчитать сводку по владельцам: файл с несколькими владельцами
+    /// учитывается у каждого из них
+    pub fn summarize<'a>(
+        &self,
+        files: impl IntoIterator<Item = &'a str>,
+        diagnostics: impl IntoIterator<Item = (&'a str, bool)>,
+    ) -> Vec<OwnerSummary> {
+        let mut groups: BTreeMap<String, OwnerSummary> = BTreeMap::new();
+        let owners_of = |path: &str| -> Vec<String> {
+            let owners = self.owners_for_module(path);
+            if owners.is_empty() {
+                vec![UNOWNED.to_string()]
+            } else {
+                owners.to_vec()
+            }
+        };
+
+        for path in files {
+            for owner in owners_of(path) {
+                owner_entry(&mut groups, owner).files += 1;
+            }
+        }
+        for (path, is_error) in diagnostics {
+            for owner in owners_of(path) {
+                let summary = owner_entry(&mut groups, owner);
+                if is_error {
+                    summary.errors += 1;
+                } else {
+                    summary.warnings += 1;
+                }
+            }
+        }
+
+        groups.into_values().collect()
+    }
+}
+
+fn owner_entry(groups: &mut BTreeMap<String, OwnerSummary>, owner: String) -> &mut OwnerSummary {
+    groups.entry(owner.clone()).or_insert_with(|| OwnerSummary {
+        owner,
+        ..Default::default()
+    })
+}
+
+/// Скомпилировать шаблон по правилам CODEOWNERS
+fn compile_target(pattern: &str) -> Result<RuleTarget> {
+    let is_object_ref = !pattern.contains('/')
+        && pattern.split_once('.').is_some_and(|(kind, name)| {
+            kind.chars().all(|c| c.is_ascii_alphabetic()) && !name.eq_ignore_ascii_case("bsl")
+        });
+    if is_object_ref {
+        return Ok(RuleTarget::Object(Glob::new(pattern)?.compile_matcher()));
+    }
+
+    // Шаблон с `/` в начале или середине привязан к корню, иначе совпадает на любой глубине
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.contains('/');
+    let base = trimmed.trim_start_matches('/');
+    let base = if anchored {
+        base.to_string()
+    } else {
+        format!("**/{}", base)
+    };
+
+    // Шаблон каталога захватывает всё содержимое
+    let mut matchers = vec![build_glob(&format!("{}/**", base))?];
+    if !pattern.ends_with('/') {
+        matchers.push(build_glob(&base)?);
+    }
+    Ok(RuleTarget::Path(matchers))
+}
+
+fn build_glob(pattern: &str) -> Result<GlobMatcher> {
+    Ok(GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNERS: &str = r#"
+# Владельцы по умолчанию
+*                       @core
+CommonModules/          @platform-team
+Catalog.Номенклатура    @nsi @sales
+CommonModule.Продажи*   @sales
+/Documents/Заказ/Forms  @ui
+"#;
+
+    #[test]
+    fn test_owners_for_module() {
+        let map = OwnershipMap::parse(OWNERS).unwrap();
+        assert_eq!(map.rules().len(), 5);
+
+        assert_eq!(map.owners_for_module("Reports/Продажи/Ext/ObjectModule.bsl"), ["@core"]);
+        assert_eq!(
+            map.owners_for_module("CommonModules/Общий/Ext/Module.bsl"),
+            ["@platform-team"]
+        );
+        assert_eq!(
+            map.owners_for_module("CommonModules/ПродажиСервер/Ext/Module.bsl"),
+            ["@sales"]
+        );
+        assert_eq!(
+            map.owners_for_module("Catalogs/Номенклатура/Ext/ObjectModule.bsl"),
+            ["@nsi", "@sales"]
+        );
+        assert_eq!(
+            map.owners_for_module("Documents/Заказ/Forms/Форма/Ext/Form/Module.bsl"),
+            ["@ui"]
+        );
+        assert_eq!(map.group_for_module("Documents/Заказ/Ext/ObjectModule.bsl"), "@core");
+    }
+
+    #[test]
+    fn test_summarize_by_owner() {
+        let map = OwnershipMap::parse("Catalog.Номенклатура @nsi @sales\n").unwrap();
+        let module = "Catalogs/Номенклатура/Ext/ObjectModule.bsl";
+        let other = "CommonModules/Общий/Ext/Module.bsl";
+
+        let summary = map.summarize([module, other], [(module, true), (other, false)]);
+        let owners: Vec<_> = summary.iter().map(|s| s.owner.as_str()).collect();
+        assert_eq!(owners, vec![UNOWNED, "@nsi", "@sales"]);
+        assert_eq!(summary[1].errors, 1);
+        assert_eq!(summary[0].warnings, 1);
+    }
+}
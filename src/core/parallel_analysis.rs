@@ -9,9 +9,11 @@ use rayon::prelude::*;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use tracing::info_span;
 
 use crate::core::analysis_cache::AnalysisCacheManager;
 use crate::core::type_checker::{TypeChecker, TypeContext, TypeDiagnostic};
+use crate::data::WriteGuard;
 use crate::parsing::bsl::common::ParserFactory;
 
 /// Результат анализа одного файла
@@ -72,8 +74,10 @@ pub struct ParallelAnalysisConfig {
     pub show_progress: bool,
     /// Использовать кеширование
     pub use_cache: bool,
-    /// Директория для кеша
-    pub cache_dir: Option<PathBuf>,
+    /// Хранилище кеша: каталог, `sqlite://` или `s3://`
+    pub cache_location: Option<String>,
+    /// Разрешение на запись кеша (режим только для чтения)
+    pub write_guard: WriteGuard,
 }
 
 impl Default for ParallelAnalysisConfig {
@@ -83,7 +87,8 @@ impl Default for ParallelAnalysisConfig {
             chunk_size: 10,
             show_progress: true,
             use_cache: true,
-            cache_dir: Some(PathBuf::from(".bsl_cache")),
+            cache_location: Some(".bsl_cache".to_string()),
+            write_guard: WriteGuard::default(),
         }
     }
 }
@@ -98,8 +103,12 @@ impl ParallelAnalyzer {
     /// Создать новый параллельный анализатор
     pub fn new(config: ParallelAnalysisConfig) -> Result<Self> {
         let cache_manager = if config.use_cache {
-            if let Some(cache_dir) = &config.cache_dir {
-                let manager = AnalysisCacheManager::new(cache_dir, env!("CARGO_PKG_VERSION"))?;
+            if let Some(location) = &config.cache_location {
+                let manager = AnalysisCacheManager::open(
+                    location,
+                    env!("CARGO_PKG_VERSION"),
+                    config.write_guard.clone(),
+                )?;
                 Some(Arc::new(Mutex::new(manager)))
             } else {
                 None
@@ -163,6 +172,7 @@ impl ParallelAnalyzer {
                         functions: HashMap::new(),
                         current_scope: crate::core::dependency_graph::Scope::Global,
                         scope_stack: vec![],
+                        active_contexts: crate::parsing::bsl::ContextSet::ALL,
                     },
                     diagnostics: vec![],
                     analysis_time: std::time::Duration::ZERO,
@@ -209,6 +219,7 @@ impl ParallelAnalyzer {
                             functions: cached.function_signatures,
                             current_scope: crate::core::dependency_graph::Scope::Global,
                             scope_stack: vec![],
+                            active_contexts: crate::parsing::bsl::ContextSet::ALL,
                         },
                         diagnostics: vec![], // Кешированные диагностики не сохраняем пока
                         analysis_time: std::time::Duration::from_micros(10), // Очень быстро из кеша
@@ -219,18 +230,19 @@ impl ParallelAnalyzer {
             }
         }
 
-        // Анализируем файл
-        let mut parser = ParserFactory::create();
-        let program = parser.parse(&content)?;
-
         let file_name = file_path
             .file_name()
             .and_then(|name| name.to_str())
             .unwrap_or("unknown.bsl")
             .to_string();
 
-        let type_checker = TypeChecker::new(file_name);
-        let (context, diagnostics) = type_checker.check(&program);
+        // Анализируем файл
+        let mut parser = ParserFactory::create();
+        let program = info_span!("parse", file = %file_name).in_scope(|| parser.parse(&content))?;
+
+        let type_checker = TypeChecker::new(file_name.clone());
+        let (context, diagnostics) =
+            info_span!("resolve", file = %file_name).in_scope(|| type_checker.check(&program));
 
         let analysis_time = analysis_start.elapsed();
 
@@ -432,6 +444,9 @@ impl ProjectAnalysisUtils {
         // Стандартные места расположения конфигурации
         let candidates = [
             root.join("src").join("cf"),
+            root.join("src")
+                .join("Configuration")
+                .join("Configuration.mdo"),
             root.join("Configuration.xml"),
             root.join("Ext").join("Configuration.xml"),
             root.join("ConfigDumpInfo.xml"),
@@ -511,6 +511,7 @@ impl BenchmarkSuite {
                 functions: std::collections::HashMap::new(),
                 current_scope: crate::core::dependency_graph::Scope::Global,
                 scope_stack: vec![],
+                active_contexts: crate::parsing::bsl::ContextSet::ALL,
             };
 
             let mut analyzer = crate::core::flow_sensitive::FlowSensitiveAnalyzer::new(context);
@@ -255,6 +255,7 @@ impl PlatformTypeResolver {
                 line: None,
                 column: None,
                 notes: vec![format!("Platform manager type: {}", name)],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -366,6 +367,7 @@ impl PlatformTypeResolver {
                 line: None,
                 column: None,
                 notes: vec![format!("Inferred catalog type: {}", qualified_name)],
+                resolver: None,
             },
             // Default facet is Manager for "Справочники.X"
             active_facet: Some(FacetKind::Manager),
@@ -397,6 +399,7 @@ impl PlatformTypeResolver {
                 line: None,
                 column: None,
                 notes: vec![format!("Inferred document type: {}", qualified_name)],
+                resolver: None,
             },
             active_facet: Some(FacetKind::Manager),
             available_facets: vec![
@@ -427,6 +430,7 @@ impl PlatformTypeResolver {
                 line: None,
                 column: None,
                 notes: vec![format!("Inferred enum type: {}", qualified_name)],
+                resolver: None,
             },
             active_facet: Some(FacetKind::Manager),
             available_facets: vec![
@@ -447,6 +451,7 @@ impl PlatformTypeResolver {
                 line: None,
                 column: None,
                 notes: vec![reason.to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
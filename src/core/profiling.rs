@@ -0,0 +1,259 @@
+//! Профиль выполнения в формате Chrome Tracing
+//!
+//! Слой `tracing` записывает время жизни спанов (разбор, разрешение типов,
+//! индексация) и сохраняет их как события `"ph": "X"` формата Trace Event.
+//! Файл открывается в `chrome://tracing`, Perfetto UI и speedscope, которые
+//! показывают его в виде flamegraph, — его можно приложить к issue о
+//! производительности.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::cell::Cell;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context as LayerContext;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Событие Trace Event Format (complete event)
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+    pub name: String,
+    /// Target спана (модуль, в котором он создан)
+    pub cat: String,
+    pub ph: &'static str,
+    /// Начало в микросекундах от старта профилирования
+    pub ts: f64,
+    /// Длительность в микросекундах
+    pub dur: f64,
+    pub pid: u32,
+    pub tid: u64,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub args: Map<String, Value>,
+}
+
+/// Накопленные события профиля; клонируется между слоем и владельцем
+#[derive(Debug, Clone)]
+pub struct TraceRecorder {
+    start: Instant,
+    events: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+impl TraceRecorder {
+    /// Записанные события в порядке закрытия спанов
+    pub fn events(&self) -> Vec<TraceEvent> {
+        self.events
+            .lock()
+            .map(|events| events.clone())
+            .unwrap_or_default()
+    }
+
+    /// Сохранить профиль в JSON файл формата Chrome Tracing
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        let mut events = self.events();
+        // Просмотрщики ожидают события в порядке начала
+        events.sort_by(|a, b| a.ts.total_cmp(&b.ts));
+        let trace = serde_json::json!({
+            "traceEvents": events,
+            "displayTimeUnit": "ms",
+        });
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Не удалось создать файл профиля {}", path.display()))?;
+        serde_json::to_writer(std::io::BufWriter::new(file), &trace)?;
+        Ok(())
+    }
+
+    fn micros_since_start(&self, at: Instant) -> f64 {
+        at.duration_since(self.start).as_secs_f64() * 1_000_000.0
+    }
+}
+
+/// Слой `tracing`, записывающий спаны в [`TraceRecorder`]
+pub struct ChromeTraceLayer {
+    recorder: TraceRecorder,
+}
+
+/// Создать слой профилирования и связанный с ним накопитель событий
+pub fn chrome_trace_layer() -> (ChromeTraceLayer, TraceRecorder) {
+    let recorder = TraceRecorder {
+        start: Instant::now(),
+        events: Arc::new(Mutex::new(Vec::new())),
+    };
+    (
+        ChromeTraceLayer {
+            recorder: recorder.clone(),
+        },
+        recorder,
+    )
+}
+
+/// Состояние открытого спана (хранится в extensions реестра)
+struct SpanTiming {
+    args: Map<String, Value>,
+    /// Первый вход в спан и поток, в котором он произошёл
+    entered: Option<(Instant, u64)>,
+}
+
+/// Короткий стабильный номер текущего потока для поля `tid`
+fn current_thread_id() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static ID: Cell<u64> = const { Cell::new(0) };
+    }
+    ID.with(|id| {
+        if id.get() == 0 {
+            id.set(NEXT.fetch_add(1, Ordering::Relaxed));
+        }
+        id.get()
+    })
+}
+
+/// Значения полей спана как аргументы события
+struct ArgsVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for ArgsVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(
+            field.name().to_string(),
+            Value::String(format!("{:?}", value)),
+        );
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0
+            .insert(field.name().to_string(), Value::String(value.to_string()));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+}
+
+impl<S> Layer<S> for ChromeTraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut args = Map::new();
+        attrs.record(&mut ArgsVisitor(&mut args));
+        span.extensions_mut().insert(SpanTiming {
+            args,
+            entered: None,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(timing) = extensions.get_mut::<SpanTiming>() {
+            values.record(&mut ArgsVisitor(&mut timing.args));
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(timing) = extensions.get_mut::<SpanTiming>() {
+            // Асинхронный спан входит многократно — длительность считаем от первого входа
+            if timing.entered.is_none() {
+                timing.entered = Some((Instant::now(), current_thread_id()));
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(timing) = span.extensions_mut().remove::<SpanTiming>() else {
+            return;
+        };
+        // Спан, в который ни разу не входили, не занимал времени
+        let Some((entered, tid)) = timing.entered else {
+            return;
+        };
+        let event = TraceEvent {
+            name: span.name().to_string(),
+            cat: span.metadata().target().to_string(),
+            ph: "X",
+            ts: self.recorder.micros_since_start(entered),
+            dur: entered.elapsed().as_secs_f64() * 1_000_000.0,
+            pid: std::process::id(),
+            tid,
+            args: timing.args,
+        };
+        if let Ok(mut events) = self.recorder.events.lock() {
+            events.push(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::info_span;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_nested_spans_recorded() {
+        let (layer, recorder) = chrome_trace_layer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            info_span!("index").in_scope(|| {
+                info_span!("parse", file = "Модуль.bsl").in_scope(|| {});
+                // Спан без входа не попадает в профиль
+                let _unused = info_span!("resolve");
+            });
+        });
+
+        let events = recorder.events();
+        let names: Vec<&str> = events.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["parse", "index"]);
+        let (parse, index) = (&events[0], &events[1]);
+        assert_eq!(parse.args["file"], "Модуль.bsl");
+        assert_eq!(parse.tid, index.tid);
+        assert!(parse.ts >= index.ts);
+        assert!(parse.ts + parse.dur <= index.ts + index.dur);
+    }
+
+    #[test]
+    fn test_write_chrome_trace() {
+        let (layer, recorder) = chrome_trace_layer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            info_span!("parse", source = "platform").in_scope(|| {});
+        });
+
+        let path = std::env::temp_dir().join(format!("bsl_profile_{}.json", std::process::id()));
+        recorder.write_to(&path).unwrap();
+        let trace: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let event = &trace["traceEvents"][0];
+        assert_eq!(event["name"], "parse");
+        assert_eq!(event["ph"], "X");
+        assert_eq!(event["args"]["source"], "platform");
+        assert!(event["dur"].as_f64().unwrap() >= 0.0);
+    }
+}
@@ -0,0 +1,416 @@
+//! Пороги качества (quality gates) для CI
+//!
+//! После анализа проекта проверяются настроенные пороги: максимум ошибок,
+//! минимальное покрытие типизации, отсутствие новых `Dynamic` в строгих
+//! модулях и отсутствие снижения покрытия относительно baseline (по проекту
+//! и по каждому модулю). Каждый порог имеет свой код выхода, чтобы CI мог
+//! отличить причину падения сборки.
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Код выхода при ошибке выполнения (не связан с порогами)
+pub const EXIT_RUNTIME_ERROR: i32 = 1;
+
+/// Допустимое снижение покрытия, процентных пунктов: погрешность округления
+/// процентов в файле baseline
+pub const COVERAGE_DROP_TOLERANCE: f32 = 0.05;
+
+/// Настройки порогов качества
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityGatesConfig {
+    /// Максимально допустимое количество ошибок типов
+    pub max_errors: Option<usize>,
+    /// Минимальное покрытие типизации, %
+    pub min_coverage: Option<f32>,
+    /// Шаблоны путей строгих модулей (`CommonModules/**`), в которых
+    /// не допускаются новые выражения с типом Dynamic
+    pub strict_modules: Vec<String>,
+    /// JSON-файл с допустимым количеством Dynamic по модулям (baseline)
+    pub dynamic_baseline: Option<String>,
+    /// JSON-файл с покрытием типизации проекта и модулей, ниже которого
+    /// покрытие опускаться не должно
+    pub coverage_baseline: Option<String>,
+}
+
+impl QualityGatesConfig {
+    /// Задан ли хотя бы один порог
+    pub fn is_enabled(&self) -> bool {
+        self.max_errors.is_some()
+            || self.min_coverage.is_some()
+            || !self.strict_modules.is_empty()
+            || self.coverage_baseline.is_some()
+    }
+}
+
+/// Порог качества
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityGate {
+    MaxErrors,
+    MinCoverage,
+    NoNewDynamic,
+    NoCoverageDrop,
+}
+
+impl QualityGate {
+    /// Код выхода процесса при нарушении порога. Коды не пересекаются с
+    /// кодами других команд CLI: `5` — недетерминизм вывода, `6` —
+    /// несогласованность данных, `7` — провал самопроверки
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            QualityGate::MaxErrors => 2,
+            QualityGate::MinCoverage => 3,
+            QualityGate::NoNewDynamic => 4,
+            QualityGate::NoCoverageDrop => 8,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            QualityGate::MaxErrors => "Максимум ошибок",
+            QualityGate::MinCoverage => "Минимальное покрытие",
+            QualityGate::NoNewDynamic => "Нет новых Dynamic в строгих модулях",
+            QualityGate::NoCoverageDrop => "Покрытие не ниже baseline",
+        }
+    }
+}
+
+/// Данные анализа, по которым проверяются пороги
+#[derive(Debug, Clone, Default)]
+pub struct GateInput {
+    pub errors: usize,
+    pub coverage_percentage: f32,
+    /// Путь модуля (относительно корня проекта) → количество выражений Dynamic
+    pub dynamic_by_module: BTreeMap<String, usize>,
+    /// Путь модуля → покрытие типизации, % (модули без выражений не входят)
+    pub coverage_by_module: BTreeMap<String, f32>,
+}
+
+/// Результат проверки одного порога
+#[derive(Debug, Clone, Serialize)]
+pub struct GateResult {
+    pub gate: QualityGate,
+    pub passed: bool,
+    pub actual: String,
+    pub threshold: String,
+    /// Подробности нарушения (например, модули с новыми Dynamic)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+/// Итог проверки порогов
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GateReport {
+    pub results: Vec<GateResult>,
+}
+
+impl GateReport {
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.passed)
+    }
+
+    /// Нарушенные пороги
+    pub fn failed(&self) -> impl Iterator<Item = &GateResult> {
+        self.results.iter().filter(|r| !r.passed)
+    }
+
+    /// Код выхода: 0 — все пороги пройдены, иначе код первого нарушенного порога
+    /// (порядок: ошибки, покрытие, Dynamic, снижение покрытия)
+    pub fn exit_code(&self) -> i32 {
+        self.failed().map(|r| r.gate.exit_code()).next().unwrap_or(0)
+    }
+
+    /// Текстовая сводка для консоли
+    pub fn to_text(&self) -> String {
+        let mut output = String::from("🚦 Пороги качества:\n");
+        for result in &self.results {
+            output.push_str(&format!(
+                "   {} {}: {} (порог: {})\n",
+                if result.passed { "✅" } else { "❌" },
+                result.gate.title(),
+                result.actual,
+                result.threshold
+            ));
+            for detail in &result.details {
+                output.push_str(&format!("      - {}\n", detail));
+            }
+        }
+        if !self.passed() {
+            output.push_str(&format!("   Код выхода: {}\n", self.exit_code()));
+        }
+        output
+    }
+}
+
+/// Baseline допустимых Dynamic по модулям
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DynamicBaseline {
+    pub modules: BTreeMap<String, usize>,
+}
+
+impl DynamicBaseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать baseline {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Некорректный baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Не удалось записать baseline {}", path.display()))
+    }
+
+    /// Снять baseline по строгим модулям из текущих результатов анализа
+    pub fn capture(config: &QualityGatesConfig, input: &GateInput) -> Result<Self> {
+        let strict = compile_strict_modules(&config.strict_modules)?;
+        Ok(Self {
+            modules: input
+                .dynamic_by_module
+                .iter()
+                .filter(|(module, count)| **count > 0 && strict.is_match(module.as_str()))
+                .map(|(module, count)| (module.clone(), *count))
+                .collect(),
+        })
+    }
+}
+
+/// Baseline покрытия типизации: проект в целом и модули
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoverageBaseline {
+    /// Покрытие проекта, %
+    pub total: f32,
+    /// Путь модуля (относительно корня проекта) → покрытие, %
+    pub modules: BTreeMap<String, f32>,
+}
+
+impl CoverageBaseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать baseline {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Некорректный baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Не удалось записать baseline {}", path.display()))
+    }
+
+    /// Снять baseline покрытия из текущих результатов анализа
+    pub fn capture(input: &GateInput) -> Self {
+        Self {
+            total: input.coverage_percentage,
+            modules: input.coverage_by_module.clone(),
+        }
+    }
+
+    /// Снижения покрытия относительно baseline: проект и модули, покрытие
+    /// которых упало больше допуска. Новые модули и модули без выражений не
+    /// сравниваются
+    pub fn drops(&self, input: &GateInput) -> Vec<String> {
+        let dropped = |actual: f32, baseline: f32| actual < baseline - COVERAGE_DROP_TOLERANCE;
+        let mut drops = Vec::new();
+        if dropped(input.coverage_percentage, self.total) {
+            drops.push(format!(
+                "проект: {:.1}% (было {:.1}%)",
+                input.coverage_percentage, self.total
+            ));
+        }
+        for (module, &baseline) in &self.modules {
+            match input.coverage_by_module.get(module) {
+                Some(&actual) if dropped(actual, baseline) => {
+                    drops.push(format!(
+                        "{}: {:.1}% (было {:.1}%)",
+                        module, actual, baseline
+                    ));
+                }
+                _ => {}
+            }
+        }
+        drops
+    }
+}
+
+fn compile_strict_modules(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(
+            Glob::new(pattern)
+                .with_context(|| format!("Неверный шаблон строгих модулей '{}'", pattern))?,
+        );
+    }
+    Ok(builder.build()?)
+}
+
+/// Проверить пороги качества. Без baseline Dynamic любой Dynamic в строгом
+/// модуле считается новым; снижение покрытия проверяется только с baseline
+/// покрытия.
+pub fn evaluate(
+    config: &QualityGatesConfig,
+    input: &GateInput,
+    baseline: Option<&DynamicBaseline>,
+    coverage_baseline: Option<&CoverageBaseline>,
+) -> Result<GateReport> {
+    let mut results = Vec::new();
+
+    if let Some(max_errors) = config.max_errors {
+        results.push(GateResult {
+            gate: QualityGate::MaxErrors,
+            passed: input.errors <= max_errors,
+            actual: input.errors.to_string(),
+            threshold: format!("≤ {}", max_errors),
+            details: Vec::new(),
+        });
+    }
+
+    if let Some(min_coverage) = config.min_coverage {
+        results.push(GateResult {
+            gate: QualityGate::MinCoverage,
+            passed: input.coverage_percentage >= min_coverage,
+            actual: format!("{:.1}%", input.coverage_percentage),
+            threshold: format!("≥ {:.1}%", min_coverage),
+            details: Vec::new(),
+        });
+    }
+
+    if !config.strict_modules.is_empty() {
+        let strict = compile_strict_modules(&config.strict_modules)?;
+        let mut details = Vec::new();
+        let mut new_dynamic = 0;
+
+        for (module, &count) in &input.dynamic_by_module {
+            if !strict.is_match(module.as_str()) {
+                continue;
+            }
+            let allowed = baseline
+                .and_then(|b| b.modules.get(module))
+                .copied()
+                .unwrap_or(0);
+            if count > allowed {
+                new_dynamic += count - allowed;
+                details.push(format!("{}: {} (допустимо {})", module, count, allowed));
+            }
+        }
+
+        results.push(GateResult {
+            gate: QualityGate::NoNewDynamic,
+            passed: new_dynamic == 0,
+            actual: format!("новых Dynamic: {}", new_dynamic),
+            threshold: "0".to_string(),
+            details,
+        });
+    }
+
+    if let Some(coverage_baseline) = coverage_baseline {
+        let details = coverage_baseline.drops(input);
+        results.push(GateResult {
+            gate: QualityGate::NoCoverageDrop,
+            passed: details.is_empty(),
+            actual: format!("снижений: {}", details.len()),
+            threshold: format!("≥ {:.1}% по проекту и модулям", coverage_baseline.total),
+            details,
+        });
+    }
+
+    Ok(GateReport { results })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> GateInput {
+        GateInput {
+            errors: 3,
+            coverage_percentage: 72.5,
+            dynamic_by_module: BTreeMap::from([
+                ("CommonModules/Ядро/Ext/Module.bsl".to_string(), 2),
+                ("Catalogs/Товары/Ext/ObjectModule.bsl".to_string(), 10),
+            ]),
+            coverage_by_module: BTreeMap::from([
+                ("CommonModules/Ядро/Ext/Module.bsl".to_string(), 90.0),
+                ("Catalogs/Товары/Ext/ObjectModule.bsl".to_string(), 60.0),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_gates_exit_codes() {
+        let config = QualityGatesConfig {
+            max_errors: Some(5),
+            min_coverage: Some(80.0),
+            strict_modules: vec!["CommonModules/**".to_string()],
+            dynamic_baseline: None,
+            coverage_baseline: None,
+        };
+
+        let report = evaluate(&config, &input(), None, None).unwrap();
+        assert!(!report.passed());
+        let failed: Vec<_> = report.failed().map(|r| r.gate).collect();
+        assert_eq!(
+            failed,
+            vec![QualityGate::MinCoverage, QualityGate::NoNewDynamic]
+        );
+        assert_eq!(report.exit_code(), 3);
+        assert!(report.to_text().contains("CommonModules/Ядро/Ext/Module.bsl: 2"));
+    }
+
+    #[test]
+    fn test_dynamic_baseline() {
+        let config = QualityGatesConfig {
+            strict_modules: vec!["CommonModules/**".to_string()],
+            ..Default::default()
+        };
+
+        let baseline = DynamicBaseline::capture(&config, &input()).unwrap();
+        assert_eq!(baseline.modules.len(), 1);
+
+        let report = evaluate(&config, &input(), Some(&baseline), None).unwrap();
+        assert!(report.passed());
+        assert_eq!(report.exit_code(), 0);
+    }
+
+    #[test]
+    fn test_coverage_baseline() {
+        let config = QualityGatesConfig {
+            coverage_baseline: Some("coverage-baseline.json".to_string()),
+            ..Default::default()
+        };
+        assert!(config.is_enabled());
+
+        let baseline = CoverageBaseline::capture(&input());
+        let report = evaluate(&config, &input(), None, Some(&baseline)).unwrap();
+        assert!(report.passed());
+
+        // Покрытие справочника упало, новый модуль не сравнивается
+        let mut dropped = input();
+        dropped.coverage_percentage = 72.5 - COVERAGE_DROP_TOLERANCE / 2.0;
+        dropped
+            .coverage_by_module
+            .insert("Catalogs/Товары/Ext/ObjectModule.bsl".to_string(), 55.0);
+        dropped
+            .coverage_by_module
+            .insert("Documents/Заказ/Ext/ObjectModule.bsl".to_string(), 0.0);
+        let report = evaluate(&config, &dropped, None, Some(&baseline)).unwrap();
+        assert_eq!(report.exit_code(), 8);
+        let failed = report.failed().next().unwrap();
+        assert_eq!(
+            failed.details,
+            ["Catalogs/Товары/Ext/ObjectModule.bsl: 55.0% (было 60.0%)"]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coverage-baseline.json");
+        baseline.save(&path).unwrap();
+        let loaded = CoverageBaseline::load(&path).unwrap();
+        assert_eq!(loaded.modules, baseline.modules);
+        assert_eq!(loaded.total, 72.5);
+    }
+}
@@ -0,0 +1,374 @@
+//! Индекс «перейти к чему угодно» (палитра Ctrl+P)
+//!
+//! Объединяет типы, их методы, категории справки и символы проекта
+//! (процедуры и функции модулей) в один список с общим ранжированием.
+//! Индекс строится один раз, а поиск — линейный проход по заранее
+//! приведённым к нижнему регистру именам, что укладывается в единицы
+//! миллисекунд даже на полной справке платформы.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::method_header_regex;
+use crate::unified::data::RawTypeData;
+
+/// Лимит результатов по умолчанию
+pub const DEFAULT_QUICK_OPEN_LIMIT: usize = 50;
+
+/// Вид элемента палитры
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickOpenKind {
+    Type,
+    Method,
+    Category,
+    Symbol,
+}
+
+impl QuickOpenKind {
+    /// Надбавка к оценке при равном совпадении имени
+    fn bonus(&self) -> u32 {
+        match self {
+            QuickOpenKind::Type => 30,
+            QuickOpenKind::Symbol => 20,
+            QuickOpenKind::Method => 10,
+            QuickOpenKind::Category => 0,
+        }
+    }
+}
+
+/// Элемент палитры
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuickOpenItem {
+    pub label: String,
+    pub kind: QuickOpenKind,
+    /// Пояснение: владелец метода, путь категории, модуль символа
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Страница веб-интерфейса
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Файл и строка (с 0) для символов проекта
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// Найденный элемент с оценкой (больше — выше в списке)
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickOpenMatch {
+    #[serde(flatten)]
+    pub item: QuickOpenItem,
+    pub score: u32,
+}
+
+struct Entry {
+    item: QuickOpenItem,
+    /// Имена для сопоставления в нижнем регистре
+    keys: Vec<String>,
+    /// Первые буквы слов имени (`ТаблицаЗначений` → `тз`)
+    initials: String,
+}
+
+/// Индекс палитры
+#[derive(Default)]
+pub struct QuickOpenIndex {
+    entries: Vec<Entry>,
+}
+
+/// Первые буквы слов: заглавные после строчных и буквы после `.`/`_`
+fn initials(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev: Option<char> = None;
+    for ch in name.chars() {
+        let starts_word = match prev {
+            None => true,
+            Some(p) => (ch.is_uppercase() && !p.is_uppercase()) || matches!(p, '.' | '_' | ' '),
+        };
+        if starts_word && ch.is_alphanumeric() {
+            out.extend(ch.to_lowercase());
+        }
+        prev = Some(ch);
+    }
+    out
+}
+
+/// Все символы запроса встречаются в имени по порядку
+fn is_subsequence(query: &str, name: &str) -> bool {
+    let mut chars = name.chars();
+    query.chars().all(|q| chars.any(|c| c == q))
+}
+
+/// Оценка совпадения имени с запросом (оба в нижнем регистре)
+fn name_score(query: &str, key: &str, initials: &str) -> Option<u32> {
+    let length_penalty = key.chars().count().min(100) as u32;
+    if key == query {
+        Some(1000)
+    } else if key.starts_with(query) {
+        Some(800 - length_penalty)
+    } else if initials.starts_with(query) {
+        Some(600 - length_penalty)
+    } else if let Some(pos) = key.find(query) {
+        Some(400 - (key[..pos].chars().count().min(100) as u32) - length_penalty / 2)
+    } else if is_subsequence(query, key) {
+        Some(200 - length_penalty)
+    } else {
+        None
+    }
+}
+
+/// Оценка совпадения имени с запросом без учёта регистра (та же шкала, что
+/// у палитры); `None`, если имя не подходит
+pub fn match_score(query: &str, name: &str) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    name_score(&query, &name.to_lowercase(), &initials(name))
+}
+
+impl QuickOpenIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Добавить элемент; `aliases` — дополнительные имена для поиска
+    pub fn push(&mut self, item: QuickOpenItem, aliases: &[&str]) {
+        let mut keys = vec![item.label.to_lowercase()];
+        keys.extend(
+            aliases
+                .iter()
+                .filter(|alias| !alias.is_empty())
+                .map(|alias| alias.to_lowercase()),
+        );
+        let initials = initials(&item.label);
+        self.entries.push(Entry {
+            item,
+            keys,
+            initials,
+        });
+    }
+
+    /// Добавить типы, их методы и категории
+    pub fn add_types(&mut self, types: &[RawTypeData]) {
+        let mut categories = HashSet::new();
+        for raw in types {
+            let url = format!("/types/{}", urlencoding::encode(&raw.russian_name));
+            self.push(
+                QuickOpenItem {
+                    label: raw.russian_name.clone(),
+                    kind: QuickOpenKind::Type,
+                    detail: (!raw.category_path.is_empty()).then(|| raw.category_path.join(" / ")),
+                    url: Some(url.clone()),
+                    file: None,
+                    line: None,
+                },
+                &[&raw.english_name],
+            );
+            for method in &raw.methods {
+                // `Массив.Добавить` находит метод по запросу с владельцем
+                let qualified = format!("{}.{}", raw.russian_name, method.name);
+                self.push(
+                    QuickOpenItem {
+                        label: method.name.clone(),
+                        kind: QuickOpenKind::Method,
+                        detail: Some(raw.russian_name.clone()),
+                        url: Some(format!("{}#{}", url, urlencoding::encode(&method.name))),
+                        file: None,
+                        line: None,
+                    },
+                    &[&qualified],
+                );
+            }
+            for category in &raw.category_path {
+                if categories.insert(category.clone()) {
+                    self.push(
+                        QuickOpenItem {
+                            label: category.clone(),
+                            kind: QuickOpenKind::Category,
+                            detail: None,
+                            url: Some(format!("/?category={}", urlencoding::encode(category))),
+                            file: None,
+                            line: None,
+                        },
+                        &[],
+                    );
+                }
+            }
+        }
+    }
+
+    /// Добавить процедуры и функции модуля
+    pub fn add_module_symbols(&mut self, file_path: &str, text: &str) {
+        for item in module_symbols(file_path, text) {
+            self.push(item, &[]);
+        }
+    }
+
+    /// Найти до `limit` элементов, лучшие первыми. Пустой запрос ничего
+    /// не находит.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<QuickOpenMatch> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let mut found: Vec<(u32, &Entry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let best = entry
+                    .keys
+                    .iter()
+                    .filter_map(|key| name_score(&query, key, &entry.initials))
+                    .max()?;
+                Some((best + entry.item.kind.bonus(), entry))
+            })
+            .collect();
+
+        let by_rank = |a: &(u32, &Entry), b: &(u32, &Entry)| {
+            b.0.cmp(&a.0)
+                .then_with(|| a.1.item.label.len().cmp(&b.1.item.label.len()))
+                .then_with(|| a.1.item.label.cmp(&b.1.item.label))
+        };
+        if found.len() > limit {
+            found.select_nth_unstable_by(limit - 1, by_rank);
+            found.truncate(limit);
+        }
+        found.sort_by(by_rank);
+        found
+            .into_iter()
+            .map(|(score, entry)| QuickOpenMatch {
+                item: entry.item.clone(),
+                score,
+            })
+            .collect()
+    }
+}
+
+/// Процедуры и функции модуля как элементы палитры
+pub fn module_symbols(file_path: &str, text: &str) -> Vec<QuickOpenItem> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let caps = method_header_regex().captures(strip_comment(line))?;
+            Some(QuickOpenItem {
+                label: caps[2].to_string(),
+                kind: QuickOpenKind::Symbol,
+                detail: Some(file_path.to_string()),
+                url: None,
+                file: Some(file_path.to_string()),
+                line: Some(idx as u32),
+            })
+        })
+        .collect()
+}
+
+/// Символы всех модулей проекта (пути относительно `root`)
+pub fn project_symbols(root: &Path) -> Vec<QuickOpenItem> {
+    let mut out = Vec::new();
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let path = entry.path();
+        if !crate::core::fs_utils::is_bsl_file(path) {
+            continue;
+        }
+        let Ok(text) = crate::core::fs_utils::read_bsl_file(path) else {
+            continue;
+        };
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        out.extend(module_symbols(&relative.to_string_lossy(), &text));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unified::data::{RawMethodData, TypeSource};
+
+    fn raw_type(russian: &str, english: &str, methods: &[&str]) -> RawTypeData {
+        let platform = TypeSource::Platform {
+            version: "8.3".to_string(),
+        };
+        RawTypeData {
+            english_name: english.to_string(),
+            category_path: vec!["Универсальные коллекции значений".to_string()],
+            methods: methods
+                .iter()
+                .map(|name| RawMethodData::test_method(name))
+                .collect(),
+            ..RawTypeData::test_type(russian, platform)
+        }
+    }
+
+    fn index() -> QuickOpenIndex {
+        let mut index = QuickOpenIndex::new();
+        index.add_types(&[
+            raw_type("Массив", "Array", &["Добавить", "Количество"]),
+            raw_type("ТаблицаЗначений", "ValueTable", &["Добавить"]),
+        ]);
+        index.add_module_symbols(
+            "CommonModules/Общий/Module.bsl",
+            "Функция МассивИзСтроки(Строка) Экспорт\nКонецФункции",
+        );
+        index
+    }
+
+    fn labels(matches: &[QuickOpenMatch]) -> Vec<(&str, QuickOpenKind)> {
+        matches
+            .iter()
+            .map(|m| (m.item.label.as_str(), m.item.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_unified_ranking() {
+        let index = index();
+        assert_eq!(index.len(), 7);
+
+        let found = index.search("массив", 10);
+        assert_eq!(
+            labels(&found)[..2],
+            [
+                ("Массив", QuickOpenKind::Type),
+                ("МассивИзСтроки", QuickOpenKind::Symbol)
+            ]
+        );
+        assert_eq!(found[1].item.line, Some(0));
+
+        // Английское имя и первые буквы слов
+        assert_eq!(labels(&index.search("array", 1)), [("Массив", QuickOpenKind::Type)]);
+        assert_eq!(
+            labels(&index.search("тз", 1)),
+            [("ТаблицаЗначений", QuickOpenKind::Type)]
+        );
+    }
+
+    #[test]
+    fn test_method_with_owner_and_limit() {
+        let index = index();
+        let found = index.search("Массив.Доб", 5);
+        assert_eq!(found[0].item.label, "Добавить");
+        assert_eq!(found[0].item.detail.as_deref(), Some("Массив"));
+        assert_eq!(found[0].item.url.as_deref().map(|u| u.contains('#')), Some(true));
+
+        assert_eq!(index.search("а", 2).len(), 2);
+        assert!(index.search("  ", 10).is_empty());
+        assert!(index.search("ъъъ", 10).is_empty());
+    }
+}
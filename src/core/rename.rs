@@ -0,0 +1,435 @@
+//! Рефакторинг «Переименовать»
+//!
+//! Переименовываются локальные переменные и параметры метода, переменные и
+//! методы модуля вместе с их вызовами. Экспортный метод общего модуля
+//! переименовывается и в вызовах `Модуль.Метод` из других модулей проекта:
+//! вхождения в каждом файле ищет [`occurrences`] с целью
+//! [`RenameTarget::ModuleMethod`].
+//!
+//! Поиск вхождений текстовый: комментарии и строковые литералы пропускаются,
+//! обращения к членам объектов (`Объект.Имя`) не считаются вхождениями
+//! локальных имён, а вызовы (`Имя(`) — вхождениями переменных.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+use crate::core::completion_scope::{scope_symbols, CompletionGroup, ScopeSymbolKind};
+use crate::core::example_mining::strip_comment;
+use crate::core::extract_procedure::KEYWORDS;
+use crate::core::module_structure::{method_end_regex, method_header_regex};
+use crate::core::module_variables::module_declarations;
+
+/// Что переименовывается
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameTarget {
+    /// Параметр или локальная переменная метода (строки метода включительно)
+    Local {
+        name: String,
+        lines: RangeInclusive<u32>,
+    },
+    /// Переменная модуля (`Перем` вне методов)
+    ModuleVariable { name: String },
+    /// Процедура или функция, объявленная в модуле
+    Method { name: String, exported: bool },
+    /// Метод другого модуля, вызванный как `Модуль.Метод`
+    ModuleMethod { module: String, name: String },
+}
+
+impl RenameTarget {
+    pub fn name(&self) -> &str {
+        match self {
+            RenameTarget::Local { name, .. }
+            | RenameTarget::ModuleVariable { name }
+            | RenameTarget::Method { name, .. }
+            | RenameTarget::ModuleMethod { name, .. } => name,
+        }
+    }
+}
+
+/// Вхождение имени: строка и столбец начала (с 0, в символах)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Occurrence {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Идентификатор в строке кода
+pub(crate) struct Site {
+    pub(crate) start: usize,
+    pub(crate) name: String,
+    /// Объект перед точкой (`Модуль` в `Модуль.Метод`)
+    pub(crate) qualifier: Option<String>,
+    /// За именем идёт `(`
+    pub(crate) call: bool,
+}
+
+fn is_bsl_letter(ch: char) -> bool {
+    ch.is_ascii_alphabetic() || matches!(ch, 'а'..='я' | 'А'..='Я' | 'ё' | 'Ё')
+}
+
+/// Проверить, что `name` — допустимый идентификатор BSL: кириллическая или
+/// латинская буква либо `_` в начале, далее буквы, цифры и `_`, не ключевое слово
+pub fn validate_identifier(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|ch| is_bsl_letter(ch) || ch == '_')
+        && chars.all(|ch| is_bsl_letter(ch) || ch.is_ascii_digit() || ch == '_');
+    if !valid {
+        return Err(format!(
+            "«{}» не является идентификатором BSL: ожидаются буквы (кириллица или латиница), \
+             цифры и _, первый символ — не цифра",
+            name
+        ));
+    }
+    if KEYWORDS.contains(&name.to_lowercase().as_str()) {
+        return Err(format!("«{}» — ключевое слово BSL", name));
+    }
+    Ok(())
+}
+
+/// Символы строки, в которых комментарий и содержимое строковых литералов
+/// заменены пробелами (позиции символов сохраняются)
+pub(crate) fn code_chars(line: &str) -> Vec<char> {
+    let code_len = strip_comment(line).chars().count();
+    // Продолжение многострочного литерала: `|текст`
+    let mut in_string = line.trim_start().starts_with('|');
+    line.chars()
+        .enumerate()
+        .map(|(idx, ch)| match ch {
+            _ if idx >= code_len => ' ',
+            '"' => {
+                in_string = !in_string;
+                ch
+            }
+            _ if in_string => ' ',
+            _ => ch,
+        })
+        .collect()
+}
+
+pub(crate) fn sites(chars: &[char]) -> Vec<Site> {
+    let is_ident = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let mut sites: Vec<Site> = Vec::new();
+    let mut idx = 0;
+    while idx < chars.len() {
+        let ch = chars[idx];
+        if !(ch.is_alphabetic() || ch == '_') || (idx > 0 && is_ident(chars[idx - 1])) {
+            idx += 1;
+            continue;
+        }
+        let start = idx;
+        while idx < chars.len() && is_ident(chars[idx]) {
+            idx += 1;
+        }
+        let before = chars[..start].iter().rposition(|ch| !ch.is_whitespace());
+        let qualifier = before
+            .filter(|&dot| chars[dot] == '.')
+            .and_then(|dot| {
+                let end = chars[..dot].iter().rposition(|ch| !ch.is_whitespace())? + 1;
+                let begin = chars[..end]
+                    .iter()
+                    .rposition(|&ch| !is_ident(ch))
+                    .map_or(0, |pos| pos + 1);
+                Some(chars[begin..end].iter().collect::<String>())
+            })
+            // После точки без объекта (`).Имя`) имя всё равно член, а не локальное
+            .or_else(|| before.filter(|&dot| chars[dot] == '.').map(|_| String::new()));
+        let call = chars[idx..].iter().find(|ch| !ch.is_whitespace()) == Some(&'(');
+        sites.push(Site {
+            start,
+            name: chars[start..idx].iter().collect(),
+            qualifier,
+            call,
+        });
+    }
+    sites
+}
+
+/// Строки методов модуля: заголовок и `КонецПроцедуры`/`КонецФункции`
+/// (незавершённый метод продолжается до конца текста)
+pub(crate) fn method_ranges(text: &str) -> Vec<RangeInclusive<u32>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    let mut last = 0;
+    for (idx, line) in text.lines().enumerate() {
+        let code = strip_comment(line);
+        last = idx as u32;
+        if method_header_regex().is_match(code) {
+            start = Some(idx as u32);
+        } else if method_end_regex().is_match(code) {
+            if let Some(start) = start.take() {
+                ranges.push(start..=idx as u32);
+            }
+        }
+    }
+    ranges.extend(start.map(|start| start..=last));
+    ranges
+}
+
+/// Имя метода, объявленного в строке `line`, и признак `Экспорт`
+pub(crate) fn declared_method(line: &str) -> Option<(String, bool)> {
+    let code = strip_comment(line);
+    let caps = method_header_regex().captures(code)?;
+    let tail = code[caps.get(0)?.end()..].to_lowercase();
+    let exported = tail.split_once(')').is_some_and(|(_, rest)| {
+        rest.split_whitespace()
+            .any(|word| word == "экспорт" || word == "export")
+    });
+    Some((caps[2].to_string(), exported))
+}
+
+pub(crate) fn same_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+pub(crate) fn local_declaration_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^\s*(?:перем|var)\s+([^;]+)").unwrap())
+}
+
+/// Метод объявляет собственный символ `name` — параметр или `Перем`.
+/// Простое присваивание переменной модуля внутри метода её не скрывает.
+fn declares_local(text: &str, method: &RangeInclusive<u32>, name: &str) -> bool {
+    let parameter = scope_symbols(text, *method.start() as usize).iter().any(|symbol| {
+        symbol.kind == ScopeSymbolKind::Parameter
+            && symbol.group == CompletionGroup::Local
+            && same_name(&symbol.name, name)
+    });
+    parameter
+        || text
+            .lines()
+            .enumerate()
+            .filter(|(idx, _)| method.contains(&(*idx as u32)))
+            .filter_map(|(_, line)| local_declaration_regex().captures(strip_comment(line)))
+            .any(|caps| {
+                caps[1]
+                    .split(',')
+                    .filter_map(|item| item.split_whitespace().next())
+                    .any(|declared| same_name(declared, name))
+            })
+}
+
+/// Что переименовывается, если курсор стоит на имени в строке `line`,
+/// столбце `column` (с 0, в символах)
+pub fn rename_target(text: &str, line: u32, column: u32) -> Option<RenameTarget> {
+    let source = text.lines().nth(line as usize)?;
+    let column = column as usize;
+    let site = sites(&code_chars(source))
+        .into_iter()
+        .find(|site| (site.start..=site.start + site.name.chars().count()).contains(&column))?;
+    if KEYWORDS.contains(&site.name.to_lowercase().as_str()) {
+        return None;
+    }
+    if let Some(module) = site.qualifier {
+        return (!module.is_empty()).then_some(RenameTarget::ModuleMethod {
+            module,
+            name: site.name,
+        });
+    }
+
+    let symbol = scope_symbols(text, line as usize)
+        .into_iter()
+        .find(|symbol| same_name(&symbol.name, &site.name))?;
+    match (symbol.group, symbol.kind) {
+        (CompletionGroup::Local, kind) => {
+            let lines = method_ranges(text)
+                .into_iter()
+                .find(|range| range.contains(&line))?;
+            // Присваивание переменной модуля выглядит как локальная переменная
+            let module_variable = kind == ScopeSymbolKind::Variable
+                && !declares_local(text, &lines, &symbol.name)
+                && module_declarations(text)
+                    .iter()
+                    .any(|variable| same_name(&variable.name, &symbol.name));
+            if module_variable {
+                return Some(RenameTarget::ModuleVariable { name: symbol.name });
+            }
+            Some(RenameTarget::Local {
+                name: symbol.name,
+                lines,
+            })
+        }
+        (_, ScopeSymbolKind::Procedure | ScopeSymbolKind::Function) => {
+            let exported = text
+                .lines()
+                .filter_map(declared_method)
+                .find(|(name, _)| same_name(name, &symbol.name))
+                .is_some_and(|(_, exported)| exported);
+            Some(RenameTarget::Method {
+                name: symbol.name,
+                exported,
+            })
+        }
+        _ => Some(RenameTarget::ModuleVariable { name: symbol.name }),
+    }
+}
+
+/// Вхождения переименовываемого имени в тексте модуля
+pub fn occurrences(text: &str, target: &RenameTarget) -> Vec<Occurrence> {
+    let name = target.name();
+    // Методы, где локальный символ скрывает переменную модуля
+    let shadowed: Vec<RangeInclusive<u32>> = match target {
+        RenameTarget::ModuleVariable { .. } => method_ranges(text)
+            .into_iter()
+            .filter(|range| declares_local(text, range, name))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut found = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let idx = idx as u32;
+        let declaration = matches!(target, RenameTarget::Method { .. })
+            && declared_method(line).is_some_and(|(declared, _)| same_name(&declared, name));
+        for site in sites(&code_chars(line)) {
+            if !same_name(&site.name, name) {
+                continue;
+            }
+            let matched = match target {
+                RenameTarget::Local { lines, .. } => {
+                    lines.contains(&idx) && site.qualifier.is_none() && !site.call
+                }
+                RenameTarget::ModuleVariable { .. } => {
+                    site.qualifier.is_none()
+                        && !site.call
+                        && !shadowed.iter().any(|range| range.contains(&idx))
+                }
+                RenameTarget::Method { .. } => {
+                    site.qualifier.is_none() && (site.call || declaration)
+                }
+                RenameTarget::ModuleMethod { module, .. } => site
+                    .qualifier
+                    .as_deref()
+                    .is_some_and(|qualifier| same_name(qualifier, module)),
+            };
+            if matched {
+                found.push(Occurrence {
+                    line: idx,
+                    column: site.start as u32,
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Имя `new_name` уже занято там, где виден переименовываемый символ: среди
+/// символов метода для локального имени, среди всех символов модуля для
+/// переменных и методов модуля
+pub fn conflicts(text: &str, target: &RenameTarget, new_name: &str) -> bool {
+    if same_name(target.name(), new_name) {
+        return false;
+    }
+    let lines: Vec<u32> = match target {
+        RenameTarget::Local { lines, .. } => vec![*lines.start()],
+        RenameTarget::ModuleMethod { .. } => return false,
+        _ => method_ranges(text)
+            .iter()
+            .map(|range| *range.start())
+            // Строка вне методов: только символы модуля
+            .chain([u32::MAX])
+            .collect(),
+    };
+    let names: HashSet<String> = lines
+        .into_iter()
+        .flat_map(|line| scope_symbols(text, line as usize))
+        .map(|symbol| symbol.name.to_lowercase())
+        .collect();
+    names.contains(&new_name.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = r#"Перем Кеш;
+
+Функция Сумма(Знач Товары) Экспорт
+    Итог = 0; // Итог в комментарии
+    Для Каждого Строка Из Товары Цикл
+        Итог = Итог + Строка.Итог;
+    КонецЦикла;
+    Сообщить("Итог: " + Итог);
+    Возврат Итог;
+КонецФункции
+
+Процедура Заполнить()
+    Кеш = Сумма(Новый Массив);
+    Итог = Кеш;
+КонецПроцедуры
+"#;
+
+    fn positions(found: &[Occurrence]) -> Vec<(u32, u32)> {
+        found.iter().map(|o| (o.line, o.column)).collect()
+    }
+
+    #[test]
+    fn test_local_variable_stays_in_method() {
+        let target = rename_target(MODULE, 5, 9).unwrap();
+        assert_eq!(
+            target,
+            RenameTarget::Local {
+                name: "Итог".to_string(),
+                lines: 2..=9,
+            }
+        );
+        // Член `Строка.Итог`, комментарий, литерал и `Итог` другого метода не трогаются
+        assert_eq!(
+            positions(&occurrences(MODULE, &target)),
+            [(3, 4), (5, 8), (5, 15), (7, 24), (8, 12)]
+        );
+        assert!(conflicts(MODULE, &target, "строка"));
+        assert!(!conflicts(MODULE, &target, "Результат"));
+    }
+
+    #[test]
+    fn test_module_variable_assigned_in_method() {
+        let target = rename_target(MODULE, 13, 12).unwrap();
+        assert_eq!(
+            target,
+            RenameTarget::ModuleVariable {
+                name: "Кеш".to_string()
+            }
+        );
+        assert_eq!(
+            positions(&occurrences(MODULE, &target)),
+            [(0, 6), (12, 4), (13, 11)]
+        );
+    }
+
+    #[test]
+    fn test_method_and_qualified_calls() {
+        let target = rename_target(MODULE, 12, 11).unwrap();
+        assert_eq!(
+            target,
+            RenameTarget::Method {
+                name: "Сумма".to_string(),
+                exported: true,
+            }
+        );
+        assert_eq!(positions(&occurrences(MODULE, &target)), [(2, 8), (12, 10)]);
+        assert!(conflicts(MODULE, &target, "Кеш"));
+
+        let caller = "Итог = Расчеты.Сумма(Товары) + Расчеты . сумма();";
+        let target = rename_target(caller, 0, 16).unwrap();
+        assert_eq!(
+            target,
+            RenameTarget::ModuleMethod {
+                module: "Расчеты".to_string(),
+                name: "Сумма".to_string(),
+            }
+        );
+        assert_eq!(positions(&occurrences(caller, &target)), [(0, 15), (0, 41)]);
+    }
+
+    #[test]
+    fn test_validate_identifier() {
+        assert!(validate_identifier("НовоеИмя_2").is_ok());
+        assert!(validate_identifier("_name").is_ok());
+        assert!(validate_identifier("2Имя").is_err());
+        assert!(validate_identifier("Имя-Фамилия").is_err());
+        assert!(validate_identifier("Ελληνικά").is_err());
+        assert!(validate_identifier("КонецЕсли").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Арена временных данных одного запроса
+//!
+//! Автодополнение и анализ документа разбирают текст модуля построчно:
+//! режут строки на инструкции и слова, приводят слова к нижнему регистру,
+//! чтобы сравнить с ключевыми словами. На больших модулях это сотни тысяч
+//! мелких выделений памяти, которые живут до конца строки, — в профиле
+//! они заметнее самого разбора.
+//!
+//! Такие данные выделяются в [`RequestArena`] (bump-аллокатор `bumpalo`):
+//! выделение — сдвиг указателя, освобождение — сброс арены целиком в
+//! конце прохода. Арена переиспользуется потоком: [`with_request_arena`]
+//! берёт её из пула потока и после прохода возвращает сброшенной, сохраняя
+//! выделенные блоки для следующего запроса. Вложенный проход получает
+//! собственную арену, а слишком разросшаяся арена не возвращается в пул,
+//! чтобы один огромный модуль не держал память потока.
+//!
+//! Из арены ничего не выходит: результаты прохода (`VariableAssignments`,
+//! `TypeGuard`) по-прежнему владеют своими строками.
+
+use bumpalo::collections::{String as ArenaString, Vec as ArenaVec};
+use bumpalo::Bump;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+/// Больше этого арена в пул потока не возвращается
+pub const MAX_RETAINED_BYTES: usize = 4 * 1024 * 1024;
+
+static POOLING: AtomicBool = AtomicBool::new(true);
+static PASSES: AtomicU64 = AtomicU64::new(0);
+static REUSED: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static POOL: RefCell<Option<Bump>> = const { RefCell::new(None) };
+}
+
+/// Временная память прохода по тексту
+pub struct RequestArena {
+    bump: Bump,
+}
+
+impl RequestArena {
+    fn new(bump: Bump) -> Self {
+        Self { bump }
+    }
+
+    /// Пустой вектор в арене
+    pub fn vec<T>(&self) -> ArenaVec<'_, T> {
+        ArenaVec::new_in(&self.bump)
+    }
+
+    /// Копия строки в арене
+    pub fn alloc_str(&self, text: &str) -> &str {
+        self.bump.alloc_str(text)
+    }
+
+    /// Строка в нижнем регистре (по правилам Unicode, как `to_lowercase`)
+    pub fn lowercase(&self, text: &str) -> &str {
+        if !text.chars().any(char::is_uppercase) {
+            return self.bump.alloc_str(text);
+        }
+        let mut out = ArenaString::with_capacity_in(text.len(), &self.bump);
+        for ch in text.chars() {
+            out.extend(ch.to_lowercase());
+        }
+        out.into_bump_str()
+    }
+
+    /// Выделено байтов с начала прохода
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+}
+
+/// Выполнить проход с ареной из пула потока; после прохода арена
+/// сбрасывается и возвращается в пул
+pub fn with_request_arena<R>(pass: impl FnOnce(&RequestArena) -> R) -> R {
+    let pooled = POOLING
+        .load(Ordering::Relaxed)
+        .then(|| POOL.with(|pool| pool.borrow_mut().take()))
+        .flatten();
+    if pooled.is_some() {
+        REUSED.fetch_add(1, Ordering::Relaxed);
+    }
+    let mut arena = RequestArena::new(pooled.unwrap_or_default());
+
+    let result = pass(&arena);
+
+    let allocated = arena.allocated_bytes();
+    PASSES.fetch_add(1, Ordering::Relaxed);
+    PEAK_BYTES.fetch_max(allocated, Ordering::Relaxed);
+    if POOLING.load(Ordering::Relaxed) && allocated <= MAX_RETAINED_BYTES {
+        arena.bump.reset();
+        POOL.with(|pool| {
+            // Пул занят — арена вложенного прохода освобождается
+            let mut pool = pool.borrow_mut();
+            if pool.is_none() {
+                *pool = Some(arena.bump);
+            }
+        });
+    }
+    result
+}
+
+/// Включить или выключить пул арен; без пула каждый проход выделяет арену
+/// заново. Только для сравнения в бенчмарках: флаг общий для процесса,
+/// поэтому в публичный API не входит.
+#[doc(hidden)]
+pub fn set_pooling(enabled: bool) {
+    POOLING.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        POOL.with(|pool| pool.borrow_mut().take());
+    }
+}
+
+/// Статистика арен процесса
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ArenaStats {
+    /// Выполнено проходов
+    pub passes: u64,
+    /// Проходов с ареной из пула
+    pub reused: u64,
+    /// Наибольший объём одного прохода в байтах
+    pub peak_bytes: usize,
+}
+
+pub fn arena_stats() -> ArenaStats {
+    ArenaStats {
+        passes: PASSES.load(Ordering::Relaxed),
+        reused: REUSED.load(Ordering::Relaxed),
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_arena() {
+        let before = arena_stats();
+        let words = with_request_arena(|arena| {
+            let mut words = arena.vec();
+            for word in "Если Флаг ТОГДА Значение".split(' ') {
+                words.push(arena.lowercase(word));
+            }
+            assert_eq!(arena.alloc_str("Тогда"), "Тогда");
+            assert_eq!(arena.lowercase("ВЫБРАТЬ Ёлка"), "выбрать ёлка");
+            assert!(arena.allocated_bytes() > 0);
+            words
+                .iter()
+                .map(|word| word.to_string())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(words, ["если", "флаг", "тогда", "значение"]);
+
+        // Вложенный проход получает свою арену, следующий — арену из пула
+        with_request_arena(|outer| {
+            let inner = with_request_arena(|inner| inner.lowercase("А").to_string());
+            assert_eq!(outer.lowercase(&inner), "а");
+        });
+        let after = arena_stats();
+        assert!(after.passes >= before.passes + 3);
+        assert!(after.reused > before.reused);
+    }
+}
@@ -0,0 +1,422 @@
+//! Семантические токены модуля BSL
+//!
+//! Раскраска по регулярным выражениям не отличает параметр от локальной
+//! переменной, а глобальную функцию платформы — от метода модуля. Здесь роли
+//! идентификаторов берутся из дерева tree-sitter: объявление метода (с
+//! признаком `Экспорт`), параметр и его использования в теле, вызов метода
+//! модуля, `Новый Тип`, менеджер метаданных и объект за ним
+//! (`Справочники.Номенклатура`). Категорию остальных имён по синтаксису не
+//! определить — они возвращаются как [`UnresolvedName`], и вызывающий
+//! классифицирует их по разрешённому типу ([`classify_resolution`]).
+
+use std::collections::HashSet;
+use tree_sitter::{Node, Tree};
+
+use crate::domain::types::{ConcreteType, ResolutionResult, TypeResolution};
+
+/// Легенда типов токенов (порядок соответствует [`SemanticTokenType`])
+pub const TOKEN_TYPES: &[&str] = &[
+    "type",
+    "parameter",
+    "function",
+    "namespace",
+    "class",
+    "method",
+    "variable",
+    "property",
+];
+
+/// Легенда модификаторов (бит `1 << i` соответствует `TOKEN_MODIFIERS[i]`)
+pub const TOKEN_MODIFIERS: &[&str] = &["declaration", "defaultLibrary", "exported"];
+
+pub const MODIFIER_DECLARATION: u32 = 1;
+pub const MODIFIER_DEFAULT_LIBRARY: u32 = 1 << 1;
+pub const MODIFIER_EXPORTED: u32 = 1 << 2;
+
+/// Менеджеры объектов конфигурации глобального контекста
+const METADATA_MANAGERS: &[&str] = &[
+    "Справочники",
+    "Catalogs",
+    "Документы",
+    "Documents",
+    "Перечисления",
+    "Enums",
+    "Константы",
+    "Constants",
+    "РегистрыСведений",
+    "InformationRegisters",
+    "РегистрыНакопления",
+    "AccumulationRegisters",
+    "РегистрыБухгалтерии",
+    "AccountingRegisters",
+    "РегистрыРасчета",
+    "CalculationRegisters",
+    "ПланыВидовХарактеристик",
+    "ChartsOfCharacteristicTypes",
+    "ПланыСчетов",
+    "ChartsOfAccounts",
+    "ПланыВидовРасчета",
+    "ChartsOfCalculationTypes",
+    "ПланыОбмена",
+    "ExchangePlans",
+    "БизнесПроцессы",
+    "BusinessProcesses",
+    "Задачи",
+    "Tasks",
+    "Обработки",
+    "DataProcessors",
+    "Отчеты",
+    "Reports",
+];
+
+/// Тип семантического токена
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticTokenType {
+    /// Тип платформы (`Новый Массив`)
+    Type,
+    Parameter,
+    /// Глобальная функция платформы
+    Function,
+    /// Менеджер метаданных (`Справочники`)
+    Namespace,
+    /// Объект конфигурации (`Номенклатура` в `Справочники.Номенклатура`)
+    Class,
+    /// Процедура или функция модуля
+    Method,
+    Variable,
+    Property,
+}
+
+impl SemanticTokenType {
+    /// Индекс в [`TOKEN_TYPES`]
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Токен: позиция в символах, тип и модификаторы
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub line: u32,
+    pub start: u32,
+    pub length: u32,
+    pub token_type: SemanticTokenType,
+    pub modifiers: u32,
+}
+
+/// Идентификатор, категория которого зависит от разрешения типа
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedName {
+    pub line: u32,
+    pub start: u32,
+    pub length: u32,
+    pub name: String,
+    /// Стоит в позиции вызова (`Имя(...)`)
+    pub call: bool,
+}
+
+/// Токены модуля, определённые по синтаксису, и имена для разрешения
+#[derive(Debug, Clone, Default)]
+pub struct ModuleTokens {
+    pub tokens: Vec<SemanticToken>,
+    pub unresolved: Vec<UnresolvedName>,
+}
+
+/// Является ли имя менеджером объектов конфигурации
+pub fn is_metadata_manager(name: &str) -> bool {
+    let name = name.to_lowercase();
+    METADATA_MANAGERS
+        .iter()
+        .any(|manager| manager.to_lowercase() == name)
+}
+
+/// Категория имени по разрешённому типу; `None` — имя не раскрашивается
+pub fn classify_resolution(
+    resolution: &TypeResolution,
+    call: bool,
+) -> Option<(SemanticTokenType, u32)> {
+    match &resolution.result {
+        ResolutionResult::Concrete(ConcreteType::GlobalFunction(_)) => {
+            Some((SemanticTokenType::Function, MODIFIER_DEFAULT_LIBRARY))
+        }
+        ResolutionResult::Concrete(ConcreteType::Platform(_)) if !call => {
+            Some((SemanticTokenType::Type, MODIFIER_DEFAULT_LIBRARY))
+        }
+        ResolutionResult::Concrete(ConcreteType::Configuration(_)) if !call => {
+            Some((SemanticTokenType::Class, 0))
+        }
+        // Неизвестный вызов — метод другого модуля или опечатка
+        _ if call => None,
+        _ => Some((SemanticTokenType::Variable, 0)),
+    }
+}
+
+/// Собрать токены модуля по дереву tree-sitter
+pub fn module_tokens(tree: &Tree, source: &str) -> ModuleTokens {
+    let mut collector = Collector {
+        source,
+        methods: HashSet::new(),
+        params: HashSet::new(),
+        out: ModuleTokens::default(),
+    };
+    collector.collect_methods(tree.root_node());
+    collector.visit(tree.root_node());
+    let mut out = collector.out;
+    out.tokens.sort_by_key(|token| (token.line, token.start));
+    out.unresolved.sort_by_key(|name| (name.line, name.start));
+    out
+}
+
+/// Упаковать токены в формат LSP: по пять чисел на токен, строка и начало —
+/// относительно предыдущего токена. Токены должны быть отсортированы.
+pub fn encode(tokens: &[SemanticToken]) -> Vec<u32> {
+    let mut data = Vec::with_capacity(tokens.len() * 5);
+    let (mut line, mut start) = (0, 0);
+    for token in tokens {
+        let delta_line = token.line - line;
+        let delta_start = if delta_line == 0 {
+            token.start - start
+        } else {
+            token.start
+        };
+        data.extend([
+            delta_line,
+            delta_start,
+            token.length,
+            token.token_type.index(),
+            token.modifiers,
+        ]);
+        line = token.line;
+        start = token.start;
+    }
+    data
+}
+
+struct Collector<'a> {
+    source: &'a str,
+    /// Имена методов модуля в нижнем регистре
+    methods: HashSet<String>,
+    /// Параметры текущего метода в нижнем регистре
+    params: HashSet<String>,
+    out: ModuleTokens,
+}
+
+fn is_method_definition(node: Node) -> bool {
+    matches!(node.kind(), "procedure_definition" | "function_definition")
+}
+
+fn first_child_of_kind<'t>(node: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    let mut cursor = node.walk();
+    let found = node.children(&mut cursor).find(|child| child.kind() == kind);
+    found
+}
+
+impl<'a> Collector<'a> {
+    fn text(&self, node: Node) -> &'a str {
+        &self.source[node.byte_range()]
+    }
+
+    /// Позиция узла в символах: (строка, начало, длина)
+    fn position(&self, node: Node) -> (u32, u32, u32) {
+        let point = node.start_position();
+        let line_start = node.start_byte() - point.column;
+        let start = self.source[line_start..node.start_byte()].chars().count();
+        let length = self.text(node).chars().count();
+        (point.row as u32, start as u32, length as u32)
+    }
+
+    fn push(&mut self, node: Node, token_type: SemanticTokenType, modifiers: u32) {
+        let (line, start, length) = self.position(node);
+        self.out.tokens.push(SemanticToken {
+            line,
+            start,
+            length,
+            token_type,
+            modifiers,
+        });
+    }
+
+    fn collect_methods(&mut self, node: Node) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if is_method_definition(child) {
+                if let Some(name) = first_child_of_kind(child, "identifier") {
+                    self.methods.insert(self.text(name).to_lowercase());
+                }
+            } else {
+                self.collect_methods(child);
+            }
+        }
+    }
+
+    fn visit(&mut self, node: Node) {
+        match node.kind() {
+            "procedure_definition" | "function_definition" => self.visit_method(node),
+            "new_expression" => {
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    if child.kind() == "identifier" {
+                        self.push(child, SemanticTokenType::Type, MODIFIER_DEFAULT_LIBRARY);
+                    } else {
+                        self.visit(child);
+                    }
+                }
+            }
+            "call_expression" | "method_call" => {
+                let mut callee_seen = false;
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    if child.kind() == "identifier" && !callee_seen {
+                        callee_seen = true;
+                        self.visit_callee(child);
+                    } else {
+                        self.visit(child);
+                    }
+                }
+            }
+            "property_access" => self.visit_property_access(node),
+            "identifier" => self.visit_identifier(node),
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    self.visit(child);
+                }
+            }
+        }
+    }
+
+    fn visit_method(&mut self, node: Node) {
+        let exported = first_child_of_kind(node, "EXPORT_KEYWORD").is_some();
+        let name = first_child_of_kind(node, "identifier");
+        if let Some(name) = name {
+            let modifiers = MODIFIER_DECLARATION | if exported { MODIFIER_EXPORTED } else { 0 };
+            self.push(name, SemanticTokenType::Method, modifiers);
+        }
+
+        self.params.clear();
+        if let Some(parameters) = first_child_of_kind(node, "parameters") {
+            let mut cursor = parameters.walk();
+            for parameter in parameters.children(&mut cursor) {
+                if parameter.kind() != "parameter" {
+                    continue;
+                }
+                if let Some(ident) = first_child_of_kind(parameter, "identifier") {
+                    self.params.insert(self.text(ident).to_lowercase());
+                    self.push(ident, SemanticTokenType::Parameter, MODIFIER_DECLARATION);
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if Some(child) != name && child.kind() != "parameters" {
+                self.visit(child);
+            }
+        }
+        self.params.clear();
+    }
+
+    fn visit_callee(&mut self, node: Node) {
+        let name = self.text(node).to_lowercase();
+        if self.methods.contains(&name) {
+            self.push(node, SemanticTokenType::Method, 0);
+        } else {
+            self.unresolved(node, true);
+        }
+    }
+
+    fn visit_property_access(&mut self, node: Node) {
+        let mut object = None;
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.kind() == "property" {
+                let token_type = match object {
+                    Some(object) if is_metadata_manager(self.text(object)) => {
+                        SemanticTokenType::Class
+                    }
+                    _ => SemanticTokenType::Property,
+                };
+                self.push(child, token_type, 0);
+            } else if object.is_none() && child.is_named() {
+                object = Some(child);
+                self.visit(child);
+            } else {
+                self.visit(child);
+            }
+        }
+    }
+
+    fn visit_identifier(&mut self, node: Node) {
+        let text = self.text(node);
+        if self.params.contains(&text.to_lowercase()) {
+            self.push(node, SemanticTokenType::Parameter, 0);
+        } else if is_metadata_manager(text) {
+            self.push(node, SemanticTokenType::Namespace, MODIFIER_DEFAULT_LIBRARY);
+        } else {
+            self.unresolved(node, false);
+        }
+    }
+
+    fn unresolved(&mut self, node: Node, call: bool) {
+        let (line, start, length) = self.position(node);
+        self.out.unresolved.push(UnresolvedName {
+            line,
+            start,
+            length,
+            name: self.text(node).to_string(),
+            call,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::bsl::tree_sitter_adapter::TreeSitterAdapter;
+
+    fn token_at(tokens: &ModuleTokens, line: u32, start: u32) -> Option<&SemanticToken> {
+        tokens
+            .tokens
+            .iter()
+            .find(|token| token.line == line && token.start == start)
+    }
+
+    #[test]
+    fn test_module_tokens() {
+        let source = "Функция Сумма(Товары, Знач НДС) Экспорт
+    Запрос = Новый Массив;
+    Ссылка = Справочники.Номенклатура.ПустаяСсылка();
+    Пересчитать(НДС);
+    Возврат СтрДлина(Товары);
+КонецФункции
+
+Процедура Пересчитать(Ставка)
+КонецПроцедуры
+";
+        let tree = TreeSitterAdapter::new().unwrap().parse_tree(source).unwrap();
+        let tokens = module_tokens(&tree, source);
+
+        let declaration = token_at(&tokens, 0, 8).unwrap();
+        assert_eq!(declaration.token_type, SemanticTokenType::Method);
+        assert_eq!(declaration.modifiers, MODIFIER_DECLARATION | MODIFIER_EXPORTED);
+        assert_eq!(token_at(&tokens, 0, 14).unwrap().token_type, SemanticTokenType::Parameter);
+        assert_eq!(token_at(&tokens, 1, 19).unwrap().token_type, SemanticTokenType::Type);
+        assert_eq!(token_at(&tokens, 2, 13).unwrap().token_type, SemanticTokenType::Namespace);
+        assert_eq!(token_at(&tokens, 2, 25).unwrap().token_type, SemanticTokenType::Class);
+        assert_eq!(token_at(&tokens, 3, 4).unwrap().token_type, SemanticTokenType::Method);
+        assert_eq!(token_at(&tokens, 3, 16).unwrap().token_type, SemanticTokenType::Parameter);
+        let private = token_at(&tokens, 7, 10).unwrap();
+        assert_eq!(private.modifiers, MODIFIER_DECLARATION);
+
+        // Глобальная функция и локальные переменные определяются разрешением типа
+        let call = tokens.unresolved.iter().find(|name| name.name == "СтрДлина").unwrap();
+        assert!(call.call);
+        assert!(tokens.unresolved.iter().any(|name| name.name == "Запрос" && !name.call));
+        assert_eq!(token_at(&tokens, 7, 22).unwrap().modifiers, MODIFIER_DECLARATION);
+
+        let data = encode(&tokens.tokens);
+        assert_eq!(data.len(), tokens.tokens.len() * 5);
+        assert_eq!(&data[..5], &[0, 8, 5, SemanticTokenType::Method.index(), 5]);
+        assert_eq!(&data[5..7], &[0, 6]);
+    }
+}
@@ -0,0 +1,309 @@
+//! Подсказка сигнатур (`textDocument/signatureHelp`)
+//!
+//! По тексту до курсора определяется незакрытый вызов `Получатель.Метод(`
+//! и номер текущего аргумента. Сигнатуры метода платформы строятся из его
+//! документации (`MethodDocumentation`): каждый вариант синтаксиса — отдельная
+//! сигнатура, активной выбирается первая, в которую помещаются уже
+//! введённые аргументы. Для экспортного метода общего модуля сигнатура
+//! строится по именам параметров из заголовка метода.
+
+use tower_lsp::lsp_types::*;
+
+use crate::core::common_module_calls::CommonModuleExport;
+use crate::documentation::core::hierarchy::{MethodDocumentation, ParameterDocumentation};
+
+/// Незакрытый вызов в позиции курсора
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallSite {
+    /// Выражение перед точкой (`Таблица` в `Таблица.Добавить(`)
+    pub receiver: Option<String>,
+    /// Имя вызываемого метода
+    pub method: String,
+    /// Номер аргумента под курсором (с 0)
+    pub argument_index: usize,
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '.'
+}
+
+/// Вызов, внутри аргументов которого находится курсор
+pub fn call_site(text: &str, line: u32, column: u32) -> Option<CallSite> {
+    // Открытые скобки: (вызываемое выражение, число запятых)
+    let mut open: Vec<(String, usize)> = Vec::new();
+    let mut in_string = false;
+
+    for (idx, source_line) in text.lines().enumerate().take(line as usize + 1) {
+        let chars: Vec<char> = source_line.chars().collect();
+        let end = if idx == line as usize {
+            (column as usize).min(chars.len())
+        } else {
+            chars.len()
+        };
+        // Многострочная строка продолжается строками, начинающимися с `|`
+        in_string = in_string && source_line.trim_start().starts_with('|');
+        let mut pos = if in_string {
+            chars.iter().position(|&ch| ch == '|').map_or(0, |p| p + 1)
+        } else {
+            0
+        };
+
+        while pos < end {
+            let ch = chars[pos];
+            match ch {
+                '"' => in_string = !in_string,
+                _ if in_string => {}
+                '/' if chars.get(pos + 1) == Some(&'/') => break,
+                '(' => {
+                    let start = chars[..pos]
+                        .iter()
+                        .rposition(|&c| !is_name_char(c))
+                        .map_or(0, |p| p + 1);
+                    open.push((chars[start..pos].iter().collect(), 0));
+                }
+                ')' => {
+                    open.pop();
+                }
+                ',' => {
+                    if let Some((_, commas)) = open.last_mut() {
+                        *commas += 1;
+                    }
+                }
+                _ => {}
+            }
+            pos += 1;
+        }
+    }
+
+    let (callee, commas) = open.pop()?;
+    let (receiver, method) = match callee.rsplit_once('.') {
+        Some((receiver, method)) => (Some(receiver.to_string()), method.to_string()),
+        None => (None, callee),
+    };
+    if method.is_empty() || receiver.as_deref() == Some("") {
+        return None;
+    }
+    Some(CallSite {
+        receiver,
+        method,
+        argument_index: commas,
+    })
+}
+
+/// Вариант, в который помещаются `argument_count` аргументов: первый с
+/// достаточным числом параметров, иначе вариант с наибольшим их числом
+pub fn select_overload(parameter_counts: &[usize], argument_count: usize) -> usize {
+    parameter_counts
+        .iter()
+        .position(|&count| count >= argument_count)
+        .or_else(|| {
+            parameter_counts
+                .iter()
+                .enumerate()
+                .max_by_key(|(idx, &count)| (count, std::cmp::Reverse(*idx)))
+                .map(|(idx, _)| idx)
+        })
+        .unwrap_or(0)
+}
+
+/// Длина в единицах UTF-16 (смещения меток параметров в LSP)
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+/// Сигнатура из имени и меток параметров
+fn signature(
+    name: &str,
+    parameters: Vec<(String, Option<String>)>,
+    suffix: &str,
+    documentation: String,
+) -> SignatureInformation {
+    let mut label = format!("{}(", name);
+    let mut infos = Vec::new();
+    for (idx, (parameter, doc)) in parameters.into_iter().enumerate() {
+        if idx > 0 {
+            label.push_str(", ");
+        }
+        let start = utf16_len(&label);
+        label.push_str(&parameter);
+        infos.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, utf16_len(&label)]),
+            documentation: doc.filter(|doc| !doc.is_empty()).map(Documentation::String),
+        });
+    }
+    label.push(')');
+    label.push_str(suffix);
+
+    SignatureInformation {
+        label,
+        documentation: (!documentation.is_empty()).then_some(Documentation::MarkupContent(
+            MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: documentation,
+            },
+        )),
+        parameters: Some(infos),
+        active_parameter: None,
+    }
+}
+
+/// Метка и описание параметра: необязательный — в квадратных скобках
+fn parameter_label(parameter: &ParameterDocumentation) -> (String, Option<String>) {
+    let mut label = parameter.name.clone();
+    if let Some(type_name) = parameter.parameter_type.get_name() {
+        label.push_str(": ");
+        label.push_str(&type_name);
+    }
+    if let Some(default) = &parameter.default_value {
+        label.push_str(" = ");
+        label.push_str(default);
+    }
+    let mut doc = parameter.description.clone();
+    if !parameter.required {
+        label = format!("[{}]", label);
+        doc = format!("Необязательный. {}", doc).trim_end().to_string();
+    }
+    (label, Some(doc))
+}
+
+/// Подсказка сигнатур метода платформы: по сигнатуре на вариант синтаксиса
+pub fn method_signature_help(
+    type_name: &str,
+    method: &MethodDocumentation,
+    argument_index: usize,
+) -> SignatureHelp {
+    let name = format!("{}.{}", type_name, method.russian_name);
+    let suffix = method
+        .return_type
+        .as_ref()
+        .and_then(|resolution| resolution.get_name())
+        .map(|return_type| format!(": {}", return_type))
+        .unwrap_or_default();
+
+    type Variant<'a> = (Option<&'a str>, &'a [ParameterDocumentation]);
+    let variants: Vec<Variant> = if method.overloads.is_empty() {
+        vec![(None, method.parameters.as_slice())]
+    } else {
+        method
+            .overloads
+            .iter()
+            .map(|overload| (Some(overload.name.as_str()), overload.parameters.as_slice()))
+            .collect()
+    };
+
+    let signatures: Vec<SignatureInformation> = variants
+        .iter()
+        .map(|(variant, parameters)| {
+            let documentation = match variant {
+                Some(variant) => {
+                    format!("*Вариант синтаксиса: {}*\n\n{}", variant, method.description)
+                }
+                None => method.description.clone(),
+            };
+            signature(
+                &name,
+                parameters.iter().map(parameter_label).collect(),
+                &suffix,
+                documentation.trim_end().to_string(),
+            )
+        })
+        .collect();
+    let counts: Vec<usize> = variants.iter().map(|(_, parameters)| parameters.len()).collect();
+
+    SignatureHelp {
+        signatures,
+        active_signature: Some(select_overload(&counts, argument_index + 1) as u32),
+        active_parameter: Some(argument_index as u32),
+    }
+}
+
+/// Подсказка сигнатуры экспортного метода общего модуля
+pub fn export_signature_help(export: &CommonModuleExport, argument_index: usize) -> SignatureHelp {
+    let documentation = if export.is_async {
+        "Асинхронный метод: вызов возвращает `Обещание`".to_string()
+    } else {
+        String::new()
+    };
+    let parameters = export.params.iter().map(|name| (name.clone(), None)).collect();
+
+    SignatureHelp {
+        signatures: vec![signature(&export.qualified_name(), parameters, "", documentation)],
+        active_signature: Some(0),
+        active_parameter: Some(argument_index as u32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documentation::core::hierarchy::MethodOverload;
+    use crate::domain::types::TypeResolution;
+
+    fn parameter(name: &str, required: bool) -> ParameterDocumentation {
+        ParameterDocumentation {
+            name: name.to_string(),
+            parameter_type: TypeResolution::unknown(),
+            description: String::new(),
+            required,
+            default_value: None,
+        }
+    }
+
+    #[test]
+    fn test_call_site() {
+        let text = "Процедура Тест()\n    Таблица.Добавить(Строка(\"(,\"), 1, \n";
+        let site = call_site(text, 1, 37).unwrap();
+        assert_eq!(site.receiver.as_deref(), Some("Таблица"));
+        assert_eq!(site.method, "Добавить");
+        assert_eq!(site.argument_index, 2);
+
+        // Внутри вложенного вызова
+        let site = call_site(text, 1, 28).unwrap();
+        assert_eq!(site.receiver, None);
+        assert_eq!(site.method, "Строка");
+
+        // Вызов уже закрыт
+        assert_eq!(call_site("Сообщить(1);", 0, 12), None);
+    }
+
+    #[test]
+    fn test_overload_selection() {
+        let method = MethodDocumentation {
+            name: "Найти".to_string(),
+            russian_name: "Найти".to_string(),
+            english_name: "Find".to_string(),
+            description: "Ищет значение".to_string(),
+            parameters: Vec::new(),
+            return_type: None,
+            examples: Vec::new(),
+            availability: Vec::new(),
+            exceptions: Vec::new(),
+            overloads: vec![
+                MethodOverload {
+                    name: "По индексу".to_string(),
+                    parameters: vec![parameter("Индекс", true)],
+                },
+                MethodOverload {
+                    name: "По значению".to_string(),
+                    parameters: vec![parameter("Значение", true), parameter("Колонки", false)],
+                },
+            ],
+        };
+
+        let help = method_signature_help("ТаблицаЗначений", &method, 0);
+        assert_eq!(help.signatures.len(), 2);
+        assert_eq!(help.active_signature, Some(0));
+        assert_eq!(help.signatures[1].label, "ТаблицаЗначений.Найти(Значение, [Колонки])");
+
+        // Второй аргумент есть только во втором варианте
+        let help = method_signature_help("ТаблицаЗначений", &method, 1);
+        assert_eq!(help.active_signature, Some(1));
+        assert_eq!(help.active_parameter, Some(1));
+        let Some(parameters) = &help.signatures[1].parameters else {
+            panic!("ожидались параметры");
+        };
+        assert_eq!(parameters[1].label, ParameterLabel::LabelOffsets([32, 41]));
+
+        assert_eq!(select_overload(&[1, 2], 3), 1);
+    }
+}
@@ -0,0 +1,240 @@
+//! Шаблоны типовых конструкций BSL для автодополнения
+//!
+//! `ДляКаждого` разворачивается в цикл с позициями табуляции для имени
+//! элемента и коллекции, `Попытка` — в блок с обработкой исключения,
+//! `НоваяТаблицаЗначений` — в создание таблицы с колонками. Тело шаблона
+//! записано в синтаксисе сниппетов LSP (`${1:Элемент}`, `$0`).
+//!
+//! Пользовательские шаблоны читаются из JSON-файла в формате сниппетов
+//! VS Code и заменяют встроенные с тем же префиксом:
+//!
+//! ```json
+//! {
+//!   "Запрос к справочнику": {
+//!     "prefix": "ЗапросСправочник",
+//!     "body": ["Запрос = Новый Запрос;", "Запрос.Текст = \"ВЫБРАТЬ * ИЗ Справочник.${1:Имя}\";"],
+//!     "description": "Запрос ко всем элементам справочника"
+//!   }
+//! }
+//! ```
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Шаблон конструкции
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// Что вводится для вызова шаблона (`ДляКаждого`)
+    pub prefix: String,
+    pub description: String,
+    /// Тело в синтаксисе сниппетов LSP
+    pub body: String,
+}
+
+impl Snippet {
+    fn new(prefix: &str, description: &str, body: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            description: description.to_string(),
+            body: body.to_string(),
+        }
+    }
+}
+
+/// Тело в файле шаблонов: строка или массив строк
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SnippetBody {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct SnippetDefinition {
+    prefix: String,
+    body: SnippetBody,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Встроенные и пользовательские шаблоны
+#[derive(Debug, Clone)]
+pub struct SnippetProvider {
+    snippets: Vec<Snippet>,
+}
+
+impl Default for SnippetProvider {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl SnippetProvider {
+    /// Встроенные шаблоны
+    pub fn builtin() -> Self {
+        Self {
+            snippets: vec![
+                Snippet::new(
+                    "ДляКаждого",
+                    "Цикл по элементам коллекции",
+                    "Для Каждого ${1:Элемент} Из ${2:Коллекция} Цикл\n\t$0\nКонецЦикла;",
+                ),
+                Snippet::new(
+                    "Для",
+                    "Цикл по счётчику",
+                    "Для ${1:Индекс} = ${2:0} По ${3:Граница} Цикл\n\t$0\nКонецЦикла;",
+                ),
+                Snippet::new(
+                    "Пока",
+                    "Цикл с условием",
+                    "Пока ${1:Условие} Цикл\n\t$0\nКонецЦикла;",
+                ),
+                Snippet::new(
+                    "Если",
+                    "Условие",
+                    "Если ${1:Условие} Тогда\n\t$0\nКонецЕсли;",
+                ),
+                Snippet::new(
+                    "ЕслиИначе",
+                    "Условие с веткой Иначе",
+                    "Если ${1:Условие} Тогда\n\t$2\nИначе\n\t$0\nКонецЕсли;",
+                ),
+                Snippet::new(
+                    "Попытка",
+                    "Обработка исключения",
+                    "Попытка\n\t$0\nИсключение\n\t\
+                     ${1:ЗаписьЖурналаРегистрации(\"${2:Событие}\", \
+                     УровеньЖурналаРегистрации.Ошибка, , , \
+                     ПодробноеПредставлениеОшибки(ИнформацияОбОшибке()));}\n\
+                     КонецПопытки;",
+                ),
+                Snippet::new(
+                    "НоваяТаблицаЗначений",
+                    "Таблица значений с колонками",
+                    "${1:Таблица} = Новый ТаблицаЗначений;\n\
+                     $1.Колонки.Добавить(\"${2:Колонка1}\", \
+                     Новый ОписаниеТипов(\"${3:Строка}\"));\n\
+                     $1.Колонки.Добавить(\"${4:Колонка2}\", \
+                     Новый ОписаниеТипов(\"${5:Число}\"));\n$0",
+                ),
+                Snippet::new(
+                    "НоваяСтруктура",
+                    "Структура с ключами",
+                    "${1:Параметры} = Новый Структура(\"${2:Ключ1, Ключ2}\");\n$0",
+                ),
+                Snippet::new(
+                    "Процедура",
+                    "Процедура модуля",
+                    "Процедура ${1:Имя}(${2})\n\t$0\nКонецПроцедуры",
+                ),
+                Snippet::new(
+                    "Функция",
+                    "Функция модуля",
+                    "Функция ${1:Имя}(${2})\n\t$0\n\tВозврат ${3:Неопределено};\nКонецФункции",
+                ),
+                Snippet::new(
+                    "Запрос",
+                    "Запрос и выборка результата",
+                    "Запрос = Новый Запрос;\nЗапрос.Текст =\n\t\"ВЫБРАТЬ\n\t|\t${1:*}\n\t|ИЗ\n\t|\t\
+                     ${2:Справочник.Имя}\";\n\nВыборка = Запрос.Выполнить().Выбрать();\n\
+                     Пока Выборка.Следующий() Цикл\n\t$0\nКонецЦикла;",
+                ),
+            ],
+        }
+    }
+
+    /// Встроенные шаблоны и шаблоны из файла `path` (формат сниппетов
+    /// VS Code); пользовательский шаблон заменяет встроенный с тем же префиксом
+    pub fn with_user_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+        let definitions: BTreeMap<String, SnippetDefinition> = serde_json::from_str(&content)
+            .with_context(|| format!("Неверный формат шаблонов {}", path.display()))?;
+
+        let mut provider = Self::builtin();
+        for (name, definition) in definitions {
+            let body = match definition.body {
+                SnippetBody::Text(text) => text,
+                SnippetBody::Lines(lines) => lines.join("\n"),
+            };
+            provider.insert(Snippet {
+                prefix: definition.prefix,
+                description: definition.description.unwrap_or(name),
+                body,
+            });
+        }
+        Ok(provider)
+    }
+
+    /// Добавить шаблон; шаблон с тем же префиксом (без учёта регистра)
+    /// заменяется
+    pub fn insert(&mut self, snippet: Snippet) {
+        let prefix = snippet.prefix.to_lowercase();
+        self.snippets
+            .retain(|existing| existing.prefix.to_lowercase() != prefix);
+        self.snippets.push(snippet);
+    }
+
+    /// Шаблоны, префикс которых начинается с введённого (без учёта регистра);
+    /// после точки шаблоны не предлагаются
+    pub fn matching(&self, typed: &str) -> Vec<&Snippet> {
+        if typed.is_empty() || typed.contains('.') {
+            return Vec::new();
+        }
+        let typed = typed.to_lowercase();
+        self.snippets
+            .iter()
+            .filter(|snippet| snippet.prefix.to_lowercase().starts_with(&typed))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.snippets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snippets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_snippets_override_builtin() {
+        let builtin = SnippetProvider::builtin();
+        let matches = builtin.matching("длякаж");
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].body.starts_with("Для Каждого ${1:Элемент} Из"));
+        assert!(builtin.matching("Таблица.Для").is_empty());
+        assert_eq!(builtin.matching("Для").len(), 2);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snippets.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "Цикл по коллекции": {
+                    "prefix": "ДляКаждого",
+                    "body": ["Для Каждого ${1:Стр} Из ${2:Таблица} Цикл", "КонецЦикла;"]
+                },
+                "Сообщение": {
+                    "prefix": "Сообщ", "body": "Сообщить(\"$1\");", "description": "Вывод"
+                }
+            }"#,
+        )
+        .unwrap();
+        let provider = SnippetProvider::with_user_file(&path).unwrap();
+        assert_eq!(provider.len(), builtin.len() + 1);
+        let each = provider.matching("ДляКаждого");
+        assert_eq!(
+            each[0].body,
+            "Для Каждого ${1:Стр} Из ${2:Таблица} Цикл\nКонецЦикла;"
+        );
+        assert_eq!(each[0].description, "Цикл по коллекции");
+        assert_eq!(provider.matching("сооб")[0].description, "Вывод");
+    }
+}
@@ -0,0 +1,305 @@
+//! Локальная телеметрия использования (opt-in)
+//!
+//! Если задан файл телеметрии (`telemetry_path`, `BSL_TELEMETRY`), процесс
+//! считает запущенные команды, использованные возможности и длительности
+//! операций, а при завершении добавляет счётчики в этот JSON-файл. Данные
+//! никуда не отправляются и анонимны: в файл попадают только имена команд и
+//! возможностей и длительности — без путей, имён объектов и текста модулей.
+//! Файл можно открыть или приложить к обсуждению, чтобы решить, какие
+//! подсистемы стоит оптимизировать.
+//!
+//! Длительности хранятся гистограммой с фиксированными границами корзин:
+//! гистограммы разных запусков складываются, а перцентили считаются по сумме.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use crate::data::WriteGuard;
+
+/// Версия формата файла; файл другой версии начинается заново
+pub const TELEMETRY_FORMAT_VERSION: u32 = 1;
+
+/// Верхние границы корзин гистограммы, мс; последняя корзина — всё, что дольше
+const BUCKET_BOUNDS_MS: &[f64] = &[
+    1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0,
+    30000.0,
+];
+
+/// Гистограмма длительностей операции
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyHistogram {
+    pub count: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+    /// Число замеров по корзинам `BUCKET_BOUNDS_MS` и корзина переполнения
+    pub buckets: Vec<u64>,
+    /// Оценки перцентилей (верхняя граница корзины), пересчитываются при
+    /// сохранении для удобства чтения файла
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, ms: f64) {
+        self.buckets.resize(BUCKET_BOUNDS_MS.len() + 1, 0);
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|&bound| ms <= bound)
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.buckets.resize(BUCKET_BOUNDS_MS.len() + 1, 0);
+        for (bucket, count) in self.buckets.iter_mut().zip(&other.buckets) {
+            *bucket += count;
+        }
+        self.count += other.count;
+        self.total_ms += other.total_ms;
+        self.max_ms = self.max_ms.max(other.max_ms);
+    }
+
+    /// Перцентиль `p` (0–100): верхняя граница корзины, в которую он попадает
+    /// (для корзины переполнения — максимум)
+    pub fn percentile(&self, p: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let rank = ((p / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (idx, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let bound = BUCKET_BOUNDS_MS.get(idx).copied().unwrap_or(self.max_ms);
+                return bound.min(self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+
+    fn refresh_percentiles(&mut self) {
+        self.p50_ms = self.percentile(50.0);
+        self.p90_ms = self.percentile(90.0);
+        self.p99_ms = self.percentile(99.0);
+    }
+}
+
+/// Содержимое файла телеметрии
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryReport {
+    pub format_version: u32,
+    /// Версия инструмента при последнем сохранении
+    pub tool_version: String,
+    /// Сколько раз процессы сохраняли счётчики
+    pub sessions: u64,
+    /// Время последнего сохранения (секунды Unix)
+    pub updated_at: u64,
+    /// Запуски команд CLI (`analyze`, `doctor`, ...)
+    pub commands: BTreeMap<String, u64>,
+    /// Обращения к возможностям (`lsp.hover`, `lsp.rename`, ...)
+    pub features: BTreeMap<String, u64>,
+    /// Длительности операций
+    pub timings: BTreeMap<String, LatencyHistogram>,
+}
+
+impl TelemetryReport {
+    fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.features.is_empty() && self.timings.is_empty()
+    }
+
+    /// Добавить счётчики другого отчёта
+    pub fn merge(&mut self, other: &Self) {
+        for (name, count) in &other.commands {
+            *self.commands.entry(name.clone()).or_default() += count;
+        }
+        for (name, count) in &other.features {
+            *self.features.entry(name.clone()).or_default() += count;
+        }
+        for (name, histogram) in &other.timings {
+            self.timings.entry(name.clone()).or_default().merge(histogram);
+        }
+        self.sessions += other.sessions;
+    }
+
+    /// Прочитать файл телеметрии; отсутствующий файл — пустой отчёт, файл
+    /// другой версии формата или повреждённый файл начинается заново
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Не удалось прочитать {}", path.display()))
+            }
+        };
+        match serde_json::from_str::<Self>(&content) {
+            Ok(report) if report.format_version == TELEMETRY_FORMAT_VERSION => Ok(report),
+            _ => {
+                warn!("Файл телеметрии {} не распознан и будет перезаписан", path.display());
+                Ok(Self::default())
+            }
+        }
+    }
+}
+
+/// Файл и счётчики текущего процесса
+#[derive(Debug)]
+struct TelemetrySink {
+    path: PathBuf,
+    write_guard: WriteGuard,
+    session: Mutex<TelemetryReport>,
+}
+
+/// Накопитель телеметрии процесса; клоны разделяют счётчики. Если файл
+/// телеметрии не задан, все методы ничего не делают.
+#[derive(Debug, Clone, Default)]
+pub struct Telemetry {
+    sink: Option<Arc<TelemetrySink>>,
+}
+
+impl Telemetry {
+    /// Телеметрия в файл `path` (`None` — выключена)
+    pub fn new(path: Option<&str>, write_guard: WriteGuard) -> Self {
+        Self {
+            sink: path.map(|path| {
+                Arc::new(TelemetrySink {
+                    path: PathBuf::from(path),
+                    write_guard,
+                    session: Mutex::new(TelemetryReport::default()),
+                })
+            }),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut TelemetryReport)) {
+        if let Some(mut session) = self.sink.as_ref().and_then(|sink| sink.session.lock().ok()) {
+            apply(&mut session);
+        }
+    }
+
+    /// Учесть запуск команды CLI
+    pub fn record_command(&self, name: &str) {
+        self.update(|session| *session.commands.entry(name.to_string()).or_default() += 1);
+    }
+
+    /// Учесть обращение к возможности
+    pub fn record_feature(&self, name: &str) {
+        self.update(|session| *session.features.entry(name.to_string()).or_default() += 1);
+    }
+
+    /// Учесть длительность операции
+    pub fn record_timing(&self, name: &str, duration: Duration) {
+        self.update(|session| {
+            let ms = duration.as_secs_f64() * 1000.0;
+            session.timings.entry(name.to_string()).or_default().record(ms);
+        });
+    }
+
+    /// Добавить накопленные счётчики в файл и начать их заново. В режиме
+    /// только для чтения файл не записывается, счётчики теряются.
+    pub fn flush(&self) -> Result<()> {
+        let Some(sink) = &self.sink else {
+            return Ok(());
+        };
+        let session = match sink.session.lock() {
+            Ok(mut session) => std::mem::take(&mut *session),
+            Err(_) => return Ok(()),
+        };
+        if session.is_empty() || !sink.write_guard.allows("телеметрия", &sink.path) {
+            return Ok(());
+        }
+
+        let mut report = TelemetryReport::load(&sink.path)?;
+        report.merge(&session);
+        report.sessions += 1;
+        report.format_version = TELEMETRY_FORMAT_VERSION;
+        report.tool_version = env!("CARGO_PKG_VERSION").to_string();
+        report.updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        for histogram in report.timings.values_mut() {
+            histogram.refresh_percentiles();
+        }
+
+        if let Some(dir) = sink.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Не удалось создать каталог {}", dir.display()))?;
+        }
+        // Через временный файл: параллельные процессы не увидят половину JSON
+        let tmp_path = sink.path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Не удалось записать {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, &sink.path)
+            .with_context(|| format!("Не удалось записать {}", sink.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let mut histogram = LatencyHistogram::default();
+        for ms in [0.5, 3.0, 4.0, 8.0, 15.0, 40.0, 40.0, 90.0, 150.0, 700.0] {
+            histogram.record(ms);
+        }
+        assert_eq!(histogram.count, 10);
+        assert_eq!(histogram.percentile(50.0), 20.0);
+        assert_eq!(histogram.percentile(90.0), 200.0);
+        // Последний замер — в корзине до 1000 мс, но больше максимума не бывает
+        assert_eq!(histogram.percentile(99.0), 700.0);
+
+        let mut other = LatencyHistogram::default();
+        other.record(60_000.0);
+        histogram.merge(&other);
+        assert_eq!(histogram.count, 11);
+        assert_eq!(histogram.percentile(100.0), 60_000.0);
+    }
+
+    #[test]
+    fn test_flush_aggregates_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("telemetry/usage.json");
+        let path_str = path.to_string_lossy().to_string();
+
+        for _ in 0..2 {
+            let telemetry = Telemetry::new(Some(&path_str), WriteGuard::default());
+            telemetry.record_command("analyze");
+            telemetry.record_feature("lsp.hover");
+            telemetry.record_timing("initialize", Duration::from_millis(30));
+            telemetry.flush().unwrap();
+            // Повторный flush без новых событий ничего не добавляет
+            telemetry.flush().unwrap();
+        }
+
+        let report = TelemetryReport::load(&path).unwrap();
+        assert_eq!(report.sessions, 2);
+        assert_eq!(report.commands["analyze"], 2);
+        assert_eq!(report.features["lsp.hover"], 2);
+        assert_eq!(report.timings["initialize"].count, 2);
+        assert_eq!(report.timings["initialize"].p50_ms, 30.0);
+
+        // Выключенная телеметрия и режим только для чтения не пишут файл
+        Telemetry::default().record_command("doctor");
+        let read_only = Telemetry::new(Some(&path_str), WriteGuard::new(true));
+        read_only.record_command("doctor");
+        read_only.flush().unwrap();
+        assert!(!TelemetryReport::load(&path).unwrap().commands.contains_key("doctor"));
+    }
+}
@@ -13,9 +13,10 @@ use crate::domain::types::{
 };
 use crate::parsing::bsl::ast::*;
 use crate::parsing::bsl::graph_builder::DependencyGraphBuilder;
+use crate::parsing::bsl::preprocessor::ContextSet;
 use crate::parsing::bsl::visitor::AstVisitor;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Диагностическое сообщение о проблеме с типами
 #[derive(Debug, Clone)]
@@ -47,6 +48,9 @@ pub struct TypeContext {
     pub current_scope: Scope,
     /// Стек областей видимости
     pub scope_stack: Vec<Scope>,
+    /// Контексты компиляции, для которых идёт анализ: код веток
+    /// `#Если ... Тогда`, не компилируемых в них, не анализируется
+    pub active_contexts: ContextSet,
 }
 
 /// Сигнатура функции
@@ -68,6 +72,9 @@ pub struct TypeChecker {
     interprocedural_analyzer: Option<InterproceduralAnalyzer>,
     #[allow(dead_code)]
     cache_manager: Option<AnalysisCacheManager>,
+    /// Методы модуля, объявленные только в ветках условной компиляции вне
+    /// `active_contexts`
+    unavailable_functions: HashSet<String>,
 }
 
 impl TypeChecker {
@@ -79,6 +86,7 @@ impl TypeChecker {
                 functions: HashMap::new(),
                 current_scope: Scope::Module(file_name.clone()),
                 scope_stack: Vec::new(),
+                active_contexts: ContextSet::ALL,
             },
             diagnostics: Vec::new(),
             dependency_graph: None,
@@ -87,9 +95,19 @@ impl TypeChecker {
             flow_analyzer: None,
             interprocedural_analyzer: None,
             cache_manager: None,
+            unavailable_functions: HashSet::new(),
         }
     }
 
+    /// Анализ только для контекстов компиляции `contexts` (например,
+    /// `ContextSet::SERVER`): ветки `#Если`, которые в них не компилируются,
+    /// пропускаются, а вызов метода, объявленного только в таких ветках, —
+    /// предупреждение
+    pub fn with_contexts(mut self, contexts: ContextSet) -> Self {
+        self.context.active_contexts = contexts;
+        self
+    }
+
     /// Создание type checker с кешированием
     pub fn with_cache<P: AsRef<std::path::Path>>(
         file_name: String,
@@ -103,6 +121,7 @@ impl TypeChecker {
                 functions: HashMap::new(),
                 current_scope: Scope::Module(file_name.clone()),
                 scope_stack: Vec::new(),
+                active_contexts: ContextSet::ALL,
             },
             diagnostics: Vec::new(),
             dependency_graph: None,
@@ -111,11 +130,19 @@ impl TypeChecker {
             flow_analyzer: None,
             interprocedural_analyzer: None,
             cache_manager: Some(cache_manager),
+            unavailable_functions: HashSet::new(),
         })
     }
 
     /// Проверка типов в программе
     pub fn check(mut self, program: &Program) -> (TypeContext, Vec<TypeDiagnostic>) {
+        // Анализируем программу такой, какой её видит компилятор в активных
+        // контекстах; методы из отброшенных веток запоминаем для диагностики
+        let declared = declared_methods(&program.for_contexts(ContextSet::ALL));
+        let program = &program.for_contexts(self.context.active_contexts);
+        let available = declared_methods(program);
+        self.unavailable_functions = declared.difference(&available).cloned().collect();
+
         // Сначала строим граф зависимостей
         let builder = DependencyGraphBuilder::new(self.current_file.clone());
         self.dependency_graph = Some(builder.build(program));
@@ -270,6 +297,8 @@ impl TypeChecker {
                             TypeResolution::unknown()
                         }
                     }
+                    // Тип результата обещания не выводится
+                    UnaryOp::Await => TypeResolution::unknown(),
                 }
             }
 
@@ -307,6 +336,15 @@ impl TypeChecker {
                         }
 
                         signature.return_type
+                    } else if self.unavailable_functions.contains(func_name) {
+                        self.add_diagnostic(
+                            DiagnosticSeverity::Warning,
+                            format!(
+                                "Функция '{}' не компилируется в контексте: {}",
+                                func_name, self.context.active_contexts
+                            ),
+                        );
+                        TypeResolution::unknown()
                     } else {
                         self.add_diagnostic(
                             DiagnosticSeverity::Info,
@@ -435,6 +473,20 @@ impl TypeChecker {
     }
 }
 
+/// Имена процедур и функций верхнего уровня программы
+fn declared_methods(program: &Program) -> HashSet<String> {
+    program
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::ProcedureDecl { name, .. } | Statement::FunctionDecl { name, .. } => {
+                Some(name.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 impl AstVisitor for TypeChecker {
     fn visit_var_declaration(&mut self, name: &str, value: Option<&Expression>, _export: bool) {
         let var_type = if let Some(expr) = value {
@@ -796,6 +848,44 @@ mod tests {
                 && d.message.contains("ожидает 2 аргументов")));
     }
 
+    #[test]
+    fn test_check_per_compilation_context() {
+        let code = r#"
+            #Если Сервер Тогда
+            Функция ПрочитатьНастройки()
+                Возврат 1;
+            КонецФункции
+            #КонецЕсли
+            Перем Настройки = ПрочитатьНастройки();
+        "#;
+        let program = BslParser::new(code).unwrap().parse().unwrap();
+
+        let (context, diagnostics) = TypeChecker::new("test.bsl".to_string())
+            .with_contexts(ContextSet::SERVER)
+            .check(&program);
+        assert_eq!(context.active_contexts, ContextSet::SERVER);
+        assert!(context.functions.contains_key("ПрочитатьНастройки"));
+        assert!(diagnostics
+            .iter()
+            .all(|d| !d.message.contains("не компилируется")));
+
+        // На клиенте функции нет: вызов — предупреждение, а не «не найдена»
+        let (context, diagnostics) = TypeChecker::new("test.bsl".to_string())
+            .with_contexts(ContextSet::WEB_CLIENT)
+            .check(&program);
+        assert!(!context.functions.contains_key("ПрочитатьНастройки"));
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.severity == DiagnosticSeverity::Warning
+                && d.message
+                    == "Функция 'ПрочитатьНастройки' не компилируется в контексте: ВебКлиент"));
+
+        // Без ограничения анализируются все ветки
+        let (context, _) = TypeChecker::new("test.bsl".to_string()).check(&program);
+        assert_eq!(context.active_contexts, ContextSet::ALL);
+        assert!(context.functions.contains_key("ПрочитатьНастройки"));
+    }
+
     #[test]
     fn test_type_mismatch_warning() {
         let code = r#"
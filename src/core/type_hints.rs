@@ -217,7 +217,7 @@ impl TypeHintsProvider {
     }
 
     /// Проверить нужно ли показывать hint для типа
-    fn should_show_hint(&self, type_res: &TypeResolution) -> bool {
+    pub fn should_show_hint(&self, type_res: &TypeResolution) -> bool {
         // Проверяем уровень уверенности
 
         // Для тестов показываем все типы
@@ -333,7 +333,7 @@ impl TypeHintsProvider {
     }
 
     /// Форматировать тип для краткого отображения в hint
-    fn format_type_hint(&self, type_res: &TypeResolution) -> String {
+    pub fn format_type_hint(&self, type_res: &TypeResolution) -> String {
         let base_text = match &type_res.result {
             ResolutionResult::Concrete(ConcreteType::Primitive(primitive)) => match primitive {
                 crate::core::types::PrimitiveType::String => "Строка",
@@ -376,12 +376,13 @@ impl TypeHintsProvider {
 
         let full_text = format!("{}{}", base_text, confidence_indicator);
 
-        // Обрезаем если слишком длинный
-        if full_text.len() > self.settings.max_hint_length {
-            format!(
-                "{}...",
-                &full_text[..self.settings.max_hint_length.saturating_sub(3)]
-            )
+        // Обрезаем если слишком длинный (по символам: имена типов кириллические)
+        if full_text.chars().count() > self.settings.max_hint_length {
+            let kept: String = full_text
+                .chars()
+                .take(self.settings.max_hint_length.saturating_sub(3))
+                .collect();
+            format!("{}...", kept)
         } else {
             full_text
         }
@@ -431,7 +432,7 @@ impl TypeHintsProvider {
     }
 
     /// Форматировать детальную информацию о типе
-    fn format_detailed_type(&self, type_res: &TypeResolution) -> String {
+    pub fn format_detailed_type(&self, type_res: &TypeResolution) -> String {
         match &type_res.result {
             ResolutionResult::Concrete(concrete) => {
                 format!(
@@ -836,6 +837,7 @@ mod tests {
             functions,
             current_scope: crate::core::dependency_graph::Scope::Global,
             scope_stack: vec![],
+            active_contexts: crate::parsing::bsl::ContextSet::ALL,
         }
     }
 
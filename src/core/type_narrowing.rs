@@ -239,6 +239,7 @@ impl TypeNarrower {
                 line: None,
                 column: None,
                 notes: vec!["Type narrowed from condition".to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -256,6 +257,7 @@ impl TypeNarrower {
                 line: None,
                 column: None,
                 notes: vec!["Type narrowed from condition".to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -279,6 +281,7 @@ impl TypeNarrower {
                 line: None,
                 column: None,
                 notes: vec!["Type narrowed from condition".to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -302,6 +305,7 @@ impl TypeNarrower {
                 line: None,
                 column: None,
                 notes: vec!["Truthy value in condition".to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -319,6 +323,7 @@ impl TypeNarrower {
                 line: None,
                 column: None,
                 notes: vec!["Unknown type".to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -395,6 +400,7 @@ mod tests {
             functions: HashMap::new(),
             current_scope: Scope::Global,
             scope_stack: vec![],
+            active_contexts: crate::parsing::bsl::ContextSet::ALL,
         }
     }
 
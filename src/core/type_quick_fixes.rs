@@ -0,0 +1,302 @@
+//! Quick fixes для диагностик типов
+//!
+//! Для несовместимого присваивания предлагается явное преобразование
+//! значения (`Число(...)`, `Строка(...)`) или аннотация `// @type Тип` в
+//! конце строки: она объявляет, что с этого присваивания переменная имеет
+//! указанный тип. Аннотацию учитывает вывод типов локальных переменных, а
+//! строка с ней не проверяется на совместимость присваивания. Для
+//! переменной, используемой без объявления, добавляется `Перем` в начало
+//! метода (или модуля, если переменная используется вне методов).
+//!
+//! Действия строятся по тексту диагностик, которые клиент передаёт в
+//! запросе `textDocument/codeAction`.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tower_lsp::lsp_types::*;
+
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::{method_end_regex, method_header_regex};
+
+/// Типы, в которые значение преобразуется одноимённой функцией платформы
+pub const CONVERSIONS: &[&str] = &["Строка", "Число", "Булево", "Дата"];
+
+/// Отступ тела метода, если по тексту его не определить
+const DEFAULT_INDENT: &str = "    ";
+
+fn annotation_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^//\s*@(?:type|тип)\s+([\p{L}_][\p{L}\p{N}_.]*)").unwrap()
+    })
+}
+
+fn mismatch_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"^Несовместимые типы в присваивании '([^']+)': присваивается (.+), ожидается (.+)$",
+        )
+        .unwrap()
+    })
+}
+
+fn undeclared_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^Переменная '([^']+)' используется без объявления").unwrap())
+}
+
+/// Тип из аннотации `// @type Тип` (или `// @тип Тип`) в конце строки
+pub fn type_annotation(line: &str) -> Option<&str> {
+    let comment = &line[strip_comment(line).len()..];
+    annotation_regex()
+        .captures(comment)
+        .and_then(|caps| caps.get(1))
+        .map(|name| name.as_str())
+}
+
+/// Текст диагностики несовместимого присваивания, по которому строятся
+/// quick fixes
+pub fn assignment_mismatch_message(variable: &str, actual: &str, expected: &str) -> String {
+    format!(
+        "Несовместимые типы в присваивании '{}': присваивается {}, ожидается {}",
+        variable, actual, expected
+    )
+}
+
+/// Правая часть присваивания в строке: (начало, конец) в символах
+fn assigned_value_span(line: &str) -> Option<(usize, usize)> {
+    let code = strip_comment(line);
+    let mut in_string = false;
+    let mut eq = None;
+    for (idx, ch) in code.chars().enumerate() {
+        match ch {
+            '"' => in_string = !in_string,
+            '=' if !in_string => {
+                eq = Some(idx);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let chars: Vec<char> = code.chars().collect();
+    let mut start = eq? + 1;
+    let mut end = chars.len();
+    while end > start && (chars[end - 1].is_whitespace() || chars[end - 1] == ';') {
+        end -= 1;
+    }
+    while start < end && chars[start].is_whitespace() {
+        start += 1;
+    }
+    (start < end).then_some((start, end))
+}
+
+fn quick_fix(
+    title: String,
+    uri: &Url,
+    diagnostic: &Diagnostic,
+    edit: TextEdit,
+    preferred: bool,
+) -> CodeActionOrCommand {
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), vec![edit]);
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        is_preferred: Some(preferred),
+        ..Default::default()
+    })
+}
+
+/// Преобразование значения и аннотация для несовместимого присваивания
+fn mismatch_fixes(
+    uri: &Url,
+    lines: &[&str],
+    diagnostic: &Diagnostic,
+    actual: &str,
+    expected: &str,
+) -> Vec<CodeActionOrCommand> {
+    let line_idx = diagnostic.range.start.line;
+    let Some(line) = lines.get(line_idx as usize) else {
+        return Vec::new();
+    };
+    let mut fixes = Vec::new();
+
+    if let Some((start, end)) = assigned_value_span(line) {
+        let value: String = line.chars().skip(start).take(end - start).collect();
+        let prefix = format!("{}(", expected);
+        let converted = value.to_lowercase().starts_with(&prefix.to_lowercase());
+        if CONVERSIONS.contains(&expected) && !converted {
+            fixes.push(quick_fix(
+                format!("Преобразовать значение: {}(...)", expected),
+                uri,
+                diagnostic,
+                TextEdit {
+                    range: Range::new(
+                        Position::new(line_idx, start as u32),
+                        Position::new(line_idx, end as u32),
+                    ),
+                    new_text: format!("{}{})", prefix, value),
+                },
+                true,
+            ));
+        }
+    }
+
+    // Аннотация дописывается только в строку без комментария
+    if strip_comment(line).len() == line.len() && !actual.contains(' ') {
+        let end = line.trim_end().chars().count() as u32;
+        fixes.push(quick_fix(
+            format!("Добавить аннотацию // @type {}", actual),
+            uri,
+            diagnostic,
+            TextEdit {
+                range: Range::new(Position::new(line_idx, end), Position::new(line_idx, end)),
+                new_text: format!(" // @type {}", actual),
+            },
+            false,
+        ));
+    }
+    fixes
+}
+
+/// Объявление `Перем` в начале метода, содержащего строку, или модуля
+fn declare_variable_fix(
+    uri: &Url,
+    lines: &[&str],
+    diagnostic: &Diagnostic,
+    variable: &str,
+) -> CodeActionOrCommand {
+    let line_idx = (diagnostic.range.start.line as usize).min(lines.len());
+    let header = lines[..line_idx]
+        .iter()
+        .enumerate()
+        .rev()
+        .take_while(|(_, line)| !method_end_regex().is_match(line))
+        .find(|(_, line)| method_header_regex().is_match(line))
+        .map(|(idx, _)| idx);
+
+    let (insert_line, indent) = match header {
+        Some(header) => {
+            // Заголовок может занимать несколько строк
+            let body = (header..lines.len())
+                .find(|&idx| strip_comment(lines[idx]).contains(')'))
+                .map_or(header + 1, |idx| idx + 1);
+            let indent = lines[body..]
+                .iter()
+                .find(|line| !line.trim().is_empty())
+                .map(|line| &line[..line.len() - line.trim_start().len()])
+                .filter(|indent| !indent.is_empty())
+                .unwrap_or(DEFAULT_INDENT);
+            (body, indent)
+        }
+        None => (0, ""),
+    };
+    let at = Position::new(insert_line as u32, 0);
+    quick_fix(
+        format!("Объявить переменную '{}'", variable),
+        uri,
+        diagnostic,
+        TextEdit {
+            range: Range::new(at, at),
+            new_text: format!("{}Перем {};\n", indent, variable),
+        },
+        true,
+    )
+}
+
+/// Quick fixes для диагностик типов из запроса code action
+pub fn type_quick_fixes(
+    uri: &Url,
+    text: &str,
+    diagnostics: &[Diagnostic],
+) -> Vec<CodeActionOrCommand> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut actions = Vec::new();
+    for diagnostic in diagnostics {
+        if let Some(caps) = mismatch_regex().captures(&diagnostic.message) {
+            actions.extend(mismatch_fixes(uri, &lines, diagnostic, &caps[2], &caps[3]));
+        } else if let Some(caps) = undeclared_regex().captures(&diagnostic.message) {
+            actions.push(declare_variable_fix(uri, &lines, diagnostic, &caps[1]));
+        }
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = "Функция Сумма(Знач А,
+    Б)
+    Итог = Строка(А); // число в строку
+    Итог = Б + 1;
+    Возврат Итог;
+КонецФункции
+";
+
+    fn edits(action: &CodeActionOrCommand) -> (String, Vec<TextEdit>) {
+        let CodeActionOrCommand::CodeAction(action) = action else {
+            panic!("ожидалось CodeAction");
+        };
+        let changes = action.edit.as_ref().unwrap().changes.as_ref().unwrap();
+        (action.title.clone(), changes.values().next().unwrap().clone())
+    }
+
+    fn diagnostic(line: u32, message: String) -> Diagnostic {
+        Diagnostic {
+            range: Range::new(Position::new(line, 0), Position::new(line, 10)),
+            message,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mismatch_fixes() {
+        let uri = Url::parse("file:///Module.bsl").unwrap();
+        let message = assignment_mismatch_message("Итог", "Число", "Строка");
+        let actions = type_quick_fixes(&uri, MODULE, &[diagnostic(3, message)]);
+        assert_eq!(actions.len(), 2);
+
+        let (title, convert) = edits(&actions[0]);
+        assert_eq!(title, "Преобразовать значение: Строка(...)");
+        assert_eq!(convert[0].new_text, "Строка(Б + 1)");
+        assert_eq!(convert[0].range.start, Position::new(3, 11));
+        assert_eq!(convert[0].range.end, Position::new(3, 16));
+
+        let (_, annotate) = edits(&actions[1]);
+        assert_eq!(annotate[0].new_text, " // @type Число");
+        assert_eq!(annotate[0].range.start, Position::new(3, 17));
+
+        // Уже преобразованное значение и строка с комментарием
+        let message = assignment_mismatch_message("Итог", "Число", "Строка");
+        assert!(type_quick_fixes(&uri, MODULE, &[diagnostic(2, message)]).is_empty());
+
+        assert_eq!(type_annotation("Х = Прочитать(); // @type Массив"), Some("Массив"));
+        assert_eq!(type_annotation("Х = \"// @type Массив\";"), None);
+    }
+
+    #[test]
+    fn test_declare_variable_fix() {
+        let uri = Url::parse("file:///Module.bsl").unwrap();
+        let message = "Переменная 'Сумма2' используется без объявления".to_string();
+        let actions = type_quick_fixes(&uri, MODULE, &[diagnostic(3, message.clone())]);
+        let (title, declare) = edits(&actions[0]);
+        assert_eq!(title, "Объявить переменную 'Сумма2'");
+        assert_eq!(declare[0].range.start, Position::new(2, 0));
+        assert_eq!(declare[0].new_text, "    Перем Сумма2;\n");
+
+        // Вне методов объявление добавляется в начало модуля
+        let text = format!("{}\nСумма2 = 1;\n", MODULE);
+        let actions = type_quick_fixes(&uri, &text, &[diagnostic(7, message)]);
+        let (_, declare) = edits(&actions[0]);
+        assert_eq!(declare[0].range.start, Position::new(0, 0));
+        assert_eq!(declare[0].new_text, "Перем Сумма2;\n");
+    }
+}
@@ -0,0 +1,88 @@
+//! Ссылки на типы по имени в тексте модуля
+//!
+//! Имя типа в коде BSL встречается в конструкторе (`Новый Имя`) и в
+//! описании типа (`Тип("Имя")`). По этим ссылкам проверяется, не совпадает
+//! ли имя у типов разных источников (объекта конфигурации и типа платформы).
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::core::example_mining::strip_comment;
+
+/// Упоминание имени типа в тексте
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeNameReference {
+    /// Строка (с 0)
+    pub line: u32,
+    /// Позиция начала имени в символах
+    pub column: u32,
+    pub name: String,
+}
+
+fn reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r#"(?i)(?:^|[^\p{L}\p{N}_])(?:(?:новый|new)\s+([\p{L}_][\p{L}\p{N}_]*)|(?:тип|type)\s*\(\s*"([^"]+)"\s*\))"#,
+        )
+        .unwrap()
+    })
+}
+
+/// Находится ли байтовая позиция внутри строкового литерала
+fn inside_string(code: &str, byte_idx: usize) -> bool {
+    code[..byte_idx].matches('"').count() % 2 == 1
+}
+
+/// Ссылки на типы по имени в тексте модуля (комментарии и строки
+/// пропускаются)
+pub fn type_name_references(text: &str) -> Vec<TypeNameReference> {
+    let mut out = Vec::new();
+    for (idx, source) in text.lines().enumerate() {
+        let code = strip_comment(source);
+        for caps in reference_regex().captures_iter(code) {
+            let Some(name) = caps.get(1).or_else(|| caps.get(2)) else {
+                continue;
+            };
+            // Для `Тип("...")` имя само стоит в кавычках — проверяем конец вызова
+            let at = match caps.get(1) {
+                Some(_) => name.start(),
+                None => caps.get(0).map_or(name.end(), |m| m.end()),
+            };
+            if inside_string(code, at) {
+                continue;
+            }
+            out.push(TypeNameReference {
+                line: idx as u32,
+                column: code[..name.start()].chars().count() as u32,
+                name: name.as_str().trim().to_string(),
+            });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constructor_and_type_references() {
+        let text = r#"Файл = Новый Файл(Путь);
+Если ТипЗнч(Значение) = Тип("СправочникСсылка.Товары") Тогда
+    Цвет = New Color(0, 0, 0); // Новый Комментарий
+КонецЕсли;
+Сообщить("Новый Текст");"#;
+        let refs = type_name_references(text);
+        let names: Vec<&str> = refs.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["Файл", "СправочникСсылка.Товары", "Color"]);
+        assert_eq!((refs[0].line, refs[0].column), (0, 13));
+        assert_eq!((refs[2].line, refs[2].column), (2, 15));
+    }
+
+    #[test]
+    fn test_identifier_suffix_is_not_keyword() {
+        assert!(type_name_references("НеНовый Файл").is_empty());
+        assert!(type_name_references("ПолучитьТип(\"Файл\")").is_empty());
+    }
+}
@@ -420,11 +420,13 @@ impl WebTypeInterface {
     pub async fn search_types(&self, query: &str) -> Vec<TypeDisplayInfo> {
         let matching_resolutions = self.unified_system.find_types_by_pattern(query).await;
 
+        // Идентификатор — имя типа, как в `get_all_types_for_display`: по нему
+        // работает `get_type_details`, и он не зависит от порядка выдачи
         matching_resolutions
             .into_iter()
-            .enumerate()
-            .map(|(i, resolution)| {
-                TypeDisplayInfo::from_resolution(format!("search_result_{}", i), resolution)
+            .map(|resolution| {
+                let id = resolution.get_name().unwrap_or_default();
+                TypeDisplayInfo::from_resolution(id, resolution)
             })
             .collect()
     }
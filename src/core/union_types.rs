@@ -76,6 +76,7 @@ impl UnionTypeManager {
                     line: None,
                     column: None,
                     notes: vec!["Union type simplified to concrete".to_string()],
+                    resolver: None,
                 },
                 active_facet: None,
                 available_facets: vec![],
@@ -89,6 +90,7 @@ impl UnionTypeManager {
                     line: None,
                     column: None,
                     notes: vec![format!("Union of {} types", simplified.len())],
+                    resolver: None,
                 },
                 active_facet: None,
                 available_facets: vec![],
@@ -239,6 +241,7 @@ impl UnionTypeManager {
                 line: None,
                 column: None,
                 notes: vec!["Never type (empty union)".to_string()],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -261,6 +264,7 @@ impl UnionTypeManager {
                     line: None,
                     column: None,
                     notes: vec![],
+                    resolver: None,
                 },
                 active_facet: None,
                 available_facets: vec![],
@@ -286,6 +290,7 @@ impl UnionTypeManager {
                             line: None,
                             column: None,
                             notes: vec![],
+                            resolver: None,
                         },
                         active_facet: None,
                         available_facets: vec![],
@@ -0,0 +1,520 @@
+//! Отчёт об использовании типов и методов в проекте
+//!
+//! Сопоставляет каталог известных типов (платформа + конфигурация) с исходным
+//! кодом проекта и показывает, какие типы и методы используются чаще всего,
+//! какие не используются вовсе и где вызывается устаревший API. Результаты
+//! агрегируются по группам модулей (по умолчанию — верхний каталог метаданных).
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::example_mining::{constructor_regex, method_call_regex, strip_comment, ReceiverTypes};
+use crate::data::{RawTypeData, TypeSource};
+
+/// Маркеры устаревшего API в документации синтакс-помощника («для
+/// совместимости» само по себе встречается и в описаниях действующих методов)
+const DEPRECATED_MARKERS: &[&str] = &[
+    "не рекомендуется",
+    "устарел",
+    "сохранен для совместимости",
+    "сохранён для совместимости",
+    "оставлен для совместимости",
+    "deprecated",
+];
+
+/// Сколько записей выводить в разделах «чаще всего» по умолчанию
+pub const DEFAULT_TOP_N: usize = 20;
+
+/// Функция, определяющая группу (подсистему) модуля по относительному пути
+pub type GroupResolver = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Отбор модулей для отчёта (например, только одна подсистема)
+pub type ModuleFilter = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Частота использования типа или метода
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageEntry {
+    pub name: String,
+    /// Типы, которым принадлежит метод (для типов — пусто)
+    pub owners: Vec<String>,
+    pub count: usize,
+    /// В скольких модулях встречается
+    pub files: usize,
+}
+
+/// Вызов устаревшего метода
+#[derive(Debug, Clone, Serialize)]
+pub struct DeprecatedUsage {
+    pub method: String,
+    pub owners: Vec<String>,
+    pub file_path: String,
+    pub line: usize,
+    pub column: usize,
+    pub group: String,
+}
+
+/// Использование внутри группы модулей
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupUsage {
+    pub group: String,
+    pub files: usize,
+    pub total_usages: usize,
+    pub deprecated_usages: usize,
+    /// Самые используемые типы и методы группы
+    pub top: Vec<UsageEntry>,
+}
+
+/// Итоговый отчёт
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    pub files_scanned: usize,
+    pub most_used_types: Vec<UsageEntry>,
+    pub most_used_methods: Vec<UsageEntry>,
+    pub unused_types: Vec<String>,
+    pub unused_methods: Vec<String>,
+    pub deprecated_usages: Vec<DeprecatedUsage>,
+    pub groups: Vec<GroupUsage>,
+}
+
+#[derive(Default)]
+struct Counter {
+    count: usize,
+    files: HashSet<String>,
+}
+
+#[derive(Default)]
+struct GroupCounter {
+    files: HashSet<String>,
+    members: HashMap<String, Counter>,
+    deprecated: usize,
+}
+
+/// Информация о методе из каталога
+struct MethodInfo {
+    display_name: String,
+    owners: Vec<String>,
+}
+
+/// Сборщик статистики использования
+pub struct UsageCollector {
+    /// Имя типа (нижний регистр) → отображаемое имя
+    known_types: HashMap<String, String>,
+    /// Имя метода (нижний регистр) → описание
+    known_methods: HashMap<String, MethodInfo>,
+    /// Устаревшие методы платформы: (тип-владелец, метод) в нижнем регистре.
+    /// Одноимённый метод другого типа устаревшим не считается
+    deprecated_methods: HashSet<(String, String)>,
+    type_usage: HashMap<String, Counter>,
+    method_usage: HashMap<String, Counter>,
+    groups: BTreeMap<String, GroupCounter>,
+    deprecated_usages: Vec<DeprecatedUsage>,
+    group_resolver: GroupResolver,
+    module_filter: Option<ModuleFilter>,
+    files_scanned: usize,
+}
+
+/// Группа по умолчанию: верхний каталог выгрузки (CommonModules, Catalogs, ...)
+pub fn default_group(file_path: &str) -> String {
+    let normalized = file_path.replace('\\', "/");
+    match normalized
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()[..]
+    {
+        [] | [_] => "(корень)".to_string(),
+        [first, ..] => first.to_string(),
+    }
+}
+
+/// Проверить, помечен ли метод в документации как устаревший
+pub fn is_deprecated_documentation(documentation: &str) -> bool {
+    let lower = documentation.to_lowercase();
+    DEPRECATED_MARKERS
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
+
+impl UsageCollector {
+    /// Создать сборщик по каталогу известных типов
+    pub fn new(catalog: &[RawTypeData]) -> Self {
+        let mut known_types = HashMap::new();
+        let mut known_methods: HashMap<String, MethodInfo> = HashMap::new();
+        let mut deprecated_methods = HashSet::new();
+
+        for raw in catalog {
+            for name in [&raw.russian_name, &raw.english_name] {
+                if !name.is_empty() {
+                    known_types.insert(name.to_lowercase(), raw.russian_name.clone());
+                }
+            }
+            for method in &raw.methods {
+                let info = known_methods
+                    .entry(method.name.to_lowercase())
+                    .or_insert_with(|| MethodInfo {
+                        display_name: method.name.clone(),
+                        owners: Vec::new(),
+                    });
+                if !info.owners.contains(&raw.russian_name) {
+                    info.owners.push(raw.russian_name.clone());
+                }
+                // Только платформенный API может быть «устаревшим»
+                if matches!(raw.source, TypeSource::Platform { .. })
+                    && is_deprecated_documentation(&method.documentation)
+                {
+                    deprecated_methods
+                        .insert((raw.russian_name.to_lowercase(), method.name.to_lowercase()));
+                }
+            }
+        }
+
+        Self {
+            known_types,
+            known_methods,
+            deprecated_methods,
+            type_usage: HashMap::new(),
+            method_usage: HashMap::new(),
+            groups: BTreeMap::new(),
+            deprecated_usages: Vec::new(),
+            group_resolver: Box::new(default_group),
+            module_filter: None,
+            files_scanned: 0,
+        }
+    }
+
+    /// Задать способ группировки модулей (например, по подсистемам)
+    pub fn with_group_resolver(mut self, resolver: GroupResolver) -> Self {
+        self.group_resolver = resolver;
+        self
+    }
+
+    /// Учитывать только модули, прошедшие фильтр
+    pub fn with_module_filter(mut self, filter: ModuleFilter) -> Self {
+        self.module_filter = Some(filter);
+        self
+    }
+
+    /// Просканировать все модули каталога проекта
+    pub fn add_directory(&mut self, root: &Path) -> Result<()> {
+        for entry in WalkDir::new(root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let path = entry.path();
+            if !crate::core::fs_utils::is_bsl_file(path) {
+                continue;
+            }
+            let Ok(text) = crate::core::fs_utils::read_bsl_file(path) else {
+                continue;
+            };
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            self.add_source(&relative.to_string_lossy().replace('\\', "/"), &text);
+        }
+        Ok(())
+    }
+
+    /// Учесть один модуль
+    pub fn add_source(&mut self, file_path: &str, text: &str) {
+        if let Some(filter) = &self.module_filter {
+            if !filter(file_path) {
+                return;
+            }
+        }
+        self.files_scanned += 1;
+        let group = (self.group_resolver)(file_path);
+        self.groups
+            .entry(group.clone())
+            .or_default()
+            .files
+            .insert(file_path.to_string());
+
+        let mut receivers = ReceiverTypes::default();
+        for (idx, raw_line) in text.lines().enumerate() {
+            let code = strip_comment(raw_line);
+            if code.trim().is_empty() {
+                continue;
+            }
+            receivers.observe(code);
+
+            for caps in method_call_regex().captures_iter(code) {
+                let name = caps.get(2).unwrap();
+                let key = name.as_str().to_lowercase();
+                let Some(info) = self.known_methods.get(&key) else {
+                    continue;
+                };
+                let display_name = info.display_name.clone();
+                // Устаревший метод определяется по типу получателя: без
+                // известного типа вызов не помечается
+                let owner = caps
+                    .get(1)
+                    .and_then(|receiver| receivers.type_of(receiver.as_str()))
+                    .and_then(|type_name| self.known_types.get(type_name));
+                if let Some(owner) = owner.filter(|owner| {
+                    self.deprecated_methods
+                        .contains(&(owner.to_lowercase(), key.clone()))
+                }) {
+                    self.deprecated_usages.push(DeprecatedUsage {
+                        method: display_name.clone(),
+                        owners: vec![owner.clone()],
+                        file_path: file_path.to_string(),
+                        line: idx + 1,
+                        column: code[..name.start()].chars().count() + 1,
+                        group: group.clone(),
+                    });
+                    self.groups.entry(group.clone()).or_default().deprecated += 1;
+                }
+                bump(&mut self.method_usage, &key, file_path);
+                bump(
+                    &mut self.groups.entry(group.clone()).or_default().members,
+                    &display_name,
+                    file_path,
+                );
+            }
+
+            for caps in constructor_regex().captures_iter(code) {
+                let key = caps[1].to_lowercase();
+                let Some(display_name) = self.known_types.get(&key).cloned() else {
+                    continue;
+                };
+                bump(
+                    &mut self.type_usage,
+                    &display_name.to_lowercase(),
+                    file_path,
+                );
+                bump(
+                    &mut self.groups.entry(group.clone()).or_default().members,
+                    &display_name,
+                    file_path,
+                );
+            }
+        }
+    }
+
+    /// Сформировать отчёт
+    pub fn finish(self, top_n: usize) -> UsageReport {
+        // Использование учитывается по русскому имени, под каким бы именем ни был вызван тип
+        let mut unused_types: Vec<String> = self
+            .known_types
+            .values()
+            .filter(|display| !self.type_usage.contains_key(&display.to_lowercase()))
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        unused_types.sort();
+
+        let mut unused_methods: Vec<String> = self
+            .known_methods
+            .iter()
+            .filter(|(key, _)| !self.method_usage.contains_key(*key))
+            .map(|(_, info)| info.display_name.clone())
+            .collect();
+        unused_methods.sort();
+
+        let most_used_types = top_entries(
+            self.type_usage.iter().map(|(key, counter)| UsageEntry {
+                name: self
+                    .known_types
+                    .get(key)
+                    .cloned()
+                    .unwrap_or_else(|| key.clone()),
+                owners: Vec::new(),
+                count: counter.count,
+                files: counter.files.len(),
+            }),
+            top_n,
+        );
+        let most_used_methods = top_entries(
+            self.method_usage.iter().map(|(key, counter)| {
+                let info = &self.known_methods[key];
+                UsageEntry {
+                    name: info.display_name.clone(),
+                    owners: info.owners.clone(),
+                    count: counter.count,
+                    files: counter.files.len(),
+                }
+            }),
+            top_n,
+        );
+
+        let groups = self
+            .groups
+            .into_iter()
+            .map(|(group, counter)| GroupUsage {
+                group,
+                files: counter.files.len(),
+                total_usages: counter.members.values().map(|c| c.count).sum(),
+                deprecated_usages: counter.deprecated,
+                top: top_entries(
+                    counter.members.into_iter().map(|(name, c)| UsageEntry {
+                        name,
+                        owners: Vec::new(),
+                        count: c.count,
+                        files: c.files.len(),
+                    }),
+                    top_n,
+                ),
+            })
+            .collect();
+
+        UsageReport {
+            files_scanned: self.files_scanned,
+            most_used_types,
+            most_used_methods,
+            unused_types,
+            unused_methods,
+            deprecated_usages: self.deprecated_usages,
+            groups,
+        }
+    }
+}
+
+fn bump(map: &mut HashMap<String, Counter>, key: &str, file_path: &str) {
+    let counter = map.entry(key.to_string()).or_default();
+    counter.count += 1;
+    counter.files.insert(file_path.to_string());
+}
+
+fn top_entries(entries: impl Iterator<Item = UsageEntry>, top_n: usize) -> Vec<UsageEntry> {
+    let mut entries: Vec<UsageEntry> = entries.collect();
+    entries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    entries.truncate(top_n);
+    entries
+}
+
+impl UsageReport {
+    /// Текстовое представление для CLI
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!(
+            "📊 Отчёт об использовании типов ({} модулей)\n",
+            self.files_scanned
+        ));
+
+        out.push_str("\n🔝 Самые используемые типы:\n");
+        for entry in &self.most_used_types {
+            out.push_str(&format!(
+                "  {:<40} {:>6} (модулей: {})\n",
+                entry.name, entry.count, entry.files
+            ));
+        }
+
+        out.push_str("\n🔝 Самые используемые методы:\n");
+        for entry in &self.most_used_methods {
+            out.push_str(&format!(
+                "  {:<40} {:>6} (модулей: {}) [{}]\n",
+                entry.name,
+                entry.count,
+                entry.files,
+                entry.owners.join(", ")
+            ));
+        }
+
+        out.push_str(&format!(
+            "\n💤 Не используются: {} типов, {} методов\n",
+            self.unused_types.len(),
+            self.unused_methods.len()
+        ));
+
+        if !self.deprecated_usages.is_empty() {
+            out.push_str(&format!(
+                "\n⚠️ Вызовы устаревшего API: {}\n",
+                self.deprecated_usages.len()
+            ));
+            for usage in &self.deprecated_usages {
+                out.push_str(&format!(
+                    "  {}:{}:{} {}\n",
+                    usage.file_path, usage.line, usage.column, usage.method
+                ));
+            }
+        }
+
+        out.push_str("\n📁 По группам:\n");
+        for group in &self.groups {
+            out.push_str(&format!(
+                "  {:<40} модулей: {:>4}, использований: {:>6}, устаревших: {}\n",
+                group.group, group.files, group.total_usages, group.deprecated_usages
+            ));
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::RawMethodData;
+
+    fn method(name: &str, documentation: &str) -> RawMethodData {
+        RawMethodData {
+            documentation: documentation.to_string(),
+            ..RawMethodData::test_method(name)
+        }
+    }
+
+    fn platform_type(name: &str, methods: Vec<RawMethodData>) -> RawTypeData {
+        let platform = TypeSource::Platform {
+            version: "8.3".to_string(),
+        };
+        RawTypeData {
+            methods,
+            ..RawTypeData::test_type(name, platform)
+        }
+    }
+
+    #[test]
+    fn test_usage_report() {
+        let catalog = vec![
+            platform_type(
+                "Массив",
+                vec![
+                    method("Добавить", "Добавляет элемент"),
+                    method("Очистить", ""),
+                ],
+            ),
+            platform_type("Запрос", vec![method("Выполнить", "Выполняет запрос")]),
+            platform_type(
+                "ПостроительЗапроса",
+                vec![method("Выполнить", "Не рекомендуется для использования")],
+            ),
+            platform_type("ТаблицаЗначений", vec![]),
+        ];
+
+        let mut collector = UsageCollector::new(&catalog);
+        collector.add_source(
+            "CommonModules/Общий/Ext/Module.bsl",
+            "Список = Новый Массив;\nСписок.Добавить(1);\nСписок.Добавить(2); // Список.Очистить()\nСписок.Добавить(3);\n",
+        );
+        collector.add_source(
+            "Catalogs/Товары/Ext/ObjectModule.bsl",
+            "Запрос = Новый Запрос;\nРезультат = Запрос.Выполнить();\n\
+             Построитель = Новый ПостроительЗапроса;\nПостроитель.Выполнить();\n",
+        );
+        let report = collector.finish(DEFAULT_TOP_N);
+
+        assert_eq!(report.files_scanned, 2);
+        assert_eq!(report.most_used_methods[0].name, "Добавить");
+        assert_eq!(report.most_used_methods[0].count, 3);
+        assert_eq!(report.unused_methods, vec!["Очистить".to_string()]);
+        assert_eq!(report.unused_types, vec!["ТаблицаЗначений".to_string()]);
+
+        // Запрос.Выполнить не устарел, хотя одноимённый метод построителя устарел
+        assert_eq!(report.deprecated_usages.len(), 1);
+        assert_eq!(report.deprecated_usages[0].line, 4);
+        assert_eq!(report.deprecated_usages[0].owners, ["ПостроительЗапроса"]);
+        assert_eq!(report.deprecated_usages[0].group, "Catalogs");
+        assert!(!is_deprecated_documentation(
+            "Используется для совместимости с внешними системами"
+        ));
+        assert!(is_deprecated_documentation(
+            "Метод сохранен для совместимости"
+        ));
+
+        let groups: Vec<_> = report.groups.iter().map(|g| g.group.as_str()).collect();
+        assert_eq!(groups, vec!["Catalogs", "CommonModules"]);
+    }
+}
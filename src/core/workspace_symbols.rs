@@ -0,0 +1,346 @@
+//! Символы рабочей области (`workspace/symbol`, Ctrl+T)
+//!
+//! Процедуры и функции модулей проекта собираются в `ProjectSymbolIndex`
+//! фоновым обходом рабочей области; открытые документы подменяют версии с
+//! диска. Объекты конфигурации (из единого репозитория типов) и типы
+//! платформы (из справки синтакс-помощника) ищутся по имени в
+//! `DocumentationSearchEngine`. Кандидаты обоих источников ранжируются одной
+//! оценкой совпадения имени (`quick_open::match_score`) с надбавкой за вид:
+//! методы проекта выше объектов конфигурации, объекты выше типов платформы.
+//! Символ без файла, который можно открыть в редакторе, не возвращается.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::core::example_mining::strip_comment;
+use crate::core::module_structure::method_header_regex;
+use crate::core::quick_open::match_score;
+use crate::documentation::core::hierarchy::{
+    DocumentationSourceType, TypeDocumentationFull, UiMetadata,
+};
+use crate::documentation::core::ids::type_id;
+use crate::documentation::search::SearchResultItem;
+use crate::domain::types::{ConcreteType, ResolutionResult, TypeResolution};
+use crate::unified::data::{RawTypeData, TypeSource};
+
+/// Провайдер документов поискового движка для типов единого репозитория
+pub const REPOSITORY_PROVIDER_ID: &str = "repository";
+
+/// Лимит символов в ответе по умолчанию
+pub const DEFAULT_WORKSPACE_SYMBOL_LIMIT: usize = 100;
+
+/// Вид символа рабочей области
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceSymbolKind {
+    Procedure,
+    Function,
+    ConfigurationObject,
+    PlatformType,
+}
+
+impl WorkspaceSymbolKind {
+    /// Надбавка к оценке при равном совпадении имени
+    fn bonus(&self) -> u32 {
+        match self {
+            WorkspaceSymbolKind::Procedure | WorkspaceSymbolKind::Function => 30,
+            WorkspaceSymbolKind::ConfigurationObject => 20,
+            WorkspaceSymbolKind::PlatformType => 0,
+        }
+    }
+}
+
+/// Символ рабочей области
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkspaceSymbol {
+    pub name: String,
+    pub kind: WorkspaceSymbolKind,
+    /// Модуль метода или раздел справки типа
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+    /// Модуль, файл описания объекта метаданных или страница справки
+    pub file: String,
+    /// Строка и колонка имени (с 0)
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Процедуры и функции модуля; `container` — подпись модуля в выдаче
+pub fn module_procedures(
+    file_path: &str,
+    container: Option<&str>,
+    text: &str,
+) -> Vec<WorkspaceSymbol> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let caps = method_header_regex().captures(strip_comment(line))?;
+            let name = caps.get(2)?;
+            let keyword = caps[1].to_lowercase();
+            let kind = if keyword == "функция" || keyword == "function" {
+                WorkspaceSymbolKind::Function
+            } else {
+                WorkspaceSymbolKind::Procedure
+            };
+            Some(WorkspaceSymbol {
+                name: name.as_str().to_string(),
+                kind,
+                container: container.map(str::to_string),
+                file: file_path.to_string(),
+                line: idx as u32,
+                column: line[..name.start()].encode_utf16().count() as u32,
+            })
+        })
+        .collect()
+}
+
+/// Процедуры и функции модулей проекта по файлам
+#[derive(Debug, Clone, Default)]
+pub struct ProjectSymbolIndex {
+    root: Option<PathBuf>,
+    files: HashMap<String, Vec<WorkspaceSymbol>>,
+}
+
+impl ProjectSymbolIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Собрать индекс из каталога проекта
+    pub fn scan_directory(root: &Path) -> Result<Self> {
+        let mut index = Self {
+            root: Some(root.to_path_buf()),
+            files: HashMap::new(),
+        };
+        for entry in WalkDir::new(root)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let path = entry.path();
+            if !crate::core::fs_utils::is_bsl_file(path) {
+                continue;
+            }
+            let Ok(text) = crate::core::fs_utils::read_bsl_file(path) else {
+                continue;
+            };
+            index.update_file(&path.to_string_lossy(), &text);
+        }
+        Ok(index)
+    }
+
+    /// Число символов в индексе
+    pub fn len(&self) -> usize {
+        self.files.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.values().all(Vec::is_empty)
+    }
+
+    /// Подпись модуля: путь относительно корня проекта
+    pub fn container(&self, file_path: &str) -> String {
+        let path = Path::new(file_path);
+        self.root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Заменить символы модуля символами из `text`
+    pub fn update_file(&mut self, file_path: &str, text: &str) {
+        let container = self.container(file_path);
+        let symbols = module_procedures(file_path, Some(&container), text);
+        self.files.insert(file_path.to_string(), symbols);
+    }
+
+    /// Убрать символы модуля (файл удалён)
+    pub fn remove_file(&mut self, file_path: &str) {
+        self.files.remove(file_path);
+    }
+
+    /// Все символы, кроме символов модулей из `skip_files`
+    pub fn symbols<'a>(
+        &'a self,
+        skip_files: &'a [&str],
+    ) -> impl Iterator<Item = &'a WorkspaceSymbol> + 'a {
+        self.files
+            .iter()
+            .filter(move |(file, _)| !skip_files.contains(&file.as_str()))
+            .flat_map(|(_, symbols)| symbols)
+    }
+}
+
+/// Оценка символа проекта по запросу с надбавкой за вид
+pub fn symbol_score(query: &str, symbol: &WorkspaceSymbol) -> Option<u32> {
+    match_score(query, &symbol.name).map(|score| score + symbol.kind.bonus())
+}
+
+/// Файл источника документа, если он есть на диске. Путь страницы
+/// синтакс-помощника хранится без корня файловой системы (`catalog_path`).
+fn existing_file(source_file: &str) -> Option<String> {
+    let path = Path::new(source_file);
+    if path.is_file() {
+        return Some(source_file.to_string());
+    }
+    let rooted = Path::new("/").join(path);
+    rooted
+        .is_file()
+        .then(|| rooted.to_string_lossy().to_string())
+}
+
+/// Символ и оценка для найденного по имени документа справки. Глобальные
+/// функции и документы без файла на диске пропускаются.
+pub fn documentation_symbol(item: &SearchResultItem) -> Option<(u32, WorkspaceSymbol)> {
+    let kind = match item.source_type {
+        DocumentationSourceType::Platform { .. } => WorkspaceSymbolKind::PlatformType,
+        DocumentationSourceType::Configuration { .. }
+        | DocumentationSourceType::UserDefined { .. } => WorkspaceSymbolKind::ConfigurationObject,
+        DocumentationSourceType::GlobalFunction => return None,
+    };
+    let file = existing_file(item.source_file.as_deref()?)?;
+    let symbol = WorkspaceSymbol {
+        name: item.display_name.clone(),
+        kind,
+        container: (!item.category.is_empty()).then(|| item.category.replace('/', " / ")),
+        file,
+        line: 0,
+        column: 0,
+    };
+    Some((item.relevance_score as u32 + kind.bonus(), symbol))
+}
+
+/// До `limit` символов, лучшие первыми
+pub fn rank_symbols(mut scored: Vec<(u32, WorkspaceSymbol)>, limit: usize) -> Vec<WorkspaceSymbol> {
+    scored.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| a.1.name.len().cmp(&b.1.name.len()))
+            .then_with(|| a.1.name.cmp(&b.1.name))
+            .then_with(|| a.1.file.cmp(&b.1.file))
+    });
+    scored.truncate(limit);
+    scored.into_iter().map(|(_, symbol)| symbol).collect()
+}
+
+/// Документ поискового движка для объекта конфигурации из единого
+/// репозитория. Типы платформы и объекты без файла описания пропускаются:
+/// платформа индексируется из справки, где у типа есть страница.
+pub fn repository_documentation(raw: &RawTypeData) -> Option<TypeDocumentationFull> {
+    if !matches!(raw.source, TypeSource::Configuration { .. }) {
+        return None;
+    }
+    let file = raw.parse_metadata.file_path.as_str();
+    if file.is_empty() || file == "unknown" {
+        return None;
+    }
+    let resolution = TypeResolution::from_raw_data(raw);
+    let ResolutionResult::Concrete(ConcreteType::Configuration(object_type)) = &resolution.result
+    else {
+        return None;
+    };
+
+    let mut qualified_name = raw.category_path.clone();
+    qualified_name.push(raw.russian_name.clone());
+    let mut hierarchy_path = vec!["Конфигурация".to_string()];
+    hierarchy_path.extend(raw.category_path.iter().cloned());
+
+    Some(TypeDocumentationFull {
+        id: type_id("configuration", &qualified_name.join(".")),
+        russian_name: raw.russian_name.clone(),
+        english_name: raw.english_name.clone(),
+        aliases: Vec::new(),
+        source_type: DocumentationSourceType::Configuration {
+            object_type: object_type.clone(),
+        },
+        hierarchy_path: hierarchy_path.clone(),
+        available_facets: resolution.available_facets.clone(),
+        active_facet: resolution.active_facet,
+        type_resolution: resolution,
+        methods: Vec::new(),
+        properties: Vec::new(),
+        constructors: Vec::new(),
+        description: raw.documentation.clone(),
+        examples: Vec::new(),
+        availability: Vec::new(),
+        since_version: String::new(),
+        notes: Vec::new(),
+        related_types: Vec::new(),
+        parent_type: None,
+        child_types: Vec::new(),
+        source_file: Some(file.to_string()),
+        ui_metadata: UiMetadata {
+            icon: String::new(),
+            color: String::new(),
+            tree_path: hierarchy_path,
+            expanded: false,
+            sort_weight: 0,
+            css_classes: vec!["config-type".to_string()],
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = "Процедура Заполнить() Экспорт
+КонецПроцедуры
+
+// Функция ЗаполнитьСтарое()
+Асинх Функция ЗаполнитьТаблицу(Таблица)
+КонецФункции
+";
+
+    #[test]
+    fn test_module_procedures_and_ranking() {
+        let symbols = module_procedures("/p/Module.bsl", Some("Module.bsl"), MODULE);
+        let names: Vec<(&str, WorkspaceSymbolKind, u32, u32)> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind, s.line, s.column))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("Заполнить", WorkspaceSymbolKind::Procedure, 0, 10),
+                ("ЗаполнитьТаблицу", WorkspaceSymbolKind::Function, 4, 14),
+            ]
+        );
+
+        let mut index = ProjectSymbolIndex::new();
+        index.update_file("/p/Module.bsl", MODULE);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.symbols(&["/p/Module.bsl"]).count(), 0);
+
+        // Объект конфигурации с тем же совпадением имени ниже метода проекта
+        let object = WorkspaceSymbol {
+            name: "ЗаполнитьТаблицу".to_string(),
+            kind: WorkspaceSymbolKind::ConfigurationObject,
+            container: None,
+            file: "/p/Catalogs/Заполнение.xml".to_string(),
+            line: 0,
+            column: 0,
+        };
+        let mut scored: Vec<(u32, WorkspaceSymbol)> = index
+            .symbols(&[])
+            .filter_map(|s| Some((symbol_score("зт", s)?, s.clone())))
+            .collect();
+        scored.push((symbol_score("зт", &object).unwrap(), object));
+        let kinds: Vec<WorkspaceSymbolKind> =
+            rank_symbols(scored, 10).iter().map(|s| s.kind).collect();
+        // Первые буквы слов выше подпоследовательности в `Заполнить`
+        assert_eq!(
+            kinds,
+            [
+                WorkspaceSymbolKind::Function,
+                WorkspaceSymbolKind::ConfigurationObject,
+                WorkspaceSymbolKind::Procedure,
+            ]
+        );
+        assert!(index.symbols(&[]).all(|s| symbol_score("ъ", s).is_none()));
+    }
+}
@@ -0,0 +1,300 @@
+//! Команды конфигурации и модули их обработчиков
+//!
+//! Общие команды описаны в `CommonCommands/<Имя>.xml` (EDT:
+//! `src/CommonCommands/<Имя>/<Имя>.mdo`), команды объектов — в описании
+//! владельца (`ChildObjects/Command` конфигуратора, `commands` EDT).
+//! Обработчик `ОбработкаКоманды` лежит в модуле команды:
+//! `CommonCommands/<Имя>/Ext/CommandModule.bsl` и
+//! `<Вид>/<Объект>/Commands/<Команда>/Ext/CommandModule.bsl` (в EDT — без
+//! `Ext`). Индекс связывает описание команды с её модулем для проверки
+//! обработчиков и перехода от `Метаданные.ОбщиеКоманды.Имя` к обработчику.
+
+use anyhow::{Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Виды объектов с командами: каталог выгрузки, вид и имя коллекции в
+/// `Метаданные`
+const COMMAND_OWNER_KINDS: &[(&str, &str, &str)] = &[
+    ("Catalogs", "Catalog", "Справочники"),
+    ("Documents", "Document", "Документы"),
+    ("DocumentJournals", "DocumentJournal", "ЖурналыДокументов"),
+    ("Enums", "Enum", "Перечисления"),
+    ("DataProcessors", "DataProcessor", "Обработки"),
+    ("Reports", "Report", "Отчеты"),
+    ("InformationRegisters", "InformationRegister", "РегистрыСведений"),
+    ("AccumulationRegisters", "AccumulationRegister", "РегистрыНакопления"),
+    ("AccountingRegisters", "AccountingRegister", "РегистрыБухгалтерии"),
+    ("CalculationRegisters", "CalculationRegister", "РегистрыРасчета"),
+    ("ChartsOfAccounts", "ChartOfAccounts", "ПланыСчетов"),
+    ("ChartsOfCharacteristicTypes", "ChartOfCharacteristicTypes", "ПланыВидовХарактеристик"),
+    ("ChartsOfCalculationTypes", "ChartOfCalculationTypes", "ПланыВидовРасчета"),
+    ("BusinessProcesses", "BusinessProcess", "БизнесПроцессы"),
+    ("Tasks", "Task", "Задачи"),
+    ("ExchangePlans", "ExchangePlan", "ПланыОбмена"),
+    ("FilterCriteria", "FilterCriterion", "КритерииОтбора"),
+];
+
+const COMMON_COMMANDS_DIR: &str = "CommonCommands";
+const MODULE_FILE: &str = "CommandModule.bsl";
+
+/// Команда конфигурации
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandInfo {
+    pub name: String,
+    /// Владелец: `Catalog.Товары`; `None` — общая команда
+    pub owner: Option<String>,
+    /// Модуль команды; `None` — модуля в выгрузке нет
+    pub module_path: Option<PathBuf>,
+}
+
+impl CommandInfo {
+    /// Полное имя: `CommonCommand.Печать`, `Catalog.Товары.Command.Печать`
+    pub fn full_name(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("{}.Command.{}", owner, self.name),
+            None => format!("CommonCommand.{}", self.name),
+        }
+    }
+}
+
+/// Индекс команд конфигурации
+#[derive(Debug, Clone, Default)]
+pub struct CommandIndex {
+    commands: Vec<CommandInfo>,
+}
+
+fn read_dir_sorted(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Не удалось прочитать {}", dir.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    entries.sort();
+    Ok(entries)
+}
+
+/// Модуль команды в каталоге `dir` (`Ext/CommandModule.bsl` или
+/// `CommandModule.bsl`)
+fn module_in(dir: &Path) -> Option<PathBuf> {
+    [dir.join("Ext").join(MODULE_FILE), dir.join(MODULE_FILE)]
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+/// Описание объекта и его каталог: `<Имя>.xml` конфигуратора (каталог
+/// `<Имя>` рядом) или `<Имя>/<Имя>.mdo` EDT
+fn object_files(entry: &Path) -> Option<(String, PathBuf, PathBuf)> {
+    if entry.is_file() {
+        if entry.extension()? != "xml" {
+            return None;
+        }
+        let name = entry.file_stem()?.to_string_lossy().to_string();
+        let dir = entry.with_file_name(&name);
+        return Some((name, entry.to_path_buf(), dir));
+    }
+    let name = entry.file_name()?.to_string_lossy().to_string();
+    let mdo = entry.join(format!("{}.mdo", name));
+    mdo.is_file().then(|| (name, mdo, entry.to_path_buf()))
+}
+
+fn read_object(file: &Path) -> Result<String> {
+    std::fs::read_to_string(file)
+        .with_context(|| format!("Не удалось прочитать {}", file.display()))
+}
+
+fn metadata_command_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)^(?:Метаданные|Metadata)\.(?:",
+            r"(?:ОбщиеКоманды|CommonCommands)\.([\p{L}_][\p{L}\p{N}_]*)|",
+            r"([\p{L}_]+)\.([\p{L}_][\p{L}\p{N}_]*)\.",
+            r"(?:Команды|Commands)\.([\p{L}_][\p{L}\p{N}_]*))$",
+        ))
+        .unwrap()
+    })
+}
+
+impl CommandIndex {
+    /// Загрузить общие команды и команды объектов из каталога выгрузки или
+    /// проекта EDT; пустой индекс, если команд нет
+    pub fn load(config_path: &Path) -> Result<Self> {
+        let mut commands = Vec::new();
+        for root in [config_path.to_path_buf(), config_path.join("src")] {
+            let common = root.join(COMMON_COMMANDS_DIR);
+            if common.is_dir() {
+                for entry in read_dir_sorted(&common)? {
+                    let Some((name, _, dir)) = object_files(&entry) else {
+                        continue;
+                    };
+                    commands.push(CommandInfo {
+                        module_path: module_in(&dir),
+                        name,
+                        owner: None,
+                    });
+                }
+            }
+            for (kind_dir, kind, _) in COMMAND_OWNER_KINDS {
+                let kind_dir = root.join(kind_dir);
+                if !kind_dir.is_dir() {
+                    continue;
+                }
+                for entry in read_dir_sorted(&kind_dir)? {
+                    let Some((object, file, dir)) = object_files(&entry) else {
+                        continue;
+                    };
+                    let declared = parse_declared_commands(&read_object(&file)?)
+                        .with_context(|| format!("Описание объекта {}", file.display()))?;
+                    for name in declared {
+                        commands.push(CommandInfo {
+                            module_path: module_in(&dir.join("Commands").join(&name)),
+                            name,
+                            owner: Some(format!("{}.{}", kind, object)),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(Self::from_commands(commands))
+    }
+
+    pub fn from_commands(commands: Vec<CommandInfo>) -> Self {
+        Self { commands }
+    }
+
+    pub fn commands(&self) -> &[CommandInfo] {
+        &self.commands
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Команда по полному имени (`CommonCommand.Печать`) без учёта регистра
+    pub fn find(&self, full_name: &str) -> Option<&CommandInfo> {
+        let full_name = full_name.trim().to_lowercase();
+        self.commands
+            .iter()
+            .find(|command| command.full_name().to_lowercase() == full_name)
+    }
+
+    /// Команда, на которую ссылается выражение
+    /// `Метаданные.ОбщиеКоманды.Имя` или `Метаданные.Справочники.Товары.Команды.Имя`
+    pub fn find_reference(&self, expression: &str) -> Option<&CommandInfo> {
+        let expression: String = expression.split_whitespace().collect();
+        let caps = metadata_command_regex().captures(&expression)?;
+        if let Some(name) = caps.get(1) {
+            return self.find(&format!("CommonCommand.{}", name.as_str()));
+        }
+        let collection = caps[2].to_lowercase();
+        let (_, kind, _) = COMMAND_OWNER_KINDS.iter().find(|(dir, _, russian)| {
+            dir.to_lowercase() == collection || russian.to_lowercase() == collection
+        })?;
+        self.find(&format!("{}.{}.Command.{}", kind, &caps[3], &caps[4]))
+    }
+
+    /// Команда, модулем которой является файл
+    pub fn for_module(&self, module_path: &Path) -> Option<&CommandInfo> {
+        self.commands
+            .iter()
+            .find(|command| command.module_path.as_deref() == Some(module_path))
+    }
+}
+
+/// Имена команд из описания объекта: `ChildObjects/Command/Properties/Name`
+/// конфигуратора или `commands/name` EDT
+pub fn parse_declared_commands(content: &str) -> Result<Vec<String>> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut names = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                path.push(String::from_utf8_lossy(e.local_name().as_ref()).to_string());
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                match tail.as_slice() {
+                    [_, _, "ChildObjects", "Command", "Properties", "Name"]
+                    | [_, "commands", "name"] => names.push(text),
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Ошибка XML описания на позиции {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<MetaDataObject xmlns="http://v8.1c.ru/8.3/MDClasses">
+  <Catalog uuid="00000000-0000-0000-0000-000000000001">
+    <Properties><Name>Товары</Name></Properties>
+    <ChildObjects>
+      <Attribute><Properties><Name>Артикул</Name></Properties></Attribute>
+      <Command uuid="00000000-0000-0000-0000-000000000002">
+        <Properties><Name>ПечатьЭтикеток</Name></Properties>
+      </Command>
+    </ChildObjects>
+  </Catalog>
+</MetaDataObject>"#;
+
+    #[test]
+    fn test_load_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let common = root.join("CommonCommands");
+        std::fs::create_dir_all(common.join("Обновить").join("Ext")).unwrap();
+        std::fs::write(common.join("Обновить.xml"), "<MetaDataObject/>").unwrap();
+        std::fs::write(common.join("Обновить/Ext/CommandModule.bsl"), "").unwrap();
+        let catalogs = root.join("Catalogs");
+        std::fs::create_dir_all(&catalogs).unwrap();
+        std::fs::write(catalogs.join("Товары.xml"), CATALOG_XML).unwrap();
+
+        let index = CommandIndex::load(root).unwrap();
+        assert_eq!(index.len(), 2);
+        let refresh = index.find_reference("Метаданные.ОбщиеКоманды.обновить").unwrap();
+        assert_eq!(refresh.full_name(), "CommonCommand.Обновить");
+        assert_eq!(
+            index.for_module(refresh.module_path.as_deref().unwrap()),
+            Some(refresh)
+        );
+        let print = index
+            .find_reference("Метаданные.Справочники.Товары.Команды.ПечатьЭтикеток")
+            .unwrap();
+        assert_eq!(print.owner.as_deref(), Some("Catalog.Товары"));
+        // Модуль команды в выгрузке отсутствует
+        assert!(print.module_path.is_none());
+    }
+}
@@ -0,0 +1,287 @@
+//! Общие реквизиты конфигурации
+//!
+//! Общий реквизит (`CommonAttributes/<Имя>.xml` конфигуратора или
+//! `src/CommonAttributes/<Имя>/<Имя>.mdo` EDT) добавляется к объектам из
+//! своего состава (`Content`): у каждого объекта указано, используется ли
+//! реквизит (`Use`, `DontUse` или `Auto`). `Auto` и объекты, которых нет в
+//! составе, следуют автоиспользованию (`AutoUse`). Такой реквизит доступен
+//! у объекта так же, как собственный: `Документ.Организация`.
+
+use anyhow::{Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::metadata_values::{object_file, value_type_name};
+
+const ATTRIBUTES_DIR: &str = "CommonAttributes";
+
+/// Виды объектов, к которым общий реквизит добавляется автоматически
+const DATA_OBJECT_TAGS: &[&str] = &[
+    "Catalog",
+    "Document",
+    "ChartOfCharacteristicTypes",
+    "ChartOfAccounts",
+    "ChartOfCalculationTypes",
+    "ExchangePlan",
+    "BusinessProcess",
+    "Task",
+    "InformationRegister",
+    "AccumulationRegister",
+    "AccountingRegister",
+    "CalculationRegister",
+];
+
+/// Использование реквизита объектом из состава
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AttributeUse {
+    Use,
+    DontUse,
+    /// По автоиспользованию реквизита
+    Auto,
+}
+
+impl AttributeUse {
+    fn parse(text: &str) -> Self {
+        match text {
+            "Use" => AttributeUse::Use,
+            "DontUse" => AttributeUse::DontUse,
+            _ => AttributeUse::Auto,
+        }
+    }
+}
+
+/// Объект из состава общего реквизита
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContentItem {
+    /// Объект метаданных: `Document.ЗаказКлиента`
+    pub metadata: String,
+    #[serde(rename = "use")]
+    pub usage: AttributeUse,
+}
+
+/// Общий реквизит
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommonAttribute {
+    pub name: String,
+    pub synonym: Option<String>,
+    /// Имена типов BSL; несколько — составной тип
+    pub types: Vec<String>,
+    /// Автоиспользование: реквизит есть у всех объектов данных, кроме
+    /// исключённых в составе
+    pub auto_use: bool,
+    pub content: Vec<ContentItem>,
+    pub file_path: PathBuf,
+}
+
+impl CommonAttribute {
+    /// Есть ли реквизит у объекта `name` вида `metadata_tag` (`Catalog`)
+    pub fn applies_to(&self, metadata_tag: &str, name: &str) -> bool {
+        let metadata = format!("{}.{}", metadata_tag, name).to_lowercase();
+        let usage = self
+            .content
+            .iter()
+            .find(|item| item.metadata.to_lowercase() == metadata)
+            .map_or(AttributeUse::Auto, |item| item.usage);
+        match usage {
+            AttributeUse::Use => true,
+            AttributeUse::DontUse => false,
+            AttributeUse::Auto => self.auto_use && DATA_OBJECT_TAGS.contains(&metadata_tag),
+        }
+    }
+
+    /// Описание для справки и автодополнения
+    pub fn description(&self) -> String {
+        match &self.synonym {
+            Some(synonym) => format!("Общий реквизит. {}", synonym),
+            None => "Общий реквизит".to_string(),
+        }
+    }
+}
+
+/// Разобрать описание общего реквизита. `None` — в описании нет имени.
+pub fn parse_common_attribute(content: &str, file_path: &Path) -> Result<Option<CommonAttribute>> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut attribute = CommonAttribute {
+        name: String::new(),
+        synonym: None,
+        types: Vec::new(),
+        auto_use: false,
+        content: Vec::new(),
+        file_path: file_path.to_path_buf(),
+    };
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut synonym_lang = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                path.push(String::from_utf8_lossy(e.local_name().as_ref()).to_string());
+                let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                if matches!(
+                    tail.as_slice(),
+                    [_, _, "Properties", "Content", "Item"] | [_, "content"]
+                ) {
+                    attribute.content.push(ContentItem {
+                        metadata: String::new(),
+                        usage: AttributeUse::Auto,
+                    });
+                }
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                // Конфигуратор: MetaDataObject/CommonAttribute/Properties/...;
+                // EDT: CommonAttribute/{name,synonym,type/types,content,autoUse}
+                match tail.as_slice() {
+                    [_, _, "Properties", "Name"] | [_, "name"] => attribute.name = text,
+                    [_, _, "Properties", "Synonym", "item", "lang"] | [_, "synonym", "key"] => {
+                        synonym_lang = text
+                    }
+                    [_, _, "Properties", "Synonym", "item", "content"]
+                    | [_, "synonym", "value"]
+                        if attribute.synonym.is_none() || synonym_lang == "ru" =>
+                    {
+                        attribute.synonym = Some(text);
+                    }
+                    [_, _, "Properties", "Type", "Type" | "TypeSet"] | [_, "type", "types"] => {
+                        attribute.types.push(value_type_name(&text))
+                    }
+                    [_, _, "Properties", "AutoUse"] | [_, "autoUse"] => {
+                        attribute.auto_use = text == "Use"
+                    }
+                    [_, _, "Properties", "Content", "Item", "Metadata"]
+                    | [_, "content", "metadata"] => {
+                        if let Some(item) = attribute.content.last_mut() {
+                            item.metadata = text;
+                        }
+                    }
+                    [_, _, "Properties", "Content", "Item", "Use"] | [_, "content", "use"] => {
+                        if let Some(item) = attribute.content.last_mut() {
+                            item.usage = AttributeUse::parse(&text);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Ошибка XML описания на позиции {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    attribute.content.retain(|item| !item.metadata.is_empty());
+    Ok((!attribute.name.is_empty()).then_some(attribute))
+}
+
+/// Загрузить общие реквизиты из каталога выгрузки или проекта EDT; пустой
+/// список, если их нет
+pub fn load(config_path: &Path) -> Result<Vec<CommonAttribute>> {
+    let mut attributes = Vec::new();
+    for dir in [
+        config_path.join(ATTRIBUTES_DIR),
+        config_path.join("src").join(ATTRIBUTES_DIR),
+    ] {
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .with_context(|| format!("Не удалось прочитать {}", dir.display()))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .collect();
+        entries.sort();
+        for file in entries.iter().filter_map(|entry| object_file(entry)) {
+            let content = std::fs::read_to_string(&file)
+                .with_context(|| format!("Не удалось прочитать {}", file.display()))?;
+            let attribute = parse_common_attribute(&content, &file)
+                .with_context(|| format!("Описание общего реквизита {}", file.display()))?;
+            attributes.extend(attribute);
+        }
+    }
+    Ok(attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ATTRIBUTE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<MetaDataObject xmlns="http://v8.1c.ru/8.3/MDClasses" xmlns:v8="http://v8.1c.ru/8.1/data/core" xmlns:xr="http://v8.1c.ru/8.3/xcf/readable">
+  <CommonAttribute uuid="3f0c7a52-0000-0000-0000-000000000001">
+    <Properties>
+      <Name>Организация</Name>
+      <Synonym><v8:item><v8:lang>ru</v8:lang><v8:content>Организация</v8:content></v8:item></Synonym>
+      <Type><v8:Type>cfg:CatalogRef.Организации</v8:Type></Type>
+      <Content>
+        <xr:Item>
+          <xr:Metadata>Document.ЗаказКлиента</xr:Metadata>
+          <xr:Use>Use</xr:Use>
+        </xr:Item>
+        <xr:Item>
+          <xr:Metadata>Catalog.Валюты</xr:Metadata>
+          <xr:Use>DontUse</xr:Use>
+        </xr:Item>
+      </Content>
+      <AutoUse>DontUse</AutoUse>
+    </Properties>
+  </CommonAttribute>
+</MetaDataObject>"#;
+
+    const ATTRIBUTE_MDO: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<mdclass:CommonAttribute xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass">
+  <name>ОбластьДанных</name>
+  <type><types>Number</types></type>
+  <content>
+    <metadata>Catalog.Валюты</metadata>
+    <use>DontUse</use>
+  </content>
+  <autoUse>Use</autoUse>
+</mdclass:CommonAttribute>"#;
+
+    #[test]
+    fn test_common_attributes() {
+        let dir = tempfile::tempdir().unwrap();
+        let designer = dir.path().join(ATTRIBUTES_DIR);
+        std::fs::create_dir_all(&designer).unwrap();
+        std::fs::write(designer.join("Организация.xml"), ATTRIBUTE_XML).unwrap();
+        let edt = dir
+            .path()
+            .join("src")
+            .join(ATTRIBUTES_DIR)
+            .join("ОбластьДанных");
+        std::fs::create_dir_all(&edt).unwrap();
+        std::fs::write(edt.join("ОбластьДанных.mdo"), ATTRIBUTE_MDO).unwrap();
+
+        let attributes = load(dir.path()).unwrap();
+        assert_eq!(attributes.len(), 2);
+        let organization = &attributes[0];
+        assert_eq!(organization.types, ["СправочникСсылка.Организации"]);
+        assert_eq!(organization.description(), "Общий реквизит. Организация");
+        assert!(organization.applies_to("Document", "ЗаказКлиента"));
+        assert!(!organization.applies_to("Document", "Поступление"));
+        assert!(!organization.applies_to("Catalog", "Валюты"));
+
+        // Автоиспользование: все объекты данных, кроме исключённых
+        let area = &attributes[1];
+        assert_eq!(area.types, ["Число"]);
+        assert!(area.applies_to("Document", "Поступление"));
+        assert!(area.applies_to("InformationRegister", "КурсыВалют"));
+        assert!(!area.applies_to("Catalog", "Валюты"));
+        assert!(!area.applies_to("Report", "Продажи"));
+    }
+}
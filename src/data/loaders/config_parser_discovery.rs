@@ -441,6 +441,7 @@ impl ConfigurationDiscoveryParser {
                     .into_iter()
                     .filter(|s| !s.is_empty())
                     .collect(),
+                    resolver: None,
                 },
                 active_facet: Some(facet),
                 available_facets: vec![facet],
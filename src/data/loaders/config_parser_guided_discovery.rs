@@ -2,6 +2,9 @@
 //!
 //! Использует Configuration.xml как опорный файл для получения полного списка
 //! объектов метаданных, что гораздо надежнее чем рекурсивный обход каталогов
+//!
+//! Проект 1C:EDT разбирается так же: опорный файл — `src/Configuration/Configuration.mdo`,
+//! объекты — `src/Catalogs/<Имя>/<Имя>.mdo` (см. [`ProjectLayout`])
 
 use anyhow::{Context, Result};
 use quick_xml::events::Event;
@@ -10,17 +13,31 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use super::metadata_flags::MetadataFlags;
+use super::metadata_values::value_type_name;
+use super::predefined::{self, PredefinedItem};
+use super::project_layout::{edt_collection_tag, ProjectLayout};
 use crate::domain::types::{
     Attribute, Certainty, ConcreteType, ConfigurationType, FacetKind, MetadataKind,
     ResolutionMetadata, ResolutionResult, ResolutionSource, TabularSection, TypeResolution,
 };
 
+/// Виды объектов с предопределёнными элементами
+const PREDEFINED_TAGS: &[&str] = &[
+    "Catalog",
+    "ChartOfAccounts",
+    "ChartOfCharacteristicTypes",
+    "ChartOfCalculationTypes",
+];
+
 /// Configuration-guided Discovery парсер
 #[derive(Debug)]
 pub struct ConfigurationGuidedParser {
     config_path: PathBuf,
     discovered_objects: HashMap<String, DiscoveredMetadata>,
     configuration_info: Option<ConfigurationInfo>,
+    /// Формат исходников; определяется при разборе
+    layout: Option<ProjectLayout>,
 }
 
 /// Информация о конфигурации из Configuration.xml
@@ -49,9 +66,24 @@ pub struct DiscoveredMetadata {
     pub file_path: PathBuf,
     pub reference_source: ReferenceSource,
     pub synonym: Option<String>,
+    /// Английский синоним (`<v8:lang>en</v8:lang>`) — источник английского
+    /// имени объекта
+    pub english_synonym: Option<String>,
     pub uuid: Option<String>,
     pub attributes: Vec<AttributeInfo>,
     pub tabular_sections: Vec<TabularSectionInfo>,
+    /// Полнотекстовый поиск, история данных и другие флаги из Properties
+    pub flags: MetadataFlags,
+    /// Тег вида объекта в Configuration.xml (`AccumulationRegister`):
+    /// различает виды регистров с одним `MetadataKind::Register`
+    pub metadata_tag: String,
+    /// Регистры, по которым документ формирует движения
+    /// (`AccumulationRegister.ТоварыНаСкладах`)
+    pub register_records: Vec<String>,
+    /// Значения перечисления в порядке описания
+    pub enum_values: Vec<String>,
+    /// Предопределённые элементы справочника или плана (`Ext/Predefined.xml`)
+    pub predefined: Vec<PredefinedItem>,
 }
 
 /// Источник обнаружения ссылки
@@ -69,7 +101,69 @@ pub struct AttributeInfo {
     pub name: String,
     pub type_definition: String,
     pub synonym: Option<String>,
+    /// Обязательный: измерение или `FillChecking` = `ShowError`
     pub mandatory: bool,
+    pub comment: Option<String>,
+    /// Индексирование из `Indexing` (`Index`, `IndexWithAdditionalOrder`);
+    /// `None` — не индексируется
+    pub indexing: Option<String>,
+    /// Только чтение; у реквизитов в описании такого свойства нет, это
+    /// стандартные реквизиты, которые платформа не даёт присвоить (`Проведен`)
+    pub read_only: bool,
+    pub role: AttributeRole,
+}
+
+/// Вид поля объекта
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeRole {
+    Attribute,
+    /// Измерение регистра
+    Dimension,
+    /// Ресурс регистра
+    Resource,
+    /// Стандартный реквизит платформы (`Код`, `Период`)
+    Standard,
+}
+
+impl AttributeRole {
+    /// Вид по тегу описания (`Attribute`, `Dimension`, `Resource`)
+    pub fn from_tag(tag: &str) -> Self {
+        match tag {
+            "Dimension" => AttributeRole::Dimension,
+            "Resource" => AttributeRole::Resource,
+            _ => AttributeRole::Attribute,
+        }
+    }
+}
+
+impl AttributeInfo {
+    /// Описание для справки: вид поля регистра, синоним, комментарий,
+    /// обязательность и индексирование
+    pub fn description(&self) -> String {
+        let role = match self.role {
+            AttributeRole::Dimension => Some("Измерение".to_string()),
+            AttributeRole::Resource => Some("Ресурс".to_string()),
+            AttributeRole::Attribute | AttributeRole::Standard => None,
+        };
+        let mut parts: Vec<String> = role
+            .iter()
+            .chain(self.synonym.iter())
+            .chain(self.comment.iter())
+            .filter(|text| !text.is_empty())
+            .cloned()
+            .collect();
+        if self.mandatory && self.role != AttributeRole::Dimension {
+            parts.push("Обязательный".to_string());
+        }
+        match self.indexing.as_deref() {
+            Some("IndexWithAdditionalOrder") => {
+                parts.push("Индексируется с доп. упорядочиванием".to_string())
+            }
+            Some(_) => parts.push("Индексируется".to_string()),
+            None => {}
+        }
+        parts.join(". ")
+    }
 }
 
 /// Информация о табличной части
@@ -97,6 +191,9 @@ struct StandardAttributes {
 
     // Общие
     pub posting: Option<String>,
+
+    // Регистры накопления: `Balance` или `Turnovers`
+    pub register_type: Option<String>,
 }
 
 impl ConfigurationGuidedParser {
@@ -106,23 +203,28 @@ impl ConfigurationGuidedParser {
             config_path: config_path.as_ref().to_path_buf(),
             discovered_objects: HashMap::new(),
             configuration_info: None,
+            layout: None,
         }
     }
 
     /// Запустить Configuration-guided парсинг
     pub fn parse_with_configuration_guide(&mut self) -> Result<Vec<TypeResolution>> {
-        // Фаза 1: Парсинг Configuration.xml как опорного файла
-        let config_xml_path = self.config_path.join("Configuration.xml");
-        if !config_xml_path.exists() {
+        // Фаза 1: Парсинг Configuration.xml (Configuration.mdo EDT) как опорного файла
+        let Some(layout) = ProjectLayout::detect(&self.config_path) else {
             return Err(anyhow::anyhow!(
-                "Configuration.xml не найден: {}",
-                config_xml_path.display()
+                "Configuration.xml не найден: {} (проект EDT: src/Configuration/Configuration.mdo)",
+                self.config_path.join("Configuration.xml").display()
             ));
-        }
+        };
 
-        let config_info = self.parse_configuration_xml(&config_xml_path)?;
+        let config_info = if layout.is_edt() {
+            self.parse_configuration_mdo(&layout.configuration_file())?
+        } else {
+            self.parse_configuration_xml(&layout.configuration_file())?
+        };
 
         self.configuration_info = Some(config_info.clone());
+        self.layout = Some(layout);
 
         // Фаза 2: Парсинг объектов метаданных по ссылкам из Configuration.xml
         let mut resolutions = Vec::new();
@@ -255,6 +357,86 @@ impl ConfigurationGuidedParser {
         Ok(config_info)
     }
 
+    /// Парсинг Configuration.mdo проекта EDT: имя, версия и списки состава
+    /// (`<catalogs>Catalog.Валюты</catalogs>`)
+    fn parse_configuration_mdo(&self, config_mdo_path: &Path) -> Result<ConfigurationInfo> {
+        let content = fs::read_to_string(config_mdo_path).with_context(|| {
+            format!(
+                "Не удается прочитать Configuration.mdo: {}",
+                config_mdo_path.display()
+            )
+        })?;
+
+        let mut reader = Reader::from_str(&content);
+        reader.trim_text(true);
+
+        let mut config_info = ConfigurationInfo {
+            name: String::new(),
+            uuid: None,
+            version: None,
+            metadata_objects: Vec::new(),
+        };
+
+        let mut buf = Vec::new();
+        let mut path: Vec<String> = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => {
+                    if path.is_empty() {
+                        config_info.uuid = uuid_attribute(&e);
+                    }
+                    path.push(String::from_utf8_lossy(e.local_name().as_ref()).to_string());
+                }
+                Ok(Event::End(_)) => {
+                    path.pop();
+                }
+                Ok(Event::Text(e)) => {
+                    let text = e.unescape()?.trim().to_string();
+                    let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                    match tail.as_slice() {
+                        [_, "name"] => config_info.name = text,
+                        [_, "version"] if !text.is_empty() => config_info.version = Some(text),
+                        // `<catalogs>Catalog.Валюты</catalogs>`
+                        [_, collection] => {
+                            let xml_tag = edt_collection_tag(collection);
+                            let name = text.split_once('.').map(|(_, name)| name);
+                            if let (Some(xml_tag), Some(name)) = (xml_tag, name) {
+                                if let Some(kind) = self.xml_tag_to_metadata_kind(xml_tag) {
+                                    config_info.metadata_objects.push(MetadataReference {
+                                        name: name.to_string(),
+                                        kind,
+                                        xml_tag: xml_tag.to_string(),
+                                    });
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(anyhow::anyhow!(
+                        "Ошибка XML в Configuration.mdo на позиции {}: {}",
+                        reader.buffer_position(),
+                        e
+                    ))
+                }
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        if config_info.name.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Не удалось извлечь имя конфигурации из Configuration.mdo"
+            ));
+        }
+
+        Ok(config_info)
+    }
+
     /// Преобразование XML тега в тип метаданных (динамическое определение)
     fn xml_tag_to_metadata_kind(&self, xml_tag: &str) -> Option<MetadataKind> {
         match xml_tag {
@@ -282,6 +464,12 @@ impl ConfigurationGuidedParser {
             // Системные элементы исключаем
             "Language" | "Configuration" | "ConfigDumpInfo" => None,
 
+            // Внешние источники данных загружаются отдельно (таблицы и функции)
+            "ExternalDataSource" => None,
+
+            // Общие реквизиты загружаются отдельно и дополняют объекты своего состава
+            "CommonAttribute" => None,
+
             // Все неизвестные теги считаем обработками (безопасная стратегия)
             _ => {
                 println!(
@@ -298,6 +486,20 @@ impl ConfigurationGuidedParser {
         &self,
         metadata_ref: &MetadataReference,
     ) -> Result<Option<DiscoveredMetadata>> {
+        // В проекте EDT у объекта постоянное место: src/Catalogs/<Имя>/<Имя>.mdo
+        let layout = self
+            .layout
+            .clone()
+            .or_else(|| ProjectLayout::detect(&self.config_path));
+        if let Some(layout) = layout.filter(ProjectLayout::is_edt) {
+            return match layout.object_file(&metadata_ref.xml_tag, &metadata_ref.name) {
+                Some(file_path) if file_path.is_file() => {
+                    self.parse_metadata_from_mdo(&file_path, metadata_ref)
+                }
+                _ => Ok(None),
+            };
+        }
+
         // Ищем XML файл объекта динамически по всей структуре каталогов
         let xml_file_path = self.find_metadata_file_dynamically(metadata_ref)?;
 
@@ -359,29 +561,23 @@ impl ConfigurationGuidedParser {
         let mut reader = Reader::from_str(&content);
         reader.trim_text(true);
 
-        let mut metadata = DiscoveredMetadata {
-            name: metadata_ref.name.clone(), // Имя уже известно из Configuration.xml
-            kind: metadata_ref.kind,
-            qualified_name: format!(
-                "{}.{}",
-                self.get_kind_prefix(metadata_ref.kind),
-                metadata_ref.name
-            ),
-            file_path: xml_file_path.to_path_buf(),
-            reference_source: ReferenceSource::ConfigurationChildObjects,
-            synonym: None,
-            uuid: None,
-            attributes: Vec::new(),
-            tabular_sections: Vec::new(),
-        };
+        let mut metadata = self.new_metadata(xml_file_path, metadata_ref);
 
         let mut buf = Vec::new();
         let mut in_properties = false;
         let mut in_child_objects = false;
         let mut in_attribute_properties = false;
+        let mut in_register_records = false;
         let mut current_element = String::new();
         let mut current_attribute: Option<AttributeInfo> = None;
+        // Типы реквизита из `<Type><v8:Type>...</v8:Type></Type>`
+        let mut attribute_types: Vec<String> = Vec::new();
         let mut current_tabular_section: Option<TabularSectionInfo> = None;
+        let mut current_enum_value: Option<String> = None;
+        // Синоним `<Synonym><v8:item><v8:lang>` / `<v8:content>`: берётся
+        // русский, иначе первый
+        let mut in_synonym = false;
+        let mut synonym_lang = String::new();
 
         // Для стандартных атрибутов
         let mut standard_attributes = StandardAttributes::default();
@@ -401,14 +597,29 @@ impl ConfigurationGuidedParser {
                         "ChildObjects" => {
                             in_child_objects = true;
                         }
+                        "RegisterRecords" if in_properties => {
+                            in_register_records = true;
+                        }
                         "Attribute" | "Resource" | "Dimension" if in_child_objects => {
+                            attribute_types.clear();
                             current_attribute = Some(AttributeInfo {
                                 name: String::new(),
                                 type_definition: "xs:string".to_string(),
                                 synonym: None,
                                 mandatory: tag_name == "Dimension",
+                                comment: None,
+                                indexing: None,
+                                read_only: false,
+                                role: AttributeRole::from_tag(&tag_name),
                             });
                         }
+                        "EnumValue" if in_child_objects => {
+                            current_enum_value = Some(String::new());
+                        }
+                        "Synonym" => {
+                            in_synonym = true;
+                            synonym_lang.clear();
+                        }
                         "TabularSection" if in_child_objects => {
                             current_tabular_section = Some(TabularSectionInfo {
                                 name: String::new(),
@@ -438,7 +649,26 @@ impl ConfigurationGuidedParser {
                     let text = e.unescape()?.into_owned();
 
                     if !text.trim().is_empty() {
-                        if in_properties {
+                        if in_synonym && current_element == "v8:lang" {
+                            synonym_lang = text;
+                        } else if in_synonym && current_element == "v8:content" {
+                            let synonym = if let Some(ref mut attr) = current_attribute {
+                                Some(&mut attr.synonym)
+                            } else if let Some(ref mut ts) = current_tabular_section {
+                                Some(&mut ts.synonym)
+                            } else if in_properties && synonym_lang == "en" {
+                                Some(&mut metadata.english_synonym)
+                            } else if in_properties {
+                                Some(&mut metadata.synonym)
+                            } else {
+                                None
+                            };
+                            if let Some(synonym) = synonym {
+                                if synonym.is_none() || synonym_lang == "ru" {
+                                    *synonym = Some(text);
+                                }
+                            }
+                        } else if in_properties {
                             // Парсинг стандартных атрибутов из Properties
                             match current_element.as_str() {
                                 "CodeLength" => {
@@ -466,18 +696,35 @@ impl ConfigurationGuidedParser {
                                     standard_attributes.number_periodicity = Some(text)
                                 }
                                 "Posting" => standard_attributes.posting = Some(text),
-                                _ => {}
+                                "RegisterType" => standard_attributes.register_type = Some(text),
+                                "xr:Item" if in_register_records => {
+                                    metadata.register_records.push(text)
+                                }
+                                property => {
+                                    metadata.flags.apply_property(property, &text);
+                                }
                             }
                         } else if let Some(ref mut attr) = current_attribute {
                             if in_attribute_properties {
-                                if current_element.as_str() == "Name" {
-                                    attr.name = text;
+                                match current_element.as_str() {
+                                    "Name" => attr.name = text,
+                                    "Comment" => attr.comment = Some(text),
+                                    "Indexing" if text != "DontIndex" => attr.indexing = Some(text),
+                                    "FillChecking" if text == "ShowError" => attr.mandatory = true,
+                                    "v8:Type" | "v8:TypeSet" => {
+                                        attribute_types.push(value_type_name(&text))
+                                    }
+                                    _ => {}
                                 }
                             }
                         } else if let Some(ref mut ts) = current_tabular_section {
                             if current_element.as_str() == "Name" {
                                 ts.name = text;
                             }
+                        } else if let Some(ref mut value) = current_enum_value {
+                            if current_element.as_str() == "Name" && value.is_empty() {
+                                *value = text;
+                            }
                         }
                     }
                 }
@@ -494,13 +741,29 @@ impl ConfigurationGuidedParser {
                         "ChildObjects" => {
                             in_child_objects = false;
                         }
+                        "RegisterRecords" => {
+                            in_register_records = false;
+                        }
+                        "Synonym" => {
+                            in_synonym = false;
+                        }
                         "Attribute" | "Resource" | "Dimension" if in_child_objects => {
-                            if let Some(attr) = current_attribute.take() {
+                            if let Some(mut attr) = current_attribute.take() {
+                                if !attribute_types.is_empty() {
+                                    attr.type_definition = attribute_types.join(",");
+                                }
                                 if !attr.name.is_empty() {
                                     metadata.attributes.push(attr);
                                 }
                             }
                         }
+                        "EnumValue" if in_child_objects => {
+                            if let Some(value) = current_enum_value.take() {
+                                if !value.is_empty() {
+                                    metadata.enum_values.push(value);
+                                }
+                            }
+                        }
                         "TabularSection" if in_child_objects => {
                             if let Some(ts) = current_tabular_section.take() {
                                 if !ts.name.is_empty() {
@@ -526,8 +789,239 @@ impl ConfigurationGuidedParser {
             buf.clear();
         }
 
+        self.finish_metadata(&mut metadata, &standard_attributes);
+
+        Ok(Some(metadata))
+    }
+
+    /// Заготовка метаданных объекта по ссылке из опорного файла
+    fn new_metadata(
+        &self,
+        file_path: &Path,
+        metadata_ref: &MetadataReference,
+    ) -> DiscoveredMetadata {
+        DiscoveredMetadata {
+            name: metadata_ref.name.clone(), // Имя уже известно из Configuration.xml
+            kind: metadata_ref.kind,
+            qualified_name: format!(
+                "{}.{}",
+                self.get_reference_prefix(metadata_ref),
+                metadata_ref.name
+            ),
+            file_path: file_path.to_path_buf(),
+            reference_source: ReferenceSource::ConfigurationChildObjects,
+            synonym: None,
+            english_synonym: None,
+            uuid: None,
+            attributes: Vec::new(),
+            tabular_sections: Vec::new(),
+            flags: MetadataFlags::default(),
+            metadata_tag: metadata_ref.xml_tag.clone(),
+            register_records: Vec::new(),
+            enum_values: Vec::new(),
+            predefined: Vec::new(),
+        }
+    }
+
+    /// Стандартные атрибуты и предопределённые элементы после разбора описания
+    fn finish_metadata(
+        &self,
+        metadata: &mut DiscoveredMetadata,
+        standard_attributes: &StandardAttributes,
+    ) {
         // Добавляем стандартные атрибуты на основе типа метаданных
-        self.add_standard_attributes(&mut metadata, &standard_attributes);
+        self.add_standard_attributes(metadata, standard_attributes);
+
+        if PREDEFINED_TAGS.contains(&metadata.metadata_tag.as_str()) {
+            match predefined::load(&metadata.file_path) {
+                Ok(items) => metadata.predefined = items,
+                Err(e) => println!(
+                    "⚠️ Предопределённые элементы {} пропущены: {:#}",
+                    metadata.name, e
+                ),
+            }
+        }
+    }
+
+    /// Парсинг описания объекта проекта EDT (`Валюты.mdo`): свойства —
+    /// дочерние элементы корня в нижнем регистре, реквизиты, измерения,
+    /// ресурсы и табличные части — повторяющиеся `attributes`, `dimensions`,
+    /// `resources`, `tabularSections`
+    fn parse_metadata_from_mdo(
+        &self,
+        mdo_file_path: &Path,
+        metadata_ref: &MetadataReference,
+    ) -> Result<Option<DiscoveredMetadata>> {
+        let content = fs::read_to_string(mdo_file_path)
+            .with_context(|| format!("Не удается прочитать файл: {}", mdo_file_path.display()))?;
+
+        let mut reader = Reader::from_str(&content);
+        reader.trim_text(true);
+
+        let mut metadata = self.new_metadata(mdo_file_path, metadata_ref);
+        let mut standard_attributes = StandardAttributes::default();
+
+        let mut buf = Vec::new();
+        let mut path: Vec<String> = Vec::new();
+        let mut current_attribute: Option<AttributeInfo> = None;
+        let mut attribute_types: Vec<String> = Vec::new();
+        let mut current_tabular_section: Option<TabularSectionInfo> = None;
+        // Синоним `<synonym><key>ru</key><value>...</value></synonym>`:
+        // берётся русский, иначе первый
+        let mut synonym_lang = String::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => {
+                    if path.is_empty() {
+                        metadata.uuid = uuid_attribute(&e);
+                    }
+                    path.push(String::from_utf8_lossy(e.local_name().as_ref()).to_string());
+                    let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                    match tail.as_slice() {
+                        [_, field @ ("attributes" | "dimensions" | "resources")]
+                        | [_, "tabularSections", field @ "attributes"] => {
+                            let role = match *field {
+                                "dimensions" => AttributeRole::Dimension,
+                                "resources" => AttributeRole::Resource,
+                                _ => AttributeRole::Attribute,
+                            };
+                            attribute_types.clear();
+                            current_attribute = Some(AttributeInfo {
+                                name: String::new(),
+                                type_definition: "xs:string".to_string(),
+                                synonym: None,
+                                mandatory: role == AttributeRole::Dimension,
+                                comment: None,
+                                indexing: None,
+                                read_only: false,
+                                role,
+                            });
+                        }
+                        [_, "tabularSections"] => {
+                            current_tabular_section = Some(TabularSectionInfo {
+                                name: String::new(),
+                                synonym: None,
+                                attributes: Vec::new(),
+                            });
+                        }
+                        [.., "synonym"] => synonym_lang.clear(),
+                        _ => {}
+                    }
+                }
+                Ok(Event::End(_)) => {
+                    let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                    match tail.as_slice() {
+                        [_, "attributes" | "dimensions" | "resources"]
+                        | [_, "tabularSections", "attributes"] => {
+                            if let Some(mut attr) = current_attribute.take() {
+                                if !attribute_types.is_empty() {
+                                    attr.type_definition = attribute_types.join(",");
+                                }
+                                let attributes = match current_tabular_section.as_mut() {
+                                    Some(ts) => &mut ts.attributes,
+                                    None => &mut metadata.attributes,
+                                };
+                                if !attr.name.is_empty() {
+                                    attributes.push(attr);
+                                }
+                            }
+                        }
+                        [_, "tabularSections"] => {
+                            if let Some(ts) = current_tabular_section.take() {
+                                if !ts.name.is_empty() {
+                                    metadata.tabular_sections.push(ts);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                    path.pop();
+                }
+                Ok(Event::Text(e)) => {
+                    let text = e.unescape()?.trim().to_string();
+                    let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                    match tail.as_slice() {
+                        _ if text.is_empty() => {}
+                        [.., "synonym", "key"] => synonym_lang = text,
+                        [.., "synonym", "value"] => {
+                            let synonym = if let Some(ref mut attr) = current_attribute {
+                                &mut attr.synonym
+                            } else if let Some(ref mut ts) = current_tabular_section {
+                                &mut ts.synonym
+                            } else if synonym_lang == "en" {
+                                &mut metadata.english_synonym
+                            } else {
+                                &mut metadata.synonym
+                            };
+                            if synonym.is_none() || synonym_lang == "ru" {
+                                *synonym = Some(text);
+                            }
+                        }
+                        [_, "attributes" | "dimensions" | "resources", field @ ..]
+                        | [_, "tabularSections", "attributes", field @ ..] => {
+                            if let Some(ref mut attr) = current_attribute {
+                                match field {
+                                    ["name"] => attr.name = text,
+                                    ["comment"] => attr.comment = Some(text),
+                                    ["indexing"] if text != "DontIndex" => {
+                                        attr.indexing = Some(text)
+                                    }
+                                    ["fillChecking"] if text == "ShowError" => {
+                                        attr.mandatory = true
+                                    }
+                                    ["type", "types"] => {
+                                        attribute_types.push(value_type_name(&text))
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                        [_, "tabularSections", "name"] => {
+                            if let Some(ref mut ts) = current_tabular_section {
+                                ts.name = text;
+                            }
+                        }
+                        [_, "enumValues", "name"] => metadata.enum_values.push(text),
+                        [_, "codeLength"] => standard_attributes.code_length = text.parse().ok(),
+                        [_, "codeType"] => standard_attributes.code_type = Some(text),
+                        [_, "descriptionLength"] => {
+                            standard_attributes.description_length = text.parse().ok()
+                        }
+                        [_, "hierarchical"] => standard_attributes.hierarchical = text == "true",
+                        [_, "numberLength"] => {
+                            standard_attributes.number_length = text.parse().ok()
+                        }
+                        [_, "numberType"] => standard_attributes.number_type = Some(text),
+                        [_, "numberPeriodicity"] => {
+                            standard_attributes.number_periodicity = Some(text)
+                        }
+                        [_, "posting"] => standard_attributes.posting = Some(text),
+                        [_, "registerType"] => standard_attributes.register_type = Some(text),
+                        [_, "registerRecords"] => metadata.register_records.push(text),
+                        // Остальные свойства — как в Properties выгрузки (`fullTextSearch` → `FullTextSearch`)
+                        [_, property] => {
+                            metadata.flags.apply_property(&pascal_case(property), &text);
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    return Err(anyhow::anyhow!(
+                        "Ошибка XML в {} на позиции {}: {}",
+                        mdo_file_path.display(),
+                        reader.buffer_position(),
+                        e
+                    ))
+                }
+                _ => {}
+            }
+
+            buf.clear();
+        }
+
+        self.finish_metadata(&mut metadata, &standard_attributes);
 
         Ok(Some(metadata))
     }
@@ -555,6 +1049,10 @@ impl ConfigurationGuidedParser {
                         type_definition: code_type_str,
                         synonym: Some("Code".to_string()),
                         mandatory: false,
+                        comment: None,
+                        indexing: None,
+                        read_only: false,
+                        role: AttributeRole::Standard,
                     });
                 }
 
@@ -565,6 +1063,10 @@ impl ConfigurationGuidedParser {
                         type_definition: format!("Строка({})", length),
                         synonym: Some("Description".to_string()),
                         mandatory: true,
+                        comment: None,
+                        indexing: None,
+                        read_only: false,
+                        role: AttributeRole::Standard,
                     });
                 }
 
@@ -576,6 +1078,10 @@ impl ConfigurationGuidedParser {
                         type_definition: parent_type,
                         synonym: Some("Parent".to_string()),
                         mandatory: false,
+                        comment: None,
+                        indexing: None,
+                        read_only: false,
+                        role: AttributeRole::Standard,
                     });
                 }
 
@@ -596,6 +1102,10 @@ impl ConfigurationGuidedParser {
                         },
                         synonym: Some("Owner".to_string()),
                         mandatory: true,
+                        comment: None,
+                        indexing: None,
+                        read_only: false,
+                        role: AttributeRole::Standard,
                     });
                 }
             }
@@ -618,6 +1128,10 @@ impl ConfigurationGuidedParser {
                         type_definition: number_type_str,
                         synonym: Some("Number".to_string()),
                         mandatory: false,
+                        comment: None,
+                        indexing: None,
+                        read_only: false,
+                        role: AttributeRole::Standard,
                     });
                 }
 
@@ -627,6 +1141,10 @@ impl ConfigurationGuidedParser {
                     type_definition: "Дата".to_string(),
                     synonym: Some("Date".to_string()),
                     mandatory: true,
+                    comment: None,
+                    indexing: None,
+                    read_only: false,
+                    role: AttributeRole::Standard,
                 });
 
                 // Проведен (если документ проводимый)
@@ -636,6 +1154,10 @@ impl ConfigurationGuidedParser {
                         type_definition: "Булево".to_string(),
                         synonym: Some("Posted".to_string()),
                         mandatory: false,
+                        comment: None,
+                        indexing: None,
+                        read_only: true,
+                        role: AttributeRole::Standard,
                     });
                 }
             }
@@ -649,6 +1171,10 @@ impl ConfigurationGuidedParser {
                     type_definition: "ДатаВремя".to_string(),
                     synonym: Some("Period".to_string()),
                     mandatory: true,
+                    comment: None,
+                    indexing: None,
+                    read_only: false,
+                    role: AttributeRole::Standard,
                 });
 
                 // Активность (для регистров сведений)
@@ -657,7 +1183,47 @@ impl ConfigurationGuidedParser {
                     type_definition: "Булево".to_string(),
                     synonym: Some("Active".to_string()),
                     mandatory: false,
+                    comment: None,
+                    indexing: None,
+                    read_only: false,
+                    role: AttributeRole::Standard,
                 });
+
+                // Регистры, подчинённые регистратору: накопления, бухгалтерии, расчёта
+                if metadata.metadata_tag != "InformationRegister" {
+                    let standard = |name: &str, type_definition: &str, english: &str, read_only| {
+                        AttributeInfo {
+                            name: name.to_string(),
+                            type_definition: type_definition.to_string(),
+                            synonym: Some(english.to_string()),
+                            mandatory: false,
+                            comment: None,
+                            indexing: None,
+                            read_only,
+                            role: AttributeRole::Standard,
+                        }
+                    };
+                    metadata.attributes.push(standard(
+                        "Регистратор",
+                        "ДокументСсылка",
+                        "Recorder",
+                        false,
+                    ));
+                    metadata
+                        .attributes
+                        .push(standard("НомерСтроки", "Число", "LineNumber", true));
+                    // Вид движения — только у регистров остатков
+                    if metadata.metadata_tag == "AccumulationRegister"
+                        && std_attrs.register_type.as_deref() != Some("Turnovers")
+                    {
+                        metadata.attributes.push(standard(
+                            "ВидДвижения",
+                            "ВидДвиженияНакопления",
+                            "RecordType",
+                            false,
+                        ));
+                    }
+                }
             }
 
             _ => {
@@ -684,8 +1250,12 @@ impl ConfigurationGuidedParser {
                     .map(|attr| Attribute {
                         name: attr.name.clone(),
                         type_: attr.type_definition.clone(),
-                        is_composite: false,
-                        types: vec![attr.type_definition.clone()],
+                        is_composite: attr.type_definition.contains(','),
+                        types: attr
+                            .type_definition
+                            .split(',')
+                            .map(str::to_string)
+                            .collect(),
                     })
                     .collect(),
                 tabular_sections: metadata
@@ -732,8 +1302,10 @@ impl ConfigurationGuidedParser {
                             .unwrap_or_default(),
                     ]
                     .into_iter()
+                    .chain(metadata.flags.notes())
                     .filter(|s| !s.is_empty())
                     .collect(),
+                    resolver: None,
                 },
                 active_facet: Some(facet),
                 available_facets: vec![facet],
@@ -796,6 +1368,17 @@ impl ConfigurationGuidedParser {
         }
     }
 
+    /// Префикс квалифицированного имени объекта; у регистров зависит от
+    /// вида (`РегистрыНакопления`)
+    fn get_reference_prefix(&self, metadata_ref: &MetadataReference) -> &str {
+        match metadata_ref.xml_tag.as_str() {
+            "AccumulationRegister" => "РегистрыНакопления",
+            "AccountingRegister" => "РегистрыБухгалтерии",
+            "CalculationRegister" => "РегистрыРасчета",
+            _ => self.get_kind_prefix(metadata_ref.kind),
+        }
+    }
+
     /// Получить информацию о конфигурации
     pub fn get_configuration_info(&self) -> Option<&ConfigurationInfo> {
         self.configuration_info.as_ref()
@@ -844,6 +1427,25 @@ impl ConfigurationGuidedParser {
     }
 }
 
+/// UUID объекта из атрибута корневого элемента
+fn uuid_attribute(element: &quick_xml::events::BytesStart) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == b"uuid")
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|uuid| uuid.to_string())
+}
+
+/// Имя свойства выгрузки по имени EDT: `fullTextSearch` → `FullTextSearch`
+fn pascal_case(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
 /// Статистика Configuration-guided discovery
 #[derive(Debug, Default)]
 pub struct GuidedDiscoveryStats {
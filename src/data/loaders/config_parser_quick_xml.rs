@@ -363,6 +363,7 @@ impl ConfigurationQuickXmlParser {
                     .into_iter()
                     .filter(|s| !s.is_empty())
                     .collect(),
+                    resolver: None,
                 },
                 active_facet: Some(facet),
                 available_facets: vec![facet],
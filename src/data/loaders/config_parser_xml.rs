@@ -312,6 +312,7 @@ impl ConfigParserXml {
                 line: None,
                 column: None,
                 notes: object.synonym.map(|s| vec![s]).unwrap_or_default(),
+                resolver: None,
             },
             active_facet: Some(crate::core::types::FacetKind::Manager),
             available_facets: self.get_facets_for_kind(kind),
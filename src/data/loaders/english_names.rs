@@ -0,0 +1,138 @@
+//! Английские имена объектов конфигурации
+//!
+//! У объекта конфигурации одно имя — то, что задано в конфигураторе, обычно
+//! русское. Чтобы `Catalogs.Counterparties` и поиск по английскому имени
+//! находили объект так же, как типы платформы, английское имя берётся из
+//! английского синонима (`<v8:lang>en</v8:lang>`): `Order statuses` →
+//! `OrderStatuses`. Без синонима имя транслитерируется:
+//! `Контрагенты` → `Kontragenty`.
+
+/// Латинская запись русской буквы (строчная)
+fn latin(ch: char) -> Option<&'static str> {
+    let latin = match ch {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' => "g",
+        'д' => "d",
+        'е' => "e",
+        'ё' => "e",
+        'ж' => "zh",
+        'з' => "z",
+        'и' => "i",
+        'й' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ы' => "y",
+        'э' => "e",
+        'ю' => "yu",
+        'я' => "ya",
+        _ => return None,
+    };
+    Some(latin)
+}
+
+/// Транслитерация имени: заглавная русская буква даёт заглавную латинскую,
+/// остальные символы не меняются
+pub fn transliterate(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for ch in name.chars() {
+        let lower = ch.to_lowercase().next().unwrap_or(ch);
+        match latin(lower) {
+            Some(text) if ch != lower => {
+                let mut chars = text.chars();
+                if let Some(first) = chars.next() {
+                    out.extend(first.to_uppercase());
+                    out.push_str(chars.as_str());
+                }
+            }
+            Some(text) => out.push_str(text),
+            None => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Идентификатор из английского синонима: слова латиницей склеиваются с
+/// заглавной буквы (`Order statuses` → `OrderStatuses`); синоним с другими
+/// буквами или начинающийся с цифры не подходит
+pub fn english_identifier(synonym: &str) -> Option<String> {
+    let words: Vec<&str> = synonym
+        .split(|ch: char| !ch.is_alphanumeric() && ch != '_')
+        .filter(|word| !word.is_empty())
+        .collect();
+    if words.is_empty() || words.iter().any(|word| !word.is_ascii()) {
+        return None;
+    }
+    let identifier: String = words
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    let starts_with_letter = identifier
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_');
+    starts_with_letter.then_some(identifier)
+}
+
+/// Английское имя объекта: из английского синонима, иначе само имя, если оно
+/// уже латинское, иначе транслитерация
+pub fn english_name(name: &str, english_synonym: Option<&str>) -> String {
+    if let Some(identifier) = english_synonym.and_then(english_identifier) {
+        return identifier;
+    }
+    if name.is_ascii() {
+        return name.to_string();
+    }
+    transliterate(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_name() {
+        assert_eq!(transliterate("Контрагенты"), "Kontragenty");
+        assert_eq!(transliterate("ЧастичнаяОплата"), "ChastichnayaOplata");
+        assert_eq!(transliterate("Объекты_2"), "Obekty_2");
+
+        assert_eq!(
+            english_identifier("Order statuses").as_deref(),
+            Some("OrderStatuses")
+        );
+        assert_eq!(english_identifier("Статусы"), None);
+        assert_eq!(english_identifier("1C objects"), None);
+
+        assert_eq!(
+            english_name("СтатусыЗаказов", Some("Order statuses")),
+            "OrderStatuses"
+        );
+        assert_eq!(
+            english_name("СтатусыЗаказов", Some("Статусы")),
+            "StatusyZakazov"
+        );
+        assert_eq!(english_name("Products", None), "Products");
+    }
+}
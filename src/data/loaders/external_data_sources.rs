@@ -0,0 +1,398 @@
+//! Внешние источники данных конфигурации: таблицы, поля и функции
+//!
+//! Конфигуратор выгружает источник в `ExternalDataSources/<Имя>.xml` (состав
+//! в `ChildObjects`), таблицы — в `ExternalDataSources/<Имя>/Tables/<Таблица>.xml`
+//! с полями в `ChildObjects/Field`, функции — в `.../Functions/<Функция>.xml`.
+//! EDT описывает всё в одном `src/ExternalDataSources/<Имя>/<Имя>.mdo`
+//! (`tables/fields`, `functions`). Типы полей переводятся в имена типов BSL,
+//! чтобы `ВнешниеИсточникиДанных.Имя.Таблицы.Таблица` разрешался в менеджер
+//! таблицы с полями, а не в динамический тип.
+
+use anyhow::{Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::metadata_values::value_type_name;
+
+const SOURCES_DIR: &str = "ExternalDataSources";
+
+/// Поле таблицы внешнего источника
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExternalField {
+    pub name: String,
+    /// Имена типов BSL; несколько — составной тип
+    pub types: Vec<String>,
+}
+
+/// Таблица внешнего источника
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExternalTable {
+    pub name: String,
+    /// Объектная таблица (ссылки и объекты); иначе — наборы записей
+    pub object: bool,
+    pub fields: Vec<ExternalField>,
+}
+
+/// Функция внешнего источника
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExternalFunction {
+    pub name: String,
+    pub return_types: Vec<String>,
+}
+
+/// Внешний источник данных
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExternalDataSource {
+    pub name: String,
+    pub tables: Vec<ExternalTable>,
+    pub functions: Vec<ExternalFunction>,
+    /// Файл описания источника
+    pub file_path: PathBuf,
+}
+
+/// Узел при обходе XML: начало элемента или текст, с путём от корня
+/// (локальные имена без префиксов пространств имён)
+enum Node<'a> {
+    Start(&'a [&'a str]),
+    Text(&'a [&'a str], String),
+}
+
+fn walk(content: &str, mut visit: impl FnMut(Node<'_>)) -> Result<()> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                path.push(String::from_utf8_lossy(e.local_name().as_ref()).to_string());
+                let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                visit(Node::Start(&tail));
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                visit(Node::Text(&tail, text));
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Ошибка XML описания на позиции {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(())
+}
+
+/// `ObjectData` — объектная таблица, `NonObjectData` — необъектная
+fn is_object_data(table_data_type: &str) -> bool {
+    table_data_type.starts_with("Object")
+}
+
+fn table(name: String) -> ExternalTable {
+    ExternalTable {
+        name,
+        object: false,
+        fields: Vec::new(),
+    }
+}
+
+fn field() -> ExternalField {
+    ExternalField {
+        name: String::new(),
+        types: Vec::new(),
+    }
+}
+
+fn function(name: String) -> ExternalFunction {
+    ExternalFunction {
+        name,
+        return_types: Vec::new(),
+    }
+}
+
+/// Разобрать описание источника: файл конфигуратора (таблицы и функции —
+/// только имена из `ChildObjects`) или `.mdo` EDT (полный состав).
+/// `None` — в описании нет имени.
+pub fn parse_source(content: &str, file_path: &Path) -> Result<Option<ExternalDataSource>> {
+    let mut source = ExternalDataSource {
+        name: String::new(),
+        tables: Vec::new(),
+        functions: Vec::new(),
+        file_path: file_path.to_path_buf(),
+    };
+    walk(content, |node| match node {
+        // EDT: ExternalDataSource/{tables/fields, functions}
+        Node::Start([_, "tables"]) => source.tables.push(table(String::new())),
+        Node::Start([_, "tables", "fields"]) => {
+            if let Some(table) = source.tables.last_mut() {
+                table.fields.push(field());
+            }
+        }
+        Node::Start([_, "functions"]) => source.functions.push(function(String::new())),
+        Node::Text(path, text) => match path {
+            // Конфигуратор: MetaDataObject/ExternalDataSource/...
+            [_, _, "Properties", "Name"] | [_, "name"] => source.name = text,
+            [_, _, "ChildObjects", "Table"] => source.tables.push(table(text)),
+            [_, _, "ChildObjects", "Function"] => source.functions.push(function(text)),
+            [_, "tables", "name"] => {
+                if let Some(table) = source.tables.last_mut() {
+                    table.name = text;
+                }
+            }
+            [_, "tables", "tableDataType"] => {
+                if let Some(table) = source.tables.last_mut() {
+                    table.object = is_object_data(&text);
+                }
+            }
+            [_, "tables", "fields", "name"] => {
+                if let Some(field) = source.tables.last_mut().and_then(|t| t.fields.last_mut()) {
+                    field.name = text;
+                }
+            }
+            [_, "tables", "fields", "type", "types"] => {
+                if let Some(field) = source.tables.last_mut().and_then(|t| t.fields.last_mut()) {
+                    field.types.push(value_type_name(&text));
+                }
+            }
+            [_, "functions", "name"] => {
+                if let Some(function) = source.functions.last_mut() {
+                    function.name = text;
+                }
+            }
+            [_, "functions", _, "types"] => {
+                if let Some(function) = source.functions.last_mut() {
+                    function.return_types.push(value_type_name(&text));
+                }
+            }
+            _ => {}
+        },
+        _ => {}
+    })?;
+    Ok((!source.name.is_empty()).then_some(source))
+}
+
+/// Разобрать описание таблицы конфигуратора (`Tables/<Таблица>.xml`)
+pub fn parse_table(content: &str) -> Result<Option<ExternalTable>> {
+    let mut parsed = table(String::new());
+    walk(content, |node| match node {
+        Node::Start([_, _, "ChildObjects", "Field"]) => parsed.fields.push(field()),
+        Node::Text(path, text) => match path {
+            [_, _, "Properties", "Name"] => parsed.name = text,
+            [_, _, "Properties", "TableDataType"] => parsed.object = is_object_data(&text),
+            [_, _, "ChildObjects", "Field", "Properties", "Name"] => {
+                if let Some(field) = parsed.fields.last_mut() {
+                    field.name = text;
+                }
+            }
+            [_, _, "ChildObjects", "Field", "Properties", "Type", "Type" | "TypeSet"] => {
+                if let Some(field) = parsed.fields.last_mut() {
+                    field.types.push(value_type_name(&text));
+                }
+            }
+            _ => {}
+        },
+        _ => {}
+    })?;
+    Ok((!parsed.name.is_empty()).then_some(parsed))
+}
+
+/// Разобрать описание функции конфигуратора (`Functions/<Функция>.xml`):
+/// тип возвращаемого значения — `Type` внутри свойства функции
+pub fn parse_function(content: &str) -> Result<Option<ExternalFunction>> {
+    let mut parsed = function(String::new());
+    walk(content, |node| {
+        if let Node::Text(path, text) = node {
+            match path {
+                [_, _, "Properties", "Name"] => parsed.name = text,
+                [_, _, "Properties", _, "Type" | "TypeSet"] => {
+                    parsed.return_types.push(value_type_name(&text))
+                }
+                _ => {}
+            }
+        }
+    })?;
+    Ok((!parsed.name.is_empty()).then_some(parsed))
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("Не удалось прочитать {}", path.display()))
+}
+
+/// Дополнить источник конфигуратора описаниями таблиц и функций из
+/// подкаталога источника; без файла остаётся только имя
+fn load_children(source: &mut ExternalDataSource, dir: &Path) -> Result<()> {
+    for table in &mut source.tables {
+        let file = dir.join("Tables").join(format!("{}.xml", table.name));
+        if file.is_file() {
+            let parsed = parse_table(&read(&file)?)
+                .with_context(|| format!("Описание таблицы {}", file.display()))?;
+            if let Some(parsed) = parsed {
+                *table = parsed;
+            }
+        }
+    }
+    for function in &mut source.functions {
+        let file = dir.join("Functions").join(format!("{}.xml", function.name));
+        if file.is_file() {
+            let parsed = parse_function(&read(&file)?)
+                .with_context(|| format!("Описание функции {}", file.display()))?;
+            if let Some(parsed) = parsed {
+                *function = parsed;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Загрузить внешние источники данных из каталога выгрузки или проекта EDT;
+/// пустой список, если их нет
+pub fn load(config_path: &Path) -> Result<Vec<ExternalDataSource>> {
+    let mut sources = Vec::new();
+    for dir in [
+        config_path.join(SOURCES_DIR),
+        config_path.join("src").join(SOURCES_DIR),
+    ] {
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .with_context(|| format!("Не удалось прочитать {}", dir.display()))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .collect();
+        entries.sort();
+        for entry in entries {
+            if entry.is_file() && entry.extension().is_some_and(|ext| ext == "xml") {
+                let Some(mut source) = parse_source(&read(&entry)?, &entry)? else {
+                    continue;
+                };
+                load_children(&mut source, &entry.with_extension(""))?;
+                sources.push(source);
+            } else if entry.is_dir() {
+                let name = entry
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let mdo = entry.join(format!("{}.mdo", name));
+                if mdo.is_file() {
+                    sources.extend(parse_source(&read(&mdo)?, &mdo)?);
+                }
+            }
+        }
+    }
+    Ok(sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<MetaDataObject xmlns="http://v8.1c.ru/8.3/MDClasses">
+  <ExternalDataSource uuid="5c1d1d6e-0000-0000-0000-000000000001">
+    <Properties>
+      <Name>Склад</Name>
+    </Properties>
+    <ChildObjects>
+      <Table>Остатки</Table>
+      <Function>КурсНаДату</Function>
+    </ChildObjects>
+  </ExternalDataSource>
+</MetaDataObject>"#;
+
+    const TABLE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<MetaDataObject xmlns="http://v8.1c.ru/8.3/MDClasses" xmlns:v8="http://v8.1c.ru/8.1/data/core">
+  <Table uuid="5c1d1d6e-0000-0000-0000-000000000002">
+    <Properties>
+      <Name>Остатки</Name>
+      <TableDataType>NonObjectData</TableDataType>
+    </Properties>
+    <ChildObjects>
+      <Field uuid="5c1d1d6e-0000-0000-0000-000000000003">
+        <Properties>
+          <Name>Количество</Name>
+          <Type><v8:Type>xs:decimal</v8:Type></Type>
+        </Properties>
+      </Field>
+      <Field uuid="5c1d1d6e-0000-0000-0000-000000000004">
+        <Properties>
+          <Name>Товар</Name>
+          <Type><v8:Type>cfg:CatalogRef.Номенклатура</v8:Type></Type>
+        </Properties>
+      </Field>
+    </ChildObjects>
+  </Table>
+</MetaDataObject>"#;
+
+    const SOURCE_MDO: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<mdclass:ExternalDataSource xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass">
+  <name>Касса</name>
+  <tables>
+    <name>Чеки</name>
+    <tableDataType>ObjectData</tableDataType>
+    <fields>
+      <name>Сумма</name>
+      <type><types>Number</types></type>
+    </fields>
+  </tables>
+  <functions>
+    <name>ТекущаяСмена</name>
+    <returningValueType><types>String</types></returningValueType>
+  </functions>
+</mdclass:ExternalDataSource>"#;
+
+    #[test]
+    fn test_load_external_data_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let sources_dir = dir.path().join(SOURCES_DIR);
+        std::fs::create_dir_all(sources_dir.join("Склад").join("Tables")).unwrap();
+        std::fs::write(sources_dir.join("Склад.xml"), SOURCE_XML).unwrap();
+        std::fs::write(sources_dir.join("Склад/Tables/Остатки.xml"), TABLE_XML).unwrap();
+        let edt_dir = dir.path().join("src").join(SOURCES_DIR).join("Касса");
+        std::fs::create_dir_all(&edt_dir).unwrap();
+        std::fs::write(edt_dir.join("Касса.mdo"), SOURCE_MDO).unwrap();
+
+        let sources = load(dir.path()).unwrap();
+        assert_eq!(sources.len(), 2);
+
+        let warehouse = &sources[0];
+        assert_eq!(warehouse.name, "Склад");
+        let remains = &warehouse.tables[0];
+        assert!(!remains.object);
+        assert_eq!(
+            remains.fields,
+            [
+                ExternalField {
+                    name: "Количество".to_string(),
+                    types: vec!["Число".to_string()],
+                },
+                ExternalField {
+                    name: "Товар".to_string(),
+                    types: vec!["СправочникСсылка.Номенклатура".to_string()],
+                },
+            ]
+        );
+        // Файла функции нет — известно только имя
+        assert_eq!(warehouse.functions, [function("КурсНаДату".to_string())]);
+
+        let cash = &sources[1];
+        assert_eq!(cash.name, "Касса");
+        assert!(cash.tables[0].object);
+        assert_eq!(cash.tables[0].fields[0].types, ["Число"]);
+        assert_eq!(cash.functions[0].return_types, ["Строка"]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Вывод фасетов типа по набору его членов
+//!
+//! Синтакс-помощник не хранит фасеты явно: парсер угадывает их по имени
+//! типа (`...Менеджер`, `...Объект`, `...Ссылка`). Для остальных типов
+//! фасет выводится по графу «тип → члены»: `СоздатьЭлемент`/`НайтиПо*`
+//! характерны для менеджера, `Записать`/`Удалить` — для объекта,
+//! `ПолучитьОбъект`/`Пустая` — для ссылки. Каждый совпавший член
+//! повышает уверенность, фасет принимается при уверенности не ниже порога.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::domain::types::FacetKind;
+
+/// Минимальная уверенность вывода: два характерных члена
+pub const MIN_FACET_CONFIDENCE: f32 = 0.75;
+
+/// Характерные члены фасетов; `*` в конце — префикс имени
+const MEMBER_PATTERNS: &[(FacetKind, &[&str])] = &[
+    (
+        FacetKind::Manager,
+        &[
+            "СоздатьЭлемент",
+            "СоздатьГруппу",
+            "СоздатьДокумент",
+            "СоздатьНаборЗаписей",
+            "СоздатьМенеджерЗаписи",
+            "НайтиПо*",
+            "Выбрать",
+            "ВыбратьИерархически",
+            "ПустаяСсылка",
+            "ПолучитьФормуВыбора",
+            "ПолучитьФормуСписка",
+            "CreateItem",
+            "CreateFolder",
+            "CreateDocument",
+            "CreateRecordSet",
+            "CreateRecordManager",
+            "FindBy*",
+            "Select",
+            "SelectHierarchically",
+            "EmptyRef",
+            "GetChoiceForm",
+            "GetListForm",
+        ],
+    ),
+    (
+        FacetKind::Object,
+        &[
+            "Записать",
+            "Удалить",
+            "Прочитать",
+            "Заполнить",
+            "ПроверитьЗаполнение",
+            "УстановитьПометкуУдаления",
+            "Модифицированность",
+            "УстановитьНовыйКод",
+            "УстановитьНовыйНомер",
+            "Write",
+            "Delete",
+            "Read",
+            "Fill",
+            "CheckFilling",
+            "SetDeletionMark",
+            "Modified",
+            "SetNewCode",
+            "SetNewNumber",
+        ],
+    ),
+    (
+        FacetKind::Reference,
+        &[
+            "ПолучитьОбъект",
+            "Пустая",
+            "ВерсияДанных",
+            "GetObject",
+            "IsEmpty",
+            "DataVersion",
+        ],
+    ),
+];
+
+/// Фасет, выведенный по членам типа
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferredFacet {
+    pub kind: FacetKind,
+    /// Уверенность вывода (0.0 - 1.0)
+    pub confidence: f32,
+    /// Члены типа, на которых основан вывод
+    pub evidence: Vec<String>,
+}
+
+fn matches(pattern: &str, member: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => {
+            member.len() > prefix.len() && member.to_lowercase().starts_with(&prefix.to_lowercase())
+        }
+        None => member.to_lowercase() == pattern.to_lowercase(),
+    }
+}
+
+/// Вывести фасеты по именам членов (методов и свойств) типа; фасеты с
+/// уверенностью ниже [`MIN_FACET_CONFIDENCE`] отбрасываются
+pub fn infer_facets<S: AsRef<str>>(members: &[S]) -> Vec<InferredFacet> {
+    let mut inferred = Vec::new();
+    for (kind, patterns) in MEMBER_PATTERNS {
+        let evidence: BTreeSet<&str> = members
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|member| patterns.iter().any(|pattern| matches(pattern, member)))
+            .collect();
+        // Каждый следующий характерный член вдвое сокращает сомнение
+        let confidence = 1.0 - 0.5f32.powi(evidence.len() as i32);
+        if !evidence.is_empty() && confidence >= MIN_FACET_CONFIDENCE {
+            inferred.push(InferredFacet {
+                kind: *kind,
+                confidence,
+                evidence: evidence.into_iter().map(str::to_string).collect(),
+            });
+        }
+    }
+    inferred
+}
+
+/// Члены типов по имени типа
+#[derive(Debug, Clone, Default)]
+pub struct MemberGraph {
+    members: HashMap<String, BTreeSet<String>>,
+}
+
+impl MemberGraph {
+    pub fn add(&mut self, type_name: &str, member: &str) {
+        if type_name.is_empty() || member.is_empty() {
+            return;
+        }
+        self.members
+            .entry(type_name.to_string())
+            .or_default()
+            .insert(member.to_string());
+    }
+
+    /// Члены типа, включая перечисленные в его собственной странице
+    pub fn members_of(&self, type_name: &str, own: &[String]) -> Vec<String> {
+        let mut members: BTreeSet<String> = own.iter().cloned().collect();
+        if let Some(linked) = self.members.get(type_name) {
+            members.extend(linked.iter().cloned());
+        }
+        members.into_iter().collect()
+    }
+}
+
+/// Граф «тип → члены» по страницам методов и свойств, озаглавленным
+/// «Тип.Член» (для менеджеров — «СправочникМенеджер.<Имя>.Член»)
+pub fn member_graph<'a>(member_titles: impl IntoIterator<Item = &'a str>) -> MemberGraph {
+    let mut graph = MemberGraph::default();
+    for title in member_titles {
+        let russian = title.split('(').next().unwrap_or_default().trim();
+        if let Some((type_name, member)) = russian.rsplit_once('.') {
+            graph.add(type_name.trim(), member.trim());
+        }
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_facets_from_members() {
+        let graph = member_graph([
+            "СправочникМенеджер.<Имя>.СоздатьЭлемент",
+            "СправочникМенеджер.<Имя>.НайтиПоКоду (CatalogManager.<Name>.FindByCode)",
+            "СправочникМенеджер.<Имя>.НайтиПоНаименованию",
+            "ТаблицаЗначений.Удалить (ValueTable.Delete)",
+            "ТаблицаЗначений.Добавить (ValueTable.Add)",
+        ]);
+
+        let members = graph.members_of("СправочникМенеджер.<Имя>", &[]);
+        let manager = infer_facets(&members);
+        assert_eq!(manager.len(), 1);
+        assert_eq!(manager[0].kind, FacetKind::Manager);
+        assert_eq!(manager[0].confidence, 0.875);
+        assert_eq!(manager[0].evidence.len(), 3);
+
+        // Один `Удалить` у коллекции — недостаточно для фасета объекта
+        assert!(infer_facets(&graph.members_of("ТаблицаЗначений", &[])).is_empty());
+
+        let object = infer_facets(&["Записать", "Прочитать", "Ссылка"]);
+        assert_eq!(object[0].kind, FacetKind::Object);
+        assert_eq!(object[0].confidence, 0.75);
+        assert_eq!(object[0].evidence, ["Записать", "Прочитать"]);
+    }
+}
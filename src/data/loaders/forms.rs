@@ -0,0 +1,484 @@
+//! Обработчики событий управляемой формы
+//!
+//! Назначения читаются из описания формы: выгрузка конфигуратора
+//! (`Forms/<Имя>/Ext/Form.xml`: `<Events><Event name="OnOpen">ПриОткрытии</Event>`
+//! у формы и элементов, `<Command><Action>` у команд) или проект EDT
+//! (`Forms/<Имя>/Form.form`: `<handlers><event>`/`<name>` и
+//! `<action><handler><name>` у команд). Модуль формы лежит рядом
+//! (`Ext/Form/Module.bsl` и `Module.bsl` соответственно), поэтому описание
+//! находится по пути модуля.
+//!
+//! Кроме обработчиков из описания берутся элементы с их видом
+//! (`<InputField>` конфигуратора, `xsi:type="form:FormField"` EDT) и
+//! реквизиты формы с типами (`<Attributes><Attribute>`, `<attributes>`):
+//! по ним типизируются `Элементы.<Имя>` и реквизиты в модуле формы.
+
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::metadata_values::value_type_name;
+
+/// Типы данных формы: имя в XML и имя в BSL. Основной реквизит объекта
+/// типизируется как сам объект, чтобы `Объект.Реквизит` разрешался
+const FORM_DATA_KINDS: &[(&str, &str)] = &[
+    ("DocumentObject", "ДокументОбъект"),
+    ("CatalogObject", "СправочникОбъект"),
+    ("DataProcessorObject", "ОбработкаОбъект"),
+    ("ReportObject", "ОтчетОбъект"),
+    ("InformationRegisterRecordManager", "РегистрСведенийМенеджерЗаписи"),
+    ("DynamicList", "ДинамическийСписок"),
+    ("ValueTable", "ТаблицаЗначений"),
+    ("ValueTree", "ДеревоЗначений"),
+    ("ValueList", "СписокЗначений"),
+    ("FormattedString", "ФорматированнаяСтрока"),
+];
+
+/// Владелец события
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum HandlerOwner {
+    Form,
+    /// Элемент формы (`Контрагент`)
+    Item(String),
+    /// Команда формы: событие — `Action`
+    Command(String),
+}
+
+/// Назначенный в форме обработчик
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FormHandler {
+    pub owner: HandlerOwner,
+    /// Имя события в описании (`OnCreateAtServer`, `OnChange`)
+    pub event: String,
+    /// Имя процедуры модуля формы
+    pub procedure: String,
+}
+
+/// Элемент формы с типом, которым он виден в `Элементы`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FormElement {
+    pub name: String,
+    /// `ПолеФормы`, `ТаблицаФормы`, `ГруппаФормы`, `КнопкаФормы` или
+    /// `ДекорацияФормы`
+    pub type_name: String,
+}
+
+/// Реквизит формы
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FormAttribute {
+    pub name: String,
+    /// Имена типов BSL; несколько — составной тип
+    pub types: Vec<String>,
+    /// Основной реквизит формы (обычно `Объект`)
+    pub main: bool,
+}
+
+/// Описание формы: назначенные обработчики, элементы и реквизиты
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FormDescription {
+    pub handlers: Vec<FormHandler>,
+    pub items: Vec<String>,
+    pub elements: Vec<FormElement>,
+    pub attributes: Vec<FormAttribute>,
+}
+
+/// Открытый тег описания формы
+#[derive(Default)]
+struct Frame {
+    tag: String,
+    /// Имя элемента или команды: атрибут `name` (конфигуратор) или
+    /// дочерний `<name>` (EDT)
+    name: Option<String>,
+    /// EDT: `<handlers><event>`
+    event: Option<String>,
+    /// EDT: `<handlers><name>`, `<handler><name>`
+    procedure: Option<String>,
+    /// EDT: `xsi:type` элемента (`form:FormField`)
+    kind: Option<String>,
+    /// Типы реквизита формы
+    types: Vec<String>,
+    /// Основной реквизит формы
+    main: bool,
+}
+
+fn name_attribute(e: &BytesStart) -> Option<String> {
+    e.attributes()
+        .filter_map(|a| a.ok())
+        .find(|a| a.key.as_ref() == b"name")
+        .and_then(|a| a.unescape_value().ok())
+        .map(|value| value.to_string())
+}
+
+/// `xsi:type` элемента EDT без префикса пространства имён
+fn type_attribute(e: &BytesStart) -> Option<String> {
+    e.attributes()
+        .filter_map(|a| a.ok())
+        .find(|a| a.key.local_name().as_ref() == b"type")
+        .and_then(|a| a.unescape_value().ok())
+        .map(|value| value.rsplit(':').next().unwrap_or_default().to_string())
+}
+
+/// Тип элемента по тегу конфигуратора (`InputField`, `Table`) или
+/// `xsi:type` EDT (`FormField`, `FormGroup`)
+fn element_type(kind: &str) -> &'static str {
+    match kind {
+        "Table" => "ТаблицаФормы",
+        "Button" => "КнопкаФормы",
+        kind if kind.ends_with("Field") => "ПолеФормы",
+        kind if kind.ends_with("Decoration") => "ДекорацияФормы",
+        _ => "ГруппаФормы",
+    }
+}
+
+/// Имя типа BSL реквизита формы (`cfg:DocumentObject.Заказ`,
+/// `v8:ValueTable`, `xs:string`)
+pub fn attribute_type_name(xml_type: &str) -> String {
+    let name = xml_type.trim();
+    let name = name.rsplit_once(':').map_or(name, |(_, name)| name);
+    let (kind, object) = match name.split_once('.') {
+        Some((kind, object)) => (kind, Some(object)),
+        None => (name, None),
+    };
+    match FORM_DATA_KINDS.iter().find(|(xml, _)| *xml == kind) {
+        Some((_, bsl)) => match object {
+            Some(object) => format!("{}.{}", bsl, object),
+            None => bsl.to_string(),
+        },
+        None => value_type_name(name),
+    }
+}
+
+/// Реквизит формы, которому принадлежит текущий тег: ближайший
+/// именованный предок, если это реквизит, а не его колонка
+fn attribute_frame(stack: &mut [Frame]) -> Option<&mut Frame> {
+    let frame = stack.iter_mut().rev().find(|frame| frame.name.is_some())?;
+    matches!(frame.tag.as_str(), "Attribute" | "attributes").then_some(frame)
+}
+
+/// Владелец события по ближайшему именованному предку
+fn owner(stack: &[Frame]) -> HandlerOwner {
+    match stack.iter().rev().find(|frame| frame.name.is_some()) {
+        Some(frame) if frame.tag == "Command" || frame.tag == "formCommands" => {
+            HandlerOwner::Command(frame.name.clone().unwrap_or_default())
+        }
+        Some(frame) => HandlerOwner::Item(frame.name.clone().unwrap_or_default()),
+        None => HandlerOwner::Form,
+    }
+}
+
+/// Разобрать описание формы конфигуратора (`Form.xml`) или EDT (`Form.form`)
+pub fn parse_form(content: &str) -> Result<FormDescription> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut form = FormDescription::default();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let tag = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                // Атрибут `name` у событий — имя события, а не владельца
+                let name = (tag != "Event").then(|| name_attribute(&e)).flatten();
+                if let Some(name) = &name {
+                    if stack.last().is_some_and(|parent| parent.tag == "ChildItems") {
+                        form.items.push(name.clone());
+                        form.elements.push(FormElement {
+                            name: name.clone(),
+                            type_name: element_type(&tag).to_string(),
+                        });
+                    }
+                }
+                let event = (tag == "Event").then(|| name_attribute(&e)).flatten();
+                let kind = (tag == "items").then(|| type_attribute(&e)).flatten();
+                stack.push(Frame {
+                    tag,
+                    name,
+                    event,
+                    kind,
+                    ..Frame::default()
+                });
+            }
+            Ok(Event::End(_)) => {
+                let Some(frame) = stack.pop() else {
+                    continue;
+                };
+                let event = match frame.tag.as_str() {
+                    "handlers" => frame.event,
+                    // EDT: formCommands/action/handler/name
+                    "handler" if stack.last().is_some_and(|p| p.tag == "action") => {
+                        Some("Action".to_string())
+                    }
+                    _ => None,
+                };
+                if let (Some(event), Some(procedure)) = (event, frame.procedure) {
+                    form.handlers.push(FormHandler {
+                        owner: owner(&stack),
+                        event,
+                        procedure,
+                    });
+                }
+                if frame.tag == "items" {
+                    if let Some(name) = &frame.name {
+                        form.elements.push(FormElement {
+                            name: name.clone(),
+                            type_name: element_type(frame.kind.as_deref().unwrap_or_default())
+                                .to_string(),
+                        });
+                    }
+                    form.items.extend(frame.name);
+                } else if let ("Attribute" | "attributes", Some(name)) =
+                    (frame.tag.as_str(), frame.name)
+                {
+                    form.attributes.push(FormAttribute {
+                        name,
+                        types: frame.types,
+                        main: frame.main,
+                    });
+                }
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                let depth = stack.len();
+                if depth < 2 {
+                    continue;
+                }
+                let parent_tag = stack[depth - 2].tag.clone();
+                let tag = stack[depth - 1].tag.clone();
+                match (parent_tag.as_str(), tag.as_str()) {
+                    // Конфигуратор: <Event name="OnOpen">ПриОткрытии</Event>
+                    (_, "Event") => form.handlers.push(FormHandler {
+                        owner: owner(&stack[..depth - 1]),
+                        event: stack[depth - 1].event.clone().unwrap_or_default(),
+                        procedure: text,
+                    }),
+                    ("Command", "Action") => form.handlers.push(FormHandler {
+                        owner: owner(&stack[..depth - 1]),
+                        event: "Action".to_string(),
+                        procedure: text,
+                    }),
+                    ("handlers", "event") => stack[depth - 2].event = Some(text),
+                    ("handlers" | "handler", "name") => stack[depth - 2].procedure = Some(text),
+                    ("items" | "formCommands" | "attributes" | "columns", "name") => {
+                        stack[depth - 2].name = Some(text)
+                    }
+                    // Конфигуратор: <Type><v8:Type>, EDT: <valueType><types>
+                    ("Type", "Type") | ("valueType", "types") => {
+                        if let Some(attribute) = attribute_frame(&mut stack) {
+                            attribute.types.push(attribute_type_name(&text));
+                        }
+                    }
+                    ("Attribute", "MainAttribute") | ("attributes", "main") => {
+                        stack[depth - 2].main = text == "true"
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Ошибка XML формы на позиции {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    form.handlers.retain(|handler| !handler.procedure.is_empty());
+    Ok(form)
+}
+
+/// Файл описания формы по пути её модуля: `Ext/Form/Module.bsl` →
+/// `Ext/Form.xml`, `Forms/<Имя>/Module.bsl` (EDT) → `Form.form`
+pub fn form_file(module_path: &Path) -> Option<PathBuf> {
+    let dir = module_path.parent()?;
+    let file = if dir.file_name()? == "Form" {
+        dir.parent()?.join("Form.xml")
+    } else {
+        dir.join("Form.form")
+    };
+    file.is_file().then_some(file)
+}
+
+impl FormDescription {
+    /// Описание формы, модулем которой является файл; `None` — это не
+    /// модуль формы
+    pub fn load_for_module(module_path: &Path) -> Result<Option<Self>> {
+        let Some(file) = form_file(module_path) else {
+            return Ok(None);
+        };
+        let content = std::fs::read_to_string(&file)
+            .with_context(|| format!("Не удалось прочитать {}", file.display()))?;
+        parse_form(&content)
+            .with_context(|| format!("Описание формы {}", file.display()))
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORM_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Form xmlns="http://v8.1c.ru/8.3/xcf/logform" version="2.17">
+  <Events>
+    <Event name="OnCreateAtServer">ПриСозданииНаСервере</Event>
+  </Events>
+  <ChildItems>
+    <InputField name="Контрагент" id="1">
+      <DataPath>Объект.Контрагент</DataPath>
+      <Events>
+        <Event name="OnChange">КонтрагентПриИзменении</Event>
+      </Events>
+    </InputField>
+  </ChildItems>
+  <Attributes>
+    <Attribute name="Объект" id="4">
+      <Type>
+        <v8:Type>cfg:DocumentObject.Заказ</v8:Type>
+      </Type>
+      <MainAttribute>true</MainAttribute>
+    </Attribute>
+    <Attribute name="Итоги" id="5">
+      <Type>
+        <v8:Type>v8:ValueTable</v8:Type>
+      </Type>
+      <Columns>
+        <Column name="Сумма" id="6">
+          <Type>
+            <v8:Type>xs:decimal</v8:Type>
+          </Type>
+        </Column>
+      </Columns>
+    </Attribute>
+  </Attributes>
+  <Commands>
+    <Command name="Заполнить" id="2">
+      <Action>Заполнить</Action>
+    </Command>
+  </Commands>
+</Form>"#;
+
+    const FORM_EDT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<form:Form xmlns:form="http://g5.1c.ru/v8/dt/form"
+    xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <items xsi:type="form:FormField">
+    <name>Контрагент</name>
+    <handlers>
+      <event>OnChange</event>
+      <name>КонтрагентПриИзменении</name>
+    </handlers>
+  </items>
+  <handlers>
+    <event>OnOpen</event>
+    <name>ПриОткрытии</name>
+  </handlers>
+  <attributes>
+    <name>Объект</name>
+    <valueType>
+      <types>DocumentObject.Заказ</types>
+    </valueType>
+    <main>true</main>
+  </attributes>
+  <attributes>
+    <name>Итоги</name>
+    <valueType>
+      <types>ValueTable</types>
+    </valueType>
+    <columns>
+      <name>Сумма</name>
+      <valueType>
+        <types>Number</types>
+      </valueType>
+    </columns>
+  </attributes>
+  <formCommands>
+    <name>Заполнить</name>
+    <action xsi:type="form:FormCommandHandlerContainer">
+      <handler>
+        <name>ЗаполнитьКоманда</name>
+      </handler>
+    </action>
+  </formCommands>
+</form:Form>"#;
+
+    fn handler(owner: HandlerOwner, event: &str, procedure: &str) -> FormHandler {
+        FormHandler {
+            owner,
+            event: event.to_string(),
+            procedure: procedure.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_designer_and_edt_forms() {
+        let item = HandlerOwner::Item("Контрагент".to_string());
+        let command = HandlerOwner::Command("Заполнить".to_string());
+
+        let form = parse_form(FORM_XML).unwrap();
+        assert_eq!(
+            form.handlers,
+            [
+                handler(HandlerOwner::Form, "OnCreateAtServer", "ПриСозданииНаСервере"),
+                handler(item.clone(), "OnChange", "КонтрагентПриИзменении"),
+                handler(command.clone(), "Action", "Заполнить"),
+            ]
+        );
+        assert_eq!(form.items, ["Контрагент"]);
+
+        let form = parse_form(FORM_EDT).unwrap();
+        assert_eq!(
+            form.handlers,
+            [
+                handler(item, "OnChange", "КонтрагентПриИзменении"),
+                handler(HandlerOwner::Form, "OnOpen", "ПриОткрытии"),
+                handler(command, "Action", "ЗаполнитьКоманда"),
+            ]
+        );
+        assert_eq!(form.items, ["Контрагент"]);
+    }
+
+    #[test]
+    fn test_parse_form_elements_and_attributes() {
+        let attributes = [
+            FormAttribute {
+                name: "Объект".to_string(),
+                types: vec!["ДокументОбъект.Заказ".to_string()],
+                main: true,
+            },
+            FormAttribute {
+                name: "Итоги".to_string(),
+                types: vec!["ТаблицаЗначений".to_string()],
+                main: false,
+            },
+        ];
+
+        let form = parse_form(FORM_XML).unwrap();
+        let elements: Vec<(&str, &str)> = form
+            .elements
+            .iter()
+            .map(|e| (e.name.as_str(), e.type_name.as_str()))
+            .collect();
+        assert_eq!(elements, [("Контрагент", "ПолеФормы")]);
+        assert_eq!(form.attributes, attributes);
+
+        let table = r#"<Form><ChildItems><UsualGroup name="Группа"><ChildItems>
+            <Table name="Товары"></Table></ChildItems></UsualGroup></ChildItems></Form>"#;
+        let form = parse_form(table).unwrap();
+        let types: Vec<&str> = form.elements.iter().map(|e| e.type_name.as_str()).collect();
+        assert_eq!(types, ["ГруппаФормы", "ТаблицаФормы"]);
+
+        let form = parse_form(FORM_EDT).unwrap();
+        assert_eq!(form.elements[0].type_name, "ПолеФормы");
+        assert_eq!(form.attributes, attributes);
+
+        assert_eq!(attribute_type_name("cfg:CatalogRef.Валюты"), "СправочникСсылка.Валюты");
+        assert_eq!(attribute_type_name("DynamicList"), "ДинамическийСписок");
+    }
+}
@@ -0,0 +1,255 @@
+//! Импорт закладок и избранного синтакс-помощника 1С
+//!
+//! Выгрузка — текст, одна закладка на строку. Закладка записывается путём
+//! раздела справки в одном из видов:
+//!
+//! ```text
+//! v8help://SyntaxHelperLanguage/objects/catalog125/ValueTable/methods/Find.html
+//! objects/catalog125/ValueTable.html
+//! Общие объекты / Универсальные коллекции значений / ТаблицаЗначений (ValueTable) / Методы / Найти (Find)
+//! Поиск строк<TAB>Общие объекты > ТаблицаЗначений > Методы > НайтиСтроки
+//! ```
+//!
+//! Перед путём через табуляцию (`<TAB>`) может стоять название закладки; пустые
+//! строки и строки, начинающиеся с `#` или `//`, пропускаются. Раздел
+//! сопоставляется с идентификатором типа репозитория по таблице имён
+//! (русское и английское имя типа, без учёта регистра); раздел члена
+//! (`methods/Find`, `Методы / Найти`) даёт тип и имя члена. Закладки, для
+//! которых тип не найден, возвращаются отдельно, чтобы пользователь видел,
+//! что не перенеслось.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::unified::data::RawTypeData;
+
+/// Разделы справки, после которых в пути идёт имя члена типа
+const MEMBER_SECTIONS: &[&str] = &[
+    "methods",
+    "методы",
+    "properties",
+    "свойства",
+    "events",
+    "события",
+    "ctors",
+    "конструкторы",
+];
+
+/// Закладка, сопоставленная с типом репозитория
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportedBookmark {
+    /// Название закладки или последний раздел пути
+    pub title: String,
+    /// Идентификатор типа в репозитории
+    pub type_id: String,
+    pub type_name: String,
+    /// Метод, свойство, событие или конструктор типа
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+}
+
+/// Итог импорта
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BookmarkImport {
+    pub bookmarks: Vec<ImportedBookmark>,
+    /// Строки выгрузки, для которых тип не найден
+    pub unmatched: Vec<String>,
+}
+
+/// Таблица имён типов репозитория: русское и английское имя → (id, имя)
+#[derive(Debug, Clone, Default)]
+pub struct HelpAliasTable {
+    names: HashMap<String, (String, String)>,
+}
+
+impl HelpAliasTable {
+    /// Таблица по типам репозитория; при совпадении имён остаётся тип,
+    /// встретившийся первым
+    pub fn from_types(types: &[RawTypeData]) -> Self {
+        let mut names = HashMap::new();
+        for raw in types {
+            for name in [&raw.russian_name, &raw.english_name] {
+                if name.trim().is_empty() {
+                    continue;
+                }
+                names
+                    .entry(name.trim().to_lowercase())
+                    .or_insert_with(|| (raw.id.clone(), raw.russian_name.clone()));
+            }
+        }
+        Self { names }
+    }
+
+    /// Тип по разделу справки: `ТаблицаЗначений (ValueTable)`, `ValueTable.html`
+    fn resolve(&self, section: &str) -> Option<&(String, String)> {
+        section_names(section)
+            .into_iter()
+            .find_map(|name| self.names.get(&name.to_lowercase()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Варианты имени раздела: без расширения `.html` и, для разделов вида
+/// `Русское (English)`, каждое имя отдельно
+fn section_names(section: &str) -> Vec<String> {
+    let section = section.trim();
+    let section = section
+        .strip_suffix(".html")
+        .or_else(|| section.strip_suffix(".htm"))
+        .unwrap_or(section)
+        .trim();
+    match section.strip_suffix(')').and_then(|s| s.rsplit_once('(')) {
+        Some((russian, english)) => vec![russian.trim().to_string(), english.trim().to_string()],
+        None => vec![section.to_string()],
+    }
+}
+
+/// Разделы пути: схема `v8help://` и имя справки отбрасываются, разделители
+/// `/`, `\` и `>`
+fn path_sections(path: &str) -> Vec<&str> {
+    let path = match path.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').map_or("", |(_, rest)| rest),
+        None => path,
+    };
+    path.split(['/', '\\', '>'])
+        .map(str::trim)
+        .filter(|section| !section.is_empty())
+        .collect()
+}
+
+/// Первое имя раздела для названия закладки
+fn display_name(section: &str) -> String {
+    section_names(section).swap_remove(0)
+}
+
+/// Сопоставить строку выгрузки с типом; `None` — тип не найден
+fn import_line(line: &str, table: &HelpAliasTable) -> Option<ImportedBookmark> {
+    let (title, path) = match line.split_once('\t') {
+        Some((title, path)) => (Some(title.trim()), path),
+        None => (None, line),
+    };
+    let sections = path_sections(path);
+
+    // Раздел члена: тип — перед ним, член — после
+    let member_at = sections.iter().rposition(|section| {
+        let section = section.to_lowercase();
+        MEMBER_SECTIONS.contains(&section.as_str())
+    });
+    let (type_index, member) = match member_at {
+        Some(index) if index > 0 && index + 1 < sections.len() => {
+            (index - 1, Some(display_name(sections[index + 1])))
+        }
+        // Раздел типа — последний из найденных в таблице
+        _ => (
+            sections
+                .iter()
+                .rposition(|section| table.resolve(section).is_some())?,
+            None,
+        ),
+    };
+    let (type_id, type_name) = table.resolve(sections[type_index])?.clone();
+    let title = title
+        .filter(|title| !title.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| match &member {
+            Some(member) => format!("{}.{}", type_name, member),
+            None => type_name.clone(),
+        });
+    Some(ImportedBookmark {
+        title,
+        type_id,
+        type_name,
+        member,
+    })
+}
+
+/// Импортировать выгрузку закладок; повторы (тот же тип и член) пропускаются
+pub fn import_bookmarks(content: &str, table: &HelpAliasTable) -> BookmarkImport {
+    let mut import = BookmarkImport::default();
+    let mut seen = HashSet::new();
+    for line in content.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim_end_matches('\r');
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("//") {
+            continue;
+        }
+        match import_line(line, table) {
+            Some(bookmark) => {
+                let key = (bookmark.type_id.clone(), bookmark.member.clone());
+                if seen.insert(key) {
+                    import.bookmarks.push(bookmark);
+                }
+            }
+            None => import.unmatched.push(line.trim().to_string()),
+        }
+    }
+    import
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unified::data::TypeSource;
+
+    fn raw(id: &str, russian: &str, english: &str) -> RawTypeData {
+        let platform = TypeSource::Platform {
+            version: "8.3".to_string(),
+        };
+        RawTypeData {
+            russian_name: russian.to_string(),
+            english_name: english.to_string(),
+            ..RawTypeData::test_type(id, platform)
+        }
+    }
+
+    #[test]
+    fn test_import_help_bookmarks() {
+        let table = HelpAliasTable::from_types(&[
+            raw("vt", "ТаблицаЗначений", "ValueTable"),
+            raw("arr", "Массив", "Array"),
+        ]);
+        let export = "\u{feff}# Избранное синтакс-помощника\n\
+            v8help://SyntaxHelperLanguage/objects/catalog125/ValueTable/methods/Find.html\r\n\
+            Общие объекты / Универсальные коллекции значений / ТаблицаЗначений (ValueTable) / \
+            Методы / Найти (Find)\n\
+            Коллекции\tобъекты > Массив\n\
+            objects/catalog125/Array.html\n\
+            \n\
+            Глобальный контекст / Методы / Сообщить\n";
+        let import = import_bookmarks(export, &table);
+        assert_eq!(
+            import.bookmarks,
+            [
+                ImportedBookmark {
+                    title: "ТаблицаЗначений.Find".to_string(),
+                    type_id: "vt".to_string(),
+                    type_name: "ТаблицаЗначений".to_string(),
+                    member: Some("Find".to_string()),
+                },
+                ImportedBookmark {
+                    title: "ТаблицаЗначений.Найти".to_string(),
+                    type_id: "vt".to_string(),
+                    type_name: "ТаблицаЗначений".to_string(),
+                    member: Some("Найти".to_string()),
+                },
+                ImportedBookmark {
+                    title: "Коллекции".to_string(),
+                    type_id: "arr".to_string(),
+                    type_name: "Массив".to_string(),
+                    member: None,
+                },
+            ]
+        );
+        assert_eq!(
+            import.unmatched,
+            ["Глобальный контекст / Методы / Сообщить"]
+        );
+    }
+}
@@ -0,0 +1,487 @@
+//! Флаги доступности объектов метаданных для аудита интеграций
+//!
+//! Из XML выгрузки конфигурации читаются свойства, от которых зависит,
+//! видят ли объект внешние системы и поиск: полнотекстовый поиск, история
+//! данных, стандартные команды, включение справки в содержание.
+//!
+//! Состав стандартного интерфейса OData в выгрузке не хранится — он
+//! задаётся в информационной базе (`УстановитьСоставСтандартногоИнтерфейсаOData`).
+//! Поэтому он читается из текстового списка полных имён объектов, например
+//! выгруженного результата `ПолучитьСоставСтандартногоИнтерфейсаOData()`;
+//! без такого списка признак OData остаётся неизвестным.
+
+use anyhow::{Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Виды объектов с данными: каталог выгрузки, вид, русское имя вида
+const DATA_KINDS: &[(&str, &str, &str)] = &[
+    ("Catalogs", "Catalog", "Справочник"),
+    ("Documents", "Document", "Документ"),
+    ("DocumentJournals", "DocumentJournal", "ЖурналДокументов"),
+    ("Enums", "Enum", "Перечисление"),
+    ("Constants", "Constant", "Константа"),
+    (
+        "InformationRegisters",
+        "InformationRegister",
+        "РегистрСведений",
+    ),
+    (
+        "AccumulationRegisters",
+        "AccumulationRegister",
+        "РегистрНакопления",
+    ),
+    (
+        "AccountingRegisters",
+        "AccountingRegister",
+        "РегистрБухгалтерии",
+    ),
+    (
+        "CalculationRegisters",
+        "CalculationRegister",
+        "РегистрРасчета",
+    ),
+    ("ChartsOfAccounts", "ChartOfAccounts", "ПланСчетов"),
+    (
+        "ChartsOfCharacteristicTypes",
+        "ChartOfCharacteristicTypes",
+        "ПланВидовХарактеристик",
+    ),
+    (
+        "ChartsOfCalculationTypes",
+        "ChartOfCalculationTypes",
+        "ПланВидовРасчета",
+    ),
+    ("BusinessProcesses", "BusinessProcess", "БизнесПроцесс"),
+    ("Tasks", "Task", "Задача"),
+    ("ExchangePlans", "ExchangePlan", "ПланОбмена"),
+];
+
+/// Флаги объекта; `None` — свойство не задано в выгрузке (или, для OData,
+/// состав интерфейса не загружен)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataFlags {
+    /// Входит в состав стандартного интерфейса OData
+    pub standard_odata: Option<bool>,
+    /// Использовать полнотекстовый поиск (`FullTextSearch`)
+    pub full_text_search: Option<bool>,
+    /// История данных (`DataHistory`)
+    pub data_history: Option<bool>,
+    /// Использовать стандартные команды (`UseStandardCommands`)
+    pub use_standard_commands: Option<bool>,
+    /// Включать справку в содержание (`IncludeHelpInContents`)
+    pub include_help_in_contents: Option<bool>,
+}
+
+impl MetadataFlags {
+    /// Учесть свойство объекта из раздела `Properties`; `false`, если
+    /// свойство к флагам не относится
+    pub fn apply_property(&mut self, property: &str, value: &str) -> bool {
+        let flag = match property {
+            "FullTextSearch" => &mut self.full_text_search,
+            "DataHistory" => &mut self.data_history,
+            "UseStandardCommands" => &mut self.use_standard_commands,
+            "IncludeHelpInContents" => &mut self.include_help_in_contents,
+            _ => return false,
+        };
+        *flag = match value.trim() {
+            "Use" | "true" => Some(true),
+            "DontUse" | "false" => Some(false),
+            _ => None,
+        };
+        true
+    }
+
+    /// Заметки для `ResolutionMetadata::notes` (`flag:full_text_search=true`)
+    pub fn notes(&self) -> Vec<String> {
+        [
+            ("standard_odata", self.standard_odata),
+            ("full_text_search", self.full_text_search),
+            ("data_history", self.data_history),
+            ("use_standard_commands", self.use_standard_commands),
+            ("include_help_in_contents", self.include_help_in_contents),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|v| format!("flag:{}={}", name, v)))
+        .collect()
+    }
+}
+
+/// Флаги одного объекта метаданных
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectFlags {
+    /// Полное имя: `Catalog.Контрагенты`
+    pub object: String,
+    pub kind: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub synonym: Option<String>,
+    pub flags: MetadataFlags,
+}
+
+/// Фильтр отчёта: заданные условия должны выполняться одновременно
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MetadataFlagsFilter {
+    /// Вид объекта (`Catalog` или `Справочник`)
+    pub kind: Option<String>,
+    /// Подстрока имени или синонима
+    pub q: Option<String>,
+    pub odata: Option<bool>,
+    pub full_text_search: Option<bool>,
+    pub data_history: Option<bool>,
+    pub standard_commands: Option<bool>,
+}
+
+impl MetadataFlagsFilter {
+    fn matches(&self, object: &ObjectFlags) -> bool {
+        let flag = |wanted: Option<bool>, actual: Option<bool>| {
+            wanted.is_none_or(|wanted| actual == Some(wanted))
+        };
+        let kind_matches = self
+            .kind
+            .as_deref()
+            .is_none_or(|kind| english_kind(kind).is_some_and(|kind| kind == object.kind));
+        let text_matches = self.q.as_deref().is_none_or(|q| {
+            let q = q.to_lowercase();
+            object.name.to_lowercase().contains(&q)
+                || object
+                    .synonym
+                    .as_deref()
+                    .is_some_and(|s| s.to_lowercase().contains(&q))
+        });
+        kind_matches
+            && text_matches
+            && flag(self.odata, object.flags.standard_odata)
+            && flag(self.full_text_search, object.flags.full_text_search)
+            && flag(self.data_history, object.flags.data_history)
+            && flag(self.standard_commands, object.flags.use_standard_commands)
+    }
+}
+
+/// Сколько объектов с включённым флагом
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FlagCounts {
+    pub standard_odata: usize,
+    pub full_text_search: usize,
+    pub data_history: usize,
+    pub use_standard_commands: usize,
+}
+
+/// Отчёт по флагам для аудита интеграций
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataFlagsReport {
+    /// Всего объектов с данными в конфигурации
+    pub total: usize,
+    /// Загружен ли состав стандартного интерфейса OData
+    pub odata_composition_loaded: bool,
+    /// Счётчики по отобранным объектам
+    pub counts: FlagCounts,
+    pub objects: Vec<ObjectFlags>,
+}
+
+/// Вид объекта по английскому или русскому имени
+fn english_kind(kind: &str) -> Option<&'static str> {
+    let kind = kind.trim().to_lowercase();
+    DATA_KINDS
+        .iter()
+        .find(|(_, english, russian)| {
+            english.to_lowercase() == kind || russian.to_lowercase() == kind
+        })
+        .map(|(_, english, _)| *english)
+}
+
+/// Полное имя объекта в английской форме: `Справочник.Товары.ТабличнаяЧасть.Цены`
+/// → `Catalog.Товары`
+pub fn normalize_full_name(full_name: &str) -> Option<String> {
+    let mut parts = full_name.trim().split('.');
+    let kind = english_kind(parts.next()?)?;
+    let name = parts.next()?.trim();
+    (!name.is_empty()).then(|| format!("{}.{}", kind, name))
+}
+
+/// Разобрать список состава OData: по полному имени объекта в строке,
+/// пустые строки и комментарии (`//`, `#`) пропускаются
+pub fn parse_odata_composition(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//") && !line.starts_with('#'))
+        .filter_map(normalize_full_name)
+        .collect()
+}
+
+/// Флаги объектов конфигурации
+#[derive(Debug, Clone, Default)]
+pub struct MetadataFlagsIndex {
+    objects: Vec<ObjectFlags>,
+    odata_composition_loaded: bool,
+}
+
+impl MetadataFlagsIndex {
+    /// Прочитать флаги объектов из выгрузки конфигурации (каталог с
+    /// Configuration.xml)
+    pub fn load(config_path: &Path) -> Result<Self> {
+        let mut objects = Vec::new();
+        for (directory, kind, _) in DATA_KINDS {
+            let dir = config_path.join(directory);
+            if !dir.is_dir() {
+                continue;
+            }
+            let mut entries: Vec<_> = std::fs::read_dir(&dir)
+                .with_context(|| format!("Не удалось прочитать {}", dir.display()))?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "xml"))
+                .collect();
+            entries.sort();
+            for xml_path in entries {
+                let content = std::fs::read_to_string(&xml_path)
+                    .with_context(|| format!("Не удалось прочитать {}", xml_path.display()))?;
+                if let Some(object) = parse_object_flags(&content, kind)? {
+                    objects.push(object);
+                }
+            }
+        }
+        Ok(Self::from_objects(objects))
+    }
+
+    pub fn from_objects(objects: Vec<ObjectFlags>) -> Self {
+        Self {
+            objects,
+            odata_composition_loaded: false,
+        }
+    }
+
+    /// Отметить объекты, входящие в стандартный интерфейс OData
+    /// (полные имена в русской или английской форме)
+    pub fn apply_odata_composition(&mut self, full_names: &[String]) {
+        let composition: HashSet<String> = full_names
+            .iter()
+            .filter_map(|name| normalize_full_name(name))
+            .collect();
+        for object in &mut self.objects {
+            object.flags.standard_odata = Some(composition.contains(&object.object));
+        }
+        self.odata_composition_loaded = true;
+    }
+
+    /// Прочитать состав OData из файла (см. [`parse_odata_composition`])
+    pub fn load_odata_composition(&mut self, path: &Path) -> Result<()> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать состав OData {}", path.display()))?;
+        self.apply_odata_composition(&parse_odata_composition(&text));
+        Ok(())
+    }
+
+    pub fn objects(&self) -> &[ObjectFlags] {
+        &self.objects
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Объект по имени типа: `Контрагенты`, `Catalog.Контрагенты`,
+    /// `СправочникСсылка.Контрагенты`
+    pub fn find(&self, type_name: &str) -> Option<&ObjectFlags> {
+        let (prefix, name) = match type_name.split_once('.') {
+            Some((prefix, name)) => (Some(prefix.to_lowercase()), name),
+            None => (None, type_name),
+        };
+        let name = name.trim().to_lowercase();
+        self.objects.iter().find(|object| {
+            if object.name.to_lowercase() != name {
+                return false;
+            }
+            let Some(prefix) = prefix.as_deref() else {
+                return true;
+            };
+            // Префиксы вида с суффиксом фасета: СправочникСсылка, CatalogRef
+            DATA_KINDS.iter().any(|(_, english, russian)| {
+                *english == object.kind
+                    && (prefix.starts_with(&english.to_lowercase())
+                        || prefix.starts_with(&russian.to_lowercase()))
+            })
+        })
+    }
+
+    /// Отчёт по объектам, прошедшим фильтр
+    pub fn report(&self, filter: &MetadataFlagsFilter) -> MetadataFlagsReport {
+        let objects: Vec<ObjectFlags> = self
+            .objects
+            .iter()
+            .filter(|object| filter.matches(object))
+            .cloned()
+            .collect();
+        let count = |flag: fn(&MetadataFlags) -> Option<bool>| {
+            objects
+                .iter()
+                .filter(|o| flag(&o.flags) == Some(true))
+                .count()
+        };
+        MetadataFlagsReport {
+            total: self.objects.len(),
+            odata_composition_loaded: self.odata_composition_loaded,
+            counts: FlagCounts {
+                standard_odata: count(|f| f.standard_odata),
+                full_text_search: count(|f| f.full_text_search),
+                data_history: count(|f| f.data_history),
+                use_standard_commands: count(|f| f.use_standard_commands),
+            },
+            objects,
+        }
+    }
+}
+
+/// Разобрать флаги из XML объекта вида `kind` (None, если это не объект
+/// этого вида или у него нет имени)
+pub fn parse_object_flags(content: &str, kind: &str) -> Result<Option<ObjectFlags>> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut name = None;
+    let mut synonym = None;
+    let mut flags = MetadataFlags::default();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                path.push(String::from_utf8_lossy(e.local_name().as_ref()).to_string());
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                // Свойства самого объекта: MetaDataObject/<Вид>/Properties/<Свойство>
+                let own_property = path.len() >= 4 && path[1] == kind && path[2] == "Properties";
+                if text.is_empty() || !own_property {
+                    continue;
+                }
+                match (path.len(), path[3].as_str()) {
+                    (4, "Name") => name = Some(text),
+                    (4, property) => {
+                        flags.apply_property(property, &text);
+                    }
+                    (_, "Synonym") if path.last().is_some_and(|t| t == "content") => {
+                        synonym.get_or_insert(text);
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Ошибка XML объекта на позиции {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(name.map(|name| ObjectFlags {
+        object: format!("{}.{}", kind, name),
+        kind: kind.to_string(),
+        name,
+        synonym,
+        flags,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<MetaDataObject xmlns="http://v8.1c.ru/8.3/MDClasses" xmlns:v8="http://v8.1c.ru/8.1/data/core">
+  <Catalog uuid="e3cb">
+    <Properties>
+      <Name>Контрагенты</Name>
+      <Synonym>
+        <v8:item>
+          <v8:lang>ru</v8:lang>
+          <v8:content>Контрагенты</v8:content>
+        </v8:item>
+      </Synonym>
+      <UseStandardCommands>true</UseStandardCommands>
+      <FullTextSearchOnInputByString>DontUse</FullTextSearchOnInputByString>
+      <FullTextSearch>Use</FullTextSearch>
+      <DataHistory>DontUse</DataHistory>
+    </Properties>
+    <ChildObjects>
+      <Attribute>
+        <Properties>
+          <Name>ИНН</Name>
+          <FullTextSearch>DontUse</FullTextSearch>
+        </Properties>
+      </Attribute>
+    </ChildObjects>
+  </Catalog>
+</MetaDataObject>"#;
+
+    #[test]
+    fn test_parse_object_flags() {
+        let object = parse_object_flags(CATALOG_XML, "Catalog").unwrap().unwrap();
+        assert_eq!(object.object, "Catalog.Контрагенты");
+        assert_eq!(object.synonym.as_deref(), Some("Контрагенты"));
+        // Флаги реквизитов не перекрывают флаги объекта
+        assert_eq!(object.flags.full_text_search, Some(true));
+        assert_eq!(object.flags.data_history, Some(false));
+        assert_eq!(object.flags.use_standard_commands, Some(true));
+        assert_eq!(object.flags.include_help_in_contents, None);
+        assert_eq!(object.flags.standard_odata, None);
+
+        assert!(parse_object_flags(CATALOG_XML, "Document")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_odata_composition_and_report() {
+        let catalog = parse_object_flags(CATALOG_XML, "Catalog").unwrap().unwrap();
+        let document = ObjectFlags {
+            object: "Document.Заказ".to_string(),
+            kind: "Document".to_string(),
+            name: "Заказ".to_string(),
+            synonym: None,
+            flags: MetadataFlags::default(),
+        };
+        let mut index = MetadataFlagsIndex::from_objects(vec![catalog, document]);
+        let composition = "// состав OData
+Справочник.Контрагенты
+Справочник.Контрагенты.ТабличнаяЧасть.Счета
+";
+        index.apply_odata_composition(&parse_odata_composition(composition));
+
+        let report = index.report(&MetadataFlagsFilter {
+            odata: Some(true),
+            ..Default::default()
+        });
+        assert!(report.odata_composition_loaded);
+        assert_eq!(report.total, 2);
+        assert_eq!(report.objects.len(), 1);
+        assert_eq!(report.counts.full_text_search, 1);
+
+        let report = index.report(&MetadataFlagsFilter {
+            kind: Some("Документ".to_string()),
+            odata: Some(false),
+            ..Default::default()
+        });
+        assert_eq!(report.objects[0].object, "Document.Заказ");
+
+        assert!(index.find("СправочникСсылка.Контрагенты").is_some());
+        assert!(index.find("ДокументСсылка.Контрагенты").is_none());
+        assert_eq!(
+            index.find("заказ").map(|o| o.kind.as_str()),
+            Some("Document")
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Константы и параметры сеанса конфигурации с объявленными типами значений
+//!
+//! Описания читаются из выгрузки конфигуратора (`Constants/<Имя>.xml`,
+//! `SessionParameters/<Имя>.xml`) или из проекта EDT
+//! (`src/Constants/<Имя>/<Имя>.mdo`). Тип значения берётся из свойства
+//! `Type` и переводится в имя типа BSL (`cfg:CatalogRef.Валюты` →
+//! `СправочникСсылка.Валюты`), чтобы `Константы.Имя.Получить()` и
+//! `ПараметрыСеанса.Имя` разрешались в объявленный тип, а не в динамический.
+
+use anyhow::{Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::Regex;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Префиксы ссылочных типов: имя в XML и имя в BSL
+const REFERENCE_KINDS: &[(&str, &str)] = &[
+    ("CatalogRef", "СправочникСсылка"),
+    ("DocumentRef", "ДокументСсылка"),
+    ("EnumRef", "ПеречислениеСсылка"),
+    ("ChartOfCharacteristicTypesRef", "ПланВидовХарактеристикСсылка"),
+    ("ChartOfAccountsRef", "ПланСчетовСсылка"),
+    ("ChartOfCalculationTypesRef", "ПланВидовРасчетаСсылка"),
+    ("ExchangePlanRef", "ПланОбменаСсылка"),
+    ("BusinessProcessRef", "БизнесПроцессСсылка"),
+    ("TaskRef", "ЗадачаСсылка"),
+    ("ExternalDataSourceTableRef", "ВнешнийИсточникДанныхТаблицаСсылка"),
+    ("DefinedType", "ОпределяемыйТип"),
+];
+
+/// Константа или параметр сеанса
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeclaredValue {
+    pub name: String,
+    /// Имена типов BSL; несколько — составной тип
+    pub types: Vec<String>,
+}
+
+/// Обращение к значению в коде
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataValueRef {
+    /// `Константы.Имя.Получить()`
+    Constant(String),
+    /// `ПараметрыСеанса.Имя`
+    SessionParameter(String),
+}
+
+/// Индекс констант и параметров сеанса конфигурации
+#[derive(Debug, Clone, Default)]
+pub struct MetadataValueIndex {
+    constants: Vec<DeclaredValue>,
+    session_parameters: Vec<DeclaredValue>,
+}
+
+fn constant_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^(?:Константы|Constants)\.([\p{L}_][\p{L}\p{N}_]*)\.(?:Получить|Get)\(\)$")
+            .unwrap()
+    })
+}
+
+fn session_parameter_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^(?:ПараметрыСеанса|SessionParameters)\.([\p{L}_][\p{L}\p{N}_]*)$")
+            .unwrap()
+    })
+}
+
+/// Значение, к которому обращается всё выражение
+pub fn referenced_value(expression: &str) -> Option<MetadataValueRef> {
+    let expression: String = expression.split_whitespace().collect();
+    if let Some(caps) = constant_regex().captures(&expression) {
+        return Some(MetadataValueRef::Constant(caps[1].to_string()));
+    }
+    session_parameter_regex()
+        .captures(&expression)
+        .map(|caps| MetadataValueRef::SessionParameter(caps[1].to_string()))
+}
+
+/// Имя типа BSL по типу из описания метаданных (`cfg:CatalogRef.Валюты`,
+/// `xs:string` или `String` в EDT)
+pub fn value_type_name(xml_type: &str) -> String {
+    let name = xml_type.trim();
+    let name = name.rsplit_once(':').map_or(name, |(_, name)| name);
+    if let Some((kind, object)) = name.split_once('.') {
+        return match REFERENCE_KINDS.iter().find(|(xml, _)| *xml == kind) {
+            Some((_, bsl)) => format!("{}.{}", bsl, object),
+            None => name.to_string(),
+        };
+    }
+    match name {
+        "string" | "String" => "Строка",
+        "decimal" | "Number" => "Число",
+        "boolean" | "Boolean" => "Булево",
+        "dateTime" | "Date" => "Дата",
+        "ValueStorage" => "ХранилищеЗначения",
+        "UUID" => "УникальныйИдентификатор",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Файл описания объекта: `<Имя>.xml` конфигуратора или `<Имя>/<Имя>.mdo` EDT
+pub(super) fn object_file(entry: &Path) -> Option<std::path::PathBuf> {
+    if entry.is_file() {
+        return (entry.extension()? == "xml").then(|| entry.to_path_buf());
+    }
+    let name = entry.file_name()?.to_string_lossy().to_string();
+    let mdo = entry.join(format!("{}.mdo", name));
+    mdo.is_file().then_some(mdo)
+}
+
+/// Описания объектов каталога вида (`Constants`, `SessionParameters`)
+fn load_kind(config_path: &Path, kind_dir: &str) -> Result<Vec<DeclaredValue>> {
+    let mut values = Vec::new();
+    for dir in [config_path.join(kind_dir), config_path.join("src").join(kind_dir)] {
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut entries: Vec<_> = std::fs::read_dir(&dir)
+            .with_context(|| format!("Не удалось прочитать {}", dir.display()))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .collect();
+        entries.sort();
+        for file in entries.iter().filter_map(|entry| object_file(entry)) {
+            let content = std::fs::read_to_string(&file)
+                .with_context(|| format!("Не удалось прочитать {}", file.display()))?;
+            let value = parse_value_object(&content)
+                .with_context(|| format!("Описание объекта {}", file.display()))?;
+            values.extend(value);
+        }
+    }
+    Ok(values)
+}
+
+impl MetadataValueIndex {
+    /// Загрузить константы и параметры сеанса из каталога выгрузки или
+    /// проекта EDT; пустой индекс, если их нет
+    pub fn load(config_path: &Path) -> Result<Self> {
+        Ok(Self::new(
+            load_kind(config_path, "Constants")?,
+            load_kind(config_path, "SessionParameters")?,
+        ))
+    }
+
+    pub fn new(constants: Vec<DeclaredValue>, session_parameters: Vec<DeclaredValue>) -> Self {
+        Self {
+            constants,
+            session_parameters,
+        }
+    }
+
+    pub fn constants(&self) -> &[DeclaredValue] {
+        &self.constants
+    }
+
+    pub fn session_parameters(&self) -> &[DeclaredValue] {
+        &self.session_parameters
+    }
+
+    pub fn len(&self) -> usize {
+        self.constants.len() + self.session_parameters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constants.is_empty() && self.session_parameters.is_empty()
+    }
+
+    /// Объявление значения по обращению (имя без учёта регистра)
+    pub fn find(&self, reference: &MetadataValueRef) -> Option<&DeclaredValue> {
+        let (values, name) = match reference {
+            MetadataValueRef::Constant(name) => (&self.constants, name),
+            MetadataValueRef::SessionParameter(name) => (&self.session_parameters, name),
+        };
+        let name = name.to_lowercase();
+        values.iter().find(|value| value.name.to_lowercase() == name)
+    }
+}
+
+/// Разобрать описание константы или параметра сеанса: имя и типы значения.
+/// `None` — в описании нет имени.
+pub fn parse_value_object(content: &str) -> Result<Option<DeclaredValue>> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut name = String::new();
+    let mut types = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                path.push(String::from_utf8_lossy(e.local_name().as_ref()).to_string());
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                // Конфигуратор: MetaDataObject/Constant/Properties/{Name,Type/Type};
+                // EDT: Constant/{name,type/types}
+                match tail.as_slice() {
+                    [_, _, "Properties", "Name"] | [_, "name"] => name = text,
+                    [_, _, "Properties", "Type", "Type" | "TypeSet"] | [_, "type", "types"] => {
+                        types.push(value_type_name(&text))
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Ошибка XML описания на позиции {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((!name.is_empty()).then_some(DeclaredValue { name, types }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONSTANT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<MetaDataObject xmlns="http://v8.1c.ru/8.3/MDClasses" xmlns:v8="http://v8.1c.ru/8.1/data/core">
+  <Constant uuid="0b6b7d1c-0000-0000-0000-000000000001">
+    <Properties>
+      <Name>ОсновнаяВалюта</Name>
+      <Synonym><v8:item><v8:lang>ru</v8:lang><v8:content>Валюта</v8:content></v8:item></Synonym>
+      <Type><v8:Type>cfg:CatalogRef.Валюты</v8:Type></Type>
+    </Properties>
+  </Constant>
+</MetaDataObject>"#;
+
+    const SESSION_PARAMETER_MDO: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<mdclass:SessionParameter xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass">
+  <name>ТекущийПользователь</name>
+  <synonym><key>ru</key><value>Текущий пользователь</value></synonym>
+  <type>
+    <types>CatalogRef.Пользователи</types>
+    <types>CatalogRef.ВнешниеПользователи</types>
+  </type>
+</mdclass:SessionParameter>"#;
+
+    #[test]
+    fn test_parse_value_objects() {
+        let constant = parse_value_object(CONSTANT_XML).unwrap().unwrap();
+        assert_eq!(constant.name, "ОсновнаяВалюта");
+        assert_eq!(constant.types, ["СправочникСсылка.Валюты"]);
+
+        let parameter = parse_value_object(SESSION_PARAMETER_MDO).unwrap().unwrap();
+        assert_eq!(
+            parameter.types,
+            ["СправочникСсылка.Пользователи", "СправочникСсылка.ВнешниеПользователи"]
+        );
+
+        let index = MetadataValueIndex::new(vec![constant], vec![parameter]);
+        let reference = referenced_value("Константы.основнаяВалюта.Получить()").unwrap();
+        assert_eq!(index.find(&reference).unwrap().name, "ОсновнаяВалюта");
+        let reference = referenced_value("ПараметрыСеанса.ТекущийПользователь").unwrap();
+        assert_eq!(index.find(&reference).unwrap().types.len(), 2);
+        assert!(referenced_value("Константы.ОсновнаяВалюта").is_none());
+
+        assert_eq!(value_type_name("xs:decimal"), "Число");
+        assert_eq!(value_type_name("v8:ValueStorage"), "ХранилищеЗначения");
+    }
+}
@@ -1,10 +1,23 @@
 //! Загрузчики источников данных (плоская структура)
 
 pub mod category_hierarchy_parser;
+pub mod commands;
+pub mod common_attributes;
 pub mod config_parser_discovery;
 pub mod config_parser_guided_discovery;
 pub mod config_parser_quick_xml;
 pub mod config_parser_xml;
+pub mod english_names;
+pub mod external_data_sources;
 pub mod facet_cache;
+pub mod facet_inference;
+pub mod forms;
+pub mod help_bookmarks;
+pub mod metadata_flags;
+pub mod metadata_values;
 pub mod platform_types_v2;
+pub mod project_layout;
+pub mod predefined;
+pub mod roles;
+pub mod subsystems;
 pub mod syntax_helper_parser;
\ No newline at end of file
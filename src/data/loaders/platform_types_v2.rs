@@ -210,6 +210,7 @@ impl PlatformTypesResolverV2 {
                     "From syntax helper: {}",
                     type_info.identity.catalog_path
                 )],
+                resolver: None,
             },
             active_facet: type_info.metadata.default_facet,
             available_facets: type_info.metadata.available_facets.clone(),
@@ -293,6 +294,7 @@ impl PlatformTypesResolverV2 {
                 line: None,
                 column: None,
                 notes: vec![format!("Global function: {}", name)],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -320,6 +322,7 @@ impl PlatformTypesResolverV2 {
                 line: None,
                 column: None,
                 notes: vec![format!("Standard type: {}", type_name)],
+                resolver: None,
             },
             active_facet: None,
             available_facets: vec![],
@@ -0,0 +1,201 @@
+//! Предопределённые элементы справочников и планов
+//!
+//! Конфигуратор выгружает их рядом с описанием объекта:
+//! `Catalogs/Валюты.xml` → `Catalogs/Валюты/Ext/Predefined.xml`. Элементы
+//! (`Item`) могут быть вложены в группы (`ChildItems`); имя элемента
+//! уникально в пределах объекта, поэтому иерархия раскладывается в плоский
+//! список. Проект EDT хранит их рядом с `.mdo`: `src/Catalogs/Валюты/Predefined.xml`,
+//! элементы — `items` (вложенные — `childItems`) с `name`, `code`,
+//! `description`, `isFolder`.
+//! По именам разрешаются `Справочники.Валюты.Рубль` и
+//! `ПредопределенноеЗначение("Справочник.Валюты.Рубль")`.
+
+use anyhow::{Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Предопределённый элемент
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PredefinedItem {
+    pub name: String,
+    pub code: Option<String>,
+    pub description: Option<String>,
+    pub is_folder: bool,
+}
+
+impl PredefinedItem {
+    fn new() -> Self {
+        Self {
+            name: String::new(),
+            code: None,
+            description: None,
+            is_folder: false,
+        }
+    }
+}
+
+/// Разобрать `Predefined.xml`: элементы в порядке описания, группы перед
+/// своими элементами
+pub fn parse_predefined(content: &str) -> Result<Vec<PredefinedItem>> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut items: Vec<PredefinedItem> = Vec::new();
+    // Индексы открытых элементов: вложенный элемент закрывается раньше
+    // своей группы
+    let mut open: Vec<usize> = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let tag = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if matches!(tag.as_str(), "Item" | "items" | "childItems") {
+                    open.push(items.len());
+                    items.push(PredefinedItem::new());
+                }
+                path.push(tag);
+            }
+            Ok(Event::End(_)) => {
+                if matches!(path.pop().as_deref(), Some("Item" | "items" | "childItems")) {
+                    open.pop();
+                }
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                let Some(&index) = open.last() else {
+                    continue;
+                };
+                let item = &mut items[index];
+                let tail: Vec<&str> = path.iter().map(String::as_str).collect();
+                match tail.as_slice() {
+                    [.., "Item", "Name"] | [.., "items" | "childItems", "name"] => item.name = text,
+                    [.., "Item", "Code"] | [.., "items" | "childItems", "code", "value"]
+                        if !text.is_empty() =>
+                    {
+                        item.code = Some(text)
+                    }
+                    [.., "Item", "Description"] | [.., "items" | "childItems", "description"]
+                        if !text.is_empty() =>
+                    {
+                        item.description = Some(text)
+                    }
+                    [.., "Item", "IsFolder"] | [.., "items" | "childItems", "isFolder"] => {
+                        item.is_folder = text == "true"
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Ошибка XML предопределённых данных на позиции {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    items.retain(|item| !item.name.is_empty());
+    Ok(items)
+}
+
+/// Файл предопределённых данных объекта по файлу его описания
+pub fn predefined_file(object_file: &Path) -> PathBuf {
+    if object_file.extension().is_some_and(|ext| ext == "mdo") {
+        return object_file.with_file_name("Predefined.xml");
+    }
+    object_file
+        .with_extension("")
+        .join("Ext")
+        .join("Predefined.xml")
+}
+
+/// Загрузить предопределённые элементы объекта по файлу его описания;
+/// пустой список, если их нет
+pub fn load(object_file: &Path) -> Result<Vec<PredefinedItem>> {
+    let file = predefined_file(object_file);
+    if !file.is_file() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Не удалось прочитать {}", file.display()))?;
+    parse_predefined(&content)
+        .with_context(|| format!("Предопределённые данные {}", file.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREDEFINED_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<PredefinedData xmlns="http://v8.1c.ru/8.3/xcf/predef" version="2.16">
+  <Item id="8a1e1c5e-0000-0000-0000-000000000001">
+    <Name>Рубль</Name>
+    <Code>643</Code>
+    <Description>руб.</Description>
+    <IsFolder>false</IsFolder>
+  </Item>
+  <Item id="8a1e1c5e-0000-0000-0000-000000000002">
+    <Name>Иностранные</Name>
+    <Code/>
+    <IsFolder>true</IsFolder>
+    <ChildItems>
+      <Item id="8a1e1c5e-0000-0000-0000-000000000003">
+        <Name>Доллар</Name>
+        <Code>840</Code>
+        <IsFolder>false</IsFolder>
+      </Item>
+    </ChildItems>
+  </Item>
+</PredefinedData>"#;
+
+    #[test]
+    fn test_load_predefined() {
+        let dir = tempfile::tempdir().unwrap();
+        let object_file = dir.path().join("Catalogs").join("Валюты.xml");
+        assert!(load(&object_file).unwrap().is_empty());
+
+        let file = predefined_file(&object_file);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, PREDEFINED_XML).unwrap();
+
+        let items = load(&object_file).unwrap();
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["Рубль", "Иностранные", "Доллар"]);
+        assert_eq!(items[0].code.as_deref(), Some("643"));
+        assert_eq!(items[0].description.as_deref(), Some("руб."));
+        assert!(items[1].is_folder);
+        assert_eq!(items[1].code, None);
+        assert_eq!(items[2].code.as_deref(), Some("840"));
+
+        let edt_file = dir.path().join("src/Catalogs/Валюты/Валюты.mdo");
+        assert_eq!(
+            predefined_file(&edt_file),
+            dir.path().join("src/Catalogs/Валюты/Predefined.xml")
+        );
+        let edt = r#"<mdclass:PredefinedData xmlns:mdclass="http://g5.1c.ru/v8/dt/metadata/mdclass">
+  <items id="1">
+    <name>Рубль</name>
+    <code xsi:type="core:StringValue"><value>643</value></code>
+    <description>руб.</description>
+  </items>
+  <items id="2">
+    <name>Иностранные</name>
+    <isFolder>true</isFolder>
+    <childItems id="3"><name>Доллар</name></childItems>
+  </items>
+</mdclass:PredefinedData>"#;
+        let items = parse_predefined(edt).unwrap();
+        let names: Vec<&str> = items.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["Рубль", "Иностранные", "Доллар"]);
+        assert_eq!(items[0].code.as_deref(), Some("643"));
+        assert!(items[1].is_folder);
+    }
+}
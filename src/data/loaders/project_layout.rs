@@ -0,0 +1,148 @@
+//! Формат исходников конфигурации: выгрузка конфигуратора или проект EDT
+//!
+//! Конфигуратор выгружает конфигурацию в каталог с `Configuration.xml`,
+//! объекты лежат файлами `Catalogs/Валюты.xml`. Проект 1C:EDT (рабочая
+//! копия Git) хранит описания в каталоге `src`: `src/Configuration/Configuration.mdo`
+//! и `src/Catalogs/Валюты/Валюты.mdo`, элементы в нижнем регистре
+//! (`<name>`, `<attributes>`), а состав конфигурации — списками
+//! `<catalogs>Catalog.Валюты</catalogs>`. Формат определяется по опорному
+//! файлу, поэтому указывать можно как каталог выгрузки, так и корень
+//! проекта EDT (или его `src`).
+
+use std::path::{Path, PathBuf};
+
+/// Каталоги объектов по тегу вида; в EDT список состава конфигурации
+/// называется так же, но со строчной буквы (`chartsOfAccounts`)
+const METADATA_DIRS: &[(&str, &str)] = &[
+    ("Catalog", "Catalogs"),
+    ("Document", "Documents"),
+    ("Enum", "Enums"),
+    ("InformationRegister", "InformationRegisters"),
+    ("AccumulationRegister", "AccumulationRegisters"),
+    ("AccountingRegister", "AccountingRegisters"),
+    ("CalculationRegister", "CalculationRegisters"),
+    ("Report", "Reports"),
+    ("DataProcessor", "DataProcessors"),
+    ("ChartOfAccounts", "ChartsOfAccounts"),
+    ("ChartOfCharacteristicTypes", "ChartsOfCharacteristicTypes"),
+    ("ChartOfCalculationTypes", "ChartsOfCalculationTypes"),
+    ("BusinessProcess", "BusinessProcesses"),
+    ("Task", "Tasks"),
+    ("ExchangePlan", "ExchangePlans"),
+    ("FilterCriterion", "FilterCriteria"),
+    ("SettingsStorage", "SettingsStorages"),
+    ("CommonAttribute", "CommonAttributes"),
+    ("ExternalDataSource", "ExternalDataSources"),
+];
+
+/// Формат исходников
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectLayout {
+    /// Выгрузка конфигуратора: каталог с `Configuration.xml`
+    Designer { root: PathBuf },
+    /// Проект EDT: `src` — каталог с `Configuration/Configuration.mdo`
+    Edt { src: PathBuf },
+}
+
+impl ProjectLayout {
+    /// Определить формат по каталогу; `None` — опорного файла нет
+    pub fn detect(path: &Path) -> Option<Self> {
+        if path.join("Configuration.xml").is_file() {
+            return Some(ProjectLayout::Designer {
+                root: path.to_path_buf(),
+            });
+        }
+        [path.join("src"), path.to_path_buf()]
+            .into_iter()
+            .find(|src| configuration_mdo(src).is_file())
+            .map(|src| ProjectLayout::Edt { src })
+    }
+
+    /// Опорный файл со списком объектов конфигурации
+    pub fn configuration_file(&self) -> PathBuf {
+        match self {
+            ProjectLayout::Designer { root } => root.join("Configuration.xml"),
+            ProjectLayout::Edt { src } => configuration_mdo(src),
+        }
+    }
+
+    pub fn is_edt(&self) -> bool {
+        matches!(self, ProjectLayout::Edt { .. })
+    }
+
+    /// Файл описания объекта EDT: `src/Catalogs/Валюты/Валюты.mdo`;
+    /// `None` для выгрузки и неизвестного вида
+    pub fn object_file(&self, xml_tag: &str, name: &str) -> Option<PathBuf> {
+        let ProjectLayout::Edt { src } = self else {
+            return None;
+        };
+        let dir = metadata_dir(xml_tag)?;
+        Some(src.join(dir).join(name).join(format!("{}.mdo", name)))
+    }
+}
+
+fn configuration_mdo(src: &Path) -> PathBuf {
+    src.join("Configuration").join("Configuration.mdo")
+}
+
+/// Каталог объектов вида: `Catalog` → `Catalogs`
+pub fn metadata_dir(xml_tag: &str) -> Option<&'static str> {
+    METADATA_DIRS
+        .iter()
+        .find(|(tag, _)| *tag == xml_tag)
+        .map(|(_, dir)| *dir)
+}
+
+/// Тег вида по имени списка состава в `Configuration.mdo`:
+/// `chartsOfAccounts` → `ChartOfAccounts`
+pub fn edt_collection_tag(collection: &str) -> Option<&'static str> {
+    METADATA_DIRS
+        .iter()
+        .find(|(_, dir)| {
+            let (first, rest) = dir.split_at(1);
+            collection.len() == dir.len()
+                && collection.starts_with(&first.to_ascii_lowercase())
+                && collection.ends_with(rest)
+        })
+        .map(|(tag, _)| *tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(ProjectLayout::detect(dir.path()), None);
+
+        let edt_src = dir.path().join("src");
+        std::fs::create_dir_all(edt_src.join("Configuration")).unwrap();
+        std::fs::write(configuration_mdo(&edt_src), "<mdclass:Configuration/>").unwrap();
+        let layout = ProjectLayout::detect(dir.path()).unwrap();
+        assert_eq!(layout, ProjectLayout::detect(&edt_src).unwrap());
+        assert!(layout.is_edt());
+        assert_eq!(
+            layout.object_file("ChartOfAccounts", "Хозрасчетный"),
+            Some(edt_src.join("ChartsOfAccounts/Хозрасчетный/Хозрасчетный.mdo"))
+        );
+        assert_eq!(layout.object_file("Language", "Русский"), None);
+
+        std::fs::write(dir.path().join("Configuration.xml"), "<Configuration/>").unwrap();
+        let layout = ProjectLayout::detect(dir.path()).unwrap();
+        assert!(!layout.is_edt());
+        assert_eq!(layout.object_file("Catalog", "Валюты"), None);
+
+        assert_eq!(edt_collection_tag("catalogs"), Some("Catalog"));
+        assert_eq!(
+            edt_collection_tag("chartsOfAccounts"),
+            Some("ChartOfAccounts")
+        );
+        assert_eq!(
+            edt_collection_tag("filterCriteria"),
+            Some("FilterCriterion")
+        );
+        assert_eq!(edt_collection_tag("Catalogs"), None);
+        assert_eq!(edt_collection_tag("defaultLanguage"), None);
+    }
+}
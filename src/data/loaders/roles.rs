@@ -0,0 +1,231 @@
+//! Роли конфигурации и права, выданные ими
+//!
+//! Роли читаются из выгрузки конфигуратора (`Roles/<Имя>.xml` и права в
+//! `Roles/<Имя>/Ext/Rights.xml`) или из проекта EDT (`src/Roles/<Имя>/<Имя>.mdo`
+//! и `Rights.rights`). Индекс нужен для проверки строковых ссылок на роли и
+//! права в коде (`РольДоступна("Имя")`, `ПравоДоступа("Чтение", ...)`):
+//! опечатка в таком имени обнаруживается только при выполнении.
+
+use anyhow::{Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Права роли на объект метаданных
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ObjectRights {
+    /// Полное имя объекта (`Catalog.Товары`, `Configuration.Торговля`)
+    pub object: String,
+    /// Выданные права (`Read`, `Insert`, ...)
+    pub granted: Vec<String>,
+}
+
+/// Роль конфигурации
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RoleInfo {
+    pub name: String,
+    pub objects: Vec<ObjectRights>,
+}
+
+/// Индекс ролей конфигурации
+#[derive(Debug, Clone, Default)]
+pub struct RoleIndex {
+    roles: Vec<RoleInfo>,
+}
+
+/// Файлы роли: описание и права
+fn role_files(roles_dir: &Path, entry: &Path) -> Option<(String, std::path::PathBuf)> {
+    if entry.is_file() {
+        // Конфигуратор: Roles/<Имя>.xml, права в Roles/<Имя>/Ext/Rights.xml
+        if entry.extension()? != "xml" {
+            return None;
+        }
+        let name = entry.file_stem()?.to_string_lossy().to_string();
+        let rights = roles_dir.join(&name).join("Ext").join("Rights.xml");
+        return Some((name, rights));
+    }
+    // EDT: src/Roles/<Имя>/<Имя>.mdo и Rights.rights
+    let name = entry.file_name()?.to_string_lossy().to_string();
+    entry
+        .join(format!("{}.mdo", name))
+        .is_file()
+        .then(|| (name, entry.join("Rights.rights")))
+}
+
+impl RoleIndex {
+    /// Загрузить роли из каталога выгрузки или проекта EDT; пустой индекс,
+    /// если ролей нет
+    pub fn load(config_path: &Path) -> Result<Self> {
+        let mut roles = Vec::new();
+        for roles_dir in [config_path.join("Roles"), config_path.join("src").join("Roles")] {
+            if !roles_dir.is_dir() {
+                continue;
+            }
+            let mut entries: Vec<_> = std::fs::read_dir(&roles_dir)
+                .with_context(|| format!("Не удалось прочитать {}", roles_dir.display()))?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .collect();
+            entries.sort();
+            for entry in entries {
+                let Some((name, rights_path)) = role_files(&roles_dir, &entry) else {
+                    continue;
+                };
+                let objects = if rights_path.is_file() {
+                    let content = std::fs::read_to_string(&rights_path).with_context(|| {
+                        format!("Не удалось прочитать {}", rights_path.display())
+                    })?;
+                    parse_rights(&content)
+                        .with_context(|| format!("Права роли {}", rights_path.display()))?
+                } else {
+                    Vec::new()
+                };
+                roles.push(RoleInfo { name, objects });
+            }
+        }
+        Ok(Self::from_roles(roles))
+    }
+
+    pub fn from_roles(roles: Vec<RoleInfo>) -> Self {
+        Self { roles }
+    }
+
+    pub fn roles(&self) -> &[RoleInfo] {
+        &self.roles
+    }
+
+    pub fn len(&self) -> usize {
+        self.roles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty()
+    }
+
+    /// Роль по имени без учёта регистра
+    pub fn find(&self, name: &str) -> Option<&RoleInfo> {
+        let name = name.trim().to_lowercase();
+        self.roles.iter().find(|role| role.name.to_lowercase() == name)
+    }
+
+    /// Права, встречающиеся в ролях конфигурации
+    pub fn right_names(&self) -> BTreeSet<&str> {
+        self.roles
+            .iter()
+            .flat_map(|role| &role.objects)
+            .flat_map(|object| &object.granted)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Разобрать права роли (`Rights.xml` или `Rights.rights`): для каждого
+/// объекта — права со значением `true`
+pub fn parse_rights(content: &str) -> Result<Vec<ObjectRights>> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut objects: Vec<ObjectRights> = Vec::new();
+    let mut right: Option<String> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let tag = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if tag == "object" && path.len() == 1 {
+                    objects.push(ObjectRights {
+                        object: String::new(),
+                        granted: Vec::new(),
+                    });
+                }
+                path.push(tag);
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                let Some(object) = objects.last_mut().filter(|_| path.len() >= 3) else {
+                    continue;
+                };
+                // Rights/object/name и Rights/object/right/{name,value}
+                match (&path[1..], text.as_str()) {
+                    ([o, n], _) if o == "object" && n == "name" => object.object = text.clone(),
+                    ([o, r, n], _) if o == "object" && r == "right" && n == "name" => {
+                        right = Some(text.clone())
+                    }
+                    ([o, r, v], "true") if o == "object" && r == "right" && v == "value" => {
+                        object.granted.extend(right.take());
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Ошибка XML прав на позиции {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    objects.retain(|object| !object.object.is_empty());
+    Ok(objects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RIGHTS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Rights xmlns="http://v8.1c.ru/8.2/roles" version="2.17">
+  <setForNewObjects>false</setForNewObjects>
+  <object>
+    <name>Catalog.Товары</name>
+    <right><name>Read</name><value>true</value></right>
+    <right><name>Insert</name><value>false</value></right>
+    <right><name>View</name><value>true</value></right>
+  </object>
+  <object>
+    <name>Configuration.Торговля</name>
+    <right><name>ThinClient</name><value>true</value></right>
+  </object>
+</Rights>"#;
+
+    #[test]
+    fn test_parse_rights() {
+        let objects = parse_rights(RIGHTS_XML).unwrap();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].object, "Catalog.Товары");
+        assert_eq!(objects[0].granted, ["Read", "View"]);
+        assert_eq!(objects[1].granted, ["ThinClient"]);
+    }
+
+    #[test]
+    fn test_load_designer_and_edt_layouts() {
+        let dir = tempfile::tempdir().unwrap();
+        let designer = dir.path().join("Roles");
+        std::fs::create_dir_all(designer.join("Менеджер").join("Ext")).unwrap();
+        std::fs::write(designer.join("Менеджер.xml"), "<MetaDataObject/>").unwrap();
+        std::fs::write(designer.join("Менеджер/Ext/Rights.xml"), RIGHTS_XML).unwrap();
+
+        let edt = dir.path().join("src").join("Roles").join("Кладовщик");
+        std::fs::create_dir_all(&edt).unwrap();
+        std::fs::write(edt.join("Кладовщик.mdo"), "<mdclass:Role/>").unwrap();
+
+        let index = RoleIndex::load(dir.path()).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.find("менеджер").unwrap().objects.len(), 2);
+        assert!(index.find("Кладовщик").unwrap().objects.is_empty());
+        assert!(index.find("Бухгалтер").is_none());
+        assert!(index.right_names().contains("ThinClient"));
+    }
+}
@@ -0,0 +1,327 @@
+//! Парсер подсистем конфигурации 1С (Subsystems/*.xml)
+//!
+//! Подсистемы — основной способ, которым команды 1С делят ответственность за
+//! код. Индекс сопоставляет объекты метаданных (и их модули в выгрузке) с
+//! подсистемами, чтобы группировать и фильтровать диагностики, покрытие и
+//! статистику использования.
+
+use anyhow::{Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Группа для модулей, не входящих ни в одну подсистему
+pub const NO_SUBSYSTEM: &str = "(без подсистемы)";
+
+/// Описание подсистемы
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SubsystemInfo {
+    /// Имя подсистемы
+    pub name: String,
+    /// Полный путь с учётом вложенности: "Продажи/ОптовыеПродажи"
+    pub full_name: String,
+    /// Синоним (представление) подсистемы
+    pub synonym: Option<String>,
+    /// Состав: ссылки на объекты вида "Catalog.Номенклатура"
+    pub content: Vec<String>,
+}
+
+/// Индекс подсистем конфигурации
+#[derive(Debug, Clone, Default)]
+pub struct SubsystemIndex {
+    subsystems: Vec<SubsystemInfo>,
+    /// Объект метаданных → полные имена подсистем, в которые он входит
+    object_to_subsystems: HashMap<String, Vec<String>>,
+}
+
+/// Соответствие каталогов выгрузки видам метаданных
+const KIND_DIRECTORIES: &[(&str, &str)] = &[
+    ("Catalogs", "Catalog"),
+    ("Documents", "Document"),
+    ("DocumentJournals", "DocumentJournal"),
+    ("Enums", "Enum"),
+    ("Reports", "Report"),
+    ("DataProcessors", "DataProcessor"),
+    ("CommonModules", "CommonModule"),
+    ("CommonForms", "CommonForm"),
+    ("CommonCommands", "CommonCommand"),
+    ("InformationRegisters", "InformationRegister"),
+    ("AccumulationRegisters", "AccumulationRegister"),
+    ("AccountingRegisters", "AccountingRegister"),
+    ("CalculationRegisters", "CalculationRegister"),
+    ("ChartsOfAccounts", "ChartOfAccounts"),
+    ("ChartsOfCharacteristicTypes", "ChartOfCharacteristicTypes"),
+    ("ChartsOfCalculationTypes", "ChartOfCalculationTypes"),
+    ("BusinessProcesses", "BusinessProcess"),
+    ("Tasks", "Task"),
+    ("ExchangePlans", "ExchangePlan"),
+    ("Constants", "Constant"),
+    ("FilterCriteria", "FilterCriterion"),
+    ("SettingsStorages", "SettingsStorage"),
+    ("WebServices", "WebService"),
+    ("HTTPServices", "HTTPService"),
+];
+
+/// Определить объект метаданных по пути модуля выгрузки:
+/// `Catalogs/Номенклатура/Ext/ObjectModule.bsl` → `Catalog.Номенклатура`
+pub fn object_for_module(module_path: &str) -> Option<String> {
+    let normalized = module_path.replace('\\', "/");
+    let components: Vec<&str> = normalized.split('/').filter(|c| !c.is_empty()).collect();
+
+    // Ищем с конца: вложенные каталоги (например, Subsystems) не должны перехватить объект
+    for idx in (0..components.len().saturating_sub(1)).rev() {
+        if let Some((_, kind)) = KIND_DIRECTORIES
+            .iter()
+            .find(|(dir, _)| *dir == components[idx])
+        {
+            let name = components[idx + 1].trim_end_matches(".xml");
+            return Some(format!("{}.{}", kind, name));
+        }
+    }
+    None
+}
+
+impl SubsystemIndex {
+    /// Загрузить подсистемы из выгрузки конфигурации (каталог с Configuration.xml)
+    pub fn load(config_path: &Path) -> Result<Self> {
+        let mut index = Self::default();
+        let root = config_path.join("Subsystems");
+        if root.is_dir() {
+            index.load_level(&root, "")?;
+        }
+        index.rebuild_object_map();
+        Ok(index)
+    }
+
+    /// Построить индекс из уже разобранных подсистем
+    pub fn from_subsystems(subsystems: Vec<SubsystemInfo>) -> Self {
+        let mut index = Self {
+            subsystems,
+            object_to_subsystems: HashMap::new(),
+        };
+        index.rebuild_object_map();
+        index
+    }
+
+    fn load_level(&mut self, dir: &Path, parent: &str) -> Result<()> {
+        let mut entries: Vec<_> = std::fs::read_dir(dir)
+            .with_context(|| format!("Не удалось прочитать {}", dir.display()))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "xml"))
+            .collect();
+        entries.sort();
+
+        for xml_path in entries {
+            let content = std::fs::read_to_string(&xml_path)
+                .with_context(|| format!("Не удалось прочитать {}", xml_path.display()))?;
+            let Some(mut info) = parse_subsystem_xml(&content)? else {
+                continue;
+            };
+            info.full_name = if parent.is_empty() {
+                info.name.clone()
+            } else {
+                format!("{}/{}", parent, info.name)
+            };
+
+            // Вложенные подсистемы: Subsystems/<Имя>/Subsystems/*.xml
+            let nested = xml_path.with_extension("").join("Subsystems");
+            let full_name = info.full_name.clone();
+            self.subsystems.push(info);
+            if nested.is_dir() {
+                self.load_level(&nested, &full_name)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn rebuild_object_map(&mut self) {
+        self.object_to_subsystems.clear();
+        for subsystem in &self.subsystems {
+            for object in &subsystem.content {
+                self.object_to_subsystems
+                    .entry(object.clone())
+                    .or_default()
+                    .push(subsystem.full_name.clone());
+            }
+        }
+    }
+
+    /// Все подсистемы (включая вложенные)
+    pub fn subsystems(&self) -> &[SubsystemInfo] {
+        &self.subsystems
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subsystems.is_empty()
+    }
+
+    /// Подсистемы, в которые входит модуль
+    pub fn subsystems_for_module(&self, module_path: &str) -> &[String] {
+        object_for_module(module_path)
+            .and_then(|object| self.object_to_subsystems.get(&object))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Основная подсистема модуля (первая по составу) для группировки
+    pub fn group_for_module(&self, module_path: &str) -> String {
+        self.subsystems_for_module(module_path)
+            .first()
+            .cloned()
+            .unwrap_or_else(|| NO_SUBSYSTEM.to_string())
+    }
+
+    /// Входит ли модуль в подсистему (с учётом вложенных: фильтр "Продажи"
+    /// включает "Продажи/ОптовыеПродажи")
+    pub fn module_in_subsystem(&self, module_path: &str, subsystem: &str) -> bool {
+        if subsystem == NO_SUBSYSTEM {
+            return self.subsystems_for_module(module_path).is_empty();
+        }
+        self.subsystems_for_module(module_path).iter().any(|name| {
+            name == subsystem
+                || name
+                    .strip_prefix(subsystem)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+}
+
+/// Разобрать XML одной подсистемы (None, если файл не описывает подсистему)
+pub fn parse_subsystem_xml(content: &str) -> Result<Option<SubsystemInfo>> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut name = None;
+    let mut synonym = None;
+    let mut items = Vec::new();
+    let mut is_subsystem = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let tag = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if tag == "Subsystem" {
+                    is_subsystem = true;
+                }
+                path.push(tag);
+            }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                let in_properties = path.iter().any(|t| t == "Properties");
+                match path.last().map(String::as_str) {
+                    Some("Name") if in_properties && name.is_none() => name = Some(text),
+                    Some("content") if path.iter().any(|t| t == "Synonym") => {
+                        synonym.get_or_insert(text);
+                    }
+                    Some("Item") if path.iter().any(|t| t == "Content") => items.push(text),
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(anyhow::anyhow!(
+                    "Ошибка XML подсистемы на позиции {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !is_subsystem {
+        return Ok(None);
+    }
+    let Some(name) = name else {
+        return Ok(None);
+    };
+
+    Ok(Some(SubsystemInfo {
+        full_name: name.clone(),
+        name,
+        synonym,
+        content: items,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUBSYSTEM_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<MetaDataObject xmlns="http://v8.1c.ru/8.3/MDClasses" xmlns:v8="http://v8.1c.ru/8.1/data/core" xmlns:xr="http://v8.1c.ru/8.3/xcf/readable">
+  <Subsystem uuid="0f1e">
+    <Properties>
+      <Name>Продажи</Name>
+      <Synonym>
+        <v8:item>
+          <v8:lang>ru</v8:lang>
+          <v8:content>Продажи</v8:content>
+        </v8:item>
+      </Synonym>
+      <Content>
+        <xr:Item xsi:type="xr:MDObjectRef">Catalog.Номенклатура</xr:Item>
+        <xr:Item xsi:type="xr:MDObjectRef">Document.РеализацияТоваров</xr:Item>
+      </Content>
+    </Properties>
+    <ChildObjects>
+      <Subsystem>ОптовыеПродажи</Subsystem>
+    </ChildObjects>
+  </Subsystem>
+</MetaDataObject>"#;
+
+    #[test]
+    fn test_parse_subsystem_xml() {
+        let info = parse_subsystem_xml(SUBSYSTEM_XML).unwrap().unwrap();
+        assert_eq!(info.name, "Продажи");
+        assert_eq!(info.synonym.as_deref(), Some("Продажи"));
+        assert_eq!(
+            info.content,
+            vec!["Catalog.Номенклатура", "Document.РеализацияТоваров"]
+        );
+    }
+
+    #[test]
+    fn test_module_grouping() {
+        assert_eq!(
+            object_for_module("src/Catalogs/Номенклатура/Ext/ObjectModule.bsl").as_deref(),
+            Some("Catalog.Номенклатура")
+        );
+        assert_eq!(object_for_module("Module.bsl"), None);
+
+        let index = SubsystemIndex::from_subsystems(vec![
+            SubsystemInfo {
+                name: "Продажи".to_string(),
+                full_name: "Продажи".to_string(),
+                synonym: None,
+                content: vec!["Catalog.Номенклатура".to_string()],
+            },
+            SubsystemInfo {
+                name: "Опт".to_string(),
+                full_name: "Продажи/Опт".to_string(),
+                synonym: None,
+                content: vec!["Document.Заказ".to_string()],
+            },
+        ]);
+
+        let module = "Documents/Заказ/Ext/ObjectModule.bsl";
+        assert_eq!(index.group_for_module(module), "Продажи/Опт");
+        assert!(index.module_in_subsystem(module, "Продажи"));
+        assert!(!index.module_in_subsystem(module, "Прод"));
+        assert_eq!(
+            index.group_for_module("CommonModules/Общий/Ext/Module.bsl"),
+            NO_SUBSYSTEM
+        );
+    }
+}
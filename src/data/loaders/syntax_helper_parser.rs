@@ -22,6 +22,7 @@ use std::sync::{
 };
 use tracing::{debug, info, warn};
 
+use super::facet_inference::{infer_facets, member_graph, InferredFacet};
 use crate::domain::types::FacetKind;
 
 // ============================================================================
@@ -100,6 +101,9 @@ pub struct TypeStructure {
 pub struct TypeMetadata {
     pub available_facets: Vec<FacetKind>,
     pub default_facet: Option<FacetKind>,
+    /// Фасеты, выведенные по членам типа (уже включены в `available_facets`)
+    #[serde(default)]
+    pub inferred_facets: Vec<InferredFacet>,
     pub serializable: bool,
     pub exchangeable: bool,
     pub xdto_namespace: Option<String>,
@@ -123,6 +127,16 @@ pub struct MethodInfo {
     pub parameters: Vec<ParameterInfo>,
     pub return_type: Option<String>,
     pub return_description: Option<String>,
+    /// Варианты синтаксиса (перегрузки); пусто, если вариант один
+    #[serde(default)]
+    pub syntax_variants: Vec<SyntaxVariantInfo>,
+}
+
+/// Вариант синтаксиса метода («Вариант синтаксиса: По индексу»)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyntaxVariantInfo {
+    pub name: String,
+    pub parameters: Vec<ParameterInfo>,
 }
 
 /// Информация о свойстве
@@ -346,6 +360,9 @@ impl SyntaxHelperParser {
         info!("🔗 Связываем типы с категориями...");
         self.link_types_to_categories();
 
+        // Фаза 3.1: Выводим недостающие фасеты по членам типов
+        self.infer_facets_from_members();
+
         // Фаза 4: Параллельное построение индексов
         let index_start = std::time::Instant::now();
 
@@ -619,6 +636,48 @@ impl SyntaxHelperParser {
         }
     }
 
+    /// Дополняет фасеты типов выводом по графу «тип → члены»
+    fn infer_facets_from_members(&self) {
+        let titles: Vec<String> = self
+            .methods
+            .iter()
+            .map(|entry| entry.value().name.clone())
+            .chain(self.properties.iter().map(|entry| entry.value().name.clone()))
+            .collect();
+        let graph = member_graph(titles.iter().map(String::as_str));
+
+        let mut inferred_count = 0;
+        for mut entry in self.nodes.iter_mut() {
+            if let SyntaxNode::Type(ref mut type_info) = entry.value_mut() {
+                let own: Vec<String> = type_info
+                    .structure
+                    .methods
+                    .iter()
+                    .chain(&type_info.structure.properties)
+                    .cloned()
+                    .collect();
+                let members = graph.members_of(&type_info.identity.russian_name, &own);
+                for facet in infer_facets(&members) {
+                    // Фасет, найденный по имени типа, надёжнее вывода
+                    if type_info.metadata.available_facets.contains(&facet.kind) {
+                        continue;
+                    }
+                    debug!(
+                        "  Фасет {:?} типа {} выведен по членам {:?} ({:.2})",
+                        facet.kind,
+                        type_info.identity.russian_name,
+                        facet.evidence,
+                        facet.confidence
+                    );
+                    type_info.metadata.available_facets.push(facet.kind);
+                    type_info.metadata.inferred_facets.push(facet);
+                    inferred_count += 1;
+                }
+            }
+        }
+        info!("🧩 Выведено {} фасетов по членам типов", inferred_count);
+    }
+
     /// Парсит тип из документа
     fn parse_type_from_document(&self, path: &Path, document: &Html) -> Result<TypeInfo> {
         let title = self.extract_title(document);
@@ -651,6 +710,7 @@ impl SyntaxHelperParser {
             metadata: TypeMetadata {
                 available_facets: self.detect_facets(&russian, &description),
                 default_facet: None,
+                inferred_facets: Vec::new(),
                 serializable: self.is_serializable(document),
                 exchangeable: self.is_exchangeable(document),
                 xdto_namespace: None,
@@ -663,7 +723,11 @@ impl SyntaxHelperParser {
     fn parse_method_from_document(&self, document: &Html) -> Result<MethodInfo> {
         let name = self.extract_title(document);
         let description = self.extract_description(document);
-        let parameters = self.extract_parameters(document);
+        let syntax_variants = self.extract_syntax_variants(document);
+        let parameters = match syntax_variants.first() {
+            Some(variant) => variant.parameters.clone(),
+            None => self.extract_parameters(document),
+        };
         let (return_type, return_description) = self.extract_return_info(document);
 
         Ok(MethodInfo {
@@ -673,6 +737,7 @@ impl SyntaxHelperParser {
             parameters,
             return_type,
             return_description,
+            syntax_variants,
         })
     }
 
@@ -1196,6 +1261,24 @@ impl SyntaxHelperParser {
         parameters
     }
 
+    /// Варианты синтаксиса: страница делится по заголовкам «Вариант
+    /// синтаксиса:», параметры каждого варианта берутся из его части
+    fn extract_syntax_variants(&self, document: &Html) -> Vec<SyntaxVariantInfo> {
+        const MARKER: &str = "Вариант синтаксиса:";
+
+        let html = document.root_element().html();
+        html.split(MARKER)
+            .skip(1)
+            .map(|part| {
+                let name = part[..part.find('<').unwrap_or(part.len())].trim();
+                SyntaxVariantInfo {
+                    name: name.to_string(),
+                    parameters: self.extract_parameters(&Html::parse_fragment(part)),
+                }
+            })
+            .collect()
+    }
+
     fn extract_return_info(&self, document: &Html) -> (Option<String>, Option<String>) {
         // Ищем информацию о возвращаемом значении
         if let Ok(selector) = Selector::parse("div.V8SH_return, div.return") {
@@ -1575,6 +1658,38 @@ mod tests {
         assert_eq!(stats.error_count, 0);
     }
 
+    #[test]
+    fn test_syntax_variants() {
+        let html = r#"
+            <html><body>
+            <h1 class="V8SH_pagetitle">ТаблицаЗначений.Найти (ValueTable.Find)</h1>
+            <p class="V8SH_heading">Вариант синтаксиса: По значению</p>
+            <table class="V8SH_params">
+                <tr><th>Имя</th><th>Тип</th></tr>
+                <tr><td>Значение</td><td>Произвольный</td><td>Обязательный</td></tr>
+                <tr><td>Колонки</td><td>Строка</td><td>Необязательный</td></tr>
+            </table>
+            <p class="V8SH_heading">Вариант синтаксиса: По индексу</p>
+            <table class="V8SH_params">
+                <tr><th>Имя</th><th>Тип</th></tr>
+                <tr><td>Индекс</td><td>Число</td><td>Обязательный</td></tr>
+            </table>
+            </body></html>
+        "#;
+        let parser = SyntaxHelperParser::new();
+        let method = parser
+            .parse_method_from_document(&Html::parse_document(html))
+            .unwrap();
+
+        assert_eq!(method.syntax_variants.len(), 2);
+        assert_eq!(method.syntax_variants[0].name, "По значению");
+        assert_eq!(method.syntax_variants[0].parameters.len(), 2);
+        assert!(method.syntax_variants[0].parameters[1].is_optional);
+        assert_eq!(method.syntax_variants[1].parameters[0].name, "Индекс");
+        // Параметры метода — параметры первого варианта
+        assert_eq!(method.parameters.len(), 2);
+    }
+
     #[test]
     fn test_concurrent_access() {
         use std::sync::Arc;
@@ -1614,6 +1729,7 @@ mod tests {
                     metadata: TypeMetadata {
                         available_facets: vec![],
                         default_facet: None,
+                        inferred_facets: Vec::new(),
                         serializable: true,
                         exchangeable: true,
                         xdto_namespace: None,
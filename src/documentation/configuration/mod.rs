@@ -9,12 +9,15 @@ use super::core::hierarchy::{
     CategoryStatistics, DocumentationNode, DocumentationSourceType, RootCategoryNode,
     TypeDocumentationFull, UiMetadata,
 };
+use super::core::ids::type_id;
 use super::core::providers::{DocumentationProvider, ProviderConfig};
 use super::core::statistics::{InitializationStatus, ProviderStatistics};
 use super::search::AdvancedSearchQuery;
 use crate::data::loaders::config_parser_quick_xml::ConfigurationQuickXmlParser;
 use crate::data::loaders::config_parser_xml::ConfigParserXml;
+use crate::data::loaders::english_names::english_name;
 use crate::domain::types::{MetadataKind, TypeResolution};
+use crate::error::ResultExt;
 
 /// Провайдер документации конфигурационных типов
 pub struct ConfigurationDocumentationProvider {
@@ -125,13 +128,12 @@ impl ConfigurationDocumentationProvider {
                 let ts_count = metadata.map(|m| m.tabular_sections.len()).unwrap_or(0);
 
                 let type_doc = TypeDocumentationFull {
-                    id: format!(
-                        "config_{}_{}",
-                        self.get_kind_prefix(&config_type.kind),
-                        real_name
+                    id: type_id(
+                        "configuration",
+                        &format!("{}.{}", self.get_kind_prefix(&config_type.kind), real_name),
                     ),
                     russian_name: real_name.clone(),
-                    english_name: real_name.clone(),
+                    english_name: english_name(&real_name, None),
                     aliases: if let Some(ref syn) = synonym {
                         vec![syn.clone()]
                     } else {
@@ -282,43 +284,57 @@ impl DocumentationProvider for ConfigurationDocumentationProvider {
         "Конфигурационные типы"
     }
 
-    async fn initialize(&self, config: &ProviderConfig) -> Result<()> {
+    async fn initialize(&self, config: &ProviderConfig) -> crate::Result<()> {
         if !config.data_source.is_empty() && std::path::Path::new(&config.data_source).exists() {
-            self.analyze_configuration(&config.data_source).await?;
+            self.analyze_configuration(&config.data_source)
+                .await
+                .parse_err(format!(
+                    "Не удалось разобрать конфигурацию {}",
+                    config.data_source
+                ))?;
         } else {
             println!("⚠️ Конфигурация не найдена: {}", config.data_source);
         }
         Ok(())
     }
 
-    async fn get_root_category(&self) -> Result<RootCategoryNode> {
+    async fn get_root_category(&self) -> crate::Result<RootCategoryNode> {
         let cache = self.root_category_cache.read().await;
         match cache.as_ref() {
             Some(category) => Ok(category.clone()),
             None => {
                 drop(cache);
-                let category = self.build_configuration_root_category().await?;
+                let category = self
+                    .build_configuration_root_category()
+                    .await
+                    .data_source_err("Не удалось построить корневую категорию конфигурации")?;
                 *self.root_category_cache.write().await = Some(category.clone());
                 Ok(category)
             }
         }
     }
 
-    async fn get_type_details(&self, type_id: &str) -> Result<Option<TypeDocumentationFull>> {
+    async fn get_type_details(
+        &self,
+        type_id: &str,
+    ) -> crate::Result<Option<TypeDocumentationFull>> {
         let cache = self.configuration_cache.read().await;
         Ok(cache.get(type_id).cloned())
     }
 
-    async fn search_types(&self, _query: &AdvancedSearchQuery) -> Result<Vec<DocumentationNode>> {
+    async fn search_types(
+        &self,
+        _query: &AdvancedSearchQuery,
+    ) -> crate::Result<Vec<DocumentationNode>> {
         Ok(Vec::new())
     }
 
-    async fn get_all_types(&self) -> Result<Vec<TypeDocumentationFull>> {
+    async fn get_all_types(&self) -> crate::Result<Vec<TypeDocumentationFull>> {
         let cache = self.configuration_cache.read().await;
         Ok(cache.values().cloned().collect())
     }
 
-    async fn get_statistics(&self) -> Result<ProviderStatistics> {
+    async fn get_statistics(&self) -> crate::Result<ProviderStatistics> {
         let cache = self.configuration_cache.read().await;
 
         Ok(ProviderStatistics {
@@ -330,15 +346,15 @@ impl DocumentationProvider for ConfigurationDocumentationProvider {
         })
     }
 
-    async fn get_initialization_status(&self) -> Result<InitializationStatus> {
+    async fn get_initialization_status(&self) -> crate::Result<InitializationStatus> {
         Ok(self.initialization_status.read().await.clone())
     }
 
-    async fn check_availability(&self) -> Result<bool> {
+    async fn check_availability(&self) -> crate::Result<bool> {
         Ok(self.config_parser.read().await.is_some())
     }
 
-    async fn refresh(&self) -> Result<()> {
+    async fn refresh(&self) -> crate::Result<()> {
         self.configuration_cache.write().await.clear();
         *self.root_category_cache.write().await = None;
         Ok(())
@@ -575,6 +575,20 @@ pub struct MethodDocumentation {
 
     /// Возможные исключения
     pub exceptions: Vec<ExceptionDocumentation>,
+
+    /// Варианты синтаксиса (перегрузки); пусто, если вариант один
+    #[serde(default)]
+    pub overloads: Vec<MethodOverload>,
+}
+
+/// Вариант синтаксиса метода
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodOverload {
+    /// Название варианта («По индексу»)
+    pub name: String,
+
+    /// Параметры варианта
+    pub parameters: Vec<ParameterDocumentation>,
 }
 
 /// Документация параметра
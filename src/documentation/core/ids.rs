@@ -0,0 +1,100 @@
+//! Стабильные идентификаторы документов и узлов документации
+//!
+//! Идентификатор — префикс вида сущности и SHA-256 того, что её определяет
+//! (источник и полное имя типа, путь категории), а не номер в выдаче
+//! парсера или имя с заменёнными пробелами. Закладки, кешированные
+//! результаты поиска и ссылки переживают повторный разбор справки и
+//! перестановку элементов. Имена сравниваются без учёта регистра, как в BSL.
+
+use sha2::{Digest, Sha256};
+
+use super::hierarchy::TypeDocumentationFull;
+
+/// Число шестнадцатеричных символов хеша в идентификаторе
+const ID_HASH_LEN: usize = 16;
+
+/// Разделитель частей перед хешированием (не встречается в именах)
+const PART_SEPARATOR: u8 = 0x1f;
+
+/// Идентификатор `{kind}_{хеш}` по определяющим сущность частям
+pub fn stable_id(kind: &str, parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.trim().to_lowercase().as_bytes());
+        hasher.update([PART_SEPARATOR]);
+    }
+    let digest = format!("{:x}", hasher.finalize());
+    format!("{}_{}", kind, &digest[..ID_HASH_LEN])
+}
+
+/// Идентификатор типа: источник (`platform`, `configuration`) и полное имя
+pub fn type_id(source: &str, qualified_name: &str) -> String {
+    stable_id("type", &[source, qualified_name])
+}
+
+/// Идентификатор типа платформы: одноимённые типы справки (`Отбор`,
+/// `Элемент` у разных объектов) различаются путём страницы в синтакс-помощнике.
+/// Путь берётся от каталога `objects`, чтобы идентификатор не зависел от того,
+/// куда распакована справка
+pub fn platform_type_id(catalog_path: &str, name: &str) -> String {
+    let catalog_path = catalog_path.replace('\\', "/");
+    let relative = catalog_path
+        .rsplit_once("objects/")
+        .map_or(catalog_path.as_str(), |(_, relative)| relative);
+    stable_id("type", &["platform", relative, name])
+}
+
+/// Идентификатор категории по пути от корня (`["Платформа", "Коллекции"]`)
+pub fn category_id(path: &[&str]) -> String {
+    stable_id("category", path)
+}
+
+/// Хеш содержимого документа: меняется только при изменении индексируемых
+/// полей и не зависит от версии компилятора (в отличие от `DefaultHasher`)
+pub fn content_hash(type_doc: &TypeDocumentationFull) -> String {
+    let mut hasher = Sha256::new();
+    let mut update = |field: &str| {
+        hasher.update(field.as_bytes());
+        hasher.update([PART_SEPARATOR]);
+    };
+    update(&type_doc.russian_name);
+    update(&type_doc.english_name);
+    update(&type_doc.description);
+    update(&format!("{:?}", type_doc.available_facets));
+    update(type_doc.source_file.as_deref().unwrap_or_default());
+    for list in [&type_doc.aliases, &type_doc.hierarchy_path] {
+        // Длина списка: элементы соседних списков не перетекают друг в друга
+        update(&list.len().to_string());
+        for item in list {
+            update(item);
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_ids() {
+        let id = type_id("platform", "ТаблицаЗначений");
+        assert_eq!(id, type_id("platform", " таблицазначений"));
+        assert_ne!(id, type_id("configuration", "ТаблицаЗначений"));
+        assert!(id.starts_with("type_"));
+        assert_eq!(id.len(), "type_".len() + ID_HASH_LEN);
+
+        let filter = platform_type_id("/tmp/help/objects/catalog1/Filter.html", "Отбор");
+        assert_ne!(
+            filter,
+            platform_type_id("/tmp/help/objects/catalog2/Filter.html", "Отбор")
+        );
+        assert_eq!(
+            filter,
+            platform_type_id("C:\\help\\objects\\catalog1\\Filter.html", "отбор")
+        );
+
+        // Части не склеиваются: ["аб", "в"] и ["а", "бв"] различаются
+        assert_ne!(category_id(&["аб", "в"]), category_id(&["а", "бв"]));
+    }
+}
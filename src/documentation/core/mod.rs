@@ -7,12 +7,15 @@ use tokio::sync::RwLock;
 
 use super::configuration::ConfigurationDocumentationProvider;
 use super::platform::PlatformDocumentationProvider;
+#[cfg(feature = "docs-render")]
 use super::render::RenderEngine;
+#[cfg(feature = "docs-search")]
 use super::search::{AdvancedSearchQuery, DocumentationSearchEngine, SearchResults};
 use crate::documentation::core::providers::DocumentationProvider;
 
 pub mod cache;
 pub mod hierarchy;
+pub mod ids;
 pub mod providers;
 pub mod statistics;
 
@@ -33,12 +36,14 @@ pub struct BslDocumentationSystem {
     configuration_provider: Arc<ConfigurationDocumentationProvider>,
 
     /// Система поиска и индексации
+    #[cfg(feature = "docs-search")]
     search_engine: Arc<DocumentationSearchEngine>,
 
     /// Кеш для производительности
     cache_manager: Arc<DocumentationCache>,
 
     /// Система рендеринга
+    #[cfg(feature = "docs-render")]
     render_engine: Arc<RenderEngine>,
 
     /// Собранная иерархия типов
@@ -127,8 +132,10 @@ impl BslDocumentationSystem {
         Self {
             platform_provider: Arc::new(PlatformDocumentationProvider::new()),
             configuration_provider: Arc::new(ConfigurationDocumentationProvider::new()),
+            #[cfg(feature = "docs-search")]
             search_engine: Arc::new(DocumentationSearchEngine::new()),
             cache_manager: Arc::new(DocumentationCache::new()),
+            #[cfg(feature = "docs-render")]
             render_engine: Arc::new(RenderEngine::new()),
             hierarchy_cache: Arc::new(RwLock::new(None)),
             initialization_status: Arc::new(RwLock::new(InitializationStatus::default())),
@@ -166,6 +173,7 @@ impl BslDocumentationSystem {
         }
 
         // Строим поисковые индексы
+        #[cfg(feature = "docs-search")]
         self.build_search_indexes().await?;
 
         // Собираем полную иерархию
@@ -204,6 +212,7 @@ impl BslDocumentationSystem {
     }
 
     /// Поиск в документации
+    #[cfg(feature = "docs-search")]
     pub async fn search(&self, query: AdvancedSearchQuery) -> Result<SearchResults> {
         self.search_engine.search(query).await
     }
@@ -241,7 +250,10 @@ impl BslDocumentationSystem {
     pub async fn get_statistics(&self) -> Result<DocumentationStatistics> {
         let platform_stats = self.platform_provider.get_statistics().await?;
         let config_stats = self.configuration_provider.get_statistics().await?;
+        #[cfg(feature = "docs-search")]
         let search_stats = self.search_engine.get_statistics().await?;
+        #[cfg(not(feature = "docs-search"))]
+        let search_stats = Default::default();
 
         Ok(DocumentationStatistics {
             platform: platform_stats,
@@ -254,6 +266,7 @@ impl BslDocumentationSystem {
 
     // Приватные методы инициализации
 
+    #[cfg(feature = "docs-search")]
     async fn build_search_indexes(&self) -> Result<()> {
         {
             let mut status = self.initialization_status.write().await;
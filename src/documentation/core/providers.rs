@@ -1,6 +1,6 @@
 //! Трейты и базовые реализации провайдеров документации
 
-use anyhow::Result;
+use crate::error::Result;
 use async_trait::async_trait;
 
 use super::hierarchy::{DocumentationNode, RootCategoryNode, TypeDocumentationFull};
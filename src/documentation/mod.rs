@@ -7,21 +7,29 @@
 //! - `core` - центральная система и координация
 //! - `platform` - документация платформенных типов
 //! - `configuration` - документация конфигурационных типов  
-//! - `search` - система поиска и индексации
-//! - `render` - рендеринг в разные форматы
+//! - `search` - система поиска и индексации (движок — feature `docs-search`;
+//!   типы запросов и результатов доступны всегда)
+//! - `render` - рендеринг в разные форматы (feature `docs-render`)
+//! - `overlay` - заметки команды к типам поверх документации
 
 pub mod configuration;
 pub mod core;
+pub mod overlay;
 pub mod platform;
+#[cfg(feature = "docs-render")]
 pub mod render;
 pub mod search;
 
 // Re-exports для удобства
 pub use configuration::ConfigurationDocumentationProvider;
 pub use core::{BslDocumentationSystem, DocumentationNode, TypeDocumentationFull};
+pub use overlay::{Approval, NoteKind, NoteStatus, TeamNote, TeamNotesOverlay};
 pub use platform::PlatformDocumentationProvider;
+#[cfg(feature = "docs-render")]
 pub use render::{HtmlDocumentationRenderer, RenderEngine};
+#[cfg(feature = "docs-search")]
+pub use search::DocumentationSearchEngine;
 pub use search::{
-    AdvancedSearchQuery, DocumentationSearchEngine, SearchFilters, SearchOptions, SearchPagination,
-    SearchResults, SearchSort, SortDirection, SortField,
+    AdvancedSearchQuery, SearchFilters, SearchOptions, SearchPagination, SearchResults, SearchSort,
+    SortDirection, SortField,
 };
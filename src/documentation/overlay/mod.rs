@@ -0,0 +1,447 @@
+//! Заметки команды к документации типов
+//!
+//! Команда может дополнить справку платформы и описание конфигурации своими
+//! заметками, примерами и предупреждениями («в этом проекте
+//! `Запрос.Выполнить()` только через `ОбщийМодуль.Запросы`»). Заметки лежат в
+//! каталоге репозитория проекта — по YAML-файлу на тип — и редактируются
+//! вручную или через веб-интерфейс:
+//!
+//! ```yaml
+//! type: ТаблицаЗначений
+//! notes:
+//!   - kind: warning
+//!     member: Найти
+//!     text: На больших таблицах используйте НайтиСтроки с индексом
+//!     author: Иванов
+//! ```
+//!
+//! Заметки выводятся в документации и подсказке при наведении с отдельной
+//! пометкой, чтобы их нельзя было спутать со справкой платформы.
+//!
+//! Заметка, предложенная через веб-интерфейс, сначала остаётся черновиком
+//! (`status: draft`) и видна только в веб-интерфейсе; в подсказки она попадает
+//! после того, как её одобрит другой участник команды (`reviewer`). Заметки,
+//! записанные в файлы вручную, без `status` считаются опубликованными — они
+//! проходят обычное ревью вместе с репозиторием.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::data::WriteGuard;
+
+/// Вид заметки
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteKind {
+    #[default]
+    Note,
+    Example,
+    Warning,
+}
+
+impl NoteKind {
+    /// Пометка, которой заметка выделяется в документации
+    pub fn badge(self) -> &'static str {
+        match self {
+            NoteKind::Note => "Заметка команды",
+            NoteKind::Example => "Пример команды",
+            NoteKind::Warning => "Предупреждение команды",
+        }
+    }
+}
+
+/// Состояние заметки
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteStatus {
+    /// Ждёт одобрения, в подсказках не показывается
+    Draft,
+    #[default]
+    Published,
+}
+
+/// Итог одобрения заметки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Approval {
+    Approved,
+    /// Такой заметки нет
+    NotFound,
+    AlreadyPublished,
+    /// Рецензент — автор заметки
+    OwnNote,
+}
+
+/// Заметка к типу или его члену
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamNote {
+    #[serde(default)]
+    pub kind: NoteKind,
+    pub text: String,
+    /// Метод или свойство типа; `None` — заметка ко всему типу
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub status: NoteStatus,
+    /// Кто одобрил черновик
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewer: Option<String>,
+}
+
+/// Содержимое файла заметок одного типа
+#[derive(Debug, Serialize, Deserialize)]
+struct NotesFile {
+    #[serde(rename = "type")]
+    type_name: String,
+    #[serde(default)]
+    notes: Vec<TeamNote>,
+}
+
+#[derive(Debug, Clone)]
+struct TypeNotes {
+    type_name: String,
+    path: PathBuf,
+    notes: Vec<TeamNote>,
+}
+
+/// Заметки команды из каталога с YAML-файлами
+#[derive(Debug, Clone, Default)]
+pub struct TeamNotesOverlay {
+    dir: PathBuf,
+    /// Имя типа в нижнем регистре -> заметки
+    types: HashMap<String, TypeNotes>,
+    write_guard: WriteGuard,
+}
+
+impl TeamNotesOverlay {
+    /// Прочитать `*.yaml`/`*.yml` каталога; отсутствующий каталог — пустой
+    /// набор заметок, нераспознанные файлы пропускаются с предупреждением
+    pub fn load(dir: &Path, write_guard: WriteGuard) -> Result<Self> {
+        let mut overlay = Self {
+            dir: dir.to_path_buf(),
+            types: HashMap::new(),
+            write_guard,
+        };
+        if !dir.is_dir() {
+            return Ok(overlay);
+        }
+        let entries = std::fs::read_dir(dir)
+            .with_context(|| format!("Не удалось прочитать каталог {}", dir.display()))?;
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|ext| ext.to_str()),
+                    Some("yaml" | "yml")
+                )
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| Ok(serde_yaml::from_str::<NotesFile>(&content)?));
+            match parsed {
+                Ok(file) => {
+                    let entry = overlay
+                        .types
+                        .entry(file.type_name.to_lowercase())
+                        .or_insert_with(|| TypeNotes {
+                            type_name: file.type_name.clone(),
+                            path: path.clone(),
+                            notes: Vec::new(),
+                        });
+                    entry.notes.extend(file.notes);
+                }
+                Err(e) => warn!("Файл заметок {} пропущен: {:#}", path.display(), e),
+            }
+        }
+        Ok(overlay)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Заметки типа (без учёта регистра имени)
+    pub fn notes_for(&self, type_name: &str) -> &[TeamNote] {
+        self.types
+            .get(&type_name.to_lowercase())
+            .map(|entry| entry.notes.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Типы, к которым есть заметки
+    pub fn type_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .types
+            .values()
+            .map(|entry| entry.type_name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Добавить заметку и сохранить файл типа
+    pub fn add(&mut self, type_name: &str, note: TeamNote) -> Result<()> {
+        let dir = self.dir.clone();
+        let entry = self
+            .types
+            .entry(type_name.to_lowercase())
+            .or_insert_with(|| TypeNotes {
+                type_name: type_name.to_string(),
+                path: dir.join(format!("{}.yaml", file_stem(type_name))),
+                notes: Vec::new(),
+            });
+        entry.notes.push(note);
+        self.save(type_name)
+    }
+
+    /// Предложить заметку: сохраняется черновиком без рецензента
+    pub fn submit(&mut self, type_name: &str, note: TeamNote) -> Result<()> {
+        self.add(
+            type_name,
+            TeamNote {
+                status: NoteStatus::Draft,
+                reviewer: None,
+                ..note
+            },
+        )
+    }
+
+    /// Одобрить черновик по номеру: заметка публикуется с именем
+    /// рецензента; автор не может одобрить свою заметку
+    pub fn approve(&mut self, type_name: &str, index: usize, reviewer: &str) -> Result<Approval> {
+        let Some(note) = self
+            .types
+            .get_mut(&type_name.to_lowercase())
+            .and_then(|entry| entry.notes.get_mut(index))
+        else {
+            return Ok(Approval::NotFound);
+        };
+        if note.status == NoteStatus::Published {
+            return Ok(Approval::AlreadyPublished);
+        }
+        let reviewer = reviewer.trim();
+        let own = note
+            .author
+            .as_deref()
+            .is_some_and(|author| author.trim().to_lowercase() == reviewer.to_lowercase());
+        if own {
+            return Ok(Approval::OwnNote);
+        }
+        note.status = NoteStatus::Published;
+        note.reviewer = Some(reviewer.to_string());
+        self.save(type_name)?;
+        Ok(Approval::Approved)
+    }
+
+    /// Черновики всех типов для ревью: (тип, номер заметки, заметка)
+    pub fn drafts(&self) -> Vec<(&str, usize, &TeamNote)> {
+        let mut drafts: Vec<(&str, usize, &TeamNote)> = self
+            .types
+            .values()
+            .flat_map(|entry| {
+                entry
+                    .notes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, note)| note.status == NoteStatus::Draft)
+                    .map(|(index, note)| (entry.type_name.as_str(), index, note))
+            })
+            .collect();
+        drafts.sort_by_key(|(type_name, index, _)| (*type_name, *index));
+        drafts
+    }
+
+    /// Удалить заметку по номеру; `false` — такой заметки нет
+    pub fn remove(&mut self, type_name: &str, index: usize) -> Result<bool> {
+        let Some(entry) = self.types.get_mut(&type_name.to_lowercase()) else {
+            return Ok(false);
+        };
+        if index >= entry.notes.len() {
+            return Ok(false);
+        }
+        entry.notes.remove(index);
+        let now_empty = entry.notes.is_empty();
+        self.save(type_name)?;
+        if now_empty {
+            self.types.remove(&type_name.to_lowercase());
+        }
+        Ok(true)
+    }
+
+    /// Записать файл типа; последний удалённый — файл удаляется
+    fn save(&self, type_name: &str) -> Result<()> {
+        let Some(entry) = self.types.get(&type_name.to_lowercase()) else {
+            return Ok(());
+        };
+        if !self.write_guard.allows("заметки команды", &entry.path) {
+            return Ok(());
+        }
+        if entry.notes.is_empty() {
+            if entry.path.exists() {
+                std::fs::remove_file(&entry.path)
+                    .with_context(|| format!("Не удалось удалить {}", entry.path.display()))?;
+            }
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Не удалось создать каталог {}", self.dir.display()))?;
+        let content = serde_yaml::to_string(&NotesFile {
+            type_name: entry.type_name.clone(),
+            notes: entry.notes.clone(),
+        })?;
+        std::fs::write(&entry.path, content)
+            .with_context(|| format!("Не удалось записать {}", entry.path.display()))
+    }
+
+    /// Опубликованные заметки типа для подсказки при наведении; `None` —
+    /// таких заметок нет
+    pub fn hover_markdown(&self, type_name: &str) -> Option<String> {
+        let blocks: Vec<String> = self
+            .notes_for(type_name)
+            .iter()
+            .filter(|note| note.status == NoteStatus::Published)
+            .map(|note| {
+                let mut header = format!("**[{}]**", note.kind.badge());
+                if let Some(member) = &note.member {
+                    header.push_str(&format!(" `{}`", member));
+                }
+                if let Some(author) = &note.author {
+                    header.push_str(&format!(" — {}", author));
+                }
+                let text = if note.kind == NoteKind::Example {
+                    format!("```bsl\n{}\n```", note.text.trim_end())
+                } else {
+                    note.text.trim_end().to_string()
+                };
+                format!("{}\n\n{}", header, text)
+            })
+            .collect();
+        (!blocks.is_empty()).then(|| blocks.join("\n\n---\n\n"))
+    }
+
+    /// Количество типов с заметками
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+}
+
+/// Имя файла по имени типа: символы, недопустимые в путях, заменяются на `_`
+fn file_stem(type_name: &str) -> String {
+    type_name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_team_notes_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("table.yaml"),
+            "type: ТаблицаЗначений\nnotes:\n  - kind: warning\n    member: Найти\n    \
+             text: Используйте индекс\n    author: Иванов\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("broken.yml"), "notes: [").unwrap();
+
+        let mut overlay = TeamNotesOverlay::load(dir.path(), WriteGuard::default()).unwrap();
+        assert_eq!(overlay.len(), 1);
+        assert_eq!(
+            overlay.notes_for("таблицазначений")[0].kind,
+            NoteKind::Warning
+        );
+        let hover = overlay.hover_markdown("ТаблицаЗначений").unwrap();
+        assert!(hover.contains("**[Предупреждение команды]** `Найти` — Иванов"));
+
+        let example = TeamNote {
+            kind: NoteKind::Example,
+            text: "Запрос.Выполнить();".to_string(),
+            member: None,
+            author: None,
+            status: NoteStatus::Published,
+            reviewer: None,
+        };
+        overlay.add("Справочник.Товары", example.clone()).unwrap();
+        overlay.remove("ТаблицаЗначений", 0).unwrap();
+        assert!(!dir.path().join("table.yaml").exists());
+        assert!(!overlay.remove("ТаблицаЗначений", 0).unwrap());
+
+        let reloaded = TeamNotesOverlay::load(dir.path(), WriteGuard::default()).unwrap();
+        assert_eq!(reloaded.notes_for("Справочник.Товары"), [example]);
+        assert!(reloaded.notes_for("ТаблицаЗначений").is_empty());
+
+        let read_only = TeamNotesOverlay::load(dir.path(), WriteGuard::new(true));
+        let mut read_only = read_only.unwrap();
+        read_only.remove("Справочник.Товары", 0).unwrap();
+        assert!(dir.path().join("Справочник.Товары.yaml").exists());
+    }
+
+    #[test]
+    fn test_draft_review() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut overlay = TeamNotesOverlay::load(dir.path(), WriteGuard::default()).unwrap();
+        let draft = TeamNote {
+            kind: NoteKind::Warning,
+            text: "Не вызывать в цикле".to_string(),
+            member: Some("Выполнить".to_string()),
+            author: Some("Иванов".to_string()),
+            status: NoteStatus::Published,
+            reviewer: Some("Иванов".to_string()),
+        };
+        overlay.submit("Запрос", draft).unwrap();
+        assert_eq!(overlay.notes_for("Запрос")[0].status, NoteStatus::Draft);
+        assert_eq!(overlay.notes_for("Запрос")[0].reviewer, None);
+        assert_eq!(overlay.hover_markdown("Запрос"), None);
+        assert_eq!(overlay.drafts().len(), 1);
+
+        assert_eq!(
+            overlay.approve("Запрос", 0, " иванов").unwrap(),
+            Approval::OwnNote
+        );
+        assert_eq!(
+            overlay.approve("Запрос", 1, "Петров").unwrap(),
+            Approval::NotFound
+        );
+        assert_eq!(
+            overlay.approve("Запрос", 0, "Петров").unwrap(),
+            Approval::Approved
+        );
+        assert_eq!(
+            overlay.approve("Запрос", 0, "Сидоров").unwrap(),
+            Approval::AlreadyPublished
+        );
+        assert!(overlay.drafts().is_empty());
+
+        let reloaded = TeamNotesOverlay::load(dir.path(), WriteGuard::default()).unwrap();
+        let note = &reloaded.notes_for("Запрос")[0];
+        assert_eq!(note.status, NoteStatus::Published);
+        assert_eq!(note.reviewer.as_deref(), Some("Петров"));
+        assert!(reloaded
+            .hover_markdown("Запрос")
+            .unwrap()
+            .contains("Не вызывать в цикле"));
+    }
+}
@@ -4,15 +4,22 @@ use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tracing::info;
 
 use super::core::hierarchy::{
-    AvailabilityContext, CodeExample, DocumentationNode, MethodDocumentation,
-    PropertyDocumentation, RootCategoryNode, TypeDocumentationFull, UiMetadata,
+    AvailabilityContext, CodeExample, DocumentationNode, MethodDocumentation, MethodOverload,
+    ParameterDocumentation, PropertyDocumentation, RootCategoryNode, TypeDocumentationFull,
+    UiMetadata,
 };
+use super::core::ids::{category_id, platform_type_id};
 use super::core::providers::{DocumentationProvider, ProviderConfig};
 use super::core::statistics::{InitializationStatus, ProviderStatistics};
 use super::search::AdvancedSearchQuery;
-use crate::data::loaders::syntax_helper_parser::SyntaxHelperParser;
+use crate::core::compilation_context::GlobalAvailability;
+use crate::data::loaders::syntax_helper_parser::{
+    MethodInfo, OptimizationSettings, ParameterInfo, SyntaxHelperParser,
+};
+use crate::error::ResultExt;
 use crate::domain::types::{FacetKind, Method, Property, TypeResolution};
 
 /// Провайдер документации платформенных типов
@@ -111,7 +118,7 @@ impl PlatformDocumentationProvider {
             ..Default::default()
         };
 
-        self.initialize(&parser_config).await
+        Ok(self.initialize(&parser_config).await?)
     }
 
     /// Получить количество загруженных типов
@@ -133,10 +140,61 @@ impl PlatformDocumentationProvider {
             .collect())
     }
 
-    /// Конвертировать SyntaxNode в TypeDocumentationFull
+    /// Документация метода типа по имени (русскому или английскому, без
+    /// учёта регистра): параметры и варианты синтаксиса для подсказки сигнатур
+    pub async fn find_method(
+        &self,
+        type_name: &str,
+        method_name: &str,
+    ) -> Option<MethodDocumentation> {
+        let matches = |russian: &str, english: &str, name: &str| {
+            russian.to_lowercase() == name.to_lowercase()
+                || (!english.is_empty() && english.to_lowercase() == name.to_lowercase())
+        };
+        let cache = self.types_cache.read().await;
+        cache
+            .values()
+            .filter(|t| matches(&t.russian_name, &t.english_name, type_name))
+            .flat_map(|t| t.methods.iter())
+            .find(|m| matches(&m.russian_name, &m.english_name, method_name))
+            .cloned()
+    }
+
+    /// Доступность глобальных методов (клиент, сервер, ...) по русскому и
+    /// английскому имени — для отбора автодополнения по директиве компиляции
+    pub async fn global_availability(&self) -> GlobalAvailability {
+        use crate::data::loaders::syntax_helper_parser::SyntaxNode;
+
+        let parser = self.syntax_parser.read().await;
+        let mut availability = GlobalAvailability::default();
+        for node in parser.export_database().nodes.values() {
+            let SyntaxNode::GlobalFunction(func) = node else {
+                continue;
+            };
+            let mut contexts: Vec<AvailabilityContext> = Vec::new();
+            for context in func
+                .contexts
+                .iter()
+                .filter_map(|c| self.parse_availability_context(c))
+            {
+                if !contexts.contains(&context) {
+                    contexts.push(context);
+                }
+            }
+            if let Some(english) = &func.english_name {
+                availability.insert(english, contexts.clone());
+            }
+            availability.insert(&func.name, contexts);
+        }
+        availability
+    }
+
+    /// Конвертировать SyntaxNode в TypeDocumentationFull; `type_methods` —
+    /// страницы методов типа из справки
     async fn convert_syntax_node_to_documentation(
         &self,
         node: &crate::data::loaders::syntax_helper_parser::SyntaxNode,
+        type_methods: &[&MethodInfo],
     ) -> Result<TypeDocumentationFull> {
         use super::core::hierarchy::DocumentationSourceType;
         use crate::data::loaders::syntax_helper_parser::SyntaxNode;
@@ -181,6 +239,7 @@ impl PlatformDocumentationProvider {
                             ),
                             format!("aliases:{}", type_info.identity.aliases.join(",")),
                         ],
+                        resolver: None,
                     },
                     active_facet: type_info.metadata.default_facet,
                     available_facets: type_info.metadata.available_facets.clone(),
@@ -188,7 +247,7 @@ impl PlatformDocumentationProvider {
 
                 // Конвертируем методы в полную документацию
                 let methods = self
-                    .convert_methods_full(&type_info.structure.methods)
+                    .convert_methods_full(&type_info.structure.methods, type_methods)
                     .await?;
 
                 // Конвертируем свойства в полную документацию
@@ -223,7 +282,10 @@ impl PlatformDocumentationProvider {
 
                 Ok(TypeDocumentationFull {
                     // === ИДЕНТИФИКАЦИЯ ===
-                    id: type_info.identity.catalog_path.clone(),
+                    id: platform_type_id(
+                        &type_info.identity.catalog_path,
+                        &type_info.identity.russian_name,
+                    ),
                     russian_name: type_info.identity.russian_name.clone(),
                     english_name: type_info.identity.english_name.clone(),
                     aliases: type_info.identity.aliases.clone(),
@@ -338,7 +400,7 @@ impl PlatformDocumentationProvider {
         for (category_name, types) in categories_map {
             if !types.is_empty() {
                 let category_node = SubCategoryNode {
-                    id: format!("platform_category_{}", category_name.replace(' ', "_")),
+                    id: category_id(&["Платформа", &category_name]),
                     name: category_name.clone(),
                     description: format!("Платформенные типы категории: {}", category_name),
                     hierarchy_path: vec!["Платформа".to_string(), category_name.clone()],
@@ -404,7 +466,7 @@ impl DocumentationProvider for PlatformDocumentationProvider {
         "Платформенные типы 1С"
     }
 
-    async fn initialize(&self, config: &ProviderConfig) -> Result<()> {
+    async fn initialize(&self, config: &ProviderConfig) -> crate::Result<()> {
         {
             let mut status = self.initialization_status.write().await;
             status.is_initializing = true;
@@ -415,8 +477,20 @@ impl DocumentationProvider for PlatformDocumentationProvider {
         // Инициализируем парсер
         {
             let mut parser = self.syntax_parser.write().await;
+            if !config.performance_settings.show_progress {
+                // Headless-режим: без прогресс-баров в stdout
+                *parser = SyntaxHelperParser::with_settings(OptimizationSettings {
+                    show_progress: false,
+                    ..OptimizationSettings::default()
+                });
+            }
             if std::path::Path::new(&config.data_source).exists() {
-                parser.parse_directory(&config.data_source)?;
+                parser
+                    .parse_directory(&config.data_source)
+                    .parse_err(format!(
+                        "Не удалось разобрать синтакс-помощник {}",
+                        config.data_source
+                    ))?;
             }
         }
 
@@ -427,7 +501,9 @@ impl DocumentationProvider for PlatformDocumentationProvider {
         }
 
         // Строим кеш типов
-        self.build_types_cache().await?;
+        self.build_types_cache()
+            .await
+            .data_source_err("Не удалось построить кеш платформенных типов")?;
 
         {
             let mut status = self.initialization_status.write().await;
@@ -436,7 +512,10 @@ impl DocumentationProvider for PlatformDocumentationProvider {
         }
 
         // Строим корневую категорию
-        let root_category = self.build_platform_root_category().await?;
+        let root_category = self
+            .build_platform_root_category()
+            .await
+            .data_source_err("Не удалось построить корневую категорию платформы")?;
         *self.root_category_cache.write().await = Some(root_category);
 
         {
@@ -449,20 +528,26 @@ impl DocumentationProvider for PlatformDocumentationProvider {
         Ok(())
     }
 
-    async fn get_root_category(&self) -> Result<RootCategoryNode> {
+    async fn get_root_category(&self) -> crate::Result<RootCategoryNode> {
         let cache = self.root_category_cache.read().await;
         match cache.as_ref() {
             Some(category) => Ok(category.clone()),
             None => {
                 drop(cache);
-                let category = self.build_platform_root_category().await?;
+                let category = self
+                    .build_platform_root_category()
+                    .await
+                    .data_source_err("Не удалось построить корневую категорию платформы")?;
                 *self.root_category_cache.write().await = Some(category.clone());
                 Ok(category)
             }
         }
     }
 
-    async fn get_type_details(&self, type_id: &str) -> Result<Option<TypeDocumentationFull>> {
+    async fn get_type_details(
+        &self,
+        type_id: &str,
+    ) -> crate::Result<Option<TypeDocumentationFull>> {
         let cache = self.types_cache.read().await;
 
         println!("🔍 Поиск типа по ID: '{}'", type_id);
@@ -500,17 +585,20 @@ impl DocumentationProvider for PlatformDocumentationProvider {
         Ok(None)
     }
 
-    async fn search_types(&self, _query: &AdvancedSearchQuery) -> Result<Vec<DocumentationNode>> {
+    async fn search_types(
+        &self,
+        _query: &AdvancedSearchQuery,
+    ) -> crate::Result<Vec<DocumentationNode>> {
         // TODO: Реализовать поиск в платформенных типах
         Ok(Vec::new())
     }
 
-    async fn get_all_types(&self) -> Result<Vec<TypeDocumentationFull>> {
+    async fn get_all_types(&self) -> crate::Result<Vec<TypeDocumentationFull>> {
         let cache = self.types_cache.read().await;
         Ok(cache.values().cloned().collect())
     }
 
-    async fn get_statistics(&self) -> Result<ProviderStatistics> {
+    async fn get_statistics(&self) -> crate::Result<ProviderStatistics> {
         let cache = self.types_cache.read().await;
 
         let types_count = cache.len();
@@ -530,11 +618,11 @@ impl DocumentationProvider for PlatformDocumentationProvider {
         })
     }
 
-    async fn get_initialization_status(&self) -> Result<InitializationStatus> {
+    async fn get_initialization_status(&self) -> crate::Result<InitializationStatus> {
         Ok(self.initialization_status.read().await.clone())
     }
 
-    async fn check_availability(&self) -> Result<bool> {
+    async fn check_availability(&self) -> crate::Result<bool> {
         let config = self.config.read().await;
         match config.as_ref() {
             Some(cfg) => Ok(std::path::Path::new(&cfg.syntax_helper_path).exists()),
@@ -542,7 +630,7 @@ impl DocumentationProvider for PlatformDocumentationProvider {
         }
     }
 
-    async fn refresh(&self) -> Result<()> {
+    async fn refresh(&self) -> crate::Result<()> {
         // Очищаем кеши
         self.types_cache.write().await.clear();
         *self.root_category_cache.write().await = None;
@@ -568,17 +656,37 @@ impl PlatformDocumentationProvider {
         let parser = self.syntax_parser.read().await;
         let database = parser.export_database();
 
+        // Страницы методов озаглавлены «Тип.Метод (Type.Method)»
+        let mut methods_by_type: std::collections::HashMap<&str, Vec<&MethodInfo>> =
+            std::collections::HashMap::new();
+        for method in database.methods.values() {
+            if let Some((type_name, _)) = method.name.split_once('.') {
+                methods_by_type
+                    .entry(type_name.trim())
+                    .or_default()
+                    .push(method);
+            }
+        }
+
         let mut cache = self.types_cache.write().await;
 
-        for (path, node) in &database.nodes {
-            if let SyntaxNode::Type(_) = node {
-                if let Ok(type_doc) = self.convert_syntax_node_to_documentation(node).await {
-                    cache.insert(path.clone(), type_doc);
+        for node in database.nodes.values() {
+            if let SyntaxNode::Type(type_info) = node {
+                let type_methods = methods_by_type
+                    .get(type_info.identity.russian_name.as_str())
+                    .map(Vec::as_slice)
+                    .unwrap_or(&[]);
+                if let Ok(type_doc) = self
+                    .convert_syntax_node_to_documentation(node, type_methods)
+                    .await
+                {
+                    cache.insert(type_doc.id.clone(), type_doc);
                 }
             }
         }
 
-        println!("📊 Построен кеш платформенных типов: {} типов", cache.len());
+        // stdout занят протоколом LSP — только в лог
+        info!("📊 Построен кеш платформенных типов: {} типов", cache.len());
         Ok(())
     }
 
@@ -609,31 +717,97 @@ impl PlatformDocumentationProvider {
             .collect())
     }
 
-    /// Конвертировать методы в полную документацию
+    /// Конвертировать методы в полную документацию: методы со страницей в
+    /// справке — с параметрами и вариантами синтаксиса, остальные — по имени
     async fn convert_methods_full(
         &self,
         method_names: &[String],
+        type_methods: &[&MethodInfo],
     ) -> Result<Vec<MethodDocumentation>> {
-        // TODO: Загрузить полную информацию о методах включая параметры и примеры
-        Ok(method_names
+        let mut methods: Vec<MethodDocumentation> = type_methods
             .iter()
-            .map(|name| {
-                // Разбираем русское и английское название
-                let (russian_name, english_name) = self.parse_method_name(name);
+            .map(|info| self.convert_method_info(info))
+            .collect();
+        let described: std::collections::HashSet<String> =
+            methods.iter().map(|m| m.russian_name.to_lowercase()).collect();
 
-                MethodDocumentation {
-                    name: name.clone(),
-                    russian_name,
-                    english_name,
-                    description: format!("Метод {}", name), // TODO: загрузить реальное описание
-                    parameters: Vec::new(),                 // TODO: загрузить параметры
-                    return_type: None,                      // TODO: определить возвращаемый тип
-                    examples: Vec::new(),                   // TODO: загрузить примеры
-                    availability: Vec::new(),               // TODO: загрузить доступность
-                    exceptions: Vec::new(),
-                }
+        // Методы без страницы в справке — только по имени
+        let undescribed = method_names
+            .iter()
+            .filter(|name| !described.contains(&self.parse_method_name(name).0.to_lowercase()));
+        for name in undescribed {
+            // Разбираем русское и английское название
+            let (russian_name, english_name) = self.parse_method_name(name);
+
+            methods.push(MethodDocumentation {
+                name: name.clone(),
+                russian_name,
+                english_name,
+                description: format!("Метод {}", name), // TODO: загрузить реальное описание
+                parameters: Vec::new(),
+                return_type: None,
+                examples: Vec::new(),     // TODO: загрузить примеры
+                availability: Vec::new(), // TODO: загрузить доступность
+                exceptions: Vec::new(),
+                overloads: Vec::new(),
+            });
+        }
+        Ok(methods)
+    }
+
+    /// Документация метода по его странице в справке
+    fn convert_method_info(&self, info: &MethodInfo) -> MethodDocumentation {
+        // Заголовок страницы: «Тип.Метод (Type.Method)»
+        let (russian, english) = self.parse_method_name(&info.name);
+        let member = |name: &str| name.rsplit('.').next().unwrap_or(name).trim().to_string();
+        let english = info.english_name.clone().unwrap_or(english);
+        let overloads = info
+            .syntax_variants
+            .iter()
+            .map(|variant| MethodOverload {
+                name: variant.name.clone(),
+                parameters: variant.parameters.iter().map(Self::convert_parameter).collect(),
             })
-            .collect())
+            .collect();
+
+        MethodDocumentation {
+            name: member(&russian),
+            russian_name: member(&russian),
+            english_name: member(&english),
+            description: info.description.clone().unwrap_or_default(),
+            parameters: info.parameters.iter().map(Self::convert_parameter).collect(),
+            return_type: info.return_type.as_deref().map(Self::named_type),
+            examples: Vec::new(),
+            availability: Vec::new(),
+            exceptions: Vec::new(),
+            overloads,
+        }
+    }
+
+    /// Документация параметра из справки
+    fn convert_parameter(info: &ParameterInfo) -> ParameterDocumentation {
+        ParameterDocumentation {
+            name: info.name.clone(),
+            parameter_type: info
+                .type_name
+                .as_deref()
+                .map(Self::named_type)
+                .unwrap_or_else(TypeResolution::unknown),
+            description: info.description.clone().unwrap_or_default(),
+            required: !info.is_optional,
+            default_value: info.default_value.clone().filter(|v| !v.is_empty()),
+        }
+    }
+
+    /// Тип по имени из справки (может быть перечислением: «Строка, Число»)
+    fn named_type(name: &str) -> TypeResolution {
+        use crate::domain::types::{ConcreteType, PlatformType};
+
+        TypeResolution::known(ConcreteType::Platform(PlatformType {
+            name: name.trim().to_string(),
+            methods: Vec::new(),
+            properties: Vec::new(),
+        }))
     }
 
     /// Конвертировать свойства в полную документацию
@@ -680,18 +854,20 @@ impl PlatformDocumentationProvider {
 
     /// Парсинг контекста доступности
     fn parse_availability_context(&self, availability: &str) -> Option<AvailabilityContext> {
-        match availability.to_lowercase().as_str() {
-            "клиент" | "client" => Some(AvailabilityContext::Client),
+        // В справке перечисление вида «Тонкий клиент, сервер, мобильное
+        // приложение (сервер).»
+        let availability = availability.trim().trim_end_matches('.').to_lowercase();
+        match availability.as_str() {
+            "клиент" | "client" | "тонкий клиент" | "thin client" | "толстый клиент"
+            | "thick client" => Some(AvailabilityContext::Client),
             "сервер" | "server" => Some(AvailabilityContext::Server),
             "внешнее соединение" | "external connection" => {
                 Some(AvailabilityContext::ExternalConnection)
             }
-            "мобильное приложение" | "mobile app" => {
-                Some(AvailabilityContext::MobileApp)
-            }
-            "мобильный сервер" | "mobile server" => {
-                Some(AvailabilityContext::MobileServer)
-            }
+            "мобильное приложение" | "mobile app" | "мобильное приложение (клиент)"
+            | "мобильный клиент" | "mobile client" => Some(AvailabilityContext::MobileApp),
+            "мобильный сервер" | "mobile server" | "мобильное приложение (сервер)"
+            | "мобильный автономный сервер" => Some(AvailabilityContext::MobileServer),
             "веб-клиент" | "web client" => Some(AvailabilityContext::WebClient),
             _ => None,
         }
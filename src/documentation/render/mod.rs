@@ -484,7 +484,7 @@ impl HtmlDocumentationRenderer {
         &self,
         category: &super::core::hierarchy::CategoryNode,
     ) -> InteractiveTreeNode {
-        let node_id = format!("category_{}", category.name.replace(" ", "_"));
+        let node_id = category.id.clone();
 
         // Динамическая группировка - показываем только подкатегории с типами
         let mut children = Vec::new();
@@ -520,7 +520,7 @@ impl HtmlDocumentationRenderer {
                         };
 
                         children.push(InteractiveTreeNode {
-                            id: format!("subcategory_{}", sub_cat.name.replace(" ", "_")),
+                            id: sub_cat.id.clone(),
                             display_name: format!(
                                 "{} ({} типов)",
                                 sub_cat.name,
@@ -535,10 +535,7 @@ impl HtmlDocumentationRenderer {
                             children: Vec::new(), // Lazy loading дочерних типов
                             has_children: true,
                             children_loaded: false,
-                            children_url: Some(format!(
-                                "/api/tree/children/subcategory_{}",
-                                sub_cat.name.replace(" ", "_")
-                            )),
+                            children_url: Some(format!("/api/tree/children/{}", sub_cat.id)),
                             metadata: {
                                 let mut meta = std::collections::HashMap::new();
                                 meta.insert("type".to_string(), "subcategory".to_string());
@@ -1786,7 +1783,7 @@ document.addEventListener('DOMContentLoaded', function() {
 
         // Простой рендеринг без глубокой рекурсии
         for category in categories {
-            let node_id = format!("category_{}", category.name.replace(" ", "_"));
+            let node_id = &category.id;
 
             html.push_str(&format!(
                 "<div class='tree-node category-node' id='{}' onclick='toggleTreeNode(\"{}\")'>\n\
@@ -1880,7 +1877,7 @@ document.addEventListener('DOMContentLoaded', function() {
         depth: usize,
     ) -> Result<String> {
         let indent = "  ".repeat(depth);
-        let node_id = format!("type_{}", type_doc.id);
+        let node_id = &type_doc.id;
 
         Ok(format!(
             "{}<div class='tree-node type-node' id='{}' onclick='selectType(\"{}\", \"{}\")'>\n\
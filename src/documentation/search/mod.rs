@@ -1,19 +1,51 @@
 //! Система поиска и индексации документации
+//!
+//! Документ индекса — тип провайдера с идентификатором
+//! `{provider_id}:{type_id}`. При обновлении провайдера (смена конфигурации)
+//! индексы меняются инкрементально: удаляются исчезнувшие и изменившиеся
+//! документы, добавляются новые и изменившиеся, а неизменные остаются на
+//! месте. Каждый индекс блокируется только на время своей правки, поэтому
+//! поиск продолжает работать по прежним данным, пока готовятся новые.
+//!
+//! Кроме полнотекстового поиска по словам есть поиск по имени
+//! (`search_by_name`): префиксы, подстроки и первые буквы слов
+//! (`ТабЗн`, `тз`) — для навигации по символам из редактора.
+//!
+//! Сам движок и его индексы собираются с фичей `docs-search`; типы запросов
+//! и результатов доступны всегда — на них построены интерфейс провайдеров
+//! документации и рендеринг.
 
-use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
 
 use super::core::hierarchy::{AvailabilityContext, DocumentationSourceType};
-use super::core::providers::DocumentationProvider;
 use crate::domain::types::FacetKind;
 
+#[cfg(feature = "docs-search")]
+use super::core::hierarchy::TypeDocumentationFull;
+#[cfg(feature = "docs-search")]
+use super::core::ids::content_hash;
+#[cfg(feature = "docs-search")]
+use super::core::providers::DocumentationProvider;
+#[cfg(feature = "docs-search")]
+use crate::core::quick_open::match_score;
+#[cfg(feature = "docs-search")]
+use anyhow::Result;
+#[cfg(feature = "docs-search")]
+use std::collections::HashSet;
+#[cfg(feature = "docs-search")]
+use std::sync::Arc;
+#[cfg(feature = "docs-search")]
+use tokio::sync::RwLock;
+#[cfg(feature = "docs-search")]
+use tracing::{debug, info};
+
+#[cfg(feature = "docs-search")]
 pub mod fuzzy;
 // Импорты провайдеров через re-exports
 
 /// Система поиска и индексации документации
+#[cfg(feature = "docs-search")]
 pub struct DocumentationSearchEngine {
     /// Полнотекстовый индекс
     fulltext_index: Arc<RwLock<FullTextIndex>>,
@@ -32,6 +64,9 @@ pub struct DocumentationSearchEngine {
 
     /// Fuzzy matcher для нечеткого поиска
     fuzzy_matcher: Arc<RwLock<fuzzy::FuzzyMatcher>>,
+
+    /// Обновления индексов выполняются по одному
+    update_lock: Arc<tokio::sync::Mutex<()>>,
 }
 
 /// Расширенный запрос поиска
@@ -216,6 +251,10 @@ pub struct SearchResultItem {
 
     /// Путь в иерархии
     pub breadcrumb: Vec<String>,
+
+    /// Файл, из которого получен тип (страница справки, описание объекта)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<String>,
 }
 
 /// Выделенный фрагмент
@@ -271,6 +310,7 @@ pub struct PaginationInfo {
 }
 
 /// Полнотекстовый индекс
+#[cfg(feature = "docs-search")]
 #[derive(Debug, Default)]
 pub struct FullTextIndex {
     /// Индекс слов → документы
@@ -279,11 +319,15 @@ pub struct FullTextIndex {
     /// Индекс документов
     document_index: HashMap<String, DocumentIndexEntry>,
 
+    /// Слова документа (для удаления документа из индекса слов)
+    document_words: HashMap<String, HashSet<String>>,
+
     /// Настройки индексации
     indexing_config: IndexingConfig,
 }
 
 /// Индексированный документ
+#[cfg(feature = "docs-search")]
 #[derive(Debug, Clone)]
 pub struct IndexedDocument {
     /// ID документа
@@ -297,6 +341,7 @@ pub struct IndexedDocument {
 }
 
 /// Запись в индексе документа
+#[cfg(feature = "docs-search")]
 #[derive(Debug, Clone)]
 pub struct DocumentIndexEntry {
     /// ID документа
@@ -310,9 +355,14 @@ pub struct DocumentIndexEntry {
 
     /// Метаданные
     pub metadata: DocumentMetadata,
+
+    /// Хеш индексируемых полей: документ с тем же хешем при обновлении
+    /// провайдера не переиндексируется
+    pub fingerprint: String,
 }
 
 /// Метаданные документа для индексации
+#[cfg(feature = "docs-search")]
 #[derive(Debug, Clone)]
 pub struct DocumentMetadata {
     /// Тип документа
@@ -329,9 +379,19 @@ pub struct DocumentMetadata {
 
     /// Популярность (количество обращений)
     pub popularity_score: f64,
+
+    /// Английское имя типа (для поиска по имени)
+    pub english_name: String,
+
+    /// Источник типа
+    pub source_type: DocumentationSourceType,
+
+    /// Файл, из которого получен тип
+    pub source_file: Option<String>,
 }
 
 /// Конфигурация индексации
+#[cfg(feature = "docs-search")]
 #[derive(Debug, Clone)]
 pub struct IndexingConfig {
     /// Минимальная длина слова для индексации
@@ -351,6 +411,7 @@ pub struct IndexingConfig {
 }
 
 /// Индекс по категориям
+#[cfg(feature = "docs-search")]
 #[derive(Debug, Default)]
 pub struct CategoryIndex {
     /// Категория → типы
@@ -361,6 +422,7 @@ pub struct CategoryIndex {
 }
 
 /// Индекс по фасетам
+#[cfg(feature = "docs-search")]
 #[derive(Debug, Default)]
 pub struct FacetIndex {
     /// Фасет → типы
@@ -371,6 +433,7 @@ pub struct FacetIndex {
 }
 
 /// Кешированный результат поиска
+#[cfg(feature = "docs-search")]
 #[derive(Debug, Clone)]
 struct CachedSearchResult {
     /// Результаты
@@ -411,6 +474,19 @@ pub struct SearchStatistics {
     pub query_type_stats: HashMap<String, usize>,
 }
 
+/// Итог инкрементального обновления индексов провайдера
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IndexUpdateSummary {
+    /// Новые документы
+    pub added: usize,
+    /// Изменившиеся документы (переиндексированы)
+    pub updated: usize,
+    /// Исчезнувшие документы
+    pub removed: usize,
+    /// Документы без изменений
+    pub unchanged: usize,
+}
+
 /// Популярный запрос
 #[derive(Debug, Clone, Serialize)]
 pub struct PopularQuery {
@@ -424,6 +500,7 @@ pub struct PopularQuery {
     pub average_score: f64,
 }
 
+#[cfg(feature = "docs-search")]
 impl DocumentationSearchEngine {
     /// Создать новую систему поиска
     pub fn new() -> Self {
@@ -434,6 +511,7 @@ impl DocumentationSearchEngine {
             query_cache: Arc::new(RwLock::new(HashMap::new())),
             search_statistics: Arc::new(RwLock::new(SearchStatistics::default())),
             fuzzy_matcher: Arc::new(RwLock::new(fuzzy::FuzzyMatcher::default_for_bsl())),
+            update_lock: Arc::new(tokio::sync::Mutex::new(())),
         }
     }
 
@@ -443,36 +521,145 @@ impl DocumentationSearchEngine {
         platform_provider: &crate::documentation::PlatformDocumentationProvider,
         _configuration_provider: &crate::documentation::ConfigurationDocumentationProvider,
     ) -> Result<()> {
-        println!("🏗️ Начинаем построение индексов поиска...");
+        info!("Построение индексов поиска");
 
         // Получаем все типы из платформенного провайдера
         let platform_types = platform_provider.get_all_types().await?;
-        println!(
-            "📊 Получено {} платформенных типов для индексации",
+        debug!(
+            "Получено {} платформенных типов для индексации",
             platform_types.len()
         );
 
-        // Строим полнотекстовый индекс
-        self.build_fulltext_index(&platform_types).await?;
-        println!("✅ Полнотекстовый индекс построен");
+        // Повторное построение не дублирует документы: индексы обновляются
+        let summary = self
+            .update_provider_indexes(platform_provider.provider_id(), &platform_types)
+            .await;
+        info!(
+            "Индексировано {} документов (новых: {}, изменённых: {})",
+            platform_types.len(),
+            summary.added,
+            summary.updated
+        );
 
-        // Строим индексы по категориям
-        self.build_category_indexes(&platform_types).await?;
-        println!("✅ Индексы по категориям построены");
+        Ok(())
+    }
 
-        // Строим индексы по фасетам
-        self.build_facet_indexes(&platform_types).await?;
-        println!("✅ Индексы по фасетам построены");
+    /// Обновить данные провайдера и его документы в индексах. Поиск
+    /// доступен на всё время обновления.
+    pub async fn refresh_provider(
+        &self,
+        provider: &dyn DocumentationProvider,
+    ) -> Result<IndexUpdateSummary> {
+        provider.refresh().await?;
+        let types = provider.get_all_types().await?;
+        Ok(self
+            .update_provider_indexes(provider.provider_id(), &types)
+            .await)
+    }
 
-        println!("🎉 Все индексы успешно построены!");
-        Ok(())
+    /// Привести документы провайдера в индексах к набору `types`:
+    /// исчезнувшие удаляются, изменившиеся переиндексируются, новые
+    /// добавляются. Документы других провайдеров не затрагиваются.
+    pub async fn update_provider_indexes(
+        &self,
+        provider_id: &str,
+        types: &[TypeDocumentationFull],
+    ) -> IndexUpdateSummary {
+        let _update = self.update_lock.lock().await;
+        let prefix = format!("{}:", provider_id);
+        let incoming: HashMap<String, (&TypeDocumentationFull, String)> = types
+            .iter()
+            .map(|type_doc| {
+                let id = format!("{}{}", prefix, type_doc.id);
+                (id, (type_doc, content_hash(type_doc)))
+            })
+            .collect();
+
+        let mut summary = IndexUpdateSummary::default();
+        let mut stale = Vec::new();
+        let mut fresh = Vec::new();
+        {
+            let fulltext_index = self.fulltext_index.read().await;
+            for (id, entry) in &fulltext_index.document_index {
+                if !id.starts_with(&prefix) {
+                    continue;
+                }
+                match incoming.get(id) {
+                    None => {
+                        summary.removed += 1;
+                        stale.push(id.clone());
+                    }
+                    Some((_, fingerprint)) if *fingerprint != entry.fingerprint => {
+                        summary.updated += 1;
+                        stale.push(id.clone());
+                        fresh.push(id.clone());
+                    }
+                    Some(_) => summary.unchanged += 1,
+                }
+            }
+            for id in incoming.keys() {
+                if !fulltext_index.document_index.contains_key(id) {
+                    summary.added += 1;
+                    fresh.push(id.clone());
+                }
+            }
+        }
+        if stale.is_empty() && fresh.is_empty() {
+            return summary;
+        }
+        fresh.sort();
+        let fresh: Vec<(&str, &TypeDocumentationFull, &str)> = fresh
+            .iter()
+            .map(|id| {
+                let (type_doc, fingerprint) = &incoming[id];
+                (id.as_str(), *type_doc, fingerprint.as_str())
+            })
+            .collect();
+
+        {
+            let mut fulltext_index = self.fulltext_index.write().await;
+            for id in &stale {
+                Self::remove_from_fulltext(&mut fulltext_index, id);
+            }
+            for (id, type_doc, fingerprint) in &fresh {
+                self.add_to_fulltext(&mut fulltext_index, id, type_doc, fingerprint);
+            }
+        }
+        {
+            let mut category_indexes = self.category_indexes.write().await;
+            Self::remove_from_categories(&mut category_indexes, &stale);
+            for (id, type_doc, _) in &fresh {
+                Self::add_to_categories(&mut category_indexes, id, type_doc);
+            }
+        }
+        {
+            let mut facet_indexes = self.facet_indexes.write().await;
+            Self::remove_from_facets(&mut facet_indexes, &stale);
+            for (id, type_doc, _) in &fresh {
+                Self::add_to_facets(&mut facet_indexes, id, type_doc);
+            }
+        }
+
+        // Кешированные результаты могли ссылаться на удалённые документы
+        self.query_cache.write().await.clear();
+        let (documents, words) = {
+            let fulltext_index = self.fulltext_index.read().await;
+            (
+                fulltext_index.document_index.len(),
+                fulltext_index.word_index.len(),
+            )
+        };
+        let mut stats = self.search_statistics.write().await;
+        stats.total_indexed_documents = documents;
+        stats.fulltext_index_size = words;
+        summary
     }
 
     /// Выполнить поиск
     pub async fn search(&self, query: AdvancedSearchQuery) -> Result<SearchResults> {
         let start_time = std::time::Instant::now();
 
-        println!("🔍 Выполняем поиск: '{}'", query.query);
+        debug!("Выполняем поиск: '{}'", query.query);
 
         // Полнотекстовый поиск
         let mut result_documents = self.perform_fulltext_search(&query).await?;
@@ -501,8 +688,8 @@ impl DocumentationSearchEngine {
         // Обновляем статистику
         self.update_search_statistics(search_time_ms).await;
 
-        println!(
-            "✅ Поиск завершен: найдено {} результатов за {}ms",
+        debug!(
+            "Поиск завершен: найдено {} результатов за {}ms",
             total_count, search_time_ms
         );
 
@@ -517,6 +704,50 @@ impl DocumentationSearchEngine {
         })
     }
 
+    /// Найти до `limit` документов по имени (русскому, английскому или
+    /// альтернативному), лучшие первыми. `relevance_score` — оценка
+    /// совпадения имени.
+    pub async fn search_by_name(&self, query: &str, limit: usize) -> Vec<SearchResultItem> {
+        let fulltext_index = self.fulltext_index.read().await;
+        let mut found: Vec<(u32, &DocumentIndexEntry)> = fulltext_index
+            .document_index
+            .values()
+            .filter_map(|entry| {
+                let names = [&entry.title, &entry.metadata.english_name]
+                    .into_iter()
+                    .chain(&entry.metadata.tags);
+                let score = names.filter_map(|name| match_score(query, name)).max()?;
+                Some((score, entry))
+            })
+            .collect();
+        found.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| a.1.title.len().cmp(&b.1.title.len()))
+                .then_with(|| a.1.document_id.cmp(&b.1.document_id))
+        });
+
+        found
+            .into_iter()
+            .take(limit)
+            .map(|(score, entry)| SearchResultItem {
+                type_id: entry.document_id.clone(),
+                display_name: entry.title.clone(),
+                description: entry.content.clone(),
+                category: entry.metadata.category.clone(),
+                source_type: entry.metadata.source_type.clone(),
+                relevance_score: score as f64,
+                highlights: Vec::new(),
+                breadcrumb: entry
+                    .metadata
+                    .category
+                    .split('/')
+                    .map(|s| s.to_string())
+                    .collect(),
+                source_file: entry.metadata.source_file.clone(),
+            })
+            .collect()
+    }
+
     /// Получить статистику поиска
     pub async fn get_statistics(&self) -> Result<SearchStatistics> {
         Ok(self.search_statistics.read().await.clone())
@@ -765,9 +996,7 @@ impl DocumentationSearchEngine {
                     display_name: doc_entry.title.clone(),
                     description: doc_entry.content.clone(),
                     category: doc_entry.metadata.category.clone(),
-                    source_type: DocumentationSourceType::Platform {
-                        version: "8.3".to_string(),
-                    },
+                    source_type: doc_entry.metadata.source_type.clone(),
                     relevance_score: 1.0, // TODO: Реальный расчет score
                     highlights,
                     breadcrumb: doc_entry
@@ -776,6 +1005,7 @@ impl DocumentationSearchEngine {
                         .split('/')
                         .map(|s| s.to_string())
                         .collect(),
+                    source_file: doc_entry.metadata.source_file.clone(),
                 };
 
                 results.push(search_item);
@@ -901,76 +1131,69 @@ impl DocumentationSearchEngine {
             (total_time + search_time_ms as f64) / stats.total_queries as f64;
     }
 
-    /// Построить полнотекстовый индекс
-    async fn build_fulltext_index(
+    /// Добавить тип в полнотекстовый индекс
+    fn add_to_fulltext(
         &self,
-        types: &[super::core::hierarchy::TypeDocumentationFull],
-    ) -> Result<()> {
-        let mut fulltext_index = self.fulltext_index.write().await;
-
-        for (i, type_doc) in types.iter().enumerate() {
-            let document_id = format!("platform_{}", i);
-
-            // Создаем запись в индексе документов
-            let document_entry = DocumentIndexEntry {
-                document_id: document_id.clone(),
-                title: type_doc.russian_name.clone(),
-                content: format!(
-                    "{} {} {}",
-                    type_doc.russian_name, type_doc.english_name, type_doc.description
-                ),
-                metadata: DocumentMetadata {
-                    document_type: "PlatformType".to_string(),
-                    category: type_doc.hierarchy_path.join("/"),
-                    tags: type_doc.aliases.clone(),
-                    created_at: chrono::Utc::now(),
-                    popularity_score: 0.0,
-                },
-            };
-
-            fulltext_index
-                .document_index
-                .insert(document_id.clone(), document_entry);
-
-            // Индексируем слова
-            self.index_words(
-                &mut fulltext_index,
-                &document_id,
-                &type_doc.russian_name,
-                3.0,
-            )
-            .await;
-            self.index_words(
-                &mut fulltext_index,
-                &document_id,
-                &type_doc.english_name,
-                2.0,
-            )
-            .await;
-            self.index_words(
-                &mut fulltext_index,
-                &document_id,
-                &type_doc.description,
-                1.0,
-            )
-            .await;
+        fulltext_index: &mut FullTextIndex,
+        document_id: &str,
+        type_doc: &TypeDocumentationFull,
+        fingerprint: &str,
+    ) {
+        // Создаем запись в индексе документов
+        let document_entry = DocumentIndexEntry {
+            document_id: document_id.to_string(),
+            title: type_doc.russian_name.clone(),
+            content: format!(
+                "{} {} {}",
+                type_doc.russian_name, type_doc.english_name, type_doc.description
+            ),
+            metadata: DocumentMetadata {
+                document_type: "PlatformType".to_string(),
+                category: type_doc.hierarchy_path.join("/"),
+                tags: type_doc.aliases.clone(),
+                created_at: chrono::Utc::now(),
+                popularity_score: 0.0,
+                english_name: type_doc.english_name.clone(),
+                source_type: type_doc.source_type.clone(),
+                source_file: type_doc.source_file.clone(),
+            },
+            fingerprint: fingerprint.to_string(),
+        };
 
-            // Индексируем альтернативные имена
-            for alias in &type_doc.aliases {
-                self.index_words(&mut fulltext_index, &document_id, alias, 2.5)
-                    .await;
-            }
+        fulltext_index
+            .document_index
+            .insert(document_id.to_string(), document_entry);
+
+        // Индексируем слова
+        self.index_words(fulltext_index, document_id, &type_doc.russian_name, 3.0);
+        self.index_words(fulltext_index, document_id, &type_doc.english_name, 2.0);
+        self.index_words(fulltext_index, document_id, &type_doc.description, 1.0);
+
+        // Индексируем альтернативные имена
+        for alias in &type_doc.aliases {
+            self.index_words(fulltext_index, document_id, alias, 2.5);
         }
+    }
 
-        println!(
-            "📚 Индексировано {} документов в полнотекстовый индекс",
-            types.len()
-        );
-        Ok(())
+    /// Удалить документ из полнотекстового индекса
+    fn remove_from_fulltext(fulltext_index: &mut FullTextIndex, document_id: &str) {
+        fulltext_index.document_index.remove(document_id);
+        let words = fulltext_index
+            .document_words
+            .remove(document_id)
+            .unwrap_or_default();
+        for word in words {
+            if let Some(documents) = fulltext_index.word_index.get_mut(&word) {
+                documents.retain(|doc| doc.document_id != document_id);
+                if documents.is_empty() {
+                    fulltext_index.word_index.remove(&word);
+                }
+            }
+        }
     }
 
     /// Индексировать слова в тексте
-    async fn index_words(
+    fn index_words(
         &self,
         index: &mut FullTextIndex,
         document_id: &str,
@@ -996,6 +1219,11 @@ impl DocumentationSearchEngine {
                     positions: vec![position],
                 };
 
+                index
+                    .document_words
+                    .entry(document_id.to_string())
+                    .or_default()
+                    .insert(normalized_word.clone());
                 index
                     .word_index
                     .entry(normalized_word)
@@ -1018,71 +1246,90 @@ impl DocumentationSearchEngine {
             .collect()
     }
 
-    /// Построить индексы по категориям
-    async fn build_category_indexes(
-        &self,
-        types: &[super::core::hierarchy::TypeDocumentationFull],
-    ) -> Result<()> {
-        let mut category_indexes = self.category_indexes.write().await;
-
-        for (i, type_doc) in types.iter().enumerate() {
-            let document_id = format!("platform_{}", i);
-            let category_path = type_doc.hierarchy_path.join("/");
+    /// Добавить тип в индекс его категории
+    fn add_to_categories(
+        category_indexes: &mut HashMap<String, CategoryIndex>,
+        document_id: &str,
+        type_doc: &TypeDocumentationFull,
+    ) {
+        let category_path = type_doc.hierarchy_path.join("/");
+        let category_index = category_indexes.entry(category_path.clone()).or_default();
+
+        category_index
+            .category_to_types
+            .entry(category_path.clone())
+            .or_default()
+            .push(document_id.to_string());
+
+        category_index
+            .type_to_category
+            .insert(document_id.to_string(), category_path);
+    }
 
-            let category_index = category_indexes
-                .entry(category_path.clone())
-                .or_insert_with(CategoryIndex::default);
+    /// Удалить документы из индексов категорий; опустевшие категории
+    /// удаляются
+    fn remove_from_categories(
+        category_indexes: &mut HashMap<String, CategoryIndex>,
+        document_ids: &[String],
+    ) {
+        if document_ids.is_empty() {
+            return;
+        }
+        let removed: HashSet<&str> = document_ids.iter().map(String::as_str).collect();
+        category_indexes.retain(|_, index| {
+            index
+                .type_to_category
+                .retain(|id, _| !removed.contains(id.as_str()));
+            index.category_to_types.retain(|_, types| {
+                types.retain(|id| !removed.contains(id.as_str()));
+                !types.is_empty()
+            });
+            !index.category_to_types.is_empty()
+        });
+    }
 
-            category_index
-                .category_to_types
-                .entry(category_path.clone())
-                .or_insert_with(Vec::new)
-                .push(document_id.clone());
+    /// Добавить тип в индексы его фасетов
+    fn add_to_facets(
+        facet_indexes: &mut HashMap<FacetKind, FacetIndex>,
+        document_id: &str,
+        type_doc: &TypeDocumentationFull,
+    ) {
+        for facet in &type_doc.available_facets {
+            let facet_index = facet_indexes.entry(*facet).or_default();
 
-            category_index
-                .type_to_category
-                .insert(document_id, category_path);
+            facet_index
+                .facet_to_types
+                .entry(*facet)
+                .or_default()
+                .push(document_id.to_string());
+
+            facet_index
+                .type_to_facets
+                .entry(document_id.to_string())
+                .or_default()
+                .push(*facet);
         }
-
-        println!(
-            "🗂️ Построено {} индексов по категориям",
-            category_indexes.len()
-        );
-        Ok(())
     }
 
-    /// Построить индексы по фасетам
-    async fn build_facet_indexes(
-        &self,
-        types: &[super::core::hierarchy::TypeDocumentationFull],
-    ) -> Result<()> {
-        let mut facet_indexes = self.facet_indexes.write().await;
-
-        for (i, type_doc) in types.iter().enumerate() {
-            let document_id = format!("platform_{}", i);
-
-            // Индексируем по фасетам типа
-            for facet in &type_doc.available_facets {
-                let facet_index = facet_indexes
-                    .entry(*facet)
-                    .or_insert_with(FacetIndex::default);
-
-                facet_index
-                    .facet_to_types
-                    .entry(*facet)
-                    .or_insert_with(Vec::new)
-                    .push(document_id.clone());
-
-                facet_index
-                    .type_to_facets
-                    .entry(document_id.clone())
-                    .or_insert_with(Vec::new)
-                    .push(*facet);
-            }
+    /// Удалить документы из индексов фасетов
+    fn remove_from_facets(
+        facet_indexes: &mut HashMap<FacetKind, FacetIndex>,
+        document_ids: &[String],
+    ) {
+        if document_ids.is_empty() {
+            return;
         }
-
-        println!("🏷️ Построено {} индексов по фасетам", facet_indexes.len());
-        Ok(())
+        let removed: HashSet<&str> = document_ids.iter().map(String::as_str).collect();
+        facet_indexes.retain(|_, index| {
+            index
+                .type_to_facets
+                .retain(|id, _| !removed.contains(id.as_str()));
+            index.facet_to_types.retain(|_, types| {
+                types.retain(|id| !removed.contains(id.as_str()));
+                !types.is_empty()
+            });
+            !index.facet_to_types.is_empty()
+        });
     }
 }
 
@@ -1160,6 +1407,7 @@ impl Default for SearchStatistics {
     }
 }
 
+#[cfg(feature = "docs-search")]
 impl Default for IndexingConfig {
     fn default() -> Self {
         Self {
@@ -1208,8 +1456,141 @@ impl Default for IndexingConfig {
 }
 
 // Реализация значения по умолчанию для корректности API с new()
+#[cfg(feature = "docs-search")]
 impl Default for DocumentationSearchEngine {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(all(test, feature = "docs-search"))]
+mod tests {
+    use super::*;
+    use crate::documentation::core::hierarchy::UiMetadata;
+    use crate::domain::types::TypeResolution;
+
+    fn type_doc(id: &str, name: &str, description: &str) -> TypeDocumentationFull {
+        TypeDocumentationFull {
+            id: id.to_string(),
+            russian_name: name.to_string(),
+            english_name: String::new(),
+            aliases: Vec::new(),
+            source_type: DocumentationSourceType::GlobalFunction,
+            hierarchy_path: vec!["Коллекции".to_string()],
+            type_resolution: TypeResolution::unknown(),
+            available_facets: vec![FacetKind::Collection],
+            active_facet: None,
+            methods: Vec::new(),
+            properties: Vec::new(),
+            constructors: Vec::new(),
+            description: description.to_string(),
+            examples: Vec::new(),
+            availability: Vec::new(),
+            since_version: String::new(),
+            notes: Vec::new(),
+            related_types: Vec::new(),
+            parent_type: None,
+            child_types: Vec::new(),
+            source_file: None,
+            ui_metadata: UiMetadata {
+                icon: String::new(),
+                color: String::new(),
+                tree_path: Vec::new(),
+                expanded: false,
+                sort_weight: 0,
+                css_classes: Vec::new(),
+            },
+        }
+    }
+
+    async fn found(engine: &DocumentationSearchEngine, query: &str) -> Vec<String> {
+        let query = AdvancedSearchQuery {
+            query: query.to_string(),
+            options: SearchOptions {
+                fuzzy_search: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut ids: Vec<String> = engine
+            .search(query)
+            .await
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|item| item.type_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_incremental_provider_update() {
+        let engine = DocumentationSearchEngine::new();
+        let initial = vec![
+            type_doc("array", "Массив", "упорядоченная коллекция"),
+            type_doc("map", "Соответствие", "коллекция пар"),
+            type_doc("list", "СписокЗначений", "коллекция значений"),
+        ];
+        let summary = engine.update_provider_indexes("platform", &initial).await;
+        assert_eq!(summary.added, 3);
+        engine
+            .update_provider_indexes("config", &[type_doc("array", "Массив", "реквизит")])
+            .await;
+
+        let refreshed = vec![
+            type_doc("array", "Массив", "упорядоченная коллекция"),
+            type_doc("map", "Соответствие", "ассоциативный словарь"),
+            type_doc("structure", "Структура", "коллекция свойств"),
+        ];
+        let summary = engine.update_provider_indexes("platform", &refreshed).await;
+        assert_eq!(
+            summary,
+            IndexUpdateSummary {
+                added: 1,
+                updated: 1,
+                removed: 1,
+                unchanged: 1,
+            }
+        );
+
+        assert_eq!(
+            found(&engine, "коллекция").await,
+            ["platform:array", "platform:structure"]
+        );
+        assert_eq!(found(&engine, "словарь").await, ["platform:map"]);
+        assert!(found(&engine, "СписокЗначений").await.is_empty());
+        // Документы другого провайдера не затронуты
+        assert_eq!(found(&engine, "реквизит").await, ["config:array"]);
+
+        let stats = engine.get_statistics().await.unwrap();
+        assert_eq!(stats.total_indexed_documents, 4);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_name() {
+        let engine = DocumentationSearchEngine::new();
+        let mut table = type_doc("table", "ТаблицаЗначений", "коллекция строк");
+        table.english_name = "ValueTable".to_string();
+        table.source_file = Some("objects/ValueTable.html".to_string());
+        let types = vec![
+            table,
+            type_doc("tree", "ДеревоЗначений", "иерархия строк"),
+            type_doc("value", "Значение", ""),
+        ];
+        engine.update_provider_indexes("platform", &types).await;
+
+        let names = |items: Vec<SearchResultItem>| -> Vec<String> {
+            items.into_iter().map(|item| item.display_name).collect()
+        };
+        // Префикс выше подстроки; первые буквы слов и английское имя
+        assert_eq!(
+            names(engine.search_by_name("Знач", 10).await),
+            ["Значение", "ДеревоЗначений", "ТаблицаЗначений"]
+        );
+        assert_eq!(names(engine.search_by_name("дер", 10).await), ["ДеревоЗначений"]);
+        let found = engine.search_by_name("тз", 10).await;
+        assert_eq!(found[0].source_file.as_deref(), Some("objects/ValueTable.html"));
+        assert_eq!(names(engine.search_by_name("valuetab", 1).await), ["ТаблицаЗначений"]);
+    }
+}
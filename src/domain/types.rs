@@ -113,6 +113,26 @@ pub enum MetadataKind {
     ChartOfCharacteristicTypes,
 }
 
+impl MetadataKind {
+    /// Вид объекта по категории типа конфигурации: имя варианта (`Catalog`,
+    /// так её записывает загрузчик) или русское имя (`Справочник`)
+    pub fn from_category(category: &str) -> Option<Self> {
+        match category {
+            "Catalog" | "Справочник" => Some(MetadataKind::Catalog),
+            "Document" | "Документ" => Some(MetadataKind::Document),
+            "Register" | "Регистр" => Some(MetadataKind::Register),
+            "Report" | "Отчет" => Some(MetadataKind::Report),
+            "DataProcessor" | "Обработка" => Some(MetadataKind::DataProcessor),
+            "Enum" | "Перечисление" => Some(MetadataKind::Enum),
+            "ChartOfAccounts" | "ПланСчетов" => Some(MetadataKind::ChartOfAccounts),
+            "ChartOfCharacteristicTypes" | "ПланВидовХарактеристик" => {
+                Some(MetadataKind::ChartOfCharacteristicTypes)
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Primitive BSL types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PrimitiveType {
@@ -260,6 +280,9 @@ pub struct ResolutionMetadata {
     pub line: Option<u32>,
     pub column: Option<u32>,
     pub notes: Vec<String>,
+    /// Резолвер цепочки, который дал результат (для отладки)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolver: Option<String>,
 }
 
 /// Combined type information with gradual typing support
@@ -376,6 +399,19 @@ impl TypeResolution {
         matches!(self.certainty, Certainty::Known)
     }
 
+    /// Файл описания объекта метаданных (XML выгрузки или MDO), из которого
+    /// получен конфигурационный тип
+    pub fn definition_path(&self) -> Option<&str> {
+        match &self.result {
+            ResolutionResult::Concrete(ConcreteType::Configuration(_)) => {
+                let file = self.metadata.file.as_deref()?;
+                let path = file.strip_prefix("guided:").unwrap_or(file);
+                (!path.is_empty()).then_some(path)
+            }
+            _ => None,
+        }
+    }
+
     pub fn get_name(&self) -> Option<String> {
         match &self.result {
             ResolutionResult::Concrete(concrete) => match concrete {
@@ -443,13 +479,11 @@ impl TypeResolution {
             }
             TypeSource::Configuration { .. } => {
                 // Определяем тип конфигурационного объекта по пути категории
-                let kind = if raw_data.category_path.contains(&"Справочник".to_string()) {
-                    MetadataKind::Catalog
-                } else if raw_data.category_path.contains(&"Документ".to_string()) {
-                    MetadataKind::Document
-                } else {
-                    MetadataKind::Catalog // По умолчанию
-                };
+                let kind = raw_data
+                    .category_path
+                    .iter()
+                    .find_map(|category| MetadataKind::from_category(category))
+                    .unwrap_or(MetadataKind::Catalog); // По умолчанию
 
                 let attributes: Vec<Attribute> = raw_data
                     .properties
@@ -457,8 +491,12 @@ impl TypeResolution {
                     .map(|raw_prop| Attribute {
                         name: raw_prop.name.clone(),
                         type_: raw_prop.type_name.clone(),
-                        is_composite: false,
-                        types: vec![raw_prop.type_name.clone()],
+                        is_composite: raw_prop.is_composite(),
+                        types: raw_prop
+                            .type_names()
+                            .into_iter()
+                            .map(str::to_string)
+                            .collect(),
                     })
                     .collect();
 
@@ -485,11 +523,20 @@ impl TypeResolution {
             TypeSource::UserDefined { .. } => ResolutionSource::Static,
         };
 
+        // Для объектов метаданных запоминаем файл описания (для навигации из hover)
+        let metadata = match &raw_data.source {
+            TypeSource::Configuration { .. } => ResolutionMetadata {
+                file: Some(raw_data.parse_metadata.file_path.clone()),
+                ..Default::default()
+            },
+            _ => ResolutionMetadata::default(),
+        };
+
         Self {
             certainty: Certainty::Known,
             result,
             source,
-            metadata: ResolutionMetadata::default(),
+            metadata,
             active_facet: None,
             available_facets: raw_data
                 .available_facets
@@ -578,6 +625,15 @@ impl TypeResolution {
                     })
                     .collect();
             }
+            // Составной тип: у значения наверняка есть только члены, общие
+            // для всех его типов
+            ResolutionResult::Union(types) => {
+                let members: Vec<_> = types
+                    .iter()
+                    .map(|member| TypeResolution::known(member.type_.clone()).to_raw_data())
+                    .collect();
+                (methods, properties) = common_members(&members);
+            }
             _ => {}
         }
 
@@ -586,7 +642,13 @@ impl TypeResolution {
             russian_name: name.clone(),
             english_name: name.clone(),
             source,
-            category_path: vec!["Platform".to_string()],
+            // Вид объекта конфигурации переживает обход через репозиторий
+            category_path: match &self.result {
+                ResolutionResult::Concrete(ConcreteType::Configuration(config_type)) => {
+                    vec![format!("{:?}", config_type.kind)]
+                }
+                _ => vec!["Platform".to_string()],
+            },
             methods,
             properties,
             documentation: format!("Тип: {}", name),
@@ -600,8 +662,9 @@ impl TypeResolution {
                     properties: Vec::new(),
                 })
                 .collect(),
+            // Файл описания объекта метаданных переживает обход через репозиторий
             parse_metadata: crate::unified::data::ParseMetadata {
-                file_path: "unknown".to_string(),
+                file_path: self.definition_path().unwrap_or("unknown").to_string(),
                 line: 0,
                 column: 0,
             },
@@ -609,6 +672,207 @@ impl TypeResolution {
     }
 }
 
+/// Методы и свойства, которые есть у каждого из описаний (имена без учёта
+/// регистра). Свойство, тип которого у описаний различается, получает
+/// составной тип из всех вариантов.
+fn common_members(
+    raws: &[crate::architecture::data::RawTypeData],
+) -> (
+    Vec<crate::unified::data::RawMethodData>,
+    Vec<crate::unified::data::RawPropertyData>,
+) {
+    let Some((first, rest)) = raws.split_first() else {
+        return (Vec::new(), Vec::new());
+    };
+    let same_name = |a: &str, b: &str| a.to_lowercase() == b.to_lowercase();
+    let methods = first
+        .methods
+        .iter()
+        .filter(|method| {
+            rest.iter()
+                .all(|raw| raw.methods.iter().any(|m| same_name(&m.name, &method.name)))
+        })
+        .cloned()
+        .collect();
+    let properties = first
+        .properties
+        .iter()
+        .filter_map(|property| {
+            let mut type_names = property.type_names();
+            for raw in rest {
+                let other = raw
+                    .properties
+                    .iter()
+                    .find(|p| same_name(&p.name, &property.name))?;
+                for name in other.type_names() {
+                    if !type_names.contains(&name) {
+                        type_names.push(name);
+                    }
+                }
+            }
+            Some(crate::unified::data::RawPropertyData {
+                type_name: type_names.join(","),
+                ..property.clone()
+            })
+        })
+        .collect();
+    (methods, properties)
+}
+
+/// Порядок уверенности: `Known` сильнее любой `Inferred`, `Unknown` слабее всех
+fn weaker_certainty(a: Certainty, b: Certainty) -> Certainty {
+    match (a, b) {
+        (Certainty::Unknown, _) | (_, Certainty::Unknown) => Certainty::Unknown,
+        (Certainty::Inferred(x), Certainty::Inferred(y)) => Certainty::Inferred(x.min(y)),
+        (Certainty::Inferred(x), Certainty::Known) | (Certainty::Known, Certainty::Inferred(x)) => {
+            Certainty::Inferred(x)
+        }
+        (Certainty::Known, Certainty::Known) => Certainty::Known,
+    }
+}
+
+impl ConcreteType {
+    /// Один и тот же тип: сравниваются вид и имя, а не состав методов
+    /// и реквизитов (они зависят от того, откуда тип загружен)
+    pub fn same_type(&self, other: &ConcreteType) -> bool {
+        match (self, other) {
+            (ConcreteType::Platform(a), ConcreteType::Platform(b)) => {
+                a.name.to_lowercase() == b.name.to_lowercase()
+            }
+            (ConcreteType::Configuration(a), ConcreteType::Configuration(b)) => {
+                a.kind == b.kind && a.name.to_lowercase() == b.name.to_lowercase()
+            }
+            (ConcreteType::Primitive(a), ConcreteType::Primitive(b)) => a == b,
+            (ConcreteType::Special(a), ConcreteType::Special(b)) => a == b,
+            (ConcreteType::GlobalFunction(a), ConcreteType::GlobalFunction(b)) => a.name == b.name,
+            _ => false,
+        }
+    }
+}
+
+/// Операции решётки типов: объединение веток (`join`), пересечение
+/// уточнений (`meet`) и проверка подтипа. `Dynamic` — тип постепенной
+/// типизации: объединение с ним неизвестно, а пересечение и проверка
+/// подтипа с ним ничего не ограничивают.
+impl TypeResolution {
+    /// Конкретные типы результата с весами; `None` для неизвестного типа
+    fn members(&self) -> Option<Vec<WeightedType>> {
+        match &self.result {
+            ResolutionResult::Concrete(type_) => Some(vec![WeightedType {
+                type_: type_.clone(),
+                weight: 1.0,
+            }]),
+            ResolutionResult::Union(types) if !types.is_empty() => Some(types.clone()),
+            _ => None,
+        }
+    }
+
+    /// Результат из набора типов: один тип — конкретный, несколько —
+    /// объединение с весами, нормированными к единице
+    fn from_members(mut members: Vec<WeightedType>) -> ResolutionResult {
+        if members.len() == 1 {
+            return ResolutionResult::Concrete(members.remove(0).type_);
+        }
+        let total: f32 = members.iter().map(|m| m.weight).sum();
+        if total > 0.0 {
+            for member in &mut members {
+                member.weight /= total;
+            }
+        }
+        ResolutionResult::Union(members)
+    }
+
+    /// Объединение результатов равновероятных веток: `Строка` и `Число`
+    /// дают `Строка|Число`. Уверенность — наименьшая из веток; если хоть
+    /// одна ветка неизвестна, неизвестно и объединение.
+    pub fn join_all<I>(resolutions: I) -> TypeResolution
+    where
+        I: IntoIterator<Item = TypeResolution>,
+    {
+        let resolutions: Vec<TypeResolution> = resolutions.into_iter().collect();
+        if resolutions.is_empty() {
+            return TypeResolution::unknown();
+        }
+        let share = 1.0 / resolutions.len() as f32;
+        let mut members: Vec<WeightedType> = Vec::new();
+        let mut certainty = Certainty::Known;
+        for resolution in &resolutions {
+            let Some(branch) = resolution.members() else {
+                return TypeResolution {
+                    source: ResolutionSource::Inferred,
+                    ..TypeResolution::unknown()
+                };
+            };
+            certainty = weaker_certainty(certainty, resolution.certainty);
+            let total: f32 = branch.iter().map(|m| m.weight).sum();
+            for member in branch {
+                let weight = if total > 0.0 { member.weight / total * share } else { 0.0 };
+                match members.iter_mut().find(|m| m.type_.same_type(&member.type_)) {
+                    Some(existing) => existing.weight += weight,
+                    None => members.push(WeightedType {
+                        type_: member.type_,
+                        weight,
+                    }),
+                }
+            }
+        }
+
+        let first = &resolutions[0];
+        let same_source = resolutions.iter().all(|r| r.source == first.source);
+        TypeResolution {
+            certainty,
+            result: Self::from_members(members),
+            source: if same_source {
+                first.source
+            } else {
+                ResolutionSource::Inferred
+            },
+            metadata: ResolutionMetadata::default(),
+            active_facet: None,
+            available_facets: vec![],
+        }
+    }
+
+    /// Объединение двух результатов (см. [`TypeResolution::join_all`])
+    pub fn join(&self, other: &TypeResolution) -> TypeResolution {
+        Self::join_all([self.clone(), other.clone()])
+    }
+
+    /// Пересечение: типы, допустимые в обоих результатах (веса берутся
+    /// из `self`). `None`, если общих типов нет.
+    pub fn meet(&self, other: &TypeResolution) -> Option<TypeResolution> {
+        let Some(mine) = self.members() else {
+            return Some(other.clone());
+        };
+        let Some(theirs) = other.members() else {
+            return Some(self.clone());
+        };
+        let common: Vec<WeightedType> = mine
+            .into_iter()
+            .filter(|m| theirs.iter().any(|t| t.type_.same_type(&m.type_)))
+            .collect();
+        if common.is_empty() {
+            return None;
+        }
+        Some(TypeResolution {
+            certainty: weaker_certainty(self.certainty, other.certainty),
+            result: Self::from_members(common),
+            ..self.clone()
+        })
+    }
+
+    /// Каждый тип `self` допустим в `other` (`Строка` — подтип
+    /// `Строка|Число`). С неизвестным типом совместим любой.
+    pub fn is_subtype_of(&self, other: &TypeResolution) -> bool {
+        match (self.members(), other.members()) {
+            (Some(mine), Some(theirs)) => mine
+                .iter()
+                .all(|m| theirs.iter().any(|t| t.type_.same_type(&m.type_))),
+            _ => true,
+        }
+    }
+}
+
 impl GlobalFunction {
     /// Resolve return type for polymorphic functions based on arguments
     pub fn resolve_return_type(&self, args: &[TypeResolution]) -> TypeResolution {
@@ -655,3 +919,70 @@ impl GlobalFunction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn primitive(type_: PrimitiveType) -> TypeResolution {
+        TypeResolution::known(ConcreteType::Primitive(type_))
+    }
+
+    fn names(resolution: &TypeResolution) -> Vec<(String, f32)> {
+        match &resolution.result {
+            ResolutionResult::Union(types) => types
+                .iter()
+                .map(|t| match &t.type_ {
+                    ConcreteType::Primitive(p) => (p.to_string(), t.weight),
+                    other => (format!("{:?}", other), t.weight),
+                })
+                .collect(),
+            other => panic!("ожидалось объединение, получено {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_join_builds_union() {
+        let string = primitive(PrimitiveType::String);
+        let number = primitive(PrimitiveType::Number);
+
+        assert_eq!(string.join(&string).result, string.result);
+        let union = string.join(&number);
+        assert_eq!(union.certainty, Certainty::Known);
+        assert_eq!(
+            names(&union),
+            [("Строка".to_string(), 0.5), ("Число".to_string(), 0.5)]
+        );
+
+        // Третья ветка со строкой: Строка в двух ветках из трёх
+        let three = TypeResolution::join_all([string.clone(), number.clone(), string.clone()]);
+        let weights = names(&three);
+        assert!((weights[0].1 - 2.0 / 3.0).abs() < 1e-6);
+
+        let inferred = TypeResolution::inferred(0.6, number.result.clone());
+        assert_eq!(string.join(&inferred).certainty, Certainty::Inferred(0.6));
+        assert_eq!(string.join(&TypeResolution::unknown()).result, ResolutionResult::Dynamic);
+    }
+
+    #[test]
+    fn test_meet_and_subtype() {
+        let string = primitive(PrimitiveType::String);
+        let number = primitive(PrimitiveType::Number);
+        let date = primitive(PrimitiveType::Date);
+        let string_or_number = string.join(&number);
+        let number_or_date = number.join(&date);
+
+        let common = string_or_number.meet(&number_or_date).unwrap();
+        assert_eq!(common.result, number.result);
+        assert!(string.meet(&date).is_none());
+        assert_eq!(
+            string.meet(&TypeResolution::unknown()).map(|r| r.result),
+            Some(string.result.clone())
+        );
+
+        assert!(string.is_subtype_of(&string_or_number));
+        assert!(!string_or_number.is_subtype_of(&string));
+        assert!(string_or_number.is_subtype_of(&string_or_number.join(&date)));
+        assert!(TypeResolution::unknown().is_subtype_of(&string));
+    }
+}
@@ -0,0 +1,189 @@
+//! Ошибки публичного API библиотеки
+//!
+//! Внутри крейта по-прежнему используется `anyhow`, но публичные трейты
+//! (репозиторий типов, резолверы, провайдеры документации, парсеры)
+//! возвращают [`Error`], чтобы встраивающий код мог различать причины сбоя
+//! без разбора текста сообщений.
+
+use std::path::PathBuf;
+
+/// Исходная ошибка, сохранённая как причина
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Результат публичного API
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Ошибка библиотеки
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Не удалось разобрать BSL-код, XML конфигурации или HTML синтакс-помощника
+    #[error("Ошибка разбора: {message}")]
+    Parse {
+        message: String,
+        #[source]
+        cause: Option<BoxError>,
+    },
+
+    /// Источник данных (репозиторий, удалённый сервер, синтакс-помощник) недоступен
+    /// или вернул некорректные данные
+    #[error("Ошибка источника данных: {message}")]
+    DataSource {
+        message: String,
+        #[source]
+        cause: Option<BoxError>,
+    },
+
+    /// Не удалось разрешить тип выражения
+    #[error("Ошибка разрешения типа: {message}")]
+    Resolution {
+        message: String,
+        #[source]
+        cause: Option<BoxError>,
+    },
+
+    /// Ошибка ввода-вывода
+    #[error("Ошибка ввода-вывода{}: {source}", display_path(.path))]
+    Io {
+        path: Option<PathBuf>,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Некорректные настройки
+    #[error("Ошибка конфигурации: {message}")]
+    Config {
+        message: String,
+        #[source]
+        cause: Option<BoxError>,
+    },
+}
+
+fn display_path(path: &Option<PathBuf>) -> String {
+    path.as_ref()
+        .map(|p| format!(" ({})", p.display()))
+        .unwrap_or_default()
+}
+
+impl Error {
+    pub fn parse(message: impl Into<String>) -> Self {
+        Error::Parse {
+            message: message.into(),
+            cause: None,
+        }
+    }
+
+    pub fn data_source(message: impl Into<String>) -> Self {
+        Error::DataSource {
+            message: message.into(),
+            cause: None,
+        }
+    }
+
+    pub fn resolution(message: impl Into<String>) -> Self {
+        Error::Resolution {
+            message: message.into(),
+            cause: None,
+        }
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Error::Config {
+            message: message.into(),
+            cause: None,
+        }
+    }
+
+    /// Ошибка ввода-вывода для конкретного файла
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Error::Io {
+            path: Some(path.into()),
+            source,
+        }
+    }
+
+    /// Сохранить исходную ошибку как причину (для `Io` причина уже задана)
+    pub fn with_cause(mut self, error: impl Into<BoxError>) -> Self {
+        match &mut self {
+            Error::Parse { cause, .. }
+            | Error::DataSource { cause, .. }
+            | Error::Resolution { cause, .. }
+            | Error::Config { cause, .. } => *cause = Some(error.into()),
+            Error::Io { .. } => {}
+        }
+        self
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(source: std::io::Error) -> Self {
+        Error::Io { path: None, source }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::parse("некорректный JSON").with_cause(error)
+    }
+}
+
+/// Перевод внутренних ошибок (`anyhow` и др.) в [`Error`] нужного вида
+pub trait ResultExt<T> {
+    fn parse_err(self, message: impl Into<String>) -> Result<T>;
+    fn data_source_err(self, message: impl Into<String>) -> Result<T>;
+    fn resolution_err(self, message: impl Into<String>) -> Result<T>;
+    fn config_err(self, message: impl Into<String>) -> Result<T>;
+}
+
+impl<T, E: Into<BoxError>> ResultExt<T> for std::result::Result<T, E> {
+    fn parse_err(self, message: impl Into<String>) -> Result<T> {
+        self.map_err(|e| Error::parse(message).with_cause(e))
+    }
+
+    fn data_source_err(self, message: impl Into<String>) -> Result<T> {
+        self.map_err(|e| Error::data_source(message).with_cause(e))
+    }
+
+    fn resolution_err(self, message: impl Into<String>) -> Result<T> {
+        self.map_err(|e| Error::resolution(message).with_cause(e))
+    }
+
+    fn config_err(self, message: impl Into<String>) -> Result<T> {
+        self.map_err(|e| Error::config(message).with_cause(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_error_kinds_and_causes() {
+        let internal: anyhow::Result<()> = Err(anyhow::anyhow!("connection refused"));
+        let error = internal.data_source_err("сервер недоступен").unwrap_err();
+
+        assert!(matches!(error, Error::DataSource { .. }));
+        assert_eq!(error.to_string(), "Ошибка источника данных: сервер недоступен");
+        assert_eq!(error.source().unwrap().to_string(), "connection refused");
+
+        let io = Error::io(
+            "types.json",
+            std::io::Error::new(std::io::ErrorKind::NotFound, "not found"),
+        );
+        assert_eq!(io.to_string(), "Ошибка ввода-вывода (types.json): not found");
+    }
+
+    #[test]
+    fn test_interop_with_anyhow() {
+        fn internal() -> anyhow::Result<()> {
+            Err(Error::resolution("Массив.Неизвестный"))?;
+            Ok(())
+        }
+
+        let error = internal().unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<Error>(),
+            Some(Error::Resolution { .. })
+        ));
+    }
+}
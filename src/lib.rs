@@ -11,6 +11,9 @@ pub mod unified;
 pub mod architecture;
 
 pub mod documentation;
+pub mod error;
+// Чистые точки входа для фаззинга и property-тестов
+pub mod pure;
 
 // Плоская структура модулей (адаптеры на период миграции на плоскую структуру)
 pub mod domain;
@@ -21,6 +24,7 @@ pub mod parsing;
 
 pub use core::resolution::TypeResolver;
 pub use domain::types;
+pub use error::{Error, Result};
 pub use parsing::bsl::{BslParser, Expression, Statement};
 
 /// Version of the type system
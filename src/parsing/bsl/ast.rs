@@ -1,5 +1,6 @@
 //! Abstract Syntax Tree для BSL
 
+use super::preprocessor::ContextSet;
 use serde::{Deserialize, Serialize};
 
 /// Корневой узел программы
@@ -91,6 +92,129 @@ pub enum Statement {
 
     /// Вызвать исключение
     Raise(String),
+
+    /// Условная компиляция: `#Если ... Тогда`, `#ИначеЕсли`, `#Иначе`,
+    /// `#КонецЕсли`
+    CompilationBlock { branches: Vec<CompilationBranch> },
+}
+
+/// Ветка условной компиляции
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompilationBranch {
+    /// Условие как в тексте (`Сервер Или ВнешнееСоединение`); `None` — `#Иначе`
+    pub condition: Option<String>,
+    /// Контексты, в которых компилируется ветка (без контекстов предыдущих
+    /// веток)
+    pub contexts: ContextSet,
+    pub body: Vec<Statement>,
+}
+
+impl Program {
+    /// Программа, как её видит компилятор в контекстах `contexts`: ветки
+    /// условной компиляции, не компилируемые ни в одном из них, отброшены,
+    /// а остальные встроены на место блока
+    pub fn for_contexts(&self, contexts: ContextSet) -> Program {
+        Program {
+            statements: statements_for_contexts(&self.statements, contexts),
+        }
+    }
+}
+
+fn statements_for_contexts(statements: &[Statement], contexts: ContextSet) -> Vec<Statement> {
+    let mut result = Vec::new();
+    for statement in statements {
+        let statement = match statement {
+            Statement::CompilationBlock { branches } => {
+                for branch in branches {
+                    let active = contexts.intersection(branch.contexts);
+                    if !active.is_empty() {
+                        result.extend(statements_for_contexts(&branch.body, active));
+                    }
+                }
+                continue;
+            }
+            Statement::ProcedureDecl {
+                name,
+                params,
+                body,
+                export,
+            } => Statement::ProcedureDecl {
+                name: name.clone(),
+                params: params.clone(),
+                body: statements_for_contexts(body, contexts),
+                export: *export,
+            },
+            Statement::FunctionDecl {
+                name,
+                params,
+                body,
+                return_value,
+                export,
+            } => Statement::FunctionDecl {
+                name: name.clone(),
+                params: params.clone(),
+                body: statements_for_contexts(body, contexts),
+                return_value: return_value.clone(),
+                export: *export,
+            },
+            Statement::If {
+                condition,
+                then_branch,
+                else_if_branches,
+                else_branch,
+            } => Statement::If {
+                condition: condition.clone(),
+                then_branch: statements_for_contexts(then_branch, contexts),
+                else_if_branches: else_if_branches
+                    .iter()
+                    .map(|(condition, body)| {
+                        (condition.clone(), statements_for_contexts(body, contexts))
+                    })
+                    .collect(),
+                else_branch: else_branch
+                    .as_ref()
+                    .map(|body| statements_for_contexts(body, contexts)),
+            },
+            Statement::For {
+                variable,
+                from,
+                to,
+                step,
+                body,
+            } => Statement::For {
+                variable: variable.clone(),
+                from: from.clone(),
+                to: to.clone(),
+                step: step.clone(),
+                body: statements_for_contexts(body, contexts),
+            },
+            Statement::ForEach {
+                variable,
+                collection,
+                body,
+            } => Statement::ForEach {
+                variable: variable.clone(),
+                collection: collection.clone(),
+                body: statements_for_contexts(body, contexts),
+            },
+            Statement::While { condition, body } => Statement::While {
+                condition: condition.clone(),
+                body: statements_for_contexts(body, contexts),
+            },
+            Statement::Try {
+                try_block,
+                catch_block,
+            } => Statement::Try {
+                try_block: statements_for_contexts(try_block, contexts),
+                catch_block: catch_block
+                    .as_ref()
+                    .map(|body| statements_for_contexts(body, contexts)),
+            },
+            other => other.clone(),
+        };
+        result.push(statement);
+    }
+    result
 }
 
 /// Выражения
@@ -194,6 +318,8 @@ pub enum BinaryOp {
 pub enum UnaryOp {
     Not,
     Minus,
+    /// Ждать (8.3.18+): результат обещания
+    Await,
 }
 
 /// Информация о позиции в исходном коде
@@ -1,7 +1,7 @@
 //! Общие типы и трейты для парсеров
 
 use super::ast::Program;
-use anyhow::Result;
+use crate::error::Result;
 
 /// Общий trait для всех парсеров BSL
 pub trait Parser: Send + Sync {
@@ -0,0 +1,392 @@
+//! Возможности языка, зависящие от версии платформы 1С:Предприятие
+//!
+//! Новые конструкции появляются в языке постепенно: например, асинхронные
+//! методы (`Асинх`/`Ждать`) поддерживаются с 8.3.18, а на более старых
+//! платформах `Ждать` — обычное имя переменной или метода. Целевая версия
+//! (`platform_version` в настройках) определяет, какие конструкции разбирает
+//! парсер, а для более старых целей — какие из них помечаются как
+//! неподдерживаемые.
+//!
+//! Там, где асинхронные методы поддерживаются, `Ждать` допустим только в
+//! теле метода с модификатором `Асинх` ([`await_outside_async`]).
+
+use serde::Serialize;
+use std::fmt;
+
+/// Версия платформы `8.3.18`; без номера релиза (`8.3`) — любая версия
+/// ветки, то есть самая новая
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PlatformVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub release: Option<u32>,
+}
+
+impl PlatformVersion {
+    pub const fn new(major: u32, minor: u32, release: u32) -> Self {
+        Self {
+            major,
+            minor,
+            release: Some(release),
+        }
+    }
+
+    /// Разобрать `8.3`, `8.3.18` или `8.3.18.1334` (номер сборки не важен)
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let release = match parts.next() {
+            Some(release) => Some(release.parse().ok()?),
+            None => None,
+        };
+        Some(Self {
+            major,
+            minor,
+            release,
+        })
+    }
+
+    /// Не старше ли эта версия, чем `other`
+    pub fn at_least(&self, other: &Self) -> bool {
+        let key = |v: &Self| (v.major, v.minor, v.release.unwrap_or(u32::MAX));
+        key(self) >= key(other)
+    }
+}
+
+impl fmt::Display for PlatformVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if let Some(release) = self.release {
+            write!(f, ".{}", release)?;
+        }
+        Ok(())
+    }
+}
+
+/// Конструкция языка, появившаяся в определённой версии платформы
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LanguageFeature {
+    /// Асинхронные методы: модификатор `Асинх` и оператор `Ждать`
+    AsyncMethods,
+}
+
+impl LanguageFeature {
+    pub const ALL: &'static [LanguageFeature] = &[LanguageFeature::AsyncMethods];
+
+    /// Первая версия платформы с поддержкой конструкции
+    pub fn since(&self) -> PlatformVersion {
+        match self {
+            LanguageFeature::AsyncMethods => PlatformVersion::new(8, 3, 18),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            LanguageFeature::AsyncMethods => "асинхронные методы (Асинх/Ждать)",
+        }
+    }
+}
+
+/// Набор возможностей языка для целевой версии платформы
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LanguageFeatures {
+    target: Option<PlatformVersion>,
+}
+
+impl Default for LanguageFeatures {
+    fn default() -> Self {
+        Self::latest()
+    }
+}
+
+impl LanguageFeatures {
+    /// Все возможности языка (цель не ограничена)
+    pub fn latest() -> Self {
+        Self { target: None }
+    }
+
+    /// Возможности версии из настроек; нераспознанная версия — все возможности
+    pub fn for_version(version: &str) -> Self {
+        Self {
+            target: PlatformVersion::parse(version),
+        }
+    }
+
+    pub fn target(&self) -> Option<PlatformVersion> {
+        self.target
+    }
+
+    pub fn supports(&self, feature: LanguageFeature) -> bool {
+        self.target
+            .is_none_or(|target| target.at_least(&feature.since()))
+    }
+}
+
+/// Конструкция, которой нет в целевой версии платформы
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnsupportedSyntax {
+    pub feature: LanguageFeature,
+    /// Ключевое слово, как оно написано в модуле
+    pub keyword: String,
+    /// Позиция ключевого слова (строка и столбец в символах, с нуля)
+    pub line: u32,
+    pub column: u32,
+}
+
+impl UnsupportedSyntax {
+    pub fn message(&self, features: &LanguageFeatures) -> String {
+        let target = features
+            .target()
+            .map(|target| target.to_string())
+            .unwrap_or_default();
+        format!(
+            "'{}': {} поддерживаются с версии платформы {}, целевая версия {}",
+            self.keyword,
+            self.feature.description(),
+            self.feature.since(),
+            target
+        )
+    }
+}
+
+/// Слово или знак кода (вне строк, дат и комментариев)
+struct CodeToken {
+    text: String,
+    line: u32,
+    column: u32,
+}
+
+impl CodeToken {
+    fn is(&self, words: &[&str]) -> bool {
+        words.iter().any(|w| w.to_lowercase() == self.text.to_lowercase())
+    }
+
+    fn is_word(&self) -> bool {
+        self.text.starts_with(|c: char| c.is_alphabetic() || c == '_')
+    }
+}
+
+fn code_tokens(source: &str) -> Vec<CodeToken> {
+    let mut tokens = Vec::new();
+    // Строковый литерал может продолжаться на следующих строках (`|...`)
+    let mut in_string = false;
+    for (line_idx, line) in source.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if in_string {
+                if c == '"' {
+                    if chars.get(i + 1) == Some(&'"') {
+                        i += 1;
+                    } else {
+                        in_string = false;
+                    }
+                }
+                i += 1;
+            } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+                break;
+            } else if c == '"' {
+                in_string = true;
+                i += 1;
+            } else if c == '\'' {
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+                i += 1;
+            } else if c.is_alphabetic() || c == '_' {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(CodeToken {
+                    text: chars[start..i].iter().collect(),
+                    line: line_idx as u32,
+                    column: start as u32,
+                });
+            } else {
+                if !c.is_whitespace() {
+                    tokens.push(CodeToken {
+                        text: c.to_string(),
+                        line: line_idx as u32,
+                        column: i as u32,
+                    });
+                }
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+const ASYNC_KEYWORDS: &[&str] = &["Асинх", "Async"];
+const AWAIT_KEYWORDS: &[&str] = &["Ждать", "Await"];
+const METHOD_KEYWORDS: &[&str] = &["Процедура", "Функция", "Procedure", "Function"];
+const METHOD_END_KEYWORDS: &[&str] =
+    &["КонецПроцедуры", "КонецФункции", "EndProcedure", "EndFunction"];
+/// Слова, которые не бывают операндом: после них `Ждать` — имя переменной
+const NON_OPERAND_KEYWORDS: &[&str] = &[
+    "И", "Или", "Тогда", "Цикл", "По", "Из", "And", "Or", "Then", "Do", "To", "In",
+];
+
+/// `Ждать Обещание`: дальше операнд, а не присваивание или оператор
+fn is_await_operator(token: &CodeToken, next: Option<&CodeToken>) -> bool {
+    token.is(AWAIT_KEYWORDS)
+        && next.is_some_and(|next| next.is_word() && !next.is(NON_OPERAND_KEYWORDS))
+}
+
+/// Оператор `Ждать` вне асинхронного метода
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MisplacedAwait {
+    /// Ключевое слово, как оно написано в модуле
+    pub keyword: String,
+    /// Позиция ключевого слова (строка и столбец в символах, с нуля)
+    pub line: u32,
+    pub column: u32,
+    /// Метод, в котором стоит `Ждать` (`None` — тело модуля)
+    pub method: Option<String>,
+}
+
+impl MisplacedAwait {
+    pub fn message(&self) -> String {
+        match &self.method {
+            Some(method) => format!(
+                "'{}' допустим только в асинхронных методах: объявите {} с модификатором Асинх",
+                self.keyword, method
+            ),
+            None => format!("'{}' допустим только в асинхронных методах", self.keyword),
+        }
+    }
+}
+
+/// Операторы `Ждать` вне методов с модификатором `Асинх`. Если целевая
+/// версия не поддерживает асинхронные методы, `Ждать` уже помечен
+/// [`unsupported_syntax`] и здесь не проверяется.
+pub fn await_outside_async(source: &str, features: &LanguageFeatures) -> Vec<MisplacedAwait> {
+    if !features.supports(LanguageFeature::AsyncMethods) {
+        return Vec::new();
+    }
+    let tokens = code_tokens(source);
+    let mut found = Vec::new();
+    // Текущий метод: имя и признак `Асинх`
+    let mut method: Option<(String, bool)> = None;
+    for (idx, token) in tokens.iter().enumerate() {
+        let next = tokens.get(idx + 1);
+        let previous = idx.checked_sub(1).map(|prev| &tokens[prev]);
+        if token.is(METHOD_KEYWORDS) && previous.is_none_or(|prev| prev.text != ".") {
+            let name = next.map(|next| next.text.clone()).unwrap_or_default();
+            let is_async = previous.is_some_and(|prev| prev.is(ASYNC_KEYWORDS));
+            method = Some((name, is_async));
+        } else if token.is(METHOD_END_KEYWORDS) {
+            method = None;
+        } else if is_await_operator(token, next) && !method.as_ref().is_some_and(|m| m.1) {
+            found.push(MisplacedAwait {
+                keyword: token.text.clone(),
+                line: token.line,
+                column: token.column,
+                method: method.as_ref().map(|(name, _)| name.clone()),
+            });
+        }
+    }
+    found
+}
+
+/// Конструкции модуля, которых нет в целевой версии платформы
+pub fn unsupported_syntax(source: &str, features: &LanguageFeatures) -> Vec<UnsupportedSyntax> {
+    if features.supports(LanguageFeature::AsyncMethods) {
+        return Vec::new();
+    }
+    let tokens = code_tokens(source);
+    let mut found = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        let next = tokens.get(idx + 1);
+        let unsupported = if token.is(ASYNC_KEYWORDS) {
+            next.is_some_and(|next| next.is(METHOD_KEYWORDS))
+        } else {
+            is_await_operator(token, next)
+        };
+        if unsupported {
+            found.push(UnsupportedSyntax {
+                feature: LanguageFeature::AsyncMethods,
+                keyword: token.text.clone(),
+                line: token.line,
+                column: token.column,
+            });
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_comparison() {
+        let async_since = LanguageFeature::AsyncMethods.since();
+        assert!(PlatformVersion::parse("8.3.18").unwrap().at_least(&async_since));
+        assert!(PlatformVersion::parse("8.3.24.1467").unwrap().at_least(&async_since));
+        assert!(!PlatformVersion::parse("8.3.17").unwrap().at_least(&async_since));
+        assert!(!PlatformVersion::parse("8.2").unwrap().at_least(&async_since));
+        assert!(PlatformVersion::parse("8.3").unwrap().at_least(&async_since));
+        assert_eq!(PlatformVersion::parse("вчерашняя"), None);
+
+        assert!(LanguageFeatures::for_version("8.3").supports(LanguageFeature::AsyncMethods));
+        assert!(LanguageFeatures::latest().supports(LanguageFeature::AsyncMethods));
+        assert!(!LanguageFeatures::for_version("8.3.17").supports(LanguageFeature::AsyncMethods));
+    }
+
+    #[test]
+    fn test_unsupported_async_syntax() {
+        let source = r#"
+Асинх Процедура Загрузить()
+    Результат = Ждать ЗагрузитьАсинх(); // Ждать Обещание
+    Текст = "Асинх Функция";
+КонецПроцедуры
+
+Процедура Старая()
+    Ждать = Истина;
+    Если Ждать И Готово Тогда
+        Ждать(1);
+    КонецЕсли;
+КонецПроцедуры
+"#;
+        let old = LanguageFeatures::for_version("8.3.17");
+        let found = unsupported_syntax(source, &old);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].keyword.as_str(), found[0].line, found[0].column), ("Асинх", 1, 0));
+        assert_eq!((found[1].keyword.as_str(), found[1].line, found[1].column), ("Ждать", 2, 16));
+        assert!(found[0].message(&old).contains("8.3.18"));
+
+        let new = LanguageFeatures::for_version("8.3.18");
+        assert!(unsupported_syntax(source, &new).is_empty());
+    }
+
+    #[test]
+    fn test_await_outside_async() {
+        let source = r#"
+Асинх Процедура Загрузить()
+    Ждать ЗагрузитьАсинх();
+КонецПроцедуры
+
+Функция Прочитать()
+    Ждать = Истина;
+    Возврат Ждать ПрочитатьАсинх(); // Ждать в комментарии
+КонецФункции
+
+Ждать Загрузить();
+"#;
+        let found = await_outside_async(source, &LanguageFeatures::latest());
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].line, found[0].column), (7, 12));
+        assert_eq!(found[0].method.as_deref(), Some("Прочитать"));
+        assert!(found[0].message().contains("Асинх"));
+        assert_eq!((found[1].line, found[1].method.as_ref()), (10, None));
+
+        // На старой платформе `Ждать` помечается как неподдерживаемый
+        let old = LanguageFeatures::for_version("8.3.17");
+        assert!(await_outside_async(source, &old).is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 //! Лексический анализатор BSL
 
+use super::language_features::{LanguageFeature, LanguageFeatures};
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_while, take_while1},
@@ -48,6 +49,10 @@ pub enum Token {
     False,
     Undefined,
     Null,
+    /// Асинх (8.3.18+, см. `tokenize_with_features`)
+    Async,
+    /// Ждать (8.3.18+)
+    Await,
 
     // Идентификаторы и литералы
     Identifier(String),
@@ -79,6 +84,10 @@ pub enum Token {
     Dot,
     Question,
 
+    /// Инструкция препроцессора: текст строки после `#`
+    /// (`Если Сервер Тогда`, `Область Имя`)
+    Preprocessor(String),
+
     // Специальные
     Newline,
     Eof,
@@ -207,6 +216,12 @@ pub fn delimiter(input: &str) -> IResult<&str, Token> {
     ))(input)
 }
 
+/// Парсинг инструкции препроцессора (до конца строки)
+pub fn preprocessor(input: &str) -> IResult<&str, Token> {
+    let (input, text) = preceded(char('#'), take_while(|c| c != '\n' && c != '\r'))(input)?;
+    Ok((input, Token::Preprocessor(text.trim().to_string())))
+}
+
 /// Пропуск комментариев
 pub fn comment(input: &str) -> IResult<&str, ()> {
     value((), preceded(tag("//"), take_while(|c| c != '\n')))(input)
@@ -220,6 +235,7 @@ pub fn token(input: &str) -> IResult<&str, Token> {
     let (input, _) = multispace0(input)?;
 
     alt((
+        preprocessor,
         date_literal,
         string_literal,
         number,
@@ -234,6 +250,27 @@ pub fn tokenize(input: &str) -> IResult<&str, Vec<Token>> {
     many0(token)(input)
 }
 
+/// Токенизация для целевой версии платформы: `Асинх` и `Ждать` — ключевые
+/// слова только там, где они поддерживаются, иначе это обычные имена
+pub fn tokenize_with_features<'a>(
+    input: &'a str,
+    features: &LanguageFeatures,
+) -> IResult<&'a str, Vec<Token>> {
+    let (rest, mut tokens) = tokenize(input)?;
+    if features.supports(LanguageFeature::AsyncMethods) {
+        for token in &mut tokens {
+            if let Token::Identifier(name) = token {
+                match name.to_lowercase().as_str() {
+                    "асинх" | "async" => *token = Token::Async,
+                    "ждать" | "await" => *token = Token::Await,
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok((rest, tokens))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,4 +319,29 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_preprocessor_lines() {
+        let code = "#Если Сервер Тогда\r\nА = 1;\n  #КонецЕсли";
+        let (_, tokens) = tokenize(code).unwrap();
+        assert_eq!(
+            tokens[0],
+            Token::Preprocessor("Если Сервер Тогда".to_string())
+        );
+        assert_eq!(tokens[1], Token::Identifier("А".to_string()));
+        assert_eq!(tokens[5], Token::Preprocessor("КонецЕсли".to_string()));
+    }
+
+    #[test]
+    fn test_async_keywords_depend_on_version() {
+        let code = "Асинх Функция Ф() Возврат Ждать П; КонецФункции";
+        let (_, tokens) = tokenize_with_features(code, &LanguageFeatures::latest()).unwrap();
+        assert_eq!(tokens[0], Token::Async);
+        assert_eq!(tokens[6], Token::Await);
+
+        let old = LanguageFeatures::for_version("8.3.17");
+        let (_, tokens) = tokenize_with_features(code, &old).unwrap();
+        assert_eq!(tokens[0], Token::Identifier("Асинх".to_string()));
+        assert_eq!(tokens[6], Token::Identifier("Ждать".to_string()));
+    }
 }
@@ -3,13 +3,17 @@
 pub mod ast;
 pub mod common;
 pub mod graph_builder;
+pub mod language_features;
 pub mod lexer;
 pub mod parser;
+pub mod preprocessor;
 pub mod tree_sitter_adapter;
 pub mod visitor;
 
 pub use ast::{Expression, Program, Statement};
 pub use common::{Parser, ParserFactory};
 pub use graph_builder::DependencyGraphBuilder;
+pub use language_features::{LanguageFeature, LanguageFeatures, PlatformVersion};
 pub use parser::BslParser;
+pub use preprocessor::{ContextSet, Directive};
 pub use visitor::AstVisitor;
@@ -2,27 +2,42 @@
 
 use super::ast::*;
 use super::common::Parser;
-use super::lexer::{tokenize, Token};
-use anyhow::{anyhow, Result};
+use super::language_features::LanguageFeatures;
+use super::lexer::{tokenize_with_features, Token};
+use super::preprocessor::{branch_contexts, ContextSet, Directive};
+use crate::error::{Error, Result};
 
 /// Основной парсер BSL
 pub struct BslParser {
     tokens: Option<Vec<Token>>,
     _position: usize,
+    /// Конструкции языка целевой версии платформы
+    features: LanguageFeatures,
 }
 
 impl BslParser {
     /// Создание нового парсера (для совместимости)
     pub fn new(input: &str) -> Result<Self, String> {
-        match tokenize(input) {
+        let features = LanguageFeatures::latest();
+        match tokenize_with_features(input, &features) {
             Ok((_, tokens)) => Ok(Self {
                 tokens: Some(tokens),
                 _position: 0,
+                features,
             }),
             Err(e) => Err(format!("Tokenization error: {:?}", e)),
         }
     }
 
+    /// Парсер для целевой версии платформы (разбор через `Parser::parse`)
+    pub fn with_features(features: LanguageFeatures) -> Self {
+        Self {
+            tokens: None,
+            _position: 0,
+            features,
+        }
+    }
+
     /// Парсинг программы (для совместимости со старым API)
     pub fn parse(&mut self) -> Result<Program, String> {
         if let Some(tokens) = self.tokens.take() {
@@ -37,6 +52,7 @@ impl BslParser {
         let mut inner = InnerParser {
             tokens,
             position: 0,
+            depth: 0,
         };
         let statements = inner.parse_statements()?;
         Ok(Program { statements })
@@ -46,9 +62,9 @@ impl BslParser {
 impl Parser for BslParser {
     fn parse(&mut self, source: &str) -> Result<Program> {
         // Создаём новый парсер для каждого вызова
-        match tokenize(source) {
-            Ok((_, tokens)) => self.parse_with_tokens(tokens).map_err(|e| anyhow!(e)),
-            Err(e) => Err(anyhow!("Tokenization error: {:?}", e)),
+        match tokenize_with_features(source, &self.features) {
+            Ok((_, tokens)) => self.parse_with_tokens(tokens).map_err(Error::parse),
+            Err(e) => Err(Error::parse(format!("Tokenization error: {:?}", e))),
         }
     }
 
@@ -57,10 +73,18 @@ impl Parser for BslParser {
     }
 }
 
+/// Предельная вложенность операторов и выражений: глубже разбор
+/// возвращает ошибку, а не переполняет стек. Уровень выражения проходит
+/// всю цепочку приоритетов операций (несколько кадров стека), поэтому
+/// предел рассчитан на стек потока 2 МБ в отладочной сборке
+pub const MAX_NESTING: usize = 64;
+
 /// Внутренний парсер с токенами
 struct InnerParser {
     tokens: Vec<Token>,
     position: usize,
+    /// Текущая вложенность операторов и выражений
+    depth: usize,
 }
 
 impl InnerParser {
@@ -72,6 +96,14 @@ impl InnerParser {
             if self.check_end_keyword() {
                 break;
             }
+            // Области и инструкции расширений на компиляцию не влияют
+            if matches!(
+                self.current_directive(),
+                Some(Directive::Region(_) | Directive::EndRegion | Directive::Other(_))
+            ) {
+                self.advance();
+                continue;
+            }
 
             statements.push(self.parse_statement()?);
             self.skip_semicolons();
@@ -80,12 +112,43 @@ impl InnerParser {
         Ok(statements)
     }
 
+    /// Разобрать вложенную конструкцию, не превышая `MAX_NESTING`
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if self.depth >= MAX_NESTING {
+            return Err(format!("Nesting deeper than {} levels", MAX_NESTING));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
     /// Парсинг одного оператора
     fn parse_statement(&mut self) -> Result<Statement, String> {
+        self.nested(Self::parse_statement_kind)
+    }
+
+    fn parse_statement_kind(&mut self) -> Result<Statement, String> {
         match &self.current_token() {
+            Some(Token::Preprocessor(_)) => self.parse_compilation_block(),
             Some(Token::Var) => self.parse_var_declaration(),
             Some(Token::Procedure) => self.parse_procedure(),
             Some(Token::Function) => self.parse_function(),
+            // Асинхронность не меняет объявление: результат вызова — обещание
+            Some(Token::Async) => {
+                self.advance();
+                match self.current_token() {
+                    Some(Token::Procedure) => self.parse_procedure(),
+                    Some(Token::Function) => self.parse_function(),
+                    other => Err(format!(
+                        "Expected procedure or function after Async, got {:?}",
+                        other
+                    )),
+                }
+            }
             Some(Token::If) => self.parse_if(),
             Some(Token::For) => self.parse_for(),
             Some(Token::While) => self.parse_while(),
@@ -196,6 +259,39 @@ impl InnerParser {
         })
     }
 
+    /// Парсинг блока условной компиляции `#Если ... #КонецЕсли`
+    fn parse_compilation_block(&mut self) -> Result<Statement, String> {
+        let mut condition = match self.current_directive() {
+            Some(Directive::If(condition)) => Some(condition),
+            other => return Err(format!("Unexpected preprocessor directive: {:?}", other)),
+        };
+        self.advance();
+
+        let mut branches = Vec::new();
+        let mut taken = ContextSet::EMPTY;
+        loop {
+            let contexts = branch_contexts(condition.as_deref(), taken);
+            taken = taken.union(contexts);
+            let body = self.parse_statements()?;
+            branches.push(CompilationBranch {
+                condition,
+                contexts,
+                body,
+            });
+
+            let directive = self.current_directive();
+            self.advance();
+            condition = match directive {
+                Some(Directive::ElseIf(condition)) => Some(condition),
+                Some(Directive::Else) => None,
+                Some(Directive::EndIf) => break,
+                other => return Err(format!("Expected #КонецЕсли, got {:?}", other)),
+            };
+        }
+
+        Ok(Statement::CompilationBlock { branches })
+    }
+
     /// Парсинг условного оператора
     fn parse_if(&mut self) -> Result<Statement, String> {
         self.expect(Token::If)?;
@@ -338,7 +434,9 @@ impl InnerParser {
 
     /// Парсинг присваивания или вызова процедуры
     fn parse_assignment_or_call(&mut self) -> Result<Statement, String> {
-        let expr = self.parse_expression()?;
+        // Цель — имя с обращениями к членам и индексами: `=` после неё —
+        // присваивание, внутри выражения — сравнение
+        let expr = self.parse_postfix()?;
 
         if self.check(Token::Assign) {
             self.advance();
@@ -360,7 +458,7 @@ impl InnerParser {
 
     /// Парсинг выражения
     fn parse_expression(&mut self) -> Result<Expression, String> {
-        self.parse_or()
+        self.nested(Self::parse_or)
     }
 
     /// Парсинг логического ИЛИ
@@ -465,7 +563,7 @@ impl InnerParser {
     fn parse_unary(&mut self) -> Result<Expression, String> {
         if self.check(Token::Not) {
             self.advance();
-            let operand = self.parse_unary()?;
+            let operand = self.nested(Self::parse_unary)?;
             return Ok(Expression::Unary {
                 op: UnaryOp::Not,
                 operand: Box::new(operand),
@@ -474,13 +572,22 @@ impl InnerParser {
 
         if self.check(Token::Minus) {
             self.advance();
-            let operand = self.parse_unary()?;
+            let operand = self.nested(Self::parse_unary)?;
             return Ok(Expression::Unary {
                 op: UnaryOp::Minus,
                 operand: Box::new(operand),
             });
         }
 
+        if self.check(Token::Await) {
+            self.advance();
+            let operand = self.nested(Self::parse_unary)?;
+            return Ok(Expression::Unary {
+                op: UnaryOp::Await,
+                operand: Box::new(operand),
+            });
+        }
+
         self.parse_postfix()
     }
 
@@ -569,9 +676,11 @@ impl InnerParser {
                 self.advance();
                 Ok(Expression::Identifier("Процедура".to_string()))
             }
+            // Скобки — тоже уровень вложенности: до разбора выражения внутри
+            // на стеке вся цепочка приоритетов внешнего
             Some(Token::LeftParen) => {
                 self.advance();
-                let expr = self.parse_expression()?;
+                let expr = self.nested(Self::parse_expression)?;
                 self.expect(Token::RightParen)?;
                 Ok(expr)
             }
@@ -726,8 +835,19 @@ impl InnerParser {
         }
     }
 
+    /// Инструкция препроцессора в текущей позиции
+    fn current_directive(&self) -> Option<Directive> {
+        match self.current_token() {
+            Some(Token::Preprocessor(text)) => Some(Directive::parse(text)),
+            _ => None,
+        }
+    }
+
     fn check_end_keyword(&self) -> bool {
         matches!(
+            self.current_directive(),
+            Some(Directive::ElseIf(_) | Directive::Else | Directive::EndIf)
+        ) || matches!(
             self.current_token(),
             Some(Token::EndIf)
                 | Some(Token::EndDo)
@@ -752,7 +872,7 @@ impl InnerParser {
 
     fn match_equality_op(&mut self) -> Option<BinaryOp> {
         match self.current_token() {
-            Some(Token::Equal) => {
+            Some(Token::Equal | Token::Assign) => {
                 self.advance();
                 Some(BinaryOp::Equal)
             }
@@ -864,4 +984,144 @@ mod tests {
             _ => panic!("Expected function declaration"),
         }
     }
+
+    #[test]
+    fn test_parse_compilation_blocks() {
+        let code = "#Область ПрограммныйИнтерфейс
+#Если Сервер Или ВнешнееСоединение Тогда
+Процедура Записать() Экспорт
+    #Если Сервер Тогда
+    А = 1;
+    #КонецЕсли
+КонецПроцедуры
+#Иначе
+Б = 2;
+#КонецЕсли
+#КонецОбласти
+В = 3;";
+        let program = BslParser::new(code).unwrap().parse().unwrap();
+        assert_eq!(program.statements.len(), 2);
+        let Statement::CompilationBlock { branches } = &program.statements[0] else {
+            panic!("Expected compilation block");
+        };
+        assert_eq!(branches.len(), 2);
+        assert_eq!(
+            branches[0].contexts,
+            ContextSet::SERVER.union(ContextSet::EXTERNAL_CONNECTION)
+        );
+        assert_eq!(branches[1].condition, None);
+        assert_eq!(branches[1].contexts, branches[0].contexts.complement());
+
+        // На клиенте процедура не компилируется, на сервере — с телом
+        let client = program.for_contexts(ContextSet::THIN_CLIENT);
+        assert_eq!(client.statements.len(), 2);
+        assert!(matches!(
+            &client.statements[0],
+            Statement::Assignment { .. }
+        ));
+        let server = program.for_contexts(ContextSet::SERVER);
+        match &server.statements[0] {
+            Statement::ProcedureDecl { body, .. } => assert_eq!(body.len(), 1),
+            _ => panic!("Expected procedure declaration"),
+        }
+        let connection = program.for_contexts(ContextSet::EXTERNAL_CONNECTION);
+        match &connection.statements[0] {
+            Statement::ProcedureDecl { body, .. } => assert!(body.is_empty()),
+            _ => panic!("Expected procedure declaration"),
+        }
+    }
+
+    #[test]
+    fn test_parse_async_by_platform_version() {
+        let code = "Асинх Функция Загрузить() Возврат Ждать Обещание; КонецФункции";
+        let program = BslParser::new(code).unwrap().parse().unwrap();
+        match &program.statements[0] {
+            Statement::FunctionDecl { return_value, .. } => assert!(matches!(
+                return_value,
+                Some(Expression::Unary {
+                    op: UnaryOp::Await,
+                    ..
+                })
+            )),
+            _ => panic!("Expected function declaration"),
+        }
+
+        // До 8.3.18 `Ждать` — обычное имя переменной
+        let old = LanguageFeatures::for_version("8.3.17");
+        let program = Parser::parse(&mut BslParser::with_features(old), "Ждать = 1;").unwrap();
+        assert!(matches!(&program.statements[0], Statement::Assignment { .. }));
+        assert!(Parser::parse(&mut BslParser::with_features(old), code).is_err());
+    }
+
+    #[test]
+    fn test_parse_equality_with_assign_token() {
+        let code = "Если ТипЗнч(А) = Тип(\"Строка\") Тогда Б = А = 1; КонецЕсли;";
+        let program = BslParser::new(code).unwrap().parse().unwrap();
+        match &program.statements[0] {
+            Statement::If {
+                condition,
+                then_branch,
+                ..
+            } => {
+                assert!(matches!(
+                    condition,
+                    Expression::Binary {
+                        op: BinaryOp::Equal,
+                        ..
+                    }
+                ));
+                // Первый `=` — присваивание, второй — сравнение
+                assert!(matches!(
+                    &then_branch[0],
+                    Statement::Assignment {
+                        target: Expression::Identifier(_),
+                        value: Expression::Binary {
+                            op: BinaryOp::Equal,
+                            ..
+                        },
+                    }
+                ));
+            }
+            _ => panic!("Expected if statement"),
+        }
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let depth = MAX_NESTING * 4;
+        let code = format!("А = {}1{};", "(".repeat(depth), ")".repeat(depth));
+        let error = BslParser::new(&code).unwrap().parse().unwrap_err();
+        assert!(error.contains("Nesting"));
+
+        let code = format!("А = {}Истина;", "НЕ ".repeat(depth));
+        assert!(BslParser::new(&code).unwrap().parse().is_err());
+
+        let code = format!("А = {}1{};", "(".repeat(10), ")".repeat(10));
+        assert!(BslParser::new(&code).unwrap().parse().is_ok());
+    }
+
+    #[test]
+    fn test_nesting_limit_fits_thread_stack() {
+        // Стек как у потока теста по умолчанию: предел должен срабатывать
+        // раньше переполнения
+        let handle = std::thread::Builder::new()
+            .stack_size(2 * 1024 * 1024)
+            .spawn(|| {
+                for depth in [MAX_NESTING - 1, MAX_NESTING, MAX_NESTING * 8] {
+                    let parens = format!("А = {}1{};", "(".repeat(depth), ")".repeat(depth));
+                    let _ = BslParser::new(&parens).unwrap().parse();
+                    let unary = format!("А = {}1;", "НЕ -".repeat(depth));
+                    let _ = BslParser::new(&unary).unwrap().parse();
+                    let blocks = format!(
+                        "{}А = ({});{}",
+                        "Если Истина Тогда ".repeat(depth),
+                        "(".repeat(depth) + "1" + &")".repeat(depth),
+                        " КонецЕсли;".repeat(depth)
+                    );
+                    let _ = BslParser::new(&blocks).unwrap().parse();
+                }
+            })
+            .unwrap();
+        assert!(handle.join().is_ok());
+    }
 }
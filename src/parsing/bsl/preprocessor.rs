@@ -0,0 +1,395 @@
+//! Инструкции препроцессора BSL
+//!
+//! Код между `#Если Сервер Тогда` и `#КонецЕсли` компилируется только в
+//! перечисленных контекстах (сервер, тонкий клиент, внешнее соединение...).
+//! Условие вычисляется здесь как множество контекстов `ContextSet`: `ИЛИ` —
+//! объединение, `И` — пересечение, `НЕ` — дополнение. Ветка `#ИначеЕсли`
+//! компилируется там, где её условие истинно, а условия предыдущих веток —
+//! нет; `#Иначе` — там, где не подошла ни одна ветка.
+//!
+//! `#Область`/`#КонецОбласти` и инструкции расширений (`#Вставка`,
+//! `#Удаление`) на компиляцию не влияют и пропускаются.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Множество контекстов компиляции
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContextSet(u16);
+
+/// Символы препроцессора: русское и английское имя и контексты
+const SYMBOLS: &[(&str, &str, ContextSet)] = &[
+    ("Сервер", "Server", ContextSet::SERVER),
+    ("НаСервере", "AtServer", ContextSet::SERVER),
+    ("Клиент", "Client", ContextSet::CLIENT),
+    ("НаКлиенте", "AtClient", ContextSet::CLIENT),
+    ("ТонкийКлиент", "ThinClient", ContextSet::THIN_CLIENT),
+    ("ВебКлиент", "WebClient", ContextSet::WEB_CLIENT),
+    ("МобильныйКлиент", "MobileClient", ContextSet::MOBILE_CLIENT),
+    (
+        "ТолстыйКлиентОбычноеПриложение",
+        "ThickClientOrdinaryApplication",
+        ContextSet::THICK_CLIENT_ORDINARY_APPLICATION,
+    ),
+    (
+        "ТолстыйКлиентУправляемоеПриложение",
+        "ThickClientManagedApplication",
+        ContextSet::THICK_CLIENT_MANAGED_APPLICATION,
+    ),
+    (
+        "ВнешнееСоединение",
+        "ExternalConnection",
+        ContextSet::EXTERNAL_CONNECTION,
+    ),
+    (
+        "МобильноеПриложениеКлиент",
+        "MobileAppClient",
+        ContextSet::MOBILE_APP_CLIENT,
+    ),
+    (
+        "МобильноеПриложениеСервер",
+        "MobileAppServer",
+        ContextSet::MOBILE_APP_SERVER,
+    ),
+    (
+        "МобильныйАвтономныйСервер",
+        "MobileStandaloneServer",
+        ContextSet::MOBILE_STANDALONE_SERVER,
+    ),
+];
+
+/// Отдельные контексты в порядке вывода (без составного `Клиент`)
+const PRIMITIVES: &[(&str, ContextSet)] = &[
+    ("Сервер", ContextSet::SERVER),
+    ("ТонкийКлиент", ContextSet::THIN_CLIENT),
+    ("ВебКлиент", ContextSet::WEB_CLIENT),
+    ("МобильныйКлиент", ContextSet::MOBILE_CLIENT),
+    (
+        "ТолстыйКлиентОбычноеПриложение",
+        ContextSet::THICK_CLIENT_ORDINARY_APPLICATION,
+    ),
+    (
+        "ТолстыйКлиентУправляемоеПриложение",
+        ContextSet::THICK_CLIENT_MANAGED_APPLICATION,
+    ),
+    ("ВнешнееСоединение", ContextSet::EXTERNAL_CONNECTION),
+    ("МобильноеПриложениеКлиент", ContextSet::MOBILE_APP_CLIENT),
+    ("МобильноеПриложениеСервер", ContextSet::MOBILE_APP_SERVER),
+    (
+        "МобильныйАвтономныйСервер",
+        ContextSet::MOBILE_STANDALONE_SERVER,
+    ),
+];
+
+impl ContextSet {
+    pub const EMPTY: Self = Self(0);
+    pub const SERVER: Self = Self(1 << 0);
+    pub const THIN_CLIENT: Self = Self(1 << 1);
+    pub const WEB_CLIENT: Self = Self(1 << 2);
+    pub const MOBILE_CLIENT: Self = Self(1 << 3);
+    pub const THICK_CLIENT_ORDINARY_APPLICATION: Self = Self(1 << 4);
+    pub const THICK_CLIENT_MANAGED_APPLICATION: Self = Self(1 << 5);
+    pub const EXTERNAL_CONNECTION: Self = Self(1 << 6);
+    pub const MOBILE_APP_CLIENT: Self = Self(1 << 7);
+    pub const MOBILE_APP_SERVER: Self = Self(1 << 8);
+    pub const MOBILE_STANDALONE_SERVER: Self = Self(1 << 9);
+    /// Символ `Клиент`: любое клиентское приложение
+    pub const CLIENT: Self = Self(
+        Self::THIN_CLIENT.0
+            | Self::WEB_CLIENT.0
+            | Self::MOBILE_CLIENT.0
+            | Self::THICK_CLIENT_ORDINARY_APPLICATION.0
+            | Self::THICK_CLIENT_MANAGED_APPLICATION.0
+            | Self::MOBILE_APP_CLIENT.0,
+    );
+    /// Все контексты: код вне инструкций препроцессора
+    pub const ALL: Self = Self((1 << 10) - 1);
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    pub fn complement(self) -> Self {
+        Self::ALL.difference(self)
+    }
+
+    /// Контексты символа препроцессора (`Сервер`, `ThinClient`, без учёта
+    /// регистра)
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        let symbol = symbol.to_lowercase();
+        SYMBOLS
+            .iter()
+            .find(|(russian, english, _)| {
+                russian.to_lowercase() == symbol || english.to_lowercase() == symbol
+            })
+            .map(|&(_, _, contexts)| contexts)
+    }
+
+    /// Вычислить условие инструкции (`Сервер Или ВнешнееСоединение`,
+    /// `НЕ ВебКлиент`); `None` — неизвестный символ или ошибка в условии
+    pub fn parse_condition(condition: &str) -> Option<Self> {
+        let spaced = condition.replace('(', " ( ").replace(')', " ) ");
+        let words: Vec<String> = spaced.split_whitespace().map(str::to_lowercase).collect();
+        let mut position = 0;
+        let contexts = parse_or(&words, &mut position)?;
+        (position == words.len()).then_some(contexts)
+    }
+}
+
+impl fmt::Display for ContextSet {
+    /// `Сервер, ВнешнееСоединение`; все клиентские контексты — `Клиент`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Self::ALL {
+            return write!(f, "все контексты");
+        }
+        if self.is_empty() {
+            return write!(f, "нет контекстов");
+        }
+        let mut names = Vec::new();
+        let mut rest = *self;
+        if rest.contains(Self::CLIENT) {
+            names.push("Клиент");
+            rest = rest.difference(Self::CLIENT);
+        }
+        names.extend(
+            PRIMITIVES
+                .iter()
+                .filter(|(_, contexts)| rest.contains(*contexts))
+                .map(|(name, _)| *name),
+        );
+        write!(f, "{}", names.join(", "))
+    }
+}
+
+fn parse_or(words: &[String], position: &mut usize) -> Option<ContextSet> {
+    let mut contexts = parse_and(words, position)?;
+    while matches!(words.get(*position).map(String::as_str), Some("или" | "or")) {
+        *position += 1;
+        contexts = contexts.union(parse_and(words, position)?);
+    }
+    Some(contexts)
+}
+
+fn parse_and(words: &[String], position: &mut usize) -> Option<ContextSet> {
+    let mut contexts = parse_not(words, position)?;
+    while matches!(words.get(*position).map(String::as_str), Some("и" | "and")) {
+        *position += 1;
+        contexts = contexts.intersection(parse_not(words, position)?);
+    }
+    Some(contexts)
+}
+
+fn parse_not(words: &[String], position: &mut usize) -> Option<ContextSet> {
+    let word = words.get(*position)?;
+    *position += 1;
+    match word.as_str() {
+        "не" | "not" => Some(parse_not(words, position)?.complement()),
+        "(" => {
+            let contexts = parse_or(words, position)?;
+            (words.get(*position).map(String::as_str) == Some(")")).then(|| {
+                *position += 1;
+                contexts
+            })
+        }
+        symbol => ContextSet::from_symbol(symbol),
+    }
+}
+
+/// Инструкция препроцессора (строка, начинающаяся с `#`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Directive {
+    /// `#Если <условие> Тогда`
+    If(String),
+    /// `#ИначеЕсли <условие> Тогда`
+    ElseIf(String),
+    /// `#Иначе`
+    Else,
+    /// `#КонецЕсли`
+    EndIf,
+    /// `#Область Имя`
+    Region(String),
+    /// `#КонецОбласти`
+    EndRegion,
+    /// Прочие инструкции (`#Вставка`, `#Удаление`, ...)
+    Other(String),
+}
+
+impl Directive {
+    /// Разобрать текст инструкции без `#` (`Если Сервер Тогда`)
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim().trim_start_matches('#').trim();
+        let (keyword, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let condition = || {
+            let rest = rest.trim();
+            // Строчные буквы кириллицы и латиницы занимают столько же байт
+            let lower = rest.to_lowercase();
+            let end = lower
+                .strip_suffix("тогда")
+                .or_else(|| lower.strip_suffix("then"))
+                .map_or(rest.len(), str::len);
+            rest.get(..end).unwrap_or(rest).trim().to_string()
+        };
+        match keyword.to_lowercase().as_str() {
+            "если" | "if" => Self::If(condition()),
+            "иначеесли" | "elsif" | "elseif" => Self::ElseIf(condition()),
+            "иначе" | "else" => Self::Else,
+            "конецесли" | "endif" => Self::EndIf,
+            "область" | "region" => Self::Region(rest.trim().to_string()),
+            "конецобласти" | "endregion" => Self::EndRegion,
+            _ => Self::Other(text.to_string()),
+        }
+    }
+}
+
+/// Контексты ветки условной компиляции: условие ветки за вычетом условий
+/// предыдущих веток (`taken`); `None` в `condition` — ветка `#Иначе`.
+/// Неизвестное условие считается истинным везде, чтобы код не выпадал из
+/// анализа.
+pub fn branch_contexts(condition: Option<&str>, taken: ContextSet) -> ContextSet {
+    let contexts = match condition {
+        Some(condition) => ContextSet::parse_condition(condition).unwrap_or(ContextSet::ALL),
+        None => ContextSet::ALL,
+    };
+    contexts.difference(taken)
+}
+
+/// Контексты компиляции каждой строки текста модуля (с 0); строки самих
+/// инструкций получают контексты охватывающего блока
+pub fn line_contexts(text: &str) -> Vec<ContextSet> {
+    // Кадр блока `#Если`: контексты снаружи блока и условия пройденных веток
+    let mut frames: Vec<(ContextSet, ContextSet)> = Vec::new();
+    let mut current = ContextSet::ALL;
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if !trimmed.starts_with('#') {
+            lines.push(current);
+            continue;
+        }
+        match Directive::parse(trimmed) {
+            Directive::If(condition) => {
+                lines.push(current);
+                let branch = branch_contexts(Some(&condition), ContextSet::EMPTY);
+                frames.push((current, branch));
+                current = current.intersection(branch);
+            }
+            Directive::ElseIf(condition) => {
+                let Some((outer, taken)) = frames.last_mut() else {
+                    lines.push(current);
+                    continue;
+                };
+                lines.push(*outer);
+                let branch = branch_contexts(Some(&condition), *taken);
+                *taken = taken.union(branch);
+                current = outer.intersection(branch);
+            }
+            Directive::Else => {
+                let Some((outer, taken)) = frames.last_mut() else {
+                    lines.push(current);
+                    continue;
+                };
+                lines.push(*outer);
+                current = outer.intersection(branch_contexts(None, *taken));
+                *taken = ContextSet::ALL;
+            }
+            Directive::EndIf => {
+                if let Some((outer, _)) = frames.pop() {
+                    current = outer;
+                }
+                lines.push(current);
+            }
+            _ => lines.push(current),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condition_contexts() {
+        assert_eq!(
+            ContextSet::parse_condition("Сервер"),
+            Some(ContextSet::SERVER)
+        );
+        assert_eq!(
+            ContextSet::parse_condition(
+                "Сервер ИЛИ ТолстыйКлиентОбычноеПриложение Или ExternalConnection"
+            ),
+            Some(
+                ContextSet::SERVER
+                    .union(ContextSet::THICK_CLIENT_ORDINARY_APPLICATION)
+                    .union(ContextSet::EXTERNAL_CONNECTION)
+            )
+        );
+        assert_eq!(
+            ContextSet::parse_condition("Клиент И НЕ (ВебКлиент)"),
+            Some(ContextSet::CLIENT.difference(ContextSet::WEB_CLIENT))
+        );
+        assert_eq!(ContextSet::parse_condition("Сервер ИЛИ"), None);
+        assert_eq!(ContextSet::parse_condition("Неизвестно"), None);
+        assert_eq!(
+            ContextSet::SERVER.union(ContextSet::CLIENT).to_string(),
+            "Клиент, Сервер"
+        );
+    }
+
+    #[test]
+    fn test_directives_and_line_contexts() {
+        assert_eq!(
+            Directive::parse("#Если Сервер Или ВнешнееСоединение Тогда"),
+            Directive::If("Сервер Или ВнешнееСоединение".to_string())
+        );
+        assert_eq!(Directive::parse("#КонецЕсли"), Directive::EndIf);
+        assert_eq!(
+            Directive::parse("#Область СлужебныйПрограммныйИнтерфейс"),
+            Directive::Region("СлужебныйПрограммныйИнтерфейс".to_string())
+        );
+
+        let text = "А = 1;
+#Если Сервер Тогда
+Б = 2;
+#ИначеЕсли Клиент Или Сервер Тогда
+В = 3;
+    #Если ВебКлиент Тогда
+    Г = 4;
+    #КонецЕсли
+#Иначе
+Д = 5;
+#КонецЕсли
+Е = 6;";
+        let contexts = line_contexts(text);
+        assert_eq!(contexts[0], ContextSet::ALL);
+        assert_eq!(contexts[2], ContextSet::SERVER);
+        assert_eq!(contexts[4], ContextSet::CLIENT);
+        assert_eq!(contexts[6], ContextSet::WEB_CLIENT);
+        assert_eq!(contexts[7], ContextSet::CLIENT);
+        assert_eq!(
+            contexts[9],
+            ContextSet::ALL.difference(ContextSet::SERVER.union(ContextSet::CLIENT))
+        );
+        assert_eq!(contexts[11], ContextSet::ALL);
+    }
+}
@@ -4,6 +4,7 @@
 //! с нашей системой типов, конвертируя tree-sitter AST в наш формат.
 
 use crate::parser::ast::{BinaryOp, Expression, Parameter, Program, Statement, UnaryOp};
+use crate::error::ResultExt;
 use crate::parser::common::{Parser, TextChange};
 use anyhow::{Context, Result};
 use tree_sitter::{Language, Node, Parser as TSParser};
@@ -62,6 +63,25 @@ impl TreeSitterAdapter {
         Ok(program)
     }
 
+    /// Дерево tree-sitter без конвертации в AST (нужны позиции узлов)
+    pub fn parse_tree(&mut self, source: &str) -> Result<tree_sitter::Tree> {
+        self.parser
+            .parse(source, None)
+            .context("Failed to parse BSL code")
+    }
+
+    /// Перестроить дерево после правок: `old_tree` — прежнее дерево, к которому
+    /// уже применены `Tree::edit`; неизменённые поддеревья переиспользуются
+    pub fn reparse_tree(
+        &mut self,
+        source: &str,
+        old_tree: &tree_sitter::Tree,
+    ) -> Result<tree_sitter::Tree> {
+        self.parser
+            .parse(source, Some(old_tree))
+            .context("Failed to parse BSL code")
+    }
+
     /// Конвертировать корневой узел в Program
     fn convert_program(&self, node: Node) -> Result<Program> {
         let mut statements = Vec::new();
@@ -768,6 +788,7 @@ impl TreeSitterAdapter {
         match op_text {
             "-" => UnaryOp::Minus,
             "НЕ" | "NOT" => UnaryOp::Not,
+            "ЖДАТЬ" | "AWAIT" => UnaryOp::Await,
             _ => UnaryOp::Not, // По умолчанию
         }
     }
@@ -825,12 +846,15 @@ impl TreeSitterAdapter {
     }
 }
 
+/// Сообщение об ошибке разбора для публичного API (подробности — в причине)
+const PARSE_ERROR_MESSAGE: &str = "tree-sitter не смог разобрать модуль";
+
 impl Parser for TreeSitterAdapter {
-    fn parse(&mut self, source: &str) -> Result<Program> {
-        self.parse_impl(source)
+    fn parse(&mut self, source: &str) -> crate::Result<Program> {
+        self.parse_impl(source).parse_err(PARSE_ERROR_MESSAGE)
     }
 
-    fn parse_incremental(&mut self, source: &str, changes: &[TextChange]) -> Result<Program> {
+    fn parse_incremental(&mut self, source: &str, changes: &[TextChange]) -> crate::Result<Program> {
         // Применяем изменения к дереву
         if let Some(tree) = &mut self.last_tree {
             for change in changes {
@@ -855,7 +879,7 @@ impl Parser for TreeSitterAdapter {
         }
 
         // Парсим с использованием старого дерева
-        self.parse_impl(source)
+        self.parse_impl(source).parse_err(PARSE_ERROR_MESSAGE)
     }
 
     fn name(&self) -> &str {
@@ -88,6 +88,7 @@ pub trait AstVisitor {
                 self.visit_try(try_block, catch_block.as_ref());
             }
             Statement::Raise(message) => self.visit_raise(message),
+            Statement::CompilationBlock { branches } => self.visit_compilation_block(branches),
         }
     }
 
@@ -244,6 +245,15 @@ pub trait AstVisitor {
 
     fn visit_raise(&mut self, _message: &str) {}
 
+    /// Блок условной компиляции; по умолчанию обходятся все ветки
+    fn visit_compilation_block(&mut self, branches: &[CompilationBranch]) {
+        for branch in branches {
+            for stmt in &branch.body {
+                self.visit_statement(stmt);
+            }
+        }
+    }
+
     fn visit_number(&mut self, _value: f64) {}
 
     fn visit_string(&mut self, _value: &str) {}
@@ -0,0 +1,273 @@
+//! Тексты запросов в строковых литералах BSL
+//!
+//! В модуле запрос записывается строкой, обычно многострочной:
+//!
+//! ```bsl
+//! Запрос = Новый Запрос;
+//! Запрос.Текст =
+//!     "ВЫБРАТЬ
+//!     |    Номенклатура.Ссылка
+//!     |ИЗ
+//!     |    Справочник.Номенклатура КАК Номенклатура";
+//! ```
+//!
+//! Здесь находятся присваивания `Переменная.Текст = "..."` и
+//! `Переменная = Новый Запрос("...")` и из литерала восстанавливается текст
+//! запроса: `""` — кавычка, `|` в начале строки продолжения отбрасывается,
+//! строки-комментарии между строками продолжения пропускаются, литералы,
+//! сложенные через `+`, склеиваются. Текст, в который подставляются
+//! переменные, по коду не восстановить — такие присваивания пропускаются.
+//!
+//! Значения параметров ищутся в вызовах `Запрос.УстановитьПараметр("Имя", ...)`
+//! и `Запрос.Параметры.Вставить("Имя", ...)`.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Текст запроса, присвоенный в коде
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedQuery {
+    /// Переменная запроса (`Запрос` в `Запрос.Текст = ...`)
+    pub variable: String,
+    /// Строка присваивания (с 0)
+    pub line: u32,
+    /// Текст запроса без `|` и экранирования кавычек
+    pub text: String,
+}
+
+fn query_assignment_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?mi)(?:^|;)[ \t]*([\p{L}_][\p{L}\p{N}_]*)[ \t]*",
+            r"(?:\.[ \t]*(?:текст|text)[ \t]*=",
+            r#"|=[ \t]*(?:новый|new)[ \t]+(?:запрос|query)[ \t]*\()\s*""#,
+        ))
+        .unwrap()
+    })
+}
+
+/// Установка значения параметра запроса в коде
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParameterCall {
+    /// Переменная запроса
+    pub variable: String,
+    /// Имя параметра; `None` — имя задано не строковым литералом
+    pub name: Option<String>,
+    /// Выражение значения, как оно записано в коде
+    pub value: String,
+    /// Позиция выражения значения (строка с 0, столбец в символах)
+    pub line: u32,
+    pub column: u32,
+}
+
+fn parameter_call_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)([\p{L}_][\p{L}\p{N}_]*)[ \t]*\.[ \t]*(?:установитьпараметр|setparameter",
+            r"|(?:параметры|parameters)[ \t]*\.[ \t]*(?:вставить|insert))[ \t]*\(",
+        ))
+        .unwrap()
+    })
+}
+
+/// Байтовые диапазоны аргументов вызова, начинающихся в `start` (после
+/// открывающей скобки); `None` — скобка не закрыта
+fn call_arguments(code: &str, start: usize) -> Option<Vec<(usize, usize)>> {
+    let mut arguments = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut argument_start = start;
+    for (offset, ch) in code[start..].char_indices() {
+        let idx = start + offset;
+        match ch {
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '(' | '[' => depth += 1,
+            ')' | ']' if depth > 0 => depth -= 1,
+            ')' => {
+                arguments.push((argument_start, idx));
+                return Some(arguments);
+            }
+            ',' if depth == 0 => {
+                arguments.push((argument_start, idx));
+                argument_start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Строка (с 0) и столбец в символах байтовой позиции
+fn position(code: &str, offset: usize) -> (u32, u32) {
+    let line_start = code[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+    (
+        code[..offset].matches('\n').count() as u32,
+        code[line_start..offset].chars().count() as u32,
+    )
+}
+
+/// Установки параметров запросов в коде модуля, в порядке появления;
+/// закомментированные вызовы пропускаются
+pub fn parameter_calls(code: &str) -> Vec<ParameterCall> {
+    parameter_call_regex()
+        .captures_iter(code)
+        .filter_map(|caps| {
+            let whole = caps.get(0)?;
+            let line_start = code[..whole.start()].rfind('\n').map_or(0, |idx| idx + 1);
+            if code[line_start..whole.start()].contains("//") {
+                return None;
+            }
+            let arguments = call_arguments(code, whole.end())?;
+            let name = arguments.first().and_then(|&(start, end)| {
+                let name = code[start..end].trim();
+                let inner = name.strip_prefix('"')?.strip_suffix('"')?;
+                Some(inner.replace("\"\"", "\""))
+            });
+            let (value, offset) = match arguments.get(1) {
+                Some(&(start, end)) => {
+                    let raw = &code[start..end];
+                    (raw.trim(), start + (raw.len() - raw.trim_start().len()))
+                }
+                None => ("", whole.end()),
+            };
+            let (line, column) = position(code, offset);
+            Some(ParameterCall {
+                variable: caps.get(1)?.as_str().to_string(),
+                name,
+                value: value.to_string(),
+                line,
+                column,
+            })
+        })
+        .collect()
+}
+
+/// Содержимое литерала, открытого кавычкой в `start`, и позиция после
+/// закрывающей кавычки; `None` — литерал не закрыт
+fn read_literal(text: &str, start: usize) -> Option<(String, usize)> {
+    let body = start + 1;
+    let mut out = String::new();
+    let mut chars = text[body..].char_indices().peekable();
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            '"' => {
+                if chars.next_if(|&(_, next)| next == '"').is_some() {
+                    out.push('"');
+                } else {
+                    return Some((out, body + idx + 1));
+                }
+            }
+            '\n' => {
+                out.push('\n');
+                // Строка продолжения начинается с `|`, комментарии пропускаются
+                loop {
+                    while let Some((_, ' ' | '\t' | '\r')) = chars.peek() {
+                        chars.next();
+                    }
+                    match chars.next() {
+                        Some((_, '|')) => break,
+                        Some((_, '/')) if chars.next_if(|&(_, c)| c == '/').is_some() => {
+                            while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+                            chars.next()?;
+                        }
+                        _ => return None,
+                    }
+                }
+            }
+            '\r' => {}
+            _ => out.push(ch),
+        }
+    }
+    None
+}
+
+/// Литерал в `start` и литералы, прибавленные к нему через `+`
+fn read_text(text: &str, start: usize) -> Option<String> {
+    let (mut query, mut end) = read_literal(text, start)?;
+    while let Some(rest) = text[end..].trim_start().strip_prefix('+') {
+        let rest = rest.trim_start();
+        if !rest.starts_with('"') {
+            return None;
+        }
+        let (part, next) = read_literal(text, text.len() - rest.len())?;
+        query.push_str(&part);
+        end = next;
+    }
+    Some(query)
+}
+
+/// Тексты запросов, присвоенные в коде модуля, в порядке появления
+pub fn embedded_queries(code: &str) -> Vec<EmbeddedQuery> {
+    query_assignment_regex()
+        .captures_iter(code)
+        .filter_map(|caps| {
+            let whole = caps.get(0)?;
+            let variable = caps.get(1)?;
+            let text = read_text(code, whole.end() - 1)?;
+            Some(EmbeddedQuery {
+                variable: variable.as_str().to_string(),
+                line: code[..variable.start()].matches('\n').count() as u32,
+                text,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_query_texts() {
+        let code = r#"Запрос = Новый Запрос;
+Запрос.Текст =
+	"ВЫБРАТЬ
+	|	Номенклатура.Ссылка
+	// |	Номенклатура.Код,
+	|ИЗ
+	|	Справочник.Номенклатура КАК Номенклатура
+	|ГДЕ Номенклатура.Артикул = ""А-1""";
+Второй = Новый Запрос("ВЫБРАТЬ 1 КАК Один " + "ИЗ Документ.Заказ");
+Третий.Текст = "ВЫБРАТЬ * ИЗ " + ИмяТаблицы;"#;
+        let queries = embedded_queries(code);
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[0].variable, "Запрос");
+        assert_eq!(queries[0].line, 1);
+        assert_eq!(
+            queries[0].text,
+            "ВЫБРАТЬ\n\tНоменклатура.Ссылка\nИЗ\n\tСправочник.Номенклатура КАК Номенклатура\n\
+             ГДЕ Номенклатура.Артикул = \"А-1\""
+        );
+        assert_eq!(
+            queries[1],
+            EmbeddedQuery {
+                variable: "Второй".to_string(),
+                line: 8,
+                text: "ВЫБРАТЬ 1 КАК Один ИЗ Документ.Заказ".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parameter_calls() {
+        let code = r#"Запрос.УстановитьПараметр("Товар", Справочники.Товары.НайтиПоКоду("1"));
+// Запрос.УстановитьПараметр("Старый", 1);
+Запрос.Параметры.Вставить("Имя", "А, ""Б"""); Запрос.УстановитьПараметр(Ключ, Значение);
+Отбор.УстановитьПараметр("Дата",
+    ТекущаяДата());"#;
+        let calls = parameter_calls(code);
+        assert_eq!(calls.len(), 4);
+        assert_eq!(calls[0].name.as_deref(), Some("Товар"));
+        assert_eq!(calls[0].value, r#"Справочники.Товары.НайтиПоКоду("1")"#);
+        assert_eq!((calls[0].line, calls[0].column), (0, 35));
+        assert_eq!(calls[1].name.as_deref(), Some("Имя"));
+        assert_eq!(calls[1].value, r#""А, ""Б""""#);
+        assert_eq!(calls[2].name, None);
+        assert_eq!(calls[3].variable, "Отбор");
+        assert_eq!(calls[3].value, "ТекущаяДата()");
+        assert_eq!((calls[3].line, calls[3].column), (4, 4));
+    }
+}
@@ -2,5 +2,6 @@
 
 pub mod ast;
 pub mod batch;
+pub mod embedded;
 pub mod parser;
 pub mod type_checker;
@@ -66,12 +66,7 @@ pub fn parse_1c_batch_query(input: &str) -> Result<super::batch::BatchQuery, Str
 
 pub fn parse_query(input: &str) -> IResult<&str, Query> {
     let (input, select_clause) = parse_select_clause(input)?;
-    #[cfg(debug_assertions)]
-    eprintln!("[DEBUG] After SELECT: remaining = '{}'", input);
-
     let (input, from_clause) = parse_from_clause(input)?;
-    #[cfg(debug_assertions)]
-    eprintln!("[DEBUG] After FROM: remaining = '{}'", input);
     let (input, where_clause) = opt(parse_where_clause)(input)?;
     let (input, group_by_clause) = opt(parse_group_by_clause)(input)?;
     let (input, having_clause) = opt(parse_having_clause)(input)?;
@@ -138,16 +133,7 @@ fn parse_select_field(input: &str) -> IResult<&str, SelectField> {
 
 fn parse_from_clause(input: &str) -> IResult<&str, FromClause> {
     let (input, _) = ws(tag_no_case("ИЗ"))(input)?;
-    #[cfg(debug_assertions)]
-    eprintln!("[DEBUG] parse_from_clause after 'ИЗ': input = '{}'", input);
-
     let (input, sources) = separated_list1(ws(char(',')), parse_table_source)(input)?;
-    #[cfg(debug_assertions)]
-    eprintln!(
-        "[DEBUG] parse_from_clause after sources: input = '{}'",
-        input
-    );
-
     Ok((input, FromClause { sources }))
 }
 
@@ -228,9 +214,10 @@ fn parse_virtual_table(input: &str) -> IResult<&str, TableReference> {
             )),
             ws(char('.')),
             parse_identifier,
+            // Параметры можно пропускать: `Остатки(&Период, )`
             delimited(
                 ws(char('(')),
-                separated_list0(ws(char(',')), parse_virtual_parameter),
+                separated_list0(ws(char(',')), opt(parse_virtual_parameter)),
                 ws(char(')')),
             ),
         )),
@@ -238,20 +225,28 @@ fn parse_virtual_table(input: &str) -> IResult<&str, TableReference> {
             TableReference::VirtualTable(
                 format!("{}.{}", reg_type, reg_name),
                 vt_name.to_string(),
-                params,
+                params.into_iter().flatten().collect(),
             )
         },
     )(input)
 }
 
+/// Параметр виртуальной таблицы: `Имя = Выражение` или позиционное
+/// выражение (`&Период`) с пустым именем
 fn parse_virtual_parameter(input: &str) -> IResult<&str, VirtualTableParameter> {
-    map(
-        separated_pair(parse_identifier, ws(char('=')), parse_expression),
-        |(name, value)| VirtualTableParameter {
-            name: name.to_string(),
+    alt((
+        map(
+            separated_pair(parse_identifier, ws(char('=')), parse_expression),
+            |(name, value)| VirtualTableParameter {
+                name: name.to_string(),
+                value,
+            },
+        ),
+        map(parse_expression, |value| VirtualTableParameter {
+            name: String::new(),
             value,
-        },
-    )(input)
+        }),
+    ))(input)
 }
 
 fn parse_subquery_reference(input: &str) -> IResult<&str, TableReference> {
@@ -516,12 +511,13 @@ fn parse_postfix_expression(input: &str) -> IResult<&str, Expression> {
 
     let (input, fields) = many0(preceded(ws(char('.')), parse_identifier))(input)?;
 
-    let expr = fields.into_iter().fold(expr, |acc, field| {
-        if let Expression::Field(table) = acc {
-            Expression::QualifiedField(table, field.to_string())
-        } else {
-            acc
+    // `Товары.Номенклатура.Родитель` — поле `Номенклатура.Родитель` таблицы `Товары`
+    let expr = fields.into_iter().fold(expr, |acc, field| match acc {
+        Expression::Field(table) => Expression::QualifiedField(table, field.to_string()),
+        Expression::QualifiedField(table, path) => {
+            Expression::QualifiedField(table, format!("{}.{}", path, field))
         }
+        acc => acc,
     });
 
     Ok((input, expr))
@@ -832,4 +828,31 @@ mod tests {
         let result = parse_query(query);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_virtual_table_positional_parameters() {
+        let query =
+            "ВЫБРАТЬ Остатки.Товар ИЗ РегистрНакопления.Товары.Остатки(&Дата, ) КАК Остатки";
+        let (_, parsed) = parse_query(query).unwrap();
+        let TableReference::VirtualTable(base, name, params) = &parsed.from_clause.sources[0].table
+        else {
+            panic!("ожидалась виртуальная таблица");
+        };
+        assert_eq!((base.as_str(), name.as_str()), ("РегистрНакопления.Товары", "Остатки"));
+        assert_eq!(params[0].value, Expression::Parameter("Дата".to_string()));
+    }
+
+    #[test]
+    fn test_field_path_through_reference() {
+        let query = "ВЫБРАТЬ Товары.Номенклатура.Родитель.Наименование \
+                     ИЗ Документ.Заказ КАК Товары";
+        let (_, parsed) = parse_query(query).unwrap();
+        assert_eq!(
+            parsed.select_clause.fields[0].expression,
+            Expression::QualifiedField(
+                "Товары".to_string(),
+                "Номенклатура.Родитель.Наименование".to_string()
+            )
+        );
+    }
 }
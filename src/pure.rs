@@ -0,0 +1,95 @@
+//! Чистые точки входа для фаззинга и property-тестов
+//!
+//! Разбор модуля и разрешение выражения без файловой системы, переменных
+//! окружения и общих кешей: всё, что обычно читается с диска (типы
+//! платформы и конфигурации), передаётся вызывающим. Результат зависит
+//! только от аргументов, а некорректный или патологический ввод (глубокая
+//! вложенность скобок, обрывы конструкций) даёт ошибку разбора, а не
+//! панику.
+//!
+//! ```no_run
+//! use bsl_gradual_types::pure::{parse_module_str, resolve_expression_str};
+//! use bsl_gradual_types::parsing::bsl::LanguageFeatures;
+//!
+//! # async fn example() {
+//! let source = "Массив = Новый Массив;";
+//! let _ = parse_module_str(source, LanguageFeatures::latest());
+//! let resolution = resolve_expression_str("Массив", source, 0, 0, Vec::new()).await;
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use crate::architecture::data::{InMemoryTypeRepository, RawTypeData, TypeRepository};
+use crate::architecture::domain::{TypeContext, TypeResolutionService};
+use crate::domain::types::TypeResolution;
+use crate::error::Result;
+use crate::parsing::bsl::common::Parser;
+use crate::parsing::bsl::{BslParser, LanguageFeatures, Program};
+
+/// Разобрать текст модуля для целевой версии платформы
+pub fn parse_module_str(source: &str, features: LanguageFeatures) -> Result<Program> {
+    Parser::parse(&mut BslParser::with_features(features), source)
+}
+
+/// Тип выражения `expression` в позиции `line`/`column` (с нуля) текста
+/// модуля `source`; известны только типы `types`.
+///
+/// Контекст вида модуля (модуль объекта, формы) не строится: он зависит от
+/// пути файла и описаний метаданных рядом с ним.
+pub async fn resolve_expression_str(
+    expression: &str,
+    source: &str,
+    line: u32,
+    column: u32,
+    types: Vec<RawTypeData>,
+) -> TypeResolution {
+    let repository = Arc::new(InMemoryTypeRepository::new());
+    if repository.save_types(types).await.is_err() {
+        return TypeResolution::unknown();
+    }
+    let service = TypeResolutionService::new(repository);
+    let context = TypeContext {
+        file_path: None,
+        line: Some(line),
+        column: Some(column),
+        local_variables: Default::default(),
+        current_function: None,
+        current_facet: None,
+    };
+    service
+        .resolve_in_document(expression, &context, source)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::bsl::parser::MAX_NESTING;
+
+    #[test]
+    fn test_parse_module_str() {
+        let features = LanguageFeatures::latest();
+        let program = parse_module_str("Перем А;\nА = 1;", features).unwrap();
+        assert_eq!(program.statements.len(), 2);
+
+        for source in ["Процедура", "Если А Тогда", "А = (((1", "\u{0}\u{ffff}", ""]
+        {
+            let _ = parse_module_str(source, features);
+        }
+        let deep = format!("А = {}1;", "(".repeat(MAX_NESTING * 8));
+        assert!(parse_module_str(&deep, features).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_expression_str_is_deterministic() {
+        let source = "Процедура Тест()\n    Сумма = 10;\n    Итог = Сумма;\nКонецПроцедуры";
+        let first = resolve_expression_str("Сумма", source, 2, 4, Vec::new()).await;
+        let second = resolve_expression_str("Сумма", source, 2, 4, Vec::new()).await;
+        assert_eq!(first.get_name(), second.get_name());
+        assert_eq!(first.certainty, second.certainty);
+
+        let _ = resolve_expression_str("", "", 100, 100, Vec::new()).await;
+        let _ = resolve_expression_str(")(.", "\"", 0, 0, Vec::new()).await;
+    }
+}
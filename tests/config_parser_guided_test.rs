@@ -1,7 +1,9 @@
 //! Простые unit-тесты для Configuration-guided Discovery парсера
 
-use bsl_gradual_types::data::loaders::config_parser_guided_discovery::ConfigurationGuidedParser;
 use bsl_gradual_types::core::types::MetadataKind;
+use bsl_gradual_types::data::loaders::config_parser_guided_discovery::{
+    AttributeRole, ConfigurationGuidedParser,
+};
 
 #[test]
 fn test_parse_basic() {
@@ -59,3 +61,88 @@ fn test_register_attributes() {
     assert!(attr_names.contains(&"Период"));
     assert!(attr_names.contains(&"Активность"));
 }
+
+#[test]
+fn test_attribute_descriptions() {
+    let mut parser = ConfigurationGuidedParser::new("tests/fixtures/xml_full");
+    parser.parse_with_configuration_guide().unwrap();
+
+    let metadata = parser
+        .get_discovered_metadata("Документы.ЗаказНаряды")
+        .unwrap();
+    assert_eq!(metadata.synonym.as_deref(), Some("Заказ наряды"));
+
+    let attribute = |name: &str| metadata.attributes.iter().find(|a| a.name == name).unwrap();
+    let indexed = attribute("РеквизитИндексируемый");
+    assert_eq!(indexed.synonym.as_deref(), Some("Реквизит индексируемый"));
+    assert_eq!(indexed.indexing.as_deref(), Some("Index"));
+    assert_eq!(
+        indexed.description(),
+        "Реквизит индексируемый. Индексируется"
+    );
+    assert!(!indexed.read_only);
+    assert!(attribute("НомерЗаказ").indexing.is_none());
+    assert!(attribute("Проведен").read_only);
+}
+
+#[test]
+fn test_edt_project() {
+    // Корень рабочей копии EDT: описания в src/
+    let mut parser = ConfigurationGuidedParser::new("tests/fixtures/edt_project");
+    let resolutions = parser.parse_with_configuration_guide().unwrap();
+    assert!(!resolutions.is_empty());
+
+    let info = parser.get_configuration_info().unwrap();
+    assert_eq!(info.name, "Торговля");
+    assert_eq!(info.version.as_deref(), Some("1.0.0.1"));
+    assert_eq!(info.metadata_objects.len(), 4);
+    // Документа нет в src — ссылка есть, объекта нет
+    assert_eq!(parser.get_guided_discovery_stats().missing_objects, 1);
+
+    let catalog = parser
+        .get_discovered_metadata("Справочники.Валюты")
+        .unwrap();
+    assert_eq!(
+        catalog.uuid.as_deref(),
+        Some("c0000000-0000-0000-0000-000000000003")
+    );
+    assert_eq!(catalog.synonym.as_deref(), Some("Валюты"));
+    assert_eq!(catalog.english_synonym.as_deref(), Some("Currencies"));
+    assert_eq!(catalog.flags.full_text_search, Some(true));
+    let names: Vec<&str> = catalog.attributes.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["Курс", "Код", "Наименование", "Родитель"]);
+    let rate = &catalog.attributes[0];
+    assert_eq!(rate.type_definition, "Число");
+    assert_eq!(
+        rate.description(),
+        "Курс валюты. Обязательный. Индексируется"
+    );
+    let rates = &catalog.tabular_sections[0];
+    assert_eq!(rates.name, "Курсы");
+    assert_eq!(rates.attributes[0].name, "Период");
+    assert_eq!(rates.attributes[0].type_definition, "Дата");
+    assert_eq!(catalog.predefined[0].name, "Рубль");
+    assert_eq!(catalog.predefined[0].code.as_deref(), Some("643"));
+
+    let enumeration = parser
+        .get_discovered_metadata("Перечисления.СтатусыЗаказов")
+        .unwrap();
+    assert_eq!(enumeration.enum_values, ["Новый", "Оплачен"]);
+
+    let register = parser
+        .get_discovered_metadata("РегистрыНакопления.ТоварыНаСкладах")
+        .unwrap();
+    let fields: Vec<(&str, AttributeRole)> = register
+        .attributes
+        .iter()
+        .map(|a| (a.name.as_str(), a.role))
+        .collect();
+    assert_eq!(fields[0], ("Количество", AttributeRole::Resource));
+    assert_eq!(fields[1], ("Валюта", AttributeRole::Dimension));
+    assert_eq!(
+        register.attributes[1].type_definition,
+        "СправочникСсылка.Валюты"
+    );
+    // Регистр оборотов — без вида движения
+    assert!(!fields.iter().any(|(name, _)| *name == "ВидДвижения"));
+}
@@ -13,6 +13,7 @@ fn create_test_context() -> TypeContext {
         functions: HashMap::new(),
         current_scope: Scope::Global,
         scope_stack: vec![],
+        active_contexts: bsl_gradual_types::parsing::bsl::ContextSet::ALL,
     }
 }
 
@@ -36,6 +36,7 @@ mod tests {
             line: Some(1),
             column: Some(1),
             notes: vec!["test note".to_string()],
+            resolver: None,
         };
 
         let resolution = TypeResolution {